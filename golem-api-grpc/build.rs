@@ -61,17 +61,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "proto/golem/component/v1/component_ifs.proto",
                 "proto/golem/componentcompilation/v1/component_compilation_service.proto",
                 "proto/golem/worker/complete_parameters.proto",
+                "proto/golem/worker/domain_event.proto",
                 "proto/golem/worker/idempotency_key.proto",
                 "proto/golem/worker/invoke_parameters.proto",
                 "proto/golem/worker/invoke_result.proto",
                 "proto/golem/worker/log_event.proto",
                 "proto/golem/worker/promise_id.proto",
                 "proto/golem/worker/public_oplog.proto",
+                "proto/golem/worker/saga.proto",
+                "proto/golem/worker/stored_oplog.proto",
                 "proto/golem/worker/update_mode.proto",
                 "proto/golem/worker/worker_id.proto",
                 "proto/golem/worker/worker_metadata.proto",
                 "proto/golem/worker/worker_filter.proto",
+                "proto/golem/worker/worker_name_query.proto",
                 "proto/golem/worker/worker_status.proto",
+                "proto/golem/worker/worker_status_event.proto",
                 "proto/golem/worker/v1/worker_service.proto",
                 "proto/golem/worker/v1/worker_execution_error.proto",
                 "proto/golem/worker/v1/worker_error.proto",
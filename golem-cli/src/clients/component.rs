@@ -12,12 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use crate::model::component::Component;
 use crate::model::{ComponentName, GolemError, PathBufOrStdin};
 use async_trait::async_trait;
 use golem_wasm_rpc_stubgen::model::oam::Application;
-use golem_client::model::ComponentType;
+use golem_client::model::{CompilationStatus, ComponentType};
 use golem_common::uri::oss::urn::ComponentUrn;
 
 #[async_trait]
@@ -45,6 +46,7 @@ pub trait ComponentClient {
         project: &Option<Self::ProjectContext>,
         component_type: ComponentType,
         ifs: PathBuf,
+        function_aliases: Option<HashMap<String, String>>,
     ) -> Result<Component, GolemError>;
     async fn update(
         &self,
@@ -52,5 +54,22 @@ pub trait ComponentClient {
         file: PathBufOrStdin,
         component_type: Option<ComponentType>,
         ifs: PathBuf,
+        function_aliases: Option<HashMap<String, String>>,
     ) -> Result<Component, GolemError>;
+    /// Writes a version of the component's WASM to a file, returning the number of bytes written
+    async fn download(
+        &self,
+        urn: &ComponentUrn,
+        version: Option<u64>,
+        output: &Path,
+    ) -> Result<u64, GolemError>;
+    /// Gets the status of the compilation of a component version, if the compilation service
+    /// knows about it
+    async fn get_compilation_status(
+        &self,
+        urn: &ComponentUrn,
+        version: u64,
+    ) -> Result<Option<CompilationStatus>, GolemError>;
+    /// Deletes all versions of the component
+    async fn delete(&self, urn: &ComponentUrn) -> Result<(), GolemError>;
 }
@@ -19,6 +19,7 @@ use crate::model::{
 };
 use async_trait::async_trait;
 use golem_client::model::{InvokeParameters, InvokeResult, ScanCursor, WorkerFilter, WorkerId};
+use golem_common::model::maintenance::ComponentMaintenance;
 use golem_common::model::public_oplog::PublicOplogEntry;
 use golem_common::uri::oss::urn::{ComponentUrn, WorkerUrn};
 
@@ -32,13 +33,14 @@ pub trait WorkerClient {
         env: Vec<(String, String)>,
     ) -> Result<WorkerId, GolemError>;
 
+    /// Fails with the error returned by the API, so transient failures can be told apart
     async fn invoke_and_await(
         &self,
         worker_urn: WorkerUrn,
         function: String,
         parameters: InvokeParameters,
         idempotency_key: Option<IdempotencyKey>,
-    ) -> Result<InvokeResult, GolemError>;
+    ) -> Result<InvokeResult, golem_client::Error<golem_client::api::WorkerError>>;
 
     async fn invoke(
         &self,
@@ -69,6 +71,13 @@ pub trait WorkerClient {
         count: Option<u64>,
         precise: Option<bool>,
     ) -> Result<WorkersMetadataResponse, GolemError>;
+    async fn search(
+        &self,
+        component_urn: ComponentUrn,
+        prefix: Option<String>,
+        pattern: Option<String>,
+        count: Option<u64>,
+    ) -> Result<Vec<WorkerId>, GolemError>;
     async fn connect(
         &self,
         worker_urn: WorkerUrn,
@@ -88,6 +97,34 @@ pub trait WorkerClient {
         }
     }
 
+    /// Connects to all the workers of a component matching the filter, showing their events
+    /// prefixed with the name of their worker
+    async fn connect_many(
+        &self,
+        component_urn: ComponentUrn,
+        filter: Vec<String>,
+        connect_options: WorkerConnectOptions,
+        format: Format,
+    ) -> Result<(), GolemError>;
+
+    async fn connect_many_forever(
+        &self,
+        component_urn: ComponentUrn,
+        filter: Vec<String>,
+        connect_options: WorkerConnectOptions,
+        format: Format,
+    ) -> Result<(), GolemError> {
+        loop {
+            self.connect_many(
+                component_urn.clone(),
+                filter.clone(),
+                connect_options.clone(),
+                format,
+            )
+            .await?;
+        }
+    }
+
     async fn update(
         &self,
         worker_urn: WorkerUrn,
@@ -100,6 +137,17 @@ pub trait WorkerClient {
         worker_urn: WorkerUrn,
         from: u64,
     ) -> Result<Vec<(u64, PublicOplogEntry)>, GolemError>;
+
+    async fn get_maintenance(
+        &self,
+        component_urn: ComponentUrn,
+    ) -> Result<ComponentMaintenance, GolemError>;
+    async fn start_maintenance(
+        &self,
+        component_urn: ComponentUrn,
+        message: Option<String>,
+    ) -> Result<ComponentMaintenance, GolemError>;
+    async fn end_maintenance(&self, component_urn: ComponentUrn) -> Result<(), GolemError>;
 }
 
 pub fn worker_name_required(urn: &WorkerUrn) -> Result<String, GolemError> {
@@ -12,9 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::model::ComponentUriArg;
+use crate::model::{ComponentUriArg, ComponentUriOrPick};
 use crate::oss::model::OssContext;
-use golem_common::uri::oss::uri::ComponentUri;
 
 pub mod api_definition;
 pub mod api_deployment;
@@ -23,11 +22,12 @@ pub mod profile;
 pub mod worker;
 
 pub trait ComponentRefSplit<ProjectRef> {
-    fn split(self) -> (ComponentUri, Option<ProjectRef>);
+    fn split(self) -> (ComponentUriOrPick, Option<ProjectRef>);
 }
 
 impl ComponentRefSplit<OssContext> for ComponentUriArg {
-    fn split(self) -> (ComponentUri, Option<OssContext>) {
-        (self.uri, None)
+    fn split(self) -> (ComponentUriOrPick, Option<OssContext>) {
+        let project = self.project.project_id.map(|_| self.project);
+        (self.uri, project)
     }
 }
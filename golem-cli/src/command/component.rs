@@ -15,17 +15,23 @@
 use std::error::Error;
 use std::fmt::Display;
 use std::{fmt, fs};
-use std::io::Cursor;
+use std::io::{Cursor, Write};
 use std::path::{Path, PathBuf};
+use crate::command::worker::InvokeParameterList;
 use crate::command::ComponentRefSplit;
+use crate::model::deploy::UpdateRate;
 use crate::model::{
     ComponentName, Format, GolemError, GolemResult, PathBufOrStdin, WorkerUpdateMode,
 };
+use crate::parse_key_val;
 use crate::service::component::ComponentService;
 use crate::service::deploy::DeployService;
 use crate::service::project::ProjectResolver;
 use clap::Subcommand;
 use golem_client::model::ComponentType;
+use golem_common::model::initial_file_system::{
+    InitialFile, InitialFilePermissions, InitialFileSystemManifest, MANIFEST_PATH,
+};
 use std::sync::Arc;
 use golem_wasm_rpc_stubgen::model::oam::{Application, Component};
 use golem_wasm_rpc_stubgen::model::wasm_rpc::DEFAULT_CONFIG_FILE_NAME;
@@ -34,6 +40,7 @@ use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 use tracing::{error, info};
 use url::Url;
+use uuid::Uuid;
 use zip::write::{FileOptions, SimpleFileOptions};
 use zip::ZipWriter;
 
@@ -66,6 +73,11 @@ pub enum ComponentSubCommand<ProjectRef: clap::Args, ComponentRef: clap::Args> {
         #[command(flatten)]
         component_type: ComponentTypeArg,
 
+        /// Short names which can be used to invoke exported functions, for example
+        /// `--alias add-user=golem:it/api.{add-user}`
+        #[arg(long = "alias", value_parser = parse_key_val, value_name = "ALIAS=FUNCTION")]
+        aliases: Vec<(String, String)>,
+
         /// Do not ask for confirmation for performing an update in case the component already exists
         #[arg(short = 'y', long)]
         non_interactive: bool,
@@ -86,6 +98,12 @@ pub enum ComponentSubCommand<ProjectRef: clap::Args, ComponentRef: clap::Args> {
         #[command(flatten)]
         component_type: UpdatedComponentTypeArg,
 
+        /// Short names which can be used to invoke exported functions, for example
+        /// `--alias add-user=golem:it/api.{add-user}`. If none specified, the previous version's
+        /// aliases are kept.
+        #[arg(long = "alias", value_parser = parse_key_val, value_name = "ALIAS=FUNCTION")]
+        aliases: Vec<(String, String)>,
+
         /// Try to automatically update all existing workers to the new version
         #[arg(long, default_value_t = false)]
         try_update_workers: bool,
@@ -121,6 +139,39 @@ pub enum ComponentSubCommand<ProjectRef: clap::Args, ComponentRef: clap::Args> {
         #[arg(short = 't', long)]
         version: Option<u64>,
     },
+    /// Prints the JSON Schema of the parameters and results of an exported function
+    ///
+    /// The schema describes the JSON values accepted by `worker invoke --parameters` and
+    /// returned as the invocation results.
+    #[command()]
+    Schema {
+        /// The Golem component
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+
+        /// The exported function or its alias, for example `golem:it/api.{add-user}`
+        #[arg(short, long)]
+        function: String,
+
+        /// The version of the component. If none specified, the latest version is used.
+        #[arg(short = 't', long)]
+        version: Option<u64>,
+    },
+    /// Downloads the WASM of a deployed component
+    #[command()]
+    Download {
+        /// The Golem component
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+
+        /// The version of the component. If none specified, the latest version is downloaded.
+        #[arg(short = 't', long)]
+        version: Option<u64>,
+
+        /// The file to write to. Defaults to the component id with a .wasm extension.
+        #[arg(short, long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
     /// Try to automatically update all existing workers to the latest version
     #[command()]
     TryUpdateWorkers {
@@ -132,6 +183,35 @@ pub enum ComponentSubCommand<ProjectRef: clap::Args, ComponentRef: clap::Args> {
         #[arg(long, default_value = "auto")]
         update_mode: WorkerUpdateMode,
     },
+    /// Updates all existing workers to a given version at a limited rate, and reports the
+    /// workers which failed to update
+    ///
+    /// The progress is saved, so an update can be continued with the resume token reported in its
+    /// result, or with the resume token it was started with.
+    #[command()]
+    UpdateWorkers {
+        /// The component whose workers are updated
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+
+        /// The version to update the workers to
+        #[arg(short = 't', long)]
+        target_version: u64,
+
+        /// Update mode - auto or manual
+        #[arg(long, default_value = "auto")]
+        update_mode: WorkerUpdateMode,
+
+        /// Maximum number of updates issued, per sec, min or hour, for example `10/min`.
+        /// If not specified, the updates are issued one after the other without waiting.
+        #[arg(long)]
+        rate: Option<UpdateRate>,
+
+        /// Continue the update saved under this token, skipping the workers it already updated,
+        /// or start a new update saved under it
+        #[arg(long)]
+        resume_token: Option<String>,
+    },
     /// Redeploy all workers of a component using the latest version
     #[command()]
     Redeploy {
@@ -143,6 +223,25 @@ pub enum ComponentSubCommand<ProjectRef: clap::Args, ComponentRef: clap::Args> {
         #[arg(short = 'y', long)]
         non_interactive: bool,
     },
+    /// Uploads a WASM as a temporary component, invokes a function of it in an ephemeral worker
+    /// and prints the result. The temporary component is deleted afterwards.
+    #[command()]
+    Try {
+        /// The project to create the temporary component in
+        #[command(flatten)]
+        project_ref: ProjectRef,
+
+        /// The WASM file to be used as a Golem component
+        #[arg(value_name = "component-file", value_hint = clap::ValueHint::FilePath)]
+        component_file: PathBufOrStdin,
+
+        /// Name of the function to be invoked
+        #[arg(short, long)]
+        function: String,
+
+        #[command(flatten)]
+        parameters: InvokeParameterList,
+    },
 }
 
 #[derive(clap::Args, Debug, Clone)]
@@ -235,8 +334,14 @@ impl<
                 component_name,
                 component_file,
                 component_type,
+                aliases,
                 non_interactive,
             } => {
+                let aliases = if aliases.is_empty() {
+                    None
+                } else {
+                    Some(aliases.into_iter().collect())
+                };
                 match read_yaml_content() {
                     Ok(config) => {
                         match compress_files(config.clone()).await{
@@ -250,7 +355,8 @@ impl<
                                         Some(project_id),
                                         non_interactive,
                                         format,
-                                        ifs
+                                        ifs,
+                                        aliases,
                                     )
                                     .await
                             }
@@ -266,13 +372,21 @@ impl<
                 component_name_or_uri,
                 component_file,
                 component_type,
+                aliases,
                 try_update_workers,
                 update_mode,
                 non_interactive,
             } => {
                 let (component_name_or_uri, project_ref) = component_name_or_uri.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
-
+                let component_name_or_uri = service
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
+                let aliases = if aliases.is_empty() {
+                    None
+                } else {
+                    Some(aliases.into_iter().collect())
+                };
 
                 match read_yaml_content() {
                     Ok(config) => {
@@ -287,7 +401,8 @@ impl<
                                         project_id.clone(),
                                         non_interactive,
                                         format,
-                                        ifs
+                                        ifs,
+                                        aliases,
                                     )
                                     .await?;
                                 if try_update_workers {
@@ -320,34 +435,127 @@ impl<
             } => {
                 let (component_name_or_uri, project_ref) = component_name_or_uri.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = service
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
                 service
                     .get(component_name_or_uri, version, project_id)
                     .await
             }
+            ComponentSubCommand::Schema {
+                component_name_or_uri,
+                function,
+                version,
+            } => {
+                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
+                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = service
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
+                service
+                    .function_schema(component_name_or_uri, version, &function, project_id)
+                    .await
+            }
+            ComponentSubCommand::Download {
+                component_name_or_uri,
+                version,
+                output,
+            } => {
+                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
+                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = service
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
+                service
+                    .download(component_name_or_uri, version, output, project_id)
+                    .await
+            }
             ComponentSubCommand::TryUpdateWorkers {
                 component_name_or_uri,
                 update_mode,
             } => {
                 let (component_name_or_uri, project_ref) = component_name_or_uri.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = service
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
                 deploy_service
                     .try_update_all_workers(component_name_or_uri, project_id, update_mode)
                     .await
             }
+            ComponentSubCommand::UpdateWorkers {
+                component_name_or_uri,
+                target_version,
+                update_mode,
+                rate,
+                resume_token,
+            } => {
+                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
+                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = service
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
+                deploy_service
+                    .update_workers(
+                        component_name_or_uri,
+                        project_id,
+                        target_version,
+                        update_mode,
+                        rate,
+                        resume_token,
+                    )
+                    .await
+            }
             ComponentSubCommand::Redeploy {
                 component_name_or_uri,
                 non_interactive,
             } => {
                 let (component_name_or_uri, project_ref) = component_name_or_uri.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = service
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
                 deploy_service
                     .redeploy(component_name_or_uri, project_id, non_interactive, format)
                     .await
             }
+            ComponentSubCommand::Try {
+                project_ref,
+                component_file,
+                function,
+                parameters,
+            } => {
+                let project_id = projects.resolve_id_or_default(project_ref).await?;
+                let ifs =
+                    empty_initial_file_system().map_err(|error| GolemError(error.to_string()))?;
+                let result = deploy_service
+                    .try_component(
+                        component_file,
+                        ifs.clone(),
+                        function,
+                        parameters.parameters,
+                        parameters.wave,
+                        Some(project_id),
+                        format,
+                    )
+                    .await;
+                let _ = fs::remove_file(ifs);
+                result
+            }
         }
     }
 }
 
+// Temporary components are uploaded without any files
+fn empty_initial_file_system() -> Result<PathBuf, Box<dyn Error>> {
+    let path = std::env::temp_dir().join(format!("golem-try-{}.zip", Uuid::new_v4()));
+    let mut zip = ZipWriter::new(fs::File::create(&path)?);
+    zip.start_file(MANIFEST_PATH, SimpleFileOptions::default())?;
+    zip.write_all(&InitialFileSystemManifest::default().to_json())?;
+    zip.finish()?;
+    Ok(path)
+}
+
 async fn compress_files(application: Application) -> Result<PathBuf, Box<dyn Error>> {
     // Create an in-memory buffer (Vec<u8>)
     let mut buffer = Cursor::new(Vec::new());
@@ -358,7 +566,8 @@ async fn compress_files(application: Application) -> Result<PathBuf, Box<dyn Err
         .compression_method(zip::CompressionMethod::Stored) // You can also use Deflated, Bzip2, etc.
         .unix_permissions(0o755); // Set permissions
 
-    // Add files to the zip with the defined options
+    // Add files to the zip with the defined options, listing each of them in the manifest
+    let mut manifest = InitialFileSystemManifest::default();
     info!("Compressing");
     if let Some(component) = application.spec.components.get(0) {
         info!("Compressed component: {:?}", component);
@@ -379,20 +588,22 @@ async fn compress_files(application: Application) -> Result<PathBuf, Box<dyn Err
                 info!("Processing file: {:?}", file);
                 let source_path = file.clone().source_path;
 
-                // Handle files based on permissions
-                let target_folder = match file.permissions {
-                    Permissions::ReadOnly => "read-only",
-                    Permissions::ReadWrite => "read-write",
+                let initial_file = InitialFile {
+                    path: format!("/{}", file.target_path.trim_start_matches('/')),
+                    permissions: match file.permissions {
+                        Permissions::ReadOnly => InitialFilePermissions::ReadOnly,
+                        Permissions::ReadWrite => InitialFilePermissions::ReadWrite,
+                    },
+                    template: file.template,
                 };
-
-                let target_path = format!("{}/{}", target_folder, file.target_path);
                 match source_path{
                     FileSource::Path(source_path) => {
                         let mut file_reader = std::fs::File::open(source_path)?;
 
-                        // Add file to the corresponding folder in the ZIP archive
-                        zip.start_file(target_path, options)?;
+                        // Add file to the ZIP archive under its path in the worker's file system
+                        zip.start_file(initial_file.archive_path(), options)?;
                         std::io::copy(&mut file_reader, &mut zip)?;
+                        manifest.files.push(initial_file);
                     }
                     FileSource::Url(url) => {
                         info!("Url found {}", url.as_str());
@@ -413,6 +624,9 @@ async fn compress_files(application: Application) -> Result<PathBuf, Box<dyn Err
     zip.start_file("config/golem.yaml", options)?;
     std::io::copy(&mut golem_yaml_file, &mut zip)?;
 
+    zip.start_file(MANIFEST_PATH, options)?;
+    zip.write_all(&manifest.to_json())?;
+
     zip.finish()?;
 
     // Write the buffer to an asynchronous file
@@ -516,7 +730,10 @@ pub struct FileProperty{
     pub source_path: FileSource,
     #[serde(rename = "targetPath")]
     pub target_path: String,
-    pub permissions: Permissions
+    pub permissions: Permissions,
+    /// Replace `${VARIABLE}` placeholders with the worker's environment variables
+    #[serde(default)]
+    pub template: bool,
 }
 
 
@@ -580,6 +797,7 @@ fn write_yaml_content(source: PathBuf) -> Result<(), Box<dyn Error>> {
                 source_path: FileSource::Path(format!("./read-only/{}", file_name)),
                 target_path: format!("/{}", file_name),
                 permissions: Permissions::ReadOnly,
+                template: false,
             })
         }
     }else{
@@ -601,6 +819,7 @@ fn write_yaml_content(source: PathBuf) -> Result<(), Box<dyn Error>> {
                     source_path: FileSource::Path(format!("./read-write/{}", file_name)),
                     target_path: format!("/{}", file_name),
                     permissions: Permissions::ReadWrite,
+                    template: false,
                 })
             }
         }else{
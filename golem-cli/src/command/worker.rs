@@ -20,6 +20,7 @@ use golem_common::model::TargetWorkerId;
 use golem_common::uri::oss::uri::{ComponentUri, WorkerUri};
 use golem_common::uri::oss::url::{ComponentUrl, WorkerUrl};
 use golem_common::uri::oss::urn::{ComponentUrn, WorkerUrn};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::join;
 use tokio::task::spawn;
@@ -28,10 +29,13 @@ use crate::model::{
     Format, GolemError, GolemResult, IdempotencyKey, JsonValueParser, WorkerName, WorkerUpdateMode,
 };
 use crate::oss::model::OssContext;
+use crate::service::component::ComponentService;
 use crate::service::project::ProjectResolver;
 use crate::service::worker::WorkerService;
 use crate::{parse_bool, parse_key_val};
 
+/// Worker reference arguments. If the worker is not specified, it is picked interactively,
+/// from the workers of the given component, or of a component picked first.
 #[derive(clap::Args, Debug, Clone)]
 pub struct OssWorkerNameOrUriArg {
     /// Worker URI. Either URN or URL.
@@ -39,7 +43,6 @@ pub struct OssWorkerNameOrUriArg {
         short = 'W',
         long,
         conflicts_with_all(["worker_name", "component", "component_name"]),
-        required = true,
         value_name = "URI"
     )]
     worker: Option<WorkerUri>,
@@ -48,17 +51,17 @@ pub struct OssWorkerNameOrUriArg {
     #[arg(
         short = 'C',
         long,
+        group = "worker_component",
         conflicts_with_all(["component_name", "worker"]),
-        required = true,
         value_name = "URI"
     )]
     component: Option<ComponentUri>,
 
-    #[arg(short, long, conflicts_with_all(["component", "worker"]), required = true)]
+    #[arg(short, long, group = "worker_component", conflicts_with_all(["component", "worker"]))]
     component_name: Option<String>,
 
     /// Name of the worker
-    #[arg(short, long, conflicts_with = "worker", required = true)]
+    #[arg(short, long, conflicts_with = "worker", requires = "worker_component")]
     worker_name: Option<WorkerName>,
 }
 
@@ -69,18 +72,27 @@ pub struct InvokeParameterList {
     #[arg(
         short = 'j', long, value_name = "json", value_parser = ValueParser::new(JsonValueParser), group = "param"
     )]
-    parameters: Option<serde_json::value::Value>,
+    pub parameters: Option<serde_json::value::Value>,
 
-    /// Function parameter in WAVE format
+    /// Function parameter in WAVE format, for example `--arg '{a: 1, b: "x"}'`
     ///
     /// You can specify this argument multiple times for multiple parameters.
+    /// The values are parsed using the parameter types of the component.
     #[arg(short = 'a', long = "arg", value_name = "wave", group = "param")]
-    wave: Vec<String>,
+    pub wave: Vec<String>,
+}
+
+/// A worker given on the command line, or the component to pick the worker of if the worker
+/// was not given
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum WorkerUriOrPick {
+    Uri(WorkerUri),
+    Pick { component: Option<ComponentUri> },
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct OssWorkerUriArg {
-    pub uri: WorkerUri,
+    pub uri: WorkerUriOrPick,
     pub worker_name: bool,
     pub component_name: bool,
 }
@@ -111,14 +123,25 @@ impl clap::Args for OssWorkerUriArg {
 
 impl From<&OssWorkerNameOrUriArg> for OssWorkerUriArg {
     fn from(value: &OssWorkerNameOrUriArg) -> Self {
-        match &value.worker {
-            Some(uri) => OssWorkerUriArg {
-                uri: uri.clone(),
+        let component = match (&value.component, &value.component_name) {
+            (Some(uri), _) => Some(uri.clone()),
+            (None, Some(name)) => Some(ComponentUri::URL(ComponentUrl { name: name.clone() })),
+            (None, None) => None,
+        };
+
+        match (&value.worker, &value.worker_name) {
+            (Some(uri), _) => OssWorkerUriArg {
+                uri: WorkerUriOrPick::Uri(uri.clone()),
                 worker_name: false,
                 component_name: false,
             },
-            None => {
-                let worker_name = value.worker_name.clone().unwrap().0;
+            (None, None) => OssWorkerUriArg {
+                uri: WorkerUriOrPick::Pick { component },
+                worker_name: false,
+                component_name: value.component_name.is_some(),
+            },
+            (None, Some(worker_name)) => {
+                let worker_name = worker_name.0.clone();
 
                 match &value.component {
                     Some(ComponentUri::URN(component_urn)) => {
@@ -129,7 +152,7 @@ impl From<&OssWorkerNameOrUriArg> for OssWorkerUriArg {
                             },
                         });
                         OssWorkerUriArg {
-                            uri,
+                            uri: WorkerUriOrPick::Uri(uri),
                             worker_name: true,
                             component_name: false,
                         }
@@ -141,7 +164,7 @@ impl From<&OssWorkerNameOrUriArg> for OssWorkerUriArg {
                         });
 
                         OssWorkerUriArg {
-                            uri,
+                            uri: WorkerUriOrPick::Uri(uri),
                             worker_name: true,
                             component_name: false,
                         }
@@ -154,7 +177,7 @@ impl From<&OssWorkerNameOrUriArg> for OssWorkerUriArg {
                         });
 
                         OssWorkerUriArg {
-                            uri,
+                            uri: WorkerUriOrPick::Uri(uri),
                             worker_name: true,
                             component_name: true,
                         }
@@ -167,15 +190,35 @@ impl From<&OssWorkerNameOrUriArg> for OssWorkerUriArg {
 
 impl From<&OssWorkerUriArg> for OssWorkerNameOrUriArg {
     fn from(value: &OssWorkerUriArg) -> Self {
+        let uri = match &value.uri {
+            WorkerUriOrPick::Uri(uri) => uri,
+            WorkerUriOrPick::Pick { component } => {
+                return match component {
+                    Some(ComponentUri::URL(url)) if value.component_name => OssWorkerNameOrUriArg {
+                        worker: None,
+                        component: None,
+                        component_name: Some(url.name.clone()),
+                        worker_name: None,
+                    },
+                    _ => OssWorkerNameOrUriArg {
+                        worker: None,
+                        component: component.clone(),
+                        component_name: None,
+                        worker_name: None,
+                    },
+                };
+            }
+        };
+
         if !value.worker_name {
             OssWorkerNameOrUriArg {
-                worker: Some(value.uri.clone()),
+                worker: Some(uri.clone()),
                 component: None,
                 component_name: None,
                 worker_name: None,
             }
         } else {
-            match &value.uri {
+            match uri {
                 WorkerUri::URN(urn) => {
                     let component_uri = ComponentUri::URN(ComponentUrn {
                         id: urn.id.component_id.clone(),
@@ -275,10 +318,13 @@ pub enum WorkerSubcommand<ComponentRef: clap::Args, WorkerRef: clap::Args> {
         worker_name: WorkerName,
 
         /// List of environment variables (key-value pairs) passed to the worker
+        ///
+        /// The values can contain the placeholders `{{worker_name}}`, `{{component_id}}`,
+        /// `{{component_version}}` and `{{account_id}}`, replaced when the worker is created.
         #[arg(short, long, value_parser = parse_key_val, value_name = "ENV=VAL")]
         env: Vec<(String, String)>,
 
-        /// List of command line arguments passed to the worker
+        /// List of command line arguments passed to the worker, can contain the same placeholders as the environment variables
         #[arg(value_name = "args")]
         args: Vec<String>,
     },
@@ -337,11 +383,47 @@ pub enum WorkerSubcommand<ComponentRef: clap::Args, WorkerRef: clap::Args> {
         connect_options: WorkerConnectOptions,
     },
 
+    /// Invokes functions on many workers, reading the invocations from a file
+    ///
+    /// Each line of the input file is a JSON object of the form
+    /// `{"worker": "worker:///component/worker", "function": "...", "params": [...]}`, optionally
+    /// with an `idempotencyKey`. The results are written to the output file as they complete,
+    /// one JSON object per invocation, referring to the invocation's line in the input file.
+    #[command()]
+    InvokeBatch {
+        /// Newline-delimited JSON file of the invocations
+        #[arg(short, long, value_name = "file", value_hint = clap::ValueHint::FilePath)]
+        input: PathBuf,
+
+        /// File to write the newline-delimited JSON results to
+        #[arg(short, long, value_name = "file", value_hint = clap::ValueHint::FilePath)]
+        output: PathBuf,
+
+        /// Maximum number of invocations running at the same time
+        #[arg(short, long, default_value_t = 8)]
+        concurrency: usize,
+
+        /// Number of times an invocation failing with a transient error is retried
+        #[arg(short, long, default_value_t = 3)]
+        retries: u32,
+    },
+
     /// Connect to a worker and live stream its standard output, error and log channels
+    ///
+    /// With `--filter`, connects to all the workers of the component matching the filters and
+    /// prefixes each line with the name of its worker.
     #[command()]
     Connect {
         #[command(flatten)]
         worker_ref: WorkerRef,
+
+        /// Filter for the workers of the component to connect to, in the same form as for `worker list`,
+        /// for example `status == Running`
+        ///
+        /// Can be used multiple times (AND condition is applied between them)
+        #[arg(long)]
+        filter: Option<Vec<String>>,
+
         #[command(flatten)]
         connect_options: WorkerConnectOptions,
     },
@@ -392,6 +474,8 @@ pub enum WorkerSubcommand<ComponentRef: clap::Args, WorkerRef: clap::Args> {
         /// Filter for worker metadata in form of `property op value`.
         ///
         /// Filter examples: `name = worker-name`, `version >= 0`, `status = Running`, `env.var1 = value`.
+        /// Conditions can be combined with `&&`, `||`, `!` and parentheses, e.g.
+        /// `status == Running && env.REGION == "eu" && created_at > 2024-06-01`.
         /// Can be used multiple times (AND condition is applied between them)
         #[arg(short, long)]
         filter: Option<Vec<String>>,
@@ -411,6 +495,29 @@ pub enum WorkerSubcommand<ComponentRef: clap::Args, WorkerRef: clap::Args> {
         #[arg(long)]
         precise: Option<bool>,
     },
+    /// Finds workers of a component by name
+    ///
+    /// Much faster than `worker list` with a name filter, as the workers' metadata is not scanned.
+    #[command()]
+    Search {
+        /// The Golem component the workers to be found belong to
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+
+        /// Finds the workers whose name starts with this prefix
+        #[arg(long, conflicts_with = "pattern", required_unless_present = "pattern")]
+        prefix: Option<String>,
+
+        /// Finds the workers whose name contains the characters of this pattern in the same order, ignoring case
+        ///
+        /// For example `ordsvc` matches `order-service-1`. The closest matches are listed first.
+        #[arg(long)]
+        pattern: Option<String>,
+
+        /// Maximum number of workers to list, default is 50
+        #[arg(short = 'n', long)]
+        count: Option<u64>,
+    },
     /// Updates a worker
     #[command()]
     Update {
@@ -439,6 +546,8 @@ pub enum WorkerSubcommand<ComponentRef: clap::Args, WorkerRef: clap::Args> {
         /// Filter for selecting workers by their metadata in form of `property op value`.
         ///
         /// Filter examples: `name = worker-name`, `version >= 0`, `status = Running`, `env.var1 = value`.
+        /// Conditions can be combined with `&&`, `||`, `!` and parentheses, e.g.
+        /// `status == Running && env.REGION == "eu" && created_at > 2024-06-01`.
         /// Can be used multiple times (AND condition is applied between them)
         #[arg(short, long)]
         filter: Option<Vec<String>>,
@@ -464,14 +573,49 @@ pub enum WorkerSubcommand<ComponentRef: clap::Args, WorkerRef: clap::Args> {
         #[arg(short, long)]
         from: Option<u64>,
     },
+    /// Shows the execution timeline of a worker, built from its oplog
+    #[command()]
+    Timeline {
+        #[command(flatten)]
+        worker_ref: WorkerRef,
+    },
+    /// Puts a component in maintenance for a planned downtime
+    ///
+    /// While the component is in maintenance, invoking its workers fails with a retryable error
+    /// and the queued invocations are held back. The workers can still be listed, created,
+    /// updated, interrupted or deleted.
+    #[command()]
+    StartMaintenance {
+        /// The component to put in maintenance
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+
+        /// Message returned to the rejected callers, for example the expected end of the downtime
+        #[arg(short, long)]
+        message: Option<String>,
+    },
+    /// Ends the maintenance of a component
+    #[command()]
+    EndMaintenance {
+        /// The component in maintenance
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+    },
+    /// Shows whether a component is in maintenance
+    #[command()]
+    GetMaintenance {
+        /// The component to check
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+    },
 }
 
 pub trait WorkerRefSplit<ProjectRef> {
-    fn split(self) -> (WorkerUri, Option<ProjectRef>);
+    fn split(self) -> (WorkerUriOrPick, Option<ProjectRef>);
 }
 
 impl WorkerRefSplit<OssContext> for OssWorkerUriArg {
-    fn split(self) -> (WorkerUri, Option<OssContext>) {
+    fn split(self) -> (WorkerUriOrPick, Option<OssContext>) {
         (self.uri, None)
     }
 }
@@ -484,6 +628,7 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
         self,
         format: Format,
         service: Arc<dyn WorkerService<ProjectContext = ProjectContext> + Send + Sync>,
+        components: Arc<dyn ComponentService<ProjectContext = ProjectContext> + Send + Sync>,
         projects: Arc<dyn ProjectResolver<ProjectRef, ProjectContext> + Send + Sync>,
     ) -> Result<GolemResult, GolemError>
     where
@@ -499,6 +644,9 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
             } => {
                 let (component_name_or_uri, project_ref) = component_name_or_uri.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = components
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
                 // At the point we also needs to transfer the files to the worker
                 service
                     .add(component_name_or_uri, worker_name, env, args, project_id)
@@ -515,6 +663,9 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
             } => {
                 let (worker_uri, project_ref) = worker_ref.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let worker_uri = service
+                    .worker_uri_or_pick(worker_uri, project_id.clone())
+                    .await?;
                 if connect {
                     let worker_uri_clone = worker_uri.clone();
                     let project_id_clone = project_id.clone();
@@ -562,6 +713,9 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
             } => {
                 let (worker_uri, project_ref) = worker_ref.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let worker_uri = service
+                    .worker_uri_or_pick(worker_uri, project_id.clone())
+                    .await?;
 
                 if connect {
                     let invoke_future = service.invoke(
@@ -589,12 +743,48 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
                         .await
                 }
             }
+            WorkerSubcommand::InvokeBatch {
+                input,
+                output,
+                concurrency,
+                retries,
+            } => {
+                let project_id = projects.resolve_id_or_default_opt(None).await?;
+                service
+                    .invoke_batch(input, output, concurrency, retries, project_id)
+                    .await
+            }
             WorkerSubcommand::Connect {
                 worker_ref,
+                filter: Some(filter),
                 connect_options,
             } => {
                 let (worker_uri, project_ref) = worker_ref.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_uri = match worker_uri {
+                    WorkerUriOrPick::Pick {
+                        component: Some(component_uri),
+                    } => component_uri,
+                    _ => {
+                        return Err(GolemError(
+                            "--filter requires the component to be specified with --component or --component-name, without a worker".to_string(),
+                        ))
+                    }
+                };
+                service
+                    .connect_many(component_uri, filter, project_id, connect_options, format)
+                    .await
+            }
+            WorkerSubcommand::Connect {
+                worker_ref,
+                filter: None,
+                connect_options,
+            } => {
+                let (worker_uri, project_ref) = worker_ref.split();
+                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let worker_uri = service
+                    .worker_uri_or_pick(worker_uri, project_id.clone())
+                    .await?;
                 service
                     .connect(worker_uri, project_id, connect_options, format)
                     .await
@@ -602,26 +792,41 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
             WorkerSubcommand::Interrupt { worker_ref } => {
                 let (worker_uri, project_ref) = worker_ref.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let worker_uri = service
+                    .worker_uri_or_pick(worker_uri, project_id.clone())
+                    .await?;
                 service.interrupt(worker_uri, project_id).await
             }
             WorkerSubcommand::Resume { worker_ref } => {
                 let (worker_uri, project_ref) = worker_ref.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let worker_uri = service
+                    .worker_uri_or_pick(worker_uri, project_id.clone())
+                    .await?;
                 service.resume(worker_uri, project_id).await
             }
             WorkerSubcommand::SimulatedCrash { worker_ref } => {
                 let (worker_uri, project_ref) = worker_ref.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let worker_uri = service
+                    .worker_uri_or_pick(worker_uri, project_id.clone())
+                    .await?;
                 service.simulated_crash(worker_uri, project_id).await
             }
             WorkerSubcommand::Delete { worker_ref } => {
                 let (worker_uri, project_ref) = worker_ref.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let worker_uri = service
+                    .worker_uri_or_pick(worker_uri, project_id.clone())
+                    .await?;
                 service.delete(worker_uri, project_id).await
             }
             WorkerSubcommand::Get { worker_ref } => {
                 let (worker_uri, project_ref) = worker_ref.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let worker_uri = service
+                    .worker_uri_or_pick(worker_uri, project_id.clone())
+                    .await?;
                 service.get(worker_uri, project_id).await
             }
             WorkerSubcommand::List {
@@ -633,6 +838,9 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
             } => {
                 let (component_name_or_uri, project_ref) = component_name_or_uri.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = components
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
                 service
                     .list(
                         component_name_or_uri,
@@ -644,6 +852,21 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
                     )
                     .await
             }
+            WorkerSubcommand::Search {
+                component_name_or_uri,
+                prefix,
+                pattern,
+                count,
+            } => {
+                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
+                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = components
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
+                service
+                    .search(component_name_or_uri, prefix, pattern, count, project_id)
+                    .await
+            }
             WorkerSubcommand::Update {
                 worker_ref,
                 target_version,
@@ -652,6 +875,9 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
             } => {
                 let (worker_uri, project_ref) = worker_ref.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let worker_uri = service
+                    .worker_uri_or_pick(worker_uri, project_id.clone())
+                    .await?;
                 service
                     .update(worker_uri, target_version, mode, project_id)
                     .await
@@ -665,6 +891,9 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
             } => {
                 let (component_name_or_uri, project_ref) = component_name_or_uri.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = components
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
                 service
                     .update_many(
                         component_name_or_uri,
@@ -678,10 +907,58 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
             WorkerSubcommand::Oplog { worker_ref, from } => {
                 let (worker_uri, project_ref) = worker_ref.split();
                 let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let worker_uri = service
+                    .worker_uri_or_pick(worker_uri, project_id.clone())
+                    .await?;
                 service
                     .get_oplog(worker_uri, from.unwrap_or_default(), project_id)
                     .await
             }
+            WorkerSubcommand::Timeline { worker_ref } => {
+                let (worker_uri, project_ref) = worker_ref.split();
+                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let worker_uri = service
+                    .worker_uri_or_pick(worker_uri, project_id.clone())
+                    .await?;
+                service.get_timeline(worker_uri, project_id).await
+            }
+            WorkerSubcommand::StartMaintenance {
+                component_name_or_uri,
+                message,
+            } => {
+                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
+                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = components
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
+                service
+                    .start_maintenance(component_name_or_uri, message, project_id)
+                    .await
+            }
+            WorkerSubcommand::EndMaintenance {
+                component_name_or_uri,
+            } => {
+                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
+                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = components
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
+                service
+                    .end_maintenance(component_name_or_uri, project_id)
+                    .await
+            }
+            WorkerSubcommand::GetMaintenance {
+                component_name_or_uri,
+            } => {
+                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
+                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let component_name_or_uri = components
+                    .component_uri_or_pick(component_name_or_uri, &project_id)
+                    .await?;
+                service
+                    .get_maintenance(component_name_or_uri, project_id)
+                    .await
+            }
         }
     }
 }
@@ -14,17 +14,34 @@
 
 use crate::command::worker::WorkerConnectOptions;
 use crate::model::Format;
-use colored::Colorize;
-use golem_common::model::{LogLevel, Timestamp};
+use colored::{Color, Colorize};
+use golem_common::model::{LogLevel, NamedWorkerEvent, Timestamp, WorkerEvent};
+use std::collections::HashMap;
 use std::fmt::Write;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// Colors of the worker names prefixing the lines of a multi-worker connection, in the order the
+/// workers are first seen
+const WORKER_COLORS: [Color; 10] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::BrightCyan,
+    Color::BrightMagenta,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+];
+
 #[derive(Clone)]
 pub struct ConnectOutput {
     state: Arc<Mutex<ConnectOutputState>>,
     options: WorkerConnectOptions,
     format: Format,
+    worker: Option<(String, Color)>,
 }
 
 struct ConnectOutputState {
@@ -45,6 +62,39 @@ impl ConnectOutput {
             })),
             options,
             format,
+            worker: None,
+        }
+    }
+
+    /// Prefixes every line with the name of the worker, in the given color
+    pub fn with_worker(self, worker_name: String, color: Color) -> Self {
+        ConnectOutput {
+            worker: Some((worker_name, color)),
+            ..self
+        }
+    }
+
+    pub async fn emit(&self, event: WorkerEvent) {
+        match event {
+            WorkerEvent::StdOut { timestamp, bytes } => {
+                self.emit_stdout(timestamp, String::from_utf8_lossy(&bytes).to_string())
+                    .await;
+            }
+            WorkerEvent::StdErr { timestamp, bytes } => {
+                self.emit_stderr(timestamp, String::from_utf8_lossy(&bytes).to_string())
+                    .await;
+            }
+            WorkerEvent::Log {
+                timestamp,
+                level,
+                context,
+                message,
+            } => {
+                self.emit_log(timestamp, level, context, message);
+            }
+            WorkerEvent::Close => {}
+            WorkerEvent::InvocationStart { .. } => {}
+            WorkerEvent::InvocationFinished { .. } => {}
         }
     }
 
@@ -169,15 +219,20 @@ impl ConnectOutput {
     }
 
     fn json_value(&self, level_or_source: &str, context: &str, message: &str) -> serde_json::Value {
-        serde_json::json!({
+        let mut json = serde_json::json!({
             "timestamp": Timestamp::now_utc(),
             "level": level_or_source,
             "context": context,
             "message": message,
-        })
+        });
+        if let Some((worker_name, _)) = &self.worker {
+            json["worker"] = serde_json::Value::String(worker_name.clone());
+        }
+        json
     }
 
     fn colored(&self, level: LogLevel, s: &str) {
+        let worker = self.worker_prefix();
         if self.options.colors {
             let colored = match level {
                 LogLevel::Trace => s.blue(),
@@ -187,9 +242,19 @@ impl ConnectOutput {
                 LogLevel::Error => s.red(),
                 LogLevel::Critical => s.red().bold(),
             };
-            println!("{}", colored);
+            println!("{}{}", worker, colored);
         } else {
-            println!("{}", s);
+            println!("{}{}", worker, s);
+        }
+    }
+
+    fn worker_prefix(&self) -> String {
+        match &self.worker {
+            Some((worker_name, color)) if self.options.colors => {
+                format!("{} ", format!("[{worker_name}]").color(*color))
+            }
+            Some((worker_name, _)) => format!("[{worker_name}] "),
+            None => String::new(),
         }
     }
 
@@ -210,3 +275,37 @@ impl ConnectOutput {
         result
     }
 }
+
+/// Output of a connection to multiple workers, keeping the partial lines of each worker apart
+/// and prefixing the lines with the name of their worker
+#[derive(Clone)]
+pub struct MultiConnectOutput {
+    workers: Arc<Mutex<HashMap<String, ConnectOutput>>>,
+    options: WorkerConnectOptions,
+    format: Format,
+}
+
+impl MultiConnectOutput {
+    pub fn new(options: WorkerConnectOptions, format: Format) -> Self {
+        MultiConnectOutput {
+            workers: Arc::new(Mutex::new(HashMap::new())),
+            options,
+            format,
+        }
+    }
+
+    pub async fn emit(&self, event: NamedWorkerEvent) {
+        let output = {
+            let mut workers = self.workers.lock().await;
+            let color = WORKER_COLORS[workers.len() % WORKER_COLORS.len()];
+            workers
+                .entry(event.worker_name.clone())
+                .or_insert_with(|| {
+                    ConnectOutput::new(self.options.clone(), self.format)
+                        .with_worker(event.worker_name, color)
+                })
+                .clone()
+        };
+        output.emit(event.event).await;
+    }
+}
@@ -17,14 +17,17 @@ use crate::clients::api_deployment::ApiDeploymentClient;
 use crate::clients::component::ComponentClient;
 use crate::clients::health_check::HealthCheckClient;
 use crate::clients::worker::WorkerClient;
+use crate::progress::ProgressMode;
 use crate::service::api_definition::{ApiDefinitionService, ApiDefinitionServiceLive};
 use crate::service::api_deployment::{ApiDeploymentService, ApiDeploymentServiceLive};
 use crate::service::component::{ComponentService, ComponentServiceLive};
+use crate::service::component_cache::ComponentMetadataCache;
 use crate::service::deploy::{DeployService, DeployServiceLive};
 use crate::service::project::ProjectResolver;
 use crate::service::version::{VersionService, VersionServiceLive};
 use crate::service::worker::{WorkerService, WorkerServiceLive};
 use std::fmt::Display;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 pub trait ServiceFactory {
@@ -39,11 +42,28 @@ pub trait ServiceFactory {
         &self,
     ) -> Box<dyn ComponentClient<ProjectContext = Self::ProjectContext> + Send + Sync>;
 
+    fn component_metadata_cache(&self) -> Option<ComponentMetadataCache>;
+
+    /// Directory where the progress of worker fleet updates is saved for resuming them
+    fn fleet_update_state_dir(&self) -> Option<PathBuf>;
+
+    /// Whether to only use locally cached data instead of reaching the Golem services
+    fn offline(&self) -> bool;
+
+    /// Whether the user can be asked to pick the entities missing from the command line
+    fn interactive(&self) -> bool;
+
+    /// How the progress of long-running operations is reported
+    fn progress(&self) -> ProgressMode;
+
     fn component_service(
         &self,
     ) -> Arc<dyn ComponentService<ProjectContext = Self::ProjectContext> + Send + Sync> {
         Arc::new(ComponentServiceLive {
             client: self.component_client(),
+            cache: self.component_metadata_cache(),
+            offline: self.offline(),
+            interactive: self.interactive(),
         })
     }
 
@@ -58,6 +78,8 @@ pub trait ServiceFactory {
         Arc::new(WorkerServiceLive {
             client: self.worker_client(),
             components: self.component_service(),
+            interactive: self.interactive(),
+            progress: self.progress(),
         })
     }
 
@@ -102,6 +124,8 @@ pub trait ServiceFactory {
         Arc::new(DeployServiceLive {
             component_service: self.component_service(),
             worker_service: self.worker_service(),
+            fleet_update_state_dir: self.fleet_update_state_dir(),
+            progress: self.progress(),
         })
     }
 }
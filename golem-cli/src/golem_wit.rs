@@ -0,0 +1,143 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Adds the `golem:api` interfaces provided by the worker executor which are not part of the
+//! published golem-wit package to a component, so it can import them.
+
+use std::path::{Path, PathBuf};
+
+use golem_common::guest_wit::GOLEM_API_EXTENSIONS;
+use itertools::Itertools;
+
+use crate::model::{GolemError, GolemResult};
+
+/// The `golem:api` package the interfaces belong to
+const GOLEM_API_PACKAGE: &str = "golem:api@1.1.0-rc1";
+
+#[derive(clap::Args, Debug)]
+pub struct GolemWitArgs {
+    /// Directory of the `golem:api` package in the WIT dependencies of the component
+    #[arg(short, long, value_name = "DIR", default_value = "wit/deps/golem")]
+    pub output: PathBuf,
+}
+
+pub fn handle_golem_wit(args: GolemWitArgs) -> Result<GolemResult, GolemError> {
+    check_package(&args.output)?;
+    std::fs::create_dir_all(&args.output).map_err(|err| {
+        GolemError(format!(
+            "Failed to create directory {:?}: {err}",
+            args.output
+        ))
+    })?;
+    for (name, content) in GOLEM_API_EXTENSIONS {
+        let path = args.output.join(name);
+        std::fs::write(&path, content)
+            .map_err(|err| GolemError(format!("Failed to write {path:?}: {err}")))?;
+    }
+
+    Ok(GolemResult::Str(format!(
+        "Added {} to {}",
+        GOLEM_API_EXTENSIONS.iter().map(|(name, _)| name).join(", "),
+        args.output.display()
+    )))
+}
+
+/// The files of a WIT package have to be in the same directory, so the interfaces can only be
+/// added next to the same version of `golem:api`
+fn check_package(dir: &Path) -> Result<(), GolemError> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "wit") {
+            let content = std::fs::read_to_string(&path)
+                .map_err(|err| GolemError(format!("Failed to read {path:?}: {err}")))?;
+            let package = content
+                .lines()
+                .map(str::trim)
+                .find_map(|line| line.strip_prefix("package "))
+                .map(|package| package.trim_end_matches(';').trim());
+            if let Some(package) = package {
+                if package != GOLEM_API_PACKAGE {
+                    return Err(GolemError(format!(
+                        "{path:?} belongs to {package}, the interfaces can only be added to {GOLEM_API_PACKAGE}"
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::guest_wit::GOLEM_API_EXTENSIONS;
+    use wit_parser::{Resolve, UnresolvedPackage};
+
+    use crate::golem_wit::{handle_golem_wit, GolemWitArgs};
+
+    #[test]
+    fn interfaces_are_added_to_the_golem_api_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("wit").join("deps").join("golem");
+        std::fs::create_dir_all(&output).unwrap();
+        std::fs::write(
+            output.join("golem-host.wit"),
+            "package golem:api@1.1.0-rc1;\n\ninterface host {\n  get-self-name: func() -> string;\n}\n",
+        )
+        .unwrap();
+
+        handle_golem_wit(GolemWitArgs {
+            output: output.clone(),
+        })
+        .unwrap();
+
+        let mut resolve = Resolve::new();
+        resolve
+            .push(UnresolvedPackage::parse_dir(&output).unwrap())
+            .unwrap();
+        let mut interfaces = resolve
+            .interfaces
+            .iter()
+            .filter_map(|(_, interface)| interface.name.clone())
+            .collect::<Vec<_>>();
+        interfaces.sort();
+        let mut expected = GOLEM_API_EXTENSIONS
+            .iter()
+            .map(|(name, _)| name.trim_end_matches(".wit").to_string())
+            .chain(Some("host".to_string()))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(interfaces, expected);
+    }
+
+    #[test]
+    fn other_versions_of_golem_api_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("golem-host.wit"),
+            "package golem:api@0.2.0;\n\ninterface host {}\n",
+        )
+        .unwrap();
+
+        assert!(handle_golem_wit(GolemWitArgs {
+            output: dir.path().to_path_buf(),
+        })
+        .is_err());
+        assert!(!dir.path().join("metrics.wit").exists());
+    }
+}
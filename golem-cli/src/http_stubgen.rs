@@ -0,0 +1,1277 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generates typed imports for calling an external REST API from workers. The generated WIT
+//! interface is implemented on the host by the generated bindings, which are added to the
+//! linker of a custom worker executor and record every call in the oplog.
+
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use openapiv3::{
+    Content, IntegerFormat, NumberFormat, OpenAPI, Operation, Parameter, ParameterSchemaOrContent,
+    ReferenceOr, Schema, SchemaKind, StatusCode, Type, VariantOrUnknownOrEmpty,
+};
+
+use crate::model::{GolemError, GolemResult};
+
+#[derive(clap::Args, Debug)]
+pub struct HttpStubgenArgs {
+    /// The OpenAPI specification of the external service, in JSON or YAML
+    #[arg(short, long, value_name = "SPEC")]
+    pub spec: PathBuf,
+
+    /// Package of the generated WIT interface, for example `petstore:client`
+    #[arg(short, long)]
+    pub package: String,
+
+    /// Name of the generated WIT interface
+    #[arg(short, long, default_value = "api")]
+    pub interface: String,
+
+    /// Crate of the custom worker executor. The WIT file is written to its `wit` directory
+    /// and the host bindings to its `src` directory.
+    #[arg(short, long, value_name = "DIR")]
+    pub output: PathBuf,
+
+    /// Time allowed for each call of the service, in seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = 30)]
+    pub timeout: u64,
+}
+
+pub fn handle_http_stubgen(args: HttpStubgenArgs) -> Result<GolemResult, GolemError> {
+    let spec = std::fs::read_to_string(&args.spec).map_err(|err| {
+        GolemError(format!(
+            "Failed to read the OpenAPI specification {:?}: {err}",
+            args.spec
+        ))
+    })?;
+    let spec: OpenAPI = serde_yaml::from_str(&spec)
+        .map_err(|err| GolemError(format!("Invalid OpenAPI specification: {err}")))?;
+    let api = Api::from_spec(&spec, &args.package, &args.interface)?;
+
+    let wit_path = args
+        .output
+        .join("wit")
+        .join(format!("{}.wit", api.file_name()));
+    let rust_path = args
+        .output
+        .join("src")
+        .join(format!("{}.rs", api.file_name().replace('-', "_")));
+    write_file(&wit_path, &api.render_wit())?;
+    write_file(&rust_path, &api.render_host(args.timeout))?;
+
+    Ok(GolemResult::Str(format!(
+        "Generated {} and {}",
+        wit_path.display(),
+        rust_path.display()
+    )))
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), GolemError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|err| GolemError(format!("Failed to create directory {dir:?}: {err}")))?;
+    }
+    std::fs::write(path, content)
+        .map_err(|err| GolemError(format!("Failed to write {path:?}: {err}")))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum WitType {
+    Bool,
+    S32,
+    S64,
+    F32,
+    F64,
+    String,
+    /// Schemas without a WIT equivalent, passed as a string holding the JSON value
+    Json,
+    List(Box<WitType>),
+    Option(Box<WitType>),
+    Named(String),
+}
+
+impl WitType {
+    fn list(item: WitType) -> WitType {
+        match item {
+            WitType::Json => WitType::Json,
+            item => WitType::List(Box::new(item)),
+        }
+    }
+
+    fn optional(value: WitType) -> WitType {
+        match value {
+            WitType::Json | WitType::Option(_) => value,
+            value => WitType::Option(Box::new(value)),
+        }
+    }
+
+    fn wit(&self) -> String {
+        match self {
+            WitType::Bool => "bool".to_string(),
+            WitType::S32 => "s32".to_string(),
+            WitType::S64 => "s64".to_string(),
+            WitType::F32 => "f32".to_string(),
+            WitType::F64 => "f64".to_string(),
+            WitType::String | WitType::Json => "string".to_string(),
+            WitType::List(item) => format!("list<{}>", item.wit()),
+            WitType::Option(value) => format!("option<{}>", value.wit()),
+            WitType::Named(name) => wit_ident(name),
+        }
+    }
+
+    fn rust(&self) -> String {
+        match self {
+            WitType::Bool => "bool".to_string(),
+            WitType::S32 => "i32".to_string(),
+            WitType::S64 => "i64".to_string(),
+            WitType::F32 => "f32".to_string(),
+            WitType::F64 => "f64".to_string(),
+            WitType::String | WitType::Json => "String".to_string(),
+            WitType::List(item) => format!("Vec<{}>", item.rust()),
+            WitType::Option(value) => format!("Option<{}>", value.rust()),
+            WitType::Named(name) => format!("api::{}", upper_camel_case(name)),
+        }
+    }
+
+    /// Rust expression converting the value of this type to a `serde_json::Value`
+    fn to_json(&self, expr: &str) -> String {
+        match self {
+            WitType::Json => format!("json_string_to_value(&{expr})"),
+            _ => format!("{expr}.to_json()"),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum TypeDef {
+    Record { name: String, fields: Vec<Field> },
+    Enum { name: String, cases: Vec<String> },
+}
+
+#[derive(Debug)]
+struct Field {
+    json_name: String,
+    ty: WitType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ParamLocation {
+    Path,
+    Query,
+    Body,
+}
+
+#[derive(Debug)]
+struct Param {
+    name: String,
+    json_name: String,
+    location: ParamLocation,
+    ty: WitType,
+}
+
+#[derive(Debug)]
+struct Function {
+    name: String,
+    doc: Option<String>,
+    method: &'static str,
+    path: String,
+    params: Vec<Param>,
+    result: Option<WitType>,
+}
+
+#[derive(Debug)]
+struct Api {
+    title: String,
+    package: String,
+    namespace: String,
+    package_name: String,
+    interface: String,
+    default_base_url: String,
+    types: Vec<TypeDef>,
+    functions: Vec<Function>,
+}
+
+impl Api {
+    fn from_spec(spec: &OpenAPI, package: &str, interface: &str) -> Result<Api, GolemError> {
+        let (namespace, package_name) = package
+            .split('@')
+            .next()
+            .and_then(|name| name.split_once(':'))
+            .filter(|(namespace, name)| is_kebab_case(namespace) && is_kebab_case(name))
+            .ok_or_else(|| {
+                GolemError(format!(
+                    "Invalid package {package}, expected a name like petstore:client"
+                ))
+            })?;
+        if !is_kebab_case(interface) {
+            return Err(GolemError(format!(
+                "Invalid interface {interface}, expected a kebab-case name"
+            )));
+        }
+
+        let mut generator = Generator::new(spec);
+        let mut functions: Vec<Function> = Vec::new();
+        for (path, item) in &spec.paths.paths {
+            let Some(item) = item.as_item() else {
+                continue;
+            };
+            let operations = [
+                ("GET", &item.get),
+                ("PUT", &item.put),
+                ("POST", &item.post),
+                ("DELETE", &item.delete),
+                ("OPTIONS", &item.options),
+                ("HEAD", &item.head),
+                ("PATCH", &item.patch),
+                ("TRACE", &item.trace),
+            ];
+            for (method, operation) in operations {
+                if let Some(operation) = operation {
+                    let mut function =
+                        generator.function(method, path, &item.parameters, operation)?;
+                    let base_name = function.name.clone();
+                    let mut idx = 2;
+                    while functions.iter().any(|f| f.name == function.name) {
+                        function.name = format!("{base_name}{idx}");
+                        idx += 1;
+                    }
+                    functions.push(function);
+                }
+            }
+        }
+
+        Ok(Api {
+            title: spec.info.title.clone(),
+            package: package.to_string(),
+            namespace: namespace.to_string(),
+            package_name: package_name.to_string(),
+            interface: interface.to_string(),
+            default_base_url: default_base_url(spec),
+            types: generator.types,
+            functions,
+        })
+    }
+
+    fn file_name(&self) -> String {
+        format!("{}-{}", self.namespace, self.package_name)
+    }
+
+    fn base_url_env_var(&self) -> String {
+        format!("{}_BASE_URL", self.file_name().replace('-', "_")).to_uppercase()
+    }
+
+    fn render_wit(&self) -> String {
+        let mut wit = String::new();
+        let _ = writeln!(wit, "package {};", self.package);
+        let _ = writeln!(wit);
+        let _ = writeln!(
+            wit,
+            "/// Client of {}, generated from its OpenAPI specification",
+            self.title
+        );
+        let _ = writeln!(wit, "interface {} {{", wit_ident(&self.interface));
+        let _ = writeln!(
+            wit,
+            "  /// Failure of a call, with the status code of the error response, or no status if\n  /// the service could not be reached"
+        );
+        let _ = writeln!(wit, "  record api-error {{");
+        let _ = writeln!(wit, "    status: option<u16>,");
+        let _ = writeln!(wit, "    message: string,");
+        let _ = writeln!(wit, "  }}");
+
+        for def in &self.types {
+            let _ = writeln!(wit);
+            match def {
+                TypeDef::Record { name, fields } => {
+                    let _ = writeln!(wit, "  record {} {{", wit_ident(name));
+                    for field in fields {
+                        let _ = writeln!(
+                            wit,
+                            "    {}: {},",
+                            wit_ident(&kebab_case(&field.json_name)),
+                            field.ty.wit()
+                        );
+                    }
+                    let _ = writeln!(wit, "  }}");
+                }
+                TypeDef::Enum { name, cases } => {
+                    let _ = writeln!(wit, "  enum {} {{", wit_ident(name));
+                    for case in cases {
+                        let _ = writeln!(wit, "    {},", wit_ident(&kebab_case(case)));
+                    }
+                    let _ = writeln!(wit, "  }}");
+                }
+            }
+        }
+
+        for function in &self.functions {
+            let _ = writeln!(wit);
+            if let Some(doc) = &function.doc {
+                let _ = writeln!(wit, "  /// {doc}");
+            }
+            let params = function
+                .params
+                .iter()
+                .map(|param| format!("{}: {}", wit_ident(&param.name), param.ty.wit()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let result = function
+                .result
+                .as_ref()
+                .map(|ty| ty.wit())
+                .unwrap_or_else(|| "_".to_string());
+            let _ = writeln!(
+                wit,
+                "  {}: func({params}) -> result<{result}, api-error>;",
+                wit_ident(&function.name)
+            );
+        }
+        let _ = writeln!(wit, "}}");
+        let _ = writeln!(wit);
+        let _ = writeln!(wit, "world imports {{");
+        let _ = writeln!(wit, "  import {};", wit_ident(&self.interface));
+        let _ = writeln!(wit, "}}");
+        wit
+    }
+
+    fn render_host(&self, timeout_seconds: u64) -> String {
+        let module = format!(
+            "self::{}::{}::{}",
+            rust_ident(&self.namespace),
+            rust_ident(&self.package_name),
+            rust_ident(&self.interface)
+        );
+        let mut rust = String::new();
+        let _ = writeln!(
+            rust,
+            "// Generated by `golem-cli http-stubgen` from the OpenAPI specification of {}.",
+            self.title
+        );
+        let _ = writeln!(rust, "//");
+        let _ = writeln!(
+            rust,
+            "// Add the bindings to the linker of the worker executor with\n// `{}::add_to_linker_get_host(&mut linker, get_durable_ctx)`.\n// The base URL of the service can be overridden by the {} worker environment variable.",
+            module.replace("self::", ""),
+            self.base_url_env_var()
+        );
+        let _ = writeln!(rust);
+        let _ = writeln!(rust, "#![allow(dead_code)]");
+        let _ = writeln!(rust);
+        let _ = writeln!(rust, "use std::time::Duration;");
+        let _ = writeln!(rust);
+        let _ = writeln!(rust, "use async_trait::async_trait;");
+        let _ = writeln!(
+            rust,
+            "use golem_worker_executor_base::durable_host::rest::*;"
+        );
+        let _ = writeln!(
+            rust,
+            "use golem_worker_executor_base::durable_host::DurableWorkerCtx;"
+        );
+        let _ = writeln!(
+            rust,
+            "use golem_worker_executor_base::workerctx::WorkerCtx;"
+        );
+        let _ = writeln!(rust, "use serde_json::{{Map, Value}};");
+        let _ = writeln!(rust);
+        let _ = writeln!(rust, "use {module} as api;");
+        let _ = writeln!(rust, "use {module}::Host;");
+        let _ = writeln!(rust);
+        let _ = writeln!(rust, "wasmtime::component::bindgen!({{");
+        let _ = writeln!(rust, "    path: \"wit/{}.wit\",", self.file_name());
+        let _ = writeln!(rust, "    interfaces: \"");
+        let _ = writeln!(
+            rust,
+            "      import {}/{};",
+            self.package,
+            wit_ident(&self.interface)
+        );
+        let _ = writeln!(rust, "    \",");
+        let _ = writeln!(rust, "    tracing: false,");
+        let _ = writeln!(rust, "    async: true,");
+        let _ = writeln!(rust, "    trappable_imports: true,");
+        let _ = writeln!(rust, "    skip_mut_forwarding_impls: true,");
+        let _ = writeln!(rust, "}});");
+        let _ = writeln!(rust);
+        let _ = writeln!(
+            rust,
+            "const INTERFACE: &str = \"{}:{}/{}\";",
+            self.namespace, self.package_name, self.interface
+        );
+        let _ = writeln!(rust);
+        let _ = writeln!(rust, "const ENDPOINT: RestEndpoint = RestEndpoint {{");
+        let _ = writeln!(
+            rust,
+            "    base_url_env_var: \"{}\",",
+            self.base_url_env_var()
+        );
+        let _ = writeln!(rust, "    default_base_url: {:?},", self.default_base_url);
+        let _ = writeln!(rust, "    timeout: Duration::from_secs({timeout_seconds}),");
+        let _ = writeln!(rust, "}};");
+
+        let _ = writeln!(rust);
+        let _ = writeln!(rust, "#[async_trait]");
+        let _ = writeln!(
+            rust,
+            "impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {{"
+        );
+        for (idx, function) in self.functions.iter().enumerate() {
+            if idx > 0 {
+                let _ = writeln!(rust);
+            }
+            self.render_host_function(&mut rust, function);
+        }
+        let _ = writeln!(rust, "}}");
+
+        for def in &self.types {
+            let _ = writeln!(rust);
+            render_conversions(&mut rust, def);
+        }
+        let _ = writeln!(rust);
+        rust.push_str(HOST_HELPERS);
+        rust
+    }
+
+    fn render_host_function(&self, rust: &mut String, function: &Function) {
+        let params = function
+            .params
+            .iter()
+            .map(|param| format!(", {}: {}", rust_ident(&param.name), param.ty.rust()))
+            .collect::<String>();
+        let result = function
+            .result
+            .as_ref()
+            .map(|ty| ty.rust())
+            .unwrap_or_else(|| "()".to_string());
+        let _ = writeln!(
+            rust,
+            "    async fn {}(&mut self{params}) -> anyhow::Result<Result<{result}, api::ApiError>> {{",
+            rust_ident(&function.name)
+        );
+
+        let mut path_template = String::new();
+        let mut path_args = Vec::new();
+        let mut rest = function.path.as_str();
+        while let Some((literal, tail)) = rest.split_once('{') {
+            path_template.push_str(literal);
+            path_template.push_str("{}");
+            let (name, tail) = tail.split_once('}').unwrap_or((tail, ""));
+            if let Some(param) = function
+                .params
+                .iter()
+                .find(|param| param.location == ParamLocation::Path && param.json_name == name)
+            {
+                path_args.push(format!(
+                    "path_segment({})",
+                    param.ty.to_json(&rust_ident(&param.name))
+                ));
+            }
+            rest = tail;
+        }
+        path_template.push_str(rest);
+        let path = if path_args.is_empty() {
+            format!("{:?}.to_string()", function.path)
+        } else {
+            format!("format!({:?}, {})", path_template, path_args.join(", "))
+        };
+
+        let query_params = function
+            .params
+            .iter()
+            .filter(|param| param.location == ParamLocation::Query)
+            .map(|param| {
+                format!(
+                    "({:?}, {})",
+                    param.json_name,
+                    param.ty.to_json(&rust_ident(&param.name))
+                )
+            })
+            .collect::<Vec<_>>();
+        let query = if query_params.is_empty() {
+            "Vec::new()".to_string()
+        } else {
+            format!("query_pairs(vec![{}])", query_params.join(", "))
+        };
+
+        let body = match function
+            .params
+            .iter()
+            .find(|param| param.location == ParamLocation::Body)
+        {
+            None => "None".to_string(),
+            Some(param) => {
+                let name = rust_ident(&param.name);
+                match &param.ty {
+                    WitType::Json => format!("Some({name})"),
+                    WitType::Option(value) => {
+                        format!("{name}.map(|body| {}.to_string())", value.to_json("body"))
+                    }
+                    ty => format!("Some({}.to_string())", ty.to_json(&name)),
+                }
+            }
+        };
+
+        let decode = match &function.result {
+            None => "decode_unit_response",
+            Some(WitType::Json) => "decode_json_response",
+            Some(_) => "decode_response",
+        };
+
+        let _ = writeln!(rust, "        let response = self");
+        let _ = writeln!(rust, "            .durable_rest_call(");
+        let _ = writeln!(rust, "                INTERFACE,");
+        let _ = writeln!(rust, "                {:?},", function.name);
+        let _ = writeln!(rust, "                &ENDPOINT,");
+        let _ = writeln!(rust, "                RestRequest {{");
+        let _ = writeln!(
+            rust,
+            "                    method: {:?}.to_string(),",
+            function.method
+        );
+        let _ = writeln!(rust, "                    path: {path},");
+        let _ = writeln!(rust, "                    query: {query},");
+        let _ = writeln!(rust, "                    body: {body},");
+        let _ = writeln!(rust, "                }},");
+        let _ = writeln!(rust, "            )");
+        let _ = writeln!(rust, "            .await?;");
+        let _ = writeln!(
+            rust,
+            "        Ok({decode}(response).map_err(api::ApiError::from))"
+        );
+        let _ = writeln!(rust, "    }}");
+    }
+}
+
+fn render_conversions(rust: &mut String, def: &TypeDef) {
+    match def {
+        TypeDef::Record { name, fields } => {
+            let ty = format!("api::{}", upper_camel_case(name));
+            let _ = writeln!(rust, "impl ToJson for {ty} {{");
+            let _ = writeln!(rust, "    fn to_json(&self) -> Value {{");
+            let _ = writeln!(rust, "        let mut object = Map::new();");
+            for field in fields {
+                let name = rust_ident(&kebab_case(&field.json_name));
+                match &field.ty {
+                    WitType::Option(value) => {
+                        let _ = writeln!(rust, "        if let Some(value) = &self.{name} {{");
+                        let _ = writeln!(
+                            rust,
+                            "            object.insert({:?}.to_string(), {});",
+                            field.json_name,
+                            value.to_json("value")
+                        );
+                        let _ = writeln!(rust, "        }}");
+                    }
+                    ty => {
+                        let _ = writeln!(
+                            rust,
+                            "        object.insert({:?}.to_string(), {});",
+                            field.json_name,
+                            ty.to_json(&format!("self.{name}"))
+                        );
+                    }
+                }
+            }
+            let _ = writeln!(rust, "        Value::Object(object)");
+            let _ = writeln!(rust, "    }}");
+            let _ = writeln!(rust, "}}");
+            let _ = writeln!(rust);
+            let _ = writeln!(rust, "impl FromJson for {ty} {{");
+            let _ = writeln!(
+                rust,
+                "    fn from_json(value: &Value) -> Result<Self, String> {{"
+            );
+            let _ = writeln!(rust, "        Ok({ty} {{");
+            for field in fields {
+                let decode = match field.ty {
+                    WitType::Json => "json_field",
+                    _ => "field",
+                };
+                let _ = writeln!(
+                    rust,
+                    "            {}: {decode}(value, {:?})?,",
+                    rust_ident(&kebab_case(&field.json_name)),
+                    field.json_name
+                );
+            }
+            let _ = writeln!(rust, "        }})");
+            let _ = writeln!(rust, "    }}");
+            let _ = writeln!(rust, "}}");
+        }
+        TypeDef::Enum { name, cases } => {
+            let ty = format!("api::{}", upper_camel_case(name));
+            let _ = writeln!(rust, "impl ToJson for {ty} {{");
+            let _ = writeln!(rust, "    fn to_json(&self) -> Value {{");
+            let _ = writeln!(rust, "        Value::from(match self {{");
+            for case in cases {
+                let _ = writeln!(
+                    rust,
+                    "            {ty}::{} => {case:?},",
+                    upper_camel_case(&kebab_case(case))
+                );
+            }
+            let _ = writeln!(rust, "        }})");
+            let _ = writeln!(rust, "    }}");
+            let _ = writeln!(rust, "}}");
+            let _ = writeln!(rust);
+            let _ = writeln!(rust, "impl FromJson for {ty} {{");
+            let _ = writeln!(
+                rust,
+                "    fn from_json(value: &Value) -> Result<Self, String> {{"
+            );
+            let _ = writeln!(rust, "        match value.as_str() {{");
+            for case in cases {
+                let _ = writeln!(
+                    rust,
+                    "            Some({case:?}) => Ok({ty}::{}),",
+                    upper_camel_case(&kebab_case(case))
+                );
+            }
+            let _ = writeln!(
+                rust,
+                "            _ => Err(format!(\"unexpected value {{value}}\")),"
+            );
+            let _ = writeln!(rust, "        }}");
+            let _ = writeln!(rust, "    }}");
+            let _ = writeln!(rust, "}}");
+        }
+    }
+}
+
+/// Collects the types used by the operations, turning the object schemas into records and the
+/// string enumerations into enums
+struct Generator<'a> {
+    spec: &'a OpenAPI,
+    types: Vec<TypeDef>,
+    type_names: HashSet<String>,
+    components: HashMap<String, WitType>,
+    resolving: HashSet<String>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(spec: &'a OpenAPI) -> Self {
+        Self {
+            spec,
+            types: Vec::new(),
+            type_names: HashSet::from(["api-error".to_string()]),
+            components: HashMap::new(),
+            resolving: HashSet::new(),
+        }
+    }
+
+    fn function(
+        &mut self,
+        method: &'static str,
+        path: &str,
+        common_parameters: &[ReferenceOr<Parameter>],
+        operation: &Operation,
+    ) -> Result<Function, GolemError> {
+        let spec = self.spec;
+        let components = spec.components.as_ref();
+        let name = kebab_case(
+            &operation
+                .operation_id
+                .clone()
+                .unwrap_or_else(|| format!("{method} {path}")),
+        );
+
+        let mut params: Vec<Param> = Vec::new();
+        for parameter in common_parameters.iter().chain(&operation.parameters) {
+            let parameter = match parameter {
+                ReferenceOr::Item(parameter) => Some(parameter),
+                ReferenceOr::Reference { reference } => reference
+                    .strip_prefix("#/components/parameters/")
+                    .and_then(|name| components.and_then(|c| c.parameters.get(name)))
+                    .and_then(|parameter| parameter.as_item()),
+            };
+            let (data, location) = match parameter {
+                Some(Parameter::Path { parameter_data, .. }) => {
+                    (parameter_data, ParamLocation::Path)
+                }
+                Some(Parameter::Query { parameter_data, .. }) => {
+                    (parameter_data, ParamLocation::Query)
+                }
+                _ => continue,
+            };
+            let ty = match &data.format {
+                ParameterSchemaOrContent::Schema(schema) => {
+                    self.type_of(schema, &format!("{name}-{}", data.name))
+                }
+                ParameterSchemaOrContent::Content(_) => WitType::Json,
+            };
+            let param = Param {
+                name: kebab_case(&data.name),
+                json_name: data.name.clone(),
+                location,
+                ty: if data.required {
+                    ty
+                } else {
+                    WitType::optional(ty)
+                },
+            };
+            // Operation parameters override the common ones of the path
+            params.retain(|p| !(p.json_name == param.json_name && p.location == location));
+            params.push(param);
+        }
+
+        for (idx, segment) in path.split('{').skip(1).enumerate() {
+            let param_name = segment.split('}').next().unwrap_or_default();
+            if !params
+                .iter()
+                .any(|p| p.location == ParamLocation::Path && p.json_name == param_name)
+            {
+                return Err(GolemError(format!(
+                    "Path parameter {param_name} (#{}) of {method} {path} is not declared",
+                    idx + 1
+                )));
+            }
+        }
+
+        let request_body = operation.request_body.as_ref().and_then(|body| match body {
+            ReferenceOr::Item(body) => Some(body),
+            ReferenceOr::Reference { reference } => reference
+                .strip_prefix("#/components/requestBodies/")
+                .and_then(|name| components.and_then(|c| c.request_bodies.get(name)))
+                .and_then(|body| body.as_item()),
+        });
+        if let Some(body) = request_body {
+            let ty = match json_schema(&body.content) {
+                Some(schema) => self.type_of(schema, &format!("{name}-body")),
+                None => WitType::Json,
+            };
+            let body_name = if params.iter().any(|p| p.name == "body") {
+                "request-body"
+            } else {
+                "body"
+            };
+            params.push(Param {
+                name: body_name.to_string(),
+                json_name: body_name.to_string(),
+                location: ParamLocation::Body,
+                ty: if body.required {
+                    ty
+                } else {
+                    WitType::optional(ty)
+                },
+            });
+        }
+
+        let success = operation
+            .responses
+            .responses
+            .iter()
+            .find(|(status, _)| match status {
+                StatusCode::Code(code) => (200..300).contains(code),
+                StatusCode::Range(range) => *range == 2,
+            })
+            .and_then(|(_, response)| match response {
+                ReferenceOr::Item(response) => Some(response),
+                ReferenceOr::Reference { reference } => reference
+                    .strip_prefix("#/components/responses/")
+                    .and_then(|name| components.and_then(|c| c.responses.get(name)))
+                    .and_then(|response| response.as_item()),
+            });
+        let result = match success {
+            Some(response) if !response.content.is_empty() => {
+                Some(match json_schema(&response.content) {
+                    Some(schema) => self.type_of(schema, &format!("{name}-response")),
+                    None => WitType::Json,
+                })
+            }
+            _ => None,
+        };
+
+        Ok(Function {
+            name,
+            doc: operation
+                .summary
+                .as_ref()
+                .or(operation.description.as_ref())
+                .and_then(|doc| doc.lines().next())
+                .map(|doc| doc.trim().to_string())
+                .filter(|doc| !doc.is_empty()),
+            method,
+            path: path.to_string(),
+            params,
+            result,
+        })
+    }
+
+    fn type_of<S: Borrow<Schema>>(&mut self, schema: &ReferenceOr<S>, hint: &str) -> WitType {
+        match schema {
+            ReferenceOr::Item(schema) => self.type_of_schema(schema.borrow(), hint),
+            ReferenceOr::Reference { reference } => {
+                match reference.strip_prefix("#/components/schemas/") {
+                    Some(name) => self.component_type(name),
+                    None => WitType::Json,
+                }
+            }
+        }
+    }
+
+    fn component_type(&mut self, name: &str) -> WitType {
+        if let Some(ty) = self.components.get(name) {
+            return ty.clone();
+        }
+        // WIT types cannot be recursive
+        if !self.resolving.insert(name.to_string()) {
+            return WitType::Json;
+        }
+        let spec = self.spec;
+        let ty = match spec
+            .components
+            .as_ref()
+            .and_then(|components| components.schemas.get(name))
+        {
+            Some(schema) => self.type_of(schema, name),
+            None => WitType::Json,
+        };
+        self.resolving.remove(name);
+        self.components.insert(name.to_string(), ty.clone());
+        ty
+    }
+
+    fn type_of_schema(&mut self, schema: &Schema, hint: &str) -> WitType {
+        let ty = match &schema.schema_kind {
+            SchemaKind::Type(Type::Boolean(_)) => WitType::Bool,
+            SchemaKind::Type(Type::Integer(integer)) => match integer.format {
+                VariantOrUnknownOrEmpty::Item(IntegerFormat::Int32) => WitType::S32,
+                _ => WitType::S64,
+            },
+            SchemaKind::Type(Type::Number(number)) => match number.format {
+                VariantOrUnknownOrEmpty::Item(NumberFormat::Float) => WitType::F32,
+                _ => WitType::F64,
+            },
+            SchemaKind::Type(Type::String(string)) => {
+                let cases = string
+                    .enumeration
+                    .iter()
+                    .flatten()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let case_names = cases
+                    .iter()
+                    .map(|case| kebab_case(case))
+                    .collect::<HashSet<_>>();
+                if cases.is_empty() || case_names.len() != cases.len() {
+                    WitType::String
+                } else {
+                    let name = self.type_name(hint);
+                    self.types.push(TypeDef::Enum {
+                        name: name.clone(),
+                        cases,
+                    });
+                    WitType::Named(name)
+                }
+            }
+            SchemaKind::Type(Type::Array(array)) => match &array.items {
+                Some(items) => WitType::list(self.type_of(items, &format!("{hint}-item"))),
+                None => WitType::Json,
+            },
+            SchemaKind::Type(Type::Object(object)) if !object.properties.is_empty() => {
+                let name = self.type_name(hint);
+                let mut fields = Vec::new();
+                for (property, schema) in &object.properties {
+                    let ty = self.type_of(schema, &format!("{hint}-{property}"));
+                    fields.push(Field {
+                        json_name: property.clone(),
+                        ty: if object.required.contains(property) {
+                            ty
+                        } else {
+                            WitType::optional(ty)
+                        },
+                    });
+                }
+                self.types.push(TypeDef::Record {
+                    name: name.clone(),
+                    fields,
+                });
+                WitType::Named(name)
+            }
+            _ => WitType::Json,
+        };
+        if schema.schema_data.nullable {
+            WitType::optional(ty)
+        } else {
+            ty
+        }
+    }
+
+    fn type_name(&mut self, hint: &str) -> String {
+        let base = kebab_case(hint);
+        let mut name = base.clone();
+        let mut idx = 2;
+        while !self.type_names.insert(name.clone()) {
+            name = format!("{base}{idx}");
+            idx += 1;
+        }
+        name
+    }
+}
+
+fn json_schema(content: &Content) -> Option<&ReferenceOr<Schema>> {
+    content
+        .iter()
+        .find(|(media_type, _)| media_type.contains("json"))
+        .and_then(|(_, media_type)| media_type.schema.as_ref())
+}
+
+fn default_base_url(spec: &OpenAPI) -> String {
+    spec.servers
+        .first()
+        .map(|server| {
+            let mut url = server.url.clone();
+            for (name, variable) in server.variables.iter().flatten() {
+                url = url.replace(&format!("{{{name}}}"), &variable.default);
+            }
+            url
+        })
+        .unwrap_or_default()
+}
+
+/// Splits a name in any of the usual casings into lower case words
+fn words(name: &str) -> Vec<String> {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (idx, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_ascii_uppercase() && !current.is_empty() {
+            let prev = chars[idx - 1];
+            let next_is_lower = chars.get(idx + 1).is_some_and(|c| c.is_ascii_lowercase());
+            if !prev.is_ascii_uppercase() || next_is_lower {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c.to_ascii_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Converts a name to a WIT identifier, whose words cannot start with a digit
+fn kebab_case(name: &str) -> String {
+    let mut result = String::new();
+    for word in words(name) {
+        if word.starts_with(|c: char| c.is_ascii_digit()) {
+            if result.is_empty() {
+                result.push('n');
+            }
+        } else if !result.is_empty() {
+            result.push('-');
+        }
+        result.push_str(&word);
+    }
+    if result.is_empty() {
+        "value".to_string()
+    } else {
+        result
+    }
+}
+
+fn is_kebab_case(name: &str) -> bool {
+    !name.is_empty() && kebab_case(name) == name
+}
+
+fn upper_camel_case(name: &str) -> String {
+    name.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+const WIT_KEYWORDS: &[&str] = &[
+    "as",
+    "bool",
+    "borrow",
+    "char",
+    "constructor",
+    "enum",
+    "export",
+    "f32",
+    "f64",
+    "flags",
+    "from",
+    "func",
+    "future",
+    "import",
+    "include",
+    "interface",
+    "list",
+    "own",
+    "option",
+    "package",
+    "record",
+    "resource",
+    "result",
+    "s8",
+    "s16",
+    "s32",
+    "s64",
+    "static",
+    "stream",
+    "string",
+    "tuple",
+    "type",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "use",
+    "variant",
+    "with",
+    "world",
+];
+
+fn wit_ident(name: &str) -> String {
+    if WIT_KEYWORDS.contains(&name) {
+        format!("%{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// The Rust name `bindgen!` gives to a WIT identifier
+fn rust_ident(name: &str) -> String {
+    let name = name.replace('-', "_");
+    if RUST_KEYWORDS.contains(&name.as_str()) {
+        format!("{name}_")
+    } else {
+        name
+    }
+}
+
+const HOST_HELPERS: &str = r#"impl From<RestError> for api::ApiError {
+    fn from(error: RestError) -> Self {
+        Self {
+            status: error.status,
+            message: error.message,
+        }
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::path::Path;
+
+    use crate::http_stubgen::{kebab_case, Api};
+    use openapiv3::OpenAPI;
+    use wit_parser::{Resolve, UnresolvedPackage};
+
+    /// Resolves the generated WIT package, failing on syntax errors and unknown types
+    fn resolve_wit(wit: &str) -> Resolve {
+        let mut resolve = Resolve::new();
+        let package = UnresolvedPackage::parse(Path::new("petstore-client.wit"), wit).unwrap();
+        resolve.push(package).unwrap();
+        resolve
+    }
+
+    /// Parses the generated host bindings and returns the names of the implemented host functions
+    fn host_functions(host: &str) -> Vec<String> {
+        let file = syn::parse_file(host).unwrap();
+        file.items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Impl(item) => Some(item),
+                _ => None,
+            })
+            .filter(|item| {
+                item.trait_
+                    .as_ref()
+                    .is_some_and(|(_, path, _)| path.is_ident("Host"))
+            })
+            .flat_map(|item| item.items.iter())
+            .filter_map(|item| match item {
+                syn::ImplItem::Fn(function) => Some(function.sig.ident.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    const PETSTORE: &str = r#"
+openapi: 3.0.0
+info:
+  title: Petstore
+  version: 1.0.0
+servers:
+  - url: https://petstore.example.com/v1
+paths:
+  /pets/{petId}:
+    get:
+      operationId: getPetById
+      summary: Finds a pet by its id
+      parameters:
+        - name: petId
+          in: path
+          required: true
+          schema:
+            type: integer
+            format: int64
+      responses:
+        '200':
+          description: The pet
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/Pet'
+  /pets:
+    get:
+      operationId: findPets
+      parameters:
+        - name: status
+          in: query
+          schema:
+            type: string
+            enum: [available, sold]
+      responses:
+        '200':
+          description: The pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: '#/components/schemas/Pet'
+    post:
+      operationId: addPet
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/Pet'
+      responses:
+        '204':
+          description: Added
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [id, name]
+      properties:
+        id:
+          type: integer
+          format: int64
+        name:
+          type: string
+        type:
+          type: string
+        tags:
+          type: array
+          items:
+            type: string
+"#;
+
+    #[test]
+    fn names_are_converted_to_kebab_case() {
+        assert_eq!(kebab_case("getPetById"), "get-pet-by-id");
+        assert_eq!(kebab_case("HTTPServer"), "http-server");
+        assert_eq!(kebab_case("pet_id"), "pet-id");
+        assert_eq!(kebab_case("GET /pets/{petId}"), "get-pets-pet-id");
+        assert_eq!(kebab_case("2fa"), "n2fa");
+        assert_eq!(kebab_case("pet 2"), "pet2");
+    }
+
+    #[test]
+    fn operations_are_generated_as_typed_functions() {
+        let spec: OpenAPI = serde_yaml::from_str(PETSTORE).unwrap();
+        let api = Api::from_spec(&spec, "petstore:client", "api").unwrap();
+
+        let wit = api.render_wit();
+        let resolve = resolve_wit(&wit);
+        let interface = resolve
+            .interfaces
+            .iter()
+            .find_map(|(_, interface)| {
+                (interface.name.as_deref() == Some("api")).then_some(interface)
+            })
+            .unwrap();
+        let mut functions = interface.functions.keys().cloned().collect::<Vec<_>>();
+        functions.sort();
+        assert_eq!(functions, vec!["add-pet", "find-pets", "get-pet-by-id"]);
+
+        assert!(wit.contains("package petstore:client;"));
+        assert!(wit.contains("  record pet {\n    id: s64,\n    name: string,\n    %type: option<string>,\n    tags: option<list<string>>,\n  }"));
+        assert!(wit.contains("  enum find-pets-status {\n    available,\n    sold,\n  }"));
+        assert!(wit.contains("  /// Finds a pet by its id\n  get-pet-by-id: func(pet-id: s64) -> result<pet, api-error>;"));
+        assert!(wit.contains(
+            "  find-pets: func(status: option<find-pets-status>) -> result<list<pet>, api-error>;"
+        ));
+        assert!(wit.contains("  add-pet: func(body: pet) -> result<_, api-error>;"));
+
+        let host = api.render_host(30);
+        let mut functions = host_functions(&host);
+        functions.sort();
+        assert_eq!(functions, vec!["add_pet", "find_pets", "get_pet_by_id"]);
+
+        assert!(host.contains("base_url_env_var: \"PETSTORE_CLIENT_BASE_URL\""));
+        assert!(host.contains("timeout: Duration::from_secs(30),"));
+        assert!(host.contains("default_base_url: \"https://petstore.example.com/v1\""));
+        assert!(host.contains(
+            "async fn get_pet_by_id(&mut self, pet_id: i64) -> anyhow::Result<Result<api::Pet, api::ApiError>>"
+        ));
+        assert!(host.contains("path: format!(\"/pets/{}\", path_segment(pet_id.to_json())),"));
+        assert!(host.contains("query: query_pairs(vec![(\"status\", status.to_json())]),"));
+        assert!(host.contains("body: Some(body.to_json().to_string()),"));
+        assert!(host.contains("type_: field(value, \"type\")?,"));
+    }
+
+    #[test]
+    fn undeclared_path_parameters_are_rejected() {
+        let spec: OpenAPI = serde_yaml::from_str(
+            r#"
+openapi: 3.0.0
+info:
+  title: Broken
+  version: 1.0.0
+paths:
+  /pets/{petId}:
+    delete:
+      responses:
+        '204':
+          description: Deleted
+"#,
+        )
+        .unwrap();
+
+        assert!(Api::from_spec(&spec, "broken:client", "api").is_err());
+    }
+}
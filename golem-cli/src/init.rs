@@ -20,6 +20,8 @@ use crate::command::worker::{OssWorkerUriArg, WorkerSubcommand};
 use crate::completion::PrintCompletion;
 use crate::config::{CloudProfile, Config, OssProfile, Profile, ProfileConfig, ProfileName};
 use crate::diagnose::diagnose;
+use crate::golem_wit::{handle_golem_wit, GolemWitArgs};
+use crate::http_stubgen::{handle_http_stubgen, HttpStubgenArgs};
 use crate::model::{
     ComponentUriArg, Format, GolemError, GolemResult, HasFormatConfig, HasVerbosity,
 };
@@ -68,6 +70,20 @@ pub enum InitCommand<ProfileAdd: clap::Args> {
         subcommand: golem_wasm_rpc_stubgen::Command,
     },
 
+    /// Generate typed and durable imports for calling a REST API from an OpenAPI specification
+    #[command()]
+    HttpStubgen {
+        #[command(flatten)]
+        args: HttpStubgenArgs,
+    },
+
+    /// Add the Golem host interfaces missing from golem-wit to the WIT dependencies of a component
+    #[command()]
+    GolemWit {
+        #[command(flatten)]
+        args: GolemWitArgs,
+    },
+
     /// Upload and manage Golem components
     #[command()]
     Component {
@@ -231,6 +247,8 @@ pub async fn async_main<ProfileAdd: Into<UniversalProfileAdd> + clap::Args>(
         }
         #[cfg(feature = "stubgen")]
         InitCommand::Stubgen { subcommand } => handle_stubgen(subcommand).await,
+        InitCommand::HttpStubgen { args } => handle_http_stubgen(args),
+        InitCommand::GolemWit { args } => handle_golem_wit(args),
         _ => Err(GolemError(
             "Your Golem CLI is not configured. Please run `golem-cli init`".to_owned(),
         )),
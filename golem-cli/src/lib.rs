@@ -38,9 +38,13 @@ pub mod connect_output;
 pub mod diagnose;
 pub mod examples;
 pub mod factory;
+pub mod golem_wit;
+pub mod http_stubgen;
 pub mod init;
 pub mod model;
 pub mod oss;
+pub mod picker;
+pub mod progress;
 pub mod service;
 pub mod stubgen;
 
@@ -12,9 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod batch;
 pub mod component;
 pub mod deploy;
 pub mod invoke_result_view;
+pub mod json_schema;
 pub mod text;
 pub mod wave;
 
@@ -27,6 +29,7 @@ use std::str::FromStr;
 
 use crate::cloud::AccountId;
 use crate::model::text::fmt::TextFormat;
+use crate::oss::model::OssContext;
 use clap::builder::{StringValueParser, TypedValueParser};
 use clap::error::{ContextKind, ContextValue, ErrorKind};
 use clap::{Arg, ArgMatches, Error, FromArgMatches};
@@ -267,45 +270,60 @@ impl clap::Args for ComponentUriArg {
     }
 }
 
+/// Component reference arguments. If the component is not specified, it is picked interactively.
 #[derive(clap::Args, Debug, Clone)]
-#[group(required = true, multiple = false)]
+#[group(multiple = false)]
 struct ComponentUriOrNameArgs {
     /// Component URI. Either URN or URL.
-    #[arg(
-        short = 'C',
-        long,
-        group = "component_group",
-        required = true,
-        value_name = "URI"
-    )]
+    #[arg(short = 'C', long, group = "component_group", value_name = "URI")]
     component: Option<ComponentUri>,
 
     /// Name of the component
-    #[arg(short, long, group = "component_group", required = true)]
+    #[arg(short, long, group = "component_group")]
     component_name: Option<String>,
+
+    #[command(flatten)]
+    project: OssContext,
 }
 
 impl From<&ComponentUriOrNameArgs> for ComponentUriArg {
     fn from(value: &ComponentUriOrNameArgs) -> ComponentUriArg {
-        if let Some(uri) = &value.component {
-            ComponentUriArg {
-                uri: uri.clone(),
+        match (&value.component, &value.component_name) {
+            (Some(uri), _) => ComponentUriArg {
+                uri: ComponentUriOrPick::Uri(uri.clone()),
                 explicit_name: false,
-            }
-        } else {
-            let name = value.component_name.as_ref().unwrap().to_string();
-
-            ComponentUriArg {
-                uri: ComponentUri::URL(ComponentUrl { name }),
+                project: value.project,
+            },
+            (None, Some(name)) => ComponentUriArg {
+                uri: ComponentUriOrPick::Uri(ComponentUri::URL(ComponentUrl {
+                    name: name.to_string(),
+                })),
                 explicit_name: true,
-            }
+                project: value.project,
+            },
+            (None, None) => ComponentUriArg {
+                uri: ComponentUriOrPick::Pick,
+                explicit_name: false,
+                project: value.project,
+            },
         }
     }
 }
 
 impl From<&ComponentUriArg> for ComponentUriOrNameArgs {
     fn from(value: &ComponentUriArg) -> ComponentUriOrNameArgs {
-        let name = if let ComponentUri::URL(url) = &value.uri {
+        let uri = match &value.uri {
+            ComponentUriOrPick::Uri(uri) => uri,
+            ComponentUriOrPick::Pick => {
+                return ComponentUriOrNameArgs {
+                    component: None,
+                    component_name: None,
+                    project: value.project,
+                }
+            }
+        };
+
+        let name = if let ComponentUri::URL(url) = uri {
             if value.explicit_name {
                 Some(&url.name)
             } else {
@@ -317,12 +335,14 @@ impl From<&ComponentUriArg> for ComponentUriOrNameArgs {
 
         match name {
             None => ComponentUriOrNameArgs {
-                component: Some(value.uri.clone()),
+                component: Some(uri.clone()),
                 component_name: None,
+                project: value.project,
             },
             Some(name) => ComponentUriOrNameArgs {
                 component: None,
                 component_name: Some(name.to_string()),
+                project: value.project,
             },
         }
     }
@@ -331,10 +351,19 @@ impl From<&ComponentUriArg> for ComponentUriOrNameArgs {
 #[derive(Clone, PartialEq, Eq, Debug, Display, FromStr)]
 pub struct ComponentName(pub String); // TODO: Validate
 
+/// A component given on the command line, or `Pick` if it has to be picked interactively
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ComponentUriOrPick {
+    Uri(ComponentUri),
+    Pick,
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct ComponentUriArg {
-    pub uri: ComponentUri,
+    pub uri: ComponentUriOrPick,
     pub explicit_name: bool,
+    /// Project to look up the component name in
+    pub project: OssContext,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Display, FromStr)]
@@ -506,6 +535,12 @@ pub struct WorkerMetadataView {
     pub total_linear_memory_size: u64,
     #[serde(rename = "ownedResources")]
     pub owned_resources: HashMap<String, golem_client::model::ResourceMetadata>,
+    #[serde(rename = "expiresAt")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(rename = "diskUsage")]
+    pub disk_usage: u64,
 }
 
 impl TrimDateTime for WorkerMetadataView {
@@ -534,6 +569,8 @@ impl From<WorkerMetadata> for WorkerMetadataView {
             component_size,
             total_linear_memory_size,
             owned_resources,
+            expires_at,
+            disk_usage,
         } = value;
 
         WorkerMetadataView {
@@ -553,6 +590,8 @@ impl From<WorkerMetadata> for WorkerMetadataView {
             component_size,
             total_linear_memory_size,
             owned_resources,
+            expires_at,
+            disk_usage,
         }
     }
 }
@@ -573,6 +612,8 @@ pub struct WorkerMetadata {
     pub component_size: u64,
     pub total_linear_memory_size: u64,
     pub owned_resources: HashMap<String, golem_client::model::ResourceMetadata>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub disk_usage: u64,
 }
 
 impl From<golem_client::model::WorkerMetadata> for WorkerMetadata {
@@ -591,6 +632,9 @@ impl From<golem_client::model::WorkerMetadata> for WorkerMetadata {
             component_size,
             total_linear_memory_size,
             owned_resources,
+            expires_at,
+            remaining_ttl_seconds: _,
+            disk_usage,
         } = value;
 
         WorkerMetadata {
@@ -608,6 +652,8 @@ impl From<golem_client::model::WorkerMetadata> for WorkerMetadata {
             component_size,
             total_linear_memory_size,
             owned_resources,
+            expires_at,
+            disk_usage,
         }
     }
 }
@@ -680,3 +726,56 @@ impl From<golem_client::model::ApiDeployment> for ApiDeployment {
 pub trait HasVerbosity {
     fn verbosity(&self) -> Verbosity;
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::{ComponentUriArg, ComponentUriOrPick};
+    use clap::{Args, FromArgMatches};
+    use golem_common::uri::oss::uri::ComponentUri;
+    use golem_common::uri::oss::url::ComponentUrl;
+
+    fn parse_component_arg(args: &[&str]) -> Result<ComponentUriArg, clap::Error> {
+        let command = ComponentUriArg::augment_args(clap::Command::new("test"));
+        let matches =
+            command.try_get_matches_from(std::iter::once("test").chain(args.iter().copied()))?;
+        ComponentUriArg::from_arg_matches(&matches)
+    }
+
+    #[test]
+    fn missing_component_is_picked() {
+        let arg = parse_component_arg(&[]).unwrap();
+
+        assert_eq!(arg.uri, ComponentUriOrPick::Pick);
+        assert!(!arg.explicit_name);
+    }
+
+    #[test]
+    fn component_name_is_used_when_given() {
+        let arg = parse_component_arg(&["--component-name", "shopping-cart"]).unwrap();
+
+        assert_eq!(
+            arg.uri,
+            ComponentUriOrPick::Uri(ComponentUri::URL(ComponentUrl {
+                name: "shopping-cart".to_string()
+            }))
+        );
+        assert!(arg.explicit_name);
+    }
+
+    #[test]
+    fn component_and_component_name_conflict() {
+        let result = parse_component_arg(&[
+            "--component",
+            "urn:component:679ae459-8700-41d9-920c-7e2887459c94",
+            "--component-name",
+            "shopping-cart",
+        ]);
+
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::ArgumentConflict
+        );
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::model::{GolemError, IdempotencyKey};
+use golem_client::api::WorkerError;
+use golem_common::uri::oss::uri::WorkerUri;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// A single line of an `invoke-batch` input file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchInvocation {
+    pub worker: WorkerUri,
+    pub function: String,
+    /// Parameters in the same JSON format as accepted by `invoke-and-await --parameters`
+    #[serde(default)]
+    pub params: Vec<Value>,
+    /// If missing, a fresh key is generated and reused for all attempts of the invocation
+    #[serde(default)]
+    pub idempotency_key: Option<IdempotencyKey>,
+}
+
+/// A single line of an `invoke-batch` results file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchInvocationResult {
+    /// The 1-based line number of the invocation in the input file
+    pub line: usize,
+    pub worker: String,
+    pub function: String,
+    pub idempotency_key: Option<IdempotencyKey>,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BatchInvocationResult {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvokeBatchResult {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub output: PathBuf,
+}
+
+/// Parses the newline-delimited JSON input of `invoke-batch`, skipping empty lines.
+///
+/// Returns the invocations with their 1-based line numbers.
+pub fn parse_batch(input: &str) -> Result<Vec<(usize, BatchInvocation)>, GolemError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str::<BatchInvocation>(line)
+                .map(|invocation| (idx + 1, invocation))
+                .map_err(|err| GolemError(format!("Invalid invocation on line {}: {err}", idx + 1)))
+        })
+        .collect()
+}
+
+/// Whether an invocation failing with this error is worth retrying.
+///
+/// Connection problems, gateway errors and the cluster being rebalanced are transient,
+/// everything else (invalid parameters, failing workers, etc.) is reported as is.
+pub fn is_transient(error: &golem_client::Error<WorkerError>) -> bool {
+    match error {
+        golem_client::Error::Reqwest(error) => {
+            error.is_connect() || error.is_timeout() || error.is_request()
+        }
+        golem_client::Error::Item(WorkerError::Error503(_)) => true,
+        golem_client::Error::Item(WorkerError::Error500(error)) => matches!(
+            error.golem_error,
+            golem_client::model::GolemError::ShardingNotReady(_)
+                | golem_client::model::GolemError::InvalidShardId(_)
+        ),
+        golem_client::Error::Unexpected { code, .. } => matches!(code, 502..=504),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::batch::{is_transient, parse_batch};
+    use golem_client::api::WorkerError;
+    use golem_client::model::{
+        ErrorBody, ErrorsBody, GolemError, GolemErrorBody, GolemErrorShardingNotReady,
+        GolemErrorUnknown,
+    };
+
+    #[test]
+    fn parses_invocations_with_line_numbers() {
+        let input = r#"{"worker": "worker:///shopping-cart/cart-1", "function": "golem:it/api.{add-item}", "params": [{"productId": "p1"}]}
+
+{"worker": "worker:///shopping-cart/cart-2", "function": "golem:it/api.{checkout}", "idempotencyKey": "key-2"}
+"#;
+        let invocations = parse_batch(input).unwrap();
+
+        assert_eq!(invocations.len(), 2);
+        assert_eq!(invocations[0].0, 1);
+        assert_eq!(invocations[0].1.params.len(), 1);
+        assert_eq!(invocations[1].0, 3);
+        assert!(invocations[1].1.params.is_empty());
+        assert_eq!(
+            invocations[1]
+                .1
+                .idempotency_key
+                .as_ref()
+                .map(|key| key.0.as_str()),
+            Some("key-2")
+        );
+    }
+
+    #[test]
+    fn reports_invalid_line() {
+        let input = r#"{"worker": "worker:///shopping-cart/cart-1", "function": "golem:it/api.{checkout}"}
+{"function": "golem:it/api.{checkout}"}"#;
+        let err = parse_batch(input).unwrap_err();
+
+        assert!(err.0.starts_with("Invalid invocation on line 2"));
+    }
+
+    #[test]
+    fn transient_errors() {
+        assert!(is_transient(&golem_client::Error::Item(
+            WorkerError::Error503(ErrorBody {
+                error: "unavailable".to_string(),
+            })
+        )));
+        assert!(is_transient(&golem_client::Error::Item(
+            WorkerError::Error500(GolemErrorBody {
+                golem_error: GolemError::ShardingNotReady(GolemErrorShardingNotReady {}),
+            })
+        )));
+        assert!(is_transient(
+            &golem_client::Error::<WorkerError>::Unexpected {
+                code: 502,
+                data: Default::default(),
+            }
+        ));
+        assert!(!is_transient(&golem_client::Error::Item(
+            WorkerError::Error500(GolemErrorBody {
+                golem_error: GolemError::Unknown(GolemErrorUnknown {
+                    details: "failed".to_string(),
+                }),
+            })
+        )));
+        assert!(!is_transient(&golem_client::Error::Item(
+            WorkerError::Error400(ErrorsBody {
+                errors: vec!["unknown function".to_string()],
+            })
+        )));
+        assert!(!is_transient(
+            &golem_client::Error::<WorkerError>::Unexpected {
+                code: 500,
+                data: Default::default(),
+            }
+        ));
+    }
+}
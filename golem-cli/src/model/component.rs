@@ -22,15 +22,15 @@ use golem_common::model::ComponentId;
 use golem_common::uri::oss::urn::ComponentUrn;
 use golem_wasm_ast::analysis::wave::DisplayNamedFunc;
 use golem_wasm_ast::analysis::{
-    AnalysedExport, AnalysedFunction, AnalysedFunctionResult, AnalysedInstance,
-    AnalysedResourceMode, NameOptionTypePair, NameTypePair, TypeEnum, TypeFlags, TypeRecord,
-    TypeTuple, TypeVariant,
+    AnalysedExport, AnalysedFunction, AnalysedFunctionParameter, AnalysedFunctionResult,
+    AnalysedInstance, AnalysedResourceMode, NameOptionTypePair, NameTypePair, TypeEnum, TypeFlags,
+    TypeRecord, TypeTuple, TypeVariant,
 };
 use rib::{ParsedFunctionName, ParsedFunctionSite};
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Component {
     pub versioned_component_id: VersionedComponentId,
     pub component_name: String,
@@ -125,7 +125,7 @@ impl From<&Component> for ComponentView {
     }
 }
 
-fn render_type(typ: &AnalysedType) -> String {
+pub fn render_type(typ: &AnalysedType) -> String {
     match typ {
         AnalysedType::Variant(TypeVariant { cases }) => {
             let cases_str = cases
@@ -239,6 +239,14 @@ fn resolve_function<'t>(
     component: &'t Component,
     function: &str,
 ) -> Result<(&'t AnalysedFunction, ParsedFunctionName), GolemError> {
+    // Aliases declared by the component are resolved the same way as by the worker service
+    let function = component
+        .metadata
+        .function_aliases
+        .as_ref()
+        .and_then(|aliases| aliases.get(function))
+        .map(|name| name.as_str())
+        .unwrap_or(function);
     let parsed = ParsedFunctionName::parse(function).map_err(GolemError)?;
     let mut functions = Vec::new();
 
@@ -282,21 +290,43 @@ pub fn function_result_types<'t>(
     component: &'t Component,
     function: &str,
 ) -> Result<Vec<&'t AnalysedType>, GolemError> {
+    Ok(function_results(component, function)?
+        .into_iter()
+        .map(|r| &r.typ)
+        .collect())
+}
+
+pub fn function_results<'t>(
+    component: &'t Component,
+    function: &str,
+) -> Result<Vec<&'t AnalysedFunctionResult>, GolemError> {
     let (func, _) = resolve_function(component, function)?;
 
-    Ok(func.results.iter().map(|r| &r.typ).collect())
+    Ok(func.results.iter().collect())
 }
 
 pub fn function_params_types<'t>(
     component: &'t Component,
     function: &str,
 ) -> Result<Vec<&'t AnalysedType>, GolemError> {
+    Ok(function_params(component, function)?
+        .into_iter()
+        .map(|p| &p.typ)
+        .collect())
+}
+
+/// The parameters of a function the caller has to provide, so without the resource handle of
+/// indexed resource functions
+pub fn function_params<'t>(
+    component: &'t Component,
+    function: &str,
+) -> Result<Vec<&'t AnalysedFunctionParameter>, GolemError> {
     let (func, parsed) = resolve_function(component, function)?;
 
     if parsed.function().is_indexed_resource() {
-        Ok(func.parameters.iter().skip(1).map(|r| &r.typ).collect())
+        Ok(func.parameters.iter().skip(1).collect())
     } else {
-        Ok(func.parameters.iter().map(|r| &r.typ).collect())
+        Ok(func.parameters.iter().collect())
     }
 }
 
@@ -12,11 +12,156 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use golem_common::uri::oss::urn::WorkerUrn;
+use crate::model::GolemError;
+use golem_common::uri::oss::urn::{ComponentUrn, WorkerUrn};
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct TryUpdateAllWorkersResult {
     pub triggered: Vec<WorkerUrn>,
     pub failed: Vec<WorkerUrn>,
 }
+
+/// The maximum rate of the updates issued by `component update-workers`, in the form of `10/min`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UpdateRate {
+    pub count: u32,
+    pub per: Duration,
+}
+
+impl UpdateRate {
+    /// The time to wait between two updates
+    pub fn interval(&self) -> Duration {
+        self.per / self.count
+    }
+}
+
+impl FromStr for UpdateRate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (count, unit) = s
+            .split_once('/')
+            .ok_or_else(|| format!("Invalid rate {s}, expected for example 10/min"))?;
+        let count: u32 = count
+            .trim()
+            .parse()
+            .map_err(|err| format!("Invalid number of updates in rate {s}: {err}"))?;
+        if count == 0 {
+            return Err(format!(
+                "Invalid rate {s}, the number of updates must be positive"
+            ));
+        }
+        let per = match unit.trim() {
+            "s" | "sec" => Duration::from_secs(1),
+            "m" | "min" => Duration::from_secs(60),
+            "h" | "hour" => Duration::from_secs(3600),
+            unit => {
+                return Err(format!(
+                    "Invalid unit {unit} in rate {s}, expected one of sec, min or hour"
+                ))
+            }
+        };
+        Ok(UpdateRate { count, per })
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerUpdateFailure {
+    pub worker_urn: WorkerUrn,
+    pub error: String,
+}
+
+/// Progress of a `component update-workers` run, saved after each worker so an interrupted run
+/// can be resumed with its resume token
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerFleetUpdateState {
+    pub component_urn: ComponentUrn,
+    pub target_version: u64,
+    pub succeeded: Vec<WorkerUrn>,
+    pub failed: Vec<WorkerUpdateFailure>,
+}
+
+impl WorkerFleetUpdateState {
+    pub fn new(component_urn: ComponentUrn, target_version: u64) -> Self {
+        Self {
+            component_urn,
+            target_version,
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, GolemError> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            GolemError(format!(
+                "Failed to read the progress of the update from {}: {err}",
+                path.display()
+            ))
+        })?;
+        serde_json::from_str(&content).map_err(|err| {
+            GolemError(format!(
+                "Invalid progress of the update in {}: {err}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Replaces the saved progress, so it is never left partially written
+    pub fn save(&self, path: &Path) -> Result<(), GolemError> {
+        let write = || {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let temp_path = path.with_extension("json.tmp");
+            fs::write(&temp_path, serde_json::to_vec(self)?)?;
+            fs::rename(&temp_path, path)
+        };
+        write().map_err(|err: std::io::Error| {
+            GolemError(format!(
+                "Failed to save the progress of the update to {}: {err}",
+                path.display()
+            ))
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerFleetUpdateResult {
+    pub resume_token: String,
+    pub target_version: u64,
+    /// Number of workers which were already running the target version or a newer one
+    pub skipped: usize,
+    pub succeeded: Vec<WorkerUrn>,
+    pub failed: Vec<WorkerUpdateFailure>,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::deploy::UpdateRate;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_rates() {
+        let rate: UpdateRate = "10/min".parse().unwrap();
+
+        assert_eq!(rate.count, 10);
+        assert_eq!(rate.interval(), Duration::from_secs(6));
+        assert_eq!(
+            "2/s".parse::<UpdateRate>().unwrap().interval(),
+            Duration::from_millis(500)
+        );
+        assert!("0/min".parse::<UpdateRate>().is_err());
+        assert!("10/day".parse::<UpdateRate>().is_err());
+        assert!("10".parse::<UpdateRate>().is_err());
+    }
+}
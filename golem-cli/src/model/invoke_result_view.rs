@@ -166,6 +166,9 @@ mod tests {
                     results: func_res,
                 })],
                 memories: vec![],
+                files: None,
+                function_aliases: None,
+                capabilities: None,
             },
             project_id: None,
             created_at: Some(Utc::now()),
@@ -0,0 +1,281 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! JSON Schemas of the JSON representation of WIT values, as accepted by `invoke` with `-j` and
+//! returned as invocation results:
+//! - records are objects, tuples and lists are arrays
+//! - variants are objects with the case name as their only key, and `null` for cases without a
+//!   value; results are `{"ok": ...}` or `{"err": ...}` the same way
+//! - enums are the case names, flags are arrays of the set flag names
+//! - options are `null` for `none`, or the value itself
+//! - chars are their Unicode code points, handles are strings
+
+use crate::model::component::{function_params, function_results, Component};
+use crate::model::GolemError;
+use golem_wasm_ast::analysis::{AnalysedType, TypeResult};
+use serde_json::{json, Map, Value};
+
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The schemas of the parameters a function is invoked with, and of its results. Both are arrays
+/// with an item for each parameter or result.
+pub fn function_json_schema(component: &Component, function: &str) -> Result<Value, GolemError> {
+    let parameters = function_params(component, function)?
+        .into_iter()
+        .map(|param| (Some(param.name.as_str()), &param.typ))
+        .collect::<Vec<_>>();
+    let results = function_results(component, function)?
+        .into_iter()
+        .map(|result| (result.name.as_deref(), &result.typ))
+        .collect::<Vec<_>>();
+
+    Ok(json!({
+        "function": function,
+        "parameters": items_json_schema(&format!("Parameters of {function}"), &parameters),
+        "results": items_json_schema(&format!("Results of {function}"), &results),
+    }))
+}
+
+fn items_json_schema(title: &str, items: &[(Option<&str>, &AnalysedType)]) -> Value {
+    let prefix_items = items
+        .iter()
+        .map(|(name, typ)| {
+            let mut schema = type_json_schema(typ);
+            if let (Some(name), Value::Object(schema)) = (name, &mut schema) {
+                schema.insert("title".to_string(), json!(name));
+            }
+            schema
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "title": title,
+        "type": "array",
+        "prefixItems": prefix_items,
+        "items": false,
+        "minItems": items.len(),
+    })
+}
+
+/// The JSON Schema of the JSON representation of values of type `typ`
+pub fn type_json_schema(typ: &AnalysedType) -> Value {
+    match typ {
+        AnalysedType::Bool(_) => json!({ "type": "boolean" }),
+        AnalysedType::U8(_) => integer_json_schema(u8::MIN as i128, u8::MAX as i128),
+        AnalysedType::U16(_) => integer_json_schema(u16::MIN as i128, u16::MAX as i128),
+        AnalysedType::U32(_) => integer_json_schema(u32::MIN as i128, u32::MAX as i128),
+        AnalysedType::U64(_) => integer_json_schema(u64::MIN as i128, u64::MAX as i128),
+        AnalysedType::S8(_) => integer_json_schema(i8::MIN as i128, i8::MAX as i128),
+        AnalysedType::S16(_) => integer_json_schema(i16::MIN as i128, i16::MAX as i128),
+        AnalysedType::S32(_) => integer_json_schema(i32::MIN as i128, i32::MAX as i128),
+        AnalysedType::S64(_) => integer_json_schema(i64::MIN as i128, i64::MAX as i128),
+        AnalysedType::F32(_) | AnalysedType::F64(_) => json!({ "type": "number" }),
+        AnalysedType::Chr(_) => json!({
+            "type": "integer",
+            "minimum": 0,
+            "maximum": char::MAX as u32,
+            "description": "Unicode code point",
+        }),
+        AnalysedType::Str(_) => json!({ "type": "string" }),
+        AnalysedType::List(list) => json!({
+            "type": "array",
+            "items": type_json_schema(&list.inner),
+        }),
+        AnalysedType::Tuple(tuple) => json!({
+            "type": "array",
+            "prefixItems": tuple.items.iter().map(type_json_schema).collect::<Vec<_>>(),
+            "items": false,
+            "minItems": tuple.items.len(),
+        }),
+        AnalysedType::Record(record) => {
+            let properties = record
+                .fields
+                .iter()
+                .map(|field| (field.name.clone(), type_json_schema(&field.typ)))
+                .collect::<Map<_, _>>();
+            let required = record
+                .fields
+                .iter()
+                .map(|field| field.name.clone())
+                .collect::<Vec<_>>();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false,
+            })
+        }
+        AnalysedType::Variant(variant) => json!({
+            "oneOf": variant
+                .cases
+                .iter()
+                .map(|case| case_json_schema(&case.name, case.typ.as_ref()))
+                .collect::<Vec<_>>(),
+        }),
+        AnalysedType::Result(TypeResult { ok, err }) => json!({
+            "oneOf": [
+                case_json_schema("ok", ok.as_deref()),
+                case_json_schema("err", err.as_deref()),
+            ],
+        }),
+        AnalysedType::Enum(enum_type) => json!({
+            "type": "string",
+            "enum": enum_type.cases,
+        }),
+        AnalysedType::Flags(flags) => json!({
+            "type": "array",
+            "items": { "type": "string", "enum": flags.names },
+            "uniqueItems": true,
+        }),
+        AnalysedType::Option(option) => json!({
+            "anyOf": [{ "type": "null" }, type_json_schema(&option.inner)],
+        }),
+        AnalysedType::Handle(_) => json!({
+            "type": "string",
+            "description": "Resource handle",
+        }),
+    }
+}
+
+fn integer_json_schema(minimum: i128, maximum: i128) -> Value {
+    json!({
+        "type": "integer",
+        "minimum": minimum,
+        "maximum": maximum,
+    })
+}
+
+/// An object with `name` as its only property, holding a value of `typ` or `null` if the case
+/// has no value
+fn case_json_schema(name: &str, typ: Option<&AnalysedType>) -> Value {
+    let value = match typ {
+        Some(typ) => type_json_schema(typ),
+        None => json!({ "type": "null" }),
+    };
+    json!({
+        "type": "object",
+        "properties": { name: value },
+        "required": [name],
+        "additionalProperties": false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_wasm_ast::analysis::analysed_type::{
+        case, chr, field, flags, list, option, r#enum, record, result_err, str, u8, unit_case,
+        variant,
+    };
+    use serde_json::json;
+
+    use crate::model::json_schema::type_json_schema;
+
+    #[test]
+    fn record_schema() {
+        let typ = record(vec![
+            field("name", str()),
+            field("age", option(u8())),
+            field("tags", list(r#enum(&["admin", "guest"]))),
+        ]);
+
+        assert_eq!(
+            type_json_schema(&typ),
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "age": {
+                        "anyOf": [
+                            { "type": "null" },
+                            { "type": "integer", "minimum": 0, "maximum": 255 },
+                        ],
+                    },
+                    "tags": {
+                        "type": "array",
+                        "items": { "type": "string", "enum": ["admin", "guest"] },
+                    },
+                },
+                "required": ["name", "age", "tags"],
+                "additionalProperties": false,
+            })
+        );
+    }
+
+    #[test]
+    fn variant_and_result_schemas() {
+        let typ = variant(vec![case("text", str()), unit_case("empty")]);
+
+        assert_eq!(
+            type_json_schema(&typ),
+            json!({
+                "oneOf": [
+                    {
+                        "type": "object",
+                        "properties": { "text": { "type": "string" } },
+                        "required": ["text"],
+                        "additionalProperties": false,
+                    },
+                    {
+                        "type": "object",
+                        "properties": { "empty": { "type": "null" } },
+                        "required": ["empty"],
+                        "additionalProperties": false,
+                    },
+                ],
+            })
+        );
+
+        assert_eq!(
+            type_json_schema(&result_err(chr())),
+            json!({
+                "oneOf": [
+                    {
+                        "type": "object",
+                        "properties": { "ok": { "type": "null" } },
+                        "required": ["ok"],
+                        "additionalProperties": false,
+                    },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "err": {
+                                "type": "integer",
+                                "minimum": 0,
+                                "maximum": 0x10FFFF,
+                                "description": "Unicode code point",
+                            },
+                        },
+                        "required": ["err"],
+                        "additionalProperties": false,
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn flags_schema() {
+        assert_eq!(
+            type_json_schema(&flags(&["read", "write"])),
+            json!({
+                "type": "array",
+                "items": { "type": "string", "enum": ["read", "write"] },
+                "uniqueItems": true,
+            })
+        );
+    }
+}
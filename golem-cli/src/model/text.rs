@@ -296,6 +296,9 @@ pub mod api_definition {
             .fmt_field("Version", &def.version, format_main_id)
             .fmt_field_option("Created at", &def.created_at, |d| d.to_string())
             .fmt_field_optional("Draft", &def.draft, def.draft, |d| d.to_string())
+            .fmt_field_option("CORS origins", &def.cors, |cors| {
+                cors.allow_origins.join(", ")
+            })
             .fmt_field_optional(
                 "Routes",
                 def.routes.as_slice(),
@@ -465,6 +468,8 @@ pub mod component {
     use crate::model::component::ComponentView;
     use crate::model::text::fmt::*;
     use cli_table::{format::Justify, print_stdout, Table, WithTitle};
+    use colored::Colorize;
+    use golem_client::model::{CompilationState, CompilationStatus};
     use serde::{Deserialize, Serialize};
 
     #[derive(Table)]
@@ -554,20 +559,64 @@ pub mod component {
     }
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
-    pub struct ComponentGetView(pub ComponentView);
+    #[serde(rename_all = "camelCase")]
+    pub struct ComponentGetView {
+        #[serde(flatten)]
+        pub component: ComponentView,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[serde(default)]
+        pub compilation_status: Option<CompilationStatus>,
+    }
 
     impl MessageWithFields for ComponentGetView {
         fn message(&self) -> String {
             format!(
                 "Got metadata for component {}",
-                format_message_highlight(&self.0.component_name)
+                format_message_highlight(&self.component.component_name)
             )
         }
 
         fn fields(&self) -> Vec<(&'static str, String)> {
-            component_view_fields(&self.0)
+            let mut fields = component_view_fields(&self.component);
+
+            if let Some(status) = &self.compilation_status {
+                fields.push(("Compilation", format_compilation_status(status)));
+            }
+
+            fields
         }
     }
+
+    fn format_compilation_status(status: &CompilationStatus) -> String {
+        let state = match status.state {
+            CompilationState::Compiled => status.state.to_string().green(),
+            CompilationState::Failed => status.state.to_string().bright_red(),
+            CompilationState::RetryScheduled => status.state.to_string().yellow(),
+            CompilationState::Queued | CompilationState::Compiling => {
+                status.state.to_string().cyan()
+            }
+        };
+
+        let mut result = format!(
+            "{state} ({} priority, {} attempts)",
+            status.priority, status.attempts
+        );
+        if let Some(error) = &status.last_error {
+            result.push_str(&format!("\nLast error: {}", format_warn(error)));
+        }
+        if !status.executors.is_empty() {
+            let warm = status
+                .executors
+                .iter()
+                .filter(|executor| executor.warm)
+                .count();
+            result.push_str(&format!(
+                "\nPrecompiled on {warm} of {} worker executors",
+                status.executors.len()
+            ));
+        }
+        result
+    }
 }
 
 pub mod example {
@@ -685,7 +734,10 @@ pub mod profile {
 }
 
 pub mod worker {
-    use crate::model::deploy::TryUpdateAllWorkersResult;
+    use crate::model::batch::InvokeBatchResult;
+    use crate::model::deploy::{
+        TryUpdateAllWorkersResult, WorkerFleetUpdateResult, WorkerUpdateFailure,
+    };
     use crate::model::invoke_result_view::InvokeResultView;
     use crate::model::text::fmt::*;
     use crate::model::{
@@ -696,8 +748,13 @@ pub mod worker {
     use chrono::{DateTime, Utc};
     use cli_table::{format::Justify, Table};
     use colored::Colorize;
-    use golem_client::model::PublicOplogEntry;
+    use golem_client::model::{PublicOplogEntry, UpdateRecord};
+    use golem_common::model::maintenance::ComponentMaintenance;
     use golem_common::model::public_oplog::{PublicUpdateDescription, PublicWorkerInvocation};
+    use golem_common::model::timeline::{
+        TimelineEventKind, TimelineInvocationStatus, WorkerTimeline,
+    };
+    use golem_common::model::Timestamp;
     use golem_common::uri::oss::urn::{ComponentUrn, WorkerUrn};
     use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
     use golem_wasm_rpc::{type_annotated_value_to_string, ValueAndType};
@@ -772,12 +829,16 @@ pub mod worker {
                 .fmt_field_option("Worker name", &self.0.worker_urn.id.worker_name, format_id)
                 .fmt_field("Component version", &self.0.component_version, format_id)
                 .field("Created at", &self.0.created_at)
+                .fmt_field_option("Expires at", &self.0.expires_at, |expires_at| {
+                    expires_at.to_string()
+                })
                 .fmt_field("Component size", &self.0.component_size, format_binary_size)
                 .fmt_field(
                     "Total linear memory size",
                     &self.0.total_linear_memory_size,
                     format_binary_size,
                 )
+                .fmt_field("Disk usage", &self.0.disk_usage, format_binary_size)
                 .fmt_field_optional("Arguments", &self.0.args, !self.0.args.is_empty(), |args| {
                     args.join(" ")
                 })
@@ -801,12 +862,49 @@ pub mod worker {
                 )
                 .fmt_field_option("Last error", &self.0.last_error, |err| {
                     format_stack(err.as_ref())
-                });
+                })
+                .fmt_field_optional(
+                    "Failed updates",
+                    &self.0.updates,
+                    self.0
+                        .updates
+                        .iter()
+                        .any(|update| matches!(update, UpdateRecord::FailedUpdate(_))),
+                    |updates| format_failed_updates(updates),
+                );
 
             fields.build()
         }
     }
 
+    /// Lists the failed updates with where the replay diverged from the oplog, if that is why they
+    /// failed. The diverging entry itself can be looked up with `worker oplog --from`.
+    pub fn format_failed_updates(updates: &[UpdateRecord]) -> String {
+        updates
+            .iter()
+            .filter_map(|update| match update {
+                UpdateRecord::FailedUpdate(failed) => {
+                    let mut lines = vec![format!(
+                        "{} at {}: {}",
+                        format_id(&failed.target_version),
+                        failed.timestamp,
+                        format_error(failed.details.as_deref().unwrap_or("?"))
+                    )];
+                    if let Some(divergence) = &failed.divergence {
+                        lines.push(format!(
+                            "  diverged at oplog index {}",
+                            format_id(&divergence.oplog_index)
+                        ));
+                        lines.push(format!("  expected: {}", divergence.expected));
+                        lines.push(format!("  recorded: {}", divergence.got));
+                    }
+                    Some(lines.join("\n"))
+                }
+                _ => None,
+            })
+            .join("\n")
+    }
+
     #[derive(Table)]
     struct WorkerMetadataTableView {
         #[table(title = "Component URN")]
@@ -859,6 +957,50 @@ pub mod worker {
         }
     }
 
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ComponentMaintenanceView {
+        pub component_urn: ComponentUrn,
+        #[serde(flatten)]
+        pub maintenance: ComponentMaintenance,
+    }
+
+    impl MessageWithFields for ComponentMaintenanceView {
+        fn message(&self) -> String {
+            format!(
+                "Component {} is in maintenance",
+                format_message_highlight(&self.component_urn)
+            )
+        }
+
+        fn fields(&self) -> Vec<(&'static str, String)> {
+            let mut fields = FieldsBuilder::new();
+
+            fields
+                .fmt_field("Component URN", &self.component_urn, format_main_id)
+                .field("Started at", &self.maintenance.started_at)
+                .fmt_field_option("Message", &self.maintenance.message, |message| {
+                    message.to_string()
+                });
+
+            fields.build()
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct WorkerSearchView(pub Vec<WorkerUrn>);
+
+    impl TextFormat for WorkerSearchView {
+        fn print(&self) {
+            if self.0.is_empty() {
+                println!("No matching workers found");
+            } else {
+                for worker_urn in &self.0 {
+                    println!("{worker_urn}");
+                }
+            }
+        }
+    }
+
     impl TextFormat for IdempotencyKey {
         fn print(&self) {
             printdoc!(
@@ -910,6 +1052,72 @@ pub mod worker {
         }
     }
 
+    #[derive(Table)]
+    struct WorkerUpdateFailureTableView {
+        #[table(title = "Worker URN")]
+        pub worker_urn: WorkerUrn,
+
+        #[table(title = "Name")]
+        pub worker_name: String,
+
+        #[table(title = "Error")]
+        pub error: String,
+    }
+
+    impl From<&WorkerUpdateFailure> for WorkerUpdateFailureTableView {
+        fn from(value: &WorkerUpdateFailure) -> Self {
+            WorkerUpdateFailureTableView {
+                worker_urn: value.worker_urn.clone(),
+                worker_name: value.worker_urn.id.worker_name.clone().unwrap_or_default(),
+                error: value.error.clone(),
+            }
+        }
+    }
+
+    impl TextFormat for WorkerFleetUpdateResult {
+        fn print(&self) {
+            println!(
+                "Updated {} workers to version {}, {} failed, {} were already up to date",
+                format_message_highlight(&self.succeeded.len()),
+                self.target_version,
+                if self.failed.is_empty() {
+                    "0".to_string()
+                } else {
+                    format_warn(&self.failed.len())
+                },
+                self.skipped
+            );
+
+            if !self.failed.is_empty() {
+                println!("{}", format_warn("Failed to update the following workers:"));
+                print_table::<_, WorkerUpdateFailureTableView>(&self.failed);
+                println!(
+                    "Retry them with {}",
+                    format_message_highlight(&format!("--resume-token {}", self.resume_token))
+                );
+            }
+        }
+    }
+
+    impl TextFormat for InvokeBatchResult {
+        fn print(&self) {
+            println!(
+                "Invoked {} functions, {} succeeded, {} failed",
+                self.total,
+                format_message_highlight(&self.succeeded),
+                if self.failed > 0 {
+                    format_warn(&self.failed)
+                } else {
+                    self.failed.to_string()
+                }
+            );
+            println!(
+                "Results written to {}",
+                format_message_highlight(&self.output.display())
+            );
+        }
+    }
+
     impl TextFormat for InvokeResultView {
         fn print(&self) {
             fn print_results_format(format: &str) {
@@ -1202,7 +1410,182 @@ pub mod worker {
                     println!("{}", format_message_highlight("RESTART"));
                     println!("{pad}at:                {}", format_id(&params.timestamp));
                 }
+                PublicOplogEntry::CancelInvocation(params) => {
+                    println!("{}", format_message_highlight("CANCEL INVOCATION"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    println!(
+                        "{pad}idempotency key:   {}",
+                        format_id(&params.idempotency_key)
+                    );
+                }
+                PublicOplogEntry::ChangeEnvironment(params) => {
+                    println!("{}", format_message_highlight("CHANGE ENVIRONMENT"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    println!("{pad}env:");
+                    for (k, v) in &params.env {
+                        println!("{pad}  - {}: {}", k, format_id(&v));
+                    }
+                }
+                PublicOplogEntry::Redacted(params) => {
+                    println!("{}", format_message_highlight("REDACTED"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    println!("{pad}reason:            {}", params.reason);
+                    println!("{pad}entries:");
+                    for idx in &params.entries {
+                        println!("{pad}  - {}", format_id(idx));
+                    }
+                }
+                PublicOplogEntry::InvocationTimedOut(params) => {
+                    println!("{}", format_message_highlight("INVOCATION TIMED OUT"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    println!(
+                        "{pad}idempotency key:   {}",
+                        format_id(&params.idempotency_key)
+                    );
+                    println!(
+                        "{pad}timeout:           {} ms",
+                        format_id(&params.timeout.as_millis())
+                    );
+                }
+                PublicOplogEntry::HoldInvocation(params) => {
+                    println!("{}", format_message_highlight("HOLD INVOCATION"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    println!(
+                        "{pad}idempotency key:   {}",
+                        format_id(&params.idempotency_key)
+                    );
+                }
+                PublicOplogEntry::ReleaseInvocation(params) => {
+                    println!("{}", format_message_highlight("RELEASE INVOCATION"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    println!(
+                        "{pad}idempotency key:   {}",
+                        format_id(&params.idempotency_key)
+                    );
+                }
+                PublicOplogEntry::SwitchInvocation(params) => {
+                    println!("{}", format_message_highlight("SWITCH INVOCATION"));
+                    println!("{pad}at:                {}", format_id(&params.timestamp));
+                    println!(
+                        "{pad}idempotency key:   {}",
+                        format_id(&params.idempotency_key)
+                    );
+                }
+            }
+        }
+    }
+
+    impl TextFormat for WorkerTimeline {
+        fn print(&self) {
+            const WIDTH: usize = 60;
+
+            let (Some(start), Some(end)) = (&self.start, &self.end) else {
+                println!("The worker's oplog is empty");
+                return;
+            };
+            let start_ms = start.to_millis();
+            let total_ms = end.to_millis().saturating_sub(start_ms).max(1);
+            let column = |ts: &Timestamp| -> usize {
+                let offset = ts.to_millis().saturating_sub(start_ms).min(total_ms);
+                (offset as u128 * (WIDTH - 1) as u128 / total_ms as u128) as usize
+            };
+
+            println!(
+                "{} {} - {} ({} ms)",
+                format_message_highlight("TIMELINE"),
+                format_id(start),
+                format_id(end),
+                total_ms
+            );
+
+            for invocation in &self.invocations {
+                let mut bar = vec![' '; WIDTH];
+                let from = column(&invocation.start);
+                let to = invocation.end.as_ref().map(column).unwrap_or(WIDTH - 1);
+                let fill = if invocation.end.is_some() { '=' } else { '~' };
+                for c in bar.iter_mut().take(to + 1).skip(from) {
+                    *c = fill;
+                }
+                for call in &invocation.host_calls {
+                    bar[column(&call.timestamp)] = '*';
+                }
+                bar[from] = '[';
+                if invocation.end.is_some() {
+                    bar[to] = match invocation.status {
+                        TimelineInvocationStatus::Failed => '!',
+                        TimelineInvocationStatus::Interrupted => 'x',
+                        _ => ']',
+                    };
+                }
+
+                let duration = invocation
+                    .duration_ms
+                    .map(|ms| format!("{ms} ms"))
+                    .unwrap_or_else(|| "-".to_string());
+                let retries = if invocation.retries > 0 {
+                    format!(" ({} retries)", format_warn(&invocation.retries))
+                } else {
+                    String::new()
+                };
+                let status = format!("{:?}", invocation.status);
+                println!(
+                    "{}: |{}| {:<11} {:>10}  {}{}",
+                    format_main_id(&format!("#{:0>5}", invocation.start_index)),
+                    bar.iter().collect::<String>(),
+                    status,
+                    duration,
+                    format_export(&invocation.function_name),
+                    retries
+                );
+            }
+
+            if !self.events.is_empty() {
+                let mut bar = vec![' '; WIDTH];
+                for event in &self.events {
+                    bar[column(&event.timestamp)] = timeline_event_marker(&event.kind);
+                }
+                println!(
+                    "{}: |{}|",
+                    format_main_id("events"),
+                    bar.iter().collect::<String>()
+                );
+                println!();
+
+                for event in &self.events {
+                    let mut line = format!(
+                        "{}: {} {} {:?}",
+                        format_main_id(&format!("#{:0>5}", event.oplog_index)),
+                        timeline_event_marker(&event.kind),
+                        format_id(&event.timestamp),
+                        event.kind
+                    );
+                    if let Some(target_version) = event.target_version {
+                        line.push_str(&format!(" to version {}", format_id(&target_version)));
+                    }
+                    if let Some(details) = &event.details {
+                        line.push_str(&format!(": {}", format_error(details)));
+                    }
+                    println!("{line}");
+                }
             }
+
+            println!();
+            println!("Legend: [==] completed, [~~ running, [==! failed, [==x interrupted, * host call");
+        }
+    }
+
+    fn timeline_event_marker(kind: &TimelineEventKind) -> char {
+        match kind {
+            TimelineEventKind::Created => 'c',
+            TimelineEventKind::Error => '!',
+            TimelineEventKind::Interrupted => 'x',
+            TimelineEventKind::Suspended => 's',
+            TimelineEventKind::Exited => 'e',
+            TimelineEventKind::Restarted => 'r',
+            TimelineEventKind::Jump => 'j',
+            TimelineEventKind::UpdatePending
+            | TimelineEventKind::UpdateSucceeded
+            | TimelineEventKind::UpdateFailed => 'u',
         }
     }
 
@@ -66,3 +66,65 @@ pub fn function_wave_compatible(func: &AnalysedFunction) -> bool {
     func.parameters.iter().all(|p| type_wave_compatible(&p.typ))
         && func.results.iter().all(|r| type_wave_compatible(&r.typ))
 }
+
+/// Shows where a WAVE parse error is in the input, using the span at the end of the error
+/// message (for example `invalid token at 14..15`):
+///
+/// ```text
+/// invalid token at 14..15
+///   {a: 1, b: "x"}}
+///                 ^
+/// ```
+///
+/// The error is returned as is if it has no span.
+pub fn show_wave_error(input: &str, error: &str) -> String {
+    let span = error
+        .rsplit_once(" at ")
+        .and_then(|(_, span)| span.split_once(".."))
+        .and_then(|(start, end)| Some((start.parse::<usize>().ok()?, end.parse::<usize>().ok()?)));
+
+    match span {
+        Some((start, end)) if start <= end && end <= input.len() => {
+            match (input.get(..start), input.get(start..end)) {
+                (Some(before), Some(spanned)) => format!(
+                    "{error}\n  {input}\n  {}{}",
+                    " ".repeat(before.chars().count()),
+                    "^".repeat(spanned.chars().count().max(1))
+                ),
+                _ => error.to_string(),
+            }
+        }
+        _ => error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::wave::show_wave_error;
+
+    #[test]
+    fn error_with_span() {
+        assert_eq!(
+            show_wave_error(r#"{a: 1, b: "x"}}"#, "invalid token at 14..15"),
+            "invalid token at 14..15\n  {a: 1, b: \"x\"}}\n                ^"
+        );
+        assert_eq!(
+            show_wave_error("some-case(", "unexpected end of input at 10..10"),
+            "unexpected end of input at 10..10\n  some-case(\n            ^"
+        );
+    }
+
+    #[test]
+    fn error_without_span() {
+        assert_eq!(
+            show_wave_error("{a: 1}", "missing field b"),
+            "missing field b"
+        );
+        assert_eq!(
+            show_wave_error("{a: 1}", "invalid value at 40..42"),
+            "invalid value at 40..42"
+        );
+    }
+}
@@ -12,22 +12,90 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::io::Read;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use async_trait::async_trait;
+use futures_util::{Stream, TryStreamExt};
 use golem_wasm_rpc_stubgen::model::oam::{Application};
 use crate::clients::component::ComponentClient;
 use golem_common::uri::oss::urn::ComponentUrn;
 use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tracing::info;
-use golem_client::model::ComponentType;
+use golem_client::api::ComponentError;
+use golem_client::model::{CompilationStatus, ComponentType};
 use crate::model::component::Component;
 use crate::model::{ComponentName, GolemError, PathBufOrStdin};
 use crate::oss::model::OssContext;
+use crate::progress::{Progress, ProgressMode, ProgressOperation, ProgressUnit};
+
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+async fn write_stream<B: AsRef<[u8]>, E: std::fmt::Display>(
+    mut stream: impl Stream<Item = Result<B, E>> + Unpin,
+    output: &Path,
+) -> Result<u64, GolemError> {
+    let mut file = File::create(output)
+        .await
+        .map_err(|e| GolemError(format!("Can't create output file: {e}")))?;
+    let mut written = 0;
+    while let Some(chunk) = stream
+        .try_next()
+        .await
+        .map_err(|e| GolemError(format!("Failed to download component: {e}")))?
+    {
+        file.write_all(chunk.as_ref())
+            .await
+            .map_err(|e| GolemError(format!("Can't write output file: {e}")))?;
+        written += chunk.as_ref().len() as u64;
+    }
+    file.flush()
+        .await
+        .map_err(|e| GolemError(format!("Can't write output file: {e}")))?;
+    Ok(written)
+}
+
+/// Streams a file as a request body, reporting the progress of the upload once the body is sent
+async fn upload_body(
+    path: &Path,
+    progress: ProgressMode,
+    operation: ProgressOperation,
+) -> Result<reqwest::Body, GolemError> {
+    let file = File::open(path)
+        .await
+        .map_err(|e| GolemError(format!("Can't open component file: {e}")))?;
+    let size = file
+        .metadata()
+        .await
+        .map_err(|e| GolemError(format!("Can't open component file: {e}")))?
+        .len();
+
+    let stream = futures_util::stream::try_unfold(
+        (file, None),
+        move |(mut file, started): (File, Option<Progress>)| async move {
+            let progress =
+                started.unwrap_or_else(|| progress.start(operation, ProgressUnit::Bytes, size));
+            let mut chunk = vec![0; UPLOAD_CHUNK_SIZE];
+            let read = file.read(&mut chunk).await?;
+            if read == 0 {
+                progress.finish();
+                Ok::<_, std::io::Error>(None)
+            } else {
+                chunk.truncate(read);
+                progress.inc(read as u64);
+                Ok(Some((chunk, (file, Some(progress)))))
+            }
+        },
+    );
+    Ok(reqwest::Body::wrap_stream(stream))
+}
 
 #[derive(Debug, Clone)]
 pub struct ComponentClientLive<C: golem_client::api::ComponentClient + Sync + Send> {
     pub client: C,
+    /// How the progress of the uploads is reported
+    pub progress: ProgressMode,
 }
 
 #[async_trait]
@@ -66,13 +134,17 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
     async fn find(
         &self,
         name: Option<ComponentName>,
-        _project: &Option<Self::ProjectContext>,
+        project: &Option<Self::ProjectContext>,
     ) -> Result<Vec<Component>, GolemError> {
         info!("Getting components");
 
         let name = name.map(|n| n.0);
+        let project_id = project.and_then(|p| p.project_id);
 
-        let components = self.client.get_components(name.as_deref()).await?;
+        let components = self
+            .client
+            .get_components(name.as_deref(), project_id.as_ref())
+            .await?;
         Ok(components.into_iter().map(|c| c.into()).collect())
     }
 
@@ -83,19 +155,27 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
         project: &Option<Self::ProjectContext>,
         component_type: ComponentType,
         ifs: PathBuf,
+        function_aliases: Option<HashMap<String, String>>,
     ) -> Result<Component, GolemError> {
         info!("Adding component {name:?} from {file:?}");
 
+        let project_id = project.and_then(|p| p.project_id);
+
         let component = match file {
             PathBufOrStdin::Path(path) => {
-                let file = File::open(path.clone())
-                    .await
-                    .map_err(|e| GolemError(format!("Can't open component file: {e}")))?;
-                let ifs_file = File::open(ifs.clone())
-                    .await
-                    .map_err(|e| GolemError(format!("Can't open component file: {e}")))?;
+                let file =
+                    upload_body(&path, self.progress, ProgressOperation::ComponentUpload).await?;
+                let ifs_file =
+                    upload_body(&ifs, self.progress, ProgressOperation::IfsUpload).await?;
                 self.client
-                    .create_component(&name.0, Some(&component_type), file, Some(ifs_file))
+                    .create_component(
+                        project_id.as_ref(),
+                        &name.0,
+                        Some(&component_type),
+                        file,
+                        Some(ifs_file),
+                        function_aliases.as_ref(),
+                    )
                     .await?
             }
             PathBufOrStdin::Stdin => {
@@ -106,7 +186,14 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
                     .map_err(|e| GolemError(format!("Failed to read stdin: {e:?}")))?;
 
                 self.client
-                    .create_component(&name.0, Some(&component_type), bytes.clone(), Some(bytes))
+                    .create_component(
+                        project_id.as_ref(),
+                        &name.0,
+                        Some(&component_type),
+                        bytes.clone(),
+                        Some(bytes),
+                        function_aliases.as_ref(),
+                    )
                     .await?
             }
         };
@@ -120,20 +207,25 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
         file: PathBufOrStdin,
         component_type: Option<ComponentType>,
         ifs: PathBuf,
+        function_aliases: Option<HashMap<String, String>>,
     ) -> Result<Component, GolemError> {
         info!("Updating component {urn} from {file:?}");
 
         let component = match file {
             PathBufOrStdin::Path(path) => {
-                let file = File::open(path)
-                    .await
-                    .map_err(|e| GolemError(format!("Can't open component file: {e}")))?;
-                let ifs_file = File::open(ifs.clone())
-                    .await
-                    .map_err(|e| GolemError(format!("Can't open component file: {e}")))?;
+                let file =
+                    upload_body(&path, self.progress, ProgressOperation::ComponentUpload).await?;
+                let ifs_file =
+                    upload_body(&ifs, self.progress, ProgressOperation::IfsUpload).await?;
 
                 self.client
-                    .update_component(&urn.id.0, component_type.as_ref(), Some(file), Some(ifs_file))
+                    .update_component(
+                        &urn.id.0,
+                        component_type.as_ref(),
+                        Some(file),
+                        Some(ifs_file),
+                        function_aliases.as_ref(),
+                    )
                     .await?
             }
             PathBufOrStdin::Stdin => {
@@ -143,15 +235,59 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
                     .read_to_end(&mut bytes)
                     .map_err(|e| GolemError(format!("Failed to read stdin: {e:?}")))?;
 
-                let ifs_file = File::open(ifs.clone())
-                    .await
-                    .map_err(|e| GolemError(format!("Can't open component file: {e}")))?;
+                let ifs_file =
+                    upload_body(&ifs, self.progress, ProgressOperation::IfsUpload).await?;
                 self.client
-                    .update_component(&urn.id.0, component_type.as_ref(), Some(bytes), Some(ifs_file))
+                    .update_component(
+                        &urn.id.0,
+                        component_type.as_ref(),
+                        Some(bytes),
+                        Some(ifs_file),
+                        function_aliases.as_ref(),
+                    )
                     .await?
             }
         };
 
         Ok(component.into())
     }
+    async fn download(
+        &self,
+        urn: &ComponentUrn,
+        version: Option<u64>,
+        output: &Path,
+    ) -> Result<u64, GolemError> {
+        info!("Downloading component {urn} to {output:?}");
+
+        let stream = self
+            .client
+            .download_component(&urn.id.0, version, None)
+            .await?;
+        write_stream(stream, output).await
+    }
+
+    async fn get_compilation_status(
+        &self,
+        urn: &ComponentUrn,
+        version: u64,
+    ) -> Result<Option<CompilationStatus>, GolemError> {
+        info!("Getting compilation status of component {urn} version {version}");
+
+        match self
+            .client
+            .get_component_compilation_status(&urn.id.0, version)
+            .await
+        {
+            Ok(status) => Ok(Some(status)),
+            Err(golem_client::Error::Item(ComponentError::Error404(_))) => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn delete(&self, urn: &ComponentUrn) -> Result<(), GolemError> {
+        info!("Deleting component {urn}");
+
+        let _ = self.client.delete_component(&urn.id.0).await?;
+        Ok(())
+    }
 }
@@ -20,10 +20,11 @@ use golem_client::model::{
     GolemError, GolemErrorComponentDownloadFailed, GolemErrorComponentParseFailed,
     GolemErrorFailedToResumeWorker, GolemErrorGetLatestVersionOfComponentFailed,
     GolemErrorInterrupted, GolemErrorInvalidRequest, GolemErrorInvalidShardId,
-    GolemErrorPromiseAlreadyCompleted, GolemErrorPromiseDropped, GolemErrorPromiseNotFound,
-    GolemErrorRuntimeError, GolemErrorUnexpectedOplogEntry, GolemErrorUnknown,
-    GolemErrorValueMismatch, GolemErrorWorkerAlreadyExists, GolemErrorWorkerCreationFailed,
-    GolemErrorWorkerNotFound, PromiseId, WorkerId, WorkerServiceErrorsBody,
+    GolemErrorInvocationTimedOut, GolemErrorPromiseAlreadyCompleted, GolemErrorPromiseDropped,
+    GolemErrorPromiseNotFound, GolemErrorResultTooLarge, GolemErrorRuntimeError,
+    GolemErrorUnexpectedOplogEntry, GolemErrorUnknown, GolemErrorValueMismatch,
+    GolemErrorWorkerAlreadyExists, GolemErrorWorkerCreationFailed, GolemErrorWorkerNotFound,
+    PromiseId, WorkerId, WorkerServiceErrorsBody,
 };
 use itertools::Itertools;
 
@@ -35,6 +36,7 @@ impl ResponseContentErrorMapper for ComponentError {
             ComponentError::Error403(error) => error.error,
             ComponentError::Error404(error) => error.error,
             ComponentError::Error409(error) => error.error,
+            ComponentError::Error413(error) => error.error,
             ComponentError::Error500(error) => error.error,
         }
     }
@@ -49,6 +51,7 @@ impl ResponseContentErrorMapper for WorkerError {
             WorkerError::Error404(error) => error.error,
             WorkerError::Error409(error) => error.error,
             WorkerError::Error500(error) => display_golem_error(error.golem_error),
+            WorkerError::Error503(error) => error.error,
         }
     }
 }
@@ -193,6 +196,16 @@ fn display_golem_error(error: GolemError) -> String {
         }
         GolemError::InvalidAccount(_) => "Invalid account".to_string(),
         GolemError::ShardingNotReady(_) => "Sharding not ready".to_string(),
+        GolemError::ResultTooLarge(GolemErrorResultTooLarge {
+            size,
+            limit,
+            blob_key,
+        }) => format!(
+            "Invocation result of {size} bytes exceeds the limit of {limit} bytes, stored as {blob_key}"
+        ),
+        GolemError::InvocationTimedOut(GolemErrorInvocationTimedOut { timeout_millis }) => {
+            format!("Invocation timed out after {timeout_millis} ms")
+        }
     }
 }
 
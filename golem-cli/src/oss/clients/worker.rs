@@ -12,11 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::future::Future;
 use std::time::Duration;
 
 use crate::clients::worker::{worker_name_required, WorkerClient};
 use crate::command::worker::WorkerConnectOptions;
-use crate::connect_output::ConnectOutput;
+use crate::connect_output::{ConnectOutput, MultiConnectOutput};
 use crate::model::{
     Format, GolemError, IdempotencyKey, WorkerMetadata, WorkerName, WorkerUpdateMode,
     WorkersMetadataResponse,
@@ -26,18 +27,21 @@ use futures_util::{future, pin_mut, SinkExt, StreamExt};
 use golem_client::api::WorkerError;
 use golem_client::model::{
     InvokeParameters, InvokeResult, ScanCursor, UpdateWorkerRequest, WorkerCreationRequest,
-    WorkerFilter, WorkerId, WorkersMetadataRequest,
+    WorkerFilter, WorkerId, WorkersMetadataRequest, WorkersSearchRequest,
 };
 use golem_client::{Context, Error};
+use golem_common::model::maintenance::{ComponentMaintenance, StartComponentMaintenance};
 use golem_common::model::public_oplog::{OplogCursor, PublicOplogEntry};
-use golem_common::model::WorkerEvent;
+use golem_common::model::{NamedWorkerEvent, WorkerEvent};
 use golem_common::uri::oss::urn::{ComponentUrn, WorkerUrn};
 use native_tls::TlsConnector;
+use serde::de::DeserializeOwned;
 use tokio::{task, time};
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use tokio_tungstenite::tungstenite::protocol::Message;
 use tokio_tungstenite::{connect_async_tls_with_config, Connector};
 use tracing::{debug, error, info, trace};
+use url::Url;
 
 #[derive(Clone)]
 pub struct WorkerClientLive<C: golem_client::api::WorkerClient + Sync + Send> {
@@ -62,9 +66,11 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
             .launch_new_worker(
                 &component_urn.id.0,
                 &WorkerCreationRequest {
-                    name: name.0,
+                    name: Some(name.0),
                     args,
                     env: env.into_iter().collect(),
+                    ttl_seconds: None,
+                    anti_affinity_key: None,
                 },
             )
             .await?
@@ -77,12 +83,11 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
         function: String,
         parameters: InvokeParameters,
         idempotency_key: Option<IdempotencyKey>,
-    ) -> Result<InvokeResult, GolemError> {
+    ) -> Result<InvokeResult, Error<WorkerError>> {
         info!("Invoke and await for function {function} in {worker_urn}");
 
         if let Some(worker_name) = &worker_urn.id.worker_name {
-            Ok(self
-                .client
+            self.client
                 .invoke_and_await_function(
                     &worker_urn.id.component_id.0,
                     worker_name,
@@ -90,17 +95,16 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                     &function,
                     &parameters,
                 )
-                .await?)
+                .await
         } else {
-            Ok(self
-                .client
+            self.client
                 .invoke_and_await_function_without_name(
                     &worker_urn.id.component_id.0,
                     idempotency_key.as_ref().map(|k| k.0.as_str()),
                     &function,
                     &parameters,
                 )
-                .await?)
+                .await
         }
     }
 
@@ -187,6 +191,8 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
             .delete_worker(
                 &worker_urn.id.component_id.0,
                 &worker_name_required(&worker_urn)?,
+                None,
+                None,
             )
             .await?;
         Ok(())
@@ -227,6 +233,7 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                     cursor,
                     count,
                     precise,
+                    fields: None,
                 },
             )
             .await?
@@ -261,17 +268,206 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                 cursor.as_deref(),
                 count,
                 precise,
+                None,
             )
             .await?
             .into())
     }
 
+    async fn search(
+        &self,
+        component_urn: ComponentUrn,
+        prefix: Option<String>,
+        pattern: Option<String>,
+        count: Option<u64>,
+    ) -> Result<Vec<WorkerId>, GolemError> {
+        info!("Searching workers of component: {component_urn}");
+
+        Ok(self
+            .client
+            .search_workers(
+                &component_urn.id.0,
+                &WorkersSearchRequest {
+                    prefix,
+                    pattern,
+                    count,
+                },
+            )
+            .await?
+            .workers)
+    }
+
     async fn connect(
         &self,
         worker_urn: WorkerUrn,
         connect_options: WorkerConnectOptions,
         format: Format,
     ) -> Result<(), GolemError> {
+        let url = self.websocket_url(&[
+            "v1",
+            "components",
+            &worker_urn.id.component_id.0.to_string(),
+            "workers",
+            &worker_name_required(&worker_urn)?,
+            "connect",
+        ])?;
+
+        info!("Connecting to {worker_urn}");
+
+        let output = ConnectOutput::new(connect_options, format);
+        self.stream_events(url, move |event: WorkerEvent| {
+            let output = output.clone();
+            async move { output.emit(event).await }
+        })
+        .await
+    }
+
+    async fn connect_many(
+        &self,
+        component_urn: ComponentUrn,
+        filter: Vec<String>,
+        connect_options: WorkerConnectOptions,
+        format: Format,
+    ) -> Result<(), GolemError> {
+        let mut url = self.websocket_url(&[
+            "v1",
+            "components",
+            &component_urn.id.0.to_string(),
+            "connect",
+        ])?;
+        if !filter.is_empty() {
+            let mut query = url.query_pairs_mut();
+            for filter in &filter {
+                query.append_pair("filter", filter);
+            }
+        }
+
+        info!("Connecting to the workers of {component_urn}");
+
+        let output = MultiConnectOutput::new(connect_options, format);
+        self.stream_events(url, move |event: NamedWorkerEvent| {
+            let output = output.clone();
+            async move { output.emit(event).await }
+        })
+        .await
+    }
+
+    async fn update(
+        &self,
+        worker_urn: WorkerUrn,
+        mode: WorkerUpdateMode,
+        target_version: u64,
+    ) -> Result<(), GolemError> {
+        info!("Updating worker {worker_urn}");
+        let update_mode = match mode {
+            WorkerUpdateMode::Automatic => golem_client::model::WorkerUpdateMode::Automatic,
+            WorkerUpdateMode::Manual => golem_client::model::WorkerUpdateMode::Manual,
+        };
+
+        let _ = self
+            .client
+            .update_worker(
+                &worker_urn.id.component_id.0,
+                &worker_name_required(&worker_urn)?,
+                &UpdateWorkerRequest {
+                    mode: update_mode,
+                    target_version
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_oplog(
+        &self,
+        worker_urn: WorkerUrn,
+        from: u64,
+    ) -> Result<Vec<(u64, PublicOplogEntry)>, GolemError> {
+        let mut entries = Vec::new();
+        let mut cursor: Option<OplogCursor> = None;
+
+        loop {
+            let chunk = self
+                .client
+                .get_oplog(
+                    &worker_urn.id.component_id.0,
+                    &worker_name_required(&worker_urn)?,
+                    from,
+                    100,
+                    cursor.as_ref(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .await?;
+
+            trace!(
+                "Got {} oplog entries starting from {}, last index is {}",
+                chunk.entries.len(),
+                chunk.first_index_in_chunk,
+                chunk.last_index
+            );
+
+            if chunk.entries.is_empty() {
+                break;
+            } else {
+                entries.extend(
+                    chunk
+                        .entries
+                        .into_iter()
+                        .zip(chunk.indices)
+                        .map(|(e, idx)| (idx, e)),
+                );
+                cursor = chunk.next;
+            }
+        }
+
+        Ok(entries)
+    }
+
+    async fn get_maintenance(
+        &self,
+        component_urn: ComponentUrn,
+    ) -> Result<ComponentMaintenance, GolemError> {
+        info!("Getting the maintenance of component: {component_urn}");
+
+        Ok(self
+            .client
+            .get_component_maintenance(&component_urn.id.0)
+            .await?)
+    }
+
+    async fn start_maintenance(
+        &self,
+        component_urn: ComponentUrn,
+        message: Option<String>,
+    ) -> Result<ComponentMaintenance, GolemError> {
+        info!("Starting the maintenance of component: {component_urn}");
+
+        Ok(self
+            .client
+            .start_component_maintenance(
+                &component_urn.id.0,
+                &StartComponentMaintenance { message },
+            )
+            .await?)
+    }
+
+    async fn end_maintenance(&self, component_urn: ComponentUrn) -> Result<(), GolemError> {
+        info!("Ending the maintenance of component: {component_urn}");
+
+        let _ = self
+            .client
+            .end_component_maintenance(&component_urn.id.0)
+            .await?;
+        Ok(())
+    }
+}
+
+impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClientLive<C> {
+    fn websocket_url(&self, path: &[&str]) -> Result<Url, GolemError> {
         let mut url = self.context.base_url.clone();
 
         let ws_schema = if url.scheme() == "http" { "ws" } else { "wss" };
@@ -281,13 +477,18 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
 
         url.path_segments_mut()
             .map_err(|_| GolemError("Can't get path.".to_string()))?
-            .push("v1")
-            .push("components")
-            .push(&worker_urn.id.component_id.0.to_string())
-            .push("workers")
-            .push(&worker_name_required(&worker_urn)?)
-            .push("connect");
+            .extend(path);
+
+        Ok(url)
+    }
 
+    /// Streams the events of a worker connection websocket until it gets closed
+    async fn stream_events<T, F, Fut>(&self, url: Url, on_event: F) -> Result<(), GolemError>
+    where
+        T: DeserializeOwned + Send,
+        F: Fn(T) -> Fut + Clone + Send,
+        Fut: Future<Output = ()> + Send,
+    {
         let mut request = url
             .into_client_request()
             .map_err(|e| GolemError(format!("Can't create request: {e}")))?;
@@ -312,8 +513,6 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
             None
         };
 
-        info!("Connecting to {worker_urn}");
-
         let (ws_stream, _) = connect_async_tls_with_config(request, None, false, connector)
             .await
             .map_err(|e| match e {
@@ -351,10 +550,8 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
             }
         });
 
-        let output = ConnectOutput::new(connect_options, format);
-
         let read_res = read.for_each(move |message_or_error| {
-            let output = output.clone();
+            let on_event = on_event.clone();
             async move {
                 match message_or_error {
                     Err(error) => {
@@ -363,8 +560,7 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                     Ok(message) => {
                         let instance_connect_msg = match message {
                             Message::Text(str) => {
-                                let parsed: serde_json::Result<WorkerEvent> =
-                                    serde_json::from_str(&str);
+                                let parsed: serde_json::Result<T> = serde_json::from_str(&str);
 
                                 match parsed {
                                     Ok(parsed) => Some(parsed),
@@ -375,8 +571,7 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                                 }
                             }
                             Message::Binary(data) => {
-                                let parsed: serde_json::Result<WorkerEvent> =
-                                    serde_json::from_slice(&data);
+                                let parsed: serde_json::Result<T> = serde_json::from_slice(&data);
                                 match parsed {
                                     Ok(parsed) => Some(parsed),
                                     Err(err) => {
@@ -410,37 +605,8 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
                             }
                         };
 
-                        match instance_connect_msg {
-                            None => {}
-                            Some(msg) => match msg {
-                                WorkerEvent::StdOut { timestamp, bytes } => {
-                                    output
-                                        .emit_stdout(
-                                            timestamp,
-                                            String::from_utf8_lossy(&bytes).to_string(),
-                                        )
-                                        .await;
-                                }
-                                WorkerEvent::StdErr { timestamp, bytes } => {
-                                    output
-                                        .emit_stderr(
-                                            timestamp,
-                                            String::from_utf8_lossy(&bytes).to_string(),
-                                        )
-                                        .await;
-                                }
-                                WorkerEvent::Log {
-                                    timestamp,
-                                    level,
-                                    context,
-                                    message,
-                                } => {
-                                    output.emit_log(timestamp, level, context, message);
-                                }
-                                WorkerEvent::Close => {}
-                                WorkerEvent::InvocationStart { .. } => {}
-                                WorkerEvent::InvocationFinished { .. } => {}
-                            },
+                        if let Some(event) = instance_connect_msg {
+                            on_event(event).await;
                         }
                     }
                 }
@@ -452,76 +618,6 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
         future::select(pings, read_res).await;
         Ok(())
     }
-
-    async fn update(
-        &self,
-        worker_urn: WorkerUrn,
-        mode: WorkerUpdateMode,
-        target_version: u64,
-    ) -> Result<(), GolemError> {
-        info!("Updating worker {worker_urn}");
-        let update_mode = match mode {
-            WorkerUpdateMode::Automatic => golem_client::model::WorkerUpdateMode::Automatic,
-            WorkerUpdateMode::Manual => golem_client::model::WorkerUpdateMode::Manual,
-        };
-
-        let _ = self
-            .client
-            .update_worker(
-                &worker_urn.id.component_id.0,
-                &worker_name_required(&worker_urn)?,
-                &UpdateWorkerRequest {
-                    mode: update_mode,
-                    target_version
-                },
-            )
-            .await?;
-        Ok(())
-    }
-
-    async fn get_oplog(
-        &self,
-        worker_urn: WorkerUrn,
-        from: u64,
-    ) -> Result<Vec<(u64, PublicOplogEntry)>, GolemError> {
-        let mut entries = Vec::new();
-        let mut cursor: Option<OplogCursor> = None;
-
-        loop {
-            let chunk = self
-                .client
-                .get_oplog(
-                    &worker_urn.id.component_id.0,
-                    &worker_name_required(&worker_urn)?,
-                    from,
-                    100,
-                    cursor.as_ref(),
-                )
-                .await?;
-
-            trace!(
-                "Got {} oplog entries starting from {}, last index is {}",
-                chunk.entries.len(),
-                chunk.first_index_in_chunk,
-                chunk.last_index
-            );
-
-            if chunk.entries.is_empty() {
-                break;
-            } else {
-                entries.extend(
-                    chunk
-                        .entries
-                        .into_iter()
-                        .enumerate()
-                        .map(|(i, e)| (chunk.first_index_in_chunk + i as u64, e)),
-                );
-                cursor = chunk.next;
-            }
-        }
-
-        Ok(entries)
-    }
 }
 
 fn get_worker_golem_error(status: u16, body: Vec<u8>) -> GolemError {
@@ -532,6 +628,7 @@ fn get_worker_golem_error(status: u16, body: Vec<u8>) -> GolemError {
         404 => serde_json::from_slice(&body).map(|body| Error::Item(WorkerError::Error404(body))),
         409 => serde_json::from_slice(&body).map(|body| Error::Item(WorkerError::Error409(body))),
         500 => serde_json::from_slice(&body).map(|body| Error::Item(WorkerError::Error500(body))),
+        503 => serde_json::from_slice(&body).map(|body| Error::Item(WorkerError::Error503(body))),
         _ => Ok(Error::unexpected(status, body.into())),
     };
     error.unwrap_or_else(Error::from).into()
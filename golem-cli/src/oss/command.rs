@@ -20,6 +20,8 @@ use crate::command::worker::{OssWorkerUriArg, WorkerSubcommand};
 use crate::completion;
 use crate::completion::PrintCompletion;
 use crate::diagnose;
+use crate::golem_wit::GolemWitArgs;
+use crate::http_stubgen::HttpStubgenArgs;
 use crate::model::{ComponentUriArg, Format, HasFormatConfig, HasVerbosity};
 use crate::oss::model::OssContext;
 use clap::{CommandFactory, Parser, Subcommand};
@@ -64,6 +66,20 @@ pub enum OssCommand<ProfileAdd: clap::Args> {
         subcommand: golem_wasm_rpc_stubgen::Command,
     },
 
+    /// Generate typed and durable imports for calling a REST API from an OpenAPI specification
+    #[command()]
+    HttpStubgen {
+        #[command(flatten)]
+        args: HttpStubgenArgs,
+    },
+
+    /// Add the Golem host interfaces missing from golem-wit to the WIT dependencies of a component
+    #[command()]
+    GolemWit {
+        #[command(flatten)]
+        args: GolemWitArgs,
+    },
+
     /// Manage Golem api definitions
     #[command()]
     ApiDefinition {
@@ -114,6 +130,16 @@ pub struct GolemOssCommand<ProfileAdd: clap::Args> {
     #[arg(short = 'F', long, global = true)]
     pub format: Option<Format>,
 
+    /// Use the locally cached component metadata instead of reaching the component service
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Fail instead of asking to pick the worker or component when it is not specified
+    ///
+    /// Workers and components are only picked interactively when running in a terminal.
+    #[arg(long, global = true)]
+    pub no_interactive: bool,
+
     #[command(subcommand)]
     pub command: OssCommand<ProfileAdd>,
 }
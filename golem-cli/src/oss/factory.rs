@@ -26,9 +26,12 @@ use crate::oss::clients::component::ComponentClientLive;
 use crate::oss::clients::health_check::HealthCheckClientLive;
 use crate::oss::clients::worker::WorkerClientLive;
 use crate::oss::model::OssContext;
+use crate::progress::ProgressMode;
+use crate::service::component_cache::ComponentMetadataCache;
 use crate::service::project::{ProjectResolver, ProjectResolverOss};
 use golem_client::Context;
 use itertools::Itertools;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tracing::warn;
 use url::Url;
@@ -40,6 +43,11 @@ pub struct OssServiceFactoryConfig {
     pub service_http_client_config: HttpClientConfig,
     pub health_check_http_client_config: HttpClientConfig,
     pub allow_insecure: bool,
+    pub component_metadata_cache_dir: Option<PathBuf>,
+    pub fleet_update_state_dir: Option<PathBuf>,
+    pub offline: bool,
+    pub interactive: bool,
+    pub progress: ProgressMode,
 }
 
 #[derive(Debug, Clone)]
@@ -61,7 +69,13 @@ impl OssServiceFactory {
         })
     }
 
-    pub fn from_profile(profile: &OssProfile) -> Result<Self, GolemError> {
+    pub fn from_profile(
+        profile: &OssProfile,
+        config_dir: &Path,
+        offline: bool,
+        interactive: bool,
+        progress: ProgressMode,
+    ) -> Result<Self, GolemError> {
         let component_url = profile.url.clone();
         let worker_url = profile
             .worker_url
@@ -75,6 +89,11 @@ impl OssServiceFactory {
             service_http_client_config: HttpClientConfig::new_for_service_calls(allow_insecure),
             health_check_http_client_config: HttpClientConfig::new_for_health_check(allow_insecure),
             allow_insecure,
+            component_metadata_cache_dir: Some(config_dir.join("cache").join("components")),
+            fleet_update_state_dir: Some(config_dir.join("fleet-updates")),
+            offline,
+            interactive,
+            progress,
         })
     }
 
@@ -124,9 +143,33 @@ impl ServiceFactory for OssServiceFactory {
             client: golem_client::api::ComponentClientLive {
                 context: self.component_context(),
             },
+            progress: self.config.progress,
         })
     }
 
+    fn component_metadata_cache(&self) -> Option<ComponentMetadataCache> {
+        self.config
+            .component_metadata_cache_dir
+            .as_deref()
+            .map(ComponentMetadataCache::new)
+    }
+
+    fn fleet_update_state_dir(&self) -> Option<PathBuf> {
+        self.config.fleet_update_state_dir.clone()
+    }
+
+    fn offline(&self) -> bool {
+        self.config.offline
+    }
+
+    fn interactive(&self) -> bool {
+        self.config.interactive
+    }
+
+    fn progress(&self) -> ProgressMode {
+        self.config.progress
+    }
+
     fn worker_client(&self) -> Arc<dyn WorkerClient + Send + Sync> {
         Arc::new(WorkerClientLive {
             client: golem_client::api::WorkerClientLive {
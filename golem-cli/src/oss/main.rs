@@ -17,13 +17,18 @@ use crate::completion::PrintCompletion;
 use crate::config::{OssProfile, ProfileName};
 use crate::diagnose::diagnose;
 use crate::factory::ServiceFactory;
+use crate::golem_wit::handle_golem_wit;
+use crate::http_stubgen::handle_http_stubgen;
 use crate::init::{init_profile, DummyProfileAuth, ProfileAuth};
 use crate::model::{ApiDefinitionId, ApiDefinitionVersion, GolemError, GolemResult};
 use crate::oss::command::{GolemOssCommand, OssCommand};
 use crate::oss::factory::OssServiceFactory;
 use crate::oss::model::OssContext;
+use crate::progress::ProgressMode;
 use crate::stubgen::handle_stubgen;
-use crate::{check_for_newer_server_version, examples, ConfiguredMainArgs, MainArgs, VERSION};
+use crate::{
+    check_for_newer_server_version, examples, picker, ConfiguredMainArgs, MainArgs, VERSION,
+};
 use golem_common::uri::oss::uri::{ComponentUri, ResourceUri, WorkerUri};
 use golem_common::uri::oss::url::{ComponentUrl, ResourceUrl, WorkerUrl};
 use golem_common::uri::oss::urn::{ComponentUrn, ResourceUrn, WorkerUrn};
@@ -41,10 +46,16 @@ pub async fn async_main<ProfileAdd: Into<UniversalProfileAdd> + clap::Args>(
     } = args;
 
     let profile_auth = &DummyProfileAuth;
+    let offline = command.offline;
+    let interactive = !command.no_interactive && picker::is_terminal();
+    let progress = ProgressMode::new(format);
 
     let factory = || async {
-        let factory = OssServiceFactory::from_profile(&profile)?;
-        check_for_newer_server_version(factory.version_service().as_ref(), VERSION).await;
+        let factory =
+            OssServiceFactory::from_profile(&profile, &config_dir, offline, interactive, progress)?;
+        if !offline {
+            check_for_newer_server_version(factory.version_service().as_ref(), VERSION).await;
+        }
         Ok::<OssServiceFactory, GolemError>(factory)
     };
 
@@ -65,7 +76,12 @@ pub async fn async_main<ProfileAdd: Into<UniversalProfileAdd> + clap::Args>(
             let factory = factory().await?;
 
             subcommand
-                .handle(format, factory.worker_service(), factory.project_resolver())
+                .handle(
+                    format,
+                    factory.worker_service(),
+                    factory.component_service(),
+                    factory.project_resolver(),
+                )
                 .await
         }
         OssCommand::Examples(golem_examples::cli::Command::New {
@@ -82,6 +98,8 @@ pub async fn async_main<ProfileAdd: Into<UniversalProfileAdd> + clap::Args>(
         }
         #[cfg(feature = "stubgen")]
         OssCommand::Stubgen { subcommand } => handle_stubgen(subcommand).await,
+        OssCommand::HttpStubgen { args } => handle_http_stubgen(args),
+        OssCommand::GolemWit { args } => handle_golem_wit(args),
         OssCommand::ApiDefinition { subcommand } => {
             let factory = factory().await?;
 
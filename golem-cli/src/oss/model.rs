@@ -14,15 +14,24 @@
 
 use std::fmt::{Display, Formatter};
 
-#[derive(clap::Args, Debug, Clone, Copy)]
-pub struct OssContext {}
+use uuid::Uuid;
+
+#[derive(clap::Args, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OssContext {
+    /// Project of the components, the default project if not specified
+    #[arg(long = "project", value_name = "PROJECT_ID")]
+    pub project_id: Option<Uuid>,
+}
 
 impl OssContext {
-    pub const EMPTY: OssContext = OssContext {};
+    pub const EMPTY: OssContext = OssContext { project_id: None };
 }
 
 impl Display for OssContext {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "OSS context")
+        match &self.project_id {
+            Some(project_id) => write!(f, "{project_id}"),
+            None => write!(f, "default"),
+        }
     }
 }
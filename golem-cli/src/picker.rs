@@ -0,0 +1,87 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interactive pickers for the entities a command refers to, used when they are not given on
+//! the command line.
+
+use crate::model::component::Component;
+use crate::model::text::fmt::format_binary_size;
+use crate::model::{GolemError, WorkerMetadata};
+use inquire::Select;
+use std::fmt::{Display, Formatter};
+use std::io::IsTerminal;
+
+/// Number of options shown at once, the rest are reachable by scrolling or filtering
+const PAGE_SIZE: usize = 15;
+
+/// Whether the CLI can ask the user, which requires both the input and the output to be a terminal
+pub fn is_terminal() -> bool {
+    std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// Lets the user pick one of the options with fuzzy filtering, each option showing a summary
+/// of the entity's metadata
+pub fn pick<T: Display>(kind: &str, options: Vec<T>) -> Result<T, GolemError> {
+    if options.is_empty() {
+        return Err(GolemError(format!("No {kind}s found to pick from")));
+    }
+
+    Select::new(&format!("Select the {kind}:"), options)
+        .with_page_size(PAGE_SIZE)
+        .with_help_message("↑↓ to move, type to filter, enter to select, esc to cancel")
+        .prompt()
+        .map_err(|err| GolemError(format!("No {kind} selected: {err}")))
+}
+
+pub struct ComponentOption(pub Component);
+
+impl Display for ComponentOption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let component = &self.0;
+        write!(
+            f,
+            "{}  (version {}, {}, {} exports, {})",
+            component.component_name,
+            component.versioned_component_id.version,
+            component.component_type,
+            component.metadata.exports.len(),
+            format_binary_size(&component.component_size),
+        )
+    }
+}
+
+pub struct WorkerOption(pub WorkerMetadata);
+
+impl Display for WorkerOption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let worker = &self.0;
+        write!(
+            f,
+            "{}  ({}, version {}, {} pending, created {})",
+            worker.worker_id.worker_name,
+            worker.status,
+            worker.component_version,
+            worker.pending_invocation_count,
+            worker.created_at.format("%Y-%m-%d %H:%M:%S"),
+        )?;
+        if let Some(error) = &worker.last_error {
+            write!(
+                f,
+                "  last error: {}",
+                error.lines().next().unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Progress reporting of long-running operations. Progress is written to the standard error, so
+//! the result of the command on the standard output is not affected: as a progress bar when
+//! running in a terminal in text mode, and as newline-delimited JSON events in JSON mode.
+
+use crate::model::text::fmt::format_binary_size;
+use crate::model::Format;
+use serde::Serialize;
+use std::io::{IsTerminal, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Minimum time between two reports of the same operation
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+const BAR_WIDTH: u64 = 30;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ProgressMode {
+    Hidden,
+    Bar,
+    Json,
+}
+
+impl ProgressMode {
+    pub fn new(format: Format) -> Self {
+        match format {
+            Format::Json => ProgressMode::Json,
+            Format::Text if std::io::stderr().is_terminal() => ProgressMode::Bar,
+            _ => ProgressMode::Hidden,
+        }
+    }
+
+    /// Starts reporting the progress of an operation processing `total` units
+    pub fn start(self, operation: ProgressOperation, unit: ProgressUnit, total: u64) -> Progress {
+        let progress = Progress {
+            mode: self,
+            inner: Arc::new(ProgressInner {
+                operation,
+                unit,
+                total,
+                state: Mutex::new(ProgressState {
+                    current: 0,
+                    failed: 0,
+                    last_report: None,
+                    finished: false,
+                }),
+            }),
+        };
+        progress.report(ProgressStage::Started, true);
+        progress
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProgressOperation {
+    ComponentUpload,
+    IfsUpload,
+    WorkerUpdate,
+    BatchInvoke,
+}
+
+impl ProgressOperation {
+    fn label(&self) -> &'static str {
+        match self {
+            ProgressOperation::ComponentUpload => "Uploading component",
+            ProgressOperation::IfsUpload => "Uploading initial file system",
+            ProgressOperation::WorkerUpdate => "Updating workers",
+            ProgressOperation::BatchInvoke => "Invoking",
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProgressUnit {
+    Bytes,
+    Workers,
+    Invocations,
+}
+
+impl ProgressUnit {
+    fn format(&self, amount: u64) -> String {
+        match self {
+            ProgressUnit::Bytes => format_binary_size(&amount),
+            ProgressUnit::Workers | ProgressUnit::Invocations => amount.to_string(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum ProgressStage {
+    Started,
+    Running,
+    Finished,
+}
+
+/// Progress event written in JSON mode
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProgressEvent {
+    operation: ProgressOperation,
+    stage: ProgressStage,
+    unit: ProgressUnit,
+    current: u64,
+    total: u64,
+    failed: u64,
+}
+
+/// Reports the progress of a single operation. Clones report to the same operation, so it
+/// can be shared by the concurrent parts of the operation.
+#[derive(Clone)]
+pub struct Progress {
+    mode: ProgressMode,
+    inner: Arc<ProgressInner>,
+}
+
+struct ProgressInner {
+    operation: ProgressOperation,
+    unit: ProgressUnit,
+    total: u64,
+    state: Mutex<ProgressState>,
+}
+
+struct ProgressState {
+    current: u64,
+    failed: u64,
+    last_report: Option<Instant>,
+    finished: bool,
+}
+
+impl Progress {
+    /// Records the successful processing of `amount` units
+    pub fn inc(&self, amount: u64) {
+        self.advance(amount, 0)
+    }
+
+    /// Records a unit which failed to be processed
+    pub fn fail(&self) {
+        self.advance(1, 1)
+    }
+
+    pub fn finish(&self) {
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.finished {
+                return;
+            }
+            state.finished = true;
+        }
+        self.report(ProgressStage::Finished, true);
+    }
+
+    fn advance(&self, amount: u64, failed: u64) {
+        if self.mode == ProgressMode::Hidden {
+            return;
+        }
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            state.current += amount;
+            state.failed += failed;
+        }
+        self.report(ProgressStage::Running, false);
+    }
+
+    fn report(&self, stage: ProgressStage, force: bool) {
+        if self.mode == ProgressMode::Hidden {
+            return;
+        }
+
+        let mut state = self.inner.state.lock().unwrap();
+        let now = Instant::now();
+        let due = match state.last_report {
+            Some(last) => now.duration_since(last) >= REPORT_INTERVAL,
+            None => true,
+        };
+        if !force && !due {
+            return;
+        }
+        state.last_report = Some(now);
+
+        let mut stderr = std::io::stderr().lock();
+        // Progress reporting is best effort, failing to write it does not fail the operation
+        let _ = match self.mode {
+            ProgressMode::Json => {
+                let event = ProgressEvent {
+                    operation: self.inner.operation,
+                    stage,
+                    unit: self.inner.unit,
+                    current: state.current,
+                    total: self.inner.total,
+                    failed: state.failed,
+                };
+                match serde_json::to_string(&event) {
+                    Ok(json) => writeln!(stderr, "{json}"),
+                    Err(_) => Ok(()),
+                }
+            }
+            ProgressMode::Bar => {
+                let line = self.render_bar(&state);
+                if stage == ProgressStage::Finished {
+                    writeln!(stderr, "\r{line}")
+                } else {
+                    write!(stderr, "\r{line}").and_then(|_| stderr.flush())
+                }
+            }
+            ProgressMode::Hidden => Ok(()),
+        };
+    }
+
+    fn render_bar(&self, state: &ProgressState) -> String {
+        let total = self.inner.total;
+        let current = state.current.min(total);
+        let filled = if total == 0 {
+            BAR_WIDTH
+        } else {
+            current * BAR_WIDTH / total
+        };
+        let percent = if total == 0 {
+            100
+        } else {
+            current * 100 / total
+        };
+
+        let mut line = format!(
+            "{} [{}{}] {:>3}% {}/{}",
+            self.inner.operation.label(),
+            "#".repeat(filled as usize),
+            "-".repeat((BAR_WIDTH - filled) as usize),
+            percent,
+            self.inner.unit.format(current),
+            self.inner.unit.format(total),
+        );
+        if state.failed > 0 {
+            line.push_str(&format!(", {} failed", state.failed));
+        }
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::progress::{ProgressMode, ProgressOperation, ProgressState, ProgressUnit};
+
+    #[test]
+    fn progress_bar_rendering() {
+        let progress =
+            ProgressMode::Hidden.start(ProgressOperation::WorkerUpdate, ProgressUnit::Workers, 4);
+        let state = ProgressState {
+            current: 3,
+            failed: 1,
+            last_report: None,
+            finished: false,
+        };
+
+        assert_eq!(
+            progress.render_bar(&state),
+            "Updating workers [######################--------]  75% 3/4, 1 failed"
+        );
+    }
+}
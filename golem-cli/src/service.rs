@@ -15,6 +15,7 @@
 pub mod api_definition;
 pub mod api_deployment;
 pub mod component;
+pub mod component_cache;
 pub mod deploy;
 pub mod project;
 pub mod version;
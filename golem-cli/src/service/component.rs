@@ -13,9 +13,14 @@
 // limitations under the License.
 
 use crate::clients::component::ComponentClient;
+use crate::service::component_cache::ComponentMetadataCache;
 use crate::model::component::{Component, ComponentView};
+use crate::model::json_schema::function_json_schema;
 use crate::model::text::component::{ComponentAddView, ComponentGetView, ComponentUpdateView};
-use crate::model::{ComponentName, Format, GolemError, GolemResult, PathBufOrStdin};
+use crate::model::{
+    ComponentName, ComponentUriOrPick, Format, GolemError, GolemResult, PathBufOrStdin,
+};
+use crate::picker::{pick, ComponentOption};
 use async_trait::async_trait;
 use golem_client::model::ComponentType;
 use golem_common::model::ComponentId;
@@ -24,8 +29,11 @@ use golem_common::uri::oss::url::ComponentUrl;
 use golem_common::uri::oss::urn::ComponentUrn;
 use indoc::formatdoc;
 use itertools::Itertools;
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::path::PathBuf;
+use std::time::Duration;
+use tracing::info;
 use golem_wasm_rpc_stubgen::model::oam::{Application};
 
 #[async_trait]
@@ -40,8 +48,18 @@ pub trait ComponentService {
         project: Option<Self::ProjectContext>,
         non_interactive: bool,
         format: Format,
-        ifs: PathBuf
+        ifs: PathBuf,
+        function_aliases: Option<HashMap<String, String>>,
     ) -> Result<GolemResult, GolemError>;
+    async fn add_metadata(
+        &self,
+        component_name: ComponentName,
+        component_file: PathBufOrStdin,
+        component_type: ComponentType,
+        project: &Option<Self::ProjectContext>,
+        ifs: PathBuf,
+        function_aliases: Option<HashMap<String, String>>,
+    ) -> Result<Component, GolemError>;
     async fn update(
         &self,
         component_uri: ComponentUri,
@@ -50,24 +68,42 @@ pub trait ComponentService {
         project: Option<Self::ProjectContext>,
         non_interactive: bool,
         format: Format,
-        ifs: PathBuf
+        ifs: PathBuf,
+        function_aliases: Option<HashMap<String, String>>,
     ) -> Result<GolemResult, GolemError>;
     async fn list(
         &self,
         component_name: Option<ComponentName>,
         project: Option<Self::ProjectContext>,
     ) -> Result<GolemResult, GolemError>;
+    async fn list_metadata(
+        &self,
+        project: &Option<Self::ProjectContext>,
+    ) -> Result<Vec<Component>, GolemError>;
     async fn get(
         &self,
         component_uri: ComponentUri,
         version: Option<u64>,
         project: Option<Self::ProjectContext>,
     ) -> Result<GolemResult, GolemError>;
+    async fn function_schema(
+        &self,
+        component_uri: ComponentUri,
+        version: Option<u64>,
+        function: &str,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError>;
     async fn resolve_uri(
         &self,
         uri: ComponentUri,
         project: &Option<Self::ProjectContext>,
     ) -> Result<ComponentUrn, GolemError>;
+    /// Gets the component given on the command line, or lets the user pick one if it was not given
+    async fn component_uri_or_pick(
+        &self,
+        component: ComponentUriOrPick,
+        project: &Option<Self::ProjectContext>,
+    ) -> Result<ComponentUri, GolemError>;
     async fn get_metadata(
         &self,
         component_urn: &ComponentUrn,
@@ -77,10 +113,79 @@ pub trait ComponentService {
         &self,
         component_urn: &ComponentUrn,
     ) -> Result<Component, GolemError>;
+    async fn download(
+        &self,
+        component_uri: ComponentUri,
+        version: Option<u64>,
+        output: Option<PathBuf>,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError>;
+    async fn delete(&self, component_urn: &ComponentUrn) -> Result<(), GolemError>;
+    /// Whether only the cached component metadata is used, without reaching the component service
+    fn offline(&self) -> bool;
 }
 
+/// How long the latest version of a component fetched from the component service is used
+/// without checking for a newer one
+const LATEST_VERSION_CACHE_TTL: Duration = Duration::from_secs(60);
+
 pub struct ComponentServiceLive<ProjectContext> {
     pub client: Box<dyn ComponentClient<ProjectContext = ProjectContext> + Send + Sync>,
+    pub cache: Option<ComponentMetadataCache>,
+    /// Only use the cached component metadata, without reaching the component service
+    pub offline: bool,
+    /// Whether missing components can be picked interactively
+    pub interactive: bool,
+}
+
+impl<ProjectContext: Send + Sync> ComponentServiceLive<ProjectContext> {
+    fn cached(&self, component: Component) -> Component {
+        if let Some(cache) = &self.cache {
+            cache.put(&component);
+        }
+        component
+    }
+
+    /// Completes a component name which did not match any component to the only cached component
+    /// name starting with it
+    fn complete_name(&self, name: &str) -> Result<Option<String>, GolemError> {
+        let Some(cache) = &self.cache else {
+            return Ok(None);
+        };
+
+        let names = cache.complete_name(name);
+        match names.as_slice() {
+            [] => Ok(None),
+            [completed] => Ok(Some(completed.clone())),
+            _ => Err(GolemError(format!(
+                "Can't find component {name}, did you mean one of: {}",
+                names.join(", ")
+            ))),
+        }
+    }
+
+    fn offline_cache(&self) -> Result<&ComponentMetadataCache, GolemError> {
+        self.cache.as_ref().ok_or_else(|| {
+            GolemError("Component metadata cache is not available in offline mode".to_string())
+        })
+    }
+
+    async fn find(
+        &self,
+        component_name: Option<ComponentName>,
+        project: &Option<ProjectContext>,
+    ) -> Result<Vec<Component>, GolemError> {
+        if self.offline {
+            let name = component_name.as_ref().map(|name| name.0.as_str());
+            Ok(self.offline_cache()?.find(name))
+        } else {
+            let components = self.client.find(component_name, project).await?;
+            Ok(components
+                .into_iter()
+                .map(|component| self.cached(component))
+                .collect())
+        }
+    }
 }
 
 #[async_trait]
@@ -97,7 +202,8 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
         project: Option<Self::ProjectContext>,
         non_interactive: bool,
         format: Format,
-        ifs: PathBuf
+        ifs: PathBuf,
+        function_aliases: Option<HashMap<String, String>>,
     ) -> Result<GolemResult, GolemError> {
         let result = self
             .client
@@ -106,7 +212,8 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
                 component_file.clone(),
                 &project,
                 component_type,
-                ifs.clone()
+                ifs.clone(),
+                function_aliases.clone(),
             )
             .await;
 
@@ -132,7 +239,7 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
                             name: component_name.0.clone(),
                         });
                         let urn = self.resolve_uri(component_uri, &project).await?;
-                        self.client.update(urn, component_file, Some(component_type), ifs).await.map(|component| GolemResult::Ok(Box::new(ComponentUpdateView(component.into()))))
+                        self.client.update(urn, component_file, Some(component_type), ifs, function_aliases).await.map(|component| GolemResult::Ok(Box::new(ComponentUpdateView(component.into()))))
 
                     }
                     Ok(false) => Err(GolemError(message)),
@@ -148,6 +255,35 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
         Ok(result)
     }
 
+    async fn add_metadata(
+        &self,
+        component_name: ComponentName,
+        component_file: PathBufOrStdin,
+        component_type: ComponentType,
+        project: &Option<Self::ProjectContext>,
+        ifs: PathBuf,
+        function_aliases: Option<HashMap<String, String>>,
+    ) -> Result<Component, GolemError> {
+        if self.offline {
+            return Err(GolemError(
+                "Components can not be added in offline mode".to_string(),
+            ));
+        }
+
+        let component = self
+            .client
+            .add(
+                component_name,
+                component_file,
+                project,
+                component_type,
+                ifs,
+                function_aliases,
+            )
+            .await?;
+        Ok(self.cached(component))
+    }
+
     async fn update(
         &self,
         component_uri: ComponentUri,
@@ -156,7 +292,8 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
         project: Option<Self::ProjectContext>,
         non_interactive: bool,
         format: Format,
-        ifs: PathBuf
+        ifs: PathBuf,
+        function_aliases: Option<HashMap<String, String>>,
     ) -> Result<GolemResult, GolemError> {
         let result = self.resolve_uri(component_uri.clone(), &project).await;
 
@@ -183,7 +320,7 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
                                 ComponentUri::URL(ComponentUrl { name }) => ComponentName(name.clone()),
                                 _ => unreachable!(),
                             };
-                            self.client.add(component_name, component_file, &project, component_type.unwrap_or(ComponentType::Durable), ifs).await.map(|component| {
+                            self.client.add(component_name, component_file, &project, component_type.unwrap_or(ComponentType::Durable), ifs, function_aliases).await.map(|component| {
                                 GolemResult::Ok(Box::new(ComponentAddView(component.into())))
                             })
 
@@ -195,7 +332,7 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
             Err(other) => Err(other),
             Ok(urn) => self
                 .client
-                .update(urn, component_file.clone(), component_type, ifs)
+                .update(urn, component_file.clone(), component_type, ifs, function_aliases)
                 .await
                 .map(|component| GolemResult::Ok(Box::new(ComponentUpdateView(component.into())))),
         }?;
@@ -208,12 +345,19 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
         component_name: Option<ComponentName>,
         project: Option<Self::ProjectContext>,
     ) -> Result<GolemResult, GolemError> {
-        let components = self.client.find(component_name, &project).await?;
+        let components = self.find(component_name, &project).await?;
         let views: Vec<ComponentView> = components.into_iter().map(|t| t.into()).collect();
 
         Ok(GolemResult::Ok(Box::new(views)))
     }
 
+    async fn list_metadata(
+        &self,
+        project: &Option<Self::ProjectContext>,
+    ) -> Result<Vec<Component>, GolemError> {
+        self.find(None, project).await
+    }
+
     async fn get(
         &self,
         component_uri: ComponentUri,
@@ -225,8 +369,35 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
             Some(v) => self.get_metadata(&urn, v).await?,
             None => self.get_latest_metadata(&urn).await?,
         };
+        let compilation_status = if self.offline {
+            None
+        } else {
+            self.client
+                .get_compilation_status(&urn, component.versioned_component_id.version)
+                .await?
+        };
         let view: ComponentView = component.into();
-        Ok(GolemResult::Ok(Box::new(ComponentGetView(view))))
+        Ok(GolemResult::Ok(Box::new(ComponentGetView {
+            component: view,
+            compilation_status,
+        })))
+    }
+
+    async fn function_schema(
+        &self,
+        component_uri: ComponentUri,
+        version: Option<u64>,
+        function: &str,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        let urn = self.resolve_uri(component_uri, &project).await?;
+        let component = match version {
+            Some(v) => self.get_metadata(&urn, v).await?,
+            None => self.get_latest_metadata(&urn).await?,
+        };
+        Ok(GolemResult::Json(function_json_schema(
+            &component, function,
+        )?))
     }
 
     async fn resolve_uri(
@@ -237,10 +408,19 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
         match uri {
             ComponentUri::URN(urn) => Ok(urn),
             ComponentUri::URL(ComponentUrl { name }) => {
-                let components = self
-                    .client
+                let mut name = name;
+                let mut components = self
                     .find(Some(ComponentName(name.clone())), project_context)
                     .await?;
+                if components.is_empty() {
+                    if let Some(completed) = self.complete_name(&name)? {
+                        info!("Completed component name {name} to {completed}");
+                        components = self
+                            .find(Some(ComponentName(completed.clone())), project_context)
+                            .await?;
+                        name = completed;
+                    }
+                }
                 let components: Vec<Component> = components
                     .into_iter()
                     .chunk_by(|c| c.versioned_component_id.component_id)
@@ -281,15 +461,120 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
         }
     }
 
+    async fn component_uri_or_pick(
+        &self,
+        component: ComponentUriOrPick,
+        project: &Option<Self::ProjectContext>,
+    ) -> Result<ComponentUri, GolemError> {
+        if let ComponentUriOrPick::Uri(uri) = component {
+            return Ok(uri);
+        }
+
+        if !self.interactive {
+            return Err(GolemError(
+                "Missing component, specify it with --component or --component-name".to_string(),
+            ));
+        }
+
+        // Only the latest version of each component is offered
+        let options = self
+            .list_metadata(project)
+            .await?
+            .into_iter()
+            .sorted_by_key(|c| {
+                (
+                    c.component_name.clone(),
+                    c.versioned_component_id.component_id,
+                    std::cmp::Reverse(c.versioned_component_id.version),
+                )
+            })
+            .dedup_by(|a, b| {
+                a.versioned_component_id.component_id == b.versioned_component_id.component_id
+            })
+            .map(ComponentOption)
+            .collect();
+        let component = pick("component", options)?.0;
+
+        Ok(ComponentUri::URN(ComponentUrn {
+            id: ComponentId(component.versioned_component_id.component_id),
+        }))
+    }
+
     async fn get_metadata(
         &self,
         urn: &ComponentUrn,
         version: u64,
     ) -> Result<Component, GolemError> {
-        self.client.get_metadata(urn, version).await
+        if let Some(component) = self.cache.as_ref().and_then(|cache| cache.get(urn, version)) {
+            Ok(component)
+        } else if self.offline {
+            Err(GolemError(format!(
+                "Metadata of version {version} of component {urn} is not cached"
+            )))
+        } else {
+            let component = self.client.get_metadata(urn, version).await?;
+            Ok(self.cached(component))
+        }
     }
 
     async fn get_latest_metadata(&self, urn: &ComponentUrn) -> Result<Component, GolemError> {
-        self.client.get_latest_metadata(urn).await
+        if self.offline {
+            self.offline_cache()?
+                .get_latest(urn)
+                .ok_or_else(|| GolemError(format!("Metadata of component {urn} is not cached")))
+        } else if let Some(component) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get_recent_latest(urn, LATEST_VERSION_CACHE_TTL))
+        {
+            Ok(component)
+        } else {
+            let component = self.client.get_latest_metadata(urn).await?;
+            if let Some(cache) = &self.cache {
+                cache.put_latest(&component);
+            }
+            Ok(component)
+        }
+    }
+
+    async fn download(
+        &self,
+        component_uri: ComponentUri,
+        version: Option<u64>,
+        output: Option<PathBuf>,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        if self.offline {
+            return Err(GolemError(
+                "Components can not be downloaded in offline mode".to_string(),
+            ));
+        }
+
+        let urn = self.resolve_uri(component_uri, &project).await?;
+        let output = output.unwrap_or_else(|| match version {
+            Some(version) => PathBuf::from(format!("{}-{version}.wasm", urn.id)),
+            None => PathBuf::from(format!("{}.wasm", urn.id)),
+        });
+
+        let size = self.client.download(&urn, version, &output).await?;
+
+        Ok(GolemResult::Str(format!(
+            "Downloaded {size} bytes to {}",
+            output.display()
+        )))
+    }
+
+    async fn delete(&self, component_urn: &ComponentUrn) -> Result<(), GolemError> {
+        if self.offline {
+            return Err(GolemError(
+                "Components can not be deleted in offline mode".to_string(),
+            ));
+        }
+
+        self.client.delete(component_urn).await
+    }
+
+    fn offline(&self) -> bool {
+        self.offline
     }
 }
@@ -0,0 +1,272 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::model::component::Component;
+use crate::model::GolemError;
+use golem_common::model::ComponentId;
+use golem_common::uri::oss::urn::ComponentUrn;
+use std::fs::{create_dir_all, read_dir, read_to_string, write, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Local cache of component metadata.
+///
+/// A component version's metadata never changes, so cached versions are never refetched.
+/// Each version is stored in `<cache dir>/<component id>/<version>.json`, and the latest version
+/// fetched from the component service in `<cache dir>/<component id>/latest`.
+#[derive(Debug, Clone)]
+pub struct ComponentMetadataCache {
+    dir: PathBuf,
+}
+
+impl ComponentMetadataCache {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    pub fn get(&self, urn: &ComponentUrn, version: u64) -> Option<Component> {
+        let path = self.component_dir(&urn.id).join(format!("{version}.json"));
+        let file = File::open(&path).ok()?;
+
+        match serde_json::from_reader(BufReader::new(file)) {
+            Ok(component) => Some(component),
+            Err(err) => {
+                warn!("Ignoring invalid cached component metadata {path:?}: {err}");
+                None
+            }
+        }
+    }
+
+    /// Gets the highest cached version of a component, which is not necessarily the latest one
+    pub fn get_latest(&self, urn: &ComponentUrn) -> Option<Component> {
+        self.versions(urn)
+            .into_iter()
+            .max()
+            .and_then(|version| self.get(urn, version))
+    }
+
+    /// Gets the latest version of a component, if it was fetched from the component service less
+    /// than `max_age` ago
+    pub fn get_recent_latest(&self, urn: &ComponentUrn, max_age: Duration) -> Option<Component> {
+        let path = self.component_dir(&urn.id).join("latest");
+        let age = path.metadata().ok()?.modified().ok()?.elapsed().ok()?;
+        if age >= max_age {
+            return None;
+        }
+
+        let version = read_to_string(&path).ok()?.trim().parse().ok()?;
+        self.get(urn, version)
+    }
+
+    /// Gets the highest cached version of all cached components, optionally filtered by name
+    pub fn find(&self, name: Option<&str>) -> Vec<Component> {
+        let Ok(entries) = read_dir(&self.dir) else {
+            return Vec::new();
+        };
+
+        entries
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+            .filter_map(|id| {
+                self.get_latest(&ComponentUrn {
+                    id: ComponentId(id),
+                })
+            })
+            .filter(|component| name.map_or(true, |name| component.component_name == name))
+            .collect()
+    }
+
+    /// Gets the names of the cached components starting with `prefix`, sorted and deduplicated
+    pub fn complete_name(&self, prefix: &str) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .find(None)
+            .into_iter()
+            .map(|component| component.component_name)
+            .filter(|name| name.starts_with(prefix))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Stores a component version's metadata. Failing to do so is not an error, the metadata
+    /// is fetched again next time.
+    pub fn put(&self, component: &Component) {
+        if let Err(err) = self.try_put(component) {
+            warn!("{}", err.0);
+        }
+    }
+
+    /// Stores the metadata of the latest version of a component, just fetched from the
+    /// component service
+    pub fn put_latest(&self, component: &Component) {
+        let result = self.try_put(component).and_then(|_| {
+            let path = self
+                .component_dir(&ComponentId(component.versioned_component_id.component_id))
+                .join("latest");
+            write(path, component.versioned_component_id.version.to_string()).map_err(|err| {
+                GolemError(format!(
+                    "Can't save the latest component version to cache: {err}"
+                ))
+            })
+        });
+        if let Err(err) = result {
+            warn!("{}", err.0);
+        }
+    }
+
+    fn try_put(&self, component: &Component) -> Result<(), GolemError> {
+        let dir = self.component_dir(&ComponentId(component.versioned_component_id.component_id));
+        create_dir_all(&dir).map_err(|err| {
+            GolemError(format!(
+                "Can't create component metadata cache directory: {err}"
+            ))
+        })?;
+
+        let path = dir.join(format!("{}.json", component.versioned_component_id.version));
+        let file = File::create(&path).map_err(|err| {
+            GolemError(format!("Can't open component metadata cache file: {err}"))
+        })?;
+        serde_json::to_writer(BufWriter::new(file), component)
+            .map_err(|err| GolemError(format!("Can't save component metadata to cache: {err}")))?;
+
+        debug!("Cached component metadata in {path:?}");
+        Ok(())
+    }
+
+    fn component_dir(&self, component_id: &ComponentId) -> PathBuf {
+        self.dir.join(component_id.0.to_string())
+    }
+
+    fn versions(&self, urn: &ComponentUrn) -> Vec<u64> {
+        let Ok(entries) = read_dir(self.component_dir(&urn.id)) else {
+            return Vec::new();
+        };
+
+        entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? == "json" {
+                    path.file_stem()?.to_str()?.parse().ok()
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::component::Component;
+    use crate::service::component_cache::ComponentMetadataCache;
+    use golem_client::model::{ComponentMetadata, ComponentType, VersionedComponentId};
+    use golem_common::model::ComponentId;
+    use golem_common::uri::oss::urn::ComponentUrn;
+    use std::time::Duration;
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    fn component(id: Uuid, name: &str, version: u64) -> Component {
+        Component {
+            versioned_component_id: VersionedComponentId {
+                component_id: id,
+                version,
+            },
+            component_name: name.to_string(),
+            component_size: 100,
+            component_type: ComponentType::Durable,
+            metadata: ComponentMetadata {
+                exports: vec![],
+                producers: vec![],
+                memories: vec![],
+                files: None,
+                function_aliases: None,
+                capabilities: None,
+            },
+            project_id: None,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn caches_component_versions() {
+        let dir = TempDir::new().unwrap();
+        let cache = ComponentMetadataCache::new(dir.path());
+
+        let id = Uuid::new_v4();
+        let urn = ComponentUrn {
+            id: ComponentId(id),
+        };
+        assert_eq!(cache.get_latest(&urn), None);
+
+        cache.put(&component(id, "shopping-cart", 0));
+        cache.put(&component(id, "shopping-cart", 2));
+        cache.put(&component(Uuid::new_v4(), "inventory", 0));
+
+        assert_eq!(cache.get(&urn, 0), Some(component(id, "shopping-cart", 0)));
+        assert_eq!(cache.get(&urn, 1), None);
+        assert_eq!(
+            cache.get_latest(&urn),
+            Some(component(id, "shopping-cart", 2))
+        );
+        assert_eq!(
+            cache.find(Some("shopping-cart")),
+            vec![component(id, "shopping-cart", 2)]
+        );
+        assert_eq!(cache.find(None).len(), 2);
+    }
+
+    #[test]
+    fn completes_cached_component_names() {
+        let dir = TempDir::new().unwrap();
+        let cache = ComponentMetadataCache::new(dir.path());
+
+        cache.put(&component(Uuid::new_v4(), "shopping-cart", 0));
+        cache.put(&component(Uuid::new_v4(), "shopping-list", 1));
+        cache.put(&component(Uuid::new_v4(), "inventory", 0));
+
+        assert_eq!(
+            cache.complete_name("shop"),
+            vec!["shopping-cart".to_string(), "shopping-list".to_string()]
+        );
+        assert_eq!(cache.complete_name("inv"), vec!["inventory".to_string()]);
+        assert!(cache.complete_name("orders").is_empty());
+    }
+
+    #[test]
+    fn latest_version_is_only_used_while_recent() {
+        let dir = TempDir::new().unwrap();
+        let cache = ComponentMetadataCache::new(dir.path());
+
+        let id = Uuid::new_v4();
+        let urn = ComponentUrn {
+            id: ComponentId(id),
+        };
+        cache.put(&component(id, "shopping-cart", 3));
+        assert_eq!(cache.get_recent_latest(&urn, Duration::from_secs(60)), None);
+
+        cache.put_latest(&component(id, "shopping-cart", 2));
+        assert_eq!(
+            cache.get_recent_latest(&urn, Duration::from_secs(60)),
+            Some(component(id, "shopping-cart", 2))
+        );
+        assert_eq!(cache.get_recent_latest(&urn, Duration::ZERO), None);
+    }
+}
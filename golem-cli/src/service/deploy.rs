@@ -12,16 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::model::{Format, GolemError, GolemResult, WorkerName, WorkerUpdateMode};
+use crate::model::deploy::{
+    UpdateRate, WorkerFleetUpdateResult, WorkerFleetUpdateState, WorkerUpdateFailure,
+};
+use crate::model::{
+    ComponentName, Format, GolemError, GolemResult, PathBufOrStdin, WorkerName, WorkerUpdateMode,
+};
+use crate::progress::{ProgressMode, ProgressOperation, ProgressUnit};
 use crate::service::component::ComponentService;
 use crate::service::worker::WorkerService;
 use async_trait::async_trait;
-use golem_common::uri::oss::uri::ComponentUri;
-use golem_common::uri::oss::urn::WorkerUrn;
+use golem_client::model::ComponentType;
+use golem_common::model::{ComponentId, TargetWorkerId};
+use golem_common::uri::oss::uri::{ComponentUri, WorkerUri};
+use golem_common::uri::oss::urn::{ComponentUrn, WorkerUrn};
 use inquire::Confirm;
+use serde_json::Value;
 use std::fmt::Display;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::{debug, info};
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, error, info};
+use uuid::Uuid;
 
 /// Higher-level deployment operations implemented on top of the underlying services
 #[async_trait]
@@ -35,6 +47,20 @@ pub trait DeployService {
         mode: WorkerUpdateMode,
     ) -> Result<GolemResult, GolemError>;
 
+    /// Updates the workers of the component to the target version, one at a time and at most
+    /// at the given rate. The progress is saved under the resume token, which is generated if
+    /// not given and reported in the result, and passing the token again skips the workers which
+    /// were already updated.
+    async fn update_workers(
+        &self,
+        component_uri: ComponentUri,
+        project: Option<Self::ProjectContext>,
+        target_version: u64,
+        mode: WorkerUpdateMode,
+        rate: Option<UpdateRate>,
+        resume_token: Option<String>,
+    ) -> Result<GolemResult, GolemError>;
+
     async fn redeploy(
         &self,
         component_uri: ComponentUri,
@@ -42,11 +68,43 @@ pub trait DeployService {
         non_interactive: bool,
         format: Format,
     ) -> Result<GolemResult, GolemError>;
+
+    /// Uploads the component as a temporary ephemeral component, invokes a function of it in an
+    /// ephemeral worker, then deletes the component
+    async fn try_component(
+        &self,
+        component_file: PathBufOrStdin,
+        ifs: PathBuf,
+        function: String,
+        parameters: Option<Value>,
+        wave: Vec<String>,
+        project: Option<Self::ProjectContext>,
+        format: Format,
+    ) -> Result<GolemResult, GolemError>;
 }
 
 pub struct DeployServiceLive<ProjectContext> {
     pub component_service: Arc<dyn ComponentService<ProjectContext = ProjectContext> + Send + Sync>,
     pub worker_service: Arc<dyn WorkerService<ProjectContext = ProjectContext> + Send + Sync>,
+    /// Directory of the saved progress of `update_workers`
+    pub fleet_update_state_dir: Option<PathBuf>,
+    pub progress: ProgressMode,
+}
+
+impl<ProjectContext> DeployServiceLive<ProjectContext> {
+    fn fleet_update_state_path(&self, resume_token: &str) -> Result<PathBuf, GolemError> {
+        if resume_token.is_empty()
+            || !resume_token
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(GolemError(format!("Invalid resume token: {resume_token}")));
+        }
+        self.fleet_update_state_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{resume_token}.json")))
+            .ok_or_else(|| GolemError("The progress of worker updates cannot be saved".to_string()))
+    }
 }
 
 #[async_trait]
@@ -79,6 +137,112 @@ impl<ProjectContext: Display + Send + Sync> DeployService for DeployServiceLive<
             .await
     }
 
+    async fn update_workers(
+        &self,
+        component_uri: ComponentUri,
+        project: Option<Self::ProjectContext>,
+        target_version: u64,
+        mode: WorkerUpdateMode,
+        rate: Option<UpdateRate>,
+        resume_token: Option<String>,
+    ) -> Result<GolemResult, GolemError> {
+        let component_urn = self
+            .component_service
+            .resolve_uri(component_uri, &project)
+            .await?;
+
+        // A token chosen by the caller starts a new update if nothing was saved under it yet,
+        // so an update interrupted before reporting its result can still be resumed
+        let resume_token = resume_token.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let state_path = self.fleet_update_state_path(&resume_token)?;
+        let mut state = if state_path.exists() {
+            let state = WorkerFleetUpdateState::load(&state_path)?;
+            if state.component_urn != component_urn || state.target_version != target_version {
+                return Err(GolemError(format!(
+                    "Resume token {resume_token} belongs to the update of component {} to version {}",
+                    state.component_urn, state.target_version
+                )));
+            }
+            state
+        } else {
+            WorkerFleetUpdateState::new(component_urn.clone(), target_version)
+        };
+        state.save(&state_path)?;
+
+        let known_workers = self
+            .worker_service
+            .list_worker_metadata(&component_urn, None, Some(true))
+            .await?;
+
+        let mut skipped = 0;
+        let mut to_update = Vec::new();
+        for worker in known_workers {
+            let worker_urn = WorkerUrn {
+                id: worker.worker_id.clone().into_target_worker_id(),
+            };
+            if state.succeeded.contains(&worker_urn) {
+                continue;
+            } else if worker.component_version >= target_version {
+                skipped += 1;
+            } else {
+                to_update.push(worker_urn);
+            }
+        }
+
+        info!(
+            "Updating {} workers of component {} to version {target_version}",
+            to_update.len(),
+            component_urn
+        );
+
+        let mut ticks = rate.map(|rate| {
+            let mut ticks = tokio::time::interval(rate.interval());
+            ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            ticks
+        });
+        let progress = self.progress.start(
+            ProgressOperation::WorkerUpdate,
+            ProgressUnit::Workers,
+            to_update.len() as u64,
+        );
+        for worker_urn in to_update {
+            if let Some(ticks) = &mut ticks {
+                ticks.tick().await;
+            }
+
+            let result = self
+                .worker_service
+                .update_by_urn(worker_urn.clone(), target_version, mode.clone())
+                .await;
+
+            // Workers which failed in a previous run are retried when resuming
+            state
+                .failed
+                .retain(|failure| failure.worker_urn != worker_urn);
+            match result {
+                Ok(_) => {
+                    progress.inc(1);
+                    state.succeeded.push(worker_urn);
+                }
+                Err(GolemError(error)) => {
+                    progress.fail();
+                    error!("Failed to update worker {worker_urn}: {error}");
+                    state.failed.push(WorkerUpdateFailure { worker_urn, error });
+                }
+            }
+            state.save(&state_path)?;
+        }
+        progress.finish();
+
+        Ok(GolemResult::Ok(Box::new(WorkerFleetUpdateResult {
+            resume_token,
+            target_version,
+            skipped,
+            succeeded: state.succeeded,
+            failed: state.failed,
+        })))
+    }
+
     async fn redeploy(
         &self,
         component_uri: ComponentUri,
@@ -157,4 +321,610 @@ impl<ProjectContext: Display + Send + Sync> DeployService for DeployServiceLive<
             "Operation completed successfully".to_string(),
         ))
     }
+
+    async fn try_component(
+        &self,
+        component_file: PathBufOrStdin,
+        ifs: PathBuf,
+        function: String,
+        parameters: Option<Value>,
+        wave: Vec<String>,
+        project: Option<Self::ProjectContext>,
+        format: Format,
+    ) -> Result<GolemResult, GolemError> {
+        let component_name = ComponentName(format!("try-{}", Uuid::new_v4()));
+        info!("Adding temporary component {}", component_name.0);
+        let component = self
+            .component_service
+            .add_metadata(
+                component_name,
+                component_file,
+                ComponentType::Ephemeral,
+                &project,
+                ifs,
+                None,
+            )
+            .await?;
+        let component_urn = ComponentUrn {
+            id: ComponentId(component.versioned_component_id.component_id),
+        };
+
+        let worker_uri = WorkerUri::URN(WorkerUrn {
+            id: TargetWorkerId {
+                component_id: component_urn.id.clone(),
+                worker_name: None,
+            },
+        });
+        let result = self
+            .worker_service
+            .invoke_and_await(
+                format, worker_uri, None, function, parameters, wave, project,
+            )
+            .await;
+
+        // The result of the invocation is still reported if the cleanup fails
+        info!("Deleting temporary component {}", component_urn);
+        if let Err(GolemError(err)) = self.component_service.delete(&component_urn).await {
+            error!(
+                "Failed to delete temporary component {}: {err}",
+                component_urn
+            );
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::command::worker::{WorkerConnectOptions, WorkerUriOrPick};
+    use crate::model::component::Component;
+    use crate::model::{
+        ComponentName, ComponentUriOrPick, Format, GolemError, GolemResult, IdempotencyKey,
+        PathBufOrStdin, WorkerMetadata, WorkerName, WorkerUpdateMode,
+    };
+    use crate::progress::ProgressMode;
+    use crate::service::component::ComponentService;
+    use crate::service::deploy::{DeployService, DeployServiceLive};
+    use crate::service::worker::WorkerService;
+    use async_trait::async_trait;
+    use golem_client::model::{ComponentMetadata, ComponentType, ScanCursor, VersionedComponentId};
+    use golem_common::uri::oss::uri::{ComponentUri, WorkerUri};
+    use golem_common::uri::oss::urn::{ComponentUrn, WorkerUrn};
+    use serde_json::Value;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+
+    #[derive(Default)]
+    struct TestComponentService {
+        component_id: Uuid,
+        delete_fails: bool,
+        added: Mutex<Vec<(String, ComponentType)>>,
+        deleted: Mutex<Vec<ComponentUrn>>,
+    }
+
+    #[async_trait]
+    impl ComponentService for TestComponentService {
+        type ProjectContext = String;
+
+        async fn add(
+            &self,
+            _component_name: ComponentName,
+            _component_file: PathBufOrStdin,
+            _component_type: ComponentType,
+            _project: Option<Self::ProjectContext>,
+            _non_interactive: bool,
+            _format: Format,
+            _ifs: PathBuf,
+            _function_aliases: Option<HashMap<String, String>>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn add_metadata(
+            &self,
+            component_name: ComponentName,
+            _component_file: PathBufOrStdin,
+            component_type: ComponentType,
+            _project: &Option<Self::ProjectContext>,
+            _ifs: PathBuf,
+            _function_aliases: Option<HashMap<String, String>>,
+        ) -> Result<Component, GolemError> {
+            self.added
+                .lock()
+                .unwrap()
+                .push((component_name.0.clone(), component_type.clone()));
+
+            Ok(Component {
+                versioned_component_id: VersionedComponentId {
+                    component_id: self.component_id,
+                    version: 0,
+                },
+                component_name: component_name.0,
+                component_size: 100,
+                component_type,
+                metadata: ComponentMetadata {
+                    exports: vec![],
+                    producers: vec![],
+                    memories: vec![],
+                    files: None,
+                    function_aliases: None,
+                    capabilities: None,
+                },
+                project_id: None,
+                created_at: None,
+            })
+        }
+
+        async fn update(
+            &self,
+            _component_uri: ComponentUri,
+            _component_file: PathBufOrStdin,
+            _component_type: Option<ComponentType>,
+            _project: Option<Self::ProjectContext>,
+            _non_interactive: bool,
+            _format: Format,
+            _ifs: PathBuf,
+            _function_aliases: Option<HashMap<String, String>>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn list(
+            &self,
+            _component_name: Option<ComponentName>,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn list_metadata(
+            &self,
+            _project: &Option<Self::ProjectContext>,
+        ) -> Result<Vec<Component>, GolemError> {
+            unimplemented!()
+        }
+
+        async fn get(
+            &self,
+            _component_uri: ComponentUri,
+            _version: Option<u64>,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn function_schema(
+            &self,
+            _component_uri: ComponentUri,
+            _version: Option<u64>,
+            _function: &str,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn resolve_uri(
+            &self,
+            _uri: ComponentUri,
+            _project: &Option<Self::ProjectContext>,
+        ) -> Result<ComponentUrn, GolemError> {
+            unimplemented!()
+        }
+
+        async fn component_uri_or_pick(
+            &self,
+            _component: ComponentUriOrPick,
+            _project: &Option<Self::ProjectContext>,
+        ) -> Result<ComponentUri, GolemError> {
+            unimplemented!()
+        }
+
+        async fn get_metadata(
+            &self,
+            _component_urn: &ComponentUrn,
+            _version: u64,
+        ) -> Result<Component, GolemError> {
+            unimplemented!()
+        }
+
+        async fn get_latest_metadata(
+            &self,
+            _component_urn: &ComponentUrn,
+        ) -> Result<Component, GolemError> {
+            unimplemented!()
+        }
+
+        async fn download(
+            &self,
+            _component_uri: ComponentUri,
+            _version: Option<u64>,
+            _output: Option<PathBuf>,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn delete(&self, component_urn: &ComponentUrn) -> Result<(), GolemError> {
+            self.deleted.lock().unwrap().push(component_urn.clone());
+
+            if self.delete_fails {
+                Err(GolemError("Component service is not available".to_string()))
+            } else {
+                Ok(())
+            }
+        }
+
+        fn offline(&self) -> bool {
+            false
+        }
+    }
+
+    #[derive(Default)]
+    struct TestWorkerService {
+        invocation_fails: bool,
+        invoked: Mutex<Vec<(WorkerUri, String)>>,
+    }
+
+    #[async_trait]
+    impl WorkerService for TestWorkerService {
+        type ProjectContext = String;
+
+        async fn add(
+            &self,
+            _component_uri: ComponentUri,
+            _worker_name: WorkerName,
+            _env: Vec<(String, String)>,
+            _args: Vec<String>,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn add_by_urn(
+            &self,
+            _component_urn: ComponentUrn,
+            _worker_name: WorkerName,
+            _env: Vec<(String, String)>,
+            _args: Vec<String>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn resolve_uri(
+            &self,
+            _worker_uri: WorkerUri,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<WorkerUrn, GolemError> {
+            unimplemented!()
+        }
+
+        async fn worker_uri_or_pick(
+            &self,
+            _worker: WorkerUriOrPick,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<WorkerUri, GolemError> {
+            unimplemented!()
+        }
+
+        async fn invoke_and_await(
+            &self,
+            _format: Format,
+            worker_uri: WorkerUri,
+            _idempotency_key: Option<IdempotencyKey>,
+            function: String,
+            _parameters: Option<Value>,
+            _wave: Vec<String>,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            self.invoked.lock().unwrap().push((worker_uri, function));
+
+            if self.invocation_fails {
+                Err(GolemError("Function trapped".to_string()))
+            } else {
+                Ok(GolemResult::Str("42".to_string()))
+            }
+        }
+
+        async fn invoke(
+            &self,
+            _worker_uri: WorkerUri,
+            _idempotency_key: Option<IdempotencyKey>,
+            _function: String,
+            _parameters: Option<Value>,
+            _wave: Vec<String>,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn invoke_batch(
+            &self,
+            _input: PathBuf,
+            _output: PathBuf,
+            _concurrency: usize,
+            _retries: u32,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn connect(
+            &self,
+            _worker_uri: WorkerUri,
+            _project: Option<Self::ProjectContext>,
+            _connect_options: WorkerConnectOptions,
+            _format: Format,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn connect_many(
+            &self,
+            _component_uri: ComponentUri,
+            _filter: Vec<String>,
+            _project: Option<Self::ProjectContext>,
+            _connect_options: WorkerConnectOptions,
+            _format: Format,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn interrupt(
+            &self,
+            _worker_uri: WorkerUri,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn resume(
+            &self,
+            _worker_uri: WorkerUri,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn simulated_crash(
+            &self,
+            _worker_uri: WorkerUri,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn delete(
+            &self,
+            _worker_uri: WorkerUri,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn delete_by_urn(&self, _worker_urn: WorkerUrn) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn get(
+            &self,
+            _worker_uri: WorkerUri,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn get_function(
+            &self,
+            _worker_uri: WorkerUri,
+            _function: &str,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn list(
+            &self,
+            _component_uri: ComponentUri,
+            _filter: Option<Vec<String>>,
+            _count: Option<u64>,
+            _cursor: Option<ScanCursor>,
+            _precise: Option<bool>,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn search(
+            &self,
+            _component_uri: ComponentUri,
+            _prefix: Option<String>,
+            _pattern: Option<String>,
+            _count: Option<u64>,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn update(
+            &self,
+            _worker_uri: WorkerUri,
+            _target_version: u64,
+            _mode: WorkerUpdateMode,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn update_by_urn(
+            &self,
+            _worker_urn: WorkerUrn,
+            _target_version: u64,
+            _mode: WorkerUpdateMode,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn update_many(
+            &self,
+            _component_uri: ComponentUri,
+            _filter: Option<Vec<String>>,
+            _target_version: u64,
+            _mode: WorkerUpdateMode,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn update_many_by_urn(
+            &self,
+            _component_urn: ComponentUrn,
+            _filter: Option<Vec<String>>,
+            _target_version: u64,
+            _mode: WorkerUpdateMode,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn list_worker_metadata(
+            &self,
+            _component_urn: &ComponentUrn,
+            _filter: Option<Vec<String>>,
+            _precise: Option<bool>,
+        ) -> Result<Vec<WorkerMetadata>, GolemError> {
+            unimplemented!()
+        }
+
+        async fn get_oplog(
+            &self,
+            _worker_uri: WorkerUri,
+            _from: u64,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn get_timeline(
+            &self,
+            _worker_uri: WorkerUri,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn get_maintenance(
+            &self,
+            _component_uri: ComponentUri,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn start_maintenance(
+            &self,
+            _component_uri: ComponentUri,
+            _message: Option<String>,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+
+        async fn end_maintenance(
+            &self,
+            _component_uri: ComponentUri,
+            _project: Option<Self::ProjectContext>,
+        ) -> Result<GolemResult, GolemError> {
+            unimplemented!()
+        }
+    }
+
+    async fn try_component(
+        component_service: Arc<TestComponentService>,
+        worker_service: Arc<TestWorkerService>,
+    ) -> Result<GolemResult, GolemError> {
+        let deploy_service = DeployServiceLive {
+            component_service,
+            worker_service,
+            fleet_update_state_dir: None,
+            progress: ProgressMode::Hidden,
+        };
+
+        deploy_service
+            .try_component(
+                PathBufOrStdin::Path(PathBuf::from("component.wasm")),
+                PathBuf::from("/"),
+                "golem:it/api.{add}".to_string(),
+                None,
+                vec!["1".to_string()],
+                None,
+                Format::Text,
+            )
+            .await
+    }
+
+    #[test]
+    async fn try_invokes_an_ephemeral_worker_of_a_temporary_component() {
+        let component_id = Uuid::new_v4();
+        let component_service = Arc::new(TestComponentService {
+            component_id,
+            ..Default::default()
+        });
+        let worker_service = Arc::new(TestWorkerService::default());
+
+        let result = try_component(component_service.clone(), worker_service.clone()).await;
+
+        assert!(matches!(result, Ok(GolemResult::Str(value)) if value == "42"));
+
+        let added = component_service.added.lock().unwrap().clone();
+        assert_eq!(added.len(), 1);
+        assert!(added[0].0.starts_with("try-"));
+        assert_eq!(added[0].1, ComponentType::Ephemeral);
+
+        let invoked = worker_service.invoked.lock().unwrap().clone();
+        assert_eq!(invoked.len(), 1);
+        match &invoked[0] {
+            (WorkerUri::URN(WorkerUrn { id }), function) => {
+                assert_eq!(id.component_id.0, component_id);
+                assert_eq!(id.worker_name, None);
+                assert_eq!(function, "golem:it/api.{add}");
+            }
+            other => panic!("Unexpected worker URI {:?}", other),
+        }
+
+        let deleted = component_service.deleted.lock().unwrap().clone();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].id.0, component_id);
+    }
+
+    #[test]
+    async fn try_deletes_the_temporary_component_when_the_invocation_fails() {
+        let component_service = Arc::new(TestComponentService::default());
+        let worker_service = Arc::new(TestWorkerService {
+            invocation_fails: true,
+            ..Default::default()
+        });
+
+        let result = try_component(component_service.clone(), worker_service).await;
+
+        assert!(matches!(result, Err(GolemError(err)) if err == "Function trapped"));
+        assert_eq!(component_service.deleted.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    async fn try_reports_the_result_when_the_cleanup_fails() {
+        let component_service = Arc::new(TestComponentService {
+            delete_fails: true,
+            ..Default::default()
+        });
+        let worker_service = Arc::new(TestWorkerService::default());
+
+        let result = try_component(component_service.clone(), worker_service).await;
+
+        assert!(matches!(result, Ok(GolemResult::Str(value)) if value == "42"));
+        assert_eq!(component_service.deleted.lock().unwrap().len(), 1);
+    }
 }
@@ -44,8 +44,9 @@ impl ProjectResolverOss {
 impl ProjectResolver<OssContext, OssContext> for ProjectResolverOss {
     async fn resolve_id_or_default(
         &self,
-        _project_ref: OssContext,
+        project_ref: OssContext,
     ) -> Result<OssContext, GolemError> {
-        Ok(OssContext::EMPTY)
+        // Projects are referenced by id, which is checked by the component service
+        Ok(project_ref)
     }
 }
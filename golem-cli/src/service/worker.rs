@@ -13,31 +13,49 @@
 // limitations under the License.
 
 use crate::clients::worker::WorkerClient;
-use crate::command::worker::WorkerConnectOptions;
+use crate::model::batch::{
+    is_transient, parse_batch, BatchInvocation, BatchInvocationResult, InvokeBatchResult,
+};
+use crate::command::worker::{WorkerConnectOptions, WorkerUriOrPick};
 use crate::model::component::{
-    format_function_name, function_params_types, show_exported_function, Component,
+    format_function_name, function_params, function_params_types, render_type,
+    show_exported_function, Component,
 };
 use crate::model::deploy::TryUpdateAllWorkersResult;
 use crate::model::invoke_result_view::InvokeResultView;
-use crate::model::text::worker::{WorkerAddView, WorkerGetView};
+use crate::model::text::worker::{
+    ComponentMaintenanceView, WorkerAddView, WorkerGetView, WorkerSearchView,
+};
+use crate::model::wave::{show_wave_error, type_wave_compatible};
 use crate::model::{
-    Format, GolemError, GolemResult, IdempotencyKey, WorkerMetadata, WorkerName, WorkerUpdateMode,
-    WorkersMetadataResponseView,
+    ComponentUriOrPick, Format, GolemError, GolemResult, IdempotencyKey, WorkerMetadata,
+    WorkerName, WorkerUpdateMode, WorkersMetadataResponseView,
 };
+use crate::picker::{pick, WorkerOption};
+use crate::progress::{ProgressMode, ProgressOperation, ProgressUnit};
 use crate::service::component::ComponentService;
 use async_trait::async_trait;
-use golem_client::model::{AnalysedType, InvokeParameters, InvokeResult, ScanCursor, WorkerFilter};
+use futures_util::{stream, StreamExt};
+use golem_client::model::{InvokeParameters, InvokeResult, ScanCursor, WorkerFilter};
+use golem_common::model::timeline::WorkerTimeline;
 use golem_common::model::{StringFilterComparator, TargetWorkerId, WorkerNameFilter};
+use golem_common::type_check::TypeCheckIn;
 use golem_common::uri::oss::uri::{ComponentUri, WorkerUri};
 use golem_common::uri::oss::url::{ComponentUrl, WorkerUrl};
 use golem_common::uri::oss::urn::{ComponentUrn, WorkerUrn};
-use golem_wasm_ast::analysis::{AnalysedExport, AnalysedFunction, AnalysedInstance};
-use golem_wasm_rpc::json::TypeAnnotatedValueJsonExtensions;
+use golem_wasm_ast::analysis::{
+    AnalysedExport, AnalysedFunction, AnalysedFunctionParameter, AnalysedInstance,
+};
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use golem_wasm_rpc::type_annotated_value_from_str;
 use itertools::Itertools;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::task::JoinHandle;
 use tracing::{error, info};
 use uuid::Uuid;
@@ -74,6 +92,13 @@ pub trait WorkerService {
         project: Option<Self::ProjectContext>,
     ) -> Result<WorkerUrn, GolemError>;
 
+    /// Gets the worker given on the command line, or lets the user pick one if it was not given
+    async fn worker_uri_or_pick(
+        &self,
+        worker: WorkerUriOrPick,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<WorkerUri, GolemError>;
+
     async fn invoke_and_await(
         &self,
         format: Format,
@@ -95,6 +120,15 @@ pub trait WorkerService {
         project: Option<Self::ProjectContext>,
     ) -> Result<GolemResult, GolemError>;
 
+    async fn invoke_batch(
+        &self,
+        input: PathBuf,
+        output: PathBuf,
+        concurrency: usize,
+        retries: u32,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError>;
+
     async fn connect(
         &self,
         worker_uri: WorkerUri,
@@ -103,6 +137,15 @@ pub trait WorkerService {
         format: Format,
     ) -> Result<GolemResult, GolemError>;
 
+    async fn connect_many(
+        &self,
+        component_uri: ComponentUri,
+        filter: Vec<String>,
+        project: Option<Self::ProjectContext>,
+        connect_options: WorkerConnectOptions,
+        format: Format,
+    ) -> Result<GolemResult, GolemError>;
+
     async fn interrupt(
         &self,
         worker_uri: WorkerUri,
@@ -152,6 +195,15 @@ pub trait WorkerService {
         project: Option<Self::ProjectContext>,
     ) -> Result<GolemResult, GolemError>;
 
+    async fn search(
+        &self,
+        component_uri: ComponentUri,
+        prefix: Option<String>,
+        pattern: Option<String>,
+        count: Option<u64>,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError>;
+
     async fn update(
         &self,
         worker_uri: WorkerUri,
@@ -197,11 +249,40 @@ pub trait WorkerService {
         from: u64,
         project: Option<Self::ProjectContext>,
     ) -> Result<GolemResult, GolemError>;
+
+    async fn get_timeline(
+        &self,
+        worker_uri: WorkerUri,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError>;
+
+    async fn get_maintenance(
+        &self,
+        component_uri: ComponentUri,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError>;
+
+    async fn start_maintenance(
+        &self,
+        component_uri: ComponentUri,
+        message: Option<String>,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError>;
+
+    async fn end_maintenance(
+        &self,
+        component_uri: ComponentUri,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError>;
 }
 
 pub struct WorkerServiceLive<ProjectContext: Send + Sync> {
     pub client: Arc<dyn WorkerClient + Send + Sync>,
     pub components: Arc<dyn ComponentService<ProjectContext = ProjectContext> + Send + Sync>,
+    /// Whether missing workers can be picked interactively
+    pub interactive: bool,
+    /// How the progress of updates and batch invocations is reported
+    pub progress: ProgressMode,
 }
 
 async fn resolve_worker_component_version<ProjectContext: Send + Sync>(
@@ -214,6 +295,15 @@ async fn resolve_worker_component_version<ProjectContext: Send + Sync>(
         worker_name,
     } = worker_urn.id;
 
+    if components.offline() {
+        // The workers can't be looked up without the worker service, so the latest cached
+        // version of the component is assumed
+        return components
+            .get_latest_metadata(&ComponentUrn { id: component_id })
+            .await
+            .map(Some);
+    }
+
     if let Some(worker_name) = worker_name {
         let component_urn = ComponentUrn { id: component_id };
 
@@ -248,12 +338,26 @@ async fn resolve_worker_component_version<ProjectContext: Send + Sync>(
     }
 }
 
-fn parse_parameter(wave: &str, typ: &AnalysedType) -> Result<TypeAnnotatedValue, GolemError> {
+fn parse_parameter(
+    wave: &str,
+    param: &AnalysedFunctionParameter,
+) -> Result<TypeAnnotatedValue, GolemError> {
+    if !type_wave_compatible(&param.typ) {
+        return Err(GolemError(format!(
+            "Parameter {} of type {} cannot be given in WAVE format, use --parameters instead",
+            param.name,
+            render_type(&param.typ)
+        )));
+    }
+
     // Avoid converting from typ to AnalysedType
-    match type_annotated_value_from_str(typ, wave) {
+    match type_annotated_value_from_str(&param.typ, wave) {
         Ok(value) => Ok(value),
         Err(err) => Err(GolemError(format!(
-            "Failed to parse wave parameter {wave}: {err:?}"
+            "Failed to parse parameter {} of type {} in WAVE format: {}",
+            param.name,
+            render_type(&param.typ),
+            show_wave_error(wave, &err.to_string())
         ))),
     }
 }
@@ -325,7 +429,7 @@ async fn resolve_parameters<ProjectContext: Send + Sync>(
 
             let mut type_annotated_values = Vec::new();
             for (json_param, typ) in parameters.iter().zip(types) {
-                match TypeAnnotatedValue::parse_with_type(json_param, typ) {
+                match json_param.type_check_in(typ) {
                     Ok(tav) => type_annotated_values.push(tav),
                     Err(err) => {
                         return Err(GolemError(format!(
@@ -341,20 +445,24 @@ async fn resolve_parameters<ProjectContext: Send + Sync>(
     } else {
         // No JSON parameters, we use the WAVE ones
         let component = get_component_metadata_for_worker(client, components, worker_urn).await?;
-        let types = function_params_types(&component, function)?;
+        let params = function_params(&component, function)?;
 
-        if types.len() != wave.len() {
+        if params.len() != wave.len() {
             return Err(GolemError(format!(
-                "Unexpected number of parameters: got {}, expected {}",
+                "Unexpected number of parameters: got {}, expected {} ({})",
                 wave.len(),
-                types.len()
+                params.len(),
+                params
+                    .iter()
+                    .map(|p| format!("{}: {}", p.name, render_type(&p.typ)))
+                    .join(", ")
             )));
         }
 
         let type_annotated_values = wave
             .iter()
-            .zip(types)
-            .map(|(wave, typ)| parse_parameter(wave, typ))
+            .zip(params)
+            .map(|(wave, param)| parse_parameter(wave, param))
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok((type_annotated_values, Some(component)))
@@ -403,6 +511,86 @@ enum AsyncComponentRequest {
     Async(JoinHandle<Result<Option<Component>, GolemError>>),
 }
 
+impl<ProjectContext: Send + Sync + 'static> WorkerServiceLive<ProjectContext> {
+    async fn invoke_batch_entry(
+        &self,
+        line: usize,
+        invocation: BatchInvocation,
+        worker_urn: Result<WorkerUrn, GolemError>,
+        retries: u32,
+    ) -> BatchInvocationResult {
+        let idempotency_key = invocation
+            .idempotency_key
+            .clone()
+            .unwrap_or_else(IdempotencyKey::fresh);
+        let mut attempts = 0;
+
+        let parameters = match worker_urn {
+            Ok(worker_urn) => resolve_parameters(
+                self.client.as_ref(),
+                self.components.as_ref(),
+                &worker_urn,
+                Some(Value::Array(invocation.params.clone())),
+                vec![],
+                &invocation.function,
+            )
+            .await
+            .map(|(parameters, _)| (worker_urn, parameters)),
+            Err(err) => Err(err),
+        };
+
+        let outcome = match parameters {
+            Ok((worker_urn, parameters)) => loop {
+                attempts += 1;
+                let result = self
+                    .client
+                    .invoke_and_await(
+                        worker_urn.clone(),
+                        invocation.function.clone(),
+                        InvokeParameters {
+                            params: parameters.clone(),
+                            blob_params: None,
+                        },
+                        Some(idempotency_key.clone()),
+                    )
+                    .await;
+
+                match result {
+                    Err(err) if attempts <= retries && is_transient(&err) => {
+                        info!(
+                            "Invocation on line {line} failed with {}, retrying (attempt {attempts})",
+                            GolemError::from(err)
+                        );
+                        let delay = 200 * 2u64.pow((attempts - 1).min(5));
+                        tokio::time::sleep(Duration::from_millis(delay)).await;
+                    }
+                    Ok(result) => {
+                        break serde_json::to_value(&result.result)
+                            .map_err(|err| GolemError(err.to_string()))
+                    }
+                    Err(err) => break Err(GolemError::from(err)),
+                }
+            },
+            Err(err) => Err(err),
+        };
+
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+
+        BatchInvocationResult {
+            line,
+            worker: invocation.worker.to_string(),
+            function: invocation.function,
+            idempotency_key: Some(idempotency_key),
+            attempts,
+            result,
+            error,
+        }
+    }
+}
+
 #[async_trait]
 impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<ProjectContext> {
     type ProjectContext = ProjectContext;
@@ -462,6 +650,50 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
         }
     }
 
+    async fn worker_uri_or_pick(
+        &self,
+        worker: WorkerUriOrPick,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<WorkerUri, GolemError> {
+        let component = match worker {
+            WorkerUriOrPick::Uri(uri) => return Ok(uri),
+            WorkerUriOrPick::Pick { component } => component,
+        };
+
+        if !self.interactive {
+            return Err(GolemError(
+                "Missing worker, specify it with --worker, or with --component or --component-name and --worker-name".to_string(),
+            ));
+        }
+
+        let component_uri = match component {
+            Some(component_uri) => component_uri,
+            None => {
+                self.components
+                    .component_uri_or_pick(ComponentUriOrPick::Pick, &project)
+                    .await?
+            }
+        };
+        let component_urn = self.components.resolve_uri(component_uri, &project).await?;
+
+        let workers = self
+            .list_worker_metadata(&component_urn, None, None)
+            .await?;
+        let options = workers
+            .into_iter()
+            .map(WorkerOption)
+            .sorted_by(|a, b| a.0.worker_id.worker_name.cmp(&b.0.worker_id.worker_name))
+            .collect();
+        let worker = pick("worker", options)?.0;
+
+        Ok(WorkerUri::URN(WorkerUrn {
+            id: TargetWorkerId {
+                component_id: component_urn.id,
+                worker_name: Some(worker.worker_id.worker_name),
+            },
+        }))
+    }
+
     async fn invoke_and_await(
         &self,
         format: Format,
@@ -508,7 +740,10 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
             .invoke_and_await(
                 worker_urn.clone(),
                 function.clone(),
-                InvokeParameters { params: parameters },
+                InvokeParameters {
+                    params: parameters,
+                    blob_params: None,
+                },
                 idempotency_key,
             )
             .await?;
@@ -557,7 +792,10 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
             .invoke(
                 worker_urn,
                 function,
-                InvokeParameters { params: parameters },
+                InvokeParameters {
+                    params: parameters,
+                    blob_params: None,
+                },
                 idempotency_key,
             )
             .await?;
@@ -565,6 +803,100 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
         Ok(GolemResult::Str("Invoked".to_string()))
     }
 
+    async fn invoke_batch(
+        &self,
+        input: PathBuf,
+        output: PathBuf,
+        concurrency: usize,
+        retries: u32,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        let content = std::fs::read_to_string(&input)
+            .map_err(|err| GolemError(format!("Failed to read {}: {err}", input.display())))?;
+        let invocations = parse_batch(&content)?;
+
+        let mut writer =
+            BufWriter::new(File::create(&output).map_err(|err| {
+                GolemError(format!("Failed to create {}: {err}", output.display()))
+            })?);
+
+        // Workers referenced by URL are resolved once per component. A component which
+        // can't be resolved only fails the invocations targeting it.
+        let mut component_urns: HashMap<String, Result<ComponentUrn, GolemError>> = HashMap::new();
+        let mut resolved = Vec::new();
+        for (line, invocation) in invocations {
+            let worker_urn = match &invocation.worker {
+                WorkerUri::URN(urn) => Ok(urn.clone()),
+                WorkerUri::URL(WorkerUrl {
+                    component_name,
+                    worker_name,
+                }) => {
+                    if !component_urns.contains_key(component_name) {
+                        let component_uri = ComponentUri::URL(ComponentUrl {
+                            name: component_name.clone(),
+                        });
+                        let component_urn =
+                            self.components.resolve_uri(component_uri, &project).await;
+                        component_urns.insert(component_name.clone(), component_urn);
+                    }
+
+                    component_urns[component_name]
+                        .clone()
+                        .map(|component_urn| WorkerUrn {
+                            id: TargetWorkerId {
+                                component_id: component_urn.id,
+                                worker_name: worker_name.clone(),
+                            },
+                        })
+                }
+            };
+            resolved.push((line, invocation, worker_urn));
+        }
+
+        let total = resolved.len();
+        let progress = self.progress.start(
+            ProgressOperation::BatchInvoke,
+            ProgressUnit::Invocations,
+            total as u64,
+        );
+        let mut results = stream::iter(resolved)
+            .map(|(line, invocation, worker_urn)| {
+                self.invoke_batch_entry(line, invocation, worker_urn, retries)
+            })
+            .buffer_unordered(concurrency.max(1));
+
+        // Results are written as they complete, so the file is useful even if the batch is aborted
+        let mut succeeded = 0;
+        while let Some(result) = results.next().await {
+            if result.is_success() {
+                succeeded += 1;
+                progress.inc(1);
+            } else {
+                progress.fail();
+                error!(
+                    "Invocation on line {} failed: {}",
+                    result.line,
+                    result.error.as_deref().unwrap_or_default()
+                );
+            }
+
+            let json = serde_json::to_string(&result).map_err(|err| GolemError(err.to_string()))?;
+            writeln!(writer, "{json}")
+                .and_then(|_| writer.flush())
+                .map_err(|err| {
+                    GolemError(format!("Failed to write {}: {err}", output.display()))
+                })?;
+        }
+        progress.finish();
+
+        Ok(GolemResult::Ok(Box::new(InvokeBatchResult {
+            total,
+            succeeded,
+            failed: total - succeeded,
+            output,
+        })))
+    }
+
     async fn connect(
         &self,
         worker_uri: WorkerUri,
@@ -581,6 +913,23 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
         Err(GolemError("Unexpected connection closure".to_string()))
     }
 
+    async fn connect_many(
+        &self,
+        component_uri: ComponentUri,
+        filter: Vec<String>,
+        project: Option<Self::ProjectContext>,
+        connect_options: WorkerConnectOptions,
+        format: Format,
+    ) -> Result<GolemResult, GolemError> {
+        let component_urn = self.components.resolve_uri(component_uri, &project).await?;
+
+        self.client
+            .connect_many_forever(component_urn, filter, connect_options, format)
+            .await?;
+
+        Err(GolemError("Unexpected connection closure".to_string()))
+    }
+
     async fn interrupt(
         &self,
         worker_uri: WorkerUri,
@@ -722,6 +1071,31 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
         }
     }
 
+    async fn search(
+        &self,
+        component_uri: ComponentUri,
+        prefix: Option<String>,
+        pattern: Option<String>,
+        count: Option<u64>,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        let component_urn = self.components.resolve_uri(component_uri, &project).await?;
+
+        let worker_ids = self
+            .client
+            .search(component_urn, prefix, pattern, count)
+            .await?;
+
+        Ok(GolemResult::Ok(Box::new(WorkerSearchView(
+            worker_ids
+                .into_iter()
+                .map(|worker_id| WorkerUrn {
+                    id: worker_id.into_target_worker_id(),
+                })
+                .collect(),
+        ))))
+    }
+
     async fn update(
         &self,
         worker_uri: WorkerUri,
@@ -773,6 +1147,11 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
             .filter(|worker| worker.component_version < target_version)
             .collect::<Vec<_>>();
 
+        let progress = self.progress.start(
+            ProgressOperation::WorkerUpdate,
+            ProgressUnit::Workers,
+            to_update.len() as u64,
+        );
         let mut triggered = Vec::new();
         let mut failed = Vec::new();
         for worker in to_update {
@@ -784,11 +1163,14 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
                 .await;
 
             if result.is_ok() {
+                progress.inc(1);
                 triggered.push(worker_urn);
             } else {
+                progress.fail();
                 failed.push(worker_urn);
             }
         }
+        progress.finish();
 
         Ok(GolemResult::Ok(Box::new(TryUpdateAllWorkersResult {
             triggered,
@@ -840,4 +1222,60 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
         let entries = self.client.get_oplog(worker_urn, from).await?;
         Ok(GolemResult::Ok(Box::new(entries)))
     }
+
+    async fn get_timeline(
+        &self,
+        worker_uri: WorkerUri,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        let worker_urn = self.resolve_uri(worker_uri, project).await?;
+
+        let entries = self.client.get_oplog(worker_urn, 0).await?;
+        let timeline =
+            WorkerTimeline::from_oplog(entries.iter().map(|(idx, entry)| (*idx, entry)));
+        Ok(GolemResult::Ok(Box::new(timeline)))
+    }
+
+    async fn get_maintenance(
+        &self,
+        component_uri: ComponentUri,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        let component_urn = self.components.resolve_uri(component_uri, &project).await?;
+
+        let maintenance = self.client.get_maintenance(component_urn.clone()).await?;
+        Ok(GolemResult::Ok(Box::new(ComponentMaintenanceView {
+            component_urn,
+            maintenance,
+        })))
+    }
+
+    async fn start_maintenance(
+        &self,
+        component_uri: ComponentUri,
+        message: Option<String>,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        let component_urn = self.components.resolve_uri(component_uri, &project).await?;
+
+        let maintenance = self
+            .client
+            .start_maintenance(component_urn.clone(), message)
+            .await?;
+        Ok(GolemResult::Ok(Box::new(ComponentMaintenanceView {
+            component_urn,
+            maintenance,
+        })))
+    }
+
+    async fn end_maintenance(
+        &self,
+        component_uri: ComponentUri,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        let component_urn = self.components.resolve_uri(component_uri, &project).await?;
+
+        self.client.end_maintenance(component_urn).await?;
+        Ok(GolemResult::Str("Maintenance ended".to_string()))
+    }
 }
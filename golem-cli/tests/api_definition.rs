@@ -155,6 +155,7 @@ fn golem_def_with_response(
                 response,
             },
         }],
+        cors: None,
     }
 }
 
@@ -284,6 +285,7 @@ pub fn to_definition(
                 }
             })
             .collect(),
+        cors: request.cors,
         created_at,
     }
 }
@@ -126,6 +126,14 @@ fn make(
             worker_connect((deps, name.to_string(), cli.with_args(short), ref_kind))
         }
     );
+    add_test!(
+        r,
+        format!("worker_connect_filtered{suffix}"),
+        TestType::IntegrationTest,
+        move |deps: &EnvBasedTestDependencies, cli: &CliLive, _tracing: &Tracing| {
+            worker_connect_filtered((deps, name.to_string(), cli.with_args(short), ref_kind))
+        }
+    );
     add_test!(
         r,
         format!("worker_connect_failed{suffix}"),
@@ -765,6 +773,95 @@ fn worker_connect(
     Ok(())
 }
 
+fn worker_connect_filtered(
+    (deps, name, cli, ref_kind): (
+        &(impl TestDependencies + Send + Sync + 'static),
+        String,
+        CliLive,
+        RefKind,
+    ),
+) -> anyhow::Result<()> {
+    let cfg = &cli.config;
+
+    let stdout_service = deps.component_directory().join("write-stdout.wasm");
+    let component: ComponentView = cli.run(&[
+        "component",
+        "add",
+        &cfg.arg('c', "component-name"),
+        &format!("{name} worker_connect_filtered"),
+        stdout_service.to_str().unwrap(),
+    ])?;
+    let worker_names = [
+        format!("{name}_worker_connect_filtered_1"),
+        format!("{name}_worker_connect_filtered_2"),
+    ];
+    for worker_name in &worker_names {
+        let _: WorkerUrn = cli.run(&[
+            "worker",
+            "add",
+            &cfg.arg('w', "worker-name"),
+            worker_name,
+            &component_ref_key(cfg, ref_kind),
+            &component_ref_value(&component, ref_kind),
+        ])?;
+    }
+
+    let mut child = cli.run_stdout(&[
+        "worker",
+        "connect",
+        &component_ref_key(cfg, ref_kind),
+        &component_ref_value(&component, ref_kind),
+        "--filter",
+        "version >= 0",
+    ])?;
+
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or(anyhow!("Can't get golem cli stdout"))?;
+
+    std::thread::spawn(move || {
+        let reader = BufReader::new(stdout);
+        for line in reader.lines() {
+            tx.send(line.unwrap()).unwrap()
+        }
+    });
+
+    for worker_name in &worker_names {
+        let mut cli_args = vec![
+            "worker".to_owned(),
+            "invoke-and-await".to_owned(),
+            cfg.arg('f', "function"),
+            "run".to_owned(),
+            cfg.arg('j', "parameters"),
+            "[]".to_owned(),
+        ];
+        cli_args.append(&mut worker_ref(cfg, ref_kind, &component, worker_name));
+        let _ = cli.run_json(&cli_args)?;
+    }
+
+    let mut connected_workers = Vec::new();
+    for _ in &worker_names {
+        let line = rx.recv_timeout(Duration::from_secs(5))?;
+        let json: serde_json::Value = serde_json::from_str(&line)?;
+
+        assert_eq!(
+            json.get("message").and_then(|message| message.as_str()),
+            Some("Sample text written to the output")
+        );
+        connected_workers.push(json.get("worker").unwrap().as_str().unwrap().to_string());
+    }
+    connected_workers.sort();
+
+    assert_eq!(connected_workers, worker_names);
+
+    child.kill()?;
+
+    Ok(())
+}
+
 fn worker_connect_failed(
     (deps, name, cli, ref_kind): (
         &(impl TestDependencies + Send + Sync + 'static),
@@ -50,6 +50,10 @@ fn generate(yaml_path: PathBuf, out_dir: OsString) {
                 "ComponentMetadata",
                 "golem_common::model::component_metadata::ComponentMetadata",
             ),
+            (
+                "ComponentMaintenance",
+                "golem_common::model::maintenance::ComponentMaintenance",
+            ),
             ("ComponentType", "golem_common::model::ComponentType"),
             (
                 "OplogCursor",
@@ -57,6 +61,10 @@ fn generate(yaml_path: PathBuf, out_dir: OsString) {
             ),
             ("PromiseId", "golem_common::model::PromiseId"),
             ("ShardId", "golem_common::model::ShardId"),
+            (
+                "StartComponentMaintenance",
+                "golem_common::model::maintenance::StartComponentMaintenance",
+            ),
             (
                 "TypeAnnotatedValue",
                 "golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue",
@@ -19,6 +19,7 @@ use figment::Figment;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::time::Duration;
+use tonic::codec::CompressionEncoding;
 use url::Url;
 
 const ENV_VAR_PREFIX: &str = "GOLEM__";
@@ -350,6 +351,9 @@ pub struct RedisConfig {
     pub key_prefix: String,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// Finds the master through Redis Sentinel instead of connecting to `host` and `port`,
+    /// following it when the sentinels fail over to another server
+    pub sentinel: Option<RedisSentinelConfig>,
 }
 
 impl RedisConfig {
@@ -360,6 +364,31 @@ impl RedisConfig {
         ))
         .expect("Failed to parse Redis URL")
     }
+
+    /// Describes where Redis is reached, for logging
+    pub fn endpoint(&self) -> String {
+        match &self.sentinel {
+            Some(sentinel) => format!(
+                "master {} of sentinels {}",
+                sentinel.master_name,
+                sentinel.hosts.join(", ")
+            ),
+            None => self.url().to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RedisSentinelConfig {
+    /// Name of the master monitored by the sentinels
+    pub master_name: String,
+    /// Sentinel endpoints in `host:port` form, with IPv6 addresses in brackets as in `[::1]:26379`.
+    /// The port defaults to 26379.
+    pub hosts: Vec<String>,
+    /// Credentials of the sentinels, the credentials of the master are `username` and `password`
+    /// of the Redis configuration
+    pub username: Option<String>,
+    pub password: Option<String>,
 }
 
 impl Default for RedisConfig {
@@ -374,6 +403,7 @@ impl Default for RedisConfig {
             key_prefix: "".to_string(),
             username: None,
             password: None,
+            sentinel: None,
         }
     }
 }
@@ -417,6 +447,30 @@ impl RetryConfig {
     }
 }
 
+/// Compression of the gRPC messages sent by a service. Every service accepts all the supported
+/// encodings, so the encoding is negotiated per channel and each side can use a different one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GrpcCompression {
+    None,
+    #[default]
+    Gzip,
+    Zstd,
+}
+
+impl GrpcCompression {
+    pub const ACCEPTED_ENCODINGS: [CompressionEncoding; 2] =
+        [CompressionEncoding::Gzip, CompressionEncoding::Zstd];
+
+    /// The encoding used for sending messages, `None` if they are sent uncompressed
+    pub fn encoding(&self) -> Option<CompressionEncoding> {
+        match self {
+            GrpcCompression::None => None,
+            GrpcCompression::Gzip => Some(CompressionEncoding::Gzip),
+            GrpcCompression::Zstd => Some(CompressionEncoding::Zstd),
+        }
+    }
+}
+
 pub fn env_config_provider() -> Env {
     Env::prefixed(ENV_VAR_PREFIX).split(ENV_VAR_NESTED_SEPARATOR)
 }
@@ -161,3 +161,20 @@ pub fn proto_api_definition_draft_string<T: HasProtoApiDefinitionRequest>(
         .and_then(|d| d.api_definition())
         .map(|d| d.draft.to_string())
 }
+
+/// Configures the message compression of a generated tonic client or server: it accepts all the
+/// supported encodings and sends with the one selected by the given `GrpcCompression`.
+#[macro_export]
+macro_rules! with_grpc_compression {
+    ($target:expr, $compression:expr) => {{
+        let target = $crate::config::GrpcCompression::ACCEPTED_ENCODINGS
+            .into_iter()
+            .fold($target, |target, encoding| {
+                target.accept_compressed(encoding)
+            });
+        match $compression.encoding() {
+            Some(encoding) => target.send_compressed(encoding),
+            None => target,
+        }
+    }};
+}
@@ -0,0 +1,41 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `golem:api` host interfaces provided by the worker executor which are not part of the
+//! published golem-wit package. The worker executor generates its host bindings from the same
+//! files, and components import the interfaces after adding the files to the `golem:api` package
+//! in their `wit/deps` directory, for example with `golem-cli golem-wit`.
+
+/// Names and contents of the WIT files of the interfaces
+pub const GOLEM_API_EXTENSIONS: &[(&str, &str)] = &[
+    ("caller.wit", include_str!("../wit/golem-api/caller.wit")),
+    (
+        "child-workers.wit",
+        include_str!("../wit/golem-api/child-workers.wit"),
+    ),
+    ("events.wit", include_str!("../wit/golem-api/events.wit")),
+    (
+        "feature-flags.wit",
+        include_str!("../wit/golem-api/feature-flags.wit"),
+    ),
+    (
+        "keyvalue.wit",
+        include_str!("../wit/golem-api/keyvalue.wit"),
+    ),
+    ("metrics.wit", include_str!("../wit/golem-api/metrics.wit")),
+    (
+        "scheduling.wit",
+        include_str!("../wit/golem-api/scheduling.wit"),
+    ),
+];
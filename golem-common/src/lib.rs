@@ -21,14 +21,17 @@ pub mod config;
 
 pub mod golem_version;
 pub mod grpc;
+pub mod guest_wit;
 pub mod metrics;
 pub mod model;
 pub mod newtype;
 pub mod redis;
+pub mod request_capture;
 pub mod retriable_error;
 pub mod retries;
 pub mod serialization;
 pub mod tracing;
+pub mod type_check;
 pub mod uri;
 
 #[cfg(test)]
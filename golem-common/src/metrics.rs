@@ -124,6 +124,13 @@ pub mod redis {
             &["svc", "api", "cmd"]
         )
         .unwrap();
+        static ref REDIS_RECONNECT_TOTAL: Counter =
+            register_counter!("redis_reconnect_total", "Number of reconnections to Redis").unwrap();
+        static ref REDIS_FAILOVER_TOTAL: Counter = register_counter!(
+            "redis_failover_total",
+            "Number of Redis failovers observed, reconnecting to another server than before"
+        )
+        .unwrap();
         static ref REDIS_SERIALIZED_SIZE_BYTES: HistogramVec = register_histogram_vec!(
             "redis_serialized_size_bytes",
             "Size of serialized Redis entities",
@@ -161,6 +168,13 @@ pub mod redis {
             .inc();
     }
 
+    pub fn record_redis_reconnect(failover: bool) {
+        REDIS_RECONNECT_TOTAL.inc();
+        if failover {
+            REDIS_FAILOVER_TOTAL.inc();
+        }
+    }
+
     pub fn record_redis_serialized_size(
         svc_name: &'static str,
         entity_name: &'static str,
@@ -487,3 +501,199 @@ pub mod db {
             .observe(size as f64);
     }
 }
+
+pub mod grpc {
+    use std::task::{Context, Poll};
+
+    use bytes::Bytes;
+    use futures::future::BoxFuture;
+    use futures::{FutureExt, TryStreamExt};
+    use http_02::{HeaderMap, Request, Response};
+    use http_body::combinators::UnsyncBoxBody;
+    use http_body::Body as HttpBody;
+    use lazy_static::lazy_static;
+    use prometheus::*;
+    use tonic::transport::Body;
+    use tower::{Layer, Service};
+
+    const GRPC_MESSAGE_HEADER_SIZE: usize = 5;
+
+    lazy_static! {
+        static ref GRPC_MESSAGE_BYTES_TOTAL: IntCounterVec = register_int_counter_vec!(
+            "grpc_message_bytes_total",
+            "Size of the gRPC messages received and sent by the servers as transferred, by the encoding they were compressed with ('identity' if they were sent uncompressed)",
+            &["service", "direction", "encoding"]
+        )
+        .unwrap();
+    }
+
+    fn record_grpc_message_bytes(service: &str, direction: &str, encoding: &str, size: usize) {
+        GRPC_MESSAGE_BYTES_TOTAL
+            .with_label_values(&[service, direction, encoding])
+            .inc_by(size as u64);
+    }
+
+    /// Follows the length-prefixed message framing of a gRPC body split into arbitrary chunks,
+    /// recording the size of each message by whether it was compressed
+    struct MessageBytesCounter {
+        service: String,
+        direction: &'static str,
+        encoding: String,
+        header: [u8; GRPC_MESSAGE_HEADER_SIZE],
+        header_len: usize,
+        compressed: bool,
+        remaining: usize,
+    }
+
+    impl MessageBytesCounter {
+        fn new(service: String, direction: &'static str, headers: &HeaderMap) -> Self {
+            let encoding = headers
+                .get("grpc-encoding")
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or("identity")
+                .to_string();
+            Self {
+                service,
+                direction,
+                encoding,
+                header: [0; GRPC_MESSAGE_HEADER_SIZE],
+                header_len: 0,
+                compressed: false,
+                remaining: 0,
+            }
+        }
+
+        fn observe(&mut self, mut chunk: &[u8]) {
+            while !chunk.is_empty() {
+                if self.remaining > 0 {
+                    let size = self.remaining.min(chunk.len());
+                    let encoding = if self.compressed {
+                        self.encoding.as_str()
+                    } else {
+                        "identity"
+                    };
+                    record_grpc_message_bytes(&self.service, self.direction, encoding, size);
+                    self.remaining -= size;
+                    chunk = &chunk[size..];
+                } else {
+                    let size = (GRPC_MESSAGE_HEADER_SIZE - self.header_len).min(chunk.len());
+                    self.header[self.header_len..self.header_len + size]
+                        .copy_from_slice(&chunk[..size]);
+                    self.header_len += size;
+                    chunk = &chunk[size..];
+                    if self.header_len == GRPC_MESSAGE_HEADER_SIZE {
+                        self.compressed = self.header[0] == 1;
+                        self.remaining = u32::from_be_bytes([
+                            self.header[1],
+                            self.header[2],
+                            self.header[3],
+                            self.header[4],
+                        ]) as usize;
+                        self.header_len = 0;
+                    }
+                }
+            }
+        }
+    }
+
+    /// The fully qualified name of the gRPC service from a request path such as
+    /// `/golem.workerexecutor.v1.WorkerExecutor/InvokeWorker`
+    fn grpc_service_name(path: &str) -> String {
+        path.trim_start_matches('/')
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// Layer for the tonic servers recording the compressed and uncompressed bytes of the
+    /// received and sent gRPC messages
+    #[derive(Clone, Default)]
+    pub struct GrpcMessageMetricsLayer;
+
+    impl<S> Layer<S> for GrpcMessageMetricsLayer {
+        type Service = GrpcMessageMetrics<S>;
+
+        fn layer(&self, inner: S) -> Self::Service {
+            GrpcMessageMetrics { inner }
+        }
+    }
+
+    #[derive(Clone)]
+    pub struct GrpcMessageMetrics<S> {
+        inner: S,
+    }
+
+    impl<S, ResBody> Service<Request<Body>> for GrpcMessageMetrics<S>
+    where
+        S: Service<Request<Body>, Response = Response<ResBody>>,
+        S::Future: Send + 'static,
+        ResBody: HttpBody<Data = Bytes> + Send + 'static,
+    {
+        type Response = Response<UnsyncBoxBody<Bytes, ResBody::Error>>;
+        type Error = S::Error;
+        type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, request: Request<Body>) -> Self::Future {
+            let service = grpc_service_name(request.uri().path());
+            let mut received =
+                MessageBytesCounter::new(service.clone(), "received", request.headers());
+            let request = request.map(|body| {
+                Body::wrap_stream(body.inspect_ok(move |chunk| received.observe(chunk)))
+            });
+
+            self.inner
+                .call(request)
+                .map(move |result| {
+                    result.map(|response| {
+                        let mut sent =
+                            MessageBytesCounter::new(service, "sent", response.headers());
+                        response.map(|body| {
+                            body.map_data(move |chunk| {
+                                sent.observe(&chunk);
+                                chunk
+                            })
+                            .boxed_unsync()
+                        })
+                    })
+                })
+                .boxed()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use test_r::test;
+
+        use http_02::HeaderMap;
+
+        use crate::metrics::grpc::{MessageBytesCounter, GRPC_MESSAGE_BYTES_TOTAL};
+
+        fn recorded(encoding: &str) -> u64 {
+            GRPC_MESSAGE_BYTES_TOTAL
+                .with_label_values(&["test.Service", "received", encoding])
+                .get()
+        }
+
+        #[test]
+        fn messages_split_across_chunks_are_counted_by_compression() {
+            let mut headers = HeaderMap::new();
+            headers.insert("grpc-encoding", "zstd".parse().unwrap());
+            let mut counter =
+                MessageBytesCounter::new("test.Service".to_string(), "received", &headers);
+
+            // A compressed message of 4 bytes followed by an uncompressed one of 3 bytes
+            let body = [1, 0, 0, 0, 4, 10, 11, 12, 13, 0, 0, 0, 0, 3, 20, 21, 22];
+            for chunk in [&body[..3], &body[3..7], &body[7..15], &body[15..]] {
+                counter.observe(chunk);
+            }
+
+            assert_eq!(recorded("zstd"), 4);
+            assert_eq!(recorded("identity"), 3);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bincode::{Decode, Encode};
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+const COMPONENT_SECTION: u8 = 4;
+const CANONICAL_SECTION: u8 = 8;
+
+/// Component model features used by a component which not every worker executor supports,
+/// detected when the component gets analysed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Object, Encode, Decode)]
+pub struct ComponentCapabilities {
+    /// The component uses component-model async: exports lifted or imports lowered with the
+    /// `async` option, or the async built-ins like `task.return`, streams and futures. A worker
+    /// of such a component can interleave multiple in-flight invocations at its await points.
+    pub component_model_async: bool,
+}
+
+impl ComponentCapabilities {
+    /// Detects the capabilities by decoding the canonical function definitions of the component
+    /// and of all its nested components
+    pub fn detect(data: &[u8]) -> Result<ComponentCapabilities, String> {
+        Ok(ComponentCapabilities {
+            component_model_async: uses_component_model_async(data)?,
+        })
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::component::ComponentCapabilities>
+    for ComponentCapabilities
+{
+    fn from(value: golem_api_grpc::proto::golem::component::ComponentCapabilities) -> Self {
+        Self {
+            component_model_async: value.component_model_async,
+        }
+    }
+}
+
+impl From<ComponentCapabilities>
+    for golem_api_grpc::proto::golem::component::ComponentCapabilities
+{
+    fn from(value: ComponentCapabilities) -> Self {
+        Self {
+            component_model_async: value.component_model_async,
+        }
+    }
+}
+
+fn uses_component_model_async(component: &[u8]) -> Result<bool, String> {
+    let mut reader = Reader::new(component);
+    let preamble = reader.bytes(8)?;
+    if preamble[0..4] != *b"\0asm" {
+        return Err("Not a WebAssembly binary".to_string());
+    }
+    if preamble[6..8] != [0x01, 0x00] {
+        return Err("Not a WebAssembly component".to_string());
+    }
+    while !reader.is_empty() {
+        let id = reader.u8()?;
+        let size = reader.u32()? as usize;
+        let contents = reader.bytes(size)?;
+        let uses_async = match id {
+            COMPONENT_SECTION => uses_component_model_async(contents)?,
+            CANONICAL_SECTION => canonical_section_uses_async(contents)?,
+            _ => false,
+        };
+        if uses_async {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn canonical_section_uses_async(section: &[u8]) -> Result<bool, String> {
+    let mut reader = Reader::new(section);
+    let count = reader.u32()?;
+    for _ in 0..count {
+        match reader.u8()? {
+            // canon lift
+            0x00 => {
+                reader.u8()?;
+                reader.u32()?;
+                if options_use_async(&mut reader)? {
+                    return Ok(true);
+                }
+                reader.u32()?;
+            }
+            // canon lower
+            0x01 => {
+                reader.u8()?;
+                reader.u32()?;
+                if options_use_async(&mut reader)? {
+                    return Ok(true);
+                }
+            }
+            // resource.new, resource.drop and resource.rep
+            0x02..=0x04 => {
+                reader.u32()?;
+            }
+            // The built-ins of component-model async, from task.cancel to error-context.drop
+            0x05..=0x3f => return Ok(true),
+            // Built-ins of other proposals, like shared-everything threads, are not decoded
+            _ => return Ok(false),
+        }
+    }
+    Ok(false)
+}
+
+fn options_use_async(reader: &mut Reader) -> Result<bool, String> {
+    let count = reader.u32()?;
+    for _ in 0..count {
+        match reader.u8()? {
+            // string encodings
+            0x00..=0x02 => {}
+            // memory, realloc and post-return
+            0x03..=0x05 => {
+                reader.u32()?;
+            }
+            // async, callback and the other options added by component-model async
+            _ => return Ok(true),
+        }
+    }
+    Ok(false)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        let byte = *self
+            .data
+            .get(self.position)
+            .ok_or("Unexpected end of the component")?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    /// Reads an unsigned LEB128 encoded 32 bit integer
+    fn u32(&mut self) -> Result<u32, String> {
+        let mut result: u32 = 0;
+        for shift in (0..35).step_by(7) {
+            let byte = self.u8()?;
+            result |= ((byte & 0x7f) as u32) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+        Err("Invalid LEB128 encoded integer in the component".to_string())
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .position
+            .checked_add(count)
+            .filter(|end| *end <= self.data.len())
+            .ok_or("Unexpected end of the component")?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::{ComponentCapabilities, CANONICAL_SECTION, COMPONENT_SECTION};
+
+    const PREAMBLE: [u8; 8] = [0x00, 0x61, 0x73, 0x6d, 0x0d, 0x00, 0x01, 0x00];
+    const CUSTOM_SECTION: u8 = 0;
+
+    fn leb128(mut value: usize) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(byte);
+                return bytes;
+            }
+            bytes.push(byte | 0x80);
+        }
+    }
+
+    fn section(id: u8, contents: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![id];
+        bytes.extend(leb128(contents.len()));
+        bytes.extend(contents);
+        bytes
+    }
+
+    fn component(sections: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = PREAMBLE.to_vec();
+        for section in sections {
+            bytes.extend(section);
+        }
+        bytes
+    }
+
+    /// A canonical section lifting core function 1 with the given options, as type 2
+    fn lift(options: &[u8]) -> Vec<u8> {
+        let mut contents = vec![0x01, 0x00, 0x00, 0x01];
+        contents.extend(options);
+        contents.push(0x02);
+        section(CANONICAL_SECTION, &contents)
+    }
+
+    fn uses_async(component: &[u8]) -> bool {
+        ComponentCapabilities::detect(component)
+            .unwrap()
+            .component_model_async
+    }
+
+    #[test]
+    fn component_without_canonical_functions_is_sync() {
+        assert!(!uses_async(&component(&[section(
+            CUSTOM_SECTION,
+            &[0x04, b'n', b'a', b'm', b'e']
+        )])));
+    }
+
+    #[test]
+    fn sync_lifts_and_lowers_are_sync() {
+        // Lifting with utf8 encoding, memory 0, realloc 3 and post-return 200
+        let sync_lift = lift(&[0x04, 0x00, 0x03, 0x00, 0x04, 0x03, 0x05, 0xc8, 0x01]);
+        // Lowering function 0 with memory 0, and dropping resource 5
+        let sync_lower = section(
+            CANONICAL_SECTION,
+            &[0x02, 0x01, 0x00, 0x00, 0x01, 0x03, 0x00, 0x03, 0x05],
+        );
+
+        assert!(!uses_async(&component(&[sync_lift, sync_lower])));
+    }
+
+    #[test]
+    fn async_lifts_are_detected() {
+        assert!(uses_async(&component(&[lift(&[0x02, 0x03, 0x00, 0x06])])));
+    }
+
+    #[test]
+    fn async_callbacks_are_detected() {
+        assert!(uses_async(&component(&[lift(&[0x01, 0x07, 0x04])])));
+    }
+
+    #[test]
+    fn async_built_ins_are_detected() {
+        // task.return without results and options
+        assert!(uses_async(&component(&[section(
+            CANONICAL_SECTION,
+            &[0x01, 0x09, 0x01, 0x00, 0x00]
+        )])));
+    }
+
+    #[test]
+    fn async_in_nested_components_is_detected() {
+        let nested = component(&[lift(&[0x01, 0x06])]);
+
+        assert!(uses_async(&component(&[
+            lift(&[0x00]),
+            section(COMPONENT_SECTION, &nested)
+        ])));
+    }
+
+    #[test]
+    fn core_modules_are_rejected() {
+        let module = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+
+        assert_eq!(
+            ComponentCapabilities::detect(&module),
+            Err("Not a WebAssembly component".to_string())
+        );
+    }
+
+    #[test]
+    fn truncated_components_are_rejected() {
+        let mut truncated = component(&[lift(&[0x01, 0x06])]);
+        truncated.pop();
+
+        assert!(ComponentCapabilities::detect(&truncated).is_err());
+    }
+}
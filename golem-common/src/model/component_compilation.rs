@@ -0,0 +1,182 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use golem_api_grpc::proto::golem::componentcompilation::v1 as grpc;
+use poem_openapi::{Enum, Object};
+use serde::{Deserialize, Serialize};
+
+use crate::model::Timestamp;
+
+/// Compilations are processed in the order of their priority, and in the order they were
+/// requested within the same priority
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Enum,
+)]
+pub enum CompilationPriority {
+    /// Compilation of a component version just uploaded by a user
+    Interactive,
+    /// Recompilations nobody is waiting for
+    Background,
+}
+
+impl From<grpc::CompilationPriority> for CompilationPriority {
+    fn from(value: grpc::CompilationPriority) -> Self {
+        match value {
+            grpc::CompilationPriority::Interactive => CompilationPriority::Interactive,
+            grpc::CompilationPriority::Background => CompilationPriority::Background,
+        }
+    }
+}
+
+impl From<CompilationPriority> for grpc::CompilationPriority {
+    fn from(value: CompilationPriority) -> Self {
+        match value {
+            CompilationPriority::Interactive => grpc::CompilationPriority::Interactive,
+            CompilationPriority::Background => grpc::CompilationPriority::Background,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Enum)]
+pub enum CompilationState {
+    Queued,
+    Compiling,
+    /// The last attempt failed, and the compilation is going to be queued again
+    RetryScheduled,
+    Compiled,
+    /// All the attempts failed
+    Failed,
+}
+
+impl From<grpc::CompilationState> for CompilationState {
+    fn from(value: grpc::CompilationState) -> Self {
+        match value {
+            grpc::CompilationState::Queued => CompilationState::Queued,
+            grpc::CompilationState::Compiling => CompilationState::Compiling,
+            grpc::CompilationState::RetryScheduled => CompilationState::RetryScheduled,
+            grpc::CompilationState::Compiled => CompilationState::Compiled,
+            grpc::CompilationState::Failed => CompilationState::Failed,
+        }
+    }
+}
+
+impl From<CompilationState> for grpc::CompilationState {
+    fn from(value: CompilationState) -> Self {
+        match value {
+            CompilationState::Queued => grpc::CompilationState::Queued,
+            CompilationState::Compiling => grpc::CompilationState::Compiling,
+            CompilationState::RetryScheduled => grpc::CompilationState::RetryScheduled,
+            CompilationState::Compiled => grpc::CompilationState::Compiled,
+            CompilationState::Failed => grpc::CompilationState::Failed,
+        }
+    }
+}
+
+/// Status of the compilation of a component version by the component compilation service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct CompilationStatus {
+    pub state: CompilationState,
+    pub priority: CompilationPriority,
+    /// Number of compilation attempts started so far
+    pub attempts: u32,
+    /// Error of the last failed attempt
+    pub last_error: Option<String>,
+    pub updated_at: Timestamp,
+    /// Worker executors the compiled component was distributed to
+    pub executors: Vec<ExecutorCacheStatus>,
+}
+
+impl TryFrom<grpc::CompilationStatus> for CompilationStatus {
+    type Error = String;
+
+    fn try_from(value: grpc::CompilationStatus) -> Result<Self, Self::Error> {
+        let state = grpc::CompilationState::try_from(value.state)
+            .map_err(|_| format!("Invalid compilation state: {}", value.state))?;
+        let priority = grpc::CompilationPriority::try_from(value.priority)
+            .map_err(|_| format!("Invalid compilation priority: {}", value.priority))?;
+
+        Ok(Self {
+            state: state.into(),
+            priority: priority.into(),
+            attempts: value.attempts,
+            last_error: value.last_error,
+            updated_at: value.updated_at.ok_or("Missing updated_at")?.into(),
+            executors: value
+                .executors
+                .into_iter()
+                .map(|executor| executor.try_into())
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+impl From<CompilationStatus> for grpc::CompilationStatus {
+    fn from(value: CompilationStatus) -> Self {
+        let state: grpc::CompilationState = value.state.into();
+        let priority: grpc::CompilationPriority = value.priority.into();
+
+        Self {
+            state: state as i32,
+            priority: priority as i32,
+            attempts: value.attempts,
+            last_error: value.last_error,
+            updated_at: Some(value.updated_at.into()),
+            executors: value
+                .executors
+                .into_iter()
+                .map(|executor| executor.into())
+                .collect(),
+        }
+    }
+}
+
+/// Whether a worker executor has the natively compiled component version in its cache, so
+/// creating workers of the version on it does not have to wait for the compilation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ExecutorCacheStatus {
+    /// Address of the worker executor
+    pub executor: String,
+    pub warm: bool,
+    /// Error of the failed precompilation on the executor
+    pub error: Option<String>,
+    pub updated_at: Timestamp,
+}
+
+impl TryFrom<grpc::ExecutorCacheStatus> for ExecutorCacheStatus {
+    type Error = String;
+
+    fn try_from(value: grpc::ExecutorCacheStatus) -> Result<Self, Self::Error> {
+        Ok(Self {
+            executor: value.executor,
+            warm: value.warm,
+            error: value.error,
+            updated_at: value.updated_at.ok_or("Missing updated_at")?.into(),
+        })
+    }
+}
+
+impl From<ExecutorCacheStatus> for grpc::ExecutorCacheStatus {
+    fn from(value: ExecutorCacheStatus) -> Self {
+        Self {
+            executor: value.executor,
+            warm: value.warm,
+            error: value.error,
+            updated_at: Some(value.updated_at.into()),
+        }
+    }
+}
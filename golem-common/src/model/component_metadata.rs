@@ -13,8 +13,12 @@
 // limitations under the License.
 
 use bincode::{Decode, Encode};
+use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
 
+use crate::model::component_capabilities::ComponentCapabilities;
+use crate::model::exports::function_by_name;
+use crate::model::initial_file_system::InitialFile;
 use crate::SafeDisplay;
 use golem_wasm_ast::analysis::AnalysedFunctionParameter;
 use golem_wasm_ast::core::Mem;
@@ -32,6 +36,20 @@ pub struct ComponentMetadata {
     pub exports: Vec<AnalysedExport>,
     pub producers: Vec<Producers>,
     pub memories: Vec<LinearMemory>,
+    /// The files of the component's initial file system
+    #[serde(default)]
+    #[oai(default)]
+    pub files: Vec<InitialFile>,
+    /// Short names which can be used instead of the fully qualified names of exported functions
+    /// when invoking the component, for example `add-user` for `golem:it/api.{add-user}`
+    #[serde(default)]
+    #[oai(default)]
+    pub function_aliases: HashMap<String, String>,
+    /// Component model features used by the component which the worker executors have to
+    /// support to run it
+    #[serde(default)]
+    #[oai(default)]
+    pub capabilities: ComponentCapabilities,
 }
 
 impl ComponentMetadata {
@@ -39,6 +57,76 @@ impl ComponentMetadata {
         let raw = RawComponentMetadata::analyse_component(data)?;
         Ok(raw.into())
     }
+
+    /// The fully qualified name of the function `function_name` refers to. Names which are not
+    /// aliases are returned unchanged.
+    pub fn resolve_function_name<'a>(&'a self, function_name: &'a str) -> &'a str {
+        self.function_aliases
+            .get(function_name)
+            .map(|name| name.as_str())
+            .unwrap_or(function_name)
+    }
+
+    /// The exports extended with a root level function for each alias, having the signature of
+    /// the function it refers to, so calls using aliases can be type checked like any other call
+    pub fn exports_with_aliases(&self) -> Vec<AnalysedExport> {
+        let mut exports = self.exports.clone();
+        let mut aliases = self.function_aliases.iter().collect::<Vec<_>>();
+        aliases.sort();
+        for (alias, function_name) in aliases {
+            if let Ok(Some(function)) = function_by_name(&self.exports, function_name) {
+                exports.push(AnalysedExport::Function(AnalysedFunction {
+                    name: alias.clone(),
+                    ..function
+                }));
+            }
+        }
+        exports
+    }
+
+    /// Checks that every alias is a valid name not shadowing a root level export, and refers to
+    /// an exported function
+    pub fn validate_function_aliases(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let mut aliases = self.function_aliases.iter().collect::<Vec<_>>();
+        aliases.sort();
+        for (alias, function_name) in aliases {
+            if !is_valid_function_alias(alias) {
+                errors.push(format!(
+                    "Invalid function alias {alias}: aliases must be kebab-case names"
+                ));
+            } else if matches!(function_by_name(&self.exports, alias), Ok(Some(_))) {
+                errors.push(format!(
+                    "Function alias {alias} has the same name as an exported function"
+                ));
+            }
+            match function_by_name(&self.exports, function_name) {
+                Ok(Some(_)) => {}
+                Ok(None) => errors.push(format!(
+                    "Function {function_name} of alias {alias} is not exported by the component"
+                )),
+                Err(err) => errors.push(format!(
+                    "Invalid function name {function_name} of alias {alias}: {err}"
+                )),
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Whether `alias` can be used as a function alias: a kebab-case name, which can never be
+/// mistaken for a fully qualified function name
+pub fn is_valid_function_alias(alias: &str) -> bool {
+    alias.starts_with(|c: char| c.is_ascii_lowercase())
+        && !alias.ends_with('-')
+        && !alias.contains("--")
+        && alias
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
 }
 
 #[derive(
@@ -263,6 +351,9 @@ impl From<RawComponentMetadata> for ComponentMetadata {
             exports,
             producers,
             memories,
+            files: vec![],
+            function_aliases: HashMap::new(),
+            capabilities: value.capabilities,
         }
     }
 }
@@ -289,6 +380,16 @@ impl TryFrom<golem_api_grpc::proto::golem::component::ComponentMetadata> for Com
                 .into_iter()
                 .map(|memory| memory.into())
                 .collect(),
+            files: value
+                .files
+                .into_iter()
+                .map(|file| file.try_into())
+                .collect::<Result<_, _>>()?,
+            function_aliases: value.function_aliases,
+            capabilities: value
+                .capabilities
+                .map(|capabilities| capabilities.into())
+                .unwrap_or_default(),
         })
     }
 }
@@ -311,6 +412,9 @@ impl From<ComponentMetadata> for golem_api_grpc::proto::golem::component::Compon
                 .into_iter()
                 .map(|memory| memory.into())
                 .collect(),
+            files: value.files.into_iter().map(|file| file.into()).collect(),
+            function_aliases: value.function_aliases,
+            capabilities: Some(value.capabilities.into()),
         }
     }
 }
@@ -320,14 +424,26 @@ pub struct RawComponentMetadata {
     pub exports: Vec<AnalysedExport>,
     pub producers: Vec<WasmAstProducers>,
     pub memories: Vec<Mem>,
+    pub capabilities: ComponentCapabilities,
 }
 
 impl RawComponentMetadata {
     pub fn analyse_component(
         data: &[u8],
     ) -> Result<RawComponentMetadata, ComponentProcessingError> {
-        let component = Component::<IgnoreAllButMetadata>::from_bytes(data)
-            .map_err(ComponentProcessingError::Parsing)?;
+        let capabilities =
+            ComponentCapabilities::detect(data).map_err(ComponentProcessingError::Parsing)?;
+
+        let component =
+            Component::<IgnoreAllButMetadata>::from_bytes(data).map_err(|err| {
+                if capabilities.component_model_async {
+                    ComponentProcessingError::Unsupported(format!(
+                        "the component uses component-model async, which cannot be analysed yet ({err})"
+                    ))
+                } else {
+                    ComponentProcessingError::Parsing(err)
+                }
+            })?;
 
         let producers = component
             .get_all_producers()
@@ -354,6 +470,7 @@ impl RawComponentMetadata {
             exports,
             producers,
             memories,
+            capabilities,
         })
     }
 }
@@ -362,6 +479,7 @@ impl RawComponentMetadata {
 pub enum ComponentProcessingError {
     Parsing(String),
     Analysis(AnalysisFailure),
+    Unsupported(String),
 }
 
 impl SafeDisplay for ComponentProcessingError {
@@ -369,6 +487,7 @@ impl SafeDisplay for ComponentProcessingError {
         match self {
             ComponentProcessingError::Parsing(_) => self.to_string(),
             ComponentProcessingError::Analysis(_) => self.to_string(),
+            ComponentProcessingError::Unsupported(_) => self.to_string(),
         }
     }
 }
@@ -381,6 +500,9 @@ impl Display for ComponentProcessingError {
                 let AnalysisFailure { reason } = source;
                 write!(f, "Analysis error: {}", reason)
             }
+            ComponentProcessingError::Unsupported(reason) => {
+                write!(f, "Unsupported component: {}", reason)
+            }
         }
     }
 }
@@ -429,3 +551,100 @@ fn drop_from_constructor(constructor: &AnalysedFunction) -> AnalysedFunction {
         results: vec![],
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+
+    use golem_wasm_ast::analysis::analysed_type::str;
+    use golem_wasm_ast::analysis::{
+        AnalysedExport, AnalysedFunction, AnalysedFunctionParameter, AnalysedInstance,
+    };
+
+    use super::{is_valid_function_alias, ComponentMetadata};
+
+    fn metadata(function_aliases: &[(&str, &str)]) -> ComponentMetadata {
+        ComponentMetadata {
+            exports: vec![
+                AnalysedExport::Instance(AnalysedInstance {
+                    name: "golem:it/api".to_string(),
+                    functions: vec![AnalysedFunction {
+                        name: "add-user".to_string(),
+                        parameters: vec![AnalysedFunctionParameter {
+                            name: "name".to_string(),
+                            typ: str(),
+                        }],
+                        results: vec![],
+                    }],
+                }),
+                AnalysedExport::Function(AnalysedFunction {
+                    name: "run".to_string(),
+                    parameters: vec![],
+                    results: vec![],
+                }),
+            ],
+            producers: vec![],
+            memories: vec![],
+            files: vec![],
+            function_aliases: function_aliases
+                .iter()
+                .map(|(alias, name)| (alias.to_string(), name.to_string()))
+                .collect::<HashMap<_, _>>(),
+            capabilities: Default::default(),
+        }
+    }
+
+    #[test]
+    fn aliases_are_resolved() {
+        let metadata = metadata(&[("add-user", "golem:it/api.{add-user}")]);
+
+        assert_eq!(
+            metadata.resolve_function_name("add-user"),
+            "golem:it/api.{add-user}"
+        );
+        assert_eq!(metadata.resolve_function_name("run"), "run");
+        assert!(metadata.validate_function_aliases().is_ok());
+    }
+
+    #[test]
+    fn aliases_are_exported_with_the_signature_of_their_function() {
+        let metadata = metadata(&[("add-user", "golem:it/api.{add-user}")]);
+
+        let exports = metadata.exports_with_aliases();
+
+        assert_eq!(exports.len(), 3);
+        match exports.last() {
+            Some(AnalysedExport::Function(function)) => {
+                assert_eq!(function.name, "add-user");
+                assert_eq!(function.parameters.len(), 1);
+            }
+            other => panic!("Unexpected export {other:?}"),
+        }
+    }
+
+    #[test]
+    fn invalid_aliases_are_rejected() {
+        let metadata = metadata(&[
+            ("run", "golem:it/api.{add-user}"),
+            ("Add_User", "golem:it/api.{add-user}"),
+            ("remove-user", "golem:it/api.{remove-user}"),
+        ]);
+
+        let errors = metadata.validate_function_aliases().unwrap_err();
+
+        assert_eq!(errors.len(), 3);
+    }
+
+    #[test]
+    fn alias_names() {
+        assert!(is_valid_function_alias("add-user"));
+        assert!(is_valid_function_alias("add-user2"));
+        assert!(!is_valid_function_alias(""));
+        assert!(!is_valid_function_alias("2fa"));
+        assert!(!is_valid_function_alias("add-"));
+        assert!(!is_valid_function_alias("add--user"));
+        assert!(!is_valid_function_alias("golem:it/api.{add-user}"));
+    }
+}
@@ -0,0 +1,362 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+
+use bincode::{Decode, Encode};
+use poem_openapi::{Enum, Object};
+use serde::{Deserialize, Serialize};
+
+/// Path of the manifest in an initial file system archive
+pub const MANIFEST_PATH: &str = "ifs-manifest.json";
+
+/// Directory of an initial file system archive containing the files listed in the manifest,
+/// each one stored under its path in the worker's file system
+pub const FILES_DIR: &str = "files";
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode, Enum,
+)]
+#[serde(rename_all = "kebab-case")]
+#[oai(rename_all = "kebab-case")]
+pub enum InitialFilePermissions {
+    #[default]
+    ReadOnly,
+    ReadWrite,
+}
+
+impl From<golem_api_grpc::proto::golem::component::InitialFilePermissions>
+    for InitialFilePermissions
+{
+    fn from(value: golem_api_grpc::proto::golem::component::InitialFilePermissions) -> Self {
+        match value {
+            golem_api_grpc::proto::golem::component::InitialFilePermissions::ReadOnly => {
+                InitialFilePermissions::ReadOnly
+            }
+            golem_api_grpc::proto::golem::component::InitialFilePermissions::ReadWrite => {
+                InitialFilePermissions::ReadWrite
+            }
+        }
+    }
+}
+
+impl From<InitialFilePermissions>
+    for golem_api_grpc::proto::golem::component::InitialFilePermissions
+{
+    fn from(value: InitialFilePermissions) -> Self {
+        match value {
+            InitialFilePermissions::ReadOnly => {
+                golem_api_grpc::proto::golem::component::InitialFilePermissions::ReadOnly
+            }
+            InitialFilePermissions::ReadWrite => {
+                golem_api_grpc::proto::golem::component::InitialFilePermissions::ReadWrite
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct InitialFile {
+    /// Absolute path of the file in the worker's file system
+    pub path: String,
+    pub permissions: InitialFilePermissions,
+    /// If true, `${VARIABLE}` placeholders in the file are replaced with the worker's
+    /// environment variables when the worker's file system is initialized
+    #[serde(default)]
+    #[oai(default)]
+    pub template: bool,
+}
+
+impl InitialFile {
+    /// Path of the file's contents in the initial file system archive
+    pub fn archive_path(&self) -> String {
+        format!("{FILES_DIR}{}", self.path)
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::component::InitialFile> for InitialFile {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::component::InitialFile,
+    ) -> Result<Self, Self::Error> {
+        let permissions =
+            golem_api_grpc::proto::golem::component::InitialFilePermissions::try_from(
+                value.permissions,
+            )
+            .map_err(|_| format!("Invalid initial file permissions: {}", value.permissions))?;
+
+        Ok(Self {
+            path: value.path,
+            permissions: permissions.into(),
+            template: value.template,
+        })
+    }
+}
+
+impl From<InitialFile> for golem_api_grpc::proto::golem::component::InitialFile {
+    fn from(value: InitialFile) -> Self {
+        let permissions: golem_api_grpc::proto::golem::component::InitialFilePermissions =
+            value.permissions.into();
+
+        Self {
+            path: value.path,
+            permissions: permissions as i32,
+            template: value.template,
+        }
+    }
+}
+
+/// Describes the files of a component's initial file system, packaged as the `ifs-manifest.json`
+/// entry of the archive uploaded together with the component
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct InitialFileSystemManifest {
+    pub files: Vec<InitialFile>,
+}
+
+impl InitialFileSystemManifest {
+    /// The manifest of an archive uploaded without one, listing every file of the archive with
+    /// the default permissions
+    pub fn with_default_permissions(archive_entries: &[String]) -> Self {
+        let mut files = archive_entries
+            .iter()
+            .filter(|name| !name.ends_with('/'))
+            .filter_map(|name| name.strip_prefix(FILES_DIR))
+            .filter(|path| path.starts_with('/'))
+            .map(|path| InitialFile {
+                path: path.to_string(),
+                permissions: InitialFilePermissions::default(),
+                template: false,
+            })
+            .collect::<Vec<_>>();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { files }
+    }
+
+    pub fn from_json(data: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice(data).map_err(|err| format!("Invalid manifest: {err}"))
+    }
+
+    pub fn to_json(&self) -> Vec<u8> {
+        serde_json::to_vec_pretty(self).expect("Failed to serialize the manifest")
+    }
+
+    /// Checks the manifest against the names of the entries of the archive it was packaged in.
+    ///
+    /// Every file must have an absolute, normalized path, must be listed only once and must be
+    /// present in the archive, and every file in the archive must be listed in the manifest.
+    pub fn validate(&self, archive_entries: &[String]) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        let archived_files = archive_entries
+            .iter()
+            .filter(|name| name.starts_with(&format!("{FILES_DIR}/")) && !name.ends_with('/'))
+            .map(|name| name.as_str())
+            .collect::<HashSet<_>>();
+
+        let mut listed_files = HashSet::new();
+        for file in &self.files {
+            if let Err(error) = validate_path(&file.path) {
+                errors.push(error);
+                continue;
+            }
+            if !listed_files.insert(file.archive_path()) {
+                errors.push(format!("File {} is listed more than once", file.path));
+            } else if !archived_files.contains(file.archive_path().as_str()) {
+                errors.push(format!("File {} is missing from the archive", file.path));
+            }
+        }
+
+        let mut unlisted = archived_files
+            .into_iter()
+            .filter(|name| !listed_files.contains(*name))
+            .collect::<Vec<_>>();
+        unlisted.sort();
+        for name in unlisted {
+            errors.push(format!(
+                "Archive entry {name} is not listed in the manifest"
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn validate_path(path: &str) -> Result<(), String> {
+    let Some(relative) = path.strip_prefix('/') else {
+        return Err(format!("File path {path} is not absolute"));
+    };
+    if relative
+        .split('/')
+        .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+    {
+        return Err(format!("File path {path} is not normalized"));
+    }
+    Ok(())
+}
+
+/// Replaces the `${VARIABLE}` placeholders of a templated file. Placeholders of variables
+/// which are not defined are kept as they are.
+pub fn render_template(content: &str, variables: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        match placeholder.find('}') {
+            Some(end) => {
+                match variables.get(&placeholder[2..end]) {
+                    Some(value) => result.push_str(value),
+                    None => result.push_str(&placeholder[..=end]),
+                }
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                result.push_str(placeholder);
+                rest = "";
+            }
+        }
+    }
+    result.push_str(rest);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+
+    use crate::model::initial_file_system::{
+        render_template, InitialFile, InitialFilePermissions, InitialFileSystemManifest,
+    };
+
+    fn file(path: &str) -> InitialFile {
+        InitialFile {
+            path: path.to_string(),
+            permissions: InitialFilePermissions::ReadOnly,
+            template: false,
+        }
+    }
+
+    #[test]
+    fn valid_manifest() {
+        let manifest = InitialFileSystemManifest {
+            files: vec![file("/config.json"), file("/data/users.csv")],
+        };
+        let entries = vec![
+            "ifs-manifest.json".to_string(),
+            "files/".to_string(),
+            "files/config.json".to_string(),
+            "files/data/".to_string(),
+            "files/data/users.csv".to_string(),
+        ];
+
+        assert_eq!(manifest.validate(&entries), Ok(()));
+    }
+
+    #[test]
+    fn invalid_manifest() {
+        let manifest = InitialFileSystemManifest {
+            files: vec![
+                file("config.json"),
+                file("/../etc/passwd"),
+                file("/data/users.csv"),
+                file("/data/users.csv"),
+                file("/missing.txt"),
+            ],
+        };
+        let entries = vec![
+            "files/data/users.csv".to_string(),
+            "files/unlisted.txt".to_string(),
+        ];
+
+        assert_eq!(
+            manifest.validate(&entries),
+            Err(vec![
+                "File path config.json is not absolute".to_string(),
+                "File path /../etc/passwd is not normalized".to_string(),
+                "File /data/users.csv is listed more than once".to_string(),
+                "File /missing.txt is missing from the archive".to_string(),
+                "Archive entry files/unlisted.txt is not listed in the manifest".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn default_manifest() {
+        let entries = vec![
+            "files/".to_string(),
+            "files/data/users.csv".to_string(),
+            "files/data/".to_string(),
+            "files/config.json".to_string(),
+            "README.md".to_string(),
+        ];
+        let manifest = InitialFileSystemManifest::with_default_permissions(&entries);
+
+        assert_eq!(
+            manifest.files,
+            vec![file("/config.json"), file("/data/users.csv")]
+        );
+        assert_eq!(manifest.validate(&entries), Ok(()));
+    }
+
+    #[test]
+    fn manifest_json() {
+        let manifest = InitialFileSystemManifest::from_json(
+            br#"{"files": [{"path": "/config.json", "permissions": "read-write", "template": true}, {"path": "/logo.png", "permissions": "read-only"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.files,
+            vec![
+                InitialFile {
+                    path: "/config.json".to_string(),
+                    permissions: InitialFilePermissions::ReadWrite,
+                    template: true,
+                },
+                file("/logo.png"),
+            ]
+        );
+        assert_eq!(
+            InitialFileSystemManifest::from_json(&manifest.to_json()),
+            Ok(manifest)
+        );
+    }
+
+    #[test]
+    fn templates_are_rendered() {
+        let variables = HashMap::from([
+            ("GOLEM_WORKER_NAME".to_string(), "worker-1".to_string()),
+            ("REGION".to_string(), "eu".to_string()),
+        ]);
+
+        assert_eq!(
+            render_template(
+                "name=${GOLEM_WORKER_NAME} region=${REGION} other=${OTHER} broken=${",
+                &variables
+            ),
+            "name=worker-1 region=eu other=${OTHER} broken=${"
+        );
+    }
+}
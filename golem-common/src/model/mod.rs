@@ -47,12 +47,25 @@ use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Value;
 use uuid::{uuid, Uuid};
 
+pub mod component_capabilities;
+pub mod component_compilation;
 pub mod component_metadata;
 pub mod exports;
+pub mod initial_file_system;
+pub mod maintenance;
 pub mod oplog;
+pub mod oplog_codec;
+pub mod oplog_migration;
+pub mod oplog_protobuf;
 pub mod public_oplog;
 pub mod regions;
+pub mod timeline;
 pub mod trim_date;
+pub mod worker_defaults;
+pub mod worker_filter;
+pub mod worker_metadata_projection;
+pub mod worker_name_query;
+pub mod worker_status_event;
 
 use crate::uri::oss::urn::WorkerUrn;
 use golem_api_grpc::proto::golem::shardmanager::{
@@ -535,8 +548,40 @@ impl IntoValue for PromiseId {
     }
 }
 
+/// Identity of a client calling a worker through the API gateway, as verified by the TLS client
+/// certificate it authenticated with
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Encode, Decode, Serialize, Deserialize)]
+pub struct CallerIdentity {
+    /// Distinguished name of the certificate's subject
+    pub subject: String,
+    /// URI subject alternative names, such as SPIFFE ids
+    pub uris: Vec<String>,
+    /// DNS subject alternative names
+    pub dns_names: Vec<String>,
+}
+
+impl From<CallerIdentity> for golem_api_grpc::proto::golem::worker::CallerIdentity {
+    fn from(value: CallerIdentity) -> Self {
+        Self {
+            subject: value.subject,
+            uris: value.uris,
+            dns_names: value.dns_names,
+        }
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::worker::CallerIdentity> for CallerIdentity {
+    fn from(value: golem_api_grpc::proto::golem::worker::CallerIdentity) -> Self {
+        Self {
+            subject: value.subject,
+            uris: value.uris,
+            dns_names: value.dns_names,
+        }
+    }
+}
+
 /// Actions that can be scheduled to be executed at a given point in time
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Encode, Decode)]
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
 pub enum ScheduledAction {
     /// Completes a given promise
     CompletePromise {
@@ -551,6 +596,20 @@ pub enum ScheduledAction {
         last_oplog_index: OplogIndex,
         next_after: Duration,
     },
+    /// Deletes a worker and all its storage, if its expiry did not change since the action
+    /// was scheduled
+    DeleteWorker {
+        owned_worker_id: OwnedWorkerId,
+        expires_at: Timestamp,
+    },
+    /// Enqueues an invocation of an exported function of a worker. The idempotency key makes
+    /// enqueuing it more than once safe.
+    Invoke {
+        owned_worker_id: OwnedWorkerId,
+        idempotency_key: IdempotencyKey,
+        full_function_name: String,
+        function_input: Vec<golem_wasm_rpc::Value>,
+    },
 }
 
 impl ScheduledAction {
@@ -563,6 +622,12 @@ impl ScheduledAction {
             ScheduledAction::ArchiveOplog {
                 owned_worker_id, ..
             } => owned_worker_id.clone(),
+            ScheduledAction::DeleteWorker {
+                owned_worker_id, ..
+            } => owned_worker_id.clone(),
+            ScheduledAction::Invoke {
+                owned_worker_id, ..
+            } => owned_worker_id.clone(),
         }
     }
 }
@@ -578,11 +643,23 @@ impl Display for ScheduledAction {
             } => {
                 write!(f, "archive[{}]", owned_worker_id)
             }
+            ScheduledAction::DeleteWorker {
+                owned_worker_id, ..
+            } => {
+                write!(f, "delete[{}]", owned_worker_id)
+            }
+            ScheduledAction::Invoke {
+                owned_worker_id,
+                full_function_name,
+                ..
+            } => {
+                write!(f, "invoke[{}/{}]", owned_worker_id, full_function_name)
+            }
         }
     }
 }
 
-#[derive(Debug, Encode, Decode)]
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
 pub struct ScheduleId {
     pub timestamp: i64,
     pub action: ScheduledAction,
@@ -594,6 +671,28 @@ impl Display for ScheduleId {
     }
 }
 
+/// Something created by a worker, which a cascading delete of the worker deletes too
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub enum WorkerDependent {
+    /// A worker spawned through the child workers host interface
+    ChildWorker(WorkerId),
+    /// A promise created by the worker
+    Promise(PromiseId),
+    /// An invocation of the worker scheduled through the API. It is not removed once it runs,
+    /// cancelling it is then a no-op.
+    Schedule(ScheduleId),
+}
+
+impl Display for WorkerDependent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkerDependent::ChildWorker(worker_id) => write!(f, "child worker {worker_id}"),
+            WorkerDependent::Promise(promise_id) => write!(f, "promise {promise_id}"),
+            WorkerDependent::Schedule(schedule_id) => write!(f, "schedule {schedule_id}"),
+        }
+    }
+}
+
 #[derive(
     Clone,
     Copy,
@@ -710,6 +809,12 @@ impl Pod {
     }
 }
 
+impl Display for Pod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.host, self.port)
+    }
+}
+
 impl From<GrpcPod> for Pod {
     fn from(value: GrpcPod) -> Self {
         Self {
@@ -723,6 +828,8 @@ impl From<GrpcPod> for Pod {
 pub struct RoutingTable {
     pub number_of_shards: NumberOfShards,
     shard_assignments: HashMap<ShardId, Pod>,
+    /// Version of the shard assignments, changed by the shard manager whenever they change
+    pub epoch: u64,
 }
 
 impl RoutingTable {
@@ -746,6 +853,15 @@ impl RoutingTable {
     pub fn all(&self) -> HashSet<&Pod> {
         self.shard_assignments.values().collect()
     }
+
+    /// Number of shards assigned to each pod
+    pub fn shard_counts(&self) -> HashMap<&Pod, usize> {
+        let mut counts = HashMap::new();
+        for pod in self.shard_assignments.values() {
+            *counts.entry(pod).or_insert(0) += 1;
+        }
+        counts
+    }
 }
 
 impl From<GrpcRoutingTable> for RoutingTable {
@@ -760,6 +876,7 @@ impl From<GrpcRoutingTable> for RoutingTable {
                 .map(RoutingTableEntry::from)
                 .map(|routing_table_entry| (routing_table_entry.shard_id, routing_table_entry.pod))
                 .collect(),
+            epoch: value.epoch,
         }
     }
 }
@@ -975,6 +1092,8 @@ pub struct WorkerMetadata {
     pub created_at: Timestamp,
     pub parent: Option<WorkerId>,
     pub last_known_status: WorkerStatusRecord,
+    /// The point in time after which the worker gets deleted, if it has a TTL
+    pub expires_at: Option<Timestamp>,
 }
 
 impl WorkerMetadata {
@@ -987,12 +1106,57 @@ impl WorkerMetadata {
             created_at: Timestamp::now_utc(),
             parent: None,
             last_known_status: WorkerStatusRecord::default(),
+            expires_at: None,
         }
     }
 
     pub fn owned_worker_id(&self) -> OwnedWorkerId {
         OwnedWorkerId::new(&self.account_id, &self.worker_id)
     }
+
+    /// The environment variables the worker is started with, taking changes made after its
+    /// creation into account
+    pub fn current_env(&self) -> &[(String, String)] {
+        self.last_known_status
+            .overridden_env
+            .as_deref()
+            .unwrap_or(&self.env)
+    }
+}
+
+/// Shadow invocation settings of a worker. While set, every invocation of the worker is also sent
+/// to the shadow worker, which is running a candidate component version.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct WorkerShadow {
+    pub shadow_worker_id: WorkerId,
+    /// If true, the results of the shadow invocations are compared to the original ones and
+    /// differences are logged, otherwise they are discarded
+    pub compare_results: bool,
+}
+
+impl From<WorkerShadow> for golem_api_grpc::proto::golem::workerexecutor::v1::WorkerShadow {
+    fn from(value: WorkerShadow) -> Self {
+        Self {
+            shadow_worker_id: Some(value.shadow_worker_id.into()),
+            compare_results: value.compare_results,
+        }
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::workerexecutor::v1::WorkerShadow> for WorkerShadow {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::workerexecutor::v1::WorkerShadow,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            shadow_worker_id: value
+                .shadow_worker_id
+                .ok_or("Missing shadow_worker_id")?
+                .try_into()?,
+            compare_results: value.compare_results,
+        })
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
@@ -1002,7 +1166,8 @@ pub struct WorkerResourceDescription {
 }
 
 /// Contains status information about a worker according to a given oplog index.
-/// This status is just cached information, all fields must be computable by the oplog alone.
+/// This status is just cached information, all fields must be computable by the oplog alone,
+/// except `disk_usage` which is only reported by the running worker.
 /// By having an associated oplog_idx, the cached information can be used together with the
 /// tail of the oplog to determine the actual status of the worker.
 #[derive(Clone, Debug, PartialEq, Encode, Decode)]
@@ -1010,7 +1175,11 @@ pub struct WorkerStatusRecord {
     pub status: WorkerStatus,
     pub deleted_regions: DeletedRegions,
     pub overridden_retry_config: Option<RetryConfig>,
+    /// Environment variables replacing the ones the worker was created with
+    pub overridden_env: Option<Vec<(String, String)>>,
     pub pending_invocations: Vec<TimestampedWorkerInvocation>,
+    /// Pending invocations which are not executed until they get released
+    pub held_invocations: HashSet<IdempotencyKey>,
     pub pending_updates: VecDeque<TimestampedUpdateDescription>,
     pub failed_updates: Vec<FailedUpdateRecord>,
     pub successful_updates: Vec<SuccessfulUpdateRecord>,
@@ -1019,6 +1188,11 @@ pub struct WorkerStatusRecord {
     pub component_version: ComponentVersion,
     pub component_size: u64,
     pub total_linear_memory_size: u64,
+    /// Total size of the files in the worker's file system in bytes, as of the last time the
+    /// worker was running. File writes are not persisted in the oplog, so unlike the other
+    /// fields it cannot be recalculated from it, and it is carried over from the last known
+    /// status instead.
+    pub disk_usage: u64,
     pub owned_resources: HashMap<WorkerResourceId, WorkerResourceDescription>,
     pub oplog_idx: OplogIndex,
     pub fs_version: u64
@@ -1030,7 +1204,9 @@ impl Default for WorkerStatusRecord {
             status: WorkerStatus::Idle,
             deleted_regions: DeletedRegions::new(),
             overridden_retry_config: None,
+            overridden_env: None,
             pending_invocations: Vec::new(),
+            held_invocations: HashSet::new(),
             pending_updates: VecDeque::new(),
             failed_updates: Vec::new(),
             successful_updates: Vec::new(),
@@ -1039,6 +1215,7 @@ impl Default for WorkerStatusRecord {
             component_version: 0,
             component_size: 0,
             total_linear_memory_size: 0,
+            disk_usage: 0,
             owned_resources: HashMap::new(),
             oplog_idx: OplogIndex::default(),
             fs_version: 0
@@ -1053,6 +1230,33 @@ pub struct FailedUpdateRecord {
     pub details: Option<String>,
 }
 
+/// The point where replaying a worker's oplog with a new component version diverged from the
+/// recorded history, making an automatic update fail
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct UpdateDivergence {
+    /// Timestamp of the failed update record
+    pub timestamp: Timestamp,
+    pub target_version: ComponentVersion,
+    /// Index of the first oplog entry that did not match the replay
+    pub oplog_index: OplogIndex,
+    /// What the new version expected to find at `oplog_index`
+    pub expected: String,
+    /// What the previous version recorded at `oplog_index`
+    pub got: String,
+}
+
+impl From<UpdateDivergence> for golem_api_grpc::proto::golem::worker::UpdateDivergence {
+    fn from(value: UpdateDivergence) -> Self {
+        Self {
+            oplog_index: value.oplog_index.into(),
+            expected: value.expected,
+            got: value.got,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
 pub struct SuccessfulUpdateRecord {
     pub timestamp: Timestamp,
@@ -1175,36 +1379,54 @@ impl From<WorkerStatus> for i32 {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+#[derive(Clone, Debug, PartialEq, Encode, Decode, Serialize, Deserialize)]
 pub enum WorkerInvocation {
-    ExportedFunction {
+    /// Invocation of an exported function (original 1.0 version)
+    ExportedFunctionV1 {
         idempotency_key: IdempotencyKey,
         full_function_name: String,
+        #[serde(with = "crate::model::oplog_codec::bincode_values")]
         function_input: Vec<golem_wasm_rpc::Value>,
     },
     ManualUpdate {
         target_version: ComponentVersion,
     },
+    /// Invocation of an exported function
+    ExportedFunction {
+        idempotency_key: IdempotencyKey,
+        full_function_name: String,
+        #[serde(with = "crate::model::oplog_codec::bincode_values")]
+        function_input: Vec<golem_wasm_rpc::Value>,
+        /// Identity of the API gateway client making the invocation, if it authenticated with a
+        /// client certificate
+        #[serde(default)]
+        caller: Option<CallerIdentity>,
+    },
 }
 
 impl WorkerInvocation {
     pub fn is_idempotency_key(&self, key: &IdempotencyKey) -> bool {
-        match self {
-            Self::ExportedFunction {
-                idempotency_key, ..
-            } => idempotency_key == key,
-            _ => false,
-        }
+        self.idempotency_key() == Some(key)
     }
 
     pub fn idempotency_key(&self) -> Option<&IdempotencyKey> {
         match self {
-            Self::ExportedFunction {
+            Self::ExportedFunctionV1 {
+                idempotency_key, ..
+            }
+            | Self::ExportedFunction {
                 idempotency_key, ..
             } => Some(idempotency_key),
             _ => None,
         }
     }
+
+    pub fn caller(&self) -> Option<&CallerIdentity> {
+        match self {
+            Self::ExportedFunction { caller, .. } => caller.as_ref(),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Encode, Decode)]
@@ -1387,6 +1609,25 @@ impl Display for WorkerCreatedAtFilter {
     }
 }
 
+/// Matches the workers having a TTL by their expiry. Workers without a TTL never match.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode, Object)]
+pub struct WorkerExpiresAtFilter {
+    pub comparator: FilterComparator,
+    pub value: Timestamp,
+}
+
+impl WorkerExpiresAtFilter {
+    pub fn new(comparator: FilterComparator, value: Timestamp) -> Self {
+        Self { comparator, value }
+    }
+}
+
+impl Display for WorkerExpiresAtFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expires_at {} {}", self.comparator, self.value)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Encode, Decode, Object)]
 pub struct WorkerEnvFilter {
     pub name: String,
@@ -1488,6 +1729,7 @@ pub enum WorkerFilter {
     Version(WorkerVersionFilter),
     CreatedAt(WorkerCreatedAtFilter),
     Env(WorkerEnvFilter),
+    ExpiresAt(WorkerExpiresAtFilter),
     And(WorkerAndFilter),
     Or(WorkerOrFilter),
     Not(WorkerNotFilter),
@@ -1544,6 +1786,9 @@ impl WorkerFilter {
             WorkerFilter::CreatedAt(WorkerCreatedAtFilter { comparator, value }) => {
                 comparator.matches(&metadata.created_at, &value)
             }
+            WorkerFilter::ExpiresAt(WorkerExpiresAtFilter { comparator, value }) => metadata
+                .expires_at
+                .map_or(false, |expires_at| comparator.matches(&expires_at, &value)),
             WorkerFilter::Status(WorkerStatusFilter { comparator, value }) => {
                 comparator.matches(&metadata.last_known_status.status, &value)
             }
@@ -1606,6 +1851,10 @@ impl WorkerFilter {
         WorkerFilter::CreatedAt(WorkerCreatedAtFilter::new(comparator, value))
     }
 
+    pub fn new_expires_at(comparator: FilterComparator, value: Timestamp) -> Self {
+        WorkerFilter::ExpiresAt(WorkerExpiresAtFilter::new(comparator, value))
+    }
+
     pub fn from(filters: Vec<String>) -> Result<WorkerFilter, String> {
         let mut fs = Vec::new();
         for f in filters {
@@ -1633,6 +1882,9 @@ impl Display for WorkerFilter {
             WorkerFilter::Env(filter) => {
                 write!(f, "{}", filter)
             }
+            WorkerFilter::ExpiresAt(filter) => {
+                write!(f, "{}", filter)
+            }
             WorkerFilter::Not(filter) => {
                 write!(f, "{}", filter)
             }
@@ -1650,44 +1902,7 @@ impl FromStr for WorkerFilter {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let elements = s.split_whitespace().collect::<Vec<&str>>();
-
-        if elements.len() == 3 {
-            let arg = elements[0];
-            let comparator = elements[1];
-            let value = elements[2];
-            match arg {
-                "name" => Ok(WorkerFilter::new_name(
-                    comparator.parse()?,
-                    value.to_string(),
-                )),
-                "version" => Ok(WorkerFilter::new_version(
-                    comparator.parse()?,
-                    value
-                        .parse()
-                        .map_err(|e| format!("Invalid filter value: {}", e))?,
-                )),
-                "status" => Ok(WorkerFilter::new_status(
-                    comparator.parse()?,
-                    value.parse()?,
-                )),
-                "created_at" | "createdAt" => Ok(WorkerFilter::new_created_at(
-                    comparator.parse()?,
-                    value.parse()?,
-                )),
-                _ if arg.starts_with("env.") => {
-                    let name = &arg[4..];
-                    Ok(WorkerFilter::new_env(
-                        name.to_string(),
-                        comparator.parse()?,
-                        value.to_string(),
-                    ))
-                }
-                _ => Err(format!("Invalid filter: {}", s)),
-            }
-        } else {
-            Err(format!("Invalid filter: {}", s))
-        }
+        worker_filter::parse_worker_filter(s)
     }
 }
 
@@ -1724,6 +1939,16 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerFilter> for WorkerFilte
                 golem_api_grpc::proto::golem::worker::worker_filter::Filter::Env(filter) => Ok(
                     WorkerFilter::new_env(filter.name, filter.comparator.try_into()?, filter.value),
                 ),
+                golem_api_grpc::proto::golem::worker::worker_filter::Filter::ExpiresAt(filter) => {
+                    let value = filter
+                        .value
+                        .map(|t| t.into())
+                        .ok_or_else(|| "Missing value".to_string())?;
+                    Ok(WorkerFilter::new_expires_at(
+                        filter.comparator.try_into()?,
+                        value,
+                    ))
+                }
                 golem_api_grpc::proto::golem::worker::worker_filter::Filter::Not(filter) => {
                     let filter = *filter.filter.ok_or_else(|| "Missing filter".to_string())?;
                     Ok(WorkerFilter::new_not(filter.try_into()?))
@@ -1802,6 +2027,14 @@ impl From<WorkerFilter> for golem_api_grpc::proto::golem::worker::WorkerFilter {
                     },
                 )
             }
+            WorkerFilter::ExpiresAt(WorkerExpiresAtFilter { comparator, value }) => {
+                golem_api_grpc::proto::golem::worker::worker_filter::Filter::ExpiresAt(
+                    golem_api_grpc::proto::golem::worker::WorkerExpiresAtFilter {
+                        value: Some(value.into()),
+                        comparator: comparator.into(),
+                    },
+                )
+            }
             WorkerFilter::Not(WorkerNotFilter { filter }) => {
                 let f: golem_api_grpc::proto::golem::worker::WorkerFilter = (*filter).into();
                 golem_api_grpc::proto::golem::worker::worker_filter::Filter::Not(Box::new(
@@ -2227,6 +2460,46 @@ impl WorkerEvent {
             WorkerEvent::Close => None,
         }
     }
+
+    /// Recreates the event recorded as `entry` by `as_oplog_entry`
+    pub fn from_oplog_entry(entry: &OplogEntry) -> Option<WorkerEvent> {
+        match entry {
+            OplogEntry::Log {
+                timestamp,
+                level,
+                context,
+                message,
+            } => {
+                let level = match level {
+                    oplog::LogLevel::Stdout => {
+                        return Some(WorkerEvent::StdOut {
+                            timestamp: *timestamp,
+                            bytes: message.as_bytes().to_vec(),
+                        })
+                    }
+                    oplog::LogLevel::Stderr => {
+                        return Some(WorkerEvent::StdErr {
+                            timestamp: *timestamp,
+                            bytes: message.as_bytes().to_vec(),
+                        })
+                    }
+                    oplog::LogLevel::Trace => LogLevel::Trace,
+                    oplog::LogLevel::Debug => LogLevel::Debug,
+                    oplog::LogLevel::Info => LogLevel::Info,
+                    oplog::LogLevel::Warn => LogLevel::Warn,
+                    oplog::LogLevel::Error => LogLevel::Error,
+                    oplog::LogLevel::Critical => LogLevel::Critical,
+                };
+                Some(WorkerEvent::Log {
+                    timestamp: *timestamp,
+                    level,
+                    context: context.clone(),
+                    message: message.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Display for WorkerEvent {
@@ -2403,6 +2676,48 @@ impl TryFrom<WorkerEvent> for golem_api_grpc::proto::golem::worker::LogEvent {
     }
 }
 
+/// Which of the log events recorded in a worker's oplog are replayed when connecting to it,
+/// before the live events. By default only the live events are streamed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectOptions {
+    /// Replays all the recorded log events
+    pub from_beginning: bool,
+    /// Replays only the last `n` recorded log events
+    pub last_n: Option<u64>,
+}
+
+impl ConnectOptions {
+    pub fn replays_history(&self) -> bool {
+        self.from_beginning || self.last_n.is_some()
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::worker::ConnectOptions> for ConnectOptions {
+    fn from(value: golem_api_grpc::proto::golem::worker::ConnectOptions) -> Self {
+        Self {
+            from_beginning: value.from_beginning,
+            last_n: value.last_n,
+        }
+    }
+}
+
+impl From<ConnectOptions> for golem_api_grpc::proto::golem::worker::ConnectOptions {
+    fn from(value: ConnectOptions) -> Self {
+        Self {
+            from_beginning: value.from_beginning,
+            last_n: value.last_n,
+        }
+    }
+}
+
+/// An event of one of the workers followed by a connection to multiple workers of a component
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedWorkerEvent {
+    pub worker_name: String,
+    pub event: WorkerEvent,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Enum)]
 #[repr(i32)]
 pub enum ComponentType {
@@ -2482,10 +2797,11 @@ mod tests {
 
     use crate::model::oplog::OplogIndex;
     use crate::model::{
-        AccountId, ComponentId, FilterComparator, IdempotencyKey, ShardId, StringFilterComparator,
-        TargetWorkerId, Timestamp, WorkerFilter, WorkerId, WorkerMetadata, WorkerStatus,
-        WorkerStatusRecord,
+        AccountId, ComponentId, FilterComparator, IdempotencyKey, LogLevel, PromiseId, ShardId,
+        StringFilterComparator, TargetWorkerId, Timestamp, WorkerDependent, WorkerEvent,
+        WorkerFilter, WorkerId, WorkerMetadata, WorkerStatus, WorkerStatusRecord,
     };
+    use crate::serialization::{deserialize, serialize};
     use bincode::{Decode, Encode};
     use poem_openapi::types::ToJSON;
     use rand::{thread_rng, Rng};
@@ -2672,6 +2988,7 @@ mod tests {
                 fs_version: 1,
                 ..WorkerStatusRecord::default()
             },
+            expires_at: None,
         };
 
         assert!(
@@ -2728,6 +3045,36 @@ mod tests {
             .matches(&worker_metadata));
     }
 
+    #[test]
+    fn worker_filter_matches_expiry() {
+        let worker_id = WorkerId {
+            worker_name: "worker-1".to_string(),
+            component_id: ComponentId::new_v4(),
+        };
+        let account_id = AccountId {
+            value: "account-1".to_string(),
+        };
+        let without_ttl = WorkerMetadata::default(worker_id.clone(), account_id.clone());
+        let with_ttl = WorkerMetadata {
+            expires_at: Some(Timestamp::from(1_724_000_000_000)),
+            ..WorkerMetadata::default(worker_id, account_id)
+        };
+
+        let expiring_soon = WorkerFilter::new_expires_at(
+            FilterComparator::Less,
+            Timestamp::from(1_725_000_000_000),
+        );
+        assert!(expiring_soon.matches(&with_ttl));
+        assert!(!expiring_soon.matches(&without_ttl));
+
+        let expiring_later = WorkerFilter::new_expires_at(
+            FilterComparator::GreaterEqual,
+            Timestamp::from(1_725_000_000_000),
+        );
+        assert!(!expiring_later.matches(&with_ttl));
+        assert!(!expiring_later.matches(&without_ttl));
+    }
+
     #[test]
     fn target_worker_id_force_shards() {
         let mut rng = thread_rng();
@@ -2823,4 +3170,42 @@ mod tests {
         let deserialized: IdempotencyKey = serde_json::from_str(&serialized).unwrap();
         assert_eq!(key, deserialized);
     }
+
+    #[test]
+    fn worker_events_are_recreated_from_their_oplog_entries() {
+        let events = vec![
+            WorkerEvent::stdout("hello\n".as_bytes().to_vec()),
+            WorkerEvent::stderr("oops\n".as_bytes().to_vec()),
+            WorkerEvent::log(LogLevel::Warn, "context", "message"),
+        ];
+
+        for event in events {
+            let entry = event.as_oplog_entry().unwrap();
+            assert_eq!(WorkerEvent::from_oplog_entry(&entry), Some(event));
+        }
+        assert_eq!(
+            WorkerEvent::from_oplog_entry(&crate::model::oplog::OplogEntry::restart()),
+            None
+        );
+    }
+
+    #[test]
+    fn worker_dependent_serialization_roundtrip() {
+        let worker_id = WorkerId {
+            component_id: ComponentId::from_str("ebb6f8fb-1f2e-4bcd-a37c-4eb65b4a0c33").unwrap(),
+            worker_name: "parent".to_string(),
+        };
+        let dependent = WorkerDependent::Promise(PromiseId {
+            worker_id,
+            oplog_idx: OplogIndex::from_u64(5),
+        });
+
+        let serialized = serialize(&dependent).unwrap();
+        let deserialized: WorkerDependent = deserialize(&serialized).unwrap();
+        assert_eq!(deserialized, dependent);
+        assert_eq!(
+            dependent.to_string(),
+            "promise ebb6f8fb-1f2e-4bcd-a37c-4eb65b4a0c33/parent/5"
+        );
+    }
 }
@@ -24,14 +24,17 @@ use golem_wasm_rpc::{IntoValue, Value};
 use poem_openapi::{Enum, NewType};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
 use crate::config::RetryConfig;
 use crate::model::regions::OplogRegion;
 use crate::model::{
-    AccountId, ComponentVersion, IdempotencyKey, Timestamp, WorkerId, WorkerInvocation,
+    AccountId, CallerIdentity, ComponentVersion, IdempotencyKey, Timestamp, WorkerId,
+    WorkerInvocation,
 };
 
 #[derive(
@@ -155,7 +158,7 @@ impl From<AtomicOplogIndex> for OplogIndex {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PayloadId(pub Uuid);
 
 impl Default for PayloadId {
@@ -229,7 +232,7 @@ impl Display for WorkerResourceId {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Encode, Decode)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Encode, Decode, Serialize, Deserialize)]
 pub struct IndexedResourceKey {
     pub resource_name: String,
     pub resource_params: Vec<String>,
@@ -267,7 +270,37 @@ pub enum LogLevel {
     Critical,
 }
 
-#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+impl LogLevel {
+    /// Whether a message logged with `level` passes a filter set to `self`.
+    ///
+    /// The stdout and stderr channels are never filtered.
+    pub fn allows(&self, level: LogLevel) -> bool {
+        match level {
+            LogLevel::Stdout | LogLevel::Stderr => true,
+            _ => *self as u8 <= level as u8,
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "stdout" => Ok(LogLevel::Stdout),
+            "stderr" => Ok(LogLevel::Stderr),
+            "trace" => Ok(LogLevel::Trace),
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            "critical" => Ok(LogLevel::Critical),
+            _ => Err(format!("Unknown log level: {s}")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode, Serialize, Deserialize)]
 pub enum OplogEntry {
     Create {
         timestamp: Timestamp,
@@ -287,8 +320,8 @@ pub enum OplogEntry {
         response: OplogPayload,
         wrapped_function_type: WrappedFunctionType,
     },
-    /// The worker has been invoked
-    ExportedFunctionInvoked {
+    /// The worker has been invoked (original 1.0 version)
+    ExportedFunctionInvokedV1 {
         timestamp: Timestamp,
         function_name: String,
         request: OplogPayload,
@@ -404,6 +437,60 @@ pub enum OplogEntry {
         response: OplogPayload,
         wrapped_function_type: WrappedFunctionType,
     },
+    /// A pending invocation was cancelled before the worker started executing it
+    CancelPendingInvocation {
+        timestamp: Timestamp,
+        idempotency_key: IdempotencyKey,
+    },
+    /// Replaces the environment variables the worker was created with. The new environment is
+    /// used from the next time the worker gets restarted or updated.
+    ChangeEnvironment {
+        timestamp: Timestamp,
+        env: Vec<(String, String)>,
+    },
+    /// The payloads of the listed entries were replaced by placeholder values of the same type
+    /// through the admin API. Only recorded for auditing, has no effect on execution.
+    Redacted {
+        timestamp: Timestamp,
+        entries: Vec<OplogIndex>,
+        reason: String,
+    },
+    /// An invocation did not complete within the timeout set in its invocation context, and got
+    /// interrupted or, if it was still pending, cancelled
+    InvocationTimedOut {
+        timestamp: Timestamp,
+        idempotency_key: IdempotencyKey,
+        timeout: Duration,
+    },
+    /// A pending invocation was held in the invocation queue, the worker does not start executing
+    /// it until it gets released
+    HoldPendingInvocation {
+        timestamp: Timestamp,
+        idempotency_key: IdempotencyKey,
+    },
+    /// A held pending invocation was released, and gets executed in its turn
+    ReleasePendingInvocation {
+        timestamp: Timestamp,
+        idempotency_key: IdempotencyKey,
+    },
+    /// The worker of a component using component-model async switched to another in-flight
+    /// invocation at an await point. The entries following it, up to the next switch, belong to
+    /// the invocation with the given idempotency key.
+    SwitchInvocation {
+        timestamp: Timestamp,
+        idempotency_key: IdempotencyKey,
+    },
+    /// The worker has been invoked
+    ExportedFunctionInvoked {
+        timestamp: Timestamp,
+        function_name: String,
+        request: OplogPayload,
+        idempotency_key: IdempotencyKey,
+        /// Identity of the API gateway client making the invocation, if it authenticated with a
+        /// client certificate
+        #[serde(default)]
+        caller: Option<CallerIdentity>,
+    },
 }
 
 impl OplogEntry {
@@ -581,6 +668,57 @@ impl OplogEntry {
         }
     }
 
+    pub fn cancel_pending_invocation(idempotency_key: IdempotencyKey) -> OplogEntry {
+        OplogEntry::CancelPendingInvocation {
+            timestamp: Timestamp::now_utc(),
+            idempotency_key,
+        }
+    }
+
+    pub fn change_environment(env: Vec<(String, String)>) -> OplogEntry {
+        OplogEntry::ChangeEnvironment {
+            timestamp: Timestamp::now_utc(),
+            env,
+        }
+    }
+
+    pub fn redacted(entries: Vec<OplogIndex>, reason: String) -> OplogEntry {
+        OplogEntry::Redacted {
+            timestamp: Timestamp::now_utc(),
+            entries,
+            reason,
+        }
+    }
+
+    pub fn invocation_timed_out(idempotency_key: IdempotencyKey, timeout: Duration) -> OplogEntry {
+        OplogEntry::InvocationTimedOut {
+            timestamp: Timestamp::now_utc(),
+            idempotency_key,
+            timeout,
+        }
+    }
+
+    pub fn hold_pending_invocation(idempotency_key: IdempotencyKey) -> OplogEntry {
+        OplogEntry::HoldPendingInvocation {
+            timestamp: Timestamp::now_utc(),
+            idempotency_key,
+        }
+    }
+
+    pub fn release_pending_invocation(idempotency_key: IdempotencyKey) -> OplogEntry {
+        OplogEntry::ReleasePendingInvocation {
+            timestamp: Timestamp::now_utc(),
+            idempotency_key,
+        }
+    }
+
+    pub fn switch_invocation(idempotency_key: IdempotencyKey) -> OplogEntry {
+        OplogEntry::SwitchInvocation {
+            timestamp: Timestamp::now_utc(),
+            idempotency_key,
+        }
+    }
+
     pub fn is_end_atomic_region(&self, idx: OplogIndex) -> bool {
         matches!(self, OplogEntry::EndAtomicRegion { begin_index, .. } if *begin_index == idx)
     }
@@ -630,6 +768,12 @@ impl OplogEntry {
                 | OplogEntry::DescribeResource { .. }
                 | OplogEntry::Log { .. }
                 | OplogEntry::Restart { .. }
+                | OplogEntry::CancelPendingInvocation { .. }
+                | OplogEntry::ChangeEnvironment { .. }
+                | OplogEntry::Redacted { .. }
+                | OplogEntry::InvocationTimedOut { .. }
+                | OplogEntry::HoldPendingInvocation { .. }
+                | OplogEntry::ReleasePendingInvocation { .. }
         )
     }
 
@@ -637,6 +781,7 @@ impl OplogEntry {
         match self {
             OplogEntry::Create { timestamp, .. }
             | OplogEntry::ImportedFunctionInvokedV1 { timestamp, .. }
+            | OplogEntry::ExportedFunctionInvokedV1 { timestamp, .. }
             | OplogEntry::ExportedFunctionInvoked { timestamp, .. }
             | OplogEntry::ExportedFunctionCompleted { timestamp, .. }
             | OplogEntry::Suspend { timestamp }
@@ -660,13 +805,20 @@ impl OplogEntry {
             | OplogEntry::DescribeResource { timestamp, .. }
             | OplogEntry::Log { timestamp, .. }
             | OplogEntry::Restart { timestamp }
-            | OplogEntry::ImportedFunctionInvoked { timestamp, .. } => *timestamp,
+            | OplogEntry::ImportedFunctionInvoked { timestamp, .. }
+            | OplogEntry::CancelPendingInvocation { timestamp, .. }
+            | OplogEntry::ChangeEnvironment { timestamp, .. }
+            | OplogEntry::Redacted { timestamp, .. }
+            | OplogEntry::InvocationTimedOut { timestamp, .. }
+            | OplogEntry::HoldPendingInvocation { timestamp, .. }
+            | OplogEntry::ReleasePendingInvocation { timestamp, .. }
+            | OplogEntry::SwitchInvocation { timestamp, .. } => *timestamp,
         }
     }
 }
 
 /// Describes a pending update
-#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
 pub enum UpdateDescription {
     /// Automatic update by replaying the oplog on the new version
     Automatic { target_version: ComponentVersion },
@@ -694,7 +846,7 @@ pub struct TimestampedUpdateDescription {
     pub description: UpdateDescription,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
 pub enum OplogPayload {
     /// Load the payload from the given byte array
     Inline(Vec<u8>),
@@ -706,7 +858,7 @@ pub enum OplogPayload {
     },
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
 pub enum WrappedFunctionType {
     /// The side-effect reads from the worker's local state (for example local file system,
     /// random generator, etc.)
@@ -728,12 +880,18 @@ pub enum WrappedFunctionType {
 }
 
 /// Describes the error that occurred in the worker
-#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
 pub enum WorkerError {
     Unknown(String),
     InvalidRequest(String),
     StackOverflow,
     OutOfMemory,
+    /// The worker's code trapped
+    Trap(String),
+    /// An operation performed on behalf of the worker timed out
+    Timeout(String),
+    /// Reading or writing the persisted state of the worker failed
+    StorageFailure(String),
 }
 
 impl WorkerError {
@@ -748,6 +906,216 @@ impl WorkerError {
             WorkerError::InvalidRequest(message) => format!("{message}{error_logs}"),
             WorkerError::StackOverflow => format!("Stack overflow{error_logs}"),
             WorkerError::OutOfMemory => format!("Out of memory{error_logs}"),
+            WorkerError::Trap(message) => format!("{message}{error_logs}"),
+            WorkerError::Timeout(message) => format!("{message}{error_logs}"),
+            WorkerError::StorageFailure(message) => format!("{message}{error_logs}"),
+        }
+    }
+
+    /// Classifies the error by its cause, which is decided when the error is recorded
+    pub fn kind(&self) -> WorkerErrorKind {
+        match self {
+            WorkerError::StackOverflow | WorkerError::Trap(_) => WorkerErrorKind::GuestTrap,
+            WorkerError::OutOfMemory => WorkerErrorKind::OutOfMemory,
+            WorkerError::Timeout(_) => WorkerErrorKind::Timeout,
+            WorkerError::StorageFailure(_) => WorkerErrorKind::StorageFailure,
+            WorkerError::InvalidRequest(_) | WorkerError::Unknown(_) => WorkerErrorKind::HostError,
+        }
+    }
+
+    /// A stable identifier of the error, equal for errors that only differ in details such as
+    /// numbers, identifiers and code addresses, so similar failures of different workers can be
+    /// grouped together.
+    pub fn fingerprint(&self) -> String {
+        let message = match self {
+            WorkerError::Unknown(message) => normalize_error_message(message),
+            WorkerError::InvalidRequest(message)
+            | WorkerError::Trap(message)
+            | WorkerError::Timeout(message)
+            | WorkerError::StorageFailure(message) => normalize_error_message(message),
+            WorkerError::StackOverflow => "stack overflow".to_string(),
+            WorkerError::OutOfMemory => "out of memory".to_string(),
+        };
+
+        // FNV-1a, as the fingerprint must not change between releases
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in format!("{:?}\n{message}", self.kind()).bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        format!("{hash:016x}")
+    }
+}
+
+/// Removes the parts of an error message that vary between occurrences of the same error:
+/// hexadecimal addresses, UUIDs and other numbers, and whitespace.
+fn normalize_error_message(message: &str) -> String {
+    message
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .map(normalize_error_word)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn normalize_error_word(word: &str) -> String {
+    let trimmed = word.trim_matches(|c: char| !c.is_alphanumeric());
+    if Uuid::parse_str(trimmed).is_ok() {
+        return word.replace(trimmed, "<uuid>");
+    }
+
+    let mut result = String::with_capacity(word.len());
+    let mut chars = word.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '0' && chars.peek() == Some(&'x') {
+            chars.next();
+            while chars.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                chars.next();
+            }
+            result.push_str("0x?");
+        } else if c.is_ascii_digit() {
+            while chars.peek().is_some_and(|c| c.is_ascii_digit()) {
+                chars.next();
+            }
+            result.push('N');
+        } else {
+            result.push(c);
         }
     }
+    result
+}
+
+/// Broad classification of worker failures
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Enum)]
+pub enum WorkerErrorKind {
+    /// The worker's code trapped, for example by panicking or overflowing its stack
+    GuestTrap,
+    /// A host function or the executor failed while running the worker
+    HostError,
+    /// The worker could not allocate more memory
+    OutOfMemory,
+    /// An operation performed on behalf of the worker timed out
+    Timeout,
+    /// Reading or writing the worker's persisted state failed
+    StorageFailure,
+}
+
+impl From<WorkerErrorKind> for golem_api_grpc::proto::golem::worker::WorkerErrorKind {
+    fn from(value: WorkerErrorKind) -> Self {
+        match value {
+            WorkerErrorKind::GuestTrap => Self::GuestTrap,
+            WorkerErrorKind::HostError => Self::HostError,
+            WorkerErrorKind::OutOfMemory => Self::OutOfMemory,
+            WorkerErrorKind::Timeout => Self::Timeout,
+            WorkerErrorKind::StorageFailure => Self::StorageFailure,
+        }
+    }
+}
+
+impl TryFrom<i32> for WorkerErrorKind {
+    type Error = String;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        golem_api_grpc::proto::golem::worker::WorkerErrorKind::try_from(value)
+            .map_err(|_| format!("Unknown worker error kind: {value}"))?
+            .try_into()
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerErrorKind> for WorkerErrorKind {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::WorkerErrorKind,
+    ) -> Result<Self, Self::Error> {
+        match value {
+            golem_api_grpc::proto::golem::worker::WorkerErrorKind::Unspecified => {
+                Err("Unspecified worker error kind".to_string())
+            }
+            golem_api_grpc::proto::golem::worker::WorkerErrorKind::GuestTrap => Ok(Self::GuestTrap),
+            golem_api_grpc::proto::golem::worker::WorkerErrorKind::HostError => Ok(Self::HostError),
+            golem_api_grpc::proto::golem::worker::WorkerErrorKind::OutOfMemory => {
+                Ok(Self::OutOfMemory)
+            }
+            golem_api_grpc::proto::golem::worker::WorkerErrorKind::Timeout => Ok(Self::Timeout),
+            golem_api_grpc::proto::golem::worker::WorkerErrorKind::StorageFailure => {
+                Ok(Self::StorageFailure)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::str::FromStr;
+
+    use crate::model::oplog::{LogLevel, WorkerError, WorkerErrorKind};
+
+    #[test]
+    fn log_levels_filter_lower_levels() {
+        assert!(LogLevel::Warn.allows(LogLevel::Warn));
+        assert!(LogLevel::Warn.allows(LogLevel::Error));
+        assert!(LogLevel::Warn.allows(LogLevel::Critical));
+        assert!(!LogLevel::Warn.allows(LogLevel::Info));
+        assert!(!LogLevel::Warn.allows(LogLevel::Trace));
+        assert!(LogLevel::Trace.allows(LogLevel::Debug));
+    }
+
+    #[test]
+    fn log_levels_never_filter_stdout_and_stderr() {
+        assert!(LogLevel::Critical.allows(LogLevel::Stdout));
+        assert!(LogLevel::Critical.allows(LogLevel::Stderr));
+    }
+
+    #[test]
+    fn log_levels_are_parsed_case_insensitively() {
+        assert_eq!(LogLevel::from_str("warn"), Ok(LogLevel::Warn));
+        assert_eq!(LogLevel::from_str("DEBUG"), Ok(LogLevel::Debug));
+        assert!(LogLevel::from_str("verbose").is_err());
+    }
+
+    #[test]
+    fn worker_error_fingerprints_ignore_varying_details() {
+        let e1 = WorkerError::Trap(
+            "error while executing at wasm backtrace:\n    0: 0x1b2f - <unknown>!handle\n\nCaused by:\n    wasm trap: wasm `unreachable` instruction executed (item 12)".to_string(),
+        );
+        let e2 = WorkerError::Trap(
+            "error while executing at wasm backtrace:\n    0: 0x2c41 - <unknown>!handle\n\nCaused by:\n    wasm trap: wasm `unreachable` instruction executed (item 7)".to_string(),
+        );
+        let e3 = WorkerError::Trap(
+            "error while executing at wasm backtrace:\n    0: 0x2c41 - <unknown>!update\n\nCaused by:\n    wasm trap: wasm `unreachable` instruction executed (item 7)".to_string(),
+        );
+
+        assert_eq!(e1.kind(), WorkerErrorKind::GuestTrap);
+        assert_eq!(e1.fingerprint(), e2.fingerprint());
+        assert_ne!(e1.fingerprint(), e3.fingerprint());
+    }
+
+    #[test]
+    fn worker_error_kinds() {
+        assert_eq!(
+            WorkerError::StorageFailure("connection reset".to_string()).kind(),
+            WorkerErrorKind::StorageFailure
+        );
+        assert_eq!(
+            WorkerError::Timeout("Invocation timed out after 100ms".to_string()).kind(),
+            WorkerErrorKind::Timeout
+        );
+        // Only the recorded variant decides the kind, not the message
+        assert_eq!(
+            WorkerError::Unknown("Remote call timed out".to_string()).kind(),
+            WorkerErrorKind::HostError
+        );
+        assert_eq!(
+            WorkerError::OutOfMemory.kind(),
+            WorkerErrorKind::OutOfMemory
+        );
+    }
 }
@@ -0,0 +1,470 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Encodings of the stored oplog entries.
+//!
+//! Every encoded entry starts with a header byte identifying the codec, followed by the version
+//! of the `OplogEntry` schema it was encoded with. Entries are always decoded by the codec their
+//! header names, independently of the encoding configured for writing, so oplogs written with
+//! different encodings remain readable. Entries of older schema versions are upgraded while
+//! decoding them by the migrations in [`crate::model::oplog_migration`].
+//!
+//! The archived oplog chunks hold their entries encoded the same way, one by one.
+
+use bytes::{BufMut, Bytes, BytesMut};
+use golem_api_grpc::proto::golem::worker::stored;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::model::oplog::OplogEntry;
+use crate::model::oplog_migration::{OplogMigration, MIGRATIONS};
+use crate::serialization::{try_deserialize, SERIALIZATION_VERSION_V2};
+
+/// Version of the `OplogEntry` schema the entries are encoded with. Increase it, and add a
+/// migration for the previous version, when changing `OplogEntry` in a way which is not
+/// backward compatible.
+pub const OPLOG_SCHEMA_VERSION: u8 = 1;
+
+/// bincode 2 with bincode::config::standard(), without a schema version. It is the same as the
+/// generic `SERIALIZATION_VERSION_V2`, so oplogs written before the codecs were introduced are
+/// decoded by it.
+pub const OPLOG_ENCODING_BINCODE_V2: u8 = SERIALIZATION_VERSION_V2;
+
+/// bincode 2 with bincode::config::standard()
+pub const OPLOG_ENCODING_BINCODE_V3: u8 = 3;
+
+/// serde_json encoding of `OplogEntry`, without a schema version
+pub const OPLOG_ENCODING_JSON_V1: u8 = 0x10;
+
+/// serde_json encoding of `OplogEntry`
+pub const OPLOG_ENCODING_JSON_V2: u8 = 0x11;
+
+/// protobuf encoding of `OplogEntry` as `golem.worker.stored.OplogEntry`
+pub const OPLOG_ENCODING_PROTOBUF_V1: u8 = 0x20;
+
+/// Header of the oplog chunks holding a list of entries, each encoded by [`encode`]. Chunks
+/// written before have the header of the generic `SERIALIZATION_VERSION_V2`.
+pub const OPLOG_CHUNK_ENCODED_ENTRIES: u8 = 0x30;
+
+/// The schema version of the entries written without a schema version
+const UNTAGGED_SCHEMA_VERSION: u8 = 1;
+
+/// The encoding used for writing new oplog entries
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OplogEncoding {
+    /// Compact binary encoding
+    #[default]
+    Bincode,
+    /// Human readable encoding, useful for inspecting the oplogs directly in the storage
+    Json,
+    /// Binary encoding with a published schema, for tools reading the oplogs directly from the
+    /// storage
+    Protobuf,
+}
+
+impl OplogEncoding {
+    pub fn codec(&self) -> &'static dyn OplogEntryCodec {
+        match self {
+            OplogEncoding::Bincode => &BincodeCodec,
+            OplogEncoding::Json => &JsonCodec,
+            OplogEncoding::Protobuf => &ProtobufCodec,
+        }
+    }
+}
+
+pub trait OplogEntryCodec: Send + Sync {
+    /// The header byte written in front of the entries encoded by this codec
+    fn header(&self) -> u8;
+
+    fn encode(&self, entry: &OplogEntry) -> Result<Vec<u8>, String>;
+
+    fn decode(&self, data: &[u8]) -> Result<OplogEntry, String>;
+}
+
+pub struct BincodeCodec;
+
+impl OplogEntryCodec for BincodeCodec {
+    fn header(&self) -> u8 {
+        OPLOG_ENCODING_BINCODE_V3
+    }
+
+    fn encode(&self, entry: &OplogEntry) -> Result<Vec<u8>, String> {
+        bincode::encode_to_vec(entry, bincode::config::standard())
+            .map_err(|e| format!("Failed to serialize oplog entry: {e}"))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<OplogEntry, String> {
+        let (entry, _) = bincode::decode_from_slice(data, bincode::config::standard())
+            .map_err(|e| format!("Failed to deserialize oplog entry: {e}"))?;
+        Ok(entry)
+    }
+}
+
+pub struct JsonCodec;
+
+impl OplogEntryCodec for JsonCodec {
+    fn header(&self) -> u8 {
+        OPLOG_ENCODING_JSON_V2
+    }
+
+    fn encode(&self, entry: &OplogEntry) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(entry).map_err(|e| format!("Failed to serialize oplog entry: {e}"))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<OplogEntry, String> {
+        serde_json::from_slice(data).map_err(|e| format!("Failed to deserialize oplog entry: {e}"))
+    }
+}
+
+pub struct ProtobufCodec;
+
+impl OplogEntryCodec for ProtobufCodec {
+    fn header(&self) -> u8 {
+        OPLOG_ENCODING_PROTOBUF_V1
+    }
+
+    fn encode(&self, entry: &OplogEntry) -> Result<Vec<u8>, String> {
+        Ok(stored::OplogEntry::from(entry.clone()).encode_to_vec())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<OplogEntry, String> {
+        stored::OplogEntry::decode(data)
+            .map_err(|e| format!("Failed to deserialize oplog entry: {e}"))?
+            .try_into()
+            .map_err(|e| format!("Failed to deserialize oplog entry: {e}"))
+    }
+}
+
+/// An oplog entry decoded from the storage
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedOplogEntry {
+    pub entry: OplogEntry,
+    /// The schema version the entry was stored with
+    pub schema_version: u8,
+}
+
+impl DecodedOplogEntry {
+    /// Whether the entry was upgraded from an older schema version, so storing it again would
+    /// spare upgrading it on the next read
+    pub fn is_migrated(&self) -> bool {
+        self.schema_version < OPLOG_SCHEMA_VERSION
+    }
+}
+
+/// Encodes an oplog entry with the given encoding, prefixed by the codec's header and the schema
+/// version
+pub fn encode(encoding: OplogEncoding, entry: &OplogEntry) -> Result<Bytes, String> {
+    let codec = encoding.codec();
+    let data = codec.encode(entry)?;
+    let mut bytes = BytesMut::with_capacity(data.len() + 2);
+    bytes.put_u8(codec.header());
+    bytes.put_u8(OPLOG_SCHEMA_VERSION);
+    bytes.extend_from_slice(&data);
+    Ok(bytes.freeze())
+}
+
+/// Decodes an oplog entry written with any of the supported encodings and schema versions
+pub fn decode(bytes: &[u8]) -> Result<OplogEntry, String> {
+    decode_versioned(bytes).map(|decoded| decoded.entry)
+}
+
+/// Decodes an oplog entry written with any of the supported encodings and schema versions,
+/// keeping track of the schema version it was stored with
+pub fn decode_versioned(bytes: &[u8]) -> Result<DecodedOplogEntry, String> {
+    decode_with_migrations(bytes, OPLOG_SCHEMA_VERSION, MIGRATIONS)
+}
+
+pub(crate) fn decode_with_migrations(
+    bytes: &[u8],
+    current_schema_version: u8,
+    migrations: &[&dyn OplogMigration],
+) -> Result<DecodedOplogEntry, String> {
+    let (encoding, schema_version, data) = split_header(bytes)?;
+    let entry = if schema_version == current_schema_version {
+        encoding.codec().decode(data)?
+    } else if schema_version > current_schema_version {
+        return Err(format!(
+            "Oplog entry has schema version {schema_version}, newer than the supported {current_schema_version}"
+        ));
+    } else {
+        let migration = migrations
+            .iter()
+            .find(|migration| migration.source_version() == schema_version)
+            .ok_or(format!(
+                "No migration for oplog entries of schema version {schema_version}"
+            ))?;
+        migration.migrate(encoding, data)?
+    };
+    Ok(DecodedOplogEntry {
+        entry,
+        schema_version,
+    })
+}
+
+fn split_header(bytes: &[u8]) -> Result<(OplogEncoding, u8, &[u8]), String> {
+    let (header, data) = bytes
+        .split_first()
+        .ok_or("Failed to deserialize empty oplog entry".to_string())?;
+    let encoding = match *header {
+        OPLOG_ENCODING_BINCODE_V2 => {
+            return Ok((OplogEncoding::Bincode, UNTAGGED_SCHEMA_VERSION, data))
+        }
+        OPLOG_ENCODING_BINCODE_V3 => OplogEncoding::Bincode,
+        OPLOG_ENCODING_JSON_V1 => return Ok((OplogEncoding::Json, UNTAGGED_SCHEMA_VERSION, data)),
+        OPLOG_ENCODING_JSON_V2 => OplogEncoding::Json,
+        OPLOG_ENCODING_PROTOBUF_V1 => OplogEncoding::Protobuf,
+        _ => return Err(format!("Unknown oplog entry encoding: {header}")),
+    };
+    let (schema_version, data) = data
+        .split_first()
+        .ok_or("Missing schema version of oplog entry".to_string())?;
+    Ok((encoding, *schema_version, data))
+}
+
+/// Encodes a chunk of archived oplog entries, each of them with the given encoding
+pub fn encode_chunk(encoding: OplogEncoding, entries: &[OplogEntry]) -> Result<Bytes, String> {
+    let encoded = entries
+        .iter()
+        .map(|entry| encode(encoding, entry).map(|bytes| bytes.to_vec()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let data = bincode::encode_to_vec(encoded, bincode::config::standard())
+        .map_err(|e| format!("Failed to serialize oplog chunk: {e}"))?;
+    let mut bytes = BytesMut::with_capacity(data.len() + 1);
+    bytes.put_u8(OPLOG_CHUNK_ENCODED_ENTRIES);
+    bytes.extend_from_slice(&data);
+    Ok(bytes.freeze())
+}
+
+/// Decodes a chunk of archived oplog entries, including the chunks written before the entries
+/// of the chunks were encoded one by one
+pub fn decode_chunk(bytes: &[u8]) -> Result<Vec<OplogEntry>, String> {
+    match bytes.split_first() {
+        Some((&OPLOG_CHUNK_ENCODED_ENTRIES, data)) => {
+            let (encoded, _): (Vec<Vec<u8>>, _) =
+                bincode::decode_from_slice(data, bincode::config::standard())
+                    .map_err(|e| format!("Failed to deserialize oplog chunk: {e}"))?;
+            encoded.iter().map(|entry| decode(entry)).collect()
+        }
+        _ => try_deserialize(bytes)?.ok_or("Unknown oplog chunk encoding".to_string()),
+    }
+}
+
+/// Decodes an oplog entry written with any of the supported encodings into JSON, for tools
+/// inspecting the stored oplogs
+pub fn decode_to_json(bytes: &[u8]) -> Result<serde_json::Value, String> {
+    let entry = decode(bytes)?;
+    serde_json::to_value(entry).map_err(|e| format!("Failed to convert oplog entry to JSON: {e}"))
+}
+
+/// Serde representation of `golem_wasm_rpc::Value` lists, which have no serde support on their
+/// own, as their bincode encoding
+pub mod bincode_values {
+    use golem_wasm_rpc::Value;
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(values: &[Value], serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = bincode::encode_to_vec(values, bincode::config::standard())
+            .map_err(S::Error::custom)?;
+        serializer.serialize_bytes(&bytes)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Value>, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        let (values, _) = bincode::decode_from_slice(&bytes, bincode::config::standard())
+            .map_err(D::Error::custom)?;
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::time::Duration;
+
+    use golem_wasm_rpc::Value;
+
+    use crate::config::RetryConfig;
+    use crate::model::oplog::{
+        LogLevel, OplogEntry, OplogPayload, PayloadId, UpdateDescription, WorkerError,
+        WrappedFunctionType,
+    };
+    use crate::model::oplog_codec::{
+        decode, decode_chunk, decode_to_json, encode, encode_chunk, OplogEncoding,
+        OPLOG_ENCODING_JSON_V1,
+    };
+    use crate::model::{
+        AccountId, CallerIdentity, ComponentId, IdempotencyKey, Timestamp, WorkerId,
+        WorkerInvocation,
+    };
+    use crate::serialization::serialize;
+
+    fn entries() -> Vec<OplogEntry> {
+        let timestamp = Timestamp::from(1_724_000_000_123);
+        vec![
+            OplogEntry::PendingWorkerInvocation {
+                timestamp,
+                invocation: WorkerInvocation::ExportedFunction {
+                    idempotency_key: IdempotencyKey::fresh(),
+                    full_function_name: "golem:it/api.{f}".to_string(),
+                    function_input: vec![Value::U64(1), Value::String("x".to_string())],
+                    caller: Some(CallerIdentity {
+                        subject: "CN=client".to_string(),
+                        uris: vec!["spiffe://golem/client".to_string()],
+                        dns_names: vec![],
+                    }),
+                },
+            },
+            OplogEntry::PendingWorkerInvocation {
+                timestamp,
+                invocation: WorkerInvocation::ExportedFunctionV1 {
+                    idempotency_key: IdempotencyKey::fresh(),
+                    full_function_name: "golem:it/api.{f}".to_string(),
+                    function_input: vec![],
+                },
+            },
+            OplogEntry::ExportedFunctionInvokedV1 {
+                timestamp,
+                function_name: "golem:it/api.{f}".to_string(),
+                request: OplogPayload::Inline(vec![1]),
+                idempotency_key: IdempotencyKey::fresh(),
+            },
+            OplogEntry::ExportedFunctionInvoked {
+                timestamp,
+                function_name: "golem:it/api.{f}".to_string(),
+                request: OplogPayload::Inline(vec![1]),
+                idempotency_key: IdempotencyKey::fresh(),
+                caller: Some(CallerIdentity {
+                    subject: "CN=client".to_string(),
+                    uris: vec![],
+                    dns_names: vec!["client.golem".to_string()],
+                }),
+            },
+            OplogEntry::ImportedFunctionInvoked {
+                timestamp,
+                function_name: "golem::api::get-oplog-index".to_string(),
+                request: OplogPayload::Inline(vec![1, 2, 3]),
+                response: OplogPayload::Inline(vec![]),
+                wrapped_function_type: WrappedFunctionType::WriteRemoteBatched(None),
+            },
+            OplogEntry::Exited { timestamp },
+            OplogEntry::Create {
+                timestamp,
+                worker_id: WorkerId {
+                    component_id: ComponentId::new_v4(),
+                    worker_name: "worker".to_string(),
+                },
+                component_version: 2,
+                args: vec!["arg".to_string()],
+                env: vec![("KEY".to_string(), "value".to_string())],
+                account_id: AccountId {
+                    value: "account".to_string(),
+                },
+                parent: None,
+                component_size: 100,
+                initial_total_linear_memory_size: 200,
+            },
+            OplogEntry::Error {
+                timestamp,
+                error: WorkerError::StackOverflow,
+            },
+            OplogEntry::ChangeRetryPolicy {
+                timestamp,
+                new_policy: RetryConfig::max_attempts_5(),
+            },
+            OplogEntry::PendingUpdate {
+                timestamp,
+                description: UpdateDescription::SnapshotBased {
+                    target_version: 3,
+                    payload: OplogPayload::External {
+                        payload_id: PayloadId::new(),
+                        md5_hash: vec![4, 5, 6],
+                    },
+                },
+            },
+            OplogEntry::Log {
+                timestamp,
+                level: LogLevel::Warn,
+                context: "context".to_string(),
+                message: "message".to_string(),
+            },
+            OplogEntry::InvocationTimedOut {
+                timestamp,
+                idempotency_key: IdempotencyKey::fresh(),
+                timeout: Duration::from_millis(1500),
+            },
+        ]
+    }
+
+    const ENCODINGS: [OplogEncoding; 3] = [
+        OplogEncoding::Bincode,
+        OplogEncoding::Json,
+        OplogEncoding::Protobuf,
+    ];
+
+    #[test]
+    fn roundtrip() {
+        for encoding in ENCODINGS {
+            for entry in entries() {
+                let encoded = encode(encoding, &entry).unwrap();
+                assert_eq!(decode(&encoded).unwrap(), entry);
+            }
+        }
+    }
+
+    #[test]
+    fn chunk_roundtrip() {
+        let entries = entries();
+        for encoding in ENCODINGS {
+            let encoded = encode_chunk(encoding, &entries).unwrap();
+            assert_eq!(decode_chunk(&encoded).unwrap(), entries);
+        }
+    }
+
+    #[test]
+    fn decodes_chunks_written_before_codecs() {
+        let entries = entries();
+        let encoded = serialize(&entries).unwrap();
+        assert_eq!(decode_chunk(&encoded).unwrap(), entries);
+    }
+
+    #[test]
+    fn decodes_entries_written_before_codecs() {
+        for entry in entries() {
+            let encoded = serialize(&entry).unwrap();
+            assert_eq!(decode(&encoded).unwrap(), entry);
+        }
+    }
+
+    #[test]
+    fn decodes_json_entries_written_before_schema_versions() {
+        for entry in entries() {
+            let mut encoded = vec![OPLOG_ENCODING_JSON_V1];
+            encoded.extend(serde_json::to_vec(&entry).unwrap());
+            assert_eq!(decode(&encoded).unwrap(), entry);
+        }
+    }
+
+    #[test]
+    fn decodes_binary_entries_to_json() {
+        let entry = OplogEntry::Exited {
+            timestamp: Timestamp::from(1_724_000_000_123),
+        };
+        let encoded = encode(OplogEncoding::Bincode, &entry).unwrap();
+        let json = decode_to_json(&encoded).unwrap();
+        assert!(json.get("Exited").is_some());
+    }
+}
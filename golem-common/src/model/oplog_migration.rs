@@ -0,0 +1,161 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Migrations of the persisted oplog entries from older versions of the `OplogEntry` schema.
+//!
+//! Entries are upgraded lazily, when they are decoded. When the schema version is increased, the
+//! previous `OplogEntry` definition is kept as a legacy type in a migration, which decodes the
+//! stored entries of that version and converts them to the current `OplogEntry`. The migrations
+//! of even older versions are updated to convert their results further, so each of them
+//! upgrades directly to the current schema.
+
+use bincode::Decode;
+use golem_api_grpc::proto::golem::worker::stored;
+use prost::Message;
+use serde::de::DeserializeOwned;
+
+use crate::model::oplog::OplogEntry;
+use crate::model::oplog_codec::OplogEncoding;
+
+/// Upgrades the stored oplog entries of an older schema version
+pub trait OplogMigration: Send + Sync {
+    /// The schema version of the stored entries this migration upgrades
+    fn source_version(&self) -> u8;
+
+    /// Decodes an entry stored with the source schema version and upgrades it to the current one
+    fn migrate(&self, encoding: OplogEncoding, data: &[u8]) -> Result<OplogEntry, String>;
+}
+
+/// The migrations of all the supported older schema versions
+pub static MIGRATIONS: &[&dyn OplogMigration] = &[];
+
+/// Decodes an entry of an older schema version with the encoding it was stored with.
+///
+/// The protobuf schema only changes in backward compatible ways, so the legacy types are
+/// converted from the current protobuf representation of the entries.
+pub fn decode_legacy<T>(encoding: OplogEncoding, data: &[u8]) -> Result<T, String>
+where
+    T: Decode + DeserializeOwned + TryFrom<stored::OplogEntry, Error = String>,
+{
+    match encoding {
+        OplogEncoding::Bincode => bincode::decode_from_slice(data, bincode::config::standard())
+            .map(|(value, _)| value)
+            .map_err(|e| format!("Failed to deserialize legacy oplog entry: {e}")),
+        OplogEncoding::Json => serde_json::from_slice(data)
+            .map_err(|e| format!("Failed to deserialize legacy oplog entry: {e}")),
+        OplogEncoding::Protobuf => stored::OplogEntry::decode(data)
+            .map_err(|e| format!("Failed to deserialize legacy oplog entry: {e}"))?
+            .try_into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::oplog::{OplogEntry, WorkerError};
+    use crate::model::oplog_codec::{
+        decode_with_migrations, encode, OplogEncoding, OPLOG_ENCODING_JSON_V1, OPLOG_SCHEMA_VERSION,
+    };
+    use crate::model::oplog_migration::{decode_legacy, OplogMigration};
+    use crate::model::Timestamp;
+    use crate::serialization::serialize;
+
+    /// Upgrades the entries of the current schema version the way the migration added when the
+    /// version is next increased would, decoding them as the `OplogEntry` they were stored as
+    struct CurrentSchemaMigration;
+
+    impl OplogMigration for CurrentSchemaMigration {
+        fn source_version(&self) -> u8 {
+            OPLOG_SCHEMA_VERSION
+        }
+
+        fn migrate(&self, encoding: OplogEncoding, data: &[u8]) -> Result<OplogEntry, String> {
+            match decode_legacy(encoding, data)? {
+                OplogEntry::Error {
+                    timestamp,
+                    error: WorkerError::Unknown(message),
+                } => Ok(OplogEntry::Error {
+                    timestamp,
+                    error: WorkerError::Unknown(format!("migrated: {message}")),
+                }),
+                entry => Ok(entry),
+            }
+        }
+    }
+
+    /// The entry as stored by each of the released encodings
+    fn stored(entry: &OplogEntry) -> Vec<Vec<u8>> {
+        let mut untagged_json = vec![OPLOG_ENCODING_JSON_V1];
+        untagged_json.extend(serde_json::to_vec(entry).unwrap());
+        vec![
+            serialize(entry).unwrap().to_vec(),
+            untagged_json,
+            encode(OplogEncoding::Bincode, entry).unwrap().to_vec(),
+            encode(OplogEncoding::Json, entry).unwrap().to_vec(),
+            encode(OplogEncoding::Protobuf, entry).unwrap().to_vec(),
+        ]
+    }
+
+    #[test]
+    fn stored_entries_are_migrated() {
+        let timestamp = Timestamp::from(1_724_000_000_123);
+        let entry = OplogEntry::Error {
+            timestamp,
+            error: WorkerError::Unknown("failure".to_string()),
+        };
+        let expected = OplogEntry::Error {
+            timestamp,
+            error: WorkerError::Unknown("migrated: failure".to_string()),
+        };
+
+        for bytes in stored(&entry) {
+            let decoded = decode_with_migrations(
+                &bytes,
+                OPLOG_SCHEMA_VERSION + 1,
+                &[&CurrentSchemaMigration],
+            )
+            .unwrap();
+            assert_eq!(decoded.entry, expected);
+            assert_eq!(decoded.schema_version, OPLOG_SCHEMA_VERSION);
+        }
+    }
+
+    #[test]
+    fn current_entries_are_not_migrated() {
+        let entry = OplogEntry::Error {
+            timestamp: Timestamp::from(1_724_000_000_123),
+            error: WorkerError::Unknown("failure".to_string()),
+        };
+
+        for bytes in stored(&entry) {
+            let decoded =
+                decode_with_migrations(&bytes, OPLOG_SCHEMA_VERSION, &[&CurrentSchemaMigration])
+                    .unwrap();
+            assert_eq!(decoded.entry, entry);
+        }
+    }
+
+    #[test]
+    fn entries_without_migration_are_rejected() {
+        let bytes = encode(OplogEncoding::Bincode, &OplogEntry::nop()).unwrap();
+        assert!(decode_with_migrations(&bytes, OPLOG_SCHEMA_VERSION + 1, &[]).is_err());
+    }
+
+    #[test]
+    fn newer_entries_are_rejected() {
+        let bytes = encode(OplogEncoding::Bincode, &OplogEntry::nop()).unwrap();
+        assert!(decode_with_migrations(&bytes, OPLOG_SCHEMA_VERSION - 1, &[]).is_err());
+    }
+}
@@ -0,0 +1,821 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions between the oplog entries and their lossless protobuf representation in
+//! `golem.worker.stored`, used by the protobuf oplog encoding.
+
+use std::time::Duration;
+
+use golem_api_grpc::proto::golem::common::Empty;
+use golem_api_grpc::proto::golem::worker::stored;
+use golem_api_grpc::proto::golem::worker::stored::oplog_entry::Entry;
+use golem_wasm_rpc::Value;
+use uuid::Uuid;
+
+use crate::config::RetryConfig;
+use crate::model::oplog::{
+    IndexedResourceKey, LogLevel, OplogEntry, OplogIndex, OplogPayload, PayloadId,
+    UpdateDescription, WorkerError, WorkerResourceId, WrappedFunctionType,
+};
+use crate::model::regions::OplogRegion;
+use crate::model::{Timestamp, WorkerId, WorkerInvocation};
+
+impl From<OplogEntry> for stored::OplogEntry {
+    fn from(value: OplogEntry) -> Self {
+        let (timestamp, entry) = match value {
+            OplogEntry::Create {
+                timestamp,
+                worker_id,
+                component_version,
+                args,
+                env,
+                account_id,
+                parent,
+                component_size,
+                initial_total_linear_memory_size,
+            } => (
+                timestamp,
+                Entry::Create(stored::Create {
+                    worker_id: Some(worker_id.into()),
+                    component_version,
+                    args,
+                    env: env_to_proto(env),
+                    account_id: Some(account_id.into()),
+                    parent: parent.map(|parent| parent.into()),
+                    component_size,
+                    initial_total_linear_memory_size,
+                }),
+            ),
+            OplogEntry::ImportedFunctionInvokedV1 {
+                timestamp,
+                function_name,
+                response,
+                wrapped_function_type,
+            } => (
+                timestamp,
+                Entry::ImportedFunctionInvokedV1(stored::ImportedFunctionInvokedV1 {
+                    function_name,
+                    response: Some(response.into()),
+                    wrapped_function_type: Some(wrapped_function_type.into()),
+                }),
+            ),
+            OplogEntry::ExportedFunctionInvokedV1 {
+                timestamp,
+                function_name,
+                request,
+                idempotency_key,
+            } => (
+                timestamp,
+                Entry::ExportedFunctionInvokedV1(stored::ExportedFunctionInvoked {
+                    function_name,
+                    request: Some(request.into()),
+                    idempotency_key: Some(idempotency_key.into()),
+                    caller: None,
+                }),
+            ),
+            OplogEntry::ExportedFunctionCompleted {
+                timestamp,
+                response,
+                consumed_fuel,
+            } => (
+                timestamp,
+                Entry::ExportedFunctionCompleted(stored::ExportedFunctionCompleted {
+                    response: Some(response.into()),
+                    consumed_fuel,
+                }),
+            ),
+            OplogEntry::Suspend { timestamp } => (timestamp, Entry::Suspend(Empty {})),
+            OplogEntry::Error { timestamp, error } => (timestamp, Entry::Error(error.into())),
+            OplogEntry::NoOp { timestamp } => (timestamp, Entry::NoOp(Empty {})),
+            OplogEntry::Jump { timestamp, jump } => (
+                timestamp,
+                Entry::Jump(stored::Jump {
+                    start: jump.start.into(),
+                    end: jump.end.into(),
+                }),
+            ),
+            OplogEntry::Interrupted { timestamp } => (timestamp, Entry::Interrupted(Empty {})),
+            OplogEntry::Exited { timestamp } => (timestamp, Entry::Exited(Empty {})),
+            OplogEntry::ChangeRetryPolicy {
+                timestamp,
+                new_policy,
+            } => (timestamp, Entry::ChangeRetryPolicy(new_policy.into())),
+            OplogEntry::BeginAtomicRegion { timestamp } => {
+                (timestamp, Entry::BeginAtomicRegion(Empty {}))
+            }
+            OplogEntry::EndAtomicRegion {
+                timestamp,
+                begin_index,
+            } => (
+                timestamp,
+                Entry::EndAtomicRegion(stored::EndRegion {
+                    begin_index: begin_index.into(),
+                }),
+            ),
+            OplogEntry::BeginRemoteWrite { timestamp } => {
+                (timestamp, Entry::BeginRemoteWrite(Empty {}))
+            }
+            OplogEntry::EndRemoteWrite {
+                timestamp,
+                begin_index,
+            } => (
+                timestamp,
+                Entry::EndRemoteWrite(stored::EndRegion {
+                    begin_index: begin_index.into(),
+                }),
+            ),
+            OplogEntry::PendingWorkerInvocation {
+                timestamp,
+                invocation,
+            } => (timestamp, Entry::PendingWorkerInvocation(invocation.into())),
+            OplogEntry::PendingUpdate {
+                timestamp,
+                description,
+            } => (timestamp, Entry::PendingUpdate(description.into())),
+            OplogEntry::SuccessfulUpdate {
+                timestamp,
+                target_version,
+                new_component_size,
+            } => (
+                timestamp,
+                Entry::SuccessfulUpdate(stored::SuccessfulUpdate {
+                    target_version,
+                    new_component_size,
+                }),
+            ),
+            OplogEntry::FailedUpdate {
+                timestamp,
+                target_version,
+                details,
+            } => (
+                timestamp,
+                Entry::FailedUpdate(stored::FailedUpdate {
+                    target_version,
+                    details,
+                }),
+            ),
+            OplogEntry::GrowMemory { timestamp, delta } => {
+                (timestamp, Entry::GrowMemory(stored::GrowMemory { delta }))
+            }
+            OplogEntry::CreateResource { timestamp, id } => (
+                timestamp,
+                Entry::CreateResource(stored::Resource { id: id.0 }),
+            ),
+            OplogEntry::DropResource { timestamp, id } => (
+                timestamp,
+                Entry::DropResource(stored::Resource { id: id.0 }),
+            ),
+            OplogEntry::DescribeResource {
+                timestamp,
+                id,
+                indexed_resource,
+            } => (
+                timestamp,
+                Entry::DescribeResource(stored::DescribeResource {
+                    id: id.0,
+                    resource_name: indexed_resource.resource_name,
+                    resource_params: indexed_resource.resource_params,
+                }),
+            ),
+            OplogEntry::Log {
+                timestamp,
+                level,
+                context,
+                message,
+            } => (
+                timestamp,
+                Entry::Log(stored::Log {
+                    level: stored::LogLevel::from(level) as i32,
+                    context,
+                    message,
+                }),
+            ),
+            OplogEntry::Restart { timestamp } => (timestamp, Entry::Restart(Empty {})),
+            OplogEntry::ImportedFunctionInvoked {
+                timestamp,
+                function_name,
+                request,
+                response,
+                wrapped_function_type,
+            } => (
+                timestamp,
+                Entry::ImportedFunctionInvoked(stored::ImportedFunctionInvoked {
+                    function_name,
+                    request: Some(request.into()),
+                    response: Some(response.into()),
+                    wrapped_function_type: Some(wrapped_function_type.into()),
+                }),
+            ),
+            OplogEntry::CancelPendingInvocation {
+                timestamp,
+                idempotency_key,
+            } => (
+                timestamp,
+                Entry::CancelPendingInvocation(stored::Invocation {
+                    idempotency_key: Some(idempotency_key.into()),
+                }),
+            ),
+            OplogEntry::ChangeEnvironment { timestamp, env } => (
+                timestamp,
+                Entry::ChangeEnvironment(stored::Environment {
+                    env: env_to_proto(env),
+                }),
+            ),
+            OplogEntry::Redacted {
+                timestamp,
+                entries,
+                reason,
+            } => (
+                timestamp,
+                Entry::Redacted(stored::Redacted {
+                    entries: entries.into_iter().map(|idx| idx.into()).collect(),
+                    reason,
+                }),
+            ),
+            OplogEntry::InvocationTimedOut {
+                timestamp,
+                idempotency_key,
+                timeout,
+            } => (
+                timestamp,
+                Entry::InvocationTimedOut(stored::InvocationTimedOut {
+                    idempotency_key: Some(idempotency_key.into()),
+                    timeout: Some(duration_to_proto(timeout)),
+                }),
+            ),
+            OplogEntry::HoldPendingInvocation {
+                timestamp,
+                idempotency_key,
+            } => (
+                timestamp,
+                Entry::HoldPendingInvocation(stored::Invocation {
+                    idempotency_key: Some(idempotency_key.into()),
+                }),
+            ),
+            OplogEntry::ReleasePendingInvocation {
+                timestamp,
+                idempotency_key,
+            } => (
+                timestamp,
+                Entry::ReleasePendingInvocation(stored::Invocation {
+                    idempotency_key: Some(idempotency_key.into()),
+                }),
+            ),
+            OplogEntry::SwitchInvocation {
+                timestamp,
+                idempotency_key,
+            } => (
+                timestamp,
+                Entry::SwitchInvocation(stored::Invocation {
+                    idempotency_key: Some(idempotency_key.into()),
+                }),
+            ),
+            OplogEntry::ExportedFunctionInvoked {
+                timestamp,
+                function_name,
+                request,
+                idempotency_key,
+                caller,
+            } => (
+                timestamp,
+                Entry::ExportedFunctionInvoked(stored::ExportedFunctionInvoked {
+                    function_name,
+                    request: Some(request.into()),
+                    idempotency_key: Some(idempotency_key.into()),
+                    caller: caller.map(|caller| caller.into()),
+                }),
+            ),
+        };
+
+        Self {
+            timestamp: timestamp.to_millis(),
+            entry: Some(entry),
+        }
+    }
+}
+
+impl TryFrom<stored::OplogEntry> for OplogEntry {
+    type Error = String;
+
+    fn try_from(value: stored::OplogEntry) -> Result<Self, Self::Error> {
+        let timestamp = Timestamp::from(value.timestamp);
+        match value.entry.ok_or("Missing oplog entry")? {
+            Entry::Create(create) => Ok(OplogEntry::Create {
+                timestamp,
+                worker_id: WorkerId::try_from(create.worker_id.ok_or("Missing worker_id")?)?,
+                component_version: create.component_version,
+                args: create.args,
+                env: env_from_proto(create.env),
+                account_id: create.account_id.ok_or("Missing account_id")?.into(),
+                parent: create.parent.map(WorkerId::try_from).transpose()?,
+                component_size: create.component_size,
+                initial_total_linear_memory_size: create.initial_total_linear_memory_size,
+            }),
+            Entry::ImportedFunctionInvokedV1(invoked) => {
+                Ok(OplogEntry::ImportedFunctionInvokedV1 {
+                    timestamp,
+                    function_name: invoked.function_name,
+                    response: invoked.response.ok_or("Missing response")?.try_into()?,
+                    wrapped_function_type: invoked
+                        .wrapped_function_type
+                        .ok_or("Missing wrapped_function_type")?
+                        .try_into()?,
+                })
+            }
+            Entry::ExportedFunctionInvokedV1(invoked) => {
+                Ok(OplogEntry::ExportedFunctionInvokedV1 {
+                    timestamp,
+                    function_name: invoked.function_name,
+                    request: invoked.request.ok_or("Missing request")?.try_into()?,
+                    idempotency_key: invoked
+                        .idempotency_key
+                        .ok_or("Missing idempotency_key")?
+                        .into(),
+                })
+            }
+            Entry::ExportedFunctionCompleted(completed) => {
+                Ok(OplogEntry::ExportedFunctionCompleted {
+                    timestamp,
+                    response: completed.response.ok_or("Missing response")?.try_into()?,
+                    consumed_fuel: completed.consumed_fuel,
+                })
+            }
+            Entry::Suspend(_) => Ok(OplogEntry::Suspend { timestamp }),
+            Entry::Error(error) => Ok(OplogEntry::Error {
+                timestamp,
+                error: error.try_into()?,
+            }),
+            Entry::NoOp(_) => Ok(OplogEntry::NoOp { timestamp }),
+            Entry::Jump(jump) => Ok(OplogEntry::Jump {
+                timestamp,
+                jump: OplogRegion {
+                    start: OplogIndex::from_u64(jump.start),
+                    end: OplogIndex::from_u64(jump.end),
+                },
+            }),
+            Entry::Interrupted(_) => Ok(OplogEntry::Interrupted { timestamp }),
+            Entry::Exited(_) => Ok(OplogEntry::Exited { timestamp }),
+            Entry::ChangeRetryPolicy(policy) => Ok(OplogEntry::ChangeRetryPolicy {
+                timestamp,
+                new_policy: policy.try_into()?,
+            }),
+            Entry::BeginAtomicRegion(_) => Ok(OplogEntry::BeginAtomicRegion { timestamp }),
+            Entry::EndAtomicRegion(end) => Ok(OplogEntry::EndAtomicRegion {
+                timestamp,
+                begin_index: OplogIndex::from_u64(end.begin_index),
+            }),
+            Entry::BeginRemoteWrite(_) => Ok(OplogEntry::BeginRemoteWrite { timestamp }),
+            Entry::EndRemoteWrite(end) => Ok(OplogEntry::EndRemoteWrite {
+                timestamp,
+                begin_index: OplogIndex::from_u64(end.begin_index),
+            }),
+            Entry::PendingWorkerInvocation(invocation) => Ok(OplogEntry::PendingWorkerInvocation {
+                timestamp,
+                invocation: invocation.try_into()?,
+            }),
+            Entry::PendingUpdate(description) => Ok(OplogEntry::PendingUpdate {
+                timestamp,
+                description: description.try_into()?,
+            }),
+            Entry::SuccessfulUpdate(update) => Ok(OplogEntry::SuccessfulUpdate {
+                timestamp,
+                target_version: update.target_version,
+                new_component_size: update.new_component_size,
+            }),
+            Entry::FailedUpdate(update) => Ok(OplogEntry::FailedUpdate {
+                timestamp,
+                target_version: update.target_version,
+                details: update.details,
+            }),
+            Entry::GrowMemory(grow) => Ok(OplogEntry::GrowMemory {
+                timestamp,
+                delta: grow.delta,
+            }),
+            Entry::CreateResource(resource) => Ok(OplogEntry::CreateResource {
+                timestamp,
+                id: WorkerResourceId(resource.id),
+            }),
+            Entry::DropResource(resource) => Ok(OplogEntry::DropResource {
+                timestamp,
+                id: WorkerResourceId(resource.id),
+            }),
+            Entry::DescribeResource(resource) => Ok(OplogEntry::DescribeResource {
+                timestamp,
+                id: WorkerResourceId(resource.id),
+                indexed_resource: IndexedResourceKey {
+                    resource_name: resource.resource_name,
+                    resource_params: resource.resource_params,
+                },
+            }),
+            Entry::Log(log) => Ok(OplogEntry::Log {
+                timestamp,
+                level: stored::LogLevel::try_from(log.level)
+                    .map_err(|_| format!("Invalid log level: {}", log.level))?
+                    .try_into()?,
+                context: log.context,
+                message: log.message,
+            }),
+            Entry::Restart(_) => Ok(OplogEntry::Restart { timestamp }),
+            Entry::ImportedFunctionInvoked(invoked) => Ok(OplogEntry::ImportedFunctionInvoked {
+                timestamp,
+                function_name: invoked.function_name,
+                request: invoked.request.ok_or("Missing request")?.try_into()?,
+                response: invoked.response.ok_or("Missing response")?.try_into()?,
+                wrapped_function_type: invoked
+                    .wrapped_function_type
+                    .ok_or("Missing wrapped_function_type")?
+                    .try_into()?,
+            }),
+            Entry::CancelPendingInvocation(invocation) => Ok(OplogEntry::CancelPendingInvocation {
+                timestamp,
+                idempotency_key: invocation
+                    .idempotency_key
+                    .ok_or("Missing idempotency_key")?
+                    .into(),
+            }),
+            Entry::ChangeEnvironment(environment) => Ok(OplogEntry::ChangeEnvironment {
+                timestamp,
+                env: env_from_proto(environment.env),
+            }),
+            Entry::Redacted(redacted) => Ok(OplogEntry::Redacted {
+                timestamp,
+                entries: redacted
+                    .entries
+                    .into_iter()
+                    .map(OplogIndex::from_u64)
+                    .collect(),
+                reason: redacted.reason,
+            }),
+            Entry::InvocationTimedOut(timed_out) => Ok(OplogEntry::InvocationTimedOut {
+                timestamp,
+                idempotency_key: timed_out
+                    .idempotency_key
+                    .ok_or("Missing idempotency_key")?
+                    .into(),
+                timeout: duration_from_proto(timed_out.timeout.ok_or("Missing timeout")?)?,
+            }),
+            Entry::HoldPendingInvocation(invocation) => Ok(OplogEntry::HoldPendingInvocation {
+                timestamp,
+                idempotency_key: invocation
+                    .idempotency_key
+                    .ok_or("Missing idempotency_key")?
+                    .into(),
+            }),
+            Entry::ReleasePendingInvocation(invocation) => {
+                Ok(OplogEntry::ReleasePendingInvocation {
+                    timestamp,
+                    idempotency_key: invocation
+                        .idempotency_key
+                        .ok_or("Missing idempotency_key")?
+                        .into(),
+                })
+            }
+            Entry::SwitchInvocation(invocation) => Ok(OplogEntry::SwitchInvocation {
+                timestamp,
+                idempotency_key: invocation
+                    .idempotency_key
+                    .ok_or("Missing idempotency_key")?
+                    .into(),
+            }),
+            Entry::ExportedFunctionInvoked(invoked) => Ok(OplogEntry::ExportedFunctionInvoked {
+                timestamp,
+                function_name: invoked.function_name,
+                request: invoked.request.ok_or("Missing request")?.try_into()?,
+                idempotency_key: invoked
+                    .idempotency_key
+                    .ok_or("Missing idempotency_key")?
+                    .into(),
+                caller: invoked.caller.map(|caller| caller.into()),
+            }),
+        }
+    }
+}
+
+impl From<OplogPayload> for stored::OplogPayload {
+    fn from(value: OplogPayload) -> Self {
+        let payload = match value {
+            OplogPayload::Inline(data) => stored::oplog_payload::Payload::Inline(data),
+            OplogPayload::External {
+                payload_id,
+                md5_hash,
+            } => stored::oplog_payload::Payload::External(stored::ExternalPayload {
+                payload_id: payload_id.0.as_bytes().to_vec(),
+                md5_hash,
+            }),
+        };
+        Self {
+            payload: Some(payload),
+        }
+    }
+}
+
+impl TryFrom<stored::OplogPayload> for OplogPayload {
+    type Error = String;
+
+    fn try_from(value: stored::OplogPayload) -> Result<Self, Self::Error> {
+        match value.payload.ok_or("Missing payload")? {
+            stored::oplog_payload::Payload::Inline(data) => Ok(OplogPayload::Inline(data)),
+            stored::oplog_payload::Payload::External(external) => Ok(OplogPayload::External {
+                payload_id: PayloadId(
+                    Uuid::from_slice(&external.payload_id)
+                        .map_err(|err| format!("Invalid payload id: {err}"))?,
+                ),
+                md5_hash: external.md5_hash,
+            }),
+        }
+    }
+}
+
+impl From<WrappedFunctionType> for stored::WrappedFunctionType {
+    fn from(value: WrappedFunctionType) -> Self {
+        use stored::wrapped_function_type::Type;
+
+        let (r#type, begin_index) = match value {
+            WrappedFunctionType::ReadLocal => (Type::ReadLocal, None),
+            WrappedFunctionType::WriteLocal => (Type::WriteLocal, None),
+            WrappedFunctionType::ReadRemote => (Type::ReadRemote, None),
+            WrappedFunctionType::WriteRemote => (Type::WriteRemote, None),
+            WrappedFunctionType::WriteRemoteBatched(begin_index) => {
+                (Type::WriteRemoteBatched, begin_index.map(|idx| idx.into()))
+            }
+        };
+        Self {
+            r#type: r#type as i32,
+            begin_index,
+        }
+    }
+}
+
+impl TryFrom<stored::WrappedFunctionType> for WrappedFunctionType {
+    type Error = String;
+
+    fn try_from(value: stored::WrappedFunctionType) -> Result<Self, Self::Error> {
+        use stored::wrapped_function_type::Type;
+
+        match Type::try_from(value.r#type) {
+            Ok(Type::ReadLocal) => Ok(WrappedFunctionType::ReadLocal),
+            Ok(Type::WriteLocal) => Ok(WrappedFunctionType::WriteLocal),
+            Ok(Type::ReadRemote) => Ok(WrappedFunctionType::ReadRemote),
+            Ok(Type::WriteRemote) => Ok(WrappedFunctionType::WriteRemote),
+            Ok(Type::WriteRemoteBatched) => Ok(WrappedFunctionType::WriteRemoteBatched(
+                value.begin_index.map(OplogIndex::from_u64),
+            )),
+            Ok(Type::Unspecified) | Err(_) => {
+                Err(format!("Invalid wrapped function type: {}", value.r#type))
+            }
+        }
+    }
+}
+
+impl From<WorkerError> for stored::WorkerError {
+    fn from(value: WorkerError) -> Self {
+        use stored::worker_error::Error;
+
+        let error = match value {
+            WorkerError::Unknown(message) => Error::Unknown(message),
+            WorkerError::InvalidRequest(message) => Error::InvalidRequest(message),
+            WorkerError::StackOverflow => Error::StackOverflow(Empty {}),
+            WorkerError::OutOfMemory => Error::OutOfMemory(Empty {}),
+            WorkerError::Trap(message) => Error::Trap(message),
+            WorkerError::Timeout(message) => Error::Timeout(message),
+            WorkerError::StorageFailure(message) => Error::StorageFailure(message),
+        };
+        Self { error: Some(error) }
+    }
+}
+
+impl TryFrom<stored::WorkerError> for WorkerError {
+    type Error = String;
+
+    fn try_from(value: stored::WorkerError) -> Result<Self, Self::Error> {
+        use stored::worker_error::Error;
+
+        match value.error.ok_or("Missing error")? {
+            Error::Unknown(message) => Ok(WorkerError::Unknown(message)),
+            Error::InvalidRequest(message) => Ok(WorkerError::InvalidRequest(message)),
+            Error::StackOverflow(_) => Ok(WorkerError::StackOverflow),
+            Error::OutOfMemory(_) => Ok(WorkerError::OutOfMemory),
+            Error::Trap(message) => Ok(WorkerError::Trap(message)),
+            Error::Timeout(message) => Ok(WorkerError::Timeout(message)),
+            Error::StorageFailure(message) => Ok(WorkerError::StorageFailure(message)),
+        }
+    }
+}
+
+impl From<RetryConfig> for stored::RetryConfig {
+    fn from(value: RetryConfig) -> Self {
+        Self {
+            max_attempts: value.max_attempts,
+            min_delay: Some(duration_to_proto(value.min_delay)),
+            max_delay: Some(duration_to_proto(value.max_delay)),
+            multiplier: value.multiplier,
+            max_jitter_factor: value.max_jitter_factor,
+        }
+    }
+}
+
+impl TryFrom<stored::RetryConfig> for RetryConfig {
+    type Error = String;
+
+    fn try_from(value: stored::RetryConfig) -> Result<Self, Self::Error> {
+        Ok(Self {
+            max_attempts: value.max_attempts,
+            min_delay: duration_from_proto(value.min_delay.ok_or("Missing min_delay")?)?,
+            max_delay: duration_from_proto(value.max_delay.ok_or("Missing max_delay")?)?,
+            multiplier: value.multiplier,
+            max_jitter_factor: value.max_jitter_factor,
+        })
+    }
+}
+
+impl From<WorkerInvocation> for stored::WorkerInvocation {
+    fn from(value: WorkerInvocation) -> Self {
+        use stored::worker_invocation::Invocation;
+
+        let invocation = match value {
+            WorkerInvocation::ExportedFunctionV1 {
+                idempotency_key,
+                full_function_name,
+                function_input,
+            } => Invocation::ExportedFunctionV1(stored::ExportedFunction {
+                idempotency_key: Some(idempotency_key.into()),
+                full_function_name,
+                function_input: function_input
+                    .into_iter()
+                    .map(|value| value.into())
+                    .collect(),
+                caller: None,
+            }),
+            WorkerInvocation::ManualUpdate { target_version } => {
+                Invocation::ManualUpdate(target_version)
+            }
+            WorkerInvocation::ExportedFunction {
+                idempotency_key,
+                full_function_name,
+                function_input,
+                caller,
+            } => Invocation::ExportedFunction(stored::ExportedFunction {
+                idempotency_key: Some(idempotency_key.into()),
+                full_function_name,
+                function_input: function_input
+                    .into_iter()
+                    .map(|value| value.into())
+                    .collect(),
+                caller: caller.map(|caller| caller.into()),
+            }),
+        };
+        Self {
+            invocation: Some(invocation),
+        }
+    }
+}
+
+impl TryFrom<stored::WorkerInvocation> for WorkerInvocation {
+    type Error = String;
+
+    fn try_from(value: stored::WorkerInvocation) -> Result<Self, Self::Error> {
+        use stored::worker_invocation::Invocation;
+
+        match value.invocation.ok_or("Missing invocation")? {
+            Invocation::ExportedFunctionV1(function) => Ok(WorkerInvocation::ExportedFunctionV1 {
+                idempotency_key: function
+                    .idempotency_key
+                    .ok_or("Missing idempotency_key")?
+                    .into(),
+                full_function_name: function.full_function_name,
+                function_input: function
+                    .function_input
+                    .into_iter()
+                    .map(Value::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            }),
+            Invocation::ManualUpdate(target_version) => {
+                Ok(WorkerInvocation::ManualUpdate { target_version })
+            }
+            Invocation::ExportedFunction(function) => Ok(WorkerInvocation::ExportedFunction {
+                idempotency_key: function
+                    .idempotency_key
+                    .ok_or("Missing idempotency_key")?
+                    .into(),
+                full_function_name: function.full_function_name,
+                function_input: function
+                    .function_input
+                    .into_iter()
+                    .map(Value::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+                caller: function.caller.map(|caller| caller.into()),
+            }),
+        }
+    }
+}
+
+impl From<UpdateDescription> for stored::UpdateDescription {
+    fn from(value: UpdateDescription) -> Self {
+        use stored::update_description::Description;
+
+        let description = match value {
+            UpdateDescription::Automatic { target_version } => {
+                Description::Automatic(target_version)
+            }
+            UpdateDescription::SnapshotBased {
+                target_version,
+                payload,
+            } => Description::SnapshotBased(stored::SnapshotBasedUpdate {
+                target_version,
+                payload: Some(payload.into()),
+            }),
+        };
+        Self {
+            description: Some(description),
+        }
+    }
+}
+
+impl TryFrom<stored::UpdateDescription> for UpdateDescription {
+    type Error = String;
+
+    fn try_from(value: stored::UpdateDescription) -> Result<Self, Self::Error> {
+        use stored::update_description::Description;
+
+        match value.description.ok_or("Missing update description")? {
+            Description::Automatic(target_version) => {
+                Ok(UpdateDescription::Automatic { target_version })
+            }
+            Description::SnapshotBased(update) => Ok(UpdateDescription::SnapshotBased {
+                target_version: update.target_version,
+                payload: update.payload.ok_or("Missing payload")?.try_into()?,
+            }),
+        }
+    }
+}
+
+impl From<LogLevel> for stored::LogLevel {
+    fn from(value: LogLevel) -> Self {
+        match value {
+            LogLevel::Stdout => stored::LogLevel::Stdout,
+            LogLevel::Stderr => stored::LogLevel::Stderr,
+            LogLevel::Trace => stored::LogLevel::Trace,
+            LogLevel::Debug => stored::LogLevel::Debug,
+            LogLevel::Info => stored::LogLevel::Info,
+            LogLevel::Warn => stored::LogLevel::Warn,
+            LogLevel::Error => stored::LogLevel::Error,
+            LogLevel::Critical => stored::LogLevel::Critical,
+        }
+    }
+}
+
+impl TryFrom<stored::LogLevel> for LogLevel {
+    type Error = String;
+
+    fn try_from(value: stored::LogLevel) -> Result<Self, Self::Error> {
+        match value {
+            stored::LogLevel::Unspecified => Err("Unspecified log level".to_string()),
+            stored::LogLevel::Stdout => Ok(LogLevel::Stdout),
+            stored::LogLevel::Stderr => Ok(LogLevel::Stderr),
+            stored::LogLevel::Trace => Ok(LogLevel::Trace),
+            stored::LogLevel::Debug => Ok(LogLevel::Debug),
+            stored::LogLevel::Info => Ok(LogLevel::Info),
+            stored::LogLevel::Warn => Ok(LogLevel::Warn),
+            stored::LogLevel::Error => Ok(LogLevel::Error),
+            stored::LogLevel::Critical => Ok(LogLevel::Critical),
+        }
+    }
+}
+
+fn env_to_proto(env: Vec<(String, String)>) -> Vec<stored::EnvironmentVariable> {
+    env.into_iter()
+        .map(|(name, value)| stored::EnvironmentVariable { name, value })
+        .collect()
+}
+
+fn env_from_proto(env: Vec<stored::EnvironmentVariable>) -> Vec<(String, String)> {
+    env.into_iter()
+        .map(|variable| (variable.name, variable.value))
+        .collect()
+}
+
+fn duration_to_proto(duration: Duration) -> prost_types::Duration {
+    prost_types::Duration {
+        seconds: duration.as_secs() as i64,
+        nanos: duration.subsec_nanos() as i32,
+    }
+}
+
+fn duration_from_proto(duration: prost_types::Duration) -> Result<Duration, String> {
+    let seconds =
+        u64::try_from(duration.seconds).map_err(|_| format!("Negative duration: {duration:?}"))?;
+    let nanos =
+        u32::try_from(duration.nanos).map_err(|_| format!("Negative duration: {duration:?}"))?;
+    Ok(Duration::new(seconds, nanos))
+}
@@ -112,6 +112,18 @@ impl From<RetryConfig> for PublicRetryConfig {
     }
 }
 
+impl From<PublicRetryConfig> for RetryConfig {
+    fn from(retry_config: PublicRetryConfig) -> Self {
+        RetryConfig {
+            max_attempts: retry_config.max_attempts,
+            min_delay: retry_config.min_delay,
+            max_delay: retry_config.max_delay,
+            multiplier: retry_config.multiplier,
+            max_jitter_factor: retry_config.max_jitter_factor,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
 pub struct ExportedFunctionParameters {
     pub idempotency_key: IdempotencyKey,
@@ -225,6 +237,50 @@ pub struct FailedUpdateParameters {
     pub details: Option<String>,
 }
 
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct CancelInvocationParameters {
+    pub timestamp: Timestamp,
+    pub idempotency_key: IdempotencyKey,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct ChangeEnvironmentParameters {
+    pub timestamp: Timestamp,
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct RedactedParameters {
+    pub timestamp: Timestamp,
+    pub entries: Vec<OplogIndex>,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct InvocationTimedOutParameters {
+    pub timestamp: Timestamp,
+    pub idempotency_key: IdempotencyKey,
+    pub timeout: Duration,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct HoldInvocationParameters {
+    pub timestamp: Timestamp,
+    pub idempotency_key: IdempotencyKey,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct ReleaseInvocationParameters {
+    pub timestamp: Timestamp,
+    pub idempotency_key: IdempotencyKey,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct SwitchInvocationParameters {
+    pub timestamp: Timestamp,
+    pub idempotency_key: IdempotencyKey,
+}
+
 #[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
 pub struct GrowMemoryParameters {
     pub timestamp: Timestamp,
@@ -324,6 +380,59 @@ pub enum PublicOplogEntry {
     Log(LogParameters),
     /// Marks the point where the worker was restarted from clean initial state
     Restart(TimestampParameter),
+    /// A pending invocation was cancelled before the worker started executing it
+    CancelInvocation(CancelInvocationParameters),
+    /// Replaces the environment variables of the worker, from its next restart or update
+    ChangeEnvironment(ChangeEnvironmentParameters),
+    /// The payloads of some earlier entries were replaced by placeholder values
+    Redacted(RedactedParameters),
+    /// An invocation did not complete within its timeout and got interrupted
+    InvocationTimedOut(InvocationTimedOutParameters),
+    /// A pending invocation was held back, it is not executed until it gets released
+    HoldInvocation(HoldInvocationParameters),
+    /// A held pending invocation was released
+    ReleaseInvocation(ReleaseInvocationParameters),
+    /// The worker switched to another in-flight invocation at an await point
+    SwitchInvocation(SwitchInvocationParameters),
+}
+
+impl PublicOplogEntry {
+    pub fn timestamp(&self) -> Timestamp {
+        match self {
+            PublicOplogEntry::Create(params) => params.timestamp,
+            PublicOplogEntry::ImportedFunctionInvoked(params) => params.timestamp,
+            PublicOplogEntry::ExportedFunctionInvoked(params) => params.timestamp,
+            PublicOplogEntry::ExportedFunctionCompleted(params) => params.timestamp,
+            PublicOplogEntry::Suspend(params) => params.timestamp,
+            PublicOplogEntry::Error(params) => params.timestamp,
+            PublicOplogEntry::NoOp(params) => params.timestamp,
+            PublicOplogEntry::Jump(params) => params.timestamp,
+            PublicOplogEntry::Interrupted(params) => params.timestamp,
+            PublicOplogEntry::Exited(params) => params.timestamp,
+            PublicOplogEntry::ChangeRetryPolicy(params) => params.timestamp,
+            PublicOplogEntry::BeginAtomicRegion(params) => params.timestamp,
+            PublicOplogEntry::EndAtomicRegion(params) => params.timestamp,
+            PublicOplogEntry::BeginRemoteWrite(params) => params.timestamp,
+            PublicOplogEntry::EndRemoteWrite(params) => params.timestamp,
+            PublicOplogEntry::PendingWorkerInvocation(params) => params.timestamp,
+            PublicOplogEntry::PendingUpdate(params) => params.timestamp,
+            PublicOplogEntry::SuccessfulUpdate(params) => params.timestamp,
+            PublicOplogEntry::FailedUpdate(params) => params.timestamp,
+            PublicOplogEntry::GrowMemory(params) => params.timestamp,
+            PublicOplogEntry::CreateResource(params) => params.timestamp,
+            PublicOplogEntry::DropResource(params) => params.timestamp,
+            PublicOplogEntry::DescribeResource(params) => params.timestamp,
+            PublicOplogEntry::Log(params) => params.timestamp,
+            PublicOplogEntry::Restart(params) => params.timestamp,
+            PublicOplogEntry::CancelInvocation(params) => params.timestamp,
+            PublicOplogEntry::ChangeEnvironment(params) => params.timestamp,
+            PublicOplogEntry::Redacted(params) => params.timestamp,
+            PublicOplogEntry::InvocationTimedOut(params) => params.timestamp,
+            PublicOplogEntry::HoldInvocation(params) => params.timestamp,
+            PublicOplogEntry::ReleaseInvocation(params) => params.timestamp,
+            PublicOplogEntry::SwitchInvocation(params) => params.timestamp,
+        }
+    }
 }
 
 impl TryFrom<golem_api_grpc::proto::golem::worker::OplogEntry> for PublicOplogEntry {
@@ -576,6 +685,87 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::OplogEntry> for PublicOplogEn
                     timestamp: restart.timestamp.ok_or("Missing timestamp field")?.into(),
                 }))
             }
+            oplog_entry::Entry::CancelInvocation(cancel_invocation) => Ok(
+                PublicOplogEntry::CancelInvocation(CancelInvocationParameters {
+                    timestamp: cancel_invocation
+                        .timestamp
+                        .ok_or("Missing timestamp field")?
+                        .into(),
+                    idempotency_key: cancel_invocation
+                        .idempotency_key
+                        .ok_or("Missing idempotency_key field")?
+                        .into(),
+                }),
+            ),
+            oplog_entry::Entry::ChangeEnvironment(change_environment) => Ok(
+                PublicOplogEntry::ChangeEnvironment(ChangeEnvironmentParameters {
+                    timestamp: change_environment
+                        .timestamp
+                        .ok_or("Missing timestamp field")?
+                        .into(),
+                    env: change_environment.env.into_iter().collect(),
+                }),
+            ),
+            oplog_entry::Entry::Redacted(redacted) => {
+                Ok(PublicOplogEntry::Redacted(RedactedParameters {
+                    timestamp: redacted.timestamp.ok_or("Missing timestamp field")?.into(),
+                    entries: redacted
+                        .entries
+                        .into_iter()
+                        .map(OplogIndex::from_u64)
+                        .collect(),
+                    reason: redacted.reason,
+                }))
+            }
+            oplog_entry::Entry::InvocationTimedOut(invocation_timed_out) => Ok(
+                PublicOplogEntry::InvocationTimedOut(InvocationTimedOutParameters {
+                    timestamp: invocation_timed_out
+                        .timestamp
+                        .ok_or("Missing timestamp field")?
+                        .into(),
+                    idempotency_key: invocation_timed_out
+                        .idempotency_key
+                        .ok_or("Missing idempotency_key field")?
+                        .into(),
+                    timeout: Duration::from_millis(invocation_timed_out.timeout_millis),
+                }),
+            ),
+            oplog_entry::Entry::HoldInvocation(hold_invocation) => {
+                Ok(PublicOplogEntry::HoldInvocation(HoldInvocationParameters {
+                    timestamp: hold_invocation
+                        .timestamp
+                        .ok_or("Missing timestamp field")?
+                        .into(),
+                    idempotency_key: hold_invocation
+                        .idempotency_key
+                        .ok_or("Missing idempotency_key field")?
+                        .into(),
+                }))
+            }
+            oplog_entry::Entry::ReleaseInvocation(release_invocation) => Ok(
+                PublicOplogEntry::ReleaseInvocation(ReleaseInvocationParameters {
+                    timestamp: release_invocation
+                        .timestamp
+                        .ok_or("Missing timestamp field")?
+                        .into(),
+                    idempotency_key: release_invocation
+                        .idempotency_key
+                        .ok_or("Missing idempotency_key field")?
+                        .into(),
+                }),
+            ),
+            oplog_entry::Entry::SwitchInvocation(switch_invocation) => Ok(
+                PublicOplogEntry::SwitchInvocation(SwitchInvocationParameters {
+                    timestamp: switch_invocation
+                        .timestamp
+                        .ok_or("Missing timestamp field")?
+                        .into(),
+                    idempotency_key: switch_invocation
+                        .idempotency_key
+                        .ok_or("Missing idempotency_key field")?
+                        .into(),
+                }),
+            ),
         }
     }
 }
@@ -871,6 +1061,78 @@ impl TryFrom<PublicOplogEntry> for golem_api_grpc::proto::golem::worker::OplogEn
                     )),
                 }
             }
+            PublicOplogEntry::CancelInvocation(cancel_invocation) => {
+                golem_api_grpc::proto::golem::worker::OplogEntry {
+                    entry: Some(oplog_entry::Entry::CancelInvocation(
+                        golem_api_grpc::proto::golem::worker::CancelInvocationParameters {
+                            timestamp: Some(cancel_invocation.timestamp.into()),
+                            idempotency_key: Some(cancel_invocation.idempotency_key.into()),
+                        },
+                    )),
+                }
+            }
+            PublicOplogEntry::ChangeEnvironment(change_environment) => {
+                golem_api_grpc::proto::golem::worker::OplogEntry {
+                    entry: Some(oplog_entry::Entry::ChangeEnvironment(
+                        golem_api_grpc::proto::golem::worker::ChangeEnvironmentParameters {
+                            timestamp: Some(change_environment.timestamp.into()),
+                            env: change_environment.env.into_iter().collect(),
+                        },
+                    )),
+                }
+            }
+            PublicOplogEntry::Redacted(redacted) => {
+                golem_api_grpc::proto::golem::worker::OplogEntry {
+                    entry: Some(oplog_entry::Entry::Redacted(
+                        golem_api_grpc::proto::golem::worker::RedactedParameters {
+                            timestamp: Some(redacted.timestamp.into()),
+                            entries: redacted.entries.into_iter().map(|idx| idx.into()).collect(),
+                            reason: redacted.reason,
+                        },
+                    )),
+                }
+            }
+            PublicOplogEntry::InvocationTimedOut(invocation_timed_out) => {
+                golem_api_grpc::proto::golem::worker::OplogEntry {
+                    entry: Some(oplog_entry::Entry::InvocationTimedOut(
+                        golem_api_grpc::proto::golem::worker::InvocationTimedOutParameters {
+                            timestamp: Some(invocation_timed_out.timestamp.into()),
+                            idempotency_key: Some(invocation_timed_out.idempotency_key.into()),
+                            timeout_millis: invocation_timed_out.timeout.as_millis() as u64,
+                        },
+                    )),
+                }
+            }
+            PublicOplogEntry::HoldInvocation(hold_invocation) => {
+                golem_api_grpc::proto::golem::worker::OplogEntry {
+                    entry: Some(oplog_entry::Entry::HoldInvocation(
+                        golem_api_grpc::proto::golem::worker::HoldInvocationParameters {
+                            timestamp: Some(hold_invocation.timestamp.into()),
+                            idempotency_key: Some(hold_invocation.idempotency_key.into()),
+                        },
+                    )),
+                }
+            }
+            PublicOplogEntry::ReleaseInvocation(release_invocation) => {
+                golem_api_grpc::proto::golem::worker::OplogEntry {
+                    entry: Some(oplog_entry::Entry::ReleaseInvocation(
+                        golem_api_grpc::proto::golem::worker::ReleaseInvocationParameters {
+                            timestamp: Some(release_invocation.timestamp.into()),
+                            idempotency_key: Some(release_invocation.idempotency_key.into()),
+                        },
+                    )),
+                }
+            }
+            PublicOplogEntry::SwitchInvocation(switch_invocation) => {
+                golem_api_grpc::proto::golem::worker::OplogEntry {
+                    entry: Some(oplog_entry::Entry::SwitchInvocation(
+                        golem_api_grpc::proto::golem::worker::SwitchInvocationParameters {
+                            timestamp: Some(switch_invocation.timestamp.into()),
+                            idempotency_key: Some(switch_invocation.idempotency_key.into()),
+                        },
+                    )),
+                }
+            }
         })
     }
 }
@@ -1172,3 +1434,303 @@ impl From<OplogCursor> for golem_api_grpc::proto::golem::worker::OplogCursor {
         }
     }
 }
+
+/// Filters the entries of an oplog. All the given conditions must hold for an entry to match.
+///
+/// Queries are evaluated by the worker executors, so only the matching entries are sent to the
+/// clients.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OplogQuery {
+    /// Only invocations (and pending invocations) of this exported function
+    pub exported_function: Option<String>,
+    /// Only calls of host functions of this interface, for example `golem blobstore::container`
+    pub imported_interface: Option<String>,
+    pub from_timestamp: Option<Timestamp>,
+    pub to_timestamp: Option<Timestamp>,
+    /// Only entries recording an error: worker failures, failed updates and error logs
+    #[serde(default)]
+    pub errors_only: bool,
+}
+
+impl OplogQuery {
+    pub fn matches(&self, entry: &PublicOplogEntry) -> bool {
+        let timestamp = entry.timestamp();
+        if self.from_timestamp.is_some_and(|from| timestamp < from)
+            || self.to_timestamp.is_some_and(|to| timestamp > to)
+        {
+            return false;
+        }
+
+        if let Some(exported_function) = &self.exported_function {
+            let function_name = match entry {
+                PublicOplogEntry::ExportedFunctionInvoked(params) => Some(&params.function_name),
+                PublicOplogEntry::PendingWorkerInvocation(PendingWorkerInvocationParameters {
+                    invocation: PublicWorkerInvocation::ExportedFunction(params),
+                    ..
+                }) => Some(&params.full_function_name),
+                _ => None,
+            };
+            if function_name != Some(exported_function) {
+                return false;
+            }
+        }
+
+        if let Some(imported_interface) = &self.imported_interface {
+            let interface = match entry {
+                PublicOplogEntry::ImportedFunctionInvoked(params) => params
+                    .function_name
+                    .rsplit_once("::")
+                    .map(|(interface, _)| interface),
+                _ => None,
+            };
+            if interface != Some(imported_interface.as_str()) {
+                return false;
+            }
+        }
+
+        if self.errors_only {
+            let is_error = matches!(
+                entry,
+                PublicOplogEntry::Error(_)
+                    | PublicOplogEntry::FailedUpdate(_)
+                    | PublicOplogEntry::Log(LogParameters {
+                        level: LogLevel::Error | LogLevel::Critical,
+                        ..
+                    })
+            );
+            if !is_error {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::worker::OplogQuery> for OplogQuery {
+    fn from(value: golem_api_grpc::proto::golem::worker::OplogQuery) -> Self {
+        Self {
+            exported_function: value.exported_function,
+            imported_interface: value.imported_interface,
+            from_timestamp: value.from_timestamp.map(|timestamp| timestamp.into()),
+            to_timestamp: value.to_timestamp.map(|timestamp| timestamp.into()),
+            errors_only: value.errors_only,
+        }
+    }
+}
+
+impl From<OplogQuery> for golem_api_grpc::proto::golem::worker::OplogQuery {
+    fn from(value: OplogQuery) -> Self {
+        Self {
+            exported_function: value.exported_function,
+            imported_interface: value.imported_interface,
+            from_timestamp: value.from_timestamp.map(|timestamp| timestamp.into()),
+            to_timestamp: value.to_timestamp.map(|timestamp| timestamp.into()),
+            errors_only: value.errors_only,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::oplog::{LogLevel, OplogIndex};
+    use crate::model::public_oplog::{
+        CancelInvocationParameters, ChangeEnvironmentParameters, Empty, ErrorParameters,
+        ExportedFunctionInvokedParameters, HoldInvocationParameters,
+        ImportedFunctionInvokedParameters, InvocationTimedOutParameters, LogParameters, OplogQuery,
+        PublicOplogEntry, PublicWrappedFunctionType, RedactedParameters,
+        ReleaseInvocationParameters, SwitchInvocationParameters, TimestampParameter,
+    };
+    use crate::model::{IdempotencyKey, Timestamp};
+    use golem_wasm_ast::analysis::analysed_type::bool;
+    use golem_wasm_rpc::{Value, ValueAndType};
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    fn unit() -> ValueAndType {
+        ValueAndType::new(Value::Bool(true), bool())
+    }
+
+    fn entries() -> Vec<PublicOplogEntry> {
+        vec![
+            PublicOplogEntry::ExportedFunctionInvoked(ExportedFunctionInvokedParameters {
+                timestamp: Timestamp::from(1000),
+                function_name: "golem:it/api.{add-item}".to_string(),
+                request: vec![],
+                idempotency_key: IdempotencyKey::fresh(),
+            }),
+            PublicOplogEntry::ImportedFunctionInvoked(ImportedFunctionInvokedParameters {
+                timestamp: Timestamp::from(2000),
+                function_name: "golem blobstore::container::get_data".to_string(),
+                request: unit(),
+                response: unit(),
+                wrapped_function_type: PublicWrappedFunctionType::ReadRemote(Empty),
+            }),
+            PublicOplogEntry::Log(LogParameters {
+                timestamp: Timestamp::from(3000),
+                level: LogLevel::Error,
+                context: "".to_string(),
+                message: "failed to add item".to_string(),
+            }),
+            PublicOplogEntry::Error(ErrorParameters {
+                timestamp: Timestamp::from(4000),
+                error: "trap".to_string(),
+            }),
+            PublicOplogEntry::Suspend(TimestampParameter {
+                timestamp: Timestamp::from(5000),
+            }),
+        ]
+    }
+
+    fn matching(query: OplogQuery) -> Vec<u64> {
+        entries()
+            .iter()
+            .filter(|entry| query.matches(entry))
+            .map(|entry| entry.timestamp().to_millis())
+            .collect()
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(
+            matching(OplogQuery::default()),
+            vec![1000, 2000, 3000, 4000, 5000]
+        );
+    }
+
+    #[test]
+    fn query_by_function_and_interface() {
+        assert_eq!(
+            matching(OplogQuery {
+                exported_function: Some("golem:it/api.{add-item}".to_string()),
+                ..Default::default()
+            }),
+            vec![1000]
+        );
+        assert_eq!(
+            matching(OplogQuery {
+                imported_interface: Some("golem blobstore::container".to_string()),
+                ..Default::default()
+            }),
+            vec![2000]
+        );
+        assert_eq!(
+            matching(OplogQuery {
+                imported_interface: Some("golem blobstore".to_string()),
+                ..Default::default()
+            }),
+            Vec::<u64>::new()
+        );
+    }
+
+    #[test]
+    fn query_by_time_window_and_errors() {
+        assert_eq!(
+            matching(OplogQuery {
+                from_timestamp: Some(Timestamp::from(2000)),
+                to_timestamp: Some(Timestamp::from(4000)),
+                ..Default::default()
+            }),
+            vec![2000, 3000, 4000]
+        );
+        assert_eq!(
+            matching(OplogQuery {
+                from_timestamp: Some(Timestamp::from(3500)),
+                errors_only: true,
+                ..Default::default()
+            }),
+            vec![4000]
+        );
+    }
+
+    #[test]
+    fn cancel_invocation_proto_roundtrip() {
+        let entry = PublicOplogEntry::CancelInvocation(CancelInvocationParameters {
+            timestamp: Timestamp::from(6000),
+            idempotency_key: IdempotencyKey::fresh(),
+        });
+
+        let proto: golem_api_grpc::proto::golem::worker::OplogEntry =
+            entry.clone().try_into().unwrap();
+        let decoded: PublicOplogEntry = proto.try_into().unwrap();
+
+        assert_eq!(decoded, entry);
+    }
+
+    #[test]
+    fn change_environment_proto_roundtrip() {
+        let entry = PublicOplogEntry::ChangeEnvironment(ChangeEnvironmentParameters {
+            timestamp: Timestamp::from(7000),
+            env: BTreeMap::from([
+                ("A".to_string(), "1".to_string()),
+                ("B".to_string(), "2".to_string()),
+            ]),
+        });
+
+        let proto: golem_api_grpc::proto::golem::worker::OplogEntry =
+            entry.clone().try_into().unwrap();
+        let decoded: PublicOplogEntry = proto.try_into().unwrap();
+
+        assert_eq!(decoded, entry);
+    }
+
+    #[test]
+    fn redacted_proto_roundtrip() {
+        let entry = PublicOplogEntry::Redacted(RedactedParameters {
+            timestamp: Timestamp::from(8000),
+            entries: vec![OplogIndex::from_u64(3), OplogIndex::from_u64(5)],
+            reason: "personal data logged by mistake".to_string(),
+        });
+
+        let proto: golem_api_grpc::proto::golem::worker::OplogEntry =
+            entry.clone().try_into().unwrap();
+        let decoded: PublicOplogEntry = proto.try_into().unwrap();
+
+        assert_eq!(decoded, entry);
+    }
+
+    #[test]
+    fn invocation_timed_out_proto_roundtrip() {
+        let entry = PublicOplogEntry::InvocationTimedOut(InvocationTimedOutParameters {
+            timestamp: Timestamp::from(9000),
+            idempotency_key: IdempotencyKey::fresh(),
+            timeout: Duration::from_millis(2500),
+        });
+
+        let proto: golem_api_grpc::proto::golem::worker::OplogEntry =
+            entry.clone().try_into().unwrap();
+        let decoded: PublicOplogEntry = proto.try_into().unwrap();
+
+        assert_eq!(decoded, entry);
+    }
+
+    #[test]
+    fn hold_release_and_switch_invocation_proto_roundtrip() {
+        let idempotency_key = IdempotencyKey::fresh();
+        let entries = vec![
+            PublicOplogEntry::HoldInvocation(HoldInvocationParameters {
+                timestamp: Timestamp::from(10000),
+                idempotency_key: idempotency_key.clone(),
+            }),
+            PublicOplogEntry::ReleaseInvocation(ReleaseInvocationParameters {
+                timestamp: Timestamp::from(11000),
+                idempotency_key: idempotency_key.clone(),
+            }),
+            PublicOplogEntry::SwitchInvocation(SwitchInvocationParameters {
+                timestamp: Timestamp::from(12000),
+                idempotency_key,
+            }),
+        ];
+
+        for entry in entries {
+            let proto: golem_api_grpc::proto::golem::worker::OplogEntry =
+                entry.clone().try_into().unwrap();
+            let decoded: PublicOplogEntry = proto.try_into().unwrap();
+
+            assert_eq!(decoded, entry);
+        }
+    }
+}
@@ -0,0 +1,472 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::model::public_oplog::{PublicOplogEntry, PublicWorkerInvocation};
+use crate::model::{ComponentVersion, IdempotencyKey, Timestamp};
+use poem_openapi::{Enum, Object};
+use serde::{Deserialize, Serialize};
+
+/// A view of a worker's oplog organized for rendering as a timeline (Gantt chart).
+///
+/// Invocations are represented as spans with their host function calls nested inside,
+/// while everything else that is interesting on a timeline (failures, interruptions,
+/// restarts, updates) is collected as point-like events.
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct WorkerTimeline {
+    pub start: Option<Timestamp>,
+    pub end: Option<Timestamp>,
+    pub invocations: Vec<TimelineInvocation>,
+    pub events: Vec<TimelineEvent>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum TimelineInvocationStatus {
+    /// The invocation has not finished yet (or the worker is still retrying it)
+    Running,
+    Completed,
+    /// The last attempt of the invocation failed and it has not been retried (yet)
+    Failed,
+    Interrupted,
+    Exited,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct TimelineInvocation {
+    pub function_name: String,
+    pub idempotency_key: IdempotencyKey,
+    pub start_index: u64,
+    pub end_index: Option<u64>,
+    pub start: Timestamp,
+    pub end: Option<Timestamp>,
+    pub duration_ms: Option<u64>,
+    pub status: TimelineInvocationStatus,
+    /// Number of times the invocation was resumed after a failure
+    pub retries: u32,
+    pub consumed_fuel: Option<i64>,
+    pub host_calls: Vec<TimelineHostCall>,
+}
+
+/// A host function call made while an invocation was running.
+///
+/// The oplog only records the point when the host call's result got persisted, so
+/// `offset_ms` is measured from the start of the enclosing invocation to that point.
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct TimelineHostCall {
+    pub oplog_index: u64,
+    pub function_name: String,
+    pub timestamp: Timestamp,
+    pub offset_ms: u64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum TimelineEventKind {
+    Created,
+    Error,
+    Interrupted,
+    Suspended,
+    Exited,
+    Restarted,
+    Jump,
+    UpdatePending,
+    UpdateSucceeded,
+    UpdateFailed,
+}
+
+#[derive(Clone, Debug, Serialize, PartialEq, Deserialize, Object)]
+pub struct TimelineEvent {
+    pub oplog_index: u64,
+    pub timestamp: Timestamp,
+    pub kind: TimelineEventKind,
+    pub target_version: Option<ComponentVersion>,
+    pub details: Option<String>,
+}
+
+impl TimelineEvent {
+    fn new(oplog_index: u64, timestamp: &Timestamp, kind: TimelineEventKind) -> Self {
+        Self {
+            oplog_index,
+            timestamp: *timestamp,
+            kind,
+            target_version: None,
+            details: None,
+        }
+    }
+}
+
+impl TimelineInvocation {
+    fn finish(
+        &mut self,
+        oplog_index: u64,
+        timestamp: &Timestamp,
+        status: TimelineInvocationStatus,
+    ) {
+        self.end_index = Some(oplog_index);
+        self.end = Some(*timestamp);
+        self.duration_ms = Some(millis_between(&self.start, timestamp));
+        self.status = status;
+    }
+
+    /// Called when the worker continues making progress on this invocation after it was
+    /// previously marked as failed or interrupted.
+    fn resume(&mut self) {
+        if self.status == TimelineInvocationStatus::Failed {
+            self.retries += 1;
+        }
+        if self.status != TimelineInvocationStatus::Running {
+            self.end_index = None;
+            self.end = None;
+            self.duration_ms = None;
+            self.status = TimelineInvocationStatus::Running;
+        }
+    }
+}
+
+impl WorkerTimeline {
+    /// Builds the timeline from oplog entries paired with their oplog indices, in oplog order.
+    pub fn from_oplog<'a>(
+        entries: impl IntoIterator<Item = (u64, &'a PublicOplogEntry)>,
+    ) -> WorkerTimeline {
+        let mut timeline = WorkerTimeline {
+            start: None,
+            end: None,
+            invocations: Vec::new(),
+            events: Vec::new(),
+        };
+        // Index into `timeline.invocations` of the invocation that has not completed yet
+        let mut current: Option<usize> = None;
+
+        for (idx, entry) in entries {
+            let timestamp = entry_timestamp(entry);
+            if timeline.start.is_none() {
+                timeline.start = Some(*timestamp);
+            }
+            timeline.end = Some(*timestamp);
+
+            match entry {
+                PublicOplogEntry::Create(_) => {
+                    timeline.events.push(TimelineEvent::new(
+                        idx,
+                        timestamp,
+                        TimelineEventKind::Created,
+                    ));
+                }
+                PublicOplogEntry::ExportedFunctionInvoked(params) => {
+                    timeline.invocations.push(TimelineInvocation {
+                        function_name: params.function_name.clone(),
+                        idempotency_key: params.idempotency_key.clone(),
+                        start_index: idx,
+                        end_index: None,
+                        start: params.timestamp,
+                        end: None,
+                        duration_ms: None,
+                        status: TimelineInvocationStatus::Running,
+                        retries: 0,
+                        consumed_fuel: None,
+                        host_calls: Vec::new(),
+                    });
+                    current = Some(timeline.invocations.len() - 1);
+                }
+                PublicOplogEntry::ExportedFunctionCompleted(params) => {
+                    if let Some(invocation) = current.take().map(|i| &mut timeline.invocations[i]) {
+                        invocation.resume();
+                        invocation.consumed_fuel = Some(params.consumed_fuel);
+                        invocation.finish(idx, timestamp, TimelineInvocationStatus::Completed);
+                    }
+                }
+                PublicOplogEntry::ImportedFunctionInvoked(params) => {
+                    if let Some(invocation) = current.map(|i| &mut timeline.invocations[i]) {
+                        invocation.resume();
+                        invocation.host_calls.push(TimelineHostCall {
+                            oplog_index: idx,
+                            function_name: params.function_name.clone(),
+                            timestamp: params.timestamp,
+                            offset_ms: millis_between(&invocation.start, &params.timestamp),
+                        });
+                    }
+                }
+                PublicOplogEntry::Error(params) => {
+                    if let Some(invocation) = current.map(|i| &mut timeline.invocations[i]) {
+                        invocation.resume();
+                        invocation.finish(idx, timestamp, TimelineInvocationStatus::Failed);
+                    }
+                    let mut event = TimelineEvent::new(idx, timestamp, TimelineEventKind::Error);
+                    event.details = Some(params.error.clone());
+                    timeline.events.push(event);
+                }
+                PublicOplogEntry::Interrupted(_) => {
+                    if let Some(invocation) = current.map(|i| &mut timeline.invocations[i]) {
+                        invocation.resume();
+                        invocation.finish(idx, timestamp, TimelineInvocationStatus::Interrupted);
+                    }
+                    timeline.events.push(TimelineEvent::new(
+                        idx,
+                        timestamp,
+                        TimelineEventKind::Interrupted,
+                    ));
+                }
+                PublicOplogEntry::Exited(_) => {
+                    if let Some(invocation) = current.take().map(|i| &mut timeline.invocations[i]) {
+                        invocation.resume();
+                        invocation.finish(idx, timestamp, TimelineInvocationStatus::Exited);
+                    }
+                    timeline.events.push(TimelineEvent::new(
+                        idx,
+                        timestamp,
+                        TimelineEventKind::Exited,
+                    ));
+                }
+                PublicOplogEntry::Suspend(_) => {
+                    timeline.events.push(TimelineEvent::new(
+                        idx,
+                        timestamp,
+                        TimelineEventKind::Suspended,
+                    ));
+                }
+                PublicOplogEntry::Restart(_) => {
+                    // Restarting from a clean state abandons the invocation in progress
+                    current = None;
+                    timeline.events.push(TimelineEvent::new(
+                        idx,
+                        timestamp,
+                        TimelineEventKind::Restarted,
+                    ));
+                }
+                PublicOplogEntry::Jump(params) => {
+                    let mut event = TimelineEvent::new(idx, timestamp, TimelineEventKind::Jump);
+                    event.details = Some(format!("{} to {}", params.jump.start, params.jump.end));
+                    timeline.events.push(event);
+                }
+                PublicOplogEntry::PendingUpdate(params) => {
+                    let mut event =
+                        TimelineEvent::new(idx, timestamp, TimelineEventKind::UpdatePending);
+                    event.target_version = Some(params.target_version);
+                    timeline.events.push(event);
+                }
+                PublicOplogEntry::PendingWorkerInvocation(params) => {
+                    if let PublicWorkerInvocation::ManualUpdate(update) = &params.invocation {
+                        let mut event =
+                            TimelineEvent::new(idx, timestamp, TimelineEventKind::UpdatePending);
+                        event.target_version = Some(update.target_version);
+                        timeline.events.push(event);
+                    }
+                }
+                PublicOplogEntry::SuccessfulUpdate(params) => {
+                    let mut event =
+                        TimelineEvent::new(idx, timestamp, TimelineEventKind::UpdateSucceeded);
+                    event.target_version = Some(params.target_version);
+                    timeline.events.push(event);
+                }
+                PublicOplogEntry::FailedUpdate(params) => {
+                    let mut event =
+                        TimelineEvent::new(idx, timestamp, TimelineEventKind::UpdateFailed);
+                    event.target_version = Some(params.target_version);
+                    event.details = params.details.clone();
+                    timeline.events.push(event);
+                }
+                PublicOplogEntry::NoOp(_)
+                | PublicOplogEntry::ChangeRetryPolicy(_)
+                | PublicOplogEntry::BeginAtomicRegion(_)
+                | PublicOplogEntry::EndAtomicRegion(_)
+                | PublicOplogEntry::BeginRemoteWrite(_)
+                | PublicOplogEntry::EndRemoteWrite(_)
+                | PublicOplogEntry::GrowMemory(_)
+                | PublicOplogEntry::CreateResource(_)
+                | PublicOplogEntry::DropResource(_)
+                | PublicOplogEntry::DescribeResource(_)
+                | PublicOplogEntry::Log(_)
+                | PublicOplogEntry::CancelInvocation(_)
+                | PublicOplogEntry::ChangeEnvironment(_)
+                | PublicOplogEntry::Redacted(_)
+                | PublicOplogEntry::InvocationTimedOut(_)
+                | PublicOplogEntry::HoldInvocation(_)
+                | PublicOplogEntry::ReleaseInvocation(_)
+                | PublicOplogEntry::SwitchInvocation(_) => {}
+            }
+        }
+
+        timeline
+    }
+}
+
+fn entry_timestamp(entry: &PublicOplogEntry) -> &Timestamp {
+    match entry {
+        PublicOplogEntry::Create(params) => &params.timestamp,
+        PublicOplogEntry::ImportedFunctionInvoked(params) => &params.timestamp,
+        PublicOplogEntry::ExportedFunctionInvoked(params) => &params.timestamp,
+        PublicOplogEntry::ExportedFunctionCompleted(params) => &params.timestamp,
+        PublicOplogEntry::Suspend(params) => &params.timestamp,
+        PublicOplogEntry::Error(params) => &params.timestamp,
+        PublicOplogEntry::NoOp(params) => &params.timestamp,
+        PublicOplogEntry::Jump(params) => &params.timestamp,
+        PublicOplogEntry::Interrupted(params) => &params.timestamp,
+        PublicOplogEntry::Exited(params) => &params.timestamp,
+        PublicOplogEntry::ChangeRetryPolicy(params) => &params.timestamp,
+        PublicOplogEntry::BeginAtomicRegion(params) => &params.timestamp,
+        PublicOplogEntry::EndAtomicRegion(params) => &params.timestamp,
+        PublicOplogEntry::BeginRemoteWrite(params) => &params.timestamp,
+        PublicOplogEntry::EndRemoteWrite(params) => &params.timestamp,
+        PublicOplogEntry::PendingWorkerInvocation(params) => &params.timestamp,
+        PublicOplogEntry::PendingUpdate(params) => &params.timestamp,
+        PublicOplogEntry::SuccessfulUpdate(params) => &params.timestamp,
+        PublicOplogEntry::FailedUpdate(params) => &params.timestamp,
+        PublicOplogEntry::GrowMemory(params) => &params.timestamp,
+        PublicOplogEntry::CreateResource(params) => &params.timestamp,
+        PublicOplogEntry::DropResource(params) => &params.timestamp,
+        PublicOplogEntry::DescribeResource(params) => &params.timestamp,
+        PublicOplogEntry::Log(params) => &params.timestamp,
+        PublicOplogEntry::Restart(params) => &params.timestamp,
+        PublicOplogEntry::CancelInvocation(params) => &params.timestamp,
+        PublicOplogEntry::ChangeEnvironment(params) => &params.timestamp,
+        PublicOplogEntry::Redacted(params) => &params.timestamp,
+        PublicOplogEntry::InvocationTimedOut(params) => &params.timestamp,
+        PublicOplogEntry::HoldInvocation(params) => &params.timestamp,
+        PublicOplogEntry::ReleaseInvocation(params) => &params.timestamp,
+        PublicOplogEntry::SwitchInvocation(params) => &params.timestamp,
+    }
+}
+
+fn millis_between(from: &Timestamp, to: &Timestamp) -> u64 {
+    to.to_millis().saturating_sub(from.to_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::public_oplog::{
+        Empty, ErrorParameters, ExportedFunctionCompletedParameters,
+        ExportedFunctionInvokedParameters, ImportedFunctionInvokedParameters, PublicOplogEntry,
+        PublicWrappedFunctionType, TimestampParameter,
+    };
+    use crate::model::timeline::{TimelineEventKind, TimelineInvocationStatus, WorkerTimeline};
+    use crate::model::{IdempotencyKey, Timestamp};
+    use golem_wasm_ast::analysis::analysed_type::bool;
+    use golem_wasm_rpc::{Value, ValueAndType};
+    use std::str::FromStr;
+
+    fn ts(seconds: u32) -> Timestamp {
+        Timestamp::from_str(&format!("2024-01-01T00:00:{seconds:02}Z")).unwrap()
+    }
+
+    fn invoked(seconds: u32, function_name: &str) -> PublicOplogEntry {
+        PublicOplogEntry::ExportedFunctionInvoked(ExportedFunctionInvokedParameters {
+            timestamp: ts(seconds),
+            function_name: function_name.to_string(),
+            request: vec![],
+            idempotency_key: IdempotencyKey::new(function_name.to_string()),
+        })
+    }
+
+    fn host_call(seconds: u32) -> PublicOplogEntry {
+        PublicOplogEntry::ImportedFunctionInvoked(ImportedFunctionInvokedParameters {
+            timestamp: ts(seconds),
+            function_name: "golem::api::get-self-uri".to_string(),
+            request: ValueAndType::new(Value::Bool(true), bool()),
+            response: ValueAndType::new(Value::Bool(true), bool()),
+            wrapped_function_type: PublicWrappedFunctionType::ReadLocal(Empty),
+        })
+    }
+
+    fn completed(seconds: u32) -> PublicOplogEntry {
+        PublicOplogEntry::ExportedFunctionCompleted(ExportedFunctionCompletedParameters {
+            timestamp: ts(seconds),
+            response: ValueAndType::new(Value::Bool(true), bool()),
+            consumed_fuel: 100,
+        })
+    }
+
+    fn error(seconds: u32) -> PublicOplogEntry {
+        PublicOplogEntry::Error(ErrorParameters {
+            timestamp: ts(seconds),
+            error: "trap".to_string(),
+        })
+    }
+
+    fn timeline_of(entries: &[PublicOplogEntry]) -> WorkerTimeline {
+        WorkerTimeline::from_oplog(
+            entries
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| (i as u64 + 1, entry)),
+        )
+    }
+
+    #[test]
+    fn completed_invocation_with_host_calls() {
+        let timeline = timeline_of(&[invoked(1, "f1"), host_call(2), host_call(4), completed(5)]);
+
+        assert_eq!(timeline.invocations.len(), 1);
+        let invocation = &timeline.invocations[0];
+        assert_eq!(invocation.status, TimelineInvocationStatus::Completed);
+        assert_eq!(invocation.start_index, 1);
+        assert_eq!(invocation.end_index, Some(4));
+        assert_eq!(invocation.duration_ms, Some(4000));
+        assert_eq!(invocation.consumed_fuel, Some(100));
+        assert_eq!(
+            invocation
+                .host_calls
+                .iter()
+                .map(|c| c.offset_ms)
+                .collect::<Vec<_>>(),
+            vec![1000, 3000]
+        );
+        assert_eq!(timeline.start, Some(ts(1)));
+        assert_eq!(timeline.end, Some(ts(5)));
+    }
+
+    #[test]
+    fn retried_invocation() {
+        let timeline = timeline_of(&[
+            invoked(1, "f1"),
+            error(2),
+            host_call(3),
+            completed(4),
+            invoked(5, "f2"),
+            error(6),
+        ]);
+
+        assert_eq!(timeline.invocations.len(), 2);
+        assert_eq!(
+            timeline.invocations[0].status,
+            TimelineInvocationStatus::Completed
+        );
+        assert_eq!(timeline.invocations[0].retries, 1);
+        assert_eq!(timeline.invocations[0].duration_ms, Some(3000));
+        assert_eq!(
+            timeline.invocations[1].status,
+            TimelineInvocationStatus::Failed
+        );
+        assert_eq!(timeline.invocations[1].retries, 0);
+        assert_eq!(timeline.invocations[1].end_index, Some(6));
+        assert_eq!(
+            timeline.events.iter().map(|e| e.kind).collect::<Vec<_>>(),
+            vec![TimelineEventKind::Error, TimelineEventKind::Error]
+        );
+    }
+
+    #[test]
+    fn restart_abandons_running_invocation() {
+        let timeline = timeline_of(&[
+            invoked(1, "f1"),
+            PublicOplogEntry::Restart(TimestampParameter { timestamp: ts(2) }),
+            completed(3),
+        ]);
+
+        assert_eq!(
+            timeline.invocations[0].status,
+            TimelineInvocationStatus::Running
+        );
+        assert_eq!(timeline.invocations[0].end, None);
+    }
+}
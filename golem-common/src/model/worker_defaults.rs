@@ -0,0 +1,330 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use humantime_serde::re::humantime;
+use poem_openapi::{Enum, Object};
+use serde::{Deserialize, Serialize};
+
+use crate::config::RetryConfig;
+use crate::model::public_oplog::PublicRetryConfig;
+
+/// Worker environment variable overriding the retry policy of the worker, holding the JSON
+/// encoded `RetryConfig`
+pub const RETRY_POLICY_ENV_VAR: &str = "GOLEM_RETRY_POLICY";
+/// Worker environment variable limiting the total linear memory of the worker in bytes
+pub const MAX_MEMORY_ENV_VAR: &str = "GOLEM_MAX_MEMORY";
+/// Worker environment variable making the executor unload the worker from memory after it has
+/// been idle for the given duration (for example `10m`)
+pub const IDLE_TIMEOUT_ENV_VAR: &str = "GOLEM_IDLE_TIMEOUT";
+/// Worker environment variable limiting the total size of the files in the worker's file
+/// system in bytes
+pub const MAX_DISK_USAGE_ENV_VAR: &str = "GOLEM_MAX_DISK_USAGE";
+
+/// Settings applied to newly created workers, defined for an account and optionally overridden
+/// for each of its components. The values given when creating a worker take precedence.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct WorkerDefaults {
+    #[serde(default)]
+    #[oai(default)]
+    pub env: HashMap<String, String>,
+    pub retry_policy: Option<PublicRetryConfig>,
+    /// Maximum total linear memory of a worker in bytes
+    pub max_memory: Option<u64>,
+    /// Workers idle for this long are unloaded from the executor's memory
+    pub idle_timeout_millis: Option<u64>,
+    /// Maximum total size of the files in a worker's file system in bytes
+    pub max_disk_usage: Option<u64>,
+    /// How the names of the workers created without a name are generated, `Uuid` by default
+    pub name_generation: Option<WorkerNameGeneration>,
+}
+
+/// Strategy generating the names of the workers created without a name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Enum)]
+pub enum WorkerNameGeneration {
+    /// A random UUID
+    #[default]
+    Uuid,
+    /// A ULID, which is random but sorts by creation time
+    Ulid,
+    /// Consecutive numbers for each component, starting from 1
+    Sequential,
+}
+
+impl WorkerDefaults {
+    /// Overrides these defaults with the more specific ones, merging the environments
+    pub fn merge(mut self, overrides: WorkerDefaults) -> WorkerDefaults {
+        self.env.extend(overrides.env);
+        WorkerDefaults {
+            env: self.env,
+            retry_policy: overrides.retry_policy.or(self.retry_policy),
+            max_memory: overrides.max_memory.or(self.max_memory),
+            idle_timeout_millis: overrides.idle_timeout_millis.or(self.idle_timeout_millis),
+            max_disk_usage: overrides.max_disk_usage.or(self.max_disk_usage),
+            name_generation: overrides.name_generation.or(self.name_generation),
+        }
+    }
+
+    /// Computes the environment of a new worker, applying the defaults to the environment
+    /// variables given for the worker. The retry policy and the limits are passed to the
+    /// executor as reserved environment variables.
+    pub fn apply(&self, env: HashMap<String, String>) -> HashMap<String, String> {
+        let mut result = self.env.clone();
+        if let Some(retry_policy) = &self.retry_policy {
+            let retry_config: RetryConfig = retry_policy.clone().into();
+            if let Ok(json) = serde_json::to_string(&retry_config) {
+                result.insert(RETRY_POLICY_ENV_VAR.to_string(), json);
+            }
+        }
+        if let Some(max_memory) = self.max_memory {
+            result.insert(MAX_MEMORY_ENV_VAR.to_string(), max_memory.to_string());
+        }
+        if let Some(idle_timeout_millis) = self.idle_timeout_millis {
+            result.insert(
+                IDLE_TIMEOUT_ENV_VAR.to_string(),
+                humantime::format_duration(Duration::from_millis(idle_timeout_millis)).to_string(),
+            );
+        }
+        if let Some(max_disk_usage) = self.max_disk_usage {
+            result.insert(
+                MAX_DISK_USAGE_ENV_VAR.to_string(),
+                max_disk_usage.to_string(),
+            );
+        }
+        result.extend(env);
+        result
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        for key in self.env.keys() {
+            if key.is_empty() {
+                errors.push("Environment variable names cannot be empty".to_string());
+            } else if is_reserved_env_var(key) {
+                errors.push(format!(
+                    "Environment variable {key} is set by the retry policy and the limits"
+                ));
+            }
+        }
+        if let Some(retry_policy) = &self.retry_policy {
+            if retry_policy.min_delay > retry_policy.max_delay {
+                errors.push("The minimum retry delay cannot exceed the maximum delay".to_string());
+            }
+            if retry_policy.multiplier < 1.0 {
+                errors.push("The retry delay multiplier must be at least 1".to_string());
+            }
+        }
+        if self.max_memory == Some(0) {
+            errors.push("The maximum memory must be positive".to_string());
+        }
+        if self.idle_timeout_millis == Some(0) {
+            errors.push("The idle timeout must be positive".to_string());
+        }
+        if self.max_disk_usage == Some(0) {
+            errors.push("The maximum disk usage must be positive".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn is_reserved_env_var(key: &str) -> bool {
+    key == RETRY_POLICY_ENV_VAR
+        || key == MAX_MEMORY_ENV_VAR
+        || key == IDLE_TIMEOUT_ENV_VAR
+        || key == MAX_DISK_USAGE_ENV_VAR
+}
+
+/// The environment visible to the worker itself, without the reserved variables configuring the
+/// executor
+pub fn guest_env(env: &[(String, String)]) -> Vec<(String, String)> {
+    env.iter()
+        .filter(|(key, _)| !is_reserved_env_var(key))
+        .cloned()
+        .collect()
+}
+
+fn find_env_var<'a>(env: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    env.iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.as_str())
+}
+
+const CROCKFORD_BASE32: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Encodes a ULID made of the lower 48 bits of `timestamp_millis` and the lower 80 bits of
+/// `randomness` as its 26 characters long canonical string
+pub fn ulid(timestamp_millis: u64, randomness: u128) -> String {
+    let value =
+        ((timestamp_millis as u128 & 0xFFFF_FFFF_FFFF) << 80) | (randomness & ((1u128 << 80) - 1));
+    (0..26)
+        .rev()
+        .map(|i| CROCKFORD_BASE32[((value >> (i * 5)) & 0x1F) as usize] as char)
+        .collect()
+}
+
+/// The retry policy set for the worker through its environment, if any
+pub fn retry_policy_from_env(env: &[(String, String)]) -> Option<RetryConfig> {
+    find_env_var(env, RETRY_POLICY_ENV_VAR).and_then(|value| serde_json::from_str(value).ok())
+}
+
+/// The memory limit set for the worker through its environment, if any
+pub fn max_memory_from_env(env: &[(String, String)]) -> Option<u64> {
+    find_env_var(env, MAX_MEMORY_ENV_VAR).and_then(|value| value.parse().ok())
+}
+
+/// The idle timeout set for the worker through its environment, if any
+pub fn idle_timeout_from_env(env: &[(String, String)]) -> Option<Duration> {
+    find_env_var(env, IDLE_TIMEOUT_ENV_VAR).and_then(|value| humantime::parse_duration(value).ok())
+}
+
+/// The file system size limit set for the worker through its environment, if any
+pub fn max_disk_usage_from_env(env: &[(String, String)]) -> Option<u64> {
+    find_env_var(env, MAX_DISK_USAGE_ENV_VAR).and_then(|value| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use crate::model::public_oplog::PublicRetryConfig;
+    use crate::model::worker_defaults::{
+        guest_env, idle_timeout_from_env, max_disk_usage_from_env, max_memory_from_env,
+        retry_policy_from_env, ulid, WorkerDefaults, WorkerNameGeneration,
+    };
+
+    fn env(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn component_defaults_override_account_defaults() {
+        let account = WorkerDefaults {
+            env: env(&[("A", "account"), ("B", "account")]),
+            max_memory: Some(1024),
+            idle_timeout_millis: Some(60000),
+            ..Default::default()
+        };
+        let component = WorkerDefaults {
+            env: env(&[("B", "component")]),
+            max_memory: Some(2048),
+            name_generation: Some(WorkerNameGeneration::Sequential),
+            ..Default::default()
+        };
+
+        let merged = account.merge(component);
+
+        assert_eq!(merged.env, env(&[("A", "account"), ("B", "component")]));
+        assert_eq!(merged.max_memory, Some(2048));
+        assert_eq!(merged.idle_timeout_millis, Some(60000));
+        assert_eq!(
+            merged.name_generation,
+            Some(WorkerNameGeneration::Sequential)
+        );
+    }
+
+    #[test]
+    fn worker_env_overrides_defaults() {
+        let defaults = WorkerDefaults {
+            env: env(&[("A", "default"), ("B", "default")]),
+            retry_policy: Some(PublicRetryConfig {
+                max_attempts: 3,
+                min_delay: Duration::from_millis(100),
+                max_delay: Duration::from_secs(1),
+                multiplier: 2.0,
+                max_jitter_factor: None,
+            }),
+            max_memory: Some(1024),
+            idle_timeout_millis: Some(90000),
+            max_disk_usage: Some(4096),
+            name_generation: None,
+        };
+
+        let result: Vec<(String, String)> = defaults
+            .apply(env(&[("B", "worker")]))
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            result
+                .iter()
+                .find(|(k, _)| k == "B")
+                .map(|(_, v)| v.as_str()),
+            Some("worker")
+        );
+        assert_eq!(
+            result
+                .iter()
+                .find(|(k, _)| k == "A")
+                .map(|(_, v)| v.as_str()),
+            Some("default")
+        );
+        assert_eq!(
+            retry_policy_from_env(&result).map(|policy| policy.max_attempts),
+            Some(3)
+        );
+        assert_eq!(max_memory_from_env(&result), Some(1024));
+        assert_eq!(
+            idle_timeout_from_env(&result),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(max_disk_usage_from_env(&result), Some(4096));
+
+        let mut visible = guest_env(&result);
+        visible.sort();
+        assert_eq!(
+            visible,
+            vec![
+                ("A".to_string(), "default".to_string()),
+                ("B".to_string(), "worker".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn reserved_env_vars_are_rejected() {
+        let defaults = WorkerDefaults {
+            env: env(&[("GOLEM_MAX_MEMORY", "1")]),
+            max_memory: Some(0),
+            ..Default::default()
+        };
+
+        assert_eq!(defaults.validate().map_err(|errors| errors.len()), Err(2));
+    }
+
+    #[test]
+    fn ulids_are_canonical_and_sort_by_time() {
+        assert_eq!(ulid(0, 0), "00000000000000000000000000");
+        assert_eq!(ulid(u64::MAX, u128::MAX), "7ZZZZZZZZZZZZZZZZZZZZZZZZZ");
+        assert_eq!(&ulid(1469922850259, 0)[..10], "01ARZ3NDEK");
+
+        let earlier = ulid(1_700_000_000_000, u128::MAX);
+        let later = ulid(1_700_000_000_001, 0);
+        assert!(earlier < later);
+    }
+}
@@ -0,0 +1,363 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Textual worker filter language.
+//!
+//! ```text
+//! filter     := or
+//! or         := and (("||" | "OR") and)*
+//! and        := unary (("&&" | "AND") unary)*
+//! unary      := ("!" | "NOT") unary | "(" filter ")" | condition
+//! condition  := property comparator value
+//! property   := "name" | "version" | "status" | "created_at" | "createdAt" | "expires_at"
+//!               | "expiresAt" | "env." key
+//! value      := bare-word | "double quoted string"
+//! ```
+//!
+//! For example: `status == Running && env.REGION == "eu" && created_at > 2024-06-01`
+
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+
+use crate::model::{Timestamp, WorkerFilter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Comparator(String),
+    Word(String),
+    Quoted(String),
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+}
+
+pub(crate) fn parse_worker_filter(s: &str) -> Result<WorkerFilter, String> {
+    let tokens = tokenize(s)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let filter = parser.parse_or()?;
+    match parser.peek() {
+        None => Ok(filter),
+        Some(token) => Err(format!(
+            "Invalid filter: {s}, unexpected {}",
+            describe(token)
+        )),
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => tokens.push(Token::LParen),
+            ')' => tokens.push(Token::RParen),
+            '&' => {
+                expect_char(&mut chars, '&', s)?;
+                tokens.push(Token::And);
+            }
+            '|' => {
+                expect_char(&mut chars, '|', s)?;
+                tokens.push(Token::Or);
+            }
+            '!' => {
+                if chars.next_if_eq(&'=').is_some() {
+                    tokens.push(Token::Comparator("!=".to_string()));
+                } else {
+                    tokens.push(Token::Not);
+                }
+            }
+            '=' | '<' | '>' => {
+                let mut comparator = c.to_string();
+                if let Some(eq) = chars.next_if_eq(&'=') {
+                    comparator.push(eq);
+                }
+                tokens.push(Token::Comparator(comparator));
+            }
+            '"' => tokens.push(Token::Quoted(quoted(&mut chars, s)?)),
+            c => {
+                let mut word = c.to_string();
+                while let Some(c) = chars.next_if(|c| !is_delimiter(*c)) {
+                    word.push(c);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '(' | ')' | '&' | '|' | '!' | '=' | '<' | '>' | '"')
+}
+
+fn expect_char(chars: &mut Peekable<Chars>, expected: char, s: &str) -> Result<(), String> {
+    match chars.next() {
+        Some(c) if c == expected => Ok(()),
+        _ => Err(format!(
+            "Invalid filter: {s}, expected '{expected}{expected}'"
+        )),
+    }
+}
+
+fn quoted(chars: &mut Peekable<Chars>, s: &str) -> Result<String, String> {
+    let mut result = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => break Ok(result),
+            Some('\\') => match chars.next() {
+                Some(c) => result.push(c),
+                None => break Err(format!("Invalid filter: {s}, unterminated string")),
+            },
+            Some(c) => result.push(c),
+            None => break Err(format!("Invalid filter: {s}, unterminated string")),
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::LParen => "'('".to_string(),
+        Token::RParen => "')'".to_string(),
+        Token::And => "'&&'".to_string(),
+        Token::Or => "'||'".to_string(),
+        Token::Not => "'!'".to_string(),
+        Token::Comparator(comparator) => format!("'{comparator}'"),
+        Token::Word(word) => format!("'{word}'"),
+        Token::Quoted(value) => format!("\"{value}\""),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next_token(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn next_is(&self, f: impl Fn(&Token) -> bool) -> bool {
+        self.peek().map(f).unwrap_or(false)
+    }
+
+    fn parse_or(&mut self) -> Result<WorkerFilter, String> {
+        let mut filters = vec![self.parse_and()?];
+        while self.next_is(|t| *t == Token::Or || t.is_keyword("or")) {
+            self.next_token();
+            filters.push(self.parse_and()?);
+        }
+        Ok(if filters.len() == 1 {
+            filters.pop().unwrap()
+        } else {
+            WorkerFilter::new_or(filters)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<WorkerFilter, String> {
+        let mut filters = vec![self.parse_unary()?];
+        while self.next_is(|t| *t == Token::And || t.is_keyword("and")) {
+            self.next_token();
+            filters.push(self.parse_unary()?);
+        }
+        Ok(if filters.len() == 1 {
+            filters.pop().unwrap()
+        } else {
+            WorkerFilter::new_and(filters)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<WorkerFilter, String> {
+        match self.next_token() {
+            Some(Token::Not) => Ok(self.parse_unary()?.not()),
+            Some(token) if token.is_keyword("not") => Ok(self.parse_unary()?.not()),
+            Some(Token::LParen) => {
+                let filter = self.parse_or()?;
+                match self.next_token() {
+                    Some(Token::RParen) => Ok(filter),
+                    Some(token) => Err(format!("Expected ')', got {}", describe(&token))),
+                    None => Err("Expected ')' at the end of the filter".to_string()),
+                }
+            }
+            Some(Token::Word(property)) => self.parse_condition(property),
+            Some(token) => Err(format!(
+                "Expected a filter condition, got {}",
+                describe(&token)
+            )),
+            None => Err("Expected a filter condition at the end of the filter".to_string()),
+        }
+    }
+
+    fn parse_condition(&mut self, property: String) -> Result<WorkerFilter, String> {
+        let comparator = match self.next_token() {
+            Some(Token::Comparator(comparator)) | Some(Token::Word(comparator)) => comparator,
+            Some(token) => {
+                return Err(format!(
+                    "Expected a comparator after '{property}', got {}",
+                    describe(&token)
+                ))
+            }
+            None => return Err(format!("Expected a comparator after '{property}'")),
+        };
+        let value = match self.next_token() {
+            Some(Token::Word(value)) | Some(Token::Quoted(value)) => value,
+            Some(token) => {
+                return Err(format!(
+                    "Expected a value after '{property} {comparator}', got {}",
+                    describe(&token)
+                ))
+            }
+            None => return Err(format!("Expected a value after '{property} {comparator}'")),
+        };
+
+        match property.as_str() {
+            "name" => Ok(WorkerFilter::new_name(comparator.parse()?, value)),
+            "version" => Ok(WorkerFilter::new_version(
+                comparator.parse()?,
+                value
+                    .parse()
+                    .map_err(|e| format!("Invalid filter value: {}", e))?,
+            )),
+            "status" => Ok(WorkerFilter::new_status(
+                comparator.parse()?,
+                value.parse()?,
+            )),
+            "created_at" | "createdAt" => Ok(WorkerFilter::new_created_at(
+                comparator.parse()?,
+                parse_timestamp(&value)?,
+            )),
+            "expires_at" | "expiresAt" => Ok(WorkerFilter::new_expires_at(
+                comparator.parse()?,
+                parse_timestamp(&value)?,
+            )),
+            _ if property.starts_with("env.") && property.len() > 4 => Ok(WorkerFilter::new_env(
+                property[4..].to_string(),
+                comparator.parse()?,
+                value,
+            )),
+            _ => Err(format!("Unknown filter property: {property}")),
+        }
+    }
+}
+
+/// Accepts full ISO 8601 timestamps and plain dates, the latter meaning midnight UTC
+fn parse_timestamp(value: &str) -> Result<Timestamp, String> {
+    Timestamp::from_str(value).or_else(|err| {
+        if value.len() == 10 {
+            Timestamp::from_str(&format!("{value}T00:00:00Z"))
+        } else {
+            Err(err)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::str::FromStr;
+
+    use crate::model::{
+        FilterComparator, StringFilterComparator, Timestamp, WorkerFilter, WorkerStatus,
+    };
+
+    #[test]
+    fn single_conditions() {
+        assert_eq!(
+            WorkerFilter::from_str("name == worker-1").unwrap(),
+            WorkerFilter::new_name(StringFilterComparator::Equal, "worker-1".to_string())
+        );
+        assert_eq!(
+            WorkerFilter::from_str("name like \"my worker\"").unwrap(),
+            WorkerFilter::new_name(StringFilterComparator::Like, "my worker".to_string())
+        );
+        assert_eq!(
+            WorkerFilter::from_str("version<3").unwrap(),
+            WorkerFilter::new_version(FilterComparator::Less, 3)
+        );
+        assert_eq!(
+            WorkerFilter::from_str("expires_at <= 2024-06-01").unwrap(),
+            WorkerFilter::new_expires_at(
+                FilterComparator::LessEqual,
+                Timestamp::from_str("2024-06-01T00:00:00Z").unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn combined_conditions() {
+        assert_eq!(
+            WorkerFilter::from_str(
+                "status == Running && env.REGION == \"eu\" && created_at > 2024-06-01"
+            )
+            .unwrap(),
+            WorkerFilter::new_and(vec![
+                WorkerFilter::new_status(FilterComparator::Equal, WorkerStatus::Running),
+                WorkerFilter::new_env(
+                    "REGION".to_string(),
+                    StringFilterComparator::Equal,
+                    "eu".to_string()
+                ),
+                WorkerFilter::new_created_at(
+                    FilterComparator::Greater,
+                    Timestamp::from_str("2024-06-01T00:00:00Z").unwrap()
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn precedence_and_grouping() {
+        let name = |n: &str| WorkerFilter::new_name(StringFilterComparator::Equal, n.to_string());
+
+        assert_eq!(
+            WorkerFilter::from_str("name = a || name = b && name = c").unwrap(),
+            WorkerFilter::new_or(vec![
+                name("a"),
+                WorkerFilter::new_and(vec![name("b"), name("c")])
+            ])
+        );
+        assert_eq!(
+            WorkerFilter::from_str("!(name = a OR name = b) and name != c").unwrap(),
+            WorkerFilter::new_and(vec![
+                WorkerFilter::new_or(vec![name("a"), name("b")]).not(),
+                WorkerFilter::new_name(StringFilterComparator::NotEqual, "c".to_string())
+            ])
+        );
+    }
+
+    #[test]
+    fn invalid_filters() {
+        assert!(WorkerFilter::from_str("name ==").is_err());
+        assert!(WorkerFilter::from_str("(name == a").is_err());
+        assert!(WorkerFilter::from_str("name == a &&").is_err());
+        assert!(WorkerFilter::from_str("unknown == a").is_err());
+        assert!(WorkerFilter::from_str("name == \"a").is_err());
+    }
+}
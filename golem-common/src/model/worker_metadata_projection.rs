@@ -0,0 +1,257 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use golem_api_grpc::proto::golem::worker::WorkerMetadata;
+use poem_openapi::Enum;
+use serde::{Deserialize, Serialize};
+
+/// A part of the worker metadata which can be selected when listing workers
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Enum)]
+pub enum WorkerMetadataField {
+    Args,
+    Env,
+    ComponentVersion,
+    RetryCount,
+    PendingInvocationCount,
+    Updates,
+    /// The last error, its kind and its fingerprint
+    LastError,
+    ComponentSize,
+    TotalLinearMemorySize,
+    OwnedResources,
+    ExpiresAt,
+    DiskUsage,
+}
+
+impl From<WorkerMetadataField> for golem_api_grpc::proto::golem::worker::WorkerMetadataField {
+    fn from(value: WorkerMetadataField) -> Self {
+        use golem_api_grpc::proto::golem::worker::WorkerMetadataField as Proto;
+        match value {
+            WorkerMetadataField::Args => Proto::Args,
+            WorkerMetadataField::Env => Proto::Env,
+            WorkerMetadataField::ComponentVersion => Proto::ComponentVersion,
+            WorkerMetadataField::RetryCount => Proto::RetryCount,
+            WorkerMetadataField::PendingInvocationCount => Proto::PendingInvocationCount,
+            WorkerMetadataField::Updates => Proto::Updates,
+            WorkerMetadataField::LastError => Proto::LastError,
+            WorkerMetadataField::ComponentSize => Proto::ComponentSize,
+            WorkerMetadataField::TotalLinearMemorySize => Proto::TotalLinearMemorySize,
+            WorkerMetadataField::OwnedResources => Proto::OwnedResources,
+            WorkerMetadataField::ExpiresAt => Proto::ExpiresAt,
+            WorkerMetadataField::DiskUsage => Proto::DiskUsage,
+        }
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerMetadataField> for WorkerMetadataField {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::WorkerMetadataField,
+    ) -> Result<Self, Self::Error> {
+        use golem_api_grpc::proto::golem::worker::WorkerMetadataField as Proto;
+        match value {
+            Proto::Unspecified => Err("Unspecified worker metadata field".to_string()),
+            Proto::Args => Ok(WorkerMetadataField::Args),
+            Proto::Env => Ok(WorkerMetadataField::Env),
+            Proto::ComponentVersion => Ok(WorkerMetadataField::ComponentVersion),
+            Proto::RetryCount => Ok(WorkerMetadataField::RetryCount),
+            Proto::PendingInvocationCount => Ok(WorkerMetadataField::PendingInvocationCount),
+            Proto::Updates => Ok(WorkerMetadataField::Updates),
+            Proto::LastError => Ok(WorkerMetadataField::LastError),
+            Proto::ComponentSize => Ok(WorkerMetadataField::ComponentSize),
+            Proto::TotalLinearMemorySize => Ok(WorkerMetadataField::TotalLinearMemorySize),
+            Proto::OwnedResources => Ok(WorkerMetadataField::OwnedResources),
+            Proto::ExpiresAt => Ok(WorkerMetadataField::ExpiresAt),
+            Proto::DiskUsage => Ok(WorkerMetadataField::DiskUsage),
+        }
+    }
+}
+
+/// Selects the parts of the worker metadata to return when listing workers, to reduce the size of
+/// the responses. The worker id, account id, status and creation time are always returned, the
+/// fields which are not selected are left empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkerMetadataProjection {
+    pub fields: HashSet<WorkerMetadataField>,
+}
+
+impl WorkerMetadataProjection {
+    pub fn includes(&self, field: WorkerMetadataField) -> bool {
+        self.fields.contains(&field)
+    }
+
+    /// Clears the fields of `metadata` which are not selected
+    pub fn apply(&self, metadata: &mut WorkerMetadata) {
+        if !self.includes(WorkerMetadataField::Args) {
+            metadata.args.clear();
+        }
+        if !self.includes(WorkerMetadataField::Env) {
+            metadata.env.clear();
+        }
+        if !self.includes(WorkerMetadataField::ComponentVersion) {
+            metadata.component_version = 0;
+        }
+        if !self.includes(WorkerMetadataField::RetryCount) {
+            metadata.retry_count = 0;
+        }
+        if !self.includes(WorkerMetadataField::PendingInvocationCount) {
+            metadata.pending_invocation_count = 0;
+        }
+        if !self.includes(WorkerMetadataField::Updates) {
+            metadata.updates.clear();
+        }
+        if !self.includes(WorkerMetadataField::LastError) {
+            metadata.last_error = None;
+            metadata.last_error_kind = None;
+            metadata.last_error_fingerprint = None;
+        }
+        if !self.includes(WorkerMetadataField::ComponentSize) {
+            metadata.component_size = 0;
+        }
+        if !self.includes(WorkerMetadataField::TotalLinearMemorySize) {
+            metadata.total_linear_memory_size = 0;
+        }
+        if !self.includes(WorkerMetadataField::OwnedResources) {
+            metadata.owned_resources.clear();
+        }
+        if !self.includes(WorkerMetadataField::ExpiresAt) {
+            metadata.expires_at = None;
+        }
+        if !self.includes(WorkerMetadataField::DiskUsage) {
+            metadata.disk_usage = 0;
+        }
+    }
+}
+
+impl FromIterator<WorkerMetadataField> for WorkerMetadataProjection {
+    fn from_iter<T: IntoIterator<Item = WorkerMetadataField>>(iter: T) -> Self {
+        Self {
+            fields: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerMetadataProjection>
+    for WorkerMetadataProjection
+{
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::WorkerMetadataProjection,
+    ) -> Result<Self, Self::Error> {
+        // Fields unknown to this version are not returned
+        value.fields().map(WorkerMetadataField::try_from).collect()
+    }
+}
+
+impl From<WorkerMetadataProjection>
+    for golem_api_grpc::proto::golem::worker::WorkerMetadataProjection
+{
+    fn from(value: WorkerMetadataProjection) -> Self {
+        Self {
+            fields: value
+                .fields
+                .into_iter()
+                .map(|field| {
+                    golem_api_grpc::proto::golem::worker::WorkerMetadataField::from(field) as i32
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+
+    use golem_api_grpc::proto::golem::worker::WorkerMetadata;
+
+    use super::{WorkerMetadataField, WorkerMetadataProjection};
+
+    #[test]
+    fn projection_clears_unselected_fields() {
+        let mut metadata = WorkerMetadata {
+            args: vec!["arg".to_string()],
+            env: HashMap::from([("KEY".to_string(), "value".to_string())]),
+            status: 1,
+            component_version: 3,
+            last_error: Some("error".to_string()),
+            disk_usage: 1024,
+            ..WorkerMetadata::default()
+        };
+
+        let projection: WorkerMetadataProjection =
+            [WorkerMetadataField::Env, WorkerMetadataField::DiskUsage]
+                .into_iter()
+                .collect();
+        projection.apply(&mut metadata);
+
+        assert!(metadata.args.is_empty());
+        assert_eq!(metadata.env.len(), 1);
+        assert_eq!(metadata.status, 1);
+        assert_eq!(metadata.component_version, 0);
+        assert_eq!(metadata.last_error, None);
+        assert_eq!(metadata.disk_usage, 1024);
+    }
+
+    #[test]
+    fn projection_proto_roundtrip() {
+        let projection: WorkerMetadataProjection = [
+            WorkerMetadataField::Args,
+            WorkerMetadataField::LastError,
+            WorkerMetadataField::OwnedResources,
+        ]
+        .into_iter()
+        .collect();
+
+        let proto: golem_api_grpc::proto::golem::worker::WorkerMetadataProjection =
+            projection.clone().into();
+        assert_eq!(WorkerMetadataProjection::try_from(proto), Ok(projection));
+    }
+
+    #[test]
+    fn projection_with_unspecified_field_is_rejected() {
+        let proto = golem_api_grpc::proto::golem::worker::WorkerMetadataProjection {
+            fields: vec![
+                golem_api_grpc::proto::golem::worker::WorkerMetadataField::Env as i32,
+                golem_api_grpc::proto::golem::worker::WorkerMetadataField::Unspecified as i32,
+            ],
+        };
+
+        assert_eq!(
+            WorkerMetadataProjection::try_from(proto),
+            Err("Unspecified worker metadata field".to_string())
+        );
+    }
+
+    #[test]
+    fn projection_ignores_unknown_fields() {
+        let proto = golem_api_grpc::proto::golem::worker::WorkerMetadataProjection {
+            fields: vec![
+                golem_api_grpc::proto::golem::worker::WorkerMetadataField::Env as i32,
+                1000,
+            ],
+        };
+
+        assert_eq!(
+            WorkerMetadataProjection::try_from(proto),
+            Ok([WorkerMetadataField::Env].into_iter().collect())
+        );
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use golem_api_grpc::proto::golem::worker::worker_name_query;
+
+/// Finds workers by their name, without scanning their metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkerNameQuery {
+    /// Names starting with the given prefix
+    Prefix(String),
+    /// Names containing all characters of the pattern in the same order, ignoring case.
+    /// For example `ordsvc` matches `order-service-1`.
+    Pattern(String),
+}
+
+impl WorkerNameQuery {
+    /// Returns at most `count` of the matching names, the closest matches first
+    pub fn search(&self, names: impl IntoIterator<Item = String>, count: usize) -> Vec<String> {
+        let mut matches: Vec<(usize, String)> = names
+            .into_iter()
+            .filter_map(|name| self.score(&name).map(|score| (score, name)))
+            .collect();
+        matches.sort();
+        matches
+            .into_iter()
+            .take(count)
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    /// Lower scores are closer matches, `None` means the name does not match at all
+    fn score(&self, name: &str) -> Option<usize> {
+        match self {
+            WorkerNameQuery::Prefix(prefix) => name.starts_with(prefix.as_str()).then_some(0),
+            WorkerNameQuery::Pattern(pattern) => {
+                // Counts the skipped characters before and between the matched ones
+                let name: Vec<char> = name.to_lowercase().chars().collect();
+                let mut score = 0;
+                let mut pos = 0;
+                for c in pattern.to_lowercase().chars() {
+                    let skipped = name[pos..].iter().position(|n| *n == c)?;
+                    score += skipped;
+                    pos += skipped + 1;
+                }
+                Some(score)
+            }
+        }
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerNameQuery> for WorkerNameQuery {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::WorkerNameQuery,
+    ) -> Result<Self, Self::Error> {
+        match value.query {
+            Some(worker_name_query::Query::Prefix(prefix)) => Ok(WorkerNameQuery::Prefix(prefix)),
+            Some(worker_name_query::Query::Pattern(pattern)) => {
+                Ok(WorkerNameQuery::Pattern(pattern))
+            }
+            None => Err("Missing query".to_string()),
+        }
+    }
+}
+
+impl From<WorkerNameQuery> for golem_api_grpc::proto::golem::worker::WorkerNameQuery {
+    fn from(value: WorkerNameQuery) -> Self {
+        let query = match value {
+            WorkerNameQuery::Prefix(prefix) => worker_name_query::Query::Prefix(prefix),
+            WorkerNameQuery::Pattern(pattern) => worker_name_query::Query::Pattern(pattern),
+        };
+        Self { query: Some(query) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::WorkerNameQuery;
+
+    fn names() -> Vec<String> {
+        vec![
+            "payment-worker".to_string(),
+            "order-service-2".to_string(),
+            "order-service-1".to_string(),
+            "OrderArchive".to_string(),
+        ]
+    }
+
+    #[test]
+    fn prefix_search() {
+        let query = WorkerNameQuery::Prefix("order-".to_string());
+        assert_eq!(
+            query.search(names(), 10),
+            vec!["order-service-1".to_string(), "order-service-2".to_string()]
+        );
+        assert_eq!(
+            query.search(names(), 1),
+            vec!["order-service-1".to_string()]
+        );
+    }
+
+    #[test]
+    fn pattern_search() {
+        let query = WorkerNameQuery::Pattern("ordsvc2".to_string());
+        assert_eq!(
+            query.search(names(), 10),
+            vec!["order-service-2".to_string()]
+        );
+
+        let query = WorkerNameQuery::Pattern("ORDER".to_string());
+        assert_eq!(
+            query.search(names(), 10),
+            vec![
+                "OrderArchive".to_string(),
+                "order-service-1".to_string(),
+                "order-service-2".to_string()
+            ]
+        );
+
+        let query = WorkerNameQuery::Pattern("arc".to_string());
+        assert_eq!(query.search(names(), 10), vec!["OrderArchive".to_string()]);
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::model::{Timestamp, WorkerId, WorkerMetadata, WorkerStatus};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkerStatusEventKind {
+    Created,
+    StatusChanged,
+    Deleted,
+}
+
+impl From<WorkerStatusEventKind> for golem_api_grpc::proto::golem::worker::WorkerStatusEventKind {
+    fn from(value: WorkerStatusEventKind) -> Self {
+        use golem_api_grpc::proto::golem::worker::WorkerStatusEventKind as Proto;
+        match value {
+            WorkerStatusEventKind::Created => Proto::Created,
+            WorkerStatusEventKind::StatusChanged => Proto::StatusChanged,
+            WorkerStatusEventKind::Deleted => Proto::Deleted,
+        }
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerStatusEventKind>
+    for WorkerStatusEventKind
+{
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::WorkerStatusEventKind,
+    ) -> Result<Self, Self::Error> {
+        use golem_api_grpc::proto::golem::worker::WorkerStatusEventKind as Proto;
+        match value {
+            Proto::Unspecified => Err("Unspecified worker status event kind".to_string()),
+            Proto::Created => Ok(WorkerStatusEventKind::Created),
+            Proto::StatusChanged => Ok(WorkerStatusEventKind::StatusChanged),
+            Proto::Deleted => Ok(WorkerStatusEventKind::Deleted),
+        }
+    }
+}
+
+/// A change of a worker's status, broadcast by the worker executor running it so clients can
+/// watch the workers instead of polling their metadata
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkerStatusEvent {
+    pub worker_id: WorkerId,
+    pub timestamp: Timestamp,
+    pub kind: WorkerStatusEventKind,
+    /// The status of the worker after the change, or its last status if it got deleted
+    pub status: WorkerStatus,
+}
+
+impl WorkerStatusEvent {
+    pub fn new(kind: WorkerStatusEventKind, metadata: &WorkerMetadata) -> Self {
+        Self {
+            worker_id: metadata.worker_id.clone(),
+            timestamp: Timestamp::now_utc(),
+            kind,
+            status: metadata.last_known_status.status.clone(),
+        }
+    }
+}
+
+impl From<WorkerStatusEvent> for golem_api_grpc::proto::golem::worker::WorkerStatusEvent {
+    fn from(value: WorkerStatusEvent) -> Self {
+        Self {
+            worker_id: Some(value.worker_id.into()),
+            timestamp: Some(value.timestamp.into()),
+            kind: golem_api_grpc::proto::golem::worker::WorkerStatusEventKind::from(value.kind)
+                as i32,
+            status: value.status.into(),
+        }
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerStatusEvent> for WorkerStatusEvent {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::WorkerStatusEvent,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            worker_id: value.worker_id.ok_or("Missing worker_id")?.try_into()?,
+            timestamp: value.timestamp.ok_or("Missing timestamp")?.into(),
+            kind: value.kind().try_into()?,
+            status: value.status.try_into()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::{ComponentId, Timestamp, WorkerId, WorkerStatus};
+
+    use super::{WorkerStatusEvent, WorkerStatusEventKind};
+
+    #[test]
+    fn worker_status_event_proto_roundtrip() {
+        let event = WorkerStatusEvent {
+            worker_id: WorkerId {
+                component_id: ComponentId::new_v4(),
+                worker_name: "worker".to_string(),
+            },
+            timestamp: Timestamp::from(1_724_000_000_123),
+            kind: WorkerStatusEventKind::StatusChanged,
+            status: WorkerStatus::Failed,
+        };
+
+        let proto: golem_api_grpc::proto::golem::worker::WorkerStatusEvent = event.clone().into();
+        assert_eq!(WorkerStatusEvent::try_from(proto), Ok(event));
+    }
+
+    #[test]
+    fn worker_status_event_with_unspecified_kind_is_rejected() {
+        let event = WorkerStatusEvent {
+            worker_id: WorkerId {
+                component_id: ComponentId::new_v4(),
+                worker_name: "worker".to_string(),
+            },
+            timestamp: Timestamp::from(1_724_000_000_123),
+            kind: WorkerStatusEventKind::Created,
+            status: WorkerStatus::Idle,
+        };
+
+        let mut proto: golem_api_grpc::proto::golem::worker::WorkerStatusEvent = event.into();
+        proto.kind =
+            golem_api_grpc::proto::golem::worker::WorkerStatusEventKind::Unspecified as i32;
+        assert_eq!(
+            WorkerStatusEvent::try_from(proto),
+            Err("Unspecified worker status event kind".to_string())
+        );
+    }
+}
@@ -13,8 +13,9 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::atomic::AtomicBool;
-use std::sync::{atomic, Arc};
+use std::sync::{atomic, Arc, Mutex};
 use std::time::Instant;
 
 use bincode::{Decode, Encode};
@@ -24,11 +25,13 @@ use fred::cmd;
 use fred::prelude::{RedisPool as FredRedisPool, *};
 use fred::types::{
     InfoKind, Limit, MultipleKeys, MultipleOrderedPairs, MultipleValues, MultipleZaddValues,
-    Ordering, RedisKey, RedisMap, XCap, ZRange, ZSort, XID,
+    Ordering, RedisKey, RedisMap, Server, XCap, ZRange, ZSort, XID,
 };
-use tracing::{debug, Level};
+use tracing::{debug, warn, Level};
+use url::{Host, Url};
 
-use crate::metrics::redis::{record_redis_failure, record_redis_success};
+use crate::config::RedisSentinelConfig;
+use crate::metrics::redis::{record_redis_failure, record_redis_reconnect, record_redis_success};
 use crate::serialization::{deserialize, serialize};
 
 // Re-export fred Error
@@ -56,6 +59,9 @@ impl RedisPool {
         redis_config.tracing.default_tracing_level = Level::DEBUG;
         redis_config.username.clone_from(&config.username);
         redis_config.password.clone_from(&config.password);
+        if let Some(sentinel) = &config.sentinel {
+            redis_config.server = sentinel_server_config(sentinel)?;
+        }
 
         // NOTE: jitter setting is not converted, using the default fred jitter settings
         let policy = ReconnectPolicy::new_exponential(
@@ -65,6 +71,9 @@ impl RedisPool {
             config.retries.multiplier.round() as u32,
         );
         let pool = FredRedisPool::new(redis_config, None, None, Some(policy), config.pool_size)?;
+        for client in pool.clients() {
+            observe_reconnects(client);
+        }
 
         Ok(RedisPool {
             pool,
@@ -96,6 +105,87 @@ impl RedisPool {
     }
 }
 
+fn sentinel_server_config(sentinel: &RedisSentinelConfig) -> Result<ServerConfig, RedisError> {
+    if sentinel.hosts.is_empty() {
+        return Err(RedisError::new(
+            RedisErrorKind::Config,
+            "At least one Redis sentinel host must be configured",
+        ));
+    }
+
+    let hosts = sentinel
+        .hosts
+        .iter()
+        .map(|host| sentinel_server(host))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ServerConfig::Sentinel {
+        hosts,
+        service_name: sentinel.master_name.clone(),
+        username: sentinel.username.clone(),
+        password: sentinel.password.clone(),
+    })
+}
+
+/// The sentinel endpoints are given as `host:port`, with IPv6 addresses in brackets as in
+/// `[::1]:26379`. Without a port the default sentinel port is used.
+fn sentinel_server(host: &str) -> Result<Server, RedisError> {
+    const DEFAULT_SENTINEL_PORT: u16 = 26379;
+
+    // An IPv6 address without a port does not need the brackets
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(Server::new(ip.to_string(), DEFAULT_SENTINEL_PORT));
+    }
+
+    let invalid = || {
+        RedisError::new(
+            RedisErrorKind::Config,
+            format!("Invalid Redis sentinel host {host}"),
+        )
+    };
+    let url = Url::parse(&format!("redis://{host}")).map_err(|_| invalid())?;
+    if !url.username().is_empty()
+        || url.password().is_some()
+        || !url.path().is_empty()
+        || url.query().is_some()
+        || url.fragment().is_some()
+    {
+        return Err(invalid());
+    }
+
+    let name = match url.host() {
+        Some(Host::Domain(domain)) if !domain.is_empty() => domain.to_string(),
+        Some(Host::Ipv4(ip)) => ip.to_string(),
+        Some(Host::Ipv6(ip)) => ip.to_string(),
+        _ => return Err(invalid()),
+    };
+    Ok(Server::new(
+        name,
+        url.port().unwrap_or(DEFAULT_SENTINEL_PORT),
+    ))
+}
+
+/// Counts the reconnections of a client, and the failovers among them when the client ends up
+/// connected to a different server than before, as when sentinel promotes a new master. The
+/// first connection of the client only records the server it is connected to.
+fn observe_reconnects(client: &RedisClient) {
+    let last_server: Arc<Mutex<Option<Server>>> = Arc::new(Mutex::new(None));
+    client.on_reconnect(move |server| {
+        let previous = last_server.lock().unwrap().replace(server.clone());
+        if let Some(previous) = previous {
+            let failover = previous != server;
+            if failover {
+                warn!(
+                    "Redis failover: reconnected to {}:{} instead of {}:{}",
+                    server.host, server.port, previous.host, previous.port
+                );
+            }
+            record_redis_reconnect(failover);
+        }
+        Ok(())
+    });
+}
+
 pub struct RedisLabelledApi<'a> {
     svc_name: &'static str,
     api_name: &'static str,
@@ -194,6 +284,31 @@ impl<'a> RedisLabelledApi<'a> {
         )
     }
 
+    /// Runs a Lua script. The keys are prefixed, the arguments are passed as is.
+    pub async fn eval<R, K, V>(&self, script: &str, keys: Vec<K>, args: V) -> RedisResult<R>
+    where
+        R: FromRedis,
+        K: AsRef<str>,
+        V: TryInto<MultipleValues> + Send,
+        V::Error: Into<RedisError> + Send,
+    {
+        self.ensure_connected().await?;
+        let start = Instant::now();
+        self.record(
+            start,
+            "EVAL",
+            self.pool
+                .eval(
+                    script,
+                    keys.iter()
+                        .map(|k| self.prefixed_key(k))
+                        .collect::<Vec<_>>(),
+                    args,
+                )
+                .await,
+        )
+    }
+
     pub async fn mget<R, K>(&self, keys: K) -> RedisResult<R>
     where
         R: FromRedis,
@@ -706,6 +821,24 @@ impl<'a> RedisLabelledApi<'a> {
         Ok(connected_slaves)
     }
 
+    /// Returns the major and minor version of the Redis server
+    pub async fn info_server_version(&self) -> RedisResult<Option<(u32, u32)>> {
+        self.ensure_connected().await?;
+        let start = Instant::now();
+        let info: String =
+            self.record(start, "INFO", self.pool.info(Some(InfoKind::Server)).await)?;
+        let version = info
+            .lines()
+            .filter_map(|line| line.trim().split_once(':'))
+            .find(|(key, _)| *key == "redis_version")
+            .and_then(|(_, version)| {
+                let mut parts = version.split('.').map(|part| part.parse::<u32>().ok());
+                Some((parts.next()??, parts.next()??))
+            });
+        debug!(version = format!("{:?}", version), "Redis server version");
+        Ok(version)
+    }
+
     pub async fn scan<K>(
         &self,
         pattern: K,
@@ -884,3 +1017,54 @@ impl RedisTransaction {
         self.trx.scard(self.prefixed_key(key)).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use fred::types::Server;
+
+    use crate::redis::sentinel_server;
+
+    #[test]
+    fn sentinel_hosts_are_parsed() {
+        assert_eq!(
+            sentinel_server("sentinel-1").unwrap(),
+            Server::new("sentinel-1", 26379)
+        );
+        assert_eq!(
+            sentinel_server("sentinel-2:26380").unwrap(),
+            Server::new("sentinel-2", 26380)
+        );
+        assert_eq!(
+            sentinel_server("10.0.0.1:26381").unwrap(),
+            Server::new("10.0.0.1", 26381)
+        );
+    }
+
+    #[test]
+    fn ipv6_sentinel_hosts_are_parsed() {
+        assert_eq!(
+            sentinel_server("[::1]:26382").unwrap(),
+            Server::new("::1", 26382)
+        );
+        assert_eq!(
+            sentinel_server("[fe80::1]").unwrap(),
+            Server::new("fe80::1", 26379)
+        );
+        assert_eq!(
+            sentinel_server("fe80::1").unwrap(),
+            Server::new("fe80::1", 26379)
+        );
+    }
+
+    #[test]
+    fn invalid_sentinel_hosts_are_rejected() {
+        assert!(sentinel_server("sentinel:port").is_err());
+        assert!(sentinel_server("sentinel:26379:1").is_err());
+        assert!(sentinel_server("::1:26379:x").is_err());
+        assert!(sentinel_server("user@sentinel:26379").is_err());
+        assert!(sentinel_server("sentinel:26379/0").is_err());
+        assert!(sentinel_server("").is_err());
+    }
+}
@@ -0,0 +1,250 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Format of the request capture files written by the worker service. Each line of a capture
+//! file is a JSON encoded `CapturedExchange`, holding a sanitized incoming request and the
+//! response it got, so the file can be replayed against a test deployment.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// The value sensitive headers and JSON fields are replaced with in the captured requests
+pub const REDACTED: &str = "<redacted>";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturedExchange {
+    pub captured_at: DateTime<Utc>,
+    pub server: CapturedServer,
+    pub request: CapturedRequest,
+    pub response: CapturedResponse,
+}
+
+/// The server of the worker service which received the request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapturedServer {
+    /// The worker and API definition management API
+    Api,
+    /// The API gateway serving the custom requests of the deployed API definitions
+    Gateway,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturedRequest {
+    pub method: String,
+    /// Path and query of the request
+    pub uri: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: CapturedBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturedResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: CapturedBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum CapturedBody {
+    Empty,
+    Json(Value),
+    Text(String),
+    /// Binary or oversized body, only its size is recorded so it cannot be replayed
+    Omitted {
+        size: usize,
+    },
+}
+
+impl CapturedBody {
+    /// Captures a body, redacting the sensitive fields of JSON bodies. Bodies larger than
+    /// `max_size` bytes or which are not valid UTF-8 are omitted.
+    pub fn from_bytes(bytes: &[u8], max_size: usize, redacted_fields: &[String]) -> Self {
+        if bytes.is_empty() {
+            CapturedBody::Empty
+        } else if bytes.len() > max_size {
+            CapturedBody::Omitted { size: bytes.len() }
+        } else if let Ok(mut json) = serde_json::from_slice::<Value>(bytes) {
+            redact_json(&mut json, redacted_fields);
+            CapturedBody::Json(json)
+        } else {
+            match std::str::from_utf8(bytes) {
+                Ok(text) => CapturedBody::Text(text.to_string()),
+                Err(_) => CapturedBody::Omitted { size: bytes.len() },
+            }
+        }
+    }
+
+    /// The body to be sent when replaying, or `None` if it was not captured
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        match self {
+            CapturedBody::Empty => Some(Vec::new()),
+            CapturedBody::Json(json) => Some(json.to_string().into_bytes()),
+            CapturedBody::Text(text) => Some(text.as_bytes().to_vec()),
+            CapturedBody::Omitted { .. } => None,
+        }
+    }
+
+    pub fn is_replayable(&self) -> bool {
+        !matches!(self, CapturedBody::Omitted { .. })
+    }
+
+    /// Whether some fields of the body were redacted, so it differs from the original one
+    pub fn is_redacted(&self) -> bool {
+        match self {
+            CapturedBody::Json(json) => contains_redacted(json),
+            _ => false,
+        }
+    }
+}
+
+fn contains_redacted(value: &Value) -> bool {
+    match value {
+        Value::String(value) => value == REDACTED,
+        Value::Object(fields) => fields.values().any(contains_redacted),
+        Value::Array(items) => items.iter().any(contains_redacted),
+        _ => false,
+    }
+}
+
+/// Replaces the values of the headers in `redacted_headers` (compared case-insensitively)
+pub fn redact_headers<'a>(
+    headers: impl IntoIterator<Item = (&'a str, String)>,
+    redacted_headers: &[String],
+) -> BTreeMap<String, String> {
+    headers
+        .into_iter()
+        .map(|(name, value)| {
+            let name = name.to_lowercase();
+            if redacted_headers
+                .iter()
+                .any(|redacted| redacted.eq_ignore_ascii_case(&name))
+            {
+                (name, REDACTED.to_string())
+            } else {
+                (name, value)
+            }
+        })
+        .collect()
+}
+
+/// Replaces the values of all object fields whose name contains any of `redacted_fields`
+/// (case-insensitively), at any depth
+pub fn redact_json(value: &mut Value, redacted_fields: &[String]) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                let name = name.to_lowercase();
+                if redacted_fields
+                    .iter()
+                    .any(|redacted| name.contains(&redacted.to_lowercase()))
+                {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(field, redacted_fields);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                redact_json(item, redacted_fields);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::request_capture::{redact_headers, CapturedBody, REDACTED};
+    use serde_json::json;
+
+    fn redacted_fields() -> Vec<String> {
+        vec!["token".to_string(), "env".to_string()]
+    }
+
+    #[test]
+    fn json_body_is_redacted_at_any_depth() {
+        let body = json!({
+            "name": "worker-1",
+            "env": { "SECRET": "value" },
+            "args": [{ "accessToken": "abc", "value": 1 }]
+        });
+
+        let captured =
+            CapturedBody::from_bytes(body.to_string().as_bytes(), 1024, &redacted_fields());
+
+        assert_eq!(
+            captured,
+            CapturedBody::Json(json!({
+                "name": "worker-1",
+                "env": REDACTED,
+                "args": [{ "accessToken": REDACTED, "value": 1 }]
+            }))
+        );
+    }
+
+    #[test]
+    fn large_and_binary_bodies_are_omitted() {
+        let large = CapturedBody::from_bytes(b"0123456789", 4, &redacted_fields());
+        let binary = CapturedBody::from_bytes(&[0xff, 0xfe, 0x00], 1024, &redacted_fields());
+
+        assert_eq!(large, CapturedBody::Omitted { size: 10 });
+        assert_eq!(binary, CapturedBody::Omitted { size: 3 });
+        assert!(!large.is_replayable());
+        assert_eq!(large.to_bytes(), None);
+    }
+
+    #[test]
+    fn redacted_bodies_are_detected() {
+        let redacted = CapturedBody::from_bytes(
+            json!({ "args": [{ "token": "abc" }] })
+                .to_string()
+                .as_bytes(),
+            1024,
+            &redacted_fields(),
+        );
+        let plain = CapturedBody::from_bytes(
+            json!({ "args": ["abc"] }).to_string().as_bytes(),
+            1024,
+            &redacted_fields(),
+        );
+
+        assert!(redacted.is_redacted());
+        assert!(!plain.is_redacted());
+        assert!(!CapturedBody::Text(REDACTED.to_string()).is_redacted());
+    }
+
+    #[test]
+    fn headers_are_redacted_case_insensitively() {
+        let headers = redact_headers(
+            vec![
+                ("Authorization", "Bearer xyz".to_string()),
+                ("Content-Type", "application/json".to_string()),
+            ],
+            &["authorization".to_string()],
+        );
+
+        assert_eq!(headers.get("authorization"), Some(&REDACTED.to_string()));
+        assert_eq!(
+            headers.get("content-type"),
+            Some(&"application/json".to_string())
+        );
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use golem_wasm_ast::analysis::{AnalysedType, TypeOption, TypeResult};
+use golem_wasm_rpc::json::TypeAnnotatedValueJsonExtensions;
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use serde_json::{Map, Value as JsonValue};
+
+/// Type checks a user provided input against an expected type.
+///
+/// Compared to the canonical JSON mapping of WIT values, the following shorthands are accepted:
+/// - `option<T>` values can be given as `null` (or omitted, when they are record fields) for `none`,
+///   and as a plain `T` for `some`
+/// - `result<T, E>` values can be given as `{"ok": ...}` / `{"err": ...}`, or as a plain `T`
+///   for a successful result
+pub trait TypeCheckIn {
+    fn type_check_in(&self, typ: &AnalysedType) -> Result<TypeAnnotatedValue, Vec<String>>;
+}
+
+impl TypeCheckIn for JsonValue {
+    fn type_check_in(&self, typ: &AnalysedType) -> Result<TypeAnnotatedValue, Vec<String>> {
+        TypeAnnotatedValue::parse_with_type(&expand_shorthands(self, typ), typ)
+    }
+}
+
+/// Rewrites the shorthand forms accepted by `TypeCheckIn` to the canonical JSON representation
+fn expand_shorthands(json: &JsonValue, typ: &AnalysedType) -> JsonValue {
+    match (typ, json) {
+        (AnalysedType::Option(TypeOption { inner }), json) => {
+            if json.is_null() {
+                JsonValue::Null
+            } else {
+                expand_shorthands(json, inner)
+            }
+        }
+        (AnalysedType::Result(TypeResult { ok, err }), json) => match as_result_case(json) {
+            Some(("ok", value)) => result_case("ok", value, ok.as_deref()),
+            Some((_, value)) => result_case("err", value, err.as_deref()),
+            None => result_case("ok", json, ok.as_deref()),
+        },
+        (AnalysedType::Record(record), JsonValue::Object(fields)) => {
+            let mut result = fields.clone();
+            for field in &record.fields {
+                match fields.get(&field.name) {
+                    Some(value) => {
+                        result.insert(field.name.clone(), expand_shorthands(value, &field.typ));
+                    }
+                    None => {
+                        if let AnalysedType::Option(_) = field.typ {
+                            result.insert(field.name.clone(), JsonValue::Null);
+                        }
+                    }
+                }
+            }
+            JsonValue::Object(result)
+        }
+        (AnalysedType::Variant(variant), JsonValue::Object(fields)) if fields.len() == 1 => {
+            let (name, value) = fields.iter().next().unwrap();
+            let case_type = variant
+                .cases
+                .iter()
+                .find(|case| &case.name == name)
+                .and_then(|case| case.typ.as_ref());
+            match case_type {
+                Some(case_type) => {
+                    let mut result = Map::new();
+                    result.insert(name.clone(), expand_shorthands(value, case_type));
+                    JsonValue::Object(result)
+                }
+                None => json.clone(),
+            }
+        }
+        (AnalysedType::List(list), JsonValue::Array(items)) => JsonValue::Array(
+            items
+                .iter()
+                .map(|item| expand_shorthands(item, &list.inner))
+                .collect(),
+        ),
+        (AnalysedType::Tuple(tuple), JsonValue::Array(items)) => JsonValue::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| match tuple.items.get(idx) {
+                    Some(item_type) => expand_shorthands(item, item_type),
+                    None => item.clone(),
+                })
+                .collect(),
+        ),
+        _ => json.clone(),
+    }
+}
+
+/// Recognizes the explicit `{"ok": ...}` and `{"err": ...}` forms of a result value
+fn as_result_case(json: &JsonValue) -> Option<(&str, &JsonValue)> {
+    match json {
+        JsonValue::Object(fields) if fields.len() == 1 => {
+            let (name, value) = fields.iter().next().unwrap();
+            match name.as_str() {
+                "ok" => Some(("ok", value)),
+                "err" => Some(("err", value)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn result_case(name: &str, value: &JsonValue, typ: Option<&AnalysedType>) -> JsonValue {
+    let value = match typ {
+        Some(typ) => expand_shorthands(value, typ),
+        None => value.clone(),
+    };
+    let mut result = Map::new();
+    result.insert(name.to_string(), value);
+    JsonValue::Object(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_wasm_ast::analysis::analysed_type::{field, option, record, result, str, u32};
+    use serde_json::json;
+
+    use super::expand_shorthands;
+
+    #[test]
+    fn option_shorthands() {
+        let typ = option(u32());
+        assert_eq!(expand_shorthands(&json!(null), &typ), json!(null));
+        assert_eq!(expand_shorthands(&json!(1), &typ), json!(1));
+    }
+
+    #[test]
+    fn missing_optional_record_fields() {
+        let typ = record(vec![field("a", u32()), field("b", option(str()))]);
+        assert_eq!(
+            expand_shorthands(&json!({"a": 1}), &typ),
+            json!({"a": 1, "b": null})
+        );
+        assert_eq!(
+            expand_shorthands(&json!({"a": 1, "b": "x"}), &typ),
+            json!({"a": 1, "b": "x"})
+        );
+    }
+
+    #[test]
+    fn result_shorthands() {
+        let typ = result(u32(), str());
+        assert_eq!(expand_shorthands(&json!(1), &typ), json!({"ok": 1}));
+        assert_eq!(expand_shorthands(&json!({"ok": 1}), &typ), json!({"ok": 1}));
+        assert_eq!(
+            expand_shorthands(&json!({"err": "failure"}), &typ),
+            json!({"err": "failure"})
+        );
+    }
+
+    #[test]
+    fn nested_shorthands() {
+        let typ = result(record(vec![field("x", option(u32()))]), str());
+        assert_eq!(
+            expand_shorthands(&json!({}), &typ),
+            json!({"ok": {"x": null}})
+        );
+    }
+}
@@ -17,12 +17,15 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::net::{Ipv4Addr, SocketAddrV4};
 use std::path::Path;
+use std::time::Duration;
 use uuid::Uuid;
 
-use golem_common::config::{ConfigExample, ConfigLoader, HasConfigExamples, RetryConfig};
+use golem_common::config::{
+    ConfigExample, ConfigLoader, GrpcCompression, HasConfigExamples, RetryConfig,
+};
 use golem_common::tracing::TracingConfig;
 use golem_worker_executor_base::services::golem_config::{
-    BlobStorageConfig, CompiledComponentServiceConfig,
+    BlobStorageConfig, CompiledComponentServiceConfig, ShardManagerServiceGrpcConfig,
 };
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -37,10 +40,12 @@ pub struct ServerConfig {
 
     // Workers
     pub compile_worker: CompileWorkerConfig,
+    pub precompilation: PrecompilationConfig,
 
     // GRPC
     pub grpc_host: String,
     pub grpc_port: u16,
+    pub grpc_compression: GrpcCompression,
 
     // Metrics and healthcheck
     pub http_host: String,
@@ -76,8 +81,34 @@ impl ComponentServiceConfig {
 pub struct CompileWorkerConfig {
     pub retries: RetryConfig,
     pub max_component_size: usize,
+    /// Retries of failed compilations, requeued with a delay
+    pub compilation_retries: RetryConfig,
+    /// Number of finished compilations whose status is kept, the oldest ones are forgotten first
+    pub max_finished_compilations: usize,
+}
+
+/// Distribution of the compiled component versions to the worker executors, so creating the
+/// first worker of a version does not have to wait for the executor to compile it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum PrecompilationConfig {
+    Enabled(PrecompilationEnabledConfig),
+    Disabled(PrecompilationDisabledConfig),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PrecompilationEnabledConfig {
+    /// The shard manager providing the worker executors to precompile on
+    pub shard_manager_service: ShardManagerServiceGrpcConfig,
+    pub retries: RetryConfig,
+    /// Number of worker executors a component version is precompiled on, the ones owning the
+    /// most shards first as they host the most workers
+    pub max_executors: usize,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PrecompilationDisabledConfig {}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct IFSWorkerConfig {
     pub retries: RetryConfig,
@@ -94,8 +125,10 @@ impl Default for ServerConfig {
             compiled_component_service: Default::default(),
             blob_storage: BlobStorageConfig::default_local_file_system(),
             compile_worker: Default::default(),
+            precompilation: Default::default(),
             grpc_host: "0.0.0.0".to_string(),
             grpc_port: 9091,
+            grpc_compression: GrpcCompression::default(),
             http_host: "0.0.0.0".to_string(),
             http_port: 8084,
         }
@@ -104,14 +137,27 @@ impl Default for ServerConfig {
 
 impl HasConfigExamples<ServerConfig> for ServerConfig {
     fn examples() -> Vec<ConfigExample<Self>> {
-        vec![(
-            "with s3 blob storage and disabled compiled component service",
-            Self {
-                blob_storage: BlobStorageConfig::default_s3(),
-                compiled_component_service: CompiledComponentServiceConfig::disabled(),
-                ..Self::default()
-            },
-        )]
+        vec![
+            (
+                "with s3 blob storage and disabled compiled component service",
+                Self {
+                    blob_storage: BlobStorageConfig::default_s3(),
+                    compiled_component_service: CompiledComponentServiceConfig::disabled(),
+                    ..Self::default()
+                },
+            ),
+            (
+                "with precompilation on the worker executors",
+                Self {
+                    precompilation: PrecompilationConfig::Enabled(PrecompilationEnabledConfig {
+                        shard_manager_service: ShardManagerServiceGrpcConfig::default(),
+                        retries: RetryConfig::max_attempts_3(),
+                        max_executors: 3,
+                    }),
+                    ..Self::default()
+                },
+            ),
+        ]
     }
 }
 
@@ -131,10 +177,24 @@ impl Default for CompileWorkerConfig {
         Self {
             retries: RetryConfig::max_attempts_3(),
             max_component_size: 1000000,
+            compilation_retries: RetryConfig {
+                max_attempts: 5,
+                min_delay: Duration::from_secs(1),
+                max_delay: Duration::from_secs(60),
+                multiplier: 3.0,
+                max_jitter_factor: Some(0.15),
+            },
+            max_finished_compilations: 1000,
         }
     }
 }
 
+impl Default for PrecompilationConfig {
+    fn default() -> Self {
+        Self::Disabled(PrecompilationDisabledConfig {})
+    }
+}
+
 pub fn make_config_loader() -> ConfigLoader<ServerConfig> {
     ConfigLoader::new_with_examples(Path::new("config/component-compilation-service.toml"))
 }
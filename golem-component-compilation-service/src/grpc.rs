@@ -23,11 +23,13 @@ use golem_api_grpc::proto::golem::common::{Empty, ErrorBody, ErrorsBody};
 use golem_api_grpc::proto::golem::component;
 use golem_api_grpc::proto::golem::componentcompilation::v1::component_compilation_service_server::ComponentCompilationService as GrpcCompilationServer;
 use golem_api_grpc::proto::golem::componentcompilation::v1::{
-    component_compilation_error, component_compilation_response, ComponentCompilationError,
-    ComponentCompilationRequest, ComponentCompilationResponse,
+    component_compilation_error, component_compilation_response, get_compilation_status_response,
+    ComponentCompilationError, ComponentCompilationRequest, ComponentCompilationResponse,
+    GetCompilationStatusRequest, GetCompilationStatusResponse,
 };
 use golem_common::grpc::proto_component_id_string;
 use golem_common::metrics::api::TraceErrorKind;
+use golem_common::model::component_compilation::CompilationStatus;
 use golem_common::model::ComponentId;
 use golem_common::recorded_grpc_api_request;
 use tonic::{Request, Response, Status};
@@ -73,6 +75,35 @@ impl GrpcCompilationServer for CompileGrpcService {
             result: Some(response),
         }))
     }
+
+    async fn get_compilation_status(
+        &self,
+        request: Request<GetCompilationStatusRequest>,
+    ) -> Result<tonic::Response<GetCompilationStatusResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_compilation_status",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        let response = match self
+            .get_compilation_status_impl(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(status) => record.succeed(get_compilation_status_response::Result::Success(
+                status.into(),
+            )),
+            Err(error) => record.fail(
+                get_compilation_status_response::Result::Failure(error.clone()),
+                &ComponentCompilationTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(GetCompilationStatusResponse {
+            result: Some(response),
+        }))
+    }
 }
 
 impl CompileGrpcService {
@@ -82,11 +113,24 @@ impl CompileGrpcService {
     ) -> Result<(), ComponentCompilationError> {
         let component_id = make_component_id(request.component_id)?;
         let component_version = request.component_version;
+        let priority = request.priority().into();
         self.service
-            .enqueue_compilation(component_id, component_version)
+            .enqueue_compilation(component_id, component_version, priority)
             .await?;
         Ok(())
     }
+
+    async fn get_compilation_status_impl(
+        &self,
+        request: GetCompilationStatusRequest,
+    ) -> Result<CompilationStatus, ComponentCompilationError> {
+        let component_id = make_component_id(request.component_id)?;
+        let status = self
+            .service
+            .get_compilation_status(component_id, request.component_version)
+            .await?;
+        Ok(status)
+    }
 }
 
 impl From<crate::model::CompilationError> for ComponentCompilationError {
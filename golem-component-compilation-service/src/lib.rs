@@ -18,12 +18,14 @@ use std::{
 };
 
 use prometheus::Registry;
-use tonic::codec::CompressionEncoding;
 use tracing::info;
 
 use config::ServerConfig;
 use golem_api_grpc::proto::golem::componentcompilation::v1::component_compilation_service_server::ComponentCompilationServiceServer;
+use golem_common::config::GrpcCompression;
+use golem_common::metrics::grpc::GrpcMessageMetricsLayer;
 use golem_common::tracing::init_tracing_with_default_env_filter;
+use golem_common::with_grpc_compression;
 use golem_worker_executor_base::services::golem_config::BlobStorageConfig;
 use golem_worker_executor_base::storage::blob::s3::S3BlobStorage;
 use golem_worker_executor_base::storage::blob::BlobStorage;
@@ -77,7 +79,8 @@ async fn run(config: ServerConfig, prometheus: Registry) -> Result<(), Box<dyn s
             Arc::new(
                 storage::blob::fs::FileSystemBlobStorage::new(&config.root)
                     .await
-                    .expect("Failed to create file system blob storage"),
+                    .expect("Failed to create file system blob storage")
+                    .with_oplog_payload_sharding(config.oplog_payload_shard_fan_out),
             )
         }
         BlobStorageConfig::InMemory => {
@@ -101,10 +104,11 @@ async fn run(config: ServerConfig, prometheus: Registry) -> Result<(), Box<dyn s
     let compilation_service = ComponentCompilationServiceImpl::new(
         config.compile_worker,
         config.component_service,
+        config.precompilation,
         engine,
         compiled_component,
-        ifs_service
-
+        ifs_service,
+        config.grpc_compression,
     );
 
     let compilation_service = Arc::new(compilation_service);
@@ -112,7 +116,7 @@ async fn run(config: ServerConfig, prometheus: Registry) -> Result<(), Box<dyn s
     let ipv4_address: Ipv4Addr = config.grpc_host.parse().expect("Invalid IP address");
     let address = SocketAddr::new(ipv4_address.into(), config.grpc_port);
 
-    start_grpc_server(address, compilation_service).await?;
+    start_grpc_server(address, config.grpc_compression, compilation_service).await?;
 
     info!("Server started on port {}", config.grpc_port);
 
@@ -123,6 +127,7 @@ async fn run(config: ServerConfig, prometheus: Registry) -> Result<(), Box<dyn s
 
 async fn start_grpc_server(
     addr: SocketAddr,
+    grpc_compression: GrpcCompression,
     service: Arc<dyn CompilationService + Send + Sync>,
 ) -> Result<(), tonic::transport::Error> {
     let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
@@ -132,12 +137,12 @@ async fn start_grpc_server(
         .await;
 
     tonic::transport::Server::builder()
+        .layer(GrpcMessageMetricsLayer)
         .add_service(health_service)
-        .add_service(
-            ComponentCompilationServiceServer::new(CompileGrpcService::new(service))
-                .send_compressed(CompressionEncoding::Gzip)
-                .accept_compressed(CompressionEncoding::Gzip),
-        )
+        .add_service(with_grpc_compression!(
+            ComponentCompilationServiceServer::new(CompileGrpcService::new(service)),
+            grpc_compression
+        ))
         .serve(addr)
         .await
 }
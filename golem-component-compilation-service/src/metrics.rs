@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use golem_common::model::component_compilation::ExecutorCacheStatus;
 use lazy_static::lazy_static;
 use prometheus::*;
 
@@ -21,14 +22,37 @@ lazy_static! {
         "Number of outstanding compilation requests"
     )
     .unwrap();
+    static ref PRECOMPILATIONS_TOTAL: CounterVec = register_counter_vec!(
+        "component_precompilations_total",
+        "Number of compiled components sent to worker executors",
+        &["result"]
+    )
+    .unwrap();
+    static ref PRECOMPILATION_COVERAGE: Histogram = register_histogram!(
+        "component_precompilation_coverage",
+        "Ratio of the worker executors having a compiled component version in their cache",
+        vec![0.0, 0.25, 0.5, 0.75, 0.9, 1.0]
+    )
+    .unwrap();
 }
 
-pub fn increment_queue_length() {
-    COMPILATION_QUEUE_LENGTH.inc();
+pub fn set_queue_length(length: usize) {
+    COMPILATION_QUEUE_LENGTH.set(length as f64);
 }
 
-pub fn decrement_queue_length() {
-    COMPILATION_QUEUE_LENGTH.dec();
+pub fn record_precompilation(executors: &[ExecutorCacheStatus]) {
+    let warm = executors.iter().filter(|executor| executor.warm).count();
+    let cold = executors.len() - warm;
+
+    PRECOMPILATIONS_TOTAL
+        .with_label_values(&["success"])
+        .inc_by(warm as f64);
+    PRECOMPILATIONS_TOTAL
+        .with_label_values(&["failure"])
+        .inc_by(cold as f64);
+    if !executors.is_empty() {
+        PRECOMPILATION_COVERAGE.observe(warm as f64 / executors.len() as f64);
+    }
 }
 
 pub fn register_all() -> Registry {
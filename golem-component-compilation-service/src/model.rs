@@ -19,12 +19,14 @@ use std::path::Path;
 use reqwest::Url;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::DeserializeOwned;
+use golem_common::model::component_compilation::CompilationPriority;
 use golem_common::model::ComponentId;
 use tokio::sync::mpsc;
 use wasmtime::component::Component;
+use wasmtime::Engine;
 use golem_worker_executor_base::services::ifs::InitialFileSystem;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ComponentWithVersion {
     pub id: ComponentId,
     pub version: u64,
@@ -39,11 +41,13 @@ impl Display for ComponentWithVersion {
 #[derive(Debug)]
 pub struct CompilationRequest {
     pub component: ComponentWithVersion,
+    pub priority: CompilationPriority,
 }
 
 pub struct CompiledComponent {
     pub component_and_version: ComponentWithVersion,
     pub component: Component,
+    pub engine: Engine,
 }
 
 
@@ -0,0 +1,441 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use golem_common::config::RetryConfig;
+use golem_common::model::component_compilation::{
+    CompilationPriority, CompilationState, CompilationStatus, ExecutorCacheStatus,
+};
+use golem_common::model::Timestamp;
+use golem_common::retries::get_delay;
+use tokio::sync::Notify;
+
+use crate::model::*;
+
+// Compilation requests waiting for the compile worker, ordered by priority and then by the
+// order they were requested in, together with the status of the compilations requested since
+// the service started. Only the most recently finished compilations are kept.
+pub struct CompilationQueue {
+    state: Mutex<QueueState>,
+    available: Notify,
+    retries: RetryConfig,
+}
+
+#[derive(Default)]
+struct QueueState {
+    queue: BinaryHeap<QueuedCompilation>,
+    next_sequence: u64,
+    compilations: HashMap<ComponentWithVersion, TrackedCompilation>,
+    max_finished: usize,
+}
+
+struct TrackedCompilation {
+    status: CompilationStatus,
+    // Sequence number of the queue entry of the compilation, older entries of the same
+    // compilation left in the queue after it was requested again are skipped
+    sequence: Option<u64>,
+}
+
+struct QueuedCompilation {
+    priority: CompilationPriority,
+    sequence: u64,
+    component: ComponentWithVersion,
+}
+
+impl Ord for QueuedCompilation {
+    // The greatest entry is popped first from the heap, so the comparison is reversed
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.priority, other.sequence).cmp(&(self.priority, self.sequence))
+    }
+}
+
+impl PartialOrd for QueuedCompilation {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for QueuedCompilation {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedCompilation {}
+
+impl CompilationQueue {
+    pub fn new(retries: RetryConfig, max_finished: usize) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                max_finished,
+                ..QueueState::default()
+            }),
+            available: Notify::new(),
+            retries,
+        }
+    }
+
+    // Queues a compilation. If it is already queued, it is moved to the position its priority
+    // gets it, but is never demoted to a lower priority.
+    pub fn enqueue(&self, request: CompilationRequest) {
+        let mut state = self.state.lock().unwrap();
+        let priority = match state.compilations.get(&request.component) {
+            Some(tracked) if is_pending(tracked.status.state) => {
+                request.priority.min(tracked.status.priority)
+            }
+            _ => request.priority,
+        };
+        let attempts = match state.compilations.get(&request.component) {
+            Some(tracked) if is_pending(tracked.status.state) => tracked.status.attempts,
+            _ => 0,
+        };
+        state.push(request.component, priority, attempts, None);
+        drop(state);
+
+        self.available.notify_one();
+    }
+
+    // Waits for the next compilation to process, marking it as being compiled
+    pub async fn next(&self) -> ComponentWithVersion {
+        loop {
+            let next = self.state.lock().unwrap().pop();
+            if let Some(component) = next {
+                return component;
+            }
+            self.available.notified().await;
+        }
+    }
+
+    // Marks the compilation as finished once the compiled component is uploaded
+    pub fn compiled(&self, component: &ComponentWithVersion) {
+        self.state
+            .lock()
+            .unwrap()
+            .update(component, CompilationState::Compiled, None);
+    }
+
+    // Records a failed attempt of compiling or uploading the component, queueing the compilation
+    // again after a delay unless all the configured attempts failed already. Retries are
+    // recompilations in the background, not to hold up the compilation of new uploads.
+    pub fn failed(self: &Arc<Self>, component: &ComponentWithVersion, error: String) {
+        let mut state = self.state.lock().unwrap();
+        let Some(tracked) = state.compilations.get(component) else {
+            return;
+        };
+        let attempts = tracked.status.attempts;
+
+        match get_delay(&self.retries, attempts as u64) {
+            Some(delay) => {
+                state.update(
+                    component,
+                    CompilationState::RetryScheduled,
+                    Some(error.clone()),
+                );
+                drop(state);
+
+                let queue = self.clone();
+                let component = component.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    queue.retry(component, attempts, error);
+                });
+            }
+            None => state.update(component, CompilationState::Failed, Some(error)),
+        }
+    }
+
+    // Records the result of distributing the compiled component to the worker executors
+    pub fn precompiled(
+        &self,
+        component: &ComponentWithVersion,
+        executors: Vec<ExecutorCacheStatus>,
+    ) {
+        if let Some(tracked) = self.state.lock().unwrap().compilations.get_mut(component) {
+            tracked.status.executors = executors;
+        }
+    }
+
+    pub fn status(&self, component: &ComponentWithVersion) -> Option<CompilationStatus> {
+        self.state
+            .lock()
+            .unwrap()
+            .compilations
+            .get(component)
+            .map(|tracked| tracked.status.clone())
+    }
+
+    fn retry(&self, component: ComponentWithVersion, attempts: u32, last_error: String) {
+        let mut state = self.state.lock().unwrap();
+        // The compilation may have been requested again while waiting for the retry
+        let still_scheduled = state
+            .compilations
+            .get(&component)
+            .is_some_and(|tracked| tracked.status.state == CompilationState::RetryScheduled);
+        if still_scheduled {
+            state.push(
+                component,
+                CompilationPriority::Background,
+                attempts,
+                Some(last_error),
+            );
+            drop(state);
+
+            self.available.notify_one();
+        }
+    }
+}
+
+impl QueueState {
+    fn push(
+        &mut self,
+        component: ComponentWithVersion,
+        priority: CompilationPriority,
+        attempts: u32,
+        last_error: Option<String>,
+    ) {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+
+        self.compilations.insert(
+            component.clone(),
+            TrackedCompilation {
+                status: CompilationStatus {
+                    state: CompilationState::Queued,
+                    priority,
+                    attempts,
+                    last_error,
+                    updated_at: Timestamp::now_utc(),
+                    executors: vec![],
+                },
+                sequence: Some(sequence),
+            },
+        );
+        self.queue.push(QueuedCompilation {
+            priority,
+            sequence,
+            component,
+        });
+        self.record_queue_length();
+    }
+
+    fn pop(&mut self) -> Option<ComponentWithVersion> {
+        while let Some(entry) = self.queue.pop() {
+            if let Some(tracked) = self.compilations.get_mut(&entry.component) {
+                if tracked.sequence == Some(entry.sequence) {
+                    tracked.sequence = None;
+                    tracked.status.state = CompilationState::Compiling;
+                    tracked.status.attempts += 1;
+                    tracked.status.updated_at = Timestamp::now_utc();
+                    self.record_queue_length();
+                    return Some(entry.component);
+                }
+            }
+        }
+        None
+    }
+
+    fn update(
+        &mut self,
+        component: &ComponentWithVersion,
+        state: CompilationState,
+        last_error: Option<String>,
+    ) {
+        if let Some(tracked) = self.compilations.get_mut(component) {
+            tracked.status.state = state;
+            tracked.status.last_error = last_error;
+            tracked.status.updated_at = Timestamp::now_utc();
+        }
+        if is_finished(state) {
+            self.forget_oldest_finished();
+        }
+    }
+
+    fn forget_oldest_finished(&mut self) {
+        let mut finished = self
+            .compilations
+            .iter()
+            .filter(|(_, tracked)| is_finished(tracked.status.state))
+            .map(|(component, tracked)| (tracked.status.updated_at, component.clone()))
+            .collect::<Vec<_>>();
+        if finished.len() <= self.max_finished {
+            return;
+        }
+
+        finished.sort_by_key(|(updated_at, _)| *updated_at);
+        let excess = finished.len() - self.max_finished;
+        for (_, component) in finished.into_iter().take(excess) {
+            self.compilations.remove(&component);
+        }
+    }
+
+    fn record_queue_length(&self) {
+        let queued = self
+            .compilations
+            .values()
+            .filter(|tracked| tracked.sequence.is_some())
+            .count();
+        crate::metrics::set_queue_length(queued);
+    }
+}
+
+fn is_finished(state: CompilationState) -> bool {
+    matches!(state, CompilationState::Compiled | CompilationState::Failed)
+}
+
+fn is_pending(state: CompilationState) -> bool {
+    matches!(
+        state,
+        CompilationState::Queued | CompilationState::RetryScheduled
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use golem_common::config::RetryConfig;
+    use golem_common::model::component_compilation::{CompilationPriority, CompilationState};
+    use golem_common::model::ComponentId;
+    use uuid::Uuid;
+
+    use crate::model::{CompilationRequest, ComponentWithVersion};
+    use crate::service::compilation_queue::CompilationQueue;
+
+    fn request(
+        id: &ComponentId,
+        version: u64,
+        priority: CompilationPriority,
+    ) -> CompilationRequest {
+        CompilationRequest {
+            component: ComponentWithVersion {
+                id: id.clone(),
+                version,
+            },
+            priority,
+        }
+    }
+
+    fn retries(max_attempts: u32) -> RetryConfig {
+        RetryConfig {
+            max_attempts,
+            min_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            multiplier: 1.0,
+            max_jitter_factor: None,
+        }
+    }
+
+    #[test]
+    async fn interactive_compilations_first() {
+        let id = ComponentId(Uuid::new_v4());
+        let queue = CompilationQueue::new(retries(1), 10);
+
+        queue.enqueue(request(&id, 0, CompilationPriority::Background));
+        queue.enqueue(request(&id, 1, CompilationPriority::Background));
+        queue.enqueue(request(&id, 2, CompilationPriority::Interactive));
+        // Requested again with a higher priority
+        queue.enqueue(request(&id, 1, CompilationPriority::Interactive));
+
+        let order = vec![
+            queue.next().await.version,
+            queue.next().await.version,
+            queue.next().await.version,
+        ];
+        assert_eq!(order, vec![2, 1, 0]);
+        assert_eq!(
+            queue
+                .status(&request(&id, 0, CompilationPriority::Background).component)
+                .map(|status| status.state),
+            Some(CompilationState::Compiling)
+        );
+    }
+
+    #[test]
+    async fn failed_compilations_are_retried() {
+        let id = ComponentId(Uuid::new_v4());
+        let queue = Arc::new(CompilationQueue::new(retries(2), 10));
+        let component = request(&id, 0, CompilationPriority::Interactive).component;
+
+        queue.enqueue(request(&id, 0, CompilationPriority::Interactive));
+        assert_eq!(queue.next().await, component);
+        queue.failed(&component, "first failure".to_string());
+
+        assert_eq!(queue.next().await, component);
+        queue.failed(&component, "second failure".to_string());
+
+        let status = queue.status(&component).unwrap();
+        assert_eq!(status.state, CompilationState::Failed);
+        assert_eq!(status.attempts, 2);
+        assert_eq!(status.last_error, Some("second failure".to_string()));
+    }
+
+    #[test]
+    async fn retries_are_queued_in_the_background() {
+        let id = ComponentId(Uuid::new_v4());
+        let queue = Arc::new(CompilationQueue::new(retries(2), 10));
+        let failing = request(&id, 0, CompilationPriority::Interactive).component;
+
+        queue.enqueue(request(&id, 0, CompilationPriority::Interactive));
+        assert_eq!(queue.next().await, failing);
+        queue.failed(&failing, "failure".to_string());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        queue.enqueue(request(&id, 1, CompilationPriority::Interactive));
+
+        assert_eq!(queue.next().await.version, 1);
+        assert_eq!(queue.next().await, failing);
+        assert_eq!(
+            queue.status(&failing).map(|status| status.priority),
+            Some(CompilationPriority::Background)
+        );
+    }
+
+    #[test]
+    async fn only_the_latest_finished_compilations_are_kept() {
+        let id = ComponentId(Uuid::new_v4());
+        let queue = CompilationQueue::new(retries(1), 2);
+
+        for version in 0..3 {
+            queue.enqueue(request(&id, version, CompilationPriority::Interactive));
+            let component = queue.next().await;
+            queue.compiled(&component);
+            // Finished compilations are told apart by their timestamps
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        queue.enqueue(request(&id, 3, CompilationPriority::Interactive));
+
+        let states = (0..4)
+            .map(|version| {
+                queue
+                    .status(&request(&id, version, CompilationPriority::Interactive).component)
+                    .map(|status| status.state)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            vec![
+                None,
+                Some(CompilationState::Compiled),
+                Some(CompilationState::Compiled),
+                Some(CompilationState::Queued)
+            ]
+        );
+    }
+}
@@ -13,9 +13,12 @@
 // limitations under the License.
 
 use super::*;
-use crate::config::{CompileWorkerConfig, ComponentServiceConfig};
+use crate::config::{CompileWorkerConfig, ComponentServiceConfig, PrecompilationConfig};
 use crate::model::*;
 use async_trait::async_trait;
+use crate::service::compilation_queue::CompilationQueue;
+use golem_common::model::component_compilation::{CompilationPriority, CompilationStatus};
+use golem_common::config::GrpcCompression;
 use golem_common::model::ComponentId;
 use golem_worker_executor_base::services::compiled_component::CompiledComponentService;
 use std::sync::Arc;
@@ -26,6 +29,7 @@ use golem_worker_executor_base::services::blob_store::BlobStoreService;
 // use golem_worker_executor_base::services::ifs::InitialFileSystemService;
 // use golem_worker_executor_base::services::ifs::InitialFileSystemService;
 use crate::service::ifs_worker::InitialFileSystemWorker;
+use crate::service::precompile_worker::PrecompileWorker;
 
 #[async_trait]
 pub trait CompilationService {
@@ -33,26 +37,38 @@ pub trait CompilationService {
         &self,
         component_id: ComponentId,
         component_version: u64,
+        priority: CompilationPriority,
     ) -> Result<(), CompilationError>;
+
+    async fn get_compilation_status(
+        &self,
+        component_id: ComponentId,
+        component_version: u64,
+    ) -> Result<CompilationStatus, CompilationError>;
 }
 
 #[derive(Clone)]
 pub struct ComponentCompilationServiceImpl {
-    queue: mpsc::Sender<CompilationRequest>,
+    queue: Arc<CompilationQueue>,
 }
 
 impl ComponentCompilationServiceImpl {
     pub fn new(
         compile_worker: CompileWorkerConfig,
         component_service: ComponentServiceConfig,
+        precompilation: PrecompilationConfig,
 
         engine: Engine,
 
         compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
         // ifs_service : Arc<dyn InitialFileSystemService + Send + Sync>
-        ifs_service: Arc<dyn BlobStoreService + Send + Sync>
+        ifs_service: Arc<dyn BlobStoreService + Send + Sync>,
+        grpc_compression: GrpcCompression,
     ) -> Self {
-        let (compile_tx, compile_rx) = mpsc::channel(100);
+        let queue = Arc::new(CompilationQueue::new(
+            compile_worker.compilation_retries.clone(),
+            compile_worker.max_finished_compilations,
+        ));
         let (upload_tx, upload_rx) = mpsc::channel(100);
         let (ifs_tx, ifs_rx) = mpsc::channel(100);
 
@@ -63,19 +79,32 @@ impl ComponentCompilationServiceImpl {
             engine.clone(),
             compiled_component_service.clone(),
             upload_tx,
-            compile_rx,
+            queue.clone(),
+            grpc_compression,
         );
 
+        let precompile_tx = match precompilation {
+            PrecompilationConfig::Enabled(config) => {
+                let (precompile_tx, precompile_rx) = mpsc::channel(100);
+                PrecompileWorker::start(config, queue.clone(), precompile_rx, grpc_compression);
+                Some(precompile_tx)
+            }
+            PrecompilationConfig::Disabled(_) => None,
+        };
+
         UploadWorker::start(
             component_service.uri(),
             component_service.clone().access_token,
             compiled_component_service.clone(),
             compile_worker ,
             upload_rx,
-            ifs_tx
+            ifs_tx,
+            precompile_tx,
+            queue.clone(),
+            grpc_compression,
         );
         InitialFileSystemWorker::start(ifs_service.clone(), ifs_rx);
-        Self { queue: compile_tx }
+        Self { queue }
     }
 }
 
@@ -85,20 +114,36 @@ impl CompilationService for ComponentCompilationServiceImpl {
         &self,
         component_id: ComponentId,
         component_version: u64,
+        priority: CompilationPriority,
     ) -> Result<(), CompilationError> {
         tracing::info!(
-            "Enqueueing compilation for component {}@{}",
+            "Enqueueing compilation for component {}@{} with {:?} priority",
             component_id,
-            component_version
+            component_version,
+            priority
         );
         let request = CompilationRequest {
             component: ComponentWithVersion {
                 id: component_id,
                 version: component_version,
             },
+            priority,
         };
-        self.queue.send(request).await?;
-        crate::metrics::increment_queue_length();
+        self.queue.enqueue(request);
         Ok(())
     }
+
+    async fn get_compilation_status(
+        &self,
+        component_id: ComponentId,
+        component_version: u64,
+    ) -> Result<CompilationStatus, CompilationError> {
+        let component = ComponentWithVersion {
+            id: component_id,
+            version: component_version,
+        };
+        self.queue
+            .status(&component)
+            .ok_or(CompilationError::ComponentNotFound(component))
+    }
 }
@@ -14,6 +14,7 @@
 
 use crate::config::CompileWorkerConfig;
 use crate::model::*;
+use crate::service::compilation_queue::CompilationQueue;
 use crate::UriBackConversion;
 use futures_util::TryStreamExt;
 use golem_api_grpc::proto::golem::component::v1::component_service_client::ComponentServiceClient;
@@ -21,10 +22,11 @@ use golem_api_grpc::proto::golem::component::v1::download_component_response;
 use golem_api_grpc::proto::golem::component::v1::ComponentError;
 use golem_api_grpc::proto::golem::component::v1::DownloadComponentRequest;
 use golem_common::client::{GrpcClient, GrpcClientConfig};
-use golem_common::config::RetryConfig;
+use golem_common::config::{GrpcCompression, RetryConfig};
 use golem_common::metrics::external_calls::record_external_call_response_size_bytes;
 use golem_common::model::ComponentId;
 use golem_common::retries::with_retries;
+use golem_common::with_grpc_compression;
 use golem_worker_executor_base::grpc::authorised_grpc_request;
 use golem_worker_executor_base::grpc::is_grpc_retriable;
 use golem_worker_executor_base::grpc::GrpcError;
@@ -34,7 +36,6 @@ use http::Uri;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::mpsc;
-use tonic::codec::CompressionEncoding;
 use tonic::transport::Channel;
 use uuid::Uuid;
 use wasmtime::component::Component;
@@ -63,7 +64,8 @@ impl CompileWorker {
         compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
 
         sender: mpsc::Sender<CompiledComponent>,
-        mut recv: mpsc::Receiver<CompilationRequest>,
+        queue: Arc<CompilationQueue>,
+        grpc_compression: GrpcCompression,
     ) {
         let max_component_size = config.max_component_size;
         let worker = Self {
@@ -73,10 +75,11 @@ impl CompileWorker {
             access_token,
             client: GrpcClient::new(
                 move |channel| {
-                    ComponentServiceClient::new(channel)
-                        .max_decoding_message_size(max_component_size)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
+                    with_grpc_compression!(
+                        ComponentServiceClient::new(channel)
+                            .max_decoding_message_size(max_component_size),
+                        grpc_compression
+                    )
                 },
                 uri.as_http_02(),
                 GrpcClientConfig {
@@ -87,17 +90,23 @@ impl CompileWorker {
         };
 
         tokio::spawn(async move {
-            while let Some(request) = recv.recv().await {
-                crate::metrics::decrement_queue_length();
-                let result = worker.compile_component(&request.component).await;
+            loop {
+                let component_with_version = queue.next().await;
+                let result = worker.compile_component(&component_with_version).await;
                 match result {
-                    Err(_) => {}
+                    Err(error) => {
+                        tracing::warn!(
+                            "Failed to compile component {component_with_version}: {error}"
+                        );
+                        queue.failed(&component_with_version, error.to_string());
+                    }
                     Ok(component) => {
-                        tracing::info!("Compiled component {}", request.component);
+                        tracing::info!("Compiled component {}", component_with_version);
                         let send_result = sender
                             .send(CompiledComponent {
-                                component_and_version: request.component,
-                                component
+                                component_and_version: component_with_version,
+                                component,
+                                engine: worker.engine.clone(),
                             })
                             .await;
 
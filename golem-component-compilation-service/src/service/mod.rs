@@ -12,10 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod compilation_queue;
 pub mod compile_service;
 mod compile_worker;
 mod upload_worker;
 mod ifs_worker;
+mod precompile_worker;
 
 pub use compile_service::CompilationService;
 pub use compile_worker::CompileWorker;
@@ -0,0 +1,238 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::future::join_all;
+use golem_api_grpc::proto::golem::shardmanager::v1::shard_manager_service_client::ShardManagerServiceClient;
+use golem_api_grpc::proto::golem::shardmanager::v1::{
+    get_routing_table_response, GetRoutingTableRequest,
+};
+use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
+use golem_api_grpc::proto::golem::workerexecutor::v1::{
+    precompile_component_response, PrecompileComponentRequest,
+};
+use golem_common::client::{GrpcClient, GrpcClientConfig, MultiTargetGrpcClient};
+use golem_common::config::GrpcCompression;
+use golem_common::model::component_compilation::ExecutorCacheStatus;
+use golem_common::model::{Pod, RoutingTable, Timestamp};
+use golem_common::with_grpc_compression;
+use golem_worker_executor_base::grpc::UriBackConversion;
+use tokio::sync::mpsc;
+use tonic::transport::Channel;
+
+use crate::config::PrecompilationEnabledConfig;
+use crate::model::*;
+use crate::service::compilation_queue::CompilationQueue;
+
+// Worker that loads the uploaded compiled components into the caches of the worker executors
+// owning the most shards, as they are the most likely to host the workers of a component.
+#[derive(Clone)]
+pub struct PrecompileWorker {
+    shard_manager: GrpcClient<ShardManagerServiceClient<Channel>>,
+    executors: MultiTargetGrpcClient<WorkerExecutorClient<Channel>>,
+    queue: Arc<CompilationQueue>,
+    max_executors: usize,
+}
+
+impl PrecompileWorker {
+    pub fn start(
+        config: PrecompilationEnabledConfig,
+        queue: Arc<CompilationQueue>,
+        mut recv: mpsc::Receiver<ComponentWithVersion>,
+        grpc_compression: GrpcCompression,
+    ) {
+        let worker = Self {
+            shard_manager: GrpcClient::new(
+                move |channel| {
+                    with_grpc_compression!(
+                        ShardManagerServiceClient::new(channel),
+                        grpc_compression
+                    )
+                },
+                config.shard_manager_service.uri().as_http_02(),
+                GrpcClientConfig {
+                    retries_on_unavailable: config.shard_manager_service.retries.clone(),
+                    ..Default::default()
+                },
+            ),
+            executors: MultiTargetGrpcClient::new(
+                move |channel| {
+                    with_grpc_compression!(WorkerExecutorClient::new(channel), grpc_compression)
+                },
+                GrpcClientConfig {
+                    retries_on_unavailable: config.retries.clone(),
+                    ..Default::default()
+                },
+            ),
+            queue,
+            max_executors: config.max_executors,
+        };
+
+        tokio::spawn(async move {
+            while let Some(component) = recv.recv().await {
+                worker.precompile(component).await
+            }
+        });
+    }
+
+    async fn precompile(&self, component: ComponentWithVersion) {
+        let pods = match self.get_executors().await {
+            Ok(pods) => pods,
+            Err(err) => {
+                tracing::warn!(
+                    "Failed to get the worker executors to precompile component {component} on: {err}"
+                );
+                return;
+            }
+        };
+
+        let results = join_all(pods.iter().map(|pod| self.precompile_on(pod, &component))).await;
+        let executors = pods
+            .iter()
+            .zip(results)
+            .map(|(pod, result)| ExecutorCacheStatus {
+                executor: pod.to_string(),
+                warm: result.is_ok(),
+                error: result.err(),
+                updated_at: Timestamp::now_utc(),
+            })
+            .collect::<Vec<_>>();
+
+        let warm = executors.iter().filter(|executor| executor.warm).count();
+        tracing::info!(
+            "Precompiled component {component} on {warm} of {} worker executors",
+            executors.len()
+        );
+        for executor in &executors {
+            if let Some(error) = &executor.error {
+                tracing::warn!(
+                    "Failed to precompile component {component} on worker executor {}: {error}",
+                    executor.executor
+                );
+            }
+        }
+
+        crate::metrics::record_precompilation(&executors);
+        self.queue.precompiled(&component, executors);
+    }
+
+    async fn get_executors(&self) -> Result<Vec<Pod>, String> {
+        let response = self
+            .shard_manager
+            .call(|client| Box::pin(client.get_routing_table(GetRoutingTableRequest {})))
+            .await
+            .map_err(|status| status.to_string())?
+            .into_inner();
+
+        match response.result {
+            Some(get_routing_table_response::Result::Success(routing_table)) => {
+                let routing_table: RoutingTable = routing_table.into();
+                Ok(select_executors(
+                    routing_table.shard_counts(),
+                    self.max_executors,
+                ))
+            }
+            Some(get_routing_table_response::Result::Failure(error)) => {
+                Err(format!("Shard manager error: {error:?}"))
+            }
+            None => Err("Empty response".to_string()),
+        }
+    }
+
+    async fn precompile_on(
+        &self,
+        pod: &Pod,
+        component: &ComponentWithVersion,
+    ) -> Result<(), String> {
+        let request = PrecompileComponentRequest {
+            component_id: Some(component.id.clone().into()),
+            component_version: component.version,
+        };
+        let response = self
+            .executors
+            .call(pod.uri_02(), move |client| {
+                Box::pin(client.precompile_component(request.clone()))
+            })
+            .await
+            .map_err(|status| status.to_string())?
+            .into_inner();
+
+        match response.result {
+            Some(precompile_component_response::Result::Success(_)) => Ok(()),
+            Some(precompile_component_response::Result::Failure(error)) => {
+                Err(format!("{error:?}"))
+            }
+            None => Err("Empty response".to_string()),
+        }
+    }
+}
+
+// Selects the given number of pods owning the most shards
+fn select_executors(shard_counts: HashMap<&Pod, usize>, max_executors: usize) -> Vec<Pod> {
+    let mut pods = shard_counts.into_iter().collect::<Vec<_>>();
+    pods.sort_by(|(pod1, count1), (pod2, count2)| {
+        count2
+            .cmp(count1)
+            .then_with(|| pod1.to_string().cmp(&pod2.to_string()))
+    });
+    pods.into_iter()
+        .take(max_executors)
+        .map(|(pod, _)| pod.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+
+    use golem_api_grpc::proto::golem::shardmanager::Pod as GrpcPod;
+    use golem_common::model::Pod;
+
+    use crate::service::precompile_worker::select_executors;
+
+    fn pod(port: u16) -> Pod {
+        GrpcPod {
+            host: "localhost".to_string(),
+            port: port as u32,
+            pod_name: None,
+        }
+        .into()
+    }
+
+    #[test]
+    fn executors_owning_the_most_shards_are_selected() {
+        let (a, b, c) = (pod(9000), pod(9001), pod(9002));
+        let shard_counts = HashMap::from([(&a, 3), (&b, 10), (&c, 5)]);
+
+        assert_eq!(
+            select_executors(shard_counts.clone(), 2),
+            vec![b.clone(), c.clone()]
+        );
+        assert_eq!(select_executors(shard_counts, 0), vec![]);
+    }
+
+    #[test]
+    fn all_executors_are_selected_below_the_limit() {
+        let (a, b) = (pod(9000), pod(9001));
+
+        assert_eq!(
+            select_executors(HashMap::from([(&a, 2), (&b, 2)]), 5),
+            vec![a.clone(), b.clone()]
+        );
+    }
+}
@@ -28,15 +28,17 @@ use golem_api_grpc::proto::golem::component::v1::{download_ifs_response, Compone
 use golem_api_grpc::proto::golem::component::v1::component_error::Error;
 use golem_api_grpc::proto::golem::component::v1::ifs_service_client::IfsServiceClient;
 use golem_common::client::{GrpcClient, GrpcClientConfig};
-use golem_common::config::RetryConfig;
+use golem_common::config::{GrpcCompression, RetryConfig};
 use golem_common::metrics::external_calls::record_external_call_response_size_bytes;
 use golem_common::model::ComponentId;
 use golem_common::retries::with_retries;
 use golem_common::tracing::directive::default::info;
+use golem_common::with_grpc_compression;
 use golem_worker_executor_base::grpc::{authorised_grpc_request, is_grpc_retriable, GrpcError, UriBackConversion};
 use golem_worker_executor_base::services::ifs::InitialFileSystem;
 use crate::config::{CompileWorkerConfig, IFSWorkerConfig};
 use crate::model::*;
+use crate::service::compilation_queue::CompilationQueue;
 
 // Worker that uploads compiled components to the cloud.
 #[derive(Clone)]
@@ -48,8 +50,10 @@ pub struct UploadWorker {
     // Resources
     compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
     client: GrpcClient<IfsServiceClient<Channel>>,
-    ifs_tx: Sender<InitialFileSystemToUpload>
-
+    ifs_tx: Sender<InitialFileSystemToUpload>,
+    // Not set if precompilation on the worker executors is disabled
+    precompile_tx: Option<Sender<ComponentWithVersion>>,
+    queue: Arc<CompilationQueue>,
 }
 
 impl UploadWorker {
@@ -60,6 +64,9 @@ impl UploadWorker {
         config: CompileWorkerConfig,
         mut recv: mpsc::Receiver<CompiledComponent>,
         ifs_tx: mpsc::Sender<InitialFileSystemToUpload>,
+        precompile_tx: Option<mpsc::Sender<ComponentWithVersion>>,
+        queue: Arc<CompilationQueue>,
+        grpc_compression: GrpcCompression,
     ) {
         let worker = Self {
             compiled_component_service,
@@ -67,7 +74,7 @@ impl UploadWorker {
             config: config.clone(),
             client: GrpcClient::new(
                 move |channel| {
-                    IfsServiceClient::new(channel)
+                    with_grpc_compression!(IfsServiceClient::new(channel), grpc_compression)
                 },
                 uri.as_http_02(),
                     GrpcClientConfig{
@@ -75,7 +82,9 @@ impl UploadWorker {
                         ..Default::default()
                     }
             ),
-            ifs_tx
+            ifs_tx,
+            precompile_tx,
+            queue,
         };
 
         tokio::spawn(async move {
@@ -92,6 +101,7 @@ impl UploadWorker {
         let CompiledComponent {
             component_and_version,
             component,
+            engine,
         } = compiled_component;
 
 
@@ -102,14 +112,26 @@ impl UploadWorker {
                 &component_and_version.id,
                 component_and_version.version,
                 &component,
+                &engine,
             )
             .await
             .map_err(|err| CompilationError::ComponentUploadFailed(err.to_string()));
 
         if let Err(ref err) = upload_result {
             tracing::warn!("Failed to upload compiled component {component_and_version}: {err:?}");
+            self.queue.failed(&component_and_version, err.to_string());
         } else {
             tracing::info!("Successfully uploaded compiled component {component_and_version}");
+            self.queue.compiled(&component_and_version);
+            // Precompilation is only an optimization, it is skipped instead of holding up the
+            // uploads while the worker executors are slow to respond
+            if let Some(precompile_tx) = &self.precompile_tx {
+                if let Err(err) = precompile_tx.try_send(component_and_version.clone()) {
+                    tracing::warn!(
+                        "Skipping the precompilation of component {component_and_version}: {err}"
+                    );
+                }
+            }
             if let Err(err) = self.download_and_process_ifs(&component_and_version).await {
                 tracing::error!("Failed to process IFS for component {component_and_version}: {err:?}");
             }
@@ -27,7 +27,7 @@ impl<'a> TraceErrorKind for ComponentTraceErrorKind<'a> {
 }
 
 mod conversion {
-    use crate::service::component;
+    use crate::service::{component, project};
     use golem_api_grpc::proto::golem::common::{ErrorBody, ErrorsBody};
     use golem_api_grpc::proto::golem::component::v1::{component_error, ComponentError};
     use golem_common::SafeDisplay;
@@ -71,6 +71,65 @@ mod conversion {
                         error: value.to_safe_string(),
                     })
                 }
+                component::ComponentError::InvalidInitialFileSystem(errors) => {
+                    component_error::Error::BadRequest(ErrorsBody { errors })
+                }
+                component::ComponentError::InvalidFunctionAliases(errors) => {
+                    component_error::Error::BadRequest(ErrorsBody { errors })
+                }
+                component::ComponentError::RangeNotSatisfiable { .. } => {
+                    component_error::Error::BadRequest(ErrorsBody {
+                        errors: vec![value.to_safe_string()],
+                    })
+                }
+                component::ComponentError::CompilationServiceError(_) => {
+                    component_error::Error::InternalError(ErrorBody {
+                        error: value.to_safe_string(),
+                    })
+                }
+                component::ComponentError::LimitExceeded(_) => {
+                    component_error::Error::LimitExceeded(ErrorBody {
+                        error: value.to_safe_string(),
+                    })
+                }
+                component::ComponentError::QuotaExceeded { .. } => {
+                    component_error::Error::LimitExceeded(ErrorBody {
+                        error: value.to_safe_string(),
+                    })
+                }
+            };
+            ComponentError { error: Some(error) }
+        }
+    }
+
+    impl From<project::ProjectError> for ComponentError {
+        fn from(value: project::ProjectError) -> Self {
+            let error = match value {
+                project::ProjectError::AlreadyExists(_) => {
+                    component_error::Error::AlreadyExists(ErrorBody {
+                        error: value.to_safe_string(),
+                    })
+                }
+                project::ProjectError::UnknownProjectId(_) => {
+                    component_error::Error::NotFound(ErrorBody {
+                        error: value.to_safe_string(),
+                    })
+                }
+                project::ProjectError::NotEmpty(_) => {
+                    component_error::Error::BadRequest(ErrorsBody {
+                        errors: vec![value.to_safe_string()],
+                    })
+                }
+                project::ProjectError::LimitExceeded { .. } => {
+                    component_error::Error::LimitExceeded(ErrorBody {
+                        error: value.to_safe_string(),
+                    })
+                }
+                project::ProjectError::InternalRepoError(_) => {
+                    component_error::Error::InternalError(ErrorBody {
+                        error: value.to_safe_string(),
+                    })
+                }
             };
             ComponentError { error: Some(error) }
         }
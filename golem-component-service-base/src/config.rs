@@ -14,6 +14,7 @@
 
 use golem_service_base::model::Empty;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", content = "config")]
@@ -47,3 +48,33 @@ impl ComponentCompilationEnabledConfig {
             .expect("Failed to build ComponentCompilationService URI")
     }
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ComponentGcConfig {
+    pub enabled: bool,
+    pub dry_run: bool,
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    #[serde(with = "humantime_serde")]
+    pub min_object_age: Duration,
+}
+
+impl Default for ComponentGcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dry_run: false,
+            interval: Duration::from_secs(60 * 60),
+            min_object_age: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// Limits of the components stored by the account, unlimited if not set
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ComponentQuotaConfig {
+    /// Maximum size of the WASM of a single component version in bytes
+    pub max_component_size: Option<u64>,
+    /// Maximum total size of the WASMs of all the stored component versions in bytes
+    pub max_total_component_size: Option<u64>,
+}
@@ -14,6 +14,7 @@
 
 pub mod api;
 pub mod config;
+pub mod metrics;
 pub mod model;
 pub mod repo;
 pub mod service;
@@ -0,0 +1,63 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod gc {
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    use crate::service::component_gc::ObjectStoreKind;
+
+    lazy_static! {
+        static ref COMPONENT_GC_RUNS_TOTAL: IntCounterVec = register_int_counter_vec!(
+            "component_gc_runs_total",
+            "Number of component store garbage collection runs",
+            &["dry_run"]
+        )
+        .unwrap();
+        static ref COMPONENT_GC_UNREFERENCED_OBJECTS: IntGauge = register_int_gauge!(
+            "component_gc_unreferenced_objects",
+            "Number of unreferenced objects found by the last garbage collection run"
+        )
+        .unwrap();
+        static ref COMPONENT_GC_DELETED_OBJECTS_TOTAL: IntCounterVec = register_int_counter_vec!(
+            "component_gc_deleted_objects_total",
+            "Number of unreferenced objects deleted from the object stores",
+            &["store"]
+        )
+        .unwrap();
+        static ref COMPONENT_GC_RECLAIMED_BYTES_TOTAL: IntCounterVec = register_int_counter_vec!(
+            "component_gc_reclaimed_bytes_total",
+            "Number of bytes reclaimed by deleting unreferenced objects",
+            &["store"]
+        )
+        .unwrap();
+    }
+
+    pub fn record_gc_run(dry_run: bool, unreferenced_objects: usize) {
+        COMPONENT_GC_RUNS_TOTAL
+            .with_label_values(&[&dry_run.to_string()])
+            .inc();
+        COMPONENT_GC_UNREFERENCED_OBJECTS.set(unreferenced_objects as i64);
+    }
+
+    pub fn record_object_deleted(store: &ObjectStoreKind, size: u64) {
+        let store = store.to_string();
+        COMPONENT_GC_DELETED_OBJECTS_TOTAL
+            .with_label_values(&[&store])
+            .inc();
+        COMPONENT_GC_RECLAIMED_BYTES_TOTAL
+            .with_label_values(&[&store])
+            .inc_by(size);
+    }
+}
@@ -1,5 +1,5 @@
 use golem_common::model::component_metadata::ComponentMetadata;
-use golem_common::model::ComponentType;
+use golem_common::model::{AccountId, ComponentType, ProjectId};
 use golem_service_base::model::{ComponentName, VersionedComponentId};
 use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
@@ -58,3 +58,48 @@ impl<Namespace> From<Component<Namespace>> for golem_api_grpc::proto::golem::com
         }
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Project {
+    pub project_id: ProjectId,
+    pub owner_account_id: AccountId,
+    pub name: String,
+    pub description: String,
+    pub max_components: Option<u32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<Project> for golem_service_base::model::Project {
+    fn from(value: Project) -> Self {
+        Self {
+            project_id: value.project_id,
+            owner_account_id: value.owner_account_id,
+            name: value.name,
+            description: value.description,
+            max_components: value.max_components,
+            created_at: value.created_at,
+        }
+    }
+}
+
+/// The components stored by an account, and the limits of storing more
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountUsage {
+    pub account_id: AccountId,
+    pub component_versions: u64,
+    pub total_component_size: u64,
+    pub max_component_size: Option<u64>,
+    pub max_total_component_size: Option<u64>,
+}
+
+impl From<AccountUsage> for golem_service_base::model::AccountUsage {
+    fn from(value: AccountUsage) -> Self {
+        Self {
+            account_id: value.account_id,
+            component_versions: value.component_versions,
+            total_component_size: value.total_component_size,
+            max_component_size: value.max_component_size,
+            max_total_component_size: value.max_total_component_size,
+        }
+    }
+}
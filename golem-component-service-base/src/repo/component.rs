@@ -74,6 +74,28 @@ impl From<ComponentRecord> for VersionedComponentId {
     }
 }
 
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct ComponentVersionRecord {
+    pub component_id: Uuid,
+    pub version: i64,
+}
+
+/// Number and total size of the stored component versions
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct ComponentUsageRecord {
+    pub component_versions: i64,
+    pub total_size: i64,
+}
+
+impl From<ComponentVersionRecord> for VersionedComponentId {
+    fn from(value: ComponentVersionRecord) -> Self {
+        VersionedComponentId {
+            component_id: ComponentId(value.component_id),
+            version: value.version as u64,
+        }
+    }
+}
+
 impl<Namespace> TryFrom<Component<Namespace>> for ComponentRecord
 where
     Namespace: Display,
@@ -97,6 +119,9 @@ where
 
 #[async_trait]
 pub trait ComponentRepo {
+    /// Stores a new component version. Fails with `RepoError::LimitExceeded` if it is the first
+    /// version of a component which would exceed the `max_components` of the project owning the
+    /// namespace, checked in the same transaction.
     async fn create(&self, component: &ComponentRecord) -> Result<(), RepoError>;
 
     async fn get(&self, component_id: &Uuid) -> Result<Vec<ComponentRecord>, RepoError>;
@@ -125,6 +150,12 @@ pub trait ComponentRepo {
     async fn get_namespace(&self, component_id: &Uuid) -> Result<Option<String>, RepoError>;
 
     async fn delete(&self, namespace: &str, component_id: &Uuid) -> Result<(), RepoError>;
+
+    /// Returns every stored component version, across all namespaces
+    async fn get_all_versions(&self) -> Result<Vec<ComponentVersionRecord>, RepoError>;
+
+    /// Returns the number and total size of every stored component version, across all namespaces
+    async fn get_usage(&self) -> Result<ComponentUsageRecord, RepoError>;
 }
 
 pub struct DbComponentRepo<DB: Database> {
@@ -228,6 +259,16 @@ impl<Repo: ComponentRepo + Send + Sync> ComponentRepo for LoggedComponentRepo<Re
         let result = self.repo.delete(namespace, component_id).await;
         Self::logged_with_id("delete", component_id, result)
     }
+
+    async fn get_all_versions(&self) -> Result<Vec<ComponentVersionRecord>, RepoError> {
+        let result = self.repo.get_all_versions().await;
+        Self::logged("get_all_versions", result)
+    }
+
+    async fn get_usage(&self) -> Result<ComponentUsageRecord, RepoError> {
+        let result = self.repo.get_usage().await;
+        Self::logged("get_usage", result)
+    }
 }
 
 #[trait_gen(sqlx::Postgres -> sqlx::Postgres, sqlx::Sqlite)]
@@ -236,6 +277,23 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
     async fn create(&self, component: &ComponentRecord) -> Result<(), RepoError> {
         let mut transaction = self.db_pool.begin().await?;
 
+        // Locking the row of the project owning the namespace first, so concurrent creations in
+        // the same project are serialized until the commit and cannot exceed its limit together
+        let max_components = match Uuid::parse_str(&component.namespace) {
+            Ok(project_id) => sqlx::query(
+                r#"
+                  UPDATE projects SET max_components = max_components
+                  WHERE project_id = $1
+                  RETURNING max_components
+                   "#,
+            )
+            .bind(project_id)
+            .fetch_optional(&mut *transaction)
+            .await?
+            .and_then(|row| row.get::<Option<i32>, _>("max_components")),
+            Err(_) => None,
+        };
+
         let result = sqlx::query("SELECT namespace, name FROM components WHERE component_id = $1")
             .bind(component.component_id)
             .fetch_optional(&mut *transaction)
@@ -250,6 +308,22 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
                 ));
             }
         } else {
+            if let Some(max_components) = max_components {
+                let components: i64 =
+                    sqlx::query("SELECT COUNT(*) AS count FROM components WHERE namespace = $1")
+                        .bind(component.namespace.clone())
+                        .fetch_one(&mut *transaction)
+                        .await?
+                        .get("count");
+
+                if components >= max_components as i64 {
+                    return Err(RepoError::LimitExceeded(format!(
+                        "Project {} reached its limit of {max_components} components",
+                        component.namespace
+                    )));
+                }
+            }
+
             sqlx::query(
                 r#"
                   INSERT INTO components
@@ -572,6 +646,29 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
         transaction.commit().await?;
         Ok(())
     }
+
+    async fn get_all_versions(&self) -> Result<Vec<ComponentVersionRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentVersionRecord>(
+            "SELECT component_id, version FROM component_versions",
+        )
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn get_usage(&self) -> Result<ComponentUsageRecord, RepoError> {
+        sqlx::query_as::<_, ComponentUsageRecord>(
+            r#"
+                SELECT
+                    COUNT(*) AS component_versions,
+                    COALESCE(SUM(size), 0) AS total_size
+                FROM component_versions
+            "#,
+        )
+        .fetch_one(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
 }
 
 pub mod record_metadata_serde {
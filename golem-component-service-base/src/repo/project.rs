@@ -0,0 +1,276 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::model::Project;
+use async_trait::async_trait;
+use conditional_trait_gen::trait_gen;
+use golem_common::model::{AccountId, ProjectId};
+use golem_service_base::repo::RepoError;
+use sqlx::{Database, Pool, Row};
+use std::ops::Deref;
+use std::sync::Arc;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct ProjectRecord {
+    pub project_id: Uuid,
+    pub owner_account_id: String,
+    pub name: String,
+    pub description: String,
+    pub max_components: Option<i32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ProjectRecord> for Project {
+    fn from(value: ProjectRecord) -> Self {
+        Project {
+            project_id: ProjectId(value.project_id),
+            owner_account_id: AccountId::from(value.owner_account_id.as_str()),
+            name: value.name,
+            description: value.description,
+            max_components: value.max_components.map(|max| max as u32),
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<Project> for ProjectRecord {
+    fn from(value: Project) -> Self {
+        Self {
+            project_id: value.project_id.0,
+            owner_account_id: value.owner_account_id.value,
+            name: value.name,
+            description: value.description,
+            max_components: value.max_components.map(|max| max as i32),
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[async_trait]
+pub trait ProjectRepo {
+    async fn create(&self, project: &ProjectRecord) -> Result<(), RepoError>;
+
+    async fn get(&self, project_id: &Uuid) -> Result<Option<ProjectRecord>, RepoError>;
+
+    async fn get_all(&self, owner_account_id: &str) -> Result<Vec<ProjectRecord>, RepoError>;
+
+    async fn get_by_name(
+        &self,
+        owner_account_id: &str,
+        name: &str,
+    ) -> Result<Option<ProjectRecord>, RepoError>;
+
+    /// Counts the components stored in the namespace of the project
+    async fn get_component_count(&self, namespace: &str) -> Result<u64, RepoError>;
+
+    /// Deletes the project unless components are stored in its namespace, checked in the same
+    /// transaction. Returns false if the project still has components.
+    async fn delete(&self, project_id: &Uuid, namespace: &str) -> Result<bool, RepoError>;
+}
+
+pub struct DbProjectRepo<DB: Database> {
+    db_pool: Arc<Pool<DB>>,
+}
+
+impl<DB: Database> DbProjectRepo<DB> {
+    pub fn new(db_pool: Arc<Pool<DB>>) -> Self {
+        Self { db_pool }
+    }
+}
+
+pub struct LoggedProjectRepo<Repo: ProjectRepo> {
+    repo: Repo,
+}
+
+impl<Repo: ProjectRepo> LoggedProjectRepo<Repo> {
+    pub fn new(repo: Repo) -> Self {
+        Self { repo }
+    }
+
+    fn logged<R>(message: &'static str, result: Result<R, RepoError>) -> Result<R, RepoError> {
+        match &result {
+            Ok(_) => debug!("{}", message),
+            Err(error) => error!(error = error.to_string(), "{message}"),
+        }
+        result
+    }
+
+    fn logged_with_id<R>(
+        message: &'static str,
+        project_id: &Uuid,
+        result: Result<R, RepoError>,
+    ) -> Result<R, RepoError> {
+        match &result {
+            Ok(_) => debug!(project_id = project_id.to_string(), "{}", message),
+            Err(error) => error!(
+                project_id = project_id.to_string(),
+                error = error.to_string(),
+                "{message}"
+            ),
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl<Repo: ProjectRepo + Send + Sync> ProjectRepo for LoggedProjectRepo<Repo> {
+    async fn create(&self, project: &ProjectRecord) -> Result<(), RepoError> {
+        let result = self.repo.create(project).await;
+        Self::logged_with_id("create", &project.project_id, result)
+    }
+
+    async fn get(&self, project_id: &Uuid) -> Result<Option<ProjectRecord>, RepoError> {
+        let result = self.repo.get(project_id).await;
+        Self::logged_with_id("get", project_id, result)
+    }
+
+    async fn get_all(&self, owner_account_id: &str) -> Result<Vec<ProjectRecord>, RepoError> {
+        let result = self.repo.get_all(owner_account_id).await;
+        Self::logged("get_all", result)
+    }
+
+    async fn get_by_name(
+        &self,
+        owner_account_id: &str,
+        name: &str,
+    ) -> Result<Option<ProjectRecord>, RepoError> {
+        let result = self.repo.get_by_name(owner_account_id, name).await;
+        Self::logged("get_by_name", result)
+    }
+
+    async fn get_component_count(&self, namespace: &str) -> Result<u64, RepoError> {
+        let result = self.repo.get_component_count(namespace).await;
+        Self::logged("get_component_count", result)
+    }
+
+    async fn delete(&self, project_id: &Uuid, namespace: &str) -> Result<bool, RepoError> {
+        let result = self.repo.delete(project_id, namespace).await;
+        Self::logged_with_id("delete", project_id, result)
+    }
+}
+
+#[trait_gen(sqlx::Postgres -> sqlx::Postgres, sqlx::Sqlite)]
+#[async_trait]
+impl ProjectRepo for DbProjectRepo<sqlx::Postgres> {
+    async fn create(&self, project: &ProjectRecord) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO projects
+                (project_id, owner_account_id, name, description, max_components, created_at)
+              VALUES
+                ($1, $2, $3, $4, $5, $6)
+               "#,
+        )
+        .bind(project.project_id)
+        .bind(project.owner_account_id.clone())
+        .bind(project.name.clone())
+        .bind(project.description.clone())
+        .bind(project.max_components)
+        .bind(project.created_at)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, project_id: &Uuid) -> Result<Option<ProjectRecord>, RepoError> {
+        sqlx::query_as::<_, ProjectRecord>(
+            r#"
+                SELECT project_id, owner_account_id, name, description, max_components, created_at
+                FROM projects
+                WHERE project_id = $1
+                "#,
+        )
+        .bind(project_id)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn get_all(&self, owner_account_id: &str) -> Result<Vec<ProjectRecord>, RepoError> {
+        sqlx::query_as::<_, ProjectRecord>(
+            r#"
+                SELECT project_id, owner_account_id, name, description, max_components, created_at
+                FROM projects
+                WHERE owner_account_id = $1
+                ORDER BY name
+                "#,
+        )
+        .bind(owner_account_id)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn get_by_name(
+        &self,
+        owner_account_id: &str,
+        name: &str,
+    ) -> Result<Option<ProjectRecord>, RepoError> {
+        sqlx::query_as::<_, ProjectRecord>(
+            r#"
+                SELECT project_id, owner_account_id, name, description, max_components, created_at
+                FROM projects
+                WHERE owner_account_id = $1 AND name = $2
+                "#,
+        )
+        .bind(owner_account_id)
+        .bind(name)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn get_component_count(&self, namespace: &str) -> Result<u64, RepoError> {
+        let result = sqlx::query("SELECT COUNT(*) AS count FROM components WHERE namespace = $1")
+            .bind(namespace)
+            .fetch_one(self.db_pool.deref())
+            .await?;
+
+        let count: i64 = result.get("count");
+        Ok(count as u64)
+    }
+
+    async fn delete(&self, project_id: &Uuid, namespace: &str) -> Result<bool, RepoError> {
+        let mut transaction = self.db_pool.begin().await?;
+
+        // Taking the same lock as the creation of components in the project, so no component
+        // can be added between counting and deleting
+        sqlx::query("UPDATE projects SET max_components = max_components WHERE project_id = $1")
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        let components: i64 =
+            sqlx::query("SELECT COUNT(*) AS count FROM components WHERE namespace = $1")
+                .bind(namespace)
+                .fetch_one(&mut *transaction)
+                .await?
+                .get("count");
+        if components > 0 {
+            return Ok(false);
+        }
+
+        sqlx::query("DELETE FROM projects WHERE project_id = $1")
+            .bind(project_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(true)
+    }
+}
@@ -12,24 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::fmt::{Debug, Display};
 use std::io::Cursor;
 use std::num::TryFromIntError;
 use std::sync::Arc;
 use anyhow::Error;
-use crate::model::Component;
+use crate::config::ComponentQuotaConfig;
+use crate::model::{AccountUsage, Component};
 use crate::repo::component::ComponentRepo;
 use crate::service::component_compilation::ComponentCompilationService;
 use crate::service::component_processor::process_component;
+use crate::service::project::OSS_ACCOUNT_ID;
 use async_trait::async_trait;
 use chrono::Utc;
+use golem_common::model::component_compilation::CompilationStatus;
 use golem_common::model::component_metadata::ComponentProcessingError;
-use golem_common::model::{ComponentId, ComponentType};
+use golem_common::model::{AccountId, ComponentId, ComponentType};
 use golem_common::SafeDisplay;
-use golem_service_base::model::{ComponentName, VersionedComponentId};
+use golem_service_base::model::{ComponentName, ComponentQuota, VersionedComponentId};
 use golem_service_base::repo::RepoError;
 use golem_service_base::service::component_object_store::ComponentObjectStore;
-use golem_service_base::stream::ByteStream;
+use golem_service_base::stream::{ByteRange, ByteStream};
 use tap::TapFallible;
 use tonic::include_file_descriptor_set;
 use tracing::{error, info};
@@ -37,6 +41,7 @@ use golem_common::tracing::directive::default::info;
 use zip::read::ZipArchive;
 use tokio::fs;
 use golem_service_base::service::ifs_object_store::IFSObjectStore;
+use crate::service::ifs::read_initial_file_system_manifest;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ComponentError {
@@ -48,6 +53,8 @@ pub enum ComponentError {
     UnknownVersionedComponentId(VersionedComponentId),
     #[error(transparent)]
     ComponentProcessingError(#[from] ComponentProcessingError),
+    #[error("{0}")]
+    LimitExceeded(String),
     #[error("Internal repository error: {0}")]
     InternalRepoError(RepoError),
     #[error("Internal error: failed to convert {what}: {error}")]
@@ -56,6 +63,41 @@ pub enum ComponentError {
     ComponentStoreError { message: String, error: String },
     #[error("Initial file system storage error: {message}")]
     InitialFileSystemStorageError { message: String },
+    #[error("Invalid initial file system: {}", .0.join(", "))]
+    InvalidInitialFileSystem(Vec<String>),
+    #[error("Invalid function aliases: {}", .0.join(", "))]
+    InvalidFunctionAliases(Vec<String>),
+    #[error("Range not satisfiable: {details}")]
+    RangeNotSatisfiable {
+        details: String,
+        /// The size of the whole WASM in bytes
+        size: u64,
+    },
+    #[error("Component compilation service error: {0}")]
+    CompilationServiceError(String),
+    #[error("{}", quota_exceeded_message(.quota, .limit, .used, .requested))]
+    QuotaExceeded {
+        quota: ComponentQuota,
+        limit: u64,
+        used: u64,
+        requested: u64,
+    },
+}
+
+fn quota_exceeded_message(
+    quota: &ComponentQuota,
+    limit: &u64,
+    used: &u64,
+    requested: &u64,
+) -> String {
+    match quota {
+        ComponentQuota::ComponentSize => format!(
+            "Component of {requested} bytes exceeds the maximum component size of {limit} bytes"
+        ),
+        ComponentQuota::TotalComponentSize => format!(
+            "Component of {requested} bytes exceeds the component storage limit of {limit} bytes, {used} bytes are already used"
+        ),
+    }
 }
 
 impl ComponentError {
@@ -81,20 +123,38 @@ impl SafeDisplay for ComponentError {
             ComponentError::UnknownComponentId(_) => self.to_string(),
             ComponentError::UnknownVersionedComponentId(_) => self.to_string(),
             ComponentError::ComponentProcessingError(inner) => inner.to_safe_string(),
+            ComponentError::LimitExceeded(_) => self.to_string(),
             ComponentError::InternalRepoError(inner) => inner.to_safe_string(),
             ComponentError::InternalConversionError { .. } => self.to_string(),
             ComponentError::ComponentStoreError { .. } => self.to_string(),
             ComponentError::InitialFileSystemStorageError { .. } => self.to_string(),
+            ComponentError::InvalidInitialFileSystem(_) => self.to_string(),
+            ComponentError::InvalidFunctionAliases(_) => self.to_string(),
+            ComponentError::RangeNotSatisfiable { .. } => self.to_string(),
+            ComponentError::CompilationServiceError(_) => self.to_string(),
+            ComponentError::QuotaExceeded { .. } => self.to_string(),
         }
     }
 }
 
 impl From<RepoError> for ComponentError {
     fn from(error: RepoError) -> Self {
-        ComponentError::InternalRepoError(error)
+        match error {
+            RepoError::LimitExceeded(message) => ComponentError::LimitExceeded(message),
+            error => ComponentError::InternalRepoError(error),
+        }
     }
 }
 
+/// A stream of a component's WASM, or a range of it
+pub struct ComponentDownload {
+    pub stream: ByteStream,
+    /// The size of the whole WASM in bytes
+    pub size: u64,
+    /// The streamed range, or `None` if the whole WASM is streamed
+    pub range: Option<ByteRange>,
+}
+
 pub fn create_new_component<Namespace>(
     component_id: &ComponentId,
     component_name: &ComponentName,
@@ -132,16 +192,20 @@ pub trait ComponentService<Namespace> {
         component_type: ComponentType,
         data: Vec<u8>,
         namespace: &Namespace,
-        ifs_data: Vec<u8>
+        ifs_data: Vec<u8>,
+        function_aliases: HashMap<String, String>,
     ) -> Result<Component<Namespace>, ComponentError>;
 
+    /// Uploads a new version of the component. If no function aliases are given, the ones of
+    /// the previous version are kept.
     async fn update(
         &self,
         component_id: &ComponentId,
         data: Vec<u8>,
         component_type: Option<ComponentType>,
         namespace: &Namespace,
-        ifs: Vec<u8>
+        ifs: Vec<u8>,
+        function_aliases: Option<HashMap<String, String>>,
     ) -> Result<Component<Namespace>, ComponentError>;
 
     async fn download(
@@ -158,6 +222,15 @@ pub trait ComponentService<Namespace> {
         namespace: &Namespace,
     ) -> Result<ByteStream, ComponentError>;
 
+    /// Streams the component's WASM, or only the bytes requested by an HTTP `Range` header
+    async fn download_stream_range(
+        &self,
+        component_id: &ComponentId,
+        version: Option<u64>,
+        range: Option<&str>,
+        namespace: &Namespace,
+    ) -> Result<ComponentDownload, ComponentError>;
+
     async fn get_protected_data(
         &self,
         component_id: &ComponentId,
@@ -205,13 +278,27 @@ pub trait ComponentService<Namespace> {
         component_id: &ComponentId,
         namespace: &Namespace,
     ) -> Result<(), ComponentError>;
+
+    /// Gets the status of the compilation of an existing component version. It is `None` if the
+    /// compilation service does not know about the version, for example because it was restarted
+    /// since the version was compiled.
+    async fn get_compilation_status(
+        &self,
+        component_id: &VersionedComponentId,
+        namespace: &Namespace,
+    ) -> Result<Option<CompilationStatus>, ComponentError>;
+
+    /// Gets the size of the components stored by the account, across all its namespaces, and
+    /// the quotas limiting it
+    async fn get_account_usage(&self) -> Result<AccountUsage, ComponentError>;
 }
 
 pub struct ComponentServiceDefault {
     component_repo: Arc<dyn ComponentRepo + Sync + Send>,
     object_store: Arc<dyn ComponentObjectStore + Sync + Send>,
     component_compilation: Arc<dyn ComponentCompilationService + Sync + Send>,
-    ifs_store: Arc<dyn IFSObjectStore + Sync + Send>
+    ifs_store: Arc<dyn IFSObjectStore + Sync + Send>,
+    quota: ComponentQuotaConfig,
 }
 
 impl ComponentServiceDefault {
@@ -220,12 +307,14 @@ impl ComponentServiceDefault {
         object_store: Arc<dyn ComponentObjectStore + Sync + Send>,
         component_compilation: Arc<dyn ComponentCompilationService + Sync + Send>,
         ifs_store: Arc<dyn IFSObjectStore + Sync + Send>,
+        quota: ComponentQuotaConfig,
     ) -> Self {
         ComponentServiceDefault {
             component_repo,
             object_store,
             component_compilation,
-            ifs_store
+            ifs_store,
+            quota,
         }
     }
 }
@@ -243,7 +332,8 @@ where
         component_type: ComponentType,
         data: Vec<u8>,
         namespace: &Namespace,
-        ifs_data: Vec<u8>
+        ifs_data: Vec<u8>,
+        function_aliases: HashMap<String, String>,
     ) -> Result<Component<Namespace>, ComponentError> {
         info!(namespace = %namespace, "Create component");
 
@@ -251,13 +341,23 @@ where
             .await?
             .map_or(Ok(()), |id| Err(ComponentError::AlreadyExists(id)))?;
 
-        let component = create_new_component(
+        self.check_quota(data.len() as u64).await?;
+
+        let manifest = read_initial_file_system_manifest(&ifs_data)?;
+
+        let mut component = create_new_component(
             component_id,
             component_name,
             component_type,
             &data,
             namespace,
         )?;
+        component.metadata.files = manifest.files;
+        component.metadata.function_aliases = function_aliases;
+        component
+            .metadata
+            .validate_function_aliases()
+            .map_err(ComponentError::InvalidFunctionAliases)?;
 
         info!(namespace = %namespace,"Uploaded component - exports {:?}",component.metadata.exports
         );
@@ -290,9 +390,11 @@ where
             .try_into()
             .map_err(|e| ComponentError::conversion_error("record", e))?;
 
-        let result = self.component_repo.create(&record).await;
-        if let Err(RepoError::UniqueViolation(_)) = result {
-            Err(ComponentError::AlreadyExists(component_id.clone()))?;
+        match self.component_repo.create(&record).await {
+            Err(RepoError::UniqueViolation(_)) => {
+                Err(ComponentError::AlreadyExists(component_id.clone()))?
+            }
+            result => result?,
         }
 
         self.component_compilation
@@ -308,12 +410,16 @@ where
         data: Vec<u8>,
         component_type: Option<ComponentType>,
         namespace: &Namespace,
-        ifs: Vec<u8>
+        ifs: Vec<u8>,
+        function_aliases: Option<HashMap<String, String>>,
     ) -> Result<Component<Namespace>, ComponentError> {
         info!(namespace = %namespace, "Update component");
+        self.check_quota(data.len() as u64).await?;
+
         let created_at = Utc::now();
-        let metadata =
+        let mut metadata =
             process_component(&data).map_err(ComponentError::ComponentProcessingError)?;
+        metadata.files = read_initial_file_system_manifest(&ifs)?.files;
 
         let next_component = self
             .component_repo
@@ -327,6 +433,12 @@ where
             })
             .map(Component::next_version)?;
 
+        metadata.function_aliases = function_aliases
+            .unwrap_or_else(|| next_component.metadata.function_aliases.clone());
+        metadata
+            .validate_function_aliases()
+            .map_err(ComponentError::InvalidFunctionAliases)?;
+
         info!(namespace = %namespace, "Uploaded component - exports {:?}", metadata.exports);
 
         let component_size: u64 = data.len().try_into().map_err(|e: TryFromIntError| {
@@ -419,6 +531,49 @@ where
         Ok(stream)
     }
 
+    async fn download_stream_range(
+        &self,
+        component_id: &ComponentId,
+        version: Option<u64>,
+        range: Option<&str>,
+        namespace: &Namespace,
+    ) -> Result<ComponentDownload, ComponentError> {
+        let versioned_component_id = self
+            .get_versioned_component_id(component_id, version, namespace)
+            .await?
+            .ok_or(ComponentError::UnknownComponentId(component_id.clone()))?;
+
+        let object_key = self.get_protected_object_store_key(&versioned_component_id);
+
+        let size = self
+            .object_store
+            .get_size(&object_key)
+            .await
+            .tap_err(
+                |e| error!(namespace = %namespace, "Error downloading component - error: {}", e),
+            )
+            .map_err(|e| ComponentError::component_store_error("Error downloading component", e))?;
+
+        let range = match range {
+            Some(range) => ByteRange::parse_http_range(range, size)
+                .map_err(|details| ComponentError::RangeNotSatisfiable { details, size })?,
+            None => None,
+        };
+
+        info!(namespace = %namespace, range = ?range, "Download component as stream");
+
+        let stream = match range {
+            Some(range) => self.object_store.get_stream_range(&object_key, range).await,
+            None => self.object_store.get_stream(&object_key).await,
+        };
+
+        Ok(ComponentDownload {
+            stream,
+            size,
+            range,
+        })
+    }
+
     async fn get_protected_data(
         &self,
         component_id: &ComponentId,
@@ -610,9 +765,54 @@ where
             Err(ComponentError::UnknownComponentId(component_id.clone()))
         }
     }
+
+    async fn get_compilation_status(
+        &self,
+        component_id: &VersionedComponentId,
+        namespace: &Namespace,
+    ) -> Result<Option<CompilationStatus>, ComponentError> {
+        info!(namespace = %namespace, "Get component compilation status");
+
+        let component = self
+            .component_repo
+            .get_by_version(&component_id.component_id.0, component_id.version)
+            .await?;
+        if !component.is_some_and(|c| c.namespace == namespace.to_string()) {
+            return Err(ComponentError::UnknownVersionedComponentId(
+                component_id.clone(),
+            ));
+        }
+
+        self.component_compilation
+            .get_compilation_status(&component_id.component_id, component_id.version)
+            .await
+            .map_err(ComponentError::CompilationServiceError)
+    }
+
+    async fn get_account_usage(&self) -> Result<AccountUsage, ComponentError> {
+        let usage = self.component_repo.get_usage().await?;
+        Ok(AccountUsage {
+            account_id: AccountId::from(OSS_ACCOUNT_ID),
+            component_versions: usage.component_versions as u64,
+            total_component_size: usage.total_size as u64,
+            max_component_size: self.quota.max_component_size,
+            max_total_component_size: self.quota.max_total_component_size,
+        })
+    }
 }
 
 impl ComponentServiceDefault {
+    /// Checks whether a new component version of the given size can be stored, before uploading it
+    async fn check_quota(&self, requested: u64) -> Result<(), ComponentError> {
+        if self.quota.max_component_size.is_none() && self.quota.max_total_component_size.is_none()
+        {
+            return Ok(());
+        }
+
+        let used = self.component_repo.get_usage().await?.total_size as u64;
+        check_quota(&self.quota, used, requested)
+    }
+
     fn get_user_object_store_key(&self, id: &VersionedComponentId) -> String {
         format!("{id}:user")
     }
@@ -776,12 +976,42 @@ impl ComponentServiceDefault {
     }
 }
 
+fn check_quota(
+    quota: &ComponentQuotaConfig,
+    used: u64,
+    requested: u64,
+) -> Result<(), ComponentError> {
+    if let Some(limit) = quota.max_component_size {
+        if requested > limit {
+            return Err(ComponentError::QuotaExceeded {
+                quota: ComponentQuota::ComponentSize,
+                limit,
+                used,
+                requested,
+            });
+        }
+    }
+    if let Some(limit) = quota.max_total_component_size {
+        if used.saturating_add(requested) > limit {
+            return Err(ComponentError::QuotaExceeded {
+                quota: ComponentQuota::TotalComponentSize,
+                limit,
+                used,
+                requested,
+            });
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use test_r::test;
 
-    use crate::service::component::ComponentError;
+    use crate::config::ComponentQuotaConfig;
+    use crate::service::component::{check_quota, ComponentError};
     use golem_common::SafeDisplay;
+    use golem_service_base::model::ComponentQuota;
     use golem_service_base::repo::RepoError;
 
     #[test]
@@ -793,4 +1023,32 @@ mod tests {
             "Internal repository error".to_string()
         );
     }
+
+    #[test]
+    pub fn test_quota_check() {
+        let quota = ComponentQuotaConfig {
+            max_component_size: Some(100),
+            max_total_component_size: Some(1000),
+        };
+
+        assert!(check_quota(&quota, 900, 100).is_ok());
+        assert!(check_quota(&ComponentQuotaConfig::default(), u64::MAX, u64::MAX).is_ok());
+        assert!(matches!(
+            check_quota(&quota, 0, 101),
+            Err(ComponentError::QuotaExceeded {
+                quota: ComponentQuota::ComponentSize,
+                limit: 100,
+                ..
+            })
+        ));
+        assert!(matches!(
+            check_quota(&quota, 901, 100),
+            Err(ComponentError::QuotaExceeded {
+                quota: ComponentQuota::TotalComponentSize,
+                limit: 1000,
+                used: 901,
+                requested: 100,
+            })
+        ));
+    }
 }
@@ -14,18 +14,30 @@
 
 use async_trait::async_trait;
 use golem_api_grpc::proto::golem::componentcompilation::v1::{
+    component_compilation_error,
     component_compilation_service_client::ComponentCompilationServiceClient,
-    ComponentCompilationRequest,
+    get_compilation_status_response, CompilationPriority, ComponentCompilationRequest,
+    GetCompilationStatusRequest,
 };
 use golem_common::client::{GrpcClient, GrpcClientConfig};
+use golem_common::config::GrpcCompression;
+use golem_common::model::component_compilation::CompilationStatus;
 use golem_common::model::ComponentId;
-use tonic::codec::CompressionEncoding;
+use golem_common::with_grpc_compression;
 use tonic::transport::Channel;
 use tracing::log::info;
 
 #[async_trait]
 pub trait ComponentCompilationService {
     async fn enqueue_compilation(&self, component_id: &ComponentId, component_version: u64, ifs_data: Vec<u8>);
+
+    /// Gets the status of the compilation of a component version, or `None` if the compilation
+    /// service does not know about it
+    async fn get_compilation_status(
+        &self,
+        component_id: &ComponentId,
+        component_version: u64,
+    ) -> Result<Option<CompilationStatus>, String>;
 }
 
 pub struct ComponentCompilationServiceDefault {
@@ -33,12 +45,13 @@ pub struct ComponentCompilationServiceDefault {
 }
 
 impl ComponentCompilationServiceDefault {
-    pub fn new(uri: http_02::Uri) -> Self {
+    pub fn new(uri: http_02::Uri, grpc_compression: GrpcCompression) -> Self {
         let client = GrpcClient::new(
-            |channel| {
-                ComponentCompilationServiceClient::new(channel)
-                    .send_compressed(CompressionEncoding::Gzip)
-                    .accept_compressed(CompressionEncoding::Gzip)
+            move |channel| {
+                with_grpc_compression!(
+                    ComponentCompilationServiceClient::new(channel),
+                    grpc_compression
+                )
             },
             uri,
             GrpcClientConfig::default(), // TODO
@@ -62,7 +75,7 @@ impl ComponentCompilationService for ComponentCompilationServiceDefault {
                         component_id: Some(component_id_clone.into()),
                         component_version,
                         ifs_data: ifs_data_clone,
-
+                        priority: CompilationPriority::Interactive as i32,
                     };
 
                     client.enqueue_compilation(request).await
@@ -76,6 +89,37 @@ impl ComponentCompilationService for ComponentCompilationServiceDefault {
             Err(e) => tracing::error!("Failed to enqueue compilation: {e:?}"),
         }
     }
+
+    async fn get_compilation_status(
+        &self,
+        component_id: &ComponentId,
+        component_version: u64,
+    ) -> Result<Option<CompilationStatus>, String> {
+        let component_id = component_id.clone();
+        let response = self
+            .client
+            .call(move |client| {
+                let request = GetCompilationStatusRequest {
+                    component_id: Some(component_id.clone().into()),
+                    component_version,
+                };
+                Box::pin(async move { client.get_compilation_status(request).await })
+            })
+            .await
+            .map_err(|e| format!("Failed to get compilation status: {e:?}"))?
+            .into_inner();
+
+        match response.result {
+            Some(get_compilation_status_response::Result::Success(status)) => {
+                Ok(Some(status.try_into()?))
+            }
+            Some(get_compilation_status_response::Result::Failure(error)) => match error.error {
+                Some(component_compilation_error::Error::NotFound(_)) => Ok(None),
+                error => Err(format!("Failed to get compilation status: {error:?}")),
+            },
+            None => Err("Empty response".to_string()),
+        }
+    }
 }
 
 pub struct ComponentCompilationServiceDisabled;
@@ -83,4 +127,12 @@ pub struct ComponentCompilationServiceDisabled;
 #[async_trait]
 impl ComponentCompilationService for ComponentCompilationServiceDisabled {
     async fn enqueue_compilation(&self, component_id: &ComponentId, component_version: u64, ifs_data: Vec<u8>) {}
+
+    async fn get_compilation_status(
+        &self,
+        _component_id: &ComponentId,
+        _component_version: u64,
+    ) -> Result<Option<CompilationStatus>, String> {
+        Ok(None)
+    }
 }
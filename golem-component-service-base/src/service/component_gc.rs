@@ -0,0 +1,313 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use golem_common::model::ComponentId;
+use golem_service_base::model::VersionedComponentId;
+use golem_service_base::service::component_object_store::{ComponentObjectStore, ObjectStoreEntry};
+use golem_service_base::service::ifs_object_store::IFSObjectStore;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::metrics::gc::{record_gc_run, record_object_deleted};
+use crate::repo::component::ComponentRepo;
+use crate::service::component::ComponentError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObjectStoreKind {
+    Component,
+    InitialFileSystem,
+}
+
+impl Display for ObjectStoreKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectStoreKind::Component => write!(f, "component"),
+            ObjectStoreKind::InitialFileSystem => write!(f, "ifs"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnreferencedObject {
+    pub store: ObjectStoreKind,
+    pub key: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentGcReport {
+    pub dry_run: bool,
+    pub scanned_objects: u64,
+    pub unreferenced_objects: Vec<UnreferencedObject>,
+    /// Bytes removed from the object stores, or the bytes that would be removed in dry-run mode
+    pub reclaimed_bytes: u64,
+    pub failed_deletions: u64,
+}
+
+/// Finds objects in the component and IFS object stores which are not referenced by any
+/// stored component version (left behind by deletions or failed uploads) and removes them.
+#[async_trait]
+pub trait ComponentGcService {
+    async fn collect_garbage(&self, dry_run: bool) -> Result<ComponentGcReport, ComponentError>;
+}
+
+pub struct ComponentGcServiceDefault {
+    component_repo: Arc<dyn ComponentRepo + Sync + Send>,
+    object_store: Arc<dyn ComponentObjectStore + Sync + Send>,
+    ifs_store: Arc<dyn IFSObjectStore + Sync + Send>,
+    min_object_age: Duration,
+}
+
+impl ComponentGcServiceDefault {
+    pub fn new(
+        component_repo: Arc<dyn ComponentRepo + Sync + Send>,
+        object_store: Arc<dyn ComponentObjectStore + Sync + Send>,
+        ifs_store: Arc<dyn IFSObjectStore + Sync + Send>,
+        min_object_age: Duration,
+    ) -> Self {
+        Self {
+            component_repo,
+            object_store,
+            ifs_store,
+            min_object_age,
+        }
+    }
+
+    /// Objects younger than `min_object_age` may belong to an upload which did not create its
+    /// component version row yet, so they are never considered garbage.
+    fn is_old_enough(&self, entry: &ObjectStoreEntry, now: SystemTime) -> bool {
+        match entry.last_modified {
+            Some(last_modified) => now
+                .duration_since(last_modified)
+                .map(|age| age >= self.min_object_age)
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+
+    async fn delete(&self, object: &UnreferencedObject) -> Result<(), anyhow::Error> {
+        match object.store {
+            ObjectStoreKind::Component => self.object_store.delete(&object.key).await,
+            ObjectStoreKind::InitialFileSystem => self.ifs_store.delete(&object.key).await,
+        }
+    }
+}
+
+#[async_trait]
+impl ComponentGcService for ComponentGcServiceDefault {
+    async fn collect_garbage(&self, dry_run: bool) -> Result<ComponentGcReport, ComponentError> {
+        info!(dry_run, "Component store garbage collection started");
+
+        // Listing the stores before reading the versions, so objects of components created
+        // in the meantime are seen as referenced
+        let component_objects = self.object_store.list().await.map_err(|e| {
+            ComponentError::component_store_error("Failed to list component store", e)
+        })?;
+        let ifs_objects = self
+            .ifs_store
+            .list()
+            .await
+            .map_err(|e| ComponentError::component_store_error("Failed to list IFS store", e))?;
+
+        let versions: HashSet<VersionedComponentId> = self
+            .component_repo
+            .get_all_versions()
+            .await?
+            .into_iter()
+            .map(|r| r.into())
+            .collect();
+        let components: HashSet<ComponentId> =
+            versions.iter().map(|v| v.component_id.clone()).collect();
+
+        let now = SystemTime::now();
+        let mut report = ComponentGcReport {
+            dry_run,
+            scanned_objects: (component_objects.len() + ifs_objects.len()) as u64,
+            ..ComponentGcReport::default()
+        };
+
+        let candidates = component_objects
+            .into_iter()
+            .map(|entry| {
+                let reference = component_object_reference(&entry.key);
+                (ObjectStoreKind::Component, entry, reference)
+            })
+            .chain(ifs_objects.into_iter().map(|entry| {
+                let reference = ifs_object_reference(&entry.key);
+                (ObjectStoreKind::InitialFileSystem, entry, reference)
+            }));
+
+        for (store, entry, reference) in candidates {
+            let referenced = match reference {
+                ObjectReference::Version(id) => versions.contains(&id),
+                ObjectReference::Component(id) => components.contains(&id),
+                ObjectReference::Unknown => true,
+            };
+
+            if !referenced && self.is_old_enough(&entry, now) {
+                let object = UnreferencedObject {
+                    store,
+                    key: entry.key,
+                    size: entry.size,
+                };
+
+                if dry_run {
+                    info!(store = %store, key = object.key, "Unreferenced object found (dry run)");
+                    report.reclaimed_bytes += object.size;
+                } else {
+                    match self.delete(&object).await {
+                        Ok(()) => {
+                            info!(store = %store, key = object.key, "Unreferenced object deleted");
+                            record_object_deleted(&store, object.size);
+                            report.reclaimed_bytes += object.size;
+                        }
+                        Err(error) => {
+                            warn!(
+                                store = %store,
+                                key = object.key,
+                                error = error.to_string(),
+                                "Failed to delete unreferenced object"
+                            );
+                            report.failed_deletions += 1;
+                        }
+                    }
+                }
+
+                report.unreferenced_objects.push(object);
+            }
+        }
+
+        record_gc_run(dry_run, report.unreferenced_objects.len());
+
+        info!(
+            dry_run,
+            scanned_objects = report.scanned_objects,
+            unreferenced_objects = report.unreferenced_objects.len(),
+            reclaimed_bytes = report.reclaimed_bytes,
+            failed_deletions = report.failed_deletions,
+            "Component store garbage collection finished"
+        );
+
+        Ok(report)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ObjectReference {
+    Version(VersionedComponentId),
+    Component(ComponentId),
+    /// Keys not written by the component service are never collected
+    Unknown,
+}
+
+/// Component store keys are `{component_id}#{version}:user`, `{component_id}#{version}:protected`
+/// or `ifs/{component_id}/{path}`
+fn component_object_reference(key: &str) -> ObjectReference {
+    if let Some(path) = key.strip_prefix("ifs/") {
+        return match path.split('/').next().map(Uuid::parse_str) {
+            Some(Ok(component_id)) => ObjectReference::Component(ComponentId(component_id)),
+            _ => ObjectReference::Unknown,
+        };
+    }
+
+    let versioned_id = key
+        .strip_suffix(":user")
+        .or_else(|| key.strip_suffix(":protected"))
+        .and_then(|id| id.split_once('#'));
+
+    match versioned_id {
+        Some((component_id, version)) => {
+            match (Uuid::parse_str(component_id), version.parse::<u64>()) {
+                (Ok(component_id), Ok(version)) => {
+                    ObjectReference::Version(VersionedComponentId {
+                        component_id: ComponentId(component_id),
+                        version,
+                    })
+                }
+                _ => ObjectReference::Unknown,
+            }
+        }
+        None => ObjectReference::Unknown,
+    }
+}
+
+/// IFS store keys are `{component_id}.zip`
+fn ifs_object_reference(key: &str) -> ObjectReference {
+    match key.strip_suffix(".zip").map(Uuid::parse_str) {
+        Some(Ok(component_id)) => ObjectReference::Component(ComponentId(component_id)),
+        _ => ObjectReference::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::ComponentId;
+    use golem_service_base::model::VersionedComponentId;
+    use uuid::Uuid;
+
+    use super::{component_object_reference, ifs_object_reference, ObjectReference};
+
+    #[test]
+    pub fn component_object_keys_are_parsed() {
+        let component_id = Uuid::new_v4();
+        let versioned = ObjectReference::Version(VersionedComponentId {
+            component_id: ComponentId(component_id),
+            version: 3,
+        });
+
+        assert_eq!(
+            component_object_reference(&format!("{component_id}#3:user")),
+            versioned
+        );
+        assert_eq!(
+            component_object_reference(&format!("{component_id}#3:protected")),
+            versioned
+        );
+        assert_eq!(
+            component_object_reference(&format!("ifs/{component_id}/read-only/file.txt")),
+            ObjectReference::Component(ComponentId(component_id))
+        );
+        assert_eq!(
+            component_object_reference(&format!("{component_id}#x:user")),
+            ObjectReference::Unknown
+        );
+        assert_eq!(
+            component_object_reference("some-other-file"),
+            ObjectReference::Unknown
+        );
+    }
+
+    #[test]
+    pub fn ifs_object_keys_are_parsed() {
+        let component_id = Uuid::new_v4();
+
+        assert_eq!(
+            ifs_object_reference(&format!("{component_id}.zip")),
+            ObjectReference::Component(ComponentId(component_id))
+        );
+        assert_eq!(ifs_object_reference("archive.zip"), ObjectReference::Unknown);
+        assert_eq!(
+            ifs_object_reference(&component_id.to_string()),
+            ObjectReference::Unknown
+        );
+    }
+}
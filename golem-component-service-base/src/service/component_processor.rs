@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use golem_common::model::component_metadata::{
     ComponentMetadata, ComponentProcessingError, LinearMemory, RawComponentMetadata,
 };
@@ -40,5 +42,8 @@ pub fn process_component(data: &[u8]) -> Result<ComponentMetadata, ComponentProc
         exports,
         producers,
         memories,
+        files: vec![],
+        function_aliases: HashMap::new(),
+        capabilities: raw_component_metadata.capabilities,
     })
 }
@@ -9,6 +9,10 @@ use golem_service_base::service::ifs_object_store::IFSObjectStore;
 use golem_service_base::stream::ByteStream;
 use crate::repo::component::ComponentRepo;
 use crate::service::component::ComponentError;
+use golem_common::model::initial_file_system::{InitialFileSystemManifest, MANIFEST_PATH};
+use std::io::{Cursor, Read};
+use zip::result::ZipError;
+use zip::ZipArchive;
 
 #[async_trait]
 pub trait InitialFileSystemService<Namespace>{
@@ -103,7 +107,104 @@ where
     }
 }
 
+/// Reads the manifest of an uploaded initial file system archive and checks it against the
+/// contents of the archive
+pub fn read_initial_file_system_manifest(
+    ifs_data: &[u8],
+) -> Result<InitialFileSystemManifest, ComponentError> {
+    let mut zip = ZipArchive::new(Cursor::new(ifs_data)).map_err(|e| {
+        ComponentError::InvalidInitialFileSystem(vec![format!("Invalid zip archive: {e}")])
+    })?;
+    let entries = zip
+        .file_names()
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>();
+
+    // Archives without a manifest get every file with the default permissions
+    let manifest = match zip.by_name(MANIFEST_PATH) {
+        Ok(mut manifest_entry) => {
+            let mut manifest_data = Vec::new();
+            manifest_entry
+                .read_to_end(&mut manifest_data)
+                .map_err(|e| {
+                    ComponentError::InvalidInitialFileSystem(vec![format!(
+                        "Failed to read {MANIFEST_PATH}: {e}"
+                    )])
+                })?;
+            InitialFileSystemManifest::from_json(&manifest_data)
+                .map_err(|e| ComponentError::InvalidInitialFileSystem(vec![e]))?
+        }
+        Err(ZipError::FileNotFound) => {
+            InitialFileSystemManifest::with_default_permissions(&entries)
+        }
+        Err(e) => {
+            return Err(ComponentError::InvalidInitialFileSystem(vec![format!(
+                "Failed to read {MANIFEST_PATH}: {e}"
+            )]))
+        }
+    };
+    manifest
+        .validate(&entries)
+        .map_err(ComponentError::InvalidInitialFileSystem)?;
+
+    let mut errors = Vec::new();
+    for file in manifest.files.iter().filter(|file| file.template) {
+        let mut content = String::new();
+        let is_text = zip
+            .by_name(&file.archive_path())
+            .ok()
+            .is_some_and(|mut entry| entry.read_to_string(&mut content).is_ok());
+        if !is_text {
+            errors.push(format!("Templated file {} is not valid UTF-8", file.path));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(manifest)
+    } else {
+        Err(ComponentError::InvalidInitialFileSystem(errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
 
+    use std::io::{Cursor, Write};
 
+    use golem_common::model::initial_file_system::{InitialFile, InitialFilePermissions};
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
 
+    use crate::service::ifs::read_initial_file_system_manifest;
 
+    #[test]
+    fn archive_without_manifest_gets_default_permissions() {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for name in ["files/config.json", "files/data/users.csv"] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(b"content").unwrap();
+        }
+        let archive = writer.finish().unwrap().into_inner();
+
+        let manifest = read_initial_file_system_manifest(&archive).unwrap();
+
+        assert_eq!(
+            manifest.files,
+            vec![
+                InitialFile {
+                    path: "/config.json".to_string(),
+                    permissions: InitialFilePermissions::ReadOnly,
+                    template: false,
+                },
+                InitialFile {
+                    path: "/data/users.csv".to_string(),
+                    permissions: InitialFilePermissions::ReadOnly,
+                    template: false,
+                },
+            ]
+        );
+    }
+}
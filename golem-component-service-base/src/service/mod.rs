@@ -14,5 +14,7 @@
 
 pub mod component;
 pub mod component_compilation;
+pub mod component_gc;
 pub mod component_processor;
 pub mod ifs;
+pub mod project;
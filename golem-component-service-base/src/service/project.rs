@@ -0,0 +1,210 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use golem_common::model::{AccountId, ProjectId};
+use golem_common::SafeDisplay;
+use golem_service_base::auth::ProjectNamespace;
+use golem_service_base::repo::RepoError;
+use tracing::info;
+
+use crate::model::Project;
+use crate::repo::project::ProjectRepo;
+
+/// The OSS services have a single account, owning all the projects
+pub const OSS_ACCOUNT_ID: &str = "-1";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProjectError {
+    #[error("Project already exists: {0}")]
+    AlreadyExists(String),
+    #[error("Unknown project id: {0}")]
+    UnknownProjectId(ProjectId),
+    #[error("Project {0} still has components")]
+    NotEmpty(ProjectId),
+    #[error("Project {project_id} reached its limit of {max_components} components")]
+    LimitExceeded {
+        project_id: ProjectId,
+        max_components: u32,
+    },
+    #[error("Internal repository error: {0}")]
+    InternalRepoError(RepoError),
+}
+
+impl SafeDisplay for ProjectError {
+    fn to_safe_string(&self) -> String {
+        match self {
+            ProjectError::AlreadyExists(_) => self.to_string(),
+            ProjectError::UnknownProjectId(_) => self.to_string(),
+            ProjectError::NotEmpty(_) => self.to_string(),
+            ProjectError::LimitExceeded { .. } => self.to_string(),
+            ProjectError::InternalRepoError(inner) => inner.to_safe_string(),
+        }
+    }
+}
+
+impl From<RepoError> for ProjectError {
+    fn from(error: RepoError) -> Self {
+        ProjectError::InternalRepoError(error)
+    }
+}
+
+/// Manages the projects grouping the components of the account. Components outside of any
+/// project belong to the default project, which always exists and has no limits.
+#[async_trait]
+pub trait ProjectService {
+    async fn create(
+        &self,
+        name: String,
+        description: String,
+        max_components: Option<u32>,
+    ) -> Result<Project, ProjectError>;
+
+    async fn get(&self, project_id: &ProjectId) -> Result<Option<Project>, ProjectError>;
+
+    async fn find_by_name(&self, name: Option<String>) -> Result<Vec<Project>, ProjectError>;
+
+    /// Deletes a project, which is only possible if all its components were deleted already
+    async fn delete(&self, project_id: &ProjectId) -> Result<(), ProjectError>;
+
+    /// Gets the namespace of the components of an existing project, or of the default project
+    /// if no project is given
+    async fn get_namespace(
+        &self,
+        project_id: Option<ProjectId>,
+    ) -> Result<ProjectNamespace, ProjectError>;
+
+    /// Checks whether another component can be created in the namespace of a project, before
+    /// uploading it. The limit is enforced again when the component is stored, as concurrent
+    /// creations may pass this check together.
+    async fn check_component_quota(&self, namespace: &ProjectNamespace)
+        -> Result<(), ProjectError>;
+}
+
+pub struct ProjectServiceDefault {
+    project_repo: Arc<dyn ProjectRepo + Sync + Send>,
+}
+
+impl ProjectServiceDefault {
+    pub fn new(project_repo: Arc<dyn ProjectRepo + Sync + Send>) -> Self {
+        ProjectServiceDefault { project_repo }
+    }
+
+    async fn get_existing(&self, project_id: &ProjectId) -> Result<Project, ProjectError> {
+        self.get(project_id)
+            .await?
+            .ok_or(ProjectError::UnknownProjectId(project_id.clone()))
+    }
+}
+
+#[async_trait]
+impl ProjectService for ProjectServiceDefault {
+    async fn create(
+        &self,
+        name: String,
+        description: String,
+        max_components: Option<u32>,
+    ) -> Result<Project, ProjectError> {
+        info!(project_name = name, "Create project");
+
+        let project = Project {
+            project_id: ProjectId::new_v4(),
+            owner_account_id: AccountId::from(OSS_ACCOUNT_ID),
+            name,
+            description,
+            max_components,
+            created_at: Utc::now(),
+        };
+
+        match self.project_repo.create(&project.clone().into()).await {
+            Ok(()) => Ok(project),
+            Err(RepoError::UniqueViolation(_)) => Err(ProjectError::AlreadyExists(project.name)),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn get(&self, project_id: &ProjectId) -> Result<Option<Project>, ProjectError> {
+        let record = self.project_repo.get(&project_id.0).await?;
+        Ok(record.map(|record| record.into()))
+    }
+
+    async fn find_by_name(&self, name: Option<String>) -> Result<Vec<Project>, ProjectError> {
+        let records = match name {
+            Some(name) => self
+                .project_repo
+                .get_by_name(OSS_ACCOUNT_ID, &name)
+                .await?
+                .into_iter()
+                .collect(),
+            None => self.project_repo.get_all(OSS_ACCOUNT_ID).await?,
+        };
+        Ok(records.into_iter().map(|record| record.into()).collect())
+    }
+
+    async fn delete(&self, project_id: &ProjectId) -> Result<(), ProjectError> {
+        info!(project_id = %project_id, "Delete project");
+
+        let namespace = self.get_namespace(Some(project_id.clone())).await?;
+        if self
+            .project_repo
+            .delete(&project_id.0, &namespace.to_string())
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(ProjectError::NotEmpty(project_id.clone()))
+        }
+    }
+
+    async fn get_namespace(
+        &self,
+        project_id: Option<ProjectId>,
+    ) -> Result<ProjectNamespace, ProjectError> {
+        match project_id {
+            Some(project_id) => {
+                let project = self.get_existing(&project_id).await?;
+                Ok(ProjectNamespace::Project(project.project_id))
+            }
+            None => Ok(ProjectNamespace::Default),
+        }
+    }
+
+    async fn check_component_quota(
+        &self,
+        namespace: &ProjectNamespace,
+    ) -> Result<(), ProjectError> {
+        let Some(project_id) = namespace.project_id() else {
+            return Ok(());
+        };
+        let project = self.get_existing(project_id).await?;
+
+        if let Some(max_components) = project.max_components {
+            let components = self
+                .project_repo
+                .get_component_count(&namespace.to_string())
+                .await?;
+            if components >= max_components as u64 {
+                return Err(ProjectError::LimitExceeded {
+                    project_id: project.project_id,
+                    max_components,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,20 +1,26 @@
 use test_r::test;
 
 use golem_common::config::{DbPostgresConfig, DbSqliteConfig};
-use golem_service_base::auth::DefaultNamespace;
+use golem_service_base::auth::{DefaultNamespace, ProjectNamespace};
 use golem_service_base::config::ComponentStoreLocalConfig;
 use golem_service_base::db;
 
 use golem_common::model::{ComponentId, ComponentType};
+use golem_component_service_base::config::ComponentQuotaConfig;
 use golem_component_service_base::model::Component;
 use golem_component_service_base::repo::component::{ComponentRepo, DbComponentRepo};
+use golem_component_service_base::repo::project::{DbProjectRepo, ProjectRepo};
 use golem_component_service_base::service::component::{
     create_new_component, ComponentService, ComponentServiceDefault,
 };
 use golem_component_service_base::service::component_compilation::{
     ComponentCompilationService, ComponentCompilationServiceDisabled,
 };
+use golem_component_service_base::service::project::{
+    ProjectError, ProjectService, ProjectServiceDefault,
+};
 use golem_service_base::model::{ComponentName, Configuration};
+use golem_service_base::repo::RepoError;
 use golem_service_base::service::component_object_store;
 use std::sync::Arc;
 use testcontainers::runners::AsyncRunner;
@@ -81,8 +87,12 @@ pub async fn test_with_postgres_db() {
 
     let component_repo: Arc<dyn ComponentRepo + Sync + Send> =
         Arc::new(DbComponentRepo::new(db_pool.clone().into()));
+    let project_repo: Arc<dyn ProjectRepo + Sync + Send> =
+        Arc::new(DbProjectRepo::new(db_pool.clone().into()));
 
     test_repo(component_repo.clone()).await;
+    test_projects(component_repo.clone(), project_repo.clone()).await;
+    test_concurrent_project_components(component_repo.clone(), project_repo.clone()).await;
     // test_services(component_repo.clone()).await;
 }
 
@@ -102,8 +112,12 @@ pub async fn test_with_sqlite_db() {
 
     let component_repo: Arc<dyn ComponentRepo + Sync + Send> =
         Arc::new(DbComponentRepo::new(db_pool.clone().into()));
+    let project_repo: Arc<dyn ProjectRepo + Sync + Send> =
+        Arc::new(DbProjectRepo::new(db_pool.clone().into()));
 
     test_repo(component_repo.clone()).await;
+    test_projects(component_repo.clone(), project_repo.clone()).await;
+    test_concurrent_project_components(component_repo.clone(), project_repo.clone()).await;
     // test_services(component_repo.clone()).await;
 }
 
@@ -135,6 +149,7 @@ async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
             object_store.clone(),
             compilation_service.clone(),
             ifs_object_store.clone(),
+            ComponentQuotaConfig::default(),
         ));
 
     let component_name1 = ComponentName("shopping-cart".to_string());
@@ -376,6 +391,8 @@ async fn test_repo(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
     test_repo_component_id_unique(component_repo.clone()).await;
     test_repo_component_name_unique_in_namespace(component_repo.clone()).await;
     test_repo_component_delete(component_repo.clone()).await;
+    test_repo_component_get_all_versions(component_repo.clone()).await;
+    test_repo_component_usage(component_repo.clone()).await;
 }
 
 async fn test_repo_component_id_unique(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
@@ -505,3 +522,210 @@ async fn test_repo_component_delete(component_repo: Arc<dyn ComponentRepo + Sync
     assert!(result4.is_ok());
     assert!(result4.unwrap().is_empty());
 }
+
+async fn test_repo_component_get_all_versions(
+    component_repo: Arc<dyn ComponentRepo + Sync + Send>,
+) {
+    let namespace1 = Uuid::new_v4().to_string();
+
+    let component_name1 = ComponentName("shopping-cart-versions".to_string());
+    let data = get_component_data("shopping-cart");
+
+    let component1 = create_new_component(
+        &ComponentId::new_v4(),
+        &component_name1,
+        ComponentType::Durable,
+        &data,
+        &namespace1,
+    )
+    .unwrap();
+
+    let result1 = component_repo
+        .create(&component1.clone().try_into().unwrap())
+        .await;
+    let result2 = component_repo
+        .create(&component1.clone().next_version().try_into().unwrap())
+        .await;
+
+    let result3 = component_repo.get_all_versions().await;
+
+    assert!(result1.is_ok());
+    assert!(result2.is_ok());
+    assert!(result3.is_ok());
+    let versions = result3
+        .unwrap()
+        .into_iter()
+        .filter(|r| r.component_id == component1.versioned_component_id.component_id.0)
+        .map(|r| r.version)
+        .collect::<Vec<_>>();
+    assert_eq!(versions.len(), 2);
+    assert!(versions.contains(&0));
+    assert!(versions.contains(&1));
+}
+
+async fn test_repo_component_usage(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
+    let namespace1 = Uuid::new_v4().to_string();
+
+    let component_name1 = ComponentName("shopping-cart-usage".to_string());
+    let data = get_component_data("shopping-cart");
+
+    let component1 = create_new_component(
+        &ComponentId::new_v4(),
+        &component_name1,
+        ComponentType::Durable,
+        &data,
+        &namespace1,
+    )
+    .unwrap();
+
+    let usage1 = component_repo.get_usage().await.unwrap();
+    component_repo
+        .create(&component1.clone().try_into().unwrap())
+        .await
+        .unwrap();
+    component_repo
+        .create(&component1.clone().next_version().try_into().unwrap())
+        .await
+        .unwrap();
+    let usage2 = component_repo.get_usage().await.unwrap();
+
+    assert_eq!(usage2.component_versions - usage1.component_versions, 2);
+    assert_eq!(usage2.total_size - usage1.total_size, 2 * data.len() as i64);
+}
+
+async fn test_projects(
+    component_repo: Arc<dyn ComponentRepo + Sync + Send>,
+    project_repo: Arc<dyn ProjectRepo + Sync + Send>,
+) {
+    let project_service = ProjectServiceDefault::new(project_repo.clone());
+
+    let project_name = format!("project-{}", Uuid::new_v4());
+    let project = project_service
+        .create(project_name.clone(), "".to_string(), Some(1))
+        .await
+        .unwrap();
+    let duplicate = project_service
+        .create(project_name.clone(), "".to_string(), None)
+        .await;
+    assert!(matches!(duplicate, Err(ProjectError::AlreadyExists(_))));
+
+    let found = project_service
+        .find_by_name(Some(project_name.clone()))
+        .await
+        .unwrap();
+    assert_eq!(found, vec![project.clone()]);
+
+    let namespace = project_service
+        .get_namespace(Some(project.project_id.clone()))
+        .await
+        .unwrap();
+    assert_eq!(
+        namespace,
+        ProjectNamespace::Project(project.project_id.clone())
+    );
+    assert_eq!(
+        ProjectNamespace::try_from(namespace.to_string()),
+        Ok(namespace.clone())
+    );
+    assert!(project_service
+        .check_component_quota(&namespace)
+        .await
+        .is_ok());
+
+    let component = create_new_component(
+        &ComponentId::new_v4(),
+        &ComponentName("shopping-cart-project".to_string()),
+        ComponentType::Durable,
+        &get_component_data("shopping-cart"),
+        &namespace,
+    )
+    .unwrap();
+    component_repo
+        .create(&component.clone().try_into().unwrap())
+        .await
+        .unwrap();
+
+    let quota = project_service.check_component_quota(&namespace).await;
+    assert!(matches!(quota, Err(ProjectError::LimitExceeded { .. })));
+    let over_limit = create_new_component(
+        &ComponentId::new_v4(),
+        &ComponentName("shopping-cart-project-2".to_string()),
+        ComponentType::Durable,
+        &get_component_data("shopping-cart"),
+        &namespace,
+    )
+    .unwrap();
+    let over_limit = component_repo.create(&over_limit.try_into().unwrap()).await;
+    assert!(matches!(over_limit, Err(RepoError::LimitExceeded(_))));
+    component_repo
+        .create(&component.clone().next_version().try_into().unwrap())
+        .await
+        .unwrap();
+
+    let delete = project_service.delete(&project.project_id).await;
+    assert!(matches!(delete, Err(ProjectError::NotEmpty(_))));
+
+    component_repo
+        .delete(
+            &namespace.to_string(),
+            &component.versioned_component_id.component_id.0,
+        )
+        .await
+        .unwrap();
+    project_service.delete(&project.project_id).await.unwrap();
+    assert!(project_service
+        .get(&project.project_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+async fn test_concurrent_project_components(
+    component_repo: Arc<dyn ComponentRepo + Sync + Send>,
+    project_repo: Arc<dyn ProjectRepo + Sync + Send>,
+) {
+    let project_service = ProjectServiceDefault::new(project_repo.clone());
+    let project = project_service
+        .create(
+            format!("project-{}", Uuid::new_v4()),
+            "".to_string(),
+            Some(2),
+        )
+        .await
+        .unwrap();
+    let namespace = ProjectNamespace::Project(project.project_id.clone());
+    let data = get_component_data("shopping-cart");
+
+    let mut creations = tokio::task::JoinSet::new();
+    for i in 0..5 {
+        let component = create_new_component(
+            &ComponentId::new_v4(),
+            &ComponentName(format!("shopping-cart-concurrent-{i}")),
+            ComponentType::Durable,
+            &data,
+            &namespace,
+        )
+        .unwrap();
+        let component_repo = component_repo.clone();
+        creations.spawn(async move { component_repo.create(&component.try_into().unwrap()).await });
+    }
+
+    let mut created = 0;
+    while let Some(result) = creations.join_next().await {
+        match result.unwrap() {
+            Ok(()) => created += 1,
+            Err(RepoError::LimitExceeded(_)) => {}
+            Err(error) => panic!("Unexpected error: {error}"),
+        }
+    }
+    assert_eq!(created, 2);
+
+    let components = project_repo
+        .get_component_count(&namespace.to_string())
+        .await
+        .unwrap();
+    assert_eq!(components, 2);
+
+    let delete = project_service.delete(&project.project_id).await;
+    assert!(matches!(delete, Err(ProjectError::NotEmpty(_))));
+}
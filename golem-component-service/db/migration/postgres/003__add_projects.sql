@@ -0,0 +1,11 @@
+CREATE TABLE projects
+(
+    project_id          uuid    NOT NULL PRIMARY KEY,
+    owner_account_id    text    NOT NULL,
+    name                text    NOT NULL,
+    description         text    NOT NULL,
+    max_components      integer,
+    created_at          timestamptz NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX projects_owner_account_id_name_idx ON projects (owner_account_id, name);
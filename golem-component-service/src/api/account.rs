@@ -0,0 +1,54 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use golem_common::recorded_http_api_request;
+use golem_component_service_base::service::component::ComponentService;
+use golem_service_base::api_tags::ApiTags;
+use golem_service_base::auth::ProjectNamespace;
+use golem_service_base::model::AccountUsage;
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use tracing::Instrument;
+
+use crate::api::component::ComponentError;
+
+type Result<T> = std::result::Result<T, ComponentError>;
+
+pub struct AccountApi {
+    pub component_service: Arc<dyn ComponentService<ProjectNamespace> + Sync + Send>,
+}
+
+#[OpenApi(prefix_path = "/v1/account", tag = ApiTags::Account)]
+impl AccountApi {
+    /// Get the usage of the account
+    ///
+    /// Returns the number and total size of the component versions stored by the account, across all
+    /// its projects, together with the quotas limiting the uploads of new component versions.
+    #[oai(path = "/usage", method = "get", operation_id = "get_account_usage")]
+    async fn get_account_usage(&self) -> Result<Json<AccountUsage>> {
+        let record = recorded_http_api_request!("get_account_usage",);
+
+        let response = self
+            .component_service
+            .get_account_usage()
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|usage| Json(usage.into()));
+
+        record.result(response)
+    }
+}
@@ -13,19 +13,25 @@
 // limitations under the License.
 
 use futures_util::TryStreamExt;
-use golem_common::model::{ComponentId, ComponentType};
+use golem_common::model::component_compilation::CompilationStatus;
+use golem_common::model::{ComponentId, ComponentType, ProjectId};
 use golem_component_service_base::service::component::{
     ComponentError as ComponentServiceError, ComponentService,
 };
+use golem_component_service_base::service::project::{
+    ProjectError as ProjectServiceError, ProjectService,
+};
 use golem_service_base::api_tags::ApiTags;
-use golem_service_base::auth::DefaultNamespace;
+use golem_service_base::auth::ProjectNamespace;
 use golem_service_base::model::*;
+use golem_service_base::stream::ByteRange;
 use poem::error::ReadBodyError;
 use poem::Body;
-use poem_openapi::param::{Path, Query};
+use poem_openapi::param::{Header, Path, Query};
 use poem_openapi::payload::{Binary, Json};
-use poem_openapi::types::multipart::Upload;
+use poem_openapi::types::multipart::{JsonField, Upload};
 use poem_openapi::*;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 use tracing::Instrument;
@@ -46,6 +52,8 @@ pub enum ComponentError {
     NotFound(Json<ErrorBody>),
     #[oai(status = 409)]
     AlreadyExists(Json<ErrorBody>),
+    #[oai(status = 413)]
+    QuotaExceeded(Json<QuotaExceededErrorBody>),
     #[oai(status = 500)]
     InternalError(Json<ErrorBody>),
 }
@@ -57,6 +65,7 @@ impl TraceErrorKind for ComponentError {
             ComponentError::NotFound(_) => "NotFound",
             ComponentError::AlreadyExists(_) => "AlreadyExists",
             ComponentError::LimitExceeded(_) => "LimitExceeded",
+            ComponentError::QuotaExceeded(_) => "QuotaExceeded",
             ComponentError::Unauthorized(_) => "Unauthorized",
             ComponentError::InternalError(_) => "InternalError",
         }
@@ -68,13 +77,18 @@ pub struct UploadPayload {
     name: ComponentName,
     component_type: Option<ComponentType>,
     component: Upload,
-    ifs: Upload
+    ifs: Upload,
+    /// Short names for exported functions, mapped to the fully qualified function names
+    function_aliases: Option<JsonField<HashMap<String, String>>>,
 }
 
 #[derive(Multipart)]
 pub struct UpdatePayload {
     component: Upload,
     ifs: Upload,
+    /// Short names for exported functions, mapped to the fully qualified function names. If not
+    /// specified, the aliases of the previous version are kept.
+    function_aliases: Option<JsonField<HashMap<String, String>>>,
 }
 
 type Result<T> = std::result::Result<T, ComponentError>;
@@ -98,6 +112,11 @@ impl From<ComponentServiceError> for ComponentError {
                     errors: vec![error.to_safe_string()],
                 }))
             }
+            ComponentServiceError::LimitExceeded(_) => {
+                ComponentError::LimitExceeded(Json(ErrorBody {
+                    error: error.to_safe_string(),
+                }))
+            }
             ComponentServiceError::InternalRepoError(_) => {
                 ComponentError::InternalError(Json(ErrorBody {
                     error: error.to_safe_string(),
@@ -118,6 +137,62 @@ impl From<ComponentServiceError> for ComponentError {
                     error: error.to_safe_string(),
                 }))
             }
+            ComponentServiceError::InvalidInitialFileSystem(errors) => {
+                ComponentError::BadRequest(Json(ErrorsBody { errors }))
+            }
+            ComponentServiceError::InvalidFunctionAliases(errors) => {
+                ComponentError::BadRequest(Json(ErrorsBody { errors }))
+            }
+            ComponentServiceError::RangeNotSatisfiable { .. } => {
+                ComponentError::BadRequest(Json(ErrorsBody {
+                    errors: vec![error.to_safe_string()],
+                }))
+            }
+            ComponentServiceError::CompilationServiceError(_) => {
+                ComponentError::InternalError(Json(ErrorBody {
+                    error: error.to_safe_string(),
+                }))
+            }
+            ComponentServiceError::QuotaExceeded {
+                ref quota,
+                limit,
+                used,
+                requested,
+            } => ComponentError::QuotaExceeded(Json(QuotaExceededErrorBody {
+                error: error.to_safe_string(),
+                quota: quota.clone(),
+                limit,
+                used,
+                requested,
+            })),
+        }
+    }
+}
+
+impl From<ProjectServiceError> for ComponentError {
+    fn from(error: ProjectServiceError) -> Self {
+        match error {
+            ProjectServiceError::UnknownProjectId(_) => ComponentError::NotFound(Json(ErrorBody {
+                error: error.to_safe_string(),
+            })),
+            ProjectServiceError::AlreadyExists(_) => {
+                ComponentError::AlreadyExists(Json(ErrorBody {
+                    error: error.to_safe_string(),
+                }))
+            }
+            ProjectServiceError::NotEmpty(_) => ComponentError::BadRequest(Json(ErrorsBody {
+                errors: vec![error.to_safe_string()],
+            })),
+            ProjectServiceError::LimitExceeded { .. } => {
+                ComponentError::LimitExceeded(Json(ErrorBody {
+                    error: error.to_safe_string(),
+                }))
+            }
+            ProjectServiceError::InternalRepoError(_) => {
+                ComponentError::InternalError(Json(ErrorBody {
+                    error: error.to_safe_string(),
+                }))
+            }
         }
     }
 }
@@ -138,8 +213,37 @@ impl From<std::io::Error> for ComponentError {
     }
 }
 
+#[derive(ApiResponse)]
+pub enum DownloadComponentResponse {
+    /// The whole WASM of the component
+    #[oai(status = 200)]
+    Full(Binary<Body>, #[oai(header = "Accept-Ranges")] String),
+    /// The range of the WASM requested by the `Range` header
+    #[oai(status = 206)]
+    Partial(
+        Binary<Body>,
+        #[oai(header = "Accept-Ranges")] String,
+        #[oai(header = "Content-Range")] String,
+    ),
+    /// The range requested by the `Range` header is outside of the WASM
+    #[oai(status = 416)]
+    RangeNotSatisfiable(Json<ErrorBody>, #[oai(header = "Content-Range")] String),
+}
+
 pub struct ComponentApi {
-    pub component_service: Arc<dyn ComponentService<DefaultNamespace> + Sync + Send>,
+    pub component_service: Arc<dyn ComponentService<ProjectNamespace> + Sync + Send>,
+    pub project_service: Arc<dyn ProjectService + Sync + Send>,
+}
+
+impl ComponentApi {
+    // Components referenced by id are looked up in the project they belong to
+    async fn component_namespace(&self, component_id: &ComponentId) -> Result<ProjectNamespace> {
+        Ok(self
+            .component_service
+            .get_namespace(component_id)
+            .await?
+            .unwrap_or_default())
+    }
 }
 
 #[OpenApi(prefix_path = "/v1/components", tag = ApiTags::Component)]
@@ -148,14 +252,26 @@ impl ComponentApi {
     ///
     /// The request body is encoded as multipart/form-data containing metadata and the WASM binary.
     /// If the component type is not specified, it will be considered as a `Durable` component.
+    /// The component is created in the default project, unless a project is specified.
     #[oai(path = "/", method = "post", operation_id = "create_component")]
-    async fn create_component(&self, payload: UploadPayload) -> Result<Json<Component>> {
-
-        let record =
-            recorded_http_api_request!("create_component", component_name = payload.name.0);
+    async fn create_component(
+        &self,
+        #[oai(name = "project-id")] project_id: Query<Option<ProjectId>>,
+        payload: UploadPayload,
+    ) -> Result<Json<Component>> {
+        let record = recorded_http_api_request!(
+            "create_component",
+            component_name = payload.name.0,
+            project_id = project_id.0.as_ref().map(|id| id.to_string())
+        );
 
         let ifs_data = payload.ifs.into_vec().await?;
-        let response = {
+        let response: Result<Json<Component>> = async {
+            let namespace = self.project_service.get_namespace(project_id.0).await?;
+            self.project_service
+                .check_component_quota(&namespace)
+                .await?;
+
             let data = payload.component.into_vec().await?;
             let component_name = payload.name;
             self.component_service
@@ -164,14 +280,19 @@ impl ComponentApi {
                     &component_name,
                     payload.component_type.unwrap_or(ComponentType::Durable),
                     data,
-                    &DefaultNamespace::default(),
-                    ifs_data
+                    &namespace,
+                    ifs_data,
+                    payload
+                        .function_aliases
+                        .map(|aliases| aliases.0)
+                        .unwrap_or_default(),
                 )
-                .instrument(record.span.clone())
                 .await
                 .map_err(|e| e.into())
                 .map(|response| Json(response.into()))
-        };
+        }
+        .instrument(record.span.clone())
+        .await;
         record.result(response)
     }
 
@@ -197,16 +318,19 @@ impl ComponentApi {
         let wasm  = payload.component;
 
         let ifs = payload.ifs.into_vec().await?;
+        let function_aliases = payload.function_aliases.map(|aliases| aliases.0);
 
         let response = {
             let data = wasm.into_vec().await?;
+            let namespace = self.component_namespace(&component_id.0).await?;
             self.component_service
                 .update(
                     &component_id.0,
                     data,
                     component_type.0,
-                    &DefaultNamespace::default(),
-                    ifs
+                    &namespace,
+                    ifs,
+                    function_aliases,
                 )
                 .instrument(record.span.clone())
                 .await
@@ -219,6 +343,7 @@ impl ComponentApi {
     /// Download a component
     ///
     /// Downloads a specific version of the component's WASM.
+    /// A single byte range can be requested with the `Range` header, which is answered with a partial content response.
     #[oai(
         path = "/:component_id/download",
         method = "get",
@@ -228,23 +353,44 @@ impl ComponentApi {
         &self,
         component_id: Path<ComponentId>,
         version: Query<Option<u64>>,
-    ) -> Result<Binary<Body>> {
+        #[oai(name = "Range")] range: Header<Option<String>>,
+    ) -> Result<DownloadComponentResponse> {
         let record = recorded_http_api_request!(
             "download_component",
             component_id = component_id.0.to_string(),
-            version = version.0.map(|v| v.to_string())
+            version = version.0.map(|v| v.to_string()),
+            range = range.0.clone()
         );
-        let response = self
+        let namespace = self.component_namespace(&component_id.0).await?;
+        let response = match self
             .component_service
-            .download_stream(&component_id.0, version.0, &DefaultNamespace::default())
+            .download_stream_range(&component_id.0, version.0, range.0.as_deref(), &namespace)
             .instrument(record.span.clone())
             .await
-            .map_err(|e| e.into())
-            .map(|bytes| {
-                Binary(Body::from_bytes_stream(bytes.map_err(|e| {
+        {
+            Ok(download) => {
+                let body = Binary(Body::from_bytes_stream(download.stream.map_err(|e| {
                     std::io::Error::new(std::io::ErrorKind::Other, e.to_string())
-                })))
-            });
+                })));
+                Ok(match download.range {
+                    Some(range) => DownloadComponentResponse::Partial(
+                        body,
+                        "bytes".to_string(),
+                        range.content_range(download.size),
+                    ),
+                    None => DownloadComponentResponse::Full(body, "bytes".to_string()),
+                })
+            }
+            Err(error @ ComponentServiceError::RangeNotSatisfiable { size, .. }) => {
+                Ok(DownloadComponentResponse::RangeNotSatisfiable(
+                    Json(ErrorBody {
+                        error: error.to_safe_string(),
+                    }),
+                    ByteRange::unsatisfied_content_range(size),
+                ))
+            }
+            Err(error) => Err(error.into()),
+        };
         record.result(response)
     }
 
@@ -273,9 +419,10 @@ impl ComponentApi {
             component_id = component_id.0.to_string()
         );
 
+        let namespace = self.component_namespace(&component_id.0).await?;
         let response = self
             .component_service
-            .get(&component_id.0, &DefaultNamespace::default())
+            .get(&component_id.0, &namespace)
             .instrument(record.span.clone())
             .await
             .map_err(|e| e.into())
@@ -284,6 +431,32 @@ impl ComponentApi {
         record.result(response)
     }
 
+    /// Delete a component
+    ///
+    /// Deletes all versions of the component. Existing workers of the component are not deleted.
+    #[oai(
+        path = "/:component_id",
+        method = "delete",
+        operation_id = "delete_component"
+    )]
+    async fn delete_component(&self, component_id: Path<ComponentId>) -> Result<Json<Empty>> {
+        let record = recorded_http_api_request!(
+            "delete_component",
+            component_id = component_id.0.to_string()
+        );
+
+        let namespace = self.component_namespace(&component_id.0).await?;
+        let response = self
+            .component_service
+            .delete(&component_id.0, &namespace)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(Empty {}));
+
+        record.result(response)
+    }
+
     /// Get the version of a given component
     ///
     /// Gets the version of a component.
@@ -310,13 +483,14 @@ impl ComponentApi {
                 }))
             })?;
 
+            let namespace = self.component_namespace(&component_id.0).await?;
             let versioned_component_id = VersionedComponentId {
                 component_id: component_id.0,
                 version: version_int,
             };
 
             self.component_service
-                .get_by_version(&versioned_component_id, &DefaultNamespace::default())
+                .get_by_version(&versioned_component_id, &namespace)
                 .instrument(record.span.clone())
                 .await
                 .map_err(|e| e.into())
@@ -331,6 +505,48 @@ impl ComponentApi {
         record.result(response)
     }
 
+    /// Get the compilation status of a component version
+    ///
+    /// Gets the state of the compilation of a component version by the component compilation service, the number of attempts made and the error of the last failed attempt.
+    /// Compilations are queued with a priority: versions uploaded by users are compiled before recompilations nobody is waiting for.
+    #[oai(
+        path = "/:component_id/versions/:version/compilation-status",
+        method = "get",
+        operation_id = "get_component_compilation_status"
+    )]
+    async fn get_component_compilation_status(
+        &self,
+        component_id: Path<ComponentId>,
+        version: Path<u64>,
+    ) -> Result<Json<CompilationStatus>> {
+        let record = recorded_http_api_request!(
+            "get_component_compilation_status",
+            component_id = component_id.0.to_string(),
+            version = version.0.to_string(),
+        );
+
+        let namespace = self.component_namespace(&component_id.0).await?;
+        let versioned_component_id = VersionedComponentId {
+            component_id: component_id.0,
+            version: version.0,
+        };
+
+        let response = self
+            .component_service
+            .get_compilation_status(&versioned_component_id, &namespace)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .and_then(|response| match response {
+                Some(status) => Ok(Json(status)),
+                None => Err(ComponentError::NotFound(Json(ErrorBody {
+                    error: "Compilation status not found".to_string(),
+                }))),
+            });
+
+        record.result(response)
+    }
+
     /// Get the latest version of a given component
     ///
     /// Gets the latest version of a component.
@@ -348,9 +564,10 @@ impl ComponentApi {
             component_id = component_id.0.to_string()
         );
 
+        let namespace = self.component_namespace(&component_id.0).await?;
         let response = self
             .component_service
-            .get_latest_version(&component_id.0, &DefaultNamespace::default())
+            .get_latest_version(&component_id.0, &namespace)
             .instrument(record.span.clone())
             .await
             .map_err(|e| e.into())
@@ -366,24 +583,29 @@ impl ComponentApi {
 
     /// Get all components
     ///
-    /// Gets all components, optionally filtered by component name.
+    /// Gets all components of a project, optionally filtered by component name. If no project is specified, the components of the default project are returned.
     #[oai(path = "/", method = "get", operation_id = "get_components")]
     async fn get_components(
         &self,
         #[oai(name = "component-name")] component_name: Query<Option<ComponentName>>,
+        #[oai(name = "project-id")] project_id: Query<Option<ProjectId>>,
     ) -> Result<Json<Vec<Component>>> {
         let record = recorded_http_api_request!(
             "get_components",
-            component_name = component_name.0.as_ref().map(|n| n.0.clone())
+            component_name = component_name.0.as_ref().map(|n| n.0.clone()),
+            project_id = project_id.0.as_ref().map(|id| id.to_string())
         );
 
-        let response = self
-            .component_service
-            .find_by_name(component_name.0, &DefaultNamespace::default())
-            .instrument(record.span.clone())
-            .await
-            .map_err(|e| e.into())
-            .map(|components| Json(components.into_iter().map(|c| c.into()).collect()));
+        let response: Result<Json<Vec<Component>>> = async {
+            let namespace = self.project_service.get_namespace(project_id.0).await?;
+            self.component_service
+                .find_by_name(component_name.0, &namespace)
+                .await
+                .map_err(|e| e.into())
+                .map(|components| Json(components.into_iter().map(|c| c.into()).collect()))
+        }
+        .instrument(record.span.clone())
+        .await;
 
         record.result(response)
     }
@@ -0,0 +1,101 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use golem_common::recorded_http_api_request;
+use golem_component_service_base::service::component_gc::{
+    ComponentGcReport, ComponentGcService, UnreferencedObject,
+};
+use golem_service_base::api_tags::ApiTags;
+use poem_openapi::param::Query;
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use tracing::Instrument;
+
+use crate::api::component::ComponentError;
+
+#[derive(Object, Debug, Clone)]
+#[oai(rename_all = "camelCase")]
+pub struct UnreferencedObjectInfo {
+    pub store: String,
+    pub key: String,
+    pub size: u64,
+}
+
+impl From<UnreferencedObject> for UnreferencedObjectInfo {
+    fn from(value: UnreferencedObject) -> Self {
+        Self {
+            store: value.store.to_string(),
+            key: value.key,
+            size: value.size,
+        }
+    }
+}
+
+#[derive(Object, Debug, Clone)]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentGcResult {
+    pub dry_run: bool,
+    pub scanned_objects: u64,
+    pub unreferenced_objects: Vec<UnreferencedObjectInfo>,
+    pub reclaimed_bytes: u64,
+    pub failed_deletions: u64,
+}
+
+impl From<ComponentGcReport> for ComponentGcResult {
+    fn from(value: ComponentGcReport) -> Self {
+        Self {
+            dry_run: value.dry_run,
+            scanned_objects: value.scanned_objects,
+            unreferenced_objects: value
+                .unreferenced_objects
+                .into_iter()
+                .map(|o| o.into())
+                .collect(),
+            reclaimed_bytes: value.reclaimed_bytes,
+            failed_deletions: value.failed_deletions,
+        }
+    }
+}
+
+pub struct ComponentGcApi {
+    pub gc_service: Arc<dyn ComponentGcService + Sync + Send>,
+}
+
+#[OpenApi(prefix_path = "/v1/admin/components", tag = ApiTags::Component)]
+impl ComponentGcApi {
+    /// Collect unreferenced component objects
+    ///
+    /// Finds WASM and initial file system objects in the object stores which are not referenced
+    /// by any component version, and deletes them unless `dry-run` is set.
+    #[oai(path = "/gc", method = "post", operation_id = "collect_component_garbage")]
+    async fn collect_component_garbage(
+        &self,
+        #[oai(name = "dry-run")] dry_run: Query<Option<bool>>,
+    ) -> Result<Json<ComponentGcResult>, ComponentError> {
+        let dry_run = dry_run.0.unwrap_or(false);
+        let record = recorded_http_api_request!("collect_component_garbage", dry_run = dry_run);
+
+        let response = self
+            .gc_service
+            .collect_garbage(dry_run)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|report| Json(report.into()));
+
+        record.result(response)
+    }
+}
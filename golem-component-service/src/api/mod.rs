@@ -20,8 +20,11 @@ use prometheus::Registry;
 use std::ops::Deref;
 use std::sync::Arc;
 
+pub mod account;
 pub mod component;
+pub mod gc;
 pub mod healthcheck;
+pub mod project;
 
 pub fn combined_routes(prometheus_registry: Arc<Registry>, services: &Services) -> Route {
     let api_service = make_open_api_service(services);
@@ -37,15 +40,31 @@ pub fn combined_routes(prometheus_registry: Arc<Registry>, services: &Services)
         .nest("/metrics", metrics)
 }
 
-type ApiServices = (component::ComponentApi, healthcheck::HealthcheckApi);
+type ApiServices = (
+    account::AccountApi,
+    component::ComponentApi,
+    gc::ComponentGcApi,
+    healthcheck::HealthcheckApi,
+    project::ProjectApi,
+);
 
 pub fn make_open_api_service(services: &Services) -> OpenApiService<ApiServices, ()> {
     OpenApiService::new(
         (
+            account::AccountApi {
+                component_service: services.component_service.clone(),
+            },
             component::ComponentApi {
                 component_service: services.component_service.clone(),
+                project_service: services.project_service.clone(),
+            },
+            gc::ComponentGcApi {
+                gc_service: services.gc_service.clone(),
             },
             healthcheck::HealthcheckApi,
+            project::ProjectApi {
+                project_service: services.project_service.clone(),
+            },
         ),
         "Golem API",
         "1.0",
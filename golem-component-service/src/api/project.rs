@@ -0,0 +1,122 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use golem_common::model::ProjectId;
+use golem_common::recorded_http_api_request;
+use golem_component_service_base::service::project::ProjectService;
+use golem_service_base::api_tags::ApiTags;
+use golem_service_base::model::{Empty, ErrorBody, Project, ProjectData};
+use poem_openapi::param::{Path, Query};
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use tracing::Instrument;
+
+use crate::api::component::ComponentError;
+
+type Result<T> = std::result::Result<T, ComponentError>;
+
+pub struct ProjectApi {
+    pub project_service: Arc<dyn ProjectService + Sync + Send>,
+}
+
+#[OpenApi(prefix_path = "/v1/projects", tag = ApiTags::Project)]
+impl ProjectApi {
+    /// Create a new project
+    ///
+    /// Projects group the components of the account, and the workers of those components.
+    /// Components created without specifying a project belong to the default project.
+    #[oai(path = "/", method = "post", operation_id = "create_project")]
+    async fn create_project(&self, data: Json<ProjectData>) -> Result<Json<Project>> {
+        let record = recorded_http_api_request!("create_project", project_name = data.0.name);
+
+        let response = self
+            .project_service
+            .create(data.0.name, data.0.description, data.0.max_components)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|project| Json(project.into()));
+
+        record.result(response)
+    }
+
+    /// Get all projects
+    ///
+    /// Gets all projects of the account, optionally filtered by project name.
+    #[oai(path = "/", method = "get", operation_id = "get_projects")]
+    async fn get_projects(
+        &self,
+        #[oai(name = "project-name")] project_name: Query<Option<String>>,
+    ) -> Result<Json<Vec<Project>>> {
+        let record =
+            recorded_http_api_request!("get_projects", project_name = project_name.0.clone());
+
+        let response = self
+            .project_service
+            .find_by_name(project_name.0)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|projects| Json(projects.into_iter().map(|p| p.into()).collect()));
+
+        record.result(response)
+    }
+
+    /// Get a project
+    #[oai(path = "/:project_id", method = "get", operation_id = "get_project")]
+    async fn get_project(&self, project_id: Path<ProjectId>) -> Result<Json<Project>> {
+        let record =
+            recorded_http_api_request!("get_project", project_id = project_id.0.to_string());
+
+        let response = self
+            .project_service
+            .get(&project_id.0)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .and_then(|response| match response {
+                Some(project) => Ok(Json(project.into())),
+                None => Err(ComponentError::NotFound(Json(ErrorBody {
+                    error: "Project not found".to_string(),
+                }))),
+            });
+
+        record.result(response)
+    }
+
+    /// Delete a project
+    ///
+    /// Only projects without any components can be deleted.
+    #[oai(
+        path = "/:project_id",
+        method = "delete",
+        operation_id = "delete_project"
+    )]
+    async fn delete_project(&self, project_id: Path<ProjectId>) -> Result<Json<Empty>> {
+        let record =
+            recorded_http_api_request!("delete_project", project_id = project_id.0.to_string());
+
+        let response = self
+            .project_service
+            .delete(&project_id.0)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(Empty {}));
+
+        record.result(response)
+    }
+}
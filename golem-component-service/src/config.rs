@@ -15,11 +15,13 @@
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use golem_common::config::{
-    ConfigExample, ConfigLoader, DbConfig, DbSqliteConfig, HasConfigExamples,
+    ConfigExample, ConfigLoader, DbConfig, DbSqliteConfig, GrpcCompression, HasConfigExamples,
 };
 use golem_common::tracing::TracingConfig;
-use golem_component_service_base::config::ComponentCompilationConfig;
-use golem_service_base::config::{ComponentStoreConfig, ComponentStoreLocalConfig, ComponentStoreS3Config, IFSStoreConfig, IFSStoreLocalConfig};
+use golem_component_service_base::config::{
+    ComponentCompilationConfig, ComponentGcConfig, ComponentQuotaConfig,
+};
+use golem_service_base::config::{ComponentStoreConfig, ComponentStoreLocalConfig, ComponentStoreS3Config, IFSStoreConfig, IFSStoreLocalConfig, S3ClientConfig};
 use golem_service_base::model::Empty;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -27,10 +29,13 @@ pub struct ComponentServiceConfig {
     pub tracing: TracingConfig,
     pub http_port: u16,
     pub grpc_port: u16,
+    pub grpc_compression: GrpcCompression,
     pub db: DbConfig,
     pub component_store: ComponentStoreConfig,
     pub compilation: ComponentCompilationConfig,
-    pub ifs_store: IFSStoreConfig
+    pub ifs_store: IFSStoreConfig,
+    pub gc: ComponentGcConfig,
+    pub quota: ComponentQuotaConfig,
 }
 
 impl Default for ComponentServiceConfig {
@@ -39,6 +44,7 @@ impl Default for ComponentServiceConfig {
             tracing: TracingConfig::local_dev("component-service"),
             http_port: 8083,
             grpc_port: 9090,
+            grpc_compression: GrpcCompression::default(),
             db: DbConfig::Sqlite(DbSqliteConfig {
                 database: "../data/golem_component.sqlite".to_string(),
                 max_connections: 10,
@@ -51,7 +57,9 @@ impl Default for ComponentServiceConfig {
             ifs_store: IFSStoreConfig::Local(IFSStoreLocalConfig {
                 root_path: "/ifs".to_string(),
                 object_prefix: "".to_string(),
-            })
+            }),
+            gc: ComponentGcConfig::default(),
+            quota: ComponentQuotaConfig::default(),
         }
     }
 }
@@ -65,6 +73,7 @@ impl HasConfigExamples<ComponentServiceConfig> for ComponentServiceConfig {
                 component_store: ComponentStoreConfig::S3(ComponentStoreS3Config {
                     bucket_name: "bucket".to_string(),
                     object_prefix: "object_prefix".to_string(),
+                    client: S3ClientConfig::default(),
                 }),
                 compilation: ComponentCompilationConfig::Disabled(Empty {}),
                 ..ComponentServiceConfig::default()
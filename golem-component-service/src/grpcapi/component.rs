@@ -34,11 +34,12 @@ use golem_api_grpc::proto::golem::component::v1::{
 };
 use golem_api_grpc::proto::golem::component::Component;
 use golem_common::grpc::proto_component_id_string;
-use golem_common::model::{ComponentId, ComponentType};
+use golem_common::model::{ComponentId, ComponentType, ProjectId};
 use golem_common::recorded_grpc_api_request;
 use golem_component_service_base::api::common::ComponentTraceErrorKind;
 use golem_component_service_base::service::component;
-use golem_service_base::auth::DefaultNamespace;
+use golem_component_service_base::service::project::ProjectService;
+use golem_service_base::auth::ProjectNamespace;
 use golem_service_base::stream::ByteStream;
 use tonic::{Request, Response, Status, Streaming};
 use golem_api_grpc::proto::golem::component::v1::update_component_request::Data;
@@ -60,19 +61,42 @@ fn internal_error(error: &str) -> ComponentError {
 }
 
 pub struct ComponentGrpcApi {
-    pub component_service: Arc<dyn component::ComponentService<DefaultNamespace> + Sync + Send>,
+    pub component_service: Arc<dyn component::ComponentService<ProjectNamespace> + Sync + Send>,
+    pub project_service: Arc<dyn ProjectService + Sync + Send>,
 }
 
 impl ComponentGrpcApi {
+    async fn project_namespace(
+        &self,
+        project_id: Option<golem_api_grpc::proto::golem::common::ProjectId>,
+    ) -> Result<ProjectNamespace, ComponentError> {
+        let project_id: Option<ProjectId> = project_id
+            .map(|id| id.try_into())
+            .transpose()
+            .map_err(|_| bad_request_error("Invalid project id"))?;
+        Ok(self.project_service.get_namespace(project_id).await?)
+    }
+
+    // Components referenced by id are looked up in the project they belong to, so the worker
+    // executors and the worker service do not have to know about projects
+    async fn component_namespace(
+        &self,
+        component_id: &ComponentId,
+    ) -> Result<ProjectNamespace, ComponentError> {
+        Ok(self
+            .component_service
+            .get_namespace(component_id)
+            .await?
+            .unwrap_or_default())
+    }
+
     async fn get(&self, request: GetComponentRequest) -> Result<Vec<Component>, ComponentError> {
         let id: ComponentId = request
             .component_id
             .and_then(|id| id.try_into().ok())
             .ok_or_else(|| bad_request_error("Missing component id"))?;
-        let result = self
-            .component_service
-            .get(&id, &DefaultNamespace::default())
-            .await?;
+        let namespace = self.component_namespace(&id).await?;
+        let result = self.component_service.get(&id, &namespace).await?;
         Ok(result.into_iter().map(|p| p.into()).collect())
     }
 
@@ -87,6 +111,7 @@ impl ComponentGrpcApi {
 
         let version = request.version;
 
+        let namespace = self.component_namespace(&id).await?;
         let versioned_component_id = golem_service_base::model::VersionedComponentId {
             component_id: id,
             version,
@@ -94,7 +119,7 @@ impl ComponentGrpcApi {
 
         let result = self
             .component_service
-            .get_by_version(&versioned_component_id, &DefaultNamespace::default())
+            .get_by_version(&versioned_component_id, &namespace)
             .await?;
         Ok(result.map(|p| p.into()))
     }
@@ -106,9 +131,10 @@ impl ComponentGrpcApi {
         let name: Option<golem_service_base::model::ComponentName> = request
             .component_name
             .map(golem_service_base::model::ComponentName);
+        let namespace = self.project_namespace(request.project_id).await?;
         let result = self
             .component_service
-            .find_by_name(name, &DefaultNamespace::default())
+            .find_by_name(name, &namespace)
             .await?;
         Ok(result.into_iter().map(|p| p.into()).collect())
     }
@@ -121,9 +147,10 @@ impl ComponentGrpcApi {
             .component_id
             .and_then(|id| id.try_into().ok())
             .ok_or_else(|| bad_request_error("Missing component id"))?;
+        let namespace = self.component_namespace(&id).await?;
         let result = self
             .component_service
-            .get_latest_version(&id, &DefaultNamespace::default())
+            .get_latest_version(&id, &namespace)
             .await?;
         match result {
             Some(component) => Ok(component.into()),
@@ -144,9 +171,10 @@ impl ComponentGrpcApi {
             .and_then(|id| id.try_into().ok())
             .ok_or_else(|| bad_request_error("Missing component id"))?;
         let version = request.version;
+        let namespace = self.component_namespace(&id).await?;
         let result = self
             .component_service
-            .download_stream(&id, version, &DefaultNamespace::default())
+            .download_stream(&id, version, &namespace)
             .await?;
         Ok(result)
     }
@@ -157,9 +185,26 @@ impl ComponentGrpcApi {
         data: Vec<u8>,
     ) -> Result<Component, ComponentError> {
         let name = golem_service_base::model::ComponentName(request.component_name.clone());
+        let namespace = self.project_namespace(request.project_id.clone()).await?;
+        self.project_service
+            .check_component_quota(&namespace)
+            .await?;
+        let function_aliases = request
+            .function_aliases
+            .clone()
+            .map(|aliases| aliases.aliases)
+            .unwrap_or_default();
         let result = self
             .component_service
-            .create(&ComponentId::new_v4(), &name, request.component_type().into(), data, &DefaultNamespace::default(), vec![])
+            .create(
+                &ComponentId::new_v4(),
+                &name,
+                request.component_type().into(),
+                data,
+                &namespace,
+                vec![],
+                function_aliases,
+            )
             .await?;
         Ok(result.into())
     }
@@ -181,9 +226,18 @@ impl ComponentGrpcApi {
             ),
             None => None,
         };
+        let function_aliases = request.function_aliases.map(|aliases| aliases.aliases);
+        let namespace = self.component_namespace(&id).await?;
         let result = self
             .component_service
-            .update(&id, data, component_type, &DefaultNamespace::default(), ifs)
+            .update(
+                &id,
+                data,
+                component_type,
+                &namespace,
+                ifs,
+                function_aliases,
+            )
             .await?;
         Ok(result.into())
     }
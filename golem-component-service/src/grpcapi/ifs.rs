@@ -7,8 +7,9 @@ use tracing::Instrument;
 use golem_api_grpc::proto::golem::common::{ErrorBody, ErrorsBody};
 use golem_api_grpc::proto::golem::component::v1::{component_error, ComponentError, DownloadComponentResponse, DownloadIfsRequest, DownloadIfsResponse};
 use golem_api_grpc::proto::golem::component::v1::download_ifs_response::Result::{Error, SuccessChunk};
+use golem_component_service_base::service::component::ComponentService;
 use golem_component_service_base::service::ifs::InitialFileSystemService;
-use golem_service_base::auth::DefaultNamespace;
+use golem_service_base::auth::ProjectNamespace;
 use golem_api_grpc::proto::golem::component::v1::ifs_service_server::IfsService;
 use golem_common::grpc::proto_component_id_string;
 use golem_common::recorded_grpc_api_request;
@@ -16,7 +17,8 @@ use golem_component_service_base::api::common::ComponentTraceErrorKind;
 use golem_service_base::stream::ByteStream;
 
 pub struct IFSGrpcApi{
-    pub ifs_service: Arc<dyn InitialFileSystemService<DefaultNamespace> + Send + Sync>
+    pub ifs_service: Arc<dyn InitialFileSystemService<ProjectNamespace> + Send + Sync>,
+    pub component_service: Arc<dyn ComponentService<ProjectNamespace> + Send + Sync>,
 }
 
 impl IFSGrpcApi {
@@ -28,9 +30,14 @@ impl IFSGrpcApi {
             .ok_or_else(|| bad_request_error("Missing component_id"))?;
 
         let version = request.version;
+        let namespace = self
+            .component_service
+            .get_namespace(&id)
+            .await?
+            .unwrap_or_default();
         let result = self
             .ifs_service
-            .download_stream(&id, version, &Default::default()).await?;
+            .download_stream(&id, version, &namespace).await?;
         Ok(result)
 
     }
@@ -14,8 +14,10 @@
 
 use golem_api_grpc::proto;
 use golem_api_grpc::proto::golem::component::v1::component_service_server::ComponentServiceServer;
+use golem_common::config::GrpcCompression;
+use golem_common::metrics::grpc::GrpcMessageMetricsLayer;
+use golem_common::with_grpc_compression;
 use std::net::SocketAddr;
-use tonic::codec::CompressionEncoding;
 use tonic::transport::{Error, Server};
 use golem_api_grpc::proto::golem::component::v1::ifs_service_server::IfsServiceServer;
 use crate::grpcapi::component::ComponentGrpcApi;
@@ -24,7 +26,11 @@ use crate::service::Services;
 mod component;
 mod ifs;
 
-pub async fn start_grpc_server(addr: SocketAddr, services: &Services) -> Result<(), Error> {
+pub async fn start_grpc_server(
+    addr: SocketAddr,
+    grpc_compression: GrpcCompression,
+    services: &Services,
+) -> Result<(), Error> {
     let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
 
     health_reporter
@@ -37,22 +43,23 @@ pub async fn start_grpc_server(addr: SocketAddr, services: &Services) -> Result<
         .unwrap();
 
     Server::builder()
+        .layer(GrpcMessageMetricsLayer)
         .add_service(reflection_service)
         .add_service(health_service)
-        .add_service(
+        .add_service(with_grpc_compression!(
             ComponentServiceServer::new(ComponentGrpcApi {
                 component_service: services.component_service.clone(),
-            })
-            .accept_compressed(CompressionEncoding::Gzip)
-            .send_compressed(CompressionEncoding::Gzip),
-        )
-        .add_service(
+                project_service: services.project_service.clone(),
+            }),
+            grpc_compression
+        ))
+        .add_service(with_grpc_compression!(
             IfsServiceServer::new(IFSGrpcApi {
                 ifs_service: services.ifs_service.clone(),
-            })
-                .accept_compressed(CompressionEncoding::Gzip)
-                .send_compressed(CompressionEncoding::Gzip),
-        )
+                component_service: services.component_service.clone(),
+            }),
+            grpc_compression
+        ))
         .serve(addr)
         .await
 }
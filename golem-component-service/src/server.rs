@@ -76,6 +76,7 @@ async fn async_main(
     prometheus_registry: Registry,
 ) -> Result<(), std::io::Error> {
     let grpc_port = config.grpc_port;
+    let grpc_compression = config.grpc_compression;
     let http_port = config.http_port;
 
     info!(
@@ -107,6 +108,24 @@ async fn async_main(
         std::io::Error::new(std::io::ErrorKind::Other, e)
     })?;
 
+    if config.gc.enabled {
+        let gc_service = services.gc_service.clone();
+        let gc_config = config.gc.clone();
+        info!(
+            "Starting component store garbage collection every {:?} (dry run: {})",
+            gc_config.interval, gc_config.dry_run
+        );
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(gc_config.interval);
+            loop {
+                interval.tick().await;
+                if let Err(error) = gc_service.collect_garbage(gc_config.dry_run).await {
+                    error!("Component store garbage collection failed: {}", error);
+                }
+            }
+        });
+    }
+
     let http_services = services.clone();
     let grpc_services = services.clone();
 
@@ -125,6 +144,7 @@ async fn async_main(
     let grpc_server = tokio::spawn(async move {
         grpcapi::start_grpc_server(
             SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), grpc_port).into(),
+            grpc_compression,
             &grpc_services,
         )
         .await
@@ -26,37 +26,59 @@ use crate::config::ComponentServiceConfig;
 use golem_component_service_base::repo::component::{
     ComponentRepo, DbComponentRepo, LoggedComponentRepo,
 };
+use golem_component_service_base::repo::project::{
+    DbProjectRepo, LoggedProjectRepo, ProjectRepo,
+};
 use golem_component_service_base::service::component::{ComponentService, ComponentServiceDefault};
+use golem_component_service_base::service::component_gc::{
+    ComponentGcService, ComponentGcServiceDefault,
+};
 use golem_component_service_base::service::ifs::{InitialFileSystemService, InitialFileSystemServiceDefault};
-use golem_service_base::auth::DefaultNamespace;
+use golem_component_service_base::service::project::{ProjectService, ProjectServiceDefault};
+use golem_service_base::auth::ProjectNamespace;
 
 #[derive(Clone)]
 pub struct Services {
-    pub component_service: Arc<dyn ComponentService<DefaultNamespace> + Sync + Send>,
+    pub component_service: Arc<dyn ComponentService<ProjectNamespace> + Sync + Send>,
     pub compilation_service: Arc<dyn ComponentCompilationService + Sync + Send>,
-    pub ifs_service: Arc<dyn InitialFileSystemService<DefaultNamespace> + Sync + Send>
+    pub ifs_service: Arc<dyn InitialFileSystemService<ProjectNamespace> + Sync + Send>,
+    pub gc_service: Arc<dyn ComponentGcService + Sync + Send>,
+    pub project_service: Arc<dyn ProjectService + Sync + Send>,
 }
 
 impl Services {
     pub async fn new(config: &ComponentServiceConfig) -> Result<Services, String> {
 
 
-        let component_repo: Arc<dyn ComponentRepo + Sync + Send> = match config.db.clone() {
+        let (component_repo, project_repo): (
+            Arc<dyn ComponentRepo + Sync + Send>,
+            Arc<dyn ProjectRepo + Sync + Send>,
+        ) = match config.db.clone() {
             DbConfig::Postgres(c) => {
                 let db_pool = db::create_postgres_pool(&c)
                     .await
                     .map_err(|e| e.to_string())?;
-                Arc::new(LoggedComponentRepo::new(DbComponentRepo::new(
-                    db_pool.clone().into(),
-                )))
+                (
+                    Arc::new(LoggedComponentRepo::new(DbComponentRepo::new(
+                        db_pool.clone().into(),
+                    ))),
+                    Arc::new(LoggedProjectRepo::new(DbProjectRepo::new(
+                        db_pool.clone().into(),
+                    ))),
+                )
             }
             DbConfig::Sqlite(c) => {
                 let db_pool = db::create_sqlite_pool(&c)
                     .await
                     .map_err(|e| e.to_string())?;
-                Arc::new(LoggedComponentRepo::new(DbComponentRepo::new(
-                    db_pool.clone().into(),
-                )))
+                (
+                    Arc::new(LoggedComponentRepo::new(DbComponentRepo::new(
+                        db_pool.clone().into(),
+                    ))),
+                    Arc::new(LoggedProjectRepo::new(DbProjectRepo::new(
+                        db_pool.clone().into(),
+                    ))),
+                )
             }
         };
 
@@ -80,34 +102,49 @@ impl Services {
                 }
             };
 
+        let grpc_compression = config.grpc_compression;
         let compilation_service: Arc<dyn ComponentCompilationService + Sync + Send> =
             match config.compilation.clone() {
-                ComponentCompilationConfig::Enabled(config) => {
-                    Arc::new(ComponentCompilationServiceDefault::new(config.uri()))
-                }
+                ComponentCompilationConfig::Enabled(config) => Arc::new(
+                    ComponentCompilationServiceDefault::new(config.uri(), grpc_compression),
+                ),
                 ComponentCompilationConfig::Disabled(_) => {
                     Arc::new(ComponentCompilationServiceDisabled)
                 }
             };
 
-        let component_service: Arc<dyn ComponentService<DefaultNamespace> + Sync + Send> =
+        let component_service: Arc<dyn ComponentService<ProjectNamespace> + Sync + Send> =
             Arc::new(ComponentServiceDefault::new(
                 component_repo.clone(),
                 object_store.clone(),
                 compilation_service.clone(),
-                ifs_object_store.clone()
+                ifs_object_store.clone(),
+                config.quota.clone(),
             ));
 
-        let ifs_service: Arc<dyn InitialFileSystemService<DefaultNamespace> + Sync + Send> =
+        let ifs_service: Arc<dyn InitialFileSystemService<ProjectNamespace> + Sync + Send> =
             Arc::new(InitialFileSystemServiceDefault::new(
                 component_repo.clone(),
                 ifs_object_store.clone(),
             ));
 
+        let gc_service: Arc<dyn ComponentGcService + Sync + Send> =
+            Arc::new(ComponentGcServiceDefault::new(
+                component_repo.clone(),
+                object_store.clone(),
+                ifs_object_store.clone(),
+                config.gc.min_object_age,
+            ));
+
+        let project_service: Arc<dyn ProjectService + Sync + Send> =
+            Arc::new(ProjectServiceDefault::new(project_repo.clone()));
+
         Ok(Services {
             component_service,
             compilation_service,
-            ifs_service
+            ifs_service,
+            gc_service,
+            project_service,
         })
     }
 }
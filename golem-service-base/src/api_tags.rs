@@ -2,9 +2,11 @@ use poem_openapi::Tags;
 
 #[derive(Tags)]
 pub enum ApiTags {
+    Account,
     ApiDeployment,
     ApiDefinition,
     Component,
+    Project,
     Worker,
     HealthCheck,
 }
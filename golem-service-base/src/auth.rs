@@ -1,5 +1,6 @@
 use std::fmt::{Display, Formatter};
 
+use golem_common::model::ProjectId;
 use serde::Deserialize;
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
@@ -41,3 +42,52 @@ impl TryFrom<String> for DefaultNamespace {
         }
     }
 }
+
+/// Namespace of the components of the OSS services. Components belong either to the default
+/// project, or to one of the projects created through the project API.
+#[derive(
+    Default, Debug, Clone, PartialEq, Eq, Hash, bincode::Encode, bincode::Decode, Deserialize,
+)]
+pub enum ProjectNamespace {
+    #[default]
+    Default,
+    Project(ProjectId),
+}
+
+impl ProjectNamespace {
+    pub fn new(project_id: Option<ProjectId>) -> Self {
+        match project_id {
+            Some(project_id) => ProjectNamespace::Project(project_id),
+            None => ProjectNamespace::Default,
+        }
+    }
+
+    pub fn project_id(&self) -> Option<&ProjectId> {
+        match self {
+            ProjectNamespace::Default => None,
+            ProjectNamespace::Project(project_id) => Some(project_id),
+        }
+    }
+}
+
+impl Display for ProjectNamespace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            // Same as the namespace of the components created before projects were introduced
+            ProjectNamespace::Default => write!(f, "{}", DefaultNamespace::default()),
+            ProjectNamespace::Project(project_id) => write!(f, "{project_id}"),
+        }
+    }
+}
+
+impl TryFrom<String> for ProjectNamespace {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match DefaultNamespace::try_from(value.clone()) {
+            Ok(_) => Ok(ProjectNamespace::Default),
+            Err(_) => ProjectId::try_from(value.as_str())
+                .map(ProjectNamespace::Project)
+                .map_err(|_| format!("Failed to parse project namespace: {value}")),
+        }
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::model::Empty;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -35,6 +36,8 @@ impl Default for ComponentStoreConfig {
 pub struct ComponentStoreS3Config {
     pub bucket_name: String,
     pub object_prefix: String,
+    #[serde(default)]
+    pub client: S3ClientConfig,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -86,4 +89,149 @@ pub struct IFSStoreLocalConfig {
 pub struct IFSStoreS3Config {
     pub bucket_name: String,
     pub object_prefix: String,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub client: S3ClientConfig,
+}
+
+/// Connection settings of an S3 compatible object store
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3ClientConfig {
+    /// Overrides the region of the AWS environment
+    pub region: Option<String>,
+    /// Custom endpoint, for example of a MinIO server
+    pub endpoint_url: Option<String>,
+    /// Addresses buckets as `<endpoint>/<bucket>` instead of `<bucket>.<endpoint>`,
+    /// which is required by most S3 compatible stores
+    pub force_path_style: bool,
+    pub credentials: S3CredentialsConfig,
+    /// A role to assume with the above credentials
+    pub assume_role: Option<S3AssumeRoleConfig>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum S3CredentialsConfig {
+    /// The default AWS credentials provider chain, checking the environment variables, the shared
+    /// profiles, web identity tokens and the instance metadata service in this order
+    Default(Empty),
+    /// Web identity token of the `AWS_WEB_IDENTITY_TOKEN_FILE` and `AWS_ROLE_ARN` environment
+    /// variables, as set up by IAM roles for service accounts (IRSA) on EKS
+    WebIdentity(Empty),
+    /// Instance profile credentials of the EC2 instance metadata service
+    InstanceProfile(Empty),
+    Static(S3StaticCredentialsConfig),
+}
+
+impl Default for S3CredentialsConfig {
+    fn default() -> Self {
+        S3CredentialsConfig::Default(Empty {})
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct S3StaticCredentialsConfig {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct S3AssumeRoleConfig {
+    pub role_arn: String,
+    /// Required by roles shared with third parties, to prevent the confused deputy problem
+    pub external_id: Option<String>,
+    pub session_name: Option<String>,
+    #[serde(default, with = "humantime_serde")]
+    pub session_duration: Option<Duration>,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::config::{ComponentStoreConfig, IFSStoreS3Config, S3CredentialsConfig};
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    fn s3_store_without_client_config_uses_the_default_credentials_chain() {
+        let config: ComponentStoreConfig = serde_json::from_value(json!({
+            "type": "S3",
+            "config": { "bucket_name": "bucket", "object_prefix": "prefix" }
+        }))
+        .unwrap();
+
+        let ComponentStoreConfig::S3(config) = config else {
+            panic!("Expected an S3 component store config");
+        };
+        assert_eq!(config.client.region, None);
+        assert_eq!(config.client.endpoint_url, None);
+        assert!(!config.client.force_path_style);
+        assert!(config.client.assume_role.is_none());
+        assert!(matches!(
+            config.client.credentials,
+            S3CredentialsConfig::Default(_)
+        ));
+    }
+
+    #[test]
+    fn s3_client_config_with_static_credentials_and_assumed_role() {
+        let config: IFSStoreS3Config = serde_json::from_value(json!({
+            "bucket_name": "bucket",
+            "object_prefix": "prefix",
+            "client": {
+                "region": "eu-central-1",
+                "endpoint_url": "http://localhost:9000",
+                "force_path_style": true,
+                "credentials": {
+                    "type": "Static",
+                    "config": { "access_key_id": "key", "secret_access_key": "secret" }
+                },
+                "assume_role": {
+                    "role_arn": "arn:aws:iam::123456789012:role/golem",
+                    "external_id": "external",
+                    "session_duration": "15m"
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(config.client.region.as_deref(), Some("eu-central-1"));
+        assert_eq!(
+            config.client.endpoint_url.as_deref(),
+            Some("http://localhost:9000")
+        );
+        assert!(config.client.force_path_style);
+
+        let S3CredentialsConfig::Static(credentials) = config.client.credentials else {
+            panic!("Expected static credentials");
+        };
+        assert_eq!(credentials.access_key_id, "key");
+        assert_eq!(credentials.secret_access_key, "secret");
+        assert_eq!(credentials.session_token, None);
+
+        let assume_role = config.client.assume_role.unwrap();
+        assert_eq!(assume_role.role_arn, "arn:aws:iam::123456789012:role/golem");
+        assert_eq!(assume_role.external_id.as_deref(), Some("external"));
+        assert_eq!(assume_role.session_name, None);
+        assert_eq!(
+            assume_role.session_duration,
+            Some(Duration::from_secs(15 * 60))
+        );
+    }
+
+    #[test]
+    fn s3_credentials_config_selects_the_provider_by_type() {
+        let web_identity: S3CredentialsConfig =
+            serde_json::from_value(json!({ "type": "WebIdentity", "config": {} })).unwrap();
+        let instance_profile: S3CredentialsConfig =
+            serde_json::from_value(json!({ "type": "InstanceProfile", "config": {} })).unwrap();
+
+        assert!(matches!(web_identity, S3CredentialsConfig::WebIdentity(_)));
+        assert!(matches!(
+            instance_profile,
+            S3CredentialsConfig::InstanceProfile(_)
+        ));
+    }
+}
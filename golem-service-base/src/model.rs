@@ -14,16 +14,20 @@
 
 use bincode::{Decode, Encode};
 use golem_common::model::component_metadata::ComponentMetadata;
+use golem_common::model::oplog::{OplogIndex, WorkerErrorKind};
 use golem_common::model::public_oplog::{OplogCursor, PublicOplogEntry};
+use golem_common::model::worker_metadata_projection::WorkerMetadataField;
+use golem_common::model::worker_name_query::WorkerNameQuery;
 use golem_common::model::{
-    ComponentId, ComponentType, ComponentVersion, PromiseId, ScanCursor, ShardId, Timestamp,
-    WorkerFilter, WorkerId, WorkerStatus,
+    AccountId, ComponentId, ComponentType, ComponentVersion, IdempotencyKey, ProjectId, PromiseId,
+    ScanCursor, ShardId, Timestamp, UpdateDivergence, WorkerFilter, WorkerId, WorkerStatus,
 };
 use golem_common::SafeDisplay;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use poem_openapi::{ApiResponse, Enum, NewType, Object, Union};
 use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
+use std::collections::hash_map::Entry;
 use std::{collections::HashMap, fmt::Display, fmt::Formatter};
 use std::borrow::Cow;
 use poem_openapi::payload::{Binary, Json, PlainText};
@@ -33,10 +37,18 @@ use thiserror::Error;
 use golem_api_grpc::proto::golem::workerexecutor::v1::FileNode;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
 pub struct WorkerCreationRequest {
-    pub name: String,
+    /// Generated with the naming strategy of the component's worker defaults if not given
+    pub name: Option<String>,
     pub args: Vec<String>,
     pub env: HashMap<String, String>,
+    /// The worker and all its storage is deleted after this many seconds
+    pub ttl_seconds: Option<u64>,
+    /// Workers with the same anti-affinity key are placed on different executors where the
+    /// shard assignment allows it
+    pub anti_affinity_key: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
@@ -47,6 +59,18 @@ pub struct WorkerCreationResponse {
     pub component_version: ComponentVersion,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct WorkerGetOrCreateResponse {
+    pub worker_id: WorkerId,
+    /// The component version of the existing worker, or the latest one if it was created
+    pub component_version: ComponentVersion,
+    /// False if the worker already existed, in which case the arguments, environment and TTL of
+    /// the request were not applied
+    pub created: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, NewType)]
 pub struct ComponentName(pub String);
 
@@ -114,7 +138,24 @@ impl std::fmt::Display for VersionedComponentId {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
 pub struct Empty {}
 
+/// Suffix of the name of a worker's shadow worker. The names of the workers cannot contain dots
+/// otherwise, so shadow workers never collide with the workers created by the users.
+pub const SHADOW_WORKER_SUFFIX: &str = ".shadow";
+
+/// The name of the shadow worker of the worker named `name`
+pub fn shadow_worker_name(name: &str) -> String {
+    format!("{name}{SHADOW_WORKER_SUFFIX}")
+}
+
+pub fn is_shadow_worker_name(name: &str) -> bool {
+    name.strip_suffix(SHADOW_WORKER_SUFFIX).is_some_and(|name| {
+        !name.ends_with(SHADOW_WORKER_SUFFIX) && validate_worker_name(name).is_ok()
+    })
+}
+
+/// Validates the name of an existing worker, which can be the name of a shadow worker too
 pub fn validate_worker_name(name: &str) -> Result<(), &'static str> {
+    let name = name.strip_suffix(SHADOW_WORKER_SUFFIX).unwrap_or(name);
     let length = name.len();
     if !(1..=100).contains(&length) {
         Err("Worker name must be between 1 and 100 characters")
@@ -965,13 +1006,201 @@ impl From<crate::model::GolemErrorShardingNotReady>
     }
 }
 
+/// The result of the invocation exceeded the executor's limit. It was stored in the blob storage
+/// under `blob_key`, from where it can be downloaded separately.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, thiserror::Error)]
+#[error("Invocation result of {size} bytes exceeds the limit of {limit} bytes")]
+pub struct GolemErrorResultTooLarge {
+    pub size: u64,
+    pub limit: u64,
+    pub blob_key: String,
+}
+
+impl SafeDisplay for GolemErrorResultTooLarge {
+    fn to_safe_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::worker::v1::ResultTooLarge> for GolemErrorResultTooLarge {
+    fn from(value: golem_api_grpc::proto::golem::worker::v1::ResultTooLarge) -> Self {
+        Self {
+            size: value.size,
+            limit: value.limit,
+            blob_key: value.blob_key,
+        }
+    }
+}
+
+impl From<GolemErrorResultTooLarge> for golem_api_grpc::proto::golem::worker::v1::ResultTooLarge {
+    fn from(value: GolemErrorResultTooLarge) -> Self {
+        Self {
+            size: value.size,
+            limit: value.limit,
+            blob_key: value.blob_key,
+        }
+    }
+}
+
+/// The invocation did not complete within the timeout set in its invocation context, and got
+/// interrupted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, thiserror::Error)]
+#[error("Invocation timed out after {timeout_millis} ms")]
+pub struct GolemErrorInvocationTimedOut {
+    pub timeout_millis: u64,
+}
+
+impl SafeDisplay for GolemErrorInvocationTimedOut {
+    fn to_safe_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::worker::v1::InvocationTimedOut>
+    for GolemErrorInvocationTimedOut
+{
+    fn from(value: golem_api_grpc::proto::golem::worker::v1::InvocationTimedOut) -> Self {
+        Self {
+            timeout_millis: value.timeout_millis,
+        }
+    }
+}
+
+impl From<GolemErrorInvocationTimedOut>
+    for golem_api_grpc::proto::golem::worker::v1::InvocationTimedOut
+{
+    fn from(value: GolemErrorInvocationTimedOut) -> Self {
+        Self {
+            timeout_millis: value.timeout_millis,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
 pub struct InvokeParameters {
     pub params: Vec<TypeAnnotatedValue>,
+    /// Parameters too large to be sent in the request, uploaded to the blob storage beforehand.
+    /// They are inserted among `params` at their index.
+    #[serde(default)]
+    #[oai(default)]
+    pub blob_params: Vec<BlobParameter>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct InvokeWaveParameters {
+    /// The parameters in WAVE (WebAssembly Value Encoding) text form, such as `"apple"` or `[1, 2]`
+    pub params: Vec<String>,
+}
+
+/// Reference to an invocation parameter uploaded to the blob storage by the caller. The worker
+/// executor reads it directly, so its size is not limited by the size of the gRPC messages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct BlobParameter {
+    /// Position of the parameter among all the parameters of the function
+    pub index: u32,
+    /// Key of the payload within the account's invocation parameter storage
+    pub key: String,
+    /// Size of the payload in bytes
+    pub size: u64,
+    pub encoding: BlobParameterEncoding,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct UploadInvocationParameterResponse {
+    /// Key to pass the uploaded parameter with in a `BlobParameter`
+    pub key: String,
+    /// Size of the uploaded parameter in bytes
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum BlobParameterEncoding {
+    /// The raw bytes of a `list<u8>` parameter
+    Bytes,
+    /// The UTF-8 encoded value of a `string` parameter
+    String,
+}
+
+impl From<BlobParameter> for golem_api_grpc::proto::golem::worker::BlobParameter {
+    fn from(value: BlobParameter) -> Self {
+        let encoding: golem_api_grpc::proto::golem::worker::BlobParameterEncoding =
+            value.encoding.into();
+        Self {
+            index: value.index,
+            key: value.key,
+            size: value.size,
+            encoding: encoding as i32,
+        }
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::worker::BlobParameter> for BlobParameter {
+    fn from(value: golem_api_grpc::proto::golem::worker::BlobParameter) -> Self {
+        Self {
+            encoding: value.encoding().into(),
+            index: value.index,
+            key: value.key,
+            size: value.size,
+        }
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::worker::BlobParameterEncoding> for BlobParameterEncoding {
+    fn from(value: golem_api_grpc::proto::golem::worker::BlobParameterEncoding) -> Self {
+        match value {
+            golem_api_grpc::proto::golem::worker::BlobParameterEncoding::Bytes => {
+                BlobParameterEncoding::Bytes
+            }
+            golem_api_grpc::proto::golem::worker::BlobParameterEncoding::String => {
+                BlobParameterEncoding::String
+            }
+        }
+    }
+}
+
+impl From<BlobParameterEncoding> for golem_api_grpc::proto::golem::worker::BlobParameterEncoding {
+    fn from(value: BlobParameterEncoding) -> Self {
+        match value {
+            BlobParameterEncoding::Bytes => {
+                golem_api_grpc::proto::golem::worker::BlobParameterEncoding::Bytes
+            }
+            BlobParameterEncoding::String => {
+                golem_api_grpc::proto::golem::worker::BlobParameterEncoding::String
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+pub struct ValidateInvocationResponse {
+    /// Whether invoking the function with the given parameters would pass validation
+    pub valid: bool,
+    /// Errors not related to a single parameter, such as an unknown function or a wrong number of parameters
+    pub errors: Vec<String>,
+    pub parameters: Vec<ParameterValidationResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+pub struct ParameterValidationResult {
+    pub name: String,
+    /// The parameter converted to the type expected by the function, if it type checks
+    pub value: Option<TypeAnnotatedValue>,
+    pub errors: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
-pub struct DeleteWorkerResponse {}
+pub struct DeleteWorkerResponse {
+    /// The resources deleted together with the worker, or the ones which would be deleted in a dry run
+    pub removed: Vec<String>,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
 pub struct InvokeResponse {}
@@ -979,6 +1208,47 @@ pub struct InvokeResponse {}
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
 pub struct InterruptResponse {}
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct AbortRunningInvocationResponse {
+    /// False if the worker was not executing the invocation, in which case it was left untouched
+    pub aborted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct CancelInvocationResponse {
+    /// False if the invocation is already running or has completed, in which case it was left untouched
+    pub canceled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct HoldInvocationResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct ReleaseInvocationResponse {}
+
+/// Parameters of an invocation to be performed later, at `scheduledAt` or after `delayMillis`
+/// counted from now
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ScheduleInvocationParameters {
+    pub params: Vec<TypeAnnotatedValue>,
+    /// RFC3339 timestamp of the invocation
+    pub scheduled_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub delay_millis: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ScheduleInvocationResponse {
+    /// Idempotency key of the scheduled invocation, generated if it was not given
+    pub idempotency_key: IdempotencyKey,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct SetWorkerProfilingResponse {}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
 pub struct ResumeResponse {}
 
@@ -991,6 +1261,16 @@ pub struct GetOplogResponse {
     pub next: Option<OplogCursor>,
     pub first_index_in_chunk: u64,
     pub last_index: u64,
+    /// The oplog index of each entry, as the entries are not contiguous when a query is given
+    pub indices: Vec<u64>,
+}
+
+/// Oplog entries of a worker as they are stored, for debugging
+#[derive(Debug, Clone, Serialize, Deserialize, Object)]
+pub struct GetRawOplogResponse {
+    pub entries: Vec<Value>,
+    pub first_index_in_chunk: u64,
+    pub last_index: u64,
 }
 
 
@@ -1030,12 +1310,305 @@ pub struct UpdateWorkerRequest {
     pub target_version: ComponentVersion,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct UpdateWorkerEnvRequest {
+    /// Replaces all the environment variables of the worker
+    pub env: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct UpdateWorkerEnvResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct RedactOplogRequest {
+    /// Indices of the oplog entries whose recorded data gets replaced by placeholder values
+    pub oplog_indices: Vec<OplogIndex>,
+    /// Recorded in the oplog together with the redacted indices, for auditing
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct RedactOplogResponse {
+    /// Index of the oplog entry recording the redaction
+    pub oplog_index: OplogIndex,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct EnableWorkerShadowRequest {
+    /// The candidate component version the shadow worker runs
+    pub component_version: ComponentVersion,
+    /// Compare the results of the shadow invocations with the original ones and log the differences
+    #[serde(default)]
+    pub compare_results: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct EnableWorkerShadowResponse {
+    pub shadow_worker_id: WorkerId,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct DisableWorkerShadowResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct SetDeduplicationWindowRequest {
+    /// How long the completed idempotency keys of the component's workers are remembered.
+    /// They are never forgotten if not set.
+    pub window_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct SetDeduplicationWindowResponse {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct SetWorkerTtlRequest {
+    /// The worker and all its storage is deleted after this many seconds, counted from now.
+    /// The worker is kept until deleted explicitly if not set.
+    pub ttl_seconds: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
+pub struct SetWorkerTtlResponse {}
+
+/// Workers of a component failing with similar errors
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ErrorGroup {
+    pub fingerprint: String,
+    pub kind: WorkerErrorKind,
+    /// The error of one of the workers in the group
+    pub example: String,
+    pub worker_count: u64,
+    /// Some of the workers in the group
+    pub workers: Vec<WorkerId>,
+}
+
+impl ErrorGroup {
+    pub const MAX_LISTED_WORKERS: usize = 10;
+
+    /// Merges the groups of different sets of workers which have the same fingerprint, the
+    /// groups with the most workers first
+    pub fn merge(groups: impl IntoIterator<Item = ErrorGroup>) -> Vec<ErrorGroup> {
+        let mut merged: HashMap<String, ErrorGroup> = HashMap::new();
+        for group in groups {
+            match merged.entry(group.fingerprint.clone()) {
+                Entry::Occupied(mut entry) => {
+                    let existing = entry.get_mut();
+                    existing.worker_count += group.worker_count;
+                    let free = Self::MAX_LISTED_WORKERS.saturating_sub(existing.workers.len());
+                    existing
+                        .workers
+                        .extend(group.workers.into_iter().take(free));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(group);
+                }
+            }
+        }
+
+        let mut groups = merged.into_values().collect::<Vec<_>>();
+        groups.sort_by(|a, b| {
+            b.worker_count
+                .cmp(&a.worker_count)
+                .then_with(|| a.fingerprint.cmp(&b.fingerprint))
+        });
+        groups
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::ErrorGroup> for ErrorGroup {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::ErrorGroup,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            fingerprint: value.fingerprint,
+            kind: value.kind.try_into()?,
+            example: value.example,
+            worker_count: value.worker_count,
+            workers: value
+                .workers
+                .into_iter()
+                .map(|worker_id| worker_id.try_into())
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ErrorGroupsResponse {
+    pub groups: Vec<ErrorGroup>,
+}
+
+/// Statistics of the live invocations of an exported function of a component, collected by all
+/// the executors since they started
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct FunctionStats {
+    pub function: String,
+    pub invocations: u64,
+    pub failures: u64,
+    /// The ratio of the failed invocations
+    pub failure_rate: f64,
+    pub consumed_fuel: u64,
+    pub average_latency_millis: f64,
+    /// The latency percentiles are the upper bounds of the histogram buckets they fall in
+    pub p50_latency_millis: f64,
+    pub p90_latency_millis: f64,
+    pub p99_latency_millis: f64,
+}
+
+impl FunctionStats {
+    /// Merges the statistics of the functions reported by the executors, the most invoked
+    /// functions first
+    pub fn aggregate(
+        stats: Vec<golem_api_grpc::proto::golem::worker::FunctionStats>,
+    ) -> Vec<FunctionStats> {
+        let mut merged: HashMap<String, golem_api_grpc::proto::golem::worker::FunctionStats> =
+            HashMap::new();
+        for stats in stats {
+            match merged.get_mut(&stats.function) {
+                Some(current) => {
+                    current.invocations += stats.invocations;
+                    current.failures += stats.failures;
+                    current.consumed_fuel += stats.consumed_fuel;
+                    current.total_duration_millis += stats.total_duration_millis;
+                    for (idx, count) in stats.latency_buckets.into_iter().enumerate() {
+                        match current.latency_buckets.get_mut(idx) {
+                            Some(current) => *current += count,
+                            None => current.latency_buckets.push(count),
+                        }
+                    }
+                }
+                None => {
+                    merged.insert(stats.function.clone(), stats);
+                }
+            }
+        }
+
+        let mut result = merged
+            .into_values()
+            .map(|stats| {
+                let ratio = |value: f64| {
+                    if stats.invocations == 0 {
+                        0.0
+                    } else {
+                        value / stats.invocations as f64
+                    }
+                };
+                FunctionStats {
+                    failure_rate: ratio(stats.failures as f64),
+                    average_latency_millis: ratio(stats.total_duration_millis as f64),
+                    p50_latency_millis: latency_percentile(&stats, 0.5),
+                    p90_latency_millis: latency_percentile(&stats, 0.9),
+                    p99_latency_millis: latency_percentile(&stats, 0.99),
+                    function: stats.function,
+                    invocations: stats.invocations,
+                    failures: stats.failures,
+                    consumed_fuel: stats.consumed_fuel,
+                }
+            })
+            .collect::<Vec<_>>();
+        result.sort_by(|a, b| {
+            b.invocations
+                .cmp(&a.invocations)
+                .then_with(|| a.function.cmp(&b.function))
+        });
+        result
+    }
+}
+
+// The last bucket has no upper bound, percentiles falling in it are reported as the last bound
+fn latency_percentile(
+    stats: &golem_api_grpc::proto::golem::worker::FunctionStats,
+    percentile: f64,
+) -> f64 {
+    let rank = (stats.invocations as f64 * percentile).ceil().max(1.0) as u64;
+    let bounds = &stats.latency_bucket_bounds_seconds;
+    let mut count = 0;
+    for (idx, bucket) in stats.latency_buckets.iter().enumerate() {
+        count += bucket;
+        if count >= rank {
+            return bounds.get(idx).or(bounds.last()).copied().unwrap_or(0.0) * 1000.0;
+        }
+    }
+    0.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+pub struct FunctionStatsResponse {
+    pub functions: Vec<FunctionStats>,
+}
+
+/// An event emitted by a worker through `golem:api/events`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct DomainEvent {
+    /// The position of the event in its topic
+    pub id: u64,
+    pub worker_id: WorkerId,
+    pub timestamp: Timestamp,
+    pub payload: Vec<u8>,
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::DomainEvent> for DomainEvent {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::DomainEvent,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.id,
+            worker_id: value.worker_id.ok_or("Missing worker_id")?.try_into()?,
+            timestamp: value.timestamp.ok_or("Missing timestamp")?.into(),
+            payload: value.payload,
+        })
+    }
+}
+
+impl From<DomainEvent> for golem_api_grpc::proto::golem::worker::DomainEvent {
+    fn from(value: DomainEvent) -> Self {
+        Self {
+            id: value.id,
+            worker_id: Some(value.worker_id.into()),
+            timestamp: Some(value.timestamp.into()),
+            payload: value.payload,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct PollEventsResponse {
+    pub events: Vec<DomainEvent>,
+    /// The cursor to continue polling from
+    pub next: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
 pub struct WorkersMetadataRequest {
     pub filter: Option<WorkerFilter>,
     pub cursor: Option<ScanCursor>,
     pub count: Option<u64>,
     pub precise: Option<bool>,
+    /// Returns only the selected parts of the worker metadata besides the worker id, status and
+    /// creation time, or all of it if missing
+    pub fields: Option<Vec<WorkerMetadataField>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
@@ -1044,6 +1617,49 @@ pub struct WorkersMetadataResponse {
     pub cursor: Option<ScanCursor>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct WorkersMetadataManyRequest {
+    pub worker_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct WorkersMetadataManyResponse {
+    /// The metadata of the requested workers which exist
+    pub workers: Vec<WorkerMetadata>,
+    /// The requested workers whose metadata could not be looked up
+    pub errors: Vec<WorkerMetadataLookupError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct WorkerMetadataLookupError {
+    pub worker_id: WorkerId,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct WorkersSearchRequest {
+    /// Finds the workers whose name starts with this prefix
+    pub prefix: Option<String>,
+    /// Finds the workers whose name contains the characters of this pattern in the same order, ignoring case
+    pub pattern: Option<String>,
+    pub count: Option<u64>,
+}
+
+impl WorkersSearchRequest {
+    pub fn query(&self) -> Result<WorkerNameQuery, String> {
+        match (&self.prefix, &self.pattern) {
+            (Some(prefix), None) => Ok(WorkerNameQuery::Prefix(prefix.clone())),
+            (None, Some(pattern)) => Ok(WorkerNameQuery::Pattern(pattern.clone())),
+            _ => Err("Exactly one of prefix and pattern must be specified".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct WorkersSearchResponse {
+    pub workers: Vec<WorkerId>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
 #[serde(rename_all = "camelCase")]
 #[oai(rename_all = "camelCase")]
@@ -1058,9 +1674,17 @@ pub struct WorkerMetadata {
     pub updates: Vec<UpdateRecord>,
     pub created_at: Timestamp,
     pub last_error: Option<String>,
+    pub last_error_kind: Option<WorkerErrorKind>,
+    /// Equal for similar errors of different workers
+    pub last_error_fingerprint: Option<String>,
     pub component_size: u64,
     pub total_linear_memory_size: u64,
     pub owned_resources: HashMap<u64, ResourceMetadata>,
+    /// The point in time after which the worker gets deleted, if it has a TTL
+    pub expires_at: Option<Timestamp>,
+    pub remaining_ttl_seconds: Option<u64>,
+    /// Total size of the files in the worker's file system in bytes
+    pub disk_usage: u64,
 }
 
 impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerMetadata> for WorkerMetadata {
@@ -1069,6 +1693,8 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerMetadata> for WorkerMet
     fn try_from(
         value: golem_api_grpc::proto::golem::worker::WorkerMetadata,
     ) -> Result<Self, Self::Error> {
+        let expires_at: Option<Timestamp> = value.expires_at.map(|t| t.into());
+        let now = Timestamp::now_utc().to_millis();
         Ok(Self {
             worker_id: value.worker_id.ok_or("Missing worker_id")?.try_into()?,
             args: value.args,
@@ -1084,6 +1710,11 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerMetadata> for WorkerMet
                 .collect::<Result<Vec<UpdateRecord>, String>>()?,
             created_at: value.created_at.ok_or("Missing created_at")?.into(),
             last_error: value.last_error,
+            last_error_kind: value
+                .last_error_kind
+                .map(WorkerErrorKind::try_from)
+                .transpose()?,
+            last_error_fingerprint: value.last_error_fingerprint,
             component_size: value.component_size,
             total_linear_memory_size: value.total_linear_memory_size,
             owned_resources: value
@@ -1091,6 +1722,10 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::WorkerMetadata> for WorkerMet
                 .into_iter()
                 .map(|(k, v)| v.try_into().map(|v| (k, v)))
                 .collect::<Result<HashMap<_, _>, _>>()?,
+            expires_at,
+            remaining_ttl_seconds: expires_at
+                .map(|expires_at| expires_at.to_millis().saturating_sub(now) / 1000),
+            disk_usage: value.disk_usage,
         })
     }
 }
@@ -1111,6 +1746,10 @@ impl From<WorkerMetadata> for golem_api_grpc::proto::golem::worker::WorkerMetada
             updates: value.updates.iter().cloned().map(|u| u.into()).collect(),
             created_at: Some(value.created_at.into()),
             last_error: value.last_error,
+            last_error_kind: value.last_error_kind.map(|kind| {
+                Into::<golem_api_grpc::proto::golem::worker::WorkerErrorKind>::into(kind).into()
+            }),
+            last_error_fingerprint: value.last_error_fingerprint,
             component_size: value.component_size,
             total_linear_memory_size: value.total_linear_memory_size,
             owned_resources: value
@@ -1118,6 +1757,8 @@ impl From<WorkerMetadata> for golem_api_grpc::proto::golem::worker::WorkerMetada
                 .into_iter()
                 .map(|(k, v)| (k, v.into()))
                 .collect(),
+            expires_at: value.expires_at.map(|t| t.into()),
+            disk_usage: value.disk_usage,
         }
     }
 }
@@ -1154,6 +1795,8 @@ pub struct FailedUpdate {
     timestamp: Timestamp,
     target_version: ComponentVersion,
     details: Option<String>,
+    /// Where the replay diverged, if the automatic update failed because of an incompatible change
+    divergence: Option<UpdateDivergence>,
 }
 
 impl TryFrom<golem_api_grpc::proto::golem::worker::UpdateRecord> for UpdateRecord {
@@ -1164,10 +1807,18 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::UpdateRecord> for UpdateRecor
     ) -> Result<Self, Self::Error> {
         match value.update.ok_or("Missing update field")? {
             golem_api_grpc::proto::golem::worker::update_record::Update::Failed(failed) => {
+                let timestamp: Timestamp = value.timestamp.ok_or("Missing timestamp")?.into();
                 Ok(Self::FailedUpdate(FailedUpdate {
-                    timestamp: value.timestamp.ok_or("Missing timestamp")?.into(),
+                    timestamp,
                     target_version: value.target_version,
-                    details: { failed.details },
+                    details: failed.details,
+                    divergence: failed.divergence.map(|divergence| UpdateDivergence {
+                        timestamp,
+                        target_version: value.target_version,
+                        oplog_index: OplogIndex::from_u64(divergence.oplog_index),
+                        expected: divergence.expected,
+                        got: divergence.got,
+                    }),
                 }))
             }
             golem_api_grpc::proto::golem::worker::update_record::Update::Pending(_) => {
@@ -1193,12 +1844,16 @@ impl From<UpdateRecord> for golem_api_grpc::proto::golem::worker::UpdateRecord {
                 timestamp,
                 target_version,
                 details,
+                divergence,
             }) => Self {
                 timestamp: Some(timestamp.into()),
                 target_version,
                 update: Some(
                     golem_api_grpc::proto::golem::worker::update_record::Update::Failed(
-                        golem_api_grpc::proto::golem::worker::FailedUpdate { details },
+                        golem_api_grpc::proto::golem::worker::FailedUpdate {
+                            details,
+                            divergence: divergence.map(|divergence| divergence.into()),
+                        },
                     ),
                 ),
             },
@@ -1341,6 +1996,10 @@ pub enum GolemError {
     InvalidAccount(GolemErrorInvalidAccount),
     #[error(transparent)]
     ShardingNotReady(GolemErrorShardingNotReady),
+    #[error(transparent)]
+    ResultTooLarge(GolemErrorResultTooLarge),
+    #[error(transparent)]
+    InvocationTimedOut(GolemErrorInvocationTimedOut),
 }
 
 impl SafeDisplay for GolemError {
@@ -1369,6 +2028,8 @@ impl SafeDisplay for GolemError {
             GolemError::Unknown(inner) => inner.to_safe_string(),
             GolemError::InvalidAccount(inner) => inner.to_safe_string(),
             GolemError::ShardingNotReady(inner) => inner.to_safe_string(),
+            GolemError::ResultTooLarge(inner) => inner.to_safe_string(),
+            GolemError::InvocationTimedOut(inner) => inner.to_safe_string(),
         }
     }
 }
@@ -1453,6 +2114,12 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::v1::WorkerExecutionError> for
             Some(golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::ShardingNotReady(err)) => {
                 Ok(GolemError::ShardingNotReady(err.into()))
             }
+            Some(golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::ResultTooLarge(err)) => {
+                Ok(GolemError::ResultTooLarge(err.into()))
+            }
+            Some(golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::InvocationTimedOut(err)) => {
+                Ok(GolemError::InvocationTimedOut(err.into()))
+            }
             None => Err("Missing field: error".to_string()),
         }
     }
@@ -1538,6 +2205,12 @@ impl From<GolemError> for golem_api_grpc::proto::golem::worker::v1::worker_execu
             GolemError::ShardingNotReady(err) => {
                 golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::ShardingNotReady(err.into())
             }
+            GolemError::ResultTooLarge(err) => {
+                golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::ResultTooLarge(err.into())
+            }
+            GolemError::InvocationTimedOut(err) => {
+                golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::InvocationTimedOut(err.into())
+            }
         }
     }
 }
@@ -1570,6 +2243,27 @@ pub struct ErrorBody {
     pub error: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Enum)]
+pub enum ComponentQuota {
+    ComponentSize,
+    TotalComponentSize,
+}
+
+#[derive(Debug, Clone, Object, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct QuotaExceededErrorBody {
+    pub error: String,
+    /// The exceeded quota
+    pub quota: ComponentQuota,
+    /// The limit of the quota in bytes
+    pub limit: u64,
+    /// Total size of the WASMs of the already stored component versions in bytes
+    pub used: u64,
+    /// Size of the rejected component in bytes
+    pub requested: u64,
+}
+
 impl From<golem_api_grpc::proto::golem::common::ErrorBody> for ErrorBody {
     fn from(value: golem_api_grpc::proto::golem::common::ErrorBody) -> Self {
         Self { error: value.error }
@@ -1665,6 +2359,47 @@ impl Component {
     }
 }
 
+/// A group of components, and of the workers of those components, owned by an account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct Project {
+    pub project_id: ProjectId,
+    pub owner_account_id: AccountId,
+    pub name: String,
+    pub description: String,
+    /// Maximum number of components in the project, unlimited if missing
+    pub max_components: Option<u32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ProjectData {
+    pub name: String,
+    #[oai(default)]
+    #[serde(default)]
+    pub description: String,
+    /// Maximum number of components in the project, unlimited if missing
+    pub max_components: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct AccountUsage {
+    pub account_id: AccountId,
+    /// Number of the stored component versions
+    pub component_versions: u64,
+    /// Total size of the WASMs of the stored component versions in bytes
+    pub total_component_size: u64,
+    /// Maximum size of the WASM of a single component version in bytes, unlimited if missing
+    pub max_component_size: Option<u64>,
+    /// Maximum total size of the WASMs of the stored component versions in bytes, unlimited if missing
+    pub max_total_component_size: Option<u64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize, Object)]
 #[serde(rename_all = "camelCase")]
 #[oai(rename_all = "camelCase")]
@@ -1770,3 +2505,135 @@ pub enum NodeType {
     Directory,
     File,
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::oplog::WorkerErrorKind;
+    use golem_common::model::{ComponentId, WorkerId};
+
+    use crate::model::{
+        is_shadow_worker_name, latency_percentile, shadow_worker_name, validate_worker_name,
+        ErrorGroup, FunctionStats,
+    };
+
+    fn function_stats(
+        function: &str,
+        invocations: u64,
+        failures: u64,
+        latency_buckets: Vec<u64>,
+    ) -> golem_api_grpc::proto::golem::worker::FunctionStats {
+        golem_api_grpc::proto::golem::worker::FunctionStats {
+            function: function.to_string(),
+            invocations,
+            failures,
+            consumed_fuel: invocations * 10,
+            total_duration_millis: invocations * 20,
+            latency_buckets,
+            latency_bucket_bounds_seconds: vec![0.01, 0.1, 1.0],
+        }
+    }
+
+    fn error_group(fingerprint: &str, workers: &[&str]) -> ErrorGroup {
+        let component_id = ComponentId::new_v4();
+        ErrorGroup {
+            fingerprint: fingerprint.to_string(),
+            kind: WorkerErrorKind::HostError,
+            example: format!("error {fingerprint}"),
+            worker_count: workers.len() as u64,
+            workers: workers
+                .iter()
+                .map(|name| WorkerId {
+                    component_id: component_id.clone(),
+                    worker_name: name.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn shadow_worker_names_do_not_collide_with_worker_names() {
+        let shadow = shadow_worker_name("worker-1");
+
+        assert!(is_shadow_worker_name(&shadow));
+        assert!(validate_worker_name(&shadow).is_ok());
+        assert!(!is_shadow_worker_name("worker-1-shadow"));
+        assert!(!is_shadow_worker_name("worker-1"));
+        assert!(validate_worker_name("worker.1").is_err());
+    }
+
+    #[test]
+    fn shadow_workers_have_no_shadows() {
+        let shadow_of_shadow = shadow_worker_name(&shadow_worker_name("worker-1"));
+
+        assert!(!is_shadow_worker_name(&shadow_of_shadow));
+        assert!(validate_worker_name(&shadow_of_shadow).is_err());
+    }
+
+    #[test]
+    fn error_groups_of_pages_are_merged() {
+        let many: Vec<String> = (0..ErrorGroup::MAX_LISTED_WORKERS)
+            .map(|i| format!("worker-{i}"))
+            .collect();
+        let many: Vec<&str> = many.iter().map(|name| name.as_str()).collect();
+
+        let groups = ErrorGroup::merge(vec![
+            error_group("a", &["worker-a"]),
+            error_group("b", &many),
+            error_group("a", &["worker-c", "worker-d"]),
+            error_group("b", &["worker-e"]),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].fingerprint, "b");
+        assert_eq!(
+            groups[0].worker_count,
+            ErrorGroup::MAX_LISTED_WORKERS as u64 + 1
+        );
+        assert_eq!(groups[0].workers.len(), ErrorGroup::MAX_LISTED_WORKERS);
+        assert_eq!(groups[1].fingerprint, "a");
+        assert_eq!(groups[1].worker_count, 3);
+        assert_eq!(groups[1].example, "error a");
+        assert_eq!(groups[1].workers.len(), 3);
+    }
+
+    #[test]
+    fn function_stats_of_executors_are_aggregated() {
+        let stats = FunctionStats::aggregate(vec![
+            function_stats("a", 2, 1, vec![1, 1, 0, 0]),
+            function_stats("b", 4, 0, vec![0, 4, 0]),
+            function_stats("a", 6, 0, vec![0, 0, 5, 0, 1]),
+            function_stats("c", 0, 0, vec![]),
+        ]);
+
+        assert_eq!(
+            stats
+                .iter()
+                .map(|stats| stats.function.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(stats[0].invocations, 8);
+        assert_eq!(stats[0].failures, 1);
+        assert_eq!(stats[0].failure_rate, 0.125);
+        assert_eq!(stats[0].consumed_fuel, 80);
+        assert_eq!(stats[0].average_latency_millis, 20.0);
+        assert_eq!(stats[0].p50_latency_millis, 1000.0);
+        assert_eq!(stats[1].p99_latency_millis, 100.0);
+        assert_eq!(stats[2].failure_rate, 0.0);
+        assert_eq!(stats[2].average_latency_millis, 0.0);
+        assert_eq!(stats[2].p50_latency_millis, 0.0);
+    }
+
+    #[test]
+    fn latency_percentiles_are_the_bounds_of_their_buckets() {
+        let stats = function_stats("f", 100, 0, vec![50, 40, 9, 1]);
+
+        assert_eq!(latency_percentile(&stats, 0.5), 10.0);
+        assert_eq!(latency_percentile(&stats, 0.9), 100.0);
+        assert_eq!(latency_percentile(&stats, 0.99), 1000.0);
+        // The last bucket has no bound, so the last bound is reported
+        assert_eq!(latency_percentile(&stats, 1.0), 1000.0);
+    }
+}
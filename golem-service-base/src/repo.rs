@@ -20,6 +20,8 @@ use std::fmt::Display;
 pub enum RepoError {
     Internal(String),
     UniqueViolation(String),
+    /// A limit stored in the database, like the maximum number of components of a project
+    LimitExceeded(String),
 }
 
 impl From<sqlx::Error> for RepoError {
@@ -41,6 +43,7 @@ impl Display for RepoError {
         match self {
             RepoError::UniqueViolation(error) => write!(f, "{}", error),
             RepoError::Internal(error) => write!(f, "{}", error),
+            RepoError::LimitExceeded(error) => write!(f, "{}", error),
         }
     }
 }
@@ -52,6 +55,7 @@ impl SafeDisplay for RepoError {
             RepoError::UniqueViolation(_) => {
                 "Internal repository error (unique key violation)".to_string()
             }
+            RepoError::LimitExceeded(error) => error.clone(),
         }
     }
 }
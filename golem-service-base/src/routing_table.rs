@@ -12,19 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::ops::Deref;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use async_trait::async_trait;
+use rand::Rng;
 use serde::Deserialize;
 use serde::Serialize;
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 use tokio::time::Instant;
-use tonic::codec::CompressionEncoding;
 use tonic::transport::Channel;
-use tonic::Status;
+use tonic::{Code, Status};
+use tracing::{debug, info, warn};
 
 use golem_api_grpc::proto::golem::shardmanager;
 use golem_api_grpc::proto::golem::shardmanager::v1::shard_manager_error::Error;
@@ -32,8 +35,14 @@ use golem_api_grpc::proto::golem::shardmanager::v1::shard_manager_service_client
 use golem_api_grpc::proto::golem::shardmanager::v1::ShardManagerError;
 use golem_common::cache::*;
 use golem_common::client::GrpcClient;
-use golem_common::model::RoutingTable;
+use golem_common::config::GrpcCompression;
+use golem_common::metrics::caching::{
+    record_cache_capacity, record_cache_eviction, record_cache_hit, record_cache_miss,
+    record_cache_size,
+};
+use golem_common::model::{ComponentId, Pod, RoutingTable, WorkerId};
 use golem_common::retriable_error::IsRetriableError;
+use golem_common::with_grpc_compression;
 
 #[derive(Debug, Clone)]
 pub enum RoutingTableError {
@@ -97,6 +106,15 @@ pub struct RoutingTableConfig {
     port: u16,
     #[serde(with = "humantime_serde")]
     invalidation_min_delay: Duration,
+    worker_assignment_cache_capacity: usize,
+    #[serde(with = "humantime_serde")]
+    ttl: Duration,
+    ttl_jitter: f64,
+    #[serde(with = "humantime_serde")]
+    refresh_ahead: Duration,
+    #[serde(with = "humantime_serde")]
+    negative_cache_ttl: Duration,
+    negative_cache_capacity: usize,
 }
 
 impl RoutingTableConfig {
@@ -105,6 +123,21 @@ impl RoutingTableConfig {
             .parse()
             .expect("Failed to parse shard manager URL")
     }
+
+    /// Time until the cached routing table gets refreshed: the TTL randomized by the jitter
+    /// ratio, so instances don't refresh in lockstep, minus the refresh-ahead window.
+    fn refresh_delay(&self) -> Duration {
+        let jitter = self.ttl_jitter.clamp(0.0, 1.0);
+        let factor = if jitter > 0.0 {
+            rand::thread_rng().gen_range((1.0 - jitter)..=(1.0 + jitter))
+        } else {
+            1.0
+        };
+        self.ttl
+            .mul_f64(factor)
+            .saturating_sub(self.refresh_ahead)
+            .max(MIN_REFRESH_DELAY)
+    }
 }
 
 impl Default for RoutingTableConfig {
@@ -113,6 +146,12 @@ impl Default for RoutingTableConfig {
             host: "localhost".to_string(),
             port: 9002,
             invalidation_min_delay: Duration::from_millis(500),
+            worker_assignment_cache_capacity: 65536,
+            ttl: Duration::from_secs(300),
+            ttl_jitter: 0.1,
+            refresh_ahead: Duration::from_secs(10),
+            negative_cache_ttl: Duration::from_secs(5),
+            negative_cache_capacity: 16384,
         }
     }
 }
@@ -120,42 +159,196 @@ impl Default for RoutingTableConfig {
 #[async_trait]
 pub trait RoutingTableService {
     async fn get_routing_table(&self) -> Result<RoutingTable, RoutingTableError>;
+    // Resolves the pod a worker is assigned to, possibly without consulting the routing table
+    async fn lookup_worker(&self, worker_id: &WorkerId) -> Result<Option<Pod>, RoutingTableError>;
     // Returns false in case of skipped (throttled) invalidation
     async fn try_invalidate_routing_table(&self) -> bool;
+    // Asks the shard manager to keep the worker's shard away from the shards of the other
+    // workers with the same anti-affinity key
+    async fn add_anti_affinity(
+        &self,
+        key: &str,
+        worker_id: &WorkerId,
+    ) -> Result<(), RoutingTableError>;
+    // Takes a deleted worker out of its anti-affinity group, if it was in one
+    async fn remove_anti_affinity(&self, worker_id: &WorkerId) -> Result<(), RoutingTableError>;
+    // Remembers that a worker or component was reported as missing, for a limited time
+    fn mark_missing(&self, entity: MissingEntity);
+    fn is_missing(&self, entity: &MissingEntity) -> bool;
+    // Forgets a missing mark, for example when the worker gets (re)created
+    fn clear_missing(&self, entity: &MissingEntity);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MissingEntity {
+    Worker(WorkerId),
+    Component(ComponentId),
 }
 
 pub trait HasRoutingTableService {
     fn routing_table_service(&self) -> &Arc<dyn RoutingTableService + Send + Sync>;
 }
 
+const WORKER_ASSIGNMENT_CACHE_NAME: &str = "worker_assignment";
+const MISSING_ENTITIES_CACHE_NAME: &str = "missing_entities";
+const EPOCH_WATCH_RETRY_DELAY: Duration = Duration::from_secs(1);
+const MIN_REFRESH_DELAY: Duration = Duration::from_secs(1);
+
 pub struct RoutingTableServiceDefault {
     config: RoutingTableConfig,
     cache: Cache<(), (), RoutingTable, RoutingTableError>,
+    assignments: Arc<WorkerAssignmentCache>,
+    missing: NegativeCache,
     last_invalidated_at: RwLock<Option<Instant>>,
     client: GrpcClient<ShardManagerServiceClient<Channel>>,
+    epoch_watcher: JoinHandle<()>,
+    refresher: JoinHandle<()>,
 }
 
 impl RoutingTableServiceDefault {
-    pub fn new(config: RoutingTableConfig) -> Self {
+    pub fn new(config: RoutingTableConfig, grpc_compression: GrpcCompression) -> Self {
         let client = GrpcClient::new(
-            |channel| {
-                ShardManagerServiceClient::new(channel)
-                    .send_compressed(CompressionEncoding::Gzip)
-                    .accept_compressed(CompressionEncoding::Gzip)
+            move |channel| {
+                with_grpc_compression!(ShardManagerServiceClient::new(channel), grpc_compression)
             },
             config.url(),
             Default::default(), // TODO
         );
+        let cache = Cache::new(
+            Some(1),
+            FullCacheEvictionMode::LeastRecentlyUsed(1),
+            BackgroundEvictionMode::None,
+            "routing_table",
+        );
+        let assignments = Arc::new(WorkerAssignmentCache::new(
+            config.worker_assignment_cache_capacity,
+        ));
+        let epoch_watcher = tokio::spawn(Self::watch_epoch(
+            client.clone(),
+            cache.clone(),
+            assignments.clone(),
+        ));
+        let refresher = tokio::spawn(Self::refresh_ahead(
+            config.clone(),
+            client.clone(),
+            cache.clone(),
+            assignments.clone(),
+        ));
+        let missing = NegativeCache::new(config.negative_cache_ttl, config.negative_cache_capacity);
         Self {
             config,
-            cache: Cache::new(
-                Some(1),
-                FullCacheEvictionMode::LeastRecentlyUsed(1),
-                BackgroundEvictionMode::None,
-                "routing_table",
-            ),
+            cache,
+            assignments,
+            missing,
             last_invalidated_at: RwLock::new(None),
             client,
+            epoch_watcher,
+            refresher,
+        }
+    }
+
+    /// Follows the routing table epoch published by the shard manager, dropping the cached
+    /// routing table and worker assignments whenever it changes.
+    async fn watch_epoch(
+        client: GrpcClient<ShardManagerServiceClient<Channel>>,
+        cache: Cache<(), (), RoutingTable, RoutingTableError>,
+        assignments: Arc<WorkerAssignmentCache>,
+    ) {
+        loop {
+            let response = client
+                .call(|client| {
+                    Box::pin(client.watch_routing_table_epoch(
+                        shardmanager::v1::WatchRoutingTableEpochRequest {},
+                    ))
+                })
+                .await;
+            match response {
+                Ok(response) => {
+                    let mut stream = response.into_inner();
+                    loop {
+                        match stream.message().await {
+                            Ok(Some(shardmanager::v1::RoutingTableEpoch { epoch })) => {
+                                if assignments.set_epoch(epoch) {
+                                    debug!(epoch, "Routing table epoch changed");
+                                    cache.remove(&());
+                                }
+                            }
+                            Ok(None) => break,
+                            Err(status) => {
+                                warn!("Routing table epoch stream failed: {}", status);
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(status) if status.code() == Code::Unimplemented => {
+                    info!("Shard Manager does not support routing table epoch notifications");
+                    break;
+                }
+                Err(status) => {
+                    warn!("Failed to watch routing table epoch: {}", status);
+                }
+            }
+            tokio::time::sleep(EPOCH_WATCH_RETRY_DELAY).await;
+        }
+    }
+
+    /// Replaces the cached routing table before its TTL runs out, so lookups are not stalled
+    /// by refetching it. If the refresh fails, the cached table is dropped once it expires.
+    async fn refresh_ahead(
+        config: RoutingTableConfig,
+        client: GrpcClient<ShardManagerServiceClient<Channel>>,
+        cache: Cache<(), (), RoutingTable, RoutingTableError>,
+        assignments: Arc<WorkerAssignmentCache>,
+    ) {
+        loop {
+            tokio::time::sleep(config.refresh_delay()).await;
+            match fetch_routing_table(client.clone()).await {
+                Ok(routing_table) => {
+                    if assignments.set_epoch(routing_table.epoch) {
+                        debug!(epoch = routing_table.epoch, "Routing table epoch changed");
+                    }
+                    cache.remove(&());
+                    let _ = cache
+                        .get_or_insert_simple(&(), || Box::pin(async move { Ok(routing_table) }))
+                        .await;
+                }
+                Err(error) => {
+                    warn!("Failed to refresh routing table: {}", error);
+                    tokio::time::sleep(config.refresh_ahead).await;
+                    cache.remove(&());
+                }
+            }
+        }
+    }
+}
+
+impl Drop for RoutingTableServiceDefault {
+    fn drop(&mut self) {
+        self.epoch_watcher.abort();
+        self.refresher.abort();
+    }
+}
+
+async fn fetch_routing_table(
+    client: GrpcClient<ShardManagerServiceClient<Channel>>,
+) -> Result<RoutingTable, RoutingTableError> {
+    let response = client
+        .call(|client| {
+            Box::pin(client.get_routing_table(shardmanager::v1::GetRoutingTableRequest {}))
+        })
+        .await
+        .map_err(RoutingTableError::ShardManagerGrpcError)?;
+    match response.into_inner() {
+        shardmanager::v1::GetRoutingTableResponse {
+            result:
+                Some(shardmanager::v1::get_routing_table_response::Result::Success(routing_table)),
+        } => Ok(routing_table.into()),
+        shardmanager::v1::GetRoutingTableResponse {
+            result: Some(shardmanager::v1::get_routing_table_response::Result::Failure(failure)),
+        } => Err(RoutingTableError::ShardManagerError(failure)),
+        shardmanager::v1::GetRoutingTableResponse { result: None } => {
+            Err(RoutingTableError::NoResult)
         }
     }
 }
@@ -165,39 +358,29 @@ impl RoutingTableService for RoutingTableServiceDefault {
     async fn get_routing_table(&self) -> Result<RoutingTable, RoutingTableError> {
         let client = self.client.clone();
         self.cache
-            .get_or_insert_simple(&(), || {
-                Box::pin(async move {
-                    let response = client
-                        .call(|client| {
-                            Box::pin(
-                                client
-                                    .get_routing_table(shardmanager::v1::GetRoutingTableRequest {}),
-                            )
-                        })
-                        .await
-                        .map_err(RoutingTableError::ShardManagerGrpcError)?;
-                    match response.into_inner() {
-                        shardmanager::v1::GetRoutingTableResponse {
-                            result:
-                                Some(shardmanager::v1::get_routing_table_response::Result::Success(
-                                    routing_table,
-                                )),
-                        } => Ok(routing_table.into()),
-                        shardmanager::v1::GetRoutingTableResponse {
-                            result:
-                                Some(shardmanager::v1::get_routing_table_response::Result::Failure(
-                                    failure,
-                                )),
-                        } => Err(RoutingTableError::ShardManagerError(failure)),
-                        shardmanager::v1::GetRoutingTableResponse { result: None } => {
-                            Err(RoutingTableError::NoResult)
-                        }
-                    }
-                })
-            })
+            .get_or_insert_simple(&(), || Box::pin(fetch_routing_table(client)))
             .await
     }
 
+    async fn lookup_worker(&self, worker_id: &WorkerId) -> Result<Option<Pod>, RoutingTableError> {
+        if let Some(pod) = self.assignments.get(worker_id) {
+            return Ok(Some(pod));
+        }
+
+        let routing_table = self.get_routing_table().await?;
+        let pod = routing_table.lookup(worker_id).cloned();
+        if let Some(pod) = &pod {
+            if !self
+                .assignments
+                .insert(routing_table.epoch, worker_id.clone(), pod.clone())
+            {
+                // The epoch changed while the routing table was fetched
+                self.cache.remove(&());
+            }
+        }
+        Ok(pod)
+    }
+
     async fn try_invalidate_routing_table(&self) -> bool {
         let now = Instant::now();
 
@@ -218,9 +401,209 @@ impl RoutingTableService for RoutingTableServiceDefault {
             return false;
         }
         self.cache.remove(&());
+        self.assignments.clear();
         *last_invalidated_at = Some(Instant::now());
         true
     }
+
+    async fn add_anti_affinity(
+        &self,
+        key: &str,
+        worker_id: &WorkerId,
+    ) -> Result<(), RoutingTableError> {
+        let request = shardmanager::v1::AddAntiAffinityRequest {
+            key: key.to_string(),
+            worker_id: Some(worker_id.clone().into()),
+        };
+        let response = self
+            .client
+            .call(|client| Box::pin(client.add_anti_affinity(request.clone())))
+            .await
+            .map_err(RoutingTableError::ShardManagerGrpcError)?;
+        match response.into_inner() {
+            shardmanager::v1::AddAntiAffinityResponse {
+                result: Some(shardmanager::v1::add_anti_affinity_response::Result::Success(_)),
+            } => Ok(()),
+            shardmanager::v1::AddAntiAffinityResponse {
+                result: Some(shardmanager::v1::add_anti_affinity_response::Result::Failure(failure)),
+            } => Err(RoutingTableError::ShardManagerError(failure)),
+            shardmanager::v1::AddAntiAffinityResponse { result: None } => {
+                Err(RoutingTableError::NoResult)
+            }
+        }
+    }
+
+    async fn remove_anti_affinity(&self, worker_id: &WorkerId) -> Result<(), RoutingTableError> {
+        let request = shardmanager::v1::RemoveAntiAffinityRequest {
+            worker_id: Some(worker_id.clone().into()),
+        };
+        let response = self
+            .client
+            .call(|client| Box::pin(client.remove_anti_affinity(request.clone())))
+            .await
+            .map_err(RoutingTableError::ShardManagerGrpcError)?;
+        match response.into_inner() {
+            shardmanager::v1::RemoveAntiAffinityResponse {
+                result: Some(shardmanager::v1::remove_anti_affinity_response::Result::Success(_)),
+            } => Ok(()),
+            shardmanager::v1::RemoveAntiAffinityResponse {
+                result:
+                    Some(shardmanager::v1::remove_anti_affinity_response::Result::Failure(failure)),
+            } => Err(RoutingTableError::ShardManagerError(failure)),
+            shardmanager::v1::RemoveAntiAffinityResponse { result: None } => {
+                Err(RoutingTableError::NoResult)
+            }
+        }
+    }
+
+    fn mark_missing(&self, entity: MissingEntity) {
+        self.missing.insert(entity);
+    }
+
+    fn is_missing(&self, entity: &MissingEntity) -> bool {
+        self.missing.contains(entity)
+    }
+
+    fn clear_missing(&self, entity: &MissingEntity) {
+        self.missing.remove(entity);
+    }
+}
+
+/// Worker to pod assignments resolved from the routing table, all belonging to the same epoch.
+struct WorkerAssignmentCache {
+    capacity: usize,
+    state: Mutex<WorkerAssignments>,
+}
+
+struct WorkerAssignments {
+    epoch: u64,
+    pods: HashMap<WorkerId, Pod>,
+}
+
+impl WorkerAssignmentCache {
+    fn new(capacity: usize) -> Self {
+        record_cache_capacity(WORKER_ASSIGNMENT_CACHE_NAME, capacity);
+        record_cache_size(WORKER_ASSIGNMENT_CACHE_NAME, 0);
+        Self {
+            capacity,
+            state: Mutex::new(WorkerAssignments {
+                epoch: 0,
+                pods: HashMap::new(),
+            }),
+        }
+    }
+
+    fn get(&self, worker_id: &WorkerId) -> Option<Pod> {
+        let pod = self.state.lock().unwrap().pods.get(worker_id).cloned();
+        if pod.is_some() {
+            record_cache_hit(WORKER_ASSIGNMENT_CACHE_NAME);
+        } else {
+            record_cache_miss(WORKER_ASSIGNMENT_CACHE_NAME);
+        }
+        pod
+    }
+
+    /// Caches an assignment resolved from a routing table of the given epoch. Returns false if
+    /// the assignment was resolved from an outdated routing table and was not cached.
+    fn insert(&self, epoch: u64, worker_id: WorkerId, pod: Pod) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if epoch < state.epoch {
+            return false;
+        }
+        if epoch > state.epoch {
+            state.epoch = epoch;
+            state.pods.clear();
+        }
+        if self.capacity == 0 {
+            return true;
+        }
+        if state.pods.len() >= self.capacity {
+            record_cache_eviction(WORKER_ASSIGNMENT_CACHE_NAME, "full");
+            state.pods.clear();
+        }
+        state.pods.insert(worker_id, pod);
+        record_cache_size(WORKER_ASSIGNMENT_CACHE_NAME, state.pods.len());
+        true
+    }
+
+    /// Switches to a new epoch, returns true if it was different from the current one.
+    fn set_epoch(&self, epoch: u64) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.epoch == epoch {
+            return false;
+        }
+        state.epoch = epoch;
+        state.pods.clear();
+        record_cache_size(WORKER_ASSIGNMENT_CACHE_NAME, 0);
+        true
+    }
+
+    fn clear(&self) {
+        self.state.lock().unwrap().pods.clear();
+        record_cache_size(WORKER_ASSIGNMENT_CACHE_NAME, 0);
+    }
+}
+
+/// Workers and components recently reported as missing, each remembered until its expiry.
+struct NegativeCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<MissingEntity, Instant>>,
+}
+
+impl NegativeCache {
+    fn new(ttl: Duration, capacity: usize) -> Self {
+        record_cache_capacity(MISSING_ENTITIES_CACHE_NAME, capacity);
+        record_cache_size(MISSING_ENTITIES_CACHE_NAME, 0);
+        Self {
+            ttl,
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn insert(&self, entity: MissingEntity) {
+        if self.capacity == 0 || self.ttl.is_zero() {
+            return;
+        }
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        if !entries.contains_key(&entity) && entries.len() >= self.capacity {
+            entries.retain(|_, expires_at| *expires_at > now);
+            if entries.len() >= self.capacity {
+                record_cache_eviction(MISSING_ENTITIES_CACHE_NAME, "full");
+                entries.clear();
+            }
+        }
+        entries.insert(entity, now + self.ttl);
+        record_cache_size(MISSING_ENTITIES_CACHE_NAME, entries.len());
+    }
+
+    fn contains(&self, entity: &MissingEntity) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let missing = match entries.get(entity) {
+            Some(expires_at) if *expires_at > Instant::now() => true,
+            Some(_) => {
+                entries.remove(entity);
+                record_cache_size(MISSING_ENTITIES_CACHE_NAME, entries.len());
+                false
+            }
+            None => false,
+        };
+        if missing {
+            record_cache_hit(MISSING_ENTITIES_CACHE_NAME);
+        } else {
+            record_cache_miss(MISSING_ENTITIES_CACHE_NAME);
+        }
+        missing
+    }
+
+    fn remove(&self, entity: &MissingEntity) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.remove(entity).is_some() {
+            record_cache_size(MISSING_ENTITIES_CACHE_NAME, entries.len());
+        }
+    }
 }
 
 pub struct RoutingTableServiceNoop {}
@@ -231,7 +614,117 @@ impl RoutingTableService for RoutingTableServiceNoop {
         Err(RoutingTableError::NoResult)
     }
 
+    async fn lookup_worker(&self, _worker_id: &WorkerId) -> Result<Option<Pod>, RoutingTableError> {
+        Err(RoutingTableError::NoResult)
+    }
+
     async fn try_invalidate_routing_table(&self) -> bool {
         return false;
     }
+
+    async fn add_anti_affinity(
+        &self,
+        _key: &str,
+        _worker_id: &WorkerId,
+    ) -> Result<(), RoutingTableError> {
+        Err(RoutingTableError::NoResult)
+    }
+
+    async fn remove_anti_affinity(&self, _worker_id: &WorkerId) -> Result<(), RoutingTableError> {
+        Ok(())
+    }
+
+    fn mark_missing(&self, _entity: MissingEntity) {}
+
+    fn is_missing(&self, _entity: &MissingEntity) -> bool {
+        false
+    }
+
+    fn clear_missing(&self, _entity: &MissingEntity) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use test_r::test;
+
+    use golem_api_grpc::proto::golem::shardmanager;
+    use golem_common::model::{ComponentId, Pod, WorkerId};
+
+    use super::{MissingEntity, NegativeCache, WorkerAssignmentCache};
+
+    fn worker_id(name: &str) -> WorkerId {
+        WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: name.to_string(),
+        }
+    }
+
+    fn pod(port: u32) -> Pod {
+        shardmanager::Pod {
+            host: "localhost".to_string(),
+            port,
+            pod_name: None,
+        }
+        .into()
+    }
+
+    #[test]
+    fn assignments_are_dropped_on_epoch_change() {
+        let cache = WorkerAssignmentCache::new(16);
+        let worker = worker_id("w1");
+
+        assert!(cache.insert(1, worker.clone(), pod(9000)));
+        assert_eq!(cache.get(&worker), Some(pod(9000)));
+
+        assert!(!cache.set_epoch(1));
+        assert_eq!(cache.get(&worker), Some(pod(9000)));
+
+        assert!(cache.set_epoch(2));
+        assert_eq!(cache.get(&worker), None);
+    }
+
+    #[test]
+    fn outdated_assignments_are_not_cached() {
+        let cache = WorkerAssignmentCache::new(16);
+        let worker = worker_id("w1");
+
+        assert!(cache.set_epoch(5));
+        assert!(!cache.insert(4, worker.clone(), pod(9000)));
+        assert_eq!(cache.get(&worker), None);
+
+        assert!(cache.insert(6, worker.clone(), pod(9001)));
+        assert_eq!(cache.get(&worker), Some(pod(9001)));
+    }
+
+    #[test]
+    fn missing_entities_expire() {
+        let cache = NegativeCache::new(Duration::from_millis(1), 16);
+        let worker = MissingEntity::Worker(worker_id("w1"));
+
+        cache.insert(worker.clone());
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(!cache.contains(&worker));
+    }
+
+    #[test]
+    fn missing_entities_are_cleared_and_bounded() {
+        let cache = NegativeCache::new(Duration::from_secs(60), 2);
+        let w1 = MissingEntity::Worker(worker_id("w1"));
+        let w2 = MissingEntity::Worker(worker_id("w2"));
+        let component = MissingEntity::Component(ComponentId::new_v4());
+
+        cache.insert(w1.clone());
+        cache.insert(w2.clone());
+        assert!(cache.contains(&w1));
+        assert!(cache.contains(&w2));
+
+        cache.remove(&w1);
+        assert!(!cache.contains(&w1));
+
+        cache.insert(w1.clone());
+        cache.insert(component.clone());
+        assert!(cache.contains(&component));
+        assert_eq!(cache.entries.lock().unwrap().len(), 1);
+    }
 }
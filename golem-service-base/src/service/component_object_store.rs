@@ -13,15 +13,16 @@
 // limitations under the License.
 
 use crate::config::{ComponentStoreLocalConfig, ComponentStoreS3Config};
-use crate::stream::ByteStream;
+use crate::service::s3_client::new_s3_client;
+use crate::stream::{ByteRange, ByteStream};
 use anyhow::Error;
 use async_trait::async_trait;
-use aws_config::BehaviorVersion;
 use futures::Stream;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::SystemTime;
 use tracing::{debug, info};
 
 #[async_trait]
@@ -30,9 +31,106 @@ pub trait ComponentObjectStore {
 
     async fn get_stream(&self, object_key: &str) -> ByteStream;
 
+    /// Streams an inclusive range of bytes of the object
+    async fn get_stream_range(&self, object_key: &str, range: ByteRange) -> ByteStream;
+
+    async fn get_size(&self, object_key: &str) -> Result<u64, anyhow::Error>;
+
     async fn put(&self, object_key: &str, data: Vec<u8>) -> Result<(), anyhow::Error>;
 
     async fn delete(&self, object_key: &str) -> Result<(), anyhow::Error>;
+
+    /// Lists every object stored under the configured prefix, with keys relative to the prefix
+    async fn list(&self) -> Result<Vec<ObjectStoreEntry>, anyhow::Error>;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectStoreEntry {
+    pub key: String,
+    pub size: u64,
+    pub last_modified: Option<SystemTime>,
+}
+
+pub(crate) async fn list_s3_objects(
+    client: &aws_sdk_s3::Client,
+    bucket_name: &str,
+    object_prefix: &str,
+) -> Result<Vec<ObjectStoreEntry>, anyhow::Error> {
+    let prefix = if object_prefix.is_empty() {
+        None
+    } else {
+        Some(format!("{object_prefix}/"))
+    };
+
+    info!("Listing objects: {}/{}", bucket_name, object_prefix);
+
+    let mut result = Vec::new();
+    let mut continuation_token = None;
+    loop {
+        let response = client
+            .list_objects_v2()
+            .bucket(bucket_name)
+            .set_prefix(prefix.clone())
+            .set_continuation_token(continuation_token)
+            .send()
+            .await?;
+
+        for object in response.contents() {
+            if let Some(key) = object.key() {
+                let key = match &prefix {
+                    Some(prefix) => key.strip_prefix(prefix.as_str()).unwrap_or(key),
+                    None => key,
+                };
+                result.push(ObjectStoreEntry {
+                    key: key.to_string(),
+                    size: object.size().unwrap_or_default().max(0) as u64,
+                    last_modified: object
+                        .last_modified()
+                        .and_then(|t| SystemTime::try_from(*t).ok()),
+                });
+            }
+        }
+
+        match response.next_continuation_token() {
+            Some(token) => continuation_token = Some(token.to_string()),
+            None => break,
+        }
+    }
+
+    Ok(result)
+}
+
+pub(crate) fn list_fs_objects(dir_path: &Path) -> Result<Vec<ObjectStoreEntry>, anyhow::Error> {
+    fn walk(root: &Path, dir: &Path, result: &mut Vec<ObjectStoreEntry>) -> std::io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                walk(root, &path, result)?;
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                let key = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                result.push(ObjectStoreEntry {
+                    key,
+                    size: metadata.len(),
+                    last_modified: metadata.modified().ok(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    debug!("Listing objects: {}", dir_path.display());
+
+    let mut result = Vec::new();
+    if dir_path.exists() {
+        walk(dir_path, dir_path, &mut result)?;
+    }
+    Ok(result)
 }
 
 pub struct AwsByteStream(aws_sdk_s3::primitives::ByteStream);
@@ -65,8 +163,7 @@ impl AwsS3ComponentObjectStore {
             "S3 Component Object Store bucket: {}, prefix: {}",
             config.bucket_name, config.object_prefix
         );
-        let sdk_config = aws_config::load_defaults(BehaviorVersion::v2024_03_28()).await;
-        let client = aws_sdk_s3::Client::new(&sdk_config);
+        let client = new_s3_client(&config.client).await;
         Self {
             client,
             bucket_name: config.bucket_name.clone(),
@@ -120,6 +217,44 @@ impl ComponentObjectStore for AwsS3ComponentObjectStore {
         }
     }
 
+    async fn get_stream_range(&self, object_key: &str, range: ByteRange) -> ByteStream {
+        let key = self.get_key(object_key);
+
+        info!(
+            "Getting object range: {}/{} {}-{}",
+            self.bucket_name, key, range.start, range.end
+        );
+
+        match self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .range(format!("bytes={}-{}", range.start, range.end))
+            .send()
+            .await
+        {
+            Ok(response) => response.body.into(),
+            Err(error) => ByteStream::error(error),
+        }
+    }
+
+    async fn get_size(&self, object_key: &str) -> Result<u64, anyhow::Error> {
+        let key = self.get_key(object_key);
+
+        info!("Getting object size: {}/{}", self.bucket_name, key);
+
+        let response = self
+            .client
+            .head_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .send()
+            .await?;
+
+        Ok(response.content_length().unwrap_or_default().max(0) as u64)
+    }
+
     async fn put(&self, object_key: &str, data: Vec<u8>) -> Result<(), anyhow::Error> {
         let key = self.get_key(object_key);
         info!("Putting object: {}/{}", self.bucket_name, key);
@@ -149,6 +284,10 @@ impl ComponentObjectStore for AwsS3ComponentObjectStore {
 
         Ok(())
     }
+
+    async fn list(&self) -> Result<Vec<ObjectStoreEntry>, anyhow::Error> {
+        list_s3_objects(&self.client, &self.bucket_name, &self.object_prefix).await
+    }
 }
 
 pub struct FsComponentObjectStore {
@@ -213,6 +352,43 @@ impl ComponentObjectStore for FsComponentObjectStore {
         }
     }
 
+    async fn get_stream_range(&self, object_key: &str, range: ByteRange) -> ByteStream {
+        let dir_path = self.get_dir_path();
+
+        debug!(
+            "Getting object range: {}/{} {}-{}",
+            dir_path.display(),
+            object_key,
+            range.start,
+            range.end
+        );
+
+        let file_path = dir_path.join(object_key);
+
+        match aws_sdk_s3::primitives::ByteStream::read_from()
+            .path(file_path)
+            .offset(range.start)
+            .length(aws_sdk_s3::primitives::Length::Exact(
+                range.end - range.start + 1,
+            ))
+            .build()
+            .await
+        {
+            Ok(stream) => stream.into(),
+            Err(error) => ByteStream::error(error),
+        }
+    }
+
+    async fn get_size(&self, object_key: &str) -> Result<u64, anyhow::Error> {
+        let dir_path = self.get_dir_path();
+
+        debug!("Getting object size: {}/{}", dir_path.display(), object_key);
+
+        let file_path = dir_path.join(object_key);
+
+        Ok(fs::metadata(file_path)?.len())
+    }
+
     async fn put(&self, object_key: &str, data: Vec<u8>) -> Result<(), anyhow::Error> {
         let dir_path = self.get_dir_path();
         info!("Putting object: {}/{}", dir_path.display(), object_key);
@@ -252,6 +428,10 @@ impl ComponentObjectStore for FsComponentObjectStore {
 
         Ok(())
     }
+
+    async fn list(&self) -> Result<Vec<ObjectStoreEntry>, anyhow::Error> {
+        list_fs_objects(&self.get_dir_path())
+    }
 }
 
 #[cfg(test)]
@@ -260,6 +440,7 @@ mod tests {
 
     use crate::config::ComponentStoreLocalConfig;
     use crate::service::component_object_store::{ComponentObjectStore, FsComponentObjectStore};
+    use crate::stream::ByteRange;
     use futures::TryStreamExt;
 
     #[test]
@@ -289,5 +470,44 @@ mod tests {
         let stream = store.get_stream("not_existing").await;
         let stream_data = stream.try_collect::<Vec<_>>().await;
         assert!(stream_data.is_err());
+
+        assert_eq!(store.get_size(object_key).await.unwrap(), 11);
+
+        let stream = store
+            .get_stream_range(object_key, ByteRange { start: 6, end: 10 })
+            .await;
+        let stream_data: Vec<Vec<u8>> = stream.try_collect::<Vec<_>>().await.unwrap();
+        let stream_data: Vec<u8> = stream_data.into_iter().flatten().collect();
+        assert_eq!(stream_data, b"world".to_vec());
+    }
+
+    #[test]
+    pub async fn test_fs_object_store_list() {
+        let config = ComponentStoreLocalConfig {
+            root_path: "/tmp/cloud-service-list".to_string(),
+            object_prefix: "prefix".to_string(),
+        };
+
+        let store = FsComponentObjectStore::new(&config).unwrap();
+
+        store.put("a", b"hello".to_vec()).await.unwrap();
+        store.put("nested/b", b"world!".to_vec()).await.unwrap();
+
+        let mut entries = store
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.key, e.size))
+            .collect::<Vec<_>>();
+        entries.sort();
+
+        assert_eq!(
+            entries,
+            vec![("a".to_string(), 5), ("nested/b".to_string(), 6)]
+        );
+
+        store.delete("a").await.unwrap();
+        store.delete("nested/b").await.unwrap();
     }
 }
@@ -4,8 +4,9 @@ use async_trait::async_trait;
 use anyhow::Error;
 use tracing::log::{debug, info};
 use crate::config::{IFSStoreLocalConfig, IFSStoreS3Config};
+use crate::service::component_object_store::{list_fs_objects, list_s3_objects, ObjectStoreEntry};
 use crate::stream::ByteStream;
-use aws_config::BehaviorVersion;
+use crate::service::s3_client::new_s3_client;
 #[async_trait]
 pub trait IFSObjectStore {
 
@@ -17,6 +18,9 @@ pub trait IFSObjectStore {
 
     async fn delete(&self, object_key: &str) -> Result<(), Error>;
 
+    /// Lists every object stored under the configured prefix, with keys relative to the prefix
+    async fn list(&self) -> Result<Vec<ObjectStoreEntry>, Error>;
+
 }
 
 pub struct AwsS3IFSObjectStore{
@@ -31,8 +35,7 @@ impl AwsS3IFSObjectStore{
             "S3 Component Object Store bucket: {}, prefix: {}",
             config.bucket_name, config.object_prefix
         );
-        let sdk_config = aws_config::load_defaults(BehaviorVersion::v2024_03_28()).await;
-        let client = aws_sdk_s3::Client::new(&sdk_config);
+        let client = new_s3_client(&config.client).await;
         Self {
             client,
             bucket_name: config.bucket_name.clone(),
@@ -40,24 +43,85 @@ impl AwsS3IFSObjectStore{
         }
     }
 
+    fn get_key(&self, object_key: &str) -> String {
+        if self.object_prefix.is_empty() {
+            object_key.to_string()
+        } else {
+            format!("{}/{}", self.object_prefix, object_key)
+        }
+    }
 }
 
 #[async_trait]
 impl IFSObjectStore for AwsS3IFSObjectStore {
     async fn get(&self, object_key: &str) -> Result<Vec<u8>, Error> {
-        todo!()
+        let key = self.get_key(object_key);
+
+        info!("Getting object: {}/{}", self.bucket_name, key);
+
+        let response = self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .send()
+            .await?;
+
+        let data = response.body.collect().await?;
+        Ok(data.to_vec())
     }
 
     async fn get_stream(&self, object_key: &str) -> ByteStream {
-        todo!()
+        let key = self.get_key(object_key);
+
+        info!("Getting object: {}/{}", self.bucket_name, key);
+
+        match self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(response) => response.body.into(),
+            Err(error) => ByteStream::error(error),
+        }
     }
 
     async fn put(&self, object_key: &str, data: Vec<u8>) -> Result<(), Error> {
-        todo!()
+        let key = self.get_key(object_key);
+
+        info!("Putting object: {}/{}", self.bucket_name, key);
+
+        self.client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .body(aws_sdk_s3::primitives::ByteStream::from(data))
+            .send()
+            .await?;
+
+        Ok(())
     }
 
     async fn delete(&self, object_key: &str) -> Result<(), Error> {
-        todo!()
+        let key = self.get_key(object_key);
+
+        info!("Deleting object: {}/{}", self.bucket_name, key);
+
+        self.client
+            .delete_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<ObjectStoreEntry>, Error> {
+        list_s3_objects(&self.client, &self.bucket_name, &self.object_prefix).await
     }
 }
 
@@ -177,4 +241,8 @@ impl IFSObjectStore for FsIFSObjectStore {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    async fn list(&self) -> Result<Vec<ObjectStoreEntry>, Error> {
+        list_fs_objects(&self.get_dir_path())
+    }
+}
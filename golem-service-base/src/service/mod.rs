@@ -14,3 +14,4 @@
 
 pub mod component_object_store;
 pub mod ifs_object_store;
+pub mod s3_client;
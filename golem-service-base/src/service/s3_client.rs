@@ -0,0 +1,141 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::config::{S3ClientConfig, S3CredentialsConfig};
+use aws_config::imds::credentials::ImdsCredentialsProvider;
+use aws_config::sts::AssumeRoleProvider;
+use aws_config::web_identity_token::WebIdentityTokenCredentialsProvider;
+use aws_config::{BehaviorVersion, ConfigLoader};
+use aws_sdk_s3::config::{Credentials, Region};
+use tracing::info;
+
+/// Creates an S3 client with the credentials, region and endpoint of an object store's config
+pub async fn new_s3_client(config: &S3ClientConfig) -> aws_sdk_s3::Client {
+    let mut sdk_config = base_config_loader(config).load().await;
+
+    if let Some(assume_role) = &config.assume_role {
+        info!("Assuming role {} for S3 access", assume_role.role_arn);
+        let mut builder = AssumeRoleProvider::builder(&assume_role.role_arn)
+            .session_name(
+                assume_role
+                    .session_name
+                    .clone()
+                    .unwrap_or_else(|| "golem".to_string()),
+            )
+            .configure(&sdk_config);
+        if let Some(external_id) = &assume_role.external_id {
+            builder = builder.external_id(external_id);
+        }
+        if let Some(session_duration) = assume_role.session_duration {
+            builder = builder.session_length(session_duration);
+        }
+        let provider = builder.build().await;
+
+        sdk_config = with_region(aws_config::defaults(BehaviorVersion::v2024_03_28()), config)
+            .credentials_provider(provider)
+            .load()
+            .await;
+    }
+
+    // The endpoint is only set for S3, so assuming a role still reaches the AWS STS endpoint
+    let mut s3_config = aws_sdk_s3::config::Builder::from(&sdk_config);
+    if let Some(endpoint_url) = &config.endpoint_url {
+        info!("S3 endpoint: {endpoint_url}");
+        s3_config = s3_config.endpoint_url(endpoint_url);
+    }
+    if config.force_path_style {
+        s3_config = s3_config.force_path_style(true);
+    }
+
+    aws_sdk_s3::Client::from_conf(s3_config.build())
+}
+
+fn base_config_loader(config: &S3ClientConfig) -> ConfigLoader {
+    let loader = with_region(aws_config::defaults(BehaviorVersion::v2024_03_28()), config);
+    match &config.credentials {
+        S3CredentialsConfig::Default(_) => loader,
+        S3CredentialsConfig::WebIdentity(_) => {
+            loader.credentials_provider(WebIdentityTokenCredentialsProvider::builder().build())
+        }
+        S3CredentialsConfig::InstanceProfile(_) => {
+            loader.credentials_provider(ImdsCredentialsProvider::builder().build())
+        }
+        S3CredentialsConfig::Static(credentials) => loader.credentials_provider(Credentials::new(
+            &credentials.access_key_id,
+            &credentials.secret_access_key,
+            credentials.session_token.clone(),
+            None,
+            "golem-config",
+        )),
+    }
+}
+
+fn with_region(loader: ConfigLoader, config: &S3ClientConfig) -> ConfigLoader {
+    match &config.region {
+        Some(region) => loader.region(Region::new(region.clone())),
+        None => loader,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::config::{
+        S3AssumeRoleConfig, S3ClientConfig, S3CredentialsConfig, S3StaticCredentialsConfig,
+    };
+    use crate::service::s3_client::new_s3_client;
+    use aws_sdk_s3::config::Region;
+
+    fn static_credentials() -> S3CredentialsConfig {
+        S3CredentialsConfig::Static(S3StaticCredentialsConfig {
+            access_key_id: "key".to_string(),
+            secret_access_key: "secret".to_string(),
+            session_token: None,
+        })
+    }
+
+    #[test]
+    async fn client_uses_the_configured_region() {
+        let client = new_s3_client(&S3ClientConfig {
+            region: Some("eu-central-1".to_string()),
+            endpoint_url: Some("http://localhost:9000".to_string()),
+            force_path_style: true,
+            credentials: static_credentials(),
+            assume_role: None,
+        })
+        .await;
+
+        assert_eq!(client.config().region(), Some(&Region::new("eu-central-1")));
+    }
+
+    #[test]
+    async fn client_keeps_the_configured_region_when_assuming_a_role() {
+        let client = new_s3_client(&S3ClientConfig {
+            region: Some("eu-central-1".to_string()),
+            endpoint_url: None,
+            force_path_style: false,
+            credentials: static_credentials(),
+            assume_role: Some(S3AssumeRoleConfig {
+                role_arn: "arn:aws:iam::123456789012:role/golem".to_string(),
+                external_id: None,
+                session_name: None,
+                session_duration: None,
+            }),
+        })
+        .await;
+
+        assert_eq!(client.config().region(), Some(&Region::new("eu-central-1")));
+    }
+}
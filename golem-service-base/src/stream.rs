@@ -58,11 +58,84 @@ impl ByteStream {
     }
 }
 
+/// An inclusive range of bytes of an object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    /// Parses the value of an HTTP `Range` header, resolving it against the object's size.
+    ///
+    /// Only single byte ranges are supported. Returns `Ok(None)` when the header is malformed,
+    /// does not use byte units or requests multiple ranges, in which case the header is ignored
+    /// and the whole object is returned, and `Err` when the range can not be satisfied.
+    pub fn parse_http_range(header: &str, size: u64) -> Result<Option<ByteRange>, String> {
+        let Some(spec) = header.trim().strip_prefix("bytes=") else {
+            return Ok(None);
+        };
+        if spec.contains(',') {
+            return Ok(None);
+        }
+        let Some((start, end)) = spec.split_once('-') else {
+            return Ok(None);
+        };
+        let parse = |value: &str| value.trim().parse::<u64>().ok();
+
+        let range = match (start.trim().is_empty(), end.trim().is_empty()) {
+            (false, false) => match (parse(start), parse(end)) {
+                (Some(start), Some(end)) if start <= end => ByteRange {
+                    start,
+                    end: end.min(size.saturating_sub(1)),
+                },
+                _ => return Ok(None),
+            },
+            (false, true) => match parse(start) {
+                Some(start) => ByteRange {
+                    start,
+                    end: size.saturating_sub(1),
+                },
+                None => return Ok(None),
+            },
+            (true, false) => match parse(end) {
+                Some(0) => {
+                    return Err(format!("Range {spec} does not select any bytes"));
+                }
+                Some(suffix) => ByteRange {
+                    start: size.saturating_sub(suffix),
+                    end: size.saturating_sub(1),
+                },
+                None => return Ok(None),
+            },
+            (true, true) => return Ok(None),
+        };
+
+        if size == 0 || range.start >= size {
+            Err(format!(
+                "Range {spec} is outside of the object's {size} bytes"
+            ))
+        } else {
+            Ok(Some(range))
+        }
+    }
+
+    /// The value of the `Content-Range` header of a response returning this range
+    pub fn content_range(&self, size: u64) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end, size)
+    }
+
+    /// The value of the `Content-Range` header of a response rejecting an unsatisfiable range
+    pub fn unsatisfied_content_range(size: u64) -> String {
+        format!("bytes */{size}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use test_r::test;
 
-    use crate::stream::ByteStream;
+    use crate::stream::{ByteRange, ByteStream};
     use anyhow::Error;
     use futures::{stream, StreamExt, TryStreamExt};
 
@@ -100,4 +173,45 @@ mod tests {
         let result = stream.collect::<Vec<Result<Vec<u8>, Error>>>().await.len();
         assert_eq!(result, 2);
     }
+
+    #[test]
+    pub fn test_parse_http_range() {
+        assert_eq!(
+            ByteRange::parse_http_range("bytes=0-99", 1000),
+            Ok(Some(ByteRange { start: 0, end: 99 }))
+        );
+        assert_eq!(
+            ByteRange::parse_http_range("bytes=900-", 1000),
+            Ok(Some(ByteRange {
+                start: 900,
+                end: 999
+            }))
+        );
+        assert_eq!(
+            ByteRange::parse_http_range("bytes=-100", 1000),
+            Ok(Some(ByteRange {
+                start: 900,
+                end: 999
+            }))
+        );
+        assert_eq!(
+            ByteRange::parse_http_range("bytes=500-2000", 1000),
+            Ok(Some(ByteRange {
+                start: 500,
+                end: 999
+            }))
+        );
+        assert_eq!(ByteRange::parse_http_range("items=0-1", 1000), Ok(None));
+        assert_eq!(ByteRange::parse_http_range("bytes=5-1", 1000), Ok(None));
+        assert_eq!(ByteRange::parse_http_range("bytes=0-1,5-6", 1000), Ok(None));
+        assert_eq!(ByteRange::parse_http_range("bytes=abc", 1000), Ok(None));
+        assert_eq!(ByteRange::parse_http_range("bytes=x-5", 1000), Ok(None));
+        assert!(ByteRange::parse_http_range("bytes=1000-", 1000).is_err());
+        assert!(ByteRange::parse_http_range("bytes=-0", 1000).is_err());
+        assert_eq!(
+            ByteRange { start: 0, end: 99 }.content_range(1000),
+            "bytes 0-99/1000"
+        );
+        assert_eq!(ByteRange::unsatisfied_content_range(1000), "bytes */1000");
+    }
 }
@@ -0,0 +1,15 @@
+CREATE TABLE shard_manager_state
+(
+    id         integer   NOT NULL,
+    state      bytea     NOT NULL,
+    updated_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (id)
+);
+
+CREATE TABLE shard_manager_leader
+(
+    id         integer     NOT NULL,
+    holder     text        NOT NULL,
+    expires_at timestamptz NOT NULL,
+    PRIMARY KEY (id)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE shard_manager_leader
+    ADD COLUMN generation bigint NOT NULL DEFAULT 0;
@@ -39,6 +39,12 @@ pub enum ShardManagerError {
     SerializationError(String),
     #[error("Redis error {0}")]
     RedisError(fred::error::RedisError),
+    #[error("Postgres error {0}")]
+    PostgresError(sqlx::Error),
+    #[error("Lost the shard manager leadership")]
+    LostLeadership,
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
 }
 
 impl IsRetriableError for ShardManagerError {
@@ -52,6 +58,9 @@ impl IsRetriableError for ShardManagerError {
             ShardManagerError::WorkerExecutionError(_) => true, // TODO: can we define which ones are retryable?
             ShardManagerError::SerializationError(_) => false,
             ShardManagerError::RedisError(_) => false,
+            ShardManagerError::PostgresError(_) => false,
+            ShardManagerError::LostLeadership => false,
+            ShardManagerError::InvalidRequest(_) => false,
         }
     }
 
@@ -96,6 +105,16 @@ impl From<ShardManagerError> for golem::shardmanager::v1::ShardManagerError {
             ShardManagerError::RedisError(err) => {
                 error(shard_manager_error::Error::Unknown, err.to_string())
             }
+            ShardManagerError::PostgresError(err) => {
+                error(shard_manager_error::Error::Unknown, err.to_string())
+            }
+            ShardManagerError::LostLeadership => error(
+                shard_manager_error::Error::Unknown,
+                "LostLeadership".to_string(),
+            ),
+            ShardManagerError::InvalidRequest(details) => {
+                error(shard_manager_error::Error::InvalidRequest, details)
+            }
         }
     }
 }
@@ -0,0 +1,327 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use golem_common::redis::RedisPool;
+use sqlx::{Pool, Postgres};
+use tracing::{debug, info, warn};
+
+use crate::error::ShardManagerError;
+use crate::shard_manager_config::LeaderElectionConfig;
+
+/// A lease based leader election between shard manager instances sharing the same persistence
+#[async_trait]
+pub trait LeaderElection {
+    /// Acquires the lease for `holder` if nobody holds it or the previous lease expired, or
+    /// extends it if `holder` already holds it. Returns the generation of the lease if `holder`
+    /// is the leader. The generation changes every time the lease gets a new holder.
+    async fn try_acquire(
+        &self,
+        holder: &str,
+        lease_duration: Duration,
+    ) -> Result<Option<u64>, ShardManagerError>;
+}
+
+/// Identifies a leadership. The persistence only accepts writes made with the fence of the
+/// current, unexpired lease, so a leader which lost its lease cannot overwrite the state
+/// written by its successor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LeaseFence {
+    pub holder: String,
+    pub generation: u64,
+}
+
+pub struct LeaderElectionRedis {
+    pool: RedisPool,
+}
+
+pub(crate) const REDIS_LEADER_KEY: &str = "shard:shard_manager_leader";
+pub(crate) const REDIS_GENERATION_KEY: &str = "shard:shard_manager_leader_generation";
+
+const ACQUIRE_SCRIPT: &str = r#"
+local holder = redis.call('GET', KEYS[1])
+if holder == ARGV[1] then
+    redis.call('PEXPIRE', KEYS[1], ARGV[2])
+    local generation = redis.call('GET', KEYS[2])
+    if generation == false then
+        return redis.call('INCR', KEYS[2])
+    end
+    return tonumber(generation)
+elseif holder == false then
+    redis.call('SET', KEYS[1], ARGV[1], 'PX', ARGV[2])
+    return redis.call('INCR', KEYS[2])
+end
+return 0
+"#;
+
+#[async_trait]
+impl LeaderElection for LeaderElectionRedis {
+    async fn try_acquire(
+        &self,
+        holder: &str,
+        lease_duration: Duration,
+    ) -> Result<Option<u64>, ShardManagerError> {
+        let generation: i64 = self
+            .pool
+            .with("leader_election", "try_acquire")
+            .eval(
+                ACQUIRE_SCRIPT,
+                vec![REDIS_LEADER_KEY, REDIS_GENERATION_KEY],
+                vec![holder.to_string(), lease_duration.as_millis().to_string()],
+            )
+            .await
+            .map_err(ShardManagerError::RedisError)?;
+
+        // Generations start from 1, 0 means that another instance holds the lease
+        Ok((generation > 0).then_some(generation as u64))
+    }
+}
+
+impl LeaderElectionRedis {
+    pub fn new(pool: &RedisPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+pub struct LeaderElectionPostgres {
+    pool: Arc<Pool<Postgres>>,
+}
+
+// The lease is stored in a single row of the shard_manager_leader table
+pub(crate) const LEADER_ROW_ID: i32 = 0;
+
+#[async_trait]
+impl LeaderElection for LeaderElectionPostgres {
+    async fn try_acquire(
+        &self,
+        holder: &str,
+        lease_duration: Duration,
+    ) -> Result<Option<u64>, ShardManagerError> {
+        // Expiration is decided by the database's clock, so instances with skewed clocks agree
+        let generation: Option<i64> = sqlx::query_scalar(
+            r#"
+              INSERT INTO shard_manager_leader (id, holder, expires_at, generation)
+              VALUES ($1, $2, now() + make_interval(secs => $3), 1)
+              ON CONFLICT (id) DO UPDATE
+              SET holder = excluded.holder,
+                  expires_at = excluded.expires_at,
+                  generation = CASE
+                      WHEN shard_manager_leader.holder = excluded.holder
+                          THEN shard_manager_leader.generation
+                      ELSE shard_manager_leader.generation + 1
+                  END
+              WHERE shard_manager_leader.holder = excluded.holder
+                 OR shard_manager_leader.expires_at < now()
+              RETURNING generation
+            "#,
+        )
+        .bind(LEADER_ROW_ID)
+        .bind(holder)
+        .bind(lease_duration.as_secs_f64())
+        .fetch_optional(self.pool.as_ref())
+        .await
+        .map_err(ShardManagerError::PostgresError)?;
+
+        Ok(generation.map(|generation| generation as u64))
+    }
+}
+
+impl LeaderElectionPostgres {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        Self { pool }
+    }
+}
+
+/// The leadership of this shard manager instance
+pub struct LeaderLease {
+    election: Arc<dyn LeaderElection + Send + Sync>,
+    fence: LeaseFence,
+    acquired_at: Instant,
+    config: LeaderElectionConfig,
+}
+
+impl LeaderLease {
+    /// Waits until this instance becomes the leader
+    pub async fn acquire(
+        election: Arc<dyn LeaderElection + Send + Sync>,
+        config: LeaderElectionConfig,
+    ) -> Self {
+        let holder = instance_id();
+        info!(holder = %holder, "Waiting for the shard manager leadership");
+
+        loop {
+            let attempt = Instant::now();
+            match election.try_acquire(&holder, config.lease_duration).await {
+                Ok(Some(generation)) => {
+                    info!(
+                        holder = %holder,
+                        generation,
+                        "Acquired the shard manager leadership"
+                    );
+                    return Self {
+                        election,
+                        fence: LeaseFence { holder, generation },
+                        acquired_at: attempt,
+                        config,
+                    };
+                }
+                Ok(None) => debug!("Another shard manager instance is the leader"),
+                Err(err) => warn!("Failed to acquire the shard manager leadership: {err}"),
+            }
+            tokio::time::sleep(config.renew_interval).await;
+        }
+    }
+
+    pub fn fence(&self) -> LeaseFence {
+        self.fence.clone()
+    }
+
+    /// Keeps renewing the lease, only returning when the leadership is lost. After that this
+    /// instance must stop managing the shards, as another instance may have taken over.
+    ///
+    /// The leadership is given up a safety margin before the lease expires if it could not be
+    /// renewed. The lease is counted from the start of the last successful renewal, as the
+    /// store may have extended it any time after that.
+    pub async fn hold(&self) -> ShardManagerError {
+        let validity = self
+            .config
+            .lease_duration
+            .saturating_sub(self.config.safety_margin);
+        let mut valid_until = self.acquired_at + validity;
+
+        loop {
+            let next_renewal = Instant::now() + self.config.renew_interval;
+            tokio::time::sleep_until(next_renewal.min(valid_until).into()).await;
+
+            let attempt = Instant::now();
+            if attempt >= valid_until {
+                return ShardManagerError::LostLeadership;
+            }
+
+            let renewal = tokio::time::timeout(
+                valid_until - attempt,
+                self.election
+                    .try_acquire(&self.fence.holder, self.config.lease_duration),
+            )
+            .await;
+            match renewal {
+                Ok(Ok(Some(generation))) if generation == self.fence.generation => {
+                    valid_until = attempt + validity
+                }
+                Ok(Ok(_)) => return ShardManagerError::LostLeadership,
+                Ok(Err(err)) => warn!("Failed to renew the shard manager leadership: {err}"),
+                Err(_) => warn!("Renewing the shard manager leadership timed out"),
+            }
+        }
+    }
+}
+
+fn instance_id() -> String {
+    let host = env::var("HOSTNAME").unwrap_or("shard-manager".to_string());
+    format!("{}-{}", host, std::process::id())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use async_trait::async_trait;
+
+    use crate::error::ShardManagerError;
+    use crate::leader_election::{LeaderElection, LeaderLease};
+    use crate::shard_manager_config::LeaderElectionConfig;
+
+    #[derive(Default)]
+    struct InMemoryLeaderElection {
+        holder: Mutex<Option<(String, u64)>>,
+        unavailable: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl LeaderElection for InMemoryLeaderElection {
+        async fn try_acquire(
+            &self,
+            holder: &str,
+            _lease_duration: Duration,
+        ) -> Result<Option<u64>, ShardManagerError> {
+            if *self.unavailable.lock().unwrap() {
+                return Err(ShardManagerError::Timeout);
+            }
+            let mut current = self.holder.lock().unwrap();
+            match current.as_ref() {
+                Some((current_holder, generation)) if current_holder == holder => {
+                    Ok(Some(*generation))
+                }
+                Some(_) => Ok(None),
+                None => {
+                    *current = Some((holder.to_string(), 1));
+                    Ok(Some(1))
+                }
+            }
+        }
+    }
+
+    fn config() -> LeaderElectionConfig {
+        LeaderElectionConfig {
+            enabled: true,
+            lease_duration: Duration::from_millis(200),
+            renew_interval: Duration::from_millis(10),
+            safety_margin: Duration::from_millis(100),
+        }
+    }
+
+    #[test]
+    async fn leadership_is_lost_when_taken_over() {
+        let election = Arc::new(InMemoryLeaderElection::default());
+
+        let lease = LeaderLease::acquire(election.clone(), config()).await;
+        assert_eq!(lease.fence().generation, 1);
+
+        *election.holder.lock().unwrap() = Some(("other-instance".to_string(), 2));
+        let error = lease.hold().await;
+        assert!(matches!(error, ShardManagerError::LostLeadership));
+    }
+
+    #[test]
+    async fn leader_steps_down_before_the_lease_expires() {
+        let election = Arc::new(InMemoryLeaderElection::default());
+
+        let lease = LeaderLease::acquire(election.clone(), config()).await;
+        *election.unavailable.lock().unwrap() = true;
+        let unavailable_since = Instant::now();
+
+        let error = lease.hold().await;
+        let held_for = unavailable_since.elapsed();
+
+        assert!(matches!(error, ShardManagerError::LostLeadership));
+        assert!(held_for < config().lease_duration);
+    }
+
+    #[test]
+    async fn renewals_keep_the_leadership() {
+        let election = Arc::new(InMemoryLeaderElection::default());
+
+        let lease = LeaderLease::acquire(election.clone(), config()).await;
+        let held = tokio::time::timeout(config().lease_duration * 2, lease.hold()).await;
+
+        assert!(held.is_err());
+    }
+}
@@ -15,8 +15,10 @@
 mod error;
 mod healthcheck;
 mod http_server;
+mod leader_election;
 mod model;
 mod persistence;
+mod planning;
 mod rebalancing;
 mod shard_management;
 mod shard_manager_config;
@@ -24,12 +26,18 @@ mod worker_executor;
 
 use std::env;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::pin::Pin;
 use std::sync::Arc;
 
 use crate::error::ShardManagerTraceErrorKind;
 use crate::healthcheck::{get_unhealthy_pods, GrpcHealthCheck, HealthCheck};
 use crate::http_server::HttpServerImpl;
-use crate::shard_manager_config::{make_config_loader, HealthCheckK8sConfig, HealthCheckMode};
+use crate::leader_election::{
+    LeaderElection, LeaderElectionPostgres, LeaderElectionRedis, LeaderLease,
+};
+use crate::shard_manager_config::{
+    make_config_loader, HealthCheckK8sConfig, HealthCheckMode, PersistenceConfig,
+};
 use error::ShardManagerError;
 use golem_api_grpc::proto;
 use golem_api_grpc::proto::golem;
@@ -37,18 +45,26 @@ use golem_api_grpc::proto::golem::shardmanager::v1::shard_manager_service_server
     ShardManagerService, ShardManagerServiceServer,
 };
 
+use golem_common::metrics::grpc::GrpcMessageMetricsLayer;
+use golem_common::grpc::proto_worker_id_string;
+use golem_common::model::WorkerId;
 use golem_common::recorded_grpc_api_request;
+use futures::{Stream, StreamExt};
+use golem_common::redis::RedisPool;
 use golem_common::tracing::init_tracing_with_default_env_filter;
+use golem_common::with_grpc_compression;
+use golem_service_base::db;
 use model::{Pod, RoutingTable};
-use persistence::{PersistenceService, PersistenceServiceDefault};
+use persistence::{PersistenceService, PersistenceServicePostgres, PersistenceServiceRedis};
+use planning::{ClusterChange, RebalancePlan};
 use prometheus::{default_registry, Registry};
 use shard_management::ShardManagement;
 use shard_manager_config::ShardManagerConfig;
-use tonic::codec::CompressionEncoding;
 use tonic::transport::Server;
+use tokio_stream::wrappers::WatchStream;
 use tonic::Response;
 use tracing::Instrument;
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
 use worker_executor::{WorkerExecutorService, WorkerExecutorServiceDefault};
 
 #[cfg(test)]
@@ -88,10 +104,13 @@ impl ShardManagerServiceImpl {
         Ok(shard_manager_service)
     }
 
-    async fn get_routing_table_internal(&self) -> RoutingTable {
-        let routing_table = self.shard_management.current_snapshot().await;
-        info!("Shard Manager providing routing table: {}", routing_table);
-        routing_table
+    async fn get_routing_table_internal(&self) -> (RoutingTable, u64) {
+        let (routing_table, epoch) = self.shard_management.current_snapshot_with_epoch().await;
+        info!(
+            "Shard Manager providing routing table (epoch {}): {}",
+            epoch, routing_table
+        );
+        (routing_table, epoch)
     }
 
     async fn register_internal(
@@ -107,6 +126,70 @@ impl ShardManagerServiceImpl {
         Ok(())
     }
 
+    async fn plan_rebalance_internal(
+        &self,
+        request: golem::shardmanager::v1::PlanRebalanceRequest,
+    ) -> Result<RebalancePlan, ShardManagerError> {
+        let routing_table = self.shard_management.current_snapshot().await;
+
+        let removed_pods = request
+            .remove_pods
+            .iter()
+            .map(|pod| {
+                routing_table.find_pod(pod).ok_or_else(|| {
+                    ShardManagerError::InvalidRequest(format!(
+                        "Pod {}:{} is not registered",
+                        pod.host, pod.port
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let number_of_shards = match request.number_of_shards {
+            Some(0) => Err(ShardManagerError::InvalidRequest(
+                "The number of shards must be positive".to_string(),
+            )),
+            number_of_shards => Ok(number_of_shards.map(|n| n as usize)),
+        }?;
+        let change = ClusterChange {
+            added_pods: request.add_pods.into_iter().map(Pod::planned).collect(),
+            removed_pods,
+            number_of_shards,
+        };
+
+        let plan = RebalancePlan::new(
+            &routing_table,
+            &change,
+            self.shard_manager_config.rebalance_threshold,
+        );
+        info!("Shard Manager planned rebalance: {}", plan);
+        Ok(plan)
+    }
+
+    async fn add_anti_affinity_internal(
+        &self,
+        request: golem::shardmanager::v1::AddAntiAffinityRequest,
+    ) -> Result<(), ShardManagerError> {
+        if request.key.is_empty() {
+            return Err(ShardManagerError::InvalidRequest(
+                "The anti-affinity key must not be empty".to_string(),
+            ));
+        }
+        let worker_id = worker_id(request.worker_id)?;
+
+        self.shard_management
+            .add_anti_affinity(request.key, worker_id)
+            .await
+    }
+
+    async fn remove_anti_affinity_internal(
+        &self,
+        request: golem::shardmanager::v1::RemoveAntiAffinityRequest,
+    ) -> Result<(), ShardManagerError> {
+        let worker_id = worker_id(request.worker_id)?;
+
+        self.shard_management.remove_anti_affinity(&worker_id).await
+    }
+
     fn start_health_check(&self) {
         let delay = self.shard_manager_config.health_check.delay;
         let shard_management = self.shard_management.clone();
@@ -144,8 +227,14 @@ impl ShardManagerServiceImpl {
     }
 }
 
+type RoutingTableEpochStream = Pin<
+    Box<dyn Stream<Item = Result<golem::shardmanager::v1::RoutingTableEpoch, tonic::Status>> + Send>,
+>;
+
 #[tonic::async_trait]
 impl ShardManagerService for ShardManagerServiceImpl {
+    type WatchRoutingTableEpochStream = RoutingTableEpochStream;
+
     async fn get_routing_table(
         &self,
         _request: tonic::Request<golem::shardmanager::v1::GetRoutingTableRequest>,
@@ -153,7 +242,7 @@ impl ShardManagerService for ShardManagerServiceImpl {
     {
         let record = recorded_grpc_api_request!("get_routing_table",);
 
-        let response = self
+        let (routing_table, epoch) = self
             .get_routing_table_internal()
             .instrument(record.span.clone())
             .await;
@@ -162,13 +251,28 @@ impl ShardManagerService for ShardManagerServiceImpl {
             golem::shardmanager::v1::GetRoutingTableResponse {
                 result: Some(
                     golem::shardmanager::v1::get_routing_table_response::Result::Success(
-                        response.into(),
+                        golem::shardmanager::RoutingTable {
+                            epoch,
+                            ..routing_table.into()
+                        },
                     ),
                 ),
             },
         ))
     }
 
+    async fn watch_routing_table_epoch(
+        &self,
+        _request: tonic::Request<golem::shardmanager::v1::WatchRoutingTableEpochRequest>,
+    ) -> Result<tonic::Response<Self::WatchRoutingTableEpochStream>, tonic::Status> {
+        let record = recorded_grpc_api_request!("watch_routing_table_epoch",);
+
+        let stream = WatchStream::new(self.shard_management.subscribe_epoch())
+            .map(|epoch| Ok(golem::shardmanager::v1::RoutingTableEpoch { epoch }));
+
+        Ok(Response::new(record.succeed(Box::pin(stream))))
+    }
+
     async fn register(
         &self,
         request: tonic::Request<golem::shardmanager::v1::RegisterRequest>,
@@ -206,6 +310,134 @@ impl ShardManagerService for ShardManagerServiceImpl {
             result: Some(result),
         }))
     }
+
+    async fn plan_rebalance(
+        &self,
+        request: tonic::Request<golem::shardmanager::v1::PlanRebalanceRequest>,
+    ) -> Result<tonic::Response<golem::shardmanager::v1::PlanRebalanceResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "plan_rebalance",
+            add_pods = request.add_pods.len(),
+            remove_pods = request.remove_pods.len(),
+            number_of_shards = request.number_of_shards,
+        );
+
+        let response = self
+            .plan_rebalance_internal(request)
+            .instrument(record.span.clone())
+            .await;
+
+        let result = match response {
+            Ok(plan) => record.succeed(
+                golem::shardmanager::v1::plan_rebalance_response::Result::Success(plan.into()),
+            ),
+            Err(error) => {
+                let error: golem::shardmanager::v1::ShardManagerError = error.into();
+                record.fail(
+                    golem::shardmanager::v1::plan_rebalance_response::Result::Failure(
+                        error.clone(),
+                    ),
+                    &ShardManagerTraceErrorKind(&error),
+                )
+            }
+        };
+
+        Ok(Response::new(
+            golem::shardmanager::v1::PlanRebalanceResponse {
+                result: Some(result),
+            },
+        ))
+    }
+
+    async fn add_anti_affinity(
+        &self,
+        request: tonic::Request<golem::shardmanager::v1::AddAntiAffinityRequest>,
+    ) -> Result<tonic::Response<golem::shardmanager::v1::AddAntiAffinityResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "add_anti_affinity",
+            key = &request.key,
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        let response = self
+            .add_anti_affinity_internal(request)
+            .instrument(record.span.clone())
+            .await;
+
+        let result = match response {
+            Ok(_) => record.succeed(
+                golem::shardmanager::v1::add_anti_affinity_response::Result::Success(
+                    golem::common::Empty {},
+                ),
+            ),
+            Err(error) => {
+                let error: golem::shardmanager::v1::ShardManagerError = error.into();
+                record.fail(
+                    golem::shardmanager::v1::add_anti_affinity_response::Result::Failure(
+                        error.clone(),
+                    ),
+                    &ShardManagerTraceErrorKind(&error),
+                )
+            }
+        };
+
+        Ok(Response::new(
+            golem::shardmanager::v1::AddAntiAffinityResponse {
+                result: Some(result),
+            },
+        ))
+    }
+
+    async fn remove_anti_affinity(
+        &self,
+        request: tonic::Request<golem::shardmanager::v1::RemoveAntiAffinityRequest>,
+    ) -> Result<tonic::Response<golem::shardmanager::v1::RemoveAntiAffinityResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "remove_anti_affinity",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        let response = self
+            .remove_anti_affinity_internal(request)
+            .instrument(record.span.clone())
+            .await;
+
+        let result = match response {
+            Ok(_) => record.succeed(
+                golem::shardmanager::v1::remove_anti_affinity_response::Result::Success(
+                    golem::common::Empty {},
+                ),
+            ),
+            Err(error) => {
+                let error: golem::shardmanager::v1::ShardManagerError = error.into();
+                record.fail(
+                    golem::shardmanager::v1::remove_anti_affinity_response::Result::Failure(
+                        error.clone(),
+                    ),
+                    &ShardManagerTraceErrorKind(&error),
+                )
+            }
+        };
+
+        Ok(Response::new(
+            golem::shardmanager::v1::RemoveAntiAffinityResponse {
+                result: Some(result),
+            },
+        ))
+    }
+}
+
+fn worker_id(worker_id: Option<golem::worker::WorkerId>) -> Result<WorkerId, ShardManagerError> {
+    worker_id
+        .ok_or_else(|| ShardManagerError::InvalidRequest("Missing worker id".to_string()))?
+        .try_into()
+        .map_err(ShardManagerError::InvalidRequest)
 }
 
 pub fn server_main() -> Result<(), Box<dyn std::error::Error>> {
@@ -245,17 +477,53 @@ async fn async_main(
         registry,
     );
 
-    info!("Using Redis at {}", shard_manager_config.redis.url());
-    let pool = golem_common::redis::RedisPool::configured(&shard_manager_config.redis).await?;
-
     let shard_manager_config = Arc::new(shard_manager_config.clone());
 
-    let persistence_service = Arc::new(PersistenceServiceDefault::new(
-        &pool,
-        &shard_manager_config.number_of_shards,
-    ));
+    // Standby instances wait for the lease, before reading the routing table, until the
+    // leader's lease expires. The persistence only accepts writes while the lease is held.
+    let (persistence_service, leader_lease): (
+        Arc<dyn PersistenceService + Send + Sync>,
+        Option<LeaderLease>,
+    ) = match &shard_manager_config.persistence {
+        PersistenceConfig::Redis(_) => {
+            info!("Using Redis at {}", shard_manager_config.redis.endpoint());
+            let pool = RedisPool::configured(&shard_manager_config.redis).await?;
+            let leader_lease = acquire_leader_lease(
+                Arc::new(LeaderElectionRedis::new(&pool)),
+                &shard_manager_config,
+            )
+            .await;
+            (
+                Arc::new(PersistenceServiceRedis::new(
+                    &pool,
+                    &shard_manager_config.number_of_shards,
+                    leader_lease.as_ref().map(|lease| lease.fence()),
+                )),
+                leader_lease,
+            )
+        }
+        PersistenceConfig::Postgres(config) => {
+            db::postgres_migrate(config, "./db/migration/postgres").await?;
+            let pool = Arc::new(db::create_postgres_pool(config).await?);
+            let leader_lease = acquire_leader_lease(
+                Arc::new(LeaderElectionPostgres::new(pool.clone())),
+                &shard_manager_config,
+            )
+            .await;
+            (
+                Arc::new(PersistenceServicePostgres::new(
+                    pool,
+                    &shard_manager_config.number_of_shards,
+                    leader_lease.as_ref().map(|lease| lease.fence()),
+                )),
+                leader_lease,
+            )
+        }
+    };
+    let grpc_compression = shard_manager_config.grpc_compression;
     let worker_executors = Arc::new(WorkerExecutorServiceDefault::new(
         shard_manager_config.worker_executors.clone(),
+        grpc_compression,
     ));
 
     let shard_manager_port_str = env::var("GOLEM_SHARD_MANAGER_PORT")?;
@@ -292,20 +560,43 @@ async fn async_main(
     )
     .await?;
 
-    let service = ShardManagerServiceServer::new(shard_manager);
+    let service = with_grpc_compression!(
+        ShardManagerServiceServer::new(shard_manager),
+        grpc_compression
+    );
 
-    Server::builder()
+    let server = Server::builder()
+        .layer(GrpcMessageMetricsLayer)
         .add_service(reflection_service)
-        .add_service(
-            service
-                .accept_compressed(CompressionEncoding::Gzip)
-                .send_compressed(CompressionEncoding::Gzip),
-        )
+        .add_service(service)
         .add_service(health_service)
-        .serve(addr)
-        .await?;
+        .serve(addr);
+
+    match &leader_lease {
+        Some(leader_lease) => {
+            tokio::select! {
+                result = server => result?,
+                error = leader_lease.hold() => {
+                    error!("Stopping the shard manager: {error}");
+                    return Err(error.into());
+                }
+            }
+        }
+        None => server.await?,
+    }
 
     info!("Server started on port {}", shard_manager_port);
 
     Ok(())
 }
+
+async fn acquire_leader_lease(
+    election: Arc<dyn LeaderElection + Send + Sync>,
+    shard_manager_config: &ShardManagerConfig,
+) -> Option<LeaderLease> {
+    if shard_manager_config.leader_election.enabled {
+        Some(LeaderLease::acquire(election, shard_manager_config.leader_election.clone()).await)
+    } else {
+        None
+    }
+}
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use core::cmp::Ordering;
-use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::hash::Hash;
 use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
@@ -26,7 +26,7 @@ use tonic::transport::Endpoint;
 use tracing::{error, warn};
 
 use golem_api_grpc::proto::golem;
-use golem_common::model::ShardId;
+use golem_common::model::{ShardId, WorkerId};
 
 use crate::error::ShardManagerError;
 use crate::rebalancing::Rebalance;
@@ -52,6 +52,30 @@ impl Pod {
         }
     }
 
+    /// Constructs a pod which has not registered yet, for planning its addition
+    pub fn planned(pod: golem::shardmanager::Pod) -> Self {
+        Self {
+            ip: pod
+                .host
+                .parse()
+                .unwrap_or(IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED)),
+            host: pod.host,
+            port: pod.port as u16,
+            pod_name: pod.pod_name,
+        }
+    }
+
+    /// Checks whether a pod given in a request refers to this pod, either by its name or its address
+    pub fn matches(&self, pod: &golem::shardmanager::Pod) -> bool {
+        match (&self.pod_name, &pod.pod_name) {
+            (Some(name), Some(requested_name)) => name == requested_name,
+            _ => {
+                (pod.host == self.host || pod.host == self.ip.to_string())
+                    && pod.port == self.port as u32
+            }
+        }
+    }
+
     pub fn endpoint(&self) -> Endpoint {
         Endpoint::from(self.uri())
     }
@@ -204,6 +228,13 @@ impl RoutingTable {
     pub fn has_pod(&self, pod: &Pod) -> bool {
         self.shard_assignments.contains_key(pod)
     }
+
+    pub fn find_pod(&self, pod: &golem::shardmanager::Pod) -> Option<Pod> {
+        self.shard_assignments
+            .keys()
+            .find(|registered| registered.matches(pod))
+            .cloned()
+    }
 }
 
 impl From<RoutingTable> for golem::shardmanager::RoutingTable {
@@ -223,6 +254,7 @@ impl From<RoutingTable> for golem::shardmanager::RoutingTable {
                     shard_id: Some(shard_id.into()),
                 })
                 .collect(),
+            epoch: 0,
         }
     }
 }
@@ -365,6 +397,97 @@ impl Display for Unassignments {
     }
 }
 
+/// Workers which should not run on the same pod, grouped by anti-affinity key
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct AntiAffinityGroups {
+    /// The key and the shard of each worker in a group
+    workers: HashMap<WorkerId, (String, ShardId)>,
+    /// The number of workers of each group on each of its shards
+    groups: BTreeMap<String, BTreeMap<ShardId, usize>>,
+    /// The groups having workers on each shard
+    shard_groups: BTreeMap<ShardId, BTreeSet<String>>,
+}
+
+impl AntiAffinityGroups {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the worker of the shard to the group of the key, moving it out of its previous group.
+    /// Returns false if it was already in the group.
+    pub fn add(&mut self, key: String, worker_id: WorkerId, shard_id: ShardId) -> bool {
+        if self
+            .workers
+            .get(&worker_id)
+            .is_some_and(|(current, _)| *current == key)
+        {
+            return false;
+        }
+        self.remove(&worker_id);
+
+        *self
+            .groups
+            .entry(key.clone())
+            .or_default()
+            .entry(shard_id)
+            .or_default() += 1;
+        self.shard_groups
+            .entry(shard_id)
+            .or_default()
+            .insert(key.clone());
+        self.workers.insert(worker_id, (key, shard_id));
+        true
+    }
+
+    /// Removes the worker from its group, returns false if it was not in any. A shard leaves the
+    /// group together with the last worker of the group on it.
+    pub fn remove(&mut self, worker_id: &WorkerId) -> bool {
+        let Some((key, shard_id)) = self.workers.remove(worker_id) else {
+            return false;
+        };
+
+        if let Some(group) = self.groups.get_mut(&key) {
+            if let Some(count) = group.get_mut(&shard_id) {
+                *count -= 1;
+                if *count == 0 {
+                    group.remove(&shard_id);
+                    if let Some(keys) = self.shard_groups.get_mut(&shard_id) {
+                        keys.remove(&key);
+                        if keys.is_empty() {
+                            self.shard_groups.remove(&shard_id);
+                        }
+                    }
+                }
+            }
+            if group.is_empty() {
+                self.groups.remove(&key);
+            }
+        }
+        true
+    }
+
+    /// The shards of each group
+    pub fn groups(&self) -> impl Iterator<Item = BTreeSet<ShardId>> + '_ {
+        self.groups
+            .values()
+            .map(|group| group.keys().copied().collect())
+    }
+
+    /// Checks whether the shard can be owned by a pod owning the given shards without having
+    /// another shard of any of its groups on the same pod. Only the groups of the shard are
+    /// looked at.
+    pub fn allows(&self, shard_id: &ShardId, shard_ids: &BTreeSet<ShardId>) -> bool {
+        let Some(keys) = self.shard_groups.get(shard_id) else {
+            return true;
+        };
+        keys.iter().all(|key| {
+            self.groups[key]
+                .keys()
+                .all(|other| other == shard_id || !shard_ids.contains(other))
+        })
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, Encode, Decode)]
 pub struct ShardManagerState {
     pub number_of_shards: usize,
@@ -12,26 +12,47 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use bytes::Bytes;
+use fred::types::RedisValue;
 use golem_common::redis::RedisPool;
+use golem_common::serialization::{deserialize, serialize};
+use sqlx::{Pool, Postgres};
 
 use crate::error::ShardManagerError;
-use crate::model::{RoutingTable, ShardManagerState};
+use crate::leader_election::{LeaseFence, LEADER_ROW_ID, REDIS_GENERATION_KEY, REDIS_LEADER_KEY};
+use crate::model::{AntiAffinityGroups, RoutingTable, ShardManagerState};
 
 #[async_trait]
 pub trait PersistenceService {
     async fn write(&self, routing_table: &RoutingTable) -> Result<(), ShardManagerError>;
     async fn read(&self) -> Result<RoutingTable, ShardManagerError>;
+    async fn write_anti_affinity_groups(
+        &self,
+        groups: &AntiAffinityGroups,
+    ) -> Result<(), ShardManagerError>;
+    async fn read_anti_affinity_groups(&self) -> Result<AntiAffinityGroups, ShardManagerError>;
 }
 
-pub struct PersistenceServiceDefault {
+pub struct PersistenceServiceRedis {
     pool: RedisPool,
     number_of_shards: usize,
+    fence: Option<LeaseFence>,
 }
 
+// Only writes the value if the lease is still held with the same generation
+const FENCED_SET_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] and redis.call('GET', KEYS[2]) == ARGV[2] then
+    redis.call('SET', KEYS[3], ARGV[3])
+    return 1
+end
+return 0
+"#;
+
 #[async_trait]
-impl PersistenceService for PersistenceServiceDefault {
+impl PersistenceService for PersistenceServiceRedis {
     async fn write(&self, routing_table: &RoutingTable) -> Result<(), ShardManagerError> {
         let shard_manager_state = ShardManagerState::new(routing_table);
         let key = "shard:shard_manager_state";
@@ -40,11 +61,7 @@ impl PersistenceService for PersistenceServiceDefault {
             .serialize(&shard_manager_state)
             .map_err(ShardManagerError::SerializationError)?;
 
-        self.pool
-            .with("persistence", "write")
-            .set(key, value, None, None, false)
-            .await
-            .map_err(ShardManagerError::RedisError)
+        self.set(key, value, "write").await
     }
 
     async fn read(&self) -> Result<RoutingTable, ShardManagerError> {
@@ -68,13 +85,211 @@ impl PersistenceService for PersistenceServiceDefault {
             None => Ok(RoutingTable::new(self.number_of_shards)),
         }
     }
+
+    async fn write_anti_affinity_groups(
+        &self,
+        groups: &AntiAffinityGroups,
+    ) -> Result<(), ShardManagerError> {
+        let key = "shard:anti_affinity_groups";
+        let value = self
+            .pool
+            .serialize(groups)
+            .map_err(ShardManagerError::SerializationError)?;
+
+        self.set(key, value, "write_anti_affinity_groups").await
+    }
+
+    async fn read_anti_affinity_groups(&self) -> Result<AntiAffinityGroups, ShardManagerError> {
+        let key = "shard:anti_affinity_groups";
+
+        let value: Option<Bytes> = self
+            .pool
+            .with("persistence", "read_anti_affinity_groups")
+            .get(key)
+            .await
+            .map_err(ShardManagerError::RedisError)?;
+
+        match value {
+            Some(value) => self
+                .pool
+                .deserialize(&value)
+                .map_err(ShardManagerError::SerializationError),
+            None => Ok(AntiAffinityGroups::new()),
+        }
+    }
 }
 
-impl PersistenceServiceDefault {
-    pub fn new(pool: &RedisPool, number_of_shards: &usize) -> Self {
+impl PersistenceServiceRedis {
+    /// Writes are only accepted while the lease identified by `fence` is held, if given
+    pub fn new(pool: &RedisPool, number_of_shards: &usize, fence: Option<LeaseFence>) -> Self {
         Self {
             pool: pool.clone(),
             number_of_shards: *number_of_shards,
+            fence,
+        }
+    }
+
+    async fn set(
+        &self,
+        key: &str,
+        value: Bytes,
+        op: &'static str,
+    ) -> Result<(), ShardManagerError> {
+        match &self.fence {
+            None => self
+                .pool
+                .with("persistence", op)
+                .set(key, value, None, None, false)
+                .await
+                .map_err(ShardManagerError::RedisError),
+            Some(fence) => {
+                let written: i64 = self
+                    .pool
+                    .with("persistence", op)
+                    .eval(
+                        FENCED_SET_SCRIPT,
+                        vec![REDIS_LEADER_KEY, REDIS_GENERATION_KEY, key],
+                        vec![
+                            RedisValue::from(fence.holder.as_str()),
+                            RedisValue::from(fence.generation.to_string()),
+                            RedisValue::from(value),
+                        ],
+                    )
+                    .await
+                    .map_err(ShardManagerError::RedisError)?;
+
+                if written == 1 {
+                    Ok(())
+                } else {
+                    Err(ShardManagerError::LostLeadership)
+                }
+            }
+        }
+    }
+}
+
+pub struct PersistenceServicePostgres {
+    pool: Arc<Pool<Postgres>>,
+    number_of_shards: usize,
+    fence: Option<LeaseFence>,
+}
+
+// The state is stored in a single row of the shard_manager_state table, and the anti-affinity
+// groups in another one
+const STATE_ROW_ID: i32 = 0;
+const ANTI_AFFINITY_GROUPS_ROW_ID: i32 = 1;
+
+#[async_trait]
+impl PersistenceService for PersistenceServicePostgres {
+    async fn write(&self, routing_table: &RoutingTable) -> Result<(), ShardManagerError> {
+        let shard_manager_state = ShardManagerState::new(routing_table);
+        let value =
+            serialize(&shard_manager_state).map_err(ShardManagerError::SerializationError)?;
+
+        self.write_row(STATE_ROW_ID, value.to_vec()).await
+    }
+
+    async fn read(&self) -> Result<RoutingTable, ShardManagerError> {
+        match self.read_row(STATE_ROW_ID).await? {
+            Some(value) => {
+                let shard_manager_state: ShardManagerState =
+                    deserialize(&value).map_err(ShardManagerError::SerializationError)?;
+                Ok(shard_manager_state.get_routing_table())
+            }
+            None => Ok(RoutingTable::new(self.number_of_shards)),
+        }
+    }
+
+    async fn write_anti_affinity_groups(
+        &self,
+        groups: &AntiAffinityGroups,
+    ) -> Result<(), ShardManagerError> {
+        let value = serialize(groups).map_err(ShardManagerError::SerializationError)?;
+        self.write_row(ANTI_AFFINITY_GROUPS_ROW_ID, value.to_vec())
+            .await
+    }
+
+    async fn read_anti_affinity_groups(&self) -> Result<AntiAffinityGroups, ShardManagerError> {
+        match self.read_row(ANTI_AFFINITY_GROUPS_ROW_ID).await? {
+            Some(value) => deserialize(&value).map_err(ShardManagerError::SerializationError),
+            None => Ok(AntiAffinityGroups::new()),
+        }
+    }
+}
+
+impl PersistenceServicePostgres {
+    /// Writes are only accepted while the lease identified by `fence` is held, if given
+    pub fn new(
+        pool: Arc<Pool<Postgres>>,
+        number_of_shards: &usize,
+        fence: Option<LeaseFence>,
+    ) -> Self {
+        Self {
+            pool,
+            number_of_shards: *number_of_shards,
+            fence,
         }
     }
+
+    async fn write_row(&self, id: i32, value: Vec<u8>) -> Result<(), ShardManagerError> {
+        let Some(fence) = &self.fence else {
+            return self.write_row_unfenced(id, value).await;
+        };
+
+        // The leader row is locked for the duration of the write, so the lease cannot change
+        // hands before it commits
+        let result = sqlx::query(
+            r#"
+              INSERT INTO shard_manager_state (id, state, updated_at)
+              SELECT $1, $2, CURRENT_TIMESTAMP
+              WHERE EXISTS (
+                  SELECT 1 FROM shard_manager_leader
+                  WHERE id = $3 AND holder = $4 AND generation = $5 AND expires_at > now()
+                  FOR SHARE
+              )
+              ON CONFLICT (id) DO UPDATE
+              SET state = excluded.state, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(value)
+        .bind(LEADER_ROW_ID)
+        .bind(&fence.holder)
+        .bind(fence.generation as i64)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(ShardManagerError::PostgresError)?;
+
+        if result.rows_affected() == 0 {
+            Err(ShardManagerError::LostLeadership)
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn write_row_unfenced(&self, id: i32, value: Vec<u8>) -> Result<(), ShardManagerError> {
+        sqlx::query(
+            r#"
+              INSERT INTO shard_manager_state (id, state, updated_at)
+              VALUES ($1, $2, CURRENT_TIMESTAMP)
+              ON CONFLICT (id) DO UPDATE
+              SET state = excluded.state, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(value)
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(ShardManagerError::PostgresError)?;
+
+        Ok(())
+    }
+
+    async fn read_row(&self, id: i32) -> Result<Option<Vec<u8>>, ShardManagerError> {
+        sqlx::query_scalar("SELECT state FROM shard_manager_state WHERE id = $1")
+            .bind(id)
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(ShardManagerError::PostgresError)
+    }
 }
@@ -0,0 +1,260 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use golem_api_grpc::proto::golem;
+use golem_common::model::ShardId;
+
+use crate::model::{Pod, RoutingTable};
+use crate::rebalancing::Rebalance;
+
+/// A change of the cluster to be planned without applying it
+#[derive(Clone, Debug, Default)]
+pub struct ClusterChange {
+    pub added_pods: Vec<Pod>,
+    pub removed_pods: Vec<Pod>,
+    pub number_of_shards: Option<usize>,
+}
+
+/// The shard movements affecting a single pod
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PodShardMovement {
+    pub shards_before: usize,
+    pub shards_after: usize,
+    pub assigned_shards: usize,
+    pub revoked_shards: usize,
+}
+
+/// The outcome of a cluster change, computed with the same rebalancing logic the shard
+/// management applies
+#[derive(Clone, Debug)]
+pub struct RebalancePlan {
+    pub number_of_shards: usize,
+    pub pods: BTreeMap<Pod, PodShardMovement>,
+    /// The number of shards which would get a different owner
+    pub moved_shards: usize,
+}
+
+impl RebalancePlan {
+    pub fn new(routing_table: &RoutingTable, change: &ClusterChange, threshold: f64) -> Self {
+        let mut planned = routing_table.clone();
+
+        if let Some(number_of_shards) = change.number_of_shards {
+            let limit = ShardId::new(number_of_shards as i64);
+            planned.number_of_shards = number_of_shards;
+            for shard_ids in planned.shard_assignments.values_mut() {
+                shard_ids.retain(|shard_id| *shard_id < limit);
+            }
+        }
+        for pod in &change.removed_pods {
+            planned.remove_pod(pod);
+        }
+        for pod in &change.added_pods {
+            if !planned.has_pod(pod) {
+                planned.add_pod(pod);
+            }
+        }
+
+        let rebalance = Rebalance::from_routing_table(&planned, threshold);
+        planned.rebalance(rebalance);
+
+        let owners_before = owners(routing_table);
+        let owners_after = owners(&planned);
+        let moved_shards = (0..planned.number_of_shards)
+            .map(|shard_id| ShardId::new(shard_id as i64))
+            .filter(|shard_id| owners_before.get(shard_id) != owners_after.get(shard_id))
+            .count();
+
+        let empty = BTreeSet::new();
+        let pods = routing_table
+            .shard_assignments
+            .keys()
+            .chain(planned.shard_assignments.keys())
+            .map(|pod| {
+                let before = routing_table.shard_assignments.get(pod).unwrap_or(&empty);
+                let after = planned.shard_assignments.get(pod).unwrap_or(&empty);
+                (
+                    pod.clone(),
+                    PodShardMovement {
+                        shards_before: before.len(),
+                        shards_after: after.len(),
+                        assigned_shards: after.difference(before).count(),
+                        revoked_shards: before.difference(after).count(),
+                    },
+                )
+            })
+            .collect();
+
+        RebalancePlan {
+            number_of_shards: planned.number_of_shards,
+            pods,
+            moved_shards,
+        }
+    }
+}
+
+fn owners(routing_table: &RoutingTable) -> BTreeMap<ShardId, &Pod> {
+    routing_table
+        .shard_assignments
+        .iter()
+        .flat_map(|(pod, shard_ids)| shard_ids.iter().map(move |shard_id| (*shard_id, pod)))
+        .collect()
+}
+
+impl Display for RebalancePlan {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let pods: Vec<String> = self
+            .pods
+            .iter()
+            .map(|(pod, movement)| {
+                format!(
+                    "{}: {} -> {} (+{}, -{})",
+                    pod,
+                    movement.shards_before,
+                    movement.shards_after,
+                    movement.assigned_shards,
+                    movement.revoked_shards
+                )
+            })
+            .collect();
+        write!(
+            f,
+            "{{ number_of_shards: {}, moved_shards: {}, pods: [{}] }}",
+            self.number_of_shards,
+            self.moved_shards,
+            pods.join(", ")
+        )
+    }
+}
+
+impl From<RebalancePlan> for golem::shardmanager::v1::RebalancePlan {
+    fn from(value: RebalancePlan) -> Self {
+        Self {
+            number_of_shards: value.number_of_shards as u32,
+            pods: value
+                .pods
+                .into_iter()
+                .map(
+                    |(pod, movement)| golem::shardmanager::v1::PodShardMovement {
+                        pod: Some(pod.into()),
+                        shards_before: movement.shards_before as u32,
+                        shards_after: movement.shards_after as u32,
+                        assigned_shards: movement.assigned_shards as u32,
+                        revoked_shards: movement.revoked_shards as u32,
+                    },
+                )
+                .collect(),
+            moved_shards: value.moved_shards as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::ShardId;
+
+    use crate::model::{Pod, RoutingTable};
+    use crate::planning::{ClusterChange, PodShardMovement, RebalancePlan};
+
+    fn pod(idx: usize) -> Pod {
+        Pod::new(format!("pod{}", idx), (9000 + idx) as u16)
+    }
+
+    fn balanced_routing_table(number_of_shards: usize, number_of_pods: usize) -> RoutingTable {
+        let mut routing_table = RoutingTable::new(number_of_shards);
+        for shard_id in 0..number_of_shards {
+            routing_table
+                .shard_assignments
+                .entry(pod(shard_id % number_of_pods))
+                .or_default()
+                .insert(ShardId::new(shard_id as i64));
+        }
+        routing_table
+    }
+
+    fn movement(
+        shards_before: usize,
+        shards_after: usize,
+        assigned_shards: usize,
+        revoked_shards: usize,
+    ) -> PodShardMovement {
+        PodShardMovement {
+            shards_before,
+            shards_after,
+            assigned_shards,
+            revoked_shards,
+        }
+    }
+
+    #[test]
+    fn empty_change_moves_nothing() {
+        let routing_table = balanced_routing_table(12, 3);
+        let plan = RebalancePlan::new(&routing_table, &ClusterChange::default(), 0.0);
+
+        assert_eq!(plan.moved_shards, 0);
+        assert_eq!(plan.pods.len(), 3);
+        assert!(plan.pods.values().all(|m| *m == movement(4, 4, 0, 0)));
+    }
+
+    #[test]
+    fn adding_a_pod() {
+        let routing_table = balanced_routing_table(12, 3);
+        let change = ClusterChange {
+            added_pods: vec![pod(3)],
+            ..ClusterChange::default()
+        };
+        let plan = RebalancePlan::new(&routing_table, &change, 0.0);
+
+        assert_eq!(plan.moved_shards, 3);
+        assert_eq!(plan.pods[&pod(3)], movement(0, 3, 3, 0));
+        assert_eq!(
+            plan.pods.values().map(|m| m.revoked_shards).sum::<usize>(),
+            3
+        );
+    }
+
+    #[test]
+    fn removing_a_pod() {
+        let routing_table = balanced_routing_table(12, 3);
+        let change = ClusterChange {
+            removed_pods: vec![pod(2)],
+            ..ClusterChange::default()
+        };
+        let plan = RebalancePlan::new(&routing_table, &change, 0.0);
+
+        assert_eq!(plan.moved_shards, 4);
+        assert_eq!(plan.pods[&pod(2)], movement(4, 0, 0, 4));
+        assert_eq!(plan.pods[&pod(0)].shards_after, 6);
+        assert_eq!(plan.pods[&pod(1)].shards_after, 6);
+    }
+
+    #[test]
+    fn changing_the_number_of_shards() {
+        let routing_table = balanced_routing_table(12, 3);
+        let change = ClusterChange {
+            number_of_shards: Some(18),
+            ..ClusterChange::default()
+        };
+        let plan = RebalancePlan::new(&routing_table, &change, 0.0);
+
+        assert_eq!(plan.number_of_shards, 18);
+        assert_eq!(plan.moved_shards, 6);
+        assert!(plan.pods.values().all(|m| *m == movement(4, 6, 2, 0)));
+    }
+}
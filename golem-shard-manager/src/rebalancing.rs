@@ -7,7 +7,7 @@ use tracing::trace;
 
 use golem_common::model::ShardId;
 
-use crate::model::{Assignments, Pod, RoutingTable, Unassignments};
+use crate::model::{AntiAffinityGroups, Assignments, Pod, RoutingTable, Unassignments};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Rebalance {
@@ -176,6 +176,99 @@ impl Rebalance {
         }
     }
 
+    /// Extends the plan to move apart the shards of the same anti-affinity group which would be
+    /// owned by the same pod. Each such shard is swapped with a shard of a pod not owning any shard
+    /// of the group, so the shard counts stay balanced. Groups having more shards than the number
+    /// of pods cannot be fully separated.
+    pub fn separate_anti_affinity_groups(
+        &mut self,
+        routing_table: &RoutingTable,
+        groups: &AntiAffinityGroups,
+    ) {
+        let mut planned = routing_table.clone();
+        planned.rebalance(self.clone());
+
+        for group in groups.groups() {
+            for shard_id in &group {
+                let Some(source) = owner(&planned, shard_id) else {
+                    continue;
+                };
+                let mut source_shards = planned.shard_assignments[&source].clone();
+                source_shards.remove(shard_id);
+                if source_shards.is_disjoint(&group) {
+                    continue;
+                }
+
+                let swap = planned
+                    .shard_assignments
+                    .iter()
+                    .filter(|(pod, shard_ids)| **pod != source && shard_ids.is_disjoint(&group))
+                    .find_map(|(pod, shard_ids)| {
+                        shard_ids
+                            .iter()
+                            .find(|&other| {
+                                let mut target_shards = shard_ids.clone();
+                                target_shards.remove(other);
+                                groups.allows(other, &source_shards)
+                                    && groups.allows(shard_id, &target_shards)
+                            })
+                            .map(|other| (pod.clone(), *other))
+                    });
+
+                match swap {
+                    Some((target, other)) => {
+                        trace!(
+                            "Swapping shard {} of {} with shard {} of {} for anti-affinity",
+                            shard_id,
+                            source,
+                            other,
+                            target
+                        );
+                        self.move_shard(routing_table, *shard_id, &source, &target);
+                        self.move_shard(routing_table, other, &target, &source);
+                        move_shard(&mut planned, *shard_id, &source, &target);
+                        move_shard(&mut planned, other, &target, &source);
+                    }
+                    None => {
+                        trace!(
+                            "No pod to move shard {} of {} to for anti-affinity",
+                            shard_id,
+                            source
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn move_shard(
+        &mut self,
+        routing_table: &RoutingTable,
+        shard_id: ShardId,
+        from: &Pod,
+        to: &Pod,
+    ) {
+        let owned_by = |pod: &Pod| {
+            routing_table
+                .shard_assignments
+                .get(pod)
+                .is_some_and(|shard_ids| shard_ids.contains(&shard_id))
+        };
+
+        self.assignments.unassign(from.clone(), shard_id);
+        if owned_by(from) {
+            self.unassignments.unassign(from.clone(), shard_id);
+        }
+        if owned_by(to) {
+            // Moving back to its current owner
+            if let Some(shard_ids) = self.unassignments.unassignments.get_mut(to) {
+                shard_ids.remove(&shard_id);
+            }
+        } else {
+            self.assignments.assign(to.clone(), shard_id);
+        }
+    }
+
     pub fn get_assignments(&self) -> &Assignments {
         &self.assignments
     }
@@ -236,6 +329,25 @@ impl Rebalance {
     }
 }
 
+fn owner(routing_table: &RoutingTable, shard_id: &ShardId) -> Option<Pod> {
+    routing_table
+        .shard_assignments
+        .iter()
+        .find(|(_, shard_ids)| shard_ids.contains(shard_id))
+        .map(|(pod, _)| pod.clone())
+}
+
+fn move_shard(routing_table: &mut RoutingTable, shard_id: ShardId, from: &Pod, to: &Pod) {
+    if let Some(shard_ids) = routing_table.shard_assignments.get_mut(from) {
+        shard_ids.remove(&shard_id);
+    }
+    routing_table
+        .shard_assignments
+        .entry(to.clone())
+        .or_default()
+        .insert(shard_id);
+}
+
 impl Display for Rebalance {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
@@ -250,11 +362,13 @@ impl Display for Rebalance {
 mod tests {
     use test_r::test;
 
+    use std::collections::BTreeSet;
+
     use tracing_test::traced_test;
 
-    use golem_common::model::ShardId;
+    use golem_common::model::{ComponentId, ShardId, WorkerId};
 
-    use crate::model::{Pod, RoutingTable};
+    use crate::model::{AntiAffinityGroups, Pod, RoutingTable};
     use crate::rebalancing::Rebalance;
 
     struct TestConfig {
@@ -328,6 +442,20 @@ mod tests {
         }
     }
 
+    fn anti_affinity_groups(groups: Vec<(&str, Vec<i64>)>) -> AntiAffinityGroups {
+        let mut result = AntiAffinityGroups::new();
+        for (key, shards) in groups {
+            for shard_id in shard_ids(shards) {
+                let worker_id = WorkerId {
+                    component_id: ComponentId::new_v4(),
+                    worker_name: format!("{key}-{shard_id}"),
+                };
+                result.add(key.to_string(), worker_id, shard_id);
+            }
+        }
+        result
+    }
+
     fn get_assigned_ids(rebalance: &Rebalance, pod: &Pod) -> Vec<ShardId> {
         let mut assigned_ids = rebalance
             .get_assignments()
@@ -723,4 +851,130 @@ mod tests {
 
         assert_eq!(rebalance.unassignments.unassignments.len(), 0);
     }
+
+    #[test]
+    #[traced_test]
+    fn anti_affinity_group_is_separated() {
+        let routing_table = new_routing_table(TestConfig {
+            number_of_shards: 4,
+            number_of_pods: 2,
+            initial_assignments: vec![(0, vec![0, 1]), (1, vec![2, 3])],
+        });
+        let groups = anti_affinity_groups(vec![("replicas", vec![0, 1])]);
+
+        let mut rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        rebalance.separate_anti_affinity_groups(&routing_table, &groups);
+
+        assert_assignments(&rebalance, vec![(0, vec![2]), (1, vec![0])]);
+        assert_unassignments(&rebalance, vec![(0, vec![0]), (1, vec![2])]);
+    }
+
+    #[test]
+    #[traced_test]
+    fn anti_affinity_group_is_separated_on_initial_assignment() {
+        let routing_table = new_routing_table(TestConfig {
+            number_of_shards: 4,
+            number_of_pods: 2,
+            initial_assignments: vec![],
+        });
+        let groups = anti_affinity_groups(vec![("replicas", vec![0, 2])]);
+
+        let mut rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        rebalance.separate_anti_affinity_groups(&routing_table, &groups);
+
+        assert_assignments(&rebalance, vec![(0, vec![1, 2]), (1, vec![0, 3])]);
+        assert!(rebalance.get_unassignments().is_empty());
+    }
+
+    #[test]
+    #[traced_test]
+    fn anti_affinity_group_larger_than_pod_count_is_kept() {
+        let routing_table = new_routing_table(TestConfig {
+            number_of_shards: 4,
+            number_of_pods: 2,
+            initial_assignments: vec![(0, vec![0, 1]), (1, vec![2, 3])],
+        });
+        let groups = anti_affinity_groups(vec![("replicas", vec![0, 1, 2])]);
+
+        let mut rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        rebalance.separate_anti_affinity_groups(&routing_table, &groups);
+
+        assert!(rebalance.is_empty());
+    }
+
+    fn worker(name: &str) -> WorkerId {
+        WorkerId {
+            component_id: ComponentId::new_v4(),
+            worker_name: name.to_string(),
+        }
+    }
+
+    #[test]
+    #[traced_test]
+    fn removed_workers_leave_their_anti_affinity_group() {
+        let routing_table = new_routing_table(TestConfig {
+            number_of_shards: 4,
+            number_of_pods: 2,
+            initial_assignments: vec![(0, vec![0, 1]), (1, vec![2, 3])],
+        });
+        let replica_1 = worker("replica-1");
+        let replica_2 = worker("replica-2");
+        let mut groups = AntiAffinityGroups::new();
+        assert!(groups.add("replicas".to_string(), replica_1.clone(), ShardId::new(0)));
+        assert!(groups.add("replicas".to_string(), replica_2.clone(), ShardId::new(1)));
+        assert!(!groups.add("replicas".to_string(), replica_2.clone(), ShardId::new(1)));
+
+        assert!(groups.remove(&replica_2));
+        assert!(!groups.remove(&replica_2));
+
+        let mut rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        rebalance.separate_anti_affinity_groups(&routing_table, &groups);
+
+        assert!(rebalance.is_empty());
+        assert_eq!(groups.groups().count(), 1);
+
+        assert!(groups.remove(&replica_1));
+        assert_eq!(groups, AntiAffinityGroups::new());
+    }
+
+    #[test]
+    #[traced_test]
+    fn shards_stay_in_the_group_until_their_last_worker_is_removed() {
+        let routing_table = new_routing_table(TestConfig {
+            number_of_shards: 4,
+            number_of_pods: 2,
+            initial_assignments: vec![(0, vec![0, 1]), (1, vec![2, 3])],
+        });
+        let replica_1 = worker("replica-1");
+        let replica_2 = worker("replica-2");
+        let mut groups = AntiAffinityGroups::new();
+        groups.add("replicas".to_string(), replica_1.clone(), ShardId::new(0));
+        groups.add("replicas".to_string(), replica_2, ShardId::new(0));
+        groups.add("replicas".to_string(), worker("replica-3"), ShardId::new(1));
+
+        assert!(groups.remove(&replica_1));
+
+        let mut rebalance = Rebalance::from_routing_table(&routing_table, 0.0);
+        rebalance.separate_anti_affinity_groups(&routing_table, &groups);
+
+        assert_assignments(&rebalance, vec![(0, vec![2]), (1, vec![0])]);
+        assert_unassignments(&rebalance, vec![(0, vec![0]), (1, vec![2])]);
+    }
+
+    #[test]
+    fn workers_move_between_anti_affinity_groups() {
+        let replica = worker("replica");
+        let mut groups = AntiAffinityGroups::new();
+        groups.add("group-1".to_string(), worker("other"), ShardId::new(1));
+        groups.add("group-1".to_string(), replica.clone(), ShardId::new(0));
+        groups.add("group-2".to_string(), replica.clone(), ShardId::new(0));
+
+        let pod_shards: BTreeSet<ShardId> = shard_ids(vec![1]).into_iter().collect();
+        assert!(groups.allows(&ShardId::new(0), &pod_shards));
+        assert!(groups.allows(&ShardId::new(2), &pod_shards));
+        assert_eq!(groups.groups().count(), 2);
+
+        groups.add("group-2".to_string(), worker("other-2"), ShardId::new(1));
+        assert!(!groups.allows(&ShardId::new(0), &pod_shards));
+    }
 }
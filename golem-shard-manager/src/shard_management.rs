@@ -14,16 +14,18 @@
 
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use async_rwlock::RwLock;
+use golem_common::model::{ShardId, WorkerId};
 use itertools::Itertools;
-use tokio::sync::{Mutex, Notify};
+use tokio::sync::{watch, Mutex, Notify};
 use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
 use crate::error::ShardManagerError;
 use crate::healthcheck::{get_unhealthy_pods, HealthCheck};
-use crate::model::{Pod, RoutingTable};
+use crate::model::{AntiAffinityGroups, Pod, RoutingTable};
 use crate::persistence::PersistenceService;
 use crate::rebalancing::Rebalance;
 use crate::worker_executor::{assign_shards, revoke_shards, WorkerExecutorService};
@@ -31,10 +33,13 @@ use crate::worker_executor::{assign_shards, revoke_shards, WorkerExecutorService
 #[derive(Clone)]
 pub struct ShardManagement {
     routing_table: Arc<RwLock<RoutingTable>>,
+    anti_affinity_groups: Arc<RwLock<AntiAffinityGroups>>,
+    epoch: Arc<watch::Sender<u64>>,
     change: Arc<Notify>,
     #[allow(dead_code)]
     worker_handle: Arc<WorkerHandle>, // Just kept here for abort on dropping
     updates: Arc<Mutex<ShardManagementChanges>>,
+    persistence_service: Arc<dyn PersistenceService + Send + Sync>,
 }
 
 impl ShardManagement {
@@ -47,6 +52,7 @@ impl ShardManagement {
         threshold: f64,
     ) -> Result<Self, ShardManagerError> {
         let routing_table = persistence_service.read().await.unwrap();
+        let anti_affinity_groups = persistence_service.read_anti_affinity_groups().await?;
 
         info!("Initial healthcheck started");
 
@@ -64,15 +70,22 @@ impl ShardManagement {
             unhealthy_pods,
         )));
         let routing_table = Arc::new(RwLock::new(routing_table));
+        let anti_affinity_groups = Arc::new(RwLock::new(anti_affinity_groups));
+        let epoch = Arc::new(watch::Sender::new(initial_epoch()));
 
         let worker_handle = {
             let change = change.clone();
             let updates = updates.clone();
             let routing_table = routing_table.clone();
+            let anti_affinity_groups = anti_affinity_groups.clone();
+            let epoch = epoch.clone();
+            let persistence_service = persistence_service.clone();
 
             Arc::new(WorkerHandle::new(tokio::spawn(async move {
                 Self::worker(
                     routing_table,
+                    anti_affinity_groups,
+                    epoch,
                     change,
                     updates,
                     persistence_service,
@@ -87,9 +100,12 @@ impl ShardManagement {
 
         Ok(ShardManagement {
             routing_table,
+            anti_affinity_groups,
+            epoch,
             change,
             worker_handle,
             updates,
+            persistence_service,
         })
     }
 
@@ -107,13 +123,64 @@ impl ShardManagement {
         self.change.notify_one();
     }
 
+    /// Adds a worker to an anti-affinity group, moving its shard away from the other shards of
+    /// the group on the next rebalance
+    pub async fn add_anti_affinity(
+        &self,
+        key: String,
+        worker_id: WorkerId,
+    ) -> Result<(), ShardManagerError> {
+        let number_of_shards = self.routing_table.read().await.number_of_shards;
+        let shard_id = ShardId::from_worker_id(&worker_id, number_of_shards);
+        let mut anti_affinity_groups = self.anti_affinity_groups.write().await;
+        if anti_affinity_groups.add(key.clone(), worker_id.clone(), shard_id) {
+            debug!(key, worker_id=%worker_id, shard_id=%shard_id, "Adding worker to anti-affinity group");
+            self.persistence_service
+                .write_anti_affinity_groups(&anti_affinity_groups)
+                .await?;
+            self.change.notify_one();
+        }
+        Ok(())
+    }
+
+    /// Removes a deleted worker from its anti-affinity group. Its shard is not moved back, it
+    /// is just not kept apart from the group's other shards on the next rebalances.
+    pub async fn remove_anti_affinity(
+        &self,
+        worker_id: &WorkerId,
+    ) -> Result<(), ShardManagerError> {
+        let mut anti_affinity_groups = self.anti_affinity_groups.write().await;
+        if anti_affinity_groups.remove(worker_id) {
+            debug!(worker_id=%worker_id, "Removing worker from anti-affinity group");
+            self.persistence_service
+                .write_anti_affinity_groups(&anti_affinity_groups)
+                .await?;
+        }
+        Ok(())
+    }
+
     /// Gets the current snapshot of the routing table
     pub async fn current_snapshot(&self) -> RoutingTable {
         self.routing_table.read().await.clone()
     }
 
+    /// Gets the current snapshot of the routing table together with its epoch.
+    ///
+    /// The epoch is only bumped while the routing table is write-locked, so the pair is consistent.
+    pub async fn current_snapshot_with_epoch(&self) -> (RoutingTable, u64) {
+        let routing_table = self.routing_table.read().await;
+        (routing_table.clone(), *self.epoch.borrow())
+    }
+
+    /// Subscribes to routing table epoch changes. The receiver starts with the current epoch.
+    pub fn subscribe_epoch(&self) -> watch::Receiver<u64> {
+        self.epoch.subscribe()
+    }
+
     async fn worker(
         routing_table: Arc<RwLock<RoutingTable>>,
+        anti_affinity_groups: Arc<RwLock<AntiAffinityGroups>>,
+        epoch: Arc<watch::Sender<u64>>,
         change: Arc<Notify>,
         updates: Arc<Mutex<ShardManagementChanges>>,
         persistence_service: Arc<dyn PersistenceService + Send + Sync>,
@@ -137,6 +204,7 @@ impl ShardManagement {
             // but the rebalance plan is NOT applied yet. The lock is then release for apply.
             let mut rebalance = {
                 let mut current_routing_table = routing_table.write().await;
+                let mut changed = !removed_pods.is_empty();
 
                 for pod in removed_pods {
                     current_routing_table.remove_pod(&pod);
@@ -152,11 +220,16 @@ impl ShardManagement {
                     } else {
                         // New pod, adding with empty assignment
                         current_routing_table.add_pod(&pod);
+                        changed = true;
                         info!(pod= %pod, "Pod added");
                     }
                 }
                 let mut rebalance =
                     Rebalance::from_routing_table(&current_routing_table, threshold);
+                rebalance.separate_anti_affinity_groups(
+                    &current_routing_table,
+                    &*anti_affinity_groups.read().await,
+                );
 
                 for pod in send_full_assignment {
                     let assignments = current_routing_table.get_shards(&pod).unwrap_or_default();
@@ -168,13 +241,24 @@ impl ShardManagement {
                     .await
                     .expect("Failed to persist routing table after pod changes");
 
+                if changed {
+                    Self::bump_epoch(&epoch);
+                }
+
                 rebalance
             };
 
             debug!(rebalance=%rebalance, "Applying rebalance plan");
             Self::execute_rebalance(worker_executors.clone(), &mut rebalance).await;
 
-            routing_table.write().await.rebalance(rebalance);
+            {
+                let mut current_routing_table = routing_table.write().await;
+                let changed = !rebalance.is_empty();
+                current_routing_table.rebalance(rebalance);
+                if changed {
+                    Self::bump_epoch(&epoch);
+                }
+            }
             persistence_service
                 .write(&routing_table.read().await.clone())
                 .await
@@ -182,6 +266,11 @@ impl ShardManagement {
         }
     }
 
+    fn bump_epoch(epoch: &watch::Sender<u64>) {
+        epoch.send_modify(|epoch| *epoch += 1);
+        debug!(epoch = *epoch.borrow(), "Routing table epoch changed");
+    }
+
     async fn execute_rebalance(
         worker_executors: Arc<dyn WorkerExecutorService + Send + Sync>,
         rebalance: &mut Rebalance,
@@ -214,6 +303,14 @@ impl ShardManagement {
     }
 }
 
+/// Epochs start from the startup time so that they keep increasing across shard manager restarts.
+fn initial_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 #[derive(Debug)]
 struct ShardManagementChanges {
     new_pods: HashSet<Pod>,
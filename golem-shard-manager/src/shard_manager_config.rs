@@ -18,7 +18,8 @@ use std::time::Duration;
 use serde::{Deserialize, Serialize};
 
 use golem_common::config::{
-    ConfigExample, ConfigLoader, HasConfigExamples, RedisConfig, RetryConfig,
+    ConfigExample, ConfigLoader, DbPostgresConfig, GrpcCompression, HasConfigExamples, RedisConfig,
+    RetryConfig,
 };
 use golem_common::tracing::TracingConfig;
 
@@ -31,7 +32,10 @@ pub struct ShardManagerConfig {
     pub redis: RedisConfig,
     pub worker_executors: WorkerExecutorServiceConfig,
     pub health_check: HealthCheckConfig,
+    pub persistence: PersistenceConfig,
+    pub leader_election: LeaderElectionConfig,
     pub http_port: u16,
+    pub grpc_compression: GrpcCompression,
     pub number_of_shards: usize,
     pub rebalance_threshold: f64,
 }
@@ -43,7 +47,10 @@ impl Default for ShardManagerConfig {
             redis: RedisConfig::default(),
             worker_executors: WorkerExecutorServiceConfig::default(),
             health_check: HealthCheckConfig::default(),
+            persistence: PersistenceConfig::default(),
+            leader_election: LeaderElectionConfig::default(),
             http_port: 8081,
+            grpc_compression: GrpcCompression::default(),
             number_of_shards: 1024,
             rebalance_threshold: 0.1,
         }
@@ -52,18 +59,39 @@ impl Default for ShardManagerConfig {
 
 impl HasConfigExamples<ShardManagerConfig> for ShardManagerConfig {
     fn examples() -> Vec<ConfigExample<ShardManagerConfig>> {
-        vec![(
-            "with k8s healthcheck",
-            Self {
-                health_check: HealthCheckConfig {
-                    delay: Duration::from_secs(1),
-                    mode: K8s(HealthCheckK8sConfig {
-                        namespace: "namespace".to_string(),
+        vec![
+            (
+                "with k8s healthcheck",
+                Self {
+                    health_check: HealthCheckConfig {
+                        delay: Duration::from_secs(1),
+                        mode: K8s(HealthCheckK8sConfig {
+                            namespace: "namespace".to_string(),
+                        }),
+                    },
+                    ..Self::default()
+                },
+            ),
+            (
+                "with postgres persistence and leader election",
+                Self {
+                    persistence: PersistenceConfig::Postgres(DbPostgresConfig {
+                        host: "localhost".to_string(),
+                        database: "postgres".to_string(),
+                        username: "postgres".to_string(),
+                        password: "postgres".to_string(),
+                        port: 5432,
+                        max_connections: 10,
+                        schema: None,
                     }),
+                    leader_election: LeaderElectionConfig {
+                        enabled: true,
+                        ..LeaderElectionConfig::default()
+                    },
+                    ..Self::default()
                 },
-                ..Self::default()
-            },
-        )]
+            ),
+        ]
     }
 }
 
@@ -124,6 +152,50 @@ pub struct HealthCheckK8sConfig {
     pub namespace: String,
 }
 
+/// Where the routing table is persisted, and the leadership lease is stored when leader
+/// election is enabled
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum PersistenceConfig {
+    /// Uses the Redis configured in the `redis` section
+    Redis(Empty),
+    Postgres(DbPostgresConfig),
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self::Redis(Empty {})
+    }
+}
+
+/// Leader election lets multiple shard manager instances run against the same persistence,
+/// with only the leader serving requests and managing the shards. The other instances wait
+/// until the leader's lease expires.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LeaderElectionConfig {
+    pub enabled: bool,
+    /// How long the leadership is kept without being renewed
+    #[serde(with = "humantime_serde")]
+    pub lease_duration: Duration,
+    #[serde(with = "humantime_serde")]
+    pub renew_interval: Duration,
+    /// How long before the lease would expire the leader steps down if it could not renew it,
+    /// so it stops managing the shards before another instance can take over
+    #[serde(with = "humantime_serde")]
+    pub safety_margin: Duration,
+}
+
+impl Default for LeaderElectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lease_duration: Duration::from_secs(15),
+            renew_interval: Duration::from_secs(5),
+            safety_margin: Duration::from_secs(5),
+        }
+    }
+}
+
 pub fn make_config_loader() -> ConfigLoader<ShardManagerConfig> {
     ConfigLoader::new_with_examples(Path::new("config/shard-manager.toml"))
 }
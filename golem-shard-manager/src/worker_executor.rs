@@ -18,7 +18,6 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use tokio::time::error::Elapsed;
 use tokio::time::timeout;
-use tonic::codec::CompressionEncoding;
 use tonic::transport::Channel;
 use tonic::Response;
 use tonic_health::pb::health_check_response::ServingStatus;
@@ -29,8 +28,10 @@ use tracing::info;
 use golem_api_grpc::proto::golem;
 use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
 use golem_common::client::{GrpcClientConfig, MultiTargetGrpcClient};
+use golem_common::config::GrpcCompression;
 use golem_common::model::ShardId;
 use golem_common::retries::with_retriable_errors;
+use golem_common::with_grpc_compression;
 
 use crate::error::{HealthCheckError, ShardManagerError};
 use crate::model::{pod_shard_assignments_to_string, Assignments, Pod, Unassignments};
@@ -180,12 +181,10 @@ impl WorkerExecutorService for WorkerExecutorServiceDefault {
 }
 
 impl WorkerExecutorServiceDefault {
-    pub fn new(config: WorkerExecutorServiceConfig) -> Self {
+    pub fn new(config: WorkerExecutorServiceConfig, grpc_compression: GrpcCompression) -> Self {
         let client = MultiTargetGrpcClient::new(
-            |channel| {
-                WorkerExecutorClient::new(channel)
-                    .send_compressed(CompressionEncoding::Gzip)
-                    .accept_compressed(CompressionEncoding::Gzip)
+            move |channel| {
+                with_grpc_compression!(WorkerExecutorClient::new(channel), grpc_compression)
             },
             GrpcClientConfig {
                 retries_on_unavailable: config.retries.clone(),
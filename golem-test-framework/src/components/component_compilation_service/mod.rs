@@ -19,7 +19,7 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use golem_api_grpc::proto::golem::componentcompilation::v1::{
-    component_compilation_response, ComponentCompilationRequest,
+    component_compilation_response, CompilationPriority, ComponentCompilationRequest,
 };
 use tonic::codec::CompressionEncoding;
 use tonic::transport::Channel;
@@ -49,6 +49,7 @@ pub trait ComponentCompilationService {
                 component_id: Some(component_id.clone().into()),
                 component_version,
                 ifs_data: vec![],
+                priority: CompilationPriority::Interactive as i32,
             })
             .await
             .expect("Failed to enqueue component compilation")
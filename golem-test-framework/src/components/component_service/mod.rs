@@ -154,6 +154,7 @@ pub trait ComponentService {
                 project_id: None,
                 component_name: name.to_string(),
                 component_type: Some(component_type as i32),
+                function_aliases: None,
             })),
         }];
 
@@ -233,6 +234,7 @@ pub trait ComponentService {
                 UpdateComponentRequestHeader {
                     component_id: Some(component_id.clone().into()),
                     component_type: Some(component_type as i32),
+                    function_aliases: None,
                 },
             )),
         }];
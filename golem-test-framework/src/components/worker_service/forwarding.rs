@@ -22,8 +22,11 @@ use async_trait::async_trait;
 use golem_api_grpc::proto::golem::common::{Empty, ResourceLimits};
 use golem_api_grpc::proto::golem::worker::v1::worker_service_client::WorkerServiceClient;
 use golem_api_grpc::proto::golem::worker::v1::{
-    ConnectWorkerRequest, DeleteWorkerRequest, DeleteWorkerResponse, GetOplogRequest,
-    GetOplogResponse, GetOplogSuccessResponse, GetWorkerMetadataRequest, GetWorkerMetadataResponse,
+    AbortRunningInvocationRequest, AbortRunningInvocationResponse,
+    AbortRunningInvocationSuccessResponse, CancelInvocationRequest, CancelInvocationResponse,
+    CancelInvocationSuccessResponse, ConnectWorkerRequest, DeleteWorkerRequest,
+    DeleteWorkerResponse, DeleteWorkerSuccessResponse, GetOplogRequest, GetOplogResponse,
+    GetOplogSuccessResponse, GetWorkerMetadataRequest, GetWorkerMetadataResponse,
     InterruptWorkerRequest, InterruptWorkerResponse, InvokeAndAwaitJsonRequest,
     InvokeAndAwaitJsonResponse, InvokeAndAwaitRequest, InvokeAndAwaitResponse, InvokeJsonRequest,
     InvokeRequest, InvokeResponse, LaunchNewWorkerRequest, LaunchNewWorkerResponse,
@@ -100,6 +103,7 @@ impl WorkerService for ForwardingWorkerService {
                     available_fuel: i64::MAX,
                     max_memory_per_worker: i64::MAX,
                 }),
+                ttl_millis: request.ttl_millis,
             })
             .await?
             .into_inner();
@@ -146,6 +150,8 @@ impl WorkerService for ForwardingWorkerService {
                     }
                     .into(),
                 ),
+                cascade: request.cascade,
+                dry_run: request.dry_run,
             })
             .await?
             .into_inner();
@@ -154,10 +160,12 @@ impl WorkerService for ForwardingWorkerService {
             None => Err(anyhow!(
                 "No response from golem-worker-executor delete-worker call"
             )),
-            Some(workerexecutor::v1::delete_worker_response::Result::Success(_)) => {
+            Some(workerexecutor::v1::delete_worker_response::Result::Success(success)) => {
                 Ok(DeleteWorkerResponse {
                     result: Some(worker::v1::delete_worker_response::Result::Success(
-                        Empty {},
+                        DeleteWorkerSuccessResponse {
+                            removed: success.removed,
+                        },
                     )),
                 })
             }
@@ -217,6 +225,7 @@ impl WorkerService for ForwardingWorkerService {
     }
 
     async fn invoke(&self, request: InvokeRequest) -> crate::Result<InvokeResponse> {
+        let invoke_parameters = request.invoke_parameters.unwrap_or_default();
         let result = self
             .worker_executor
             .client()
@@ -225,10 +234,7 @@ impl WorkerService for ForwardingWorkerService {
                 worker_id: request.worker_id,
                 idempotency_key: request.idempotency_key,
                 name: request.function,
-                input: request
-                    .invoke_parameters
-                    .map(|p| p.params.clone())
-                    .unwrap_or_default(),
+                input: invoke_parameters.params,
                 account_id: Some(
                     AccountId {
                         value: "test-account".to_string(),
@@ -240,6 +246,7 @@ impl WorkerService for ForwardingWorkerService {
                     max_memory_per_worker: i64::MAX,
                 }),
                 context: request.context,
+                blob_input: invoke_parameters.blob_params,
             })
             .await?
             .into_inner();
@@ -267,6 +274,7 @@ impl WorkerService for ForwardingWorkerService {
         &self,
         request: InvokeAndAwaitRequest,
     ) -> crate::Result<InvokeAndAwaitResponse> {
+        let invoke_parameters = request.invoke_parameters.unwrap_or_default();
         let result = self
             .worker_executor
             .client()
@@ -275,10 +283,7 @@ impl WorkerService for ForwardingWorkerService {
                 worker_id: request.worker_id,
                 idempotency_key: request.idempotency_key,
                 name: request.function,
-                input: request
-                    .invoke_parameters
-                    .map(|p| p.params.clone())
-                    .unwrap_or_default(),
+                input: invoke_parameters.params,
                 account_id: Some(
                     AccountId {
                         value: "test-account".to_string(),
@@ -290,6 +295,7 @@ impl WorkerService for ForwardingWorkerService {
                     max_memory_per_worker: i64::MAX,
                 }),
                 context: request.context,
+                blob_input: invoke_parameters.blob_params,
             })
             .await?
             .into_inner();
@@ -350,6 +356,7 @@ impl WorkerService for ForwardingWorkerService {
                     available_fuel: i64::MAX,
                     max_memory_per_worker: i64::MAX,
                 }),
+                options: request.options,
             })
             .await?
             .into_inner())
@@ -371,6 +378,7 @@ impl WorkerService for ForwardingWorkerService {
                     }
                     .into(),
                 ),
+                idempotency_key: request.idempotency_key,
             })
             .await?
             .into_inner();
@@ -415,6 +423,7 @@ impl WorkerService for ForwardingWorkerService {
                     }
                     .into(),
                 ),
+                idempotency_key: request.idempotency_key,
             })
             .await?
             .into_inner();
@@ -442,6 +451,100 @@ impl WorkerService for ForwardingWorkerService {
         }
     }
 
+    async fn abort_running_invocation(
+        &self,
+        request: AbortRunningInvocationRequest,
+    ) -> crate::Result<AbortRunningInvocationResponse> {
+        let result = self
+            .worker_executor
+            .client()
+            .await?
+            .abort_running_invocation(workerexecutor::v1::AbortRunningInvocationRequest {
+                worker_id: request.worker_id,
+                idempotency_key: request.idempotency_key,
+                account_id: Some(
+                    AccountId {
+                        value: "test-account".to_string(),
+                    }
+                    .into(),
+                ),
+            })
+            .await?
+            .into_inner();
+
+        match result.result {
+            None => Err(anyhow!(
+                "No response from golem-worker-executor abort-running-invocation call"
+            )),
+            Some(workerexecutor::v1::abort_running_invocation_response::Result::Success(
+                success,
+            )) => Ok(AbortRunningInvocationResponse {
+                result: Some(
+                    worker::v1::abort_running_invocation_response::Result::Success(
+                        AbortRunningInvocationSuccessResponse {
+                            aborted: success.aborted,
+                        },
+                    ),
+                ),
+            }),
+            Some(workerexecutor::v1::abort_running_invocation_response::Result::Failure(error)) => {
+                Ok(AbortRunningInvocationResponse {
+                    result: Some(
+                        worker::v1::abort_running_invocation_response::Result::Error(WorkerError {
+                            error: Some(worker::v1::worker_error::Error::InternalError(error)),
+                        }),
+                    ),
+                })
+            }
+        }
+    }
+
+    async fn cancel_invocation(
+        &self,
+        request: CancelInvocationRequest,
+    ) -> crate::Result<CancelInvocationResponse> {
+        let result = self
+            .worker_executor
+            .client()
+            .await?
+            .cancel_invocation(workerexecutor::v1::CancelInvocationRequest {
+                worker_id: request.worker_id,
+                idempotency_key: request.idempotency_key,
+                account_id: Some(
+                    AccountId {
+                        value: "test-account".to_string(),
+                    }
+                    .into(),
+                ),
+            })
+            .await?
+            .into_inner();
+
+        match result.result {
+            None => Err(anyhow!(
+                "No response from golem-worker-executor cancel-invocation call"
+            )),
+            Some(workerexecutor::v1::cancel_invocation_response::Result::Success(success)) => {
+                Ok(CancelInvocationResponse {
+                    result: Some(worker::v1::cancel_invocation_response::Result::Success(
+                        CancelInvocationSuccessResponse {
+                            canceled: success.canceled,
+                        },
+                    )),
+                })
+            }
+            Some(workerexecutor::v1::cancel_invocation_response::Result::Failure(error)) => {
+                Ok(CancelInvocationResponse {
+                    result: Some(worker::v1::cancel_invocation_response::Result::Error(
+                        WorkerError {
+                            error: Some(worker::v1::worker_error::Error::InternalError(error)),
+                        },
+                    )),
+                })
+            }
+        }
+    }
+
     async fn update_worker(
         &self,
         request: UpdateWorkerRequest,
@@ -503,6 +606,7 @@ impl WorkerService for ForwardingWorkerService {
                 from_oplog_index: request.from_oplog_index,
                 cursor: request.cursor,
                 count: request.count,
+                query: request.query,
             })
             .await?
             .into_inner();
@@ -519,6 +623,7 @@ impl WorkerService for ForwardingWorkerService {
                             next: oplog.next,
                             first_index_in_chunk: oplog.first_index_in_chunk,
                             last_index: oplog.last_index,
+                            indices: oplog.indices,
                         },
                     )),
                 })
@@ -24,8 +24,9 @@ use tracing::Level;
 
 use golem_api_grpc::proto::golem::worker::v1::worker_service_client::WorkerServiceClient;
 use golem_api_grpc::proto::golem::worker::v1::{
-    ConnectWorkerRequest, DeleteWorkerRequest, DeleteWorkerResponse, GetOplogRequest,
-    GetOplogResponse, GetWorkerMetadataRequest, GetWorkerMetadataResponse,
+    AbortRunningInvocationRequest, AbortRunningInvocationResponse, CancelInvocationRequest,
+    CancelInvocationResponse, ConnectWorkerRequest, DeleteWorkerRequest, DeleteWorkerResponse,
+    GetOplogRequest, GetOplogResponse, GetWorkerMetadataRequest, GetWorkerMetadataResponse,
     GetWorkersMetadataRequest, GetWorkersMetadataResponse, InterruptWorkerRequest,
     InterruptWorkerResponse, InvokeAndAwaitJsonRequest, InvokeAndAwaitJsonResponse,
     InvokeAndAwaitRequest, InvokeAndAwaitResponse, InvokeJsonRequest, InvokeRequest,
@@ -173,6 +174,30 @@ pub trait WorkerService {
             .into_inner())
     }
 
+    async fn abort_running_invocation(
+        &self,
+        request: AbortRunningInvocationRequest,
+    ) -> crate::Result<AbortRunningInvocationResponse> {
+        Ok(self
+            .client()
+            .await?
+            .abort_running_invocation(request)
+            .await?
+            .into_inner())
+    }
+
+    async fn cancel_invocation(
+        &self,
+        request: CancelInvocationRequest,
+    ) -> crate::Result<CancelInvocationResponse> {
+        Ok(self
+            .client()
+            .await?
+            .cancel_invocation(request)
+            .await?
+            .into_inner())
+    }
+
     async fn update_worker(
         &self,
         request: UpdateWorkerRequest,
@@ -20,17 +20,20 @@ use async_trait::async_trait;
 use golem_api_grpc::proto::golem::worker::update_record::Update;
 use golem_api_grpc::proto::golem::worker::v1::worker_error::Error;
 use golem_api_grpc::proto::golem::worker::v1::{
-    get_oplog_response, get_worker_metadata_response, get_workers_metadata_response,
-    interrupt_worker_response, invoke_and_await_json_response, invoke_and_await_response,
-    invoke_response, launch_new_worker_response, resume_worker_response, update_worker_response,
-    worker_execution_error, ConnectWorkerRequest, DeleteWorkerRequest, GetOplogRequest,
-    GetWorkerMetadataRequest, GetWorkersMetadataRequest, GetWorkersMetadataSuccessResponse,
-    InterruptWorkerRequest, InterruptWorkerResponse, InvokeAndAwaitJsonRequest,
-    InvokeAndAwaitRequest, InvokeRequest, LaunchNewWorkerRequest, ResumeWorkerRequest,
-    UpdateWorkerRequest, UpdateWorkerResponse, WorkerError, WorkerExecutionError,
+    abort_running_invocation_response, cancel_invocation_response, get_oplog_response,
+    get_worker_metadata_response, get_workers_metadata_response, interrupt_worker_response,
+    invoke_and_await_json_response, invoke_and_await_response, invoke_response,
+    launch_new_worker_response, resume_worker_response, update_worker_response,
+    worker_execution_error, AbortRunningInvocationRequest, AbortRunningInvocationResponse,
+    CancelInvocationRequest, CancelInvocationResponse, ConnectWorkerRequest, DeleteWorkerRequest,
+    GetOplogRequest, GetWorkerMetadataRequest, GetWorkersMetadataRequest,
+    GetWorkersMetadataSuccessResponse, InterruptWorkerRequest, InterruptWorkerResponse,
+    InvokeAndAwaitJsonRequest, InvokeAndAwaitRequest, InvokeRequest, LaunchNewWorkerRequest,
+    ResumeWorkerRequest, UpdateWorkerRequest, UpdateWorkerResponse, WorkerError,
+    WorkerExecutionError,
 };
 use golem_api_grpc::proto::golem::worker::{
-    log_event, InvokeParameters, LogEvent, StdErrLog, StdOutLog, UpdateMode,
+    log_event, InvocationContext, InvokeParameters, LogEvent, StdErrLog, StdOutLog, UpdateMode,
 };
 use golem_common::model::oplog::{
     OplogIndex, TimestampedUpdateDescription, UpdateDescription, WorkerResourceId,
@@ -45,6 +48,7 @@ use golem_common::model::{
 use golem_wasm_rpc::Value;
 use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
 use tokio::select;
 use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::sync::oneshot::Sender;
@@ -133,6 +137,14 @@ pub trait TestDsl {
         function_name: &str,
         params: Vec<Value>,
     ) -> crate::Result<Result<Vec<Value>, Error>>;
+    async fn invoke_and_await_with_timeout(
+        &self,
+        worker_id: impl Into<TargetWorkerId> + Send + Sync,
+        idempotency_key: &IdempotencyKey,
+        function_name: &str,
+        params: Vec<Value>,
+        timeout: Duration,
+    ) -> crate::Result<Result<Vec<Value>, Error>>;
     async fn invoke_and_await_json(
         &self,
         worker_id: impl Into<TargetWorkerId> + Send + Sync,
@@ -154,6 +166,26 @@ pub trait TestDsl {
     async fn log_output(&self, worker_id: &WorkerId);
     async fn resume(&self, worker_id: &WorkerId) -> crate::Result<()>;
     async fn interrupt(&self, worker_id: &WorkerId) -> crate::Result<()>;
+    async fn abort_running_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+    ) -> crate::Result<bool>;
+    async fn cancel_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+    ) -> crate::Result<bool>;
+    async fn hold_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+    ) -> crate::Result<()>;
+    async fn release_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+    ) -> crate::Result<()>;
     async fn simulated_crash(&self, worker_id: &WorkerId) -> crate::Result<()>;
     async fn auto_update_worker(
         &self,
@@ -267,6 +299,8 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                 name: name.to_string(),
                 args,
                 env,
+                ttl_millis: None,
+                anti_affinity_key: None,
             })
             .await?;
 
@@ -336,6 +370,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                 cursor: Some(cursor.into()),
                 count,
                 precise,
+                projection: None,
             })
             .await?;
         match response.result {
@@ -357,6 +392,8 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
             .worker_service()
             .delete_worker(DeleteWorkerRequest {
                 worker_id: Some(worker_id.clone().into()),
+                cascade: false,
+                dry_run: false,
             })
             .await?;
         Ok(())
@@ -377,6 +414,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                 function: function_name.to_string(),
                 invoke_parameters: Some(InvokeParameters {
                     params: params.into_iter().map(|v| v.into()).collect(),
+                    blob_params: vec![],
                 }),
                 context: None,
             })
@@ -410,6 +448,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                 function: function_name.to_string(),
                 invoke_parameters: Some(InvokeParameters {
                     params: params.into_iter().map(|v| v.into()).collect(),
+                    blob_params: vec![],
                 }),
                 context: None,
             })
@@ -477,35 +516,41 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
         function_name: &str,
         params: Vec<Value>,
     ) -> crate::Result<Result<Vec<Value>, Error>> {
-        let target_worker_id: TargetWorkerId = worker_id.into();
-        let invoke_response = self
-            .worker_service()
-            .invoke_and_await(InvokeAndAwaitRequest {
-                worker_id: Some(target_worker_id.into()),
-                idempotency_key: Some(idempotency_key.clone().into()),
-                function: function_name.to_string(),
-                invoke_parameters: Some(InvokeParameters {
-                    params: params.into_iter().map(|v| v.into()).collect(),
-                }),
-                context: None,
-            })
-            .await?;
+        invoke_and_await_with_context(
+            self,
+            worker_id.into(),
+            idempotency_key,
+            function_name,
+            params,
+            None,
+        )
+        .await
+    }
 
-        match invoke_response.result {
-            None => Err(anyhow!("No response from invoke_and_await")),
-            Some(invoke_and_await_response::Result::Success(response)) => Ok(Ok(response
-                .result
-                .into_iter()
-                .map(|v| v.try_into())
-                .collect::<Result<Vec<Value>, String>>()
-                .map_err(|err| anyhow!("Invocation result had unexpected format: {err}"))?)),
-            Some(invoke_and_await_response::Result::Error(WorkerError { error: Some(error) })) => {
-                Ok(Err(error))
-            }
-            Some(invoke_and_await_response::Result::Error(_)) => {
-                Err(anyhow!("Empty error response from invoke_and_await"))
-            }
-        }
+    async fn invoke_and_await_with_timeout(
+        &self,
+        worker_id: impl Into<TargetWorkerId> + Send + Sync,
+        idempotency_key: &IdempotencyKey,
+        function_name: &str,
+        params: Vec<Value>,
+        timeout: Duration,
+    ) -> crate::Result<Result<Vec<Value>, Error>> {
+        invoke_and_await_with_context(
+            self,
+            worker_id.into(),
+            idempotency_key,
+            function_name,
+            params,
+            Some(InvocationContext {
+                parent: None,
+                args: vec![],
+                env: HashMap::new(),
+                caller: None,
+                timeout_millis: Some(timeout.as_millis() as u64),
+                map_result: false,
+            }),
+        )
+        .await
     }
 
     async fn invoke_and_await_json(
@@ -524,6 +569,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                 function: function_name.to_string(),
                 invoke_parameters: params,
                 context: None,
+                transform: false,
             })
             .await?;
 
@@ -550,6 +596,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
             let mut response = cloned_service
                 .connect_worker(ConnectWorkerRequest {
                     worker_id: Some(worker_id.clone().into()),
+                    options: None,
                 })
                 .await
                 .expect("Failed to connect worker");
@@ -579,6 +626,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                 let mut response = cloned_service
                     .connect_worker(ConnectWorkerRequest {
                         worker_id: Some(worker_id.clone().into()),
+                        options: None,
                     })
                     .await
                     .expect("Failed to connect worker");
@@ -625,6 +673,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
             let mut response = cloned_service
                 .connect_worker(ConnectWorkerRequest {
                     worker_id: Some(worker_id.clone().into()),
+                    options: None,
                 })
                 .await
                 .expect("Failed to connect to worker");
@@ -648,6 +697,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
             let mut response = cloned_service
                 .connect_worker(ConnectWorkerRequest {
                     worker_id: Some(worker_id.clone().into()),
+                    options: None,
                 })
                 .await
                 .expect("Failed to connect worker");
@@ -663,6 +713,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
             .worker_service()
             .resume_worker(ResumeWorkerRequest {
                 worker_id: Some(worker_id.clone().into()),
+                idempotency_key: None,
             })
             .await?;
 
@@ -681,6 +732,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
             .interrupt_worker(InterruptWorkerRequest {
                 worker_id: Some(worker_id.clone().into()),
                 recover_immediately: false,
+                idempotency_key: None,
             })
             .await?;
 
@@ -695,12 +747,108 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
         }
     }
 
+    async fn abort_running_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+    ) -> crate::Result<bool> {
+        let response = self
+            .worker_service()
+            .abort_running_invocation(AbortRunningInvocationRequest {
+                worker_id: Some(worker_id.clone().into()),
+                idempotency_key: Some(idempotency_key.clone().into()),
+            })
+            .await?;
+
+        match response {
+            AbortRunningInvocationResponse {
+                result: Some(abort_running_invocation_response::Result::Success(success)),
+            } => Ok(success.aborted),
+            AbortRunningInvocationResponse {
+                result: Some(abort_running_invocation_response::Result::Error(error)),
+            } => Err(anyhow!("Failed to abort invocation: {error:?}")),
+            _ => Err(anyhow!("Failed to abort invocation: unknown error")),
+        }
+    }
+
+    async fn cancel_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+    ) -> crate::Result<bool> {
+        let response = self
+            .worker_service()
+            .cancel_invocation(CancelInvocationRequest {
+                worker_id: Some(worker_id.clone().into()),
+                idempotency_key: Some(idempotency_key.clone().into()),
+            })
+            .await?;
+
+        match response {
+            CancelInvocationResponse {
+                result: Some(cancel_invocation_response::Result::Success(success)),
+            } => Ok(success.canceled),
+            CancelInvocationResponse {
+                result: Some(cancel_invocation_response::Result::Error(error)),
+            } => Err(anyhow!("Failed to cancel invocation: {error:?}")),
+            _ => Err(anyhow!("Failed to cancel invocation: unknown error")),
+        }
+    }
+
+    async fn hold_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+    ) -> crate::Result<()> {
+        let response = self
+            .worker_service()
+            .interrupt_worker(InterruptWorkerRequest {
+                worker_id: Some(worker_id.clone().into()),
+                recover_immediately: false,
+                idempotency_key: Some(idempotency_key.clone().into()),
+            })
+            .await?;
+
+        match response {
+            InterruptWorkerResponse {
+                result: Some(interrupt_worker_response::Result::Success(_)),
+            } => Ok(()),
+            InterruptWorkerResponse {
+                result: Some(interrupt_worker_response::Result::Error(error)),
+            } => Err(anyhow!("Failed to hold invocation: {error:?}")),
+            _ => Err(anyhow!("Failed to hold invocation: unknown error")),
+        }
+    }
+
+    async fn release_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+    ) -> crate::Result<()> {
+        let response = self
+            .worker_service()
+            .resume_worker(ResumeWorkerRequest {
+                worker_id: Some(worker_id.clone().into()),
+                idempotency_key: Some(idempotency_key.clone().into()),
+            })
+            .await?;
+
+        match response.result {
+            Some(resume_worker_response::Result::Success(_)) => Ok(()),
+            Some(resume_worker_response::Result::Error(error)) => {
+                Err(anyhow!("Failed to release invocation: {error:?}"))
+            }
+            None => Err(anyhow!("Failed to release invocation: unknown error")),
+        }
+    }
+
     async fn simulated_crash(&self, worker_id: &WorkerId) -> crate::Result<()> {
         let response = self
             .worker_service()
             .interrupt_worker(InterruptWorkerRequest {
                 worker_id: Some(worker_id.clone().into()),
                 recover_immediately: true,
+                idempotency_key: None,
             })
             .await?;
 
@@ -781,6 +929,7 @@ impl<T: TestDependencies + Send + Sync> TestDsl for T {
                     from_oplog_index: from.into(),
                     cursor: cursor.clone(),
                     count: 100,
+                    query: None,
                 })
                 .await?;
 
@@ -914,6 +1063,7 @@ pub fn worker_error_message(error: &Error) -> String {
         Error::LimitExceeded(error) => error.error.clone(),
         Error::NotFound(error) => error.error.clone(),
         Error::AlreadyExists(error) => error.error.clone(),
+        Error::Unavailable(error) => error.error.clone(),
         Error::InternalError(error) => match &error.error {
             None => "Internal error".to_string(),
             Some(error) => match error {
@@ -994,6 +1144,15 @@ pub fn worker_error_message(error: &Error) -> String {
                 worker_execution_error::Error::ShardingNotReady(_error) => {
                     "Sharing not ready".to_string()
                 }
+                worker_execution_error::Error::ResultTooLarge(error) => {
+                    format!(
+                        "Result too large: {} bytes, limit is {} bytes",
+                        error.size, error.limit
+                    )
+                }
+                worker_execution_error::Error::InvocationTimedOut(error) => {
+                    format!("Invocation timed out after {} ms", error.timeout_millis)
+                }
             },
         },
     }
@@ -1033,6 +1192,7 @@ pub fn to_worker_metadata(
                 oplog_idx: OplogIndex::default(),
                 status: metadata.status.try_into().expect("invalid status"),
                 overridden_retry_config: None, // not passed through gRPC
+                overridden_env: None,          // already applied to the env passed through gRPC
                 deleted_regions: DeletedRegions::new(),
                 pending_invocations: vec![],
                 pending_updates: metadata
@@ -1092,6 +1252,7 @@ pub fn to_worker_metadata(
                 component_version: metadata.component_version,
                 component_size: metadata.component_size,
                 total_linear_memory_size: metadata.total_linear_memory_size,
+                disk_usage: metadata.disk_usage,
                 owned_resources: metadata
                     .owned_resources
                     .iter()
@@ -1112,6 +1273,7 @@ pub fn to_worker_metadata(
                     .collect(),
             },
             parent: None,
+            expires_at: metadata.expires_at.clone().map(|t| t.into()),
         },
         metadata.last_error.clone(),
     )
@@ -1152,6 +1314,46 @@ async fn log_and_save_component_metadata(path: &Path) {
     tokio::fs::write(&current_dir, json_data).await.unwrap()
 }
 
+async fn invoke_and_await_with_context<T: TestDependencies + Send + Sync>(
+    deps: &T,
+    target_worker_id: TargetWorkerId,
+    idempotency_key: &IdempotencyKey,
+    function_name: &str,
+    params: Vec<Value>,
+    context: Option<InvocationContext>,
+) -> crate::Result<Result<Vec<Value>, Error>> {
+    let invoke_response = deps
+        .worker_service()
+        .invoke_and_await(InvokeAndAwaitRequest {
+            worker_id: Some(target_worker_id.into()),
+            idempotency_key: Some(idempotency_key.clone().into()),
+            function: function_name.to_string(),
+            invoke_parameters: Some(InvokeParameters {
+                params: params.into_iter().map(|v| v.into()).collect(),
+                blob_params: vec![],
+            }),
+            context,
+            transform: false,
+        })
+        .await?;
+
+    match invoke_response.result {
+        None => Err(anyhow!("No response from invoke_and_await")),
+        Some(invoke_and_await_response::Result::Success(response)) => Ok(Ok(response
+            .result
+            .into_iter()
+            .map(|v| v.try_into())
+            .collect::<Result<Vec<Value>, String>>()
+            .map_err(|err| anyhow!("Invocation result had unexpected format: {err}"))?)),
+        Some(invoke_and_await_response::Result::Error(WorkerError { error: Some(error) })) => {
+            Ok(Err(error))
+        }
+        Some(invoke_and_await_response::Result::Error(_)) => {
+            Err(anyhow!("Empty error response from invoke_and_await"))
+        }
+    }
+}
+
 #[async_trait]
 pub trait TestDslUnsafe {
     async fn store_component(&self, name: &str) -> ComponentId;
@@ -1241,6 +1443,18 @@ pub trait TestDslUnsafe {
     async fn log_output(&self, worker_id: &WorkerId);
     async fn resume(&self, worker_id: &WorkerId);
     async fn interrupt(&self, worker_id: &WorkerId);
+    async fn abort_running_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+    ) -> bool;
+    async fn cancel_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+    ) -> bool;
+    async fn hold_invocation(&self, worker_id: &WorkerId, idempotency_key: &IdempotencyKey);
+    async fn release_invocation(&self, worker_id: &WorkerId, idempotency_key: &IdempotencyKey);
     async fn simulated_crash(&self, worker_id: &WorkerId);
     async fn auto_update_worker(&self, worker_id: &WorkerId, target_version: ComponentVersion);
     async fn manual_update_worker(&self, worker_id: &WorkerId, target_version: ComponentVersion);
@@ -1434,6 +1648,38 @@ impl<T: TestDsl + Sync> TestDslUnsafe for T {
             .expect("Failed to interrupt worker")
     }
 
+    async fn abort_running_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+    ) -> bool {
+        <T as TestDsl>::abort_running_invocation(self, worker_id, idempotency_key)
+            .await
+            .expect("Failed to abort invocation")
+    }
+
+    async fn cancel_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+    ) -> bool {
+        <T as TestDsl>::cancel_invocation(self, worker_id, idempotency_key)
+            .await
+            .expect("Failed to cancel invocation")
+    }
+
+    async fn hold_invocation(&self, worker_id: &WorkerId, idempotency_key: &IdempotencyKey) {
+        <T as TestDsl>::hold_invocation(self, worker_id, idempotency_key)
+            .await
+            .expect("Failed to hold invocation")
+    }
+
+    async fn release_invocation(&self, worker_id: &WorkerId, idempotency_key: &IdempotencyKey) {
+        <T as TestDsl>::release_invocation(self, worker_id, idempotency_key)
+            .await
+            .expect("Failed to release invocation")
+    }
+
     async fn simulated_crash(&self, worker_id: &WorkerId) {
         <T as TestDsl>::simulated_crash(self, worker_id)
             .await
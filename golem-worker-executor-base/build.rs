@@ -4,10 +4,17 @@ use std::path::Path;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let golem_wit_root = find_package_root("golem-wit");
+    let golem_common_root = find_package_root("golem-common");
     let out_dir = var_os("OUT_DIR").unwrap();
     let target_file = Path::new(&out_dir).join("preview2_mod.rs");
+    let extensions_target_file = Path::new(&out_dir).join("preview2_extensions_mod.rs");
 
     std::fs::write(target_file.clone(), preview2_mod_gen(&golem_wit_root)).unwrap();
+    std::fs::write(
+        extensions_target_file,
+        preview2_extensions_mod_gen(&golem_common_root),
+    )
+    .unwrap();
 
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=Cargo.toml");
@@ -93,3 +100,38 @@ fn preview2_mod_gen(golem_wit_path: &str) -> String {
         "#
     )
 }
+
+/// The `golem:api` interfaces defined in golem-common, each bound in its own module
+const GOLEM_API_EXTENSIONS: &[(&str, &str)] = &[
+    ("metrics", "metrics"),
+    ("events", "events"),
+    ("scheduling", "scheduling"),
+    ("child_workers", "child-workers"),
+    ("caller", "caller"),
+    ("feature_flags", "feature-flags"),
+    ("keyvalue", "keyvalue"),
+];
+
+fn preview2_extensions_mod_gen(golem_common_path: &str) -> String {
+    GOLEM_API_EXTENSIONS
+        .iter()
+        .map(|(module, interface)| {
+            format!(
+                r#"/// Bindings of the `golem:api/{interface}` interface, defined in golem-common
+pub mod {module} {{
+    wasmtime::component::bindgen!({{
+        path: "{golem_common_path}/wit/golem-api/{interface}.wit",
+        interfaces: "
+          import golem:api/{interface}@1.1.0-rc1;
+        ",
+        tracing: false,
+        async: true,
+        trappable_imports: true,
+        skip_mut_forwarding_impls: true,
+    }});
+}}
+"#
+            )
+        })
+        .collect()
+}
@@ -26,14 +26,24 @@ impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
     async fn get_environment(&mut self) -> anyhow::Result<Vec<(String, String)>> {
         let _permit = self.begin_async_host_function().await?;
         record_host_function_call("cli::environment", "get_environment");
-        Durability::<Ctx, (), Vec<(String, String)>, SerializableError>::wrap(
+        let replaying = self.state.is_replay();
+        let recorded = Durability::<Ctx, (), Vec<(String, String)>, SerializableError>::wrap(
             self,
             WrappedFunctionType::ReadLocal,
             "golem_environment::get_environment",
             (),
             |ctx| Box::pin(async { Host::get_environment(&mut ctx.as_wasi_view()).await }),
         )
-        .await
+        .await?;
+
+        if replaying {
+            // The instance is created with the environment changes applied, so the replayed
+            // result is replaced by the current environment. Otherwise guests reading it only
+            // once would keep the environment they were first started with.
+            Host::get_environment(&mut self.as_wasi_view()).await
+        } else {
+            Ok(recorded)
+        }
     }
 
     async fn get_arguments(&mut self) -> anyhow::Result<Vec<String>> {
@@ -14,17 +14,20 @@
 
 use crate::durable_host::DurableWorkerCtx;
 use crate::error::GolemError;
+use crate::metrics::wasm::record_replay_consistency_check;
 use crate::model::PersistenceLevel;
+use crate::services::golem_config::ReplayConsistencyCheck;
 use crate::services::oplog::{CommitLevel, Oplog, OplogOps};
 use crate::workerctx::WorkerCtx;
 use async_trait::async_trait;
 use bincode::{Decode, Encode};
-use golem_common::model::oplog::{OplogEntry, OplogIndex, WrappedFunctionType};
+use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogPayload, WrappedFunctionType};
+use golem_common::serialization::serialize;
 use std::fmt::Debug;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
-use tracing::error;
+use tracing::{error, warn};
 
 #[async_trait]
 pub trait Durability<Ctx: WorkerCtx, SerializableInput, SerializableSuccess, SerializableErr> {
@@ -362,12 +365,9 @@ impl<Ctx: WorkerCtx, SerializableInput, SerializableSuccess, SerializableErr>
 
             intermediate.and_then(|value| to_result(self, value))
         } else {
-            let (_, oplog_entry) = crate::get_oplog_entry!(
-                self.state.replay_state,
-                OplogEntry::ImportedFunctionInvoked,
-                OplogEntry::ImportedFunctionInvokedV1
-            )?;
-            DurableWorkerCtx::<Ctx>::validate_oplog_entry(&oplog_entry, function_name)?;
+            let oplog_entry = self
+                .replay_imported_function_invoked(function_name, &input)
+                .await?;
 
             let oplog = self.state.oplog.clone();
             let result = from_serialized(self, oplog, &oplog_entry).await;
@@ -476,12 +476,9 @@ impl<Ctx: WorkerCtx, SerializableInput, SerializableSuccess, SerializableErr>
             }
             result
         } else {
-            let (_, oplog_entry) = crate::get_oplog_entry!(
-                self.state.replay_state,
-                OplogEntry::ImportedFunctionInvoked,
-                OplogEntry::ImportedFunctionInvokedV1
-            )?;
-            DurableWorkerCtx::<Ctx>::validate_oplog_entry(&oplog_entry, function_name)?;
+            let oplog_entry = self
+                .replay_imported_function_invoked(function_name, &input)
+                .await?;
             let response: Result<SerializableSuccess, SerializableErr> =
                 DurableWorkerCtx::<Ctx>::default_load(self.state.oplog.clone(), &oplog_entry).await;
 
@@ -578,6 +575,83 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
         Ok(())
     }
 
+    /// Reads the oplog entry recorded for the replayed imported function call and checks that it
+    /// belongs to the same call
+    async fn replay_imported_function_invoked<SerializedInput>(
+        &mut self,
+        function_name: &str,
+        serializable_input: &SerializedInput,
+    ) -> Result<OplogEntry, GolemError>
+    where
+        SerializedInput: Encode + Debug + Send + Sync,
+    {
+        let (oplog_index, oplog_entry) = crate::get_oplog_entry!(
+            self.state.replay_state,
+            OplogEntry::ImportedFunctionInvoked,
+            OplogEntry::ImportedFunctionInvokedV1
+        )?;
+
+        let mut result = DurableWorkerCtx::<Ctx>::validate_oplog_entry(&oplog_entry, function_name);
+        if result.is_ok() {
+            result = self
+                .check_replay_consistency(
+                    oplog_index,
+                    &oplog_entry,
+                    function_name,
+                    serializable_input,
+                )
+                .await;
+        }
+        if let Err(error) = &result {
+            self.state
+                .replay_state
+                .record_divergence(oplog_index, error)
+                .await;
+        }
+        result.map(|_| oplog_entry)
+    }
+
+    /// Compares the parameters of a replayed call with the ones recorded in its oplog entry, as
+    /// configured by `oplog.replay_consistency_check`
+    async fn check_replay_consistency<SerializedInput>(
+        &self,
+        oplog_index: OplogIndex,
+        oplog_entry: &OplogEntry,
+        function_name: &str,
+        serializable_input: &SerializedInput,
+    ) -> Result<(), GolemError>
+    where
+        SerializedInput: Encode + Debug + Send + Sync,
+    {
+        let mode = self.state.config.oplog.replay_consistency_check;
+        if mode == ReplayConsistencyCheck::Disabled {
+            return Ok(());
+        }
+        // Older oplog entries did not record the parameters
+        let OplogEntry::ImportedFunctionInvoked { request, .. } = oplog_entry else {
+            return Ok(());
+        };
+
+        let actual = serialize(serializable_input).map_err(GolemError::runtime)?;
+        let divergence = replay_divergence(request, &actual);
+        record_replay_consistency_check(function_name, divergence.is_some());
+        let Some(recorded) = divergence else {
+            return Ok(());
+        };
+
+        let expected = format!(
+            "{function_name} called with the parameters recorded at oplog index {oplog_index} ({recorded})"
+        );
+        let got = format!("{serializable_input:?} ({})", hex_preview(&actual));
+        if mode == ReplayConsistencyCheck::Strict {
+            error!("Replay diverged from the oplog: expected {expected}, got {got}");
+            Err(GolemError::unexpected_oplog_entry(expected, got))
+        } else {
+            warn!("Replay diverged from the oplog, using the recorded result: expected {expected}, got {got}");
+            Ok(())
+        }
+    }
+
     fn validate_oplog_entry(
         oplog_entry: &OplogEntry,
         expected_function_name: &str,
@@ -613,3 +687,73 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
         }
     }
 }
+
+/// Compares the serialized parameters of a replayed call with the recorded ones, returning the
+/// description of the recorded parameters if they differ. External payloads are compared by
+/// their hash, so replaying does not download them.
+fn replay_divergence(recorded: &OplogPayload, actual: &[u8]) -> Option<String> {
+    match recorded {
+        OplogPayload::Inline(recorded) => {
+            (recorded.as_slice() != actual).then(|| hex_preview(recorded))
+        }
+        OplogPayload::External { md5_hash, .. } => (md5_hash.as_slice()
+            != md5::compute(actual).0.as_slice())
+        .then(|| format!("external payload with md5 hash {}", hex::encode(md5_hash))),
+    }
+}
+
+// The serialized parameters shown in divergence reports, limited to keep the errors readable
+fn hex_preview(bytes: &[u8]) -> String {
+    const MAX_PREVIEW_BYTES: usize = 64;
+    if bytes.len() > MAX_PREVIEW_BYTES {
+        format!(
+            "{}... ({} bytes)",
+            hex::encode(&bytes[..MAX_PREVIEW_BYTES]),
+            bytes.len()
+        )
+    } else {
+        hex::encode(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::oplog::{OplogPayload, PayloadId};
+
+    use super::{hex_preview, replay_divergence};
+
+    #[test]
+    fn inline_parameters_are_compared() {
+        let recorded = OplogPayload::Inline(vec![1, 2, 3]);
+
+        assert_eq!(replay_divergence(&recorded, &[1, 2, 3]), None);
+        assert_eq!(
+            replay_divergence(&recorded, &[1, 2, 4]),
+            Some("010203".to_string())
+        );
+    }
+
+    #[test]
+    fn external_parameters_are_compared_by_hash() {
+        let parameters = vec![7u8; 1024];
+        let recorded = OplogPayload::External {
+            payload_id: PayloadId::new(),
+            md5_hash: md5::compute(&parameters).to_vec(),
+        };
+
+        assert_eq!(replay_divergence(&recorded, &parameters), None);
+        let divergence = replay_divergence(&recorded, &parameters[1..]).unwrap();
+        assert!(divergence.starts_with("external payload with md5 hash "));
+    }
+
+    #[test]
+    fn long_parameters_are_truncated_in_reports() {
+        assert_eq!(hex_preview(&[0xab; 64]), "ab".repeat(64));
+        assert_eq!(
+            hex_preview(&[0xab; 65]),
+            format!("{}... (65 bytes)", "ab".repeat(64))
+        );
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use wasmtime_wasi::StreamError;
+
+use crate::durable_host::DurableWorkerCtx;
+use crate::metrics::workers::{record_disk_limit_exceeded, record_disk_usage_change};
+use crate::workerctx::WorkerCtx;
+
+/// Error given to the guest when a write would make the worker's file system exceed its
+/// size limit, reported as `insufficient-space`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskLimitExceeded;
+
+impl Display for DiskLimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The worker's file system size limit has been exceeded")
+    }
+}
+
+impl std::error::Error for DiskLimitExceeded {}
+
+/// Total size of the files written to a worker's file system.
+///
+/// The file system is recreated every time the worker is loaded, so the usage starts from
+/// zero and is rebuilt by replaying the writes.
+#[derive(Debug)]
+pub struct DiskUsage {
+    bytes: u64,
+    limit: Option<u64>,
+}
+
+impl DiskUsage {
+    pub fn new(limit: Option<u64>) -> Self {
+        Self { bytes: 0, limit }
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Checks whether the files can grow by `growth` bytes without exceeding the limit
+    pub fn reserve(&self, growth: u64) -> Result<(), DiskLimitExceeded> {
+        if self
+            .limit
+            .is_some_and(|limit| self.bytes.saturating_add(growth) > limit)
+        {
+            record_disk_limit_exceeded();
+            Err(DiskLimitExceeded)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Records a file changing its size from `old_size` to `new_size`
+    pub fn resize(&mut self, old_size: u64, new_size: u64) {
+        let bytes = self.bytes.saturating_add(new_size).saturating_sub(old_size);
+        record_disk_usage_change(bytes as i64 - self.bytes as i64);
+        self.bytes = bytes;
+    }
+}
+
+impl Drop for DiskUsage {
+    fn drop(&mut self) {
+        record_disk_usage_change(-(self.bytes as i64));
+    }
+}
+
+/// An output stream writing to a file of the worker's file system
+#[derive(Debug, Clone)]
+pub struct FileWriteStream {
+    pub path: PathBuf,
+    /// Offset of the next write, or `None` if the stream appends to the file
+    pub position: Option<u64>,
+    /// Size of the file once the writes of the stream are flushed, or `None` before the first
+    /// write. The writes are flushed in the background, so the size on disk can lag behind.
+    pub size: Option<u64>,
+}
+
+/// Size of a regular file, or 0 if it does not exist
+pub async fn file_size(path: &Path) -> u64 {
+    tokio::fs::symlink_metadata(path)
+        .await
+        .ok()
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .unwrap_or(0)
+}
+
+impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
+    /// Total size of the files written to the worker's file system in bytes
+    pub fn disk_usage(&self) -> u64 {
+        self.state.disk_usage.bytes()
+    }
+
+    pub(crate) fn register_file_write_stream(
+        &mut self,
+        handle: u32,
+        path: PathBuf,
+        position: Option<u64>,
+    ) {
+        self.state.file_write_streams.insert(
+            handle,
+            FileWriteStream {
+                path,
+                position,
+                size: None,
+            },
+        );
+    }
+
+    pub(crate) fn unregister_file_write_stream(&mut self, handle: u32) {
+        self.state.file_write_streams.remove(&handle);
+    }
+
+    /// Checks whether writing `len` bytes to an output stream fits in the worker's disk usage
+    /// limit. Returns the size of the written file before the write, or `None` if the stream
+    /// does not write to a file.
+    pub(crate) async fn begin_file_stream_write(
+        &self,
+        handle: u32,
+        len: u64,
+    ) -> Result<Option<u64>, StreamError> {
+        match self.state.file_write_streams.get(&handle) {
+            Some(stream) => {
+                let old_size = match stream.size {
+                    Some(size) => size,
+                    None => file_size(&stream.path).await,
+                };
+                let new_size = match stream.position {
+                    Some(position) => old_size.max(position.saturating_add(len)),
+                    None => old_size.saturating_add(len),
+                };
+                self.state
+                    .disk_usage
+                    .reserve(new_size - old_size)
+                    .map_err(|err| StreamError::LastOperationFailed(anyhow!(err)))?;
+                Ok(Some(old_size))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Accounts `written` bytes successfully written to an output stream, with `old_size`
+    /// returned by `begin_file_stream_write`
+    pub(crate) fn end_file_stream_write(&mut self, handle: u32, old_size: u64, written: u64) {
+        if let Some(stream) = self.state.file_write_streams.get_mut(&handle) {
+            let new_size = match &mut stream.position {
+                Some(position) => {
+                    *position = position.saturating_add(written);
+                    old_size.max(*position)
+                }
+                None => old_size.saturating_add(written),
+            };
+            stream.size = Some(new_size);
+            self.state.disk_usage.resize(old_size, new_size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::durable_host::filesystem::disk_usage::{DiskLimitExceeded, DiskUsage};
+
+    #[test]
+    fn growth_beyond_the_limit_is_denied() {
+        let mut usage = DiskUsage::new(Some(100));
+        usage.resize(0, 60);
+        usage.resize(10, 40);
+
+        assert_eq!(usage.bytes(), 90);
+        assert_eq!(usage.reserve(10), Ok(()));
+        assert_eq!(usage.reserve(11), Err(DiskLimitExceeded));
+
+        usage.resize(40, 0);
+        assert_eq!(usage.bytes(), 50);
+        assert_eq!(usage.reserve(50), Ok(()));
+    }
+}
@@ -32,6 +32,7 @@ use wasmtime_wasi::ReaddirIterator;
 
 use golem_common::model::oplog::WrappedFunctionType;
 
+use crate::durable_host::filesystem::disk_usage::{file_size, DiskLimitExceeded};
 use crate::durable_host::serialized::{
     SerializableDateTime, SerializableError, SerializableFileTimes,
 };
@@ -56,7 +57,15 @@ impl<Ctx: WorkerCtx> HostDescriptor for DurableWorkerCtx<Ctx> {
         offset: Filesize,
     ) -> Result<Resource<OutputStream>, FsError> {
         record_host_function_call("filesystem::types::descriptor", "write_via_stream");
-        HostDescriptor::write_via_stream(&mut self.as_wasi_view(), self_, offset)
+        let path = match self.table().get(&self_)? {
+            Descriptor::File(f) => Some(f.path.clone()),
+            Descriptor::Dir(_) => None,
+        };
+        let stream = HostDescriptor::write_via_stream(&mut self.as_wasi_view(), self_, offset)?;
+        if let Some(path) = path {
+            self.register_file_write_stream(stream.rep(), path, Some(offset));
+        }
+        Ok(stream)
     }
 
     fn append_via_stream(
@@ -64,7 +73,15 @@ impl<Ctx: WorkerCtx> HostDescriptor for DurableWorkerCtx<Ctx> {
         self_: Resource<Descriptor>,
     ) -> Result<Resource<OutputStream>, FsError> {
         record_host_function_call("filesystem::types::descriptor", "append_via_stream");
-        HostDescriptor::append_via_stream(&mut self.as_wasi_view(), self_)
+        let path = match self.table().get(&self_)? {
+            Descriptor::File(f) => Some(f.path.clone()),
+            Descriptor::Dir(_) => None,
+        };
+        let stream = HostDescriptor::append_via_stream(&mut self.as_wasi_view(), self_)?;
+        if let Some(path) = path {
+            self.register_file_write_stream(stream.rep(), path, None);
+        }
+        Ok(stream)
     }
 
     async fn advise(
@@ -119,7 +136,22 @@ impl<Ctx: WorkerCtx> HostDescriptor for DurableWorkerCtx<Ctx> {
             .await
             .map_err(FsError::trap)?;
         record_host_function_call("filesystem::types::descriptor", "set_size");
-        HostDescriptor::set_size(&mut self.as_wasi_view(), self_, size).await
+        let path = match self.table().get(&self_)? {
+            Descriptor::File(f) => f.path.clone(),
+            Descriptor::Dir(d) => d.path.clone(),
+        };
+        let old_size = file_size(&path).await;
+        if self
+            .state
+            .disk_usage
+            .reserve(size.saturating_sub(old_size))
+            .is_err()
+        {
+            return Err(ErrorCode::InsufficientSpace.into());
+        }
+        HostDescriptor::set_size(&mut self.as_wasi_view(), self_, size).await?;
+        self.state.disk_usage.resize(old_size, size);
+        Ok(())
     }
 
     async fn set_times(
@@ -167,7 +199,21 @@ impl<Ctx: WorkerCtx> HostDescriptor for DurableWorkerCtx<Ctx> {
             .await
             .map_err(FsError::trap)?;
         record_host_function_call("filesystem::types::descriptor", "write");
-        HostDescriptor::write(&mut self.as_wasi_view(), self_, buffer, offset).await
+        let path = match self.table().get(&self_)? {
+            Descriptor::File(f) => f.path.clone(),
+            Descriptor::Dir(d) => d.path.clone(),
+        };
+        let old_size = file_size(&path).await;
+        let new_size = old_size.max(offset.saturating_add(buffer.len() as u64));
+        if self.state.disk_usage.reserve(new_size - old_size).is_err() {
+            return Err(ErrorCode::InsufficientSpace.into());
+        }
+        let written =
+            HostDescriptor::write(&mut self.as_wasi_view(), self_, buffer, offset).await?;
+        self.state
+            .disk_usage
+            .resize(old_size, old_size.max(offset.saturating_add(written)));
+        Ok(written)
     }
 
     async fn read_directory(
@@ -438,6 +484,20 @@ impl<Ctx: WorkerCtx> HostDescriptor for DurableWorkerCtx<Ctx> {
             .await
             .map_err(FsError::trap)?;
         record_host_function_call("filesystem::types::descriptor", "rename_at");
+        // A file replaced by the rename no longer counts into the worker's disk usage
+        let renamed_path = match self.table().get(&self_)? {
+            Descriptor::File(f) => f.path.join(old_path.clone()),
+            Descriptor::Dir(d) => d.path.join(old_path.clone()),
+        };
+        let replaced_path = match self.table().get(&new_descriptor)? {
+            Descriptor::File(f) => f.path.join(new_path.clone()),
+            Descriptor::Dir(d) => d.path.join(new_path.clone()),
+        };
+        let replaced_size = if renamed_path == replaced_path {
+            0
+        } else {
+            file_size(&replaced_path).await
+        };
         HostDescriptor::rename_at(
             &mut self.as_wasi_view(),
             self_,
@@ -445,7 +505,9 @@ impl<Ctx: WorkerCtx> HostDescriptor for DurableWorkerCtx<Ctx> {
             new_descriptor,
             new_path.clone(),
         )
-        .await
+        .await?;
+        self.state.disk_usage.resize(replaced_size, 0);
+        Ok(())
     }
 
     async fn symlink_at(
@@ -473,7 +535,14 @@ impl<Ctx: WorkerCtx> HostDescriptor for DurableWorkerCtx<Ctx> {
             .await
             .map_err(FsError::trap)?;
         record_host_function_call("filesystem::types::descriptor", "unlink_file_at");
-        HostDescriptor::unlink_file_at(&mut self.as_wasi_view(), self_, path.clone()).await
+        let full_path = match self.table().get(&self_)? {
+            Descriptor::File(f) => f.path.join(path.clone()),
+            Descriptor::Dir(d) => d.path.join(path.clone()),
+        };
+        let old_size = file_size(&full_path).await;
+        HostDescriptor::unlink_file_at(&mut self.as_wasi_view(), self_, path.clone()).await?;
+        self.state.disk_usage.resize(old_size, 0);
+        Ok(())
     }
 
     async fn is_same_object(
@@ -554,7 +623,12 @@ impl<Ctx: WorkerCtx> HostDirectoryEntryStream for DurableWorkerCtx<Ctx> {
 #[async_trait]
 impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
     fn filesystem_error_code(&mut self, err: Resource<Error>) -> anyhow::Result<Option<ErrorCode>> {
-        Host::filesystem_error_code(&mut self.as_wasi_view(), err)
+        if self.table().get(&err)?.is::<DiskLimitExceeded>() {
+            // Stream writes exceeding the worker's disk usage limit
+            Ok(Some(ErrorCode::InsufficientSpace))
+        } else {
+            Host::filesystem_error_code(&mut self.as_wasi_view(), err)
+        }
     }
 
     fn convert_error_code(&mut self, err: FsError) -> anyhow::Result<ErrorCode> {
@@ -0,0 +1,63 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+use golem_common::model::CallerIdentity;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::caller::golem::api::caller;
+use crate::preview2::caller::golem::api::caller::Host;
+use crate::workerctx::WorkerCtx;
+
+// The caller identity is persisted together with the invocation, both in the pending invocation
+// and in the `ExportedFunctionInvoked` oplog entry, so it survives the worker being recovered.
+// Reading it is still recorded in the oplog, to keep replaying the workers which read it before
+// it was persisted with the invocation.
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn get_caller_identity(&mut self) -> anyhow::Result<Option<caller::CallerIdentity>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::api::caller", "get-caller-identity");
+
+        let caller = Durability::<Ctx, (), Option<CallerIdentity>, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::ReadLocal,
+            "golem::api::caller::get-caller-identity",
+            (),
+            |ctx| {
+                Box::pin(
+                    async move { Ok::<_, anyhow::Error>(ctx.state.get_current_caller_identity()) },
+                )
+            },
+        )
+        .await?;
+
+        Ok(caller.map(|caller| caller::CallerIdentity {
+            subject: caller.subject,
+            uris: caller.uris,
+            dns_names: caller.dns_names,
+        }))
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn get_caller_identity(&mut self) -> anyhow::Result<Option<caller::CallerIdentity>> {
+        (*self).get_caller_identity().await
+    }
+}
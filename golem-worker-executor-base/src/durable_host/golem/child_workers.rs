@@ -0,0 +1,216 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+use golem_common::model::{ComponentId, WorkerDependent, WorkerId};
+use uuid::Uuid;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::child_workers::golem::api::child_workers::{ChildWorkerId, Host};
+use crate::services::worker_proxy::WorkerProxyError;
+use crate::workerctx::WorkerCtx;
+
+// Children are created and deleted through the worker service, as they can belong to any shard.
+// Both calls are persisted, and the set of children is rebuilt by replaying them.
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn spawn(
+        &mut self,
+        component_id: String,
+        worker_name: String,
+        args: Vec<String>,
+        env: Vec<(String, String)>,
+    ) -> anyhow::Result<Result<ChildWorkerId, String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::api::child-workers", "spawn");
+
+        let worker_id = match parse_child_worker_id(&component_id, &worker_name) {
+            Ok(worker_id) => worker_id,
+            Err(err) => return Ok(Err(err)),
+        };
+
+        let child_id = worker_id.clone();
+        let result = Durability::<
+            Ctx,
+            (WorkerId, Vec<String>, Vec<(String, String)>),
+            (),
+            SerializableError,
+        >::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem::api::child-workers::spawn",
+            (worker_id.clone(), args.clone(), env.clone()),
+            |ctx| {
+                Box::pin(async move {
+                    let result = ctx
+                        .state
+                        .worker_proxy
+                        .create(&child_id, args, env.into_iter().collect())
+                        .await;
+                    ignore_already_exists(result)
+                })
+            },
+        )
+        .await;
+
+        match result {
+            Ok(()) => {
+                self.state
+                    .worker_service
+                    .add_dependent(
+                        &self.owned_worker_id,
+                        &WorkerDependent::ChildWorker(worker_id.clone()),
+                    )
+                    .await;
+                self.state.children.insert(worker_id);
+                Ok(Ok(ChildWorkerId {
+                    component_id,
+                    worker_name,
+                }))
+            }
+            Err(err) => Ok(Err(err.to_string())),
+        }
+    }
+
+    async fn terminate_child(
+        &mut self,
+        child: ChildWorkerId,
+    ) -> anyhow::Result<Result<(), String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::api::child-workers", "terminate-child");
+
+        let worker_id = match parse_child_worker_id(&child.component_id, &child.worker_name) {
+            Ok(worker_id) => worker_id,
+            Err(err) => return Ok(Err(err)),
+        };
+        if !self.state.children.contains(&worker_id) {
+            return Ok(Err(format!(
+                "Worker {worker_id} was not spawned by this worker"
+            )));
+        }
+
+        let child_id = worker_id.clone();
+        let result = Durability::<Ctx, WorkerId, (), SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem::api::child-workers::terminate-child",
+            worker_id.clone(),
+            |ctx| {
+                Box::pin(async move {
+                    match ctx.state.worker_proxy.delete(&child_id, false, false).await {
+                        // The child may have been deleted through the API already
+                        Err(WorkerProxyError::NotFound(_)) => Ok(()),
+                        result => result.map(|_| ()),
+                    }
+                })
+            },
+        )
+        .await;
+
+        match result {
+            Ok(()) => {
+                self.state
+                    .worker_service
+                    .remove_dependent(
+                        &self.owned_worker_id,
+                        &WorkerDependent::ChildWorker(worker_id.clone()),
+                    )
+                    .await;
+                self.state.children.remove(&worker_id);
+                Ok(Ok(()))
+            }
+            Err(err) => Ok(Err(err.to_string())),
+        }
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn spawn(
+        &mut self,
+        component_id: String,
+        worker_name: String,
+        args: Vec<String>,
+        env: Vec<(String, String)>,
+    ) -> anyhow::Result<Result<ChildWorkerId, String>> {
+        (*self).spawn(component_id, worker_name, args, env).await
+    }
+
+    async fn terminate_child(
+        &mut self,
+        child: ChildWorkerId,
+    ) -> anyhow::Result<Result<(), String>> {
+        (*self).terminate_child(child).await
+    }
+}
+
+/// A spawn is executed again if the worker got interrupted before its result was persisted, in
+/// which case the child created by the first attempt already exists
+fn ignore_already_exists(result: Result<(), WorkerProxyError>) -> Result<(), WorkerProxyError> {
+    match result {
+        Err(WorkerProxyError::AlreadyExists(_)) => Ok(()),
+        result => result,
+    }
+}
+
+fn parse_child_worker_id(component_id: &str, worker_name: &str) -> Result<WorkerId, String> {
+    let component_id = Uuid::parse_str(component_id)
+        .map_err(|err| format!("Invalid component id {component_id}: {err}"))?;
+    if worker_name.is_empty() {
+        return Err("The name of a child worker must not be empty".to_string());
+    }
+    Ok(WorkerId {
+        component_id: ComponentId(component_id),
+        worker_name: worker_name.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::{ignore_already_exists, parse_child_worker_id};
+    use crate::services::worker_proxy::WorkerProxyError;
+
+    #[test]
+    fn repeated_spawn_succeeds() {
+        assert!(ignore_already_exists(Ok(())).is_ok());
+        assert!(
+            ignore_already_exists(Err(WorkerProxyError::AlreadyExists("child".to_string())))
+                .is_ok()
+        );
+        assert!(matches!(
+            ignore_already_exists(Err(WorkerProxyError::NotFound("component".to_string()))),
+            Err(WorkerProxyError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn child_worker_id_is_validated() {
+        let worker_id =
+            parse_child_worker_id("9c0a8e4b-5b0e-4e4c-9f51-2d1e0c3f6a7b", "child-1").unwrap();
+        assert_eq!(worker_id.worker_name, "child-1");
+        assert_eq!(
+            worker_id.component_id.0.to_string(),
+            "9c0a8e4b-5b0e-4e4c-9f51-2d1e0c3f6a7b"
+        );
+
+        assert!(parse_child_worker_id("not-a-uuid", "child-1").is_err());
+        assert!(parse_child_worker_id("9c0a8e4b-5b0e-4e4c-9f51-2d1e0c3f6a7b", "").is_err());
+    }
+}
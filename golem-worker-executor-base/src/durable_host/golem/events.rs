@@ -0,0 +1,63 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::events::golem::api::events::Host;
+use crate::workerctx::WorkerCtx;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    /// The id of the emitted event is persisted, so the event is not emitted again when the
+    /// worker is replayed
+    async fn emit(&mut self, topic: String, payload: Vec<u8>) -> anyhow::Result<u64> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::api::events", "emit");
+
+        if topic.is_empty() {
+            return Err(anyhow!("The topic of an emitted event must not be empty"));
+        }
+
+        let worker_id = self.owned_worker_id.worker_id();
+        Durability::<Ctx, (String, Vec<u8>), u64, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem::api::events::emit",
+            (topic.clone(), payload.clone()),
+            |ctx| {
+                Box::pin(async move {
+                    let id = ctx
+                        .state
+                        .domain_event_service
+                        .emit(&worker_id, &topic, payload)
+                        .await?;
+                    Ok(id)
+                })
+            },
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn emit(&mut self, topic: String, payload: Vec<u8>) -> anyhow::Result<u64> {
+        (*self).emit(topic, payload).await
+    }
+}
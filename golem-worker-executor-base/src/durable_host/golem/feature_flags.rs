@@ -0,0 +1,87 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::error::GolemError;
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::feature_flags::golem::api::feature_flags::{FeatureFlag, Host};
+use crate::workerctx::WorkerCtx;
+
+impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
+    // The flags are resolved on the first access of each invocation, and the resolved set is
+    // recorded in the oplog so the invocation sees the same flags when it is replayed
+    async fn resolved_feature_flags(&mut self) -> Result<BTreeMap<String, bool>, GolemError> {
+        if let Some(feature_flags) = &self.state.feature_flags {
+            return Ok(feature_flags.clone());
+        }
+
+        let feature_flags = Durability::<Ctx, (), Vec<(String, bool)>, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::ReadRemote,
+            "golem::api::feature-flags::resolve",
+            (),
+            |ctx| {
+                Box::pin(async move {
+                    let feature_flags = ctx
+                        .state
+                        .feature_flag_service
+                        .resolve(&ctx.owned_worker_id)
+                        .await?;
+                    Ok(feature_flags.into_iter().collect())
+                })
+            },
+        )
+        .await?;
+        let feature_flags: BTreeMap<String, bool> = feature_flags.into_iter().collect();
+        self.state.feature_flags = Some(feature_flags.clone());
+        Ok(feature_flags)
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn is_enabled(&mut self, name: String) -> anyhow::Result<bool> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::api::feature-flags", "is-enabled");
+        let feature_flags = self.resolved_feature_flags().await?;
+        Ok(feature_flags.get(&name).copied().unwrap_or(false))
+    }
+
+    async fn get_all(&mut self) -> anyhow::Result<Vec<FeatureFlag>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::api::feature-flags", "get-all");
+        let feature_flags = self.resolved_feature_flags().await?;
+        Ok(feature_flags
+            .into_iter()
+            .map(|(name, enabled)| FeatureFlag { name, enabled })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn is_enabled(&mut self, name: String) -> anyhow::Result<bool> {
+        (*self).is_enabled(name).await
+    }
+
+    async fn get_all(&mut self) -> anyhow::Result<Vec<FeatureFlag>> {
+        (*self).get_all().await
+    }
+}
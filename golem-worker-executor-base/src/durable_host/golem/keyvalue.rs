@@ -0,0 +1,124 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::keyvalue::golem::api::keyvalue::{Check, Host, Write};
+use crate::storage::keyvalue::{KeyValueCheck, KeyValueWrite};
+use crate::workerctx::WorkerCtx;
+
+// Only the keys are persisted in the oplog, the outcome of a transaction is replayed from its
+// recorded result so the checks are not evaluated again.
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn commit(
+        &mut self,
+        bucket: String,
+        checks: Vec<Check>,
+        writes: Vec<Write>,
+    ) -> anyhow::Result<Result<bool, String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::api::keyvalue", "commit");
+        let account_id = self.owned_worker_id.account_id();
+        let checked_keys = checks.iter().map(|check| check.key.clone()).collect();
+        let written_keys = writes.iter().map(|write| write.key.clone()).collect();
+        let checks = checks
+            .into_iter()
+            .map(|check| KeyValueCheck {
+                key: check.key,
+                expected: check.expected,
+            })
+            .collect();
+        let writes = writes
+            .into_iter()
+            .map(|write| KeyValueWrite {
+                key: write.key,
+                value: write.value,
+                ttl: write.ttl_millis.map(Duration::from_millis),
+            })
+            .collect();
+        let result =
+            Durability::<Ctx, (String, Vec<String>, Vec<String>), bool, SerializableError>::wrap(
+                self,
+                WrappedFunctionType::WriteRemote,
+                "golem::api::keyvalue::commit",
+                (bucket.clone(), checked_keys, written_keys),
+                |ctx| {
+                    ctx.state
+                        .key_value_service
+                        .commit(account_id, bucket, checks, writes)
+                },
+            )
+            .await;
+        Ok(result.map_err(|err| format!("{err:?}")))
+    }
+
+    async fn set_with_ttl(
+        &mut self,
+        bucket: String,
+        key: String,
+        value: Vec<u8>,
+        ttl_millis: u64,
+    ) -> anyhow::Result<Result<(), String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::api::keyvalue", "set-with-ttl");
+        let account_id = self.owned_worker_id.account_id();
+        let result = Durability::<Ctx, (String, String, u64, u64), (), SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            "golem::api::keyvalue::set-with-ttl",
+            (bucket.clone(), key.clone(), value.len() as u64, ttl_millis),
+            |ctx| {
+                ctx.state.key_value_service.set_with_ttl(
+                    account_id,
+                    bucket,
+                    key,
+                    value,
+                    Duration::from_millis(ttl_millis),
+                )
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| format!("{err:?}")))
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn commit(
+        &mut self,
+        bucket: String,
+        checks: Vec<Check>,
+        writes: Vec<Write>,
+    ) -> anyhow::Result<Result<bool, String>> {
+        (*self).commit(bucket, checks, writes).await
+    }
+
+    async fn set_with_ttl(
+        &mut self,
+        bucket: String,
+        key: String,
+        value: Vec<u8>,
+        ttl_millis: u64,
+    ) -> anyhow::Result<Result<(), String>> {
+        (*self).set_with_ttl(bucket, key, value, ttl_millis).await
+    }
+}
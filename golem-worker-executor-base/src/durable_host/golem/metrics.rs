@@ -0,0 +1,109 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::guest::{increment_guest_counter, observe_guest_histogram};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::metrics::golem::api::metrics::{Host, Label};
+use crate::workerctx::WorkerCtx;
+
+// The observations are persisted as side effects, so they are not recorded again when
+// the worker is replayed.
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn increment_counter(
+        &mut self,
+        name: String,
+        labels: Vec<Label>,
+        value: u64,
+    ) -> anyhow::Result<()> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::api::metrics", "increment-counter");
+        let component_id = self.owned_worker_id.component_id();
+        let labels = to_pairs(labels);
+        Durability::<Ctx, (String, Vec<(String, String)>, u64), (), SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteLocal,
+            "golem::api::metrics::increment-counter",
+            (name.clone(), labels.clone(), value),
+            |ctx| {
+                Box::pin(async move {
+                    let limits = &ctx.state.config.limits;
+                    increment_guest_counter(&component_id, &name, &labels, value, limits);
+                    Ok(())
+                })
+            },
+        )
+        .await
+    }
+
+    async fn observe_histogram(
+        &mut self,
+        name: String,
+        labels: Vec<Label>,
+        value: f64,
+    ) -> anyhow::Result<()> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::api::metrics", "observe-histogram");
+        let component_id = self.owned_worker_id.component_id();
+        let labels = to_pairs(labels);
+        Durability::<Ctx, (String, Vec<(String, String)>, f64), (), SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteLocal,
+            "golem::api::metrics::observe-histogram",
+            (name.clone(), labels.clone(), value),
+            |ctx| {
+                Box::pin(async move {
+                    let limits = &ctx.state.config.limits;
+                    observe_guest_histogram(&component_id, &name, &labels, value, limits);
+                    Ok(())
+                })
+            },
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn increment_counter(
+        &mut self,
+        name: String,
+        labels: Vec<Label>,
+        value: u64,
+    ) -> anyhow::Result<()> {
+        (*self).increment_counter(name, labels, value).await
+    }
+
+    async fn observe_histogram(
+        &mut self,
+        name: String,
+        labels: Vec<Label>,
+        value: f64,
+    ) -> anyhow::Result<()> {
+        (*self).observe_histogram(name, labels, value).await
+    }
+}
+
+fn to_pairs(labels: Vec<Label>) -> Vec<(String, String)> {
+    labels
+        .into_iter()
+        .map(|label| (label.name, label.value))
+        .collect()
+}
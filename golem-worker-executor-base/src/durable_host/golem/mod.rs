@@ -12,6 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod caller;
+pub mod child_workers;
+pub mod events;
+pub mod feature_flags;
+pub mod keyvalue;
+pub mod metrics;
+pub mod scheduling;
 pub mod v11;
 
 use anyhow::anyhow;
@@ -23,7 +30,7 @@ use uuid::Uuid;
 use wasmtime::component::Resource;
 use wasmtime_wasi::WasiView;
 
-use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::serialized::{SerializableError, SerializableWorkerMetadata};
 use crate::durable_host::wasm_rpc::UrnExtensions;
 use crate::durable_host::{Durability, DurableWorkerCtx};
 use crate::error::GolemError;
@@ -41,7 +48,7 @@ use crate::workerctx::{InvocationManagement, StatusManagement, WorkerCtx};
 use golem_common::model::oplog::{OplogEntry, OplogIndex, WrappedFunctionType};
 use golem_common::model::regions::OplogRegion;
 use golem_common::model::{
-    ComponentId, IdempotencyKey, OwnedWorkerId, PromiseId, ScanCursor, WorkerId,
+    ComponentId, IdempotencyKey, OwnedWorkerId, PromiseId, ScanCursor, WorkerDependent, WorkerId,
 };
 
 #[async_trait]
@@ -138,15 +145,22 @@ impl<Ctx: WorkerCtx> golem::api0_2_0::host::Host for DurableWorkerCtx<Ctx> {
         record_host_function_call("golem::api", "create_promise");
         let oplog_idx = self.get_oplog_index().await?;
         let _permit = self.begin_async_host_function().await?;
-        Ok(self
+        let promise_id = self
             .public_state
             .promise_service
             .create(
                 &self.owned_worker_id.worker_id,
                 OplogIndex::from_u64(oplog_idx),
             )
-            .await
-            .into())
+            .await;
+        self.state
+            .worker_service
+            .add_dependent(
+                &self.owned_worker_id,
+                &WorkerDependent::Promise(promise_id.clone()),
+            )
+            .await;
+        Ok(promise_id.into())
     }
 
     async fn await_promise(
@@ -210,7 +224,17 @@ impl<Ctx: WorkerCtx> golem::api0_2_0::host::Host for DurableWorkerCtx<Ctx> {
             promise_id.clone(),
             |ctx| {
                 Box::pin(async move {
-                    ctx.public_state.promise_service.delete(promise_id).await;
+                    ctx.public_state
+                        .promise_service
+                        .delete(promise_id.clone())
+                        .await;
+                    ctx.state
+                        .worker_service
+                        .remove_dependent(
+                            &ctx.owned_worker_id,
+                            &WorkerDependent::Promise(promise_id),
+                        )
+                        .await;
                     Ok(())
                 })
             },
@@ -528,7 +552,26 @@ impl<Ctx: WorkerCtx> golem::api0_2_0::host::Host for DurableWorkerCtx<Ctx> {
     async fn get_self_metadata(&mut self) -> anyhow::Result<WorkerMetadata> {
         let _permit = self.begin_async_host_function().await?;
         record_host_function_call("golem::api", "get_self_metadata");
-        let metadata = self.public_state.worker().get_metadata().await?;
+        if !self
+            .is_durable_in_oplog("golem::api::get-self-metadata")
+            .await
+        {
+            let metadata = self.public_state.worker().get_metadata().await?;
+            return Ok(metadata.into());
+        }
+        let metadata = Durability::<Ctx, (), SerializableWorkerMetadata, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::ReadLocal,
+            "golem::api::get-self-metadata",
+            (),
+            |ctx| {
+                Box::pin(async move {
+                    let metadata = ctx.public_state.worker().get_metadata().await?;
+                    Ok::<_, GolemError>(metadata.into())
+                })
+            },
+        )
+        .await?;
         Ok(metadata.into())
     }
 
@@ -540,21 +583,63 @@ impl<Ctx: WorkerCtx> golem::api0_2_0::host::Host for DurableWorkerCtx<Ctx> {
         record_host_function_call("golem::api", "get_worker_metadata");
         let worker_id: WorkerId = worker_id.into();
         let owned_worker_id = OwnedWorkerId::new(&self.owned_worker_id.account_id, &worker_id);
-        let metadata = self.state.worker_service.get(&owned_worker_id).await;
+        if !self
+            .is_durable_in_oplog("golem::api::get-worker-metadata")
+            .await
+        {
+            let metadata = self.current_worker_metadata(&owned_worker_id).await?;
+            return Ok(metadata.map(|metadata| metadata.into()));
+        }
+        let metadata = Durability::<
+            Ctx,
+            WorkerId,
+            Option<SerializableWorkerMetadata>,
+            SerializableError,
+        >::wrap(
+            self,
+            WrappedFunctionType::ReadRemote,
+            "golem::api::get-worker-metadata",
+            worker_id,
+            |ctx| {
+                Box::pin(async move {
+                    let metadata = ctx.current_worker_metadata(&owned_worker_id).await?;
+                    Ok::<_, GolemError>(metadata.map(|metadata| metadata.into()))
+                })
+            },
+        )
+        .await?;
+        Ok(metadata.map(|metadata| metadata.into()))
+    }
+}
 
-        match metadata {
+impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
+    /// The worker metadata queries only became durable in a later release. Oplogs recorded
+    /// before do not hold their results, so they are executed again when replaying those.
+    async fn is_durable_in_oplog(&mut self, function_name: &str) -> bool {
+        self.state.is_live()
+            || self
+                .state
+                .replay_state
+                .is_imported_function_recorded_next(function_name)
+                .await
+    }
+
+    async fn current_worker_metadata(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Result<Option<golem_common::model::WorkerMetadata>, GolemError> {
+        match self.state.worker_service.get(owned_worker_id).await {
             Some(metadata) => {
                 let last_known_status = Ctx::compute_latest_worker_status(
                     &self.state,
-                    &owned_worker_id,
+                    owned_worker_id,
                     &Some(metadata.clone()),
                 )
                 .await?;
-                let updated_metadata = golem_common::model::WorkerMetadata {
+                Ok(Some(golem_common::model::WorkerMetadata {
                     last_known_status,
                     ..metadata
-                };
-                Ok(Some(updated_metadata.into()))
+                }))
             }
             None => Ok(None),
         }
@@ -934,6 +1019,19 @@ impl From<golem::api0_2_0::host::WorkerAnyFilter> for golem_common::model::Worke
     }
 }
 
+impl From<SerializableWorkerMetadata> for WorkerMetadata {
+    fn from(value: SerializableWorkerMetadata) -> Self {
+        Self {
+            worker_id: value.worker_id.into(),
+            args: value.args,
+            env: value.env,
+            status: value.status.into(),
+            component_version: value.component_version,
+            retry_count: 0,
+        }
+    }
+}
+
 impl From<golem_common::model::WorkerMetadata> for WorkerMetadata {
     fn from(value: golem_common::model::WorkerMetadata) -> Self {
         Self {
@@ -0,0 +1,149 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use golem_common::model::oplog::WrappedFunctionType;
+
+use crate::durable_host::io::poll::is_suspend_for_sleep;
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx, SuspendForSleep};
+use crate::metrics::wasm::record_host_function_call;
+use crate::model::InterruptKind;
+use crate::preview2::scheduling::golem::api::scheduling::{Datetime, Host};
+use crate::workerctx::WorkerCtx;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    /// The call is only persisted once the target time has passed. Until then the worker is
+    /// suspended, and when the scheduler resumes it the replay reaches the call again and goes
+    /// live, completing it if the time has come or suspending the worker again otherwise.
+    async fn sleep_until(&mut self, timestamp: Datetime) -> anyhow::Result<()> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("golem::api::scheduling", "sleep-until");
+
+        let when = to_date_time(&timestamp)?;
+
+        let result = Durability::<Ctx, (u64, u32), (), SerializableError>::wrap_conditionally(
+            self,
+            WrappedFunctionType::ReadLocal,
+            "golem::api::scheduling::sleep-until",
+            (timestamp.seconds, timestamp.nanoseconds),
+            |_ctx| {
+                Box::pin(async move {
+                    match remaining(when, Utc::now()) {
+                        Some(remaining) => Err(SuspendForSleep(remaining).into()),
+                        None => Ok(()),
+                    }
+                })
+            },
+            |result| is_suspend_for_sleep(result).is_none(), // We must not persist the suspend signal
+        )
+        .await;
+
+        match is_suspend_for_sleep(&result) {
+            Some(_) => {
+                self.state.sleep_until(when).await?;
+                Err(InterruptKind::Suspend.into())
+            }
+            None => result,
+        }
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
+    async fn sleep_until(&mut self, timestamp: Datetime) -> anyhow::Result<()> {
+        (*self).sleep_until(timestamp).await
+    }
+}
+
+fn to_date_time(timestamp: &Datetime) -> anyhow::Result<DateTime<Utc>> {
+    i64::try_from(timestamp.seconds)
+        .ok()
+        .and_then(|seconds| DateTime::<Utc>::from_timestamp(seconds, timestamp.nanoseconds))
+        .ok_or_else(|| anyhow!("Invalid timestamp to sleep until: {timestamp:?}"))
+}
+
+/// The time left until `when`, or `None` if it has already come
+fn remaining(when: DateTime<Utc>, now: DateTime<Utc>) -> Option<Duration> {
+    (when - now)
+        .to_std()
+        .ok()
+        .filter(|remaining| !remaining.is_zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::time::Duration;
+
+    use chrono::{DateTime, Utc};
+
+    use crate::durable_host::golem::scheduling::{remaining, to_date_time};
+    use crate::durable_host::io::poll::is_suspend_for_sleep;
+    use crate::durable_host::SuspendForSleep;
+    use crate::preview2::scheduling::golem::api::scheduling::Datetime;
+
+    #[test]
+    fn timestamps_are_converted() {
+        assert_eq!(
+            to_date_time(&Datetime {
+                seconds: 1_700_000_000,
+                nanoseconds: 500_000_000,
+            })
+            .unwrap(),
+            DateTime::<Utc>::from_timestamp(1_700_000_000, 500_000_000).unwrap()
+        );
+        assert!(to_date_time(&Datetime {
+            seconds: 1_700_000_000,
+            nanoseconds: 2_000_000_000,
+        })
+        .is_err());
+        assert!(to_date_time(&Datetime {
+            seconds: u64::MAX,
+            nanoseconds: 0,
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn sleeps_only_until_the_target_time() {
+        let now = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+
+        assert_eq!(
+            remaining(now + chrono::Duration::hours(2), now),
+            Some(Duration::from_secs(2 * 60 * 60))
+        );
+        assert_eq!(remaining(now, now), None);
+        assert_eq!(remaining(now - chrono::Duration::seconds(1), now), None);
+    }
+
+    #[test]
+    fn suspending_is_not_a_failure_of_the_call() {
+        let suspended: anyhow::Result<()> = Err(SuspendForSleep(Duration::from_secs(10)).into());
+        let failed: anyhow::Result<()> = Err(anyhow::anyhow!("failed"));
+
+        assert_eq!(
+            is_suspend_for_sleep(&suspended),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(is_suspend_for_sleep(&failed), None);
+        assert_eq!(is_suspend_for_sleep(&Ok(())), None);
+    }
+}
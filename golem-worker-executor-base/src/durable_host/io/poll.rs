@@ -94,7 +94,7 @@ impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
     }
 }
 
-fn is_suspend_for_sleep<T>(result: &Result<T, anyhow::Error>) -> Option<Duration> {
+pub(crate) fn is_suspend_for_sleep<T>(result: &Result<T, anyhow::Error>) -> Option<Duration> {
     if let Err(err) = result {
         if let Some(SuspendForSleep(duration)) = err.root_cause().downcast_ref::<SuspendForSleep>()
         {
@@ -210,7 +210,14 @@ impl<Ctx: WorkerCtx> HostOutputStream for DurableWorkerCtx<Ctx> {
             Ok::<(), StreamError>(())
         } else {
             // Non-stdout writes are non-persistent and always executed
-            HostOutputStream::write(&mut self.as_wasi_view(), self_, contents).await
+            let handle = self_.rep();
+            let len = contents.len() as u64;
+            let old_size = self.begin_file_stream_write(handle, len).await?;
+            HostOutputStream::write(&mut self.as_wasi_view(), self_, contents).await?;
+            if let Some(old_size) = old_size {
+                self.end_file_stream_write(handle, old_size, len);
+            }
+            Ok(())
         }
     }
 
@@ -249,7 +256,13 @@ impl<Ctx: WorkerCtx> HostOutputStream for DurableWorkerCtx<Ctx> {
     ) -> Result<(), StreamError> {
         let _permit = self.begin_async_host_function().await?;
         record_host_function_call("io::streams::output_stream", "write_zeroeas");
-        HostOutputStream::write_zeroes(&mut self.as_wasi_view(), self_, len).await
+        let handle = self_.rep();
+        let old_size = self.begin_file_stream_write(handle, len).await?;
+        HostOutputStream::write_zeroes(&mut self.as_wasi_view(), self_, len).await?;
+        if let Some(old_size) = old_size {
+            self.end_file_stream_write(handle, old_size, len);
+        }
+        Ok(())
     }
 
     async fn blocking_write_zeroes_and_flush(
@@ -262,8 +275,14 @@ impl<Ctx: WorkerCtx> HostOutputStream for DurableWorkerCtx<Ctx> {
             "io::streams::output_stream",
             "blocking_write_zeroes_and_flush",
         );
+        let handle = self_.rep();
+        let old_size = self.begin_file_stream_write(handle, len).await?;
         HostOutputStream::blocking_write_zeroes_and_flush(&mut self.as_wasi_view(), self_, len)
-            .await
+            .await?;
+        if let Some(old_size) = old_size {
+            self.end_file_stream_write(handle, old_size, len);
+        }
+        Ok(())
     }
 
     async fn splice(
@@ -274,7 +293,15 @@ impl<Ctx: WorkerCtx> HostOutputStream for DurableWorkerCtx<Ctx> {
     ) -> Result<u64, StreamError> {
         let _permit = self.begin_async_host_function().await?;
         record_host_function_call("io::streams::output_stream", "splice");
-        HostOutputStream::splice(&mut self.as_wasi_view(), self_, src, len).await
+        // The number of spliced bytes is only known afterwards, so splices are only denied
+        // once the worker's disk usage limit has already been exceeded
+        let handle = self_.rep();
+        let old_size = self.begin_file_stream_write(handle, 0).await?;
+        let written = HostOutputStream::splice(&mut self.as_wasi_view(), self_, src, len).await?;
+        if let Some(old_size) = old_size {
+            self.end_file_stream_write(handle, old_size, written);
+        }
+        Ok(written)
     }
 
     async fn blocking_splice(
@@ -285,11 +312,19 @@ impl<Ctx: WorkerCtx> HostOutputStream for DurableWorkerCtx<Ctx> {
     ) -> Result<u64, StreamError> {
         let _permit = self.begin_async_host_function().await?;
         record_host_function_call("io::streams::output_stream", "blocking_splice");
-        HostOutputStream::blocking_splice(&mut self.as_wasi_view(), self_, src, len).await
+        let handle = self_.rep();
+        let old_size = self.begin_file_stream_write(handle, 0).await?;
+        let written =
+            HostOutputStream::blocking_splice(&mut self.as_wasi_view(), self_, src, len).await?;
+        if let Some(old_size) = old_size {
+            self.end_file_stream_write(handle, old_size, written);
+        }
+        Ok(written)
     }
 
     fn drop(&mut self, rep: Resource<OutputStream>) -> anyhow::Result<()> {
         record_host_function_call("io::streams::output_stream", "drop");
+        self.unregister_file_write_stream(rep.rep());
         HostOutputStream::drop(&mut self.as_wasi_view(), rep)
     }
 }
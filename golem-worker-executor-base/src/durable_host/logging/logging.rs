@@ -18,6 +18,24 @@ use crate::preview2::wasi::logging::logging::{Host, Level};
 use crate::workerctx::WorkerCtx;
 use async_trait::async_trait;
 use golem_common::model::{LogLevel, WorkerEvent};
+use std::str::FromStr;
+use tracing::warn;
+
+/// Worker environment variable selecting the minimum level of the messages the worker
+/// logs through `wasi:logging`. Messages below this level are neither emitted as events
+/// nor persisted in the oplog.
+pub const LOG_LEVEL_ENV_VAR: &str = "GOLEM_LOG_LEVEL";
+
+pub(crate) fn min_log_level(env: &[(String, String)]) -> LogLevel {
+    env.iter()
+        .find(|(key, _)| key == LOG_LEVEL_ENV_VAR)
+        .and_then(|(_, value)| {
+            LogLevel::from_str(value)
+                .map_err(|err| warn!("Ignoring {LOG_LEVEL_ENV_VAR}: {err}"))
+                .ok()
+        })
+        .unwrap_or(LogLevel::Trace)
+}
 
 #[async_trait]
 impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
@@ -33,6 +51,10 @@ impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
             Level::Debug => LogLevel::Debug,
             Level::Trace => LogLevel::Trace,
         };
+        if !self.state.min_log_level.allows(log_level) {
+            return Ok(());
+        }
+
         let event = WorkerEvent::log(log_level, &context, &message);
         self.emit_log_event(event).await;
         Ok(())
@@ -45,3 +67,40 @@ impl<Ctx: WorkerCtx> Host for &mut DurableWorkerCtx<Ctx> {
         (*self).log(level, context, message).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::LogLevel;
+
+    use crate::durable_host::logging::logging::{min_log_level, LOG_LEVEL_ENV_VAR};
+
+    fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn everything_is_logged_by_default() {
+        assert_eq!(min_log_level(&env(&[("OTHER", "warn")])), LogLevel::Trace);
+    }
+
+    #[test]
+    fn log_level_is_read_from_the_environment() {
+        let level = min_log_level(&env(&[("OTHER", "debug"), (LOG_LEVEL_ENV_VAR, "Warn")]));
+
+        assert_eq!(level, LogLevel::Warn);
+        assert!(!level.allows(LogLevel::Info));
+        assert!(level.allows(LogLevel::Error));
+    }
+
+    #[test]
+    fn invalid_log_level_is_ignored() {
+        assert_eq!(
+            min_log_level(&env(&[(LOG_LEVEL_ENV_VAR, "verbose")])),
+            LogLevel::Trace
+        );
+    }
+}
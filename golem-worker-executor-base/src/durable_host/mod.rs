@@ -15,7 +15,7 @@
 // WASI Host implementation for Golem, delegating to the core WASI implementation (wasmtime_wasi)
 // implementing the Golem specific instrumentation on top of it.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 use std::ops::Add;
@@ -29,7 +29,10 @@ use crate::model::{
     WorkerConfig,
 };
 use crate::services::blob_store::BlobStoreService;
+use crate::services::domain_event::DomainEventService;
+use crate::services::feature_flags::FeatureFlagService;
 use crate::services::golem_config::GolemConfig;
+use crate::services::inference::InferenceService;
 use crate::services::key_value::KeyValueService;
 use crate::services::promise::PromiseService;
 use crate::services::worker::WorkerService;
@@ -42,17 +45,22 @@ use crate::workerctx::{
 use anyhow::anyhow;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures_util::stream::BoxStream;
+use futures_util::{stream, StreamExt};
 use golem_common::config::RetryConfig;
 use golem_common::model::oplog::{
     IndexedResourceKey, LogLevel, OplogEntry, OplogIndex, UpdateDescription, WorkerError,
     WorkerResourceId, WrappedFunctionType,
 };
 use golem_common::model::regions::{DeletedRegions, OplogRegion};
+use golem_common::model::worker_defaults::{
+    guest_env, max_disk_usage_from_env, max_memory_from_env, retry_policy_from_env,
+};
 use golem_common::model::{
-    AccountId, ComponentId, ComponentType, ComponentVersion, FailedUpdateRecord, IdempotencyKey,
-    OwnedWorkerId, ScanCursor, ScheduledAction, SuccessfulUpdateRecord, Timestamp, WorkerEvent,
-    WorkerFilter, WorkerId, WorkerMetadata, WorkerResourceDescription, WorkerStatus,
-    WorkerStatusRecord,
+    AccountId, CallerIdentity, ComponentId, ComponentType, ComponentVersion, ConnectOptions,
+    FailedUpdateRecord, IdempotencyKey, OwnedWorkerId, ScanCursor, ScheduledAction,
+    SuccessfulUpdateRecord, Timestamp, UpdateDivergence, WorkerEvent, WorkerFilter, WorkerId,
+    WorkerMetadata, WorkerResourceDescription, WorkerStatus, WorkerStatusRecord,
 };
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use golem_wasm_rpc::wasmtime::ResourceStore;
@@ -86,8 +94,10 @@ pub mod golem;
 pub mod http;
 pub mod io;
 pub mod keyvalue;
+pub mod nn;
 mod logging;
 mod random;
+pub mod rest;
 pub mod serialized;
 mod sockets;
 pub mod wasm_rpc;
@@ -96,8 +106,9 @@ mod durability;
 mod replay_state;
 mod sync_helper;
 
+use crate::durable_host::filesystem::disk_usage::{DiskUsage, FileWriteStream};
 use crate::durable_host::http::serialized::SerializableHttpRequest;
-use crate::durable_host::replay_state::ReplayState;
+use crate::durable_host::replay_state::{ReplayState, ReplayedInvocation};
 use crate::durable_host::sync_helper::{SyncHelper, SyncHelperPermit};
 use crate::function_result_interpreter::interpret_function_results;
 use crate::services::component::{ComponentMetadata, ComponentService};
@@ -130,6 +141,9 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
         >,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        domain_event_service: Arc<dyn DomainEventService + Send + Sync>,
+        feature_flag_service: Arc<dyn FeatureFlagService + Send + Sync>,
+        inference_service: Arc<dyn InferenceService + Send + Sync>,
         event_service: Arc<dyn WorkerEventService + Send + Sync>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
         oplog: Arc<dyn Oplog + Send + Sync>,
@@ -163,7 +177,7 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
 
         let (wasi, table) = wasi_host::create_context(
             &worker_config.args,
-            &worker_config.env,
+            &guest_env(&worker_config.env),
             temp_dir.path().to_path_buf(),
             stdin,
             stdout,
@@ -193,6 +207,9 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
                 worker_enumeration_service,
                 key_value_service,
                 blob_store_service,
+                domain_event_service,
+                feature_flag_service,
+                inference_service,
                 component_service,
                 config.clone(),
                 owned_worker_id.clone(),
@@ -202,6 +219,10 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
                 last_oplog_index,
                 component_metadata,
                 worker_config.total_linear_memory_size,
+                max_memory_from_env(&worker_config.env),
+                max_disk_usage_from_env(&worker_config.env),
+                retry_policy_from_env(&worker_config.env),
+                logging::logging::min_log_level(&worker_config.env),
             )
             .await,
             _temp_dir: temp_dir,
@@ -278,11 +299,38 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
             .current_idempotency_key
             .clone_from(&self.state.current_idempotency_key);
         status.total_linear_memory_size = self.state.total_linear_memory_size;
+        status.disk_usage = self.state.disk_usage.bytes();
         status.oplog_idx = self.state.oplog.current_oplog_index().await;
         f(&mut status);
         self.public_state.worker().update_status(status).await;
     }
 
+    /// Adds a failed update entry to the oplog and the worker status, returning its timestamp
+    async fn record_failed_update(
+        &self,
+        target_version: ComponentVersion,
+        details: Option<String>,
+    ) -> Timestamp {
+        let entry = OplogEntry::failed_update(target_version, details.clone());
+        let timestamp = entry.timestamp();
+        self.public_state.oplog.add_and_commit(entry).await;
+        self.update_worker_status(|status| {
+            status.failed_updates.push(FailedUpdateRecord {
+                timestamp,
+                target_version,
+                details: details.clone(),
+            })
+        })
+        .await;
+
+        warn!(
+            "Worker failed to update to {}: {}, update attempt aborted",
+            target_version,
+            details.unwrap_or_else(|| "?".to_string())
+        );
+        timestamp
+    }
+
     pub fn rpc(&self) -> Arc<dyn Rpc + Send + Sync> {
         self.state.rpc.clone()
     }
@@ -300,6 +348,16 @@ impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
             // The increased amount was already recorded in live mode, so our worker
             // was initialized with the correct amount of memory.
             Ok(true)
+        } else if self
+            .state
+            .max_memory
+            .is_some_and(|max_memory| self.state.total_linear_memory_size + delta > max_memory)
+        {
+            debug!(
+                "Denying memory growth of {delta} bytes for worker {}, exceeding its memory limit",
+                self.owned_worker_id.worker_id
+            );
+            Ok(false)
         } else {
             // In live mode we need to try to get more memory permits and if we can't,
             // we fail the worker, unload it from memory and schedule a retry.
@@ -570,9 +628,10 @@ impl<Ctx: WorkerCtx + DurableWorkerCtxView<Ctx>> DurableWorkerCtx<Ctx> {
                     store
                         .as_context_mut()
                         .data_mut()
-                        .on_worker_update_failed(
+                        .durable_ctx_mut()
+                        .on_automatic_update_diverged(
                             target_version,
-                            Some("Automatic update failed".to_string()),
+                            "Automatic update failed".to_string(),
                         )
                         .await;
                     RetryDecision::Immediate
@@ -583,9 +642,10 @@ impl<Ctx: WorkerCtx + DurableWorkerCtxView<Ctx>> DurableWorkerCtx<Ctx> {
                     store
                         .as_context_mut()
                         .data_mut()
-                        .on_worker_update_failed(
+                        .durable_ctx_mut()
+                        .on_automatic_update_diverged(
                             target_version,
-                            Some(format!("Automatic update failed: {error}")),
+                            format!("Automatic update failed: {error}"),
                         )
                         .await;
                     RetryDecision::Immediate
@@ -597,6 +657,29 @@ impl<Ctx: WorkerCtx + DurableWorkerCtxView<Ctx>> DurableWorkerCtx<Ctx> {
             }
         }
     }
+
+    /// Records a failed automatic update together with the point where replaying the oplog with
+    /// the new version diverged from the recorded history, if that is why it failed
+    async fn on_automatic_update_diverged(&self, target_version: ComponentVersion, error: String) {
+        let divergence = self.state.replay_state.divergence().await;
+
+        let timestamp = self.record_failed_update(target_version, Some(error)).await;
+        if let Some(divergence) = divergence {
+            self.state
+                .worker_service
+                .add_update_divergence(
+                    &self.owned_worker_id,
+                    UpdateDivergence {
+                        timestamp,
+                        target_version,
+                        oplog_index: divergence.oplog_index,
+                        expected: divergence.expected,
+                        got: divergence.got,
+                    },
+                )
+                .await;
+        }
+    }
 }
 
 #[async_trait]
@@ -609,6 +692,14 @@ impl<Ctx: WorkerCtx> InvocationManagement for DurableWorkerCtx<Ctx> {
         self.state.get_current_idempotency_key()
     }
 
+    async fn set_current_caller_identity(&mut self, caller: Option<CallerIdentity>) {
+        self.state.set_current_caller_identity(caller)
+    }
+
+    async fn get_current_caller_identity(&self) -> Option<CallerIdentity> {
+        self.state.get_current_caller_identity()
+    }
+
     fn is_live(&self) -> bool {
         self.state.is_live()
     }
@@ -635,34 +726,42 @@ impl<Ctx: WorkerCtx> StatusManagement for DurableWorkerCtx<Ctx> {
         let current_execution_status = execution_status.clone();
         match current_execution_status {
             ExecutionStatus::Running {
-                last_known_status, ..
+                last_known_status,
+                held_invocations,
+                ..
             } => {
                 *execution_status = ExecutionStatus::Suspended {
                     last_known_status,
                     component_type: self.component_metadata().component_type,
                     timestamp: Timestamp::now_utc(),
+                    held_invocations,
                 };
             }
             ExecutionStatus::Suspended { .. } => {}
             ExecutionStatus::Interrupting {
                 await_interruption,
                 last_known_status,
+                held_invocations,
                 ..
             } => {
                 *execution_status = ExecutionStatus::Suspended {
                     last_known_status,
                     component_type: self.component_metadata().component_type,
                     timestamp: Timestamp::now_utc(),
+                    held_invocations,
                 };
                 await_interruption.send(()).ok();
             }
             ExecutionStatus::Loading {
-                last_known_status, ..
+                last_known_status,
+                held_invocations,
+                ..
             } => {
                 *execution_status = ExecutionStatus::Suspended {
                     last_known_status,
                     component_type: self.component_metadata().component_type,
                     timestamp: Timestamp::now_utc(),
+                    held_invocations,
                 };
             }
         };
@@ -676,22 +775,28 @@ impl<Ctx: WorkerCtx> StatusManagement for DurableWorkerCtx<Ctx> {
         match current_execution_status {
             ExecutionStatus::Running { .. } => {}
             ExecutionStatus::Suspended {
-                last_known_status, ..
+                last_known_status,
+                held_invocations,
+                ..
             } => {
                 *execution_status = ExecutionStatus::Running {
                     last_known_status,
                     component_type: self.component_metadata().component_type,
                     timestamp: Timestamp::now_utc(),
+                    held_invocations,
                 };
             }
             ExecutionStatus::Interrupting { .. } => {}
             ExecutionStatus::Loading {
-                last_known_status, ..
+                last_known_status,
+                held_invocations,
+                ..
             } => {
                 *execution_status = ExecutionStatus::Running {
                     last_known_status,
                     component_type: self.component_metadata().component_type,
                     timestamp: Timestamp::now_utc(),
+                    held_invocations,
                 };
             }
         }
@@ -752,6 +857,7 @@ impl<Ctx: WorkerCtx> InvocationHooks for DurableWorkerCtx<Ctx> {
         full_function_name: &str,
         function_input: &Vec<Value>,
     ) -> Result<(), GolemError> {
+        self.state.feature_flags = None;
         if self.state.snapshotting_mode.is_none() {
             let proto_function_input: Vec<golem_wasm_rpc::protobuf::Val> = function_input
                 .iter()
@@ -766,6 +872,7 @@ impl<Ctx: WorkerCtx> InvocationHooks for DurableWorkerCtx<Ctx> {
                     self.get_current_idempotency_key().await.ok_or(anyhow!(
                         "No active invocation key is associated with the worker"
                     ))?,
+                    self.get_current_caller_identity().await,
                 )
                 .await
                 .unwrap_or_else(|err| {
@@ -881,13 +988,19 @@ impl<Ctx: WorkerCtx> InvocationHooks for DurableWorkerCtx<Ctx> {
                 .get_oplog_entry_exported_function_completed()
                 .await?;
 
-            if let Some(function_output) = response {
-                let is_diverged = function_output != output;
+            if let Some((oplog_idx, function_output)) = response {
+                let is_diverged = function_output != output
+                    && !self.state.replay_state.is_redacted(oplog_idx).await;
                 if is_diverged {
-                    return Err(GolemError::unexpected_oplog_entry(
+                    let error = GolemError::unexpected_oplog_entry(
                         format!("{full_function_name}({function_input:?}) => {function_output:?}"),
                         format!("{full_function_name}({function_input:?}) => {output:?}"),
-                    ));
+                    );
+                    self.state
+                        .replay_state
+                        .record_divergence(oplog_idx, &error)
+                        .await;
+                    return Err(error);
                 }
             }
         }
@@ -972,23 +1085,7 @@ impl<Ctx: WorkerCtx> UpdateManagement for DurableWorkerCtx<Ctx> {
         target_version: ComponentVersion,
         details: Option<String>,
     ) {
-        let entry = OplogEntry::failed_update(target_version, details.clone());
-        let timestamp = entry.timestamp();
-        self.public_state.oplog.add_and_commit(entry).await;
-        self.update_worker_status(|status| {
-            status.failed_updates.push(FailedUpdateRecord {
-                timestamp,
-                target_version,
-                details: details.clone(),
-            })
-        })
-        .await;
-
-        warn!(
-            "Worker failed to update to {}: {}, update attempt aborted",
-            target_version,
-            details.unwrap_or_else(|| "?".to_string())
-        );
+        self.record_failed_update(target_version, details).await;
     }
 
     async fn on_worker_update_succeeded(
@@ -1150,7 +1247,12 @@ impl<Ctx: WorkerCtx + DurableWorkerCtxView<Ctx>> ExternalOperations<Ctx> for Dur
                     match oplog_entry {
                         Err(error) => break Err(error),
                         Ok(None) => break Ok(RetryDecision::None),
-                        Ok(Some((function_name, function_input, idempotency_key))) => {
+                        Ok(Some(ReplayedInvocation {
+                            function_name,
+                            function_input,
+                            idempotency_key,
+                            caller,
+                        })) => {
                             debug!("Replaying function {function_name}");
                             let span = span!(Level::INFO, "replaying", function = function_name);
                             store
@@ -1158,6 +1260,11 @@ impl<Ctx: WorkerCtx + DurableWorkerCtxView<Ctx>> ExternalOperations<Ctx> for Dur
                                 .data_mut()
                                 .set_current_idempotency_key(idempotency_key)
                                 .await;
+                            store
+                                .as_context_mut()
+                                .data_mut()
+                                .set_current_caller_identity(caller)
+                                .await;
 
                             let full_function_name = function_name.to_string();
                             let invoke_result = invoke_worker(
@@ -1348,6 +1455,7 @@ impl<Ctx: WorkerCtx + DurableWorkerCtxView<Ctx>> ExternalOperations<Ctx> for Dur
         let default_retry_config = &this.config().retry;
         for worker in workers {
             let owned_worker_id = worker.owned_worker_id();
+            let env_retry_config = retry_policy_from_env(&worker.env);
             let actualized_metadata =
                 calculate_last_known_status(this, &owned_worker_id, &Some(worker)).await?;
             let last_error = Self::get_last_error_and_retry_count(this, &owned_worker_id).await;
@@ -1355,6 +1463,7 @@ impl<Ctx: WorkerCtx + DurableWorkerCtxView<Ctx>> ExternalOperations<Ctx> for Dur
                 actualized_metadata
                     .overridden_retry_config
                     .as_ref()
+                    .or(env_retry_config.as_ref())
                     .unwrap_or(default_retry_config),
                 &last_error,
             );
@@ -1490,7 +1599,8 @@ pub(crate) async fn recover_stderr_logs<T: HasOplogService + HasConfig>(
                     break;
                 }
             }
-            Some((_, OplogEntry::ExportedFunctionInvoked { .. })) => break,
+            Some((_, OplogEntry::ExportedFunctionInvokedV1 { .. }))
+            | Some((_, OplogEntry::ExportedFunctionInvoked { .. })) => break,
             _ => {}
         }
         if idx > OplogIndex::INITIAL {
@@ -1503,6 +1613,72 @@ pub(crate) async fn recover_stderr_logs<T: HasOplogService + HasConfig>(
     stderr_entries.join("")
 }
 
+/// The number of oplog entries read at once when replaying the log events of a worker
+const LOG_EVENT_REPLAY_CHUNK_SIZE: u64 = 1024;
+
+/// Recreates the log events recorded in the oplog until `last_oplog_idx`, as requested by
+/// `options`. The last `n` events are found by reading the oplog backwards, otherwise all of them
+/// are read from the beginning, one chunk of the oplog at a time as the stream gets consumed.
+pub(crate) async fn recover_log_events<T: HasOplogService>(
+    this: &T,
+    owned_worker_id: &OwnedWorkerId,
+    last_oplog_idx: OplogIndex,
+    options: &ConnectOptions,
+) -> BoxStream<'static, WorkerEvent> {
+    if last_oplog_idx < OplogIndex::INITIAL {
+        return stream::empty().boxed();
+    }
+    let oplog_service = this.oplog_service();
+    match options.last_n {
+        Some(n) => {
+            let n = n as usize;
+            let mut events = Vec::new();
+            let mut end = last_oplog_idx;
+            while events.len() < n {
+                let count = u64::from(end).min(LOG_EVENT_REPLAY_CHUNK_SIZE);
+                let start = OplogIndex::from_u64(u64::from(end) - count + 1);
+                let entries = oplog_service.read_range(owned_worker_id, start, end).await;
+                events.extend(
+                    entries
+                        .values()
+                        .rev()
+                        .filter_map(WorkerEvent::from_oplog_entry)
+                        .take(n - events.len()),
+                );
+                if start == OplogIndex::INITIAL {
+                    break;
+                }
+                end = start.previous();
+            }
+            events.reverse();
+            stream::iter(events).boxed()
+        }
+        None => {
+            let owned_worker_id = owned_worker_id.clone();
+            stream::unfold(OplogIndex::INITIAL, move |start| {
+                let oplog_service = oplog_service.clone();
+                let owned_worker_id = owned_worker_id.clone();
+                async move {
+                    if start > last_oplog_idx {
+                        return None;
+                    }
+                    let end = start
+                        .range_end(LOG_EVENT_REPLAY_CHUNK_SIZE)
+                        .min(last_oplog_idx);
+                    let entries = oplog_service.read_range(&owned_worker_id, start, end).await;
+                    let events: Vec<_> = entries
+                        .values()
+                        .filter_map(WorkerEvent::from_oplog_entry)
+                        .collect();
+                    Some((stream::iter(events), end.next()))
+                }
+            })
+            .flatten()
+            .boxed()
+        }
+    }
+}
+
 /// Indicates which step of the http request handling is responsible for closing an open
 /// http request (by calling end_function)
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1533,10 +1709,14 @@ pub struct PrivateDurableWorkerState {
     worker_enumeration_service: Arc<dyn worker_enumeration::WorkerEnumerationService + Send + Sync>,
     key_value_service: Arc<dyn KeyValueService + Send + Sync>,
     blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+    domain_event_service: Arc<dyn DomainEventService + Send + Sync>,
+    feature_flag_service: Arc<dyn FeatureFlagService + Send + Sync>,
+    inference_service: Arc<dyn InferenceService + Send + Sync>,
     component_service: Arc<dyn ComponentService + Send + Sync>,
     config: Arc<GolemConfig>,
     owned_worker_id: OwnedWorkerId,
     current_idempotency_key: Option<IdempotencyKey>,
+    current_caller_identity: Option<CallerIdentity>,
     rpc: Arc<dyn Rpc + Send + Sync>,
     worker_proxy: Arc<dyn WorkerProxy + Send + Sync>,
     resources: HashMap<WorkerResourceId, ResourceAny>,
@@ -1556,7 +1736,24 @@ pub struct PrivateDurableWorkerState {
     component_metadata: ComponentMetadata,
 
     total_linear_memory_size: u64,
+    /// Upper limit of `total_linear_memory_size` set through the worker's environment
+    max_memory: Option<u64>,
+    /// Total size of the files in the worker's file system, limited through the worker's
+    /// environment
+    disk_usage: DiskUsage,
+    /// Output streams writing to files, key is the resource id of the stream
+    file_write_streams: HashMap<u32, FileWriteStream>,
     sync_helper: SyncHelper,
+
+    /// Log messages below this level emitted through `wasi:logging` are dropped
+    min_log_level: LogLevel,
+
+    /// Feature flags resolved for the current invocation, resolved on first access
+    feature_flags: Option<BTreeMap<String, bool>>,
+
+    /// Workers spawned through `golem:api/child-workers` and not terminated yet. Rebuilt by
+    /// replaying the oplog.
+    children: HashSet<WorkerId>,
 }
 
 impl PrivateDurableWorkerState {
@@ -1571,6 +1768,9 @@ impl PrivateDurableWorkerState {
         >,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        domain_event_service: Arc<dyn DomainEventService + Send + Sync>,
+        feature_flag_service: Arc<dyn FeatureFlagService + Send + Sync>,
+        inference_service: Arc<dyn InferenceService + Send + Sync>,
         component_service: Arc<dyn ComponentService + Send + Sync>,
         config: Arc<GolemConfig>,
         owned_worker_id: OwnedWorkerId,
@@ -1580,6 +1780,10 @@ impl PrivateDurableWorkerState {
         last_oplog_index: OplogIndex,
         component_metadata: ComponentMetadata,
         total_linear_memory_size: u64,
+        max_memory: Option<u64>,
+        max_disk_usage: Option<u64>,
+        overridden_retry_policy: Option<RetryConfig>,
+        min_log_level: LogLevel,
     ) -> Self {
         let replay_state = ReplayState::new(
             owned_worker_id.clone(),
@@ -1598,15 +1802,19 @@ impl PrivateDurableWorkerState {
             worker_enumeration_service,
             key_value_service,
             blob_store_service,
+            domain_event_service,
+            feature_flag_service,
+            inference_service,
             component_service,
             config,
             owned_worker_id,
             current_idempotency_key: None,
+            current_caller_identity: None,
             rpc,
             worker_proxy,
             resources: HashMap::new(),
             last_resource_id: WorkerResourceId::INITIAL,
-            overridden_retry_policy: None,
+            overridden_retry_policy,
             persistence_level: PersistenceLevel::Smart,
             assume_idempotence: true,
             open_function_table: HashMap::new(),
@@ -1615,8 +1823,14 @@ impl PrivateDurableWorkerState {
             indexed_resources: HashMap::new(),
             component_metadata,
             total_linear_memory_size,
+            max_memory,
+            disk_usage: DiskUsage::new(max_disk_usage),
+            file_write_streams: HashMap::new(),
             sync_helper: SyncHelper::new(oplog.clone(), replay_state.clone()),
             replay_state,
+            min_log_level,
+            feature_flags: None,
+            children: HashSet::new(),
         }
     }
 
@@ -1806,8 +2020,19 @@ impl PrivateDurableWorkerState {
         self.current_idempotency_key.clone()
     }
 
+    /// Sets the idempotency key of the invocation starting, which has no caller identity until
+    /// `set_current_caller_identity` is called
     pub fn set_current_idempotency_key(&mut self, invocation_key: IdempotencyKey) {
         self.current_idempotency_key = Some(invocation_key);
+        self.current_caller_identity = None;
+    }
+
+    pub fn get_current_caller_identity(&self) -> Option<CallerIdentity> {
+        self.current_caller_identity.clone()
+    }
+
+    pub fn set_current_caller_identity(&mut self, caller: Option<CallerIdentity>) {
+        self.current_caller_identity = caller;
     }
 
     /// Counts the number of Error entries that are at the end of the oplog. This equals to the number of retries that have been attempted.
@@ -1996,10 +2221,12 @@ macro_rules! get_oplog_entry {
                 })+
                 entry if entry.is_hint() => {}
                 _ => {
-                    break Err($crate::error::GolemError::unexpected_oplog_entry(
-                        stringify!($case),
+                    let error = $crate::error::GolemError::unexpected_oplog_entry(
+                        stringify!($($cases)|+),
                         format!("{:?}", oplog_entry),
-                    ));
+                    );
+                    $private_state.record_divergence(oplog_index, &error).await;
+                    break Err(error);
                 }
             }
         }
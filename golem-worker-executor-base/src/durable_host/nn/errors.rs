@@ -0,0 +1,46 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use wasmtime::component::Resource;
+use wasmtime_wasi::WasiView;
+
+use crate::durable_host::nn::ErrorEntry;
+use crate::durable_host::DurableWorkerCtx;
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::nn::wasi::nn::errors::{ErrorCode, Host, HostError};
+use crate::workerctx::WorkerCtx;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> HostError for DurableWorkerCtx<Ctx> {
+    async fn code(&mut self, self_: Resource<ErrorEntry>) -> anyhow::Result<ErrorCode> {
+        record_host_function_call("nn::errors::error", "code");
+        let error = self.as_wasi_view().table().get(&self_)?;
+        Ok(error.0.code.into())
+    }
+
+    async fn data(&mut self, self_: Resource<ErrorEntry>) -> anyhow::Result<String> {
+        record_host_function_call("nn::errors::error", "data");
+        let error = self.as_wasi_view().table().get(&self_)?;
+        Ok(error.0.message.clone())
+    }
+
+    fn drop(&mut self, rep: Resource<ErrorEntry>) -> anyhow::Result<()> {
+        record_host_function_call("nn::errors::error", "drop");
+        self.as_wasi_view().table().delete(rep)?;
+        Ok(())
+    }
+}
+
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {}
@@ -0,0 +1,183 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+use wasmtime::component::Resource;
+use wasmtime_wasi::WasiView;
+
+use crate::durable_host::nn::{
+    default_target, load_model_blocking, read_model, ErrorEntry, ExecutionContextEntry, GraphEntry,
+    LoadedGraph, ModelState,
+};
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::nn::wasi::nn::graph::{
+    Error, ExecutionTarget, Graph, GraphBuilder, GraphEncoding, GraphExecutionContext, Host,
+    HostGraph,
+};
+use crate::services::inference::{self, InferenceError, InferenceModel};
+use crate::workerctx::WorkerCtx;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> HostGraph for DurableWorkerCtx<Ctx> {
+    async fn init_execution_context(
+        &mut self,
+        self_: Resource<Graph>,
+    ) -> anyhow::Result<Result<Resource<GraphExecutionContext>, Resource<Error>>> {
+        record_host_function_call("nn::graph::graph", "init_execution_context");
+        let graph = self
+            .as_wasi_view()
+            .table()
+            .get::<GraphEntry>(&self_)?
+            .graph
+            .clone();
+        let context = self
+            .as_wasi_view()
+            .table()
+            .push(ExecutionContextEntry { graph })?;
+        Ok(Ok(context))
+    }
+
+    fn drop(&mut self, rep: Resource<Graph>) -> anyhow::Result<()> {
+        record_host_function_call("nn::graph::graph", "drop");
+        self.as_wasi_view().table().delete(rep)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {
+    async fn load(
+        &mut self,
+        builder: Vec<GraphBuilder>,
+        encoding: GraphEncoding,
+        target: ExecutionTarget,
+    ) -> anyhow::Result<Result<Resource<Graph>, Resource<Error>>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("nn::graph", "load");
+
+        let encoding: inference::GraphEncoding = encoding.into();
+        let target: inference::ExecutionTarget = target.into();
+        let builders = Arc::new(builder);
+        let size = builders.iter().map(|part| part.len() as u64).sum::<u64>();
+
+        // Only the fact that the model could be loaded is persisted, not the model itself
+        let loaded: Arc<Mutex<Option<Arc<dyn InferenceModel>>>> = Arc::new(Mutex::new(None));
+        let result = Durability::<
+            Ctx,
+            (inference::GraphEncoding, inference::ExecutionTarget, u64),
+            (),
+            InferenceError,
+        >::wrap(
+            self,
+            WrappedFunctionType::ReadLocal,
+            "wasi::nn::graph::load",
+            (encoding, target, size),
+            {
+                let builders = builders.clone();
+                let loaded = loaded.clone();
+                move |ctx| {
+                    Box::pin(async move {
+                        let model = load_model_blocking(
+                            &ctx.state.config,
+                            ctx.state.inference_service.clone(),
+                            builders,
+                            encoding,
+                            target,
+                        )
+                        .await?;
+                        *loaded.lock().unwrap() = Some(model);
+                        Ok(())
+                    })
+                }
+            },
+        )
+        .await;
+
+        let state = match loaded.lock().unwrap().take() {
+            Some(model) => ModelState::Loaded(model),
+            None => ModelState::FromBuilders(builders),
+        };
+        self.push_graph(result, LoadedGraph::new(encoding, target, state))
+    }
+
+    async fn load_by_name(
+        &mut self,
+        name: String,
+    ) -> anyhow::Result<Result<Resource<Graph>, Resource<Error>>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("nn::graph", "load_by_name");
+
+        let encoding = inference::GraphEncoding::Autodetect;
+        let target = default_target(&self.state.config);
+
+        let loaded: Arc<Mutex<Option<Arc<dyn InferenceModel>>>> = Arc::new(Mutex::new(None));
+        let result = Durability::<Ctx, String, (), InferenceError>::wrap(
+            self,
+            WrappedFunctionType::ReadRemote,
+            "wasi::nn::graph::load-by-name",
+            name.clone(),
+            {
+                let name = name.clone();
+                let loaded = loaded.clone();
+                move |ctx| {
+                    Box::pin(async move {
+                        let builders = read_model(ctx, &name).await?;
+                        let model = load_model_blocking(
+                            &ctx.state.config,
+                            ctx.state.inference_service.clone(),
+                            Arc::new(vec![builders]),
+                            encoding,
+                            target,
+                        )
+                        .await?;
+                        *loaded.lock().unwrap() = Some(model);
+                        Ok(())
+                    })
+                }
+            },
+        )
+        .await;
+
+        let state = match loaded.lock().unwrap().take() {
+            Some(model) => ModelState::Loaded(model),
+            None => ModelState::FromBlobStore(name),
+        };
+        self.push_graph(result, LoadedGraph::new(encoding, target, state))
+    }
+}
+
+impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
+    fn push_graph(
+        &mut self,
+        result: Result<(), InferenceError>,
+        graph: LoadedGraph,
+    ) -> anyhow::Result<Result<Resource<Graph>, Resource<Error>>> {
+        match result {
+            Ok(()) => {
+                let graph = self.as_wasi_view().table().push(GraphEntry {
+                    graph: Arc::new(graph),
+                })?;
+                Ok(Ok(graph))
+            }
+            Err(error) => {
+                let error = self.as_wasi_view().table().push(ErrorEntry(error))?;
+                Ok(Err(error))
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use golem_common::model::oplog::WrappedFunctionType;
+use wasmtime::component::Resource;
+use wasmtime_wasi::WasiView;
+
+use crate::durable_host::nn::{ErrorEntry, ExecutionContextEntry, TensorEntry};
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::nn::wasi::nn::inference::{
+    Error, GraphExecutionContext, Host, HostGraphExecutionContext, NamedTensor,
+};
+use crate::services::inference::{self, InferenceError, InferenceErrorCode, TensorType};
+use crate::workerctx::WorkerCtx;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> HostGraphExecutionContext for DurableWorkerCtx<Ctx> {
+    async fn compute(
+        &mut self,
+        self_: Resource<GraphExecutionContext>,
+        inputs: Vec<NamedTensor>,
+    ) -> anyhow::Result<Result<Vec<NamedTensor>, Resource<Error>>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call("nn::inference::graph_execution_context", "compute");
+
+        let graph = self
+            .as_wasi_view()
+            .table()
+            .get::<ExecutionContextEntry>(&self_)?
+            .graph
+            .clone();
+        let mut tensors = Vec::with_capacity(inputs.len());
+        for (name, tensor) in inputs {
+            let tensor = self.as_wasi_view().table().delete::<TensorEntry>(tensor)?;
+            tensors.push(inference::NamedTensor {
+                name,
+                tensor: tensor.0,
+            });
+        }
+
+        // The outputs are persisted as the result, so they do not depend on the model when replaying
+        let shapes = tensors
+            .iter()
+            .map(|input| {
+                (
+                    input.name.clone(),
+                    input.tensor.dimensions.clone(),
+                    input.tensor.ty,
+                )
+            })
+            .collect::<Vec<(String, Vec<u32>, TensorType)>>();
+        let result = Durability::<
+            Ctx,
+            Vec<(String, Vec<u32>, TensorType)>,
+            Vec<inference::NamedTensor>,
+            InferenceError,
+        >::wrap(
+            self,
+            WrappedFunctionType::ReadLocal,
+            "wasi::nn::inference::compute",
+            shapes,
+            move |ctx| {
+                Box::pin(async move {
+                    let model = graph.model(ctx).await?;
+                    tokio::task::spawn_blocking(move || model.compute(tensors))
+                        .await
+                        .map_err(|err| {
+                            InferenceError::new(
+                                InferenceErrorCode::RuntimeError,
+                                format!("Inference failed: {err}"),
+                            )
+                        })?
+                })
+            },
+        )
+        .await;
+
+        match result {
+            Ok(outputs) => {
+                let mut named_tensors = Vec::with_capacity(outputs.len());
+                for output in outputs {
+                    let tensor = self
+                        .as_wasi_view()
+                        .table()
+                        .push(TensorEntry(output.tensor))?;
+                    named_tensors.push((output.name, tensor));
+                }
+                Ok(Ok(named_tensors))
+            }
+            Err(error) => {
+                let error = self.as_wasi_view().table().push(ErrorEntry(error))?;
+                Ok(Err(error))
+            }
+        }
+    }
+
+    fn drop(&mut self, rep: Resource<GraphExecutionContext>) -> anyhow::Result<()> {
+        record_host_function_call("nn::inference::graph_execution_context", "drop");
+        self.as_wasi_view().table().delete(rep)?;
+        Ok(())
+    }
+}
+
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {}
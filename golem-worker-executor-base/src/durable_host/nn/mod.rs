@@ -0,0 +1,250 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod errors;
+pub mod graph;
+pub mod inference;
+pub mod tensor;
+
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::durable_host::DurableWorkerCtx;
+use crate::preview2::nn::wasi::nn;
+use crate::services::golem_config::{GolemConfig, WasiNnConfig, WasiNnEnabledConfig};
+use crate::services::inference::{
+    ExecutionTarget, GraphEncoding, InferenceError, InferenceErrorCode, InferenceModel,
+    InferenceService, Tensor, TensorType,
+};
+use crate::workerctx::WorkerCtx;
+
+// Loading a model and running inference are recorded in the oplog. Loaded models are not
+// persisted, so when these calls are replayed the model is only loaded again if the worker
+// runs inference after the replay.
+
+pub struct TensorEntry(pub Tensor);
+
+pub struct GraphEntry {
+    pub graph: Arc<LoadedGraph>,
+}
+
+pub struct ExecutionContextEntry {
+    pub graph: Arc<LoadedGraph>,
+}
+
+pub struct ErrorEntry(pub InferenceError);
+
+pub struct LoadedGraph {
+    encoding: GraphEncoding,
+    target: ExecutionTarget,
+    model: Mutex<ModelState>,
+}
+
+enum ModelState {
+    Loaded(Arc<dyn InferenceModel>),
+    FromBuilders(Arc<Vec<Vec<u8>>>),
+    FromBlobStore(String),
+}
+
+impl LoadedGraph {
+    fn new(encoding: GraphEncoding, target: ExecutionTarget, model: ModelState) -> Self {
+        Self {
+            encoding,
+            target,
+            model: Mutex::new(model),
+        }
+    }
+
+    /// Gets the loaded model, loading it if the graph was created by replaying the oplog
+    async fn model<Ctx: WorkerCtx>(
+        &self,
+        ctx: &mut DurableWorkerCtx<Ctx>,
+    ) -> Result<Arc<dyn InferenceModel>, InferenceError> {
+        let mut state = self.model.lock().await;
+        let model = match &*state {
+            ModelState::Loaded(model) => return Ok(model.clone()),
+            ModelState::FromBuilders(builders) => {
+                load_model_blocking(
+                    &ctx.state.config,
+                    ctx.state.inference_service.clone(),
+                    builders.clone(),
+                    self.encoding,
+                    self.target,
+                )
+                .await?
+            }
+            ModelState::FromBlobStore(name) => {
+                let builders = read_model(ctx, name).await?;
+                load_model_blocking(
+                    &ctx.state.config,
+                    ctx.state.inference_service.clone(),
+                    Arc::new(vec![builders]),
+                    self.encoding,
+                    self.target,
+                )
+                .await?
+            }
+        };
+        *state = ModelState::Loaded(model.clone());
+        Ok(model)
+    }
+}
+
+fn enabled_config(config: &GolemConfig) -> Result<&WasiNnEnabledConfig, InferenceError> {
+    match &config.wasi_nn {
+        WasiNnConfig::Enabled(config) => Ok(config),
+        WasiNnConfig::Disabled(_) => Err(InferenceError::new(
+            InferenceErrorCode::UnsupportedOperation,
+            "wasi:nn is not enabled on this executor",
+        )),
+    }
+}
+
+/// The execution target models loaded by name run on
+fn default_target(config: &GolemConfig) -> ExecutionTarget {
+    match &config.wasi_nn {
+        WasiNnConfig::Enabled(config) if config.gpu => ExecutionTarget::Gpu,
+        _ => ExecutionTarget::Cpu,
+    }
+}
+
+async fn load_model_blocking(
+    config: &GolemConfig,
+    inference_service: Arc<dyn InferenceService + Send + Sync>,
+    builders: Arc<Vec<Vec<u8>>>,
+    encoding: GraphEncoding,
+    target: ExecutionTarget,
+) -> Result<Arc<dyn InferenceModel>, InferenceError> {
+    let enabled = enabled_config(config)?;
+    let supported = match target {
+        ExecutionTarget::Cpu => true,
+        ExecutionTarget::Gpu => enabled.gpu,
+        ExecutionTarget::Tpu => false,
+    };
+    if !supported {
+        return Err(InferenceError::new(
+            InferenceErrorCode::UnsupportedOperation,
+            format!("Execution target {target:?} is not available on this executor"),
+        ));
+    }
+
+    tokio::task::spawn_blocking(move || inference_service.load_model(&builders, encoding, target))
+        .await
+        .map_err(|err| {
+            InferenceError::new(
+                InferenceErrorCode::RuntimeError,
+                format!("Failed to load model: {err}"),
+            )
+        })?
+}
+
+/// Reads a model from the configured container of the worker's account
+async fn read_model<Ctx: WorkerCtx>(
+    ctx: &mut DurableWorkerCtx<Ctx>,
+    name: &str,
+) -> Result<Vec<u8>, InferenceError> {
+    let container = enabled_config(&ctx.state.config)?.model_container.clone();
+    let account_id = ctx.state.owned_worker_id.account_id();
+    let blob_store = &ctx.state.blob_store_service;
+
+    let not_found = |err: anyhow::Error| {
+        InferenceError::new(
+            InferenceErrorCode::NotFound,
+            format!("Failed to read model {name} from container {container}: {err}"),
+        )
+    };
+    let info = blob_store
+        .object_info(account_id.clone(), container.clone(), name.to_string())
+        .await
+        .map_err(not_found)?;
+    blob_store
+        .get_data(
+            account_id,
+            container.clone(),
+            name.to_string(),
+            0,
+            info.size,
+        )
+        .await
+        .map_err(not_found)
+}
+
+impl From<nn::graph::GraphEncoding> for GraphEncoding {
+    fn from(value: nn::graph::GraphEncoding) -> Self {
+        match value {
+            nn::graph::GraphEncoding::Openvino => GraphEncoding::Openvino,
+            nn::graph::GraphEncoding::Onnx => GraphEncoding::Onnx,
+            nn::graph::GraphEncoding::Tensorflow => GraphEncoding::Tensorflow,
+            nn::graph::GraphEncoding::Pytorch => GraphEncoding::Pytorch,
+            nn::graph::GraphEncoding::Tensorflowlite => GraphEncoding::Tensorflowlite,
+            nn::graph::GraphEncoding::Ggml => GraphEncoding::Ggml,
+            nn::graph::GraphEncoding::Autodetect => GraphEncoding::Autodetect,
+        }
+    }
+}
+
+impl From<nn::graph::ExecutionTarget> for ExecutionTarget {
+    fn from(value: nn::graph::ExecutionTarget) -> Self {
+        match value {
+            nn::graph::ExecutionTarget::Cpu => ExecutionTarget::Cpu,
+            nn::graph::ExecutionTarget::Gpu => ExecutionTarget::Gpu,
+            nn::graph::ExecutionTarget::Tpu => ExecutionTarget::Tpu,
+        }
+    }
+}
+
+impl From<nn::tensor::TensorType> for TensorType {
+    fn from(value: nn::tensor::TensorType) -> Self {
+        match value {
+            nn::tensor::TensorType::Fp16 => TensorType::Fp16,
+            nn::tensor::TensorType::Fp32 => TensorType::Fp32,
+            nn::tensor::TensorType::Fp64 => TensorType::Fp64,
+            nn::tensor::TensorType::Bf16 => TensorType::Bf16,
+            nn::tensor::TensorType::U8 => TensorType::U8,
+            nn::tensor::TensorType::I32 => TensorType::I32,
+            nn::tensor::TensorType::I64 => TensorType::I64,
+        }
+    }
+}
+
+impl From<TensorType> for nn::tensor::TensorType {
+    fn from(value: TensorType) -> Self {
+        match value {
+            TensorType::Fp16 => nn::tensor::TensorType::Fp16,
+            TensorType::Fp32 => nn::tensor::TensorType::Fp32,
+            TensorType::Fp64 => nn::tensor::TensorType::Fp64,
+            TensorType::Bf16 => nn::tensor::TensorType::Bf16,
+            TensorType::U8 => nn::tensor::TensorType::U8,
+            TensorType::I32 => nn::tensor::TensorType::I32,
+            TensorType::I64 => nn::tensor::TensorType::I64,
+        }
+    }
+}
+
+impl From<InferenceErrorCode> for nn::errors::ErrorCode {
+    fn from(value: InferenceErrorCode) -> Self {
+        match value {
+            InferenceErrorCode::InvalidArgument => nn::errors::ErrorCode::InvalidArgument,
+            InferenceErrorCode::InvalidEncoding => nn::errors::ErrorCode::InvalidEncoding,
+            InferenceErrorCode::Timeout => nn::errors::ErrorCode::Timeout,
+            InferenceErrorCode::RuntimeError => nn::errors::ErrorCode::RuntimeError,
+            InferenceErrorCode::UnsupportedOperation => nn::errors::ErrorCode::UnsupportedOperation,
+            InferenceErrorCode::TooLarge => nn::errors::ErrorCode::TooLarge,
+            InferenceErrorCode::NotFound => nn::errors::ErrorCode::NotFound,
+            InferenceErrorCode::Security => nn::errors::ErrorCode::Security,
+            InferenceErrorCode::Unknown => nn::errors::ErrorCode::Unknown,
+        }
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use wasmtime::component::Resource;
+use wasmtime_wasi::WasiView;
+
+use crate::durable_host::nn::TensorEntry;
+use crate::durable_host::DurableWorkerCtx;
+use crate::metrics::wasm::record_host_function_call;
+use crate::preview2::nn::wasi::nn::tensor::{
+    Host, HostTensor, TensorData, TensorDimensions, TensorType,
+};
+use crate::services::inference::Tensor;
+use crate::workerctx::WorkerCtx;
+
+#[async_trait]
+impl<Ctx: WorkerCtx> HostTensor for DurableWorkerCtx<Ctx> {
+    async fn new(
+        &mut self,
+        dimensions: TensorDimensions,
+        ty: TensorType,
+        data: TensorData,
+    ) -> anyhow::Result<Resource<TensorEntry>> {
+        record_host_function_call("nn::tensor::tensor", "new");
+        let tensor = self.as_wasi_view().table().push(TensorEntry(Tensor {
+            dimensions,
+            ty: ty.into(),
+            data,
+        }))?;
+        Ok(tensor)
+    }
+
+    async fn dimensions(
+        &mut self,
+        self_: Resource<TensorEntry>,
+    ) -> anyhow::Result<TensorDimensions> {
+        record_host_function_call("nn::tensor::tensor", "dimensions");
+        let tensor = self.as_wasi_view().table().get(&self_)?;
+        Ok(tensor.0.dimensions.clone())
+    }
+
+    async fn ty(&mut self, self_: Resource<TensorEntry>) -> anyhow::Result<TensorType> {
+        record_host_function_call("nn::tensor::tensor", "ty");
+        let tensor = self.as_wasi_view().table().get(&self_)?;
+        Ok(tensor.0.ty.into())
+    }
+
+    async fn data(&mut self, self_: Resource<TensorEntry>) -> anyhow::Result<TensorData> {
+        record_host_function_call("nn::tensor::tensor", "data");
+        let tensor = self.as_wasi_view().table().get(&self_)?;
+        Ok(tensor.0.data.clone())
+    }
+
+    fn drop(&mut self, rep: Resource<TensorEntry>) -> anyhow::Result<()> {
+        record_host_function_call("nn::tensor::tensor", "drop");
+        self.as_wasi_view().table().delete(rep)?;
+        Ok(())
+    }
+}
+
+impl<Ctx: WorkerCtx> Host for DurableWorkerCtx<Ctx> {}
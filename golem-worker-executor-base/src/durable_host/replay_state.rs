@@ -16,7 +16,7 @@ use crate::error::GolemError;
 use crate::services::oplog::{Oplog, OplogOps, OplogService};
 use golem_common::model::oplog::{AtomicOplogIndex, LogLevel, OplogEntry, OplogIndex};
 use golem_common::model::regions::{DeletedRegions, OplogRegion};
-use golem_common::model::{IdempotencyKey, OwnedWorkerId};
+use golem_common::model::{CallerIdentity, IdempotencyKey, OwnedWorkerId};
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use golem_wasm_rpc::Value;
 use metrohash::MetroHash128;
@@ -45,6 +45,25 @@ struct InternalReplayState {
     pub next_deleted_region: Option<OplogRegion>,
     /// Hashes of log entries persisted since the last read non-hint oplog entry
     pub log_hashes: HashSet<(u64, u64)>,
+    /// The first point where the replay did not match the oplog
+    pub divergence: Option<ReplayDivergence>,
+}
+
+/// An oplog entry that did not match what the replayed code did
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplayDivergence {
+    pub oplog_index: OplogIndex,
+    pub expected: String,
+    pub got: String,
+}
+
+/// An invocation of an exported function read from the oplog during replay
+#[derive(Clone, Debug)]
+pub struct ReplayedInvocation {
+    pub function_name: String,
+    pub function_input: Vec<Value>,
+    pub idempotency_key: IdempotencyKey,
+    pub caller: Option<CallerIdentity>,
 }
 
 impl ReplayState {
@@ -66,6 +85,7 @@ impl ReplayState {
                 deleted_regions,
                 next_deleted_region,
                 log_hashes: HashSet::new(),
+                divergence: None,
             })),
             has_seen_logs: Arc::new(AtomicBool::new(false)),
         };
@@ -100,6 +120,26 @@ impl ReplayState {
         internal.deleted_regions.is_in_deleted_region(oplog_index)
     }
 
+    /// Remembers where the replay diverged if `error` is caused by an unexpected oplog entry at
+    /// `oplog_index`. Only the first divergence is kept, as the later ones are its consequences.
+    pub async fn record_divergence(&self, oplog_index: OplogIndex, error: &GolemError) {
+        if let GolemError::UnexpectedOplogEntry { expected, got } = error {
+            let mut internal = self.internal.write().await;
+            if internal.divergence.is_none() {
+                internal.divergence = Some(ReplayDivergence {
+                    oplog_index,
+                    expected: expected.clone(),
+                    got: got.clone(),
+                });
+            }
+        }
+    }
+
+    pub async fn divergence(&self) -> Option<ReplayDivergence> {
+        let internal = self.internal.read().await;
+        internal.divergence.clone()
+    }
+
     /// Returns whether we are in live mode where we are executing new calls.
     pub fn is_live(&self) -> bool {
         self.last_replayed_index.get() == self.replay_target.get()
@@ -233,18 +273,63 @@ impl ReplayState {
         None
     }
 
+    /// Returns true if the next non-hint oplog entry to be replayed records an invocation of the
+    /// imported function `function_name`. Used for the host functions which only became durable
+    /// in a later release, so oplogs recorded before are replayed by executing them again.
+    pub async fn is_imported_function_recorded_next(&self, function_name: &str) -> bool {
+        let replay_target = self.replay_target.get();
+        let deleted_regions = self.deleted_regions().await;
+        let mut start = self.last_replayed_index.get().next();
+
+        const CHUNK_SIZE: u64 = 1024;
+        while start <= replay_target {
+            let entries = self
+                .oplog_service
+                .read(&self.owned_worker_id, start, CHUNK_SIZE)
+                .await;
+            if entries.is_empty() {
+                break;
+            }
+            for (idx, entry) in &entries {
+                if *idx > replay_target {
+                    return false;
+                } else if deleted_regions.is_in_deleted_region(*idx) || entry.is_hint() {
+                    continue;
+                } else {
+                    return matches!(
+                        entry,
+                        OplogEntry::ImportedFunctionInvoked { function_name: name, .. }
+                            if name == function_name
+                    );
+                }
+            }
+            start = start.range_end(entries.len() as u64).next();
+        }
+        false
+    }
+
     pub async fn get_oplog_entry_exported_function_invoked(
         &mut self,
-    ) -> Result<Option<(String, Vec<Value>, IdempotencyKey)>, GolemError> {
+    ) -> Result<Option<ReplayedInvocation>, GolemError> {
         loop {
             if self.is_replay() {
-                let (_, oplog_entry) = self.get_oplog_entry().await;
-                match &oplog_entry {
+                let (oplog_idx, oplog_entry) = self.get_oplog_entry().await;
+                let invocation = match &oplog_entry {
+                    OplogEntry::ExportedFunctionInvokedV1 {
+                        function_name,
+                        idempotency_key,
+                        ..
+                    } => Some((function_name, idempotency_key, None)),
                     OplogEntry::ExportedFunctionInvoked {
                         function_name,
                         idempotency_key,
+                        caller,
                         ..
-                    } => {
+                    } => Some((function_name, idempotency_key, caller.clone())),
+                    _ => None,
+                };
+                match invocation {
+                    Some((function_name, idempotency_key, caller)) => {
                         let request: Vec<golem_wasm_rpc::protobuf::Val> = self
                             .oplog
                             .get_payload_of_entry(&oplog_entry)
@@ -258,18 +343,21 @@ impl ReplayState {
                                     .expect("failed to decode serialized protobuf value")
                             })
                             .collect::<Vec<Value>>();
-                        break Ok(Some((
-                            function_name.to_string(),
-                            request,
-                            idempotency_key.clone(),
-                        )));
+                        break Ok(Some(ReplayedInvocation {
+                            function_name: function_name.to_string(),
+                            function_input: request,
+                            idempotency_key: idempotency_key.clone(),
+                            caller,
+                        }));
                     }
-                    entry if entry.is_hint() => {}
+                    None if oplog_entry.is_hint() => {}
                     _ => {
-                        break Err(GolemError::unexpected_oplog_entry(
+                        let error = GolemError::unexpected_oplog_entry(
                             "ExportedFunctionInvoked",
                             format!("{:?}", oplog_entry),
-                        ));
+                        );
+                        self.record_divergence(oplog_idx, &error).await;
+                        break Err(error);
                     }
                 }
             } else {
@@ -280,10 +368,10 @@ impl ReplayState {
 
     pub async fn get_oplog_entry_exported_function_completed(
         &mut self,
-    ) -> Result<Option<TypeAnnotatedValue>, GolemError> {
+    ) -> Result<Option<(OplogIndex, TypeAnnotatedValue)>, GolemError> {
         loop {
             if self.is_replay() {
-                let (_, oplog_entry) = self.get_oplog_entry().await;
+                let (oplog_idx, oplog_entry) = self.get_oplog_entry().await;
                 match &oplog_entry {
                     OplogEntry::ExportedFunctionCompleted { .. } => {
                         let response: TypeAnnotatedValue = self
@@ -293,14 +381,16 @@ impl ReplayState {
                             .expect("failed to deserialize function response payload")
                             .unwrap();
 
-                        break Ok(Some(response));
+                        break Ok(Some((oplog_idx, response)));
                     }
                     entry if entry.is_hint() => {}
                     _ => {
-                        break Err(GolemError::unexpected_oplog_entry(
+                        let error = GolemError::unexpected_oplog_entry(
                             "ExportedFunctionCompleted",
                             format!("{:?}", oplog_entry),
-                        ));
+                        );
+                        self.record_divergence(oplog_idx, &error).await;
+                        break Err(error);
                     }
                 }
             } else {
@@ -309,6 +399,35 @@ impl ReplayState {
         }
     }
 
+    /// Returns true if the payload of the oplog entry at `oplog_idx` has been redacted, so it holds
+    /// placeholder values instead of the recorded ones. Reads the rest of the oplog, so it should
+    /// only be used when a recorded payload does not match the replayed one.
+    pub async fn is_redacted(&self, oplog_idx: OplogIndex) -> bool {
+        let replay_target = self.replay_target.get();
+        let mut start = oplog_idx.next();
+
+        const CHUNK_SIZE: u64 = 1024;
+        while start <= replay_target {
+            let entries = self
+                .oplog_service
+                .read(&self.owned_worker_id, start, CHUNK_SIZE)
+                .await;
+            if entries.is_empty() {
+                break;
+            }
+            for entry in entries.values() {
+                if let OplogEntry::Redacted { entries, .. } = entry {
+                    if entries.contains(&oplog_idx) {
+                        return true;
+                    }
+                }
+            }
+            start = start.range_end(entries.len() as u64).next();
+        }
+
+        false
+    }
+
     pub(crate) async fn get_out_of_deleted_region(&mut self) {
         if self.is_replay() {
             let mut internal = self.internal.write().await;
@@ -344,3 +463,182 @@ impl ReplayState {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::Arc;
+
+    use golem_common::model::oplog::{
+        LogLevel, OplogEntry, OplogIndex, OplogPayload, WrappedFunctionType,
+    };
+    use golem_common::model::oplog_codec::OplogEncoding;
+    use golem_common::model::regions::DeletedRegions;
+    use golem_common::model::{
+        AccountId, ComponentId, ComponentType, OwnedWorkerId, Timestamp, WorkerId,
+    };
+    use uuid::Uuid;
+
+    use crate::durable_host::replay_state::{ReplayDivergence, ReplayState};
+    use crate::error::GolemError;
+    use crate::services::oplog::{CommitLevel, OplogService, PrimaryOplogService};
+    use crate::storage::blob::memory::InMemoryBlobStorage;
+    use crate::storage::indexed::memory::InMemoryIndexedStorage;
+
+    fn imported_function_invoked(function_name: &str) -> OplogEntry {
+        OplogEntry::ImportedFunctionInvoked {
+            timestamp: Timestamp::now_utc(),
+            function_name: function_name.to_string(),
+            request: OplogPayload::Inline(vec![]),
+            response: OplogPayload::Inline(vec![]),
+            wrapped_function_type: WrappedFunctionType::ReadLocal,
+        }
+    }
+
+    async fn replay_state(entries: Vec<OplogEntry>) -> ReplayState {
+        let oplog_service: Arc<dyn OplogService + Send + Sync> = Arc::new(
+            PrimaryOplogService::new(
+                Arc::new(InMemoryIndexedStorage::new()),
+                Arc::new(InMemoryBlobStorage::new()),
+                1,
+                4,
+                100,
+                OplogEncoding::Bincode,
+                false,
+            )
+            .await,
+        );
+        let owned_worker_id = OwnedWorkerId::new(
+            &AccountId {
+                value: "user1".to_string(),
+            },
+            &WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: "test".to_string(),
+            },
+        );
+        let oplog = oplog_service
+            .create(&owned_worker_id, OplogEntry::nop(), ComponentType::Durable)
+            .await;
+        for entry in entries {
+            oplog.add(entry).await;
+        }
+        oplog.commit(CommitLevel::Always).await;
+
+        let last_oplog_index = oplog.current_oplog_index().await;
+        ReplayState::new(
+            owned_worker_id,
+            oplog_service,
+            oplog,
+            DeletedRegions::new(),
+            last_oplog_index,
+        )
+        .await
+    }
+
+    #[test]
+    async fn recorded_metadata_query_is_replayed() {
+        let replay_state = replay_state(vec![
+            OplogEntry::log(LogLevel::Info, "".to_string(), "hello".to_string()),
+            imported_function_invoked("golem::api::get-self-metadata"),
+        ])
+        .await;
+
+        assert!(
+            replay_state
+                .is_imported_function_recorded_next("golem::api::get-self-metadata")
+                .await
+        );
+        assert!(
+            !replay_state
+                .is_imported_function_recorded_next("golem::api::get-worker-metadata")
+                .await
+        );
+    }
+
+    #[test]
+    async fn metadata_query_missing_from_old_oplog_is_executed_again() {
+        // Oplog recorded before the metadata queries became durable, the next replayed entry
+        // belongs to the host function called after the query
+        let replay_state = replay_state(vec![
+            OplogEntry::log(LogLevel::Info, "".to_string(), "hello".to_string()),
+            imported_function_invoked("golem_environment::get_environment"),
+        ])
+        .await;
+
+        assert!(
+            !replay_state
+                .is_imported_function_recorded_next("golem::api::get-self-metadata")
+                .await
+        );
+        assert!(
+            replay_state
+                .is_imported_function_recorded_next("golem_environment::get_environment")
+                .await
+        );
+    }
+
+    #[test]
+    async fn nothing_is_recorded_after_the_replay_target() {
+        let replay_state = replay_state(vec![]).await;
+
+        assert!(
+            !replay_state
+                .is_imported_function_recorded_next("golem::api::get-self-metadata")
+                .await
+        );
+    }
+
+    #[test]
+    async fn divergence_is_recorded_at_the_unexpected_entry() {
+        let mut replay_state = replay_state(vec![
+            OplogEntry::log(LogLevel::Info, "".to_string(), "hello".to_string()),
+            imported_function_invoked("golem_environment::get_environment"),
+        ])
+        .await;
+
+        let result = replay_state
+            .get_oplog_entry_exported_function_completed()
+            .await;
+
+        assert!(result.is_err());
+        let divergence = replay_state.divergence().await.unwrap();
+        assert_eq!(divergence.oplog_index, OplogIndex::from_u64(3));
+        assert_eq!(divergence.expected, "ExportedFunctionCompleted");
+        assert!(divergence
+            .got
+            .contains("golem_environment::get_environment"));
+    }
+
+    #[test]
+    async fn only_the_first_divergence_is_kept() {
+        let replay_state = replay_state(vec![]).await;
+
+        replay_state
+            .record_divergence(OplogIndex::from_u64(2), &GolemError::runtime("failed"))
+            .await;
+        assert_eq!(replay_state.divergence().await, None);
+
+        replay_state
+            .record_divergence(
+                OplogIndex::from_u64(3),
+                &GolemError::unexpected_oplog_entry("first", "recorded first"),
+            )
+            .await;
+        replay_state
+            .record_divergence(
+                OplogIndex::from_u64(5),
+                &GolemError::unexpected_oplog_entry("second", "recorded second"),
+            )
+            .await;
+        assert_eq!(
+            replay_state.divergence().await,
+            Some(ReplayDivergence {
+                oplog_index: OplogIndex::from_u64(3),
+                expected: "first".to_string(),
+                got: "recorded first".to_string(),
+            })
+        );
+    }
+}
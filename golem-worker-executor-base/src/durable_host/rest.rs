@@ -0,0 +1,583 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Durable REST calls, used by the host bindings generated with `golem-cli http-stubgen` from
+//! the OpenAPI specification of an external service.
+
+use std::time::Duration;
+
+use bincode::{Decode, Encode};
+use golem_common::model::oplog::WrappedFunctionType;
+use lazy_static::lazy_static;
+use reqwest::{Client, Method};
+use serde_json::Value;
+use wasmtime_wasi::bindings::cli::environment::Host as EnvironmentHost;
+
+use crate::durable_host::serialized::SerializableError;
+use crate::durable_host::{Durability, DurableWorkerCtx};
+use crate::error::GolemError;
+use crate::metrics::wasm::record_host_function_call;
+use crate::workerctx::WorkerCtx;
+
+lazy_static! {
+    static ref CLIENT: Client = Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .expect("failed to create the REST client");
+}
+
+/// The external service called by a generated interface
+#[derive(Debug, Clone)]
+pub struct RestEndpoint {
+    /// Worker environment variable overriding the base URL of the service
+    pub base_url_env_var: &'static str,
+    /// Base URL used when the environment variable is not set, taken from the specification
+    pub default_base_url: &'static str,
+    /// Time allowed for a call, from sending the request until the whole response is read
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct RestRequest {
+    pub method: String,
+    /// Path relative to the base URL, with the path parameters already encoded
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    /// JSON encoded request body
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct RestResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+/// Failure of a call, converted to the `api-error` record of the generated interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestError {
+    /// Status code of the error response, or `None` if the service could not be reached
+    pub status: Option<u16>,
+    pub message: String,
+}
+
+impl<Ctx: WorkerCtx> DurableWorkerCtx<Ctx> {
+    /// Sends a request to an external service, recording the response in the oplog so it is
+    /// not sent again when the worker is replayed.
+    ///
+    /// Failing to reach the service is returned as an error message, while responses with
+    /// error status codes are returned as they are.
+    pub async fn durable_rest_call(
+        &mut self,
+        interface: &'static str,
+        function: &'static str,
+        endpoint: &RestEndpoint,
+        request: RestRequest,
+    ) -> anyhow::Result<Result<RestResponse, String>> {
+        let _permit = self.begin_async_host_function().await?;
+        record_host_function_call(interface, function);
+        let endpoint = endpoint.clone();
+        let result = Durability::<Ctx, RestRequest, RestResponse, SerializableError>::wrap(
+            self,
+            WrappedFunctionType::WriteRemote,
+            &format!("{interface}::{function}"),
+            request.clone(),
+            |ctx| {
+                Box::pin(async move {
+                    let base_url = base_url(ctx, &endpoint).await?;
+                    send(&base_url, endpoint.timeout, request).await
+                })
+            },
+        )
+        .await;
+        Ok(result.map_err(|err| err.to_string()))
+    }
+}
+
+async fn base_url<Ctx: WorkerCtx>(
+    ctx: &mut DurableWorkerCtx<Ctx>,
+    endpoint: &RestEndpoint,
+) -> Result<String, GolemError> {
+    let env = EnvironmentHost::get_environment(&mut ctx.as_wasi_view())
+        .await
+        .map_err(|err| GolemError::runtime(err.to_string()))?;
+    Ok(resolve_base_url(&env, endpoint))
+}
+
+fn resolve_base_url(env: &[(String, String)], endpoint: &RestEndpoint) -> String {
+    env.iter()
+        .find(|(name, _)| name == endpoint.base_url_env_var)
+        .map(|(_, value)| value.clone())
+        .unwrap_or_else(|| endpoint.default_base_url.to_string())
+}
+
+async fn send(
+    base_url: &str,
+    timeout: Duration,
+    request: RestRequest,
+) -> Result<RestResponse, GolemError> {
+    let method = Method::from_bytes(request.method.as_bytes())
+        .map_err(|err| GolemError::runtime(format!("Invalid HTTP method: {err}")))?;
+    let url = format!("{}{}", base_url.trim_end_matches('/'), request.path);
+
+    let mut builder = CLIENT
+        .request(method, url)
+        .timeout(timeout)
+        .query(&request.query);
+    if let Some(body) = request.body {
+        builder = builder
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+    }
+    let response = builder
+        .send()
+        .await
+        .map_err(|err| GolemError::runtime(format!("Failed to send request: {err}")))?;
+    let status = response.status().as_u16();
+    let body = response
+        .text()
+        .await
+        .map_err(|err| GolemError::runtime(format!("Failed to read response: {err}")))?;
+    Ok(RestResponse { status, body })
+}
+
+/// Conversion of the types of a generated interface to the JSON sent to the service
+pub trait ToJson {
+    fn to_json(&self) -> Value;
+}
+
+/// Conversion of the JSON returned by the service to the types of a generated interface
+pub trait FromJson: Sized {
+    fn from_json(value: &Value) -> Result<Self, String>;
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+}
+
+impl ToJson for i32 {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+}
+
+impl ToJson for i64 {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+}
+
+impl ToJson for f32 {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+}
+
+impl ToJson for f64 {
+    fn to_json(&self) -> Value {
+        Value::from(*self)
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Value {
+        Value::from(self.as_str())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Value {
+        self.as_ref().map_or(Value::Null, ToJson::to_json)
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        value
+            .as_bool()
+            .ok_or_else(|| format!("expected a boolean, got {value}"))
+    }
+}
+
+impl FromJson for i32 {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        value
+            .as_i64()
+            .and_then(|n| i32::try_from(n).ok())
+            .ok_or_else(|| format!("expected a 32-bit integer, got {value}"))
+    }
+}
+
+impl FromJson for i64 {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        value
+            .as_i64()
+            .ok_or_else(|| format!("expected an integer, got {value}"))
+    }
+}
+
+impl FromJson for f32 {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        value
+            .as_f64()
+            .map(|n| n as f32)
+            .ok_or_else(|| format!("expected a number, got {value}"))
+    }
+}
+
+impl FromJson for f64 {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        value
+            .as_f64()
+            .ok_or_else(|| format!("expected a number, got {value}"))
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        value
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("expected a string, got {value}"))
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        value
+            .as_array()
+            .ok_or_else(|| format!("expected an array, got {value}"))?
+            .iter()
+            .map(T::from_json)
+            .collect()
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_json(value).map(Some),
+        }
+    }
+}
+
+/// Reads a field of a JSON object returned by the service, missing fields are read as `null`
+pub fn field<T: FromJson>(value: &Value, name: &str) -> Result<T, String> {
+    T::from_json(value.get(name).unwrap_or(&Value::Null)).map_err(|err| format!("{name}: {err}"))
+}
+
+/// Reads a field without a WIT equivalent, as a string holding its JSON value
+pub fn json_field(value: &Value, name: &str) -> Result<String, String> {
+    Ok(value.get(name).unwrap_or(&Value::Null).to_string())
+}
+
+/// Converts a string holding a JSON value to the value, or to a JSON string if it is not valid JSON
+pub fn json_string_to_value(json: &str) -> Value {
+    serde_json::from_str(json).unwrap_or_else(|_| Value::from(json))
+}
+
+fn param_string(value: Value) -> String {
+    match value {
+        Value::String(string) => string,
+        value => value.to_string(),
+    }
+}
+
+/// Percent-encodes a path parameter
+pub fn path_segment(value: Value) -> String {
+    param_string(value)
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                char::from(byte).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+/// Converts the query parameters to name-value pairs, leaving out the missing ones and repeating
+/// the name for each item of an array
+pub fn query_pairs(params: Vec<(&str, Value)>) -> Vec<(String, String)> {
+    params
+        .into_iter()
+        .flat_map(|(name, value)| {
+            let values = match value {
+                Value::Null => Vec::new(),
+                Value::Array(items) => items,
+                value => vec![value],
+            };
+            values
+                .into_iter()
+                .map(move |value| (name.to_string(), param_string(value)))
+        })
+        .collect()
+}
+
+/// Turns responses with a non-2xx status code and unreachable services into errors
+pub fn check_status(response: Result<RestResponse, String>) -> Result<RestResponse, RestError> {
+    match response {
+        Ok(response) if (200..300).contains(&response.status) => Ok(response),
+        Ok(response) => Err(RestError {
+            status: Some(response.status),
+            message: response.body,
+        }),
+        Err(message) => Err(RestError {
+            status: None,
+            message,
+        }),
+    }
+}
+
+/// Decodes the JSON body of a successful response
+pub fn decode_response<T: FromJson>(
+    response: Result<RestResponse, String>,
+) -> Result<T, RestError> {
+    let response = check_status(response)?;
+    serde_json::from_str::<Value>(&response.body)
+        .map_err(|err| err.to_string())
+        .and_then(|value| T::from_json(&value))
+        .map_err(|message| RestError {
+            status: Some(response.status),
+            message: format!("Invalid response: {message}"),
+        })
+}
+
+/// Returns the body of a successful response whose schema has no WIT equivalent
+pub fn decode_json_response(response: Result<RestResponse, String>) -> Result<String, RestError> {
+    check_status(response).map(|response| response.body)
+}
+
+/// Checks the status of a response whose body is not used
+pub fn decode_unit_response(response: Result<RestResponse, String>) -> Result<(), RestError> {
+    check_status(response).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use serde_json::{json, Value};
+    use warp::Filter;
+
+    use crate::durable_host::rest::{
+        check_status, decode_response, field, path_segment, query_pairs, resolve_base_url, send,
+        FromJson, RestEndpoint, RestError, RestRequest, RestResponse, ToJson,
+    };
+
+    const ENDPOINT: RestEndpoint = RestEndpoint {
+        base_url_env_var: "PETSTORE_CLIENT_BASE_URL",
+        default_base_url: "https://petstore.example.com/v1",
+        timeout: Duration::from_secs(10),
+    };
+
+    #[derive(Debug, PartialEq)]
+    struct Pet {
+        id: i64,
+        tags: Option<Vec<String>>,
+    }
+
+    impl FromJson for Pet {
+        fn from_json(value: &Value) -> Result<Self, String> {
+            Ok(Pet {
+                id: field(value, "id")?,
+                tags: field(value, "tags")?,
+            })
+        }
+    }
+
+    fn request(method: &str, path: &str) -> RestRequest {
+        RestRequest {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: Vec::new(),
+            body: None,
+        }
+    }
+
+    #[test]
+    fn base_url_can_be_overridden_by_the_worker_environment() {
+        assert_eq!(
+            resolve_base_url(&[], &ENDPOINT),
+            "https://petstore.example.com/v1"
+        );
+        assert_eq!(
+            resolve_base_url(
+                &[(
+                    "PETSTORE_CLIENT_BASE_URL".to_string(),
+                    "http://localhost:8080".to_string()
+                )],
+                &ENDPOINT
+            ),
+            "http://localhost:8080"
+        );
+    }
+
+    #[test]
+    fn parameters_are_encoded() {
+        assert_eq!(path_segment("a b/c".to_string().to_json()), "a%20b%2Fc");
+        assert_eq!(path_segment(42i64.to_json()), "42");
+        assert_eq!(
+            query_pairs(vec![
+                ("status", Some("sold".to_string()).to_json()),
+                ("missing", None::<String>.to_json()),
+                ("tag", vec!["a".to_string(), "b".to_string()].to_json()),
+            ]),
+            vec![
+                ("status".to_string(), "sold".to_string()),
+                ("tag".to_string(), "a".to_string()),
+                ("tag".to_string(), "b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn responses_are_decoded() {
+        let ok = |body: Value| {
+            Ok(RestResponse {
+                status: 200,
+                body: body.to_string(),
+            })
+        };
+
+        assert_eq!(
+            decode_response::<Pet>(ok(json!({ "id": 1, "tags": ["cute"] }))),
+            Ok(Pet {
+                id: 1,
+                tags: Some(vec!["cute".to_string()])
+            })
+        );
+        assert_eq!(
+            decode_response::<Pet>(ok(json!({ "id": 1 }))),
+            Ok(Pet { id: 1, tags: None })
+        );
+        assert_eq!(
+            decode_response::<Pet>(ok(json!({ "id": "1" }))),
+            Err(RestError {
+                status: Some(200),
+                message: "Invalid response: id: expected an integer, got \"1\"".to_string(),
+            })
+        );
+        assert_eq!(
+            check_status(Ok(RestResponse {
+                status: 404,
+                body: "Not found".to_string(),
+            })),
+            Err(RestError {
+                status: Some(404),
+                message: "Not found".to_string(),
+            })
+        );
+        assert_eq!(
+            check_status(Err("Failed to send request".to_string())),
+            Err(RestError {
+                status: None,
+                message: "Failed to send request".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    async fn requests_are_sent_to_the_service() {
+        let route = warp::path!("v1" / "pets" / String)
+            .and(warp::method())
+            .and(warp::query::<HashMap<String, String>>())
+            .and(warp::header::optional::<String>("content-type"))
+            .and(warp::body::bytes())
+            .map(
+                |id: String,
+                 method: warp::http::Method,
+                 query: HashMap<String, String>,
+                 content_type: Option<String>,
+                 body: bytes::Bytes| {
+                    json!({
+                        "id": id,
+                        "method": method.as_str(),
+                        "query": query,
+                        "contentType": content_type,
+                        "body": String::from_utf8_lossy(&body),
+                    })
+                    .to_string()
+                },
+            );
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0u16));
+        tokio::spawn(server);
+
+        let response = send(
+            &format!("http://{addr}/v1/"),
+            Duration::from_secs(10),
+            RestRequest {
+                method: "PUT".to_string(),
+                path: "/pets/rex".to_string(),
+                query: vec![("status".to_string(), "sold".to_string())],
+                body: Some("{\"name\":\"Rex\"}".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            serde_json::from_str::<Value>(&response.body).unwrap(),
+            json!({
+                "id": "rex",
+                "method": "PUT",
+                "query": { "status": "sold" },
+                "contentType": "application/json",
+                "body": "{\"name\":\"Rex\"}",
+            })
+        );
+
+        let missing = send(
+            &format!("http://{addr}"),
+            Duration::from_secs(10),
+            request("GET", "/missing"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(missing.status, 404);
+    }
+
+    #[test]
+    async fn calls_time_out() {
+        let route = warp::path!("slow").then(|| async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            "late"
+        });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0u16));
+        tokio::spawn(server);
+
+        let result = send(
+            &format!("http://{addr}"),
+            Duration::from_millis(100),
+            request("GET", "/slow"),
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -17,6 +17,7 @@ use crate::services::rpc::RpcError;
 use crate::services::worker_proxy::WorkerProxyError;
 use anyhow::anyhow;
 use bincode::{Decode, Encode};
+use golem_common::model::{ComponentVersion, WorkerId, WorkerMetadata, WorkerStatus};
 use std::ops::Add;
 use std::time::{Duration, SystemTime};
 use wasmtime_wasi::bindings::sockets::ip_name_lookup::IpAddress;
@@ -532,6 +533,29 @@ pub struct SerializableFileTimes {
     pub data_modification_timestamp: Option<SerializableDateTime>,
 }
 
+/// The part of a worker's metadata exposed to the guests through `golem:api/host`, persisted
+/// in the oplog when queried
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct SerializableWorkerMetadata {
+    pub worker_id: WorkerId,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub status: WorkerStatus,
+    pub component_version: ComponentVersion,
+}
+
+impl From<WorkerMetadata> for SerializableWorkerMetadata {
+    fn from(value: WorkerMetadata) -> Self {
+        Self {
+            worker_id: value.worker_id,
+            args: value.args,
+            env: value.env,
+            status: value.last_known_status.status,
+            component_version: value.last_known_status.component_version,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use test_r::test;
@@ -684,6 +708,8 @@ mod tests {
             ".*".prop_map(|details| GolemError::PreviousInvocationFailed { details }),
             Just(GolemError::PreviousInvocationExited),
             ".*".prop_map(|details| GolemError::Unknown { details }),
+            (any::<u64>(), any::<u64>(), ".*").prop_map(|(size, limit, blob_key)| GolemError::ResultTooLarge { size, limit, blob_key }),
+            any::<u64>().prop_map(|timeout_millis| GolemError::InvocationTimedOut { timeout_millis }),
         }
     }
 
@@ -136,16 +136,20 @@ impl SyncHelper {
                             oplog.add(entry).await;
                         }
                         SyncHelperCommand::SkipOplogEntry { check, expectation } => loop {
-                            let (_, oplog_entry) = replay_state.get_oplog_entry().await;
+                            let (oplog_index, oplog_entry) = replay_state.get_oplog_entry().await;
                             if check(&oplog_entry) {
                                 break;
                             } else if oplog_entry.is_hint() {
                             } else {
-                                let mut error = error.lock().await;
-                                *error = Some(GolemError::unexpected_oplog_entry(
+                                let unexpected = GolemError::unexpected_oplog_entry(
                                     expectation,
                                     format!("{:?}", oplog_entry),
-                                ));
+                                );
+                                replay_state
+                                    .record_divergence(oplog_index, &unexpected)
+                                    .await;
+                                let mut error = error.lock().await;
+                                *error = Some(unexpected);
                                 break;
                             }
                         },
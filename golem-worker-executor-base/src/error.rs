@@ -97,7 +97,15 @@ pub enum GolemError {
         details: String,
     },
     ShardingNotReady,
-    PermissionsNotSet
+    PermissionsNotSet,
+    ResultTooLarge {
+        size: u64,
+        limit: u64,
+        blob_key: String,
+    },
+    InvocationTimedOut {
+        timeout_millis: u64,
+    },
 }
 
 impl GolemError {
@@ -266,6 +274,19 @@ impl Display for GolemError {
             GolemError::PermissionsNotSet => {
                 write!(f, "Permissions not set")
             }
+            GolemError::ResultTooLarge {
+                size,
+                limit,
+                blob_key,
+            } => {
+                write!(
+                    f,
+                    "Invocation result of {size} bytes exceeds the limit of {limit} bytes, it was stored as {blob_key}"
+                )
+            }
+            GolemError::InvocationTimedOut { timeout_millis } => {
+                write!(f, "Invocation timed out after {timeout_millis} ms")
+            }
         }
     }
 }
@@ -299,6 +320,8 @@ impl Error for GolemError {
             GolemError::PreviousInvocationExited => "The previously invoked function exited",
             GolemError::Unknown { .. } => "Unknown error",
             GolemError::ShardingNotReady => "Sharding not ready",
+            GolemError::ResultTooLarge { .. } => "Invocation result too large",
+            GolemError::InvocationTimedOut { .. } => "Invocation timed out",
         }
     }
 }
@@ -332,6 +355,8 @@ impl TraceErrorKind for GolemError {
             GolemError::PreviousInvocationExited => "PreviousInvocationExited",
             GolemError::Unknown { .. } => "Unknown",
             GolemError::ShardingNotReady => "ShardingNotReady",
+            GolemError::ResultTooLarge { .. } => "ResultTooLarge",
+            GolemError::InvocationTimedOut { .. } => "InvocationTimedOut",
         }
     }
 }
@@ -373,6 +398,7 @@ impl From<GolemError> for Status {
                 Status::invalid_argument(format!("Value mismatch: {details}"))
             }
             GolemError::Unknown { details } => Status::unknown(details),
+            GolemError::InvocationTimedOut { .. } => Status::deadline_exceeded(format!("{value}")),
             _ => Status::internal(format!("{value}")),
         }
     }
@@ -600,6 +626,30 @@ impl From<GolemError> for golem::worker::v1::WorkerExecutionError {
                     ),
                 ),
             },
+            GolemError::ResultTooLarge {
+                size,
+                limit,
+                blob_key,
+            } => golem::worker::v1::WorkerExecutionError {
+                error: Some(
+                    golem::worker::v1::worker_execution_error::Error::ResultTooLarge(
+                        golem::worker::v1::ResultTooLarge {
+                            size,
+                            limit,
+                            blob_key,
+                        },
+                    ),
+                ),
+            },
+            GolemError::InvocationTimedOut { timeout_millis } => {
+                golem::worker::v1::WorkerExecutionError {
+                    error: Some(
+                        golem::worker::v1::worker_execution_error::Error::InvocationTimedOut(
+                            golem::worker::v1::InvocationTimedOut { timeout_millis },
+                        ),
+                    ),
+                }
+            }
         }
     }
 }
@@ -768,6 +818,18 @@ impl TryFrom<golem::worker::v1::WorkerExecutionError> for GolemError {
             Some(golem::worker::v1::worker_execution_error::Error::ShardingNotReady(_)) => {
                 Ok(GolemError::ShardingNotReady)
             }
+            Some(golem::worker::v1::worker_execution_error::Error::ResultTooLarge(
+                result_too_large,
+            )) => Ok(GolemError::ResultTooLarge {
+                size: result_too_large.size,
+                limit: result_too_large.limit,
+                blob_key: result_too_large.blob_key,
+            }),
+            Some(golem::worker::v1::worker_execution_error::Error::InvocationTimedOut(
+                invocation_timed_out,
+            )) => Ok(GolemError::InvocationTimedOut {
+                timeout_millis: invocation_timed_out.timeout_millis,
+            }),
         }
     }
 }
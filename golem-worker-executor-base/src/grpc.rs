@@ -12,12 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use bytes::Bytes;
+use chrono::Utc;
+use dashmap::DashMap;
+use futures_util::future::join_all;
+use futures_util::stream::BoxStream;
 use futures_util::Stream;
 use gethostname::gethostname;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
-use golem_wasm_rpc::protobuf::Val;
+use golem_wasm_rpc::protobuf::{TypedList, TypedRecord, TypedTuple, Val};
+use prost::Message;
 use std::cmp::min;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::{Debug, Display, Formatter};
 use std::future::Future;
 use std::marker::PhantomData;
@@ -25,8 +31,11 @@ use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Mutex;
 use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
 use tonic::{Request, Response, Status};
 use tracing::{debug, error, info, warn, Instrument};
 use uuid::Uuid;
@@ -35,32 +44,62 @@ use wasmtime::Error;
 use crate::error::*;
 use golem_api_grpc::proto::golem;
 use golem_api_grpc::proto::golem::common::ResourceLimits as GrpcResourceLimits;
-use golem_api_grpc::proto::golem::worker::{Cursor, ResourceMetadata, UpdateMode};
+use golem_api_grpc::proto::golem::worker::{
+    BlobParameter, BlobParameterEncoding, Cursor, ResourceMetadata, UpdateMode,
+};
 use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_server::WorkerExecutor;
-use golem_api_grpc::proto::golem::workerexecutor::v1::{ConnectWorkerRequest, DeleteWorkerRequest, FileNode, GetFilesRequest, GetFilesResponse, GetFilesSuccessResponse, GetOplogRequest, GetOplogResponse, GetRunningWorkersMetadataRequest, GetRunningWorkersMetadataResponse, GetWorkersMetadataRequest, GetWorkersMetadataResponse, InvokeAndAwaitWorkerRequest, InvokeAndAwaitWorkerResponseTyped, InvokeAndAwaitWorkerSuccess, NodeType, UpdateWorkerRequest, UpdateWorkerResponse};
+use golem_api_grpc::proto::golem::workerexecutor::v1::{ConnectWorkerRequest, DeleteWorkerRequest, FileNode, GetFilesRequest, GetFilesResponse, GetFilesSuccessResponse, GetInvocationResultBlobRequest, GetInvocationResultBlobResponse, GetOplogRequest, GetOplogResponse, GetRawOplogRequest, GetRawOplogResponse, GetRunningWorkersMetadataRequest, GetRunningWorkersMetadataResponse, GetWorkersMetadataRequest, GetWorkersMetadataResponse, InvokeAndAwaitWorkerRequest, InvokeAndAwaitWorkerStreamingResponse, SearchWorkersRequest, SearchWorkersResponse, InvokeAndAwaitWorkerResponseTyped, InvokeAndAwaitWorkerSuccess, NodeType, UpdateWorkerRequest, UpdateWorkerResponse};
 use golem_api_grpc::proto::golem::workerexecutor::v1::get_files_response::Result::Failure;
 use golem_common::grpc::{
     proto_account_id_string, proto_component_id_string, proto_idempotency_key_string,
     proto_promise_id_string, proto_target_worker_id_string, proto_worker_id_string,
 };
-use golem_common::metrics::api::record_new_grpc_api_active_stream;
+use golem_common::metrics::api::{
+    record_closed_grpc_api_active_stream, record_new_grpc_api_active_stream,
+};
+use golem_common::metrics::DEFAULT_TIME_BUCKETS;
 use golem_common::model::oplog::{OplogIndex, UpdateDescription};
+use golem_common::model::public_oplog::OplogQuery;
+use golem_common::model::worker_metadata_projection::{
+    WorkerMetadataField, WorkerMetadataProjection,
+};
+use golem_common::model::worker_name_query::WorkerNameQuery;
 use golem_common::model::{
-    AccountId, ComponentId, ComponentType, IdempotencyKey, OwnedWorkerId, ScanCursor, ShardId,
-    TargetWorkerId, TimestampedWorkerInvocation, WorkerEvent, WorkerFilter, WorkerId,
-    WorkerInvocation, WorkerMetadata, WorkerStatus, WorkerStatusRecord,
+    AccountId, CallerIdentity, ComponentId, ComponentType, ConnectOptions, FilterComparator,
+    IdempotencyKey, OwnedWorkerId, ScanCursor, ScheduledAction, ShardId, TargetWorkerId,
+    Timestamp, TimestampedWorkerInvocation, UpdateDivergence, WorkerDependent, WorkerEvent,
+    WorkerFilter, WorkerId, WorkerInvocation, WorkerMetadata, WorkerShadow, WorkerStatus,
+    WorkerStatusRecord,
 };
 use golem_common::{model as common_model, recorded_grpc_api_request};
+use crate::durable_host::recover_log_events;
 use crate::model::public_oplog::{find_component_version_at, get_public_oplog_chunk};
-use crate::model::{InterruptKind, LastError};
-use crate::services::events::Event;
+use crate::model::{ErrorGroup, InterruptKind, LastError, LookupResult};
+use crate::metrics::events::record_event_stream_closed;
+use crate::metrics::functions::get_function_stats;
+use crate::metrics::wasm::{
+    record_invocation_input_size, record_invocation_output_size, record_oversized_invocation_result,
+};
+use crate::services::events::{Event, EventsSubscription};
+use crate::services::golem_config::Limits;
+use crate::services::oplog::{CommitLevel, OplogService};
 use crate::services::worker_activator::{DefaultWorkerActivator, LazyWorkerActivator};
+use crate::services::autoscaling::start_autoscaling_signals;
+use crate::services::readiness::mark_shard_assignment_received;
+use crate::services::worker_consistency::check_worker_consistency;
+use crate::services::worker_shadow::shadow_invocation;
 use crate::services::worker_event::WorkerEventReceiver;
-use crate::services::{All, HasActiveWorkers, HasAll, HasBlobStoreService, HasComponentService, HasEvents, HasOplogService, HasPromiseService, HasRunningWorkerEnumerationService, HasShardManagerService, HasShardService, HasWorkerEnumerationService, HasWorkerService, UsesAllDeps};
+use crate::services::{All, HasActiveWorkers, HasAll, HasBlobStoreService, HasComponentService, HasConfig, HasDomainEventService, HasEvents, HasOplog, HasOplogService, HasPromiseService, HasRunningWorkerEnumerationService, HasSchedulerService, HasShardManagerService, HasShardService, HasWorkerEnumerationService, HasWorkerService, UsesAllDeps};
 use crate::services::blob_store::{FileOrDirectoryResponse, Node};
 use crate::worker::Worker;
 use crate::workerctx::WorkerCtx;
 
+/// The maximum number of oplog entries scanned by a single `get_oplog` request with a query
+const MAX_SCANNED_OPLOG_ENTRIES: usize = 1000;
+
+/// The maximum number of entries returned by a single `get_raw_oplog` request
+const MAX_RAW_OPLOG_ENTRIES: u64 = 100;
+
 pub enum GrpcError<E> {
     Transport(tonic::transport::Error),
     Status(Status),
@@ -126,6 +165,9 @@ pub struct WorkerExecutorImpl<
 > {
     /// Reference to all the initialized services
     services: Svcs,
+    // Serializes the creation of each worker, so of the concurrent requests creating the same
+    // worker only one creates it and the others find it existing
+    creation_locks: Arc<DashMap<WorkerId, Arc<Mutex<()>>>>,
     ctx: PhantomData<Ctx>,
 }
 
@@ -135,6 +177,7 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
     fn clone(&self) -> Self {
         Self {
             services: self.services.clone(),
+            creation_locks: self.creation_locks.clone(),
             ctx: PhantomData,
         }
     }
@@ -142,6 +185,13 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
 
 type ResponseResult<T> = Result<Response<T>, Status>;
 type ResponseStream = WorkerEventStream;
+type InvocationResultStream =
+    Pin<Box<dyn Stream<Item = Result<InvokeAndAwaitWorkerStreamingResponse, Status>> + Send>>;
+type InvocationResultBlobStream =
+    Pin<Box<dyn Stream<Item = Result<GetInvocationResultBlobResponse, Status>> + Send>>;
+
+/// Size of the chunks an oversized invocation result is returned in from the blob storage
+const RESULT_BLOB_CHUNK_SIZE: usize = 1024 * 1024;
 
 impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync + 'static>
     WorkerExecutorImpl<Ctx, Svcs>
@@ -153,6 +203,7 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
     ) -> Result<Self, Error> {
         let worker_executor = WorkerExecutorImpl {
             services: services.clone(),
+            creation_locks: Arc::new(DashMap::new()),
             ctx: PhantomData,
         };
         let worker_activator = Arc::new(DefaultWorkerActivator::new(services));
@@ -171,9 +222,14 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             shard_assignment.number_of_shards,
             &shard_assignment.shard_ids,
         );
+        mark_shard_assignment_received();
 
         info!("Registered worker executor, waiting for shard assignment...");
 
+        check_worker_consistency(&worker_executor, &shard_assignment.shard_ids).await;
+
+        start_autoscaling_signals(&worker_executor);
+
         Ctx::on_shard_assignment_changed(&worker_executor).await?;
 
         Ok(worker_executor)
@@ -224,10 +280,13 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         self.shard_service().check_worker(worker_id)
     }
 
+    /// Creates the worker, or with `get_existing` returns the component version of the worker if
+    /// it already exists instead of failing
     async fn create_worker_internal(
         &self,
         request: golem::workerexecutor::v1::CreateWorkerRequest,
-    ) -> Result<(), GolemError> {
+        get_existing: bool,
+    ) -> Result<golem::workerexecutor::v1::GetOrCreateWorkerSuccess, GolemError> {
         let worker_id = request
             .worker_id
             .ok_or(GolemError::invalid_request("worker_id not found"))?;
@@ -247,21 +306,57 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
 
         self.ensure_worker_belongs_to_this_executor(&worker_id)?;
 
-        let existing_worker = self.worker_service().get(&owned_worker_id).await;
-        if existing_worker.is_some() {
-            return Err(GolemError::worker_already_exists(worker_id.clone()));
+        let lock = self
+            .creation_locks
+            .entry(worker_id.clone())
+            .or_default()
+            .clone();
+        let guard = lock.lock().await;
+        let result = self
+            .create_worker_serialized(
+                &owned_worker_id,
+                request.args,
+                request.env,
+                component_version,
+                request.ttl_millis,
+                get_existing,
+            )
+            .await;
+        drop(guard);
+
+        // Forgets the lock of the worker when no other request is holding or waiting for it
+        self.creation_locks
+            .remove_if(&worker_id, |_, lock| Arc::strong_count(lock) == 2);
+        result
+    }
+
+    async fn create_worker_serialized(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+        component_version: u64,
+        ttl_millis: Option<u64>,
+        get_existing: bool,
+    ) -> Result<golem::workerexecutor::v1::GetOrCreateWorkerSuccess, GolemError> {
+        if let Some(existing_worker) = self.worker_service().get(owned_worker_id).await {
+            return if get_existing {
+                Ok(golem::workerexecutor::v1::GetOrCreateWorkerSuccess {
+                    component_version: existing_worker.last_known_status.component_version,
+                    created: false,
+                })
+            } else {
+                Err(GolemError::worker_already_exists(
+                    owned_worker_id.worker_id(),
+                ))
+            };
         }
 
-        let args = request.args;
-        let env = request
-            .env
-            .iter()
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect();
+        let env = env.into_iter().collect();
 
         let worker = Worker::get_or_create_suspended(
             self,
-            &owned_worker_id,
+            owned_worker_id,
             Some(args),
             Some(env),
             Some(component_version),
@@ -269,6 +364,11 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         )
         .await?;
 
+        if let Some(ttl_millis) = ttl_millis {
+            self.set_ttl(owned_worker_id, Some(Duration::from_millis(ttl_millis)))
+                .await;
+        }
+
         let mut subscription = self.events().subscribe();
         Worker::start_if_needed(worker.clone()).await?;
         if worker.is_loading() {
@@ -283,16 +383,23 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
                 })
                 .await
             {
-                Ok(Ok(())) => Ok(()),
-                Ok(Err(e)) => Err(e),
-                Err(RecvError::Closed) => Err(GolemError::unknown("Events subscription closed")),
-                Err(RecvError::Lagged(_)) => Err(GolemError::unknown(
-                    "Worker executor is overloaded and could not wait for worker to load",
-                )),
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => return Err(e),
+                Err(RecvError::Closed) => {
+                    return Err(GolemError::unknown("Events subscription closed"))
+                }
+                Err(RecvError::Lagged(_)) => {
+                    return Err(GolemError::unknown(
+                        "Worker executor is overloaded and could not wait for worker to load",
+                    ))
+                }
             }
-        } else {
-            Ok(())
         }
+
+        Ok(golem::workerexecutor::v1::GetOrCreateWorkerSuccess {
+            component_version,
+            created: true,
+        })
     }
 
     async fn complete_promise_internal(
@@ -354,7 +461,10 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         Ok(success)
     }
 
-    async fn delete_worker_internal(&self, inner: DeleteWorkerRequest) -> Result<(), GolemError> {
+    async fn delete_worker_internal(
+        &self,
+        inner: DeleteWorkerRequest,
+    ) -> Result<Vec<String>, GolemError> {
         let worker_id: WorkerId = inner
             .worker_id
             .ok_or(GolemError::invalid_request("worker_id not found"))?
@@ -370,37 +480,14 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
 
         self.ensure_worker_belongs_to_this_executor(&worker_id)?;
 
-        let metadata = self.worker_service().get(&owned_worker_id).await;
-        let worker_status =
-            Ctx::compute_latest_worker_status(self, &owned_worker_id, &metadata).await?;
-
-        let should_interrupt = match &worker_status.status {
-            WorkerStatus::Idle
-            | WorkerStatus::Running
-            | WorkerStatus::Suspended
-            | WorkerStatus::Retrying => true,
-            WorkerStatus::Exited | WorkerStatus::Failed | WorkerStatus::Interrupted => false,
-        };
-
-        if should_interrupt {
-            let worker =
-                Worker::get_or_create_suspended(self, &owned_worker_id, None, None, None, None)
-                    .await?;
-
-            if let Some(mut await_interrupted) =
-                worker.set_interrupting(InterruptKind::Interrupt).await
-            {
-                await_interrupted.recv().await.unwrap();
+        if inner.cascade {
+            Worker::delete_cascading(self, &owned_worker_id, inner.dry_run).await
+        } else {
+            if !inner.dry_run {
+                Worker::delete(self, &owned_worker_id).await?;
             }
-
-            worker.stop().await;
+            Ok(Vec::new())
         }
-
-        Ctx::on_worker_deleted(self, &worker_id).await?;
-        self.worker_service().remove(&owned_worker_id).await;
-        self.active_workers().remove(&worker_id);
-
-        Ok(())
     }
 
     async fn interrupt_worker_internal(
@@ -420,6 +507,14 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
 
         let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
 
+        if let Some(idempotency_key) = request.idempotency_key {
+            let idempotency_key: IdempotencyKey = idempotency_key.into();
+            let worker = self.pending_invocation_worker(&owned_worker_id).await?;
+            worker.hold_invocation(&idempotency_key).await?;
+            info!("Holding pending invocation {idempotency_key}");
+            return Ok(());
+        }
+
         let metadata = self.worker_service().get(&owned_worker_id).await;
         let worker_status =
             Ctx::compute_latest_worker_status(self, &owned_worker_id, &metadata).await?;
@@ -492,6 +587,202 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         Ok(())
     }
 
+    async fn abort_running_invocation_internal(
+        &self,
+        request: golem::workerexecutor::v1::AbortRunningInvocationRequest,
+    ) -> Result<bool, GolemError> {
+        let worker_id: WorkerId = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?
+            .try_into()
+            .map_err(GolemError::invalid_request)?;
+        let idempotency_key: IdempotencyKey = request
+            .idempotency_key
+            .ok_or(GolemError::invalid_request("idempotency_key not found"))?
+            .into();
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        // Only a worker in memory can be executing the invocation
+        let worker = self
+            .active_workers()
+            .iter()
+            .find(|(id, _)| *id == worker_id)
+            .map(|(_, worker)| worker);
+
+        match worker {
+            Some(worker) if worker.interrupt_invocation(&idempotency_key).await => {
+                info!("Aborted running invocation {idempotency_key}");
+                // Explicitly drop from the active worker cache - this will drop websocket connections etc.
+                self.active_workers().remove(&worker_id);
+                Ok(true)
+            }
+            _ => {
+                debug!(
+                    "Not aborting invocation {idempotency_key} as the worker is not executing it"
+                );
+                Ok(false)
+            }
+        }
+    }
+
+    async fn cancel_invocation_internal(
+        &self,
+        request: golem::workerexecutor::v1::CancelInvocationRequest,
+    ) -> Result<bool, GolemError> {
+        let worker_id: WorkerId = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?
+            .try_into()
+            .map_err(GolemError::invalid_request)?;
+        let idempotency_key: IdempotencyKey = request
+            .idempotency_key
+            .ok_or(GolemError::invalid_request("idempotency_key not found"))?
+            .into();
+        let account_id: AccountId = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into();
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        let worker = self.pending_invocation_worker(&owned_worker_id).await?;
+        let canceled = worker.cancel_invocation(&idempotency_key).await?;
+        if canceled {
+            info!("Canceled pending invocation {idempotency_key}");
+        } else {
+            debug!("Not canceling invocation {idempotency_key} as it is not pending anymore");
+        }
+        Ok(canceled)
+    }
+
+    /// Gets the worker owning the pending invocations to be managed individually
+    async fn pending_invocation_worker(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Result<Arc<Worker<Ctx>>, GolemError> {
+        self.ensure_worker_belongs_to_this_executor(&owned_worker_id.worker_id)?;
+
+        if self.worker_service().get(owned_worker_id).await.is_none() {
+            return Err(GolemError::worker_not_found(
+                owned_worker_id.worker_id.clone(),
+            ));
+        }
+
+        // The pending invocations of a worker not in memory are loaded from its oplog
+        Worker::get_or_create_suspended(self, owned_worker_id, None, None, None, None).await
+    }
+
+    async fn schedule_invocation_internal(
+        &self,
+        request: golem::workerexecutor::v1::ScheduleInvocationRequest,
+    ) -> Result<IdempotencyKey, GolemError> {
+        let scheduled_at = request
+            .scheduled_at
+            .ok_or(GolemError::invalid_request("scheduled_at not found"))?;
+        let scheduled_at =
+            chrono::DateTime::from_timestamp(scheduled_at.seconds, scheduled_at.nanos as u32)
+                .ok_or(GolemError::invalid_request("scheduled_at is out of range"))?;
+        // The scheduler only looks back one hour, so invocations due in the past run right away
+        let scheduled_at = scheduled_at.max(Utc::now());
+
+        // The worker is not started, it only gets activated once the invocation is due
+        let worker = self.get_or_create_pending(&request).await?;
+
+        let idempotency_key = request
+            .idempotency_key()?
+            .unwrap_or(IdempotencyKey::fresh());
+
+        let function_input = self.function_input(&request).await?;
+
+        info!(
+            idempotency_key = idempotency_key.to_string(),
+            scheduled_at = scheduled_at.to_rfc3339(),
+            "Scheduling invocation of {}",
+            request.name
+        );
+        let schedule_id = self
+            .scheduler_service()
+            .schedule(
+                scheduled_at,
+                ScheduledAction::Invoke {
+                    owned_worker_id: worker.owned_worker_id().clone(),
+                    idempotency_key: idempotency_key.clone(),
+                    full_function_name: request.name,
+                    function_input,
+                },
+            )
+            .await;
+        self.worker_service()
+            .add_dependent(
+                worker.owned_worker_id(),
+                &WorkerDependent::Schedule(schedule_id),
+            )
+            .await;
+
+        Ok(idempotency_key)
+    }
+
+    async fn set_worker_profiling_internal(
+        &self,
+        request: golem::workerexecutor::v1::SetWorkerProfilingRequest,
+    ) -> Result<(), GolemError> {
+        let worker_id: WorkerId = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?
+            .try_into()
+            .map_err(GolemError::invalid_request)?;
+        let account_id: AccountId = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into();
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        if request.enabled {
+            if self.worker_service().get(&owned_worker_id).await.is_none() {
+                return Err(GolemError::worker_not_found(worker_id));
+            }
+
+            // The profiler lives in the worker, so it gets loaded and kept in memory while profiled
+            let worker =
+                Worker::get_or_create_suspended(self, &owned_worker_id, None, None, None, None)
+                    .await?;
+            worker.profiler().start(self.config().limits.epoch_interval);
+            info!("Started profiling worker");
+        } else if let Some((_, worker)) = self
+            .active_workers()
+            .iter()
+            .find(|(id, _)| *id == worker_id)
+        {
+            worker.profiler().stop();
+            info!("Stopped profiling worker");
+        }
+
+        Ok(())
+    }
+
+    async fn get_worker_profile_internal(
+        &self,
+        request: golem::workerexecutor::v1::GetWorkerProfileRequest,
+    ) -> Result<Vec<u8>, GolemError> {
+        let worker_id: WorkerId = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?
+            .try_into()
+            .map_err(GolemError::invalid_request)?;
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        self.active_workers()
+            .iter()
+            .find(|(id, _)| *id == worker_id)
+            .and_then(|(_, worker)| worker.profiler().to_pprof())
+            .ok_or(GolemError::invalid_request(
+                "Worker has no profile, profiling was not started or the worker got unloaded since",
+            ))
+    }
+
     async fn resume_worker_internal(
         &self,
         request: golem::workerexecutor::v1::ResumeWorkerRequest,
@@ -509,6 +800,15 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
 
         let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
 
+        if let Some(idempotency_key) = request.idempotency_key {
+            let idempotency_key: IdempotencyKey = idempotency_key.into();
+            let worker = self.pending_invocation_worker(&owned_worker_id).await?;
+            worker.release_invocation(&idempotency_key).await?;
+            info!("Released held invocation {idempotency_key}");
+            Worker::start_if_needed(worker).await?;
+            return Ok(());
+        }
+
         self.ensure_worker_belongs_to_this_executor(&worker_id)?;
 
         let metadata = self.worker_service().get(&owned_worker_id).await;
@@ -543,7 +843,9 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         &self,
         request: &Req,
     ) -> Result<Vec<Val>, GolemError> {
-        let result = self.invoke_and_await_worker_internal_typed(request).await?;
+        let result = self
+            .invoke_and_await_worker_internal_limited(request)
+            .await?;
         let value = golem_wasm_rpc::Value::try_from(result)
             .map_err(|e| GolemError::unknown(e.to_string()))?;
 
@@ -578,19 +880,284 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             .idempotency_key()?
             .unwrap_or(IdempotencyKey::fresh());
 
-        let params_val = request.input();
+        // The blob parameters are deleted once the invocation is completed, so a retried
+        // request must not read them again
+        if let LookupResult::Complete(result) =
+            worker.lookup_invocation_result(&idempotency_key).await
+        {
+            return if request.map_result() {
+                result.and_then(|_| worker.mapped_result(&idempotency_key))
+            } else {
+                result
+            };
+        }
+
+        let function_input = self.function_input(request).await?;
+
+        let shadow = self
+            .worker_service()
+            .get_shadow(worker.owned_worker_id())
+            .await
+            .map(|shadow| (shadow, function_input.clone()));
+
+        if request.map_result() {
+            worker.request_result_mapping(&idempotency_key);
+        }
+
+        let result = worker
+            .invoke_and_await_with_timeout(
+                idempotency_key.clone(),
+                full_function_name.clone(),
+                function_input,
+                request.timeout(),
+                request.caller(),
+            )
+            .await;
+        self.delete_blob_input(request).await;
+        let mut values = result?;
+
+        if let Some((shadow, function_input)) = shadow {
+            shadow_invocation(
+                self,
+                worker.owned_worker_id(),
+                shadow,
+                idempotency_key.clone(),
+                full_function_name,
+                function_input,
+                Some(&values),
+            )
+            .await;
+        }
+
+        if request.map_result() {
+            values = worker.mapped_result(&idempotency_key)?;
+        }
+
+        record_invocation_output_size(values.encoded_len());
+
+        Ok(values)
+    }
+
+    /// Invokes the worker like `invoke_and_await_worker_internal_typed`, but results larger than
+    /// the configured maximum result size are stored in the blob storage and reported with a
+    /// `ResultTooLarge` error holding their key instead of being returned
+    async fn invoke_and_await_worker_internal_limited<Req: GrpcInvokeRequest>(
+        &self,
+        request: &Req,
+    ) -> Result<TypeAnnotatedValue, GolemError> {
+        let result = self.invoke_and_await_worker_internal_typed(request).await?;
+
+        let size = result.encoded_len();
+        let limit = self.config().limits.max_result_size;
+        if size <= limit {
+            return Ok(result);
+        }
+
+        record_oversized_invocation_result();
+        let worker_id = request.worker_id()?;
+        // A retried request gets the same key, and the result is not stored again
+        let blob_key = match request.idempotency_key()? {
+            Some(idempotency_key) => invocation_result_blob_key(&worker_id, &idempotency_key),
+            None => format!("{}/{}", worker_id.component_id, Uuid::new_v4()),
+        };
+        let account_id = request.account_id()?;
+        let already_stored = self
+            .blob_store_service()
+            .has_invocation_result(account_id.clone(), &blob_key)
+            .await
+            .unwrap_or(false);
+        if !already_stored {
+            let payload = golem_wasm_rpc::protobuf::TypeAnnotatedValue {
+                type_annotated_value: Some(result),
+            }
+            .encode_to_vec();
+            self.blob_store_service()
+                .put_invocation_result(account_id, &blob_key, &payload)
+                .await
+                .map_err(|err| {
+                    GolemError::runtime(format!(
+                        "Failed to store oversized invocation result: {err}"
+                    ))
+                })?;
+        }
+
+        Err(GolemError::ResultTooLarge {
+            size: size as u64,
+            limit: limit as u64,
+            blob_key,
+        })
+    }
+
+    async fn get_invocation_result_blob_internal(
+        &self,
+        request: GetInvocationResultBlobRequest,
+    ) -> Result<Bytes, GolemError> {
+        let account_id: AccountId = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into();
+
+        self.blob_store_service()
+            .get_invocation_result(account_id, &request.blob_key)
+            .await
+            .map_err(|err| {
+                GolemError::runtime(format!(
+                    "Failed to read invocation result {}: {err}",
+                    request.blob_key
+                ))
+            })?
+            .ok_or_else(|| {
+                GolemError::invalid_request(format!(
+                    "Invocation result {} not found",
+                    request.blob_key
+                ))
+            })
+    }
+
+    /// Deletes the blob parameters of an invocation which are not needed anymore
+    async fn delete_blob_input<Req: GrpcInvokeRequest>(&self, request: &Req) {
+        let blob_input = request.blob_input();
+        if blob_input.is_empty() {
+            return;
+        }
+        let Ok(account_id) = request.account_id() else {
+            return;
+        };
+        for blob_param in blob_input {
+            if let Err(err) = self
+                .blob_store_service()
+                .delete_invocation_parameter(account_id.clone(), &blob_param.key)
+                .await
+            {
+                warn!("Failed to delete blob parameter {}: {err}", blob_param.key);
+            }
+        }
+    }
+
+    async fn put_invocation_parameter_internal(
+        &self,
+        mut request: tonic::Streaming<golem::workerexecutor::v1::PutInvocationParameterRequest>,
+    ) -> Result<(), GolemError> {
+        let first = request
+            .message()
+            .await
+            .map_err(|status| GolemError::invalid_request(status.message()))?
+            .ok_or(GolemError::invalid_request(
+                "Empty invocation parameter upload",
+            ))?;
+        let account_id: AccountId = first
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into();
+        let key = first.key;
+        if key.is_empty()
+            || key
+                .split('/')
+                .any(|segment| segment.is_empty() || segment == "..")
+        {
+            return Err(GolemError::invalid_request(format!(
+                "Invalid invocation parameter key {key}"
+            )));
+        }
+
+        let mut payload = first.chunk;
+        while let Some(next) = request
+            .message()
+            .await
+            .map_err(|status| GolemError::invalid_request(status.message()))?
+        {
+            payload.extend_from_slice(&next.chunk);
+        }
+
+        self.blob_store_service()
+            .put_invocation_parameter(account_id, &key, &payload)
+            .await
+            .map_err(|err| {
+                GolemError::runtime(format!("Failed to store invocation parameter {key}: {err}"))
+            })
+    }
+
+    /// The parameters of an invocation, with the blob parameters read from the blob storage and
+    /// inserted among the ones passed in the request
+    async fn function_input<Req: GrpcInvokeRequest>(
+        &self,
+        request: &Req,
+    ) -> Result<Vec<golem_wasm_rpc::Value>, GolemError> {
+        let input = request.input();
+        let mut blob_input = request.blob_input();
+        record_invocation_input_size(
+            input.iter().map(|val| val.encoded_len()).sum::<usize>()
+                + blob_input
+                    .iter()
+                    .map(|blob_param| blob_param.size as usize)
+                    .sum::<usize>(),
+        );
 
-        let function_input = params_val
+        let mut function_input = input
             .into_iter()
-            .map(|val| val.clone().try_into())
+            .map(|val| val.try_into())
             .collect::<Result<Vec<_>, _>>()
             .map_err(|msg| GolemError::ValueMismatch { details: msg })?;
 
-        let values = worker
-            .invoke_and_await(idempotency_key, full_function_name, function_input)
-            .await?;
+        if blob_input.is_empty() {
+            return Ok(function_input);
+        }
 
-        Ok(values)
+        let account_id = request.account_id()?;
+        blob_input.sort_by_key(|blob_param| blob_param.index);
+        for blob_param in blob_input {
+            let index = blob_param.index as usize;
+            if index > function_input.len() {
+                return Err(GolemError::invalid_request(format!(
+                    "Blob parameter index {index} is out of range"
+                )));
+            }
+
+            let payload = self
+                .blob_store_service()
+                .get_invocation_parameter(account_id.clone(), &blob_param.key)
+                .await
+                .map_err(|err| {
+                    GolemError::runtime(format!(
+                        "Failed to read blob parameter {}: {err}",
+                        blob_param.key
+                    ))
+                })?
+                .ok_or_else(|| {
+                    GolemError::invalid_request(format!(
+                        "Blob parameter {} not found",
+                        blob_param.key
+                    ))
+                })?;
+            if payload.len() as u64 != blob_param.size {
+                return Err(GolemError::invalid_request(format!(
+                    "Blob parameter {} has {} bytes instead of the expected {}",
+                    blob_param.key,
+                    payload.len(),
+                    blob_param.size
+                )));
+            }
+
+            let value = match blob_param.encoding() {
+                BlobParameterEncoding::Bytes => golem_wasm_rpc::Value::List(
+                    payload
+                        .iter()
+                        .map(|byte| golem_wasm_rpc::Value::U8(*byte))
+                        .collect(),
+                ),
+                BlobParameterEncoding::String => golem_wasm_rpc::Value::String(
+                    String::from_utf8(payload.to_vec()).map_err(|_| GolemError::ValueMismatch {
+                        details: format!(
+                            "Blob parameter {} is not a valid UTF-8 string",
+                            blob_param.key
+                        ),
+                    })?,
+                ),
+            };
+            function_input.insert(index, value);
+        }
+
+        Ok(function_input)
     }
 
     async fn get_or_create<Req: GrpcInvokeRequest>(
@@ -659,16 +1226,46 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             .idempotency_key()?
             .unwrap_or(IdempotencyKey::fresh());
 
-        let function_input = request
-            .input()
-            .iter()
-            .map(|val| val.clone().try_into())
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|msg| GolemError::ValueMismatch { details: msg })?;
+        // The blob parameters are deleted once the invocation is accepted, so a retried request
+        // must not read them again
+        if !matches!(
+            worker.lookup_invocation_result(&idempotency_key).await,
+            LookupResult::New
+        ) {
+            return Ok(());
+        }
 
-        worker
-            .invoke(idempotency_key, full_function_name, function_input)
+        let function_input = self.function_input(request).await?;
+
+        let shadow = self
+            .worker_service()
+            .get_shadow(worker.owned_worker_id())
+            .await
+            .map(|shadow| (shadow, function_input.clone()));
+
+        worker
+            .invoke_with_caller(
+                idempotency_key.clone(),
+                full_function_name.clone(),
+                function_input,
+                request.caller(),
+            )
             .await?;
+        // The parameters are persisted in the oplog together with the accepted invocation
+        self.delete_blob_input(request).await;
+
+        if let Some((shadow, function_input)) = shadow {
+            shadow_invocation(
+                self,
+                worker.owned_worker_id(),
+                shadow,
+                idempotency_key,
+                full_function_name,
+                function_input,
+                None,
+            )
+            .await;
+        }
 
         Ok(())
     }
@@ -706,6 +1303,8 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         let shard_ids = proto_shard_ids.into_iter().map(ShardId::from).collect();
 
         self.shard_service().assign_shards(&shard_ids)?;
+        // The workers of the new shards are checked before they get recovered
+        check_worker_consistency(self, &shard_ids).await;
         Ctx::on_shard_assignment_changed(self).await?;
 
         Ok(())
@@ -729,23 +1328,85 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         let account_id: AccountId = account_id.clone().into();
 
         let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+        self.get_proto_worker_metadata(&owned_worker_id).await
+    }
+
+    async fn get_worker_metadata_many_internal(
+        &self,
+        request: golem::workerexecutor::v1::GetWorkerMetadataManyRequest,
+    ) -> Result<golem::workerexecutor::v1::GetWorkerMetadataManySuccessResponse, GolemError> {
+        let account_id: AccountId = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into();
+        let worker_ids = request
+            .worker_ids
+            .into_iter()
+            .map(WorkerId::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(GolemError::invalid_request)?;
+
+        // Workers of other shards are left to the caller, which knows where to route them
+        let (worker_ids, unassigned_worker_ids): (Vec<_>, Vec<_>) = worker_ids
+            .into_iter()
+            .partition(|worker_id| self.ensure_worker_belongs_to_this_executor(worker_id).is_ok());
+
+        let results = join_all(worker_ids.iter().map(|worker_id| {
+            let owned_worker_id = OwnedWorkerId::new(&account_id, worker_id);
+            async move { self.get_proto_worker_metadata(&owned_worker_id).await }
+        }))
+        .await;
+
+        // A worker whose metadata cannot be read does not fail the lookup of the others
+        let mut workers = Vec::new();
+        let mut failures = Vec::new();
+        for (worker_id, result) in worker_ids.into_iter().zip(results) {
+            match result {
+                Ok(metadata) => workers.push(metadata),
+                Err(GolemError::WorkerNotFound { .. }) => {}
+                Err(err) => failures.push(golem::workerexecutor::v1::WorkerMetadataFailure {
+                    worker_id: Some(worker_id.into()),
+                    error: Some(err.into()),
+                }),
+            }
+        }
+
+        Ok(
+            golem::workerexecutor::v1::GetWorkerMetadataManySuccessResponse {
+                workers,
+                unassigned_worker_ids: unassigned_worker_ids
+                    .into_iter()
+                    .map(|worker_id| worker_id.into())
+                    .collect(),
+                failures,
+            },
+        )
+    }
 
+    async fn get_proto_worker_metadata(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Result<golem::worker::WorkerMetadata, GolemError> {
         let metadata = self
             .worker_service()
-            .get(&owned_worker_id)
+            .get(owned_worker_id)
             .await
-            .ok_or(GolemError::worker_not_found(worker_id.clone()))?;
+            .ok_or(GolemError::worker_not_found(owned_worker_id.worker_id()))?;
 
         let latest_status =
-            Ctx::compute_latest_worker_status(self, &owned_worker_id, &Some(metadata.clone()))
+            Ctx::compute_latest_worker_status(self, owned_worker_id, &Some(metadata.clone()))
                 .await?;
         let last_error_and_retry_count =
-            Ctx::get_last_error_and_retry_count(self, &owned_worker_id).await;
+            Ctx::get_last_error_and_retry_count(self, owned_worker_id).await;
+        let update_divergences = self
+            .get_update_divergences(owned_worker_id, &latest_status)
+            .await;
 
         Ok(Self::create_proto_metadata(
             metadata,
             latest_status,
             last_error_and_retry_count,
+            &update_divergences,
         ))
     }
 
@@ -772,7 +1433,7 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             .into_iter()
             .map(|worker| {
                 let status = worker.last_known_status.clone();
-                Self::create_proto_metadata(worker, status, None)
+                Self::create_proto_metadata(worker, status, None, &[])
             })
             .collect();
 
@@ -816,13 +1477,45 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             )
             .await?;
 
+        let projection: Option<WorkerMetadataProjection> = request
+            .projection
+            .map(|p| p.try_into())
+            .transpose()
+            .map_err(GolemError::invalid_request)?;
+        // Skipping the lookups needed only by the fields which are not returned
+        let includes = |field| {
+            projection
+                .as_ref()
+                .map_or(true, |projection| projection.includes(field))
+        };
+        let with_last_error =
+            includes(WorkerMetadataField::LastError) || includes(WorkerMetadataField::RetryCount);
+        let with_updates = includes(WorkerMetadataField::Updates);
+
         let mut result = Vec::new();
 
         for worker in workers {
             let status = worker.last_known_status.clone();
-            let last_error_and_retry_count =
-                Ctx::get_last_error_and_retry_count(self, &worker.owned_worker_id()).await;
-            let metadata = Self::create_proto_metadata(worker, status, last_error_and_retry_count);
+            let last_error_and_retry_count = if with_last_error {
+                Ctx::get_last_error_and_retry_count(self, &worker.owned_worker_id()).await
+            } else {
+                None
+            };
+            let update_divergences = if with_updates {
+                self.get_update_divergences(&worker.owned_worker_id(), &status)
+                    .await
+            } else {
+                Vec::new()
+            };
+            let mut metadata = Self::create_proto_metadata(
+                worker,
+                status,
+                last_error_and_retry_count,
+                &update_divergences,
+            );
+            if let Some(projection) = &projection {
+                projection.apply(&mut metadata);
+            }
             result.push(metadata);
         }
 
@@ -835,6 +1528,40 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         ))
     }
 
+    async fn search_workers_internal(
+        &self,
+        request: SearchWorkersRequest,
+    ) -> Result<Vec<golem::worker::WorkerId>, GolemError> {
+        let component_id: ComponentId = request
+            .component_id
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+
+        let query: WorkerNameQuery = request
+            .query
+            .ok_or(GolemError::invalid_request("query not found"))?
+            .try_into()
+            .map_err(GolemError::invalid_request)?;
+
+        let account_id: AccountId = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into();
+
+        let count = if request.count == 0 {
+            usize::MAX
+        } else {
+            request.count as usize
+        };
+
+        let worker_ids = self
+            .worker_service()
+            .search(&account_id, &component_id, &query, count)
+            .await?;
+
+        Ok(worker_ids.into_iter().map(|id| id.into()).collect())
+    }
+
     async fn update_worker_internal(&self, request: UpdateWorkerRequest) -> Result<(), GolemError> {
         let worker_id = request
             .worker_id
@@ -983,6 +1710,74 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         Ok(())
     }
 
+    async fn update_worker_env_internal(
+        &self,
+        request: golem::workerexecutor::v1::UpdateWorkerEnvRequest,
+    ) -> Result<(), GolemError> {
+        let worker_id: WorkerId = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?
+            .try_into()
+            .map_err(GolemError::invalid_request)?;
+        let account_id: AccountId = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into();
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        if self.worker_service().get(&owned_worker_id).await.is_none() {
+            return Err(GolemError::worker_not_found(worker_id));
+        }
+
+        let mut env = request.env.into_iter().collect::<Vec<_>>();
+        env.sort();
+
+        let worker =
+            Worker::get_or_create_suspended(self, &owned_worker_id, None, None, None, None).await?;
+        worker.update_env(env).await?;
+        info!("Updated the environment of the worker");
+        Ok(())
+    }
+
+    async fn redact_oplog_internal(
+        &self,
+        request: golem::workerexecutor::v1::RedactOplogRequest,
+    ) -> Result<OplogIndex, GolemError> {
+        let worker_id: WorkerId = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?
+            .try_into()
+            .map_err(GolemError::invalid_request)?;
+        let account_id: AccountId = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into();
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        if self.worker_service().get(&owned_worker_id).await.is_none() {
+            return Err(GolemError::worker_not_found(worker_id));
+        }
+
+        let entries = request
+            .oplog_indices
+            .into_iter()
+            .map(OplogIndex::from_u64)
+            .collect::<BTreeSet<_>>();
+        if entries.is_empty() {
+            return Err(GolemError::invalid_request("No oplog entries to redact"));
+        }
+
+        let worker =
+            Worker::get_or_create_suspended(self, &owned_worker_id, None, None, None, None).await?;
+        let oplog_idx = Worker::redact_oplog(worker, entries.clone(), request.reason).await?;
+        info!("Redacted oplog entries {entries:?}, recorded at {oplog_idx}");
+        Ok(oplog_idx)
+    }
+
     async fn connect_worker_internal(
         &self,
         request: ConnectWorkerRequest,
@@ -1011,17 +1806,30 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
                 })?;
 
             if worker_status.status != WorkerStatus::Interrupted {
-                let event_service =
+                let options: ConnectOptions = request.options.map(|o| o.into()).unwrap_or_default();
+                let worker =
                     Worker::get_or_create_suspended(self, &owned_worker_id, None, None, None, None)
-                        .await?
-                        .event_service();
+                        .await?;
 
-                let receiver = event_service.receiver();
+                // Subscribing before reading the oplog, so no events get lost in between
+                let receiver = worker.event_service().receiver();
+                let replayed = if options.replays_history() {
+                    let oplog = worker.oplog();
+                    oplog.commit(CommitLevel::DurableOnly).await;
+                    let last_oplog_idx = oplog.current_oplog_index().await;
+                    Some(recover_log_events(self, &owned_worker_id, last_oplog_idx, &options).await)
+                } else {
+                    None
+                };
 
                 info!("Client connected");
                 record_new_grpc_api_active_stream();
 
-                Ok(Response::new(WorkerEventStream::new(receiver)))
+                Ok(Response::new(WorkerEventStream::new(
+                    receiver,
+                    replayed,
+                    &self.config().limits,
+                )))
             } else {
                 // We don't want 'connect' to resume interrupted workers
                 Err(GolemError::Interrupted {
@@ -1052,44 +1860,70 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
 
         self.ensure_worker_belongs_to_this_executor(&worker_id)?;
 
-        let chunk = match request.cursor {
-            Some(cursor) => {
-                get_public_oplog_chunk(
-                    self.component_service(),
-                    self.oplog_service(),
-                    &owned_worker_id,
-                    cursor.current_component_version,
-                    OplogIndex::from_u64(cursor.next_oplog_index),
-                    min(request.count as usize, 100), // TODO: configurable maximum
-                )
-                .await
-                .map_err(GolemError::unknown)?
-            }
+        let count = min(request.count as usize, 100); // TODO: configurable maximum
+        let query = request.query.map(OplogQuery::from);
+
+        let (mut next_oplog_index, mut current_component_version) = match request.cursor {
+            Some(cursor) => (
+                OplogIndex::from_u64(cursor.next_oplog_index),
+                cursor.current_component_version,
+            ),
             None => {
                 let start = OplogIndex::from_u64(request.from_oplog_index);
                 let initial_component_version =
                     find_component_version_at(self.oplog_service(), &owned_worker_id, start)
                         .await?;
+                (start, initial_component_version)
+            }
+        };
 
-                get_public_oplog_chunk(
-                    self.component_service(),
-                    self.oplog_service(),
-                    &owned_worker_id,
-                    initial_component_version,
-                    start,
-                    min(request.count as usize, 100), // TODO: configurable maximum
-                )
-                .await
-                .map_err(GolemError::unknown)?
+        // Without a query this reads a single chunk. With a query, chunks are read until enough
+        // matching entries are found, the end of the oplog is reached, or too many entries were
+        // scanned - in which case the returned cursor continues the search.
+        let first_index_in_chunk = next_oplog_index;
+        let mut entries = Vec::new();
+        let mut indices = Vec::new();
+        let mut scanned = 0;
+        let mut reached_end = false;
+        let last_index = loop {
+            let chunk = get_public_oplog_chunk(
+                self.component_service(),
+                self.oplog_service(),
+                &owned_worker_id,
+                current_component_version,
+                next_oplog_index,
+                count - entries.len(),
+            )
+            .await
+            .map_err(GolemError::unknown)?;
+
+            if chunk.entries.is_empty() {
+                reached_end = true;
+                break chunk.last_index;
+            }
+
+            scanned += chunk.entries.len();
+            let chunk_start: u64 = chunk.first_index_in_chunk.into();
+            for (offset, entry) in chunk.entries.into_iter().enumerate() {
+                if query.as_ref().map_or(true, |query| query.matches(&entry)) {
+                    indices.push(chunk_start + offset as u64);
+                    entries.push(entry);
+                }
+            }
+            next_oplog_index = chunk.next_oplog_index;
+            current_component_version = chunk.current_component_version;
+
+            if query.is_none() || entries.len() >= count || scanned >= MAX_SCANNED_OPLOG_ENTRIES {
+                break chunk.last_index;
             }
         };
 
-        let next = if chunk.entries.is_empty() {
+        let next = if reached_end {
             None
         } else {
             Some(golem::worker::OplogCursor {
-                next_oplog_index: chunk.next_oplog_index.into(),
-                current_component_version: chunk.current_component_version,
+                next_oplog_index: next_oplog_index.into(),
+                current_component_version,
             })
         };
 
@@ -1097,125 +1931,425 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             result: Some(
                 golem::workerexecutor::v1::get_oplog_response::Result::Success(
                     golem::workerexecutor::v1::GetOplogSuccessResponse {
-                        entries: chunk
-                            .entries
+                        entries: entries
                             .into_iter()
                             .map(|entry| entry.try_into())
                             .collect::<Result<Vec<_>, _>>()
                             .map_err(GolemError::unknown)?,
                         next,
-                        first_index_in_chunk: chunk.first_index_in_chunk.into(),
-                        last_index: chunk.last_index.into(),
+                        first_index_in_chunk: first_index_in_chunk.into(),
+                        last_index: last_index.into(),
+                        indices,
                     },
                 ),
             ),
         })
     }
 
-    async fn get_files_internal(
+    async fn get_raw_oplog_internal(
         &self,
-        request: GetFilesRequest,
-    ) -> Result<GetFilesResponse, GolemError> {
+        request: GetRawOplogRequest,
+    ) -> Result<GetRawOplogResponse, GolemError> {
         let worker_id = request
             .worker_id
             .ok_or(GolemError::invalid_request("worker_id not found"))?;
         let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
-        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
-
-        // path to get the metadata from
 
         let account_id = request
             .account_id
             .ok_or(GolemError::invalid_request("account_id not found"))?;
         let account_id: AccountId = account_id.into();
 
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        let response = read_raw_oplog(
+            self.oplog_service(),
+            &owned_worker_id,
+            OplogIndex::from_u64(request.from_oplog_index),
+            request.count,
+        )
+        .await?;
+
+        Ok(GetRawOplogResponse {
+            result: Some(
+                golem::workerexecutor::v1::get_raw_oplog_response::Result::Success(response),
+            ),
+        })
+    }
+
+    async fn set_worker_shadow_internal(
+        &self,
+        request: golem::workerexecutor::v1::SetWorkerShadowRequest,
+    ) -> Result<(), GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+
+        let account_id: AccountId = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into();
 
         let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
 
+        if self.worker_service().get(&owned_worker_id).await.is_none() {
+            return Err(GolemError::worker_not_found(worker_id));
+        }
 
-        match self.services.blob_store_service().get_files_metadata(owned_worker_id.clone()).await {
-            Ok(files) => {
-                info!("Successfully retrieved file metadata for worker {:?}", owned_worker_id.clone().worker_id);
+        let shadow = request
+            .shadow
+            .map(WorkerShadow::try_from)
+            .transpose()
+            .map_err(GolemError::invalid_request)?;
 
-                let response = GetFilesResponse {
-                    result: Some(golem::workerexecutor::v1::get_files_response::Result::Success(
-                        GetFilesSuccessResponse {
-                            files,
-                            file_content: None
-                        },
-                    )),
-                };
-                info!("response {:?}", response);
-                Ok(response)
-            },
-            Err(err) => {
-                error!("Failed to retrieve file metadata for worker {:?}: {:?}", owned_worker_id.worker_id, err);
-                Err(GolemError::unknown(format!("Failed to get files metadata: {:?}", err)))
+        match &shadow {
+            Some(shadow) if shadow.shadow_worker_id == worker_id => {
+                return Err(GolemError::invalid_request("A worker cannot be its own shadow"))
             }
+            Some(shadow) => info!(
+                shadow_worker_id = shadow.shadow_worker_id.to_string(),
+                "Enabling shadow invocations"
+            ),
+            None => info!("Disabling shadow invocations"),
         }
 
+        self.worker_service().set_shadow(&owned_worker_id, shadow).await;
+        Ok(())
     }
 
-    async fn get_files_or_directory_internal(
+    async fn set_worker_ttl_internal(
         &self,
-        request: GetFilesRequest
-    ) -> Result<GetFilesResponse, GolemError> {
-
+        request: golem::workerexecutor::v1::SetWorkerTtlRequest,
+    ) -> Result<(), GolemError> {
         let worker_id = request
             .worker_id
             .ok_or(GolemError::invalid_request("worker_id not found"))?;
         let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
-        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
-
-        // path to get the metadata from
 
-        let account_id = request
+        let account_id: AccountId = request
             .account_id
-            .ok_or(GolemError::invalid_request("account_id not found"))?;
-        let account_id: AccountId = account_id.into();
-
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into();
 
         let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
 
-        match self.services.blob_store_service().get_file_or_directory(owned_worker_id.clone(), request.path.unwrap()).await {
-            Ok(FileOrDirectoryResponse::DirectoryListing(files)) => {
-                info!("Successfully retrieved directory listing for worker {:?}", owned_worker_id.worker_id);
-                let files_response = files.into_iter().map(|(name, is_directory)|FileNode{
-                    name,
-                    r#type: if is_directory { NodeType::Directory as i32 } else{
-                        NodeType::File as i32
-                    }
-                }).collect() ;
-                // Create the response for a directory
-                let response = GetFilesResponse {
-                    result: Some(golem::workerexecutor::v1::get_files_response::Result::Success(
-                        GetFilesSuccessResponse {
-                            files: files_response,
-                            file_content: None, // No file content as it's a directory
-                        },
-                    )),
-                };
-                info!("response {:?}", response);
-                Ok(response)
-            },
-            Ok(FileOrDirectoryResponse::FileContent(file_content)) => {
-                info!("Successfully retrieved file content for worker {:?}", owned_worker_id.worker_id);
+        if self.worker_service().get(&owned_worker_id).await.is_none() {
+            return Err(GolemError::worker_not_found(worker_id));
+        }
 
-                // Create the response for a file
-                let response = GetFilesResponse {
-                    result: Some(golem::workerexecutor::v1::get_files_response::Result::Success(
-                        GetFilesSuccessResponse {
-                            files: Vec::new(), // No directory listing as it's a file
-                            file_content: Some(file_content), // Include the file content
+        self.set_ttl(
+            &owned_worker_id,
+            request.ttl_millis.map(Duration::from_millis),
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Makes the worker expire after `ttl` counted from now, or removes its expiry. Deleting the
+    /// worker is scheduled for each new expiry, and the scheduled deletions of the previous
+    /// expiries are skipped when they come due.
+    async fn set_ttl(&self, owned_worker_id: &OwnedWorkerId, ttl: Option<Duration>) {
+        match ttl {
+            Some(ttl) => {
+                let deadline = Utc::now() + ttl;
+                let expires_at = Timestamp::from(deadline.timestamp_millis() as u64);
+                info!(
+                    expires_at = expires_at.to_string(),
+                    "Setting the worker's TTL"
+                );
+
+                self.worker_service()
+                    .set_expiry(owned_worker_id, Some(expires_at))
+                    .await;
+                self.scheduler_service()
+                    .schedule(
+                        deadline,
+                        ScheduledAction::DeleteWorker {
+                            owned_worker_id: owned_worker_id.clone(),
+                            expires_at,
                         },
-                    )),
-                };
-                info!("response {:?}", response);
-                Ok(response)
-            },
-            Err(err) => {
-                error!("Failed to retrieve file or directory for worker {:?}: {:?}", owned_worker_id.worker_id, err);
-                Err(GolemError::unknown(format!("Failed to get file or directory: {:?}", err)))
+                    )
+                    .await;
+            }
+            None => {
+                info!("Removing the worker's TTL");
+                self.worker_service()
+                    .set_expiry(owned_worker_id, None)
+                    .await;
+            }
+        }
+    }
+
+    async fn set_deduplication_window_internal(
+        &self,
+        request: golem::workerexecutor::v1::SetDeduplicationWindowRequest,
+    ) -> Result<(), GolemError> {
+        let component_id: ComponentId = request
+            .component_id
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+
+        let window = request.window_millis.map(Duration::from_millis);
+        match window {
+            Some(window) => info!(window = ?window, "Limiting the deduplication window"),
+            None => info!("Removing the limit of the deduplication window"),
+        }
+
+        self.worker_service()
+            .set_deduplication_window(&component_id, window)
+            .await;
+        Ok(())
+    }
+
+    async fn get_function_stats_internal(
+        &self,
+        request: golem::workerexecutor::v1::GetFunctionStatsRequest,
+    ) -> Result<Vec<golem::worker::FunctionStats>, GolemError> {
+        let component_id: ComponentId = request
+            .component_id
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+
+        let mut functions = get_function_stats(&component_id)
+            .into_iter()
+            .map(|(function, stats)| golem::worker::FunctionStats {
+                function,
+                invocations: stats.invocations,
+                failures: stats.failures,
+                consumed_fuel: stats.consumed_fuel,
+                total_duration_millis: stats.total_duration.as_millis() as u64,
+                latency_buckets: stats.latency_buckets,
+                latency_bucket_bounds_seconds: DEFAULT_TIME_BUCKETS.to_vec(),
+            })
+            .collect::<Vec<_>>();
+        functions.sort_by(|a, b| a.function.cmp(&b.function));
+        Ok(functions)
+    }
+
+    async fn get_error_groups_internal(
+        &self,
+        request: golem::workerexecutor::v1::GetErrorGroupsRequest,
+    ) -> Result<(Option<Cursor>, Vec<ErrorGroup>), GolemError> {
+        let component_id: ComponentId = request
+            .component_id
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+
+        let account_id: AccountId = request
+            .account_id
+            .map(|t| t.into())
+            .ok_or(GolemError::invalid_request("Invalid account id"))?;
+
+        // Only failed and retrying workers have a last error
+        let filter = WorkerFilter::new_or(vec![
+            WorkerFilter::new_status(FilterComparator::Equal, WorkerStatus::Failed),
+            WorkerFilter::new_status(FilterComparator::Equal, WorkerStatus::Retrying),
+        ]);
+
+        // A single page is scanned per request, the caller merges the groups of the pages
+        let (next_cursor, workers) = self
+            .worker_enumeration_service()
+            .get(
+                &account_id,
+                &component_id,
+                Some(filter),
+                request
+                    .cursor
+                    .map(|cursor| ScanCursor {
+                        cursor: cursor.cursor,
+                        layer: cursor.layer as usize,
+                    })
+                    .unwrap_or_default(),
+                request.count,
+                false,
+            )
+            .await?;
+
+        let mut errors = Vec::new();
+        for worker in workers {
+            let last_error =
+                Ctx::get_last_error_and_retry_count(self, &worker.owned_worker_id()).await;
+            if let Some(last_error) = last_error {
+                errors.push((worker.worker_id, last_error));
+            }
+        }
+
+        Ok((
+            next_cursor.map(|cursor| Cursor {
+                layer: cursor.layer as u64,
+                cursor: cursor.cursor,
+            }),
+            ErrorGroup::group(errors),
+        ))
+    }
+
+    async fn precompile_component_internal(
+        &self,
+        request: golem::workerexecutor::v1::PrecompileComponentRequest,
+    ) -> Result<(), GolemError> {
+        let component_id: ComponentId = request
+            .component_id
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+
+        // Getting the component stores it in the component cache, downloading the compiled
+        // component or compiling it if it is not cached yet
+        self.component_service()
+            .get(&self.engine(), &component_id, request.component_version)
+            .await?;
+        Ok(())
+    }
+
+    async fn poll_events_internal(
+        &self,
+        request: golem::workerexecutor::v1::PollEventsRequest,
+    ) -> Result<Vec<golem::worker::DomainEvent>, GolemError> {
+        let component_id: ComponentId = request
+            .component_id
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+
+        let events = self
+            .domain_event_service()
+            .poll(&component_id, &request.topic, request.cursor, request.count)
+            .await?;
+        Ok(events.into_iter().map(|event| event.into()).collect())
+    }
+
+    async fn watch_workers_internal(
+        &self,
+        request: golem::workerexecutor::v1::WatchWorkersRequest,
+    ) -> Result<WorkerStatusEventStream, GolemError> {
+        let component_id: ComponentId = request
+            .component_id
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+
+        let filter: Option<WorkerFilter> = match request.filter {
+            Some(f) => Some(f.try_into().map_err(GolemError::invalid_request)?),
+            _ => None,
+        };
+
+        info!("Client started watching workers");
+        record_new_grpc_api_active_stream();
+
+        Ok(WorkerStatusEventStream::new(
+            self.events().subscribe(),
+            component_id,
+            filter,
+            &self.config().limits,
+        ))
+    }
+
+    async fn get_files_internal(
+        &self,
+        request: GetFilesRequest,
+    ) -> Result<GetFilesResponse, GolemError> {
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        // path to get the metadata from
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+
+        match self.services.blob_store_service().get_files_metadata(owned_worker_id.clone()).await {
+            Ok(files) => {
+                info!("Successfully retrieved file metadata for worker {:?}", owned_worker_id.clone().worker_id);
+
+                let response = GetFilesResponse {
+                    result: Some(golem::workerexecutor::v1::get_files_response::Result::Success(
+                        GetFilesSuccessResponse {
+                            files,
+                            file_content: None
+                        },
+                    )),
+                };
+                info!("response {:?}", response);
+                Ok(response)
+            },
+            Err(err) => {
+                error!("Failed to retrieve file metadata for worker {:?}: {:?}", owned_worker_id.worker_id, err);
+                Err(GolemError::unknown(format!("Failed to get files metadata: {:?}", err)))
+            }
+        }
+
+    }
+
+    async fn get_files_or_directory_internal(
+        &self,
+        request: GetFilesRequest
+    ) -> Result<GetFilesResponse, GolemError> {
+
+        let worker_id = request
+            .worker_id
+            .ok_or(GolemError::invalid_request("worker_id not found"))?;
+        let worker_id: WorkerId = worker_id.try_into().map_err(GolemError::invalid_request)?;
+        self.ensure_worker_belongs_to_this_executor(&worker_id)?;
+
+        // path to get the metadata from
+
+        let account_id = request
+            .account_id
+            .ok_or(GolemError::invalid_request("account_id not found"))?;
+        let account_id: AccountId = account_id.into();
+
+
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        match self.services.blob_store_service().get_file_or_directory(owned_worker_id.clone(), request.path.unwrap()).await {
+            Ok(FileOrDirectoryResponse::DirectoryListing(files)) => {
+                info!("Successfully retrieved directory listing for worker {:?}", owned_worker_id.worker_id);
+                let files_response = files.into_iter().map(|(name, is_directory)|FileNode{
+                    name,
+                    r#type: if is_directory { NodeType::Directory as i32 } else{
+                        NodeType::File as i32
+                    }
+                }).collect() ;
+                // Create the response for a directory
+                let response = GetFilesResponse {
+                    result: Some(golem::workerexecutor::v1::get_files_response::Result::Success(
+                        GetFilesSuccessResponse {
+                            files: files_response,
+                            file_content: None, // No file content as it's a directory
+                        },
+                    )),
+                };
+                info!("response {:?}", response);
+                Ok(response)
+            },
+            Ok(FileOrDirectoryResponse::FileContent(file_content)) => {
+                info!("Successfully retrieved file content for worker {:?}", owned_worker_id.worker_id);
+
+                // Create the response for a file
+                let response = GetFilesResponse {
+                    result: Some(golem::workerexecutor::v1::get_files_response::Result::Success(
+                        GetFilesSuccessResponse {
+                            files: Vec::new(), // No directory listing as it's a file
+                            file_content: Some(file_content), // Include the file content
+                        },
+                    )),
+                };
+                info!("response {:?}", response);
+                Ok(response)
+            },
+            Err(err) => {
+                error!("Failed to retrieve file or directory for worker {:?}: {:?}", owned_worker_id.worker_id, err);
+                Err(GolemError::unknown(format!("Failed to get file or directory: {:?}", err)))
             }
         }
 
@@ -1225,10 +2359,25 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
     }
 
 
+    async fn get_update_divergences(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        latest_status: &WorkerStatusRecord,
+    ) -> Vec<UpdateDivergence> {
+        if latest_status.failed_updates.is_empty() {
+            Vec::new()
+        } else {
+            self.worker_service()
+                .get_update_divergences(owned_worker_id)
+                .await
+        }
+    }
+
     fn create_proto_metadata(
         metadata: WorkerMetadata,
         latest_status: WorkerStatusRecord,
         last_error_and_retry_count: Option<LastError>,
+        update_divergences: &[UpdateDivergence],
     ) -> golem::worker::WorkerMetadata {
         let mut updates = Vec::new();
 
@@ -1266,12 +2415,17 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             });
         }
         for failed_update in &latest_status.failed_updates {
+            let divergence = update_divergences.iter().find(|divergence| {
+                divergence.timestamp == failed_update.timestamp
+                    && divergence.target_version == failed_update.target_version
+            });
             updates.push(golem::worker::UpdateRecord {
                 timestamp: Some(failed_update.timestamp.into()),
                 target_version: failed_update.target_version,
                 update: Some(golem::worker::update_record::Update::Failed(
                     golem::worker::FailedUpdate {
                         details: failed_update.details.clone(),
+                        divergence: divergence.cloned().map(|divergence| divergence.into()),
                     },
                 )),
             });
@@ -1295,7 +2449,14 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         golem::worker::WorkerMetadata {
             worker_id: Some(metadata.worker_id.into()),
             args: metadata.args.clone(),
-            env: HashMap::from_iter(metadata.env.iter().cloned()),
+            env: HashMap::from_iter(
+                latest_status
+                    .overridden_env
+                    .as_ref()
+                    .unwrap_or(&metadata.env)
+                    .iter()
+                    .cloned(),
+            ),
             account_id: Some(metadata.account_id.into()),
             component_version: latest_status.component_version,
             status: Into::<golem::worker::WorkerStatus>::into(latest_status.status).into(),
@@ -1308,10 +2469,18 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             updates,
             created_at: Some(metadata.created_at.into()),
             last_error: last_error_and_retry_count
+                .as_ref()
                 .map(|last_error| last_error.error.to_string(&last_error.stderr)),
+            last_error_kind: last_error_and_retry_count.as_ref().map(|last_error| {
+                Into::<golem::worker::WorkerErrorKind>::into(last_error.error.kind()).into()
+            }),
+            last_error_fingerprint: last_error_and_retry_count
+                .map(|last_error| last_error.error.fingerprint()),
             component_size: metadata.last_known_status.component_size,
             total_linear_memory_size: metadata.last_known_status.total_linear_memory_size,
             owned_resources,
+            expires_at: metadata.expires_at.map(|t| t.into()),
+            disk_usage: latest_status.disk_usage,
         }
     }
 }
@@ -1344,7 +2513,7 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         );
 
         match self
-            .create_worker_internal(request)
+            .create_worker_internal(request, false)
             .instrument(record.span.clone())
             .await
         {
@@ -1372,6 +2541,48 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         }
     }
 
+    async fn get_or_create_worker(
+        &self,
+        request: Request<golem::workerexecutor::v1::CreateWorkerRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::GetOrCreateWorkerResponse>, Status> {
+        let request = request.into_inner();
+
+        let record = recorded_grpc_api_request!(
+            "get_or_create_worker",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            component_version = request.component_version,
+            account_id = proto_account_id_string(&request.account_id),
+        );
+
+        match self
+            .create_worker_internal(request, true)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(success) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::GetOrCreateWorkerResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_or_create_worker_response::Result::Success(
+                            success,
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::GetOrCreateWorkerResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::get_or_create_worker_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
     async fn invoke_and_await_worker(
         &self,
         request: Request<InvokeAndAwaitWorkerRequest>,
@@ -1423,7 +2634,7 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             account_id = proto_account_id_string(&request.account_id),
         );
 
-        match self.invoke_and_await_worker_internal_typed(&request).instrument(record.span.clone()).await {
+        match self.invoke_and_await_worker_internal_limited(&request).instrument(record.span.clone()).await {
             Ok(type_annotated_value) => {
                 let result = golem::workerexecutor::v1::InvokeAndAwaitWorkerSuccessTyped {
                     output: Some(golem_wasm_rpc::protobuf::TypeAnnotatedValue {
@@ -1454,20 +2665,80 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         }
     }
 
-    async fn invoke_worker(
+    type InvokeAndAwaitWorkerStreamingStream = InvocationResultStream;
+
+    async fn invoke_and_await_worker_streaming(
         &self,
-        request: Request<golem::workerexecutor::v1::InvokeWorkerRequest>,
-    ) -> Result<Response<golem::workerexecutor::v1::InvokeWorkerResponse>, Status> {
+        request: Request<InvokeAndAwaitWorkerRequest>,
+    ) -> ResponseResult<Self::InvokeAndAwaitWorkerStreamingStream> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "invoke_worker",
+            "invoke_and_await_worker_streaming",
             worker_id = proto_target_worker_id_string(&request.worker_id),
-            function = request.name,
-            account_id = proto_account_id_string(&request.account_id)
+            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
+            account_id = proto_account_id_string(&request.account_id),
         );
 
-        match self
-            .invoke_worker_internal(&request)
+        let result = self
+            .invoke_and_await_worker_internal_typed(&request)
+            .instrument(record.span.clone())
+            .await;
+        let stream: InvocationResultStream = match result {
+            Ok(type_annotated_value) => {
+                let (list_elements, chunks) = invocation_result_chunks(type_annotated_value);
+                let header = InvokeAndAwaitWorkerStreamingResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::invoke_and_await_worker_streaming_response::Result::Header(
+                            golem::workerexecutor::v1::InvokeAndAwaitWorkerStreamingHeader {
+                                list_elements,
+                            },
+                        ),
+                    ),
+                };
+                // The response messages are only built when the client is ready to receive them
+                let chunks = chunks.map(|chunk| InvokeAndAwaitWorkerStreamingResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::invoke_and_await_worker_streaming_response::Result::Chunk(
+                            golem_wasm_rpc::protobuf::TypeAnnotatedValue {
+                                type_annotated_value: Some(chunk),
+                            },
+                        ),
+                    ),
+                });
+                record.succeed(Box::pin(futures_util::stream::iter(
+                    std::iter::once(header).chain(chunks).map(Ok::<_, Status>),
+                )))
+            }
+            Err(err) => record.fail(
+                Box::pin(futures_util::stream::once(futures_util::future::ready(Ok::<_, Status>(
+                    InvokeAndAwaitWorkerStreamingResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::invoke_and_await_worker_streaming_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )))),
+                &err,
+            ),
+        };
+        Ok(Response::new(stream))
+    }
+
+    async fn invoke_worker(
+        &self,
+        request: Request<golem::workerexecutor::v1::InvokeWorkerRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::InvokeWorkerResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "invoke_worker",
+            worker_id = proto_target_worker_id_string(&request.worker_id),
+            function = request.name,
+            account_id = proto_account_id_string(&request.account_id)
+        );
+
+        match self
+            .invoke_worker_internal(&request)
             .instrument(record.span.clone())
             .await
         {
@@ -1528,11 +2799,11 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             .instrument(record.span.clone())
             .await
         {
-            Ok(_) => record.succeed(Ok(Response::new(
+            Ok(removed) => record.succeed(Ok(Response::new(
                 golem::workerexecutor::v1::DeleteWorkerResponse {
                     result: Some(
                         golem::workerexecutor::v1::delete_worker_response::Result::Success(
-                            golem::common::Empty {},
+                            golem::workerexecutor::v1::DeleteWorkerSuccessResponse { removed },
                         ),
                     ),
                 },
@@ -1630,6 +2901,167 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         }
     }
 
+    async fn abort_running_invocation(
+        &self,
+        request: Request<golem::workerexecutor::v1::AbortRunningInvocationRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::AbortRunningInvocationResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "abort_running_invocation",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
+        );
+
+        match self
+            .abort_running_invocation_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(aborted) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::AbortRunningInvocationResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::abort_running_invocation_response::Result::Success(
+                            golem::workerexecutor::v1::AbortRunningInvocationSuccessResponse {
+                                aborted,
+                            },
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::AbortRunningInvocationResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::abort_running_invocation_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn cancel_invocation(
+        &self,
+        request: Request<golem::workerexecutor::v1::CancelInvocationRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::CancelInvocationResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "cancel_invocation",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
+        );
+
+        match self
+            .cancel_invocation_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(canceled) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::CancelInvocationResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::cancel_invocation_response::Result::Success(
+                            golem::workerexecutor::v1::CancelInvocationSuccessResponse { canceled },
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::CancelInvocationResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::cancel_invocation_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn set_worker_profiling(
+        &self,
+        request: Request<golem::workerexecutor::v1::SetWorkerProfilingRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::SetWorkerProfilingResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "set_worker_profiling",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            enabled = request.enabled,
+        );
+
+        match self
+            .set_worker_profiling_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::SetWorkerProfilingResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::set_worker_profiling_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::SetWorkerProfilingResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::set_worker_profiling_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_worker_profile(
+        &self,
+        request: Request<golem::workerexecutor::v1::GetWorkerProfileRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::GetWorkerProfileResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_worker_profile",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        match self
+            .get_worker_profile_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(profile) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::GetWorkerProfileResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_worker_profile_response::Result::Success(
+                            golem::workerexecutor::v1::GetWorkerProfileSuccessResponse { profile },
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::GetWorkerProfileResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::get_worker_profile_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
     async fn revoke_shards(
         &self,
         request: Request<golem::workerexecutor::v1::RevokeShardsRequest>,
@@ -1750,6 +3182,45 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
             ),
         }
     }
+    async fn get_worker_metadata_many(
+        &self,
+        request: Request<golem::workerexecutor::v1::GetWorkerMetadataManyRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::GetWorkerMetadataManyResponse>, Status> {
+        let request = request.into_inner();
+
+        let record = recorded_grpc_api_request!(
+            "get_worker_metadata_many",
+            worker_count = request.worker_ids.len()
+        );
+
+        let result = self
+            .get_worker_metadata_many_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(result) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::GetWorkerMetadataManyResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_worker_metadata_many_response::Result::Success(
+                            result,
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::GetWorkerMetadataManyResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::get_worker_metadata_many_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
 
     async fn resume_worker(
         &self,
@@ -1871,6 +3342,41 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         }
     }
 
+    async fn search_workers(
+        &self,
+        request: Request<SearchWorkersRequest>,
+    ) -> Result<Response<SearchWorkersResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "search_workers",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        let result = self
+            .search_workers_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(worker_ids) => record.succeed(Ok(Response::new(SearchWorkersResponse {
+                result: Some(
+                    golem::workerexecutor::v1::search_workers_response::Result::Success(
+                        golem::workerexecutor::v1::SearchWorkersSuccessResponse { worker_ids },
+                    ),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(SearchWorkersResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::search_workers_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
+
     async fn update_worker(
         &self,
         request: Request<UpdateWorkerRequest>,
@@ -1907,52 +3413,247 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         }
     }
 
-    async fn get_oplog(
+    async fn update_worker_env(
         &self,
-        request: Request<GetOplogRequest>,
-    ) -> Result<Response<GetOplogResponse>, Status> {
+        request: Request<golem::workerexecutor::v1::UpdateWorkerEnvRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::UpdateWorkerEnvResponse>, Status> {
         let request = request.into_inner();
         let record = recorded_grpc_api_request!(
-            "get_oplog",
+            "update_worker_env",
             worker_id = proto_worker_id_string(&request.worker_id),
         );
 
-        let result = self
-            .get_oplog_internal(request)
+        match self
+            .update_worker_env_internal(request)
             .instrument(record.span.clone())
-            .await;
-        match result {
-            Ok(response) => record.succeed(Ok(Response::new(response))),
-            Err(err) => record.fail(
-                Ok(Response::new(GetOplogResponse {
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::UpdateWorkerEnvResponse {
                     result: Some(
-                        golem::workerexecutor::v1::get_oplog_response::Result::Failure(
-                            err.clone().into(),
+                        golem::workerexecutor::v1::update_worker_env_response::Result::Success(
+                            golem::common::Empty {},
                         ),
                     ),
-                })),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::UpdateWorkerEnvResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::update_worker_env_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
                 &err,
             ),
         }
     }
 
-    async fn get_files(
+    async fn redact_oplog(
         &self,
-        request: Request<GetFilesRequest>,
-    ) -> Result<Response<GetFilesResponse>, Status> {
+        request: Request<golem::workerexecutor::v1::RedactOplogRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::RedactOplogResponse>, Status> {
         let request = request.into_inner();
-
-        // Ensure `worker_id` is provided
-        let worker_id = request.clone().worker_id.ok_or_else(|| {
-            error!("get_files: worker_id not found in request");
-            Status::invalid_argument("worker_id is required")
-        })?;
-
         let record = recorded_grpc_api_request!(
-        "get_files",
-        worker_id = proto_worker_id_string(&Some(worker_id.clone())),
-    );
-
+            "redact_oplog",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        match self
+            .redact_oplog_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(oplog_idx) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::RedactOplogResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::redact_oplog_response::Result::Success(
+                            golem::workerexecutor::v1::RedactOplogSuccessResponse {
+                                oplog_index: oplog_idx.into(),
+                            },
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::RedactOplogResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::redact_oplog_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_oplog(
+        &self,
+        request: Request<GetOplogRequest>,
+    ) -> Result<Response<GetOplogResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_oplog",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        let result = self
+            .get_oplog_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(response) => record.succeed(Ok(Response::new(response))),
+            Err(err) => record.fail(
+                Ok(Response::new(GetOplogResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_oplog_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_raw_oplog(
+        &self,
+        request: Request<GetRawOplogRequest>,
+    ) -> Result<Response<GetRawOplogResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_raw_oplog",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        let result = self
+            .get_raw_oplog_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(response) => record.succeed(Ok(Response::new(response))),
+            Err(err) => record.fail(
+                Ok(Response::new(GetRawOplogResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_raw_oplog_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
+
+    type GetInvocationResultBlobStream = InvocationResultBlobStream;
+
+    async fn get_invocation_result_blob(
+        &self,
+        request: Request<GetInvocationResultBlobRequest>,
+    ) -> ResponseResult<Self::GetInvocationResultBlobStream> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_invocation_result_blob",
+            blob_key = request.blob_key.clone(),
+            account_id = proto_account_id_string(&request.account_id),
+        );
+
+        let result = self
+            .get_invocation_result_blob_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        let stream: InvocationResultBlobStream = match result {
+            Ok(payload) => {
+                let chunks = payload
+                    .chunks(RESULT_BLOB_CHUNK_SIZE)
+                    .map(|chunk| {
+                        Ok::<_, Status>(GetInvocationResultBlobResponse {
+                            result: Some(
+                                golem::workerexecutor::v1::get_invocation_result_blob_response::Result::Chunk(
+                                    chunk.to_vec(),
+                                ),
+                            ),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                record.succeed(Box::pin(futures_util::stream::iter(chunks)))
+            }
+            Err(err) => record.fail(
+                Box::pin(futures_util::stream::once(futures_util::future::ready(Ok::<_, Status>(
+                    GetInvocationResultBlobResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::get_invocation_result_blob_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )))),
+                &err,
+            ),
+        };
+        Ok(Response::new(stream))
+    }
+
+    async fn put_invocation_parameter(
+        &self,
+        request: Request<
+            tonic::Streaming<golem::workerexecutor::v1::PutInvocationParameterRequest>,
+        >,
+    ) -> Result<Response<golem::workerexecutor::v1::PutInvocationParameterResponse>, Status> {
+        let record = recorded_grpc_api_request!("put_invocation_parameter",);
+
+        match self
+            .put_invocation_parameter_internal(request.into_inner())
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::PutInvocationParameterResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::put_invocation_parameter_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::PutInvocationParameterResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::put_invocation_parameter_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_files(
+        &self,
+        request: Request<GetFilesRequest>,
+    ) -> Result<Response<GetFilesResponse>, Status> {
+        let request = request.into_inner();
+
+        // Ensure `worker_id` is provided
+        let worker_id = request.clone().worker_id.ok_or_else(|| {
+            error!("get_files: worker_id not found in request");
+            Status::invalid_argument("worker_id is required")
+        })?;
+
+        let record = recorded_grpc_api_request!(
+        "get_files",
+        worker_id = proto_worker_id_string(&Some(worker_id.clone())),
+    );
+
         // Call the internal get_files function
         let result = self.get_files_internal(request).instrument(record.span.clone()).await;
 
@@ -1993,24 +3694,387 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
     );
         let result = self.get_files_or_directory_internal(request).instrument(record.span.clone()).await;
 
-        match result {
-            Ok(fileResponse) => {
-                record.succeed(Ok(Response::new(fileResponse)))
-            }
-            Err(err) => {
-                record.fail(
-                    Ok(Response::new(GetFilesResponse {
+        match result {
+            Ok(fileResponse) => {
+                record.succeed(Ok(Response::new(fileResponse)))
+            }
+            Err(err) => {
+                record.fail(
+                    Ok(Response::new(GetFilesResponse {
+                        result: Some(
+                            Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    })),
+                    &err,
+                )
+            }
+        }
+
+    }
+
+    async fn set_worker_shadow(
+        &self,
+        request: Request<golem::workerexecutor::v1::SetWorkerShadowRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::SetWorkerShadowResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "set_worker_shadow",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        match self
+            .set_worker_shadow_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::SetWorkerShadowResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::set_worker_shadow_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::SetWorkerShadowResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::set_worker_shadow_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn set_worker_ttl(
+        &self,
+        request: Request<golem::workerexecutor::v1::SetWorkerTtlRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::SetWorkerTtlResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "set_worker_ttl",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        match self
+            .set_worker_ttl_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::SetWorkerTtlResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::set_worker_ttl_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::SetWorkerTtlResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::set_worker_ttl_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn set_deduplication_window(
+        &self,
+        request: Request<golem::workerexecutor::v1::SetDeduplicationWindowRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::SetDeduplicationWindowResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "set_deduplication_window",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        match self
+            .set_deduplication_window_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::SetDeduplicationWindowResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::set_deduplication_window_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::SetDeduplicationWindowResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::set_deduplication_window_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_error_groups(
+        &self,
+        request: Request<golem::workerexecutor::v1::GetErrorGroupsRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::GetErrorGroupsResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_error_groups",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        match self
+            .get_error_groups_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok((cursor, groups)) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::GetErrorGroupsResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_error_groups_response::Result::Success(
+                            golem::workerexecutor::v1::GetErrorGroupsSuccessResponse {
+                                groups: groups
+                                    .into_iter()
+                                    .map(|group| golem::worker::ErrorGroup {
+                                        fingerprint: group.fingerprint,
+                                        kind: Into::<golem::worker::WorkerErrorKind>::into(
+                                            group.kind,
+                                        )
+                                        .into(),
+                                        example: group.example,
+                                        worker_count: group.worker_count,
+                                        workers: group
+                                            .workers
+                                            .into_iter()
+                                            .map(|worker_id| worker_id.into())
+                                            .collect(),
+                                    })
+                                    .collect(),
+                                cursor,
+                            },
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::GetErrorGroupsResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::get_error_groups_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_function_stats(
+        &self,
+        request: Request<golem::workerexecutor::v1::GetFunctionStatsRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::GetFunctionStatsResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_function_stats",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        match self
+            .get_function_stats_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(functions) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::GetFunctionStatsResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_function_stats_response::Result::Success(
+                            golem::workerexecutor::v1::GetFunctionStatsSuccessResponse {
+                                functions,
+                            },
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::GetFunctionStatsResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::get_function_stats_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn precompile_component(
+        &self,
+        request: Request<golem::workerexecutor::v1::PrecompileComponentRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::PrecompileComponentResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "precompile_component",
+            component_id = proto_component_id_string(&request.component_id),
+            component_version = request.component_version,
+        );
+
+        match self
+            .precompile_component_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(_) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::PrecompileComponentResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::precompile_component_response::Result::Success(
+                            golem::common::Empty {},
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::PrecompileComponentResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::precompile_component_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    async fn poll_events(
+        &self,
+        request: Request<golem::workerexecutor::v1::PollEventsRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::PollEventsResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "poll_events",
+            component_id = proto_component_id_string(&request.component_id),
+            topic = request.topic,
+            cursor = request.cursor,
+        );
+
+        match self
+            .poll_events_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(events) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::PollEventsResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::poll_events_response::Result::Success(
+                            golem::workerexecutor::v1::PollEventsSuccessResponse { events },
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::PollEventsResponse {
+                        result: Some(
+                            golem::workerexecutor::v1::poll_events_response::Result::Failure(
+                                err.clone().into(),
+                            ),
+                        ),
+                    },
+                )),
+                &err,
+            ),
+        }
+    }
+
+    type WatchWorkersStream = WorkerStatusEventStream;
+
+    async fn watch_workers(
+        &self,
+        request: Request<golem::workerexecutor::v1::WatchWorkersRequest>,
+    ) -> ResponseResult<Self::WatchWorkersStream> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "watch_workers",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        match self
+            .watch_workers_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(stream) => record.succeed(Ok(Response::new(stream))),
+            Err(err) => record.fail(Err(err.clone().into()), &err),
+        }
+    }
+
+    async fn schedule_invocation(
+        &self,
+        request: Request<golem::workerexecutor::v1::ScheduleInvocationRequest>,
+    ) -> Result<Response<golem::workerexecutor::v1::ScheduleInvocationResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "schedule_invocation",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            function = request.name,
+            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
+            account_id = proto_account_id_string(&request.account_id)
+        );
+
+        match self
+            .schedule_invocation_internal(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(idempotency_key) => record.succeed(Ok(Response::new(
+                golem::workerexecutor::v1::ScheduleInvocationResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::schedule_invocation_response::Result::Success(
+                            golem::workerexecutor::v1::ScheduleInvocationSuccessResponse {
+                                idempotency_key: Some(idempotency_key.into()),
+                            },
+                        ),
+                    ),
+                },
+            ))),
+            Err(err) => record.fail(
+                Ok(Response::new(
+                    golem::workerexecutor::v1::ScheduleInvocationResponse {
                         result: Some(
-                            Failure(
+                            golem::workerexecutor::v1::schedule_invocation_response::Result::Failure(
                                 err.clone().into(),
                             ),
                         ),
-                    })),
-                    &err,
-                )
-            }
+                    },
+                )),
+                &err,
+            ),
         }
-
     }
 }
 
@@ -2018,12 +4082,18 @@ trait GrpcInvokeRequest {
     fn account_id(&self) -> Result<AccountId, GolemError>;
     fn account_limits(&self) -> Option<GrpcResourceLimits>;
     fn input(&self) -> Vec<Val>;
+    fn blob_input(&self) -> Vec<BlobParameter>;
     fn worker_id(&self) -> Result<TargetWorkerId, GolemError>;
     fn idempotency_key(&self) -> Result<Option<IdempotencyKey>, GolemError>;
     fn name(&self) -> String;
     fn args(&self) -> Option<Vec<String>>;
     fn env(&self) -> Option<Vec<(String, String)>>;
     fn parent(&self) -> Option<WorkerId>;
+    fn caller(&self) -> Option<CallerIdentity>;
+    /// How long to wait for the result before the invocation gets interrupted
+    fn timeout(&self) -> Option<Duration>;
+    /// Whether the result should be passed through the component's `__map_result` export
+    fn map_result(&self) -> bool;
 }
 
 impl GrpcInvokeRequest for golem::workerexecutor::v1::InvokeWorkerRequest {
@@ -2043,6 +4113,10 @@ impl GrpcInvokeRequest for golem::workerexecutor::v1::InvokeWorkerRequest {
         self.input.clone()
     }
 
+    fn blob_input(&self) -> Vec<BlobParameter> {
+        self.blob_input.clone()
+    }
+
     fn worker_id(&self) -> Result<common_model::TargetWorkerId, GolemError> {
         self.worker_id
             .clone()
@@ -2076,6 +4150,24 @@ impl GrpcInvokeRequest for golem::workerexecutor::v1::InvokeWorkerRequest {
                 .and_then(|worker_id| worker_id.clone().try_into().ok())
         })
     }
+
+    fn caller(&self) -> Option<CallerIdentity> {
+        self.context
+            .as_ref()
+            .and_then(|ctx| ctx.caller.clone())
+            .map(|caller| caller.into())
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.context
+            .as_ref()
+            .and_then(|ctx| ctx.timeout_millis)
+            .map(Duration::from_millis)
+    }
+
+    fn map_result(&self) -> bool {
+        self.context.as_ref().is_some_and(|ctx| ctx.map_result)
+    }
 }
 
 impl GrpcInvokeRequest for golem::workerexecutor::v1::InvokeAndAwaitWorkerRequest {
@@ -2095,6 +4187,10 @@ impl GrpcInvokeRequest for golem::workerexecutor::v1::InvokeAndAwaitWorkerReques
         self.input.clone()
     }
 
+    fn blob_input(&self) -> Vec<BlobParameter> {
+        self.blob_input.clone()
+    }
+
     fn worker_id(&self) -> Result<common_model::TargetWorkerId, GolemError> {
         self.worker_id
             .clone()
@@ -2128,6 +4224,88 @@ impl GrpcInvokeRequest for golem::workerexecutor::v1::InvokeAndAwaitWorkerReques
                 .and_then(|worker_id| worker_id.clone().try_into().ok())
         })
     }
+
+    fn caller(&self) -> Option<CallerIdentity> {
+        self.context
+            .as_ref()
+            .and_then(|ctx| ctx.caller.clone())
+            .map(|caller| caller.into())
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.context
+            .as_ref()
+            .and_then(|ctx| ctx.timeout_millis)
+            .map(Duration::from_millis)
+    }
+
+    fn map_result(&self) -> bool {
+        self.context.as_ref().is_some_and(|ctx| ctx.map_result)
+    }
+}
+
+impl GrpcInvokeRequest for golem::workerexecutor::v1::ScheduleInvocationRequest {
+    fn account_id(&self) -> Result<AccountId, GolemError> {
+        Ok(self
+            .account_id
+            .clone()
+            .ok_or(GolemError::invalid_request("account_id not found"))?
+            .into())
+    }
+
+    fn account_limits(&self) -> Option<GrpcResourceLimits> {
+        self.account_limits.clone()
+    }
+
+    fn input(&self) -> Vec<Val> {
+        self.input.clone()
+    }
+
+    fn blob_input(&self) -> Vec<BlobParameter> {
+        vec![]
+    }
+
+    fn worker_id(&self) -> Result<common_model::TargetWorkerId, GolemError> {
+        let worker_id: WorkerId = self
+            .worker_id
+            .clone()
+            .ok_or(GolemError::invalid_request("worker_id not found"))?
+            .try_into()
+            .map_err(GolemError::invalid_request)?;
+        Ok(worker_id.into_target_worker_id())
+    }
+
+    fn idempotency_key(&self) -> Result<Option<IdempotencyKey>, GolemError> {
+        Ok(self.idempotency_key.clone().map(IdempotencyKey::from))
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn args(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    fn env(&self) -> Option<Vec<(String, String)>> {
+        None
+    }
+
+    fn parent(&self) -> Option<WorkerId> {
+        None
+    }
+
+    fn caller(&self) -> Option<CallerIdentity> {
+        None
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    fn map_result(&self) -> bool {
+        false
+    }
 }
 
 pub trait UriBackConversion {
@@ -2140,6 +4318,99 @@ impl UriBackConversion for http::Uri {
     }
 }
 
+/// Reads at most `count` entries of a worker's oplog starting at `first_index_in_chunk`, encoded
+/// as JSON the way they are stored
+async fn read_raw_oplog(
+    oplog_service: Arc<dyn OplogService + Send + Sync>,
+    owned_worker_id: &OwnedWorkerId,
+    first_index_in_chunk: OplogIndex,
+    count: u64,
+) -> Result<golem::workerexecutor::v1::GetRawOplogSuccessResponse, GolemError> {
+    if !oplog_service.exists(owned_worker_id).await {
+        return Err(GolemError::WorkerNotFound {
+            worker_id: owned_worker_id.worker_id(),
+        });
+    }
+
+    let last_index = oplog_service.get_last_index(owned_worker_id).await;
+    let entries = if first_index_in_chunk > last_index {
+        Vec::new()
+    } else {
+        let count = min(count, MAX_RAW_OPLOG_ENTRIES);
+        oplog_service
+            .read(owned_worker_id, first_index_in_chunk, count)
+            .await
+            .into_values()
+            .map(|entry| serde_json::to_string(&entry))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| GolemError::unknown(err.to_string()))?
+    };
+
+    Ok(golem::workerexecutor::v1::GetRawOplogSuccessResponse {
+        entries,
+        first_index_in_chunk: first_index_in_chunk.into(),
+        last_index: last_index.into(),
+    })
+}
+
+/// The key an oversized invocation result is stored with, derived from the invocation's
+/// idempotency key
+fn invocation_result_blob_key(
+    worker_id: &TargetWorkerId,
+    idempotency_key: &IdempotencyKey,
+) -> String {
+    let name = format!("{worker_id}/{idempotency_key}");
+    format!(
+        "{}/{}",
+        worker_id.component_id,
+        Uuid::new_v5(&Uuid::NAMESPACE_OID, name.as_bytes())
+    )
+}
+
+/// Splits the result of an invocation into the chunks returned by the streaming invocation,
+/// returning whether they are the elements of a list. The elements of a single list result are
+/// returned one by one, so results larger than the maximum gRPC message size can be returned as
+/// well.
+fn invocation_result_chunks(
+    result: TypeAnnotatedValue,
+) -> (bool, Box<dyn Iterator<Item = TypeAnnotatedValue> + Send>) {
+    let is_list =
+        |value: Option<&TypeAnnotatedValue>| matches!(value, Some(TypeAnnotatedValue::List(_)));
+    let single_result = match result {
+        TypeAnnotatedValue::Tuple(TypedTuple { mut value, .. })
+            if value.len() == 1 && is_list(value[0].type_annotated_value.as_ref()) =>
+        {
+            value.remove(0).type_annotated_value
+        }
+        TypeAnnotatedValue::Record(TypedRecord { mut value, .. })
+            if value.len() == 1
+                && is_list(
+                    value[0]
+                        .value
+                        .as_ref()
+                        .and_then(|value| value.type_annotated_value.as_ref()),
+                ) =>
+        {
+            value
+                .remove(0)
+                .value
+                .and_then(|value| value.type_annotated_value)
+        }
+        other => Some(other),
+    };
+    match single_result {
+        Some(TypeAnnotatedValue::List(TypedList { values, .. })) => (
+            true,
+            Box::new(
+                values
+                    .into_iter()
+                    .filter_map(|value| value.type_annotated_value),
+            ),
+        ),
+        other => (false, Box::new(other.into_iter())),
+    }
+}
+
 pub fn authorised_grpc_request<T>(request: T, access_token: &Uuid) -> Request<T> {
     let mut req = Request::new(request);
     req.metadata_mut().insert(
@@ -2149,14 +4420,104 @@ pub fn authorised_grpc_request<T>(request: T, access_token: &Uuid) -> Request<T>
     req
 }
 
+/// The status changes of the workers of a component running on this executor, matching an
+/// optional filter
+pub struct WorkerStatusEventStream {
+    inner: BroadcastStream<Event>,
+    component_id: ComponentId,
+    filter: Option<WorkerFilter>,
+    /// The stream gets closed when reaching this deadline, the same way as the worker connections
+    deadline: Pin<Box<tokio::time::Sleep>>,
+}
+
+impl WorkerStatusEventStream {
+    pub fn new(
+        subscription: EventsSubscription,
+        component_id: ComponentId,
+        filter: Option<WorkerFilter>,
+        limits: &Limits,
+    ) -> Self {
+        Self {
+            inner: subscription.into_stream(),
+            component_id,
+            filter,
+            deadline: Box::pin(tokio::time::sleep(limits.max_stream_duration)),
+        }
+    }
+
+    fn matches(&self, metadata: &WorkerMetadata) -> bool {
+        metadata.worker_id.component_id == self.component_id
+            && self
+                .filter
+                .as_ref()
+                .map_or(true, |filter| filter.matches(metadata))
+    }
+}
+
+impl Drop for WorkerStatusEventStream {
+    fn drop(&mut self) {
+        info!("Client stopped watching workers");
+        record_closed_grpc_api_active_stream();
+    }
+}
+
+impl Stream for WorkerStatusEventStream {
+    type Item = Result<golem::worker::WorkerStatusEvent, Status>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.deadline.as_mut().poll(cx).is_ready() {
+            record_event_stream_closed("max_duration");
+            return Poll::Ready(None);
+        }
+        loop {
+            match Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Ready(Some(Ok(Event::WorkerStatusChanged { event, metadata }))) => {
+                    if this.matches(&metadata) {
+                        return Poll::Ready(Some(Ok(event.into())));
+                    }
+                }
+                Poll::Ready(Some(Ok(_))) => {}
+                Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(n)))) => {
+                    return Poll::Ready(Some(Err(Status::data_loss(format!(
+                        "Lagged by {} events",
+                        n
+                    )))))
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
 pub struct WorkerEventStream {
     inner: Pin<Box<dyn Stream<Item = Result<WorkerEvent, BroadcastStreamRecvError>> + Send>>,
+    /// The stream gets closed when reaching this deadline, so long-running connections are
+    /// drained and their clients can reconnect, possibly to another executor
+    deadline: Pin<Box<tokio::time::Sleep>>,
 }
 
 impl WorkerEventStream {
-    pub fn new(receiver: WorkerEventReceiver) -> Self {
+    /// Streams the events of `receiver`, starting with the `replayed` log events instead of the
+    /// recent ones if the client asked for the worker's past logs
+    pub fn new(
+        receiver: WorkerEventReceiver,
+        replayed: Option<BoxStream<'static, WorkerEvent>>,
+        limits: &Limits,
+    ) -> Self {
+        let max_buffered_events = limits.max_stream_buffered_events;
+        let policy = limits.slow_stream_consumer_policy;
         WorkerEventStream {
-            inner: Box::pin(receiver.to_stream()),
+            inner: match replayed {
+                Some(replayed) => Box::pin(receiver.to_limited_stream_after_replay(
+                    replayed,
+                    max_buffered_events,
+                    policy,
+                )),
+                None => Box::pin(receiver.to_limited_stream(max_buffered_events, policy)),
+            },
+            deadline: Box::pin(tokio::time::sleep(limits.max_stream_duration)),
         }
     }
 }
@@ -2164,6 +4525,7 @@ impl WorkerEventStream {
 impl Drop for WorkerEventStream {
     fn drop(&mut self) {
         info!("Client disconnected");
+        record_closed_grpc_api_active_stream();
     }
 }
 
@@ -2171,7 +4533,12 @@ impl Stream for WorkerEventStream {
     type Item = Result<golem::worker::LogEvent, Status>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let WorkerEventStream { inner } = self.get_mut();
+        let WorkerEventStream { inner, deadline } = self.get_mut();
+        if deadline.as_mut().poll(cx).is_ready() {
+            info!("Closing worker connection after reaching its maximum duration");
+            record_event_stream_closed("max_duration");
+            return Poll::Ready(None);
+        }
         match inner.as_mut().poll_next(cx) {
             Poll::Ready(Some(Ok(event))) => match &event {
                 WorkerEvent::Close => Poll::Ready(None),
@@ -2193,3 +4560,416 @@ impl Stream for WorkerEventStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::{
+        invocation_result_blob_key, invocation_result_chunks, read_raw_oplog,
+        WorkerStatusEventStream, MAX_RAW_OPLOG_ENTRIES,
+    };
+    use crate::error::GolemError;
+    use crate::services::events::{Event, Events};
+    use crate::services::golem_config::Limits;
+    use crate::services::oplog::{CommitLevel, OplogService, PrimaryOplogService};
+    use crate::storage::blob::memory::InMemoryBlobStorage;
+    use crate::storage::indexed::memory::InMemoryIndexedStorage;
+    use futures_util::StreamExt;
+    use golem_common::model::oplog::{OplogEntry, OplogIndex};
+    use golem_common::model::oplog_codec::OplogEncoding;
+    use golem_common::model::worker_status_event::{WorkerStatusEvent, WorkerStatusEventKind};
+    use golem_common::model::{
+        AccountId, ComponentId, ComponentType, FilterComparator, IdempotencyKey, OwnedWorkerId,
+        StringFilterComparator, TargetWorkerId, WorkerFilter, WorkerId, WorkerMetadata,
+        WorkerStatus,
+    };
+    use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+    use golem_wasm_rpc::protobuf::{TypedList, TypedTuple};
+    use std::sync::Arc;
+    use tonic::Code;
+    use uuid::Uuid;
+
+    fn target_worker_id(component_id: &ComponentId, name: &str) -> TargetWorkerId {
+        TargetWorkerId {
+            component_id: component_id.clone(),
+            worker_name: Some(name.to_string()),
+        }
+    }
+
+    #[test]
+    fn result_blob_key_is_derived_from_the_idempotency_key() {
+        let component_id = ComponentId(Uuid::new_v4());
+        let worker1 = target_worker_id(&component_id, "worker-1");
+        let worker2 = target_worker_id(&component_id, "worker-2");
+        let key1 = IdempotencyKey::fresh();
+        let key2 = IdempotencyKey::fresh();
+
+        let blob_key = invocation_result_blob_key(&worker1, &key1);
+
+        assert_eq!(blob_key, invocation_result_blob_key(&worker1, &key1));
+        assert_ne!(blob_key, invocation_result_blob_key(&worker1, &key2));
+        assert_ne!(blob_key, invocation_result_blob_key(&worker2, &key1));
+        assert!(blob_key.starts_with(&format!("{component_id}/")));
+    }
+
+    fn tuple(values: Vec<TypeAnnotatedValue>) -> TypeAnnotatedValue {
+        TypeAnnotatedValue::Tuple(TypedTuple {
+            typ: vec![],
+            value: values
+                .into_iter()
+                .map(|value| golem_wasm_rpc::protobuf::TypeAnnotatedValue {
+                    type_annotated_value: Some(value),
+                })
+                .collect(),
+        })
+    }
+
+    fn list(values: Vec<TypeAnnotatedValue>) -> TypeAnnotatedValue {
+        TypeAnnotatedValue::List(TypedList {
+            typ: None,
+            values: values
+                .into_iter()
+                .map(|value| golem_wasm_rpc::protobuf::TypeAnnotatedValue {
+                    type_annotated_value: Some(value),
+                })
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn list_result_is_chunked_by_elements() {
+        let (list_elements, chunks) = invocation_result_chunks(tuple(vec![list(vec![
+            TypeAnnotatedValue::U32(1),
+            TypeAnnotatedValue::U32(2),
+        ])]));
+
+        assert!(list_elements);
+        assert_eq!(
+            chunks.collect::<Vec<_>>(),
+            vec![TypeAnnotatedValue::U32(1), TypeAnnotatedValue::U32(2)]
+        );
+    }
+
+    #[test]
+    fn single_element_list_is_distinguished_from_other_results() {
+        let (list_elements1, chunks1) =
+            invocation_result_chunks(tuple(vec![list(vec![TypeAnnotatedValue::U32(1)])]));
+        let (list_elements2, chunks2) =
+            invocation_result_chunks(tuple(vec![TypeAnnotatedValue::U32(1)]));
+        let (list_elements3, chunks3) = invocation_result_chunks(tuple(vec![list(vec![])]));
+
+        assert!(list_elements1);
+        assert_eq!(
+            chunks1.collect::<Vec<_>>(),
+            vec![TypeAnnotatedValue::U32(1)]
+        );
+        assert!(!list_elements2);
+        assert_eq!(
+            chunks2.collect::<Vec<_>>(),
+            vec![tuple(vec![TypeAnnotatedValue::U32(1)])]
+        );
+        assert!(list_elements3);
+        assert_eq!(chunks3.count(), 0);
+    }
+
+    fn worker_status_changed(
+        component_id: &ComponentId,
+        name: &str,
+        kind: WorkerStatusEventKind,
+        status: WorkerStatus,
+    ) -> Event {
+        let mut metadata = WorkerMetadata::default(
+            WorkerId {
+                component_id: component_id.clone(),
+                worker_name: name.to_string(),
+            },
+            AccountId {
+                value: "account".to_string(),
+            },
+        );
+        metadata.last_known_status.status = status;
+        Event::WorkerStatusChanged {
+            event: WorkerStatusEvent::new(kind, &metadata),
+            metadata: Box::new(metadata),
+        }
+    }
+
+    #[test]
+    async fn status_stream_only_reports_the_matching_workers_of_the_component() {
+        let component_id = ComponentId(Uuid::new_v4());
+        let other_component_id = ComponentId(Uuid::new_v4());
+        let events = Events::new(16);
+        let stream = WorkerStatusEventStream::new(
+            events.subscribe(),
+            component_id.clone(),
+            Some(WorkerFilter::new_and(vec![
+                WorkerFilter::new_name(StringFilterComparator::Like, "worker".to_string()),
+                WorkerFilter::new_status(FilterComparator::NotEqual, WorkerStatus::Failed),
+            ])),
+            &Limits::default(),
+        );
+
+        events.publish(worker_status_changed(
+            &other_component_id,
+            "worker-1",
+            WorkerStatusEventKind::Created,
+            WorkerStatus::Idle,
+        ));
+        events.publish(worker_status_changed(
+            &component_id,
+            "other-1",
+            WorkerStatusEventKind::Created,
+            WorkerStatus::Idle,
+        ));
+        events.publish(worker_status_changed(
+            &component_id,
+            "worker-1",
+            WorkerStatusEventKind::StatusChanged,
+            WorkerStatus::Failed,
+        ));
+        events.publish(Event::InvocationCompleted {
+            worker_id: WorkerId {
+                component_id: component_id.clone(),
+                worker_name: "worker-1".to_string(),
+            },
+            idempotency_key: IdempotencyKey::fresh(),
+            result: Ok(TypeAnnotatedValue::U32(1)),
+        });
+        events.publish(worker_status_changed(
+            &component_id,
+            "worker-2",
+            WorkerStatusEventKind::StatusChanged,
+            WorkerStatus::Running,
+        ));
+        drop(events);
+
+        let received = stream
+            .map(|event| event.expect("Unexpected error in the status stream"))
+            .map(|event| WorkerStatusEvent::try_from(event).unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].worker_id.component_id, component_id);
+        assert_eq!(received[0].worker_id.worker_name, "worker-2");
+        assert_eq!(received[0].kind, WorkerStatusEventKind::StatusChanged);
+        assert_eq!(received[0].status, WorkerStatus::Running);
+    }
+
+    #[test]
+    async fn status_stream_without_filter_reports_every_worker_of_the_component() {
+        let component_id = ComponentId(Uuid::new_v4());
+        let events = Events::new(16);
+        let stream = WorkerStatusEventStream::new(
+            events.subscribe(),
+            component_id.clone(),
+            None,
+            &Limits::default(),
+        );
+
+        events.publish(worker_status_changed(
+            &component_id,
+            "worker-1",
+            WorkerStatusEventKind::Created,
+            WorkerStatus::Idle,
+        ));
+        events.publish(worker_status_changed(
+            &component_id,
+            "worker-1",
+            WorkerStatusEventKind::Deleted,
+            WorkerStatus::Failed,
+        ));
+        drop(events);
+
+        let kinds = stream
+            .map(|event| WorkerStatusEvent::try_from(event.unwrap()).unwrap().kind)
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            kinds,
+            vec![
+                WorkerStatusEventKind::Created,
+                WorkerStatusEventKind::Deleted
+            ]
+        );
+    }
+
+    #[test]
+    async fn status_stream_reports_the_lost_events_of_a_lagging_client() {
+        let component_id = ComponentId(Uuid::new_v4());
+        let events = Events::new(1);
+        let mut stream = WorkerStatusEventStream::new(
+            events.subscribe(),
+            component_id.clone(),
+            None,
+            &Limits::default(),
+        );
+
+        for name in ["worker-1", "worker-2", "worker-3"] {
+            events.publish(worker_status_changed(
+                &component_id,
+                name,
+                WorkerStatusEventKind::Created,
+                WorkerStatus::Idle,
+            ));
+        }
+
+        let lagged = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(lagged.code(), Code::DataLoss);
+        let event = WorkerStatusEvent::try_from(stream.next().await.unwrap().unwrap()).unwrap();
+        assert_eq!(event.worker_id.worker_name, "worker-3");
+    }
+
+    async fn oplog_service() -> Arc<dyn OplogService + Send + Sync> {
+        Arc::new(
+            PrimaryOplogService::new(
+                Arc::new(InMemoryIndexedStorage::new()),
+                Arc::new(InMemoryBlobStorage::new()),
+                1,
+                4,
+                100,
+                OplogEncoding::Bincode,
+                false,
+            )
+            .await,
+        )
+    }
+
+    fn owned_worker_id() -> OwnedWorkerId {
+        OwnedWorkerId::new(
+            &AccountId {
+                value: "account".to_string(),
+            },
+            &WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: "worker".to_string(),
+            },
+        )
+    }
+
+    /// Creates the oplog of a worker with a create entry followed by `suspends` suspend entries
+    async fn create_oplog(
+        oplog_service: &Arc<dyn OplogService + Send + Sync>,
+        owned_worker_id: &OwnedWorkerId,
+        suspends: usize,
+    ) {
+        let oplog = oplog_service
+            .create(
+                owned_worker_id,
+                OplogEntry::create(
+                    owned_worker_id.worker_id(),
+                    0,
+                    vec![],
+                    vec![],
+                    owned_worker_id.account_id(),
+                    None,
+                    0,
+                    0,
+                ),
+                ComponentType::Durable,
+            )
+            .await;
+        for _ in 0..suspends {
+            oplog.add(OplogEntry::suspend()).await;
+        }
+        oplog.add(OplogEntry::exited()).await;
+        oplog.commit(CommitLevel::Always).await;
+    }
+
+    fn decode(entries: &[String]) -> Vec<OplogEntry> {
+        entries
+            .iter()
+            .map(|entry| serde_json::from_str(entry).unwrap())
+            .collect()
+    }
+
+    #[test]
+    async fn raw_oplog_of_missing_worker_is_not_found() {
+        let oplog_service = oplog_service().await;
+        let owned_worker_id = owned_worker_id();
+
+        let result = read_raw_oplog(oplog_service, &owned_worker_id, OplogIndex::INITIAL, 10).await;
+
+        assert!(matches!(
+            result,
+            Err(GolemError::WorkerNotFound { worker_id }) if worker_id == owned_worker_id.worker_id()
+        ));
+    }
+
+    #[test]
+    async fn raw_oplog_returns_the_requested_chunk_as_json() {
+        let oplog_service = oplog_service().await;
+        let owned_worker_id = owned_worker_id();
+        create_oplog(&oplog_service, &owned_worker_id, 2).await;
+
+        let response = read_raw_oplog(
+            oplog_service.clone(),
+            &owned_worker_id,
+            OplogIndex::from_u64(2),
+            2,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.first_index_in_chunk, 2);
+        assert_eq!(response.last_index, 4);
+        let stored = oplog_service
+            .read(&owned_worker_id, OplogIndex::from_u64(2), 2)
+            .await;
+        assert_eq!(
+            response.entries,
+            stored
+                .values()
+                .map(|entry| serde_json::to_string(entry).unwrap())
+                .collect::<Vec<_>>()
+        );
+        assert!(decode(&response.entries)
+            .iter()
+            .all(|entry| matches!(entry, OplogEntry::Suspend { .. })));
+    }
+
+    #[test]
+    async fn raw_oplog_starting_after_the_last_entry_is_empty() {
+        let oplog_service = oplog_service().await;
+        let owned_worker_id = owned_worker_id();
+        create_oplog(&oplog_service, &owned_worker_id, 0).await;
+
+        let response = read_raw_oplog(oplog_service, &owned_worker_id, OplogIndex::from_u64(3), 10)
+            .await
+            .unwrap();
+
+        assert!(response.entries.is_empty());
+        assert_eq!(response.first_index_in_chunk, 3);
+        assert_eq!(response.last_index, 2);
+    }
+
+    #[test]
+    async fn raw_oplog_chunks_are_limited() {
+        let oplog_service = oplog_service().await;
+        let owned_worker_id = owned_worker_id();
+        create_oplog(
+            &oplog_service,
+            &owned_worker_id,
+            MAX_RAW_OPLOG_ENTRIES as usize + 10,
+        )
+        .await;
+
+        let response = read_raw_oplog(
+            oplog_service,
+            &owned_worker_id,
+            OplogIndex::INITIAL,
+            MAX_RAW_OPLOG_ENTRIES * 2,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.entries.len(), MAX_RAW_OPLOG_ENTRIES as usize);
+        assert!(matches!(
+            decode(&response.entries)[0],
+            OplogEntry::Create { .. }
+        ));
+        assert_eq!(response.last_index, MAX_RAW_OPLOG_ENTRIES + 12);
+    }
+}
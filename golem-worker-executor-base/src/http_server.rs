@@ -19,10 +19,14 @@ use http_02::{Response, StatusCode};
 use prometheus::{Encoder, Registry, TextEncoder};
 use tokio::task::JoinHandle;
 use tracing::info;
+
+use crate::services::{autoscaling, readiness, worker_consistency};
 use warp::hyper::Body;
 use warp::Filter;
 
-/// The worker executor's HTTP interface provides Prometheus metrics and a healthcheck endpoint
+/// The worker executor's HTTP interface provides Prometheus metrics, healthcheck, liveness and
+/// readiness endpoints, the reports of the recent worker consistency checks and the signals for
+/// autoscalers
 pub struct HttpServerImpl {
     handle: JoinHandle<()>,
 }
@@ -57,10 +61,75 @@ async fn server(
             .unwrap()
     });
 
+    let liveness = warp::path!("liveness").map(move || {
+        Response::builder()
+            .status(StatusCode::OK)
+            .body(Body::from(body_message))
+            .unwrap()
+    });
+
+    let readiness = warp::path!("readiness").map(executor_readiness);
+
     let metrics = warp::path!("metrics").map(move || prometheus_metrics(registry.clone()));
 
+    let consistency_report =
+        warp::path!("consistency-report").map(worker_consistency_report);
+
+    let autoscaling_signals = warp::path!("autoscaling-signals").map(autoscaling_signals);
+
     info!("Http server started on {addr}");
-    warp::serve(healthcheck.or(metrics)).run(addr).await;
+    warp::serve(
+        healthcheck
+            .or(liveness)
+            .or(readiness)
+            .or(metrics)
+            .or(consistency_report)
+            .or(autoscaling_signals),
+    )
+    .run(addr)
+    .await;
+}
+
+// Load balancers should only route to the executor once it can serve invocations
+fn executor_readiness() -> Response<Body> {
+    let readiness = readiness::readiness();
+    let status = if readiness.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::to_vec(&readiness).expect("Failed to serialize readiness"),
+        ))
+        .unwrap()
+}
+
+fn worker_consistency_report() -> Response<Body> {
+    Response::builder()
+        .header("Content-Type", "application/json")
+        .body(Body::from(
+            serde_json::to_vec(&worker_consistency::recent_reports())
+                .expect("Failed to serialize consistency reports"),
+        ))
+        .unwrap()
+}
+
+fn autoscaling_signals() -> Response<Body> {
+    match autoscaling::last_signals() {
+        Some(signals) => Response::builder()
+            .header("Content-Type", "application/json")
+            .body(Body::from(
+                serde_json::to_vec(&signals).expect("Failed to serialize autoscaling signals"),
+            ))
+            .unwrap(),
+        None => Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .body(Body::from("Autoscaling signals are not available yet"))
+            .unwrap(),
+    }
 }
 
 fn prometheus_metrics(registry: Registry) -> Response<Body> {
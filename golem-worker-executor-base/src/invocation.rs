@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Instant;
+
 use golem_common::model::oplog::{WorkerError, WorkerResourceId};
 use golem_common::model::WorkerStatus;
 use golem_wasm_rpc::wasmtime::{decode_param, encode_output, type_to_analysed_type};
@@ -22,6 +24,7 @@ use wasmtime::component::{Func, Val};
 use wasmtime::{AsContextMut, StoreContextMut};
 
 use crate::error::GolemError;
+use crate::metrics::functions::record_function_invocation;
 use crate::metrics::wasm::{record_invocation, record_invocation_consumption};
 use crate::model::{InterruptKind, TrapType};
 use crate::workerctx::{PublicWorkerIo, WorkerCtx};
@@ -44,6 +47,7 @@ pub async fn invoke_worker<Ctx: WorkerCtx>(
 ) -> Result<InvokeResult, GolemError> {
     let mut store = store.as_context_mut();
     let was_live_before = store.data().is_live();
+    let started_at = Instant::now();
 
     let result = invoke_or_fail(
         full_function_name.clone(),
@@ -55,6 +59,17 @@ pub async fn invoke_worker<Ctx: WorkerCtx>(
 
     debug!("Invocation resulted in {:?}", result);
 
+    // Interrupted invocations are counted once they are resumed and finished
+    if was_live_before && !matches!(result, Ok(InvokeResult::Interrupted { .. })) {
+        record_function_invocation(
+            &store.data().worker_id().component_id,
+            &full_function_name,
+            started_at.elapsed(),
+            result.as_ref().map_or(0, |result| result.consumed_fuel()),
+            matches!(result, Err(_) | Ok(InvokeResult::Failed { .. })),
+        );
+    }
+
     match &result {
         Err(_) => {
             record_invocation(was_live_before, "failed");
@@ -22,6 +22,7 @@ pub mod invocation;
 pub mod metrics;
 pub mod model;
 pub mod preview2;
+pub mod profiler;
 pub mod services;
 pub mod storage;
 pub mod wasi_host;
@@ -36,14 +37,17 @@ use crate::http_server::HttpServerImpl;
 use crate::services::active_workers::ActiveWorkers;
 use crate::services::blob_store::{BlobStoreService, DefaultBlobStoreService};
 use crate::services::component::ComponentService;
+use crate::services::domain_event::{DefaultDomainEventService, DomainEventService};
 use crate::services::events::Events;
+use crate::services::feature_flags::{ConfiguredFeatureFlagService, FeatureFlagService};
 use crate::services::golem_config::{
     BlobStorageConfig, GolemConfig, IndexedStorageConfig, KeyValueStorageConfig,
 };
+use crate::services::inference::{InferenceService, InferenceServiceDefault};
 use crate::services::key_value::{DefaultKeyValueService, KeyValueService};
 use crate::services::oplog::{
-    BlobOplogArchiveService, CompressedOplogArchiveService, MultiLayerOplogService,
-    OplogArchiveService, OplogService, PrimaryOplogService,
+    BlobOplogArchiveService, CommitScheduler, CompressedOplogArchiveService,
+    MultiLayerOplogService, OplogArchiveService, OplogService, PrimaryOplogService,
 };
 use crate::services::promise::{DefaultPromiseService, PromiseService};
 use crate::services::scheduler::{SchedulerService, SchedulerServiceDefault};
@@ -55,8 +59,8 @@ use crate::services::worker_enumeration::{
     DefaultWorkerEnumerationService, RunningWorkerEnumerationService,
     RunningWorkerEnumerationServiceDefault, WorkerEnumerationService,
 };
-use crate::services::worker_proxy::{RemoteWorkerProxy, WorkerProxy};
-use crate::services::{component, shard_manager, All};
+use crate::services::worker_proxy::{RemoteWorkerProxy, RetryingWorkerProxy, WorkerProxy};
+use crate::services::{component, readiness, shard_manager, All};
 use crate::storage::blob::s3::S3BlobStorage;
 use crate::storage::blob::BlobStorage;
 use crate::storage::indexed::redis::RedisIndexedStorage;
@@ -69,8 +73,9 @@ use anyhow::anyhow;
 use async_trait::async_trait;
 use golem_api_grpc::proto;
 use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_server::WorkerExecutorServer;
-use golem_common::golem_version;
+use golem_common::metrics::grpc::GrpcMessageMetricsLayer;
 use golem_common::redis::RedisPool;
+use golem_common::{golem_version, with_grpc_compression};
 use humansize::{ISizeFormatter, BINARY};
 use nonempty_collections::NEVec;
 use prometheus::Registry;
@@ -78,7 +83,6 @@ use std::sync::Arc;
 use storage::keyvalue::sqlite::SqliteKeyValueStorage;
 use storage::sqlite_types::SqlitePool;
 use tokio::runtime::Handle;
-use tonic::codec::CompressionEncoding;
 use tonic::transport::Server;
 use tracing::info;
 use uuid::Uuid;
@@ -114,6 +118,9 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
         shard_service: Arc<dyn ShardService + Send + Sync>,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        domain_event_service: Arc<dyn DomainEventService + Send + Sync>,
+        feature_flag_service: Arc<dyn FeatureFlagService + Send + Sync>,
+        inference_service: Arc<dyn InferenceService + Send + Sync>,
         worker_activator: Arc<dyn WorkerActivator + Send + Sync>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
         scheduler_service: Arc<dyn SchedulerService + Send + Sync>,
@@ -121,6 +128,15 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
         events: Arc<Events>,
     ) -> anyhow::Result<All<Ctx>>;
 
+    /// Can be overridden to provide the inference backends of the `wasi:nn` host functions
+    /// instead of the ones listed in the configuration
+    fn create_inference_service(
+        &self,
+        golem_config: &GolemConfig,
+    ) -> Arc<dyn InferenceService + Send + Sync> {
+        Arc::new(InferenceServiceDefault::configured(&golem_config.wasi_nn))
+    }
+
     /// Can be overridden to customize the wasmtime configuration
     fn create_wasmtime_config(&self) -> Config {
         let mut config = Config::default();
@@ -179,7 +195,7 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
             Arc<dyn KeyValueStorage + Send + Sync>,
         ) = match &golem_config.key_value_storage {
             KeyValueStorageConfig::Redis(redis) => {
-                info!("Using Redis for key-value storage at {}", redis.url());
+                info!("Using Redis for key-value storage at {}", redis.endpoint());
                 let pool = RedisPool::configured(redis)
                     .await
                     .map_err(|err| anyhow!(err))?;
@@ -212,7 +228,7 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
                 Arc::new(RedisIndexedStorage::new(redis.clone()))
             }
             IndexedStorageConfig::Redis(redis) => {
-                info!("Using Redis for indexed-storage at {}", redis.url());
+                info!("Using Redis for indexed-storage at {}", redis.endpoint());
                 let pool = RedisPool::configured(redis).await?;
                 Arc::new(RedisIndexedStorage::new(pool.clone()))
             }
@@ -234,7 +250,8 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
                 Arc::new(
                     storage::blob::fs::FileSystemBlobStorage::new(&config.root)
                         .await
-                        .map_err(|err| anyhow!(err))?,
+                        .map_err(|err| anyhow!(err))?
+                        .with_oplog_payload_sharding(config.oplog_payload_shard_fan_out),
                 )
             }
             BlobStorageConfig::InMemory => {
@@ -243,10 +260,17 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
             }
         };
 
+        readiness::start_storage_verification(
+            key_value_storage.clone(),
+            indexed_storage.clone(),
+            blob_storage.clone(),
+        );
+
         let component_service = component::configured(
             &golem_config.component_service,
             &golem_config.component_cache,
             &golem_config.compiled_component_service,
+            golem_config.grpc_compression,
             blob_storage.clone(),
         )
         .await;
@@ -260,26 +284,38 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
         let mut oplog_archives: Vec<Arc<dyn OplogArchiveService + Send + Sync>> = Vec::new();
         for idx in 1..golem_config.oplog.indexed_storage_layers {
             let svc: Arc<dyn OplogArchiveService + Send + Sync> = Arc::new(
-                CompressedOplogArchiveService::new(indexed_storage.clone(), idx),
+                CompressedOplogArchiveService::new(indexed_storage.clone(), idx)
+                    .with_entry_encoding(golem_config.oplog.entry_encoding),
             );
             oplog_archives.push(svc);
         }
         for idx in 0..golem_config.oplog.blob_storage_layers {
-            let svc: Arc<dyn OplogArchiveService + Send + Sync> =
-                Arc::new(BlobOplogArchiveService::new(blob_storage.clone(), idx));
+            let svc: Arc<dyn OplogArchiveService + Send + Sync> = Arc::new(
+                BlobOplogArchiveService::new(blob_storage.clone(), idx)
+                    .with_entry_encoding(golem_config.oplog.entry_encoding),
+            );
             oplog_archives.push(svc);
         }
         let oplog_archives = NEVec::from_vec(oplog_archives);
 
+        let commit_scheduler = CommitScheduler::new(
+            golem_config.oplog.max_concurrent_commits,
+            golem_config.oplog.commit_slice_size,
+            golem_config.oplog.max_committed_entries_per_second,
+        );
         let oplog_service: Arc<dyn OplogService + Send + Sync> = match oplog_archives {
             None => Arc::new(
                 PrimaryOplogService::new(
                     indexed_storage.clone(),
                     blob_storage.clone(),
                     golem_config.oplog.max_operations_before_commit,
+                    golem_config.oplog.max_commits_in_flight,
                     golem_config.oplog.max_payload_size,
+                    golem_config.oplog.entry_encoding,
+                    golem_config.oplog.rewrite_migrated_entries,
                 )
-                .await,
+                .await
+                .with_commit_scheduler(commit_scheduler),
             ),
             Some(oplog_archives) => {
                 let primary = Arc::new(
@@ -287,9 +323,13 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
                         indexed_storage.clone(),
                         blob_storage.clone(),
                         golem_config.oplog.max_operations_before_commit,
+                        golem_config.oplog.max_commits_in_flight,
                         golem_config.oplog.max_payload_size,
+                        golem_config.oplog.entry_encoding,
+                        golem_config.oplog.rewrite_migrated_entries,
                     )
-                    .await,
+                    .await
+                    .with_commit_scheduler(commit_scheduler),
                 );
 
                 Arc::new(MultiLayerOplogService::new(
@@ -318,10 +358,14 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
             RunningWorkerEnumerationServiceDefault::new(active_workers.clone()),
         );
 
-        let shard_manager_service = shard_manager::configured(&golem_config.shard_manager_service);
+        let shard_manager_service = shard_manager::configured(
+            &golem_config.shard_manager_service,
+            golem_config.grpc_compression,
+        );
 
         let config = self.create_wasmtime_config();
         let engine = Arc::new(Engine::new(&config)?);
+        readiness::start_engine_warm_up(engine.clone());
         let linker = self.create_wasmtime_linker(&engine)?;
 
         let mut epoch_interval = tokio::time::interval(golem_config.limits.epoch_interval);
@@ -339,6 +383,28 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
 
         let blob_store_service = Arc::new(DefaultBlobStoreService::new(blob_storage.clone()));
 
+        let domain_event_service =
+            Arc::new(DefaultDomainEventService::new(indexed_storage.clone()));
+
+        let feature_flag_service = Arc::new(ConfiguredFeatureFlagService::new(
+            &golem_config.feature_flags,
+        ));
+
+        let inference_service = self.create_inference_service(&golem_config);
+
+        let worker_proxy: Arc<dyn WorkerProxy + Send + Sync> = Arc::new(RetryingWorkerProxy::new(
+            Arc::new(RemoteWorkerProxy::new(
+                golem_config.public_worker_api.uri(),
+                golem_config
+                    .public_worker_api
+                    .access_token
+                    .parse::<Uuid>()
+                    .expect("Access token must be an UUID"),
+                golem_config.grpc_compression,
+            )),
+            golem_config.public_worker_api.retries.clone(),
+        ));
+
         let scheduler_service = SchedulerServiceDefault::new(
             key_value_storage.clone(),
             shard_service.clone(),
@@ -346,18 +412,10 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
             lazy_worker_activator.clone(),
             oplog_service.clone(),
             worker_service.clone(),
+            worker_proxy.clone(),
             golem_config.scheduler.refresh_interval,
         );
 
-        let worker_proxy: Arc<dyn WorkerProxy + Send + Sync> = Arc::new(RemoteWorkerProxy::new(
-            golem_config.public_worker_api.uri(),
-            golem_config
-                .public_worker_api
-                .access_token
-                .parse::<Uuid>()
-                .expect("Access token must be an UUID"),
-        ));
-
         let events = Arc::new(Events::new(
             golem_config.limits.invocation_result_broadcast_capacity,
         ));
@@ -378,6 +436,9 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
                 shard_service,
                 key_value_service,
                 blob_store_service,
+                domain_event_service,
+                feature_flag_service,
+                inference_service,
                 lazy_worker_activator.clone(),
                 oplog_service,
                 scheduler_service,
@@ -391,13 +452,15 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
             WorkerExecutorImpl::<Ctx, All<Ctx>>::new(services, lazy_worker_activator, addr.port())
                 .await?;
 
-        let service = WorkerExecutorServer::new(worker_executor)
-            .accept_compressed(CompressionEncoding::Gzip)
-            .send_compressed(CompressionEncoding::Gzip);
+        let service = with_grpc_compression!(
+            WorkerExecutorServer::new(worker_executor),
+            golem_config.grpc_compression
+        );
 
         info!("Starting gRPC server on port {}", addr.port());
         Server::builder()
             .max_concurrent_streams(Some(golem_config.limits.max_concurrent_streams))
+            .layer(GrpcMessageMetricsLayer)
             .add_service(reflection_service)
             .add_service(service)
             .add_service(health_service)
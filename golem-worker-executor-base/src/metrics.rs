@@ -56,6 +56,10 @@ const MEMORY_SIZE_BUCKETS: &[f64; 11] = &[
     1073741824.0,
 ];
 
+const PAYLOAD_SIZE_BUCKETS: &[f64; 10] = &[
+    64.0, 256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0, 4194304.0, 16777216.0,
+];
+
 pub mod component {
     use std::time::Duration;
 
@@ -95,6 +99,17 @@ pub mod events {
             &["event"]
         )
         .unwrap();
+        static ref EVENT_STREAM_DROPPED_EVENTS_TOTAL: Counter = register_counter!(
+            "event_stream_dropped_events_total",
+            "Number of worker events not sent to slow worker connect stream clients"
+        )
+        .unwrap();
+        static ref EVENT_STREAM_CLOSED_TOTAL: CounterVec = register_counter_vec!(
+            "event_stream_closed_total",
+            "Number of worker connect streams closed by the server",
+            &["reason"]
+        )
+        .unwrap();
     }
 
     pub fn record_event(event: &'static str) {
@@ -104,6 +119,14 @@ pub mod events {
     pub fn record_broadcast_event(event: &'static str) {
         EVENT_BROADCAST_TOTAL.with_label_values(&[event]).inc();
     }
+
+    pub fn record_event_stream_dropped_events(count: u64) {
+        EVENT_STREAM_DROPPED_EVENTS_TOTAL.inc_by(count as f64);
+    }
+
+    pub fn record_event_stream_closed(reason: &'static str) {
+        EVENT_STREAM_CLOSED_TOTAL.with_label_values(&[reason]).inc();
+    }
 }
 
 pub mod workers {
@@ -117,6 +140,33 @@ pub mod workers {
             &["api"]
         )
         .unwrap();
+        static ref WORKER_TASK_PANIC_TOTAL: CounterVec = register_counter_vec!(
+            "worker_task_panic_total",
+            "Number of panics caught in the tasks of workers",
+            &["task"]
+        )
+        .unwrap();
+        static ref EXPIRED_WORKERS_DELETED_TOTAL: Counter = register_counter!(
+            "expired_workers_deleted_total",
+            "Number of workers deleted by the scheduler because their TTL elapsed"
+        )
+        .unwrap();
+        static ref SCHEDULED_INVOCATIONS_TOTAL: CounterVec = register_counter_vec!(
+            "scheduled_invocations_total",
+            "Number of scheduled invocations enqueued by the scheduler",
+            &["result"]
+        )
+        .unwrap();
+        static ref WORKER_DISK_USAGE_BYTES: Gauge = register_gauge!(
+            "worker_disk_usage_bytes",
+            "Total size of the files in the file systems of the active workers"
+        )
+        .unwrap();
+        static ref DISK_LIMIT_EXCEEDED_TOTAL: Counter = register_counter!(
+            "disk_limit_exceeded_total",
+            "Number of file system writes denied because of the worker's disk usage limit"
+        )
+        .unwrap();
     }
 
     pub fn record_worker_call(api_name: &'static str) {
@@ -124,6 +174,29 @@ pub mod workers {
             .with_label_values(&[api_name])
             .inc();
     }
+
+    pub fn record_worker_task_panic(task: &'static str) {
+        WORKER_TASK_PANIC_TOTAL.with_label_values(&[task]).inc();
+    }
+
+    pub fn record_expired_worker_deleted() {
+        EXPIRED_WORKERS_DELETED_TOTAL.inc();
+    }
+
+    pub fn record_scheduled_invocation(succeeded: bool) {
+        let result = if succeeded { "success" } else { "failure" };
+        SCHEDULED_INVOCATIONS_TOTAL
+            .with_label_values(&[result])
+            .inc();
+    }
+
+    pub fn record_disk_usage_change(delta: i64) {
+        WORKER_DISK_USAGE_BYTES.add(delta as f64);
+    }
+
+    pub fn record_disk_limit_exceeded() {
+        DISK_LIMIT_EXCEEDED_TOTAL.inc();
+    }
 }
 
 pub mod promises {
@@ -149,6 +222,32 @@ pub mod promises {
     }
 }
 
+pub mod idempotency {
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref DEDUPLICATED_INVOCATIONS_TOTAL: Counter = register_counter!(
+            "deduplicated_invocations_total",
+            "Number of invocations answered with the result of an earlier invocation with the same idempotency key"
+        )
+        .unwrap();
+        static ref EXPIRED_IDEMPOTENCY_KEYS_TOTAL: Counter = register_counter!(
+            "expired_idempotency_keys_total",
+            "Number of completed idempotency keys forgotten because of the component's deduplication window"
+        )
+        .unwrap();
+    }
+
+    pub fn record_deduplicated_invocation() {
+        DEDUPLICATED_INVOCATIONS_TOTAL.inc();
+    }
+
+    pub fn record_expired_idempotency_keys(count: usize) {
+        EXPIRED_IDEMPOTENCY_KEYS_TOTAL.inc_by(count as f64);
+    }
+}
+
 pub mod sharding {
     use lazy_static::lazy_static;
     use prometheus::*;
@@ -163,6 +262,43 @@ pub mod sharding {
     }
 }
 
+pub mod autoscaling {
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    use crate::services::autoscaling::AutoscalingSignals;
+
+    lazy_static! {
+        static ref PENDING_INVOCATIONS: Gauge = register_gauge!(
+            "autoscaling_pending_invocations",
+            "Number of invocations queued in the active workers"
+        )
+        .unwrap();
+        static ref MEMORY_PRESSURE: Gauge = register_gauge!(
+            "autoscaling_memory_pressure",
+            "Ratio of the worker memory in use"
+        )
+        .unwrap();
+        static ref HYDRATION_QUEUE_DEPTH: Gauge = register_gauge!(
+            "autoscaling_hydration_queue_depth",
+            "Number of workers waiting for memory to be loaded"
+        )
+        .unwrap();
+        static ref SHARD_IMBALANCE: Gauge = register_gauge!(
+            "autoscaling_shard_imbalance",
+            "Active workers of the busiest assigned shard relative to the average"
+        )
+        .unwrap();
+    }
+
+    pub fn record_autoscaling_signals(signals: &AutoscalingSignals) {
+        PENDING_INVOCATIONS.set(signals.pending_invocations as f64);
+        MEMORY_PRESSURE.set(signals.memory_pressure);
+        HYDRATION_QUEUE_DEPTH.set(signals.hydration_queue_depth as f64);
+        SHARD_IMBALANCE.set(signals.shard_imbalance);
+    }
+}
+
 pub mod wasm {
     use std::time::Duration;
 
@@ -205,6 +341,23 @@ pub mod wasm {
             crate::metrics::MEMORY_SIZE_BUCKETS.to_vec()
         )
         .unwrap();
+        static ref INVOCATION_INPUT_SIZE_BYTES: Histogram = register_histogram!(
+            "invocation_input_size_bytes",
+            "Encoded size of the parameters of an invocation",
+            crate::metrics::PAYLOAD_SIZE_BUCKETS.to_vec()
+        )
+        .unwrap();
+        static ref INVOCATION_OUTPUT_SIZE_BYTES: Histogram = register_histogram!(
+            "invocation_output_size_bytes",
+            "Encoded size of the result of an invocation",
+            crate::metrics::PAYLOAD_SIZE_BUCKETS.to_vec()
+        )
+        .unwrap();
+        static ref OVERSIZED_INVOCATION_RESULT_TOTAL: Counter = register_counter!(
+            "oversized_invocation_result_total",
+            "Number of invocation results stored in the blob storage for exceeding the size limit"
+        )
+        .unwrap();
     }
 
     lazy_static! {
@@ -226,6 +379,18 @@ pub mod wasm {
             golem_common::metrics::DEFAULT_COUNT_BUCKETS.to_vec()
         )
         .unwrap();
+        static ref REPLAY_CONSISTENCY_CHECK_TOTAL: CounterVec = register_counter_vec!(
+            "replay_consistency_check_total",
+            "Number of replayed host function calls checked against the oplog",
+            &["outcome"]
+        )
+        .unwrap();
+        static ref REPLAY_DIVERGENCE_TOTAL: CounterVec = register_counter_vec!(
+            "replay_divergence_total",
+            "Number of replayed host function calls diverging from the oplog",
+            &["function"]
+        )
+        .unwrap();
     }
 
     pub fn record_host_function_call(iface: &'static str, name: &'static str) {
@@ -243,6 +408,21 @@ pub mod wasm {
         REPLAYED_FUNCTIONS_COUNT.observe(count as f64);
     }
 
+    pub fn record_replay_consistency_check(function_name: &str, diverged: bool) {
+        if diverged {
+            REPLAY_CONSISTENCY_CHECK_TOTAL
+                .with_label_values(&["diverged"])
+                .inc();
+            REPLAY_DIVERGENCE_TOTAL
+                .with_label_values(&[function_name])
+                .inc();
+        } else {
+            REPLAY_CONSISTENCY_CHECK_TOTAL
+                .with_label_values(&["consistent"])
+                .inc();
+        }
+    }
+
     pub fn record_create_worker(duration: Duration) {
         CREATE_WORKER_SECONDS.observe(duration.as_secs_f64());
     }
@@ -265,6 +445,18 @@ pub mod wasm {
     pub fn record_allocated_memory(amount: usize) {
         ALLOCATED_MEMORY_BYTES.observe(amount as f64);
     }
+
+    pub fn record_invocation_input_size(size: usize) {
+        INVOCATION_INPUT_SIZE_BYTES.observe(size as f64);
+    }
+
+    pub fn record_invocation_output_size(size: usize) {
+        INVOCATION_OUTPUT_SIZE_BYTES.observe(size as f64);
+    }
+
+    pub fn record_oversized_invocation_result() {
+        OVERSIZED_INVOCATION_RESULT_TOTAL.inc();
+    }
 }
 
 pub mod oplog {
@@ -285,6 +477,17 @@ pub mod oplog {
             golem_common::metrics::DEFAULT_TIME_BUCKETS.to_vec()
         )
         .unwrap();
+        static ref COMMIT_QUEUE_DELAY_SECONDS: Histogram = register_histogram!(
+            "oplog_commit_queue_delay_seconds",
+            "Time oplog entries wait to be committed, including the wait for the worker's turn",
+            golem_common::metrics::DEFAULT_TIME_BUCKETS.to_vec()
+        )
+        .unwrap();
+        static ref COMMITS_WAITING: IntGauge = register_int_gauge!(
+            "oplog_commits_waiting",
+            "Number of oplog commits waiting for their turn"
+        )
+        .unwrap();
     }
 
     pub fn record_oplog_call(api_name: &'static str) {
@@ -300,4 +503,492 @@ pub mod oplog {
             })
             .observe(duration.as_secs_f64());
     }
+
+    pub fn record_commit_queued() {
+        COMMITS_WAITING.inc();
+    }
+
+    pub fn record_commit_dequeued(queue_delay: std::time::Duration) {
+        COMMITS_WAITING.dec();
+        COMMIT_QUEUE_DELAY_SECONDS.observe(queue_delay.as_secs_f64());
+    }
+}
+
+pub mod guest {
+    use std::collections::{HashMap, HashSet};
+    use std::sync::Mutex;
+
+    use golem_common::model::ComponentId;
+    use lazy_static::lazy_static;
+    use prometheus::core::Collector;
+    use prometheus::*;
+    use tracing::warn;
+
+    use crate::services::golem_config::Limits;
+
+    lazy_static! {
+        static ref GUEST_METRICS: Mutex<HashMap<ComponentId, ComponentMetrics>> =
+            Mutex::new(HashMap::new());
+        static ref GUEST_METRIC_OBSERVATIONS_DROPPED_TOTAL: IntCounterVec =
+            register_int_counter_vec!(
+                "guest_metric_observations_dropped_total",
+                "Number of dropped observations of custom metrics emitted by workers",
+                &["reason"]
+            )
+            .unwrap();
+    }
+
+    /// The custom metrics registered by the workers of a component. Once registered they are
+    /// kept for the lifetime of the executor, so their number is limited by `Limits`.
+    #[derive(Default)]
+    struct ComponentMetrics {
+        metrics: HashMap<String, GuestMetric>,
+        series: usize,
+    }
+
+    struct GuestMetric {
+        collector: GuestCollector,
+        label_names: Vec<String>,
+        series: HashSet<Vec<String>>,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum GuestMetricKind {
+        Counter,
+        Histogram,
+    }
+
+    enum GuestCollector {
+        Counter(IntCounterVec),
+        Histogram(HistogramVec),
+    }
+
+    impl GuestCollector {
+        /// Registers a new metric, exported as `guest_<component id>_<name>`
+        fn register(
+            kind: GuestMetricKind,
+            component_id: &ComponentId,
+            name: &str,
+            label_names: &[String],
+        ) -> Result<Self> {
+            let label_names: Vec<&str> = label_names.iter().map(|name| name.as_str()).collect();
+            let subsystem = component_id.0.simple().to_string();
+            let help = format!("Custom {kind:?} of the workers of component {component_id}");
+            let collector = match kind {
+                GuestMetricKind::Counter => GuestCollector::Counter(IntCounterVec::new(
+                    Opts::new(name, help)
+                        .namespace("guest")
+                        .subsystem(subsystem),
+                    &label_names,
+                )?),
+                GuestMetricKind::Histogram => GuestCollector::Histogram(HistogramVec::new(
+                    HistogramOpts::new(name, help)
+                        .namespace("guest")
+                        .subsystem(subsystem),
+                    &label_names,
+                )?),
+            };
+            let boxed: Box<dyn Collector> = match &collector {
+                GuestCollector::Counter(counter) => Box::new(counter.clone()),
+                GuestCollector::Histogram(histogram) => Box::new(histogram.clone()),
+            };
+            default_registry().register(boxed)?;
+            Ok(collector)
+        }
+
+        fn kind(&self) -> GuestMetricKind {
+            match self {
+                GuestCollector::Counter(_) => GuestMetricKind::Counter,
+                GuestCollector::Histogram(_) => GuestMetricKind::Histogram,
+            }
+        }
+    }
+
+    pub fn increment_guest_counter(
+        component_id: &ComponentId,
+        name: &str,
+        labels: &[(String, String)],
+        value: u64,
+        limits: &Limits,
+    ) {
+        let kind = GuestMetricKind::Counter;
+        with_guest_metric(
+            component_id,
+            name,
+            kind,
+            labels,
+            limits,
+            |collector, values| {
+                if let GuestCollector::Counter(counter) = collector {
+                    counter.with_label_values(values).inc_by(value);
+                }
+            },
+        );
+    }
+
+    pub fn observe_guest_histogram(
+        component_id: &ComponentId,
+        name: &str,
+        labels: &[(String, String)],
+        value: f64,
+        limits: &Limits,
+    ) {
+        let kind = GuestMetricKind::Histogram;
+        with_guest_metric(
+            component_id,
+            name,
+            kind,
+            labels,
+            limits,
+            |collector, values| {
+                if let GuestCollector::Histogram(histogram) = collector {
+                    histogram.with_label_values(values).observe(value);
+                }
+            },
+        );
+    }
+
+    /// Finds or registers the metric and its series, dropping the observation if it would
+    /// exceed the cardinality limits or does not match the already registered metric
+    fn with_guest_metric(
+        component_id: &ComponentId,
+        name: &str,
+        kind: GuestMetricKind,
+        labels: &[(String, String)],
+        limits: &Limits,
+        f: impl FnOnce(&GuestCollector, &[&str]),
+    ) {
+        let mut labels = labels.to_vec();
+        labels.sort();
+        let (label_names, values): (Vec<String>, Vec<String>) = labels.into_iter().unzip();
+
+        let mut guest_metrics = GUEST_METRICS.lock().unwrap();
+        let component_metrics = guest_metrics.entry(component_id.clone()).or_default();
+
+        if !component_metrics.metrics.contains_key(name) {
+            if component_metrics.metrics.len() >= limits.max_guest_metrics {
+                return drop_observation(component_id, name, "too_many_metrics");
+            }
+            match GuestCollector::register(kind, component_id, name, &label_names) {
+                Ok(collector) => {
+                    let metric = GuestMetric {
+                        collector,
+                        label_names: label_names.clone(),
+                        series: HashSet::new(),
+                    };
+                    component_metrics.metrics.insert(name.to_string(), metric);
+                }
+                Err(err) => {
+                    warn!("Failed to register custom metric {name}: {err}");
+                    return drop_observation(component_id, name, "invalid_metric");
+                }
+            }
+        }
+
+        let metric = component_metrics.metrics.get_mut(name).unwrap();
+        if metric.collector.kind() != kind || metric.label_names != label_names {
+            return drop_observation(component_id, name, "mismatching_metric");
+        }
+        if !metric.series.contains(&values) {
+            if component_metrics.series >= limits.max_guest_metric_series {
+                return drop_observation(component_id, name, "too_many_series");
+            }
+            metric.series.insert(values.clone());
+            component_metrics.series += 1;
+        }
+
+        let values: Vec<&str> = values.iter().map(|value| value.as_str()).collect();
+        f(&metric.collector, &values);
+    }
+
+    fn drop_observation(component_id: &ComponentId, name: &str, reason: &'static str) {
+        warn!(
+            component_id = component_id.to_string(),
+            "Dropped observation of custom metric {name}: {reason}"
+        );
+        GUEST_METRIC_OBSERVATIONS_DROPPED_TOTAL
+            .with_label_values(&[reason])
+            .inc();
+    }
+}
+
+pub mod functions {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    use golem_common::metrics::DEFAULT_TIME_BUCKETS;
+    use golem_common::model::ComponentId;
+    use lazy_static::lazy_static;
+    use prometheus::*;
+    use rib::{ParsedFunctionName, ParsedFunctionReference};
+
+    /// Maximum number of components whose function statistics are kept. Once reached, the
+    /// statistics of the least recently invoked component are dropped.
+    const MAX_TRACKED_COMPONENTS: usize = 1000;
+
+    /// The function invocations with an unparseable name are counted under this name
+    const INVALID_FUNCTION_LABEL: &str = "<invalid>";
+
+    lazy_static! {
+        static ref FUNCTION_STATS: Mutex<HashMap<ComponentId, ComponentFunctionStats>> =
+            Mutex::new(HashMap::new());
+        static ref FUNCTION_INVOCATION_TOTAL: IntCounterVec = register_int_counter_vec!(
+            "function_invocation_total",
+            "Number of live invocations of exported functions",
+            &["component_id", "function", "outcome"]
+        )
+        .unwrap();
+        static ref FUNCTION_INVOCATION_SECONDS: HistogramVec = register_histogram_vec!(
+            "function_invocation_seconds",
+            "Time taken by live invocations of exported functions",
+            &["component_id", "function"],
+            DEFAULT_TIME_BUCKETS.to_vec()
+        )
+        .unwrap();
+        static ref FUNCTION_CONSUMED_FUEL_TOTAL: IntCounterVec = register_int_counter_vec!(
+            "function_consumed_fuel_total",
+            "Amount of fuel consumed by live invocations of exported functions",
+            &["component_id", "function"]
+        )
+        .unwrap();
+    }
+
+    /// Statistics of the live invocations of an exported function since the executor started
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FunctionStats {
+        pub invocations: u64,
+        pub failures: u64,
+        pub consumed_fuel: u64,
+        pub total_duration: Duration,
+        /// Number of invocations by the first bucket of `DEFAULT_TIME_BUCKETS` their duration
+        /// fits in, with an additional last bucket for the longer ones
+        pub latency_buckets: Vec<u64>,
+    }
+
+    impl Default for FunctionStats {
+        fn default() -> Self {
+            Self {
+                invocations: 0,
+                failures: 0,
+                consumed_fuel: 0,
+                total_duration: Duration::ZERO,
+                latency_buckets: vec![0; DEFAULT_TIME_BUCKETS.len() + 1],
+            }
+        }
+    }
+
+    struct ComponentFunctionStats {
+        functions: HashMap<String, FunctionStats>,
+        last_invocation: Instant,
+    }
+
+    impl FunctionStats {
+        fn record(&mut self, duration: Duration, consumed_fuel: i64, failed: bool) {
+            self.invocations += 1;
+            if failed {
+                self.failures += 1;
+            }
+            self.consumed_fuel += consumed_fuel.max(0) as u64;
+            self.total_duration += duration;
+            let bucket = DEFAULT_TIME_BUCKETS
+                .iter()
+                .position(|bound| duration.as_secs_f64() <= *bound)
+                .unwrap_or(DEFAULT_TIME_BUCKETS.len());
+            self.latency_buckets[bucket] += 1;
+        }
+    }
+
+    pub fn record_function_invocation(
+        component_id: &ComponentId,
+        full_function_name: &str,
+        duration: Duration,
+        consumed_fuel: i64,
+        failed: bool,
+    ) {
+        let function = function_label(full_function_name);
+
+        let evicted = {
+            let mut all_stats = FUNCTION_STATS.lock().unwrap();
+            let evicted = if all_stats.contains_key(component_id) {
+                None
+            } else {
+                evict_least_recently_invoked(&mut all_stats, MAX_TRACKED_COMPONENTS - 1)
+            };
+            let component_stats =
+                all_stats
+                    .entry(component_id.clone())
+                    .or_insert_with(|| ComponentFunctionStats {
+                        functions: HashMap::new(),
+                        last_invocation: Instant::now(),
+                    });
+            component_stats.last_invocation = Instant::now();
+            component_stats
+                .functions
+                .entry(function.clone())
+                .or_default()
+                .record(duration, consumed_fuel, failed);
+            evicted
+        };
+        if let Some((evicted_component_id, evicted_functions)) = evicted {
+            remove_series(&evicted_component_id, &evicted_functions);
+        }
+
+        let component_id = component_id.to_string();
+        let outcome = if failed { "failed" } else { "success" };
+        FUNCTION_INVOCATION_TOTAL
+            .with_label_values(&[&component_id, &function, outcome])
+            .inc();
+        FUNCTION_INVOCATION_SECONDS
+            .with_label_values(&[&component_id, &function])
+            .observe(duration.as_secs_f64());
+        FUNCTION_CONSUMED_FUEL_TOTAL
+            .with_label_values(&[&component_id, &function])
+            .inc_by(consumed_fuel.max(0) as u64);
+    }
+
+    /// The statistics of the invoked functions of a component, by function name
+    pub fn get_function_stats(component_id: &ComponentId) -> HashMap<String, FunctionStats> {
+        FUNCTION_STATS
+            .lock()
+            .unwrap()
+            .get(component_id)
+            .map(|stats| stats.functions.clone())
+            .unwrap_or_default()
+    }
+
+    /// The name the invocations of a function are counted under. Methods of indexed resources
+    /// are counted without the resource parameters, so the number of names is bounded by the
+    /// exports of the component.
+    fn function_label(full_function_name: &str) -> String {
+        let Ok(parsed) = ParsedFunctionName::parse(full_function_name) else {
+            return INVALID_FUNCTION_LABEL.to_string();
+        };
+        let function = match parsed.function().clone() {
+            ParsedFunctionReference::IndexedResourceConstructor { resource, .. } => {
+                ParsedFunctionReference::RawResourceConstructor { resource }
+            }
+            ParsedFunctionReference::IndexedResourceMethod {
+                resource, method, ..
+            } => ParsedFunctionReference::RawResourceMethod { resource, method },
+            ParsedFunctionReference::IndexedResourceStaticMethod {
+                resource, method, ..
+            } => ParsedFunctionReference::RawResourceStaticMethod { resource, method },
+            ParsedFunctionReference::IndexedResourceDrop { resource, .. } => {
+                ParsedFunctionReference::RawResourceDrop { resource }
+            }
+            function => function,
+        };
+        ParsedFunctionName::new(parsed.site().clone(), function).to_string()
+    }
+
+    /// Drops the statistics of the least recently invoked component if more than `max` are
+    /// kept, returning its id and the names of its functions
+    fn evict_least_recently_invoked(
+        all_stats: &mut HashMap<ComponentId, ComponentFunctionStats>,
+        max: usize,
+    ) -> Option<(ComponentId, Vec<String>)> {
+        if all_stats.len() <= max {
+            return None;
+        }
+        let component_id = all_stats
+            .iter()
+            .min_by_key(|(_, stats)| stats.last_invocation)
+            .map(|(component_id, _)| component_id.clone())?;
+        let stats = all_stats.remove(&component_id)?;
+        Some((component_id, stats.functions.into_keys().collect()))
+    }
+
+    fn remove_series(component_id: &ComponentId, functions: &[String]) {
+        let component_id = component_id.to_string();
+        for function in functions {
+            for outcome in ["failed", "success"] {
+                let _ = FUNCTION_INVOCATION_TOTAL.remove_label_values(&[
+                    &component_id,
+                    function,
+                    outcome,
+                ]);
+            }
+            let _ = FUNCTION_INVOCATION_SECONDS.remove_label_values(&[&component_id, function]);
+            let _ = FUNCTION_CONSUMED_FUEL_TOTAL.remove_label_values(&[&component_id, function]);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use test_r::test;
+
+        use std::time::Duration;
+
+        use golem_common::metrics::DEFAULT_TIME_BUCKETS;
+        use golem_common::model::ComponentId;
+
+        use std::collections::HashMap;
+        use std::time::Instant;
+
+        use crate::metrics::functions::{
+            evict_least_recently_invoked, function_label, get_function_stats,
+            record_function_invocation, ComponentFunctionStats, INVALID_FUNCTION_LABEL,
+        };
+
+        #[test]
+        fn invocations_are_counted_per_function() {
+            let component_id = ComponentId::new_v4();
+            record_function_invocation(&component_id, "f", Duration::from_millis(3), 100, false);
+            record_function_invocation(&component_id, "f", Duration::from_secs(120), 50, true);
+            record_function_invocation(&component_id, "g", Duration::from_millis(3), 10, false);
+
+            let stats = get_function_stats(&component_id);
+            let f = &stats["f"];
+            assert_eq!(stats.len(), 2);
+            assert_eq!(f.invocations, 2);
+            assert_eq!(f.failures, 1);
+            assert_eq!(f.consumed_fuel, 150);
+            assert_eq!(f.latency_buckets[1], 1);
+            assert_eq!(f.latency_buckets[DEFAULT_TIME_BUCKETS.len()], 1);
+            assert_eq!(stats["g"].invocations, 1);
+            assert!(get_function_stats(&ComponentId::new_v4()).is_empty());
+        }
+
+        #[test]
+        fn indexed_resources_are_counted_without_their_parameters() {
+            assert_eq!(
+                function_label("golem:it/api.{counter(\"first\").inc-by}"),
+                "golem:it/api.{counter.inc-by}"
+            );
+            assert_eq!(
+                function_label("golem:it/api.{counter(\"second\").inc-by}"),
+                function_label("golem:it/api.{counter.inc-by}")
+            );
+            assert_eq!(function_label("golem:it/api.{echo}"), "golem:it/api.{echo}");
+            assert_eq!(function_label("golem:it/api.{"), INVALID_FUNCTION_LABEL);
+        }
+
+        #[test]
+        fn least_recently_invoked_components_are_evicted() {
+            let started_at = Instant::now();
+            let components: Vec<ComponentId> = (0..3).map(|_| ComponentId::new_v4()).collect();
+            let mut all_stats: HashMap<ComponentId, ComponentFunctionStats> = components
+                .iter()
+                .enumerate()
+                .map(|(idx, component_id)| {
+                    (
+                        component_id.clone(),
+                        ComponentFunctionStats {
+                            functions: HashMap::from([("f".to_string(), Default::default())]),
+                            last_invocation: started_at + Duration::from_secs(idx as u64),
+                        },
+                    )
+                })
+                .collect();
+
+            assert!(evict_least_recently_invoked(&mut all_stats, 3).is_none());
+            assert_eq!(
+                evict_least_recently_invoked(&mut all_stats, 2),
+                Some((components[0].clone(), vec!["f".to_string()]))
+            );
+            assert_eq!(all_stats.len(), 2);
+            assert!(!all_stats.contains_key(&components[0]));
+        }
+    }
 }
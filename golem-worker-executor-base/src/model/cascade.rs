@@ -0,0 +1,167 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::future::Future;
+
+use golem_common::model::{WorkerDependent, WorkerId};
+
+/// Orders the workers removed by a cascading delete of `root` depth-first, each worker coming
+/// after the child workers it spawned, together with its dependents. Deleting them in this order
+/// never leaves dependents behind without the worker owning them, even if the delete stops half
+/// way. Workers reachable through more than one owner, or through an ownership cycle, are only
+/// visited once.
+pub async fn cascade_order<F, Fut>(
+    root: &WorkerId,
+    get_dependents: F,
+) -> Vec<(WorkerId, Vec<WorkerDependent>)>
+where
+    F: Fn(WorkerId) -> Fut,
+    Fut: Future<Output = Vec<WorkerDependent>>,
+{
+    let mut visited = HashSet::from([root.clone()]);
+    let mut order = Vec::new();
+    // The workers being visited, with their dependents and the position of the next one to visit
+    let mut stack = vec![(root.clone(), get_dependents(root.clone()).await, 0)];
+
+    while let Some((_, dependents, next)) = stack.last_mut() {
+        let mut next_child = None;
+        while let Some(dependent) = dependents.get(*next) {
+            *next += 1;
+            if let WorkerDependent::ChildWorker(child_id) = dependent {
+                if visited.insert(child_id.clone()) {
+                    next_child = Some(child_id.clone());
+                    break;
+                }
+            }
+        }
+
+        match next_child {
+            Some(child_id) => {
+                let dependents = get_dependents(child_id.clone()).await;
+                stack.push((child_id, dependents, 0));
+            }
+            None => {
+                if let Some((worker_id, dependents, _)) = stack.pop() {
+                    order.push((worker_id, dependents));
+                }
+            }
+        }
+    }
+
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+
+    use golem_common::model::oplog::OplogIndex;
+    use golem_common::model::{ComponentId, PromiseId, WorkerDependent, WorkerId};
+    use uuid::Uuid;
+
+    use crate::model::cascade::cascade_order;
+
+    fn worker_ids(names: &[&str]) -> HashMap<String, WorkerId> {
+        let component_id = ComponentId(Uuid::new_v4());
+        names
+            .iter()
+            .map(|name| {
+                (
+                    name.to_string(),
+                    WorkerId {
+                        component_id: component_id.clone(),
+                        worker_name: name.to_string(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    async fn cascade(
+        root: &WorkerId,
+        dependents: &HashMap<WorkerId, Vec<WorkerDependent>>,
+    ) -> Vec<(WorkerId, Vec<WorkerDependent>)> {
+        cascade_order(root, |worker_id| async move {
+            dependents.get(&worker_id).cloned().unwrap_or_default()
+        })
+        .await
+    }
+
+    fn names(order: &[(WorkerId, Vec<WorkerDependent>)]) -> Vec<&str> {
+        order
+            .iter()
+            .map(|(worker_id, _)| worker_id.worker_name.as_str())
+            .collect()
+    }
+
+    #[test]
+    async fn children_are_deleted_before_their_parent() {
+        let ids = worker_ids(&["root", "a", "a1", "b"]);
+        let promise = WorkerDependent::Promise(PromiseId {
+            worker_id: ids["root"].clone(),
+            oplog_idx: OplogIndex::from_u64(3),
+        });
+        let dependents = HashMap::from([
+            (
+                ids["root"].clone(),
+                vec![
+                    WorkerDependent::ChildWorker(ids["a"].clone()),
+                    promise.clone(),
+                    WorkerDependent::ChildWorker(ids["b"].clone()),
+                ],
+            ),
+            (
+                ids["a"].clone(),
+                vec![WorkerDependent::ChildWorker(ids["a1"].clone())],
+            ),
+        ]);
+
+        let order = cascade(&ids["root"], &dependents).await;
+
+        assert_eq!(names(&order), vec!["a1", "a", "b", "root"]);
+        assert!(order[3].1.contains(&promise));
+    }
+
+    #[test]
+    async fn ownership_cycles_are_visited_once() {
+        let ids = worker_ids(&["root", "a", "b"]);
+        let dependents = HashMap::from([
+            (
+                ids["root"].clone(),
+                vec![
+                    WorkerDependent::ChildWorker(ids["a"].clone()),
+                    WorkerDependent::ChildWorker(ids["b"].clone()),
+                ],
+            ),
+            (
+                ids["a"].clone(),
+                vec![
+                    WorkerDependent::ChildWorker(ids["b"].clone()),
+                    WorkerDependent::ChildWorker(ids["root"].clone()),
+                ],
+            ),
+            (
+                ids["b"].clone(),
+                vec![WorkerDependent::ChildWorker(ids["a"].clone())],
+            ),
+        ]);
+
+        let order = cascade(&ids["root"], &dependents).await;
+
+        assert_eq!(names(&order), vec!["b", "a", "root"]);
+    }
+}
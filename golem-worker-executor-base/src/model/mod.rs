@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod cascade;
 pub mod public_oplog;
+pub mod redaction;
 
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 use std::sync::Arc;
@@ -23,10 +26,11 @@ use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use serde::{Deserialize, Serialize};
 use wasmtime::Trap;
 
-use golem_common::model::oplog::WorkerError;
+use golem_common::model::oplog::{WorkerError, WorkerErrorKind};
 use golem_common::model::regions::DeletedRegions;
 use golem_common::model::{
-    ComponentType, ShardAssignment, ShardId, Timestamp, WorkerId, WorkerStatusRecord,
+    ComponentType, IdempotencyKey, ShardAssignment, ShardId, Timestamp, WorkerId,
+    WorkerStatusRecord,
 };
 
 use crate::error::{GolemError, WorkerOutOfMemory};
@@ -128,22 +132,30 @@ impl From<golem_api_grpc::proto::golem::common::ResourceLimits> for CurrentResou
     }
 }
 
+/// The in-memory execution state of a worker.
+///
+/// Every state carries the idempotency keys of the pending invocations which are held in the
+/// invocation queue. The worker skips these when picking its next invocation, until they get
+/// released.
 #[derive(Clone, Debug)]
 pub enum ExecutionStatus {
     Loading {
         last_known_status: WorkerStatusRecord,
         component_type: ComponentType,
         timestamp: Timestamp,
+        held_invocations: HashSet<IdempotencyKey>,
     },
     Running {
         last_known_status: WorkerStatusRecord,
         component_type: ComponentType,
         timestamp: Timestamp,
+        held_invocations: HashSet<IdempotencyKey>,
     },
     Suspended {
         last_known_status: WorkerStatusRecord,
         component_type: ComponentType,
         timestamp: Timestamp,
+        held_invocations: HashSet<IdempotencyKey>,
     },
     Interrupting {
         interrupt_kind: InterruptKind,
@@ -151,6 +163,7 @@ pub enum ExecutionStatus {
         last_known_status: WorkerStatusRecord,
         component_type: ComponentType,
         timestamp: Timestamp,
+        held_invocations: HashSet<IdempotencyKey>,
     },
 }
 
@@ -223,6 +236,40 @@ impl ExecutionStatus {
             }
         }
     }
+
+    pub fn held_invocations(&self) -> &HashSet<IdempotencyKey> {
+        match self {
+            ExecutionStatus::Loading {
+                held_invocations, ..
+            } => held_invocations,
+            ExecutionStatus::Running {
+                held_invocations, ..
+            } => held_invocations,
+            ExecutionStatus::Suspended {
+                held_invocations, ..
+            } => held_invocations,
+            ExecutionStatus::Interrupting {
+                held_invocations, ..
+            } => held_invocations,
+        }
+    }
+
+    pub fn held_invocations_mut(&mut self) -> &mut HashSet<IdempotencyKey> {
+        match self {
+            ExecutionStatus::Loading {
+                held_invocations, ..
+            } => held_invocations,
+            ExecutionStatus::Running {
+                held_invocations, ..
+            } => held_invocations,
+            ExecutionStatus::Suspended {
+                held_invocations, ..
+            } => held_invocations,
+            ExecutionStatus::Interrupting {
+                held_invocations, ..
+            } => held_invocations,
+        }
+    }
 }
 
 /// Describes the various reasons a worker can run into a trap
@@ -250,7 +297,7 @@ impl TrapType {
                             Some(GolemError::InvalidRequest { details }) => {
                                 TrapType::Error(WorkerError::InvalidRequest(details.clone()))
                             }
-                            _ => TrapType::Error(WorkerError::Unknown(format!("{:#}", error))),
+                            _ => TrapType::Error(classify_error(error)),
                         },
                     },
                 },
@@ -273,6 +320,27 @@ impl TrapType {
     }
 }
 
+/// Records the cause of a failure together with its message, so the kind of the error does not
+/// have to be guessed from the message later
+fn classify_error(error: &anyhow::Error) -> WorkerError {
+    let message = format!("{:#}", error);
+    let root_cause = error.root_cause();
+    if root_cause.is::<Trap>() {
+        WorkerError::Trap(message)
+    } else if root_cause.is::<tokio::time::error::Elapsed>()
+        || matches!(
+            root_cause.downcast_ref::<GolemError>(),
+            Some(GolemError::InvocationTimedOut { .. })
+        )
+    {
+        WorkerError::Timeout(message)
+    } else if root_cause.is::<fred::error::RedisError>() || root_cause.is::<sqlx::Error>() {
+        WorkerError::StorageFailure(message)
+    } else {
+        WorkerError::Unknown(message)
+    }
+}
+
 /// Encapsulates a worker error with the number of retries already attempted.
 ///
 /// This can be calculated by reading the (end of the) oplog, and passed around for making
@@ -295,6 +363,52 @@ impl Display for LastError {
     }
 }
 
+/// Workers of a component whose last errors have the same fingerprint
+#[derive(Clone, Debug)]
+pub struct ErrorGroup {
+    pub fingerprint: String,
+    pub kind: WorkerErrorKind,
+    /// The error of the first worker of the group
+    pub example: String,
+    pub worker_count: u64,
+    /// The first few workers of the group
+    pub workers: Vec<WorkerId>,
+}
+
+impl ErrorGroup {
+    pub const MAX_LISTED_WORKERS: usize = 10;
+
+    /// Groups the last errors of workers by their fingerprints, the groups with the most
+    /// workers first.
+    pub fn group(errors: impl IntoIterator<Item = (WorkerId, LastError)>) -> Vec<ErrorGroup> {
+        let mut groups: HashMap<String, ErrorGroup> = HashMap::new();
+        for (worker_id, last_error) in errors {
+            let fingerprint = last_error.error.fingerprint();
+            let group = groups
+                .entry(fingerprint.clone())
+                .or_insert_with(|| ErrorGroup {
+                    fingerprint,
+                    kind: last_error.error.kind(),
+                    example: last_error.error.to_string(&last_error.stderr),
+                    worker_count: 0,
+                    workers: Vec::new(),
+                });
+            group.worker_count += 1;
+            if group.workers.len() < Self::MAX_LISTED_WORKERS {
+                group.workers.push(worker_id);
+            }
+        }
+
+        let mut groups = groups.into_values().collect::<Vec<_>>();
+        groups.sort_by(|a, b| {
+            b.worker_count
+                .cmp(&a.worker_count)
+                .then_with(|| a.fingerprint.cmp(&b.fingerprint))
+        });
+        groups
+    }
+}
+
 #[derive(Clone, Debug, PartialOrd, PartialEq)]
 pub enum PersistenceLevel {
     PersistNothing,
@@ -365,4 +479,54 @@ mod tests {
         println!("hash: {:?}", hash);
         assert_eq!(hash, -6692039695739768661);
     }
+
+    #[test]
+    fn errors_are_classified_by_their_root_cause() {
+        let trap =
+            anyhow::Error::new(Trap::UnreachableCodeReached).context("error while executing");
+        let timeout = anyhow::Error::new(GolemError::InvocationTimedOut {
+            timeout_millis: 100,
+        });
+        let other = anyhow::anyhow!("Remote call timed out");
+
+        assert_eq!(classify_error(&trap).kind(), WorkerErrorKind::GuestTrap);
+        assert_eq!(classify_error(&timeout).kind(), WorkerErrorKind::Timeout);
+        assert_eq!(classify_error(&other).kind(), WorkerErrorKind::HostError);
+        assert_eq!(
+            classify_error(&trap).to_string(""),
+            "error while executing: wasm trap: wasm `unreachable` instruction executed"
+        );
+    }
+
+    #[test]
+    fn error_groups() {
+        let component_id = ComponentId(Uuid::new_v4());
+        let errors = (0..5).map(|i| {
+            let error = if i % 2 == 0 {
+                WorkerError::Unknown(format!("Failed to process order {i}"))
+            } else {
+                WorkerError::OutOfMemory
+            };
+            (
+                WorkerId {
+                    component_id: component_id.clone(),
+                    worker_name: format!("worker-{i}"),
+                },
+                LastError {
+                    error,
+                    stderr: "".to_string(),
+                    retry_count: 0,
+                },
+            )
+        });
+
+        let groups = ErrorGroup::group(errors);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].worker_count, 3);
+        assert_eq!(groups[0].kind, WorkerErrorKind::HostError);
+        assert_eq!(groups[0].example, "Failed to process order 0");
+        assert_eq!(groups[0].workers.len(), 3);
+        assert_eq!(groups[1].worker_count, 2);
+        assert_eq!(groups[1].kind, WorkerErrorKind::OutOfMemory);
+    }
 }
@@ -38,13 +38,16 @@ use golem_api_grpc::proto::golem::worker::UpdateMode;
 use golem_common::model::exports::{find_resource_site, function_by_name};
 use golem_common::model::oplog::{OplogEntry, OplogIndex, UpdateDescription};
 use golem_common::model::public_oplog::{
-    ChangeRetryPolicyParameters, CreateParameters, DescribeResourceParameters, Empty,
-    EndRegionParameters, ErrorParameters, ExportedFunctionCompletedParameters,
-    ExportedFunctionInvokedParameters, ExportedFunctionParameters, FailedUpdateParameters,
-    GrowMemoryParameters, ImportedFunctionInvokedParameters, JumpParameters, LogParameters,
-    ManualUpdateParameters, PendingUpdateParameters, PendingWorkerInvocationParameters,
-    PublicOplogEntry, PublicUpdateDescription, PublicWorkerInvocation, ResourceParameters,
-    SnapshotBasedUpdateParameters, SuccessfulUpdateParameters, TimestampParameter,
+    CancelInvocationParameters, ChangeEnvironmentParameters, ChangeRetryPolicyParameters,
+    CreateParameters, DescribeResourceParameters, Empty, EndRegionParameters, ErrorParameters,
+    ExportedFunctionCompletedParameters, ExportedFunctionInvokedParameters,
+    ExportedFunctionParameters, FailedUpdateParameters, GrowMemoryParameters,
+    HoldInvocationParameters, ImportedFunctionInvokedParameters, InvocationTimedOutParameters,
+    JumpParameters, LogParameters, ManualUpdateParameters, PendingUpdateParameters,
+    PendingWorkerInvocationParameters, PublicOplogEntry, PublicUpdateDescription,
+    PublicWorkerInvocation, RedactedParameters, ReleaseInvocationParameters, ResourceParameters,
+    SnapshotBasedUpdateParameters, SuccessfulUpdateParameters, SwitchInvocationParameters,
+    TimestampParameter,
 };
 use golem_common::model::{
     ComponentId, ComponentVersion, IdempotencyKey, OwnedWorkerId, PromiseId, ShardId, WorkerId,
@@ -238,11 +241,18 @@ impl PublicOplogEntryOps for PublicOplogEntry {
                     },
                 ))
             }
-            OplogEntry::ExportedFunctionInvoked {
+            OplogEntry::ExportedFunctionInvokedV1 {
                 timestamp,
                 function_name,
                 request,
                 idempotency_key,
+            }
+            | OplogEntry::ExportedFunctionInvoked {
+                timestamp,
+                function_name,
+                request,
+                idempotency_key,
+                ..
             } => {
                 let payload_bytes = oplog_service
                     .download_payload(owned_worker_id, &request)
@@ -366,10 +376,16 @@ impl PublicOplogEntryOps for PublicOplogEntry {
                 invocation,
             } => {
                 let invocation = match invocation {
-                    WorkerInvocation::ExportedFunction {
+                    WorkerInvocation::ExportedFunctionV1 {
                         idempotency_key,
                         full_function_name,
                         function_input,
+                    }
+                    | WorkerInvocation::ExportedFunction {
+                        idempotency_key,
+                        full_function_name,
+                        function_input,
+                        ..
                     } => {
                         let metadata = components
                             .get_metadata(
@@ -547,6 +563,66 @@ impl PublicOplogEntryOps for PublicOplogEntry {
             OplogEntry::Restart { timestamp } => {
                 Ok(PublicOplogEntry::Restart(TimestampParameter { timestamp }))
             }
+            OplogEntry::CancelPendingInvocation {
+                timestamp,
+                idempotency_key,
+            } => Ok(PublicOplogEntry::CancelInvocation(
+                CancelInvocationParameters {
+                    timestamp,
+                    idempotency_key,
+                },
+            )),
+            OplogEntry::ChangeEnvironment { timestamp, env } => Ok(
+                PublicOplogEntry::ChangeEnvironment(ChangeEnvironmentParameters {
+                    timestamp,
+                    env: env.into_iter().collect(),
+                }),
+            ),
+            OplogEntry::Redacted {
+                timestamp,
+                entries,
+                reason,
+            } => Ok(PublicOplogEntry::Redacted(RedactedParameters {
+                timestamp,
+                entries,
+                reason,
+            })),
+            OplogEntry::InvocationTimedOut {
+                timestamp,
+                idempotency_key,
+                timeout,
+            } => Ok(PublicOplogEntry::InvocationTimedOut(
+                InvocationTimedOutParameters {
+                    timestamp,
+                    idempotency_key,
+                    timeout,
+                },
+            )),
+            OplogEntry::HoldPendingInvocation {
+                timestamp,
+                idempotency_key,
+            } => Ok(PublicOplogEntry::HoldInvocation(HoldInvocationParameters {
+                timestamp,
+                idempotency_key,
+            })),
+            OplogEntry::ReleasePendingInvocation {
+                timestamp,
+                idempotency_key,
+            } => Ok(PublicOplogEntry::ReleaseInvocation(
+                ReleaseInvocationParameters {
+                    timestamp,
+                    idempotency_key,
+                },
+            )),
+            OplogEntry::SwitchInvocation {
+                timestamp,
+                idempotency_key,
+            } => Ok(PublicOplogEntry::SwitchInvocation(
+                SwitchInvocationParameters {
+                    timestamp,
+                    idempotency_key,
+                },
+            )),
         }
     }
 }
@@ -789,6 +865,39 @@ fn encode_host_function_request_as_value(
             let payload: (String, Vec<String>) = try_deserialize(bytes)?;
             Ok(bucket_and_keys(payload.0, payload.1))
         }
+        "golem::api::keyvalue::commit" => {
+            let payload: (String, Vec<String>, Vec<String>) = try_deserialize(bytes)?;
+            Ok(ValueAndType::new(
+                Value::Record(vec![
+                    Value::String(payload.0),
+                    Value::List(payload.1.into_iter().map(Value::String).collect()),
+                    Value::List(payload.2.into_iter().map(Value::String).collect()),
+                ]),
+                record(vec![
+                    field("bucket", str()),
+                    field("checked_keys", list(str())),
+                    field("written_keys", list(str())),
+                ]),
+            ))
+        }
+        "golem::api::keyvalue::set-with-ttl" => {
+            let payload: (String, String, u64, u64) = try_deserialize(bytes)?;
+            Ok(ValueAndType::new(
+                Value::Record(vec![
+                    Value::String(payload.0),
+                    Value::String(payload.1),
+                    Value::U64(payload.2),
+                    Value::U64(payload.3),
+                ]),
+                record(vec![
+                    field("bucket", str()),
+                    field("key", str()),
+                    field("value", u64()),
+                    field("ttl_millis", u64()),
+                ]),
+            ))
+        }
+        "golem::api::feature-flags::resolve" => no_payload(),
         "golem random::insecure::get_insecure_random_bytes" => no_payload(),
         "golem random::insecure::get_insecure_random_u64" => no_payload(),
         "golem random::insecure_seed::insecure_seed" => no_payload(),
@@ -1049,6 +1158,18 @@ fn encode_host_function_response_as_value(
             let payload: Result<(), SerializableError> = try_deserialize(bytes)?;
             Ok(payload.into_value_and_type())
         }
+        "golem::api::keyvalue::commit" => {
+            let payload: Result<bool, SerializableError> = try_deserialize(bytes)?;
+            Ok(payload.into_value_and_type())
+        }
+        "golem::api::keyvalue::set-with-ttl" => {
+            let payload: Result<(), SerializableError> = try_deserialize(bytes)?;
+            Ok(payload.into_value_and_type())
+        }
+        "golem::api::feature-flags::resolve" => {
+            let payload: Result<Vec<(String, bool)>, SerializableError> = try_deserialize(bytes)?;
+            Ok(payload.into_value_and_type())
+        }
         "golem random::insecure::get_insecure_random_bytes" => {
             let payload: Result<Vec<u8>, SerializableError> = try_deserialize(bytes)?;
             Ok(payload.into_value_and_type())
@@ -1765,7 +1886,23 @@ impl IntoValue for GolemError {
                 GolemError::PermissionsNotSet => Value::Variant {
                     case_idx: 23,
                     case_value: None
-                }
+                },
+                GolemError::ResultTooLarge {
+                    size,
+                    limit,
+                    blob_key,
+                } => Value::Variant {
+                    case_idx: 24,
+                    case_value: Some(Box::new(Value::Record(vec![
+                        size.into_value(),
+                        limit.into_value(),
+                        blob_key.into_value(),
+                    ]))),
+                },
+                GolemError::InvocationTimedOut { timeout_millis } => Value::Variant {
+                    case_idx: 25,
+                    case_value: Some(Box::new(Value::Record(vec![timeout_millis.into_value()]))),
+                },
             }
         }
         into_value(self, true)
@@ -1859,6 +1996,19 @@ impl IntoValue for GolemError {
                 unit_case("PreviousInvocationExited"),
                 case("Unknown", record(vec![field("details", str())])),
                 unit_case("ShardingNotReady"),
+                unit_case("PermissionsNotSet"),
+                case(
+                    "ResultTooLarge",
+                    record(vec![
+                        field("size", u64()),
+                        field("limit", u64()),
+                        field("blob_key", str()),
+                    ]),
+                ),
+                case(
+                    "InvocationTimedOut",
+                    record(vec![field("timeout_millis", u64())]),
+                ),
             ])
         }
         get_type(true)
@@ -1907,6 +2057,10 @@ impl IntoValue for WorkerProxyError {
                 case_idx: 5,
                 case_value: Some(Box::new(error.into_value())),
             },
+            WorkerProxyError::Unavailable(error) => Value::Variant {
+                case_idx: 6,
+                case_value: Some(Box::new(error.into_value())),
+            },
         }
     }
 
@@ -1918,6 +2072,7 @@ impl IntoValue for WorkerProxyError {
             case("NotFound", str()),
             case("AlreadyExists", str()),
             case("InternalError", GolemError::get_type()),
+            case("Unavailable", str()),
         ])
     }
 }
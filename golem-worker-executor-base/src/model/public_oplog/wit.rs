@@ -16,14 +16,16 @@ use crate::model::public_oplog::{PublicOplogEntry, PublicUpdateDescription};
 use crate::preview2::golem::api1_1_0_rc1::oplog;
 use crate::preview2::wasi::clocks::wall_clock::Datetime;
 use golem_common::model::public_oplog::{
-    ChangeRetryPolicyParameters, CreateParameters, DescribeResourceParameters, EndRegionParameters,
-    ErrorParameters, ExportedFunctionCompletedParameters, ExportedFunctionInvokedParameters,
+    CancelInvocationParameters, ChangeEnvironmentParameters, ChangeRetryPolicyParameters,
+    CreateParameters, DescribeResourceParameters, EndRegionParameters, ErrorParameters,
+    ExportedFunctionCompletedParameters, ExportedFunctionInvokedParameters,
     ExportedFunctionParameters, FailedUpdateParameters, GrowMemoryParameters,
-    ImportedFunctionInvokedParameters, JumpParameters, LogParameters, ManualUpdateParameters,
-    PendingUpdateParameters, PendingWorkerInvocationParameters, PublicRetryConfig,
-    PublicWorkerInvocation, PublicWrappedFunctionType, ResourceParameters,
-    SnapshotBasedUpdateParameters, SuccessfulUpdateParameters, TimestampParameter,
-    WriteRemoteBatchedParameters,
+    HoldInvocationParameters, ImportedFunctionInvokedParameters, InvocationTimedOutParameters,
+    JumpParameters, LogParameters, ManualUpdateParameters, PendingUpdateParameters,
+    PendingWorkerInvocationParameters, PublicRetryConfig, PublicWorkerInvocation,
+    PublicWrappedFunctionType, RedactedParameters, ReleaseInvocationParameters, ResourceParameters,
+    SnapshotBasedUpdateParameters, SuccessfulUpdateParameters, SwitchInvocationParameters,
+    TimestampParameter, WriteRemoteBatchedParameters,
 };
 use golem_common::model::Timestamp;
 
@@ -218,6 +220,35 @@ impl From<PublicOplogEntry> for oplog::OplogEntry {
             PublicOplogEntry::Restart(TimestampParameter { timestamp }) => {
                 Self::Restart(timestamp.into())
             }
+            // The oplog interface of golem:api has no case for cancelled invocations, and they
+            // have no effect on the execution of the worker
+            PublicOplogEntry::CancelInvocation(CancelInvocationParameters {
+                timestamp, ..
+            }) => Self::NoOp(timestamp.into()),
+            // Environment changes only take effect when the worker is restarted, and the oplog
+            // interface of golem:api has no case for them either
+            PublicOplogEntry::ChangeEnvironment(ChangeEnvironmentParameters {
+                timestamp, ..
+            }) => Self::NoOp(timestamp.into()),
+            PublicOplogEntry::Redacted(RedactedParameters { timestamp, .. }) => {
+                Self::NoOp(timestamp.into())
+            }
+            // The oplog interface of golem:api has no case for timeouts, and the interruption of
+            // the timed out invocation is recorded by its own entry
+            PublicOplogEntry::InvocationTimedOut(InvocationTimedOutParameters {
+                timestamp,
+                ..
+            }) => Self::NoOp(timestamp.into()),
+            // Holds only change the order in which pending invocations are executed, and the
+            // oplog interface of golem:api has no case for them
+            PublicOplogEntry::HoldInvocation(HoldInvocationParameters { timestamp, .. })
+            | PublicOplogEntry::ReleaseInvocation(ReleaseInvocationParameters {
+                timestamp, ..
+            }) => Self::NoOp(timestamp.into()),
+            // The oplog interface of golem:api has no case for interleaved invocations yet
+            PublicOplogEntry::SwitchInvocation(SwitchInvocationParameters {
+                timestamp, ..
+            }) => Self::NoOp(timestamp.into()),
         }
     }
 }
@@ -0,0 +1,300 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Redaction of oplog entries which recorded data that must not be kept, such as personal data
+//! logged by mistake.
+//!
+//! The recorded values are replaced by placeholder values of the same type, so the redacted
+//! entries can still be decoded and shown in the public oplog. Only the entries which do not
+//! affect how the worker gets replayed can be redacted:
+//! - log messages, which are only hints
+//! - the results of exported functions, which are only compared with the recomputed ones during
+//!   replay, and the comparison is skipped for redacted entries
+//! - the parameters of exported functions, but only if they are in a deleted region and never
+//!   get replayed
+
+use std::sync::Arc;
+
+use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogPayload};
+use golem_common::model::regions::DeletedRegions;
+use golem_common::model::OwnedWorkerId;
+use golem_common::serialization::{serialize, try_deserialize};
+use golem_wasm_ast::analysis::AnalysedType;
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use golem_wasm_rpc::Value;
+
+use crate::services::oplog::OplogService;
+
+/// The text replacing redacted strings and log messages
+pub const REDACTED_TEXT: &str = "<redacted>";
+
+/// Replaces the data in `value` by placeholder values of the same type. Strings are replaced by
+/// [`REDACTED_TEXT`], numbers by zero, lists by empty lists, options by `none` and flags are all
+/// cleared. The cases of variants, enums and results are kept, as they usually do not hold the
+/// sensitive data but their type depends on them.
+pub fn redact_value(value: Value) -> Value {
+    match value {
+        Value::Bool(_) => Value::Bool(false),
+        Value::U8(_) => Value::U8(0),
+        Value::U16(_) => Value::U16(0),
+        Value::U32(_) => Value::U32(0),
+        Value::U64(_) => Value::U64(0),
+        Value::S8(_) => Value::S8(0),
+        Value::S16(_) => Value::S16(0),
+        Value::S32(_) => Value::S32(0),
+        Value::S64(_) => Value::S64(0),
+        Value::F32(_) => Value::F32(0.0),
+        Value::F64(_) => Value::F64(0.0),
+        Value::Char(_) => Value::Char('?'),
+        Value::String(_) => Value::String(REDACTED_TEXT.to_string()),
+        Value::List(_) => Value::List(vec![]),
+        Value::Tuple(items) => Value::Tuple(items.into_iter().map(redact_value).collect()),
+        Value::Record(fields) => Value::Record(fields.into_iter().map(redact_value).collect()),
+        Value::Variant {
+            case_idx,
+            case_value,
+        } => Value::Variant {
+            case_idx,
+            case_value: case_value.map(|value| Box::new(redact_value(*value))),
+        },
+        Value::Enum(case_idx) => Value::Enum(case_idx),
+        Value::Flags(flags) => Value::Flags(vec![false; flags.len()]),
+        Value::Option(_) => Value::Option(None),
+        Value::Result(result) => Value::Result(match result {
+            Ok(value) => Ok(value.map(|value| Box::new(redact_value(*value)))),
+            Err(value) => Err(value.map(|value| Box::new(redact_value(*value)))),
+        }),
+        // Handles are references to resources owned by the worker, not recorded data
+        Value::Handle { uri, resource_id } => Value::Handle { uri, resource_id },
+    }
+}
+
+/// Checks that the entry at `idx` can be redacted without changing how the worker gets replayed
+pub fn ensure_redactable(
+    idx: OplogIndex,
+    entry: &OplogEntry,
+    deleted_regions: &DeletedRegions,
+) -> Result<(), String> {
+    match entry {
+        OplogEntry::Log { .. } | OplogEntry::ExportedFunctionCompleted { .. } => Ok(()),
+        OplogEntry::ExportedFunctionInvokedV1 { .. }
+        | OplogEntry::ExportedFunctionInvoked { .. } => {
+            if deleted_regions.is_in_deleted_region(idx) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Oplog entry {idx} is an invocation which is replayed when the worker recovers, so its parameters cannot be redacted"
+                ))
+            }
+        }
+        _ => Err(format!(
+            "Oplog entry {idx} does not hold data which can be redacted"
+        )),
+    }
+}
+
+/// Replaces the recorded data of an entry accepted by [`ensure_redactable`] by placeholder
+/// values. Returns the redacted entry, and the payload of the original entry which is no longer
+/// referenced by it.
+pub async fn redact_oplog_entry(
+    oplog_service: &Arc<dyn OplogService + Send + Sync>,
+    owned_worker_id: &OwnedWorkerId,
+    entry: OplogEntry,
+) -> Result<(OplogEntry, Option<OplogPayload>), String> {
+    match entry {
+        OplogEntry::Log {
+            timestamp,
+            level,
+            context,
+            ..
+        } => Ok((
+            OplogEntry::Log {
+                timestamp,
+                level,
+                context,
+                message: REDACTED_TEXT.to_string(),
+            },
+            None,
+        )),
+        OplogEntry::ExportedFunctionInvokedV1 {
+            timestamp,
+            function_name,
+            request,
+            idempotency_key,
+        } => {
+            let redacted_request =
+                redact_invocation_request(oplog_service, owned_worker_id, &request).await?;
+            Ok((
+                OplogEntry::ExportedFunctionInvokedV1 {
+                    timestamp,
+                    function_name,
+                    request: redacted_request,
+                    idempotency_key,
+                },
+                Some(request),
+            ))
+        }
+        OplogEntry::ExportedFunctionInvoked {
+            timestamp,
+            function_name,
+            request,
+            idempotency_key,
+            caller,
+        } => {
+            let redacted_request =
+                redact_invocation_request(oplog_service, owned_worker_id, &request).await?;
+            Ok((
+                OplogEntry::ExportedFunctionInvoked {
+                    timestamp,
+                    function_name,
+                    request: redacted_request,
+                    idempotency_key,
+                    caller,
+                },
+                Some(request),
+            ))
+        }
+        OplogEntry::ExportedFunctionCompleted {
+            timestamp,
+            response,
+            consumed_fuel,
+        } => {
+            let bytes = oplog_service
+                .download_payload(owned_worker_id, &response)
+                .await?;
+            let Some(result) = try_deserialize::<TypeAnnotatedValue>(&bytes)? else {
+                // Functions without results have nothing to redact
+                return Ok((
+                    OplogEntry::ExportedFunctionCompleted {
+                        timestamp,
+                        response,
+                        consumed_fuel,
+                    },
+                    None,
+                ));
+            };
+            let typ = AnalysedType::try_from(&result)?;
+            let value = redact_value(Value::try_from(result)?);
+            let redacted =
+                TypeAnnotatedValue::create(&value, &typ).map_err(|errors| errors.join(", "))?;
+
+            let redacted_response = oplog_service
+                .upload_payload(owned_worker_id, &serialize(&redacted)?)
+                .await?;
+            Ok((
+                OplogEntry::ExportedFunctionCompleted {
+                    timestamp,
+                    response: redacted_response,
+                    consumed_fuel,
+                },
+                Some(response),
+            ))
+        }
+        _ => Err("The oplog entry does not hold data which can be redacted".to_string()),
+    }
+}
+
+/// Uploads the redacted version of the function parameters of an invocation
+async fn redact_invocation_request(
+    oplog_service: &Arc<dyn OplogService + Send + Sync>,
+    owned_worker_id: &OwnedWorkerId,
+    request: &OplogPayload,
+) -> Result<OplogPayload, String> {
+    let bytes = oplog_service
+        .download_payload(owned_worker_id, request)
+        .await?;
+    let params: Vec<golem_wasm_rpc::protobuf::Val> = try_deserialize(&bytes)?.unwrap_or_default();
+    let redacted_params = params
+        .into_iter()
+        .map(|param| Value::try_from(param).map(|value| redact_value(value).into()))
+        .collect::<Result<Vec<golem_wasm_rpc::protobuf::Val>, _>>()?;
+
+    oplog_service
+        .upload_payload(owned_worker_id, &serialize(&redacted_params)?)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::oplog::{LogLevel, OplogEntry, OplogIndex, OplogPayload};
+    use golem_common::model::regions::{DeletedRegions, OplogRegion};
+    use golem_common::model::IdempotencyKey;
+    use golem_wasm_rpc::Value;
+
+    use crate::model::redaction::{ensure_redactable, redact_value, REDACTED_TEXT};
+
+    #[test]
+    fn redacted_values_keep_their_shape() {
+        let value = Value::Record(vec![
+            Value::String("jane.doe@example.com".to_string()),
+            Value::U32(42),
+            Value::List(vec![Value::Char('a'), Value::Char('b')]),
+            Value::Option(Some(Box::new(Value::String("secret".to_string())))),
+            Value::Variant {
+                case_idx: 2,
+                case_value: Some(Box::new(Value::Tuple(vec![
+                    Value::Bool(true),
+                    Value::F64(1.5),
+                ]))),
+            },
+            Value::Result(Err(Some(Box::new(Value::S64(-7))))),
+            Value::Flags(vec![true, false, true]),
+            Value::Enum(3),
+        ]);
+
+        let expected = Value::Record(vec![
+            Value::String(REDACTED_TEXT.to_string()),
+            Value::U32(0),
+            Value::List(vec![]),
+            Value::Option(None),
+            Value::Variant {
+                case_idx: 2,
+                case_value: Some(Box::new(Value::Tuple(vec![
+                    Value::Bool(false),
+                    Value::F64(0.0),
+                ]))),
+            },
+            Value::Result(Err(Some(Box::new(Value::S64(0))))),
+            Value::Flags(vec![false, false, false]),
+            Value::Enum(3),
+        ]);
+
+        assert_eq!(redact_value(value), expected);
+    }
+
+    #[test]
+    fn only_entries_not_affecting_replay_are_redactable() {
+        let deleted_regions = DeletedRegions::from_regions(vec![OplogRegion::from_range(2..=3)]);
+        let log = OplogEntry::log(LogLevel::Info, "stdout".to_string(), "hello".to_string());
+        let invoked = OplogEntry::ExportedFunctionInvoked {
+            timestamp: log.timestamp(),
+            function_name: "golem:it/api.{add-item}".to_string(),
+            request: OplogPayload::Inline(vec![]),
+            idempotency_key: IdempotencyKey::fresh(),
+            caller: None,
+        };
+
+        assert!(ensure_redactable(OplogIndex::from_u64(5), &log, &deleted_regions).is_ok());
+        assert!(ensure_redactable(OplogIndex::from_u64(3), &invoked, &deleted_regions).is_ok());
+        assert!(ensure_redactable(OplogIndex::from_u64(5), &invoked, &deleted_regions).is_err());
+        assert!(ensure_redactable(
+            OplogIndex::from_u64(5),
+            &OplogEntry::restart(),
+            &deleted_regions
+        )
+        .is_err());
+    }
+}
@@ -22,6 +22,25 @@ pub type OutputStream = wasmtime_wasi::OutputStream;
 
 pub type Pollable = wasmtime_wasi::Pollable;
 
+include!(concat!(env!("OUT_DIR"), "/preview2_extensions_mod.rs"));
+
+/// Bindings of the `wasi:nn` interfaces, defined by this crate
+pub mod nn {
+    wasmtime::component::bindgen!({
+        path: "wit/nn.wit",
+        world: "ml",
+        tracing: false,
+        async: true,
+        trappable_imports: true,
+        with: {
+            "wasi:nn/tensor/tensor": crate::durable_host::nn::TensorEntry,
+            "wasi:nn/graph/graph": crate::durable_host::nn::GraphEntry,
+            "wasi:nn/inference/graph-execution-context": crate::durable_host::nn::ExecutionContextEntry,
+            "wasi:nn/errors/error": crate::durable_host::nn::ErrorEntry,
+        },
+    });
+}
+
 impl From<golem_wasm_rpc::WitValue> for golem::rpc::types::WitValue {
     fn from(value: golem_wasm_rpc::WitValue) -> Self {
         unsafe { mem::transmute(value) }
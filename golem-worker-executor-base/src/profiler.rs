@@ -0,0 +1,436 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opt-in sampling profiler of the guest code running in a worker.
+//!
+//! While profiling is enabled, the guest stack is captured every time the worker reaches its epoch
+//! deadline, so each sample stands for one epoch period of execution. The linear memory growth
+//! recorded since the previous sample is attributed to the sampled stack. The collected samples
+//! are exported in the pprof format, readable by `go tool pprof` and other compatible tools.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use prost::Message;
+use wasmtime::WasmBacktrace;
+
+/// Maximum number of frames kept from a sampled stack, counted from the innermost one
+const MAX_STACK_DEPTH: usize = 256;
+
+/// Maximum number of distinct stacks kept, samples of further stacks are only counted as dropped
+const MAX_DISTINCT_STACKS: usize = 16384;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ProfiledFrame {
+    module: Option<String>,
+    function: String,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct StackStats {
+    samples: u64,
+    memory_growth: u64,
+}
+
+#[derive(Debug)]
+struct ProfileState {
+    started_at: SystemTime,
+    stopped_at: Option<SystemTime>,
+    sample_period: Duration,
+    /// Innermost frame first
+    stacks: HashMap<Vec<ProfiledFrame>, StackStats>,
+    dropped_samples: u64,
+    pending_memory_growth: u64,
+}
+
+/// Profiling state of a single worker, shared between the worker's epoch deadline callback and
+/// the API toggling it
+#[derive(Debug, Default)]
+pub struct WorkerProfiler {
+    enabled: AtomicBool,
+    state: Mutex<Option<ProfileState>>,
+}
+
+impl WorkerProfiler {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    /// Starts a new profile, discarding the previously collected one. `sample_period` is the
+    /// amount of execution time a single sample stands for.
+    pub fn start(&self, sample_period: Duration) {
+        *self.state.lock().unwrap() = Some(ProfileState {
+            started_at: SystemTime::now(),
+            stopped_at: None,
+            sample_period,
+            stacks: HashMap::new(),
+            dropped_samples: 0,
+            pending_memory_growth: 0,
+        });
+        self.enabled.store(true, Ordering::Release);
+    }
+
+    /// Stops sampling, keeping the collected profile until the next start
+    pub fn stop(&self) {
+        self.enabled.store(false, Ordering::Release);
+        if let Some(state) = self.state.lock().unwrap().as_mut() {
+            state.stopped_at.get_or_insert_with(SystemTime::now);
+        }
+    }
+
+    /// Records a captured guest stack
+    pub fn sample(&self, backtrace: &WasmBacktrace) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let stack = backtrace
+            .frames()
+            .iter()
+            .take(MAX_STACK_DEPTH)
+            .map(|frame| ProfiledFrame {
+                module: frame.module().name().map(|name| name.to_string()),
+                function: frame
+                    .func_name()
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| format!("func[{}]", frame.func_index())),
+            })
+            .collect::<Vec<_>>();
+        if stack.is_empty() {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if let Some(state) = state.as_mut() {
+            let memory_growth = std::mem::take(&mut state.pending_memory_growth);
+            let stats = if state.stacks.len() < MAX_DISTINCT_STACKS {
+                Some(state.stacks.entry(stack).or_default())
+            } else {
+                state.stacks.get_mut(&stack)
+            };
+            match stats {
+                Some(stats) => {
+                    stats.samples += 1;
+                    stats.memory_growth += memory_growth;
+                }
+                None => state.dropped_samples += 1,
+            }
+        }
+    }
+
+    /// Records the growth of the worker's linear memory, attributed to the next sampled stack
+    pub fn record_memory_growth(&self, delta: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        if let Some(state) = self.state.lock().unwrap().as_mut() {
+            state.pending_memory_growth += delta;
+        }
+    }
+
+    /// Exports the collected samples as an uncompressed pprof profile, or `None` if profiling
+    /// was never started for this worker
+    pub fn to_pprof(&self) -> Option<Vec<u8>> {
+        let state = self.state.lock().unwrap();
+        state.as_ref().map(|state| state.to_pprof().encode_to_vec())
+    }
+}
+
+impl ProfileState {
+    fn to_pprof(&self) -> pprof::Profile {
+        let mut builder = pprof::ProfileBuilder::default();
+
+        let sample_type = vec![
+            builder.value_type("samples", "count"),
+            builder.value_type("cpu", "nanoseconds"),
+            builder.value_type("memory_growth", "bytes"),
+        ];
+        let period_type = builder.value_type("cpu", "nanoseconds");
+        let period = self.sample_period.as_nanos() as i64;
+
+        let mut sample = Vec::with_capacity(self.stacks.len());
+        for (stack, stats) in &self.stacks {
+            let location_id = stack.iter().map(|frame| builder.location(frame)).collect();
+            sample.push(pprof::Sample {
+                location_id,
+                value: vec![
+                    stats.samples as i64,
+                    stats.samples as i64 * period,
+                    stats.memory_growth as i64,
+                ],
+            });
+        }
+
+        let mut comment = Vec::new();
+        if self.dropped_samples > 0 {
+            comment.push(builder.string(&format!(
+                "{} samples dropped after reaching {MAX_DISTINCT_STACKS} distinct stacks",
+                self.dropped_samples
+            )));
+        }
+
+        let started_at = self
+            .started_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let duration = self
+            .stopped_at
+            .unwrap_or_else(SystemTime::now)
+            .duration_since(self.started_at)
+            .unwrap_or_default();
+
+        pprof::Profile {
+            sample_type,
+            sample,
+            location: builder.locations,
+            function: builder.functions,
+            string_table: builder.strings,
+            time_nanos: started_at.as_nanos() as i64,
+            duration_nanos: duration.as_nanos() as i64,
+            period_type: Some(period_type),
+            period,
+            comment,
+            default_sample_type: 1,
+        }
+    }
+}
+
+/// The subset of the pprof `perftools.profiles` protobuf schema used by the profiler
+mod pprof {
+    use std::collections::HashMap;
+
+    use super::ProfiledFrame;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Profile {
+        #[prost(message, repeated, tag = "1")]
+        pub sample_type: Vec<ValueType>,
+        #[prost(message, repeated, tag = "2")]
+        pub sample: Vec<Sample>,
+        #[prost(message, repeated, tag = "4")]
+        pub location: Vec<Location>,
+        #[prost(message, repeated, tag = "5")]
+        pub function: Vec<Function>,
+        #[prost(string, repeated, tag = "6")]
+        pub string_table: Vec<String>,
+        #[prost(int64, tag = "9")]
+        pub time_nanos: i64,
+        #[prost(int64, tag = "10")]
+        pub duration_nanos: i64,
+        #[prost(message, optional, tag = "11")]
+        pub period_type: Option<ValueType>,
+        #[prost(int64, tag = "12")]
+        pub period: i64,
+        #[prost(int64, repeated, tag = "13")]
+        pub comment: Vec<i64>,
+        #[prost(int64, tag = "14")]
+        pub default_sample_type: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ValueType {
+        #[prost(int64, tag = "1")]
+        pub r#type: i64,
+        #[prost(int64, tag = "2")]
+        pub unit: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Sample {
+        #[prost(uint64, repeated, tag = "1")]
+        pub location_id: Vec<u64>,
+        #[prost(int64, repeated, tag = "2")]
+        pub value: Vec<i64>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Location {
+        #[prost(uint64, tag = "1")]
+        pub id: u64,
+        #[prost(message, repeated, tag = "4")]
+        pub line: Vec<Line>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Line {
+        #[prost(uint64, tag = "1")]
+        pub function_id: u64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Function {
+        #[prost(uint64, tag = "1")]
+        pub id: u64,
+        #[prost(int64, tag = "2")]
+        pub name: i64,
+        #[prost(int64, tag = "3")]
+        pub system_name: i64,
+        #[prost(int64, tag = "4")]
+        pub filename: i64,
+    }
+
+    /// Interns the strings and frames of a profile. Every frame gets a single location with a
+    /// single function, as there is no line information available.
+    pub struct ProfileBuilder {
+        pub strings: Vec<String>,
+        pub locations: Vec<Location>,
+        pub functions: Vec<Function>,
+        string_ids: HashMap<String, i64>,
+        location_ids: HashMap<ProfiledFrame, u64>,
+    }
+
+    impl Default for ProfileBuilder {
+        fn default() -> Self {
+            // The first entry of the string table must be the empty string
+            Self {
+                strings: vec![String::new()],
+                locations: Vec::new(),
+                functions: Vec::new(),
+                string_ids: HashMap::from([(String::new(), 0)]),
+                location_ids: HashMap::new(),
+            }
+        }
+    }
+
+    impl ProfileBuilder {
+        pub fn string(&mut self, value: &str) -> i64 {
+            if let Some(id) = self.string_ids.get(value) {
+                return *id;
+            }
+            let id = self.strings.len() as i64;
+            self.strings.push(value.to_string());
+            self.string_ids.insert(value.to_string(), id);
+            id
+        }
+
+        pub fn value_type(&mut self, r#type: &str, unit: &str) -> ValueType {
+            ValueType {
+                r#type: self.string(r#type),
+                unit: self.string(unit),
+            }
+        }
+
+        pub fn location(&mut self, frame: &ProfiledFrame) -> u64 {
+            if let Some(id) = self.location_ids.get(frame) {
+                return *id;
+            }
+            // Ids must be non-zero
+            let id = self.locations.len() as u64 + 1;
+            let name = self.string(&frame.function);
+            let filename = self.string(frame.module.as_deref().unwrap_or_default());
+            self.functions.push(Function {
+                id,
+                name,
+                system_name: name,
+                filename,
+            });
+            self.locations.push(Location {
+                id,
+                line: vec![Line { function_id: id }],
+            });
+            self.location_ids.insert(frame.clone(), id);
+            id
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use prost::Message;
+
+    use crate::profiler::{pprof, ProfiledFrame, StackStats, WorkerProfiler};
+
+    fn frame(function: &str) -> ProfiledFrame {
+        ProfiledFrame {
+            module: Some("main".to_string()),
+            function: function.to_string(),
+        }
+    }
+
+    #[test]
+    fn memory_growth_is_only_recorded_while_enabled() {
+        let profiler = WorkerProfiler::default();
+        profiler.record_memory_growth(100);
+        assert!(profiler.to_pprof().is_none());
+
+        profiler.start(Duration::from_millis(10));
+        profiler.record_memory_growth(100);
+        profiler.stop();
+        profiler.record_memory_growth(50);
+
+        let state = profiler.state.lock().unwrap();
+        assert_eq!(state.as_ref().unwrap().pending_memory_growth, 100);
+    }
+
+    #[test]
+    fn exported_profile_is_valid_pprof() {
+        let profiler = WorkerProfiler::default();
+        profiler.start(Duration::from_millis(10));
+        {
+            let mut state = profiler.state.lock().unwrap();
+            let state = state.as_mut().unwrap();
+            state.stacks = HashMap::from([
+                (
+                    vec![frame("leaf"), frame("run")],
+                    StackStats {
+                        samples: 3,
+                        memory_growth: 65536,
+                    },
+                ),
+                (
+                    vec![frame("run")],
+                    StackStats {
+                        samples: 1,
+                        memory_growth: 0,
+                    },
+                ),
+            ]);
+        }
+        profiler.stop();
+
+        let bytes = profiler.to_pprof().unwrap();
+        let profile = pprof::Profile::decode(bytes.as_slice()).unwrap();
+
+        assert_eq!(profile.string_table[0], "");
+        assert_eq!(profile.period, 10_000_000);
+        assert_eq!(profile.sample_type.len(), 3);
+        assert_eq!(profile.location.len(), 2);
+        assert_eq!(profile.function.len(), 2);
+
+        let leaf_sample = profile
+            .sample
+            .iter()
+            .find(|sample| sample.location_id.len() == 2)
+            .unwrap();
+        assert_eq!(leaf_sample.value, vec![3, 30_000_000, 65536]);
+        let leaf_function = profile
+            .function
+            .iter()
+            .find(|function| function.id == leaf_sample.location_id[0])
+            .unwrap();
+        assert_eq!(profile.string_table[leaf_function.name as usize], "leaf");
+        assert_eq!(
+            profile.string_table[leaf_function.filename as usize],
+            "main"
+        );
+    }
+}
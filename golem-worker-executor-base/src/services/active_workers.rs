@@ -31,6 +31,7 @@ use crate::workerctx::WorkerCtx;
 pub struct ActiveWorkers<Ctx: WorkerCtx> {
     workers: Cache<WorkerId, (), Arc<Worker<Ctx>>, GolemError>,
     worker_memory: Arc<Semaphore>,
+    worker_memory_size: usize,
     priority_allocation_lock: Arc<Mutex<()>>,
     acquire_retry_delay: Duration,
 }
@@ -46,6 +47,7 @@ impl<Ctx: WorkerCtx> ActiveWorkers<Ctx> {
                 "active_workers",
             ),
             worker_memory: Arc::new(Semaphore::new(worker_memory_size)),
+            worker_memory_size,
             acquire_retry_delay: memory_config.acquire_retry_delay,
             priority_allocation_lock: Arc::new(Mutex::new(())),
         }
@@ -95,6 +97,16 @@ impl<Ctx: WorkerCtx> ActiveWorkers<Ctx> {
         self.workers.iter()
     }
 
+    /// The total amount of memory available for workers
+    pub fn total_memory(&self) -> u64 {
+        self.worker_memory_size as u64
+    }
+
+    /// The amount of worker memory not acquired by any worker
+    pub fn available_memory(&self) -> u64 {
+        self.worker_memory.available_permits() as u64
+    }
+
     pub async fn acquire(&self, memory: u64) -> OwnedSemaphorePermit {
         let mem32: u32 = memory
             .try_into()
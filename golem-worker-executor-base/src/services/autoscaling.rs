@@ -0,0 +1,173 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use golem_common::model::{ShardId, Timestamp};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::metrics::autoscaling::record_autoscaling_signals;
+use crate::services::{HasActiveWorkers, HasConfig, HasShardService};
+use crate::workerctx::WorkerCtx;
+
+/// Version of the JSON schema of [`AutoscalingSignals`]. Fields are only added within a version,
+/// renaming or removing any of them requires a new one.
+pub const AUTOSCALING_SIGNALS_SCHEMA_VERSION: u32 = 1;
+
+/// The last calculated signals, served by the HTTP interface
+static LAST_SIGNALS: RwLock<Option<AutoscalingSignals>> = RwLock::new(None);
+
+/// Executor level load signals for autoscalers such as the Kubernetes HPA or KEDA
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AutoscalingSignals {
+    pub schema_version: u32,
+    pub collected_at: Timestamp,
+    /// Number of invocations queued in the active workers, waiting to be processed
+    pub pending_invocations: usize,
+    /// Ratio of the worker memory in use, between 0 and 1
+    pub memory_pressure: f64,
+    pub total_worker_memory: u64,
+    pub available_worker_memory: u64,
+    /// Number of workers waiting for enough memory to become available to be loaded
+    pub hydration_queue_depth: usize,
+    pub active_workers: usize,
+    pub assigned_shards: usize,
+    pub number_of_shards: usize,
+    /// Number of active workers of the busiest assigned shard relative to the average of the
+    /// assigned shards. It is 1 if the workers are evenly spread, or there are no active workers.
+    pub shard_imbalance: f64,
+}
+
+/// Gets the last calculated signals, if they were calculated already
+pub fn last_signals() -> Option<AutoscalingSignals> {
+    LAST_SIGNALS.read().unwrap().clone()
+}
+
+/// Periodically recalculates the autoscaling signals, publishing them both for the HTTP
+/// interface and as Prometheus metrics
+pub fn start_autoscaling_signals<Ctx, T>(this: &T)
+where
+    Ctx: WorkerCtx,
+    T: HasActiveWorkers<Ctx> + HasShardService + HasConfig + Clone + Send + Sync + 'static,
+{
+    let this = this.clone();
+    let mut interval = tokio::time::interval(this.config().autoscaling.refresh_interval);
+    tokio::spawn(async move {
+        loop {
+            interval.tick().await;
+            let signals = collect_autoscaling_signals(&this);
+            debug!("Autoscaling signals: {signals:?}");
+            record_autoscaling_signals(&signals);
+            *LAST_SIGNALS.write().unwrap() = Some(signals);
+        }
+    });
+}
+
+pub fn collect_autoscaling_signals<Ctx, T>(this: &T) -> AutoscalingSignals
+where
+    Ctx: WorkerCtx,
+    T: HasActiveWorkers<Ctx> + HasShardService,
+{
+    let active_workers = this.active_workers();
+    let shard_assignment = this.shard_service().try_get_current_assignment();
+
+    let mut pending_invocations = 0;
+    let mut hydration_queue_depth = 0;
+    let mut workers_per_shard: HashMap<ShardId, usize> = HashMap::new();
+    let mut active_worker_count = 0;
+    for (worker_id, worker) in active_workers.iter() {
+        active_worker_count += 1;
+        pending_invocations += worker.pending_invocation_count();
+        if worker.is_waiting_for_permit() {
+            hydration_queue_depth += 1;
+        }
+        if let Some(shard_assignment) = &shard_assignment {
+            let shard_id = ShardId::from_worker_id(&worker_id, shard_assignment.number_of_shards);
+            *workers_per_shard.entry(shard_id).or_default() += 1;
+        }
+    }
+
+    let (assigned_shards, number_of_shards) = shard_assignment
+        .as_ref()
+        .map(|assignment| (assignment.shard_ids.len(), assignment.number_of_shards))
+        .unwrap_or((0, 0));
+
+    let total_worker_memory = active_workers.total_memory();
+    let available_worker_memory = active_workers.available_memory();
+
+    AutoscalingSignals {
+        schema_version: AUTOSCALING_SIGNALS_SCHEMA_VERSION,
+        collected_at: Timestamp::now_utc(),
+        pending_invocations,
+        memory_pressure: memory_pressure(total_worker_memory, available_worker_memory),
+        total_worker_memory,
+        available_worker_memory,
+        hydration_queue_depth,
+        active_workers: active_worker_count,
+        assigned_shards,
+        number_of_shards,
+        shard_imbalance: shard_imbalance(workers_per_shard.values().copied(), assigned_shards),
+    }
+}
+
+fn memory_pressure(total: u64, available: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        total.saturating_sub(available) as f64 / total as f64
+    }
+}
+
+/// The maximum number of workers per shard divided by the average, counting the assigned shards
+/// without any active workers as well
+fn shard_imbalance(workers_per_shard: impl Iterator<Item = usize>, assigned_shards: usize) -> f64 {
+    let (max, total) = workers_per_shard.fold((0, 0), |(max, total), count| {
+        (max.max(count), total + count)
+    });
+
+    if total == 0 || assigned_shards == 0 {
+        1.0
+    } else {
+        max as f64 / (total as f64 / assigned_shards as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::services::autoscaling::{memory_pressure, shard_imbalance};
+
+    #[test]
+    fn imbalance_of_evenly_spread_workers() {
+        assert_eq!(shard_imbalance([3, 3, 3, 3].into_iter(), 4), 1.0);
+        assert_eq!(shard_imbalance(std::iter::empty(), 4), 1.0);
+        assert_eq!(shard_imbalance(std::iter::empty(), 0), 1.0);
+    }
+
+    #[test]
+    fn imbalance_of_hot_shard() {
+        // 8 workers on 4 shards is 2 on average, the busiest shard has 3 times that
+        assert_eq!(shard_imbalance([6, 2].into_iter(), 4), 3.0);
+    }
+
+    #[test]
+    fn memory_pressure_ratio() {
+        assert_eq!(memory_pressure(1000, 250), 0.75);
+        assert_eq!(memory_pressure(1000, 1000), 0.0);
+        assert_eq!(memory_pressure(0, 0), 0.0);
+    }
+}
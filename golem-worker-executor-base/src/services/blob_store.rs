@@ -15,12 +15,14 @@
 use std::collections::HashMap;
 use std::future::Future;
 use std::io;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
 use bincode::{Decode, Encode};
+use bytes::Bytes;
 use futures_util::future::BoxFuture;
 use serde::{Deserialize, Serialize};
 use tokio::{fs, task};
@@ -28,11 +30,20 @@ use tokio_stream::StreamExt;
 use tonic::metadata::Binary;
 use tracing::{error, info};
 use golem_api_grpc::proto::golem::workerexecutor::v1::{FileNode, NodeType};
+use golem_common::model::initial_file_system::{
+    render_template, InitialFile, InitialFilePermissions, InitialFileSystemManifest, MANIFEST_PATH,
+};
+use golem_common::model::regions::DeletedRegions;
 use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, WorkerId, WorkerMetadata};
+use crate::model::WorkerConfig;
 use crate::services::ifs::InitialFileSystem;
 use crate::storage::blob::{BlobStorage, BlobStorageLabelledApi, BlobStorageNamespace, ExistsResult};
+use zip::result::ZipError;
 use zip::ZipArchive;
-use crate::error::GolemError;
+
+/// Directory of the shared initial file system layers within a component's directory, which
+/// cannot clash with a worker's directory as worker names cannot contain dots
+const LAYERS_DIR: &str = ".layers";
 
 /// Interface for storing blobs in a persistent storage.
 #[async_trait]
@@ -184,7 +195,65 @@ pub trait BlobStoreService {
     async fn decompress_ifs(&self,
                             worker_metadata: WorkerMetadata,
     ) -> Result<(), String>;
-    async fn set_permissions(&self, path: &Path) -> Result<(), GolemError>;
+
+    /// Checks whether the worker's extracted initial file system is in place
+    async fn worker_ifs_state(&self, worker_metadata: &WorkerMetadata) -> Result<WorkerIfsState, String>;
+    /// Checks whether there is an extracted initial file system for the worker
+    async fn has_worker_files(&self, owned_worker_id: &OwnedWorkerId) -> Result<bool, String>;
+    /// Deletes the worker's extracted initial file system, together with the changes the worker made to it
+    async fn delete_worker_files(&self, owned_worker_id: &OwnedWorkerId) -> Result<(), String>;
+    /// Applies the permissions of an initial file system entry to an extracted file
+    async fn set_permissions(
+        &self,
+        account_id: AccountId,
+        path: &Path,
+        permissions: InitialFilePermissions,
+    ) -> Result<(), String>;
+    /// Reads an invocation parameter uploaded by a caller to the account's invocation parameter
+    /// storage
+    async fn get_invocation_parameter(
+        &self,
+        account_id: AccountId,
+        key: &str,
+    ) -> Result<Option<Bytes>, String>;
+    /// Stores an invocation parameter uploaded by a caller in the account's invocation parameter
+    /// storage
+    async fn put_invocation_parameter(
+        &self,
+        account_id: AccountId,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), String>;
+    /// Deletes an invocation parameter once the invocation it was uploaded for is completed
+    async fn delete_invocation_parameter(
+        &self,
+        account_id: AccountId,
+        key: &str,
+    ) -> Result<(), String>;
+    /// Stores an invocation result too large to be returned to the caller in the account's
+    /// invocation result storage
+    async fn put_invocation_result(
+        &self,
+        account_id: AccountId,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), String>;
+    async fn get_invocation_result(
+        &self,
+        account_id: AccountId,
+        key: &str,
+    ) -> Result<Option<Bytes>, String>;
+    async fn has_invocation_result(&self, account_id: AccountId, key: &str)
+        -> Result<bool, String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerIfsState {
+    /// The worker's component version has no initial file system
+    NotUsed,
+    Present,
+    /// There is an initial file system for the worker's component version, but it was not extracted
+    Missing,
 }
 
 pub enum FileOrDirectoryResponse {
@@ -306,11 +375,68 @@ pub fn convert_to_file_nodes(node: &Node) -> Vec<FileNode> {
 
 pub struct DefaultBlobStoreService {
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+    /// Serializes storing the files of the shared initial file system layers
+    layer_lock: tokio::sync::Mutex<()>,
 }
 
 impl DefaultBlobStoreService {
     pub fn new(blob_storage: Arc<dyn BlobStorage + Send + Sync>) -> Self {
-        Self { blob_storage }
+        Self {
+            blob_storage,
+            layer_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// The directory the worker's initial file system gets extracted to
+    fn extracted_dir(owned_worker_id: &OwnedWorkerId) -> PathBuf {
+        Path::new(&owned_worker_id.worker_id.component_id.to_string())
+            .join(&owned_worker_id.worker_id.worker_name)
+            .join("extracted")
+    }
+
+    /// Links a file of a worker's initial file system to the shared layer of its component version,
+    /// storing it in the layer first if it is not there yet. Returns `false` if the file could not be
+    /// linked and has to be copied instead.
+    async fn link_from_layer(
+        &self,
+        account_id: &AccountId,
+        layer_file_path: &Path,
+        file_path: &Path,
+        file_content: &[u8],
+    ) -> Result<bool, String> {
+        let namespace = BlobStorageNamespace::CustomStorage(account_id.clone());
+        {
+            let _guard = self.layer_lock.lock().await;
+            let layer_file = self
+                .blob_storage
+                .with("decompress_ifs", "check_layer_file")
+                .exists(namespace.clone(), layer_file_path)
+                .await?;
+            if layer_file == ExistsResult::DoesNotExist {
+                self.blob_storage
+                    .with("decompress_ifs", "store_layer_file")
+                    .put_raw_staged(namespace.clone(), layer_file_path, file_content)
+                    .await
+                    .map_err(|err| {
+                        format!(
+                            "Failed to store layer file {}: {:?}",
+                            layer_file_path.display(),
+                            err
+                        )
+                    })?;
+                self.set_permissions(
+                    account_id.clone(),
+                    layer_file_path,
+                    InitialFilePermissions::ReadOnly,
+                )
+                .await?;
+            }
+        }
+
+        self.blob_storage
+            .with("decompress_ifs", "link_layer_file")
+            .link(namespace, layer_file_path, file_path)
+            .await
     }
 }
 
@@ -723,7 +849,7 @@ impl BlobStoreService for DefaultBlobStoreService {
 
         self.blob_storage
             .with("upload_initial_file_system", "store_ifs_data")
-            .put_raw(BlobStorageNamespace::InitialFileSystem(account_id), &path, &initial_file_system)
+            .put_raw_staged(BlobStorageNamespace::InitialFileSystem(account_id), &path, &initial_file_system)
             .await
             .map_err(|err| format!("Failed to store initial file contents: {:?}", err))?;
 
@@ -745,18 +871,60 @@ impl BlobStoreService for DefaultBlobStoreService {
             .map_err(|err| format!("Failed to retrieve initial file system data: {:?}", err))?
             .ok_or_else(|| format!("Compressed IFS not found at {:?}", compressed_path))?;
 
-        // Perform decompression in a blocking synchronous context
-        let extracted_files: Vec<(String, Vec<u8>)> = task::block_in_place(|| {
+        // Templates are rendered with the same environment the worker is started with
+        let variables: HashMap<String, String> = WorkerConfig::new(
+            worker_metadata.worker_id.clone(),
+            fs_version,
+            worker_metadata.args.clone(),
+            worker_metadata.env.clone(),
+            DeletedRegions::new(),
+            0,
+        )
+        .env
+        .into_iter()
+        .collect();
+
+        // Perform decompression in a blocking synchronous context. Only the files listed in
+        // the manifest are extracted, which the component service already checked at upload.
+        let extracted_files: Vec<(InitialFile, Vec<u8>)> = task::block_in_place(|| {
             let cursor = std::io::Cursor::new(ifs_data);
             let mut zip = ZipArchive::new(cursor).map_err(|e| format!("Failed to open ZipArchive: {:?}", e))?;
-            let mut files = Vec::new();
 
-            for i in 0..zip.len() {
-                let mut file = zip.by_index(i).map_err(|e| format!("Failed to read ZipArchive file at index {}: {:?}", i, e))?;
-                let file_name = file.name().to_string();
+            // Archives without a manifest get every file with the default permissions
+            let entries = zip.file_names().map(|name| name.to_string()).collect::<Vec<_>>();
+            let manifest = match zip.by_name(MANIFEST_PATH) {
+                Ok(mut manifest_entry) => {
+                    let mut manifest_data = Vec::new();
+                    manifest_entry
+                        .read_to_end(&mut manifest_data)
+                        .map_err(|e| format!("Failed to read contents of {MANIFEST_PATH} in zip: {:?}", e))?;
+                    InitialFileSystemManifest::from_json(&manifest_data)?
+                }
+                Err(ZipError::FileNotFound) => InitialFileSystemManifest::with_default_permissions(&entries),
+                Err(e) => return Err(format!("Failed to read {MANIFEST_PATH} from ZipArchive: {:?}", e)),
+            };
+            if !self.blob_storage.supports_permissions()
+                && manifest
+                    .files
+                    .iter()
+                    .any(|file| file.permissions == InitialFilePermissions::ReadOnly)
+            {
+                return Err(
+                    "The configured blob storage cannot protect read-only initial files".to_string(),
+                );
+            }
+
+            let mut files = Vec::new();
+            for file in manifest.files {
+                let archive_path = file.archive_path();
+                let mut entry = zip.by_name(&archive_path).map_err(|e| format!("Failed to read ZipArchive file {}: {:?}", archive_path, e))?;
                 let mut file_content = Vec::new();
-                std::io::copy(&mut file, &mut file_content).map_err(|e| format!("Failed to read contents of {} in zip: {:?}", file_name, e))?;
-                files.push((file_name, file_content));
+                entry.read_to_end(&mut file_content).map_err(|e| format!("Failed to read contents of {} in zip: {:?}", archive_path, e))?;
+                if file.template {
+                    let content = String::from_utf8(file_content).map_err(|e| format!("Templated file {} is not valid UTF-8: {:?}", file.path, e))?;
+                    file_content = render_template(&content, &variables).into_bytes();
+                }
+                files.push((file, file_content));
             }
             Ok::<_, String>(files)
         })?;
@@ -768,20 +936,198 @@ impl BlobStoreService for DefaultBlobStoreService {
             .await
             .map_err(|err| format!("Failed to create extracted directory: {:?}", err))?;
 
-        // Upload each extracted file asynchronously
-        for (file_name, file_content) in extracted_files {
-            let extracted_file_path = extracted_dir.join(&file_name);
+        // The read-only files are the same for all the workers of the component version, so where the
+        // storage supports it they are stored once in a shared layer, and linked into each worker's
+        // file system. Everything else is copied.
+        let share_read_only = self.blob_storage.supports_links();
+        let layer_dir = Path::new(&component_id)
+            .join(LAYERS_DIR)
+            .join(fs_version.to_string());
+
+        // Upload each extracted file asynchronously, then restrict it as the manifest says
+        for (file, file_content) in extracted_files {
+            let relative_path = file.path.trim_start_matches('/');
+            let extracted_file_path = extracted_dir.join(relative_path);
+            if share_read_only
+                && file.permissions == InitialFilePermissions::ReadOnly
+                && !file.template
+            {
+                let layer_file_path = layer_dir.join(relative_path);
+                if self
+                    .link_from_layer(
+                        &account_id,
+                        &layer_file_path,
+                        &extracted_file_path,
+                        &file_content,
+                    )
+                    .await?
+                {
+                    continue;
+                }
+            }
+
             self.blob_storage.with("decompress_ifs", "store_extracted_file")
-                .put_raw(BlobStorageNamespace::CustomStorage(account_id.clone()), &extracted_file_path, &file_content)
+                .put_raw_staged(BlobStorageNamespace::CustomStorage(account_id.clone()), &extracted_file_path, &file_content)
                 .await
                 .map_err(|err| format!("Failed to store extracted file {}: {:?}", extracted_file_path.display(), err))?;
+            self.set_permissions(account_id.clone(), &extracted_file_path, file.permissions)
+                .await
+                .map_err(|err| format!("Failed to set permissions of extracted file {}: {:?}", extracted_file_path.display(), err))?;
         }
 
         Ok(())
     }
 
-    async fn set_permissions(&self, path: &Path) -> Result<(), GolemError> {
-        todo!()
+    async fn worker_ifs_state(&self, worker_metadata: &WorkerMetadata) -> Result<WorkerIfsState, String> {
+        let account_id = &worker_metadata.account_id;
+        let component_id = worker_metadata.worker_id.component_id.to_string();
+        let compressed_path = Path::new(&component_id)
+            .join(format!("{}.ifs", worker_metadata.last_known_status.fs_version));
+
+        let archive = self.blob_storage.with("worker_ifs_state", "check_ifs_data")
+            .exists(BlobStorageNamespace::InitialFileSystem(account_id.clone()), &compressed_path)
+            .await?;
+        if archive == ExistsResult::DoesNotExist {
+            return Ok(WorkerIfsState::NotUsed);
+        }
+
+        let extracted_dir = Path::new(&component_id).join(format!("{}/extracted", worker_metadata.worker_id.worker_name));
+        let extracted = self.blob_storage.with("worker_ifs_state", "check_extracted_dir")
+            .exists(BlobStorageNamespace::CustomStorage(account_id.clone()), &extracted_dir)
+            .await?;
+        if extracted == ExistsResult::Directory {
+            Ok(WorkerIfsState::Present)
+        } else {
+            Ok(WorkerIfsState::Missing)
+        }
+    }
+
+    async fn has_worker_files(&self, owned_worker_id: &OwnedWorkerId) -> Result<bool, String> {
+        let extracted = self
+            .blob_storage
+            .with("blob_store", "has_worker_files")
+            .exists(
+                BlobStorageNamespace::CustomStorage(owned_worker_id.account_id.clone()),
+                &Self::extracted_dir(owned_worker_id),
+            )
+            .await?;
+        Ok(extracted == ExistsResult::Directory)
+    }
+
+    async fn delete_worker_files(&self, owned_worker_id: &OwnedWorkerId) -> Result<(), String> {
+        self.blob_storage
+            .with("blob_store", "delete_worker_files")
+            .delete_dir(
+                BlobStorageNamespace::CustomStorage(owned_worker_id.account_id.clone()),
+                &Self::extracted_dir(owned_worker_id),
+            )
+            .await
+    }
+
+    async fn set_permissions(
+        &self,
+        account_id: AccountId,
+        path: &Path,
+        permissions: InitialFilePermissions,
+    ) -> Result<(), String> {
+        self.blob_storage
+            .set_permissions(BlobStorageNamespace::CustomStorage(account_id), path, permissions)
+            .await
+    }
+
+    async fn get_invocation_parameter(
+        &self,
+        account_id: AccountId,
+        key: &str,
+    ) -> Result<Option<Bytes>, String> {
+        self.blob_storage
+            .get_raw(
+                "blob_store",
+                "get_invocation_parameter",
+                BlobStorageNamespace::InvocationParameters(account_id),
+                Path::new(key),
+            )
+            .await
+    }
+
+    async fn put_invocation_parameter(
+        &self,
+        account_id: AccountId,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), String> {
+        self.blob_storage
+            .put_raw(
+                "blob_store",
+                "put_invocation_parameter",
+                BlobStorageNamespace::InvocationParameters(account_id),
+                Path::new(key),
+                data,
+            )
+            .await
+    }
+
+    async fn delete_invocation_parameter(
+        &self,
+        account_id: AccountId,
+        key: &str,
+    ) -> Result<(), String> {
+        self.blob_storage
+            .delete(
+                "blob_store",
+                "delete_invocation_parameter",
+                BlobStorageNamespace::InvocationParameters(account_id),
+                Path::new(key),
+            )
+            .await
+    }
+
+    async fn put_invocation_result(
+        &self,
+        account_id: AccountId,
+        key: &str,
+        data: &[u8],
+    ) -> Result<(), String> {
+        self.blob_storage
+            .put_raw(
+                "blob_store",
+                "put_invocation_result",
+                BlobStorageNamespace::InvocationResults(account_id),
+                Path::new(key),
+                data,
+            )
+            .await
+    }
+
+    async fn get_invocation_result(
+        &self,
+        account_id: AccountId,
+        key: &str,
+    ) -> Result<Option<Bytes>, String> {
+        self.blob_storage
+            .get_raw(
+                "blob_store",
+                "get_invocation_result",
+                BlobStorageNamespace::InvocationResults(account_id),
+                Path::new(key),
+            )
+            .await
+    }
+
+    async fn has_invocation_result(
+        &self,
+        account_id: AccountId,
+        key: &str,
+    ) -> Result<bool, String> {
+        self.blob_storage
+            .get_metadata(
+                "blob_store",
+                "has_invocation_result",
+                BlobStorageNamespace::InvocationResults(account_id),
+                Path::new(key),
+            )
+            .await
+            .map(|metadata| metadata.is_some())
     }
 }
 
@@ -12,12 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use tokio::time::Instant;
-use tracing::{debug, info};
+use tracing::{debug, warn};
 use wasmtime::component::Component;
 
 use golem_common::model::ComponentId;
@@ -27,7 +28,12 @@ use crate::services::golem_config::CompiledComponentServiceConfig;
 use crate::storage::blob::{BlobStorage, BlobStorageNamespace};
 use crate::Engine;
 
-/// Service for storing compiled native binaries of WebAssembly components
+/// Service for storing compiled native binaries of WebAssembly components.
+///
+/// The binaries are shared by all the executors of the cluster through the blob storage, so a
+/// component compiled by any of them (or by the compilation service) can be loaded by the others
+/// without compiling it again. A binary can only be loaded by an engine compatible with the one
+/// compiling it, so the engine's fingerprint is part of the key.
 #[async_trait]
 pub trait CompiledComponentService {
     async fn get(
@@ -41,9 +47,14 @@ pub trait CompiledComponentService {
         component_id: &ComponentId,
         component_version: u64,
         component: &Component,
+        engine: &Engine,
     ) -> Result<(), GolemError>;
 }
 
+/// Length of the MD5 digest of the compiled binary, stored in front of it
+const DIGEST_LENGTH: usize = 16;
+
+
 pub struct DefaultCompiledComponentService {
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
 }
@@ -53,8 +64,54 @@ impl DefaultCompiledComponentService {
         Self { blob_storage }
     }
 
-    fn key(component_id: &ComponentId, component_version: u64) -> PathBuf {
-        Path::new(&component_id.to_string()).join(format!("{component_version}.cwasm"))
+    fn key(component_id: &ComponentId, component_version: u64, engine: &Engine) -> PathBuf {
+        Path::new(&component_id.to_string()).join(format!(
+            "{component_version}-{}.cwasm",
+            engine_fingerprint(engine)
+        ))
+    }
+}
+
+/// Identifies the engines which can load each other's compiled binaries. It covers the wasmtime
+/// version, the target and the compilation settings, and is stable across processes so all the
+/// nodes of the cluster agree on it.
+pub fn engine_fingerprint(engine: &Engine) -> String {
+    let mut hasher = Md5Hasher(md5::Context::new());
+    engine.precompile_compatibility_hash().hash(&mut hasher);
+    hex::encode(hasher.0.compute().0)
+}
+
+struct Md5Hasher(md5::Context);
+
+impl Hasher for Md5Hasher {
+    fn finish(&self) -> u64 {
+        let digest = self.0.clone().compute();
+        u64::from_le_bytes(digest.0[..8].try_into().unwrap())
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.consume(bytes);
+    }
+}
+
+/// Prefixes the compiled binary with its digest
+fn seal(bytes: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(DIGEST_LENGTH + bytes.len());
+    result.extend_from_slice(&md5::compute(bytes).0);
+    result.extend_from_slice(bytes);
+    result
+}
+
+/// Returns the compiled binary if its digest matches its contents
+fn unseal(sealed: &[u8]) -> Option<&[u8]> {
+    if sealed.len() < DIGEST_LENGTH {
+        return None;
+    }
+    let (digest, bytes) = sealed.split_at(DIGEST_LENGTH);
+    if md5::compute(bytes).0 == digest {
+        Some(bytes)
+    } else {
+        None
     }
 }
 
@@ -72,15 +129,24 @@ impl CompiledComponentService for DefaultCompiledComponentService {
                 "compiled_component",
                 "get",
                 BlobStorageNamespace::CompilationCache,
-                &Self::key(component_id, component_version),
+                &Self::key(component_id, component_version, engine),
             )
             .await
         {
             Ok(None) => Ok(None),
-            Ok(Some(bytes)) => {
+            Ok(Some(sealed)) => {
+                let Some(bytes) = unseal(&sealed) else {
+                    // Compiling it again overwrites the corrupted binary
+                    warn!(
+                        "Integrity check of the compiled binary of {}#{} failed, ignoring it",
+                        component_id, component_version
+                    );
+                    return Ok(None);
+                };
+
                 let start = Instant::now();
                 let component = unsafe {
-                    Component::deserialize(engine, &bytes).map_err(|err| {
+                    Component::deserialize(engine, bytes).map_err(|err| {
                         GolemError::component_download_failed(
                             component_id.clone(),
                             component_version,
@@ -112,18 +178,22 @@ impl CompiledComponentService for DefaultCompiledComponentService {
         component_id: &ComponentId,
         component_version: u64,
         component: &Component,
+        engine: &Engine,
     ) -> Result<(), GolemError> {
-
-        let bytes = component
-            .serialize()
-            .expect("Could not serialize component");
+        let bytes = component.serialize().map_err(|err| {
+            GolemError::component_download_failed(
+                component_id.clone(),
+                component_version,
+                format!("Could not serialize compiled component: {err}"),
+            )
+        })?;
         self.blob_storage
             .put_raw(
                 "compiled_component",
                 "put",
                 BlobStorageNamespace::CompilationCache,
-                &Self::key(component_id, component_version),
-                &bytes,
+                &Self::key(component_id, component_version, engine),
+                &seal(&bytes),
             )
             .await
             .map_err(|err| {
@@ -180,7 +250,26 @@ impl CompiledComponentService for CompiledComponentServiceDisabled {
         _component_id: &ComponentId,
         _component_version: u64,
         _component: &Component,
+        _engine: &Engine,
     ) -> Result<(), GolemError> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::services::compiled_component::{seal, unseal};
+
+    #[test]
+    fn sealed_binaries_are_verified() {
+        let sealed = seal(b"compiled component");
+        assert_eq!(unseal(&sealed), Some(&b"compiled component"[..]));
+
+        let mut corrupted = sealed.clone();
+        *corrupted.last_mut().unwrap() ^= 0xff;
+        assert_eq!(unseal(&corrupted), None);
+        assert_eq!(unseal(&sealed[..4]), None);
+    }
+}
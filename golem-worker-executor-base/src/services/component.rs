@@ -35,22 +35,28 @@ use golem_api_grpc::proto::golem::component::v1::{
 use golem_api_grpc::proto::golem::component::LinearMemory;
 use golem_common::cache::{BackgroundEvictionMode, Cache, FullCacheEvictionMode, SimpleCache};
 use golem_common::client::{GrpcClient, GrpcClientConfig};
-use golem_common::config::RetryConfig;
+use golem_common::config::{GrpcCompression, RetryConfig};
 use golem_common::metrics::external_calls::record_external_call_response_size_bytes;
+use golem_common::model::component_capabilities::ComponentCapabilities;
 use golem_common::model::component_metadata::RawComponentMetadata;
 use golem_common::model::{ComponentId, ComponentType, ComponentVersion};
 use golem_common::retries::with_retries;
+use golem_common::with_grpc_compression;
 use golem_wasm_ast::analysis::AnalysedExport;
 use http::Uri;
 use prost::Message;
 use tokio::task::spawn_blocking;
-use tonic::codec::CompressionEncoding;
 use tonic::transport::Channel;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 use wasmtime::component::Component;
 use wasmtime::Engine;
 
+/// Whether the wasmtime version used by the executor can run components using component-model
+/// async. Until it can, such components are rejected before compilation instead of failing with
+/// an unrelated validation error, and workers never write `SwitchInvocation` oplog entries.
+pub const COMPONENT_MODEL_ASYNC_SUPPORTED: bool = false;
+
 #[derive(Debug, Clone)]
 pub struct ComponentMetadata {
     pub version: ComponentVersion,
@@ -81,6 +87,7 @@ pub async fn configured(
     config: &ComponentServiceConfig,
     cache_config: &ComponentCacheConfig,
     compiled_config: &CompiledComponentServiceConfig,
+    grpc_compression: GrpcCompression,
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
 ) -> Arc<dyn ComponentService + Send + Sync> {
     let compiled_component_service = compiled_component::configured(compiled_config, blob_storage);
@@ -99,6 +106,7 @@ pub async fn configured(
                 config.retries.clone(),
                 compiled_component_service,
                 config.max_component_size,
+                grpc_compression,
             ))
         }
         ComponentServiceConfig::Local(config) => Arc::new(ComponentServiceLocalFileSystem::new(
@@ -136,6 +144,7 @@ impl ComponentServiceGrpc {
         retry_config: RetryConfig,
         compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
         max_component_size: usize,
+        grpc_compression: GrpcCompression,
     ) -> Self {
         Self {
             component_cache: create_component_cache(max_capacity, time_to_idle),
@@ -148,10 +157,11 @@ impl ComponentServiceGrpc {
             compiled_component_service,
             client: GrpcClient::new(
                 move |channel| {
-                    ComponentServiceClient::new(channel)
-                        .max_decoding_message_size(max_component_size)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
+                    with_grpc_compression!(
+                        ComponentServiceClient::new(channel)
+                            .max_decoding_message_size(max_component_size),
+                        grpc_compression
+                    )
                 },
                 endpoint.as_http_02(),
                 GrpcClientConfig {
@@ -211,8 +221,14 @@ impl ComponentService for ComponentServiceGrpc {
 
                             let start = Instant::now();
                             let component_id_clone2 = component_id_clone.clone();
+                            let engine_clone = engine.clone();
                             let component = spawn_blocking(move || {
-                                Component::from_binary(&engine, &bytes).map_err(|e| {
+                                check_component_capabilities(
+                                    &component_id_clone2,
+                                    component_version,
+                                    &bytes,
+                                )?;
+                                Component::from_binary(&engine_clone, &bytes).map_err(|e| {
                                     GolemError::ComponentParseFailed {
                                         component_id: component_id_clone2,
                                         component_version,
@@ -233,7 +249,7 @@ impl ComponentService for ComponentServiceGrpc {
                             );
 
                             let result = compiled_component_service
-                                .put(&component_id_clone, component_version, &component)
+                                .put(&component_id_clone, component_version, &component, &engine)
                                 .await;
 
                             match result {
@@ -495,6 +511,28 @@ fn grpc_get_latest_version_error(
     }
 }
 
+fn check_component_capabilities(
+    component_id: &ComponentId,
+    component_version: ComponentVersion,
+    bytes: &[u8],
+) -> Result<(), GolemError> {
+    let capabilities =
+        ComponentCapabilities::detect(bytes).map_err(|err| GolemError::ComponentParseFailed {
+            component_id: component_id.clone(),
+            component_version,
+            reason: err,
+        })?;
+    if capabilities.component_model_async && !COMPONENT_MODEL_ASYNC_SUPPORTED {
+        Err(GolemError::ComponentParseFailed {
+            component_id: component_id.clone(),
+            component_version,
+            reason: "The component uses component-model async (async exports, imports or built-ins), which is not supported by this worker executor".to_string(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
 fn create_component_cache(
     max_capacity: usize,
     time_to_idle: Duration,
@@ -599,6 +637,7 @@ impl ComponentServiceLocalFileSystem {
                             let bytes = tokio::fs::read(path).await?;
 
                             let start = Instant::now();
+                            check_component_capabilities(&component_id, component_version, &bytes)?;
                             let component =
                                 Component::from_binary(&engine, &bytes).map_err(|e| {
                                     GolemError::ComponentParseFailed {
@@ -618,7 +657,7 @@ impl ComponentServiceLocalFileSystem {
                             );
 
                             let result = compiled_component_service
-                                .put(&component_id, component_version, &component)
+                                .put(&component_id, component_version, &component, &engine)
                                 .await;
 
                             match result {
@@ -839,3 +878,45 @@ impl ComponentService for ComponentServiceLocalFileSystem {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::error::GolemError;
+    use crate::services::component::check_component_capabilities;
+    use golem_common::model::ComponentId;
+    use uuid::Uuid;
+
+    const PREAMBLE: [u8; 8] = [0x00, 0x61, 0x73, 0x6d, 0x0d, 0x00, 0x01, 0x00];
+
+    #[test]
+    fn sync_components_pass_the_capability_check() {
+        let component_id = ComponentId(Uuid::new_v4());
+
+        assert!(check_component_capabilities(&component_id, 0, &PREAMBLE).is_ok());
+    }
+
+    #[test]
+    fn async_components_are_rejected_by_the_capability_check() {
+        let component_id = ComponentId(Uuid::new_v4());
+        // A canonical section with a single task.return built-in
+        let mut component = PREAMBLE.to_vec();
+        component.extend([0x08, 0x05, 0x01, 0x09, 0x01, 0x00, 0x00]);
+
+        let result = check_component_capabilities(&component_id, 2, &component);
+
+        match result {
+            Err(GolemError::ComponentParseFailed {
+                component_id: failed_component_id,
+                component_version,
+                reason,
+            }) => {
+                assert_eq!(failed_component_id, component_id);
+                assert_eq!(component_version, 2);
+                assert!(reason.contains("component-model async"));
+            }
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bincode::{Decode, Encode};
+use dashmap::DashMap;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use golem_common::model::{ComponentId, Timestamp, WorkerId};
+
+use crate::error::GolemError;
+use crate::storage::indexed::{IndexedStorage, IndexedStorageLabelledApi, IndexedStorageNamespace};
+
+/// The maximum number of events returned by a single poll
+pub const MAX_POLLED_EVENTS: u64 = 1000;
+
+/// The number of times appending an event is retried when another executor appended an event
+/// with the same id concurrently
+const MAX_APPEND_ATTEMPTS: usize = 5;
+
+/// An event emitted by a worker through `golem:api/events`
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct DomainEvent {
+    /// The position of the event in its topic, starting from 1
+    pub id: u64,
+    pub worker_id: WorkerId,
+    pub timestamp: Timestamp,
+    pub payload: Vec<u8>,
+}
+
+impl From<DomainEvent> for golem_api_grpc::proto::golem::worker::DomainEvent {
+    fn from(value: DomainEvent) -> Self {
+        Self {
+            id: value.id,
+            worker_id: Some(value.worker_id.into()),
+            timestamp: Some(value.timestamp.into()),
+            payload: value.payload,
+        }
+    }
+}
+
+/// Service persisting the domain events emitted by the workers, per component and topic
+#[async_trait]
+pub trait DomainEventService {
+    /// Appends an event to the topic of the worker's component, returning its id
+    async fn emit(
+        &self,
+        worker_id: &WorkerId,
+        topic: &str,
+        payload: Vec<u8>,
+    ) -> Result<u64, GolemError>;
+
+    /// Reads at most `count` events of a topic, following the event with the id `cursor`
+    async fn poll(
+        &self,
+        component_id: &ComponentId,
+        topic: &str,
+        cursor: u64,
+        count: u64,
+    ) -> Result<Vec<DomainEvent>, GolemError>;
+}
+
+pub struct DefaultDomainEventService {
+    indexed_storage: Arc<dyn IndexedStorage + Send + Sync>,
+    // Serializes the appends of this executor per topic, so only concurrent emits of other
+    // executors have to be retried
+    append_locks: DashMap<String, Arc<Mutex<()>>>,
+}
+
+impl DefaultDomainEventService {
+    pub fn new(indexed_storage: Arc<dyn IndexedStorage + Send + Sync>) -> Self {
+        Self {
+            indexed_storage,
+            append_locks: DashMap::new(),
+        }
+    }
+
+    fn key(component_id: &ComponentId, topic: &str) -> String {
+        format!("{component_id}:{topic}")
+    }
+
+    async fn append(&self, key: &str, mut event: DomainEvent) -> Result<u64, GolemError> {
+        let mut attempt = 0;
+        loop {
+            let last_id = self
+                .indexed_storage
+                .with_entity("domain_event", "emit", "domain_event")
+                .last_id(IndexedStorageNamespace::DomainEvents, key)
+                .await
+                .map_err(GolemError::runtime)?;
+            event.id = last_id.unwrap_or(0) + 1;
+
+            match self
+                .indexed_storage
+                .with_entity("domain_event", "emit", "domain_event")
+                .append(IndexedStorageNamespace::DomainEvents, key, event.id, &event)
+                .await
+            {
+                Ok(()) => break Ok(event.id),
+                Err(err) if attempt + 1 < MAX_APPEND_ATTEMPTS => {
+                    debug!("Retrying appending event {} to {key}: {err}", event.id);
+                    attempt += 1;
+                }
+                Err(err) => break Err(GolemError::runtime(err)),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DomainEventService for DefaultDomainEventService {
+    async fn emit(
+        &self,
+        worker_id: &WorkerId,
+        topic: &str,
+        payload: Vec<u8>,
+    ) -> Result<u64, GolemError> {
+        let key = Self::key(&worker_id.component_id, topic);
+        let event = DomainEvent {
+            id: 0,
+            worker_id: worker_id.clone(),
+            timestamp: Timestamp::now_utc(),
+            payload,
+        };
+
+        let lock = self.append_locks.entry(key.clone()).or_default().clone();
+        let guard = lock.lock().await;
+        let result = self.append(&key, event).await;
+        drop(guard);
+
+        // Forgets the lock of the topic when no other emit is holding or waiting for it
+        self.append_locks
+            .remove_if(&key, |_, lock| Arc::strong_count(lock) == 2);
+        result
+    }
+
+    async fn poll(
+        &self,
+        component_id: &ComponentId,
+        topic: &str,
+        cursor: u64,
+        count: u64,
+    ) -> Result<Vec<DomainEvent>, GolemError> {
+        let count = count.min(MAX_POLLED_EVENTS);
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let events: Vec<(u64, DomainEvent)> = self
+            .indexed_storage
+            .with_entity("domain_event", "poll", "domain_event")
+            .read(
+                IndexedStorageNamespace::DomainEvents,
+                &Self::key(component_id, topic),
+                cursor + 1,
+                cursor + count,
+            )
+            .await
+            .map_err(GolemError::runtime)?;
+        Ok(events.into_iter().map(|(_, event)| event).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::Arc;
+
+    use golem_common::model::{ComponentId, WorkerId};
+
+    use crate::services::domain_event::{DefaultDomainEventService, DomainEventService};
+    use crate::storage::indexed::memory::InMemoryIndexedStorage;
+
+    #[test]
+    async fn events_are_polled_by_topic_in_order() {
+        let service = DefaultDomainEventService::new(Arc::new(InMemoryIndexedStorage::new()));
+        let component_id = ComponentId::new_v4();
+        let worker_id = WorkerId {
+            component_id: component_id.clone(),
+            worker_name: "worker".to_string(),
+        };
+
+        for i in 0..5u8 {
+            service.emit(&worker_id, "orders", vec![i]).await.unwrap();
+        }
+        service
+            .emit(&worker_id, "payments", vec![42])
+            .await
+            .unwrap();
+
+        let first = service.poll(&component_id, "orders", 0, 3).await.unwrap();
+        assert_eq!(
+            first.iter().map(|e| e.payload.clone()).collect::<Vec<_>>(),
+            vec![vec![0], vec![1], vec![2]]
+        );
+
+        let rest = service
+            .poll(&component_id, "orders", first.last().unwrap().id, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            rest.iter().map(|e| e.payload.clone()).collect::<Vec<_>>(),
+            vec![vec![3], vec![4]]
+        );
+
+        let payments = service
+            .poll(&component_id, "payments", 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(payments.len(), 1);
+        assert_eq!(payments[0].id, 1);
+        assert_eq!(payments[0].worker_id, worker_id);
+    }
+
+    #[test]
+    async fn concurrent_emits_get_distinct_ids() {
+        let storage = Arc::new(InMemoryIndexedStorage::new());
+        // Two executors sharing the storage
+        let services = [
+            Arc::new(DefaultDomainEventService::new(storage.clone())),
+            Arc::new(DefaultDomainEventService::new(storage)),
+        ];
+        let component_id = ComponentId::new_v4();
+        let worker_id = WorkerId {
+            component_id: component_id.clone(),
+            worker_name: "worker".to_string(),
+        };
+
+        let emits = (0..20u8)
+            .map(|i| {
+                let service = services[i as usize % 2].clone();
+                let worker_id = worker_id.clone();
+                tokio::spawn(async move { service.emit(&worker_id, "orders", vec![i]).await })
+            })
+            .collect::<Vec<_>>();
+        let mut ids = Vec::new();
+        for emit in emits {
+            ids.push(emit.await.unwrap().unwrap());
+        }
+        ids.sort();
+
+        assert_eq!(ids, (1..=20).collect::<Vec<_>>());
+        assert!(services
+            .iter()
+            .all(|service| service.append_locks.is_empty()));
+    }
+}
@@ -13,9 +13,11 @@
 // limitations under the License.
 
 use crate::error::GolemError;
-use golem_common::model::{IdempotencyKey, WorkerId};
+use golem_common::model::worker_status_event::WorkerStatusEvent;
+use golem_common::model::{IdempotencyKey, WorkerId, WorkerMetadata};
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::wrappers::BroadcastStream;
 
 pub struct Events {
     sender: tokio::sync::broadcast::Sender<Event>,
@@ -70,6 +72,10 @@ impl EventsSubscription {
             }
         }
     }
+
+    pub fn into_stream(self) -> BroadcastStream<Event> {
+        BroadcastStream::new(self.receiver)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -83,4 +89,10 @@ pub enum Event {
         worker_id: WorkerId,
         result: Result<(), GolemError>,
     },
+    /// A worker got created, deleted or changed its status. The metadata is the one after the
+    /// change, or the last one before the worker got deleted.
+    WorkerStatusChanged {
+        event: WorkerStatusEvent,
+        metadata: Box<WorkerMetadata>,
+    },
 }
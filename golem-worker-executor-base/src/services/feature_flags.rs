@@ -0,0 +1,158 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+
+use golem_common::model::OwnedWorkerId;
+
+use crate::error::GolemError;
+use crate::services::golem_config::{FeatureFlagRule, FeatureFlagsConfig};
+
+/// Service resolving the feature flags of the workers.
+///
+/// The flags are resolved once per invocation and recorded in the oplog, so changing them
+/// does not affect the replay of the invocations which already read them.
+#[async_trait]
+pub trait FeatureFlagService {
+    /// Resolves all the flags set for the worker, by name
+    async fn resolve(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Result<BTreeMap<String, bool>, GolemError>;
+}
+
+/// Resolves the flags from the rules of the executor's configuration
+pub struct ConfiguredFeatureFlagService {
+    rules: Vec<FeatureFlagRule>,
+}
+
+impl ConfiguredFeatureFlagService {
+    pub fn new(config: &FeatureFlagsConfig) -> Self {
+        Self {
+            rules: config.rules.clone(),
+        }
+    }
+
+    // The number of selectors of the rule if it applies to the worker
+    fn specificity(rule: &FeatureFlagRule, owned_worker_id: &OwnedWorkerId) -> Option<usize> {
+        let selectors = [
+            rule.account_id
+                .as_ref()
+                .map(|account_id| *account_id == owned_worker_id.account_id.value),
+            rule.component_id.as_ref().map(|component_id| {
+                *component_id == owned_worker_id.worker_id.component_id.to_string()
+            }),
+            rule.worker_name
+                .as_ref()
+                .map(|worker_name| *worker_name == owned_worker_id.worker_id.worker_name),
+        ];
+        if selectors.iter().all(|matches| matches.unwrap_or(true)) {
+            Some(selectors.iter().filter(|matches| matches.is_some()).count())
+        } else {
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl FeatureFlagService for ConfiguredFeatureFlagService {
+    async fn resolve(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Result<BTreeMap<String, bool>, GolemError> {
+        let mut resolved: BTreeMap<String, (usize, bool)> = BTreeMap::new();
+        for rule in &self.rules {
+            if let Some(specificity) = Self::specificity(rule, owned_worker_id) {
+                let current = resolved.get(&rule.flag);
+                if current.map_or(true, |(current, _)| specificity >= *current) {
+                    resolved.insert(rule.flag.clone(), (specificity, rule.enabled));
+                }
+            }
+        }
+        Ok(resolved
+            .into_iter()
+            .map(|(flag, (_, enabled))| (flag, enabled))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::BTreeMap;
+
+    use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, WorkerId};
+
+    use crate::services::feature_flags::{ConfiguredFeatureFlagService, FeatureFlagService};
+    use crate::services::golem_config::{FeatureFlagRule, FeatureFlagsConfig};
+
+    fn rule(flag: &str, enabled: bool) -> FeatureFlagRule {
+        FeatureFlagRule {
+            flag: flag.to_string(),
+            enabled,
+            account_id: None,
+            component_id: None,
+            worker_name: None,
+        }
+    }
+
+    #[test]
+    async fn most_specific_rule_wins() {
+        let component_id = ComponentId::new_v4();
+        let worker = |worker_name: &str| {
+            OwnedWorkerId::new(
+                &AccountId::generate(),
+                &WorkerId {
+                    component_id: component_id.clone(),
+                    worker_name: worker_name.to_string(),
+                },
+            )
+        };
+        let service = ConfiguredFeatureFlagService::new(&FeatureFlagsConfig {
+            rules: vec![
+                FeatureFlagRule {
+                    worker_name: Some("beta".to_string()),
+                    ..rule("new-checkout", true)
+                },
+                rule("new-checkout", false),
+                FeatureFlagRule {
+                    component_id: Some(component_id.to_string()),
+                    ..rule("fast-path", true)
+                },
+                FeatureFlagRule {
+                    component_id: Some(ComponentId::new_v4().to_string()),
+                    ..rule("other-component", true)
+                },
+            ],
+        });
+
+        assert_eq!(
+            service.resolve(&worker("beta")).await.unwrap(),
+            BTreeMap::from([
+                ("fast-path".to_string(), true),
+                ("new-checkout".to_string(), true)
+            ])
+        );
+        assert_eq!(
+            service.resolve(&worker("stable")).await.unwrap(),
+            BTreeMap::from([
+                ("fast-path".to_string(), true),
+                ("new-checkout".to_string(), false)
+            ])
+        );
+    }
+}
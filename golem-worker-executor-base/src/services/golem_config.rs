@@ -24,10 +24,14 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 
 use golem_common::config::{
-    ConfigExample, ConfigLoader, DbSqliteConfig, HasConfigExamples, RedisConfig, RetryConfig,
+    ConfigExample, ConfigLoader, DbSqliteConfig, GrpcCompression, HasConfigExamples, RedisConfig,
+    RetryConfig,
 };
+use golem_common::model::oplog_codec::OplogEncoding;
 use golem_common::tracing::TracingConfig;
 
+use crate::services::inference::GraphEncoding;
+
 /// The shared global Golem configuration
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GolemConfig {
@@ -46,10 +50,14 @@ pub struct GolemConfig {
     pub suspend: SuspendConfig,
     pub active_workers: ActiveWorkersConfig,
     pub scheduler: SchedulerConfig,
+    pub autoscaling: AutoscalingConfig,
     pub public_worker_api: WorkerServiceGrpcConfig,
     pub memory: MemoryConfig,
+    pub wasi_nn: WasiNnConfig,
+    pub feature_flags: FeatureFlagsConfig,
     pub grpc_address: String,
     pub port: u16,
+    pub grpc_compression: GrpcCompression,
     pub http_address: String,
     pub http_port: u16,
 }
@@ -60,11 +68,39 @@ pub struct Limits {
     pub invocation_result_broadcast_capacity: usize,
     pub max_concurrent_streams: u32,
     pub event_broadcast_capacity: usize,
+    /// Directory keeping the recent events of each worker across executor restarts, when not
+    /// set the events are only kept in memory
+    pub event_history_dir: Option<PathBuf>,
+    /// Maximum size of the on-disk event history of a single worker
+    pub event_history_max_bytes: u64,
     pub event_history_size: usize,
     pub fuel_to_borrow: i64,
     #[serde(with = "humantime_serde")]
     pub epoch_interval: Duration,
     pub epoch_ticks: u64,
+    /// Maximum number of custom metrics the workers of a component can emit
+    pub max_guest_metrics: usize,
+    /// Maximum number of distinct label sets of all the custom metrics of a component
+    pub max_guest_metric_series: usize,
+    /// Worker connect streams are closed after this time, clients are expected to reconnect
+    #[serde(with = "humantime_serde")]
+    pub max_stream_duration: Duration,
+    /// Maximum number of worker events waiting to be sent on a worker connect stream before
+    /// its client is considered a slow consumer
+    pub max_stream_buffered_events: usize,
+    /// Maximum size in bytes of an invocation result returned in a response, larger results are
+    /// stored in the blob storage and can be retrieved separately
+    pub max_result_size: usize,
+    pub slow_stream_consumer_policy: SlowConsumerPolicy,
+}
+
+/// What to do with a worker connect stream whose client cannot keep up with the worker's events
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlowConsumerPolicy {
+    /// Skips the events waiting to be sent and continues with the new ones
+    Drop,
+    /// Closes the stream with an error
+    Disconnect,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -109,6 +145,43 @@ pub struct CompiledComponentServiceEnabledConfig {}
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CompiledComponentServiceDisabledConfig {}
 
+/// Support of the `wasi:nn` inference host interface. When disabled, workers can still be
+/// instantiated, but loading any model fails with an unsupported operation error.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum WasiNnConfig {
+    Enabled(WasiNnEnabledConfig),
+    Disabled(WasiNnDisabledConfig),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WasiNnEnabledConfig {
+    /// The blob store container of the worker's account models are loaded from by name
+    pub model_container: String,
+    /// Whether models can be executed on the GPUs of this executor
+    pub gpu: bool,
+    /// The inference engines models are loaded with, tried in order for autodetected encodings
+    #[serde(default)]
+    pub backends: Vec<InferenceBackendConfig>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum InferenceBackendConfig {
+    Remote(RemoteInferenceBackendConfig),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RemoteInferenceBackendConfig {
+    pub encoding: GraphEncoding,
+    pub url: Url,
+    #[serde(with = "humantime_serde")]
+    pub timeout: Duration,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WasiNnDisabledConfig {}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", content = "config")]
 pub enum ShardManagerServiceConfig {
@@ -128,6 +201,9 @@ pub struct WorkerServiceGrpcConfig {
     pub host: String,
     pub port: u16,
     pub access_token: String,
+    /// Retries of the requests rejected as temporarily unavailable, such as invocations of a
+    /// component in maintenance
+    pub retries: RetryConfig,
 }
 
 impl GolemConfig {
@@ -236,16 +312,77 @@ pub struct SchedulerConfig {
     pub refresh_interval: Duration,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AutoscalingConfig {
+    /// How often the autoscaling signals served by the HTTP interface are recalculated
+    #[serde(with = "humantime_serde")]
+    pub refresh_interval: Duration,
+}
+
+/// Feature flags exposed to the workers through `golem:api/feature-flags`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FeatureFlagsConfig {
+    /// When a flag has multiple matching rules, the most specific one wins, and of the equally
+    /// specific ones the last
+    pub rules: Vec<FeatureFlagRule>,
+}
+
+/// Sets a flag for the workers matching all the given selectors, or for all workers if there
+/// are no selectors
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FeatureFlagRule {
+    pub flag: String,
+    pub enabled: bool,
+    #[serde(default)]
+    pub account_id: Option<String>,
+    #[serde(default)]
+    pub component_id: Option<String>,
+    #[serde(default)]
+    pub worker_name: Option<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OplogConfig {
     pub max_operations_before_commit: u64,
     pub max_operations_before_commit_ephemeral: u64,
+    /// Maximum number of batches of oplog entries being committed in the background before
+    /// adding new entries to the oplog waits for them
+    pub max_commits_in_flight: usize,
+    /// Maximum number of workers committing oplog entries to the indexed storage at the same time
+    pub max_concurrent_commits: usize,
+    /// Maximum number of oplog entries a worker commits before letting the other workers waiting
+    /// to commit take their turn
+    pub commit_slice_size: usize,
+    /// Maximum number of oplog entries committed per second by all the workers, unlimited if
+    /// not set
+    pub max_committed_entries_per_second: Option<u64>,
     pub max_payload_size: usize,
     pub indexed_storage_layers: usize,
     pub blob_storage_layers: usize,
     pub entry_count_limit: u64,
     #[serde(with = "humantime_serde")]
     pub archive_interval: Duration,
+    /// Encoding of the oplog entries written to the primary oplog and to the archived chunks.
+    /// Entries are readable whichever encoding they were written with.
+    pub entry_encoding: OplogEncoding,
+    /// Whether entries upgraded from an older schema version when reading them are stored again
+    /// with the current one, if the indexed storage supports modifying entries
+    pub rewrite_migrated_entries: bool,
+    /// How the parameters of the host function calls made while replaying a worker are checked
+    /// against the ones recorded in its oplog
+    pub replay_consistency_check: ReplayConsistencyCheck,
+}
+
+/// What to do when a replayed worker calls a host function with different parameters than the
+/// ones recorded in its oplog, meaning its execution diverged from the original one
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplayConsistencyCheck {
+    /// The parameters are not checked
+    Disabled,
+    /// The divergence is logged and counted, and the recorded result is used
+    Lenient,
+    /// The worker fails with a report of the divergence
+    Strict,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -289,6 +426,9 @@ pub struct S3BlobStorageConfig {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LocalFileSystemBlobStorageConfig {
     pub root: PathBuf,
+    /// Number of directories on each of the two levels oplog payloads are distributed into.
+    /// Values below 2 store all payloads of a worker in a single directory.
+    pub oplog_payload_shard_fan_out: usize,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -338,11 +478,15 @@ impl Default for GolemConfig {
             oplog: OplogConfig::default(),
             suspend: SuspendConfig::default(),
             scheduler: SchedulerConfig::default(),
+            autoscaling: AutoscalingConfig::default(),
             active_workers: ActiveWorkersConfig::default(),
             public_worker_api: WorkerServiceGrpcConfig::default(),
             memory: MemoryConfig::default(),
+            wasi_nn: WasiNnConfig::default(),
+            feature_flags: FeatureFlagsConfig::default(),
             grpc_address: "0.0.0.0".to_string(),
             port: 9000,
+            grpc_compression: GrpcCompression::default(),
             http_address: "0.0.0.0".to_string(),
             http_port: 8082,
         }
@@ -382,10 +526,18 @@ impl Default for Limits {
             invocation_result_broadcast_capacity: 100000,
             max_concurrent_streams: 1024,
             event_broadcast_capacity: 16,
+            event_history_dir: None,
+            event_history_max_bytes: 1024 * 1024,
             event_history_size: 128,
             fuel_to_borrow: 10000,
             epoch_interval: Duration::from_millis(10),
             epoch_ticks: 1,
+            max_guest_metrics: 100,
+            max_guest_metric_series: 1000,
+            max_stream_duration: Duration::from_secs(12 * 60 * 60),
+            max_stream_buffered_events: 12,
+            max_result_size: 2 * 1024 * 1024,
+            slow_stream_consumer_policy: SlowConsumerPolicy::Drop,
         }
     }
 }
@@ -434,6 +586,12 @@ impl CompiledComponentServiceConfig {
     }
 }
 
+impl Default for WasiNnConfig {
+    fn default() -> Self {
+        Self::Disabled(WasiNnDisabledConfig {})
+    }
+}
+
 impl Default for S3BlobStorageConfig {
     fn default() -> Self {
         Self {
@@ -454,6 +612,7 @@ impl Default for LocalFileSystemBlobStorageConfig {
     fn default() -> Self {
         Self {
             root: PathBuf::from("../data/blob_storage"),
+            oplog_payload_shard_fan_out: 256,
         }
     }
 }
@@ -479,11 +638,18 @@ impl Default for OplogConfig {
         Self {
             max_operations_before_commit: 128,
             max_operations_before_commit_ephemeral: 512,
+            max_commits_in_flight: 4,
+            max_concurrent_commits: 64,
+            commit_slice_size: 64,
+            max_committed_entries_per_second: None,
             max_payload_size: 64 * 1024,
             indexed_storage_layers: 2,
             blob_storage_layers: 1,
             entry_count_limit: 1024,
             archive_interval: Duration::from_secs(60 * 60 * 24), // 24 hours
+            entry_encoding: OplogEncoding::Bincode,
+            rewrite_migrated_entries: false,
+            replay_consistency_check: ReplayConsistencyCheck::Lenient,
         }
     }
 }
@@ -513,12 +679,21 @@ impl Default for SchedulerConfig {
     }
 }
 
+impl Default for AutoscalingConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval: Duration::from_secs(5),
+        }
+    }
+}
+
 impl Default for WorkerServiceGrpcConfig {
     fn default() -> Self {
         Self {
             host: "localhost".to_string(),
             port: 9007,
             access_token: "2a354594-7a63-4091-a46b-cc58d379f677".to_string(),
+            retries: RetryConfig::max_attempts_3(),
         }
     }
 }
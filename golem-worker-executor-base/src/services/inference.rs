@@ -0,0 +1,491 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bincode::{Decode, Encode};
+use golem_common::serialization::{deserialize, serialize};
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Handle;
+use url::Url;
+
+use crate::error::GolemError;
+use crate::services::golem_config::{
+    InferenceBackendConfig, RemoteInferenceBackendConfig, WasiNnConfig,
+};
+
+/// Loads the machine learning models used by the `wasi:nn` host interface
+pub trait InferenceService {
+    /// Loads a model with the backend available for its encoding. With `Autodetect` all the
+    /// backends are tried in order. This is a blocking call.
+    fn load_model(
+        &self,
+        builders: &[Vec<u8>],
+        encoding: GraphEncoding,
+        target: ExecutionTarget,
+    ) -> Result<Arc<dyn InferenceModel>, InferenceError>;
+}
+
+/// An inference engine executing machine learning models for the `wasi:nn` host interface.
+pub trait InferenceBackend: Send + Sync {
+    fn encoding(&self) -> GraphEncoding;
+
+    /// Loads a model from its serialized parts, such as an ONNX file or the XML and weights
+    /// files of an OpenVINO model
+    fn load(
+        &self,
+        builders: &[Vec<u8>],
+        target: ExecutionTarget,
+    ) -> Result<Arc<dyn InferenceModel>, InferenceError>;
+}
+
+pub trait InferenceModel: Send + Sync {
+    /// Runs the model. This is a blocking call, potentially taking a long time.
+    fn compute(&self, inputs: Vec<NamedTensor>) -> Result<Vec<NamedTensor>, InferenceError>;
+}
+
+pub struct InferenceServiceDefault {
+    backends: Vec<Arc<dyn InferenceBackend>>,
+}
+
+impl InferenceServiceDefault {
+    pub fn new(backends: Vec<Arc<dyn InferenceBackend>>) -> Self {
+        Self { backends }
+    }
+
+    /// Creates the backends listed in the executor's `wasi_nn` configuration
+    pub fn configured(config: &WasiNnConfig) -> Self {
+        let backends = match config {
+            WasiNnConfig::Enabled(config) => config
+                .backends
+                .iter()
+                .map(|backend| match backend {
+                    InferenceBackendConfig::Remote(config) => {
+                        Arc::new(RemoteInferenceBackend::new(config)) as Arc<dyn InferenceBackend>
+                    }
+                })
+                .collect(),
+            WasiNnConfig::Disabled(_) => Vec::new(),
+        };
+        Self::new(backends)
+    }
+}
+
+impl InferenceService for InferenceServiceDefault {
+    fn load_model(
+        &self,
+        builders: &[Vec<u8>],
+        encoding: GraphEncoding,
+        target: ExecutionTarget,
+    ) -> Result<Arc<dyn InferenceModel>, InferenceError> {
+        let candidates = self
+            .backends
+            .iter()
+            .filter(|backend| {
+                encoding == GraphEncoding::Autodetect || backend.encoding() == encoding
+            })
+            .collect::<Vec<_>>();
+
+        let mut last_error = InferenceError::new(
+            InferenceErrorCode::InvalidEncoding,
+            format!("No inference backend is available for {encoding:?} models"),
+        );
+        for backend in candidates {
+            match backend.load(builders, target) {
+                Ok(model) => return Ok(model),
+                Err(error) => last_error = error,
+            }
+        }
+        Err(last_error)
+    }
+}
+
+/// A backend running the models on an inference server.
+///
+/// Models are uploaded with `POST {url}/models`, which responds with the model's identifier,
+/// and executed with `POST {url}/models/{id}/compute`. Both the requests and the responses
+/// are bincode encoded.
+pub struct RemoteInferenceBackend {
+    encoding: GraphEncoding,
+    url: Url,
+    client: reqwest::Client,
+    timeout: Duration,
+}
+
+impl RemoteInferenceBackend {
+    pub fn new(config: &RemoteInferenceBackendConfig) -> Self {
+        Self {
+            encoding: config.encoding,
+            url: config.url.clone(),
+            client: reqwest::Client::new(),
+            timeout: config.timeout,
+        }
+    }
+}
+
+impl InferenceBackend for RemoteInferenceBackend {
+    fn encoding(&self) -> GraphEncoding {
+        self.encoding
+    }
+
+    fn load(
+        &self,
+        builders: &[Vec<u8>],
+        target: ExecutionTarget,
+    ) -> Result<Arc<dyn InferenceModel>, InferenceError> {
+        let request = LoadModelRequest {
+            builders: builders.to_vec(),
+            encoding: self.encoding,
+            target,
+        };
+        let url = endpoint(&self.url, &["models"])?;
+        let id: String = post(&self.client, url, &request, self.timeout)?;
+        let compute_url = endpoint(&self.url, &["models", &id, "compute"])?;
+
+        Ok(Arc::new(RemoteInferenceModel {
+            url: compute_url,
+            client: self.client.clone(),
+            timeout: self.timeout,
+        }))
+    }
+}
+
+struct RemoteInferenceModel {
+    url: Url,
+    client: reqwest::Client,
+    timeout: Duration,
+}
+
+impl InferenceModel for RemoteInferenceModel {
+    fn compute(&self, inputs: Vec<NamedTensor>) -> Result<Vec<NamedTensor>, InferenceError> {
+        post(
+            &self.client,
+            self.url.clone(),
+            &ComputeRequest { inputs },
+            self.timeout,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct LoadModelRequest {
+    pub builders: Vec<Vec<u8>>,
+    pub encoding: GraphEncoding,
+    pub target: ExecutionTarget,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ComputeRequest {
+    pub inputs: Vec<NamedTensor>,
+}
+
+fn endpoint(base: &Url, segments: &[&str]) -> Result<Url, InferenceError> {
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .map_err(|_| {
+            InferenceError::new(
+                InferenceErrorCode::InvalidArgument,
+                format!("Invalid inference server URL: {base}"),
+            )
+        })?
+        .pop_if_empty()
+        .extend(segments);
+    Ok(url)
+}
+
+/// Sends a request to the inference server, blocking the current thread until the response
+/// arrives or the timeout elapses
+fn post<Req: Encode, Resp: Decode>(
+    client: &reqwest::Client,
+    url: Url,
+    request: &Req,
+    timeout: Duration,
+) -> Result<Resp, InferenceError> {
+    let runtime_error =
+        |message: String| InferenceError::new(InferenceErrorCode::RuntimeError, message);
+
+    let body = serialize(request).map_err(runtime_error)?;
+    let response = Handle::current().block_on(async {
+        tokio::time::timeout(timeout, async {
+            let response = client
+                .post(url)
+                .body(body.to_vec())
+                .send()
+                .await?
+                .error_for_status()?;
+            response.bytes().await
+        })
+        .await
+    });
+
+    match response {
+        Err(_) => Err(InferenceError::new(
+            InferenceErrorCode::Timeout,
+            format!("The inference server did not respond in {timeout:?}"),
+        )),
+        Ok(Err(err)) => Err(runtime_error(format!(
+            "Failed to call the inference server: {err}"
+        ))),
+        Ok(Ok(bytes)) => {
+            let result: Result<Resp, InferenceError> =
+                deserialize(&bytes).map_err(runtime_error)?;
+            result
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Encode, Decode, Serialize, Deserialize)]
+pub enum GraphEncoding {
+    Openvino,
+    Onnx,
+    Tensorflow,
+    Pytorch,
+    Tensorflowlite,
+    Ggml,
+    Autodetect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Encode, Decode)]
+pub enum ExecutionTarget {
+    Cpu,
+    Gpu,
+    Tpu,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum TensorType {
+    Fp16,
+    Fp32,
+    Fp64,
+    Bf16,
+    U8,
+    I32,
+    I64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Tensor {
+    pub dimensions: Vec<u32>,
+    pub ty: TensorType,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct NamedTensor {
+    pub name: String,
+    pub tensor: Tensor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum InferenceErrorCode {
+    InvalidArgument,
+    InvalidEncoding,
+    Timeout,
+    RuntimeError,
+    UnsupportedOperation,
+    TooLarge,
+    NotFound,
+    Security,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct InferenceError {
+    pub code: InferenceErrorCode,
+    pub message: String,
+}
+
+impl InferenceError {
+    pub fn new(code: InferenceErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for InferenceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
+}
+
+impl From<GolemError> for InferenceError {
+    fn from(value: GolemError) -> Self {
+        Self::new(InferenceErrorCode::RuntimeError, value.to_string())
+    }
+}
+
+impl From<&InferenceError> for InferenceError {
+    fn from(value: &InferenceError) -> Self {
+        value.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use bytes::Bytes;
+    use golem_common::serialization::{deserialize, serialize};
+    use warp::Filter;
+
+    use crate::services::golem_config::RemoteInferenceBackendConfig;
+    use crate::services::inference::{
+        ComputeRequest, ExecutionTarget, GraphEncoding, InferenceBackend, InferenceError,
+        InferenceErrorCode, InferenceModel, InferenceService, InferenceServiceDefault,
+        LoadModelRequest, NamedTensor, RemoteInferenceBackend, Tensor, TensorType,
+    };
+
+    struct Identity;
+
+    impl InferenceModel for Identity {
+        fn compute(&self, inputs: Vec<NamedTensor>) -> Result<Vec<NamedTensor>, InferenceError> {
+            Ok(inputs)
+        }
+    }
+
+    struct GgmlBackend;
+
+    impl InferenceBackend for GgmlBackend {
+        fn encoding(&self) -> GraphEncoding {
+            GraphEncoding::Ggml
+        }
+
+        fn load(
+            &self,
+            builders: &[Vec<u8>],
+            _target: ExecutionTarget,
+        ) -> Result<Arc<dyn InferenceModel>, InferenceError> {
+            if builders.is_empty() {
+                Err(InferenceError::new(
+                    InferenceErrorCode::InvalidArgument,
+                    "Missing model",
+                ))
+            } else {
+                Ok(Arc::new(Identity))
+            }
+        }
+    }
+
+    fn tensor() -> NamedTensor {
+        NamedTensor {
+            name: "input".to_string(),
+            tensor: Tensor {
+                dimensions: vec![2],
+                ty: TensorType::U8,
+                data: vec![1, 2],
+            },
+        }
+    }
+
+    #[test]
+    fn loads_models_with_matching_backend() {
+        let service = InferenceServiceDefault::new(vec![Arc::new(GgmlBackend)]);
+
+        assert!(service
+            .load_model(&[vec![1]], GraphEncoding::Ggml, ExecutionTarget::Cpu)
+            .is_ok());
+        assert!(service
+            .load_model(&[vec![1]], GraphEncoding::Autodetect, ExecutionTarget::Cpu)
+            .is_ok());
+        assert_eq!(
+            service
+                .load_model(&[], GraphEncoding::Ggml, ExecutionTarget::Cpu)
+                .err()
+                .map(|err| err.code),
+            Some(InferenceErrorCode::InvalidArgument)
+        );
+        assert_eq!(
+            service
+                .load_model(&[vec![1]], GraphEncoding::Onnx, ExecutionTarget::Cpu)
+                .err()
+                .map(|err| err.code),
+            Some(InferenceErrorCode::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn no_backends_without_configuration() {
+        let service = InferenceServiceDefault::configured(&Default::default());
+
+        assert_eq!(
+            service
+                .load_model(&[vec![1]], GraphEncoding::Autodetect, ExecutionTarget::Cpu)
+                .err()
+                .map(|err| err.code),
+            Some(InferenceErrorCode::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    async fn remote_backend_loads_and_computes() {
+        let load = warp::post()
+            .and(warp::path!("models"))
+            .and(warp::body::bytes())
+            .map(|body: Bytes| {
+                let request: LoadModelRequest = deserialize(&body).unwrap();
+                let response: Result<String, InferenceError> = if request.builders.is_empty() {
+                    Err(InferenceError::new(
+                        InferenceErrorCode::InvalidArgument,
+                        "Missing model",
+                    ))
+                } else {
+                    Ok("model-1".to_string())
+                };
+                serialize(&response).unwrap().to_vec()
+            });
+        let compute = warp::post()
+            .and(warp::path!("models" / String / "compute"))
+            .and(warp::body::bytes())
+            .map(|id: String, body: Bytes| {
+                assert_eq!(id, "model-1");
+                let request: ComputeRequest = deserialize(&body).unwrap();
+                let response: Result<Vec<NamedTensor>, InferenceError> = Ok(request.inputs);
+                serialize(&response).unwrap().to_vec()
+            });
+        let (addr, server) = warp::serve(load.or(compute)).bind_ephemeral(([127, 0, 0, 1], 0u16));
+        tokio::spawn(server);
+
+        let backend = RemoteInferenceBackend::new(&RemoteInferenceBackendConfig {
+            encoding: GraphEncoding::Onnx,
+            url: format!("http://{addr}").parse().unwrap(),
+            timeout: Duration::from_secs(10),
+        });
+        let service = InferenceServiceDefault::new(vec![Arc::new(backend)]);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let missing = service
+                .load_model(&[], GraphEncoding::Onnx, ExecutionTarget::Cpu)
+                .err()
+                .map(|err| err.code);
+            let model = service
+                .load_model(&[vec![1]], GraphEncoding::Onnx, ExecutionTarget::Cpu)
+                .unwrap();
+            (missing, model.compute(vec![tensor()]).unwrap())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result,
+            (Some(InferenceErrorCode::InvalidArgument), vec![tensor()])
+        );
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
@@ -21,7 +22,8 @@ use bytes::Bytes;
 use golem_common::model::AccountId;
 
 use crate::storage::keyvalue::{
-    KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+    KeyValueCheck, KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+    KeyValueWrite,
 };
 
 /// Service implementing a persistent key-value store
@@ -78,6 +80,25 @@ pub trait KeyValueService {
         bucket: String,
         key_values: Vec<(String, Vec<u8>)>,
     ) -> anyhow::Result<()>;
+
+    /// Sets a value which is deleted after the given time to live
+    async fn set_with_ttl(
+        &self,
+        account_id: AccountId,
+        bucket: String,
+        key: String,
+        outgoing_value: Vec<u8>,
+        ttl: Duration,
+    ) -> anyhow::Result<()>;
+
+    /// Atomically applies the writes if all the checks hold, returning whether they were applied
+    async fn commit(
+        &self,
+        account_id: AccountId,
+        bucket: String,
+        checks: Vec<KeyValueCheck>,
+        writes: Vec<KeyValueWrite>,
+    ) -> anyhow::Result<bool>;
 }
 
 #[derive(Clone, Debug)]
@@ -234,4 +255,43 @@ impl KeyValueService for DefaultKeyValueService {
             .map_err(|err| anyhow!(err))?;
         Ok(())
     }
+
+    async fn set_with_ttl(
+        &self,
+        account_id: AccountId,
+        bucket: String,
+        key: String,
+        outgoing_value: Vec<u8>,
+        ttl: Duration,
+    ) -> anyhow::Result<()> {
+        self.key_value_storage
+            .with_entity("key_value", "set_with_ttl", "custom")
+            .set_raw_with_ttl(
+                KeyValueStorageNamespace::UserDefined { account_id, bucket },
+                &key,
+                &outgoing_value,
+                ttl,
+            )
+            .await
+            .map_err(|err| anyhow!(err))?;
+        Ok(())
+    }
+
+    async fn commit(
+        &self,
+        account_id: AccountId,
+        bucket: String,
+        checks: Vec<KeyValueCheck>,
+        writes: Vec<KeyValueWrite>,
+    ) -> anyhow::Result<bool> {
+        self.key_value_storage
+            .with_entity("key_value", "commit", "custom")
+            .commit_raw(
+                KeyValueStorageNamespace::UserDefined { account_id, bucket },
+                &checks,
+                &writes,
+            )
+            .await
+            .map_err(|err| anyhow!(err))
+    }
 }
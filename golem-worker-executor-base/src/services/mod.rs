@@ -20,23 +20,30 @@ use crate::workerctx::WorkerCtx;
 use tokio::runtime::Handle;
 
 pub mod active_workers;
+pub mod autoscaling;
 pub mod blob_store;
 pub mod compiled_component;
 pub mod component;
+pub mod domain_event;
 pub mod events;
+pub mod feature_flags;
 pub mod golem_config;
+pub mod inference;
 pub mod key_value;
 pub mod oplog;
 pub mod promise;
+pub mod readiness;
 pub mod rpc;
 pub mod scheduler;
 pub mod shard;
 pub mod shard_manager;
 pub mod worker;
 pub mod worker_activator;
+pub mod worker_consistency;
 pub mod worker_enumeration;
 pub mod worker_event;
 pub mod worker_proxy;
+pub mod worker_shadow;
 pub mod ifs;
 // HasXXX traits for fine-grained control of which dependencies a function needs
 
@@ -94,6 +101,18 @@ pub trait HasBlobStoreService {
     fn blob_store_service(&self) -> Arc<dyn blob_store::BlobStoreService + Send + Sync>;
 }
 
+pub trait HasDomainEventService {
+    fn domain_event_service(&self) -> Arc<dyn domain_event::DomainEventService + Send + Sync>;
+}
+
+pub trait HasFeatureFlagService {
+    fn feature_flag_service(&self) -> Arc<dyn feature_flags::FeatureFlagService + Send + Sync>;
+}
+
+pub trait HasInferenceService {
+    fn inference_service(&self) -> Arc<dyn inference::InferenceService + Send + Sync>;
+}
+
 pub trait HasOplogService {
     fn oplog_service(&self) -> Arc<dyn oplog::OplogService + Send + Sync>;
 }
@@ -142,6 +161,9 @@ pub trait HasAll<Ctx: WorkerCtx>:
     + HasWasmtimeEngine<Ctx>
     + HasKeyValueService
     + HasBlobStoreService
+    + HasDomainEventService
+    + HasFeatureFlagService
+    + HasInferenceService
     + HasOplogService
     + HasRpc
     + HasSchedulerService
@@ -167,6 +189,9 @@ impl<
             + HasWasmtimeEngine<Ctx>
             + HasKeyValueService
             + HasBlobStoreService
+            + HasDomainEventService
+            + HasFeatureFlagService
+            + HasInferenceService
             + HasOplogService
             + HasRpc
             + HasSchedulerService
@@ -199,6 +224,9 @@ pub struct All<Ctx: WorkerCtx> {
     shard_service: Arc<dyn shard::ShardService + Send + Sync>,
     key_value_service: Arc<dyn key_value::KeyValueService + Send + Sync>,
     blob_store_service: Arc<dyn blob_store::BlobStoreService + Send + Sync>,
+    domain_event_service: Arc<dyn domain_event::DomainEventService + Send + Sync>,
+    feature_flag_service: Arc<dyn feature_flags::FeatureFlagService + Send + Sync>,
+    inference_service: Arc<dyn inference::InferenceService + Send + Sync>,
     oplog_service: Arc<dyn oplog::OplogService + Send + Sync>,
     rpc: Arc<dyn rpc::Rpc + Send + Sync>,
     scheduler_service: Arc<dyn scheduler::SchedulerService + Send + Sync>,
@@ -225,6 +253,9 @@ impl<Ctx: WorkerCtx> Clone for All<Ctx> {
             shard_service: self.shard_service.clone(),
             key_value_service: self.key_value_service.clone(),
             blob_store_service: self.blob_store_service.clone(),
+            domain_event_service: self.domain_event_service.clone(),
+            feature_flag_service: self.feature_flag_service.clone(),
+            inference_service: self.inference_service.clone(),
             oplog_service: self.oplog_service.clone(),
             rpc: self.rpc.clone(),
             scheduler_service: self.scheduler_service.clone(),
@@ -257,6 +288,9 @@ impl<Ctx: WorkerCtx> All<Ctx> {
         shard_service: Arc<dyn shard::ShardService + Send + Sync>,
         key_value_service: Arc<dyn key_value::KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn blob_store::BlobStoreService + Send + Sync>,
+        domain_event_service: Arc<dyn domain_event::DomainEventService + Send + Sync>,
+        feature_flag_service: Arc<dyn feature_flags::FeatureFlagService + Send + Sync>,
+        inference_service: Arc<dyn inference::InferenceService + Send + Sync>,
         oplog_service: Arc<dyn oplog::OplogService + Send + Sync>,
         rpc: Arc<dyn rpc::Rpc + Send + Sync>,
         scheduler_service: Arc<dyn scheduler::SchedulerService + Send + Sync>,
@@ -280,6 +314,9 @@ impl<Ctx: WorkerCtx> All<Ctx> {
             shard_service,
             key_value_service,
             blob_store_service,
+            domain_event_service,
+            feature_flag_service,
+            inference_service,
             oplog_service,
             rpc,
             scheduler_service,
@@ -306,6 +343,9 @@ impl<Ctx: WorkerCtx> All<Ctx> {
             this.shard_service(),
             this.key_value_service(),
             this.blob_store_service(),
+            this.domain_event_service(),
+            this.feature_flag_service(),
+            this.inference_service(),
             this.oplog_service(),
             this.rpc(),
             this.scheduler_service(),
@@ -415,6 +455,24 @@ impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasBlobStoreService for T {
     }
 }
 
+impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasDomainEventService for T {
+    fn domain_event_service(&self) -> Arc<dyn domain_event::DomainEventService + Send + Sync> {
+        self.all().domain_event_service.clone()
+    }
+}
+
+impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasFeatureFlagService for T {
+    fn feature_flag_service(&self) -> Arc<dyn feature_flags::FeatureFlagService + Send + Sync> {
+        self.all().feature_flag_service.clone()
+    }
+}
+
+impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasInferenceService for T {
+    fn inference_service(&self) -> Arc<dyn inference::InferenceService + Send + Sync> {
+        self.all().inference_service.clone()
+    }
+}
+
 impl<Ctx: WorkerCtx, T: UsesAllDeps<Ctx = Ctx>> HasOplogService for T {
     fn oplog_service(&self) -> Arc<dyn oplog::OplogService + Send + Sync> {
         self.all().oplog_service.clone()
@@ -26,6 +26,7 @@ use crate::storage::blob::{
 use async_trait::async_trait;
 use evicting_cache_map::EvictingCacheMap;
 use golem_common::model::oplog::{OplogEntry, OplogIndex};
+use golem_common::model::oplog_codec::OplogEncoding;
 use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, ScanCursor, WorkerId};
 use tokio::sync::RwLock;
 use tracing::{error, info};
@@ -36,6 +37,7 @@ use tracing::{error, info};
 pub struct BlobOplogArchiveService {
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
     level: usize,
+    entry_encoding: OplogEncoding,
 }
 
 impl BlobOplogArchiveService {
@@ -45,8 +47,16 @@ impl BlobOplogArchiveService {
         BlobOplogArchiveService {
             blob_storage,
             level,
+            entry_encoding: OplogEncoding::default(),
         }
     }
+
+    /// Sets the encoding of the entries in the newly written chunks. Chunks are readable whichever
+    /// encoding they were written with.
+    pub fn with_entry_encoding(mut self, entry_encoding: OplogEncoding) -> Self {
+        self.entry_encoding = entry_encoding;
+        self
+    }
 }
 
 #[async_trait]
@@ -57,6 +67,7 @@ impl OplogArchiveService for BlobOplogArchiveService {
                 owned_worker_id.clone(),
                 self.blob_storage.clone(),
                 self.level,
+                self.entry_encoding,
             )
             .await,
         )
@@ -176,6 +187,23 @@ impl OplogArchiveService for BlobOplogArchiveService {
             .copied()
             .unwrap_or_else(|| OplogIndex::from_u64(0))
     }
+
+    async fn replace(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idx: OplogIndex,
+        entry: OplogEntry,
+    ) -> Result<bool, String> {
+        BlobOplogArchive::new(
+            owned_worker_id.clone(),
+            self.blob_storage.clone(),
+            self.level,
+            self.entry_encoding,
+        )
+        .await
+        .replace(idx, entry)
+        .await
+    }
 }
 
 #[derive(Debug)]
@@ -183,6 +211,7 @@ struct BlobOplogArchive {
     owned_worker_id: OwnedWorkerId,
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
     level: usize,
+    entry_encoding: OplogEncoding,
     entries: Arc<RwLock<BTreeMap<OplogIndex, PathBuf>>>,
     #[allow(clippy::type_complexity)]
     cache: RwLock<
@@ -200,6 +229,7 @@ impl BlobOplogArchive {
         owned_worker_id: OwnedWorkerId,
         blob_storage: Arc<dyn BlobStorage + Send + Sync>,
         level: usize,
+        entry_encoding: OplogEncoding,
     ) -> Self {
 
 
@@ -255,6 +285,7 @@ impl BlobOplogArchive {
             owned_worker_id,
             blob_storage,
             level,
+            entry_encoding,
             entries,
             cache: RwLock::new(EvictingCacheMap::new()),
         }
@@ -339,6 +370,43 @@ impl BlobOplogArchive {
             Ok(None)
         }
     }
+
+    /// Overwrites an entry by recompressing the chunk holding it, returning false if no chunk
+    /// of this archive holds it
+    pub async fn replace(&self, idx: OplogIndex, entry: OplogEntry) -> Result<bool, String> {
+        let entries = self.entries.read().await;
+        let Some(last_idx) = entries.keys().find(|k| **k >= idx).copied() else {
+            return Ok(false);
+        };
+        let namespace = BlobStorageNamespace::CompressedOplog {
+            account_id: self.owned_worker_id.account_id(),
+            component_id: self.owned_worker_id.component_id(),
+            level: self.level,
+        };
+        let path = self.oplog_index_to_path(last_idx);
+
+        let chunk: CompressedOplogChunk = self
+            .blob_storage
+            .with("blob_oplog", "replace")
+            .get(namespace.clone(), &path)
+            .await?
+            .ok_or(format!("compressed chunk for {last_idx} not found"))?;
+        let first_idx = Into::<u64>::into(last_idx) + 1 - chunk.count;
+        if Into::<u64>::into(idx) < first_idx {
+            return Ok(false);
+        }
+
+        let mut chunk_entries = chunk.decompress()?;
+        chunk_entries[(Into::<u64>::into(idx) - first_idx) as usize] = entry.clone();
+        let chunk = CompressedOplogChunk::compress(chunk_entries, self.entry_encoding)?;
+        self.blob_storage
+            .with("blob_oplog", "replace")
+            .put(namespace, &path, &chunk)
+            .await?;
+
+        self.cache.write().await.insert(idx, entry);
+        Ok(true)
+    }
 }
 
 #[async_trait]
@@ -406,7 +474,7 @@ impl OplogArchive for BlobOplogArchive {
             let path = self.oplog_index_to_path(oplog_index);
 
             let chunk = chunk.into_iter().map(|(_, entry)| entry).collect();
-            let compressed_chunk = CompressedOplogChunk::compress(chunk)
+            let compressed_chunk = CompressedOplogChunk::compress(chunk, self.entry_encoding)
                 .unwrap_or_else(|err| panic!("failed to compress oplog chunk: {err}"));
 
             let mut entries = self.entries.write().await;
@@ -0,0 +1,113 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Scheduling of the background oplog commits of all the workers running on the executor.
+//!
+//! Every open primary oplog commits its entries to the indexed storage in the background. With
+//! many active workers these commits compete for the storage, so they take turns:
+//! - a worker commits at most `slice_size` entries in one turn, then queues up again behind the
+//!   other waiting workers, so a worker with a long backlog cannot hold back the others
+//! - at most `max_concurrent_commits` turns run at the same time
+//! - the number of entries committed per second is capped, if configured
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+use crate::metrics::oplog::{record_commit_dequeued, record_commit_queued};
+
+#[derive(Clone, Debug)]
+pub struct CommitScheduler {
+    /// The semaphore hands out the permits in the order they were requested, which makes the
+    /// turns fair between the workers
+    turns: Arc<Semaphore>,
+    slice_size: usize,
+    throttle: Option<Arc<Mutex<Throttle>>>,
+}
+
+impl CommitScheduler {
+    pub fn new(
+        max_concurrent_commits: usize,
+        slice_size: usize,
+        max_entries_per_second: Option<u64>,
+    ) -> Self {
+        Self {
+            turns: Arc::new(Semaphore::new(
+                max_concurrent_commits.clamp(1, Semaphore::MAX_PERMITS),
+            )),
+            slice_size: slice_size.max(1),
+            throttle: max_entries_per_second.map(|entries_per_second| {
+                Arc::new(Mutex::new(Throttle {
+                    entries_per_second: entries_per_second.max(1),
+                    next_free: Instant::now(),
+                }))
+            }),
+        }
+    }
+
+    /// A scheduler which lets every commit run immediately
+    pub fn unlimited() -> Self {
+        Self::new(Semaphore::MAX_PERMITS, usize::MAX, None)
+    }
+
+    /// The maximum number of entries committed by a worker in one turn
+    pub fn slice_size(&self) -> usize {
+        self.slice_size
+    }
+
+    /// Waits for a worker's turn to commit `entries` entries, which have been waiting to be
+    /// committed since `queued_since`. The turn lasts until the returned value is dropped.
+    pub async fn turn(&self, entries: usize, queued_since: Instant) -> CommitTurn {
+        record_commit_queued();
+        let permit = self
+            .turns
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("the commit scheduler's semaphore is never closed");
+
+        if let Some(throttle) = &self.throttle {
+            let start = throttle.lock().unwrap().reserve(entries as u64);
+            tokio::time::sleep_until(start).await;
+        }
+
+        record_commit_dequeued(queued_since.elapsed());
+        CommitTurn { _permit: permit }
+    }
+}
+
+/// A worker's turn to commit oplog entries, ending when dropped
+pub struct CommitTurn {
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Spreads the committed entries in time to keep them under the throughput cap
+#[derive(Debug)]
+struct Throttle {
+    entries_per_second: u64,
+    /// The time from which new entries can be committed without exceeding the cap
+    next_free: Instant,
+}
+
+impl Throttle {
+    /// Reserves the capacity for committing `entries` entries, returning when they can be committed
+    fn reserve(&mut self, entries: u64) -> Instant {
+        let start = self.next_free.max(Instant::now());
+        self.next_free =
+            start + Duration::from_secs_f64(entries as f64 / self.entries_per_second as f64);
+        start
+    }
+}
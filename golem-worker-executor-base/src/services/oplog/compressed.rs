@@ -22,8 +22,9 @@ use evicting_cache_map::EvictingCacheMap;
 use tokio::sync::RwLock;
 use crate::error::GolemError;
 use golem_common::model::oplog::{OplogEntry, OplogIndex};
+use golem_common::model::oplog_codec::{self, OplogEncoding};
 use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, ScanCursor, WorkerId};
-use golem_common::serialization::{deserialize, serialize};
+use golem_common::serialization::serialize;
 
 use crate::services::oplog::multilayer::{OplogArchive, OplogArchiveService};
 use crate::services::oplog::PrimaryOplogService;
@@ -33,6 +34,7 @@ use crate::storage::indexed::{IndexedStorage, IndexedStorageLabelledApi, Indexed
 pub struct CompressedOplogArchiveService {
     indexed_storage: Arc<dyn IndexedStorage + Send + Sync>,
     level: usize,
+    entry_encoding: OplogEncoding,
 }
 
 impl CompressedOplogArchiveService {
@@ -43,9 +45,17 @@ impl CompressedOplogArchiveService {
         Self {
             indexed_storage,
             level,
+            entry_encoding: OplogEncoding::default(),
         }
     }
 
+    /// Sets the encoding of the entries in the newly written chunks. Chunks are readable whichever
+    /// encoding they were written with.
+    pub fn with_entry_encoding(mut self, entry_encoding: OplogEncoding) -> Self {
+        self.entry_encoding = entry_encoding;
+        self
+    }
+
     fn compressed_oplog_key(worker_id: &WorkerId) -> String {
         worker_id.to_redis_key()
     }
@@ -58,6 +68,7 @@ impl OplogArchiveService for CompressedOplogArchiveService {
             owned_worker_id.worker_id(),
             self.indexed_storage.clone(),
             self.level,
+            self.entry_encoding,
         ))
     }
 
@@ -138,6 +149,22 @@ impl OplogArchiveService for CompressedOplogArchiveService {
                 }).unwrap_or_default(),
         )
     }
+
+    async fn replace(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idx: OplogIndex,
+        entry: OplogEntry,
+    ) -> Result<bool, String> {
+        CompressedOplogArchive::new(
+            owned_worker_id.worker_id(),
+            self.indexed_storage.clone(),
+            self.level,
+            self.entry_encoding,
+        )
+        .replace(idx, entry)
+        .await
+    }
 }
 
 #[derive(Debug)]
@@ -155,6 +182,7 @@ pub struct CompressedOplogArchive {
         >,
     >,
     level: usize,
+    entry_encoding: OplogEncoding,
 }
 
 impl CompressedOplogArchive {
@@ -162,6 +190,7 @@ impl CompressedOplogArchive {
         worker_id: WorkerId,
         indexed_storage: Arc<dyn IndexedStorage + Send + Sync>,
         level: usize,
+        entry_encoding: OplogEncoding,
     ) -> Self {
         let key = CompressedOplogArchiveService::compressed_oplog_key(&worker_id);
         Self {
@@ -170,6 +199,7 @@ impl CompressedOplogArchive {
             indexed_storage,
             cache: RwLock::new(EvictingCacheMap::new()),
             level,
+            entry_encoding,
         }
     }
 
@@ -198,6 +228,40 @@ impl CompressedOplogArchive {
             Ok(None)
         }
     }
+
+    /// Overwrites an entry by recompressing the chunk holding it, returning false if no chunk
+    /// of this archive holds it
+    pub async fn replace(&self, idx: OplogIndex, entry: OplogEntry) -> Result<bool, String> {
+        let storage =
+            self.indexed_storage
+                .with_entity("compressed_oplog", "replace", "compressed_entry");
+        let namespace = IndexedStorageNamespace::CompressedOpLog { level: self.level };
+
+        match storage
+            .closest::<CompressedOplogChunk>(namespace.clone(), &self.key, idx.into())
+            .await?
+        {
+            Some((last_idx, chunk)) if last_idx - chunk.count < Into::<u64>::into(idx) => {
+                let first_idx = last_idx - chunk.count + 1;
+                let mut entries = chunk.decompress()?;
+                entries[(Into::<u64>::into(idx) - first_idx) as usize] = entry.clone();
+
+                let chunk = CompressedOplogChunk::compress(entries, self.entry_encoding)?;
+                if !storage
+                    .replace_raw(namespace, &self.key, last_idx, &serialize(&chunk)?)
+                    .await?
+                {
+                    return Err(
+                        "The configured indexed storage does not support replacing archived oplog entries"
+                            .to_string(),
+                    );
+                }
+                self.cache.write().await.insert(idx, entry);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 }
 
 /// Currently only the background-transfer fiber calls `append` and `drop_prefix` on oplog archives,
@@ -281,7 +345,7 @@ impl OplogArchive for CompressedOplogArchive {
 
             let last_id = chunk.last().unwrap().0;
             let chunk = chunk.into_iter().map(|(_, entry)| entry).collect();
-            let compressed_chunk = CompressedOplogChunk::compress(chunk)
+            let compressed_chunk = CompressedOplogChunk::compress(chunk, self.entry_encoding)
                 .unwrap_or_else(|err| panic!("failed to compress oplog chunk: {err}"));
 
             self.indexed_storage
@@ -368,10 +432,10 @@ pub struct CompressedOplogChunk {
 }
 
 impl CompressedOplogChunk {
-    pub fn compress(entries: Vec<OplogEntry>) -> Result<Self, String> {
+    pub fn compress(entries: Vec<OplogEntry>, encoding: OplogEncoding) -> Result<Self, String> {
         let count = entries.len() as u64;
-        let uncompressed_data =
-            serialize(&entries).map_err(|err| format!("failed to serialize oplog chunk: {err}"))?;
+        let uncompressed_data = oplog_codec::encode_chunk(encoding, &entries)
+            .map_err(|err| format!("failed to serialize oplog chunk: {err}"))?;
         let compressed_data = zstd::encode_all(
             &*uncompressed_data,
             CompressedOplogArchiveService::ZSTD_LEVEL,
@@ -386,7 +450,7 @@ impl CompressedOplogChunk {
     pub fn decompress(&self) -> Result<Vec<OplogEntry>, String> {
         let uncompressed_data = zstd::decode_all(&*self.compressed_data)
             .map_err(|err| format!("failed to decompress oplog chunk: {err}"))?;
-        deserialize(&uncompressed_data)
+        oplog_codec::decode_chunk(&uncompressed_data)
             .map_err(|err| format!("failed to deserialize oplog chunk: {err}"))
     }
 }
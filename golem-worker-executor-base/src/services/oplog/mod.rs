@@ -24,14 +24,15 @@ use async_trait::async_trait;
 use bincode::{Decode, Encode};
 pub use blob::BlobOplogArchiveService;
 use bytes::Bytes;
+pub use commit_scheduler::CommitScheduler;
 pub use compressed::{CompressedOplogArchive, CompressedOplogArchiveService, CompressedOplogChunk};
 use golem_common::cache::{BackgroundEvictionMode, Cache, FullCacheEvictionMode};
 use golem_common::model::oplog::{
     OplogEntry, OplogIndex, OplogPayload, UpdateDescription, WrappedFunctionType,
 };
 use golem_common::model::{
-    AccountId, ComponentId, ComponentType, ComponentVersion, IdempotencyKey, OwnedWorkerId,
-    ScanCursor, Timestamp, WorkerId,
+    AccountId, CallerIdentity, ComponentId, ComponentType, ComponentVersion, IdempotencyKey,
+    OwnedWorkerId, ScanCursor, Timestamp, WorkerId,
 };
 use golem_common::serialization::{serialize, try_deserialize};
 pub use multilayer::{MultiLayerOplog, MultiLayerOplogService, OplogArchiveService};
@@ -41,6 +42,7 @@ use tracing::{info, Instrument};
 use crate::error::GolemError;
 
 mod blob;
+mod commit_scheduler;
 mod compressed;
 mod ephemeral;
 mod multilayer;
@@ -147,6 +149,22 @@ pub trait OplogService: Debug {
         owned_worker_id: &OwnedWorkerId,
         payload: &OplogPayload,
     ) -> Result<Bytes, String>;
+
+    /// Overwrites an already committed oplog entry. Only used for redacting entries, the new
+    /// entry must not change how the worker gets replayed.
+    async fn replace(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idx: OplogIndex,
+        entry: OplogEntry,
+    ) -> Result<(), String>;
+
+    /// Deletes a big oplog payload which is no longer referenced by any oplog entry
+    async fn delete_payload(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        payload: &OplogPayload,
+    ) -> Result<(), String>;
 }
 
 /// Level of commit guarantees
@@ -243,6 +261,7 @@ pub trait OplogOps: Oplog {
         function_name: String,
         request: &R,
         idempotency_key: IdempotencyKey,
+        caller: Option<CallerIdentity>,
     ) -> Result<OplogEntry, String> {
         let serialized_request = serialize(request)?.to_vec();
 
@@ -252,6 +271,7 @@ pub trait OplogOps: Oplog {
             function_name,
             request: payload,
             idempotency_key,
+            caller,
         };
         self.add(entry.clone()).await;
         Ok(entry)
@@ -299,7 +319,8 @@ pub trait OplogOps: Oplog {
                 let response_bytes: Bytes = self.download_payload(response).await?;
                 try_deserialize(&response_bytes)
             }
-            OplogEntry::ExportedFunctionInvoked { request, .. } => {
+            OplogEntry::ExportedFunctionInvokedV1 { request, .. }
+            | OplogEntry::ExportedFunctionInvoked { request, .. } => {
                 let response_bytes: Bytes = self.download_payload(request).await?;
                 try_deserialize(&response_bytes)
             }
@@ -66,6 +66,15 @@ pub trait OplogArchiveService: Debug {
 
     /// Gets the last stored oplog entry's id in the archive
     async fn get_last_index(&self, owned_worker_id: &OwnedWorkerId) -> OplogIndex;
+
+    /// Overwrites an already archived oplog entry by rewriting the chunk holding it. Returns false
+    /// if the entry is not stored in this archive.
+    async fn replace(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idx: OplogIndex,
+        entry: OplogEntry,
+    ) -> Result<bool, String>;
 }
 
 /// Interface for secondary oplog archives - requires less functionality than the primary archive
@@ -439,6 +448,33 @@ impl OplogService for MultiLayerOplogService {
             .download_payload(owned_worker_id, payload)
             .await
     }
+
+    async fn replace(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idx: OplogIndex,
+        entry: OplogEntry,
+    ) -> Result<(), String> {
+        if !self.primary.read(owned_worker_id, idx, 1).await.is_empty() {
+            return self.primary.replace(owned_worker_id, idx, entry).await;
+        }
+        for layer in &self.lower {
+            if layer.replace(owned_worker_id, idx, entry.clone()).await? {
+                return Ok(());
+            }
+        }
+        Err(format!(
+            "Oplog entry {idx} of {owned_worker_id} does not exist"
+        ))
+    }
+
+    async fn delete_payload(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        payload: &OplogPayload,
+    ) -> Result<(), String> {
+        self.primary.delete_payload(owned_worker_id, payload).await
+    }
 }
 
 pub struct MultiLayerOplog {
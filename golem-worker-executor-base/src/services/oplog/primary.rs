@@ -14,22 +14,30 @@
 
 use crate::error::GolemError;
 use crate::metrics::oplog::record_oplog_call;
-use crate::services::oplog::{CommitLevel, OpenOplogs, Oplog, OplogConstructor, OplogService};
+use crate::services::oplog::{
+    CommitLevel, CommitScheduler, OpenOplogs, Oplog, OplogConstructor, OplogService,
+};
 use crate::storage::blob::{BlobStorage, BlobStorageNamespace};
 use crate::storage::indexed::{IndexedStorage, IndexedStorageLabelledApi, IndexedStorageNamespace};
 use async_mutex::Mutex;
 use async_trait::async_trait;
 use bytes::Bytes;
+use futures::future::{BoxFuture, Shared};
+use futures::FutureExt;
 use golem_common::model::oplog::{OplogEntry, OplogIndex, OplogPayload, PayloadId};
+use golem_common::model::oplog_codec::{self, OplogEncoding};
 use golem_common::model::{
     AccountId, ComponentId, ComponentType, OwnedWorkerId, ScanCursor, WorkerId,
 };
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::fmt::{Debug, Formatter};
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
-use tracing::{error, info};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, error, info, warn};
 
 /// The primary oplog service implementation, suitable for direct use (top level of a multi-layered setup).
 ///
@@ -41,8 +49,13 @@ pub struct PrimaryOplogService {
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
     replicas: u8,
     max_operations_before_commit: u64,
+    max_commits_in_flight: usize,
     max_payload_size: usize,
+    entry_encoding: OplogEncoding,
+    rewrite_migrated_entries: bool,
+    commit_scheduler: CommitScheduler,
     oplogs: OpenOplogs,
+    closing_oplogs: ClosingOplogs,
 }
 
 impl PrimaryOplogService {
@@ -50,7 +63,10 @@ impl PrimaryOplogService {
         indexed_storage: Arc<dyn IndexedStorage + Send + Sync>,
         blob_storage: Arc<dyn BlobStorage + Send + Sync>,
         max_operations_before_commit: u64,
+        max_commits_in_flight: usize,
         max_payload_size: usize,
+        entry_encoding: OplogEncoding,
+        rewrite_migrated_entries: bool,
     ) -> Self {
         let replicas = indexed_storage
             .with("oplog", "new")
@@ -64,11 +80,23 @@ impl PrimaryOplogService {
             blob_storage,
             replicas,
             max_operations_before_commit,
+            max_commits_in_flight,
             max_payload_size,
+            entry_encoding,
+            rewrite_migrated_entries,
+            commit_scheduler: CommitScheduler::unlimited(),
             oplogs: OpenOplogs::new("primary oplog"),
+            closing_oplogs: ClosingOplogs::new(),
         }
     }
 
+    /// Schedules the background commits of all the opened oplogs with `commit_scheduler`, instead
+    /// of letting them run as soon as they are requested
+    pub fn with_commit_scheduler(mut self, commit_scheduler: CommitScheduler) -> Self {
+        self.commit_scheduler = commit_scheduler;
+        self
+    }
+
     fn oplog_key(worker_id: &WorkerId) -> String {
         worker_id.to_redis_key()
     }
@@ -101,7 +129,7 @@ impl PrimaryOplogService {
             let md5_hash = md5::compute(data).to_vec();
 
             blob_storage
-                .put_raw(
+                .put_raw_staged(
                     "oplog",
                     "upload_payload",
                     BlobStorageNamespace::OplogPayload {
@@ -160,6 +188,7 @@ impl OplogService for PrimaryOplogService {
     ) -> Arc<dyn Oplog + Send + Sync> {
         record_oplog_call("create");
         let key = Self::oplog_key(&owned_worker_id.worker_id);
+        self.closing_oplogs.wait_for(&key).await;
         let already_exists: bool = self
             .indexed_storage
             .with("oplog", "create")
@@ -174,9 +203,13 @@ impl OplogService for PrimaryOplogService {
             panic!("oplog for worker {owned_worker_id} already exists in indexed storage")
         }
 
+        let initial_entry = oplog_codec::encode(self.entry_encoding, &initial_entry)
+            .unwrap_or_else(|err| {
+                panic!("failed to encode initial oplog entry for worker {owned_worker_id}: {err}")
+            });
         self.indexed_storage
             .with_entity("oplog", "create", "entry")
-            .append(IndexedStorageNamespace::OpLog, &key, 1, &initial_entry)
+            .append_raw(IndexedStorageNamespace::OpLog, &key, 1, &initial_entry)
             .await
             .unwrap_or_else(|err| {
                 panic!(
@@ -197,6 +230,7 @@ impl OplogService for PrimaryOplogService {
         record_oplog_call("open");
 
         let key = Self::oplog_key(&owned_worker_id.worker_id);
+        self.closing_oplogs.wait_for(&key).await;
 
         self.oplogs
             .get_or_open(
@@ -206,7 +240,12 @@ impl OplogService for PrimaryOplogService {
                     self.blob_storage.clone(),
                     self.replicas,
                     self.max_operations_before_commit,
+                    self.max_commits_in_flight,
                     self.max_payload_size,
+                    self.entry_encoding,
+                    self.rewrite_migrated_entries,
+                    self.commit_scheduler.clone(),
+                    self.closing_oplogs.clone(),
                     key,
                     last_oplog_index,
                     owned_worker_id.clone(),
@@ -218,10 +257,12 @@ impl OplogService for PrimaryOplogService {
     async fn get_last_index(&self, owned_worker_id: &OwnedWorkerId) -> OplogIndex {
         record_oplog_call("get_last_index");
 
+        let key = Self::oplog_key(&owned_worker_id.worker_id);
+        self.closing_oplogs.wait_for(&key).await;
         OplogIndex::from_u64(
         self.indexed_storage
             .with_entity("oplog", "get_last_index", "entry")
-            .last_id(IndexedStorageNamespace::OpLog, &Self::oplog_key(&owned_worker_id.worker_id))
+            .last_id(IndexedStorageNamespace::OpLog, &key)
             .await
             .unwrap_or_else(|err| {
                 panic!(
@@ -235,12 +276,11 @@ impl OplogService for PrimaryOplogService {
     async fn delete(&self, owned_worker_id: &OwnedWorkerId) {
         record_oplog_call("delete");
 
+        let key = Self::oplog_key(&owned_worker_id.worker_id);
+        self.closing_oplogs.wait_for(&key).await;
         self.indexed_storage
             .with("oplog", "delete")
-            .delete(
-                IndexedStorageNamespace::OpLog,
-                &Self::oplog_key(&owned_worker_id.worker_id),
-            )
+            .delete(IndexedStorageNamespace::OpLog, &key)
             .await
             .unwrap_or_else(|err| {
                 panic!(
@@ -257,11 +297,14 @@ impl OplogService for PrimaryOplogService {
     ) -> BTreeMap<OplogIndex, OplogEntry> {
         record_oplog_call("read");
 
-        self.indexed_storage
+        let key = Self::oplog_key(&owned_worker_id.worker_id);
+        self.closing_oplogs.wait_for(&key).await;
+        let entries = self
+            .indexed_storage
             .with_entity("oplog", "read", "entry")
-            .read(
+            .read_raw(
                 IndexedStorageNamespace::OpLog,
-                &Self::oplog_key(&owned_worker_id.worker_id),
+                &key,
                 idx.into(),
                 idx.range_end(n).into(),
             )
@@ -270,10 +313,25 @@ impl OplogService for PrimaryOplogService {
                 panic!(
                     "failed to read oplog for worker {owned_worker_id} from indexed storage: {err}"
                 )
-            })
-            .into_iter()
-            .map(|(k, v): (u64, OplogEntry)| (OplogIndex::from_u64(k), v))
-            .collect()
+            });
+
+        let mut result = BTreeMap::new();
+        for (id, bytes) in entries {
+            let entry = decode_entry(
+                &self.indexed_storage,
+                self.entry_encoding,
+                self.rewrite_migrated_entries,
+                &key,
+                id,
+                &bytes,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to decode oplog entry {id} of worker {owned_worker_id}: {err}")
+            });
+            result.insert(OplogIndex::from_u64(id), entry);
+        }
+        result
     }
 
     async fn exists(&self, owned_worker_id: &OwnedWorkerId) -> bool {
@@ -343,6 +401,61 @@ impl OplogService for PrimaryOplogService {
     ) -> Result<Bytes, String> {
         Self::download_payload(self.blob_storage.clone(), owned_worker_id, payload).await
     }
+
+    async fn replace(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idx: OplogIndex,
+        entry: OplogEntry,
+    ) -> Result<(), String> {
+        record_oplog_call("replace");
+
+        let bytes = oplog_codec::encode(self.entry_encoding, &entry)?;
+        let replaced = self
+            .indexed_storage
+            .with_entity("oplog", "replace", "entry")
+            .replace_raw(
+                IndexedStorageNamespace::OpLog,
+                &Self::oplog_key(&owned_worker_id.worker_id),
+                idx.into(),
+                &bytes,
+            )
+            .await?;
+        if replaced {
+            Ok(())
+        } else {
+            Err(
+                "The configured indexed storage does not support replacing oplog entries"
+                    .to_string(),
+            )
+        }
+    }
+
+    async fn delete_payload(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        payload: &OplogPayload,
+    ) -> Result<(), String> {
+        match payload {
+            OplogPayload::Inline(_) => Ok(()),
+            OplogPayload::External {
+                payload_id,
+                md5_hash,
+            } => {
+                self.blob_storage
+                    .delete(
+                        "oplog",
+                        "delete_payload",
+                        BlobStorageNamespace::OplogPayload {
+                            account_id: owned_worker_id.account_id(),
+                            worker_id: owned_worker_id.worker_id(),
+                        },
+                        Path::new(&format!("{}/{}", hex::encode(md5_hash), payload_id.0)),
+                    )
+                    .await
+            }
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -351,7 +464,12 @@ struct CreateOplogConstructor {
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
     replicas: u8,
     max_operations_before_commit: u64,
+    max_commits_in_flight: usize,
     max_payload_size: usize,
+    entry_encoding: OplogEncoding,
+    rewrite_migrated_entries: bool,
+    commit_scheduler: CommitScheduler,
+    closing_oplogs: ClosingOplogs,
     key: String,
     last_oplog_idx: OplogIndex,
     owned_worker_id: OwnedWorkerId,
@@ -363,7 +481,12 @@ impl CreateOplogConstructor {
         blob_storage: Arc<dyn BlobStorage + Send + Sync>,
         replicas: u8,
         max_operations_before_commit: u64,
+        max_commits_in_flight: usize,
         max_payload_size: usize,
+        entry_encoding: OplogEncoding,
+        rewrite_migrated_entries: bool,
+        commit_scheduler: CommitScheduler,
+        closing_oplogs: ClosingOplogs,
         key: String,
         last_oplog_idx: OplogIndex,
         owned_worker_id: OwnedWorkerId,
@@ -373,7 +496,12 @@ impl CreateOplogConstructor {
             blob_storage,
             replicas,
             max_operations_before_commit,
+            max_commits_in_flight,
             max_payload_size,
+            entry_encoding,
+            rewrite_migrated_entries,
+            commit_scheduler,
+            closing_oplogs,
             key,
             last_oplog_idx,
             owned_worker_id,
@@ -392,7 +520,12 @@ impl OplogConstructor for CreateOplogConstructor {
             self.blob_storage,
             self.replicas,
             self.max_operations_before_commit,
+            self.max_commits_in_flight,
             self.max_payload_size,
+            self.entry_encoding,
+            self.rewrite_migrated_entries,
+            self.commit_scheduler,
+            self.closing_oplogs,
             self.key,
             self.last_oplog_idx,
             self.owned_worker_id,
@@ -401,14 +534,80 @@ impl OplogConstructor for CreateOplogConstructor {
     }
 }
 
+/// The commit pipelines of the dropped oplogs which may still be committing their last batches,
+/// by oplog key.
+///
+/// Accessing an oplog through the service waits for its pipeline to finish first, so a reopened
+/// oplog never continues from a stale last index.
+#[derive(Clone)]
+struct ClosingOplogs {
+    committers: Arc<std::sync::Mutex<HashMap<String, Shared<BoxFuture<'static, ()>>>>>,
+}
+
+impl ClosingOplogs {
+    fn new() -> Self {
+        Self {
+            committers: Arc::new(std::sync::Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn add(&self, key: String, committer: BoxFuture<'static, ()>) {
+        self.committers
+            .lock()
+            .unwrap()
+            .insert(key, committer.shared());
+    }
+
+    /// Waits until the oplog `key`, if it was dropped, has committed all its entries
+    async fn wait_for(&self, key: &str) {
+        let committer = self.committers.lock().unwrap().get(key).cloned();
+        if let Some(committer) = committer {
+            committer.await;
+            let mut committers = self.committers.lock().unwrap();
+            if committers
+                .get(key)
+                .is_some_and(|committer| committer.peek().is_some())
+            {
+                committers.remove(key);
+            }
+        }
+    }
+}
+
+impl Debug for ClosingOplogs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClosingOplogs").finish()
+    }
+}
+
 struct PrimaryOplog {
     state: Arc<Mutex<PrimaryOplogState>>,
     key: String,
+    committer: Option<JoinHandle<()>>,
+    closing_oplogs: ClosingOplogs,
     close: Option<Box<dyn FnOnce() + Send + Sync>>,
 }
 
 impl Drop for PrimaryOplog {
     fn drop(&mut self) {
+        if let Some(committer) = self.committer.take() {
+            // Hands the still buffered entries over to the commit pipeline, which stops once the
+            // state and with it the last sender of the pipeline is dropped
+            let state = self.state.clone();
+            let key = self.key.clone();
+            self.closing_oplogs.add(
+                self.key.clone(),
+                tokio::spawn(async move {
+                    state.lock().await.commit().await;
+                    drop(state);
+                    if let Err(err) = committer.await {
+                        error!("The commit pipeline of {key} failed: {err}");
+                    }
+                })
+                .map(|_| ())
+                .boxed(),
+            );
+        }
         if let Some(close) = self.close.take() {
             close();
         }
@@ -421,12 +620,28 @@ impl PrimaryOplog {
         blob_storage: Arc<dyn BlobStorage + Send + Sync>,
         replicas: u8,
         max_operations_before_commit: u64,
+        max_commits_in_flight: usize,
         max_payload_size: usize,
+        entry_encoding: OplogEncoding,
+        rewrite_migrated_entries: bool,
+        commit_scheduler: CommitScheduler,
+        closing_oplogs: ClosingOplogs,
         key: String,
         last_oplog_idx: OplogIndex,
         owned_worker_id: OwnedWorkerId,
         close: Box<dyn FnOnce() + Send + Sync>,
     ) -> Self {
+        let (commit_queue, batches) = mpsc::channel(max_commits_in_flight.max(1));
+        let (committed, committed_idx) = watch::channel(last_oplog_idx);
+        let committer = tokio::spawn(run_committer(
+            indexed_storage.clone(),
+            entry_encoding,
+            key.clone(),
+            commit_scheduler,
+            batches,
+            committed,
+        ));
+
         Self {
             state: Arc::new(Mutex::new(PrimaryOplogState {
                 indexed_storage,
@@ -434,56 +649,147 @@ impl PrimaryOplog {
                 replicas,
                 max_operations_before_commit,
                 max_payload_size,
+                entry_encoding,
+                rewrite_migrated_entries,
                 key: key.clone(),
                 buffer: VecDeque::new(),
-                last_committed_idx: last_oplog_idx,
+                last_enqueued_idx: last_oplog_idx,
                 last_oplog_idx,
+                commit_queue,
+                committed_idx,
                 owned_worker_id,
             })),
             key,
+            committer: Some(committer),
+            closing_oplogs,
             close: Some(close),
         }
     }
 }
 
+/// A batch of buffered entries handed over to the commit pipeline
+struct CommitBatch {
+    first_idx: OplogIndex,
+    entries: Vec<OplogEntry>,
+    enqueued_at: Instant,
+}
+
+/// Appends the batches sent to the commit pipeline to the indexed storage, in order, publishing
+/// the index of the last committed entry.
+///
+/// The batches waiting in the pipeline are committed together, in slices taking turns with the
+/// other workers' commits as scheduled by `commit_scheduler`.
+///
+/// Stops when the oplog is dropped, after all the batches already in the pipeline are committed.
+async fn run_committer(
+    indexed_storage: Arc<dyn IndexedStorage + Send + Sync>,
+    entry_encoding: OplogEncoding,
+    key: String,
+    commit_scheduler: CommitScheduler,
+    mut batches: mpsc::Receiver<CommitBatch>,
+    committed: watch::Sender<OplogIndex>,
+) {
+    while let Some(batch) = batches.recv().await {
+        record_oplog_call("append");
+
+        let mut oplog_idx = batch.first_idx;
+        let mut queued_since = batch.enqueued_at;
+        let mut entries = batch.entries;
+        while let Ok(batch) = batches.try_recv() {
+            entries.extend(batch.entries);
+        }
+
+        for slice in entries.chunks(commit_scheduler.slice_size()) {
+            let _turn = commit_scheduler.turn(slice.len(), queued_since).await;
+            for entry in slice {
+                let entry = oplog_codec::encode(entry_encoding, entry).unwrap_or_else(|err| {
+                    panic!("failed to encode oplog entry {oplog_idx} for {key}: {err}")
+                });
+                indexed_storage
+                    .with_entity("oplog", "append", "entry")
+                    .append_raw(
+                        IndexedStorageNamespace::OpLog,
+                        &key,
+                        oplog_idx.into(),
+                        &entry,
+                    )
+                    .await
+                    .unwrap_or_else(|err| {
+                        panic!("failed to append oplog entry for {key} in indexed storage: {err}")
+                    });
+                committed.send_replace(oplog_idx);
+                oplog_idx = oplog_idx.next();
+            }
+            queued_since = Instant::now();
+        }
+    }
+}
+
+/// Decodes an oplog entry read from the indexed storage. If `rewrite_migrated_entries` is set,
+/// entries upgraded from an older schema version are stored again with the current one, if the
+/// storage supports it.
+async fn decode_entry(
+    indexed_storage: &Arc<dyn IndexedStorage + Send + Sync>,
+    entry_encoding: OplogEncoding,
+    rewrite_migrated_entries: bool,
+    key: &str,
+    id: u64,
+    bytes: &[u8],
+) -> Result<OplogEntry, String> {
+    let decoded = oplog_codec::decode_versioned(bytes)?;
+    if rewrite_migrated_entries && decoded.is_migrated() {
+        let bytes = oplog_codec::encode(entry_encoding, &decoded.entry)?;
+        match indexed_storage
+            .with_entity("oplog", "rewrite", "entry")
+            .replace_raw(IndexedStorageNamespace::OpLog, key, id, &bytes)
+            .await
+        {
+            Ok(true) => debug!(
+                "Rewrote oplog entry {id} of {key} migrated from schema version {}",
+                decoded.schema_version
+            ),
+            Ok(false) => {}
+            Err(err) => warn!("Failed to rewrite migrated oplog entry {id} of {key}: {err}"),
+        }
+    }
+    Ok(decoded.entry)
+}
+
+/// Waits until the commit pipeline has committed the entry at `oplog_idx`
+async fn wait_for_commit(
+    mut committed_idx: watch::Receiver<OplogIndex>,
+    oplog_idx: OplogIndex,
+    key: &str,
+) {
+    if committed_idx
+        .wait_for(|committed_idx| *committed_idx >= oplog_idx)
+        .await
+        .is_err()
+    {
+        panic!("the commit pipeline of {key} stopped before committing oplog entry {oplog_idx}")
+    }
+}
+
 struct PrimaryOplogState {
     indexed_storage: Arc<dyn IndexedStorage + Send + Sync>,
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
     replicas: u8,
     max_operations_before_commit: u64,
     max_payload_size: usize,
+    entry_encoding: OplogEncoding,
+    rewrite_migrated_entries: bool,
     key: String,
     buffer: VecDeque<OplogEntry>,
     last_oplog_idx: OplogIndex,
-    last_committed_idx: OplogIndex,
+    /// Index of the last entry handed over to the commit pipeline
+    last_enqueued_idx: OplogIndex,
+    commit_queue: mpsc::Sender<CommitBatch>,
+    /// Index of the last entry committed to the indexed storage by the commit pipeline
+    committed_idx: watch::Receiver<OplogIndex>,
     owned_worker_id: OwnedWorkerId,
 }
 
 impl PrimaryOplogState {
-    async fn append(&mut self, entries: &[OplogEntry]) {
-        record_oplog_call("append");
-
-        for entry in entries {
-            let oplog_idx = self.last_committed_idx.next();
-            self.indexed_storage
-                .with_entity("oplog", "append", "entry")
-                .append(
-                    IndexedStorageNamespace::OpLog,
-                    &self.key,
-                    oplog_idx.into(),
-                    entry,
-                )
-                .await
-                .unwrap_or_else(|err| {
-                    panic!(
-                        "failed to append oplog entry for {} in indexed storage: {err}",
-                        self.key
-                    )
-                });
-            self.last_committed_idx = oplog_idx;
-        }
-    }
-
     async fn add(&mut self, entry: OplogEntry) {
         record_oplog_call("add");
 
@@ -494,11 +800,40 @@ impl PrimaryOplogState {
         self.last_oplog_idx = self.last_oplog_idx.next();
     }
 
+    /// Hands the buffered entries over to the commit pipeline without waiting for them to be
+    /// committed. Only waits if the pipeline already has the maximum number of batches in flight.
     async fn commit(&mut self) {
         record_oplog_call("commit");
 
+        if self.buffer.is_empty() {
+            return;
+        }
+
         let entries = self.buffer.drain(..).collect::<Vec<OplogEntry>>();
-        self.append(&entries).await
+        let first_idx = self.last_enqueued_idx.next();
+        self.last_enqueued_idx = first_idx.range_end(entries.len() as u64);
+        if self
+            .commit_queue
+            .send(CommitBatch {
+                first_idx,
+                entries,
+                enqueued_at: Instant::now(),
+            })
+            .await
+            .is_err()
+        {
+            panic!("the commit pipeline of {} has stopped", self.key)
+        }
+    }
+
+    /// Waits until every entry handed over to the commit pipeline is committed
+    async fn flush(&self) {
+        wait_for_commit(
+            self.committed_idx.clone(),
+            self.last_enqueued_idx,
+            &self.key,
+        )
+        .await
     }
 
     async fn wait_for_replicas(&self, replicas: u8, timeout: Duration) -> bool {
@@ -522,10 +857,16 @@ impl PrimaryOplogState {
     async fn read(&self, oplog_index: OplogIndex) -> OplogEntry {
         record_oplog_call("read");
 
-        let entries: Vec<(u64, OplogEntry)> = self
+        wait_for_commit(
+            self.committed_idx.clone(),
+            oplog_index.min(self.last_enqueued_idx),
+            &self.key,
+        )
+        .await;
+        let entries = self
             .indexed_storage
             .with_entity("oplog", "read", "entry")
-            .read(
+            .read_raw(
                 IndexedStorageNamespace::OpLog,
                 &self.key,
                 oplog_index.into(),
@@ -539,21 +880,33 @@ impl PrimaryOplogState {
                 )
             });
 
-        entries
-            .into_iter()
-            .next()
-            .unwrap_or_else(|| {
-                panic!(
-                    "Missing oplog entry {oplog_index} for {} in indexed storage",
-                    self.key
-                )
-            })
-            .1
+        let (id, bytes) = entries.into_iter().next().unwrap_or_else(|| {
+            panic!(
+                "Missing oplog entry {oplog_index} for {} in indexed storage",
+                self.key
+            )
+        });
+        decode_entry(
+            &self.indexed_storage,
+            self.entry_encoding,
+            self.rewrite_migrated_entries,
+            &self.key,
+            id,
+            &bytes,
+        )
+        .await
+        .unwrap_or_else(|err| {
+            panic!(
+                "failed to decode oplog entry {oplog_index} from {}: {err}",
+                self.key
+            )
+        })
     }
 
     async fn drop_prefix(&self, last_dropped_id: OplogIndex) {
         record_oplog_call("drop_prefix");
 
+        self.flush().await;
         self.indexed_storage
             .with("oplog", "drop_prefix")
             .drop_prefix(
@@ -573,6 +926,7 @@ impl PrimaryOplogState {
     async fn length(&self) -> u64 {
         record_oplog_call("length");
 
+        self.flush().await;
         self.indexed_storage
             .with("oplog", "length")
             .length(IndexedStorageNamespace::OpLog, &self.key)
@@ -588,6 +942,7 @@ impl PrimaryOplogState {
     async fn delete(&self) {
         record_oplog_call("delete");
 
+        self.flush().await;
         self.indexed_storage
             .with("oplog", "delete")
             .delete(IndexedStorageNamespace::OpLog, &self.key)
@@ -623,9 +978,19 @@ impl Oplog for PrimaryOplog {
         }
     }
 
-    async fn commit(&self, _level: CommitLevel) {
-        let mut state = self.state.lock().await;
-        state.commit().await
+    async fn commit(&self, level: CommitLevel) {
+        let (committed_idx, target_idx) = {
+            let mut state = self.state.lock().await;
+            state.commit().await;
+            (state.committed_idx.clone(), state.last_enqueued_idx)
+        };
+        match level {
+            // The primary oplog only belongs to durable workers, so every level waits until the
+            // entries are committed, without keeping the state locked meanwhile
+            CommitLevel::Immediate | CommitLevel::Always | CommitLevel::DurableOnly => {
+                wait_for_commit(committed_idx, target_idx, &self.key).await
+            }
+        }
     }
 
     async fn current_oplog_index(&self) -> OplogIndex {
@@ -636,6 +1001,7 @@ impl Oplog for PrimaryOplog {
     async fn wait_for_replicas(&self, replicas: u8, timeout: Duration) -> bool {
         let mut state = self.state.lock().await;
         state.commit().await;
+        state.flush().await;
         state.wait_for_replicas(replicas, timeout).await
     }
 
@@ -20,7 +20,8 @@ use tracing::{debug, info};
 use uuid::Uuid;
 
 use golem_common::config::RedisConfig;
-use golem_common::model::oplog::WorkerError;
+use golem_common::model::oplog::{LogLevel, WorkerError};
+use golem_common::model::oplog_codec::OplogEncoding;
 use golem_common::model::regions::OplogRegion;
 use golem_common::model::ComponentId;
 use golem_common::redis::RedisPool;
@@ -31,7 +32,7 @@ use crate::services::oplog::multilayer::OplogArchiveService;
 use crate::storage::blob::memory::InMemoryBlobStorage;
 use crate::storage::indexed::memory::InMemoryIndexedStorage;
 use crate::storage::indexed::redis::RedisIndexedStorage;
-use crate::storage::indexed::IndexedStorage;
+use crate::storage::indexed::{IndexedStorage, IndexedStorageNamespace};
 
 use super::*;
 
@@ -102,12 +103,12 @@ fn rounded(entry: OplogEntry) -> OplogEntry {
             response,
             wrapped_function_type,
         },
-        OplogEntry::ExportedFunctionInvoked {
+        OplogEntry::ExportedFunctionInvokedV1 {
             timestamp,
             function_name,
             request,
             idempotency_key,
-        } => OplogEntry::ExportedFunctionInvoked {
+        } => OplogEntry::ExportedFunctionInvokedV1 {
             timestamp: rounded_ts(timestamp),
             function_name,
             request,
@@ -236,6 +237,69 @@ fn rounded(entry: OplogEntry) -> OplogEntry {
         OplogEntry::Restart { timestamp } => OplogEntry::Restart {
             timestamp: rounded_ts(timestamp),
         },
+        OplogEntry::CancelPendingInvocation {
+            timestamp,
+            idempotency_key,
+        } => OplogEntry::CancelPendingInvocation {
+            timestamp: rounded_ts(timestamp),
+            idempotency_key,
+        },
+        OplogEntry::ChangeEnvironment { timestamp, env } => OplogEntry::ChangeEnvironment {
+            timestamp: rounded_ts(timestamp),
+            env,
+        },
+        OplogEntry::Redacted {
+            timestamp,
+            entries,
+            reason,
+        } => OplogEntry::Redacted {
+            timestamp: rounded_ts(timestamp),
+            entries,
+            reason,
+        },
+        OplogEntry::InvocationTimedOut {
+            timestamp,
+            idempotency_key,
+            timeout,
+        } => OplogEntry::InvocationTimedOut {
+            timestamp: rounded_ts(timestamp),
+            idempotency_key,
+            timeout,
+        },
+        OplogEntry::HoldPendingInvocation {
+            timestamp,
+            idempotency_key,
+        } => OplogEntry::HoldPendingInvocation {
+            timestamp: rounded_ts(timestamp),
+            idempotency_key,
+        },
+        OplogEntry::ReleasePendingInvocation {
+            timestamp,
+            idempotency_key,
+        } => OplogEntry::ReleasePendingInvocation {
+            timestamp: rounded_ts(timestamp),
+            idempotency_key,
+        },
+        OplogEntry::SwitchInvocation {
+            timestamp,
+            idempotency_key,
+        } => OplogEntry::SwitchInvocation {
+            timestamp: rounded_ts(timestamp),
+            idempotency_key,
+        },
+        OplogEntry::ExportedFunctionInvoked {
+            timestamp,
+            function_name,
+            request,
+            idempotency_key,
+            caller,
+        } => OplogEntry::ExportedFunctionInvoked {
+            timestamp: rounded_ts(timestamp),
+            function_name,
+            request,
+            idempotency_key,
+            caller,
+        },
     }
 }
 
@@ -243,7 +307,16 @@ fn rounded(entry: OplogEntry) -> OplogEntry {
 async fn open_add_and_read_back(_tracing: &Tracing) {
     let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
     let blob_storage = Arc::new(InMemoryBlobStorage::new());
-    let oplog_service = PrimaryOplogService::new(indexed_storage, blob_storage, 1, 100).await;
+    let oplog_service = PrimaryOplogService::new(
+        indexed_storage,
+        blob_storage,
+        1,
+        4,
+        100,
+        OplogEncoding::Bincode,
+        false,
+    )
+    .await;
     let account_id = AccountId {
         value: "user1".to_string(),
     };
@@ -287,12 +360,312 @@ async fn open_add_and_read_back(_tracing: &Tracing) {
     );
 }
 
+#[test]
+async fn pipelined_commits_are_ordered(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let oplog_service = PrimaryOplogService::new(
+        indexed_storage,
+        blob_storage,
+        1,
+        1,
+        100,
+        OplogEncoding::Bincode,
+        false,
+    )
+    .await;
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: "test".to_string(),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+
+    let last_oplog_idx = oplog.current_oplog_index().await;
+    let mut entries = Vec::new();
+    for i in 0..100 {
+        let entry = rounded(OplogEntry::jump(OplogRegion {
+            start: OplogIndex::from_u64(i),
+            end: OplogIndex::from_u64(i + 1),
+        }));
+        oplog.add(entry.clone()).await;
+        entries.push(entry);
+    }
+
+    // Entries handed over to the commit pipeline by `add` can be read back before an explicit commit
+    let r1 = oplog.read(last_oplog_idx.next()).await;
+    assert_eq!(r1, entries[0]);
+
+    oplog.commit(CommitLevel::Always).await;
+    assert_eq!(
+        oplog.current_oplog_index().await,
+        last_oplog_idx.range_end(101)
+    );
+    assert_eq!(oplog.length().await, 100);
+
+    let stored = oplog_service
+        .read(&owned_worker_id, last_oplog_idx.next(), 100)
+        .await;
+    assert_eq!(stored.into_values().collect::<Vec<_>>(), entries);
+}
+
+#[test]
+async fn dropped_oplog_is_committed_before_reopening(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let oplog_service = PrimaryOplogService::new(
+        indexed_storage,
+        blob_storage,
+        1000,
+        1,
+        100,
+        OplogEncoding::Bincode,
+        false,
+    )
+    .await;
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: "test".to_string(),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+
+    let mut entries = Vec::new();
+    for i in 0..100 {
+        let entry = rounded(OplogEntry::jump(OplogRegion {
+            start: OplogIndex::from_u64(i),
+            end: OplogIndex::from_u64(i + 1),
+        }));
+        oplog.add(entry.clone()).await;
+        entries.push(entry);
+    }
+    drop(oplog);
+
+    // The entries still buffered when the oplog was dropped are committed before reopening it
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    assert_eq!(last_oplog_index, OplogIndex::from_u64(100));
+
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+    let entry = rounded(OplogEntry::suspend());
+    oplog.add_and_commit(entry.clone()).await;
+    entries.push(entry);
+
+    let stored = oplog_service
+        .read(&owned_worker_id, OplogIndex::from_u64(1), 101)
+        .await;
+    assert_eq!(stored.into_values().collect::<Vec<_>>(), entries);
+}
+
+#[test]
+async fn scheduled_commits_of_many_workers_are_complete(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let oplog_service = Arc::new(
+        PrimaryOplogService::new(
+            indexed_storage,
+            blob_storage,
+            4,
+            2,
+            100,
+            OplogEncoding::Bincode,
+            false,
+        )
+        .await
+        .with_commit_scheduler(CommitScheduler::new(2, 3, None)),
+    );
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+
+    let mut tasks = Vec::new();
+    for worker in 0..8 {
+        let oplog_service = oplog_service.clone();
+        let owned_worker_id = OwnedWorkerId::new(
+            &account_id,
+            &WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: format!("test-{worker}"),
+            },
+        );
+        tasks.push(tokio::spawn(async move {
+            let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+            let oplog = oplog_service
+                .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+                .await;
+
+            let last_oplog_idx = oplog.current_oplog_index().await;
+            let mut entries = Vec::new();
+            for i in 0..50 {
+                let entry = rounded(OplogEntry::jump(OplogRegion {
+                    start: OplogIndex::from_u64(i),
+                    end: OplogIndex::from_u64(i + 1),
+                }));
+                oplog.add(entry.clone()).await;
+                entries.push(entry);
+            }
+            oplog.commit(CommitLevel::Always).await;
+
+            let stored = oplog_service
+                .read(&owned_worker_id, last_oplog_idx.next(), 50)
+                .await;
+            assert_eq!(stored.into_values().collect::<Vec<_>>(), entries);
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+}
+
+#[test]
+async fn commit_turns_are_taken_in_order(_tracing: &Tracing) {
+    let scheduler = CommitScheduler::new(1, 10, None);
+    let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let first_turn = scheduler.turn(10, tokio::time::Instant::now()).await;
+    let mut tasks = Vec::new();
+    for worker in ["second", "third"] {
+        let scheduler = scheduler.clone();
+        let order = order.clone();
+        tasks.push(tokio::spawn(async move {
+            let _turn = scheduler.turn(10, tokio::time::Instant::now()).await;
+            order.lock().unwrap().push(worker);
+        }));
+        // Making sure the workers queue up in order
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(order.lock().unwrap().is_empty());
+
+    drop(first_turn);
+    for task in tasks {
+        task.await.unwrap();
+    }
+    assert_eq!(*order.lock().unwrap(), vec!["second", "third"]);
+}
+
+#[test]
+async fn commit_throughput_is_capped(_tracing: &Tracing) {
+    let scheduler = CommitScheduler::new(4, 100, Some(100));
+    let start = tokio::time::Instant::now();
+
+    drop(scheduler.turn(50, start).await);
+    drop(scheduler.turn(50, start).await);
+    drop(scheduler.turn(1, start).await);
+
+    // The first 100 entries take a second to commit at 100 entries per second
+    assert!(start.elapsed() >= Duration::from_millis(950));
+}
+
+/// Oplog entries stored by previous releases, from the serialization compatibility tests
+const PREVIOUS_RELEASE_ENTRIES: &[&[u8]] = &[
+    include_bytes!("../../../tests/goldenfiles/oplog_entry_create.bin"),
+    include_bytes!("../../../tests/goldenfiles/oplog_entry_imported_function_invoked.bin"),
+    include_bytes!("../../../tests/goldenfiles/oplog_entry_exported_function_invoked.bin"),
+    include_bytes!("../../../tests/goldenfiles/oplog_entry_exported_function_completed.bin"),
+    include_bytes!("../../../tests/goldenfiles/oplog_entry_pending_worker_invocation.bin"),
+    include_bytes!("../../../tests/goldenfiles/oplog_entry_pending_update.bin"),
+    include_bytes!("../../../tests/goldenfiles/oplog_entry_describe_resource.bin"),
+    include_bytes!("../../../tests/goldenfiles/oplog_entry_error.bin"),
+    include_bytes!("../../../tests/goldenfiles/oplog_entry_jump.bin"),
+    include_bytes!("../../../tests/goldenfiles/oplog_entry_log.bin"),
+    include_bytes!("../../../tests/goldenfiles/oplog_entry_exited.bin"),
+];
+
+#[test]
+async fn replays_oplogs_of_previous_releases(_tracing: &Tracing) {
+    for entry_encoding in [
+        OplogEncoding::Bincode,
+        OplogEncoding::Json,
+        OplogEncoding::Protobuf,
+    ] {
+        let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+        let blob_storage = Arc::new(InMemoryBlobStorage::new());
+        let oplog_service = PrimaryOplogService::new(
+            indexed_storage.clone(),
+            blob_storage,
+            1,
+            4,
+            100,
+            entry_encoding,
+            true,
+        )
+        .await;
+        let account_id = AccountId {
+            value: "user1".to_string(),
+        };
+        let worker_id = WorkerId {
+            component_id: ComponentId(Uuid::new_v4()),
+            worker_name: "test".to_string(),
+        };
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+        let mut expected = Vec::new();
+        for (idx, bytes) in PREVIOUS_RELEASE_ENTRIES.iter().enumerate() {
+            indexed_storage
+                .append(
+                    "test",
+                    "append",
+                    "entry",
+                    IndexedStorageNamespace::OpLog,
+                    &worker_id.to_redis_key(),
+                    idx as u64 + 1,
+                    bytes,
+                )
+                .await
+                .unwrap();
+            expected.push(golem_common::serialization::deserialize::<OplogEntry>(bytes).unwrap());
+        }
+
+        let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+        let stored = oplog_service
+            .read(
+                &owned_worker_id,
+                OplogIndex::INITIAL,
+                PREVIOUS_RELEASE_ENTRIES.len() as u64,
+            )
+            .await;
+        assert_eq!(stored.into_values().collect::<Vec<_>>(), expected);
+
+        // New entries are appended with the configured encoding after the old ones
+        let oplog = oplog_service
+            .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+            .await;
+        let entry = rounded(OplogEntry::exited());
+        oplog.add(entry.clone()).await;
+        oplog.commit(CommitLevel::Always).await;
+        assert_eq!(oplog.read(last_oplog_index.next()).await, entry);
+        assert_eq!(oplog.read(OplogIndex::INITIAL).await, expected[0]);
+    }
+}
+
 #[test]
 async fn open_add_and_read_back_ephemeral(_tracing: &Tracing) {
     let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
     let blob_storage = Arc::new(InMemoryBlobStorage::new());
     let primary_oplog_service = Arc::new(
-        PrimaryOplogService::new(indexed_storage.clone(), blob_storage.clone(), 1, 100).await,
+        PrimaryOplogService::new(
+            indexed_storage.clone(),
+            blob_storage.clone(),
+            1,
+            4,
+            100,
+            OplogEncoding::Bincode,
+            false,
+        )
+        .await,
     );
     let secondary_layer: Arc<dyn OplogArchiveService + Send + Sync> = Arc::new(
         CompressedOplogArchiveService::new(indexed_storage.clone(), 1),
@@ -353,7 +726,16 @@ async fn open_add_and_read_back_ephemeral(_tracing: &Tracing) {
 async fn entries_with_small_payload(_tracing: &Tracing) {
     let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
     let blob_storage = Arc::new(InMemoryBlobStorage::new());
-    let oplog_service = PrimaryOplogService::new(indexed_storage, blob_storage, 1, 100).await;
+    let oplog_service = PrimaryOplogService::new(
+        indexed_storage,
+        blob_storage,
+        1,
+        4,
+        100,
+        OplogEncoding::Bincode,
+        false,
+    )
+    .await;
     let account_id = AccountId {
         value: "user1".to_string(),
     };
@@ -386,6 +768,7 @@ async fn entries_with_small_payload(_tracing: &Tracing) {
                 "f2".to_string(),
                 &"request".to_string(),
                 IdempotencyKey::fresh(),
+                None,
             )
             .await
             .unwrap(),
@@ -463,7 +846,16 @@ async fn entries_with_small_payload(_tracing: &Tracing) {
 async fn entries_with_large_payload(_tracing: &Tracing) {
     let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
     let blob_storage = Arc::new(InMemoryBlobStorage::new());
-    let oplog_service = PrimaryOplogService::new(indexed_storage, blob_storage, 1, 100).await;
+    let oplog_service = PrimaryOplogService::new(
+        indexed_storage,
+        blob_storage,
+        1,
+        4,
+        100,
+        OplogEncoding::Bincode,
+        false,
+    )
+    .await;
     let account_id = AccountId {
         value: "user1".to_string(),
     };
@@ -500,6 +892,7 @@ async fn entries_with_large_payload(_tracing: &Tracing) {
                 "f2".to_string(),
                 &large_payload2,
                 IdempotencyKey::fresh(),
+                None,
             )
             .await
             .unwrap(),
@@ -573,6 +966,75 @@ async fn entries_with_large_payload(_tracing: &Tracing) {
     assert_eq!(p4, large_payload4);
 }
 
+#[test]
+async fn replace_entry_and_delete_payload(_tracing: &Tracing) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let oplog_service = PrimaryOplogService::new(
+        indexed_storage,
+        blob_storage,
+        1,
+        4,
+        100,
+        OplogEncoding::Bincode,
+        false,
+    )
+    .await;
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: "test".to_string(),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+
+    let last_oplog_idx = oplog.current_oplog_index().await;
+    let log = rounded(OplogEntry::log(
+        LogLevel::Info,
+        "stdout".to_string(),
+        "secret".to_string(),
+    ));
+    oplog.add(log).await;
+    let completed = oplog
+        .add_exported_function_completed(&vec![7u8; 1024 * 1024], 42)
+        .await
+        .unwrap();
+    oplog.commit(CommitLevel::Always).await;
+
+    let log_idx = last_oplog_idx.next();
+    let redacted_log = rounded(OplogEntry::log(
+        LogLevel::Info,
+        "stdout".to_string(),
+        "<redacted>".to_string(),
+    ));
+    oplog_service
+        .replace(&owned_worker_id, log_idx, redacted_log.clone())
+        .await
+        .unwrap();
+    assert_eq!(oplog.read(log_idx).await, redacted_log);
+
+    let OplogEntry::ExportedFunctionCompleted { response, .. } = completed else {
+        unreachable!()
+    };
+    check!(oplog_service
+        .download_payload(&owned_worker_id, &response)
+        .await
+        .is_ok());
+    oplog_service
+        .delete_payload(&owned_worker_id, &response)
+        .await
+        .unwrap();
+    check!(oplog_service
+        .download_payload(&owned_worker_id, &response)
+        .await
+        .is_err());
+}
+
 #[test]
 async fn multilayer_transfers_entries_after_limit_reached_1(_tracing: &Tracing) {
     multilayer_transfers_entries_after_limit_reached(false, 315, 5, 1, 3, false).await;
@@ -622,7 +1084,16 @@ async fn multilayer_transfers_entries_after_limit_reached(
 
     let blob_storage = Arc::new(InMemoryBlobStorage::new());
     let primary_oplog_service = Arc::new(
-        PrimaryOplogService::new(indexed_storage.clone(), blob_storage.clone(), 1, 100).await,
+        PrimaryOplogService::new(
+            indexed_storage.clone(),
+            blob_storage.clone(),
+            1,
+            4,
+            100,
+            OplogEncoding::Bincode,
+            false,
+        )
+        .await,
     );
     let secondary_layer: Arc<dyn OplogArchiveService + Send + Sync> = if use_blob {
         Arc::new(BlobOplogArchiveService::new(blob_storage.clone(), 1))
@@ -719,11 +1190,81 @@ async fn blob_read_from_archive(_tracing: &Tracing) {
     read_from_archive_impl(true).await;
 }
 
+#[test]
+async fn archives_entries_with_the_configured_encoding(_tracing: &Tracing) {
+    for entry_encoding in [
+        OplogEncoding::Bincode,
+        OplogEncoding::Json,
+        OplogEncoding::Protobuf,
+    ] {
+        let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+        let blob_storage = Arc::new(InMemoryBlobStorage::new());
+        let layers: Vec<Arc<dyn OplogArchiveService + Send + Sync>> = vec![
+            Arc::new(
+                CompressedOplogArchiveService::new(indexed_storage.clone(), 1)
+                    .with_entry_encoding(entry_encoding),
+            ),
+            Arc::new(
+                BlobOplogArchiveService::new(blob_storage.clone(), 2)
+                    .with_entry_encoding(entry_encoding),
+            ),
+        ];
+
+        let account_id = AccountId {
+            value: "user1".to_string(),
+        };
+        let worker_id = WorkerId {
+            component_id: ComponentId(Uuid::new_v4()),
+            worker_name: "test".to_string(),
+        };
+        let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+        let entries = vec![
+            rounded(OplogEntry::jump(OplogRegion {
+                start: OplogIndex::from_u64(5),
+                end: OplogIndex::from_u64(12),
+            })),
+            rounded(OplogEntry::error(WorkerError::OutOfMemory)),
+            rounded(OplogEntry::exited()),
+        ];
+
+        for layer in layers {
+            layer
+                .open(&owned_worker_id)
+                .await
+                .append(
+                    entries
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, entry)| (OplogIndex::from_u64(idx as u64 + 1), entry.clone()))
+                        .collect(),
+                )
+                .await;
+
+            // Reopened so the entries are decoded from the stored chunk instead of the cache
+            let stored = layer
+                .open(&owned_worker_id)
+                .await
+                .read(OplogIndex::from_u64(1), entries.len() as u64)
+                .await;
+            check!(stored.into_values().collect::<Vec<_>>() == entries);
+        }
+    }
+}
+
 async fn read_from_archive_impl(use_blob: bool) {
     let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
     let blob_storage = Arc::new(InMemoryBlobStorage::new());
     let primary_oplog_service = Arc::new(
-        PrimaryOplogService::new(indexed_storage.clone(), blob_storage.clone(), 1, 100).await,
+        PrimaryOplogService::new(
+            indexed_storage.clone(),
+            blob_storage.clone(),
+            1,
+            4,
+            100,
+            OplogEncoding::Bincode,
+            false,
+        )
+        .await,
     );
     let secondary_layer: Arc<dyn OplogArchiveService + Send + Sync> = if use_blob {
         Arc::new(BlobOplogArchiveService::new(blob_storage.clone(), 1))
@@ -803,6 +1344,114 @@ async fn read_from_archive_impl(use_blob: bool) {
     assert_eq!(first10.into_values().collect::<Vec<_>>(), original_first10);
 }
 
+#[test]
+async fn replace_archived_entry(_tracing: &Tracing) {
+    replace_archived_entry_impl(false).await;
+}
+
+#[test]
+async fn blob_replace_archived_entry(_tracing: &Tracing) {
+    replace_archived_entry_impl(true).await;
+}
+
+async fn replace_archived_entry_impl(use_blob: bool) {
+    let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
+    let blob_storage = Arc::new(InMemoryBlobStorage::new());
+    let primary_oplog_service = Arc::new(
+        PrimaryOplogService::new(
+            indexed_storage.clone(),
+            blob_storage.clone(),
+            1,
+            4,
+            100,
+            OplogEncoding::Bincode,
+            false,
+        )
+        .await,
+    );
+    let secondary_layer: Arc<dyn OplogArchiveService + Send + Sync> = if use_blob {
+        Arc::new(BlobOplogArchiveService::new(blob_storage.clone(), 1))
+    } else {
+        Arc::new(CompressedOplogArchiveService::new(
+            indexed_storage.clone(),
+            1,
+        ))
+    };
+    let tertiary_layer: Arc<dyn OplogArchiveService + Send + Sync> = if use_blob {
+        Arc::new(BlobOplogArchiveService::new(blob_storage.clone(), 2))
+    } else {
+        Arc::new(CompressedOplogArchiveService::new(
+            indexed_storage.clone(),
+            2,
+        ))
+    };
+    let oplog_service = Arc::new(MultiLayerOplogService::new(
+        primary_oplog_service.clone(),
+        nev![secondary_layer.clone(), tertiary_layer.clone()],
+        10,
+        10,
+    ));
+    let account_id = AccountId {
+        value: "user1".to_string(),
+    };
+    let worker_id = WorkerId {
+        component_id: ComponentId(Uuid::new_v4()),
+        worker_name: "test".to_string(),
+    };
+    let owned_worker_id = OwnedWorkerId::new(&account_id, &worker_id);
+
+    let last_oplog_index = oplog_service.get_last_index(&owned_worker_id).await;
+    let oplog = oplog_service
+        .open(&owned_worker_id, last_oplog_index, ComponentType::Durable)
+        .await;
+
+    let timestamp = Timestamp::now_utc();
+    let entries: Vec<OplogEntry> = (0..100)
+        .map(|i| {
+            rounded(OplogEntry::Error {
+                timestamp,
+                error: WorkerError::Unknown(i.to_string()),
+            })
+        })
+        .collect();
+
+    let initial_oplog_idx = oplog.current_oplog_index().await;
+
+    for entry in &entries {
+        oplog.add(entry.clone()).await;
+    }
+    oplog.commit(CommitLevel::Always).await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let archived_idx = initial_oplog_idx.next();
+    check!(primary_oplog_service
+        .read(&owned_worker_id, archived_idx, 1)
+        .await
+        .is_empty());
+
+    let redacted = rounded(OplogEntry::Error {
+        timestamp,
+        error: WorkerError::Unknown("<redacted>".to_string()),
+    });
+    oplog_service
+        .replace(&owned_worker_id, archived_idx, redacted.clone())
+        .await
+        .unwrap();
+
+    let mut expected = entries;
+    expected[0] = redacted;
+    let all_entries = oplog_service
+        .read(&owned_worker_id, archived_idx, 100)
+        .await;
+    assert_eq!(all_entries.into_values().collect::<Vec<_>>(), expected);
+
+    let missing_idx = OplogIndex::from_u64(Into::<u64>::into(initial_oplog_idx) + 1000);
+    check!(oplog_service
+        .replace(&owned_worker_id, missing_idx, expected[1].clone())
+        .await
+        .is_err());
+}
+
 #[test]
 async fn write_after_archive(_tracing: &Tracing) {
     write_after_archive_impl(false, Reopen::No).await;
@@ -844,7 +1493,16 @@ async fn write_after_archive_impl(use_blob: bool, reopen: Reopen) {
     let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
     let blob_storage = Arc::new(InMemoryBlobStorage::new());
     let mut primary_oplog_service = Arc::new(
-        PrimaryOplogService::new(indexed_storage.clone(), blob_storage.clone(), 1, 100).await,
+        PrimaryOplogService::new(
+            indexed_storage.clone(),
+            blob_storage.clone(),
+            1,
+            4,
+            100,
+            OplogEncoding::Bincode,
+            false,
+        )
+        .await,
     );
     let secondary_layer: Arc<dyn OplogArchiveService + Send + Sync> = if use_blob {
         Arc::new(BlobOplogArchiveService::new(blob_storage.clone(), 1))
@@ -928,7 +1586,16 @@ async fn write_after_archive_impl(use_blob: bool, reopen: Reopen) {
     } else if reopen == Reopen::Full {
         drop(oplog);
         primary_oplog_service = Arc::new(
-            PrimaryOplogService::new(indexed_storage.clone(), blob_storage.clone(), 1, 100).await,
+            PrimaryOplogService::new(
+                indexed_storage.clone(),
+                blob_storage.clone(),
+                1,
+                4,
+                100,
+                OplogEncoding::Bincode,
+                false,
+            )
+            .await,
         );
         oplog_service = Arc::new(MultiLayerOplogService::new(
             primary_oplog_service.clone(),
@@ -988,7 +1655,16 @@ async fn write_after_archive_impl(use_blob: bool, reopen: Reopen) {
     } else if reopen == Reopen::Full {
         drop(oplog);
         primary_oplog_service = Arc::new(
-            PrimaryOplogService::new(indexed_storage.clone(), blob_storage.clone(), 1, 100).await,
+            PrimaryOplogService::new(
+                indexed_storage.clone(),
+                blob_storage.clone(),
+                1,
+                4,
+                100,
+                OplogEncoding::Bincode,
+                false,
+            )
+            .await,
         );
         oplog_service = Arc::new(MultiLayerOplogService::new(
             primary_oplog_service.clone(),
@@ -1075,7 +1751,16 @@ async fn empty_layer_gets_deleted_impl(use_blob: bool) {
     let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
     let blob_storage = Arc::new(InMemoryBlobStorage::new());
     let primary_oplog_service = Arc::new(
-        PrimaryOplogService::new(indexed_storage.clone(), blob_storage.clone(), 1, 100).await,
+        PrimaryOplogService::new(
+            indexed_storage.clone(),
+            blob_storage.clone(),
+            1,
+            4,
+            100,
+            OplogEncoding::Bincode,
+            false,
+        )
+        .await,
     );
     let secondary_layer: Arc<dyn OplogArchiveService + Send + Sync> = if use_blob {
         Arc::new(BlobOplogArchiveService::new(blob_storage.clone(), 1))
@@ -1180,7 +1865,16 @@ async fn scheduled_archive_impl(use_blob: bool) {
     let indexed_storage = Arc::new(InMemoryIndexedStorage::new());
     let blob_storage = Arc::new(InMemoryBlobStorage::new());
     let primary_oplog_service = Arc::new(
-        PrimaryOplogService::new(indexed_storage.clone(), blob_storage.clone(), 1, 100).await,
+        PrimaryOplogService::new(
+            indexed_storage.clone(),
+            blob_storage.clone(),
+            1,
+            4,
+            100,
+            OplogEncoding::Bincode,
+            false,
+        )
+        .await,
     );
     let secondary_layer: Arc<dyn OplogArchiveService + Send + Sync> = if use_blob {
         Arc::new(BlobOplogArchiveService::new(blob_storage.clone(), 1))
@@ -0,0 +1,170 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use serde::Serialize;
+use tracing::{info, warn};
+use wasmtime::{Engine, Module};
+
+use crate::storage::blob::{BlobStorage, BlobStorageNamespace};
+use crate::storage::indexed::{IndexedStorage, IndexedStorageNamespace};
+use crate::storage::keyvalue::{KeyValueStorage, KeyValueStorageNamespace};
+
+/// How long to wait before checking the storage connectivity again after a failure
+const STORAGE_VERIFICATION_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Key looked up in each storage to verify it can be reached. It does not need to exist.
+const STORAGE_PROBE_KEY: &str = "readiness-probe";
+
+/// The smallest valid WebAssembly module, compiled to warm up the engine
+const EMPTY_MODULE: &[u8] = b"\0asm\x01\0\0\0";
+
+/// The readiness of the executor, served by the HTTP interface
+static READINESS: RwLock<Readiness> = RwLock::new(Readiness {
+    shard_assignment_received: false,
+    storage_verified: false,
+    engine_warm: false,
+});
+
+/// The conditions an executor must meet after starting before it can serve invocations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Readiness {
+    /// The executor is registered in the shard manager and got its shard assignment
+    pub shard_assignment_received: bool,
+    /// The key-value, indexed and blob storages could be reached
+    pub storage_verified: bool,
+    /// The compilation engine has compiled a module
+    pub engine_warm: bool,
+}
+
+impl Readiness {
+    pub fn is_ready(&self) -> bool {
+        self.shard_assignment_received && self.storage_verified && self.engine_warm
+    }
+}
+
+/// Gets the current readiness of the executor
+pub fn readiness() -> Readiness {
+    *READINESS.read().unwrap()
+}
+
+pub fn mark_shard_assignment_received() {
+    info!("Readiness: shard assignment received");
+    READINESS.write().unwrap().shard_assignment_received = true;
+}
+
+/// Verifies in the background that all the storages can be reached, retrying until they can
+pub fn start_storage_verification(
+    key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
+    indexed_storage: Arc<dyn IndexedStorage + Send + Sync>,
+    blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+) {
+    tokio::spawn(async move {
+        loop {
+            match verify_storage(&key_value_storage, &indexed_storage, &blob_storage).await {
+                Ok(()) => {
+                    info!("Readiness: storage connectivity verified");
+                    READINESS.write().unwrap().storage_verified = true;
+                    break;
+                }
+                Err(err) => {
+                    warn!("Readiness: storage connectivity check failed: {err}");
+                    tokio::time::sleep(STORAGE_VERIFICATION_RETRY_INTERVAL).await;
+                }
+            }
+        }
+    });
+}
+
+async fn verify_storage(
+    key_value_storage: &Arc<dyn KeyValueStorage + Send + Sync>,
+    indexed_storage: &Arc<dyn IndexedStorage + Send + Sync>,
+    blob_storage: &Arc<dyn BlobStorage + Send + Sync>,
+) -> Result<(), String> {
+    key_value_storage
+        .exists(
+            "readiness",
+            "verify_storage",
+            KeyValueStorageNamespace::Worker,
+            STORAGE_PROBE_KEY,
+        )
+        .await
+        .map_err(|err| format!("key-value storage: {err}"))?;
+    indexed_storage
+        .exists(
+            "readiness",
+            "verify_storage",
+            IndexedStorageNamespace::OpLog,
+            STORAGE_PROBE_KEY,
+        )
+        .await
+        .map_err(|err| format!("indexed storage: {err}"))?;
+    blob_storage
+        .exists(
+            "readiness",
+            "verify_storage",
+            BlobStorageNamespace::CompilationCache,
+            Path::new(STORAGE_PROBE_KEY),
+        )
+        .await
+        .map_err(|err| format!("blob storage: {err}"))?;
+    Ok(())
+}
+
+/// Compiles an empty module in the background, so the compiler is initialized before the
+/// first worker is loaded
+pub fn start_engine_warm_up(engine: Arc<Engine>) {
+    tokio::task::spawn_blocking(move || match Module::new(&engine, EMPTY_MODULE) {
+        Ok(_) => {
+            info!("Readiness: compilation engine warmed up");
+            READINESS.write().unwrap().engine_warm = true;
+        }
+        Err(err) => warn!("Readiness: failed to warm up the compilation engine: {err}"),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::services::readiness::Readiness;
+
+    #[test]
+    fn ready_only_when_all_conditions_are_met() {
+        let ready = Readiness {
+            shard_assignment_received: true,
+            storage_verified: true,
+            engine_warm: true,
+        };
+        assert!(ready.is_ready());
+        assert!(!Readiness {
+            shard_assignment_received: false,
+            ..ready
+        }
+        .is_ready());
+        assert!(!Readiness {
+            storage_verified: false,
+            ..ready
+        }
+        .is_ready());
+        assert!(!Readiness {
+            engine_warm: false,
+            ..ready
+        }
+        .is_ready());
+    }
+}
@@ -30,9 +30,10 @@ use crate::services::events::Events;
 use crate::services::shard::ShardService;
 use crate::services::worker_proxy::{WorkerProxy, WorkerProxyError};
 use crate::services::{
-    active_workers, blob_store, component, golem_config, key_value, oplog, promise, scheduler,
-    shard, shard_manager, worker, worker_activator, worker_enumeration, HasActiveWorkers,
-    HasBlobStoreService, HasComponentService, HasConfig, HasEvents, HasExtraDeps,
+    active_workers, blob_store, component, domain_event, feature_flags, golem_config, inference,
+    key_value, oplog, promise, scheduler, shard, shard_manager, worker, worker_activator,
+    worker_enumeration, HasActiveWorkers, HasBlobStoreService, HasComponentService, HasConfig,
+    HasDomainEventService, HasEvents, HasExtraDeps, HasFeatureFlagService, HasInferenceService,
     HasKeyValueService, HasOplogService, HasPromiseService, HasRpc,
     HasRunningWorkerEnumerationService, HasSchedulerService, HasShardManagerService,
     HasShardService, HasWasmtimeEngine, HasWorkerActivator, HasWorkerEnumerationService,
@@ -142,6 +143,9 @@ impl From<WorkerProxyError> for RpcError {
             WorkerProxyError::NotFound(error) => RpcError::NotFound { details: error },
             WorkerProxyError::AlreadyExists(error) => RpcError::Denied { details: error },
             WorkerProxyError::InternalError(error) => error.into(),
+            WorkerProxyError::Unavailable(error) => {
+                RpcError::RemoteInternalError { details: error }
+            }
         }
     }
 }
@@ -269,6 +273,9 @@ pub struct DirectWorkerInvocationRpc<Ctx: WorkerCtx> {
     shard_service: Arc<dyn shard::ShardService + Send + Sync>,
     key_value_service: Arc<dyn key_value::KeyValueService + Send + Sync>,
     blob_store_service: Arc<dyn blob_store::BlobStoreService + Send + Sync>,
+    domain_event_service: Arc<dyn domain_event::DomainEventService + Send + Sync>,
+    feature_flag_service: Arc<dyn feature_flags::FeatureFlagService + Send + Sync>,
+    inference_service: Arc<dyn inference::InferenceService + Send + Sync>,
     oplog_service: Arc<dyn oplog::OplogService + Send + Sync>,
     scheduler_service: Arc<dyn scheduler::SchedulerService + Send + Sync>,
     worker_activator: Arc<dyn worker_activator::WorkerActivator + Send + Sync>,
@@ -294,6 +301,9 @@ impl<Ctx: WorkerCtx> Clone for DirectWorkerInvocationRpc<Ctx> {
             shard_service: self.shard_service.clone(),
             key_value_service: self.key_value_service.clone(),
             blob_store_service: self.blob_store_service.clone(),
+            domain_event_service: self.domain_event_service.clone(),
+            feature_flag_service: self.feature_flag_service.clone(),
+            inference_service: self.inference_service.clone(),
             oplog_service: self.oplog_service.clone(),
             scheduler_service: self.scheduler_service.clone(),
             worker_activator: self.worker_activator.clone(),
@@ -387,6 +397,24 @@ impl<Ctx: WorkerCtx> HasSchedulerService for DirectWorkerInvocationRpc<Ctx> {
     }
 }
 
+impl<Ctx: WorkerCtx> HasDomainEventService for DirectWorkerInvocationRpc<Ctx> {
+    fn domain_event_service(&self) -> Arc<dyn domain_event::DomainEventService + Send + Sync> {
+        self.domain_event_service.clone()
+    }
+}
+
+impl<Ctx: WorkerCtx> HasFeatureFlagService for DirectWorkerInvocationRpc<Ctx> {
+    fn feature_flag_service(&self) -> Arc<dyn feature_flags::FeatureFlagService + Send + Sync> {
+        self.feature_flag_service.clone()
+    }
+}
+
+impl<Ctx: WorkerCtx> HasInferenceService for DirectWorkerInvocationRpc<Ctx> {
+    fn inference_service(&self) -> Arc<dyn inference::InferenceService + Send + Sync> {
+        self.inference_service.clone()
+    }
+}
+
 impl<Ctx: WorkerCtx> HasOplogService for DirectWorkerInvocationRpc<Ctx> {
     fn oplog_service(&self) -> Arc<dyn oplog::OplogService + Send + Sync> {
         self.oplog_service.clone()
@@ -450,6 +478,9 @@ impl<Ctx: WorkerCtx> DirectWorkerInvocationRpc<Ctx> {
         shard_manager_service: Arc<dyn shard_manager::ShardManagerService + Send + Sync>,
         key_value_service: Arc<dyn key_value::KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn blob_store::BlobStoreService + Send + Sync>,
+        domain_event_service: Arc<dyn domain_event::DomainEventService + Send + Sync>,
+        feature_flag_service: Arc<dyn feature_flags::FeatureFlagService + Send + Sync>,
+        inference_service: Arc<dyn inference::InferenceService + Send + Sync>,
         oplog_service: Arc<dyn oplog::OplogService + Send + Sync>,
         scheduler_service: Arc<dyn scheduler::SchedulerService + Send + Sync>,
         worker_activator: Arc<dyn worker_activator::WorkerActivator + Send + Sync>,
@@ -472,6 +503,9 @@ impl<Ctx: WorkerCtx> DirectWorkerInvocationRpc<Ctx> {
             shard_service,
             key_value_service,
             blob_store_service,
+            domain_event_service,
+            feature_flag_service,
+            inference_service,
             oplog_service,
             scheduler_service,
             worker_activator,
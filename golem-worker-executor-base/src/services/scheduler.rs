@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::Add;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -25,15 +25,19 @@ use tracing::{error, info, span, warn, Instrument, Level};
 
 use crate::metrics::oplog::record_scheduled_archive;
 use crate::metrics::promises::record_scheduled_promise_completed;
+use crate::metrics::workers::{record_expired_worker_deleted, record_scheduled_invocation};
 use crate::services::oplog::{MultiLayerOplog, OplogService};
 use crate::services::promise::PromiseService;
 use crate::services::shard::ShardService;
 use crate::services::worker::WorkerService;
 use crate::services::worker_activator::WorkerActivator;
+use crate::services::worker_proxy::WorkerProxy;
 use crate::storage::keyvalue::{
-    KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+    KeyValueCheck, KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
+    KeyValueWrite,
 };
-use golem_common::model::{ComponentType, ScheduleId, ScheduledAction};
+use golem_common::model::{ComponentId, ComponentType, ScheduleId, ScheduledAction};
+use golem_common::serialization::{deserialize, serialize};
 
 #[async_trait]
 pub trait SchedulerService {
@@ -51,6 +55,7 @@ pub struct SchedulerServiceDefault {
     worker_activator: Arc<dyn WorkerActivator + Send + Sync>,
     oplog_service: Arc<dyn OplogService + Send + Sync>,
     worker_service: Arc<dyn WorkerService + Send + Sync>,
+    worker_proxy: Arc<dyn WorkerProxy + Send + Sync>,
 }
 
 impl SchedulerServiceDefault {
@@ -61,6 +66,7 @@ impl SchedulerServiceDefault {
         worker_activator: Arc<dyn WorkerActivator + Send + Sync>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
         worker_service: Arc<dyn WorkerService + Send + Sync>,
+        worker_proxy: Arc<dyn WorkerProxy + Send + Sync>,
         process_interval: Duration,
     ) -> Arc<Self> {
         let svc = Self {
@@ -71,6 +77,7 @@ impl SchedulerServiceDefault {
             oplog_service,
             worker_service,
             worker_activator,
+            worker_proxy,
         };
         let svc = Arc::new(svc);
         let background_handle = {
@@ -94,30 +101,72 @@ impl SchedulerServiceDefault {
         svc
     }
 
+    /// Checks once per processing run whether a component is in maintenance. If it cannot be
+    /// checked, the invocations are not held back.
+    async fn is_in_maintenance(
+        &self,
+        checked: &mut HashMap<ComponentId, bool>,
+        component_id: &ComponentId,
+    ) -> bool {
+        if let Some(in_maintenance) = checked.get(component_id) {
+            return *in_maintenance;
+        }
+        let in_maintenance = match self.worker_proxy.in_maintenance(component_id).await {
+            Ok(in_maintenance) => in_maintenance,
+            Err(err) => {
+                warn!(
+                    component_id = component_id.to_string(),
+                    "Failed to check whether the component is in maintenance: {err}"
+                );
+                false
+            }
+        };
+        checked.insert(component_id.clone(), in_maintenance);
+        in_maintenance
+    }
+
     async fn process(&self, now: DateTime<Utc>) -> Result<(), String> {
         let (hours_since_epoch, remainder) = Self::split_time(now);
-        let previous_hours_since_epoch = hours_since_epoch - 1;
-
-        let previous_hour_key = Self::schedule_key_from_timestamp(previous_hours_since_epoch);
-        let current_hour_key = Self::schedule_key_from_timestamp(hours_since_epoch);
 
-        let all_from_prev_hour: Vec<(f64, ScheduledAction)> = self
+        // Every hour since the first one still having actions is scanned, so the actions which
+        // could not be processed yet are not left behind
+        let first_pending_raw = self
             .key_value_storage
-            .with_entity("scheduler", "process", "scheduled_action")
-            .get_sorted_set(KeyValueStorageNamespace::Schedule, &previous_hour_key)
+            .with_entity("scheduler", "process", "first_pending_bucket")
+            .get_raw(
+                KeyValueStorageNamespace::Schedule,
+                Self::FIRST_PENDING_BUCKET_KEY,
+            )
             .await?;
+        let first_pending_bucket = match &first_pending_raw {
+            Some(bytes) => deserialize::<i64>(bytes)?,
+            None => hours_since_epoch - 1,
+        }
+        .min(hours_since_epoch);
 
-        let mut all: Vec<(&str, ScheduledAction)> = all_from_prev_hour
-            .into_iter()
-            .map(|(_score, action)| (previous_hour_key.as_str(), action))
-            .collect();
+        let mut all: Vec<(i64, ScheduledAction)> = Vec::new();
+        for bucket in first_pending_bucket..hours_since_epoch {
+            let all_from_bucket: Vec<(f64, ScheduledAction)> = self
+                .key_value_storage
+                .with_entity("scheduler", "process", "scheduled_action")
+                .get_sorted_set(
+                    KeyValueStorageNamespace::Schedule,
+                    &Self::schedule_key_from_timestamp(bucket),
+                )
+                .await?;
+            all.extend(
+                all_from_bucket
+                    .into_iter()
+                    .map(|(_score, action)| (bucket, action)),
+            );
+        }
 
         let all_from_this_hour: Vec<(f64, ScheduledAction)> = self
             .key_value_storage
             .with_entity("scheduler", "process", "scheduled_action")
             .query_sorted_set(
                 KeyValueStorageNamespace::Schedule,
-                &current_hour_key,
+                &Self::schedule_key_from_timestamp(hours_since_epoch),
                 0.0,
                 remainder,
             )
@@ -126,10 +175,15 @@ impl SchedulerServiceDefault {
         all.extend(
             all_from_this_hour
                 .into_iter()
-                .map(|(_score, action)| (current_hour_key.as_str(), action)),
+                .map(|(_score, action)| (hours_since_epoch, action)),
         );
 
-        let matching: Vec<(&str, ScheduledAction)> = all
+        let mut remaining: BTreeMap<i64, usize> = BTreeMap::new();
+        for (bucket, _) in &all {
+            *remaining.entry(*bucket).or_default() += 1;
+        }
+
+        let matching: Vec<(i64, ScheduledAction)> = all
             .into_iter()
             .filter(|(_, action)| {
                 self.shard_service
@@ -139,12 +193,67 @@ impl SchedulerServiceDefault {
             .collect::<Vec<_>>();
 
         let mut owned_worker_ids = HashSet::new();
-        for (key, action) in matching {
-            owned_worker_ids.insert(action.owned_worker_id().clone());
+        let mut in_maintenance = HashMap::new();
+        for (bucket, action) in matching {
+            // Invocations of components in maintenance stay in the schedule until it ends
+            if let ScheduledAction::Invoke {
+                owned_worker_id, ..
+            } = &action
+            {
+                if self
+                    .is_in_maintenance(&mut in_maintenance, &owned_worker_id.worker_id.component_id)
+                    .await
+                {
+                    continue;
+                }
+            }
+
+            // Deleted workers must not be activated again
+            if !matches!(action, ScheduledAction::DeleteWorker { .. }) {
+                owned_worker_ids.insert(action.owned_worker_id().clone());
+            }
+
+            // Invocations are enqueued before removing them from the schedule, so they are not
+            // lost if the executor stops in between, and the ones failing to be enqueued are
+            // retried by the next run. Enqueuing one again is deduplicated by its idempotency key.
+            if let ScheduledAction::Invoke {
+                owned_worker_id,
+                idempotency_key,
+                full_function_name,
+                function_input,
+            } = &action
+            {
+                let result = self
+                    .worker_activator
+                    .invoke_worker(
+                        owned_worker_id,
+                        idempotency_key.clone(),
+                        full_function_name.clone(),
+                        function_input.clone(),
+                    )
+                    .await;
+                record_scheduled_invocation(result.is_ok());
+                if let Err(err) = result {
+                    error!(
+                        worker_id = owned_worker_id.to_string(),
+                        idempotency_key = idempotency_key.to_string(),
+                        "Failed to enqueue scheduled invocation of {full_function_name}, retrying later: {err}"
+                    );
+                    continue;
+                }
+            }
+
             self.key_value_storage
                 .with_entity("scheduler", "process", "scheduled_action")
-                .remove_from_sorted_set(KeyValueStorageNamespace::Schedule, key, &action)
+                .remove_from_sorted_set(
+                    KeyValueStorageNamespace::Schedule,
+                    &Self::schedule_key_from_timestamp(bucket),
+                    &action,
+                )
                 .await?;
+            if let Some(count) = remaining.get_mut(&bucket) {
+                *count -= 1;
+            }
 
             match action {
                 ScheduledAction::CompletePromise { promise_id, .. } => {
@@ -200,9 +309,48 @@ impl SchedulerServiceDefault {
                         // TODO: metrics
                     }
                 }
+                ScheduledAction::DeleteWorker {
+                    owned_worker_id,
+                    expires_at,
+                } => {
+                    // The TTL of the worker may have been changed or removed since scheduling
+                    if self.worker_service.get_expiry(&owned_worker_id).await == Some(expires_at) {
+                        info!(
+                            worker_id = owned_worker_id.to_string(),
+                            "Deleting expired worker"
+                        );
+                        self.worker_activator.delete_worker(&owned_worker_id).await;
+                        record_expired_worker_deleted();
+                    }
+                }
+                ScheduledAction::Invoke { .. } => {}
             }
         }
 
+        let next_first_pending_bucket = remaining
+            .iter()
+            .find(|(_, count)| **count > 0)
+            .map(|(bucket, _)| *bucket)
+            .unwrap_or(hours_since_epoch);
+        if next_first_pending_bucket != first_pending_bucket || first_pending_raw.is_none() {
+            // Not overwriting the bucket if an earlier one was scheduled in the meantime
+            self.key_value_storage
+                .with_entity("scheduler", "process", "first_pending_bucket")
+                .commit_raw(
+                    KeyValueStorageNamespace::Schedule,
+                    &[KeyValueCheck {
+                        key: Self::FIRST_PENDING_BUCKET_KEY.to_string(),
+                        expected: first_pending_raw.map(|bytes| bytes.to_vec()),
+                    }],
+                    &[KeyValueWrite {
+                        key: Self::FIRST_PENDING_BUCKET_KEY.to_string(),
+                        value: Some(serialize(&next_first_pending_bucket)?.to_vec()),
+                        ttl: None,
+                    }],
+                )
+                .await?;
+        }
+
         for owned_worker_id in owned_worker_ids {
             let span = span!(
                 Level::INFO,
@@ -220,6 +368,48 @@ impl SchedulerServiceDefault {
 
     const HOUR_IN_MILLIS: i64 = 1000 * 60 * 60;
 
+    /// Key of the first hour which may still have scheduled actions
+    const FIRST_PENDING_BUCKET_KEY: &'static str = "worker:schedule:first-pending";
+
+    /// Makes sure the hour of a newly scheduled action is not before the first pending one
+    async fn lower_first_pending_bucket(&self, bucket: i64) -> Result<(), String> {
+        loop {
+            let current = self
+                .key_value_storage
+                .with_entity("scheduler", "schedule", "first_pending_bucket")
+                .get_raw(
+                    KeyValueStorageNamespace::Schedule,
+                    Self::FIRST_PENDING_BUCKET_KEY,
+                )
+                .await?;
+            let target = match &current {
+                Some(bytes) if deserialize::<i64>(bytes)? <= bucket => return Ok(()),
+                Some(_) => bucket,
+                // Before the first run the previous hour is scanned
+                None => bucket.min(Self::split_time(Utc::now()).0 - 1),
+            };
+            let committed = self
+                .key_value_storage
+                .with_entity("scheduler", "schedule", "first_pending_bucket")
+                .commit_raw(
+                    KeyValueStorageNamespace::Schedule,
+                    &[KeyValueCheck {
+                        key: Self::FIRST_PENDING_BUCKET_KEY.to_string(),
+                        expected: current.map(|bytes| bytes.to_vec()),
+                    }],
+                    &[KeyValueWrite {
+                        key: Self::FIRST_PENDING_BUCKET_KEY.to_string(),
+                        value: Some(serialize(&target)?.to_vec()),
+                        ttl: None,
+                    }],
+                )
+                .await?;
+            if committed {
+                return Ok(());
+            }
+        }
+    }
+
     fn split_time<Tz: TimeZone>(time: DateTime<Tz>) -> (i64, f64) {
         let millis = time.timestamp_millis();
         let hours_since_epoch = millis / Self::HOUR_IN_MILLIS;
@@ -266,6 +456,11 @@ impl SchedulerService for SchedulerServiceDefault {
             .unwrap_or_else(|err| {
                 panic!("failed to add schedule for action {action} in KV storage: {err}")
             });
+        self.lower_first_pending_bucket(hours_since_epoch)
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to update the first pending schedule in KV storage: {err}")
+            });
 
         id
     }
@@ -309,12 +504,15 @@ mod tests {
     use crate::services::shard::{ShardService, ShardServiceDefault};
     use crate::services::worker::{DefaultWorkerService, WorkerService};
     use crate::services::worker_activator::{WorkerActivator, WorkerActivatorMock};
+    use crate::services::worker_proxy::{WorkerProxy, WorkerProxyMock};
     use crate::storage::blob::memory::InMemoryBlobStorage;
     use crate::storage::indexed::memory::InMemoryIndexedStorage;
     use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
     use golem_common::model::oplog::OplogIndex;
+    use golem_common::model::oplog_codec::OplogEncoding;
     use golem_common::model::{
-        AccountId, ComponentId, PromiseId, ScheduledAction, ShardId, WorkerId,
+        AccountId, ComponentId, IdempotencyKey, OwnedWorkerId, PromiseId, ScheduledAction, ShardId,
+        WorkerId,
     };
 
     fn serialized_bytes<T: Encode>(entry: &T) -> Vec<u8> {
@@ -337,13 +535,20 @@ mod tests {
         Arc::new(WorkerActivatorMock::new())
     }
 
+    fn create_worker_proxy_mock() -> Arc<dyn WorkerProxy + Send + Sync> {
+        Arc::new(WorkerProxyMock::new())
+    }
+
     async fn create_oplog_service_mock() -> Arc<dyn OplogService + Send + Sync> {
         Arc::new(
             PrimaryOplogService::new(
                 Arc::new(InMemoryIndexedStorage::new()),
                 Arc::new(InMemoryBlobStorage::new()),
                 1,
+                4,
                 1024,
+                OplogEncoding::Bincode,
+                false,
             )
             .await,
         )
@@ -403,6 +608,7 @@ mod tests {
             worker_activator,
             oplog_service,
             worker_service,
+            create_worker_proxy_mock(),
             Duration::from_secs(1000), // not testing process() here
         );
 
@@ -520,6 +726,7 @@ mod tests {
             worker_activator,
             oplog_service,
             worker_service,
+            create_worker_proxy_mock(),
             Duration::from_secs(1000), // not testing process() here
         );
 
@@ -622,6 +829,7 @@ mod tests {
             worker_activator,
             oplog_service,
             worker_service,
+            create_worker_proxy_mock(),
             Duration::from_secs(1000), // explicitly calling process for testing
         );
 
@@ -729,6 +937,7 @@ mod tests {
             worker_activator,
             oplog_service,
             worker_service,
+            create_worker_proxy_mock(),
             Duration::from_secs(1000), // explicitly calling process for testing
         );
 
@@ -834,6 +1043,7 @@ mod tests {
             worker_activator,
             oplog_service,
             worker_service,
+            create_worker_proxy_mock(),
             Duration::from_secs(1000), // explicitly calling process for testing
         );
 
@@ -945,6 +1155,7 @@ mod tests {
             worker_activator,
             oplog_service,
             worker_service,
+            create_worker_proxy_mock(),
             Duration::from_secs(1000), // explicitly calling process for testing
         );
 
@@ -1000,4 +1211,293 @@ mod tests {
         assert!(completed_promises.contains(&p3));
         assert!(completed_promises.contains(&p2));
     }
+
+    #[test]
+    pub async fn process_enqueues_due_invocations() {
+        let account_id = AccountId {
+            value: "test-account".to_string(),
+        };
+        let owned_worker_id = OwnedWorkerId::new(
+            &account_id,
+            &WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: "inst1".to_string(),
+            },
+        );
+        let k1 = IdempotencyKey::fresh();
+        let k2 = IdempotencyKey::fresh();
+
+        let kvs = Arc::new(InMemoryKeyValueStorage::new());
+
+        let shard_service = create_shard_service_mock();
+        let promise_service = create_promise_service_mock();
+        let worker_activator = Arc::new(WorkerActivatorMock::new());
+        let oplog_service = create_oplog_service_mock().await;
+        let worker_service =
+            create_worker_service_mock(kvs.clone(), shard_service.clone(), oplog_service.clone());
+
+        let svc = SchedulerServiceDefault::new(
+            kvs.clone(),
+            shard_service,
+            promise_service,
+            worker_activator.clone(),
+            oplog_service,
+            worker_service,
+            create_worker_proxy_mock(),
+            Duration::from_secs(1000), // explicitly calling process for testing
+        );
+
+        let invoke = |idempotency_key: &IdempotencyKey| ScheduledAction::Invoke {
+            owned_worker_id: owned_worker_id.clone(),
+            idempotency_key: idempotency_key.clone(),
+            full_function_name: "golem:it/api.{run}".to_string(),
+            function_input: vec![golem_wasm_rpc::Value::U64(1)],
+        };
+        let _s1 = svc
+            .schedule(
+                DateTime::from_str("2023-07-17T10:05:00Z").unwrap(),
+                invoke(&k1),
+            )
+            .await;
+        let _s2 = svc
+            .schedule(
+                DateTime::from_str("2023-07-17T10:20:00Z").unwrap(),
+                invoke(&k2),
+            )
+            .await;
+
+        svc.process(DateTime::from_str("2023-07-17T10:15:00Z").unwrap())
+            .await
+            .unwrap();
+
+        let result = kvs
+            .sorted_sets()
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect::<HashMap<_, _>>();
+        // The only item remaining is the one in the future
+        assert_eq!(
+            result,
+            HashMap::from([(
+                "Schedule/worker:schedule:469330".to_string(),
+                vec![(1200000.0, serialized_bytes(&invoke(&k2)))]
+            )])
+        );
+        assert_eq!(worker_activator.invocations(), vec![(owned_worker_id, k1)]);
+    }
+
+    #[test]
+    pub async fn process_retries_failed_invocations() {
+        let account_id = AccountId {
+            value: "test-account".to_string(),
+        };
+        let owned_worker_id = OwnedWorkerId::new(
+            &account_id,
+            &WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: "inst1".to_string(),
+            },
+        );
+        let k1 = IdempotencyKey::fresh();
+
+        let kvs = Arc::new(InMemoryKeyValueStorage::new());
+
+        let shard_service = create_shard_service_mock();
+        let promise_service = create_promise_service_mock();
+        let worker_activator = Arc::new(WorkerActivatorMock::new());
+        let oplog_service = create_oplog_service_mock().await;
+        let worker_service =
+            create_worker_service_mock(kvs.clone(), shard_service.clone(), oplog_service.clone());
+
+        let svc = SchedulerServiceDefault::new(
+            kvs.clone(),
+            shard_service,
+            promise_service,
+            worker_activator.clone(),
+            oplog_service,
+            worker_service,
+            create_worker_proxy_mock(),
+            Duration::from_secs(1000), // explicitly calling process for testing
+        );
+
+        let action = ScheduledAction::Invoke {
+            owned_worker_id: owned_worker_id.clone(),
+            idempotency_key: k1.clone(),
+            full_function_name: "golem:it/api.{run}".to_string(),
+            function_input: vec![],
+        };
+        let _s1 = svc
+            .schedule(
+                DateTime::from_str("2023-07-17T10:05:00Z").unwrap(),
+                action.clone(),
+            )
+            .await;
+
+        worker_activator.set_failing(true);
+        svc.process(DateTime::from_str("2023-07-17T10:15:00Z").unwrap())
+            .await
+            .unwrap();
+        let result1 = kvs
+            .sorted_sets()
+            .get("Schedule/worker:schedule:469330")
+            .map(|entry| entry.value().clone());
+
+        // Retried by a run more than an hour later
+        worker_activator.set_failing(false);
+        svc.process(DateTime::from_str("2023-07-17T13:15:00Z").unwrap())
+            .await
+            .unwrap();
+        let result2 = kvs
+            .sorted_sets()
+            .get("Schedule/worker:schedule:469330")
+            .map(|entry| entry.value().clone());
+
+        assert_eq!(result1, Some(vec![(300000.0, serialized_bytes(&action))]));
+        assert_eq!(result2, Some(vec![]));
+        assert_eq!(worker_activator.invocations(), vec![(owned_worker_id, k1)]);
+    }
+
+    #[test]
+    pub async fn process_holds_back_invocations_in_maintenance() {
+        let account_id = AccountId {
+            value: "test-account".to_string(),
+        };
+        let owned_worker_id = OwnedWorkerId::new(
+            &account_id,
+            &WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: "inst1".to_string(),
+            },
+        );
+        let k1 = IdempotencyKey::fresh();
+
+        let kvs = Arc::new(InMemoryKeyValueStorage::new());
+
+        let shard_service = create_shard_service_mock();
+        let promise_service = create_promise_service_mock();
+        let worker_activator = Arc::new(WorkerActivatorMock::new());
+        let oplog_service = create_oplog_service_mock().await;
+        let worker_service =
+            create_worker_service_mock(kvs.clone(), shard_service.clone(), oplog_service.clone());
+        let worker_proxy = Arc::new(WorkerProxyMock::new());
+
+        let svc = SchedulerServiceDefault::new(
+            kvs.clone(),
+            shard_service,
+            promise_service,
+            worker_activator.clone(),
+            oplog_service,
+            worker_service,
+            worker_proxy.clone(),
+            Duration::from_secs(1000), // explicitly calling process for testing
+        );
+
+        let action = ScheduledAction::Invoke {
+            owned_worker_id: owned_worker_id.clone(),
+            idempotency_key: k1.clone(),
+            full_function_name: "golem:it/api.{run}".to_string(),
+            function_input: vec![],
+        };
+        let _s1 = svc
+            .schedule(
+                DateTime::from_str("2023-07-17T10:05:00Z").unwrap(),
+                action.clone(),
+            )
+            .await;
+
+        worker_proxy.set_in_maintenance(&owned_worker_id.worker_id.component_id, true);
+        svc.process(DateTime::from_str("2023-07-17T10:15:00Z").unwrap())
+            .await
+            .unwrap();
+        let result1 = kvs
+            .sorted_sets()
+            .get("Schedule/worker:schedule:469330")
+            .map(|entry| entry.value().clone());
+        let invocations1 = worker_activator.invocations();
+
+        worker_proxy.set_in_maintenance(&owned_worker_id.worker_id.component_id, false);
+        svc.process(DateTime::from_str("2023-07-17T13:15:00Z").unwrap())
+            .await
+            .unwrap();
+        let result2 = kvs
+            .sorted_sets()
+            .get("Schedule/worker:schedule:469330")
+            .map(|entry| entry.value().clone());
+
+        assert_eq!(result1, Some(vec![(300000.0, serialized_bytes(&action))]));
+        assert_eq!(invocations1, vec![]);
+        assert_eq!(result2, Some(vec![]));
+        assert_eq!(worker_activator.invocations(), vec![(owned_worker_id, k1)]);
+    }
+
+    #[test]
+    pub async fn process_scans_from_first_pending_hour() {
+        let account_id = AccountId {
+            value: "test-account".to_string(),
+        };
+        let p1: PromiseId = PromiseId {
+            worker_id: WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: "inst1".to_string(),
+            },
+            oplog_idx: OplogIndex::from_u64(101),
+        };
+        let p2: PromiseId = PromiseId {
+            worker_id: p1.worker_id.clone(),
+            oplog_idx: OplogIndex::from_u64(102),
+        };
+
+        let kvs = Arc::new(InMemoryKeyValueStorage::new());
+
+        let shard_service = create_shard_service_mock();
+        let promise_service = create_promise_service_mock();
+        let worker_activator = create_worker_activator_mock();
+        let oplog_service = create_oplog_service_mock().await;
+        let worker_service =
+            create_worker_service_mock(kvs.clone(), shard_service.clone(), oplog_service.clone());
+
+        let svc = SchedulerServiceDefault::new(
+            kvs.clone(),
+            shard_service,
+            promise_service.clone(),
+            worker_activator,
+            oplog_service,
+            worker_service,
+            create_worker_proxy_mock(),
+            Duration::from_secs(1000), // explicitly calling process for testing
+        );
+
+        let _s1 = svc
+            .schedule(
+                DateTime::from_str("2023-07-17T07:05:00Z").unwrap(),
+                ScheduledAction::CompletePromise {
+                    promise_id: p1.clone(),
+                    account_id: account_id.clone(),
+                },
+            )
+            .await;
+
+        svc.process(DateTime::from_str("2023-07-17T10:15:00Z").unwrap())
+            .await
+            .unwrap();
+
+        let _s2 = svc
+            .schedule(
+                DateTime::from_str("2023-07-17T08:05:00Z").unwrap(),
+                ScheduledAction::CompletePromise {
+                    promise_id: p2.clone(),
+                    account_id: account_id.clone(),
+                },
+            )
+            .await;
+
+        svc.process(DateTime::from_str("2023-07-17T10:20:00Z").unwrap())
+            .await
+            .unwrap();
+
+        let completed_promises = promise_service.all_completed().await;
+
+        assert!(completed_promises.contains(&p1));
+        assert!(completed_promises.contains(&p2));
+    }
 }
@@ -19,9 +19,10 @@ use async_trait::async_trait;
 use golem_api_grpc::proto::golem::shardmanager;
 use golem_api_grpc::proto::golem::shardmanager::v1::shard_manager_service_client::ShardManagerServiceClient;
 use golem_common::client::{GrpcClient, GrpcClientConfig};
+use golem_common::config::GrpcCompression;
 use golem_common::model::{ShardAssignment, ShardId};
 use golem_common::retries::with_retries;
-use tonic::codec::CompressionEncoding;
+use golem_common::with_grpc_compression;
 use tonic::transport::Channel;
 
 use crate::error::GolemError;
@@ -36,11 +37,13 @@ pub trait ShardManagerService {
 
 pub fn configured(
     config: &ShardManagerServiceConfig,
+    grpc_compression: GrpcCompression,
 ) -> Arc<dyn ShardManagerService + Send + Sync> {
     match config {
-        ShardManagerServiceConfig::Grpc(config) => {
-            Arc::new(ShardManagerServiceGrpc::new(config.clone()))
-        }
+        ShardManagerServiceConfig::Grpc(config) => Arc::new(ShardManagerServiceGrpc::new(
+            config.clone(),
+            grpc_compression,
+        )),
         ShardManagerServiceConfig::SingleShard => Arc::new(ShardManagerServiceSingleShard::new()),
     }
 }
@@ -51,12 +54,10 @@ pub struct ShardManagerServiceGrpc {
 }
 
 impl ShardManagerServiceGrpc {
-    pub fn new(config: ShardManagerServiceGrpcConfig) -> Self {
+    pub fn new(config: ShardManagerServiceGrpcConfig, grpc_compression: GrpcCompression) -> Self {
         let client = GrpcClient::new(
-            |channel| {
-                ShardManagerServiceClient::new(channel)
-                    .send_compressed(CompressionEncoding::Gzip)
-                    .accept_compressed(CompressionEncoding::Gzip)
+            move |channel| {
+                with_grpc_compression!(ShardManagerServiceClient::new(channel), grpc_compression)
             },
             config.uri().as_http_02(),
             GrpcClientConfig {
@@ -12,12 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use golem_common::cache::{BackgroundEvictionMode, Cache, FullCacheEvictionMode, SimpleCache};
 use golem_common::model::oplog::{OplogEntry, OplogIndex};
+use golem_common::model::worker_name_query::WorkerNameQuery;
 use golem_common::model::{
-    ComponentType, OwnedWorkerId, ShardId, Timestamp, WorkerId, WorkerMetadata, WorkerStatus,
+    AccountId, ComponentId, ComponentType, OwnedWorkerId, ScanCursor, ShardId, Timestamp,
+    UpdateDivergence, WorkerDependent, WorkerId, WorkerMetadata, WorkerShadow, WorkerStatus,
     WorkerStatusRecord,
 };
 use tracing::{debug, info, warn};
@@ -31,6 +36,9 @@ use crate::storage::keyvalue::{
     KeyValueStorage, KeyValueStorageLabelledApi, KeyValueStorageNamespace,
 };
 
+/// The number of update divergences kept for each worker
+pub const MAX_UPDATE_DIVERGENCES: usize = 10;
+
 /// Service for persisting the current set of Golem workers represented by their metadata
 #[async_trait]
 pub trait WorkerService {
@@ -44,16 +52,82 @@ pub trait WorkerService {
 
     async fn get_running_workers_in_shards(&self) -> Vec<WorkerMetadata>;
 
+    /// Gets the ids of the workers marked as running in the given shards, without looking up their metadata
+    async fn get_running_worker_ids_in_shards(
+        &self,
+        shard_ids: &HashSet<ShardId>,
+    ) -> Vec<OwnedWorkerId>;
+
+    /// Excludes an inconsistent worker from recovery by removing it from the set of running workers
+    /// and recording it in the set of quarantined workers. Its oplog is kept intact.
+    async fn quarantine(&self, owned_worker_id: &OwnedWorkerId);
+
     async fn remove(&self, owned_worker_id: &OwnedWorkerId);
 
     async fn remove_cached_status(&self, owned_worker_id: &OwnedWorkerId);
 
+    /// Finds workers of a component by name, using the index of worker names maintained by `add`
+    /// and `remove`. Prefix queries only read the matching range of the index, while pattern
+    /// queries read the component's whole index. The first search of a component adds its
+    /// workers created before the index existed.
+    async fn search(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        query: &WorkerNameQuery,
+        count: usize,
+    ) -> Result<Vec<WorkerId>, GolemError>;
+
+    /// Gets the shadow invocation settings of a worker, if shadowing is enabled for it
+    async fn get_shadow(&self, owned_worker_id: &OwnedWorkerId) -> Option<WorkerShadow>;
+
+    /// Enables shadow invocations for a worker, or disables them if `shadow` is `None`
+    async fn set_shadow(&self, owned_worker_id: &OwnedWorkerId, shadow: Option<WorkerShadow>);
+
+    /// Gets the point in time after which the worker gets deleted, if it has a TTL
+    async fn get_expiry(&self, owned_worker_id: &OwnedWorkerId) -> Option<Timestamp>;
+
+    /// Sets the point in time after which the worker gets deleted, or removes the worker's
+    /// expiry if `expires_at` is `None`. Deleting the worker has to be scheduled separately.
+    async fn set_expiry(&self, owned_worker_id: &OwnedWorkerId, expires_at: Option<Timestamp>);
+
+    /// Gets how long the completed idempotency keys of a component's workers are remembered.
+    /// `None` means they are never forgotten.
+    async fn get_deduplication_window(&self, component_id: &ComponentId) -> Option<Duration>;
+
+    /// Sets how long the completed idempotency keys of a component's workers are remembered,
+    /// or removes the limit if `window` is `None`
+    async fn set_deduplication_window(&self, component_id: &ComponentId, window: Option<Duration>);
+
+    /// Gets where the replay diverged in the recent failed automatic updates of a worker
+    async fn get_update_divergences(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Vec<UpdateDivergence>;
+
+    /// Records where the replay diverged in a failed automatic update of a worker, keeping only
+    /// the most recent `MAX_UPDATE_DIVERGENCES` of them
+    async fn add_update_divergence(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        divergence: UpdateDivergence,
+    );
+
     async fn update_status(
         &self,
         owned_worker_id: &OwnedWorkerId,
         status_value: &WorkerStatusRecord,
         component_type: ComponentType,
     );
+
+    /// Gets the resources owned by a worker, which can be removed together with it
+    async fn get_dependents(&self, owned_worker_id: &OwnedWorkerId) -> Vec<WorkerDependent>;
+
+    /// Records that a resource is owned by a worker
+    async fn add_dependent(&self, owned_worker_id: &OwnedWorkerId, dependent: &WorkerDependent);
+
+    /// Removes a resource from the ones owned by a worker, once it no longer exists
+    async fn remove_dependent(&self, owned_worker_id: &OwnedWorkerId, dependent: &WorkerDependent);
 }
 
 #[derive(Clone)]
@@ -61,6 +135,8 @@ pub struct DefaultWorkerService {
     key_value_storage: Arc<dyn KeyValueStorage + Send + Sync>,
     shard_service: Arc<dyn ShardService + Send + Sync>,
     oplog_service: Arc<dyn OplogService + Send + Sync>,
+    shadows: Cache<WorkerId, (), Option<WorkerShadow>, String>,
+    deduplication_windows: Cache<ComponentId, (), Option<Duration>, String>,
 }
 
 impl DefaultWorkerService {
@@ -73,6 +149,26 @@ impl DefaultWorkerService {
             key_value_storage,
             shard_service,
             oplog_service,
+            // Looked up for every invocation, while only changed through this executor
+            shadows: Cache::new(
+                Some(4096),
+                FullCacheEvictionMode::LeastRecentlyUsed(1),
+                BackgroundEvictionMode::OlderThan {
+                    ttl: Duration::from_secs(300),
+                    period: Duration::from_secs(60),
+                },
+                "worker_shadow",
+            ),
+            // Changes made through other executors are picked up when the cached entry expires
+            deduplication_windows: Cache::new(
+                Some(1024),
+                FullCacheEvictionMode::LeastRecentlyUsed(1),
+                BackgroundEvictionMode::OlderThan {
+                    ttl: Duration::from_secs(60),
+                    period: Duration::from_secs(30),
+                },
+                "deduplication_window",
+            ),
         }
     }
 
@@ -106,6 +202,104 @@ impl DefaultWorkerService {
     fn running_in_shard_key(shard_id: &ShardId) -> String {
         format!("worker:running_in_shard:{shard_id}")
     }
+
+    fn quarantined_key() -> String {
+        "worker:quarantined".to_string()
+    }
+
+    fn shadow_key(worker_id: &WorkerId) -> String {
+        format!("worker:shadow:{}", worker_id.to_redis_key())
+    }
+
+    fn expiry_key(worker_id: &WorkerId) -> String {
+        format!("worker:expiry:{}", worker_id.to_redis_key())
+    }
+
+    fn deduplication_window_key(component_id: &ComponentId) -> String {
+        format!("worker:deduplication_window:{component_id}")
+    }
+
+    fn names_key(component_id: &ComponentId) -> String {
+        format!("worker:name-index:{component_id}")
+    }
+
+    fn names_backfilled_key(component_id: &ComponentId) -> String {
+        format!("worker:name-index-backfilled:{component_id}")
+    }
+
+    /// Score of a worker name in the name index: its first 6 bytes as a big-endian number, padded
+    /// with `fill`. It is exact in an `f64`, so the names starting with a prefix all score
+    /// between the prefix padded with `0x00` and the prefix padded with `0xff`.
+    fn name_score(name: &str, fill: u8) -> f64 {
+        let mut bytes = [0u8; 8];
+        for (idx, byte) in bytes[2..].iter_mut().enumerate() {
+            *byte = name.as_bytes().get(idx).copied().unwrap_or(fill);
+        }
+        u64::from_be_bytes(bytes) as f64
+    }
+
+    async fn add_to_name_index(&self, api_name: &'static str, worker_id: &WorkerId) {
+        self.key_value_storage
+            .with_entity("worker", api_name, "worker_name")
+            .add_to_sorted_set(
+                KeyValueStorageNamespace::Worker,
+                &Self::names_key(&worker_id.component_id),
+                Self::name_score(&worker_id.worker_name, 0x00),
+                &worker_id.worker_name,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to add worker to the index of worker names in KV storage: {err}")
+            });
+    }
+
+    /// Adds the workers created before the name index existed, once per component
+    async fn backfill_name_index(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+    ) -> Result<(), GolemError> {
+        let backfilled_key = Self::names_backfilled_key(component_id);
+        let backfilled = self
+            .key_value_storage
+            .with("worker", "search")
+            .exists(KeyValueStorageNamespace::Worker, &backfilled_key)
+            .await
+            .map_err(GolemError::unknown)?;
+        if backfilled {
+            return Ok(());
+        }
+
+        let mut cursor = ScanCursor::default();
+        loop {
+            let (next_cursor, owned_worker_ids) = self
+                .oplog_service
+                .scan_for_component(account_id, component_id, cursor, 1000)
+                .await?;
+            for owned_worker_id in owned_worker_ids {
+                self.add_to_name_index("search", &owned_worker_id.worker_id)
+                    .await;
+            }
+            if next_cursor.is_finished() {
+                break;
+            }
+            cursor = next_cursor;
+        }
+
+        self.key_value_storage
+            .with_entity("worker", "search", "worker_name_index")
+            .set(KeyValueStorageNamespace::Worker, &backfilled_key, &true)
+            .await
+            .map_err(GolemError::unknown)
+    }
+
+    fn update_divergences_key(worker_id: &WorkerId) -> String {
+        format!("worker:update_divergences:{}", worker_id.to_redis_key())
+    }
+
+    fn dependents_key(worker_id: &WorkerId) -> String {
+        format!("worker:dependents:{}", worker_id.to_redis_key())
+    }
 }
 
 #[async_trait]
@@ -150,6 +344,8 @@ impl WorkerService for DefaultWorkerService {
                 .await
                 .unwrap_or_else(|err| panic!("failed to set worker status in KV storage: {err}"));
 
+            self.add_to_name_index("add", worker_id).await;
+
             if worker_metadata.last_known_status.status == WorkerStatus::Running {
                 let shard_assignment = self.shard_service.current_assignment()?;
                 let shard_id =
@@ -214,6 +410,7 @@ impl WorkerService for DefaultWorkerService {
                         total_linear_memory_size: initial_total_linear_memory_size,
                         ..WorkerStatusRecord::default()
                     },
+                    expires_at: self.get_expiry(owned_worker_id).await,
                 };
 
                 let status_value: Option<WorkerStatusRecord> = self
@@ -261,6 +458,7 @@ impl WorkerService for DefaultWorkerService {
                         oplog_idx: last_oplog_idx,
                         ..WorkerStatusRecord::default()
                     },
+                    expires_at: None,
                 })
             }
         }
@@ -279,11 +477,96 @@ impl WorkerService for DefaultWorkerService {
         result
     }
 
+    async fn get_running_worker_ids_in_shards(
+        &self,
+        shard_ids: &HashSet<ShardId>,
+    ) -> Vec<OwnedWorkerId> {
+        record_worker_call("enum_ids");
+
+        let mut result = Vec::new();
+        for shard_id in shard_ids {
+            let mut ids: Vec<OwnedWorkerId> = self
+                .key_value_storage
+                .with_entity("worker", "enum_ids", "worker_id")
+                .members_of_set(
+                    KeyValueStorageNamespace::Worker,
+                    &Self::running_in_shard_key(shard_id),
+                )
+                .await
+                .unwrap_or_else(|err| panic!("failed to get worker ids from KV storage: {err}"));
+            result.append(&mut ids);
+        }
+        result
+    }
+
+    async fn quarantine(&self, owned_worker_id: &OwnedWorkerId) {
+        record_worker_call("quarantine");
+
+        self.remove_cached_status(owned_worker_id).await;
+
+        let shard_assignment = self
+            .shard_service
+            .current_assignment()
+            .expect("sharding assigment is not ready");
+        let shard_id = ShardId::from_worker_id(
+            &owned_worker_id.worker_id,
+            shard_assignment.number_of_shards,
+        );
+
+        self.key_value_storage
+            .with_entity("worker", "quarantine", "worker_id")
+            .remove_from_set(
+                KeyValueStorageNamespace::Worker,
+                &Self::running_in_shard_key(&shard_id),
+                owned_worker_id,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to remove worker from the set of running workers in KV storage: {err}")
+            });
+
+        self.key_value_storage
+            .with_entity("worker", "quarantine", "worker_id")
+            .add_to_set(
+                KeyValueStorageNamespace::Worker,
+                &Self::quarantined_key(),
+                owned_worker_id,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to add worker to the set of quarantined workers in KV storage: {err}")
+            });
+    }
+
     async fn remove(&self, owned_worker_id: &OwnedWorkerId) {
         record_worker_call("remove");
 
         self.oplog_service.delete(owned_worker_id).await;
         self.remove_cached_status(owned_worker_id).await;
+        self.set_shadow(owned_worker_id, None).await;
+        self.set_expiry(owned_worker_id, None).await;
+
+        self.key_value_storage
+            .with("worker", "remove")
+            .del(
+                KeyValueStorageNamespace::Worker,
+                &Self::update_divergences_key(&owned_worker_id.worker_id),
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to remove worker update divergences in the KV storage: {err}")
+            });
+
+        self.key_value_storage
+            .with("worker", "remove")
+            .del(
+                KeyValueStorageNamespace::Worker,
+                &Self::dependents_key(&owned_worker_id.worker_id),
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to remove worker dependents in the KV storage: {err}")
+            });
 
         let shard_assignment = self
             .shard_service
@@ -304,6 +587,20 @@ impl WorkerService for DefaultWorkerService {
                     "failed to remove worker from the set of running worker ids per shard in KV storage: {err}"
                 )
             });
+
+        self.key_value_storage
+            .with_entity("worker", "remove", "worker_name")
+            .remove_from_sorted_set(
+                KeyValueStorageNamespace::Worker,
+                &Self::names_key(&owned_worker_id.worker_id.component_id),
+                &owned_worker_id.worker_id.worker_name,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!(
+                    "failed to remove worker from the index of worker names in KV storage: {err}"
+                )
+            });
     }
 
     async fn remove_cached_status(&self, owned_worker_id: &OwnedWorkerId) {
@@ -321,6 +618,232 @@ impl WorkerService for DefaultWorkerService {
             });
     }
 
+    async fn search(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        query: &WorkerNameQuery,
+        count: usize,
+    ) -> Result<Vec<WorkerId>, GolemError> {
+        record_worker_call("search");
+
+        self.backfill_name_index(account_id, component_id).await?;
+
+        let storage = self
+            .key_value_storage
+            .with_entity("worker", "search", "worker_name");
+        let key = Self::names_key(component_id);
+        let names: Vec<(f64, String)> = match query {
+            WorkerNameQuery::Prefix(prefix) => {
+                storage
+                    .query_sorted_set(
+                        KeyValueStorageNamespace::Worker,
+                        &key,
+                        Self::name_score(prefix, 0x00),
+                        Self::name_score(prefix, 0xff),
+                    )
+                    .await
+            }
+            WorkerNameQuery::Pattern(_) => {
+                storage
+                    .get_sorted_set(KeyValueStorageNamespace::Worker, &key)
+                    .await
+            }
+        }
+        .map_err(GolemError::unknown)?;
+
+        Ok(query
+            .search(names.into_iter().map(|(_, name)| name), count)
+            .into_iter()
+            .map(|worker_name| WorkerId {
+                component_id: component_id.clone(),
+                worker_name,
+            })
+            .collect())
+    }
+
+    async fn get_shadow(&self, owned_worker_id: &OwnedWorkerId) -> Option<WorkerShadow> {
+        record_worker_call("get_shadow");
+
+        let key_value_storage = self.key_value_storage.clone();
+        let key = Self::shadow_key(&owned_worker_id.worker_id);
+        self.shadows
+            .get_or_insert_simple(&owned_worker_id.worker_id, || {
+                Box::pin(async move {
+                    key_value_storage
+                        .with_entity("worker", "get_shadow", "worker_shadow")
+                        .get(KeyValueStorageNamespace::Worker, &key)
+                        .await
+                })
+            })
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to get worker shadow for {owned_worker_id} from KV storage: {err}")
+            })
+    }
+
+    async fn set_shadow(&self, owned_worker_id: &OwnedWorkerId, shadow: Option<WorkerShadow>) {
+        record_worker_call("set_shadow");
+
+        let key = Self::shadow_key(&owned_worker_id.worker_id);
+        let result = match shadow {
+            Some(shadow) => {
+                self.key_value_storage
+                    .with_entity("worker", "set_shadow", "worker_shadow")
+                    .set(KeyValueStorageNamespace::Worker, &key, &shadow)
+                    .await
+            }
+            None => {
+                self.key_value_storage
+                    .with("worker", "set_shadow")
+                    .del(KeyValueStorageNamespace::Worker, &key)
+                    .await
+            }
+        };
+        result.unwrap_or_else(|err| {
+            panic!("failed to update worker shadow for {owned_worker_id} in KV storage: {err}")
+        });
+        self.shadows.remove(&owned_worker_id.worker_id);
+    }
+
+    async fn get_expiry(&self, owned_worker_id: &OwnedWorkerId) -> Option<Timestamp> {
+        record_worker_call("get_expiry");
+
+        self.key_value_storage
+            .with_entity("worker", "get_expiry", "worker_expiry")
+            .get(
+                KeyValueStorageNamespace::Worker,
+                &Self::expiry_key(&owned_worker_id.worker_id),
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to get worker expiry for {owned_worker_id} from KV storage: {err}")
+            })
+    }
+
+    async fn set_expiry(&self, owned_worker_id: &OwnedWorkerId, expires_at: Option<Timestamp>) {
+        record_worker_call("set_expiry");
+
+        let key = Self::expiry_key(&owned_worker_id.worker_id);
+        let result = match expires_at {
+            Some(expires_at) => {
+                self.key_value_storage
+                    .with_entity("worker", "set_expiry", "worker_expiry")
+                    .set(KeyValueStorageNamespace::Worker, &key, &expires_at)
+                    .await
+            }
+            None => {
+                self.key_value_storage
+                    .with("worker", "set_expiry")
+                    .del(KeyValueStorageNamespace::Worker, &key)
+                    .await
+            }
+        };
+        result.unwrap_or_else(|err| {
+            panic!("failed to update worker expiry for {owned_worker_id} in KV storage: {err}")
+        });
+    }
+
+    async fn get_deduplication_window(&self, component_id: &ComponentId) -> Option<Duration> {
+        record_worker_call("get_deduplication_window");
+
+        let key_value_storage = self.key_value_storage.clone();
+        let key = Self::deduplication_window_key(component_id);
+        self.deduplication_windows
+            .get_or_insert_simple(component_id, || {
+                Box::pin(async move {
+                    key_value_storage
+                        .with_entity("worker", "get_deduplication_window", "deduplication_window")
+                        .get(KeyValueStorageNamespace::Worker, &key)
+                        .await
+                        .map(|millis: Option<u64>| millis.map(Duration::from_millis))
+                })
+            })
+            .await
+            .unwrap_or_else(|err| {
+                panic!(
+                    "failed to get deduplication window of {component_id} from KV storage: {err}"
+                )
+            })
+    }
+
+    async fn set_deduplication_window(&self, component_id: &ComponentId, window: Option<Duration>) {
+        record_worker_call("set_deduplication_window");
+
+        let key = Self::deduplication_window_key(component_id);
+        let result = match window {
+            Some(window) => {
+                self.key_value_storage
+                    .with_entity("worker", "set_deduplication_window", "deduplication_window")
+                    .set(
+                        KeyValueStorageNamespace::Worker,
+                        &key,
+                        &(window.as_millis() as u64),
+                    )
+                    .await
+            }
+            None => {
+                self.key_value_storage
+                    .with("worker", "set_deduplication_window")
+                    .del(KeyValueStorageNamespace::Worker, &key)
+                    .await
+            }
+        };
+        result.unwrap_or_else(|err| {
+            panic!("failed to update deduplication window of {component_id} in KV storage: {err}")
+        });
+        self.deduplication_windows.remove(component_id);
+    }
+
+    async fn get_update_divergences(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+    ) -> Vec<UpdateDivergence> {
+        record_worker_call("get_update_divergences");
+
+        self.key_value_storage
+            .with_entity("worker", "get_update_divergences", "update_divergences")
+            .get(
+                KeyValueStorageNamespace::Worker,
+                &Self::update_divergences_key(&owned_worker_id.worker_id),
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!(
+                    "failed to get update divergences for {owned_worker_id} from KV storage: {err}"
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    async fn add_update_divergence(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        divergence: UpdateDivergence,
+    ) {
+        record_worker_call("add_update_divergence");
+
+        let mut divergences = self.get_update_divergences(owned_worker_id).await;
+        divergences.push(divergence);
+        if divergences.len() > MAX_UPDATE_DIVERGENCES {
+            divergences.drain(..divergences.len() - MAX_UPDATE_DIVERGENCES);
+        }
+
+        self.key_value_storage
+            .with_entity("worker", "add_update_divergence", "update_divergences")
+            .set(
+                KeyValueStorageNamespace::Worker,
+                &Self::update_divergences_key(&owned_worker_id.worker_id),
+                &divergences,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!(
+                    "failed to store update divergences for {owned_worker_id} in KV storage: {err}"
+                )
+            });
+    }
+
     async fn update_status(
         &self,
         owned_worker_id: &OwnedWorkerId,
@@ -379,4 +902,286 @@ impl WorkerService for DefaultWorkerService {
             }
         }
     }
+
+    async fn get_dependents(&self, owned_worker_id: &OwnedWorkerId) -> Vec<WorkerDependent> {
+        record_worker_call("get_dependents");
+
+        self.key_value_storage
+            .with_entity("worker", "get_dependents", "worker_dependent")
+            .members_of_set(
+                KeyValueStorageNamespace::Worker,
+                &Self::dependents_key(&owned_worker_id.worker_id),
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to get dependents of {owned_worker_id} from KV storage: {err}")
+            })
+    }
+
+    async fn add_dependent(&self, owned_worker_id: &OwnedWorkerId, dependent: &WorkerDependent) {
+        record_worker_call("add_dependent");
+
+        self.key_value_storage
+            .with_entity("worker", "add_dependent", "worker_dependent")
+            .add_to_set(
+                KeyValueStorageNamespace::Worker,
+                &Self::dependents_key(&owned_worker_id.worker_id),
+                dependent,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to add dependent of {owned_worker_id} to KV storage: {err}")
+            });
+    }
+
+    async fn remove_dependent(&self, owned_worker_id: &OwnedWorkerId, dependent: &WorkerDependent) {
+        record_worker_call("remove_dependent");
+
+        self.key_value_storage
+            .with_entity("worker", "remove_dependent", "worker_dependent")
+            .remove_from_set(
+                KeyValueStorageNamespace::Worker,
+                &Self::dependents_key(&owned_worker_id.worker_id),
+                dependent,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                panic!("failed to remove dependent of {owned_worker_id} from KV storage: {err}")
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use golem_common::model::oplog::OplogIndex;
+    use golem_common::model::oplog_codec::OplogEncoding;
+    use golem_common::model::worker_name_query::WorkerNameQuery;
+    use golem_common::model::{
+        AccountId, ComponentId, ComponentType, OwnedWorkerId, ShardId, Timestamp, UpdateDivergence,
+        WorkerId, WorkerMetadata,
+    };
+    use uuid::Uuid;
+
+    use crate::services::oplog::PrimaryOplogService;
+    use crate::services::shard::{ShardService, ShardServiceDefault};
+    use crate::services::worker::{DefaultWorkerService, WorkerService, MAX_UPDATE_DIVERGENCES};
+    use crate::storage::blob::memory::InMemoryBlobStorage;
+    use crate::storage::indexed::memory::InMemoryIndexedStorage;
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+    use crate::storage::keyvalue::{KeyValueStorageLabelledApi, KeyValueStorageNamespace};
+
+    async fn create_worker_service(kvs: Arc<InMemoryKeyValueStorage>) -> DefaultWorkerService {
+        let shard_service = Arc::new(ShardServiceDefault::new());
+        shard_service.register(1, &HashSet::from_iter(vec![ShardId::new(0)]));
+        let oplog_service = Arc::new(
+            PrimaryOplogService::new(
+                Arc::new(InMemoryIndexedStorage::new()),
+                Arc::new(InMemoryBlobStorage::new()),
+                1,
+                4,
+                1024,
+                OplogEncoding::Bincode,
+                false,
+            )
+            .await,
+        );
+        DefaultWorkerService::new(kvs, shard_service, oplog_service)
+    }
+
+    fn account_id() -> AccountId {
+        AccountId {
+            value: "test-account".to_string(),
+        }
+    }
+
+    async fn add_workers(
+        service: &DefaultWorkerService,
+        component_id: &ComponentId,
+        names: &[&str],
+    ) {
+        for name in names {
+            let worker_id = WorkerId {
+                component_id: component_id.clone(),
+                worker_name: name.to_string(),
+            };
+            service
+                .add(
+                    &WorkerMetadata::default(worker_id, account_id()),
+                    ComponentType::Durable,
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    fn names(worker_ids: Vec<WorkerId>) -> Vec<String> {
+        worker_ids
+            .into_iter()
+            .map(|worker_id| worker_id.worker_name)
+            .collect()
+    }
+
+    #[test]
+    fn name_scores_keep_prefixes_in_range() {
+        let min = DefaultWorkerService::name_score("order-service", 0x00);
+        let max = DefaultWorkerService::name_score("order-service", 0xff);
+
+        for name in ["order-", "order-service", "order-service-1", "order-x"] {
+            let score = DefaultWorkerService::name_score(name, 0x00);
+            assert!(min <= score && score <= max, "{name} is out of range");
+        }
+        for name in ["order", "orders", "payment"] {
+            let score = DefaultWorkerService::name_score(name, 0x00);
+            assert!(score < min || score > max, "{name} is in range");
+        }
+    }
+
+    #[test]
+    async fn search_by_prefix_and_pattern() {
+        let kvs = Arc::new(InMemoryKeyValueStorage::new());
+        let service = create_worker_service(kvs).await;
+        let component_id = ComponentId(Uuid::new_v4());
+        add_workers(
+            &service,
+            &component_id,
+            &["order", "order-service-2", "order-service-1", "payment"],
+        )
+        .await;
+
+        let query = WorkerNameQuery::Prefix("order-".to_string());
+        assert_eq!(
+            names(
+                service
+                    .search(&account_id(), &component_id, &query, usize::MAX)
+                    .await
+                    .unwrap()
+            ),
+            vec!["order-service-1".to_string(), "order-service-2".to_string()]
+        );
+
+        let query = WorkerNameQuery::Pattern("pay".to_string());
+        assert_eq!(
+            names(
+                service
+                    .search(&account_id(), &component_id, &query, usize::MAX)
+                    .await
+                    .unwrap()
+            ),
+            vec!["payment".to_string()]
+        );
+    }
+
+    #[test]
+    async fn search_backfills_workers_missing_from_the_index() {
+        let kvs = Arc::new(InMemoryKeyValueStorage::new());
+        let service = create_worker_service(kvs.clone()).await;
+        let component_id = ComponentId(Uuid::new_v4());
+        add_workers(&service, &component_id, &["worker-1", "worker-2"]).await;
+
+        // As if worker-2 was created before the index existed
+        kvs.with_entity("worker", "test", "worker_name")
+            .remove_from_sorted_set(
+                KeyValueStorageNamespace::Worker,
+                &DefaultWorkerService::names_key(&component_id),
+                &"worker-2".to_string(),
+            )
+            .await
+            .unwrap();
+
+        let query = WorkerNameQuery::Prefix("worker".to_string());
+        assert_eq!(
+            names(
+                service
+                    .search(&account_id(), &component_id, &query, usize::MAX)
+                    .await
+                    .unwrap()
+            ),
+            vec!["worker-1".to_string(), "worker-2".to_string()]
+        );
+    }
+
+    #[test]
+    async fn only_the_latest_update_divergences_are_kept() {
+        let kvs = Arc::new(InMemoryKeyValueStorage::new());
+        let service = create_worker_service(kvs).await;
+        let owned_worker_id = OwnedWorkerId::new(
+            &account_id(),
+            &WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: "worker-1".to_string(),
+            },
+        );
+
+        assert!(service
+            .get_update_divergences(&owned_worker_id)
+            .await
+            .is_empty());
+
+        for target_version in 1..=(MAX_UPDATE_DIVERGENCES as u64 + 2) {
+            service
+                .add_update_divergence(
+                    &owned_worker_id,
+                    UpdateDivergence {
+                        timestamp: Timestamp::now_utc(),
+                        target_version,
+                        oplog_index: OplogIndex::from_u64(target_version + 1),
+                        expected: "golem::api::get-self-metadata".to_string(),
+                        got: "golem_environment::get_environment".to_string(),
+                    },
+                )
+                .await;
+        }
+
+        let target_versions = service
+            .get_update_divergences(&owned_worker_id)
+            .await
+            .into_iter()
+            .map(|divergence| divergence.target_version)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            target_versions,
+            (3..=(MAX_UPDATE_DIVERGENCES as u64 + 2)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    async fn metadata_has_the_latest_expiry() {
+        let kvs = Arc::new(InMemoryKeyValueStorage::new());
+        let service = create_worker_service(kvs).await;
+        let component_id = ComponentId(Uuid::new_v4());
+        add_workers(&service, &component_id, &["worker-1"]).await;
+        let owned_worker_id = OwnedWorkerId::new(
+            &account_id(),
+            &WorkerId {
+                component_id,
+                worker_name: "worker-1".to_string(),
+            },
+        );
+
+        assert_eq!(
+            service.get(&owned_worker_id).await.unwrap().expires_at,
+            None
+        );
+
+        let first = Timestamp::from(1_700_000_000_000);
+        let second = Timestamp::from(1_700_000_060_000);
+        service.set_expiry(&owned_worker_id, Some(first)).await;
+        service.set_expiry(&owned_worker_id, Some(second)).await;
+        assert_eq!(service.get_expiry(&owned_worker_id).await, Some(second));
+        assert_eq!(
+            service.get(&owned_worker_id).await.unwrap().expires_at,
+            Some(second)
+        );
+
+        service.set_expiry(&owned_worker_id, None).await;
+        assert_eq!(
+            service.get(&owned_worker_id).await.unwrap().expires_at,
+            None
+        );
+    }
 }
@@ -16,9 +16,10 @@ use std::marker::PhantomData;
 use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
-use golem_common::model::OwnedWorkerId;
+use golem_common::model::{IdempotencyKey, OwnedWorkerId};
 use tracing::{error, warn};
 
+use crate::error::GolemError;
 use crate::services::HasAll;
 use crate::worker::Worker;
 use crate::workerctx::WorkerCtx;
@@ -28,6 +29,19 @@ use crate::workerctx::WorkerCtx;
 pub trait WorkerActivator {
     /// Makes sure an already existing worker is active in a background task. Returns immediately
     async fn activate_worker(&self, owned_worker_id: &OwnedWorkerId);
+
+    /// Deletes an existing worker and all its storage, stopping it first if it is running
+    async fn delete_worker(&self, owned_worker_id: &OwnedWorkerId);
+
+    /// Enqueues an invocation for a worker, creating and starting the worker if needed. Returns
+    /// once the invocation is durably enqueued, without awaiting its result
+    async fn invoke_worker(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idempotency_key: IdempotencyKey,
+        full_function_name: String,
+        function_input: Vec<golem_wasm_rpc::Value>,
+    ) -> Result<(), GolemError>;
 }
 
 pub struct LazyWorkerActivator {
@@ -61,6 +75,39 @@ impl WorkerActivator for LazyWorkerActivator {
             None => warn!("WorkerActivator is disabled, not activating instance"),
         }
     }
+
+    async fn delete_worker(&self, owned_worker_id: &OwnedWorkerId) {
+        let maybe_worker_activator = self.worker_activator.lock().unwrap().clone();
+        match maybe_worker_activator {
+            Some(worker_activator) => worker_activator.delete_worker(owned_worker_id).await,
+            None => warn!("WorkerActivator is disabled, not deleting instance"),
+        }
+    }
+
+    async fn invoke_worker(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idempotency_key: IdempotencyKey,
+        full_function_name: String,
+        function_input: Vec<golem_wasm_rpc::Value>,
+    ) -> Result<(), GolemError> {
+        let maybe_worker_activator = self.worker_activator.lock().unwrap().clone();
+        match maybe_worker_activator {
+            Some(worker_activator) => {
+                worker_activator
+                    .invoke_worker(
+                        owned_worker_id,
+                        idempotency_key,
+                        full_function_name,
+                        function_input,
+                    )
+                    .await
+            }
+            None => Err(GolemError::runtime(
+                "WorkerActivator is disabled, not invoking instance",
+            )),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -104,10 +151,35 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + Send + Sync + 'static> WorkerActivator
             }
         }
     }
+
+    async fn delete_worker(&self, owned_worker_id: &OwnedWorkerId) {
+        if let Err(err) = Worker::delete(&self.all, owned_worker_id).await {
+            error!("Failed to delete worker: {err}")
+        }
+    }
+
+    async fn invoke_worker(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idempotency_key: IdempotencyKey,
+        full_function_name: String,
+        function_input: Vec<golem_wasm_rpc::Value>,
+    ) -> Result<(), GolemError> {
+        let worker =
+            Worker::get_or_create_running(&self.all, owned_worker_id, None, None, None, None)
+                .await?;
+        worker
+            .invoke(idempotency_key, full_function_name, function_input)
+            .await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
-pub struct WorkerActivatorMock {}
+pub struct WorkerActivatorMock {
+    invocations: Mutex<Vec<(OwnedWorkerId, IdempotencyKey)>>,
+    failing: Mutex<bool>,
+}
 
 #[cfg(test)]
 impl Default for WorkerActivatorMock {
@@ -119,7 +191,19 @@ impl Default for WorkerActivatorMock {
 #[cfg(test)]
 impl WorkerActivatorMock {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            invocations: Mutex::new(Vec::new()),
+            failing: Mutex::new(false),
+        }
+    }
+
+    pub fn invocations(&self) -> Vec<(OwnedWorkerId, IdempotencyKey)> {
+        self.invocations.lock().unwrap().clone()
+    }
+
+    /// Makes the following invocations fail without being recorded
+    pub fn set_failing(&self, failing: bool) {
+        *self.failing.lock().unwrap() = failing;
     }
 }
 
@@ -129,4 +213,26 @@ impl WorkerActivator for WorkerActivatorMock {
     async fn activate_worker(&self, _owned_worker_id: &OwnedWorkerId) {
         tracing::info!("WorkerActivatorMock::activate_worker");
     }
+
+    async fn delete_worker(&self, _owned_worker_id: &OwnedWorkerId) {
+        tracing::info!("WorkerActivatorMock::delete_worker");
+    }
+
+    async fn invoke_worker(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idempotency_key: IdempotencyKey,
+        _full_function_name: String,
+        _function_input: Vec<golem_wasm_rpc::Value>,
+    ) -> Result<(), GolemError> {
+        tracing::info!("WorkerActivatorMock::invoke_worker");
+        if *self.failing.lock().unwrap() {
+            return Err(GolemError::runtime("Failing invocation"));
+        }
+        self.invocations
+            .lock()
+            .unwrap()
+            .push((owned_worker_id.clone(), idempotency_key));
+        Ok(())
+    }
 }
@@ -0,0 +1,416 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::RwLock;
+
+use golem_common::model::{OwnedWorkerId, ShardId, Timestamp};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::services::blob_store::WorkerIfsState;
+use crate::services::{HasBlobStoreService, HasOplogService, HasWorkerService};
+
+/// The number of consistency reports kept for the HTTP interface
+const MAX_REPORTS: usize = 16;
+
+/// The reports of the most recent consistency checks, served by the HTTP interface
+static REPORTS: RwLock<VecDeque<WorkerConsistencyReport>> = RwLock::new(VecDeque::new());
+
+/// The result of checking the workers of the shards assigned to the executor, either at startup
+/// or when new shards get assigned to it
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerConsistencyReport {
+    pub checked_at: Timestamp,
+    pub shard_ids: Vec<ShardId>,
+    pub checked_workers: usize,
+    /// Workers whose inconsistency was fixed
+    pub repaired: Vec<WorkerConsistencyIssue>,
+    /// Workers whose cached status was dropped, to be recalculated from their oplog when they
+    /// are next accessed
+    pub reset: Vec<WorkerConsistencyIssue>,
+    /// Workers excluded from recovery
+    pub quarantined: Vec<WorkerConsistencyIssue>,
+}
+
+impl WorkerConsistencyReport {
+    fn is_consistent(&self) -> bool {
+        self.repaired.is_empty() && self.reset.is_empty() && self.quarantined.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerConsistencyIssue {
+    pub worker_id: String,
+    pub problem: WorkerConsistencyProblem,
+    pub details: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum WorkerConsistencyProblem {
+    /// The worker has a status record but no oplog
+    MissingOplog,
+    /// The cached status refers to oplog entries that do not exist
+    StatusAheadOfOplog,
+    /// The worker's initial file system was not extracted
+    MissingFileSystem,
+}
+
+/// Gets the reports of the most recent consistency checks, the latest first
+pub fn recent_reports() -> Vec<WorkerConsistencyReport> {
+    REPORTS.read().unwrap().iter().cloned().collect()
+}
+
+/// Cross-references the status records of the workers running in the given shards with their
+/// oplogs and initial file systems. Workers that can be fixed are repaired, the rest are quarantined
+/// so they are not recovered, instead of failing on first access. Must be called before the
+/// workers of the shards are recovered.
+pub async fn check_worker_consistency<T>(
+    this: &T,
+    shard_ids: &HashSet<ShardId>,
+) -> WorkerConsistencyReport
+where
+    T: HasWorkerService + HasOplogService + HasBlobStoreService,
+{
+    info!(
+        shards = shard_ids.len(),
+        "Checking the consistency of running workers"
+    );
+
+    let worker_ids = this
+        .worker_service()
+        .get_running_worker_ids_in_shards(shard_ids)
+        .await;
+    let mut sorted_shard_ids = shard_ids.iter().copied().collect::<Vec<_>>();
+    sorted_shard_ids.sort();
+    let mut report = WorkerConsistencyReport {
+        checked_at: Timestamp::now_utc(),
+        shard_ids: sorted_shard_ids,
+        checked_workers: worker_ids.len(),
+        repaired: Vec::new(),
+        reset: Vec::new(),
+        quarantined: Vec::new(),
+    };
+
+    for owned_worker_id in worker_ids {
+        check_worker(this, &owned_worker_id, &mut report).await;
+    }
+
+    if report.is_consistent() {
+        info!(
+            checked_workers = report.checked_workers,
+            "All running workers are consistent"
+        );
+    } else {
+        warn!(
+            checked_workers = report.checked_workers,
+            repaired = report.repaired.len(),
+            reset = report.reset.len(),
+            quarantined = report.quarantined.len(),
+            "Found inconsistent workers"
+        );
+    }
+
+    let mut reports = REPORTS.write().unwrap();
+    reports.push_front(report.clone());
+    reports.truncate(MAX_REPORTS);
+    report
+}
+
+async fn check_worker<T>(
+    this: &T,
+    owned_worker_id: &OwnedWorkerId,
+    report: &mut WorkerConsistencyReport,
+) where
+    T: HasWorkerService + HasOplogService + HasBlobStoreService,
+{
+    let issue = |problem, details: String| WorkerConsistencyIssue {
+        worker_id: owned_worker_id.to_string(),
+        problem,
+        details,
+    };
+
+    if !this.oplog_service().exists(owned_worker_id).await {
+        warn!(
+            worker_id = owned_worker_id.to_string(),
+            "Quarantining worker with no oplog"
+        );
+        this.worker_service().quarantine(owned_worker_id).await;
+        report.quarantined.push(issue(
+            WorkerConsistencyProblem::MissingOplog,
+            "The worker is marked as running but it has no oplog".to_string(),
+        ));
+        return;
+    }
+
+    let Some(metadata) = this.worker_service().get(owned_worker_id).await else {
+        return;
+    };
+
+    let last_index = this.oplog_service().get_last_index(owned_worker_id).await;
+    if metadata.last_known_status.oplog_idx > last_index {
+        warn!(
+            worker_id = owned_worker_id.to_string(),
+            "Dropping cached worker status pointing beyond the end of the oplog"
+        );
+        this.worker_service()
+            .remove_cached_status(owned_worker_id)
+            .await;
+        report.reset.push(issue(
+            WorkerConsistencyProblem::StatusAheadOfOplog,
+            format!(
+                "The cached status refers to oplog index {} but the oplog ends at {}, it is recalculated from the oplog",
+                metadata.last_known_status.oplog_idx, last_index
+            ),
+        ));
+    }
+
+    match this.blob_store_service().worker_ifs_state(&metadata).await {
+        Ok(WorkerIfsState::NotUsed) | Ok(WorkerIfsState::Present) => {}
+        Ok(WorkerIfsState::Missing) => {
+            let extracted = match this
+                .blob_store_service()
+                .initialize_worker_ifs(metadata.clone())
+                .await
+            {
+                // Only reported as repaired if the file system is really in place afterwards
+                Ok(()) => match this.blob_store_service().worker_ifs_state(&metadata).await {
+                    Ok(WorkerIfsState::Present) => Ok(()),
+                    Ok(_) => {
+                        Err("the file system is still missing after extracting it".to_string())
+                    }
+                    Err(err) => Err(err),
+                },
+                Err(err) => Err(err),
+            };
+            match extracted {
+                Ok(()) => report.repaired.push(issue(
+                    WorkerConsistencyProblem::MissingFileSystem,
+                    "The initial file system was extracted again".to_string(),
+                )),
+                Err(err) => {
+                    warn!(
+                        worker_id = owned_worker_id.to_string(),
+                        "Quarantining worker with missing initial file system: {err}"
+                    );
+                    this.worker_service().quarantine(owned_worker_id).await;
+                    report.quarantined.push(issue(
+                        WorkerConsistencyProblem::MissingFileSystem,
+                        format!("Failed to extract the initial file system: {err}"),
+                    ));
+                }
+            }
+        }
+        Err(err) => {
+            warn!(
+                worker_id = owned_worker_id.to_string(),
+                "Failed to check the initial file system of the worker: {err}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    use golem_common::model::oplog::{OplogEntry, OplogIndex};
+    use golem_common::model::oplog_codec::OplogEncoding;
+    use golem_common::model::{
+        AccountId, ComponentId, ComponentType, OwnedWorkerId, ShardId, WorkerId, WorkerMetadata,
+        WorkerStatus,
+    };
+    use uuid::Uuid;
+
+    use crate::services::blob_store::{BlobStoreService, DefaultBlobStoreService};
+    use crate::services::oplog::{OplogService, PrimaryOplogService};
+    use crate::services::shard::{ShardService, ShardServiceDefault};
+    use crate::services::worker::{DefaultWorkerService, WorkerService};
+    use crate::services::worker_consistency::{check_worker_consistency, WorkerConsistencyProblem};
+    use crate::services::{HasBlobStoreService, HasOplogService, HasWorkerService};
+    use crate::storage::blob::memory::InMemoryBlobStorage;
+    use crate::storage::indexed::memory::InMemoryIndexedStorage;
+    use crate::storage::keyvalue::memory::InMemoryKeyValueStorage;
+
+    struct TestServices {
+        worker_service: Arc<DefaultWorkerService>,
+        oplog_service: Arc<PrimaryOplogService>,
+        blob_store_service: Arc<DefaultBlobStoreService>,
+    }
+
+    impl HasWorkerService for TestServices {
+        fn worker_service(&self) -> Arc<dyn WorkerService + Send + Sync> {
+            self.worker_service.clone()
+        }
+    }
+
+    impl HasOplogService for TestServices {
+        fn oplog_service(&self) -> Arc<dyn OplogService + Send + Sync> {
+            self.oplog_service.clone()
+        }
+    }
+
+    impl HasBlobStoreService for TestServices {
+        fn blob_store_service(&self) -> Arc<dyn BlobStoreService + Send + Sync> {
+            self.blob_store_service.clone()
+        }
+    }
+
+    async fn services() -> TestServices {
+        let shard_service = Arc::new(ShardServiceDefault::new());
+        shard_service.register(1, &shards());
+        let blob_storage = Arc::new(InMemoryBlobStorage::new());
+        let oplog_service = Arc::new(
+            PrimaryOplogService::new(
+                Arc::new(InMemoryIndexedStorage::new()),
+                blob_storage.clone(),
+                1,
+                4,
+                1024,
+                OplogEncoding::Bincode,
+                false,
+            )
+            .await,
+        );
+        TestServices {
+            worker_service: Arc::new(DefaultWorkerService::new(
+                Arc::new(InMemoryKeyValueStorage::new()),
+                shard_service,
+                oplog_service.clone(),
+            )),
+            oplog_service,
+            blob_store_service: Arc::new(DefaultBlobStoreService::new(blob_storage)),
+        }
+    }
+
+    fn shards() -> HashSet<ShardId> {
+        HashSet::from_iter(vec![ShardId::new(0)])
+    }
+
+    fn owned_worker_id(name: &str) -> OwnedWorkerId {
+        OwnedWorkerId::new(
+            &AccountId {
+                value: "test-account".to_string(),
+            },
+            &WorkerId {
+                component_id: ComponentId(Uuid::new_v4()),
+                worker_name: name.to_string(),
+            },
+        )
+    }
+
+    /// Adds a worker marked as running, with its cached status pointing to `oplog_idx`, and
+    /// creates its oplog if `with_oplog` is set
+    async fn add_running_worker(
+        services: &TestServices,
+        owned_worker_id: &OwnedWorkerId,
+        oplog_idx: u64,
+        with_oplog: bool,
+    ) {
+        let mut metadata =
+            WorkerMetadata::default(owned_worker_id.worker_id(), owned_worker_id.account_id());
+        metadata.last_known_status.status = WorkerStatus::Running;
+        metadata.last_known_status.oplog_idx = OplogIndex::from_u64(oplog_idx);
+        services
+            .worker_service
+            .add(&metadata, ComponentType::Durable)
+            .await
+            .unwrap();
+
+        if with_oplog {
+            services
+                .oplog_service
+                .create(
+                    owned_worker_id,
+                    OplogEntry::create(
+                        owned_worker_id.worker_id(),
+                        0,
+                        vec![],
+                        vec![],
+                        owned_worker_id.account_id(),
+                        None,
+                        0,
+                        0,
+                    ),
+                    ComponentType::Durable,
+                )
+                .await;
+        }
+    }
+
+    #[test]
+    async fn consistent_workers_are_not_reported() {
+        let services = services().await;
+        add_running_worker(&services, &owned_worker_id("consistent"), 1, true).await;
+
+        let report = check_worker_consistency(&services, &shards()).await;
+
+        assert_eq!(report.checked_workers, 1);
+        assert!(report.repaired.is_empty());
+        assert!(report.reset.is_empty());
+        assert!(report.quarantined.is_empty());
+    }
+
+    #[test]
+    async fn workers_without_oplog_are_quarantined() {
+        let services = services().await;
+        let owned_worker_id = owned_worker_id("no-oplog");
+        add_running_worker(&services, &owned_worker_id, 1, false).await;
+
+        let report = check_worker_consistency(&services, &shards()).await;
+
+        assert_eq!(report.quarantined.len(), 1);
+        assert_eq!(
+            report.quarantined[0].problem,
+            WorkerConsistencyProblem::MissingOplog
+        );
+        assert!(services
+            .worker_service
+            .get_running_worker_ids_in_shards(&shards())
+            .await
+            .is_empty());
+    }
+
+    #[test]
+    async fn statuses_ahead_of_the_oplog_are_reset_not_repaired() {
+        let services = services().await;
+        let owned_worker_id = owned_worker_id("ahead");
+        add_running_worker(&services, &owned_worker_id, 10, true).await;
+
+        let report = check_worker_consistency(&services, &shards()).await;
+
+        assert!(report.repaired.is_empty());
+        assert_eq!(report.reset.len(), 1);
+        assert_eq!(
+            report.reset[0].problem,
+            WorkerConsistencyProblem::StatusAheadOfOplog
+        );
+        let metadata = services.worker_service.get(&owned_worker_id).await.unwrap();
+        assert!(metadata.last_known_status.oplog_idx <= OplogIndex::from_u64(1));
+    }
+
+    #[test]
+    async fn only_the_given_shards_are_checked() {
+        let services = services().await;
+        add_running_worker(&services, &owned_worker_id("unchecked"), 1, false).await;
+
+        let report = check_worker_consistency(&services, &HashSet::new()).await;
+
+        assert_eq!(report.checked_workers, 0);
+        assert!(report.quarantined.is_empty());
+    }
+}
@@ -12,19 +12,75 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::metrics::events::{record_broadcast_event, record_event};
-use futures_util::{stream, StreamExt};
-use golem_common::model::{IdempotencyKey, LogLevel, WorkerEvent};
+use crate::metrics::events::{
+    record_broadcast_event, record_event, record_event_stream_closed,
+    record_event_stream_dropped_events,
+};
+use crate::services::golem_config::SlowConsumerPolicy;
+use futures_util::{future, stream, StreamExt};
+use golem_common::model::{IdempotencyKey, LogLevel, OwnedWorkerId, Timestamp, WorkerEvent};
 use ringbuf::storage::Heap;
 use ringbuf::traits::{Consumer, Producer, Split};
 use ringbuf::*;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use tokio::sync::broadcast::error::RecvError;
+use std::{fs, io};
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
 use tokio::sync::broadcast::*;
+use tokio::sync::{mpsc, oneshot};
 
 use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::Stream;
+use tracing::{debug, warn};
+
+/// The timestamp of the events which are recorded in the oplog as log entries
+fn log_event_timestamp(event: &WorkerEvent) -> Option<Timestamp> {
+    match event {
+        WorkerEvent::StdOut { timestamp, .. }
+        | WorkerEvent::StdErr { timestamp, .. }
+        | WorkerEvent::Log { timestamp, .. } => Some(*timestamp),
+        _ => None,
+    }
+}
+
+/// The last replayed log events, all having the timestamp of the last one
+#[derive(Default)]
+struct ReplayedTail {
+    timestamp: Option<Timestamp>,
+    events: Vec<WorkerEvent>,
+}
+
+impl ReplayedTail {
+    fn push(&mut self, event: &WorkerEvent) {
+        let timestamp = log_event_timestamp(event);
+        if timestamp != self.timestamp {
+            self.timestamp = timestamp;
+            self.events.clear();
+        }
+        self.events.push(event.clone());
+    }
+
+    /// Whether a live event was already replayed. Each replayed event matches only once, as the
+    /// same log line can be emitted multiple times with the same timestamp.
+    fn take(&mut self, event: &WorkerEvent) -> bool {
+        match (log_event_timestamp(event), self.timestamp) {
+            (Some(timestamp), Some(last_replayed)) if timestamp < last_replayed => true,
+            (Some(timestamp), Some(last_replayed)) if timestamp == last_replayed => {
+                match self.events.iter().position(|replayed| replayed == event) {
+                    Some(idx) => {
+                        self.events.swap_remove(idx);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
 
 /// Per-worker event stream
 pub trait WorkerEventService {
@@ -104,6 +160,94 @@ impl WorkerEventReceiver {
 
     pub fn to_stream(self) -> impl Stream<Item = Result<WorkerEvent, BroadcastStreamRecvError>> {
         let Self { history, receiver } = self;
+        Self::live_history(history).chain(BroadcastStream::new(receiver))
+    }
+
+    /// Converts the receiver to a stream which detects consumers not keeping up with the worker.
+    ///
+    /// A consumer is too slow when more than `max_buffered_events` events are waiting for it,
+    /// or it lags behind the broadcast channel. Depending on `policy` the waiting events are
+    /// then either skipped, or the stream ends with a `Lagged` error.
+    pub fn to_limited_stream(
+        self,
+        max_buffered_events: usize,
+        policy: SlowConsumerPolicy,
+    ) -> impl Stream<Item = Result<WorkerEvent, BroadcastStreamRecvError>> {
+        let Self { history, receiver } = self;
+        let live = stream::unfold(Some(receiver), move |receiver| async move {
+            let mut receiver = receiver?;
+            loop {
+                let buffered = receiver.len();
+                let result = if buffered > max_buffered_events {
+                    Err(RecvError::Lagged(0))
+                } else {
+                    receiver.recv().await
+                };
+                match result {
+                    Ok(event) => break Some((Ok(event), Some(receiver))),
+                    Err(RecvError::Closed) => break None,
+                    Err(RecvError::Lagged(lagged)) => match policy {
+                        SlowConsumerPolicy::Drop => {
+                            let (skipped, close) = Self::skip_buffered(&mut receiver);
+                            debug!("Skipped {} events of a slow consumer", lagged + skipped);
+                            record_event_stream_dropped_events(lagged + skipped);
+                            if let Some(close) = close {
+                                break Some((Ok(close), Some(receiver)));
+                            }
+                        }
+                        SlowConsumerPolicy::Disconnect => {
+                            record_event_stream_closed("slow_consumer");
+                            let missed = lagged.max(buffered as u64);
+                            break Some((Err(BroadcastStreamRecvError::Lagged(missed)), None));
+                        }
+                    },
+                }
+            }
+        });
+        Self::live_history(history).chain(live)
+    }
+
+    /// Converts the receiver to a limited stream like [`Self::to_limited_stream`], which starts
+    /// with the `replayed` log events recreated from the oplog instead of the recent events.
+    ///
+    /// The receiver has to be subscribed before reading the replayed events, so the events emitted
+    /// meanwhile are received twice. As the worker emits its events in order, these are the live
+    /// log events not newer than the last replayed one, and they are skipped.
+    ///
+    /// The replayed events are streamed as they are read, only the newest ones are kept to find
+    /// the live events that were already replayed.
+    pub fn to_limited_stream_after_replay(
+        self,
+        replayed: impl Stream<Item = WorkerEvent> + Send + 'static,
+        max_buffered_events: usize,
+        policy: SlowConsumerPolicy,
+    ) -> impl Stream<Item = Result<WorkerEvent, BroadcastStreamRecvError>> {
+        let tail = Arc::new(Mutex::new(ReplayedTail::default()));
+
+        let receiver = Self {
+            history: Vec::new(),
+            receiver: self.receiver,
+        };
+        let replayed_tail = tail.clone();
+        let live = receiver
+            .to_limited_stream(max_buffered_events, policy)
+            .filter(move |item| {
+                // Only polled after the last replayed event, when the tail is complete
+                let already_replayed = match item {
+                    Ok(event) => tail.lock().unwrap().take(event),
+                    Err(_) => false,
+                };
+                future::ready(!already_replayed)
+            });
+        replayed
+            .inspect(move |event| replayed_tail.lock().unwrap().push(event))
+            .map(Ok)
+            .chain(live)
+    }
+
+    fn live_history(
+        history: Vec<WorkerEventEntry>,
+    ) -> impl Stream<Item = Result<WorkerEvent, BroadcastStreamRecvError>> {
         stream::iter(history.into_iter().filter_map(
             |WorkerEventEntry { event, is_live }| {
                 if is_live {
@@ -113,7 +257,20 @@ impl WorkerEventReceiver {
                 }
             },
         ))
-        .chain(BroadcastStream::new(receiver))
+    }
+
+    /// Skips the events waiting in the receiver, returning their number. The close event is
+    /// never skipped, so the consumer still gets notified when the worker's event stream ends.
+    fn skip_buffered(receiver: &mut Receiver<WorkerEvent>) -> (u64, Option<WorkerEvent>) {
+        let mut skipped = 0;
+        loop {
+            match receiver.try_recv() {
+                Ok(WorkerEvent::Close) => break (skipped, Some(WorkerEvent::Close)),
+                Ok(_) => skipped += 1,
+                Err(TryRecvError::Lagged(lagged)) => skipped += lagged,
+                Err(_) => break (skipped, None),
+            }
+        }
     }
 }
 
@@ -121,6 +278,7 @@ pub struct WorkerEventServiceDefault {
     sender: Sender<WorkerEvent>,
     ring_prod: Arc<Mutex<<SharedRb<Heap<WorkerEventEntry>> as Split>::Prod>>,
     ring_cons: Arc<Mutex<<SharedRb<Heap<WorkerEventEntry>> as Split>::Cons>>,
+    persistent_history: Option<mpsc::UnboundedSender<HistoryCommand>>,
 }
 
 impl WorkerEventServiceDefault {
@@ -131,6 +289,51 @@ impl WorkerEventServiceDefault {
             sender: tx,
             ring_prod: Arc::new(Mutex::new(ring_prod)),
             ring_cons: Arc::new(Mutex::new(ring_cons)),
+            persistent_history: None,
+        }
+    }
+
+    /// Also keeps the live events in the given on-disk history, and starts with the events
+    /// persisted there, so they are available to the clients after the executor restarts.
+    ///
+    /// The file IO runs on the blocking thread pool, the events are written by a background task
+    /// which stops when the service is dropped.
+    pub async fn with_persistent_history(mut self, history: PersistentEventHistory) -> Self {
+        let (history, events) = tokio::task::spawn_blocking(move || {
+            let events = history.load();
+            (history, events)
+        })
+        .await
+        .expect("Loading the persisted worker events panicked");
+
+        for event in events {
+            self.push_to_ring(WorkerEventEntry {
+                event,
+                is_live: true,
+            });
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(write_persistent_history(history, rx));
+        self.persistent_history = Some(tx);
+        self
+    }
+
+    /// Waits until the events emitted so far are written to the on-disk history
+    pub async fn flush_persistent_history(&self) {
+        if let Some(persistent_history) = &self.persistent_history {
+            let (tx, rx) = oneshot::channel();
+            if persistent_history.send(HistoryCommand::Flush(tx)).is_ok() {
+                let _ = rx.await;
+            }
+        }
+    }
+
+    fn push_to_ring(&self, entry: WorkerEventEntry) {
+        let mut ring_prod = self.ring_prod.lock().unwrap();
+        while ring_prod.try_push(entry.clone()).is_err() {
+            let mut ring_cons = self.ring_cons.lock().unwrap();
+            let _ = ring_cons.try_pop();
         }
     }
 }
@@ -153,12 +356,15 @@ impl WorkerEventService for WorkerEventServiceDefault {
             }
         }
 
-        let entry = WorkerEventEntry { event, is_live };
-        let mut ring_prod = self.ring_prod.lock().unwrap();
-        while ring_prod.try_push(entry.clone()).is_err() {
-            let mut ring_cons = self.ring_cons.lock().unwrap();
-            let _ = ring_cons.try_pop();
+        // Events emitted while replaying are emitted again on the next replay, and the close
+        // event must not end the streams of the clients connecting after a restart
+        if is_live && event != WorkerEvent::Close {
+            if let Some(persistent_history) = &self.persistent_history {
+                let _ = persistent_history.send(HistoryCommand::Append(event.clone()));
+            }
         }
+
+        self.push_to_ring(WorkerEventEntry { event, is_live });
     }
 
     fn receiver(&self) -> WorkerEventReceiver {
@@ -186,6 +392,138 @@ impl WorkerEventService for WorkerEventServiceDefault {
     }
 }
 
+enum HistoryCommand {
+    Append(WorkerEvent),
+    Flush(oneshot::Sender<()>),
+}
+
+async fn write_persistent_history(
+    mut history: PersistentEventHistory,
+    mut rx: mpsc::UnboundedReceiver<HistoryCommand>,
+) {
+    let mut commands = Vec::new();
+    while rx.recv_many(&mut commands, 256).await > 0 {
+        let batch = std::mem::take(&mut commands);
+        history = match tokio::task::spawn_blocking(move || {
+            let mut flushed = Vec::new();
+            for command in batch {
+                match command {
+                    HistoryCommand::Append(event) => {
+                        if let Err(err) = history.append(&event) {
+                            warn!(
+                                "Failed to persist worker event to {:?}: {err}",
+                                history.current_path
+                            );
+                        }
+                    }
+                    HistoryCommand::Flush(done) => flushed.push(done),
+                }
+            }
+            for done in flushed {
+                let _ = done.send(());
+            }
+            history
+        })
+        .await
+        {
+            Ok(history) => history,
+            Err(err) => {
+                warn!("Persisting worker events stopped: {err}");
+                break;
+            }
+        };
+    }
+}
+
+/// Size-bounded on-disk history of the live events of a worker.
+///
+/// The events are appended to a segment file as JSON lines. When the segment reaches half of the
+/// size limit it replaces the previous segment, so the two together never exceed the limit by
+/// more than a single event.
+pub struct PersistentEventHistory {
+    current_path: PathBuf,
+    previous_path: PathBuf,
+    max_segment_size: u64,
+    current: Option<File>,
+    current_size: u64,
+}
+
+impl PersistentEventHistory {
+    pub fn new(root: &Path, owned_worker_id: &OwnedWorkerId, max_size: u64) -> Self {
+        let dir = Self::dir(root, owned_worker_id);
+        Self {
+            current_path: dir.join("current.jsonl"),
+            previous_path: dir.join("previous.jsonl"),
+            max_segment_size: (max_size / 2).max(1),
+            current: None,
+            current_size: 0,
+        }
+    }
+
+    /// Removes the persisted events of a deleted worker
+    pub async fn delete(root: &Path, owned_worker_id: &OwnedWorkerId) -> io::Result<()> {
+        match tokio::fs::remove_dir_all(Self::dir(root, owned_worker_id)).await {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    fn dir(root: &Path, owned_worker_id: &OwnedWorkerId) -> PathBuf {
+        root.join(owned_worker_id.account_id.to_string())
+            .join(owned_worker_id.worker_id.to_string())
+    }
+
+    /// Reads the persisted events, oldest first
+    fn load(&self) -> Vec<WorkerEvent> {
+        let mut events = Vec::new();
+        for path in [&self.previous_path, &self.current_path] {
+            if let Ok(file) = File::open(path) {
+                // A line which could not be parsed was being written when the executor stopped
+                events.extend(
+                    BufReader::new(file)
+                        .lines()
+                        .map_while(Result::ok)
+                        .filter_map(|line| serde_json::from_str(&line).ok()),
+                );
+            }
+        }
+        events
+    }
+
+    fn append(&mut self, event: &WorkerEvent) -> io::Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+        let line_size = line.len() as u64;
+
+        if self.current.is_none() {
+            self.open()?;
+        }
+        if self.current_size > 0 && self.current_size + line_size > self.max_segment_size {
+            self.current = None;
+            fs::rename(&self.current_path, &self.previous_path)?;
+            self.open()?;
+        }
+        if let Some(current) = &mut self.current {
+            current.write_all(&line)?;
+            self.current_size += line_size;
+        }
+        Ok(())
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        if let Some(dir) = self.current_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.current_path)?;
+        self.current_size = file.metadata()?.len();
+        self.current = Some(file);
+        Ok(())
+    }
+}
+
 fn label(event: &WorkerEvent) -> &'static str {
     match event {
         WorkerEvent::StdOut { .. } => "stdout",
@@ -201,14 +539,29 @@ fn label(event: &WorkerEvent) -> &'static str {
 mod tests {
     use test_r::{non_flaky, test};
 
+    use futures_util::{stream, StreamExt};
     use std::sync::Arc;
     use tokio::sync::broadcast::error::RecvError;
     use tokio::sync::Mutex;
+    use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 
+    use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, WorkerId};
+
+    use crate::services::golem_config::SlowConsumerPolicy;
     use crate::services::worker_event::{
-        WorkerEvent, WorkerEventService, WorkerEventServiceDefault,
+        PersistentEventHistory, WorkerEvent, WorkerEventService, WorkerEventServiceDefault,
     };
 
+    fn owned_worker_id() -> OwnedWorkerId {
+        OwnedWorkerId::new(
+            &AccountId::generate(),
+            &WorkerId {
+                component_id: ComponentId::new_v4(),
+                worker_name: "test-worker".to_string(),
+            },
+        )
+    }
+
     #[test]
     #[non_flaky(10)]
     pub async fn both_subscriber_gets_events_small() {
@@ -364,4 +717,191 @@ mod tests {
                 ]
         )
     }
+
+    #[test]
+    pub async fn slow_consumer_events_are_dropped() {
+        let svc = WorkerEventServiceDefault::new(16, 1);
+        let rx = svc.receiver();
+        for b in 1..9u8 {
+            svc.emit_event(WorkerEvent::stdout(vec![b]), true);
+        }
+        drop(svc);
+
+        let events: Vec<_> = rx
+            .to_limited_stream(4, SlowConsumerPolicy::Drop)
+            .collect()
+            .await;
+
+        assert_eq!(events, vec![Ok(WorkerEvent::Close)]);
+    }
+
+    #[test]
+    pub async fn slow_consumer_gets_disconnected() {
+        let svc = WorkerEventServiceDefault::new(16, 1);
+        let rx = svc.receiver();
+        for b in 1..4u8 {
+            svc.emit_event(WorkerEvent::stdout(vec![b]), true);
+        }
+
+        let mut stream = Box::pin(rx.to_limited_stream(4, SlowConsumerPolicy::Disconnect));
+        assert_eq!(stream.next().await, Some(Ok(WorkerEvent::stdout(vec![1]))));
+
+        for b in 4..9u8 {
+            svc.emit_event(WorkerEvent::stdout(vec![b]), true);
+        }
+
+        assert_eq!(
+            stream.next().await,
+            Some(Err(BroadcastStreamRecvError::Lagged(7)))
+        );
+        assert_eq!(stream.next().await, None);
+    }
+
+    #[test]
+    pub async fn replayed_events_are_not_repeated_live() {
+        let svc = WorkerEventServiceDefault::new(16, 16);
+        svc.emit_event(WorkerEvent::stdout(vec![0]), true);
+        let rx = svc.receiver();
+
+        // Emitted after subscribing but before the oplog got read, so both replayed and live
+        let first = WorkerEvent::stdout(vec![1]);
+        let second = WorkerEvent::stdout(vec![2]);
+        svc.emit_event(first.clone(), true);
+        svc.emit_event(second.clone(), true);
+        let third = WorkerEvent::stdout(vec![3]);
+        svc.emit_event(third.clone(), true);
+        drop(svc);
+
+        let events: Vec<_> = rx
+            .to_limited_stream_after_replay(
+                stream::iter(vec![first.clone(), second.clone()]),
+                16,
+                SlowConsumerPolicy::Disconnect,
+            )
+            .collect()
+            .await;
+
+        assert_eq!(
+            events,
+            vec![Ok(first), Ok(second), Ok(third), Ok(WorkerEvent::Close)]
+        );
+    }
+
+    #[test]
+    pub async fn replayed_events_are_streamed_before_the_replay_ends() {
+        let svc = WorkerEventServiceDefault::new(16, 16);
+        let rx = svc.receiver();
+        let first = WorkerEvent::stdout(vec![1]);
+
+        let mut events = Box::pin(rx.to_limited_stream_after_replay(
+            stream::iter(vec![first.clone()]).chain(stream::pending()),
+            16,
+            SlowConsumerPolicy::Disconnect,
+        ));
+
+        assert_eq!(events.next().await, Some(Ok(first)));
+    }
+
+    #[test]
+    pub async fn persisted_events_are_kept_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let owned_worker_id = owned_worker_id();
+
+        let svc = WorkerEventServiceDefault::new(16, 16)
+            .with_persistent_history(PersistentEventHistory::new(
+                dir.path(),
+                &owned_worker_id,
+                1024 * 1024,
+            ))
+            .await;
+        for b in 1..4u8 {
+            svc.emit_event(WorkerEvent::stdout(vec![b]), true);
+        }
+        svc.emit_event(WorkerEvent::stdout(vec![4]), false);
+        svc.flush_persistent_history().await;
+        drop(svc);
+
+        let svc = WorkerEventServiceDefault::new(16, 16)
+            .with_persistent_history(PersistentEventHistory::new(
+                dir.path(),
+                &owned_worker_id,
+                1024 * 1024,
+            ))
+            .await;
+        let rx = svc.receiver();
+        drop(svc);
+
+        let events: Vec<_> = rx.to_stream().collect().await;
+
+        assert_eq!(
+            events,
+            vec![
+                Ok(WorkerEvent::stdout(vec![1])),
+                Ok(WorkerEvent::stdout(vec![2])),
+                Ok(WorkerEvent::stdout(vec![3])),
+                Ok(WorkerEvent::Close),
+            ]
+        );
+    }
+
+    #[test]
+    pub async fn persisted_events_are_size_bounded() {
+        let dir = tempfile::tempdir().unwrap();
+        let owned_worker_id = owned_worker_id();
+        let max_size = 1024;
+
+        let mut history = PersistentEventHistory::new(dir.path(), &owned_worker_id, max_size);
+        for i in 0..100 {
+            history
+                .append(&WorkerEvent::stdout(format!("line {i}").into_bytes()))
+                .unwrap();
+        }
+        let events = history.load();
+        let size = std::fs::metadata(&history.current_path).unwrap().len()
+            + std::fs::metadata(&history.previous_path).unwrap().len();
+
+        assert!(size <= max_size);
+        assert!(events.len() < 100);
+        assert_eq!(
+            events.last(),
+            Some(&WorkerEvent::stdout("line 99".as_bytes().into()))
+        );
+    }
+
+    #[test]
+    pub async fn persisted_events_are_deleted_with_the_worker() {
+        let dir = tempfile::tempdir().unwrap();
+        let other_worker_id = owned_worker_id();
+        let owned_worker_id = owned_worker_id();
+
+        for worker_id in [&owned_worker_id, &other_worker_id] {
+            let svc = WorkerEventServiceDefault::new(16, 16)
+                .with_persistent_history(PersistentEventHistory::new(
+                    dir.path(),
+                    worker_id,
+                    1024 * 1024,
+                ))
+                .await;
+            svc.emit_event(WorkerEvent::stdout(vec![1]), true);
+            svc.flush_persistent_history().await;
+        }
+
+        PersistentEventHistory::delete(dir.path(), &owned_worker_id)
+            .await
+            .unwrap();
+        // Deleting the history of a worker which has none is not an error
+        PersistentEventHistory::delete(dir.path(), &owned_worker_id)
+            .await
+            .unwrap();
+
+        assert!(
+            PersistentEventHistory::new(dir.path(), &owned_worker_id, 1024 * 1024)
+                .load()
+                .is_empty()
+        );
+        assert_eq!(
+            PersistentEventHistory::new(dir.path(), &other_worker_id, 1024 * 1024).load(),
+            vec![WorkerEvent::stdout(vec![1])]
+        );
+    }
 }
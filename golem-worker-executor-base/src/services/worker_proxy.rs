@@ -18,22 +18,29 @@ use async_trait::async_trait;
 use bincode::{Decode, Encode};
 use golem_api_grpc::proto::golem::worker::v1::worker_service_client::WorkerServiceClient;
 use golem_api_grpc::proto::golem::worker::v1::{
-    invoke_and_await_typed_response, invoke_response, update_worker_response, worker_error,
-    InvokeAndAwaitRequest, InvokeAndAwaitTypedResponse, InvokeRequest, InvokeResponse,
+    delete_worker_response, get_component_maintenance_response, invoke_and_await_typed_response,
+    invoke_response, launch_new_worker_response, update_worker_response, worker_error,
+    DeleteWorkerRequest, DeleteWorkerResponse, GetComponentMaintenanceRequest,
+    GetComponentMaintenanceResponse, InvokeAndAwaitRequest, InvokeAndAwaitTypedResponse,
+    InvokeRequest, InvokeResponse, LaunchNewWorkerRequest, LaunchNewWorkerResponse,
     UpdateWorkerRequest, UpdateWorkerResponse, WorkerError,
 };
 use golem_api_grpc::proto::golem::worker::{InvocationContext, InvokeParameters, UpdateMode};
 use golem_common::client::GrpcClient;
-use golem_common::model::{ComponentVersion, IdempotencyKey, OwnedWorkerId, WorkerId};
+use golem_common::config::{GrpcCompression, RetryConfig};
+use golem_common::model::{ComponentId, ComponentVersion, IdempotencyKey, OwnedWorkerId, WorkerId};
+use golem_common::retries::RetryState;
+use golem_common::with_grpc_compression;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use golem_wasm_rpc::{Value, WitValue};
 use http::Uri;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
-use tonic::codec::CompressionEncoding;
+use std::future::Future;
+use std::sync::Arc;
 use tonic::transport::Channel;
-use tracing::debug;
+use tracing::{debug, warn};
 use uuid::Uuid;
 
 #[async_trait]
@@ -66,6 +73,27 @@ pub trait WorkerProxy {
         target_version: ComponentVersion,
         mode: UpdateMode,
     ) -> Result<(), WorkerProxyError>;
+
+    /// Creates a new worker with the latest version of its component
+    async fn create(
+        &self,
+        worker_id: &WorkerId,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+    ) -> Result<(), WorkerProxyError>;
+
+    /// Deletes a worker, and with `cascade` the resources it owns as well. Returns the removed
+    /// resources, or the ones which would be removed if `dry_run` is set.
+    async fn delete(
+        &self,
+        worker_id: &WorkerId,
+        cascade: bool,
+        dry_run: bool,
+    ) -> Result<Vec<String>, WorkerProxyError>;
+
+    /// Checks whether the component is in maintenance, in which case its workers must not be
+    /// invoked until the maintenance ends
+    async fn in_maintenance(&self, component_id: &ComponentId) -> Result<bool, WorkerProxyError>;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
@@ -76,6 +104,15 @@ pub enum WorkerProxyError {
     NotFound(String),
     AlreadyExists(String),
     InternalError(GolemError),
+    /// The request was rejected temporarily, for example because the target component is in
+    /// maintenance, and can be retried later
+    Unavailable(String),
+}
+
+impl WorkerProxyError {
+    pub fn is_retriable(&self) -> bool {
+        matches!(self, WorkerProxyError::Unavailable(_))
+    }
 }
 
 impl Error for WorkerProxyError {}
@@ -89,6 +126,7 @@ impl Display for WorkerProxyError {
             WorkerProxyError::NotFound(error) => write!(f, "Not found: {error}"),
             WorkerProxyError::AlreadyExists(error) => write!(f, "Already exists: {error}"),
             WorkerProxyError::InternalError(error) => write!(f, "Internal error: {error}"),
+            WorkerProxyError::Unavailable(error) => write!(f, "Unavailable: {error}"),
         }
     }
 }
@@ -129,6 +167,9 @@ impl From<WorkerError> for WorkerProxyError {
                     GolemError::unknown("Unknown error from the worker executor".to_string()),
                 ))
             }
+            Some(worker_error::Error::Unavailable(body)) => {
+                WorkerProxyError::Unavailable(body.error)
+            }
             None => WorkerProxyError::InternalError(GolemError::unknown(
                 "Empty error response from the worker API".to_string(),
             )),
@@ -148,13 +189,11 @@ pub struct RemoteWorkerProxy {
 }
 
 impl RemoteWorkerProxy {
-    pub fn new(endpoint: Uri, access_token: Uuid) -> Self {
+    pub fn new(endpoint: Uri, access_token: Uuid, grpc_compression: GrpcCompression) -> Self {
         Self {
             client: GrpcClient::new(
-                |channel| {
-                    WorkerServiceClient::new(channel)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
+                move |channel| {
+                    with_grpc_compression!(WorkerServiceClient::new(channel), grpc_compression)
                 },
                 endpoint.as_http_02(),
                 Default::default(), // TODO
@@ -189,6 +228,7 @@ impl WorkerProxy for RemoteWorkerProxy {
             .collect();
         let invoke_parameters = Some(InvokeParameters {
             params: proto_params,
+            blob_params: vec![],
         });
 
         let response: InvokeAndAwaitTypedResponse = self
@@ -204,7 +244,11 @@ impl WorkerProxy for RemoteWorkerProxy {
                             parent: Some(caller_worker_id.clone().into()),
                             args: caller_args.clone(),
                             env: caller_env.clone(),
+                            caller: None,
+                            timeout_millis: None,
+                            map_result: false,
                         }),
+                        transform: false,
                     },
                     &self.access_token,
                 )))
@@ -255,6 +299,7 @@ impl WorkerProxy for RemoteWorkerProxy {
             .collect();
         let invoke_parameters = Some(InvokeParameters {
             params: proto_params,
+            blob_params: vec![],
         });
 
         let response: InvokeResponse = self
@@ -270,6 +315,9 @@ impl WorkerProxy for RemoteWorkerProxy {
                             parent: Some(caller_worker_id.clone().into()),
                             args: caller_args.clone(),
                             env: caller_env.clone(),
+                            caller: None,
+                            timeout_millis: None,
+                            map_result: false,
                         }),
                     },
                     &self.access_token,
@@ -318,4 +366,440 @@ impl WorkerProxy for RemoteWorkerProxy {
             ))),
         }
     }
+
+    async fn create(
+        &self,
+        worker_id: &WorkerId,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+    ) -> Result<(), WorkerProxyError> {
+        debug!("Creating remote worker {worker_id}");
+
+        let response: LaunchNewWorkerResponse = self
+            .client
+            .call(move |client| {
+                Box::pin(client.launch_new_worker(authorised_grpc_request(
+                    LaunchNewWorkerRequest {
+                        component_id: Some(worker_id.component_id.clone().into()),
+                        name: worker_id.worker_name.clone(),
+                        args: args.clone(),
+                        env: env.clone(),
+                        ttl_millis: None,
+                        anti_affinity_key: None,
+                    },
+                    &self.access_token,
+                )))
+            })
+            .await?
+            .into_inner();
+
+        match response.result {
+            Some(launch_new_worker_response::Result::Success(_)) => Ok(()),
+            Some(launch_new_worker_response::Result::Error(error)) => Err(error.into()),
+            None => Err(WorkerProxyError::InternalError(GolemError::unknown(
+                "Empty response through the worker API".to_string(),
+            ))),
+        }
+    }
+
+    async fn delete(
+        &self,
+        worker_id: &WorkerId,
+        cascade: bool,
+        dry_run: bool,
+    ) -> Result<Vec<String>, WorkerProxyError> {
+        debug!("Deleting remote worker {worker_id}");
+
+        let response: DeleteWorkerResponse = self
+            .client
+            .call(move |client| {
+                Box::pin(client.delete_worker(authorised_grpc_request(
+                    DeleteWorkerRequest {
+                        worker_id: Some(worker_id.clone().into()),
+                        cascade,
+                        dry_run,
+                    },
+                    &self.access_token,
+                )))
+            })
+            .await?
+            .into_inner();
+
+        match response.result {
+            Some(delete_worker_response::Result::Success(success)) => Ok(success.removed),
+            Some(delete_worker_response::Result::Error(error)) => Err(error.into()),
+            None => Err(WorkerProxyError::InternalError(GolemError::unknown(
+                "Empty response through the worker API".to_string(),
+            ))),
+        }
+    }
+
+    async fn in_maintenance(&self, component_id: &ComponentId) -> Result<bool, WorkerProxyError> {
+        let response: GetComponentMaintenanceResponse = self
+            .client
+            .call(move |client| {
+                Box::pin(client.get_component_maintenance(authorised_grpc_request(
+                    GetComponentMaintenanceRequest {
+                        component_id: Some(component_id.clone().into()),
+                    },
+                    &self.access_token,
+                )))
+            })
+            .await?
+            .into_inner();
+
+        match response.result {
+            Some(get_component_maintenance_response::Result::Success(success)) => {
+                Ok(success.in_maintenance)
+            }
+            Some(get_component_maintenance_response::Result::Error(error)) => Err(error.into()),
+            None => Err(WorkerProxyError::InternalError(GolemError::unknown(
+                "Empty response through the worker API".to_string(),
+            ))),
+        }
+    }
+}
+
+/// Retries the requests of another worker proxy which are rejected as temporarily unavailable,
+/// such as invocations of a component in maintenance, following the retry configuration
+pub struct RetryingWorkerProxy {
+    inner: Arc<dyn WorkerProxy + Send + Sync>,
+    retry_config: RetryConfig,
+}
+
+impl RetryingWorkerProxy {
+    pub fn new(inner: Arc<dyn WorkerProxy + Send + Sync>, retry_config: RetryConfig) -> Self {
+        Self {
+            inner,
+            retry_config,
+        }
+    }
+}
+
+#[async_trait]
+impl WorkerProxy for RetryingWorkerProxy {
+    async fn invoke_and_await(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        function_params: Vec<WitValue>,
+        caller_worker_id: WorkerId,
+        caller_args: Vec<String>,
+        caller_env: HashMap<String, String>,
+    ) -> Result<TypeAnnotatedValue, WorkerProxyError> {
+        retry_unavailable(&self.retry_config, || {
+            self.inner.invoke_and_await(
+                owned_worker_id,
+                idempotency_key.clone(),
+                function_name.clone(),
+                function_params.clone(),
+                caller_worker_id.clone(),
+                caller_args.clone(),
+                caller_env.clone(),
+            )
+        })
+        .await
+    }
+
+    async fn invoke(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        function_params: Vec<WitValue>,
+        caller_worker_id: WorkerId,
+        caller_args: Vec<String>,
+        caller_env: HashMap<String, String>,
+    ) -> Result<(), WorkerProxyError> {
+        retry_unavailable(&self.retry_config, || {
+            self.inner.invoke(
+                owned_worker_id,
+                idempotency_key.clone(),
+                function_name.clone(),
+                function_params.clone(),
+                caller_worker_id.clone(),
+                caller_args.clone(),
+                caller_env.clone(),
+            )
+        })
+        .await
+    }
+
+    async fn update(
+        &self,
+        owned_worker_id: &OwnedWorkerId,
+        target_version: ComponentVersion,
+        mode: UpdateMode,
+    ) -> Result<(), WorkerProxyError> {
+        retry_unavailable(&self.retry_config, || {
+            self.inner.update(owned_worker_id, target_version, mode)
+        })
+        .await
+    }
+
+    async fn create(
+        &self,
+        worker_id: &WorkerId,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+    ) -> Result<(), WorkerProxyError> {
+        retry_unavailable(&self.retry_config, || {
+            self.inner.create(worker_id, args.clone(), env.clone())
+        })
+        .await
+    }
+
+    async fn delete(
+        &self,
+        worker_id: &WorkerId,
+        cascade: bool,
+        dry_run: bool,
+    ) -> Result<Vec<String>, WorkerProxyError> {
+        retry_unavailable(&self.retry_config, || {
+            self.inner.delete(worker_id, cascade, dry_run)
+        })
+        .await
+    }
+
+    async fn in_maintenance(&self, component_id: &ComponentId) -> Result<bool, WorkerProxyError> {
+        self.inner.in_maintenance(component_id).await
+    }
+}
+
+async fn retry_unavailable<R, F, Fut>(
+    retry_config: &RetryConfig,
+    action: F,
+) -> Result<R, WorkerProxyError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<R, WorkerProxyError>>,
+{
+    let mut retries = RetryState::new(retry_config);
+    loop {
+        retries.start_attempt();
+        match action().await {
+            Err(error) if error.is_retriable() => {
+                if !retries.failed_attempt().await {
+                    break Err(error);
+                }
+                warn!("Worker API request failed, retrying: {error}");
+            }
+            result => break result,
+        }
+    }
+}
+
+#[cfg(test)]
+pub struct WorkerProxyMock {
+    unavailable_responses: std::sync::Mutex<usize>,
+    invocations: std::sync::Mutex<usize>,
+    in_maintenance: std::sync::Mutex<std::collections::HashSet<ComponentId>>,
+}
+
+#[cfg(test)]
+impl Default for WorkerProxyMock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+impl WorkerProxyMock {
+    pub fn new() -> Self {
+        Self {
+            unavailable_responses: std::sync::Mutex::new(0),
+            invocations: std::sync::Mutex::new(0),
+            in_maintenance: std::sync::Mutex::new(std::collections::HashSet::new()),
+        }
+    }
+
+    /// Makes the following `count` invocations fail as unavailable
+    pub fn set_unavailable_responses(&self, count: usize) {
+        *self.unavailable_responses.lock().unwrap() = count;
+    }
+
+    /// Number of invocation attempts, including the ones failed as unavailable
+    pub fn invocations(&self) -> usize {
+        *self.invocations.lock().unwrap()
+    }
+
+    pub fn set_in_maintenance(&self, component_id: &ComponentId, in_maintenance: bool) {
+        let mut components = self.in_maintenance.lock().unwrap();
+        if in_maintenance {
+            components.insert(component_id.clone());
+        } else {
+            components.remove(component_id);
+        }
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl WorkerProxy for WorkerProxyMock {
+    async fn invoke_and_await(
+        &self,
+        _owned_worker_id: &OwnedWorkerId,
+        _idempotency_key: Option<IdempotencyKey>,
+        _function_name: String,
+        _function_params: Vec<WitValue>,
+        _caller_worker_id: WorkerId,
+        _caller_args: Vec<String>,
+        _caller_env: HashMap<String, String>,
+    ) -> Result<TypeAnnotatedValue, WorkerProxyError> {
+        unimplemented!()
+    }
+
+    async fn invoke(
+        &self,
+        _owned_worker_id: &OwnedWorkerId,
+        _idempotency_key: Option<IdempotencyKey>,
+        _function_name: String,
+        _function_params: Vec<WitValue>,
+        _caller_worker_id: WorkerId,
+        _caller_args: Vec<String>,
+        _caller_env: HashMap<String, String>,
+    ) -> Result<(), WorkerProxyError> {
+        *self.invocations.lock().unwrap() += 1;
+        let mut unavailable_responses = self.unavailable_responses.lock().unwrap();
+        if *unavailable_responses > 0 {
+            *unavailable_responses -= 1;
+            Err(WorkerProxyError::Unavailable(
+                "Component is in maintenance".to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn update(
+        &self,
+        _owned_worker_id: &OwnedWorkerId,
+        _target_version: ComponentVersion,
+        _mode: UpdateMode,
+    ) -> Result<(), WorkerProxyError> {
+        unimplemented!()
+    }
+
+    async fn create(
+        &self,
+        _worker_id: &WorkerId,
+        _args: Vec<String>,
+        _env: HashMap<String, String>,
+    ) -> Result<(), WorkerProxyError> {
+        unimplemented!()
+    }
+
+    async fn delete(
+        &self,
+        _worker_id: &WorkerId,
+        _cascade: bool,
+        _dry_run: bool,
+    ) -> Result<Vec<String>, WorkerProxyError> {
+        unimplemented!()
+    }
+
+    async fn in_maintenance(&self, component_id: &ComponentId) -> Result<bool, WorkerProxyError> {
+        Ok(self.in_maintenance.lock().unwrap().contains(component_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use golem_api_grpc::proto::golem::common::ErrorBody;
+    use golem_api_grpc::proto::golem::worker::v1::{worker_error, WorkerError};
+    use golem_common::config::RetryConfig;
+    use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, WorkerId};
+    use uuid::Uuid;
+
+    use crate::services::worker_proxy::{
+        RetryingWorkerProxy, WorkerProxy, WorkerProxyError, WorkerProxyMock,
+    };
+
+    fn retry_config(max_attempts: u32) -> RetryConfig {
+        RetryConfig {
+            max_attempts,
+            min_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            multiplier: 1.0,
+            max_jitter_factor: None,
+        }
+    }
+
+    async fn invoke(proxy: &RetryingWorkerProxy) -> Result<(), WorkerProxyError> {
+        let worker_id = WorkerId {
+            component_id: ComponentId(Uuid::new_v4()),
+            worker_name: "worker".to_string(),
+        };
+        let owned_worker_id = OwnedWorkerId::new(
+            &AccountId {
+                value: "test-account".to_string(),
+            },
+            &worker_id,
+        );
+        proxy
+            .invoke(
+                &owned_worker_id,
+                None,
+                "golem:it/api.{run}".to_string(),
+                vec![],
+                worker_id.clone(),
+                vec![],
+                HashMap::new(),
+            )
+            .await
+    }
+
+    #[test]
+    fn unavailable_worker_error_is_retriable() {
+        let error: WorkerProxyError = WorkerError {
+            error: Some(worker_error::Error::Unavailable(ErrorBody {
+                error: "Component is in maintenance".to_string(),
+            })),
+        }
+        .into();
+
+        assert_eq!(
+            error,
+            WorkerProxyError::Unavailable("Component is in maintenance".to_string())
+        );
+        assert!(error.is_retriable());
+
+        let error: WorkerProxyError = WorkerError {
+            error: Some(worker_error::Error::NotFound(ErrorBody {
+                error: "Worker not found".to_string(),
+            })),
+        }
+        .into();
+        assert!(!error.is_retriable());
+    }
+
+    #[test]
+    async fn unavailable_requests_are_retried() {
+        let inner = Arc::new(WorkerProxyMock::new());
+        inner.set_unavailable_responses(2);
+        let proxy = RetryingWorkerProxy::new(inner.clone(), retry_config(3));
+
+        assert_eq!(invoke(&proxy).await, Ok(()));
+        assert_eq!(inner.invocations(), 3);
+    }
+
+    #[test]
+    async fn unavailable_requests_fail_after_the_last_attempt() {
+        let inner = Arc::new(WorkerProxyMock::new());
+        inner.set_unavailable_responses(5);
+        let proxy = RetryingWorkerProxy::new(inner.clone(), retry_config(3));
+
+        assert!(matches!(
+            invoke(&proxy).await,
+            Err(WorkerProxyError::Unavailable(_))
+        ));
+        assert_eq!(inner.invocations(), 3);
+    }
 }
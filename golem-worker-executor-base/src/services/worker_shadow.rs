@@ -0,0 +1,108 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use golem_common::model::{IdempotencyKey, OwnedWorkerId, WorkerShadow};
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use golem_wasm_rpc::{Value, WitValue};
+use tracing::{debug, info_span, warn, Instrument};
+
+use crate::services::{HasWorkerProxy, HasWorkerService};
+
+/// Sends a copy of an invocation to the shadow worker of the invoked worker.
+///
+/// The shadow invocation runs in the background, its outcome never affects the original
+/// invocation. If `original_result` is given and the shadow is set up to compare results,
+/// the shadow worker's result is awaited and any difference is logged.
+pub async fn shadow_invocation<T>(
+    this: &T,
+    owned_worker_id: &OwnedWorkerId,
+    shadow: WorkerShadow,
+    idempotency_key: IdempotencyKey,
+    function_name: String,
+    function_input: Vec<Value>,
+    original_result: Option<&TypeAnnotatedValue>,
+) where
+    T: HasWorkerService + HasWorkerProxy,
+{
+    let (args, env) = match this.worker_service().get(owned_worker_id).await {
+        Some(metadata) => (metadata.args, metadata.env.into_iter().collect()),
+        None => (Vec::new(), HashMap::new()),
+    };
+
+    let span = info_span!(
+        "shadow_invocation",
+        worker_id = owned_worker_id.to_string(),
+        shadow_worker_id = shadow.shadow_worker_id.to_string(),
+        function_name = function_name.as_str()
+    );
+
+    let worker_proxy = this.worker_proxy();
+    let caller_worker_id = owned_worker_id.worker_id();
+    let shadow_worker_id =
+        OwnedWorkerId::new(&owned_worker_id.account_id, &shadow.shadow_worker_id);
+    // Deterministic, so retries of the original invocation are not executed twice by the shadow
+    let idempotency_key = IdempotencyKey::new(format!("shadow-{}", idempotency_key.value));
+    let params: Vec<WitValue> = function_input.into_iter().map(|v| v.into()).collect();
+    let original_result = original_result.filter(|_| shadow.compare_results).cloned();
+
+    tokio::spawn(
+        async move {
+            match original_result {
+                Some(original_result) => {
+                    match worker_proxy
+                        .invoke_and_await(
+                            &shadow_worker_id,
+                            Some(idempotency_key),
+                            function_name,
+                            params,
+                            caller_worker_id,
+                            args,
+                            env,
+                        )
+                        .await
+                    {
+                        Ok(shadow_result) if shadow_result == original_result => {
+                            debug!("Shadow invocation returned the same result")
+                        }
+                        Ok(shadow_result) => warn!(
+                            original_result = format!("{original_result:?}"),
+                            shadow_result = format!("{shadow_result:?}"),
+                            "Shadow invocation returned a different result"
+                        ),
+                        Err(err) => warn!("Shadow invocation failed: {err}"),
+                    }
+                }
+                None => {
+                    if let Err(err) = worker_proxy
+                        .invoke(
+                            &shadow_worker_id,
+                            Some(idempotency_key),
+                            function_name,
+                            params,
+                            caller_worker_id,
+                            args,
+                            env,
+                        )
+                        .await
+                    {
+                        warn!("Failed to enqueue shadow invocation: {err}");
+                    }
+                }
+            }
+        }
+        .instrument(span),
+    );
+}
@@ -16,21 +16,26 @@ use std::{fs, io};
 use std::fs::ReadDir;
 use std::os::unix::fs::PermissionsExt;
 use tokio::fs::File;
-use crate::storage::blob::{BlobMetadata, BlobStorage, BlobStorageLabelledApi, BlobStorageNamespace, ExistsResult};
+use crate::storage::blob::{is_staging_path, staging_path_of, BlobMetadata, BlobStorage, BlobStorageLabelledApi, BlobStorageNamespace, ExistsResult};
 use async_trait::async_trait;
 use bytes::Bytes;
-use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, Timestamp, WorkerId, WorkerMetadata};
+use golem_common::model::initial_file_system::InitialFilePermissions;
+use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, Timestamp, WorkerId};
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use anyhow::Error;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio_stream::StreamExt;
-use tracing::info;
+use tracing::{debug, info, warn};
 use crate::services::blob_store::FileOrDirectoryResponse;
 
+/// Staging files older than this are considered to be left behind by interrupted staged writes
+const STALE_STAGING_FILE_AGE: Duration = Duration::from_secs(60 * 60);
+
 #[derive(Debug)]
 pub struct FileSystemBlobStorage {
     root: PathBuf,
+    oplog_payload_shard_fan_out: usize,
 }
 
 impl FileSystemBlobStorage {
@@ -60,10 +65,87 @@ impl FileSystemBlobStorage {
                 .map_err(|err| format!("Failed to create custom_data directory: {err}"))?;
         }
 
-        Ok(Self { root: canonical })
+        let root = canonical.clone();
+        tokio::task::spawn_blocking(move || {
+            remove_stale_staging_files_in(&root, SystemTime::now() - STALE_STAGING_FILE_AGE)
+        });
+
+        Ok(Self {
+            root: canonical,
+            oplog_payload_shard_fan_out: 0,
+        })
+    }
+
+    /// Removes the staging files of the staged writes interrupted by a crash which were last
+    /// modified before `staged_before`
+    pub async fn remove_stale_staging_files(&self, staged_before: SystemTime) {
+        let root = self.root.clone();
+        let _ = tokio::task::spawn_blocking(move || {
+            remove_stale_staging_files_in(&root, staged_before)
+        })
+        .await;
+    }
+
+    /// Stores oplog payloads under two levels of hash-prefixed directories, each having at most
+    /// `fan_out` entries, instead of directly in the worker's directory.
+    ///
+    /// Payloads stored with the unsharded layout remain readable through the fallback in
+    /// `existing_path_of`, but the fan-out must not be changed once sharded payloads exist.
+    /// A fan-out below 2 disables sharding.
+    pub fn with_oplog_payload_sharding(mut self, fan_out: usize) -> Self {
+        self.oplog_payload_shard_fan_out = fan_out;
+        self
     }
 
     fn path_of(&self, namespace: &BlobStorageNamespace, path: &Path) -> PathBuf {
+        let mut result = self.namespace_root(namespace);
+        result.push(path);
+        result
+    }
+
+    fn sharded_path_of(&self, namespace: &BlobStorageNamespace, path: &Path) -> Option<PathBuf> {
+        let fan_out = self.oplog_payload_shard_fan_out as u64;
+        match namespace {
+            BlobStorageNamespace::OplogPayload { .. } if fan_out > 1 => {
+                let digest = md5::compute(path.to_string_lossy().as_bytes());
+                let hash = u64::from_be_bytes(digest.0[0..8].try_into().unwrap());
+                let width = format!("{:x}", fan_out - 1).len();
+
+                let mut result = self.namespace_root(namespace);
+                result.push(format!("{:0width$x}", hash % fan_out));
+                result.push(format!("{:0width$x}", (hash / fan_out) % fan_out));
+                result.push(path);
+                Some(result)
+            }
+            _ => None,
+        }
+    }
+
+    /// The path a blob is written to
+    fn target_path_of(&self, namespace: &BlobStorageNamespace, path: &Path) -> PathBuf {
+        self.sharded_path_of(namespace, path)
+            .unwrap_or_else(|| self.path_of(namespace, path))
+    }
+
+    /// The path of an already stored blob. If the blob is not in the sharded layout but exists
+    /// at its unsharded location, that one is returned. Otherwise it is the same as `target_path_of`.
+    async fn existing_path_of(&self, namespace: &BlobStorageNamespace, path: &Path) -> PathBuf {
+        match self.sharded_path_of(namespace, path) {
+            Some(sharded) => {
+                let unsharded = self.path_of(namespace, path);
+                if async_fs::metadata(&sharded).await.is_err()
+                    && async_fs::metadata(&unsharded).await.is_ok()
+                {
+                    unsharded
+                } else {
+                    sharded
+                }
+            }
+            None => self.path_of(namespace, path),
+        }
+    }
+
+    fn namespace_root(&self, namespace: &BlobStorageNamespace) -> PathBuf {
         let mut result = self.root.clone();
 
         match namespace {
@@ -94,9 +176,16 @@ impl FileSystemBlobStorage {
                 result.push("initial_file_system");
                 result.push(account_id.to_string());
             }
+            BlobStorageNamespace::InvocationParameters(account_id) => {
+                result.push("invocation_parameters");
+                result.push(account_id.to_string());
+            }
+            BlobStorageNamespace::InvocationResults(account_id) => {
+                result.push("invocation_results");
+                result.push(account_id.to_string());
+            }
         }
 
-        result.push(path);
         result
     }
 
@@ -118,7 +207,7 @@ impl BlobStorage for FileSystemBlobStorage {
         namespace: BlobStorageNamespace,
         path: &Path,
     ) -> Result<Option<Bytes>, String> {
-        let full_path = self.path_of(&namespace, path);
+        let full_path = self.existing_path_of(&namespace, path).await;
         self.ensure_path_is_inside_root(&full_path)?;
 
         if async_fs::metadata(&full_path).await.is_ok() {
@@ -138,7 +227,7 @@ impl BlobStorage for FileSystemBlobStorage {
         namespace: BlobStorageNamespace,
         path: &Path,
     ) -> Result<Option<BlobMetadata>, String> {
-        let full_path = self.path_of(&namespace, path);
+        let full_path = self.existing_path_of(&namespace, path).await;
         self.ensure_path_is_inside_root(&full_path)?;
 
         if let Ok(metadata) = async_fs::metadata(&full_path).await {
@@ -165,7 +254,7 @@ impl BlobStorage for FileSystemBlobStorage {
         path: &Path,
         data: &[u8],
     ) -> Result<(), String> {
-        let full_path = self.path_of(&namespace, path);
+        let full_path = self.target_path_of(&namespace, path);
         self.ensure_path_is_inside_root(&full_path)?;
 
 
@@ -189,7 +278,7 @@ impl BlobStorage for FileSystemBlobStorage {
         namespace: BlobStorageNamespace,
         path: &Path,
     ) -> Result<(), String> {
-        let full_path = self.path_of(&namespace, path);
+        let full_path = self.existing_path_of(&namespace, path).await;
         self.ensure_path_is_inside_root(&full_path)?;
 
         async_fs::remove_file(&full_path)
@@ -212,60 +301,43 @@ impl BlobStorage for FileSystemBlobStorage {
 
     }
 
-    async fn set_permissions(&self, base_path: &Path) -> Result<(), String> {
-        // Set permissions for all files in the `read-only` folder
-        let read_only_folder = base_path.join("read-only");
-        if read_only_folder.exists() {
-            for entry in fs::read_dir(&read_only_folder).map_err(|e| {
-                format!("Failed to read read-only directory: {}", e)
-            })? {
-                let entry = entry.map_err(|e| {
-                    format!("Failed to read entry in read-only folder: {}", e)
-                })?;
-                let path = entry.path();
-                if path.is_file() {
-                    let mut permissions = fs::metadata(&path)
-                        .map_err(|e| format!("Failed to get metadata: {}", e))?
-                        .permissions();
-                    permissions.set_readonly(true);
-                    fs::set_permissions(&path, permissions)
-                        .map_err(|e| format!("Failed to set read-only permissions: {}", e))?;
-                }
-            }
-        }
-
-        // Set permissions for all files in the `read-write` folder
-        let read_write_folder = base_path.join("read-write");
-        if read_write_folder.exists() {
-            for entry in fs::read_dir(&read_write_folder).map_err(|e| {
-                format!("Failed to read read-write directory: {}", e)
-            })? {
-                let entry = entry.map_err(|e| {
-                    format!("Failed to read entry in read-write folder: {}", e)
-                })?;
-                let path = entry.path();
-                if path.is_file() {
-                    let mut permissions = fs::metadata(&path)
-                        .map_err(|e| format!("Failed to get metadata: {}", e))?
-                        .permissions();
-                    // Set read-write permissions (e.g., 0o644 on Unix grants read-write to owner and read-only to others)
-                    permissions.set_mode(0o644);
-                    fs::set_permissions(&path, permissions)
-                        .map_err(|e| format!("Failed to set read-write permissions: {}", e))?;
-                }
-            }
+    async fn set_permissions(
+        &self,
+        namespace: BlobStorageNamespace,
+        path: &Path,
+        permissions: InitialFilePermissions,
+    ) -> Result<(), String> {
+        let path = self.path_of(&namespace, path);
+        let mut file_permissions = fs::metadata(&path)
+            .map_err(|e| format!("Failed to get metadata of {path:?}: {e}"))?
+            .permissions();
+        match permissions {
+            InitialFilePermissions::ReadOnly => file_permissions.set_readonly(true),
+            // Read-write to the owner and read-only to others
+            InitialFilePermissions::ReadWrite => file_permissions.set_mode(0o644),
         }
-
-        Ok(())
+        fs::set_permissions(&path, file_permissions)
+            .map_err(|e| format!("Failed to set permissions of {path:?}: {e}"))
     }
 
     async fn get_directory_entries(&self, root_path: &Path, path: &Path) -> Result<io::Result<Vec<(String, bool)>>, String> {
 
         let mut entries = Vec::new();
-        let mut dir_entries = tokio::fs::read_dir(path).await.unwrap();
-
-        while let Some(entry) = dir_entries.next_entry().await.unwrap() {
+        let mut dir_entries = match tokio::fs::read_dir(path).await {
+            Ok(dir_entries) => dir_entries,
+            Err(err) => return Ok(Err(err)),
+        };
+
+        loop {
+            let entry = match dir_entries.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(err) => return Ok(Err(err)),
+            };
             let path = entry.path();
+            if is_staging_path(&path) {
+                continue;
+            }
             let is_directory = path.is_dir();
             let relative_path = path.strip_prefix(root_path).ok().map(|p| p.display().to_string());
             if let Some(relative_path) = relative_path {
@@ -283,11 +355,17 @@ impl BlobStorage for FileSystemBlobStorage {
                 .get_directory_entries(&base_path, path)  // Pass base_path here
                 .await
                 .map_err(|err| format!("Failed to get directory entries: {err}"))?;
-            Ok(FileOrDirectoryResponse::DirectoryListing(directory_metadata.unwrap()))
+            let directory_metadata = directory_metadata
+                .map_err(|err| format!("Failed to read directory {path:?}: {err}"))?;
+            Ok(FileOrDirectoryResponse::DirectoryListing(
+                directory_metadata,
+            ))
         } else {
             info!("Not a directory");
             let file_content = self.get_file(path).await.map_err(|err| format!("Failed to get file content: {err}"))?;
-            Ok(FileOrDirectoryResponse::FileContent(file_content.unwrap()))
+            let file_content =
+                file_content.map_err(|err| format!("Failed to read file {path:?}: {err}"))?;
+            Ok(FileOrDirectoryResponse::FileContent(file_content))
         }
     }
 
@@ -325,6 +403,9 @@ impl BlobStorage for FileSystemBlobStorage {
 
         let mut result = Vec::new();
         while let Some(entry) = entries.try_next().await.map_err(|err| err.to_string())? {
+            if is_staging_path(&entry.path()) {
+                continue;
+            }
             if let Ok(path) = entry.path().strip_prefix(&namespace_root) {
                 result.push(path.to_path_buf());
             }
@@ -354,7 +435,7 @@ impl BlobStorage for FileSystemBlobStorage {
         namespace: BlobStorageNamespace,
         path: &Path,
     ) -> Result<ExistsResult, String> {
-        let full_path = self.path_of(&namespace, path);
+        let full_path = self.existing_path_of(&namespace, path).await;
         self.ensure_path_is_inside_root(&full_path)?;
 
         if let Ok(metadata) = async_fs::metadata(&full_path).await {
@@ -376,34 +457,143 @@ impl BlobStorage for FileSystemBlobStorage {
         from: &Path,
         to: &Path,
     ) -> Result<(), String> {
-        let from_full_path = self.path_of(&namespace, from);
-        let to_full_path = self.path_of(&namespace, to);
+        let from_full_path = self.existing_path_of(&namespace, from).await;
+        let to_full_path = self.target_path_of(&namespace, to);
 
 
 
         self.ensure_path_is_inside_root(&from_full_path)?;
         self.ensure_path_is_inside_root(&to_full_path)?;
 
+        if let Some(parent) = to_full_path.parent() {
+            if async_fs::metadata(parent).await.is_err() {
+                async_fs::create_dir_all(parent).await.map_err(|err| {
+                    format!("Failed to create parent directory {parent:?}: {err}")
+                })?;
+            }
+        }
+
         async_fs::copy(&from_full_path, &to_full_path)
             .await
             .map_err(|err| err.to_string())?;
         Ok(())
     }
 
-    async fn initialize_worker_ifs(&self, worker_metadata: WorkerMetadata) -> anyhow::Result<(), String> {
-        let source_path = Path::new(&worker_metadata.worker_id.component_id.to_string()).join("extracted");
-        let target_path = Path::new(&worker_metadata.worker_id.component_id.to_string()).join(&worker_metadata.worker_id.worker_name);
+    async fn rename(
+        &self,
+        _target_label: &'static str,
+        _op_label: &'static str,
+        namespace: BlobStorageNamespace,
+        from: &Path,
+        to: &Path,
+    ) -> Result<(), String> {
+        let from_full_path = self.existing_path_of(&namespace, from).await;
+        let to_full_path = self.target_path_of(&namespace, to);
+
+        self.ensure_path_is_inside_root(&from_full_path)?;
+        self.ensure_path_is_inside_root(&to_full_path)?;
 
+        if let Some(parent) = to_full_path.parent() {
+            if async_fs::metadata(parent).await.is_err() {
+                async_fs::create_dir_all(parent).await.map_err(|err| {
+                    format!("Failed to create parent directory {parent:?}: {err}")
+                })?;
+            }
+        }
 
-        self.copy_dir_contents(
-            "initialize_ifs",
-            "copy_dir_contents",
-            &PathBuf::from(source_path),
-            &PathBuf::from(target_path),
-            BlobStorageNamespace::InitialFileSystem(worker_metadata.clone().account_id),
-            BlobStorageNamespace::CustomStorage(worker_metadata.clone().account_id),
-        )
+        async_fs::rename(&from_full_path, &to_full_path)
             .await
+            .map_err(|err| {
+                format!("Failed to rename {from_full_path:?} to {to_full_path:?}: {err}")
+            })
+    }
+
+    async fn put_raw_staged(
+        &self,
+        _target_label: &'static str,
+        _op_label: &'static str,
+        namespace: BlobStorageNamespace,
+        path: &Path,
+        data: &[u8],
+    ) -> Result<(), String> {
+        let full_path = self.target_path_of(&namespace, path);
+        self.ensure_path_is_inside_root(&full_path)?;
+
+        let parent = full_path
+            .parent()
+            .ok_or_else(|| format!("Path {full_path:?} has no parent directory"))?;
+        if async_fs::metadata(parent).await.is_err() {
+            async_fs::create_dir_all(parent)
+                .await
+                .map_err(|err| format!("Failed to create parent directory {parent:?}: {err}"))?;
+        }
+
+        // The staging file is created in the same directory as the target so the rename never
+        // crosses file systems
+        let staging_path = staging_path_of(&full_path);
+        let write_result = async {
+            let mut file = File::create(&staging_path).await?;
+            file.write_all(data).await?;
+            file.sync_all().await?;
+            async_fs::rename(&staging_path, &full_path).await?;
+            // Persisting the rename itself
+            File::open(parent).await?.sync_all().await
+        }
+        .await;
+
+        match write_result {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                let _ = async_fs::remove_file(&staging_path).await;
+                Err(format!("Failed to store file at {full_path:?}: {err}"))
+            }
+        }
+    }
+
+    fn supports_links(&self) -> bool {
+        true
+    }
+
+    fn supports_permissions(&self) -> bool {
+        true
+    }
+
+    async fn link(
+        &self,
+        _target_label: &'static str,
+        _op_label: &'static str,
+        namespace: BlobStorageNamespace,
+        from: &Path,
+        to: &Path,
+    ) -> Result<bool, String> {
+        let from_full_path = self.existing_path_of(&namespace, from).await;
+        let to_full_path = self.target_path_of(&namespace, to);
+
+        self.ensure_path_is_inside_root(&from_full_path)?;
+        self.ensure_path_is_inside_root(&to_full_path)?;
+
+        if let Some(parent) = to_full_path.parent() {
+            if async_fs::metadata(parent).await.is_err() {
+                async_fs::create_dir_all(parent).await.map_err(|err| {
+                    format!("Failed to create parent directory {parent:?}: {err}")
+                })?;
+            }
+        }
+        if async_fs::symlink_metadata(&to_full_path).await.is_ok() {
+            async_fs::remove_file(&to_full_path)
+                .await
+                .map_err(|err| format!("Failed to replace {to_full_path:?}: {err}"))?;
+        }
+
+        // Hard links rather than symbolic links, as the sandboxed worker cannot follow links
+        // pointing outside of its own file system
+        match async_fs::hard_link(&from_full_path, &to_full_path).await {
+            Ok(()) => Ok(true),
+            Err(err) => {
+                debug!("Cannot link {from_full_path:?} to {to_full_path:?}, it has to be copied: {err}");
+                Ok(false)
+            }
+        }
     }
 
     async fn copy_dir_contents(
@@ -428,6 +618,11 @@ impl BlobStorage for FileSystemBlobStorage {
             .await
             .map_err(|e| format!("Failed to read source directory: {}", e))?;
 
+        // The target may be nested in directories which do not exist yet
+        async_fs::create_dir_all(&to_full_path)
+            .await
+            .map_err(|e| format!("Failed to create target directory: {}", e))?;
+
         while let Some(entry) = entries
             .try_next()
             .await
@@ -475,3 +670,31 @@ impl BlobStorage for FileSystemBlobStorage {
 
 
 }
+
+fn remove_stale_staging_files_in(dir: &Path, staged_before: SystemTime) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => {
+                remove_stale_staging_files_in(&path, staged_before)
+            }
+            Ok(file_type) if file_type.is_file() && is_staging_path(&path) => {
+                let stale = entry
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .is_ok_and(|modified| modified < staged_before);
+                if stale {
+                    match fs::remove_file(&path) {
+                        Ok(()) => debug!("Removed stale staging file {path:?}"),
+                        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                        Err(err) => warn!("Failed to remove stale staging file {path:?}: {err}"),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
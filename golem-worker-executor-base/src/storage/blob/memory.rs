@@ -13,11 +13,15 @@
 // limitations under the License.
 
 use std::io;
-use crate::storage::blob::{BlobMetadata, BlobStorage, BlobStorageNamespace, ExistsResult};
+use crate::storage::blob::{
+    no_local_files, set_permissions_unsupported, BlobMetadata, BlobStorage, BlobStorageNamespace,
+    ExistsResult,
+};
 use async_trait::async_trait;
 use bytes::Bytes;
 use dashmap::DashMap;
-use golem_common::model::{ComponentId, OwnedWorkerId, Timestamp, WorkerId, WorkerMetadata};
+use golem_common::model::initial_file_system::InitialFilePermissions;
+use golem_common::model::{ComponentId, OwnedWorkerId, Timestamp, WorkerId};
 use std::path::{Path, PathBuf};
 use anyhow::Error;
 use tracing::info;
@@ -28,7 +32,7 @@ pub struct InMemoryBlobStorage {
     data: DashMap<BlobStorageNamespace, DashMap<String, DashMap<String, Entry>>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Entry {
     data: Bytes,
     metadata: BlobMetadata,
@@ -154,20 +158,73 @@ impl BlobStorage for InMemoryBlobStorage {
         Ok(())
     }
 
+    async fn rename(
+        &self,
+        _target_label: &'static str,
+        _op_label: &'static str,
+        namespace: BlobStorageNamespace,
+        from: &Path,
+        to: &Path,
+    ) -> Result<(), String> {
+        let from_dir = from
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let from_key = from
+            .file_name()
+            .ok_or_else(|| format!("Path {from:?} has no file name"))?
+            .to_string_lossy()
+            .to_string();
+        let to_dir = to
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let to_key = to
+            .file_name()
+            .ok_or_else(|| format!("Path {to:?} has no file name"))?
+            .to_string_lossy()
+            .to_string();
+
+        let namespace_data = self.data.entry(namespace).or_default();
+        let entry = namespace_data
+            .get(&from_dir)
+            .and_then(|directory| directory.remove(&from_key))
+            .map(|(_, entry)| entry)
+            .ok_or_else(|| format!("Entry not found: {:?}", from))?;
+        namespace_data
+            .entry(to_dir)
+            .or_default()
+            .insert(to_key, entry);
+        Ok(())
+    }
+
     async fn get_file(&self, path: &Path) -> Result<io::Result<Vec<u8>>, String> {
-        todo!()
+        Ok(Err(no_local_files("In-memory", path)))
     }
 
-    async fn set_permissions(&self, path: &Path) -> Result<(), String> {
-        todo!()
+    async fn set_permissions(
+        &self,
+        _namespace: BlobStorageNamespace,
+        path: &Path,
+        permissions: InitialFilePermissions,
+    ) -> Result<(), String> {
+        set_permissions_unsupported("In-memory", path, permissions)
     }
 
-    async fn get_directory_entries(&self, root_path: &Path, path: &Path) -> Result<io::Result<Vec<(String, bool)>>, String> {
-        todo!()
+    async fn get_directory_entries(
+        &self,
+        _root_path: &Path,
+        path: &Path,
+    ) -> Result<io::Result<Vec<(String, bool)>>, String> {
+        Ok(Err(no_local_files("In-memory", path)))
     }
 
-    async fn get_file_or_directory(&self, base_path: &Path, path: &Path) -> Result<FileOrDirectoryResponse, String> {
-        todo!()
+    async fn get_file_or_directory(
+        &self,
+        _base_path: &Path,
+        path: &Path,
+    ) -> Result<FileOrDirectoryResponse, String> {
+        Err(no_local_files("In-memory", path).to_string())
     }
 
 
@@ -250,10 +307,17 @@ impl BlobStorage for InMemoryBlobStorage {
         namespace: BlobStorageNamespace,
         path: &Path,
     ) -> Result<ExistsResult, String> {
+        let dir = path.to_string_lossy().to_string();
+        let prefix = format!("{dir}/");
         if self
             .data
             .get(&namespace)
-            .map(|namespace_data| namespace_data.contains_key(path.to_string_lossy().as_ref()))
+            .map(|namespace_data| {
+                // Parent directories exist implicitly, as in the other storages
+                namespace_data
+                    .iter()
+                    .any(|entry| entry.key() == &dir || entry.key().starts_with(&prefix))
+            })
             .unwrap_or_default()
         {
             return Ok(ExistsResult::Directory);
@@ -284,11 +348,49 @@ impl BlobStorage for InMemoryBlobStorage {
         }
     }
 
-    async fn initialize_worker_ifs(&self, worker_metadata: WorkerMetadata) -> anyhow::Result<(), String> {
-        todo!()
-    }
+    async fn copy_dir_contents(
+        &self,
+        _target_label: &'static str,
+        _source_label: &'static str,
+        from: &Path,
+        to: &Path,
+        source: BlobStorageNamespace,
+        target: BlobStorageNamespace,
+    ) -> Result<(), String> {
+        let from = from.to_string_lossy().to_string();
+        let to = to.to_string_lossy().to_string();
+        let prefix = format!("{from}/");
+
+        // Collecting the entries first, as the source and the target can be the same namespace
+        let directories: Vec<(String, Vec<(String, Entry)>)> = match self.data.get(&source) {
+            Some(namespace_data) => namespace_data
+                .iter()
+                .filter(|directory| {
+                    directory.key() == &from || directory.key().starts_with(&prefix)
+                })
+                .map(|directory| {
+                    let target_dir = format!("{to}{}", &directory.key()[from.len()..]);
+                    let entries = directory
+                        .iter()
+                        .map(|entry| (entry.key().clone(), entry.value().clone()))
+                        .collect();
+                    (target_dir, entries)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
 
-    async fn copy_dir_contents(&self, target_label: &'static str, source_label: &'static str, from: &Path, to: &Path, source: BlobStorageNamespace, target: BlobStorageNamespace) -> Result<(), String> {
-        todo!()
+        if directories.is_empty() {
+            return Err(format!("Source directory {from:?} does not exist"));
+        }
+
+        let namespace_data = self.data.entry(target).or_default();
+        for (target_dir, entries) in directories {
+            let directory = namespace_data.entry(target_dir).or_default();
+            for (key, entry) in entries {
+                directory.insert(key, entry);
+            }
+        }
+        Ok(())
     }
 }
@@ -21,6 +21,7 @@ use bincode::{Decode, Encode};
 use bytes::Bytes;
 use tracing::info;
 use golem_common::model::{AccountId, ComponentId, OwnedWorkerId, Timestamp, WorkerId, WorkerMetadata};
+use golem_common::model::initial_file_system::InitialFilePermissions;
 use golem_common::serialization::{deserialize, serialize};
 use crate::services::blob_store::FileOrDirectoryResponse;
 
@@ -84,9 +85,13 @@ pub trait BlobStorage: Debug {
         path: &Path
     ) -> Result<io::Result<Vec<u8>>, String>;
 
+    /// Applies the permissions of an initial file system entry to an extracted file. Storages
+    /// which cannot restrict access to their blobs reject read-only permissions.
     async fn set_permissions(
         &self,
+        namespace: BlobStorageNamespace,
         path: &Path,
+        permissions: InitialFilePermissions,
     ) -> Result<(), String>;
 
     async fn get_directory_entries(&self, root_path: &Path, path: &Path) -> Result<io::Result<Vec<(String, bool)>> , String>;
@@ -169,15 +174,111 @@ pub trait BlobStorage: Debug {
         namespace: BlobStorageNamespace,
         from: &Path,
         to: &Path,
+    ) -> Result<(), String> {
+        self.rename(target_label, op_label, namespace, from, to)
+            .await
+    }
+
+    /// Renames the blob at `from` to `to`, replacing `to` if it already exists.
+    ///
+    /// Backends which can do it atomically override this; the default implementation falls back
+    /// to a copy followed by a delete, so readers may briefly observe the blob at both paths.
+    async fn rename(
+        &self,
+        target_label: &'static str,
+        op_label: &'static str,
+        namespace: BlobStorageNamespace,
+        from: &Path,
+        to: &Path,
     ) -> Result<(), String> {
         self.copy(target_label, op_label, namespace.clone(), from, to)
             .await?;
         self.delete(target_label, op_label, namespace, from).await
     }
+
+    /// Writes a blob so that a partially written version of it is never observable at `path`,
+    /// not even after a crash. The data is first written to a temporary sibling path which is
+    /// then renamed to `path`.
+    async fn put_raw_staged(
+        &self,
+        target_label: &'static str,
+        op_label: &'static str,
+        namespace: BlobStorageNamespace,
+        path: &Path,
+        data: &[u8],
+    ) -> Result<(), String> {
+        let staging_path = staging_path_of(path);
+        self.put_raw(
+            target_label,
+            op_label,
+            namespace.clone(),
+            &staging_path,
+            data,
+        )
+        .await?;
+        match self
+            .rename(
+                target_label,
+                op_label,
+                namespace.clone(),
+                &staging_path,
+                path,
+            )
+            .await
+        {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                let _ = self
+                    .delete(target_label, op_label, namespace, &staging_path)
+                    .await;
+                Err(err)
+            }
+        }
+    }
+
+    /// Whether the storage can share the contents of blobs with `link`
+    fn supports_links(&self) -> bool {
+        false
+    }
+
+    /// Whether the storage can make blobs read-only with `set_permissions`
+    fn supports_permissions(&self) -> bool {
+        false
+    }
+
+    /// Makes the blob at `from` available at `to` too, sharing its contents instead of copying
+    /// them. Returns `false` if the blob could not be linked, in which case the caller has to
+    /// copy it instead.
+    async fn link(
+        &self,
+        _target_label: &'static str,
+        _op_label: &'static str,
+        _namespace: BlobStorageNamespace,
+        _from: &Path,
+        _to: &Path,
+    ) -> Result<bool, String> {
+        Ok(false)
+    }
+
+    /// Copies the component's extracted initial file system to the worker's own storage
     async fn initialize_worker_ifs(
         &self,
-        worker_metadata: WorkerMetadata
-    ) -> anyhow::Result<(), String>;
+        worker_metadata: WorkerMetadata,
+    ) -> anyhow::Result<(), String> {
+        let component_id = worker_metadata.worker_id.component_id.to_string();
+        self.copy_dir_contents(
+            "initialize_ifs",
+            "copy_dir_contents",
+            &Path::new(&component_id).join("extracted"),
+            &Path::new(&component_id).join(&worker_metadata.worker_id.worker_name),
+            BlobStorageNamespace::InitialFileSystem(worker_metadata.account_id.clone()),
+            BlobStorageNamespace::CustomStorage(worker_metadata.account_id),
+        )
+        .await
+    }
+
+    /// Copies every blob below `from` to the same relative path below `to`, failing if there is
+    /// nothing at `from`
     async fn copy_dir_contents(
         &self,
         target_label: &'static str,
@@ -337,6 +438,47 @@ impl<'a, S: BlobStorage + ?Sized + Sync> LabelledBlobStorage<'a, S> {
             .await
     }
 
+    pub async fn rename(
+        &self,
+        namespace: BlobStorageNamespace,
+        from: &Path,
+        to: &Path,
+    ) -> Result<(), String> {
+        self.storage
+            .rename(self.svc_name, self.api_name, namespace, from, to)
+            .await
+    }
+
+    pub async fn put_raw_staged(
+        &self,
+        namespace: BlobStorageNamespace,
+        path: &Path,
+        data: &[u8],
+    ) -> Result<(), String> {
+        self.storage
+            .put_raw_staged(self.svc_name, self.api_name, namespace, path, data)
+            .await
+    }
+
+    pub fn supports_links(&self) -> bool {
+        self.storage.supports_links()
+    }
+
+    pub fn supports_permissions(&self) -> bool {
+        self.storage.supports_permissions()
+    }
+
+    pub async fn link(
+        &self,
+        namespace: BlobStorageNamespace,
+        from: &Path,
+        to: &Path,
+    ) -> Result<bool, String> {
+        self.storage
+            .link(self.svc_name, self.api_name, namespace, from, to)
+            .await
+    }
+
     pub async fn get<T: Decode>(
         &self,
         namespace: BlobStorageNamespace,
@@ -358,6 +500,49 @@ impl<'a, S: BlobStorage + ?Sized + Sync> LabelledBlobStorage<'a, S> {
     }
 }
 
+/// The local file access of the storages which do not keep their blobs in the local file system
+pub(crate) fn no_local_files(storage: &str, path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{storage} blob storage has no local file at {path:?}"),
+    )
+}
+
+/// `set_permissions` of the storages without `supports_permissions`, where every blob is
+/// writable
+pub(crate) fn set_permissions_unsupported(
+    storage: &str,
+    path: &Path,
+    permissions: InitialFilePermissions,
+) -> Result<(), String> {
+    match permissions {
+        InitialFilePermissions::ReadWrite => Ok(()),
+        InitialFilePermissions::ReadOnly => Err(format!(
+            "{storage} blob storage cannot make {path:?} read-only"
+        )),
+    }
+}
+
+/// Temporary path next to `path` used by staged writes before renaming them to their final place
+pub fn staging_path_of(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{file_name}.{}.tmp", uuid::Uuid::new_v4()))
+}
+
+/// Whether `path` is a temporary path created by `staging_path_of`, which is left behind if the
+/// staged write was interrupted
+pub fn is_staging_path(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_prefix('.'))
+        .and_then(|name| name.strip_suffix(".tmp"))
+        .and_then(|name| name.rsplit_once('.'))
+        .is_some_and(|(_, id)| uuid::Uuid::parse_str(id).is_ok())
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum BlobStorageNamespace {
     CompilationCache,
@@ -371,7 +556,11 @@ pub enum BlobStorageNamespace {
         component_id: ComponentId,
         level: usize,
     },
-    InitialFileSystem(AccountId)
+    InitialFileSystem(AccountId),
+    /// Invocation parameters uploaded by the callers, too large to be sent in the requests
+    InvocationParameters(AccountId),
+    /// Invocation results too large to be returned in the responses
+    InvocationResults(AccountId),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
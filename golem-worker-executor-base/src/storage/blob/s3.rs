@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use crate::services::golem_config::S3BlobStorageConfig;
-use crate::storage::blob::{BlobMetadata, BlobStorage, BlobStorageNamespace, ExistsResult};
+use crate::storage::blob::{
+    no_local_files,     set_permissions_unsupported, BlobMetadata, BlobStorage, BlobStorageNamespace, ExistsResult,
+};
 use async_trait::async_trait;
 use aws_sdk_s3::config::{BehaviorVersion, Credentials, Region};
 use aws_sdk_s3::error::SdkError;
@@ -23,7 +25,8 @@ use aws_sdk_s3::operation::head_object::HeadObjectError;
 use aws_sdk_s3::primitives::ByteStream;
 use aws_sdk_s3::types::{Delete, Object, ObjectIdentifier};
 use bytes::Bytes;
-use golem_common::model::{ComponentId, OwnedWorkerId, Timestamp, WorkerId, WorkerMetadata};
+use golem_common::model::initial_file_system::InitialFilePermissions;
+use golem_common::model::{ComponentId, OwnedWorkerId, Timestamp, WorkerId};
 use golem_common::retries::with_retries_customized;
 use std::error::Error;
 use std::io;
@@ -73,7 +76,11 @@ impl S3BlobStorage {
             BlobStorageNamespace::CompressedOplog { level, .. } => {
                 &self.config.compressed_oplog_buckets[*level]
             }
-            BlobStorageNamespace::InitialFileSystem(_account_id) => &self.config.custom_data_bucket
+            BlobStorageNamespace::InitialFileSystem(_account_id) => &self.config.custom_data_bucket,
+            BlobStorageNamespace::InvocationParameters(_account_id)
+            | BlobStorageNamespace::InvocationResults(_account_id) => {
+                &self.config.custom_data_bucket
+            }
         }
     }
 
@@ -137,6 +144,21 @@ impl S3BlobStorage {
                         .to_path_buf()
                 }
             }
+            // Shares the bucket of the custom data, outside the accounts' prefixes
+            BlobStorageNamespace::InvocationParameters(account_id) => {
+                let account_id_string = account_id.to_string();
+                Path::new(&self.config.object_prefix)
+                    .join("invocation_parameters")
+                    .join(account_id_string)
+                    .to_path_buf()
+            }
+            BlobStorageNamespace::InvocationResults(account_id) => {
+                let account_id_string = account_id.to_string();
+                Path::new(&self.config.object_prefix)
+                    .join("invocation_results")
+                    .join(account_id_string)
+                    .to_path_buf()
+            }
         }
     }
 
@@ -190,6 +212,40 @@ impl S3BlobStorage {
         Ok(result)
     }
 
+    async fn has_objects_below(
+        &self,
+        target_label: &'static str,
+        op_label: &'static str,
+        bucket: &str,
+        prefix: &Path,
+    ) -> Result<bool, String> {
+        let prefix = format!("{}/", prefix.to_string_lossy().trim_end_matches('/'));
+        let response = with_retries_customized(
+            target_label,
+            op_label,
+            Some(format!("{bucket} - {prefix}")),
+            &self.config.retries,
+            &(self.client.clone(), bucket, prefix.clone()),
+            |(client, bucket, prefix)| {
+                Box::pin(async move {
+                    client
+                        .list_objects_v2()
+                        .bucket(*bucket)
+                        .prefix(prefix.clone())
+                        .max_keys(1)
+                        .send()
+                        .await
+                })
+            },
+            Self::is_list_objects_v2_error_retriable,
+            Self::as_loggable_generic,
+        )
+        .await
+        .map_err(|err| err.to_string())?;
+
+        Ok(!response.contents().is_empty())
+    }
+
     fn is_get_object_error_retriable(
         error: &SdkError<aws_sdk_s3::operation::get_object::GetObjectError>,
     ) -> bool {
@@ -550,19 +606,32 @@ impl BlobStorage for S3BlobStorage {
     }
 
     async fn get_file(&self, path: &Path) -> Result<io::Result<Vec<u8>>, String> {
-        todo!()
+        Ok(Err(no_local_files("S3", path)))
     }
 
-    async fn set_permissions(&self, path: &Path) -> Result<(), String> {
-        todo!()
+    async fn set_permissions(
+        &self,
+        _namespace: BlobStorageNamespace,
+        path: &Path,
+        permissions: InitialFilePermissions,
+    ) -> Result<(), String> {
+        set_permissions_unsupported("S3", path, permissions)
     }
 
-    async fn get_directory_entries(&self, root_path: &Path, path: &Path) -> Result<io::Result<Vec<(String, bool)>>, String> {
-        todo!()
+    async fn get_directory_entries(
+        &self,
+        _root_path: &Path,
+        path: &Path,
+    ) -> Result<io::Result<Vec<(String, bool)>>, String> {
+        Ok(Err(no_local_files("S3", path)))
     }
 
-    async fn get_file_or_directory(&self, base_path: &Path, path: &Path) -> Result<FileOrDirectoryResponse, String> {
-        todo!()
+    async fn get_file_or_directory(
+        &self,
+        _base_path: &Path,
+        path: &Path,
+    ) -> Result<FileOrDirectoryResponse, String> {
+        Err(no_local_files("S3", path).to_string())
     }
 
 
@@ -807,7 +876,17 @@ impl BlobStorage for S3BlobStorage {
                     match dir_marker_head_result {
                         Ok(_) => Ok(ExistsResult::Directory),
                         Err(SdkError::ServiceError(service_error)) => match service_error.err() {
-                            HeadObjectError::NotFound(_) => Ok(ExistsResult::DoesNotExist),
+                            HeadObjectError::NotFound(_) => {
+                                // Parent directories exist implicitly, as in the other storages
+                                if self
+                                    .has_objects_below(target_label, op_label, bucket, &key)
+                                    .await?
+                                {
+                                    Ok(ExistsResult::Directory)
+                                } else {
+                                    Ok(ExistsResult::DoesNotExist)
+                                }
+                            }
                             err => Err(err.to_string()),
                         },
                         Err(err) => Err(Self::error_string(&err)),
@@ -856,11 +935,69 @@ impl BlobStorage for S3BlobStorage {
         Ok(())
     }
 
-    async fn initialize_worker_ifs(&self, worker_metadata: WorkerMetadata) -> anyhow::Result<(), String> {
-        todo!()
+    /// S3 has no rename, but a single PUT is atomic: readers see either the previous object or
+    /// the complete new one, so there is no need to stage the upload through a temporary key.
+    async fn put_raw_staged(
+        &self,
+        target_label: &'static str,
+        op_label: &'static str,
+        namespace: BlobStorageNamespace,
+        path: &Path,
+        data: &[u8],
+    ) -> Result<(), String> {
+        self.put_raw(target_label, op_label, namespace, path, data)
+            .await
     }
 
-    async fn copy_dir_contents(&self, target_label: &'static str, source_label: &'static str, from: &Path, to: &Path, source: BlobStorageNamespace, target: BlobStorageNamespace) -> Result<(), String> {
-        todo!()
+    async fn copy_dir_contents(
+        &self,
+        target_label: &'static str,
+        op_label: &'static str,
+        from: &Path,
+        to: &Path,
+        source: BlobStorageNamespace,
+        target: BlobStorageNamespace,
+    ) -> Result<(), String> {
+        let bucket = self.bucket_of(&source);
+        let source_root = self.prefix_of(&source).join(from);
+
+        // Objects are listed recursively, including the markers of the nested directories
+        let relative_paths = self
+            .list_objects(target_label, op_label, bucket, &source_root)
+            .await?
+            .iter()
+            .flat_map(|obj| obj.key.as_ref())
+            .filter_map(|key| {
+                Path::new(key)
+                    .strip_prefix(&source_root)
+                    .ok()
+                    .map(|p| p.to_path_buf())
+            })
+            .collect::<Vec<_>>();
+
+        if relative_paths.is_empty() {
+            return Err(format!("Source directory {from:?} does not exist"));
+        }
+
+        for relative_path in relative_paths {
+            let data = self
+                .get_raw(
+                    target_label,
+                    op_label,
+                    source.clone(),
+                    &from.join(&relative_path),
+                )
+                .await?
+                .ok_or_else(|| format!("Entry not found: {:?}", from.join(&relative_path)))?;
+            self.put_raw(
+                target_label,
+                op_label,
+                target.clone(),
+                &to.join(&relative_path),
+                &data,
+            )
+            .await?;
+        }
+        Ok(())
     }
 }
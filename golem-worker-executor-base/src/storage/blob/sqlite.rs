@@ -16,12 +16,15 @@ use std::io;
 use std::path::{Path, PathBuf};
 use anyhow::Error;
 use crate::storage::{
-    blob::{BlobMetadata, BlobStorage, BlobStorageNamespace, ExistsResult},
+    blob::{
+        no_local_files, set_permissions_unsupported, BlobMetadata, BlobStorage,
+        BlobStorageNamespace, ExistsResult,
+    },
     sqlite_types::SqlitePool,
 };
 use async_trait::async_trait;
 use bytes::Bytes;
-use golem_common::model::WorkerMetadata;
+use golem_common::model::initial_file_system::InitialFilePermissions;
 use crate::services::blob_store::FileOrDirectoryResponse;
 
 #[derive(Debug)]
@@ -58,6 +61,12 @@ impl SqliteBlobStorage {
             BlobStorageNamespace::InitialFileSystem(account_id) =>{
                 format!("initial_file_system-{}", account_id.value)
             }
+            BlobStorageNamespace::InvocationParameters(account_id) => {
+                format!("invocation_parameters-{}", account_id.value)
+            }
+            BlobStorageNamespace::InvocationResults(account_id) => {
+                format!("invocation_results-{}", account_id.value)
+            }
         }
     }
 
@@ -125,20 +134,58 @@ impl BlobStorage for SqliteBlobStorage {
             .map_err(|err| err.to_string())
     }
 
+    async fn rename(
+        &self,
+        target_label: &'static str,
+        op_label: &'static str,
+        namespace: BlobStorageNamespace,
+        from: &Path,
+        to: &Path,
+    ) -> Result<(), String> {
+        let renamed = self
+            .pool
+            .with(target_label, op_label)
+            .rename_blob(
+                &Self::into_string(namespace),
+                &Self::to_string(from),
+                &Self::to_string(to),
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+        if renamed {
+            Ok(())
+        } else {
+            Err(format!("Entry not found: {:?}", from))
+        }
+    }
+
     async fn get_file(&self, path: &Path) -> Result<io::Result<Vec<u8>>, String> {
-        todo!()
+        Ok(Err(no_local_files("SQLite", path)))
     }
 
-    async fn set_permissions(&self, path: &Path) -> Result<(), String> {
-        todo!()
+    async fn set_permissions(
+        &self,
+        _namespace: BlobStorageNamespace,
+        path: &Path,
+        permissions: InitialFilePermissions,
+    ) -> Result<(), String> {
+        set_permissions_unsupported("SQLite", path, permissions)
     }
 
-    async fn get_directory_entries(&self, root_path: &Path, path: &Path) -> Result<io::Result<Vec<(String, bool)>> , String> {
-        todo!()
+    async fn get_directory_entries(
+        &self,
+        _root_path: &Path,
+        path: &Path,
+    ) -> Result<io::Result<Vec<(String, bool)>>, String> {
+        Ok(Err(no_local_files("SQLite", path)))
     }
 
-    async fn get_file_or_directory(&self, base_path: &Path, path: &Path) -> Result<FileOrDirectoryResponse, String> {
-        todo!()
+    async fn get_file_or_directory(
+        &self,
+        _base_path: &Path,
+        path: &Path,
+    ) -> Result<FileOrDirectoryResponse, String> {
+        Err(no_local_files("SQLite", path).to_string())
     }
 
 
@@ -198,11 +245,30 @@ impl BlobStorage for SqliteBlobStorage {
             .map_err(|err| err.to_string())
     }
 
-    async fn initialize_worker_ifs(&self, worker_metadata: WorkerMetadata) -> anyhow::Result<(), String> {
-        todo!()
-    }
-
-    async fn copy_dir_contents(&self, target_label: &'static str, source_label: &'static str, from: &Path, to: &Path,  source: BlobStorageNamespace, target: BlobStorageNamespace) -> Result<(), String> {
-        todo!()
+    async fn copy_dir_contents(
+        &self,
+        target_label: &'static str,
+        op_label: &'static str,
+        from: &Path,
+        to: &Path,
+        source: BlobStorageNamespace,
+        target: BlobStorageNamespace,
+    ) -> Result<(), String> {
+        let copied = self
+            .pool
+            .with(target_label, op_label)
+            .copy_dir(
+                &Self::into_string(source),
+                &Self::to_string(from),
+                &Self::into_string(target),
+                &Self::to_string(to),
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+        if copied {
+            Ok(())
+        } else {
+            Err(format!("Source directory {from:?} does not exist"))
+        }
     }
 }
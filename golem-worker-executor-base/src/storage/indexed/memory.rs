@@ -136,6 +136,26 @@ impl IndexedStorage for InMemoryIndexedStorage {
         }
     }
 
+    async fn replace(
+        &self,
+        _svc_name: &'static str,
+        _api_name: &'static str,
+        _entity_name: &'static str,
+        namespace: IndexedStorageNamespace,
+        key: &str,
+        id: u64,
+        value: &[u8],
+    ) -> Result<bool, String> {
+        let composite_key = Self::composite_key(namespace, key);
+        if let Some(mut entries) = self.data.get_mut(&composite_key) {
+            if let Some(entry) = entries.get_mut(&id) {
+                *entry = value.to_vec();
+                return Ok(true);
+            }
+        }
+        Err(format!("Entry {id} does not exist"))
+    }
+
     async fn length(
         &self,
         _svc_name: &'static str,
@@ -86,6 +86,19 @@ pub trait IndexedStorage: Debug {
         value: &[u8],
     ) -> Result<(), String>;
 
+    /// Replaces the value of an existing entry of the given key. Returns false if the storage does
+    /// not support modifying stored entries, and fails if the entry does not exist.
+    async fn replace(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        entity_name: &'static str,
+        namespace: IndexedStorageNamespace,
+        key: &str,
+        id: u64,
+        value: &[u8],
+    ) -> Result<bool, String>;
+
     /// Gets the number of entries in the index of the given key
     async fn length(
         &self,
@@ -343,6 +356,27 @@ impl<'a, S: ?Sized + IndexedStorage> LabelledEntityIndexedStorage<'a, S> {
             .await
     }
 
+    /// Replaces the value of an existing entry, returning false if the storage does not support it
+    pub async fn replace_raw(
+        &self,
+        namespace: IndexedStorageNamespace,
+        key: &str,
+        id: u64,
+        value: &[u8],
+    ) -> Result<bool, String> {
+        self.storage
+            .replace(
+                self.svc_name,
+                self.api_name,
+                self.entity_name,
+                namespace,
+                key,
+                id,
+                value,
+            )
+            .await
+    }
+
     /// Reads a closed range of entries from the index of the given key, deserializing each entry
     pub async fn read<V: Decode>(
         &self,
@@ -553,4 +587,5 @@ impl<'a, S: ?Sized + IndexedStorage> LabelledEntityIndexedStorage<'a, S> {
 pub enum IndexedStorageNamespace {
     OpLog,
     CompressedOpLog { level: usize },
+    DomainEvents,
 }
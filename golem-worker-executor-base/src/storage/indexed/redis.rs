@@ -37,6 +37,7 @@ impl RedisIndexedStorage {
             IndexedStorageNamespace::CompressedOpLog { level } => {
                 format!("worker:c{level}-oplog:{key}")
             }
+            IndexedStorageNamespace::DomainEvents => format!("worker:events:{key}"),
         }
     }
 
@@ -182,6 +183,20 @@ impl IndexedStorage for RedisIndexedStorage {
         Ok(())
     }
 
+    async fn replace(
+        &self,
+        _svc_name: &'static str,
+        _api_name: &'static str,
+        _entity_name: &'static str,
+        _namespace: IndexedStorageNamespace,
+        _key: &str,
+        _id: u64,
+        _value: &[u8],
+    ) -> Result<bool, String> {
+        // The entries of Redis streams are immutable
+        Ok(false)
+    }
+
     async fn length(
         &self,
         svc_name: &'static str,
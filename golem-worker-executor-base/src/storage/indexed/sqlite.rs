@@ -37,6 +37,7 @@ impl SqliteIndexedStorage {
             IndexedStorageNamespace::CompressedOpLog { level } => {
                 format!("worker-c{level}-oplog")
             }
+            IndexedStorageNamespace::DomainEvents => "worker-events".to_string(),
         }
     }
 }
@@ -108,6 +109,29 @@ impl IndexedStorage for SqliteIndexedStorage {
             .await
     }
 
+    async fn replace(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        _entity_name: &'static str,
+        namespace: IndexedStorageNamespace,
+        key: &str,
+        id: u64,
+        value: &[u8],
+    ) -> Result<bool, String> {
+        if self
+            .pool
+            .with(svc_name, api_name)
+            .replace(&Self::to_string(&namespace), key, id, value)
+            .map_err(|e| e.to_string())
+            .await?
+        {
+            Ok(true)
+        } else {
+            Err(format!("Entry {id} does not exist"))
+        }
+    }
+
     async fn length(
         &self,
         svc_name: &'static str,
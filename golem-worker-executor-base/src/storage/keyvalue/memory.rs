@@ -12,15 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::storage::keyvalue::{KeyValueStorage, KeyValueStorageNamespace};
+use crate::storage::keyvalue::{
+    KeyValueCheck, KeyValueStorage, KeyValueStorageNamespace, KeyValueWrite,
+};
 use async_trait::async_trait;
 use bytes::Bytes;
 use dashmap::mapref::entry::Entry;
 use dashmap::{DashMap, DashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub struct InMemoryKeyValueStorage {
     kvs: DashMap<String, Vec<u8>>,
+    expirations: DashMap<String, Instant>,
+    commit_lock: Mutex<()>,
     sets: DashMap<String, DashSet<Vec<u8>>>,
     sorted_sets: DashMap<String, Vec<(f64, Vec<u8>)>>,
 }
@@ -35,6 +41,8 @@ impl InMemoryKeyValueStorage {
     pub fn new() -> Self {
         Self {
             kvs: DashMap::new(),
+            expirations: DashMap::new(),
+            commit_lock: Mutex::new(()),
             sets: DashMap::new(),
             sorted_sets: DashMap::new(),
         }
@@ -55,6 +63,42 @@ impl InMemoryKeyValueStorage {
     fn composite_key(namespace: &KeyValueStorageNamespace, key: &str) -> String {
         format!("{namespace:?}/{key}")
     }
+
+    /// Removes the key if its time to live has passed
+    fn remove_if_expired(&self, composite_key: &str) {
+        let expired = self
+            .expirations
+            .remove_if(composite_key, |_, expires_at| *expires_at <= Instant::now())
+            .is_some();
+        if expired {
+            self.kvs.remove(composite_key);
+        }
+    }
+
+    fn live_value(&self, composite_key: &str) -> Option<Vec<u8>> {
+        self.remove_if_expired(composite_key);
+        self.kvs
+            .get(composite_key)
+            .map(|value| value.value().clone())
+    }
+
+    fn write(&self, composite_key: String, value: Vec<u8>, ttl: Option<Duration>) {
+        match ttl {
+            Some(ttl) => {
+                self.expirations
+                    .insert(composite_key.clone(), Instant::now() + ttl);
+            }
+            None => {
+                self.expirations.remove(&composite_key);
+            }
+        }
+        self.kvs.insert(composite_key, value);
+    }
+
+    fn remove(&self, composite_key: &str) {
+        self.expirations.remove(composite_key);
+        self.kvs.remove(composite_key);
+    }
 }
 
 #[async_trait]
@@ -68,8 +112,7 @@ impl KeyValueStorage for InMemoryKeyValueStorage {
         key: &str,
         value: &[u8],
     ) -> Result<(), String> {
-        self.kvs
-            .insert(Self::composite_key(&namespace, key), value.to_vec());
+        self.write(Self::composite_key(&namespace, key), value.to_vec(), None);
         Ok(())
     }
 
@@ -82,8 +125,7 @@ impl KeyValueStorage for InMemoryKeyValueStorage {
         pairs: &[(&str, &[u8])],
     ) -> Result<(), String> {
         for (key, value) in pairs {
-            self.kvs
-                .insert(Self::composite_key(&namespace, key), value.to_vec());
+            self.write(Self::composite_key(&namespace, key), value.to_vec(), None);
         }
         Ok(())
     }
@@ -97,7 +139,9 @@ impl KeyValueStorage for InMemoryKeyValueStorage {
         key: &str,
         value: &[u8],
     ) -> Result<bool, String> {
-        match self.kvs.entry(Self::composite_key(&namespace, key)) {
+        let composite_key = Self::composite_key(&namespace, key);
+        self.remove_if_expired(&composite_key);
+        match self.kvs.entry(composite_key) {
             Entry::Occupied(_) => Ok(false),
             Entry::Vacant(entry) => {
                 entry.insert(value.to_vec());
@@ -106,6 +150,50 @@ impl KeyValueStorage for InMemoryKeyValueStorage {
         }
     }
 
+    async fn set_with_ttl(
+        &self,
+        _svc_name: &'static str,
+        _api_name: &'static str,
+        _entity_name: &'static str,
+        namespace: KeyValueStorageNamespace,
+        key: &str,
+        value: &[u8],
+        ttl: Duration,
+    ) -> Result<(), String> {
+        self.write(
+            Self::composite_key(&namespace, key),
+            value.to_vec(),
+            Some(ttl),
+        );
+        Ok(())
+    }
+
+    async fn commit(
+        &self,
+        _svc_name: &'static str,
+        _api_name: &'static str,
+        _entity_name: &'static str,
+        namespace: KeyValueStorageNamespace,
+        checks: &[KeyValueCheck],
+        writes: &[KeyValueWrite],
+    ) -> Result<bool, String> {
+        let _guard = self.commit_lock.lock().unwrap();
+        for check in checks {
+            let current = self.live_value(&Self::composite_key(&namespace, &check.key));
+            if current != check.expected {
+                return Ok(false);
+            }
+        }
+        for write in writes {
+            let composite_key = Self::composite_key(&namespace, &write.key);
+            match &write.value {
+                Some(value) => self.write(composite_key, value.clone(), write.ttl),
+                None => self.remove(&composite_key),
+            }
+        }
+        Ok(true)
+    }
+
     async fn get(
         &self,
         _svc_name: &'static str,
@@ -114,10 +202,9 @@ impl KeyValueStorage for InMemoryKeyValueStorage {
         namespace: KeyValueStorageNamespace,
         key: &str,
     ) -> Result<Option<Bytes>, String> {
-        match self.kvs.get(&Self::composite_key(&namespace, key)) {
-            Some(value) => Ok(Some(Bytes::from(value.value().clone()))),
-            None => Ok(None),
-        }
+        Ok(self
+            .live_value(&Self::composite_key(&namespace, key))
+            .map(Bytes::from))
     }
 
     async fn get_many(
@@ -145,7 +232,7 @@ impl KeyValueStorage for InMemoryKeyValueStorage {
         namespace: KeyValueStorageNamespace,
         key: &str,
     ) -> Result<(), String> {
-        self.kvs.remove(&Self::composite_key(&namespace, key));
+        self.remove(&Self::composite_key(&namespace, key));
         Ok(())
     }
 
@@ -170,7 +257,9 @@ impl KeyValueStorage for InMemoryKeyValueStorage {
         namespace: KeyValueStorageNamespace,
         key: &str,
     ) -> Result<bool, String> {
-        Ok(self.kvs.contains_key(&Self::composite_key(&namespace, key)))
+        let composite_key = Self::composite_key(&namespace, key);
+        self.remove_if_expired(&composite_key);
+        Ok(self.kvs.contains_key(&composite_key))
     }
 
     async fn keys(
@@ -180,11 +269,16 @@ impl KeyValueStorage for InMemoryKeyValueStorage {
         namespace: KeyValueStorageNamespace,
     ) -> Result<Vec<String>, String> {
         let prefix = Self::composite_key(&namespace, "");
+        let now = Instant::now();
         Ok(self
             .kvs
             .iter()
             .filter_map(|item| {
-                if item.key().starts_with(&prefix) {
+                let expired = self
+                    .expirations
+                    .get(item.key())
+                    .is_some_and(|expires_at| *expires_at <= now);
+                if item.key().starts_with(&prefix) && !expired {
                     Some(item.key()[prefix.len()..].to_string())
                 } else {
                     None
@@ -22,6 +22,7 @@ use bytes::Bytes;
 use golem_common::model::AccountId;
 use golem_common::serialization::{deserialize, serialize};
 use std::fmt::Debug;
+use std::time::Duration;
 
 #[async_trait]
 pub trait KeyValueStorage: Debug {
@@ -54,6 +55,31 @@ pub trait KeyValueStorage: Debug {
         value: &[u8],
     ) -> Result<bool, String>;
 
+    /// Sets a value which expires after the given time to live. Setting the key again without a
+    /// time to live makes it persistent.
+    async fn set_with_ttl(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        entity_name: &'static str,
+        namespace: KeyValueStorageNamespace,
+        key: &str,
+        value: &[u8],
+        ttl: Duration,
+    ) -> Result<(), String>;
+
+    /// Atomically applies all the writes if all the checks hold, returning whether they were
+    /// applied
+    async fn commit(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        entity_name: &'static str,
+        namespace: KeyValueStorageNamespace,
+        checks: &[KeyValueCheck],
+        writes: &[KeyValueWrite],
+    ) -> Result<bool, String>;
+
     async fn get(
         &self,
         svc_name: &'static str,
@@ -366,6 +392,44 @@ impl<'a, S: ?Sized + KeyValueStorage> LabelledEntityKeyValueStorage<'a, S> {
             .await
     }
 
+    pub async fn set_raw_with_ttl(
+        &self,
+        namespace: KeyValueStorageNamespace,
+        key: &str,
+        value: &[u8],
+        ttl: Duration,
+    ) -> Result<(), String> {
+        self.storage
+            .set_with_ttl(
+                self.svc_name,
+                self.api_name,
+                self.entity_name,
+                namespace,
+                key,
+                value,
+                ttl,
+            )
+            .await
+    }
+
+    pub async fn commit_raw(
+        &self,
+        namespace: KeyValueStorageNamespace,
+        checks: &[KeyValueCheck],
+        writes: &[KeyValueWrite],
+    ) -> Result<bool, String> {
+        self.storage
+            .commit(
+                self.svc_name,
+                self.api_name,
+                self.entity_name,
+                namespace,
+                checks,
+                writes,
+            )
+            .await
+    }
+
     pub async fn get<V: Decode>(
         &self,
         namespace: KeyValueStorageNamespace,
@@ -610,3 +674,19 @@ pub enum KeyValueStorageNamespace {
         bucket: String,
     },
 }
+
+/// A condition of a key-value transaction: the key must have the expected value, or must not
+/// exist if no value is expected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyValueCheck {
+    pub key: String,
+    pub expected: Option<Vec<u8>>,
+}
+
+/// A write of a key-value transaction, deleting the key if there is no value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyValueWrite {
+    pub key: String,
+    pub value: Option<Vec<u8>>,
+    pub ttl: Option<Duration>,
+}
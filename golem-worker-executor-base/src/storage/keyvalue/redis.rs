@@ -14,22 +14,145 @@
 
 use async_trait::async_trait;
 use bytes::Bytes;
-use fred::types::SetOptions;
+use fred::types::{Expiration, RedisValue, SetOptions};
 use golem_common::metrics::redis::{record_redis_deserialized_size, record_redis_serialized_size};
 use golem_common::redis::RedisPool;
 use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::OnceCell;
 use tracing::debug;
 
-use crate::storage::keyvalue::{KeyValueStorage, KeyValueStorageNamespace};
+use crate::storage::keyvalue::{
+    KeyValueCheck, KeyValueStorage, KeyValueStorageNamespace, KeyValueWrite,
+};
+
+/// The first Redis version supporting expiring hash fields, which are needed for expiring keys in
+/// the namespaces stored as hashes
+const HASH_FIELD_EXPIRATION_VERSION: (u32, u32) = (7, 4);
+
+// Every script only touches the keys passed in KEYS
+
+const HASH_SET_WITH_TTL_SCRIPT: &str = r#"
+redis.call('HSET', KEYS[1], ARGV[1], ARGV[2])
+redis.call('HPEXPIRE', KEYS[1], ARGV[3], 'FIELDS', 1, ARGV[1])
+return 1
+"#;
+
+// KEYS[1] is the hash, ARGV[1] is the number of checks, followed by (field, has expected value,
+// expected value) for each check and (field, operation, value, ttl in milliseconds) for each write
+const HASH_COMMIT_SCRIPT: &str = r#"
+local i = 2
+for _ = 1, tonumber(ARGV[1]) do
+    local current = redis.call('HGET', KEYS[1], ARGV[i])
+    if ARGV[i + 1] == '1' then
+        if current ~= ARGV[i + 2] then
+            return 0
+        end
+    elseif current ~= false then
+        return 0
+    end
+    i = i + 3
+end
+while i <= #ARGV do
+    if ARGV[i + 1] == 'set' then
+        -- Deleting the field first drops its previous expiration
+        redis.call('HDEL', KEYS[1], ARGV[i])
+        redis.call('HSET', KEYS[1], ARGV[i], ARGV[i + 2])
+        if ARGV[i + 3] ~= '0' then
+            redis.call('HPEXPIRE', KEYS[1], ARGV[i + 3], 'FIELDS', 1, ARGV[i])
+        end
+    else
+        redis.call('HDEL', KEYS[1], ARGV[i])
+    end
+    i = i + 4
+end
+return 1
+"#;
+
+// KEYS are the checked keys followed by the written keys, ARGV[1] is the number of checks,
+// followed by (has expected value, expected value) for each check and (operation, value, ttl in
+// milliseconds) for each write
+const KEYS_COMMIT_SCRIPT: &str = r#"
+local checks = tonumber(ARGV[1])
+local i = 2
+for k = 1, checks do
+    local current = redis.call('GET', KEYS[k])
+    if ARGV[i] == '1' then
+        if current ~= ARGV[i + 1] then
+            return 0
+        end
+    elseif current ~= false then
+        return 0
+    end
+    i = i + 2
+end
+for k = checks + 1, #KEYS do
+    if ARGV[i] == 'set' then
+        if ARGV[i + 2] ~= '0' then
+            redis.call('SET', KEYS[k], ARGV[i + 1], 'PX', ARGV[i + 2])
+        else
+            redis.call('SET', KEYS[k], ARGV[i + 1])
+        end
+    else
+        redis.call('DEL', KEYS[k])
+    end
+    i = i + 3
+end
+return 1
+"#;
 
 #[derive(Debug)]
 pub struct RedisKeyValueStorage {
     redis: RedisPool,
+    hash_field_expiration: OnceCell<bool>,
 }
 
 impl RedisKeyValueStorage {
     pub fn new(redis: RedisPool) -> Self {
-        Self { redis }
+        Self {
+            redis,
+            hash_field_expiration: OnceCell::new(),
+        }
+    }
+
+    /// Whether the server supports expiring keys in the namespaces stored as hashes, which
+    /// requires Redis 7.4 or later
+    pub async fn supports_hash_field_expiration(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+    ) -> Result<bool, String> {
+        self.hash_field_expiration
+            .get_or_try_init(|| async {
+                let version = self
+                    .redis
+                    .with(svc_name, api_name)
+                    .info_server_version()
+                    .await
+                    .map_err(|redis_err| redis_err.to_string())?;
+                debug!("Redis server version: {:?}", version);
+                Ok(version.is_some_and(|version| version >= HASH_FIELD_EXPIRATION_VERSION))
+            })
+            .await
+            .copied()
+    }
+
+    async fn ensure_hash_field_expiration(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+    ) -> Result<(), String> {
+        if self
+            .supports_hash_field_expiration(svc_name, api_name)
+            .await?
+        {
+            Ok(())
+        } else {
+            let (major, minor) = HASH_FIELD_EXPIRATION_VERSION;
+            Err(format!(
+                "Expiring keys in buckets require Redis {major}.{minor} or later"
+            ))
+        }
     }
 
     fn use_hash(namespace: &KeyValueStorageNamespace) -> Option<String> {
@@ -42,6 +165,36 @@ impl RedisKeyValueStorage {
             }
         }
     }
+
+    // A zero time to live would mean no expiration in the scripts
+    fn ttl_millis(ttl: Duration) -> String {
+        ttl.as_millis().max(1).to_string()
+    }
+
+    fn check_args(check: &KeyValueCheck) -> [RedisValue; 2] {
+        match &check.expected {
+            Some(expected) => [
+                RedisValue::from("1"),
+                RedisValue::Bytes(Bytes::copy_from_slice(expected)),
+            ],
+            None => [RedisValue::from("0"), RedisValue::from("")],
+        }
+    }
+
+    fn write_args(write: &KeyValueWrite) -> [RedisValue; 3] {
+        match &write.value {
+            Some(value) => [
+                RedisValue::from("set"),
+                RedisValue::Bytes(Bytes::copy_from_slice(value)),
+                RedisValue::from(write.ttl.map(Self::ttl_millis).unwrap_or("0".to_string())),
+            ],
+            None => [
+                RedisValue::from("del"),
+                RedisValue::from(""),
+                RedisValue::from("0"),
+            ],
+        }
+    }
 }
 
 #[async_trait]
@@ -139,6 +292,110 @@ impl KeyValueStorage for RedisKeyValueStorage {
         }
     }
 
+    async fn set_with_ttl(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        entity_name: &'static str,
+        namespace: KeyValueStorageNamespace,
+        key: &str,
+        value: &[u8],
+        ttl: Duration,
+    ) -> Result<(), String> {
+        record_redis_serialized_size(svc_name, entity_name, value.len());
+
+        match Self::use_hash(&namespace) {
+            Some(ns) => {
+                self.ensure_hash_field_expiration(svc_name, api_name)
+                    .await?;
+                let _: i64 = self
+                    .redis
+                    .with(svc_name, api_name)
+                    .eval(
+                        HASH_SET_WITH_TTL_SCRIPT,
+                        vec![ns],
+                        vec![
+                            RedisValue::from(key),
+                            RedisValue::Bytes(Bytes::copy_from_slice(value)),
+                            RedisValue::from(Self::ttl_millis(ttl)),
+                        ],
+                    )
+                    .await
+                    .map_err(|redis_err| redis_err.to_string())?;
+                Ok(())
+            }
+            None => self
+                .redis
+                .with(svc_name, api_name)
+                .set(
+                    key,
+                    value,
+                    Some(Expiration::PX(ttl.as_millis().max(1) as i64)),
+                    None,
+                    false,
+                )
+                .await
+                .map_err(|redis_err| redis_err.to_string()),
+        }
+    }
+
+    async fn commit(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        entity_name: &'static str,
+        namespace: KeyValueStorageNamespace,
+        checks: &[KeyValueCheck],
+        writes: &[KeyValueWrite],
+    ) -> Result<bool, String> {
+        for value in writes.iter().filter_map(|write| write.value.as_ref()) {
+            record_redis_serialized_size(svc_name, entity_name, value.len());
+        }
+
+        let mut args = vec![RedisValue::from(checks.len().to_string())];
+        let (script, keys) = match Self::use_hash(&namespace) {
+            Some(ns) => {
+                if writes.iter().any(|write| write.ttl.is_some()) {
+                    self.ensure_hash_field_expiration(svc_name, api_name)
+                        .await?;
+                }
+                for check in checks {
+                    args.push(RedisValue::from(check.key.as_str()));
+                    args.extend(Self::check_args(check));
+                }
+                for write in writes {
+                    args.push(RedisValue::from(write.key.as_str()));
+                    args.extend(Self::write_args(write));
+                }
+                (HASH_COMMIT_SCRIPT, vec![ns])
+            }
+            None => {
+                for check in checks {
+                    args.extend(Self::check_args(check));
+                }
+                for write in writes {
+                    args.extend(Self::write_args(write));
+                }
+                let keys = checks
+                    .iter()
+                    .map(|check| check.key.clone())
+                    .chain(writes.iter().map(|write| write.key.clone()))
+                    .collect();
+                (KEYS_COMMIT_SCRIPT, keys)
+            }
+        };
+
+        let committed: i64 = self
+            .redis
+            .with(svc_name, api_name)
+            .eval(script, keys, args)
+            .await
+            .map_err(|redis_err| redis_err.to_string())?;
+
+        debug!("commit result: {:?}", committed);
+        Ok(committed == 1)
+    }
+
     async fn get(
         &self,
         svc_name: &'static str,
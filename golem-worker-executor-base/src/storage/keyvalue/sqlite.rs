@@ -13,12 +13,13 @@
 // limitations under the License.
 
 use crate::storage::{
-    keyvalue::{KeyValueStorage, KeyValueStorageNamespace},
+    keyvalue::{KeyValueCheck, KeyValueStorage, KeyValueStorageNamespace, KeyValueWrite},
     sqlite_types::SqlitePool,
 };
 use async_trait::async_trait;
 use bytes::Bytes;
 use std::fmt;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct SqliteKeyValueStorage {
@@ -84,6 +85,39 @@ impl KeyValueStorage for SqliteKeyValueStorage {
             .map_err(|e| e.to_string())
     }
 
+    async fn set_with_ttl(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        _entity_name: &'static str,
+        namespace: KeyValueStorageNamespace,
+        key: &str,
+        value: &[u8],
+        ttl: Duration,
+    ) -> Result<(), String> {
+        self.pool
+            .with(svc_name, api_name)
+            .set_with_ttl(&Self::to_string(&namespace), key, value, ttl)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn commit(
+        &self,
+        svc_name: &'static str,
+        api_name: &'static str,
+        _entity_name: &'static str,
+        namespace: KeyValueStorageNamespace,
+        checks: &[KeyValueCheck],
+        writes: &[KeyValueWrite],
+    ) -> Result<bool, String> {
+        self.pool
+            .with(svc_name, api_name)
+            .commit(&Self::to_string(&namespace), checks, writes)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     async fn get(
         &self,
         svc_name: &'static str,
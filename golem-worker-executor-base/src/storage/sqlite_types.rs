@@ -26,9 +26,10 @@ use sqlx::SqlitePool as SqlitePoolx;
 use sqlx::{Error, Sqlite};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use super::blob::{BlobMetadata, ExistsResult};
+use super::keyvalue::{KeyValueCheck, KeyValueWrite};
 
 #[derive(Clone, Debug)]
 pub struct SqlitePool {
@@ -61,6 +62,7 @@ impl SqlitePool {
                 key TEXT NOT NULL,         -- The key to store
                 value BLOB NOT NULL,       -- The value to store
                 namespace TEXT NOT NULL,   -- The namespace of  the key value 
+                expires_at INTEGER,        -- Unix timestamp in milliseconds after which the key no longer exists
                 PRIMARY KEY(key, namespace)     -- Avoid duplicate key values in a namespace
             );
             "#,
@@ -68,6 +70,18 @@ impl SqlitePool {
         .execute(pool)
         .await?;
 
+        // Databases created before keys could expire don't have the expires_at column yet
+        let has_expires_at =
+            sqlx::query("SELECT 1 FROM pragma_table_info('kv_storage') WHERE name = 'expires_at';")
+                .fetch_optional(pool)
+                .await?
+                .is_some();
+        if !has_expires_at {
+            sqlx::query("ALTER TABLE kv_storage ADD COLUMN expires_at INTEGER;")
+                .execute(pool)
+                .await?;
+        }
+
         sqlx::query(
             r#"
               CREATE TABLE IF NOT EXISTS set_storage (
@@ -224,6 +238,14 @@ impl DBMetadata {
     }
 }
 
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn expires_at(ttl: Duration) -> i64 {
+    now_millis().saturating_add(ttl.as_millis().try_into().unwrap_or(i64::MAX))
+}
+
 pub struct SqliteLabelledApi {
     svc_name: &'static str,
     api_name: &'static str,
@@ -312,6 +334,13 @@ impl SqliteLabelledApi {
         key: &str,
         value: &[u8],
     ) -> Result<bool, Error> {
+        sqlx::query("DELETE FROM kv_storage WHERE key = ? AND namespace = ? AND expires_at <= ?;")
+            .bind(key)
+            .bind(namespace)
+            .bind(now_millis())
+            .execute(&self.pool)
+            .await?;
+
         let existing: Option<(i32,)> =
             sqlx::query_as::<_, (i32,)>("SELECT 1 FROM kv_storage WHERE key = ? AND namespace = ?")
                 .bind(key)
@@ -331,10 +360,82 @@ impl SqliteLabelledApi {
             .map(|_| existing.is_none())
     }
 
+    pub async fn set_with_ttl(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: &[u8],
+        ttl: Duration,
+    ) -> Result<(), Error> {
+        let query = sqlx::query(
+            "INSERT OR REPLACE INTO kv_storage (key, value, namespace, expires_at) VALUES (?, ?, ?, ?);",
+        )
+        .bind(key)
+        .bind(value)
+        .bind(namespace)
+        .bind(expires_at(ttl));
+
+        let start = Instant::now();
+        self.record(start, "set_with_ttl", query.execute(&self.pool).await)
+            .map(|_| ())
+    }
+
+    pub async fn commit(
+        &self,
+        namespace: &str,
+        checks: &[KeyValueCheck],
+        writes: &[KeyValueWrite],
+    ) -> Result<bool, Error> {
+        let mut tx = self.pool.begin().await?;
+        let start = Instant::now();
+
+        for check in checks {
+            let current: Option<DBValue> = sqlx::query_as(
+                "SELECT value FROM kv_storage WHERE key = ? AND namespace = ? AND (expires_at IS NULL OR expires_at > ?);",
+            )
+            .bind(&check.key)
+            .bind(namespace)
+            .bind(now_millis())
+            .fetch_optional(&mut *tx)
+            .await?;
+            if current.map(|value| value.into_bytes().to_vec()) != check.expected {
+                let result = tx.rollback().await;
+                return self.record(start, "commit", result).map(|_| false);
+            }
+        }
+        for write in writes {
+            match &write.value {
+                Some(value) => {
+                    sqlx::query(
+                        "INSERT OR REPLACE INTO kv_storage (key, value, namespace, expires_at) VALUES (?, ?, ?, ?);",
+                    )
+                    .bind(&write.key)
+                    .bind(value)
+                    .bind(namespace)
+                    .bind(write.ttl.map(expires_at))
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                None => {
+                    sqlx::query("DELETE FROM kv_storage WHERE key = ? AND namespace = ?;")
+                        .bind(&write.key)
+                        .bind(namespace)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+            }
+        }
+        let result = tx.commit().await;
+        self.record(start, "commit", result).map(|_| true)
+    }
+
     pub async fn get(&self, namespace: &str, key: &str) -> Result<Option<Bytes>, Error> {
-        let query = sqlx::query_as("SELECT value FROM kv_storage WHERE key = ? AND namespace = ?;")
-            .bind(key)
-            .bind(namespace);
+        let query = sqlx::query_as(
+            "SELECT value FROM kv_storage WHERE key = ? AND namespace = ? AND (expires_at IS NULL OR expires_at > ?);",
+        )
+        .bind(key)
+        .bind(namespace)
+        .bind(now_millis());
         let start = Instant::now();
         self.record(start, "get", self.fetch_optional::<DBValue>(query).await)
             .map(|r| r.map(|op| op.into_bytes()))
@@ -348,7 +449,7 @@ impl SqliteLabelledApi {
         let placeholders = keys.iter().map(|_| "?").collect::<Vec<_>>().join(",");
 
         let statement = format!(
-            "SELECT key, value FROM kv_storage WHERE key IN ({}) AND namespace = ?;",
+            "SELECT key, value FROM kv_storage WHERE key IN ({}) AND namespace = ? AND (expires_at IS NULL OR expires_at > ?);",
             placeholders
         );
         let mut query = sqlx::query_as(&statement);
@@ -356,7 +457,7 @@ impl SqliteLabelledApi {
         for key in &keys {
             query = query.bind(key);
         }
-        query = query.bind(namespace);
+        query = query.bind(namespace).bind(now_millis());
         let start = Instant::now();
         let results = self.record(start, "get_many", self.fetch_all::<DBKeyValue>(query).await)?;
 
@@ -398,9 +499,12 @@ impl SqliteLabelledApi {
     }
 
     pub async fn exists(&self, namespace: &str, key: &str) -> Result<bool, Error> {
-        let query = sqlx::query("SELECT 1 FROM kv_storage WHERE key = ? AND namespace = ?")
-            .bind(key)
-            .bind(namespace);
+        let query = sqlx::query(
+            "SELECT 1 FROM kv_storage WHERE key = ? AND namespace = ? AND (expires_at IS NULL OR expires_at > ?)",
+        )
+        .bind(key)
+        .bind(namespace)
+        .bind(now_millis());
 
         let start = Instant::now();
         self.record(start, "exists", query.fetch_optional(&self.pool).await)
@@ -408,8 +512,11 @@ impl SqliteLabelledApi {
     }
 
     pub async fn keys(&self, namespace: &str) -> Result<Vec<String>, Error> {
-        let query =
-            sqlx::query_as("SELECT key FROM kv_storage WHERE namespace = ?;").bind(namespace);
+        let query = sqlx::query_as(
+            "SELECT key FROM kv_storage WHERE namespace = ? AND (expires_at IS NULL OR expires_at > ?);",
+        )
+        .bind(namespace)
+        .bind(now_millis());
 
         let start = Instant::now();
         self.record(start, "keys", self.fetch_all::<(String,)>(query).await)
@@ -625,6 +732,26 @@ impl SqliteLabelledApi {
             .map(|_| ())
     }
 
+    pub async fn replace(
+        &self,
+        namespace: &str,
+        key: &str,
+        id: u64,
+        value: &[u8],
+    ) -> Result<bool, Error> {
+        let query = sqlx::query(
+            "UPDATE index_storage SET value = ? WHERE namespace = ? AND key = ? AND id = ?;",
+        )
+        .bind(value)
+        .bind(namespace)
+        .bind(key)
+        .bind(sqlx::types::Json(id));
+
+        let start = Instant::now();
+        self.record(start, "replace", query.execute(&self.pool).await)
+            .map(|result| result.rows_affected() > 0)
+    }
+
     pub async fn length(&self, namespace: &str, key: &str) -> Result<u64, Error> {
         let query = sqlx::query_as::<_, (i64,)>(
             "SELECT COUNT(*) FROM index_storage WHERE namespace = ? AND key = ?;",
@@ -793,6 +920,23 @@ impl SqliteLabelledApi {
             .map(|_| ())
     }
 
+    /// Atomically moves a blob to a new path, replacing the blob already stored there.
+    /// Returns `false` if there was no blob at `from`.
+    pub async fn rename_blob(&self, namespace: &str, from: &str, to: &str) -> Result<bool, Error> {
+        let query = sqlx::query(
+            r#"
+                UPDATE OR REPLACE blob_storage SET path = ?, last_modified_at = CURRENT_TIMESTAMP
+                WHERE namespace = ? AND path = ? AND is_directory = FALSE;
+            "#,
+        )
+        .bind(to)
+        .bind(namespace)
+        .bind(from);
+        let start = Instant::now();
+        self.record(start, "rename_blob", query.execute(&self.pool).await)
+            .map(|result| result.rows_affected() > 0)
+    }
+
     pub async fn create_dir(&self, namespace: &str, path: &str) -> Result<(), Error> {
         let query = sqlx::query(
             r#"
@@ -826,6 +970,37 @@ impl SqliteLabelledApi {
             .map(|r| r.into_iter().map(|row| PathBuf::from(row.0)).collect())
     }
 
+    /// Copies the directory and every blob below it to another path, which can be in another
+    /// namespace. Returns `false` if there was nothing to copy.
+    pub async fn copy_dir(
+        &self,
+        from_namespace: &str,
+        from: &str,
+        to_namespace: &str,
+        to: &str,
+    ) -> Result<bool, Error> {
+        let from = from.trim_end_matches('/');
+        let to = to.trim_end_matches('/');
+        let query = sqlx::query(
+            r#"
+                INSERT INTO blob_storage (namespace, path, value, size, is_directory)
+                SELECT ?, ? || substr(path, ?), value, size, is_directory
+                FROM blob_storage WHERE namespace = ? AND (path = ? OR path LIKE ?)
+                ON CONFLICT(namespace, path) DO UPDATE SET value = excluded.value, size = excluded.size,
+                    is_directory = excluded.is_directory, last_modified_at = CURRENT_TIMESTAMP;
+            "#,
+        )
+        .bind(to_namespace)
+        .bind(to)
+        .bind(from.chars().count() as i64 + 1)
+        .bind(from_namespace)
+        .bind(from)
+        .bind(format!("{from}/%"));
+        let start = Instant::now();
+        self.record(start, "copy_dir", query.execute(&self.pool).await)
+            .map(|result| result.rows_affected() > 0)
+    }
+
     pub async fn delete_dir(&self, namespace: &str, path: &str) -> Result<(), Error> {
         let path_like = format!("{}%", path);
         let query = sqlx::query("DELETE FROM blob_storage WHERE namespace = ?  AND path LIKE ?;")
@@ -837,11 +1012,20 @@ impl SqliteLabelledApi {
     }
 
     pub async fn exists_blob(&self, namespace: &str, path: &str) -> Result<ExistsResult, Error> {
+        // Parent directories exist implicitly, as in the other storages
         let query = sqlx::query_as(
-            "SELECT is_directory FROM blob_storage WHERE namespace = ? AND path = ? LIMIT 1;",
+            r#"
+                SELECT is_directory FROM (
+                    SELECT is_directory, 0 AS rank FROM blob_storage WHERE namespace = ? AND path = ?
+                    UNION ALL
+                    SELECT TRUE, 1 FROM blob_storage WHERE namespace = ? AND path LIKE ?
+                ) ORDER BY rank LIMIT 1;
+            "#,
         )
         .bind(namespace)
-        .bind(path);
+        .bind(path)
+        .bind(namespace)
+        .bind(format!("{}/%", path.trim_end_matches('/')));
 
         let start = Instant::now();
         self.record(start, "exists_blob", query.fetch_optional(&self.pool).await)
@@ -12,9 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::future::Future;
 use std::mem;
 use std::ops::DerefMut;
+use std::panic::AssertUnwindSafe;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
@@ -23,30 +26,44 @@ use crate::durable_host::recover_stderr_logs;
 use crate::error::{GolemError, WorkerOutOfMemory};
 use crate::function_result_interpreter::interpret_function_results;
 use crate::invocation::{invoke_worker, InvokeResult};
+use crate::metrics::idempotency::{
+    record_deduplicated_invocation, record_expired_idempotency_keys,
+};
+use crate::metrics::workers::record_worker_task_panic;
+use crate::model::cascade::cascade_order;
+use crate::model::redaction::{ensure_redactable, redact_oplog_entry};
 use crate::model::{ExecutionStatus, InterruptKind, LookupResult, TrapType, WorkerConfig};
+use crate::profiler::WorkerProfiler;
 use crate::services::component::ComponentMetadata;
 use crate::services::events::Event;
 use crate::services::oplog::{CommitLevel, Oplog, OplogOps};
-use crate::services::worker_event::{WorkerEventService, WorkerEventServiceDefault};
+use crate::services::worker_event::{
+    PersistentEventHistory, WorkerEventService, WorkerEventServiceDefault,
+};
+use crate::services::worker_proxy::WorkerProxyError;
 use crate::services::{
-    All, HasActiveWorkers, HasAll, HasBlobStoreService, HasComponentService, HasConfig, HasEvents,
-    HasExtraDeps, HasKeyValueService, HasOplog, HasOplogService, HasPromiseService, HasRpc,
-    HasSchedulerService, HasWasmtimeEngine, HasWorker, HasWorkerEnumerationService, HasWorkerProxy,
-    HasWorkerService, UsesAllDeps,
+    All, HasActiveWorkers, HasAll, HasBlobStoreService, HasComponentService, HasConfig,
+    HasDomainEventService, HasEvents, HasExtraDeps, HasFeatureFlagService, HasInferenceService,
+    HasKeyValueService, HasOplog, HasOplogService, HasPromiseService, HasRpc, HasSchedulerService,
+    HasWasmtimeEngine, HasWorker, HasWorkerEnumerationService, HasWorkerProxy, HasWorkerService,
+    UsesAllDeps,
 };
 use crate::workerctx::{PublicWorkerIo, WorkerCtx};
 use anyhow::anyhow;
+use futures::FutureExt;
 use golem_common::config::RetryConfig;
 use golem_common::model::oplog::{
     OplogEntry, OplogIndex, TimestampedUpdateDescription, UpdateDescription, WorkerError,
     WorkerResourceId,
 };
 use golem_common::model::regions::{DeletedRegions, DeletedRegionsBuilder, OplogRegion};
+use golem_common::model::worker_defaults::idle_timeout_from_env;
+use golem_common::model::worker_status_event::{WorkerStatusEvent, WorkerStatusEventKind};
 use golem_common::model::{exports, ComponentType};
 use golem_common::model::{
-    ComponentVersion, FailedUpdateRecord, IdempotencyKey, OwnedWorkerId, SuccessfulUpdateRecord,
-    Timestamp, TimestampedWorkerInvocation, WorkerId, WorkerInvocation, WorkerMetadata,
-    WorkerResourceDescription, WorkerStatus, WorkerStatusRecord,
+    CallerIdentity, ComponentVersion, FailedUpdateRecord, IdempotencyKey, OwnedWorkerId,
+    SuccessfulUpdateRecord, Timestamp, TimestampedWorkerInvocation, WorkerDependent, WorkerId,
+    WorkerInvocation, WorkerMetadata, WorkerResourceDescription, WorkerStatus, WorkerStatusRecord,
 };
 use golem_common::retries::get_delay;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
@@ -58,7 +75,7 @@ use tokio::sync::{Mutex, MutexGuard, OwnedSemaphorePermit};
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, span, warn, Instrument, Level};
 use wasmtime::component::Instance;
-use wasmtime::{AsContext, Store, UpdateDeadline};
+use wasmtime::{AsContext, Store, UpdateDeadline, WasmBacktrace};
 
 /// Represents worker that may be running or suspended.
 ///
@@ -83,6 +100,7 @@ pub struct Worker<Ctx: WorkerCtx> {
     queue: Arc<RwLock<VecDeque<TimestampedWorkerInvocation>>>,
     pending_updates: Arc<RwLock<VecDeque<TimestampedUpdateDescription>>>,
     invocation_results: Arc<RwLock<HashMap<IdempotencyKey, InvocationResult>>>,
+    result_mappings: RwLock<ResultMappings>,
     execution_status: Arc<RwLock<ExecutionStatus>>,
     initial_worker_metadata: WorkerMetadata,
     stopping: AtomicBool,
@@ -90,6 +108,7 @@ pub struct Worker<Ctx: WorkerCtx> {
 
     instance: Arc<Mutex<WorkerInstance>>,
     oom_retry_config: RetryConfig,
+    profiler: Arc<WorkerProfiler>,
 }
 
 impl<Ctx: WorkerCtx> HasOplog for Worker<Ctx> {
@@ -157,6 +176,144 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         Ok(worker)
     }
 
+    /// Deletes a worker and all its storage, interrupting and stopping it first if it is running
+    pub async fn delete<T>(deps: &T, owned_worker_id: &OwnedWorkerId) -> Result<(), GolemError>
+    where
+        T: HasAll<Ctx> + Send + Sync + Clone + 'static,
+    {
+        let metadata = deps.worker_service().get(owned_worker_id).await;
+        let worker_status =
+            Ctx::compute_latest_worker_status(deps, owned_worker_id, &metadata).await?;
+
+        let should_interrupt = match &worker_status.status {
+            WorkerStatus::Idle
+            | WorkerStatus::Running
+            | WorkerStatus::Suspended
+            | WorkerStatus::Retrying => true,
+            WorkerStatus::Exited | WorkerStatus::Failed | WorkerStatus::Interrupted => false,
+        };
+
+        if should_interrupt {
+            let worker =
+                Self::get_or_create_suspended(deps, owned_worker_id, None, None, None, None)
+                    .await?;
+
+            if let Some(mut await_interrupted) =
+                worker.set_interrupting(InterruptKind::Interrupt).await
+            {
+                await_interrupted.recv().await.unwrap();
+            }
+
+            worker.stop().await;
+        }
+
+        Ctx::on_worker_deleted(deps, &owned_worker_id.worker_id).await?;
+        deps.worker_service().remove(owned_worker_id).await;
+        deps.active_workers().remove(&owned_worker_id.worker_id);
+
+        if let Some(event_history_dir) = &deps.config().limits.event_history_dir {
+            if let Err(err) =
+                PersistentEventHistory::delete(event_history_dir, owned_worker_id).await
+            {
+                warn!("Failed to delete the persisted events of the worker: {err}");
+            }
+        }
+
+        if let Some(metadata) = metadata {
+            publish_worker_status_event(
+                deps,
+                WorkerStatusEventKind::Deleted,
+                &WorkerMetadata {
+                    last_known_status: worker_status,
+                    ..metadata
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Deletes a worker together with the resources it owns: the child workers it spawned (and
+    /// their own resources), its promises, its scheduled invocations and its files. Returns the
+    /// descriptions of the removed resources. With `dry_run` nothing gets deleted, only the
+    /// resources which would be removed are returned.
+    ///
+    /// The dependents are deleted depth-first, before the worker owning them, as the ownership
+    /// records are removed together with the worker.
+    pub async fn delete_cascading<T>(
+        deps: &T,
+        owned_worker_id: &OwnedWorkerId,
+        dry_run: bool,
+    ) -> Result<Vec<String>, GolemError>
+    where
+        T: HasAll<Ctx> + Send + Sync + Clone + 'static,
+    {
+        let account_id = &owned_worker_id.account_id;
+        let order = cascade_order(&owned_worker_id.worker_id, |worker_id| async move {
+            deps.worker_service()
+                .get_dependents(&OwnedWorkerId::new(account_id, &worker_id))
+                .await
+        })
+        .await;
+
+        let mut removed = Vec::new();
+        for (worker_id, dependents) in order {
+            let owned_dependent_id = OwnedWorkerId::new(account_id, &worker_id);
+            for dependent in dependents {
+                match &dependent {
+                    // Deleted before as they come earlier in the order
+                    WorkerDependent::ChildWorker(_) => {}
+                    WorkerDependent::Promise(promise_id) => {
+                        if !dry_run {
+                            deps.promise_service().delete(promise_id.clone()).await;
+                        }
+                    }
+                    WorkerDependent::Schedule(schedule_id) => {
+                        if !dry_run {
+                            deps.scheduler_service().cancel(schedule_id.clone()).await;
+                        }
+                    }
+                }
+                removed.push(dependent.to_string());
+            }
+
+            let has_files = deps
+                .blob_store_service()
+                .has_worker_files(&owned_dependent_id)
+                .await
+                .map_err(GolemError::unknown)?;
+            if has_files {
+                if !dry_run {
+                    deps.blob_store_service()
+                        .delete_worker_files(&owned_dependent_id)
+                        .await
+                        .map_err(GolemError::unknown)?;
+                }
+                removed.push(format!("files of worker {worker_id}"));
+            }
+
+            if worker_id == owned_worker_id.worker_id {
+                if !dry_run {
+                    Self::delete(deps, owned_worker_id).await?;
+                }
+            } else {
+                // Children can belong to any shard, so they are deleted through the worker service
+                match deps.worker_proxy().delete(&worker_id, false, dry_run).await {
+                    Ok(_) => {}
+                    // The child may have been deleted already
+                    Err(WorkerProxyError::NotFound(_)) => {}
+                    Err(err) => {
+                        return Err(GolemError::unknown(format!(
+                            "Failed to delete child worker {worker_id}: {err}"
+                        )))
+                    }
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+
     pub async fn new<T: HasAll<Ctx>>(
         deps: &T,
         owned_worker_id: OwnedWorkerId,
@@ -228,27 +385,42 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
             last_known_status: worker_metadata.last_known_status.clone(),
             component_type: initial_component_metadata.component_type,
             timestamp: Timestamp::now_utc(),
+            held_invocations: worker_metadata.last_known_status.held_invocations.clone(),
         }));
 
         let stopping = AtomicBool::new(false);
 
+        let limits = &deps.config().limits;
+        let mut event_service = WorkerEventServiceDefault::new(
+            limits.event_broadcast_capacity,
+            limits.event_history_size,
+        );
+        if let Some(event_history_dir) = &limits.event_history_dir {
+            event_service = event_service
+                .with_persistent_history(PersistentEventHistory::new(
+                    event_history_dir,
+                    &owned_worker_id,
+                    limits.event_history_max_bytes,
+                ))
+                .await;
+        }
+
         Ok(Worker {
             owned_worker_id,
             oplog,
-            event_service: Arc::new(WorkerEventServiceDefault::new(
-                deps.config().limits.event_broadcast_capacity,
-                deps.config().limits.event_history_size,
-            )),
+            event_service: Arc::new(event_service),
             deps: All::from_other(deps),
             queue,
             pending_updates,
             invocation_results,
+            result_mappings: RwLock::new(ResultMappings::default()),
             instance,
             execution_status,
             stopping,
             initial_worker_metadata: worker_metadata,
             worker_estimate_coefficient: deps.config().memory.worker_estimate_coefficient,
             oom_retry_config: deps.config().memory.oom_retry_config.clone(),
+            profiler: Arc::new(WorkerProfiler::default()),
         })
     }
 
@@ -317,7 +489,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
     /// If it was running then we recheck the conditions and then stop the worker.
     ///
     /// We know that the conditions remain true because:
-    /// - the invocation queue is empty, so it cannot get into `ExecutionStatus::Running`, as there is nothing to run
+    /// - the invocation queue has no invocation which is not held, so it cannot get into `ExecutionStatus::Running`, as there is nothing to run
     /// - nothing can be added to the invocation queue because we are holding the `instance` lock
     ///
     /// By passing the running lock to `stop_internal_running` it is never released and the stop eventually
@@ -356,6 +528,16 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         self.event_service.clone()
     }
 
+    pub fn owned_worker_id(&self) -> &OwnedWorkerId {
+        &self.owned_worker_id
+    }
+
+    /// The guest profiler of the worker. The collected profile lives as long as the worker is
+    /// kept in memory.
+    pub fn profiler(&self) -> Arc<WorkerProfiler> {
+        self.profiler.clone()
+    }
+
     pub fn is_loading(&self) -> bool {
         matches!(
             &*self.execution_status.read().unwrap(),
@@ -369,6 +551,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
             last_known_status: execution_status.last_known_status().clone(),
             component_type: execution_status.component_type(),
             timestamp: Timestamp::now_utc(),
+            held_invocations: execution_status.held_invocations().clone(),
         };
     }
 
@@ -393,9 +576,15 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
             .unwrap()
             .last_known_status()
             .clone();
+        // The TTL can be changed while the worker is in memory, so the expiry is always read back
+        let expires_at = self
+            .worker_service()
+            .get_expiry(&self.owned_worker_id)
+            .await;
         let result = self.initial_worker_metadata.clone();
         Ok(WorkerMetadata {
             last_known_status: updated_status,
+            expires_at,
             ..result
         })
     }
@@ -416,11 +605,17 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
             running.interrupt(interrupt_kind.clone());
         }
 
+        self.mark_interrupting(interrupt_kind)
+    }
+
+    fn mark_interrupting(&self, interrupt_kind: InterruptKind) -> Option<Receiver<()>> {
         let mut execution_status = self.execution_status.write().unwrap();
         let current_execution_status = execution_status.clone();
         match current_execution_status {
             ExecutionStatus::Running {
-                last_known_status, ..
+                last_known_status,
+                held_invocations,
+                ..
             } => {
                 let (sender, receiver) = tokio::sync::broadcast::channel(1);
                 *execution_status = ExecutionStatus::Interrupting {
@@ -429,6 +624,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                     last_known_status,
                     component_type: execution_status.component_type(),
                     timestamp: Timestamp::now_utc(),
+                    held_invocations,
                 };
                 Some(receiver)
             }
@@ -443,21 +639,320 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         }
     }
 
+    /// Interrupts the worker with `InterruptKind::Interrupt`, but only if it is currently executing
+    /// the invocation with the given idempotency key. The pending invocations are kept, and get
+    /// processed when the worker is resumed. Returns whether the worker got interrupted.
+    pub async fn interrupt_invocation(&self, idempotency_key: &IdempotencyKey) -> bool {
+        let instance = self.instance.lock().await;
+        let WorkerInstance::Running(running) = &*instance else {
+            return false;
+        };
+
+        // Holding the current invocation keeps the invocation loop from moving on to the next
+        // invocation until the worker is marked as interrupting
+        let current_invocation = running.current_invocation.read().unwrap();
+        if current_invocation.as_ref() != Some(idempotency_key) {
+            return false;
+        }
+
+        running.interrupt(InterruptKind::Interrupt);
+        self.mark_interrupting(InterruptKind::Interrupt);
+        true
+    }
+
+    /// Cancels the pending invocation with the given idempotency key, removing it from the
+    /// invocation queue before the worker starts executing it. Callers awaiting its result get an
+    /// interrupted error. Returns `false` if the invocation is already running or has completed.
+    pub async fn cancel_invocation(
+        &self,
+        idempotency_key: &IdempotencyKey,
+    ) -> Result<bool, GolemError> {
+        let cancelled = {
+            let mut queue = self.queue.write().unwrap();
+            let count = queue.len();
+            queue.retain(|pending| pending.invocation.idempotency_key() != Some(idempotency_key));
+            queue.len() < count
+        };
+
+        if cancelled {
+            self.oplog
+                .add_and_commit(OplogEntry::cancel_pending_invocation(
+                    idempotency_key.clone(),
+                ))
+                .await;
+            self.update_metadata().await?;
+            self.result_mappings
+                .write()
+                .unwrap()
+                .remove(idempotency_key);
+            self.execution_status
+                .write()
+                .unwrap()
+                .held_invocations_mut()
+                .remove(idempotency_key);
+            self.events().publish(Event::InvocationCompleted {
+                worker_id: self.owned_worker_id.worker_id(),
+                idempotency_key: idempotency_key.clone(),
+                result: Err(InterruptKind::Interrupt.into()),
+            });
+            Ok(true)
+        } else {
+            let is_current = match &*self.instance.lock().await {
+                WorkerInstance::Running(running) => running.is_invoking(idempotency_key),
+                _ => false,
+            };
+            if is_current {
+                Ok(false)
+            } else {
+                match self.lookup_invocation_result(idempotency_key).await {
+                    LookupResult::New => Err(GolemError::invalid_request(format!(
+                        "No invocation found with idempotency key {idempotency_key}"
+                    ))),
+                    _ => Ok(false),
+                }
+            }
+        }
+    }
+
+    /// Replaces the environment variables of the worker. The running instance keeps the
+    /// environment it was started with, the new one is used from the next restart or update of
+    /// the worker.
+    pub async fn update_env(&self, env: Vec<(String, String)>) -> Result<(), GolemError> {
+        self.oplog
+            .add_and_commit(OplogEntry::change_environment(env))
+            .await;
+        self.update_metadata().await
+    }
+
+    /// Replaces the recorded data of the given oplog entries by placeholder values of the same
+    /// types, and records the redaction in the oplog. All the entries are checked before any of
+    /// them gets redacted. Returns the index of the recorded `Redacted` entry.
+    ///
+    /// A loaded worker is stopped first, waiting for its current invocation to complete, and the
+    /// `instance` lock is held until the redaction is done so the worker cannot be started again
+    /// while its oplog is being rewritten. It gets restarted afterwards to process its pending
+    /// invocations.
+    pub async fn redact_oplog(
+        this: Arc<Worker<Ctx>>,
+        entries: BTreeSet<OplogIndex>,
+        reason: String,
+    ) -> Result<OplogIndex, GolemError> {
+        let mut instance = this.instance.lock().await;
+        let was_loaded = !instance.is_unloaded();
+        if was_loaded {
+            if this
+                .stopping
+                .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+                != Ok(false)
+            {
+                return Err(GolemError::invalid_request(
+                    "The worker is being stopped, retry the redaction later",
+                ));
+            }
+            this.unload_instance(&mut instance, false, None).await;
+            this.stopping.store(false, Ordering::Release);
+        }
+
+        let result = this.redact_oplog_entries(entries, reason).await;
+        drop(instance);
+
+        if was_loaded {
+            Self::start_if_needed(this).await?;
+        }
+        result
+    }
+
+    async fn redact_oplog_entries(
+        &self,
+        entries: BTreeSet<OplogIndex>,
+        reason: String,
+    ) -> Result<OplogIndex, GolemError> {
+        let oplog_service = self.oplog_service();
+        let last_committed_idx = oplog_service.get_last_index(&self.owned_worker_id).await;
+        let deleted_regions = self.get_metadata().await?.last_known_status.deleted_regions;
+
+        let mut originals = Vec::new();
+        for idx in entries {
+            if idx == OplogIndex::NONE || idx > last_committed_idx {
+                return Err(GolemError::invalid_request(format!(
+                    "Oplog entry {idx} does not exist"
+                )));
+            }
+            let entry = oplog_service
+                .read(&self.owned_worker_id, idx, 1)
+                .await
+                .remove(&idx)
+                .ok_or(GolemError::invalid_request(format!(
+                    "Oplog entry {idx} does not exist"
+                )))?;
+            ensure_redactable(idx, &entry, &deleted_regions)
+                .map_err(GolemError::invalid_request)?;
+            originals.push((idx, entry));
+        }
+
+        // Entries redacted before a failure are still recorded, so the oplog always tells which
+        // entries hold placeholder values
+        let mut redacted = Vec::new();
+        let mut failure = None;
+        for (idx, entry) in originals {
+            let unreferenced_payload =
+                match redact_oplog_entry(&oplog_service, &self.owned_worker_id, entry).await {
+                    Ok((redacted_entry, unreferenced_payload)) => {
+                        match oplog_service
+                            .replace(&self.owned_worker_id, idx, redacted_entry)
+                            .await
+                        {
+                            Ok(()) => unreferenced_payload,
+                            Err(err) => {
+                                failure = Some((idx, err));
+                                break;
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        failure = Some((idx, err));
+                        break;
+                    }
+                };
+            redacted.push(idx);
+
+            if let Some(payload) = unreferenced_payload {
+                if let Err(err) = oplog_service
+                    .delete_payload(&self.owned_worker_id, &payload)
+                    .await
+                {
+                    failure = Some((idx, format!("failed to delete its original payload: {err}")));
+                    break;
+                }
+            }
+        }
+        let failure = failure.map(|(idx, err)| {
+            GolemError::runtime(format!("Failed to redact oplog entry {idx}: {err}"))
+        });
+
+        if redacted.is_empty() {
+            return Err(
+                failure.unwrap_or(GolemError::invalid_request("No oplog entries to redact"))
+            );
+        }
+        let marker_idx = self
+            .oplog
+            .add_and_commit(OplogEntry::redacted(redacted, reason))
+            .await;
+        self.update_metadata().await?;
+
+        match failure {
+            Some(err) => Err(err),
+            None => Ok(marker_idx),
+        }
+    }
+
+    /// Holds the pending invocation with the given idempotency key in the invocation queue. The
+    /// worker keeps processing the other pending invocations, and only starts the held one once
+    /// it gets released with `release_invocation`. Holds are recorded in the oplog, so they are
+    /// kept when the worker gets unloaded or the executor restarts.
+    pub async fn hold_invocation(
+        &self,
+        idempotency_key: &IdempotencyKey,
+    ) -> Result<(), GolemError> {
+        let held = {
+            // The queue stays locked so the invocation loop cannot take the invocation meanwhile
+            let queue = self.queue.read().unwrap();
+            queue
+                .iter()
+                .any(|pending| pending.invocation.idempotency_key() == Some(idempotency_key))
+                && self
+                    .execution_status
+                    .write()
+                    .unwrap()
+                    .held_invocations_mut()
+                    .insert(idempotency_key.clone())
+        };
+
+        if held {
+            self.oplog
+                .add_and_commit(OplogEntry::hold_pending_invocation(idempotency_key.clone()))
+                .await;
+            self.update_metadata().await?;
+            Ok(())
+        } else if self
+            .execution_status
+            .read()
+            .unwrap()
+            .held_invocations()
+            .contains(idempotency_key)
+        {
+            // Already held
+            Ok(())
+        } else {
+            Err(GolemError::invalid_request(format!(
+                "No pending invocation found with idempotency key {idempotency_key}"
+            )))
+        }
+    }
+
+    /// Releases an invocation held by `hold_invocation`, letting the worker execute it in its turn
+    pub async fn release_invocation(
+        &self,
+        idempotency_key: &IdempotencyKey,
+    ) -> Result<(), GolemError> {
+        let released = self
+            .execution_status
+            .write()
+            .unwrap()
+            .held_invocations_mut()
+            .remove(idempotency_key);
+
+        if released {
+            self.oplog
+                .add_and_commit(OplogEntry::release_pending_invocation(
+                    idempotency_key.clone(),
+                ))
+                .await;
+            self.update_metadata().await?;
+            if let WorkerInstance::Running(running) = &*self.instance.lock().await {
+                running.wake_up();
+            }
+            Ok(())
+        } else {
+            Err(GolemError::invalid_request(format!(
+                "No held invocation found with idempotency key {idempotency_key}"
+            )))
+        }
+    }
+
     pub async fn invoke(
         &self,
         idempotency_key: IdempotencyKey,
         full_function_name: String,
         function_input: Vec<Value>,
+    ) -> Result<Option<Result<TypeAnnotatedValue, GolemError>>, GolemError> {
+        self.invoke_with_caller(idempotency_key, full_function_name, function_input, None)
+            .await
+    }
+
+    /// Same as `invoke`, but records the identity of the API gateway client making the
+    /// invocation together with it, so the worker can read it even after being recovered
+    pub async fn invoke_with_caller(
+        &self,
+        idempotency_key: IdempotencyKey,
+        full_function_name: String,
+        function_input: Vec<Value>,
+        caller: Option<CallerIdentity>,
     ) -> Result<Option<Result<TypeAnnotatedValue, GolemError>>, GolemError> {
         let output = self.lookup_invocation_result(&idempotency_key).await;
 
         match output {
-            LookupResult::Complete(output) => Ok(Some(output)),
+            LookupResult::Complete(output) => {
+                debug!("Answering invocation with the stored result of {idempotency_key}");
+                record_deduplicated_invocation();
+                Ok(Some(output))
+            }
             LookupResult::Interrupted => Err(InterruptKind::Interrupt.into()),
             LookupResult::Pending => Ok(None),
             LookupResult::New => {
                 // Invoke the function in the background
-                self.enqueue(idempotency_key, full_function_name, function_input)
+                self.enqueue(idempotency_key, full_function_name, function_input, caller)
                     .await;
                 Ok(None)
             }
@@ -469,9 +964,37 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         idempotency_key: IdempotencyKey,
         full_function_name: String,
         function_input: Vec<Value>,
+    ) -> Result<TypeAnnotatedValue, GolemError> {
+        self.invoke_and_await_with_timeout(
+            idempotency_key,
+            full_function_name,
+            function_input,
+            None,
+            None,
+        )
+        .await
+    }
+
+    /// Same as `invoke_and_await`, but if `timeout` is set and the invocation does not complete
+    /// in time, the timeout is recorded in the oplog and the invocation gets cancelled if it is
+    /// still pending, or interrupted if the worker is executing it. The invocation then fails
+    /// with `GolemError::InvocationTimedOut`. The identity of the API gateway client making the
+    /// invocation is recorded together with it, if given.
+    pub async fn invoke_and_await_with_timeout(
+        &self,
+        idempotency_key: IdempotencyKey,
+        full_function_name: String,
+        function_input: Vec<Value>,
+        timeout: Option<Duration>,
+        caller: Option<CallerIdentity>,
     ) -> Result<TypeAnnotatedValue, GolemError> {
         match self
-            .invoke(idempotency_key.clone(), full_function_name, function_input)
+            .invoke_with_caller(
+                idempotency_key.clone(),
+                full_function_name,
+                function_input,
+                caller,
+            )
             .await?
         {
             Some(Ok(output)) => Ok(output),
@@ -479,7 +1002,20 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
             None => {
                 debug!("Waiting for idempotency key to complete",);
 
-                let result = self.wait_for_invocation_result(&idempotency_key).await;
+                // Only waiting for the result is limited, so the invocation never gets enqueued
+                // partially
+                let result = match timeout {
+                    Some(timeout) => match tokio::time::timeout(
+                        timeout,
+                        self.wait_for_invocation_result(&idempotency_key),
+                    )
+                    .await
+                    {
+                        Ok(result) => result,
+                        Err(_) => return self.time_out_invocation(&idempotency_key, timeout).await,
+                    },
+                    None => self.wait_for_invocation_result(&idempotency_key).await,
+                };
 
                 debug!("Idempotency key lookup result: {:?}", result);
                 match result {
@@ -554,6 +1090,10 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         self.queue.read().unwrap().iter().cloned().collect()
     }
 
+    pub fn pending_invocation_count(&self) -> usize {
+        self.queue.read().unwrap().len()
+    }
+
     pub fn pending_updates(&self) -> (VecDeque<TimestampedUpdateDescription>, DeletedRegions) {
         let pending_updates = self.pending_updates.read().unwrap().clone();
         let mut deleted_regions = DeletedRegionsBuilder::new();
@@ -591,14 +1131,18 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         result: TypeAnnotatedValue,
         oplog_index: OplogIndex,
     ) {
+        let window = self.deduplication_window().await;
         let mut map = self.invocation_results.write().unwrap();
         map.insert(
             key.clone(),
             InvocationResult::Cached {
                 result: Ok(result.clone()),
                 oplog_idx: oplog_index,
+                completed_at: Timestamp::now_utc(),
             },
         );
+        forget_expired_invocation_results(&mut map, window);
+        self.result_mappings.write().unwrap().retain_results(&map);
         debug!("Stored invocation success for {key}");
         self.events().publish(Event::InvocationCompleted {
             worker_id: self.owned_worker_id.worker_id(),
@@ -622,6 +1166,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                 .collect(),
         ]
         .concat();
+        let window = self.deduplication_window().await;
         let mut map = self.invocation_results.write().unwrap();
         for key in keys_to_fail {
             let stderr = self.event_service.get_last_invocation_errors();
@@ -633,8 +1178,10 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                         stderr: stderr.clone(),
                     }),
                     oplog_idx: oplog_index,
+                    completed_at: Timestamp::now_utc(),
                 },
             );
+            self.result_mappings.write().unwrap().remove(key);
             let golem_error = trap_type.as_golem_error(&stderr);
             if let Some(golem_error) = golem_error {
                 self.events().publish(Event::InvocationCompleted {
@@ -644,6 +1191,42 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                 });
             }
         }
+        forget_expired_invocation_results(&mut map, window);
+    }
+
+    /// Makes the invocation with the given idempotency key pass its result through the
+    /// component's `__map_result` export, unless the invocation has already completed
+    pub fn request_result_mapping(&self, key: &IdempotencyKey) {
+        if !self.invocation_results.read().unwrap().contains_key(key) {
+            self.result_mappings.write().unwrap().request(key);
+        }
+    }
+
+    fn is_result_mapping_requested(&self, key: &IdempotencyKey) -> bool {
+        self.result_mappings.read().unwrap().is_requested(key)
+    }
+
+    fn store_mapped_result(
+        &self,
+        key: &IdempotencyKey,
+        result: Result<TypeAnnotatedValue, GolemError>,
+    ) {
+        self.result_mappings.write().unwrap().store(key, result);
+    }
+
+    /// Gets the result of a completed invocation as returned by the component's `__map_result`
+    /// export. Only available if the mapping was requested before the invocation completed, and
+    /// while the invocation's result is kept for deduplication.
+    pub fn mapped_result(&self, key: &IdempotencyKey) -> Result<TypeAnnotatedValue, GolemError> {
+        self.result_mappings
+            .read()
+            .unwrap()
+            .get(key)
+            .unwrap_or_else(|| {
+                Err(GolemError::invalid_request(format!(
+                    "The result of invocation {key} was not transformed, results can only be transformed by the request starting the invocation"
+                )))
+            })
     }
 
     pub async fn store_invocation_resuming(&self, key: &IdempotencyKey) {
@@ -661,10 +1244,18 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
             .update_status(&self.owned_worker_id, &status_value, component_type)
             .await;
         // Updating the status in memory
-        self.execution_status
-            .write()
-            .unwrap()
-            .set_last_known_status(status_value);
+        let changed = {
+            let mut execution_status = self.execution_status.write().unwrap();
+            let changed = execution_status.last_known_status().status != status_value.status;
+            execution_status.set_last_known_status(status_value);
+            changed
+        };
+
+        if changed {
+            if let Ok(metadata) = self.get_metadata().await {
+                publish_worker_status_event(self, WorkerStatusEventKind::StatusChanged, &metadata);
+            }
+        }
     }
 
     /// Gets the estimated memory requirement of the worker
@@ -678,6 +1269,14 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         Ok((x * (ml + c * sw)) as u64)
     }
 
+    /// Returns true if the worker is waiting for enough worker memory to become available to be
+    /// loaded. If the worker's instance is locked at the moment, it is reported as not waiting.
+    pub fn is_waiting_for_permit(&self) -> bool {
+        self.instance
+            .try_lock()
+            .is_ok_and(|instance| instance.is_waiting_for_permit())
+    }
+
     /// Returns true if the worker is running, but it is not performing any invocations at the moment
     /// (ExecutionStatus::Suspended) and has no pending invocation in its invocation queue.
     ///
@@ -716,6 +1315,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
     }
 
     pub async fn increase_memory(&self, delta: u64) -> anyhow::Result<()> {
+        self.profiler.record_memory_growth(delta);
         match &mut *self.instance.lock().await {
             WorkerInstance::Running(ref mut running) => {
                 if let Some(new_permits) = self.active_workers().try_acquire(delta).await {
@@ -736,11 +1336,12 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         idempotency_key: IdempotencyKey,
         full_function_name: String,
         function_input: Vec<Value>,
+        caller: Option<CallerIdentity>,
     ) {
         match &*self.instance.lock().await {
             WorkerInstance::Running(running) => {
                 running
-                    .enqueue(idempotency_key, full_function_name, function_input)
+                    .enqueue(idempotency_key, full_function_name, function_input, caller)
                     .await;
             }
             WorkerInstance::Unloaded | WorkerInstance::WaitingForPermit(_) => {
@@ -749,6 +1350,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                     idempotency_key,
                     full_function_name,
                     function_input,
+                    caller,
                 };
                 let entry = OplogEntry::pending_worker_invocation(invocation.clone());
                 let timestamped_invocation = TimestampedWorkerInvocation {
@@ -767,6 +1369,122 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         }
     }
 
+    async fn time_out_invocation(
+        &self,
+        idempotency_key: &IdempotencyKey,
+        timeout: Duration,
+    ) -> Result<TypeAnnotatedValue, GolemError> {
+        // The invocation may have completed right when the timeout elapsed
+        if let LookupResult::Complete(result) = self.lookup_invocation_result(idempotency_key).await
+        {
+            return result;
+        }
+
+        warn!("Invocation {idempotency_key} timed out after {timeout:?}");
+
+        // The invocation is only running if it could not be removed from the queue anymore
+        let cancelled = self
+            .cancel_invocation(idempotency_key)
+            .await
+            .unwrap_or(false);
+        if !cancelled && self.interrupt_invocation(idempotency_key).await {
+            if let Some(mut interrupted) = self.await_interruption() {
+                let _ = interrupted.recv().await;
+            }
+
+            // The timed out invocation is abandoned by skipping its oplog entries, otherwise
+            // resuming the worker would replay it and continue executing it
+            self.oplog.commit(CommitLevel::Always).await;
+            if let Some(start) = self.find_invocation_start(idempotency_key).await {
+                let end = self.oplog.current_oplog_index().await.next(); // skipping the Jump entry too
+                self.oplog
+                    .add_and_commit(OplogEntry::jump(OplogRegion { start, end }))
+                    .await;
+            }
+
+            // Same as when aborting a running invocation, the worker is dropped from the active
+            // worker cache, which also drops its websocket connections etc.
+            self.active_workers()
+                .remove(&self.owned_worker_id.worker_id);
+        }
+
+        // Recorded as the result of the invocation, so it is not executed again when retried
+        // with the same idempotency key
+        let oplog_idx = self
+            .oplog
+            .add_and_commit(OplogEntry::invocation_timed_out(
+                idempotency_key.clone(),
+                timeout,
+            ))
+            .await;
+        let error = GolemError::InvocationTimedOut {
+            timeout_millis: timeout.as_millis() as u64,
+        };
+        self.invocation_results.write().unwrap().insert(
+            idempotency_key.clone(),
+            InvocationResult::Cached {
+                result: Err(FailedInvocationResult {
+                    trap_type: TrapType::Error(timed_out_error(timeout)),
+                    stderr: "".to_string(),
+                }),
+                oplog_idx,
+                completed_at: Timestamp::now_utc(),
+            },
+        );
+        self.events().publish(Event::InvocationCompleted {
+            worker_id: self.owned_worker_id.worker_id(),
+            idempotency_key: idempotency_key.clone(),
+            result: Err(error.clone()),
+        });
+
+        Err(error)
+    }
+
+    /// Subscribes to the end of the ongoing interruption of the worker, if there is one
+    fn await_interruption(&self) -> Option<Receiver<()>> {
+        match &*self.execution_status.read().unwrap() {
+            ExecutionStatus::Interrupting {
+                await_interruption, ..
+            } => Some(await_interruption.subscribe()),
+            _ => None,
+        }
+    }
+
+    /// Finds the oplog entry starting the last, not completed invocation with the given
+    /// idempotency key
+    async fn find_invocation_start(&self, idempotency_key: &IdempotencyKey) -> Option<OplogIndex> {
+        const CHUNK_SIZE: u64 = 1024;
+
+        let mut last_idx: u64 = self.oplog.current_oplog_index().await.into();
+        while last_idx > 0 {
+            let first_idx = last_idx.saturating_sub(CHUNK_SIZE - 1).max(1);
+            let entries = self
+                .oplog_service()
+                .read(
+                    &self.owned_worker_id,
+                    OplogIndex::from_u64(first_idx),
+                    last_idx - first_idx + 1,
+                )
+                .await;
+            for (idx, entry) in entries.iter().rev() {
+                match entry {
+                    OplogEntry::ExportedFunctionInvokedV1 {
+                        idempotency_key: key,
+                        ..
+                    }
+                    | OplogEntry::ExportedFunctionInvoked {
+                        idempotency_key: key,
+                        ..
+                    } => return (key == idempotency_key).then_some(*idx),
+                    OplogEntry::ExportedFunctionCompleted { .. } => return None,
+                    _ => {}
+                }
+            }
+            last_idx = first_idx - 1;
+        }
+        None
+    }
+
     async fn wait_for_invocation_result(
         &self,
         key: &IdempotencyKey,
@@ -804,10 +1522,28 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         }
     }
 
-    async fn lookup_invocation_result(&self, key: &IdempotencyKey) -> LookupResult {
-        let maybe_result = self.invocation_results.read().unwrap().get(key).cloned();
-        if let Some(mut result) = maybe_result {
+    /// How long the completed idempotency keys of this worker are remembered
+    async fn deduplication_window(&self) -> Option<Duration> {
+        self.worker_service()
+            .get_deduplication_window(&self.owned_worker_id.component_id())
+            .await
+    }
+
+    pub async fn lookup_invocation_result(&self, key: &IdempotencyKey) -> LookupResult {
+        let mut maybe_result = self.invocation_results.read().unwrap().get(key).cloned();
+        if let Some(result) = &mut maybe_result {
             result.cache(&self.owned_worker_id, self).await;
+            if result.is_expired(self.deduplication_window().await) {
+                debug!("Idempotency key {key} is outside of the deduplication window");
+                let mut map = self.invocation_results.write().unwrap();
+                if map.get(key).map(|current| current.oplog_idx()) == Some(result.oplog_idx()) {
+                    map.remove(key);
+                    record_expired_idempotency_keys(1);
+                }
+                maybe_result = None;
+            }
+        }
+        if let Some(result) = maybe_result {
             match result {
                 InvocationResult::Cached {
                     result: Ok(values), ..
@@ -887,6 +1623,21 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         mut instance: MutexGuard<'a, WorkerInstance>,
         called_from_invocation_loop: bool,
         fail_pending_invocations: Option<GolemError>,
+    ) {
+        self.unload_instance(
+            &mut instance,
+            called_from_invocation_loop,
+            fail_pending_invocations,
+        )
+        .await;
+        self.stopping.store(false, Ordering::Release);
+    }
+
+    async fn unload_instance(
+        &self,
+        instance: &mut WorkerInstance,
+        called_from_invocation_loop: bool,
+        fail_pending_invocations: Option<GolemError>,
     ) {
         if let WorkerInstance::Running(running) = instance.unload() {
             debug!("Stopping running worker ({called_from_invocation_loop})");
@@ -924,7 +1675,6 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         } else {
             debug!("Worker was already stopped");
         }
-        self.stopping.store(false, Ordering::Release);
     }
 
     async fn restart_on_oom(
@@ -940,8 +1690,59 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
         Self::start_if_needed_internal(this, oom_retry_count).await
     }
 
+    /// Turns a panic caught in one of the worker's tasks into a failure of this worker only.
+    ///
+    /// The error is recorded in the oplog, so the worker's status reflects it the same way as
+    /// if the invocation had failed, callers waiting for the in-flight or pending invocations
+    /// get the error, and the worker is unloaded so it does not look like it is still running.
+    /// Panics can only be caught if the executor is built with `panic = "unwind"`.
+    async fn fail_after_panic(&self, message: String) {
+        let error = WorkerError::Unknown(format!("Worker executor panicked: {message}"));
+        self.oplog
+            .add_and_commit(OplogEntry::error(error.clone()))
+            .await;
+        let oplog_idx = self.oplog.current_oplog_index().await;
+
+        match self.get_metadata().await {
+            Ok(metadata) => {
+                match calculate_last_known_status(self, &self.owned_worker_id, &Some(metadata))
+                    .await
+                {
+                    Ok(status) => {
+                        if let Some(key) = &status.current_idempotency_key {
+                            self.store_invocation_failure(
+                                key,
+                                &TrapType::Error(error.clone()),
+                                oplog_idx,
+                            )
+                            .await;
+                        }
+                        self.update_status(status).await;
+                    }
+                    Err(err) => {
+                        error!("Failed to calculate the status of the panicked worker: {err}")
+                    }
+                }
+            }
+            Err(err) => error!("Failed to get the metadata of the panicked worker: {err}"),
+        }
+
+        let mut instance = self.instance.lock().await;
+        if instance.is_waiting_for_permit() {
+            // The panicking task is the one waiting for the permit, so there is nothing to abort
+            if let WorkerInstance::WaitingForPermit(mut waiting) = instance.unload() {
+                waiting.handle.take();
+            }
+        }
+        drop(instance);
+        // The panic may have happened while the worker was being stopped
+        self.stopping.store(false, Ordering::Release);
+        self.stop_internal(true, Some(GolemError::runtime(error.to_string(""))))
+            .await;
+    }
+
     async fn get_or_create_worker_metadata<
-        T: HasWorkerService + HasComponentService + HasConfig + HasOplogService,
+        T: HasWorkerService + HasComponentService + HasConfig + HasOplogService + HasEvents,
     >(
         this: &T,
         owned_worker_id: &OwnedWorkerId,
@@ -967,6 +1768,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                     account_id: owned_worker_id.account_id(),
                     created_at: Timestamp::now_utc(),
                     parent,
+                    expires_at: None,
                     last_known_status: WorkerStatusRecord {
                         component_version: component_metadata.version,
                         component_size: component_metadata.size,
@@ -981,6 +1783,7 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                 this.worker_service()
                     .add(&worker_metadata, component_metadata.component_type)
                     .await?;
+                publish_worker_status_event(this, WorkerStatusEventKind::Created, &worker_metadata);
                 Ok(worker_metadata)
             }
             Some(previous_metadata) => Ok(WorkerMetadata {
@@ -1040,8 +1843,14 @@ impl WaitingWorker {
         );
         let handle = tokio::task::spawn(
             async move {
-                let permit = parent.active_workers().acquire(memory_requirement).await;
-                Worker::start_with_permit(parent, permit, oom_retry_count).await;
+                let result = catch_task_panic("waiting-for-permits", async {
+                    let permit = parent.active_workers().acquire(memory_requirement).await;
+                    Worker::start_with_permit(parent.clone(), permit, oom_retry_count).await;
+                })
+                .await;
+                if let Err(message) = result {
+                    parent.fail_after_panic(message).await;
+                }
             }
             .instrument(span),
         );
@@ -1064,6 +1873,8 @@ struct RunningWorker {
     sender: UnboundedSender<WorkerCommand>,
     queue: Arc<RwLock<VecDeque<TimestampedWorkerInvocation>>>,
     execution_status: Arc<RwLock<ExecutionStatus>>,
+    /// Idempotency key of the exported function invocation being executed
+    current_invocation: Arc<RwLock<Option<IdempotencyKey>>>,
 
     oplog: Arc<dyn Oplog + Send + Sync>,
 
@@ -1092,6 +1903,8 @@ impl RunningWorker {
         let owned_worker_id_clone = owned_worker_id.clone();
         let waiting_for_command = Arc::new(AtomicBool::new(false));
         let waiting_for_command_clone = waiting_for_command.clone();
+        let current_invocation = Arc::new(RwLock::new(None));
+        let current_invocation_clone = current_invocation.clone();
 
         let span = span!(
             Level::INFO,
@@ -1099,16 +1912,23 @@ impl RunningWorker {
             worker_id = parent.owned_worker_id.worker_id.to_string(),
         );
         let handle = tokio::task::spawn(async move {
-            RunningWorker::invocation_loop(
-                receiver,
-                active_clone,
-                owned_worker_id_clone,
-                parent,
-                waiting_for_command_clone,
-                oom_retry_count,
+            let result = catch_task_panic(
+                "invocation-loop",
+                RunningWorker::invocation_loop(
+                    receiver,
+                    active_clone,
+                    owned_worker_id_clone,
+                    parent.clone(),
+                    waiting_for_command_clone,
+                    current_invocation_clone,
+                    oom_retry_count,
+                ),
             )
             .instrument(span)
             .await;
+            if let Err(message) = result {
+                parent.fail_after_panic(message).await;
+            }
         });
 
         RunningWorker {
@@ -1117,6 +1937,7 @@ impl RunningWorker {
             queue,
             oplog,
             execution_status,
+            current_invocation,
             permit,
             waiting_for_command,
         }
@@ -1135,11 +1956,13 @@ impl RunningWorker {
         idempotency_key: IdempotencyKey,
         full_function_name: String,
         function_input: Vec<Value>,
+        caller: Option<CallerIdentity>,
     ) {
         let invocation = WorkerInvocation::ExportedFunction {
             idempotency_key,
             full_function_name,
             function_input,
+            caller,
         };
         self.enqueue_worker_invocation(invocation).await;
     }
@@ -1171,6 +1994,15 @@ impl RunningWorker {
         self.sender.send(WorkerCommand::Interrupt(kind)).unwrap();
     }
 
+    /// Makes the invocation loop look for a pending invocation again, after one got released
+    fn wake_up(&self) {
+        self.sender.send(WorkerCommand::Invocation).unwrap();
+    }
+
+    fn is_invoking(&self, idempotency_key: &IdempotencyKey) -> bool {
+        self.current_invocation.read().unwrap().as_ref() == Some(idempotency_key)
+    }
+
     async fn create_instance<Ctx: WorkerCtx>(
         parent: Arc<Worker<Ctx>>,
     ) -> Result<(Instance, async_mutex::Mutex<Store<Ctx>>), GolemError> {
@@ -1209,6 +2041,9 @@ impl RunningWorker {
             parent.worker_enumeration_service(),
             parent.key_value_service(),
             parent.blob_store_service(),
+            parent.domain_event_service(),
+            parent.feature_flag_service(),
+            parent.inference_service(),
             parent.event_service.clone(),
             parent.active_workers(),
             parent.oplog_service(),
@@ -1224,7 +2059,7 @@ impl RunningWorker {
                 worker_metadata.worker_id.clone(),
                 worker_metadata.last_known_status.component_version,
                 worker_metadata.args.clone(),
-                worker_metadata.env.clone(),
+                worker_metadata.current_env().to_vec(),
                 worker_metadata.last_known_status.deleted_regions.clone(),
                 worker_metadata.last_known_status.total_linear_memory_size,
             ),
@@ -1235,7 +2070,12 @@ impl RunningWorker {
         let mut store = Store::new(&parent.engine(), context);
         store.set_epoch_deadline(parent.config().limits.epoch_ticks);
         let worker_id_clone = worker_metadata.worker_id.clone();
+        let profiler = parent.profiler.clone();
         store.epoch_deadline_callback(move |mut store| {
+            if profiler.is_enabled() {
+                profiler.sample(&WasmBacktrace::capture(&store));
+            }
+
             let current_level = store.get_fuel().unwrap_or(0);
             if store.data().is_out_of_fuel(current_level as i64) {
                 debug!("{worker_id_clone} ran out of fuel, borrowing more");
@@ -1285,6 +2125,7 @@ impl RunningWorker {
         owned_worker_id: OwnedWorkerId,
         parent: Arc<Worker<Ctx>>, // parent must not be dropped until the invocation_loop is running
         waiting_for_command: Arc<AtomicBool>,
+        current_invocation: Arc<RwLock<Option<IdempotencyKey>>>,
         oom_retry_count: u64,
     ) {
         loop {
@@ -1349,26 +2190,52 @@ impl RunningWorker {
             if final_decision == RetryDecision::None {
                 debug!("Invocation queue loop started");
 
-                // Exits when RunningWorker is dropped
+                let idle_timeout = parent
+                    .get_metadata()
+                    .await
+                    .ok()
+                    .and_then(|metadata| idle_timeout_from_env(metadata.current_env()));
+
+                // Exits when RunningWorker is dropped or when the worker has been idle for too long
                 waiting_for_command.store(true, Ordering::Release);
-                while let Some(cmd) = receiver.recv().await {
+                while let Some(cmd) = Self::next_command(
+                    &mut receiver,
+                    &active,
+                    &parent.execution_status,
+                    idle_timeout,
+                )
+                .await
+                {
                     waiting_for_command.store(false, Ordering::Release);
                     match cmd {
                         WorkerCommand::Invocation => {
-                            let message = active
-                                .write()
-                                .unwrap()
-                                .pop_front()
-                                .expect("Message should be present");
+                            let message = Self::next_invocation(
+                                &active,
+                                &parent.execution_status,
+                                &current_invocation,
+                            );
+                            let Some(message) = message else {
+                                // The invocation got cancelled while waiting in the queue, or
+                                // all the remaining ones are held
+                                waiting_for_command.store(true, Ordering::Release);
+                                continue;
+                            };
 
                             let mut store_mutex = store.lock().await;
                             let store = store_mutex.deref_mut();
 
+                            let caller = message.invocation.caller().cloned();
                             match message.invocation {
-                                WorkerInvocation::ExportedFunction {
+                                WorkerInvocation::ExportedFunctionV1 {
+                                    idempotency_key: invocation_key,
+                                    full_function_name,
+                                    function_input,
+                                }
+                                | WorkerInvocation::ExportedFunction {
                                     idempotency_key: invocation_key,
                                     full_function_name,
                                     function_input,
+                                    ..
                                 } => {
                                     let span = span!(
                                         Level::INFO,
@@ -1382,6 +2249,7 @@ impl RunningWorker {
                                             .data_mut()
                                             .set_current_idempotency_key(invocation_key)
                                             .await;
+                                        store.data_mut().set_current_caller_identity(caller).await;
 
                                         if let Some(idempotency_key) =
                                             &store.data().get_current_idempotency_key().await
@@ -1411,6 +2279,22 @@ impl RunningWorker {
                                                 output,
                                                 consumed_fuel,
                                             }) => {
+                                                // Kept for the component's `__map_result` export
+                                                // if the caller asked for it
+                                                let mapper_input = match store
+                                                    .data()
+                                                    .get_current_idempotency_key()
+                                                    .await
+                                                {
+                                                    Some(key)
+                                                        if parent
+                                                            .is_result_mapping_requested(&key) =>
+                                                    {
+                                                        Some((key, output.clone()))
+                                                    }
+                                                    _ => None,
+                                                };
+
                                                 let component_metadata =
                                                     store.as_context().data().component_metadata();
 
@@ -1436,6 +2320,19 @@ impl RunningWorker {
 
                                                         match result {
                                                             Ok(result) => {
+                                                                if let Some((key, input)) =
+                                                                    mapper_input
+                                                                {
+                                                                    let mapped = Self::map_result(
+                                                                        store, &instance, input,
+                                                                        &result,
+                                                                    )
+                                                                    .await;
+                                                                    parent.store_mapped_result(
+                                                                        &key, mapped,
+                                                                    );
+                                                                }
+
                                                                 store
                                                                     .data_mut()
                                                                     .on_invocation_success(
@@ -1540,6 +2437,7 @@ impl RunningWorker {
                                     }
                                     .instrument(span)
                                     .await;
+                                    *current_invocation.write().unwrap() = None;
                                     if do_break {
                                         break;
                                     }
@@ -1707,6 +2605,55 @@ impl RunningWorker {
         }
     }
 
+    /// Takes the first pending invocation from the queue which is not held
+    /// Takes the next runnable invocation out of the queue. The popped invocation becomes the
+    /// current one while the queue is still locked, so `cancel_invocation` always finds it either
+    /// in the queue or as the current invocation.
+    fn next_invocation(
+        active: &RwLock<VecDeque<TimestampedWorkerInvocation>>,
+        execution_status: &RwLock<ExecutionStatus>,
+        current_invocation: &RwLock<Option<IdempotencyKey>>,
+    ) -> Option<TimestampedWorkerInvocation> {
+        let mut queue = active.write().unwrap();
+        let position =
+            first_runnable_invocation(&queue, execution_status.read().unwrap().held_invocations())?;
+        let message = queue.remove(position)?;
+        if let Some(idempotency_key) = message.invocation.idempotency_key() {
+            *current_invocation.write().unwrap() = Some(idempotency_key.clone());
+        }
+        Some(message)
+    }
+
+    /// Waits for the next command of the invocation loop. If the worker has an idle timeout and
+    /// no command arrives in time while its queue has nothing to run, returns `None` so the worker
+    /// gets suspended and unloaded from memory.
+    async fn next_command(
+        receiver: &mut UnboundedReceiver<WorkerCommand>,
+        active: &RwLock<VecDeque<TimestampedWorkerInvocation>>,
+        execution_status: &RwLock<ExecutionStatus>,
+        idle_timeout: Option<Duration>,
+    ) -> Option<WorkerCommand> {
+        match idle_timeout {
+            Some(idle_timeout) => loop {
+                match tokio::time::timeout(idle_timeout, receiver.recv()).await {
+                    Ok(cmd) => break cmd,
+                    Err(_)
+                        if first_runnable_invocation(
+                            &active.read().unwrap(),
+                            execution_status.read().unwrap().held_invocations(),
+                        )
+                        .is_none() =>
+                    {
+                        debug!("Worker has been idle for {idle_timeout:?}, unloading it");
+                        break None;
+                    }
+                    Err(_) => {}
+                }
+            },
+            None => receiver.recv().await,
+        }
+    }
+
     async fn fail_update<Ctx: WorkerCtx>(
         target_version: ComponentVersion,
         error: String,
@@ -1718,6 +2665,55 @@ impl RunningWorker {
             .await;
     }
 
+    /// Passes the results of an invocation through the component's `__map_result` export,
+    /// returning them unchanged if there is no such export. Like the snapshotting functions, the
+    /// mapper is invoked without persisting anything, so it leaves no trace in the oplog.
+    async fn map_result<Ctx: WorkerCtx>(
+        store: &mut Store<Ctx>,
+        instance: &Instance,
+        input: Vec<Value>,
+        result: &TypeAnnotatedValue,
+    ) -> Result<TypeAnnotatedValue, GolemError> {
+        let mapper = exports::function_by_name(
+            &store.data().component_metadata().exports,
+            MAP_RESULT_FUNCTION_NAME,
+        )
+        .map_err(GolemError::unknown)?;
+        let Some(mapper) = mapper else {
+            return Ok(result.clone());
+        };
+
+        store.data_mut().begin_call_snapshotting_function();
+        let result =
+            invoke_worker(MAP_RESULT_FUNCTION_NAME.to_string(), input, store, instance).await;
+        store.data_mut().end_call_snapshotting_function();
+
+        match result? {
+            InvokeResult::Succeeded { output, .. } => {
+                interpret_function_results(output, mapper.results.into_iter().collect()).map_err(
+                    |errors| GolemError::ValueMismatch {
+                        details: errors.join(", "),
+                    },
+                )
+            }
+            InvokeResult::Failed { error, .. } => {
+                let stderr = store
+                    .data()
+                    .get_public_state()
+                    .event_service()
+                    .get_last_invocation_errors();
+                Err(GolemError::runtime(format!(
+                    "{MAP_RESULT_FUNCTION_NAME} failed: {}",
+                    error.to_string(&stderr)
+                )))
+            }
+            InvokeResult::Exited { .. } => Err(GolemError::runtime(format!(
+                "{MAP_RESULT_FUNCTION_NAME} called exit"
+            ))),
+            InvokeResult::Interrupted { interrupt_kind, .. } => Err(interrupt_kind.into()),
+        }
+    }
+
     /// Attempts to interpret the save snapshot result as a byte vector
     fn decode_snapshot_result(values: Vec<Value>) -> Option<Vec<u8>> {
         if values.len() == 1 {
@@ -1751,6 +2747,7 @@ enum InvocationResult {
     Cached {
         result: Result<TypeAnnotatedValue, FailedInvocationResult>,
         oplog_idx: OplogIndex,
+        completed_at: Timestamp,
     },
     Lazy {
         oplog_idx: OplogIndex,
@@ -1786,12 +2783,98 @@ impl InvocationResult {
                 },
                 OplogEntry::Interrupted { .. } => Err(FailedInvocationResult { trap_type: TrapType::Interrupt(InterruptKind::Interrupt), stderr: "".to_string()}),
                 OplogEntry::Exited { .. } => Err(FailedInvocationResult { trap_type: TrapType::Exit, stderr: "".to_string()}),
+                OplogEntry::InvocationTimedOut { timeout, .. } => Err(FailedInvocationResult { trap_type: TrapType::Error(timed_out_error(timeout)), stderr: "".to_string()}),
                 _ => panic!("Unexpected oplog entry pointed by invocation result at index {oplog_idx} for {owned_worker_id:?}")
             };
 
-            *self = Self::Cached { result, oplog_idx }
+            *self = Self::Cached {
+                result,
+                oplog_idx,
+                completed_at: entry.timestamp(),
+            }
+        }
+    }
+
+    /// Whether the invocation completed longer ago than the deduplication window.
+    /// Lazy results are not known to be expired until they are cached.
+    pub fn is_expired(&self, window: Option<Duration>) -> bool {
+        match (self, window) {
+            (Self::Cached { completed_at, .. }, Some(window)) => {
+                Timestamp::now_utc().to_millis()
+                    > completed_at
+                        .to_millis()
+                        .saturating_add(window.as_millis() as u64)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Removes the invocation results which completed longer ago than the deduplication window.
+///
+/// Lazy results are only loaded from the oplog on lookup, but as oplog indices grow with time,
+/// every result preceding an expired one in the oplog is expired too.
+fn forget_expired_invocation_results(
+    map: &mut HashMap<IdempotencyKey, InvocationResult>,
+    window: Option<Duration>,
+) {
+    if window.is_none() {
+        return;
+    }
+
+    let last_expired_idx = map
+        .values()
+        .filter(|result| result.is_expired(window))
+        .map(|result| result.oplog_idx())
+        .max();
+
+    if let Some(last_expired_idx) = last_expired_idx {
+        let count = map.len();
+        map.retain(|_, result| result.oplog_idx() > last_expired_idx);
+        record_expired_idempotency_keys(count - map.len());
+    }
+}
+
+/// Name of the optional export through which components transform the results of their
+/// invocations before they are returned to callers asking for it
+pub const MAP_RESULT_FUNCTION_NAME: &str = "__map_result";
+
+/// Results of invocations transformed by the component's `__map_result` export, by the
+/// idempotency key of the invocation. `None` means the transformation was requested but the
+/// invocation did not complete yet.
+#[derive(Debug, Default)]
+struct ResultMappings {
+    mappings: HashMap<IdempotencyKey, Option<Result<TypeAnnotatedValue, GolemError>>>,
+}
+
+impl ResultMappings {
+    pub fn request(&mut self, key: &IdempotencyKey) {
+        self.mappings.entry(key.clone()).or_insert(None);
+    }
+
+    pub fn is_requested(&self, key: &IdempotencyKey) -> bool {
+        self.mappings.contains_key(key)
+    }
+
+    pub fn store(&mut self, key: &IdempotencyKey, result: Result<TypeAnnotatedValue, GolemError>) {
+        if let Some(mapping) = self.mappings.get_mut(key) {
+            *mapping = Some(result);
         }
     }
+
+    pub fn get(&self, key: &IdempotencyKey) -> Option<Result<TypeAnnotatedValue, GolemError>> {
+        self.mappings.get(key).cloned().flatten()
+    }
+
+    pub fn remove(&mut self, key: &IdempotencyKey) {
+        self.mappings.remove(key);
+    }
+
+    /// Forgets the transformed results of the invocations whose results are no longer kept
+    pub fn retain_results(&mut self, results: &HashMap<IdempotencyKey, InvocationResult>) {
+        self.mappings
+            .retain(|key, mapping| mapping.is_none() || results.contains_key(key));
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -1828,6 +2911,18 @@ pub async fn get_component_metadata<Ctx: WorkerCtx>(
     Ok(component_metadata)
 }
 
+/// Notifies the clients watching the worker's component about a change of the worker's status
+fn publish_worker_status_event<T: HasEvents>(
+    this: &T,
+    kind: WorkerStatusEventKind,
+    metadata: &WorkerMetadata,
+) {
+    this.events().publish(Event::WorkerStatusChanged {
+        event: WorkerStatusEvent::new(kind, metadata),
+        metadata: Box::new(metadata.clone()),
+    });
+}
+
 /// Gets the last cached worker status record and the new oplog entries and calculates the new worker status.
 pub async fn calculate_last_known_status<T>(
     this: &T,
@@ -1859,6 +2954,7 @@ where
             last_known.overridden_retry_config.clone(),
             &new_entries,
         );
+        let overridden_env = calculate_overridden_env(last_known.overridden_env, &new_entries);
         let status = calculate_latest_worker_status(
             &last_known.status,
             &this.config().retry,
@@ -1874,6 +2970,8 @@ where
         let mut deleted_regions = calculate_deleted_regions(initial_deleted_regions, &new_entries);
         let pending_invocations =
             calculate_pending_invocations(last_known.pending_invocations, &new_entries);
+        let held_invocations =
+            calculate_held_invocations(last_known.held_invocations, &new_entries);
         let (
             pending_updates,
             failed_updates,
@@ -1917,7 +3015,9 @@ where
             oplog_idx: last_oplog_index,
             status,
             overridden_retry_config,
+            overridden_env,
             pending_invocations,
+            held_invocations,
             deleted_regions,
             pending_updates,
             failed_updates,
@@ -1928,6 +3028,8 @@ where
             component_size,
             owned_resources,
             total_linear_memory_size,
+            // Only known by the running worker, which keeps it up to date in the status
+            disk_usage: last_known.disk_usage,
             fs_version
         };
         Ok(result)
@@ -1959,7 +3061,8 @@ fn calculate_latest_worker_status(
             OplogEntry::ImportedFunctionInvoked { .. } => {
                 result = WorkerStatus::Running;
             }
-            OplogEntry::ExportedFunctionInvoked { .. } => {
+            OplogEntry::ExportedFunctionInvokedV1 { .. }
+            | OplogEntry::ExportedFunctionInvoked { .. } => {
                 result = WorkerStatus::Running;
             }
             OplogEntry::ExportedFunctionCompleted { .. } => {
@@ -2029,6 +3132,15 @@ fn calculate_latest_worker_status(
             OplogEntry::Restart { .. } => {
                 result = WorkerStatus::Idle;
             }
+            OplogEntry::CancelPendingInvocation { .. } => {}
+            OplogEntry::ChangeEnvironment { .. } => {}
+            OplogEntry::Redacted { .. } => {}
+            OplogEntry::InvocationTimedOut { .. } => {}
+            OplogEntry::HoldPendingInvocation { .. } => {}
+            OplogEntry::ReleasePendingInvocation { .. } => {}
+            OplogEntry::SwitchInvocation { .. } => {
+                result = WorkerStatus::Running;
+            }
         }
     }
     result
@@ -2060,6 +3172,19 @@ fn calculate_overridden_retry_policy(
     result
 }
 
+fn calculate_overridden_env(
+    initial: Option<Vec<(String, String)>>,
+    entries: &BTreeMap<OplogIndex, OplogEntry>,
+) -> Option<Vec<(String, String)>> {
+    let mut result = initial;
+    for entry in entries.values() {
+        if let OplogEntry::ChangeEnvironment { env, .. } = entry {
+            result = Some(env.clone());
+        }
+    }
+    result
+}
+
 fn calculate_pending_invocations(
     initial: Vec<TimestampedWorkerInvocation>,
     entries: &BTreeMap<OplogIndex, OplogEntry>,
@@ -2077,19 +3202,17 @@ fn calculate_pending_invocations(
                     invocation: invocation.clone(),
                 });
             }
-            OplogEntry::ExportedFunctionInvoked {
+            OplogEntry::ExportedFunctionInvokedV1 {
+                idempotency_key, ..
+            }
+            | OplogEntry::ExportedFunctionInvoked {
+                idempotency_key, ..
+            }
+            | OplogEntry::CancelPendingInvocation {
                 idempotency_key, ..
             } => {
-                result.retain(|invocation| match invocation {
-                    TimestampedWorkerInvocation {
-                        invocation:
-                            WorkerInvocation::ExportedFunction {
-                                idempotency_key: key,
-                                ..
-                            },
-                        ..
-                    } => key != idempotency_key,
-                    _ => true,
+                result.retain(|invocation| {
+                    !invocation.invocation.is_idempotency_key(idempotency_key)
                 });
             }
             OplogEntry::PendingUpdate {
@@ -2112,6 +3235,38 @@ fn calculate_pending_invocations(
     result
 }
 
+fn calculate_held_invocations(
+    initial: HashSet<IdempotencyKey>,
+    entries: &BTreeMap<OplogIndex, OplogEntry>,
+) -> HashSet<IdempotencyKey> {
+    let mut result = initial;
+    for entry in entries.values() {
+        match entry {
+            OplogEntry::HoldPendingInvocation {
+                idempotency_key, ..
+            } => {
+                result.insert(idempotency_key.clone());
+            }
+            OplogEntry::ReleasePendingInvocation {
+                idempotency_key, ..
+            }
+            | OplogEntry::CancelPendingInvocation {
+                idempotency_key, ..
+            }
+            | OplogEntry::ExportedFunctionInvokedV1 {
+                idempotency_key, ..
+            }
+            | OplogEntry::ExportedFunctionInvoked {
+                idempotency_key, ..
+            } => {
+                result.remove(idempotency_key);
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
 fn calculate_update_fields(
     initial_pending_updates: VecDeque<TimestampedUpdateDescription>,
     initial_failed_updates: Vec<FailedUpdateRecord>,
@@ -2200,7 +3355,10 @@ fn calculate_invocation_results(
 
     for (oplog_idx, entry) in entries {
         match entry {
-            OplogEntry::ExportedFunctionInvoked {
+            OplogEntry::ExportedFunctionInvokedV1 {
+                idempotency_key, ..
+            }
+            | OplogEntry::ExportedFunctionInvoked {
                 idempotency_key, ..
             } => {
                 current_idempotency_key = Some(idempotency_key.clone());
@@ -2221,6 +3379,15 @@ fn calculate_invocation_results(
                     invocation_results.insert(idempotency_key.clone(), *oplog_idx);
                 }
             }
+            OplogEntry::InvocationTimedOut {
+                idempotency_key, ..
+            } => {
+                invocation_results.insert(idempotency_key.clone(), *oplog_idx);
+                // A running invocation which timed out got abandoned
+                if current_idempotency_key.as_ref() == Some(idempotency_key) {
+                    current_idempotency_key = None;
+                }
+            }
             _ => {}
         }
     }
@@ -2228,6 +3395,14 @@ fn calculate_invocation_results(
     (invocation_results, current_idempotency_key)
 }
 
+/// The error recorded as the result of an invocation which timed out
+fn timed_out_error(timeout: Duration) -> WorkerError {
+    WorkerError::Timeout(format!(
+        "Invocation timed out after {}ms",
+        timeout.as_millis()
+    ))
+}
+
 fn calculate_total_linear_memory_size(
     total: u64,
     entries: &BTreeMap<OplogIndex, OplogEntry>,
@@ -2281,13 +3456,355 @@ pub fn is_worker_error_retriable(
     retry_count: u64,
 ) -> bool {
     match error {
-        WorkerError::Unknown(_) => retry_count < (retry_config.max_attempts as u64),
+        WorkerError::Unknown(_)
+        | WorkerError::Trap(_)
+        | WorkerError::Timeout(_)
+        | WorkerError::StorageFailure(_) => retry_count < (retry_config.max_attempts as u64),
         WorkerError::InvalidRequest(_) => false,
         WorkerError::StackOverflow => false,
         WorkerError::OutOfMemory => true,
     }
 }
 
+/// Runs one of the tasks of a worker, returning the message of the panic if the task panicked
+async fn catch_task_panic(
+    task: &'static str,
+    future: impl Future<Output = ()>,
+) -> Result<(), String> {
+    AssertUnwindSafe(future)
+        .catch_unwind()
+        .await
+        .map_err(|panic| {
+            let message = panic_message(panic.as_ref());
+            error!(task, panic = message, "Worker task panicked");
+            record_worker_task_panic(task);
+            message
+        })
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Gets the position of the first pending invocation in the queue which is not held
+fn first_runnable_invocation(
+    queue: &VecDeque<TimestampedWorkerInvocation>,
+    held_invocations: &HashSet<IdempotencyKey>,
+) -> Option<usize> {
+    queue.iter().position(|pending| {
+        pending
+            .invocation
+            .idempotency_key()
+            .map_or(true, |key| !held_invocations.contains(key))
+    })
+}
+
 fn is_running_worker_idle(running: &RunningWorker) -> bool {
-    running.waiting_for_command.load(Ordering::Acquire) && running.queue.read().unwrap().is_empty()
+    running.waiting_for_command.load(Ordering::Acquire)
+        && first_runnable_invocation(
+            &running.queue.read().unwrap(),
+            running.execution_status.read().unwrap().held_invocations(),
+        )
+        .is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+    use std::time::Duration;
+
+    use golem_common::model::oplog::{OplogEntry, OplogIndex};
+    use golem_common::model::{
+        CallerIdentity, IdempotencyKey, Timestamp, TimestampedWorkerInvocation, WorkerInvocation,
+    };
+    use golem_common::serialization::{deserialize, serialize};
+    use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+
+    use crate::error::GolemError;
+    use crate::worker::{
+        calculate_held_invocations, calculate_pending_invocations, catch_task_panic,
+        first_runnable_invocation, forget_expired_invocation_results, InvocationResult,
+        ResultMappings,
+    };
+
+    fn completed(oplog_idx: u64, completed_ago: Duration) -> InvocationResult {
+        InvocationResult::Cached {
+            result: Ok(TypeAnnotatedValue::Bool(true)),
+            oplog_idx: OplogIndex::from_u64(oplog_idx),
+            completed_at: Timestamp::from(
+                Timestamp::now_utc().to_millis() - completed_ago.as_millis() as u64,
+            ),
+        }
+    }
+
+    fn key(value: &str) -> IdempotencyKey {
+        IdempotencyKey::new(value.to_string())
+    }
+
+    #[test]
+    fn results_expire_after_the_deduplication_window() {
+        let old = completed(1, Duration::from_secs(120));
+        let recent = completed(2, Duration::from_secs(10));
+        let window = Some(Duration::from_secs(60));
+
+        assert!(old.is_expired(window));
+        assert!(!recent.is_expired(window));
+        assert!(!old.is_expired(None));
+        assert!(!InvocationResult::Lazy {
+            oplog_idx: OplogIndex::from_u64(1)
+        }
+        .is_expired(window));
+    }
+
+    #[test]
+    fn forgets_results_preceding_the_last_expired_one() {
+        let mut map = HashMap::from([
+            (
+                key("lazy"),
+                InvocationResult::Lazy {
+                    oplog_idx: OplogIndex::from_u64(1),
+                },
+            ),
+            (key("old"), completed(2, Duration::from_secs(120))),
+            (key("recent"), completed(3, Duration::from_secs(10))),
+        ]);
+
+        forget_expired_invocation_results(&mut map, Some(Duration::from_secs(60)));
+
+        assert_eq!(map.len(), 1);
+        assert!(map.contains_key(&key("recent")));
+    }
+
+    #[test]
+    fn keeps_every_result_without_a_deduplication_window() {
+        let mut map = HashMap::from([
+            (key("old"), completed(1, Duration::from_secs(120))),
+            (key("recent"), completed(2, Duration::from_secs(10))),
+        ]);
+
+        forget_expired_invocation_results(&mut map, None);
+
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn only_requested_results_are_mapped() {
+        let mut mappings = ResultMappings::default();
+        mappings.request(&key("requested"));
+
+        mappings.store(&key("requested"), Ok(TypeAnnotatedValue::U32(1)));
+        mappings.store(&key("other"), Ok(TypeAnnotatedValue::U32(2)));
+
+        assert!(mappings.is_requested(&key("requested")));
+        assert!(!mappings.is_requested(&key("other")));
+        assert_eq!(
+            mappings.get(&key("requested")).map(|result| result.ok()),
+            Some(Some(TypeAnnotatedValue::U32(1)))
+        );
+        assert!(mappings.get(&key("other")).is_none());
+    }
+
+    #[test]
+    fn mapping_failures_are_kept() {
+        let mut mappings = ResultMappings::default();
+        mappings.request(&key("failing"));
+
+        assert!(mappings.get(&key("failing")).is_none());
+
+        mappings.store(&key("failing"), Err(GolemError::runtime("mapper trapped")));
+
+        assert!(matches!(
+            mappings.get(&key("failing")),
+            Some(Err(GolemError::Runtime { .. }))
+        ));
+    }
+
+    #[test]
+    fn mapped_results_are_forgotten_with_the_invocation_results() {
+        let mut mappings = ResultMappings::default();
+        for name in ["kept", "expired", "pending"] {
+            mappings.request(&key(name));
+        }
+        mappings.store(&key("kept"), Ok(TypeAnnotatedValue::Bool(true)));
+        mappings.store(&key("expired"), Ok(TypeAnnotatedValue::Bool(false)));
+
+        let results = HashMap::from([(key("kept"), completed(1, Duration::from_secs(1)))]);
+        mappings.retain_results(&results);
+
+        assert!(mappings.get(&key("kept")).is_some());
+        assert!(!mappings.is_requested(&key("expired")));
+        assert!(mappings.is_requested(&key("pending")));
+    }
+
+    fn pending(idempotency_key: &str) -> TimestampedWorkerInvocation {
+        TimestampedWorkerInvocation {
+            timestamp: Timestamp::now_utc(),
+            invocation: WorkerInvocation::ExportedFunction {
+                idempotency_key: key(idempotency_key),
+                full_function_name: "golem:it/api.{echo}".to_string(),
+                function_input: vec![],
+                caller: None,
+            },
+        }
+    }
+
+    #[test]
+    fn held_invocations_are_skipped() {
+        let queue = VecDeque::from([pending("first"), pending("second"), pending("third")]);
+
+        assert_eq!(first_runnable_invocation(&queue, &HashSet::new()), Some(0));
+        assert_eq!(
+            first_runnable_invocation(&queue, &HashSet::from([key("first")])),
+            Some(1)
+        );
+        assert_eq!(
+            first_runnable_invocation(
+                &queue,
+                &HashSet::from([key("first"), key("second"), key("third")])
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn updates_are_never_held() {
+        let queue = VecDeque::from([
+            pending("first"),
+            TimestampedWorkerInvocation {
+                timestamp: Timestamp::now_utc(),
+                invocation: WorkerInvocation::ManualUpdate { target_version: 2 },
+            },
+        ]);
+
+        assert_eq!(
+            first_runnable_invocation(&queue, &HashSet::from([key("first")])),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn holds_are_restored_from_the_oplog() {
+        let entries = BTreeMap::from([
+            (
+                OplogIndex::from_u64(2),
+                OplogEntry::hold_pending_invocation(key("released")),
+            ),
+            (
+                OplogIndex::from_u64(3),
+                OplogEntry::hold_pending_invocation(key("cancelled")),
+            ),
+            (
+                OplogIndex::from_u64(4),
+                OplogEntry::hold_pending_invocation(key("held")),
+            ),
+            (
+                OplogIndex::from_u64(5),
+                OplogEntry::release_pending_invocation(key("released")),
+            ),
+            (
+                OplogIndex::from_u64(6),
+                OplogEntry::cancel_pending_invocation(key("cancelled")),
+            ),
+        ]);
+
+        let held = calculate_held_invocations(HashSet::from([key("earlier")]), &entries);
+
+        assert_eq!(held, HashSet::from([key("earlier"), key("held")]));
+    }
+
+    #[test]
+    fn pending_invocations_are_recovered_with_their_caller() {
+        let caller = CallerIdentity {
+            subject: "CN=client".to_string(),
+            uris: vec!["spiffe://example.com/client".to_string()],
+            dns_names: vec![],
+        };
+        let with_caller = WorkerInvocation::ExportedFunction {
+            idempotency_key: key("with-caller"),
+            full_function_name: "golem:it/api.{echo}".to_string(),
+            function_input: vec![],
+            caller: Some(caller.clone()),
+        };
+        let original = WorkerInvocation::ExportedFunctionV1 {
+            idempotency_key: key("original"),
+            full_function_name: "golem:it/api.{echo}".to_string(),
+            function_input: vec![],
+        };
+        // The entries are read back the way they were persisted in the oplog
+        let persisted =
+            |entry: OplogEntry| -> OplogEntry { deserialize(&serialize(&entry).unwrap()).unwrap() };
+        let entries = BTreeMap::from([
+            (
+                OplogIndex::from_u64(2),
+                persisted(OplogEntry::pending_worker_invocation(with_caller)),
+            ),
+            (
+                OplogIndex::from_u64(3),
+                persisted(OplogEntry::pending_worker_invocation(original)),
+            ),
+        ]);
+
+        let pending = calculate_pending_invocations(vec![], &entries);
+
+        assert_eq!(pending.len(), 2);
+        assert_eq!(
+            pending[0].invocation.idempotency_key(),
+            Some(&key("with-caller"))
+        );
+        assert_eq!(pending[0].invocation.caller(), Some(&caller));
+        assert_eq!(
+            pending[1].invocation.idempotency_key(),
+            Some(&key("original"))
+        );
+        assert_eq!(pending[1].invocation.caller(), None);
+    }
+
+    #[test]
+    async fn completed_tasks_are_not_reported_as_panics() {
+        let result = catch_task_panic("test", async {}).await;
+
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    async fn task_panics_are_caught_with_their_message() {
+        let static_message = catch_task_panic("test", async { panic!("static message") }).await;
+        let formatted_message = catch_task_panic("test", async {
+            let worker = "worker-1";
+            panic!("formatted message of {worker}")
+        })
+        .await;
+        let other_payload = catch_task_panic("test", async { std::panic::panic_any(42) }).await;
+
+        assert_eq!(static_message, Err("static message".to_string()));
+        assert_eq!(
+            formatted_message,
+            Err("formatted message of worker-1".to_string())
+        );
+        assert_eq!(other_payload, Err("unknown panic".to_string()));
+    }
+
+    #[test]
+    async fn task_panics_do_not_fail_the_spawned_task() {
+        let handle = tokio::spawn(async {
+            catch_task_panic("test", async {
+                tokio::task::yield_now().await;
+                panic!("panic after a suspension point")
+            })
+            .await
+        });
+
+        assert_eq!(
+            handle.await.unwrap(),
+            Err("panic after a suspension point".to_string())
+        );
+    }
 }
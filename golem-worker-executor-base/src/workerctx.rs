@@ -22,8 +22,8 @@ use wasmtime::{AsContextMut, ResourceLimiterAsync};
 
 use golem_common::model::oplog::WorkerResourceId;
 use golem_common::model::{
-    AccountId, ComponentVersion, IdempotencyKey, OwnedWorkerId, WorkerId, WorkerMetadata,
-    WorkerStatus, WorkerStatusRecord,
+    AccountId, CallerIdentity, ComponentVersion, IdempotencyKey, OwnedWorkerId, WorkerId,
+    WorkerMetadata, WorkerStatus, WorkerStatusRecord,
 };
 
 use crate::error::GolemError;
@@ -33,7 +33,10 @@ use crate::model::{
 use crate::services::active_workers::ActiveWorkers;
 use crate::services::blob_store::BlobStoreService;
 use crate::services::component::{ComponentMetadata, ComponentService};
+use crate::services::domain_event::DomainEventService;
+use crate::services::feature_flags::FeatureFlagService;
 use crate::services::golem_config::GolemConfig;
+use crate::services::inference::InferenceService;
 use crate::services::key_value::KeyValueService;
 use crate::services::oplog::{Oplog, OplogService};
 use crate::services::promise::PromiseService;
@@ -79,6 +82,9 @@ pub trait WorkerCtx:
     /// - `worker_service`: The service for managing workers
     /// - `key_value_service`: The service for storing key-value pairs
     /// - `blob_store_service`: The service for storing arbitrary blobs
+    /// - `domain_event_service`: The service for persisting the domain events emitted by the worker
+    /// - `feature_flag_service`: The service for resolving the feature flags of the worker
+    /// - `inference_service`: The service for loading the models used by `wasi:nn`
     /// - `event_service`: The service for publishing worker events
     /// - `active_workers`: The service for managing active workers
     /// - `oplog_service`: The service for reading and writing the oplog
@@ -101,6 +107,9 @@ pub trait WorkerCtx:
         >,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        domain_event_service: Arc<dyn DomainEventService + Send + Sync>,
+        feature_flag_service: Arc<dyn FeatureFlagService + Send + Sync>,
+        inference_service: Arc<dyn InferenceService + Send + Sync>,
         event_service: Arc<dyn WorkerEventService + Send + Sync>,
         active_workers: Arc<ActiveWorkers<Self>>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
@@ -193,6 +202,12 @@ pub trait InvocationManagement {
     /// Gets the invocation key associated with the current invocation of the worker.
     async fn get_current_idempotency_key(&self) -> Option<IdempotencyKey>;
 
+    /// Sets the identity of the API gateway client making the current invocation of the worker.
+    async fn set_current_caller_identity(&mut self, caller: Option<CallerIdentity>);
+
+    /// Gets the identity of the API gateway client making the current invocation of the worker.
+    async fn get_current_caller_identity(&self) -> Option<CallerIdentity>;
+
     /// Returns whether we are in live mode where we are executing new calls.
     fn is_live(&self) -> bool;
 
@@ -27,9 +27,16 @@ use assert2::check;
 use http_02::{Response, StatusCode};
 use redis::Commands;
 
+use golem_api_grpc::proto::golem::common::ResourceLimits;
+use golem_api_grpc::proto::golem::worker::v1::worker_error::Error;
 use golem_api_grpc::proto::golem::worker::v1::{worker_execution_error, ComponentParseFailed};
 use golem_api_grpc::proto::golem::worker::LogEvent;
-use golem_api_grpc::proto::golem::workerexecutor::v1::CompletePromiseRequest;
+use golem_api_grpc::proto::golem::workerexecutor::v1::{
+    get_invocation_result_blob_response, get_or_create_worker_response,
+    invoke_and_await_worker_streaming_response, CompletePromiseRequest, CreateWorkerRequest,
+    GetInvocationResultBlobRequest, InvokeAndAwaitWorkerRequest,
+    InvokeAndAwaitWorkerStreamingHeader,
+};
 use golem_common::model::{
     AccountId, ComponentId, FilterComparator, IdempotencyKey, PromiseId, ScanCursor,
     StringFilterComparator, TargetWorkerId, Timestamp, WorkerFilter, WorkerId, WorkerMetadata,
@@ -37,14 +44,16 @@ use golem_common::model::{
 };
 use golem_wasm_rpc::Value;
 
-use crate::common::{start, TestContext, TestWorkerExecutor};
+use crate::common::{start, start_customized, TestContext, TestWorkerExecutor};
 use crate::{LastUniqueId, Tracing, WorkerExecutorTestDependencies};
 use golem_common::model::oplog::{IndexedResourceKey, OplogIndex, WorkerResourceId};
+use golem_common::model::public_oplog::PublicOplogEntry;
 use golem_test_framework::config::TestDependencies;
 use golem_test_framework::dsl::{
     drain_connection, is_worker_execution_error, stdout_event_matching, stdout_events,
     worker_error_message, TestDslUnsafe,
 };
+use prost::Message;
 use tokio::time::sleep;
 use tonic::transport::Body;
 use tracing::{debug, info};
@@ -91,6 +100,272 @@ async fn interruption(
     check!(worker_error_message(&result.err().unwrap()).contains("Interrupted via the Golem API"));
 }
 
+#[test]
+#[tracing::instrument]
+async fn abort_running_invocation(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("interruption").await;
+    let worker_id = executor
+        .start_worker(&component_id, "abort-running-invocation-1")
+        .await;
+
+    let idempotency_key = IdempotencyKey::fresh();
+    let executor_clone = executor.clone();
+    let worker_id_clone = worker_id.clone();
+    let idempotency_key_clone = idempotency_key.clone();
+    let fiber = tokio::spawn(async move {
+        executor_clone
+            .invoke_and_await_with_key(worker_id_clone, &idempotency_key_clone, "run", vec![])
+            .await
+    });
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let aborted_other = executor
+        .abort_running_invocation(&worker_id, &IdempotencyKey::fresh())
+        .await;
+    let aborted_running = executor
+        .abort_running_invocation(&worker_id, &idempotency_key)
+        .await;
+    let result = fiber.await.unwrap();
+
+    drop(executor);
+
+    check!(!aborted_other);
+    check!(aborted_running);
+    check!(result.is_err());
+    check!(worker_error_message(&result.err().unwrap()).contains("Interrupted via the Golem API"));
+}
+
+#[test]
+#[tracing::instrument]
+async fn abort_invocation_of_idle_worker(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("option-service").await;
+    let worker_id = executor
+        .start_worker(&component_id, "abort-invocation-of-idle-worker-1")
+        .await;
+
+    let idempotency_key = IdempotencyKey::fresh();
+    let result = executor
+        .invoke_and_await_with_key(
+            &worker_id,
+            &idempotency_key,
+            "golem:it/api.{echo}",
+            vec![Value::Option(Some(Box::new(Value::String(
+                "Hello".to_string(),
+            ))))],
+        )
+        .await;
+
+    // The invocation already completed, so there is nothing to abort
+    let aborted = executor
+        .abort_running_invocation(&worker_id, &idempotency_key)
+        .await;
+
+    drop(executor);
+
+    check!(result.is_ok());
+    check!(!aborted);
+}
+
+#[test]
+#[tracing::instrument]
+async fn concurrent_get_or_create_creates_the_worker_once(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("option-service").await;
+    let worker_id = WorkerId {
+        component_id,
+        worker_name: "concurrent-get-or-create-1".to_string(),
+    };
+    let request = CreateWorkerRequest {
+        worker_id: Some(worker_id.into()),
+        component_version: 0,
+        args: vec![],
+        env: HashMap::new(),
+        account_id: Some(
+            AccountId {
+                value: "test-account".to_string(),
+            }
+            .into(),
+        ),
+        account_limits: None,
+        ttl_millis: None,
+    };
+
+    let client = executor.client().await.expect("Failed to get client");
+    let fibers = (0..8)
+        .map(|_| {
+            let mut client = client.clone();
+            let request = request.clone();
+            tokio::spawn(async move {
+                client
+                    .get_or_create_worker(request)
+                    .await
+                    .unwrap()
+                    .into_inner()
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut created = Vec::new();
+    for fiber in fibers {
+        match fiber.await.unwrap().result {
+            Some(get_or_create_worker_response::Result::Success(success)) => {
+                created.push(success.created)
+            }
+            other => panic!("Unexpected response: {other:?}"),
+        }
+    }
+    let again = client
+        .clone()
+        .get_or_create_worker(request)
+        .await
+        .unwrap()
+        .into_inner();
+
+    drop(executor);
+
+    check!(created.iter().filter(|created| **created).count() == 1);
+    check!(matches!(
+        again.result,
+        Some(get_or_create_worker_response::Result::Success(success)) if !success.created
+    ));
+}
+
+#[test]
+#[tracing::instrument]
+async fn cancel_pending_invocation(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("interruption").await;
+    let worker_id = executor
+        .start_worker(&component_id, "cancel-pending-invocation-1")
+        .await;
+
+    // The first invocation keeps the worker busy, so the second one stays in the queue
+    let running_key = IdempotencyKey::fresh();
+    executor
+        .invoke_with_key(&worker_id, &running_key, "run", vec![])
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let pending_key = IdempotencyKey::fresh();
+    executor
+        .invoke_with_key(&worker_id, &pending_key, "run", vec![])
+        .await
+        .unwrap();
+
+    let canceled_pending = executor.cancel_invocation(&worker_id, &pending_key).await;
+    let canceled_running = executor.cancel_invocation(&worker_id, &running_key).await;
+    let cancel_unknown = golem_test_framework::dsl::TestDsl::cancel_invocation(
+        &executor,
+        &worker_id,
+        &IdempotencyKey::fresh(),
+    )
+    .await;
+
+    let oplog = executor.get_oplog(&worker_id, OplogIndex::INITIAL).await;
+
+    drop(executor);
+
+    check!(canceled_pending);
+    check!(!canceled_running);
+    check!(cancel_unknown.is_err());
+    check!(oplog.iter().any(|entry| matches!(
+        entry,
+        PublicOplogEntry::CancelInvocation(params) if params.idempotency_key == pending_key
+    )));
+    check!(!oplog.iter().any(|entry| matches!(
+        entry,
+        PublicOplogEntry::CancelInvocation(params) if params.idempotency_key == running_key
+    )));
+}
+
+#[test]
+#[tracing::instrument]
+async fn hold_and_release_pending_invocation(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("interruption").await;
+    let worker_id = executor
+        .start_worker(&component_id, "hold-and-release-pending-invocation-1")
+        .await;
+
+    // The first invocation keeps the worker busy, so the second one stays in the queue
+    executor
+        .invoke_with_key(&worker_id, &IdempotencyKey::fresh(), "run", vec![])
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let pending_key = IdempotencyKey::fresh();
+    executor
+        .invoke_with_key(&worker_id, &pending_key, "run", vec![])
+        .await
+        .unwrap();
+
+    let hold_unknown = golem_test_framework::dsl::TestDsl::hold_invocation(
+        &executor,
+        &worker_id,
+        &IdempotencyKey::fresh(),
+    )
+    .await;
+    executor.hold_invocation(&worker_id, &pending_key).await;
+
+    // The hold is restored from the oplog, so it can still be released after a restart
+    drop(executor);
+    let executor = start(deps, &context).await.unwrap();
+
+    executor.release_invocation(&worker_id, &pending_key).await;
+    let release_again =
+        golem_test_framework::dsl::TestDsl::release_invocation(&executor, &worker_id, &pending_key)
+            .await;
+
+    let oplog = executor.get_oplog(&worker_id, OplogIndex::INITIAL).await;
+
+    drop(executor);
+
+    check!(hold_unknown.is_err());
+    check!(release_again.is_err());
+    check!(oplog.iter().any(|entry| matches!(
+        entry,
+        PublicOplogEntry::HoldInvocation(params) if params.idempotency_key == pending_key
+    )));
+    check!(oplog.iter().any(|entry| matches!(
+        entry,
+        PublicOplogEntry::ReleaseInvocation(params) if params.idempotency_key == pending_key
+    )));
+}
+
 #[test]
 #[tracing::instrument]
 async fn simulated_crash(
@@ -820,6 +1095,259 @@ async fn invoking_with_same_idempotency_key_is_idempotent_after_restart(
     );
 }
 
+#[test]
+#[tracing::instrument]
+async fn timed_out_invocation_is_not_resumed(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("interruption").await;
+    let worker_id = executor
+        .start_worker(&component_id, "timed-out-invocation-1")
+        .await;
+
+    let idempotency_key = IdempotencyKey::fresh();
+    let result1 = golem_test_framework::dsl::TestDsl::invoke_and_await_with_timeout(
+        &executor,
+        &worker_id,
+        &idempotency_key,
+        "run",
+        vec![],
+        Duration::from_secs(2),
+    )
+    .await
+    .unwrap();
+
+    // Retrying with the same idempotency key returns the recorded failure
+    let start_time = tokio::time::Instant::now();
+    let result2 = executor
+        .invoke_and_await_with_key(&worker_id, &idempotency_key, "run", vec![])
+        .await;
+    let elapsed = start_time.elapsed();
+
+    drop(executor);
+    let executor = start(deps, &context).await.unwrap();
+
+    let mut rx = executor.capture_output(&worker_id).await;
+    let result3 = executor
+        .invoke_and_await_with_key(&worker_id, &idempotency_key, "run", vec![])
+        .await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    let (metadata, _) = executor.get_worker_metadata(&worker_id).await.unwrap();
+
+    let mut events = vec![];
+    rx.recv_many(&mut events, 100).await;
+    drop(executor);
+
+    check!(worker_error_message(&result1.unwrap_err()).contains("timed out"));
+    check!(worker_error_message(&result2.unwrap_err()).contains("timed out"));
+    check!(elapsed.as_secs() < 2);
+    check!(worker_error_message(&result3.unwrap_err()).contains("timed out"));
+    check!(metadata.last_known_status.status != WorkerStatus::Running);
+    check!(stdout_events(events.into_iter()).is_empty());
+}
+
+#[test]
+#[tracing::instrument]
+async fn oversized_result_is_stored_once_per_idempotency_key(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start_customized(deps, &context, |config| {
+        config.limits.max_result_size = 64;
+    })
+    .await
+    .unwrap();
+
+    let component_id = executor.store_component("shopping-cart").await;
+    let worker_id = executor
+        .start_worker(&component_id, "oversized-result-1")
+        .await;
+
+    let _ = executor
+        .invoke_and_await(
+            &worker_id,
+            "golem:it/api.{initialize-cart}",
+            vec![Value::String("test-user-1".to_string())],
+        )
+        .await;
+    for idx in 0..4 {
+        let _ = executor
+            .invoke_and_await(
+                &worker_id,
+                "golem:it/api.{add-item}",
+                vec![Value::Record(vec![
+                    Value::String(format!("G100{idx}")),
+                    Value::String(format!("Golem T-Shirt {idx}")),
+                    Value::F32(100.0),
+                    Value::U32(idx),
+                ])],
+            )
+            .await;
+    }
+
+    let idempotency_key = IdempotencyKey::fresh();
+    let result1 = executor
+        .invoke_and_await_with_key(
+            &worker_id,
+            &idempotency_key,
+            "golem:it/api.{get-cart-contents}",
+            vec![],
+        )
+        .await;
+    let result2 = executor
+        .invoke_and_await_with_key(
+            &worker_id,
+            &idempotency_key,
+            "golem:it/api.{get-cart-contents}",
+            vec![],
+        )
+        .await;
+    let result3 = executor
+        .invoke_and_await_with_key(
+            &worker_id,
+            &IdempotencyKey::fresh(),
+            "golem:it/api.{get-cart-contents}",
+            vec![],
+        )
+        .await;
+
+    let blob_key = |result: Result<Vec<Value>, Error>| match result {
+        Err(Error::InternalError(error)) => match error.error {
+            Some(worker_execution_error::Error::ResultTooLarge(error)) => error.blob_key,
+            other => panic!("Unexpected error: {other:?}"),
+        },
+        other => panic!("Unexpected result: {other:?}"),
+    };
+    let blob_key1 = blob_key(result1);
+    let blob_key2 = blob_key(result2);
+    let blob_key3 = blob_key(result3);
+
+    let mut stream = executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .get_invocation_result_blob(GetInvocationResultBlobRequest {
+            account_id: Some(
+                AccountId {
+                    value: "test-account".to_string(),
+                }
+                .into(),
+            ),
+            blob_key: blob_key1.clone(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let mut payload = Vec::new();
+    while let Some(response) = stream.message().await.unwrap() {
+        match response.result {
+            Some(get_invocation_result_blob_response::Result::Chunk(chunk)) => {
+                payload.extend(chunk)
+            }
+            other => panic!("Unexpected response: {other:?}"),
+        }
+    }
+    let stored = golem_wasm_rpc::protobuf::TypeAnnotatedValue::decode(payload.as_slice()).unwrap();
+
+    drop(executor);
+
+    check!(blob_key1 == blob_key2);
+    check!(blob_key1 != blob_key3);
+    check!(blob_key1.starts_with(&component_id.to_string()));
+    check!(stored.type_annotated_value.is_some());
+}
+
+#[test]
+#[tracing::instrument]
+async fn list_result_is_streamed_by_elements(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("shopping-cart").await;
+    let worker_id = executor
+        .start_worker(&component_id, "streamed-result-1")
+        .await;
+
+    let _ = executor
+        .invoke_and_await(
+            &worker_id,
+            "golem:it/api.{initialize-cart}",
+            vec![Value::String("test-user-1".to_string())],
+        )
+        .await;
+    for idx in 0..3 {
+        let _ = executor
+            .invoke_and_await(
+                &worker_id,
+                "golem:it/api.{add-item}",
+                vec![Value::Record(vec![
+                    Value::String(format!("G100{idx}")),
+                    Value::String(format!("Golem T-Shirt {idx}")),
+                    Value::F32(100.0),
+                    Value::U32(idx),
+                ])],
+            )
+            .await;
+    }
+
+    let mut stream = executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .invoke_and_await_worker_streaming(InvokeAndAwaitWorkerRequest {
+            worker_id: Some(TargetWorkerId::from(worker_id.clone()).into()),
+            name: "golem:it/api.{get-cart-contents}".to_string(),
+            input: vec![],
+            idempotency_key: Some(IdempotencyKey::fresh().into()),
+            account_id: Some(
+                AccountId {
+                    value: "test-account".to_string(),
+                }
+                .into(),
+            ),
+            account_limits: Some(ResourceLimits {
+                available_fuel: i64::MAX,
+                max_memory_per_worker: i64::MAX,
+            }),
+            context: None,
+            blob_input: vec![],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    let mut responses = Vec::new();
+    while let Some(response) = stream.message().await.unwrap() {
+        responses.push(response.result);
+    }
+
+    drop(executor);
+
+    check!(
+        responses.first()
+            == Some(&Some(
+                invoke_and_await_worker_streaming_response::Result::Header(
+                    InvokeAndAwaitWorkerStreamingHeader {
+                        list_elements: true
+                    }
+                )
+            ))
+    );
+    check!(responses.len() == 4);
+    check!(responses[1..].iter().all(|response| matches!(
+        response,
+        Some(invoke_and_await_worker_streaming_response::Result::Chunk(_))
+    )));
+}
+
 #[test]
 #[tracing::instrument]
 async fn optional_parameters(
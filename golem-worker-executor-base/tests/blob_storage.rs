@@ -21,7 +21,7 @@ use tempfile::{tempdir, TempDir};
 use testcontainers_modules::minio::MinIO;
 use uuid::Uuid;
 
-use golem_common::model::{AccountId, ComponentId};
+use golem_common::model::{AccountId, ComponentId, WorkerId};
 use golem_worker_executor_base::services::golem_config::S3BlobStorageConfig;
 use golem_worker_executor_base::storage::blob::{
     fs, memory, s3, BlobStorage, BlobStorageNamespace,
@@ -513,6 +513,517 @@ macro_rules! test_blob_storage {
                         ]
                 );
             }
+
+            #[test]
+            #[tracing::instrument]
+            async fn get_metadata_and_slice() {
+                let test = $init().await;
+                let storage = test.get_blob_storage();
+                let namespace = $ns();
+
+                let path = Path::new("test-dir/test-file");
+                let data = Bytes::from("test-data");
+
+                let metadata1 = storage
+                    .get_metadata(
+                        "get_metadata_and_slice",
+                        "get-metadata",
+                        namespace.clone(),
+                        path,
+                    )
+                    .await
+                    .unwrap();
+                storage
+                    .put_raw(
+                        "get_metadata_and_slice",
+                        "put-raw",
+                        namespace.clone(),
+                        path,
+                        &data,
+                    )
+                    .await
+                    .unwrap();
+                let metadata2 = storage
+                    .get_metadata(
+                        "get_metadata_and_slice",
+                        "get-metadata-2",
+                        namespace.clone(),
+                        path,
+                    )
+                    .await
+                    .unwrap();
+                let slice = storage
+                    .get_raw_slice(
+                        "get_metadata_and_slice",
+                        "get-raw-slice",
+                        namespace.clone(),
+                        path,
+                        5,
+                        9,
+                    )
+                    .await
+                    .unwrap();
+
+                check!(metadata1.is_none());
+                check!(metadata2.map(|metadata| metadata.size) == Some(9));
+                check!(slice == Some(Bytes::from("data")));
+            }
+
+            #[test]
+            #[tracing::instrument]
+            async fn copy_and_move() {
+                let test = $init().await;
+                let storage = test.get_blob_storage();
+                let namespace = $ns();
+
+                let path = Path::new("test-file");
+                let copied_path = Path::new("copied-dir/test-file");
+                let moved_path = Path::new("moved-dir/inner-dir/test-file");
+                let data = Bytes::from("test-data");
+
+                storage
+                    .put_raw("copy_and_move", "put-raw", namespace.clone(), path, &data)
+                    .await
+                    .unwrap();
+                storage
+                    .copy(
+                        "copy_and_move",
+                        "copy",
+                        namespace.clone(),
+                        path,
+                        copied_path,
+                    )
+                    .await
+                    .unwrap();
+                storage
+                    .r#move("copy_and_move", "move", namespace.clone(), path, moved_path)
+                    .await
+                    .unwrap();
+
+                let original = storage
+                    .get_raw("copy_and_move", "get-raw", namespace.clone(), path)
+                    .await
+                    .unwrap();
+                let copied = storage
+                    .get_raw("copy_and_move", "get-raw-2", namespace.clone(), copied_path)
+                    .await
+                    .unwrap();
+                let moved = storage
+                    .get_raw("copy_and_move", "get-raw-3", namespace.clone(), moved_path)
+                    .await
+                    .unwrap();
+
+                check!(original == None);
+                check!(copied == Some(data.clone()));
+                check!(moved == Some(data));
+            }
+
+            #[test]
+            #[tracing::instrument]
+            async fn rename_replaces_existing() {
+                let test = $init().await;
+                let storage = test.get_blob_storage();
+                let namespace = $ns();
+
+                let path = Path::new("test-file");
+                let renamed_path = Path::new("renamed-dir/test-file");
+                let data = Bytes::from("test-data");
+
+                storage
+                    .put_raw(
+                        "rename_replaces_existing",
+                        "put-raw",
+                        namespace.clone(),
+                        path,
+                        &data,
+                    )
+                    .await
+                    .unwrap();
+                storage
+                    .put_raw(
+                        "rename_replaces_existing",
+                        "put-raw-2",
+                        namespace.clone(),
+                        renamed_path,
+                        &Bytes::from("old-data"),
+                    )
+                    .await
+                    .unwrap();
+                storage
+                    .rename(
+                        "rename_replaces_existing",
+                        "rename",
+                        namespace.clone(),
+                        path,
+                        renamed_path,
+                    )
+                    .await
+                    .unwrap();
+
+                let original = storage
+                    .get_raw(
+                        "rename_replaces_existing",
+                        "get-raw",
+                        namespace.clone(),
+                        path,
+                    )
+                    .await
+                    .unwrap();
+                let renamed = storage
+                    .get_raw(
+                        "rename_replaces_existing",
+                        "get-raw-2",
+                        namespace.clone(),
+                        renamed_path,
+                    )
+                    .await
+                    .unwrap();
+                let missing = storage
+                    .rename(
+                        "rename_replaces_existing",
+                        "rename-2",
+                        namespace.clone(),
+                        path,
+                        renamed_path,
+                    )
+                    .await;
+
+                check!(original == None);
+                check!(renamed == Some(data));
+                check!(missing.is_err());
+            }
+
+            #[test]
+            #[tracing::instrument]
+            async fn put_raw_staged() {
+                let test = $init().await;
+                let storage = test.get_blob_storage();
+                let namespace = $ns();
+
+                let dir = Path::new("staged-dir");
+                let path = dir.join("test-file");
+                let data = Bytes::from("test-data");
+
+                storage
+                    .create_dir("put_raw_staged", "create-dir", namespace.clone(), dir)
+                    .await
+                    .unwrap();
+                storage
+                    .put_raw_staged(
+                        "put_raw_staged",
+                        "put-raw-staged",
+                        namespace.clone(),
+                        &path,
+                        &Bytes::from("old-data"),
+                    )
+                    .await
+                    .unwrap();
+                storage
+                    .put_raw_staged(
+                        "put_raw_staged",
+                        "put-raw-staged-2",
+                        namespace.clone(),
+                        &path,
+                        &data,
+                    )
+                    .await
+                    .unwrap();
+
+                let result = storage
+                    .get_raw("put_raw_staged", "get-raw", namespace.clone(), &path)
+                    .await
+                    .unwrap();
+                let entries = storage
+                    .list_dir("put_raw_staged", "entries", namespace.clone(), dir)
+                    .await
+                    .unwrap();
+
+                check!(result == Some(data));
+                check!(entries == vec![path]);
+            }
+        }
+    };
+}
+
+// The initial file system of the workers is extracted to and copied between the namespaces of the
+// storage, so these have to be supported by every backend the executor can run with
+macro_rules! test_blob_storage_ifs {
+    ( $name:ident, $init:expr ) => {
+        mod $name {
+            use test_r::test;
+
+            use assert2::check;
+            use bytes::Bytes;
+            use golem_common::model::initial_file_system::InitialFilePermissions;
+            use golem_common::model::{ComponentId, WorkerId, WorkerMetadata};
+            use golem_worker_executor_base::storage::blob::*;
+            use std::path::Path;
+            use uuid::Uuid;
+
+            use crate::blob_storage::{custom_storage, initial_file_system, GetBlobStorage};
+
+            #[test]
+            #[tracing::instrument]
+            async fn deep_tree_with_missing_parents() {
+                let test = $init().await;
+                let storage = test.get_blob_storage();
+                let namespace = custom_storage();
+
+                let path = Path::new("a/b/c/d/test-file");
+                let data = Bytes::from("test-data");
+
+                storage
+                    .put_raw(
+                        "deep_tree_with_missing_parents",
+                        "put-raw",
+                        namespace.clone(),
+                        path,
+                        &data,
+                    )
+                    .await
+                    .unwrap();
+
+                let result = storage
+                    .get_raw(
+                        "deep_tree_with_missing_parents",
+                        "get-raw",
+                        namespace.clone(),
+                        path,
+                    )
+                    .await
+                    .unwrap();
+                let file = storage
+                    .exists(
+                        "deep_tree_with_missing_parents",
+                        "exists",
+                        namespace.clone(),
+                        path,
+                    )
+                    .await
+                    .unwrap();
+                let parent = storage
+                    .exists(
+                        "deep_tree_with_missing_parents",
+                        "exists-2",
+                        namespace.clone(),
+                        Path::new("a/b"),
+                    )
+                    .await
+                    .unwrap();
+                let entries = storage
+                    .list_dir(
+                        "deep_tree_with_missing_parents",
+                        "list-dir",
+                        namespace.clone(),
+                        Path::new("a/b/c/d"),
+                    )
+                    .await
+                    .unwrap();
+
+                check!(result == Some(data));
+                check!(file == ExistsResult::File);
+                check!(parent == ExistsResult::Directory);
+                check!(entries == vec![path.to_path_buf()]);
+            }
+
+            #[test]
+            #[tracing::instrument]
+            async fn copy_dir_contents_deep_tree() {
+                let test = $init().await;
+                let storage = test.get_blob_storage();
+                let source = initial_file_system();
+                let target = custom_storage();
+
+                storage
+                    .put_raw(
+                        "copy_dir_contents_deep_tree",
+                        "put-raw",
+                        source.clone(),
+                        Path::new("source-dir/test-file1"),
+                        &Bytes::from("test-data1"),
+                    )
+                    .await
+                    .unwrap();
+                storage
+                    .put_raw(
+                        "copy_dir_contents_deep_tree",
+                        "put-raw-2",
+                        source.clone(),
+                        Path::new("source-dir/inner-dir/deep-dir/test-file2"),
+                        &Bytes::from("test-data2"),
+                    )
+                    .await
+                    .unwrap();
+
+                storage
+                    .copy_dir_contents(
+                        "copy_dir_contents_deep_tree",
+                        "copy-dir-contents",
+                        Path::new("source-dir"),
+                        Path::new("missing-parent/target-dir"),
+                        source.clone(),
+                        target.clone(),
+                    )
+                    .await
+                    .unwrap();
+
+                let result1 = storage
+                    .get_raw(
+                        "copy_dir_contents_deep_tree",
+                        "get-raw",
+                        target.clone(),
+                        Path::new("missing-parent/target-dir/test-file1"),
+                    )
+                    .await
+                    .unwrap();
+                let result2 = storage
+                    .get_raw(
+                        "copy_dir_contents_deep_tree",
+                        "get-raw-2",
+                        target.clone(),
+                        Path::new("missing-parent/target-dir/inner-dir/deep-dir/test-file2"),
+                    )
+                    .await
+                    .unwrap();
+                let original = storage
+                    .get_raw(
+                        "copy_dir_contents_deep_tree",
+                        "get-raw-3",
+                        source.clone(),
+                        Path::new("source-dir/test-file1"),
+                    )
+                    .await
+                    .unwrap();
+
+                check!(result1 == Some(Bytes::from("test-data1")));
+                check!(result2 == Some(Bytes::from("test-data2")));
+                check!(original == Some(Bytes::from("test-data1")));
+            }
+
+            #[test]
+            #[tracing::instrument]
+            async fn copy_dir_contents_of_missing_dir() {
+                let test = $init().await;
+                let storage = test.get_blob_storage();
+
+                let result = storage
+                    .copy_dir_contents(
+                        "copy_dir_contents_of_missing_dir",
+                        "copy-dir-contents",
+                        Path::new("missing-dir"),
+                        Path::new("target-dir"),
+                        initial_file_system(),
+                        custom_storage(),
+                    )
+                    .await;
+
+                check!(result.is_err());
+            }
+
+            #[test]
+            #[tracing::instrument]
+            async fn initialize_worker_ifs() {
+                let test = $init().await;
+                let storage = test.get_blob_storage();
+                let component_id = ComponentId(Uuid::new_v4());
+                let worker_id = WorkerId {
+                    component_id: component_id.clone(),
+                    worker_name: "test-worker".to_string(),
+                };
+                let worker_metadata =
+                    WorkerMetadata::default(worker_id, crate::blob_storage::account_id());
+
+                storage
+                    .put_raw(
+                        "initialize_worker_ifs",
+                        "put-raw",
+                        initial_file_system(),
+                        &Path::new(&component_id.to_string()).join("extracted/config/app.json"),
+                        &Bytes::from("{}"),
+                    )
+                    .await
+                    .unwrap();
+
+                storage
+                    .initialize_worker_ifs(worker_metadata)
+                    .await
+                    .unwrap();
+
+                let result = storage
+                    .get_raw(
+                        "initialize_worker_ifs",
+                        "get-raw",
+                        custom_storage(),
+                        &Path::new(&component_id.to_string()).join("test-worker/config/app.json"),
+                    )
+                    .await
+                    .unwrap();
+
+                check!(result == Some(Bytes::from("{}")));
+            }
+
+            #[test]
+            #[tracing::instrument]
+            async fn set_permissions() {
+                let test = $init().await;
+                let storage = test.get_blob_storage();
+                let namespace = custom_storage();
+
+                let path = Path::new("test-dir/test-file");
+                let data = Bytes::from("test-data");
+
+                storage
+                    .put_raw("set_permissions", "put-raw", namespace.clone(), path, &data)
+                    .await
+                    .unwrap();
+                let set_read_only = storage
+                    .set_permissions(namespace.clone(), path, InitialFilePermissions::ReadOnly)
+                    .await;
+                let read_only = storage
+                    .get_raw("set_permissions", "get-raw", namespace.clone(), path)
+                    .await
+                    .unwrap();
+                storage
+                    .set_permissions(namespace.clone(), path, InitialFilePermissions::ReadWrite)
+                    .await
+                    .unwrap();
+                storage
+                    .put_raw(
+                        "set_permissions",
+                        "put-raw-2",
+                        namespace.clone(),
+                        path,
+                        &Bytes::from("test-data2"),
+                    )
+                    .await
+                    .unwrap();
+                let read_write = storage
+                    .get_raw("set_permissions", "get-raw-2", namespace.clone(), path)
+                    .await
+                    .unwrap();
+
+                check!(set_read_only.is_ok() == storage.supports_permissions());
+                check!(read_only == Some(data));
+                check!(read_write == Some(Bytes::from("test-data2")));
+            }
+
+            #[test]
+            #[tracing::instrument]
+            async fn missing_local_files() {
+                let test = $init().await;
+                let storage = test.get_blob_storage();
+
+                let root = Path::new("/missing-root");
+                let path = root.join("missing-file");
+
+                let file = storage.get_file(&path).await;
+                let entries = storage.get_directory_entries(root, &path).await;
+                let file_or_directory = storage.get_file_or_directory(root, &path).await;
+
+                check!(!matches!(file, Ok(Ok(_))));
+                check!(!matches!(entries, Ok(Ok(_))));
+                check!(file_or_directory.is_err());
+            }
         }
     };
 }
@@ -677,6 +1188,20 @@ pub(crate) fn compressed_oplog() -> BlobStorageNamespace {
     }
 }
 
+pub(crate) fn account_id() -> AccountId {
+    AccountId {
+        value: "test-account".to_string(),
+    }
+}
+
+pub(crate) fn custom_storage() -> BlobStorageNamespace {
+    BlobStorageNamespace::CustomStorage(account_id())
+}
+
+pub(crate) fn initial_file_system() -> BlobStorageNamespace {
+    BlobStorageNamespace::InitialFileSystem(account_id())
+}
+
 pub(crate) async fn sqlite() -> impl GetBlobStorage {
     let sqlx_pool_sqlite = SqlitePoolOptions::new()
         .max_connections(10)
@@ -744,3 +1269,279 @@ test_blob_storage!(
     crate::blob_storage::sqlite,
     crate::blob_storage::compressed_oplog
 );
+
+test_blob_storage_ifs!(in_memory_ifs, crate::blob_storage::in_memory);
+test_blob_storage_ifs!(filesystem_ifs, crate::blob_storage::fs);
+test_blob_storage_ifs!(s3_no_prefix_ifs, crate::blob_storage::s3);
+test_blob_storage_ifs!(s3_prefixed_ifs, crate::blob_storage::s3_prefixed);
+test_blob_storage_ifs!(sqlite_ifs, crate::blob_storage::sqlite);
+
+pub(crate) fn oplog_payload() -> BlobStorageNamespace {
+    BlobStorageNamespace::OplogPayload {
+        account_id: AccountId {
+            value: "test-account".to_string(),
+        },
+        worker_id: WorkerId {
+            component_id: ComponentId(Uuid::new_v4()),
+            worker_name: "test-worker".to_string(),
+        },
+    }
+}
+
+mod filesystem_sharding {
+    use test_r::test;
+
+    use assert2::check;
+    use bytes::Bytes;
+    use golem_worker_executor_base::storage::blob::fs::FileSystemBlobStorage;
+    use golem_worker_executor_base::storage::blob::{BlobStorage, ExistsResult};
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    #[test]
+    async fn sharded_payloads_are_not_stored_in_the_worker_directory() {
+        let dir = tempdir().unwrap();
+        let storage = FileSystemBlobStorage::new(dir.path())
+            .await
+            .unwrap()
+            .with_oplog_payload_sharding(16);
+        let namespace = crate::blob_storage::oplog_payload();
+        let path = Path::new("0123456789abcdef/payload-1");
+        let data = Bytes::from("test-data");
+
+        storage
+            .put_raw("sharding", "put-raw", namespace.clone(), path, &data)
+            .await
+            .unwrap();
+
+        let result = storage
+            .get_raw("sharding", "get-raw", namespace.clone(), path)
+            .await
+            .unwrap();
+        let unsharded = FileSystemBlobStorage::new(dir.path())
+            .await
+            .unwrap()
+            .get_raw("sharding", "get-raw", namespace.clone(), path)
+            .await
+            .unwrap();
+
+        check!(result == Some(data));
+        check!(unsharded == None);
+    }
+
+    #[test]
+    async fn unsharded_payloads_remain_readable() {
+        let dir = tempdir().unwrap();
+        let namespace = crate::blob_storage::oplog_payload();
+        let path = Path::new("0123456789abcdef/payload-1");
+        let data = Bytes::from("test-data");
+
+        FileSystemBlobStorage::new(dir.path())
+            .await
+            .unwrap()
+            .put_raw("sharding", "put-raw", namespace.clone(), path, &data)
+            .await
+            .unwrap();
+
+        let storage = FileSystemBlobStorage::new(dir.path())
+            .await
+            .unwrap()
+            .with_oplog_payload_sharding(256);
+
+        let result = storage
+            .get_raw("sharding", "get-raw", namespace.clone(), path)
+            .await
+            .unwrap();
+        let exists = storage
+            .exists("sharding", "exists", namespace.clone(), path)
+            .await
+            .unwrap();
+        storage
+            .delete("sharding", "delete", namespace.clone(), path)
+            .await
+            .unwrap();
+        let exists_after_delete = storage
+            .exists("sharding", "exists", namespace.clone(), path)
+            .await
+            .unwrap();
+
+        check!(result == Some(data));
+        check!(exists == ExistsResult::File);
+        check!(exists_after_delete == ExistsResult::DoesNotExist);
+    }
+}
+
+mod filesystem_links {
+    use test_r::test;
+
+    use assert2::check;
+    use bytes::Bytes;
+    use golem_common::model::AccountId;
+    use golem_worker_executor_base::storage::blob::fs::FileSystemBlobStorage;
+    use golem_worker_executor_base::storage::blob::{BlobStorage, BlobStorageNamespace};
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    #[test]
+    async fn linked_files_share_their_contents() {
+        let dir = tempdir().unwrap();
+        let storage = FileSystemBlobStorage::new(dir.path()).await.unwrap();
+        let namespace = BlobStorageNamespace::CustomStorage(AccountId {
+            value: "test-account".to_string(),
+        });
+        let layer_path = Path::new("component/.layers/0/data/file.txt");
+        let worker_path = Path::new("component/worker/extracted/data/file.txt");
+        let data = Bytes::from("test-data");
+
+        storage
+            .put_raw("links", "put-raw", namespace.clone(), layer_path, &data)
+            .await
+            .unwrap();
+        storage
+            .put_raw("links", "put-raw", namespace.clone(), worker_path, b"old")
+            .await
+            .unwrap();
+
+        let linked = storage
+            .link("links", "link", namespace.clone(), layer_path, worker_path)
+            .await
+            .unwrap();
+        storage
+            .delete("links", "delete", namespace.clone(), layer_path)
+            .await
+            .unwrap();
+        let result = storage
+            .get_raw("links", "get-raw", namespace.clone(), worker_path)
+            .await
+            .unwrap();
+
+        check!(storage.supports_links());
+        check!(linked);
+        check!(result == Some(data));
+    }
+}
+
+mod in_memory_rename {
+    use test_r::test;
+
+    use assert2::check;
+    use golem_common::model::AccountId;
+    use golem_worker_executor_base::storage::blob::memory::InMemoryBlobStorage;
+    use golem_worker_executor_base::storage::blob::{BlobStorage, BlobStorageNamespace};
+    use std::path::Path;
+
+    #[test]
+    async fn paths_without_file_name_are_rejected() {
+        let storage = InMemoryBlobStorage::new();
+        let namespace = BlobStorageNamespace::CustomStorage(AccountId {
+            value: "test-account".to_string(),
+        });
+
+        let from_root = storage
+            .rename(
+                "rename",
+                "rename",
+                namespace.clone(),
+                Path::new("/"),
+                Path::new("file"),
+            )
+            .await;
+        let to_root = storage
+            .rename(
+                "rename",
+                "rename",
+                namespace.clone(),
+                Path::new("file"),
+                Path::new("/"),
+            )
+            .await;
+
+        check!(from_root.is_err());
+        check!(to_root.is_err());
+    }
+}
+
+mod filesystem_staging {
+    use test_r::test;
+
+    use assert2::check;
+    use golem_common::model::AccountId;
+    use golem_worker_executor_base::services::blob_store::FileOrDirectoryResponse;
+    use golem_worker_executor_base::storage::blob::fs::FileSystemBlobStorage;
+    use golem_worker_executor_base::storage::blob::{
+        is_staging_path, staging_path_of, BlobStorage, BlobStorageNamespace,
+    };
+    use std::fs::File;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+    use tempfile::tempdir;
+
+    #[test]
+    fn staging_paths_are_recognized() {
+        let path = Path::new("dir/file.txt");
+
+        check!(is_staging_path(&staging_path_of(path)));
+        check!(!is_staging_path(path));
+        check!(!is_staging_path(Path::new("dir/.file.txt.tmp")));
+        check!(!is_staging_path(Path::new("/")));
+    }
+
+    #[test]
+    async fn leftover_staging_files_are_hidden_and_removed_once_stale() {
+        let dir = tempdir().unwrap();
+        let storage = FileSystemBlobStorage::new(dir.path()).await.unwrap();
+        let account_id = AccountId {
+            value: "test-account".to_string(),
+        };
+        let namespace = BlobStorageNamespace::CustomStorage(account_id.clone());
+
+        storage
+            .put_raw(
+                "staging",
+                "put-raw",
+                namespace.clone(),
+                Path::new("file"),
+                b"data",
+            )
+            .await
+            .unwrap();
+
+        let namespace_root = dir
+            .path()
+            .canonicalize()
+            .unwrap()
+            .join("custom_data")
+            .join(account_id.to_string());
+        let stale = staging_path_of(&namespace_root.join("file"));
+        let fresh = staging_path_of(&namespace_root.join("file"));
+        let now = SystemTime::now();
+        File::create(&stale)
+            .unwrap()
+            .set_modified(now - Duration::from_secs(2 * 60 * 60))
+            .unwrap();
+        File::create(&fresh).unwrap();
+
+        let entries = storage
+            .list_dir("staging", "list-dir", namespace.clone(), Path::new(""))
+            .await
+            .unwrap();
+        let listing = storage
+            .get_file_or_directory(&namespace_root, &namespace_root)
+            .await
+            .unwrap();
+
+        storage
+            .remove_stale_staging_files(now - Duration::from_secs(60 * 60))
+            .await;
+
+        check!(entries == vec![Path::new("file").to_path_buf()]);
+        let listed = match listing {
+            FileOrDirectoryResponse::DirectoryListing(entries) => entries,
+            FileOrDirectoryResponse::FileContent(_) => vec![],
+        };
+
+        check!(listed == vec![("file".to_string(), false)]);
+        check!(!stale.exists());
+        check!(fresh.exists());
+    }
+}
@@ -14,15 +14,15 @@ use std::sync::{Arc, RwLock, Weak};
 use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
 
 use golem_common::model::{
-    AccountId, ComponentId, ComponentVersion, IdempotencyKey, OwnedWorkerId, ScanCursor,
-    WorkerFilter, WorkerId, WorkerMetadata, WorkerStatus, WorkerStatusRecord,
+    AccountId, CallerIdentity, ComponentId, ComponentVersion, IdempotencyKey, OwnedWorkerId,
+    ScanCursor, WorkerFilter, WorkerId, WorkerMetadata, WorkerStatus, WorkerStatusRecord,
 };
 use golem_worker_executor_base::error::GolemError;
 use golem_worker_executor_base::services::golem_config::{
     BlobStorageConfig, CompiledComponentServiceConfig, CompiledComponentServiceEnabledConfig,
     ComponentServiceConfig, ComponentServiceLocalConfig, GolemConfig, IndexedStorageConfig,
-    KeyValueStorageConfig, LocalFileSystemBlobStorageConfig, MemoryConfig,
-    ShardManagerServiceConfig, WorkerServiceGrpcConfig,
+    KeyValueStorageConfig, LocalFileSystemBlobStorageConfig, ShardManagerServiceConfig,
+    WorkerServiceGrpcConfig,
 };
 
 use golem_worker_executor_base::durable_host::{
@@ -34,6 +34,9 @@ use golem_worker_executor_base::model::{
 use golem_worker_executor_base::services::active_workers::ActiveWorkers;
 use golem_worker_executor_base::services::blob_store::BlobStoreService;
 use golem_worker_executor_base::services::component::{ComponentMetadata, ComponentService};
+use golem_worker_executor_base::services::domain_event::DomainEventService;
+use golem_worker_executor_base::services::feature_flags::FeatureFlagService;
+use golem_worker_executor_base::services::inference::InferenceService;
 use golem_worker_executor_base::services::key_value::KeyValueService;
 use golem_worker_executor_base::services::oplog::{Oplog, OplogService};
 use golem_worker_executor_base::services::promise::PromiseService;
@@ -56,7 +59,7 @@ use tokio::runtime::Handle;
 use tokio::task::JoinHandle;
 
 use golem::api0_2_0;
-use golem_common::config::RedisConfig;
+use golem_common::config::{RedisConfig, RetryConfig};
 
 use golem_api_grpc::proto::golem::workerexecutor::v1::{
     get_running_workers_metadata_response, get_workers_metadata_response,
@@ -72,8 +75,16 @@ use golem_test_framework::components::shard_manager::ShardManager;
 use golem_test_framework::components::worker_executor_cluster::WorkerExecutorCluster;
 use golem_test_framework::config::TestDependencies;
 use golem_test_framework::dsl::to_worker_metadata;
+use golem_worker_executor_base::preview2::caller;
+use golem_worker_executor_base::preview2::child_workers;
+use golem_worker_executor_base::preview2::events;
+use golem_worker_executor_base::preview2::feature_flags;
 use golem_worker_executor_base::preview2::golem;
 use golem_worker_executor_base::preview2::golem::api1_1_0_rc1;
+use golem_worker_executor_base::preview2::keyvalue;
+use golem_worker_executor_base::preview2::metrics;
+use golem_worker_executor_base::preview2::nn;
+use golem_worker_executor_base::preview2::scheduling;
 use golem_worker_executor_base::services::events::Events;
 use golem_worker_executor_base::services::rpc::{
     DirectWorkerInvocationRpc, RemoteInvocationRpc, Rpc,
@@ -155,6 +166,7 @@ impl TestWorkerExecutor {
                     }
                     .into(),
                 ),
+                projection: None,
             })
             .await
             .expect("Failed to get workers metadata")
@@ -282,6 +294,17 @@ pub async fn start_limited(
     deps: &WorkerExecutorTestDependencies,
     context: &TestContext,
     system_memory_override: Option<u64>,
+) -> anyhow::Result<TestWorkerExecutor> {
+    start_customized(deps, context, |config| {
+        config.memory.system_memory_override = system_memory_override;
+    })
+    .await
+}
+
+pub async fn start_customized(
+    deps: &WorkerExecutorTestDependencies,
+    context: &TestContext,
+    customize: impl FnOnce(&mut GolemConfig),
 ) -> anyhow::Result<TestWorkerExecutor> {
     let redis = deps.redis();
     let redis_monitor = deps.redis_monitor();
@@ -290,7 +313,7 @@ pub async fn start_limited(
     println!("Using Redis on port {}", redis.public_port());
 
     let prometheus = golem_worker_executor_base::metrics::register_all();
-    let config = GolemConfig {
+    let mut config = GolemConfig {
         key_value_storage: KeyValueStorageConfig::Redis(RedisConfig {
             port: redis.public_port(),
             key_prefix: context.redis_prefix(),
@@ -299,6 +322,7 @@ pub async fn start_limited(
         indexed_storage: IndexedStorageConfig::KVStoreRedis,
         blob_storage: BlobStorageConfig::LocalFileSystem(LocalFileSystemBlobStorageConfig {
             root: Path::new("data").to_path_buf(),
+            oplog_payload_shard_fan_out: 256,
         }),
         port: context.grpc_port(),
         http_port: context.http_port(),
@@ -313,13 +337,11 @@ pub async fn start_limited(
             host: "localhost".to_string(),
             port: context.grpc_port(),
             access_token: "03494299-B515-4427-8C37-4C1C915679B7".to_string(),
-        },
-        memory: MemoryConfig {
-            system_memory_override,
-            ..Default::default()
+            retries: RetryConfig::max_attempts_3(),
         },
         ..Default::default()
     };
+    customize(&mut config);
 
     let handle = Handle::current();
 
@@ -495,6 +517,14 @@ impl InvocationManagement for TestWorkerCtx {
         self.durable_ctx.get_current_idempotency_key().await
     }
 
+    async fn set_current_caller_identity(&mut self, caller: Option<CallerIdentity>) {
+        self.durable_ctx.set_current_caller_identity(caller).await
+    }
+
+    async fn get_current_caller_identity(&self) -> Option<CallerIdentity> {
+        self.durable_ctx.get_current_caller_identity().await
+    }
+
     fn is_live(&self) -> bool {
         self.durable_ctx.is_live()
     }
@@ -628,6 +658,9 @@ impl WorkerCtx for TestWorkerCtx {
         worker_enumeration_service: Arc<dyn WorkerEnumerationService + Send + Sync>,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        domain_event_service: Arc<dyn DomainEventService + Send + Sync>,
+        feature_flag_service: Arc<dyn FeatureFlagService + Send + Sync>,
+        inference_service: Arc<dyn InferenceService + Send + Sync>,
         event_service: Arc<dyn WorkerEventService + Send + Sync>,
         _active_workers: Arc<ActiveWorkers<TestWorkerCtx>>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
@@ -650,6 +683,9 @@ impl WorkerCtx for TestWorkerCtx {
             worker_enumeration_service,
             key_value_service,
             blob_store_service,
+            domain_event_service,
+            feature_flag_service,
+            inference_service,
             event_service,
             oplog_service,
             oplog,
@@ -760,6 +796,9 @@ impl Bootstrap<TestWorkerCtx> for ServerBootstrap {
         shard_service: Arc<dyn ShardService + Send + Sync>,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        domain_event_service: Arc<dyn DomainEventService + Send + Sync>,
+        feature_flag_service: Arc<dyn FeatureFlagService + Send + Sync>,
+        inference_service: Arc<dyn InferenceService + Send + Sync>,
         worker_activator: Arc<dyn WorkerActivator + Send + Sync>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
         scheduler_service: Arc<dyn SchedulerService + Send + Sync>,
@@ -785,6 +824,9 @@ impl Bootstrap<TestWorkerCtx> for ServerBootstrap {
             shard_manager_service.clone(),
             key_value_service.clone(),
             blob_store_service.clone(),
+            domain_event_service.clone(),
+            feature_flag_service.clone(),
+            inference_service.clone(),
             oplog_service.clone(),
             scheduler_service.clone(),
             worker_activator.clone(),
@@ -806,6 +848,9 @@ impl Bootstrap<TestWorkerCtx> for ServerBootstrap {
             shard_service,
             key_value_service,
             blob_store_service,
+            domain_event_service,
+            feature_flag_service,
+            inference_service,
             oplog_service,
             rpc,
             scheduler_service,
@@ -820,6 +865,23 @@ impl Bootstrap<TestWorkerCtx> for ServerBootstrap {
         let mut linker = create_linker(engine, get_durable_ctx)?;
         api0_2_0::host::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
         api1_1_0_rc1::host::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        metrics::golem::api::metrics::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        events::golem::api::events::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        scheduling::golem::api::scheduling::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        caller::golem::api::caller::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        child_workers::golem::api::child_workers::add_to_linker_get_host(
+            &mut linker,
+            get_durable_ctx,
+        )?;
+        keyvalue::golem::api::keyvalue::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        feature_flags::golem::api::feature_flags::add_to_linker_get_host(
+            &mut linker,
+            get_durable_ctx,
+        )?;
+        nn::wasi::nn::errors::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        nn::wasi::nn::tensor::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        nn::wasi::nn::inference::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        nn::wasi::nn::graph::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
         golem_wasm_rpc::golem::rpc::types::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
         Ok(linker)
     }
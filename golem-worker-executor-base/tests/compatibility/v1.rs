@@ -265,7 +265,7 @@ pub fn wasm_rpc_value() {
 pub fn timestamped_worker_invocation() {
     let twi1 = TimestampedWorkerInvocation {
         timestamp: Timestamp::from(1724701938466),
-        invocation: WorkerInvocation::ExportedFunction {
+        invocation: WorkerInvocation::ExportedFunctionV1 {
             idempotency_key: IdempotencyKey {
                 value: "idempotency_key".to_string(),
             },
@@ -711,7 +711,7 @@ pub fn oplog_entry() {
         wrapped_function_type: WrappedFunctionType::ReadLocal,
     };
 
-    let oe3 = OplogEntry::ExportedFunctionInvoked {
+    let oe3 = OplogEntry::ExportedFunctionInvokedV1 {
         timestamp: Timestamp::from(1724701938466),
         function_name: "test:pkg/iface.{fn}".to_string(),
         request: OplogPayload::Inline(vec![0, 1, 2, 3, 4]),
@@ -780,7 +780,7 @@ pub fn oplog_entry() {
 
     let oe16 = OplogEntry::PendingWorkerInvocation {
         timestamp: Timestamp::from(1724701938466),
-        invocation: WorkerInvocation::ExportedFunction {
+        invocation: WorkerInvocation::ExportedFunctionV1 {
             idempotency_key: IdempotencyKey {
                 value: "idempotency_key".to_string(),
             },
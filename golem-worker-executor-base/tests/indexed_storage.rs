@@ -78,6 +78,7 @@ pub(crate) async fn redis_storage(deps: &WorkerExecutorTestDependencies) -> impl
         key_prefix: random_prefix.to_string(),
         username: None,
         password: None,
+        sentinel: None,
     })
     .await
     .unwrap();
@@ -233,6 +234,63 @@ macro_rules! test_indexed_storage {
                 check!(result1.is_err());
             }
 
+            #[test]
+            #[tracing::instrument]
+            async fn replace_when_supported(deps: &WorkerExecutorTestDependencies) {
+                let test = $init(deps).await;
+                let is = test.get_indexed_storage();
+                let ns = crate::indexed_storage::ns();
+
+                let key1 = "key1";
+                let value1 = "value1".as_bytes();
+                let value2 = "value2".as_bytes();
+
+                let _ = is
+                    .append("svc", "api", "entity", ns.clone(), &key1, 1, value1)
+                    .await
+                    .unwrap();
+                let replaced = is
+                    .replace("svc", "api", "entity", ns.clone(), &key1, 1, value2)
+                    .await
+                    .unwrap();
+
+                let result = is
+                    .read("svc", "api", "entity", ns.clone(), &key1, 1, 1)
+                    .await
+                    .unwrap();
+
+                let expected = if replaced { value2 } else { value1 };
+                check!(result == vec![(1, expected.into())]);
+            }
+
+            #[test]
+            #[tracing::instrument]
+            async fn replace_missing_entry(deps: &WorkerExecutorTestDependencies) {
+                let test = $init(deps).await;
+                let is = test.get_indexed_storage();
+                let ns = crate::indexed_storage::ns();
+
+                let key1 = "key1";
+                let value1 = "value1".as_bytes();
+
+                let _ = is
+                    .append("svc", "api", "entity", ns.clone(), &key1, 1, value1)
+                    .await
+                    .unwrap();
+                let missing_id = is
+                    .replace("svc", "api", "entity", ns.clone(), &key1, 2, value1)
+                    .await;
+                let missing_key = is
+                    .replace("svc", "api", "entity", ns.clone(), "key2", 1, value1)
+                    .await;
+
+                let length = is.length("svc", "api", ns.clone(), &key1).await.unwrap();
+
+                check!(matches!(missing_id, Err(_) | Ok(false)));
+                check!(matches!(missing_key, Err(_) | Ok(false)));
+                check!(length == 1);
+            }
+
             #[test]
             #[tracing::instrument]
             async fn append_can_skip(deps: &WorkerExecutorTestDependencies) {
@@ -13,20 +13,25 @@
 // limitations under the License.
 
 use crate::WorkerExecutorTestDependencies;
+use assert2::check;
 use golem_common::config::RedisConfig;
 use golem_common::model::AccountId;
 use golem_common::redis::RedisPool;
 use golem_test_framework::components::redis::Redis;
 use golem_test_framework::components::redis_monitor::RedisMonitor;
 use golem_test_framework::config::TestDependencies;
+use golem_worker_executor_base::services::key_value::{DefaultKeyValueService, KeyValueService};
 use golem_worker_executor_base::storage::keyvalue::memory::InMemoryKeyValueStorage;
 use golem_worker_executor_base::storage::keyvalue::redis::RedisKeyValueStorage;
 use golem_worker_executor_base::storage::keyvalue::sqlite::SqliteKeyValueStorage;
-use golem_worker_executor_base::storage::keyvalue::{KeyValueStorage, KeyValueStorageNamespace};
+use golem_worker_executor_base::storage::keyvalue::{
+    KeyValueStorage, KeyValueStorageNamespace, KeyValueWrite,
+};
 use golem_worker_executor_base::storage::sqlite_types::SqlitePool;
 use sqlx::sqlite::SqlitePoolOptions;
 use std::sync::Arc;
-use test_r::inherit_test_dep;
+use std::time::Duration;
+use test_r::{inherit_test_dep, test};
 use uuid::Uuid;
 
 pub(crate) trait GetKeyValueStorage {
@@ -50,7 +55,7 @@ pub(crate) async fn in_memory_storage(
     InMemoryKeyValueStorageWrapper { kvs }
 }
 
-struct RedisKeyValueStorageWrapper {
+pub(crate) struct RedisKeyValueStorageWrapper {
     kvs: RedisKeyValueStorage,
     _redis: Arc<dyn Redis + Send + Sync>,
     _monitor: Arc<dyn RedisMonitor + Send + Sync>,
@@ -64,7 +69,7 @@ impl GetKeyValueStorage for RedisKeyValueStorageWrapper {
 
 pub(crate) async fn redis_storage(
     deps: &WorkerExecutorTestDependencies,
-) -> impl GetKeyValueStorage {
+) -> RedisKeyValueStorageWrapper {
     let redis = deps.redis();
     let redis_monitor = deps.redis_monitor();
     redis.assert_valid();
@@ -80,6 +85,7 @@ pub(crate) async fn redis_storage(
         key_prefix: random_prefix.to_string(),
         username: None,
         password: None,
+        sentinel: None,
     })
     .await
     .unwrap();
@@ -91,7 +97,7 @@ pub(crate) async fn redis_storage(
     }
 }
 
-struct SqliteKeyValueStorageWrapper {
+pub(crate) struct SqliteKeyValueStorageWrapper {
     kvs: SqliteKeyValueStorage,
 }
 
@@ -103,7 +109,7 @@ impl GetKeyValueStorage for SqliteKeyValueStorageWrapper {
 
 pub(crate) async fn sqlite_storage(
     _deps: &WorkerExecutorTestDependencies,
-) -> impl GetKeyValueStorage {
+) -> SqliteKeyValueStorageWrapper {
     let sqlx_pool_sqlite = SqlitePoolOptions::new()
         .max_connections(10)
         .connect("sqlite::memory:")
@@ -760,10 +766,260 @@ macro_rules! test_kv_storage {
                 assert_eq!(result4, vec![(2.0, value2.into()), (3.0, value3.into())]);
                 assert_eq!(result5, vec![(4.0, value4.into())]);
             }
+
+            #[test]
+            #[tracing::instrument]
+            async fn commit(deps: &WorkerExecutorTestDependencies) {
+                use golem_worker_executor_base::storage::keyvalue::{KeyValueCheck, KeyValueWrite};
+
+                let test = $init(deps).await;
+                let kvs = test.get_key_value_storage();
+                let ns = $ns();
+
+                let lock = "lock";
+                let counter = "counter";
+                let write_lock = |value: &str| KeyValueWrite {
+                    key: lock.to_string(),
+                    value: Some(value.as_bytes().to_vec()),
+                    ttl: None,
+                };
+
+                let result1 = kvs
+                    .commit(
+                        "test",
+                        "api",
+                        "entity",
+                        ns.clone(),
+                        &[KeyValueCheck {
+                            key: lock.to_string(),
+                            expected: None,
+                        }],
+                        &[
+                            write_lock("owner1"),
+                            KeyValueWrite {
+                                key: counter.to_string(),
+                                value: Some(vec![1]),
+                                ttl: None,
+                            },
+                        ],
+                    )
+                    .await
+                    .unwrap();
+                let result2 = kvs
+                    .commit(
+                        "test",
+                        "api",
+                        "entity",
+                        ns.clone(),
+                        &[KeyValueCheck {
+                            key: lock.to_string(),
+                            expected: None,
+                        }],
+                        &[write_lock("owner2")],
+                    )
+                    .await
+                    .unwrap();
+                let result3 = kvs
+                    .commit(
+                        "test",
+                        "api",
+                        "entity",
+                        ns.clone(),
+                        &[KeyValueCheck {
+                            key: lock.to_string(),
+                            expected: Some("owner1".as_bytes().to_vec()),
+                        }],
+                        &[KeyValueWrite {
+                            key: counter.to_string(),
+                            value: None,
+                            ttl: None,
+                        }],
+                    )
+                    .await
+                    .unwrap();
+                let lock_value = kvs
+                    .get("test", "api", "entity", ns.clone(), lock)
+                    .await
+                    .unwrap();
+                let counter_value = kvs.get("test", "api", "entity", ns, counter).await.unwrap();
+
+                assert_eq!(result1, true);
+                assert_eq!(result2, false);
+                assert_eq!(result3, true);
+                assert_eq!(lock_value, Some("owner1".as_bytes().into()));
+                assert_eq!(counter_value, None);
+            }
         }
     };
 }
 
+async fn expiring_keys(kvs: &dyn KeyValueStorage, ns: KeyValueStorageNamespace) {
+    let key1 = "key1";
+    let key2 = "key2";
+    let value = "value".as_bytes();
+
+    kvs.set_with_ttl(
+        "test",
+        "api",
+        "entity",
+        ns.clone(),
+        key1,
+        value,
+        Duration::from_millis(200),
+    )
+    .await
+    .unwrap();
+    kvs.commit(
+        "test",
+        "api",
+        "entity",
+        ns.clone(),
+        &[],
+        &[KeyValueWrite {
+            key: key2.to_string(),
+            value: Some(value.to_vec()),
+            ttl: Some(Duration::from_millis(200)),
+        }],
+    )
+    .await
+    .unwrap();
+    let result1 = kvs
+        .get_many(
+            "test",
+            "api",
+            "entity",
+            ns.clone(),
+            vec![key1.to_string(), key2.to_string()],
+        )
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let result2 = kvs
+        .get_many(
+            "test",
+            "api",
+            "entity",
+            ns.clone(),
+            vec![key1.to_string(), key2.to_string()],
+        )
+        .await
+        .unwrap();
+    let exists = kvs.exists("test", "api", ns, key1).await.unwrap();
+
+    assert_eq!(result1, vec![Some(value.into()), Some(value.into())]);
+    assert_eq!(result2, vec![None, None]);
+    assert!(!exists);
+}
+
+#[test]
+#[tracing::instrument]
+async fn in_memory_expiring_keys(deps: &WorkerExecutorTestDependencies) {
+    let test = in_memory_storage(deps).await;
+    expiring_keys(test.get_key_value_storage(), ns()).await;
+}
+
+#[test]
+#[tracing::instrument]
+async fn redis_expiring_keys(deps: &WorkerExecutorTestDependencies) {
+    let test = redis_storage(deps).await;
+    expiring_keys(test.get_key_value_storage(), ns()).await;
+}
+
+#[test]
+#[tracing::instrument]
+async fn redis_hash_expiring_keys(deps: &WorkerExecutorTestDependencies) {
+    let test = redis_storage(deps).await;
+    let supported = test
+        .kvs
+        .supports_hash_field_expiration("test", "api")
+        .await
+        .unwrap();
+
+    if supported {
+        expiring_keys(test.get_key_value_storage(), ns2()).await;
+    } else {
+        let result = test
+            .kvs
+            .set_with_ttl(
+                "test",
+                "api",
+                "entity",
+                ns2(),
+                "key",
+                "value".as_bytes(),
+                Duration::from_millis(200),
+            )
+            .await;
+        check!(result.unwrap_err().contains("require Redis 7.4"));
+    }
+}
+
+#[test]
+#[tracing::instrument]
+async fn sqlite_expiring_keys(deps: &WorkerExecutorTestDependencies) {
+    let test = sqlite_storage(deps).await;
+    expiring_keys(test.get_key_value_storage(), ns2()).await;
+}
+
+// Guests can only set expiring keys in their buckets, through the key-value service
+async fn expiring_bucket_keys(kvs: Arc<dyn KeyValueStorage + Send + Sync>) {
+    let service = DefaultKeyValueService::new(kvs);
+    let account_id = AccountId::generate();
+    let bucket = "bucket".to_string();
+    let value = "value".as_bytes().to_vec();
+
+    service
+        .set_with_ttl(
+            account_id.clone(),
+            bucket.clone(),
+            "expiring".to_string(),
+            value.clone(),
+            Duration::from_millis(200),
+        )
+        .await
+        .unwrap();
+    service
+        .set(
+            account_id.clone(),
+            bucket.clone(),
+            "persistent".to_string(),
+            value.clone(),
+        )
+        .await
+        .unwrap();
+    let result1 = service
+        .get(account_id.clone(), bucket.clone(), "expiring".to_string())
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let result2 = service
+        .get(account_id.clone(), bucket.clone(), "expiring".to_string())
+        .await
+        .unwrap();
+    let keys = service.get_keys(account_id, bucket).await.unwrap();
+
+    check!(result1 == Some(value));
+    check!(result2.is_none());
+    check!(keys == vec!["persistent".to_string()]);
+}
+
+#[test]
+#[tracing::instrument]
+async fn in_memory_expiring_bucket_keys(_deps: &WorkerExecutorTestDependencies) {
+    expiring_bucket_keys(Arc::new(InMemoryKeyValueStorage::new())).await;
+}
+
+#[test]
+#[tracing::instrument]
+async fn sqlite_expiring_bucket_keys(deps: &WorkerExecutorTestDependencies) {
+    let test = sqlite_storage(deps).await;
+    expiring_bucket_keys(Arc::new(test.kvs)).await;
+}
+
 test_kv_storage!(
     in_memory,
     crate::key_value_storage::in_memory_storage,
@@ -23,7 +23,9 @@ use std::time::{Duration, SystemTime};
 use crate::common::{start, TestContext};
 use crate::{LastUniqueId, Tracing, WorkerExecutorTestDependencies};
 use assert2::{assert, check};
-use golem_common::model::{IdempotencyKey, WorkerStatus};
+use golem_api_grpc::proto::golem::workerexecutor::v1::UpdateWorkerEnvRequest;
+use golem_common::model::worker_defaults::MAX_DISK_USAGE_ENV_VAR;
+use golem_common::model::{AccountId, IdempotencyKey, WorkerStatus};
 use golem_test_framework::dsl::{
     drain_connection, stderr_events, stdout_events, worker_error_message, TestDslUnsafe,
 };
@@ -551,6 +553,79 @@ async fn environment_service(
     );
 }
 
+#[test]
+#[tracing::instrument]
+async fn environment_service_after_update_env_and_restart(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("environment-service").await;
+    let mut env = HashMap::new();
+    env.insert("TEST_ENV".to_string(), "test-value".to_string());
+    let worker_id = executor
+        .start_worker_with(&component_id, "environment-service-2", vec![], env)
+        .await;
+
+    let env_result1 = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{get-environment}", vec![])
+        .await
+        .unwrap();
+
+    let mut new_env = HashMap::new();
+    new_env.insert("TEST_ENV".to_string(), "updated-value".to_string());
+    executor
+        .client()
+        .await
+        .expect("Failed to get client")
+        .update_worker_env(UpdateWorkerEnvRequest {
+            worker_id: Some(worker_id.clone().into()),
+            account_id: Some(
+                AccountId {
+                    value: "test-account".to_string(),
+                }
+                .into(),
+            ),
+            env: new_env,
+        })
+        .await
+        .unwrap();
+
+    drop(executor);
+    let executor = start(deps, &context).await.unwrap();
+
+    let env_result2 = executor
+        .invoke_and_await(&worker_id, "golem:it/api.{get-environment}", vec![])
+        .await
+        .unwrap();
+
+    drop(executor);
+
+    fn test_env(result: &[Value]) -> Option<String> {
+        match result {
+            [Value::Result(Ok(Some(list)))] => match list.as_ref() {
+                Value::List(vars) => vars.iter().find_map(|var| match var {
+                    Value::Tuple(pair) => match pair.as_slice() {
+                        [Value::String(key), Value::String(value)] if key == "TEST_ENV" => {
+                            Some(value.clone())
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    check!(test_env(&env_result1) == Some("test-value".to_string()));
+    check!(test_env(&env_result2) == Some("updated-value".to_string()));
+}
+
 #[test]
 #[tracing::instrument]
 async fn http_client_response_persisted_between_invocations(
@@ -1557,6 +1632,67 @@ async fn filesystem_metadata_hash(
     check!(hash1 == hash2);
 }
 
+#[test]
+#[tracing::instrument]
+async fn file_writes_beyond_the_disk_usage_limit_fail(
+    last_unique_id: &LastUniqueId,
+    deps: &WorkerExecutorTestDependencies,
+    _tracing: &Tracing,
+) {
+    let context = TestContext::new(last_unique_id);
+    let executor = start(deps, &context).await.unwrap();
+
+    let component_id = executor.store_component("file-service").await;
+    let mut env = HashMap::new();
+    env.insert(MAX_DISK_USAGE_ENV_VAR.to_string(), "16".to_string());
+    let worker_id = executor
+        .start_worker_with(&component_id, "file-service-11", vec![], env)
+        .await;
+
+    let within_limit = executor
+        .invoke_and_await(
+            &worker_id,
+            "golem:it/api.{write-file}",
+            vec![
+                Value::String("/testfile.txt".to_string()),
+                Value::String("hello world".to_string()),
+            ],
+        )
+        .await
+        .unwrap();
+    let via_stream = executor
+        .invoke_and_await(
+            &worker_id,
+            "golem:it/api.{write-file}",
+            vec![
+                Value::String("/testfile2.txt".to_string()),
+                Value::String("hello world".to_string()),
+            ],
+        )
+        .await
+        .unwrap();
+    let direct = executor
+        .invoke_and_await(
+            &worker_id,
+            "golem:it/api.{write-file-direct}",
+            vec![
+                Value::String("testfile3.txt".to_string()),
+                Value::String("hello world".to_string()),
+            ],
+        )
+        .await
+        .unwrap();
+
+    drop(executor);
+
+    let insufficient_space = vec![Value::Result(Err(Some(Box::new(Value::String(
+        "No space left on device (os error 28)".to_string(),
+    )))))];
+    check!(within_limit == vec![Value::Result(Ok(None))]);
+    check!(via_stream == insufficient_space);
+    check!(direct == insufficient_space);
+}
+
 #[test]
 #[tracing::instrument]
 async fn ip_address_resolve(
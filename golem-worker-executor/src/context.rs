@@ -24,8 +24,8 @@ use wasmtime::{AsContextMut, ResourceLimiterAsync};
 
 use golem_common::model::oplog::WorkerResourceId;
 use golem_common::model::{
-    AccountId, ComponentVersion, IdempotencyKey, OwnedWorkerId, WorkerId, WorkerMetadata,
-    WorkerStatus, WorkerStatusRecord,
+    AccountId, CallerIdentity, ComponentVersion, IdempotencyKey, OwnedWorkerId, WorkerId,
+    WorkerMetadata, WorkerStatus, WorkerStatusRecord,
 };
 use golem_worker_executor_base::durable_host::{
     DurableWorkerCtx, DurableWorkerCtxView, PublicDurableWorkerState,
@@ -37,7 +37,10 @@ use golem_worker_executor_base::model::{
 use golem_worker_executor_base::services::active_workers::ActiveWorkers;
 use golem_worker_executor_base::services::blob_store::BlobStoreService;
 use golem_worker_executor_base::services::component::{ComponentMetadata, ComponentService};
+use golem_worker_executor_base::services::domain_event::DomainEventService;
+use golem_worker_executor_base::services::feature_flags::FeatureFlagService;
 use golem_worker_executor_base::services::golem_config::GolemConfig;
+use golem_worker_executor_base::services::inference::InferenceService;
 use golem_worker_executor_base::services::key_value::KeyValueService;
 use golem_worker_executor_base::services::oplog::{Oplog, OplogService};
 use golem_worker_executor_base::services::promise::PromiseService;
@@ -150,6 +153,14 @@ impl InvocationManagement for Context {
         self.durable_ctx.get_current_idempotency_key().await
     }
 
+    async fn set_current_caller_identity(&mut self, caller: Option<CallerIdentity>) {
+        self.durable_ctx.set_current_caller_identity(caller).await
+    }
+
+    async fn get_current_caller_identity(&self) -> Option<CallerIdentity> {
+        self.durable_ctx.get_current_caller_identity().await
+    }
+
     fn is_live(&self) -> bool {
         self.durable_ctx.is_live()
     }
@@ -292,6 +303,9 @@ impl WorkerCtx for Context {
         >,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        domain_event_service: Arc<dyn DomainEventService + Send + Sync>,
+        feature_flag_service: Arc<dyn FeatureFlagService + Send + Sync>,
+        inference_service: Arc<dyn InferenceService + Send + Sync>,
         event_service: Arc<dyn WorkerEventService + Send + Sync>,
         _active_workers: Arc<ActiveWorkers<Context>>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
@@ -314,6 +328,9 @@ impl WorkerCtx for Context {
             worker_enumeration_service,
             key_value_service,
             blob_store_service,
+            domain_event_service,
+            feature_flag_service,
+            inference_service,
             event_service,
             oplog_service,
             oplog,
@@ -21,12 +21,23 @@ use crate::context::Context;
 use crate::services::AdditionalDeps;
 use async_trait::async_trait;
 use golem_worker_executor_base::durable_host::DurableWorkerCtx;
+use golem_worker_executor_base::preview2::caller;
+use golem_worker_executor_base::preview2::child_workers;
+use golem_worker_executor_base::preview2::events;
+use golem_worker_executor_base::preview2::feature_flags;
 use golem_worker_executor_base::preview2::golem::{api0_2_0, api1_1_0_rc1};
+use golem_worker_executor_base::preview2::keyvalue;
+use golem_worker_executor_base::preview2::metrics;
+use golem_worker_executor_base::preview2::nn;
+use golem_worker_executor_base::preview2::scheduling;
 use golem_worker_executor_base::services::active_workers::ActiveWorkers;
 use golem_worker_executor_base::services::blob_store::BlobStoreService;
 use golem_worker_executor_base::services::component::ComponentService;
+use golem_worker_executor_base::services::domain_event::DomainEventService;
 use golem_worker_executor_base::services::events::Events;
+use golem_worker_executor_base::services::feature_flags::FeatureFlagService;
 use golem_worker_executor_base::services::golem_config::GolemConfig;
+use golem_worker_executor_base::services::inference::InferenceService;
 use golem_worker_executor_base::services::key_value::KeyValueService;
 use golem_worker_executor_base::services::oplog::OplogService;
 use golem_worker_executor_base::services::promise::PromiseService;
@@ -76,6 +87,9 @@ impl Bootstrap<Context> for ServerBootstrap {
         shard_service: Arc<dyn ShardService + Send + Sync>,
         key_value_service: Arc<dyn KeyValueService + Send + Sync>,
         blob_store_service: Arc<dyn BlobStoreService + Send + Sync>,
+        domain_event_service: Arc<dyn DomainEventService + Send + Sync>,
+        feature_flag_service: Arc<dyn FeatureFlagService + Send + Sync>,
+        inference_service: Arc<dyn InferenceService + Send + Sync>,
         worker_activator: Arc<dyn WorkerActivator + Send + Sync>,
         oplog_service: Arc<dyn OplogService + Send + Sync>,
         scheduler_service: Arc<dyn SchedulerService + Send + Sync>,
@@ -103,6 +117,9 @@ impl Bootstrap<Context> for ServerBootstrap {
             shard_manager_service.clone(),
             key_value_service.clone(),
             blob_store_service.clone(),
+            domain_event_service.clone(),
+            feature_flag_service.clone(),
+            inference_service.clone(),
             oplog_service.clone(),
             scheduler_service.clone(),
             worker_activator.clone(),
@@ -125,6 +142,9 @@ impl Bootstrap<Context> for ServerBootstrap {
             shard_service,
             key_value_service,
             blob_store_service,
+            domain_event_service,
+            feature_flag_service,
+            inference_service,
             oplog_service,
             rpc,
             scheduler_service,
@@ -139,6 +159,23 @@ impl Bootstrap<Context> for ServerBootstrap {
         let mut linker = create_linker(engine, get_durable_ctx)?;
         api0_2_0::host::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
         api1_1_0_rc1::host::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        metrics::golem::api::metrics::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        events::golem::api::events::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        scheduling::golem::api::scheduling::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        caller::golem::api::caller::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        child_workers::golem::api::child_workers::add_to_linker_get_host(
+            &mut linker,
+            get_durable_ctx,
+        )?;
+        keyvalue::golem::api::keyvalue::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        feature_flags::golem::api::feature_flags::add_to_linker_get_host(
+            &mut linker,
+            get_durable_ctx,
+        )?;
+        nn::wasi::nn::errors::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        nn::wasi::nn::tensor::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        nn::wasi::nn::inference::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
+        nn::wasi::nn::graph::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
         golem_wasm_rpc::golem::rpc::types::add_to_linker_get_host(&mut linker, get_durable_ctx)?;
         Ok(linker)
     }
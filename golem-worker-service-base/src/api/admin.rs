@@ -0,0 +1,107 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use golem_service_base::model::ErrorBody;
+use poem_openapi::payload::Json;
+use serde::{Deserialize, Serialize};
+
+use crate::api::WorkerApiBaseError;
+
+/// Access to the administrative endpoints, which are disabled while no token is configured
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AdminConfig {
+    /// Bearer tokens accepted in the `Authorization` header of the administrative requests
+    pub tokens: Vec<String>,
+}
+
+impl AdminConfig {
+    /// Checks the value of the `Authorization` header of a request to an administrative endpoint
+    pub fn authorize(&self, authorization: Option<&str>) -> Result<(), WorkerApiBaseError> {
+        if self.tokens.is_empty() {
+            return Err(WorkerApiBaseError::Forbidden(Json(ErrorBody {
+                error: "Administrative endpoints are disabled, no admin token is configured"
+                    .to_string(),
+            })));
+        }
+
+        match authorization.and_then(|value| value.strip_prefix("Bearer ")) {
+            None => Err(WorkerApiBaseError::Unauthorized(Json(ErrorBody {
+                error: "Missing admin bearer token".to_string(),
+            }))),
+            Some(token) => {
+                let token = token.trim().as_bytes();
+                if self
+                    .tokens
+                    .iter()
+                    .any(|expected| constant_time_eq(expected.as_bytes(), token))
+                {
+                    Ok(())
+                } else {
+                    Err(WorkerApiBaseError::Forbidden(Json(ErrorBody {
+                        error: "Invalid admin token".to_string(),
+                    })))
+                }
+            }
+        }
+    }
+}
+
+/// Compares the tokens without returning early on the first difference, so the time taken does
+/// not tell how much of a guessed token was right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::api::{AdminConfig, WorkerApiBaseError};
+
+    fn config() -> AdminConfig {
+        AdminConfig {
+            tokens: vec!["first-token".to_string(), "second-token".to_string()],
+        }
+    }
+
+    #[test]
+    fn accepts_any_configured_token() {
+        assert!(config().authorize(Some("Bearer first-token")).is_ok());
+        assert!(config().authorize(Some("Bearer second-token")).is_ok());
+    }
+
+    #[test]
+    fn rejects_missing_and_invalid_tokens() {
+        assert!(matches!(
+            config().authorize(None),
+            Err(WorkerApiBaseError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            config().authorize(Some("first-token")),
+            Err(WorkerApiBaseError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            config().authorize(Some("Bearer first-token-2")),
+            Err(WorkerApiBaseError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn disabled_without_tokens() {
+        assert!(matches!(
+            AdminConfig::default().authorize(Some("Bearer ")),
+            Err(WorkerApiBaseError::Forbidden(_))
+        ));
+    }
+}
@@ -114,6 +114,7 @@ impl<'a> TraceErrorKind for WorkerTraceErrorKind<'a> {
                 worker::v1::worker_error::Error::NotFound(_) => "NotFound",
                 worker::v1::worker_error::Error::AlreadyExists(_) => "AlreadyExists",
                 worker::v1::worker_error::Error::InternalError(_) => "InternalError",
+                worker::v1::worker_error::Error::Unavailable(_) => "Unavailable",
             },
         }
     }
@@ -4,15 +4,17 @@ use std::sync::Arc;
 use crate::api_definition::http::CompiledHttpApiDefinition;
 use crate::worker_service_rib_interpreter::{DefaultRibInterpreter, WorkerServiceRibInterpreter};
 use futures_util::FutureExt;
-use hyper::header::HOST;
-use poem::http::StatusCode;
-use poem::{Body, Endpoint, Request, Response};
+use hyper::header::{HOST, ORIGIN, UPGRADE};
+use poem::http::{Method, StatusCode};
+use poem::web::websocket::WebSocket;
+use poem::{Body, Endpoint, FromRequest, IntoResponse, Request, Response};
 use tracing::{error, info};
 
-use crate::http::{ApiInputPath, InputHttpRequest};
+use crate::api::WebSocketConnectionHandler;
+use crate::http::{ApiInputPath, ClientCertificateConfig, InputHttpRequest};
 use crate::service::api_definition_lookup::ApiDefinitionsLookup;
 
-use crate::worker_binding::RequestToWorkerBindingResolver;
+use crate::worker_binding::{BindingType, RequestToWorkerBindingResolver};
 use crate::worker_bridge_execution::WorkerRequestExecutor;
 
 // Executes custom request with the help of worker_request_executor and definition_service
@@ -20,8 +22,10 @@ use crate::worker_bridge_execution::WorkerRequestExecutor;
 #[derive(Clone)]
 pub struct CustomHttpRequestApi {
     pub worker_service_rib_interpreter: Arc<dyn WorkerServiceRibInterpreter + Sync + Send>,
+    pub worker_request_executor_service: Arc<dyn WorkerRequestExecutor + Sync + Send>,
     pub api_definition_lookup_service:
         Arc<dyn ApiDefinitionsLookup<InputHttpRequest, CompiledHttpApiDefinition> + Sync + Send>,
+    pub client_certificate: ClientCertificateConfig,
 }
 
 impl CustomHttpRequestApi {
@@ -30,6 +34,7 @@ impl CustomHttpRequestApi {
         api_definition_lookup_service: Arc<
             dyn ApiDefinitionsLookup<InputHttpRequest, CompiledHttpApiDefinition> + Sync + Send,
         >,
+        client_certificate: ClientCertificateConfig,
     ) -> Self {
         let evaluator = Arc::new(DefaultRibInterpreter::from_worker_request_executor(
             worker_request_executor_service.clone(),
@@ -37,11 +42,22 @@ impl CustomHttpRequestApi {
 
         Self {
             worker_service_rib_interpreter: evaluator,
+            worker_request_executor_service,
             api_definition_lookup_service,
+            client_certificate,
         }
     }
 
     pub async fn execute(&self, request: Request) -> Response {
+        let websocket = if is_websocket_upgrade(&request) {
+            match WebSocket::from_request_without_body(&request).await {
+                Ok(websocket) => Some(websocket),
+                Err(err) => return err.into_response(),
+            }
+        } else {
+            None
+        };
+
         let (req_parts, body) = request.into_parts();
         let headers = req_parts.headers;
         let uri = req_parts.uri;
@@ -57,6 +73,13 @@ impl CustomHttpRequestApi {
 
         info!("API request host: {}", host);
 
+        let origin = headers
+            .get(ORIGIN)
+            .and_then(|h| h.to_str().ok())
+            .map(|origin| origin.to_string());
+
+        let caller = self.client_certificate.caller_identity(&headers);
+
         let json_request_body: serde_json::Value = if body.is_empty() {
             serde_json::Value::Null
         } else {
@@ -98,14 +121,55 @@ impl CustomHttpRequestApi {
             }
         };
 
+        if let Some(origin) = &origin {
+            if input_http_request.req_method == Method::OPTIONS {
+                if let Some((cors, route_methods)) =
+                    input_http_request.resolve_cors_preflight(&possible_api_definitions)
+                {
+                    return cors.preflight_response(origin, &route_methods);
+                }
+            }
+        }
+
         match input_http_request
             .resolve_worker_binding(possible_api_definitions)
             .await
         {
-            Ok(resolved_worker_binding) => {
-                resolved_worker_binding
-                    .interpret_response_mapping(&self.worker_service_rib_interpreter)
-                    .await
+            Ok(mut resolved_worker_binding) => {
+                resolved_worker_binding.worker_detail.caller = caller;
+
+                let is_websocket_binding =
+                    BindingType::from_name(&resolved_worker_binding.binding_type)
+                        == Some(BindingType::WebSocket);
+                let cors = resolved_worker_binding.cors.clone();
+
+                let mut response = match websocket {
+                    Some(websocket) if is_websocket_binding => {
+                        let handler = WebSocketConnectionHandler::new(
+                            resolved_worker_binding,
+                            self.worker_service_rib_interpreter.clone(),
+                            self.worker_request_executor_service.clone(),
+                        );
+                        websocket
+                            .on_upgrade(move |socket| handler.handle(socket))
+                            .into_response()
+                    }
+                    None if is_websocket_binding => Response::builder()
+                        .status(StatusCode::UPGRADE_REQUIRED)
+                        .body(Body::from_string(
+                            "WebSocket route requires a WebSocket upgrade request".to_string(),
+                        )),
+                    _ => {
+                        resolved_worker_binding
+                            .interpret_response_mapping(&self.worker_service_rib_interpreter)
+                            .await
+                    }
+                };
+
+                if let (Some(cors), Some(origin)) = (&cors, &origin) {
+                    cors.add_response_headers(origin, response.headers_mut());
+                }
+                response
             }
 
             Err(msg) => {
@@ -119,6 +183,14 @@ impl CustomHttpRequestApi {
     }
 }
 
+fn is_websocket_upgrade(request: &Request) -> bool {
+    request
+        .headers()
+        .get(UPGRADE)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|upgrade| upgrade.eq_ignore_ascii_case("websocket"))
+}
+
 impl Endpoint for CustomHttpRequestApi {
     type Output = Response;
 
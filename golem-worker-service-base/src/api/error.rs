@@ -1,5 +1,7 @@
 use crate::service::component::ComponentServiceError;
+use crate::service::maintenance::MaintenanceError;
 use crate::service::worker::WorkerServiceError;
+use crate::service::worker_defaults::WorkerDefaultsError;
 use golem_common::metrics::api::TraceErrorKind;
 use golem_common::SafeDisplay;
 use golem_service_base::model::*;
@@ -27,6 +29,9 @@ pub enum WorkerApiBaseError {
     AlreadyExists(Json<ErrorBody>),
     #[oai(status = 500)]
     InternalError(Json<GolemErrorBody>),
+    /// The request can be retried later, for example after the maintenance of the component
+    #[oai(status = 503)]
+    Unavailable(Json<ErrorBody>),
 }
 
 impl TraceErrorKind for WorkerApiBaseError {
@@ -38,6 +43,7 @@ impl TraceErrorKind for WorkerApiBaseError {
             WorkerApiBaseError::Forbidden(_) => "Forbidden",
             WorkerApiBaseError::Unauthorized(_) => "Unauthorized",
             WorkerApiBaseError::InternalError(_) => "InternalError",
+            WorkerApiBaseError::Unavailable(_) => "Unavailable",
         }
     }
 }
@@ -82,15 +88,26 @@ impl From<WorkerServiceError> for WorkerApiBaseError {
 
         match error {
             ServiceError::Internal(_) => internal(error.to_safe_string()),
-            ServiceError::TypeChecker(_) => WorkerApiBaseError::BadRequest(Json(ErrorsBody {
-                errors: vec![error.to_safe_string()],
-            })),
+            ServiceError::TypeChecker(_)
+            | ServiceError::LimitExceeded { .. }
+            | ServiceError::InvalidTemplate(_)
+            | ServiceError::InvalidBlobParameter(_)
+            | ServiceError::ReservedWorkerName(_) => {
+                WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+                    errors: vec![error.to_safe_string()],
+                }))
+            }
             ServiceError::VersionedComponentIdNotFound(_)
             | ServiceError::ComponentNotFound(_)
             | ServiceError::AccountIdNotFound(_)
             | ServiceError::WorkerNotFound(_) => WorkerApiBaseError::NotFound(Json(ErrorBody {
                 error: error.to_safe_string(),
             })),
+            ServiceError::ComponentInMaintenance { .. } => {
+                WorkerApiBaseError::Unavailable(Json(ErrorBody {
+                    error: error.to_safe_string(),
+                }))
+            }
             ServiceError::Golem(golem_error) => {
                 WorkerApiBaseError::InternalError(Json(GolemErrorBody { golem_error }))
             }
@@ -141,3 +158,41 @@ impl From<ComponentServiceError> for WorkerApiBaseError {
         }
     }
 }
+
+impl From<WorkerDefaultsError> for WorkerApiBaseError {
+    fn from(error: WorkerDefaultsError) -> Self {
+        match error {
+            WorkerDefaultsError::Invalid(errors) => {
+                WorkerApiBaseError::BadRequest(Json(ErrorsBody { errors }))
+            }
+            WorkerDefaultsError::NotFound => WorkerApiBaseError::NotFound(Json(ErrorBody {
+                error: error.to_safe_string(),
+            })),
+            WorkerDefaultsError::InternalRepoError(_)
+            | WorkerDefaultsError::InternalConversionError(_) => {
+                WorkerApiBaseError::InternalError(Json(GolemErrorBody {
+                    golem_error: GolemError::Unknown(GolemErrorUnknown {
+                        details: error.to_safe_string(),
+                    }),
+                }))
+            }
+        }
+    }
+}
+
+impl From<MaintenanceError> for WorkerApiBaseError {
+    fn from(error: MaintenanceError) -> Self {
+        match error {
+            MaintenanceError::NotFound => WorkerApiBaseError::NotFound(Json(ErrorBody {
+                error: error.to_safe_string(),
+            })),
+            MaintenanceError::InternalRepoError(_) => {
+                WorkerApiBaseError::InternalError(Json(GolemErrorBody {
+                    golem_error: GolemError::Unknown(GolemErrorUnknown {
+                        details: error.to_safe_string(),
+                    }),
+                }))
+            }
+        }
+    }
+}
@@ -1,12 +1,18 @@
+pub use admin::*;
 pub use common::*;
 pub use custom_http_request_api::*;
 pub use error::*;
 pub use healthcheck::*;
 pub use register_api_definition_api::*;
+pub use request_capture::*;
+pub use websocket_connection::*;
 
 // Components and request data that can be reused for implementing server API endpoints
+mod admin;
 mod common;
 mod custom_http_request_api;
 mod error;
 mod healthcheck;
 mod register_api_definition_api;
+mod request_capture;
+mod websocket_connection;
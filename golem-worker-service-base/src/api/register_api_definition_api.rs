@@ -6,7 +6,7 @@ use std::result::Result;
 use std::time::SystemTime;
 
 use crate::api_definition::http::{
-    AllPathPatterns, CompiledHttpApiDefinition, CompiledRoute, MethodPattern,
+    AllPathPatterns, CompiledHttpApiDefinition, CompiledRoute, CorsPolicy, MethodPattern,
 };
 use crate::api_definition::{ApiDefinitionId, ApiSite, ApiVersion};
 use crate::worker_binding::{BindingType, CompiledGolemWorkerBinding};
@@ -49,6 +49,7 @@ pub struct HttpApiDefinitionRequest {
     pub routes: Vec<Route>,
     #[serde(default)]
     pub draft: bool,
+    pub cors: Option<CorsPolicy>,
 }
 
 // Mostly this data structures that represents the actual incoming request
@@ -63,6 +64,7 @@ pub struct HttpApiDefinition {
     pub routes: Vec<Route>,
     #[serde(default)]
     pub draft: bool,
+    pub cors: Option<CorsPolicy>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
@@ -76,6 +78,7 @@ pub struct HttpApiDefinitionWithTypeInfo {
     pub routes: Vec<RouteWithTypeInfo>,
     #[serde(default)]
     pub draft: bool,
+    pub cors: Option<CorsPolicy>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
@@ -88,6 +91,7 @@ impl From<CompiledHttpApiDefinition> for HttpApiDefinitionWithTypeInfo {
             version: value.version,
             routes,
             draft: value.draft,
+            cors: value.cors,
             created_at: Some(value.created_at),
         }
     }
@@ -205,6 +209,7 @@ impl TryFrom<crate::api_definition::http::HttpApiDefinition> for HttpApiDefiniti
             version: value.version,
             routes,
             draft: value.draft,
+            cors: value.cors,
             created_at: Some(value.created_at),
         })
     }
@@ -223,11 +228,16 @@ impl TryInto<crate::api_definition::http::HttpApiDefinitionRequest> for HttpApiD
             routes.push(v);
         }
 
+        if let Some(cors) = &self.cors {
+            cors.validate()?;
+        }
+
         Ok(crate::api_definition::http::HttpApiDefinitionRequest {
             id: self.id,
             version: self.version,
             routes,
             draft: self.draft,
+            cors: self.cors,
         })
     }
 }
@@ -328,7 +338,10 @@ impl TryFrom<crate::api_definition::http::HttpApiDefinition> for grpc_apidefinit
 
         let id = value.id.0;
 
-        let definition = grpc_apidefinition::HttpApiDefinition { routes };
+        let definition = grpc_apidefinition::HttpApiDefinition {
+            routes,
+            cors: value.cors.map(|cors| cors.into()),
+        };
 
         let created_at = prost_types::Timestamp::from(SystemTime::from(value.created_at));
 
@@ -350,12 +363,14 @@ impl TryFrom<grpc_apidefinition::ApiDefinition> for crate::api_definition::http:
     type Error = String;
 
     fn try_from(value: grpc_apidefinition::ApiDefinition) -> Result<Self, Self::Error> {
-        let routes = match value.definition.ok_or("definition is missing")? {
-            grpc_apidefinition::api_definition::Definition::Http(http) => http
-                .routes
-                .into_iter()
-                .map(crate::api_definition::http::Route::try_from)
-                .collect::<Result<Vec<crate::api_definition::http::Route>, String>>()?,
+        let (routes, cors) = match value.definition.ok_or("definition is missing")? {
+            grpc_apidefinition::api_definition::Definition::Http(http) => (
+                http.routes
+                    .into_iter()
+                    .map(crate::api_definition::http::Route::try_from)
+                    .collect::<Result<Vec<crate::api_definition::http::Route>, String>>()?,
+                http.cors.map(CorsPolicy::try_from).transpose()?,
+            ),
         };
 
         let id = value.id.ok_or("Api Definition ID is missing")?;
@@ -369,6 +384,7 @@ impl TryFrom<grpc_apidefinition::ApiDefinition> for crate::api_definition::http:
             version: ApiVersion(value.version),
             routes,
             draft: value.draft,
+            cors,
             created_at: created_at.into(),
         };
 
@@ -382,14 +398,20 @@ impl TryFrom<grpc_apidefinition::v1::ApiDefinitionRequest>
     type Error = String;
 
     fn try_from(value: grpc_apidefinition::v1::ApiDefinitionRequest) -> Result<Self, Self::Error> {
-        let routes = match value.definition.ok_or("definition is missing")? {
-            grpc_apidefinition::v1::api_definition_request::Definition::Http(http) => http
-                .routes
-                .into_iter()
-                .map(crate::api_definition::http::Route::try_from)
-                .collect::<Result<Vec<crate::api_definition::http::Route>, String>>()?,
+        let (routes, cors) = match value.definition.ok_or("definition is missing")? {
+            grpc_apidefinition::v1::api_definition_request::Definition::Http(http) => (
+                http.routes
+                    .into_iter()
+                    .map(crate::api_definition::http::Route::try_from)
+                    .collect::<Result<Vec<crate::api_definition::http::Route>, String>>()?,
+                http.cors.map(CorsPolicy::try_from).transpose()?,
+            ),
         };
 
+        if let Some(cors) = &cors {
+            cors.validate()?;
+        }
+
         let id = value.id.ok_or("Api Definition ID is missing")?;
 
         let result = crate::api_definition::http::HttpApiDefinitionRequest {
@@ -397,6 +419,7 @@ impl TryFrom<grpc_apidefinition::v1::ApiDefinitionRequest>
             version: ApiVersion(value.version),
             routes,
             draft: value.draft,
+            cors,
         };
 
         Ok(result)
@@ -498,7 +521,7 @@ impl TryFrom<crate::worker_binding::GolemWorkerBinding> for grpc_apidefinition::
 
         let idempotency_key = value.idempotency_key.map(|key| key.into());
 
-        let binding_type = 1;
+        let binding_type = BindingType::i32_from_name(&value.binding_type);
         let result = grpc_apidefinition::WorkerBinding {
             component: Some(value.component_id.into()),
             worker_name,
@@ -533,7 +556,9 @@ impl TryFrom<grpc_apidefinition::WorkerBinding> for crate::worker_binding::Golem
             None
         };
 
-        let binding_type = "file-server".to_string();
+        let binding_type = BindingType::from_i32(value.binding_type)
+            .ok_or("Invalid binding type")?
+            .to_string();
         let result = crate::worker_binding::GolemWorkerBinding {
             component_id,
             worker_name,
@@ -0,0 +1,458 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bytes::{Bytes, BytesMut};
+use futures::StreamExt;
+use golem_common::request_capture::{
+    redact_headers, CapturedBody, CapturedExchange, CapturedRequest, CapturedResponse,
+    CapturedServer,
+};
+use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE, UPGRADE};
+use poem::http::{HeaderMap, StatusCode};
+use poem::{Body, Endpoint, IntoResponse, Middleware, Request, Response};
+use serde::{Deserialize, Serialize};
+use tokio::fs::File;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+
+/// Shadow logging of the incoming requests and the responses they got into a capture file,
+/// which can be replayed against a test deployment
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RequestCaptureConfig {
+    pub enabled: bool,
+    /// The JSON lines file the captured requests are appended to
+    pub file: PathBuf,
+    /// Fraction of the requests to capture, between 0.0 and 1.0
+    pub sample_rate: f64,
+    /// Request and response bodies larger than this are not captured, only their size
+    pub max_body_size: usize,
+    /// Size in bytes the capture file is rotated at, renaming it to `<file>.1`
+    pub max_file_size: u64,
+    /// Number of rotated capture files kept, the oldest ones are deleted
+    pub max_rotated_files: usize,
+    /// Maximum number of captured requests waiting to be written; further ones are dropped
+    pub buffer_size: usize,
+    /// Headers whose values are never written to the capture file
+    pub redacted_headers: Vec<String>,
+    /// JSON fields whose values are never written to the capture file, matching any field
+    /// name containing one of these
+    pub redacted_fields: Vec<String>,
+}
+
+impl Default for RequestCaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: PathBuf::from("../data/request_capture.jsonl"),
+            sample_rate: 1.0,
+            max_body_size: 64 * 1024,
+            max_file_size: 100 * 1024 * 1024,
+            max_rotated_files: 5,
+            buffer_size: 1024,
+            redacted_headers: vec![
+                "authorization".to_string(),
+                "proxy-authorization".to_string(),
+                "cookie".to_string(),
+                "set-cookie".to_string(),
+                "x-forwarded-client-cert".to_string(),
+            ],
+            redacted_fields: vec![
+                "password".to_string(),
+                "secret".to_string(),
+                "token".to_string(),
+                "env".to_string(),
+            ],
+        }
+    }
+}
+
+pub struct RequestCapture {
+    config: RequestCaptureConfig,
+    sender: mpsc::Sender<CapturedExchange>,
+}
+
+impl RequestCapture {
+    /// Opens the capture file and starts the background task appending the captured requests
+    pub async fn start(config: &RequestCaptureConfig) -> std::io::Result<Arc<Self>> {
+        let mut file = CaptureFile::open(config).await?;
+
+        let (sender, mut receiver) = mpsc::channel::<CapturedExchange>(config.buffer_size);
+        let path = config.file.clone();
+        tokio::spawn(async move {
+            while let Some(exchange) = receiver.recv().await {
+                let mut batch = vec![exchange];
+                while let Ok(exchange) = receiver.try_recv() {
+                    batch.push(exchange);
+                }
+
+                for exchange in batch {
+                    match serde_json::to_vec(&exchange) {
+                        Ok(mut line) => {
+                            line.push(b'\n');
+                            if let Err(err) = file.write_all(&line).await {
+                                error!("Failed to write request capture file {path:?}: {err}");
+                            }
+                        }
+                        Err(err) => error!("Failed to serialize captured request: {err}"),
+                    }
+                }
+                if let Err(err) = file.flush().await {
+                    error!("Failed to flush request capture file {path:?}: {err}");
+                }
+            }
+        });
+
+        Ok(Arc::new(Self {
+            config: config.clone(),
+            sender,
+        }))
+    }
+
+    fn should_capture(&self, request: &Request) -> bool {
+        // Long-lived connections such as the worker connect websockets are not replayable
+        !request.headers().contains_key(UPGRADE) && fastrand::f64() < self.config.sample_rate
+    }
+
+    fn headers(&self, headers: &HeaderMap) -> BTreeMap<String, String> {
+        redact_headers(
+            headers.iter().map(|(name, value)| {
+                (
+                    name.as_str(),
+                    String::from_utf8_lossy(value.as_bytes()).to_string(),
+                )
+            }),
+            &self.config.redacted_headers,
+        )
+    }
+
+    /// Reads a body for capturing it, passing it on unchanged. Only bodies within
+    /// `max_body_size` are buffered, larger ones are streamed through.
+    async fn body(&self, body: Body, headers: &HeaderMap) -> std::io::Result<(Body, CapturedBody)> {
+        match read_limited(body, self.config.max_body_size).await? {
+            LimitedBody::Complete(bytes) => {
+                let captured = CapturedBody::from_bytes(
+                    &bytes,
+                    self.config.max_body_size,
+                    &self.config.redacted_fields,
+                );
+                Ok((Body::from_bytes(bytes), captured))
+            }
+            LimitedBody::Oversized { body, read } => {
+                let size = headers
+                    .get(CONTENT_LENGTH)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse().ok())
+                    .unwrap_or(read);
+                Ok((body, CapturedBody::Omitted { size }))
+            }
+        }
+    }
+
+    fn record(&self, server: CapturedServer, request: CapturedRequest, response: CapturedResponse) {
+        let exchange = CapturedExchange {
+            captured_at: chrono::Utc::now(),
+            server,
+            request,
+            response,
+        };
+        if let Err(err) = self.sender.try_send(exchange) {
+            warn!("Dropping captured request: {err}");
+        }
+    }
+}
+
+/// The capture file, rotated once it reaches `max_file_size`
+struct CaptureFile {
+    path: PathBuf,
+    file: BufWriter<File>,
+    size: u64,
+    max_size: u64,
+    max_rotated_files: usize,
+}
+
+impl CaptureFile {
+    async fn open(config: &RequestCaptureConfig) -> std::io::Result<Self> {
+        if let Some(parent) = config.file.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let file = Self::open_file(&config.file).await?;
+        let size = file.metadata().await?.len();
+        Ok(Self {
+            path: config.file.clone(),
+            file: BufWriter::new(file),
+            size,
+            max_size: config.max_file_size,
+            max_rotated_files: config.max_rotated_files,
+        })
+    }
+
+    async fn open_file(path: &Path) -> std::io::Result<File> {
+        tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+    }
+
+    async fn write_all(&mut self, line: &[u8]) -> std::io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 > self.max_size {
+            self.rotate().await?;
+        }
+        self.file.write_all(line).await?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush().await
+    }
+
+    /// Renames the capture file to `<file>.1`, shifting the already rotated ones and deleting
+    /// the oldest, then starts a new capture file
+    async fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush().await?;
+        if self.max_rotated_files == 0 {
+            tokio::fs::remove_file(&self.path).await?;
+        } else {
+            for idx in (1..self.max_rotated_files).rev() {
+                match tokio::fs::rename(
+                    rotated_path(&self.path, idx),
+                    rotated_path(&self.path, idx + 1),
+                )
+                .await
+                {
+                    Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+                    _ => {}
+                }
+            }
+            tokio::fs::rename(&self.path, rotated_path(&self.path, 1)).await?;
+        }
+        self.file = BufWriter::new(Self::open_file(&self.path).await?);
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, idx: usize) -> PathBuf {
+    let mut rotated = OsString::from(path.as_os_str());
+    rotated.push(format!(".{idx}"));
+    PathBuf::from(rotated)
+}
+
+enum LimitedBody {
+    Complete(Bytes),
+    /// The body is larger than the limit, the bytes read so far are put back in front of the
+    /// rest of the body
+    Oversized {
+        body: Body,
+        read: usize,
+    },
+}
+
+/// Reads a body until it ends or gets larger than `max_size` bytes
+async fn read_limited(body: Body, max_size: usize) -> std::io::Result<LimitedBody> {
+    let mut stream = Box::pin(body.into_bytes_stream());
+    let mut buffer = BytesMut::new();
+    while let Some(chunk) = stream.next().await {
+        buffer.extend_from_slice(&chunk?);
+        if buffer.len() > max_size {
+            let read = buffer.len();
+            let prefix =
+                futures::stream::once(async move { Ok::<_, std::io::Error>(buffer.freeze()) });
+            return Ok(LimitedBody::Oversized {
+                body: Body::from_bytes_stream(prefix.chain(stream)),
+                read,
+            });
+        }
+    }
+    Ok(LimitedBody::Complete(buffer.freeze()))
+}
+
+/// Middleware recording the requests of a server into the request capture, if it is enabled
+#[derive(Clone)]
+pub struct RequestCaptureMiddleware {
+    capture: Option<Arc<RequestCapture>>,
+    server: CapturedServer,
+}
+
+impl RequestCaptureMiddleware {
+    pub fn new(capture: Option<Arc<RequestCapture>>, server: CapturedServer) -> Self {
+        Self { capture, server }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for RequestCaptureMiddleware {
+    type Output = RequestCaptureEndpoint<E>;
+
+    fn transform(&self, inner: E) -> Self::Output {
+        RequestCaptureEndpoint {
+            inner,
+            capture: self.capture.clone(),
+            server: self.server,
+        }
+    }
+}
+
+pub struct RequestCaptureEndpoint<E> {
+    inner: E,
+    capture: Option<Arc<RequestCapture>>,
+    server: CapturedServer,
+}
+
+impl<E: Endpoint> Endpoint for RequestCaptureEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> poem::Result<Self::Output> {
+        let capture = match &self.capture {
+            Some(capture) if capture.should_capture(&req) => capture,
+            _ => return self.inner.call(req).await.map(IntoResponse::into_response),
+        };
+
+        let (request_body, captured_request_body) = capture
+            .body(req.take_body(), req.headers())
+            .await
+            .map_err(|err| poem::Error::new(err, StatusCode::BAD_REQUEST))?;
+        let request = CapturedRequest {
+            method: req.method().to_string(),
+            uri: req
+                .uri()
+                .path_and_query()
+                .map(|path_and_query| path_and_query.to_string())
+                .unwrap_or_else(|| req.uri().path().to_string()),
+            headers: capture.headers(req.headers()),
+            body: captured_request_body,
+        };
+        req.set_body(request_body);
+
+        match self.inner.call(req).await {
+            Ok(response) => {
+                let mut response = response.into_response();
+                let is_stream = response
+                    .headers()
+                    .get(CONTENT_TYPE)
+                    .is_some_and(|content_type| content_type == "text/event-stream");
+                let body = if is_stream {
+                    CapturedBody::Omitted { size: 0 }
+                } else {
+                    let (response_body, body) = capture
+                        .body(response.take_body(), response.headers())
+                        .await
+                        .map_err(|err| poem::Error::new(err, StatusCode::INTERNAL_SERVER_ERROR))?;
+                    response.set_body(response_body);
+                    body
+                };
+
+                capture.record(
+                    self.server,
+                    request,
+                    CapturedResponse {
+                        status: response.status().as_u16(),
+                        headers: capture.headers(response.headers()),
+                        body,
+                    },
+                );
+                Ok(response)
+            }
+            Err(err) => {
+                capture.record(
+                    self.server,
+                    request,
+                    CapturedResponse {
+                        status: err.status().as_u16(),
+                        headers: BTreeMap::new(),
+                        body: CapturedBody::Empty,
+                    },
+                );
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use bytes::Bytes;
+    use poem::Body;
+
+    use crate::api::request_capture::{
+        read_limited, rotated_path, CaptureFile, LimitedBody, RequestCaptureConfig,
+    };
+
+    fn chunked_body(chunks: &[&'static str]) -> Body {
+        Body::from_bytes_stream(futures::stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| Ok::<_, std::io::Error>(Bytes::from_static(chunk.as_bytes())))
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    #[test]
+    async fn bodies_within_the_limit_are_read() {
+        match read_limited(chunked_body(&["0123", "4567"]), 8)
+            .await
+            .unwrap()
+        {
+            LimitedBody::Complete(bytes) => assert_eq!(bytes, Bytes::from_static(b"01234567")),
+            LimitedBody::Oversized { .. } => panic!("body should fit in the limit"),
+        }
+    }
+
+    #[test]
+    async fn oversized_bodies_are_passed_on_without_reading_them_fully() {
+        match read_limited(chunked_body(&["0123", "4567", "89"]), 6)
+            .await
+            .unwrap()
+        {
+            LimitedBody::Complete(_) => panic!("body should not fit in the limit"),
+            LimitedBody::Oversized { body, read } => {
+                assert_eq!(read, 8);
+                assert_eq!(body.into_vec().await.unwrap(), b"0123456789".to_vec());
+            }
+        }
+    }
+
+    #[test]
+    async fn capture_file_is_rotated() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let config = RequestCaptureConfig {
+            file: dir.join("capture.jsonl"),
+            max_file_size: 10,
+            max_rotated_files: 2,
+            ..Default::default()
+        };
+
+        let mut file = CaptureFile::open(&config).await.unwrap();
+        for line in ["line-1\n", "line-2\n", "line-3\n", "line-4\n"] {
+            file.write_all(line.as_bytes()).await.unwrap();
+        }
+        file.flush().await.unwrap();
+
+        let read = |path| std::fs::read_to_string(path).unwrap();
+        assert_eq!(read(config.file.clone()), "line-4\n");
+        assert_eq!(read(rotated_path(&config.file, 1)), "line-3\n");
+        assert_eq!(read(rotated_path(&config.file, 2)), "line-2\n");
+        assert!(!rotated_path(&config.file, 3).exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
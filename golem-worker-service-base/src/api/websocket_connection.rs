@@ -0,0 +1,370 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use futures::{SinkExt, StreamExt};
+use golem_api_grpc::proto::golem::worker::LogEvent;
+use golem_common::model::WorkerEvent;
+use poem::web::websocket::{Message, WebSocketStream};
+use serde_json::Value;
+use tonic::Status;
+use tracing::{debug, info, warn};
+
+use crate::service::worker::ConnectWorkerStream;
+use crate::worker_binding::ResolvedWorkerBindingFromRequest;
+use crate::worker_bridge_execution::to_response::ToResponse;
+use crate::worker_bridge_execution::WorkerRequestExecutor;
+use crate::worker_service_rib_interpreter::WorkerServiceRibInterpreter;
+
+// Serves a connection made to a web-socket route of an API definition.
+//
+// The worker is resolved once, from the upgrade request, so every frame of a connection is
+// handled by the same worker. Each text frame is a JSON value which becomes the request body
+// the response mapping is evaluated with, and the result is sent back as a text frame.
+// The events of the worker (standard output, logs, etc.) are pushed to the client as well.
+pub struct WebSocketConnectionHandler {
+    binding: ResolvedWorkerBindingFromRequest,
+    worker_service_rib_interpreter: Arc<dyn WorkerServiceRibInterpreter + Sync + Send>,
+    worker_request_executor: Arc<dyn WorkerRequestExecutor + Sync + Send>,
+}
+
+impl WebSocketConnectionHandler {
+    pub fn new(
+        binding: ResolvedWorkerBindingFromRequest,
+        worker_service_rib_interpreter: Arc<dyn WorkerServiceRibInterpreter + Sync + Send>,
+        worker_request_executor: Arc<dyn WorkerRequestExecutor + Sync + Send>,
+    ) -> Self {
+        let mut binding = binding;
+        // An idempotency key resolved from the upgrade request would turn every frame
+        // but the first one into a duplicate of the first invocation
+        binding.worker_detail.idempotency_key = None;
+
+        Self {
+            binding,
+            worker_service_rib_interpreter,
+            worker_request_executor,
+        }
+    }
+
+    #[tracing::instrument(skip_all, fields(worker_name = %self.binding.worker_detail.worker_name))]
+    pub async fn handle(self, socket: WebSocketStream) {
+        info!("Serving WebSocket connection");
+
+        let (mut sink, mut stream) = socket.split();
+        // The worker may not exist before the first invocation, in which case connecting
+        // is retried after every frame until it succeeds
+        let mut worker_events = self.connect_to_worker().await;
+
+        loop {
+            let outgoing = tokio::select! {
+                message = stream.next() => {
+                    match message {
+                        Some(Ok(Message::Text(text))) => {
+                            let response = self.invoke(&text).await;
+                            if worker_events.is_none() {
+                                worker_events = self.connect_to_worker().await;
+                            }
+                            response
+                        }
+                        Some(Ok(Message::Binary(_))) => "Binary frames are not supported"
+                            .to_string()
+                            .to_response(&self.binding.request_details),
+                        Some(Ok(Message::Close(_))) | None => {
+                            info!("Client closed WebSocket connection");
+                            break;
+                        }
+                        Some(Ok(_)) => continue,
+                        Some(Err(error)) => {
+                            info!(error = error.to_string(), "Received WebSocket error");
+                            break;
+                        }
+                    }
+                }
+                event = next_worker_event(&mut worker_events) => {
+                    match event.map(|event| event.map(WorkerEvent::try_from)) {
+                        Some(Ok(Ok(event))) => {
+                            Message::Text(serde_json::json!({ "event": event }).to_string())
+                        }
+                        Some(Ok(Err(error))) => {
+                            warn!(error = error.to_string(), "Failed to convert worker event");
+                            continue;
+                        }
+                        Some(Err(status)) => {
+                            warn!(error = status.to_string(), "Worker event stream failed");
+                            worker_events = None;
+                            continue;
+                        }
+                        None => {
+                            debug!("Worker event stream ended");
+                            worker_events = None;
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            if let Err(error) = sink.send(outgoing).await {
+                info!(
+                    error = error.to_string(),
+                    "Error sending message to WebSocket client"
+                );
+                break;
+            }
+        }
+
+        if let Err(error) = sink.close().await {
+            debug!(
+                error = error.to_string(),
+                "Error closing WebSocket connection"
+            );
+        }
+    }
+
+    async fn invoke(&self, frame: &str) -> Message {
+        let request_details = &self.binding.request_details;
+
+        let body: Value = match serde_json::from_str(frame) {
+            Ok(body) => body,
+            Err(err) => {
+                return format!("Frame is not a valid JSON value: {}", err)
+                    .to_response(request_details)
+            }
+        };
+
+        match request_details.with_body(&body) {
+            Ok(request_details) => {
+                let binding = ResolvedWorkerBindingFromRequest {
+                    request_details,
+                    ..self.binding.clone()
+                };
+                binding
+                    .interpret_response_mapping(&self.worker_service_rib_interpreter)
+                    .await
+            }
+            Err(errors) => errors.join(", ").to_response(request_details),
+        }
+    }
+
+    async fn connect_to_worker(&self) -> Option<ConnectWorkerStream> {
+        let worker_detail = &self.binding.worker_detail;
+
+        match self
+            .worker_request_executor
+            .connect(
+                &worker_detail.component_id.component_id,
+                &worker_detail.worker_name,
+            )
+            .await
+        {
+            Ok(worker_events) => Some(worker_events),
+            Err(error) => {
+                debug!(
+                    error = error.to_string(),
+                    "Worker events are not available yet"
+                );
+                None
+            }
+        }
+    }
+}
+
+async fn next_worker_event(
+    worker_events: &mut Option<ConnectWorkerStream>,
+) -> Option<Result<LogEvent, Status>> {
+    match worker_events {
+        Some(worker_events) => worker_events.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use golem_common::model::{CallerIdentity, ComponentId, IdempotencyKey};
+    use golem_service_base::model::VersionedComponentId;
+    use golem_wasm_ast::analysis::analysed_type::{field, record, str};
+    use poem::web::websocket::Message;
+    use rib::{Expr, RibByteCode, RibInputTypeInfo, RibInterpreterResult};
+    use serde_json::{json, Value};
+
+    use crate::api::WebSocketConnectionHandler;
+    use crate::service::worker::ConnectWorkerStream;
+    use crate::worker_binding::{
+        HttpRequestDetails, RequestDetails, ResolvedWorkerBindingFromRequest,
+        ResponseMappingCompiled, RibInputValue, WorkerDetail,
+    };
+    use crate::worker_bridge_execution::{
+        WorkerRequest, WorkerRequestExecutor, WorkerRequestExecutorError, WorkerResponse,
+    };
+    use crate::worker_service_rib_interpreter::{EvaluationError, WorkerServiceRibInterpreter};
+
+    // Records the invocations and returns the request input of the response mapping
+    #[derive(Default)]
+    struct TestRibInterpreter {
+        invocations: Mutex<Vec<(String, Option<IdempotencyKey>)>>,
+    }
+
+    #[async_trait]
+    impl WorkerServiceRibInterpreter for TestRibInterpreter {
+        async fn evaluate(
+            &self,
+            worker_name: &str,
+            _component_id: &ComponentId,
+            idempotency_key: &Option<IdempotencyKey>,
+            _caller: &Option<CallerIdentity>,
+            _rib_byte_code: &RibByteCode,
+            rib_input: &RibInputValue,
+        ) -> Result<RibInterpreterResult, EvaluationError> {
+            self.invocations
+                .lock()
+                .unwrap()
+                .push((worker_name.to_string(), idempotency_key.clone()));
+
+            rib_input
+                .value
+                .get("request")
+                .cloned()
+                .map(RibInterpreterResult::Val)
+                .ok_or(EvaluationError("Missing request input".to_string()))
+        }
+    }
+
+    struct TestWorkerRequestExecutor {}
+
+    #[async_trait]
+    impl WorkerRequestExecutor for TestWorkerRequestExecutor {
+        async fn execute(
+            &self,
+            _resolved_worker_request: WorkerRequest,
+        ) -> Result<WorkerResponse, WorkerRequestExecutorError> {
+            Err("Test executor does not support invocations".into())
+        }
+
+        async fn connect(
+            &self,
+            _component_id: &ComponentId,
+            _worker_name: &str,
+        ) -> Result<ConnectWorkerStream, WorkerRequestExecutorError> {
+            Err("Test executor does not support connecting to workers".into())
+        }
+    }
+
+    fn binding(idempotency_key: Option<IdempotencyKey>) -> ResolvedWorkerBindingFromRequest {
+        ResolvedWorkerBindingFromRequest {
+            worker_detail: WorkerDetail {
+                component_id: VersionedComponentId {
+                    component_id: ComponentId::new_v4(),
+                    version: 0,
+                },
+                worker_name: "chat-1".to_string(),
+                idempotency_key,
+                caller: None,
+            },
+            request_details: RequestDetails::Http(HttpRequestDetails::empty()),
+            compiled_response_mapping: ResponseMappingCompiled {
+                response_rib_expr: Expr::identifier("request"),
+                compiled_response: RibByteCode {
+                    instructions: vec![],
+                },
+                rib_input: RibInputTypeInfo {
+                    types: HashMap::from_iter(vec![(
+                        "request".to_string(),
+                        record(vec![field("body", str())]),
+                    )]),
+                },
+            },
+            binding_type: "web-socket".to_string(),
+            cors: None,
+        }
+    }
+
+    fn handler(
+        binding: ResolvedWorkerBindingFromRequest,
+    ) -> (WebSocketConnectionHandler, Arc<TestRibInterpreter>) {
+        let interpreter = Arc::new(TestRibInterpreter::default());
+        let handler = WebSocketConnectionHandler::new(
+            binding,
+            interpreter.clone(),
+            Arc::new(TestWorkerRequestExecutor {}),
+        );
+        (handler, interpreter)
+    }
+
+    fn frame_json(message: Message) -> Value {
+        match message {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("Expected a text frame, got {:?}", other),
+        }
+    }
+
+    #[test]
+    async fn frames_are_evaluated_as_request_bodies() {
+        let (handler, interpreter) = handler(binding(None));
+
+        let first = frame_json(handler.invoke(r#""hello""#).await);
+        let second = frame_json(handler.invoke(r#""world""#).await);
+
+        assert_eq!(first, json!({ "result": { "body": "hello" } }));
+        assert_eq!(second, json!({ "result": { "body": "world" } }));
+        assert_eq!(
+            interpreter.invocations.lock().unwrap().clone(),
+            vec![("chat-1".to_string(), None), ("chat-1".to_string(), None)]
+        );
+    }
+
+    #[test]
+    async fn idempotency_key_of_the_upgrade_request_is_not_reused() {
+        let (handler, interpreter) = handler(binding(Some(IdempotencyKey::fresh())));
+
+        handler.invoke(r#""hello""#).await;
+        handler.invoke(r#""hello""#).await;
+
+        assert!(interpreter
+            .invocations
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|(_, idempotency_key)| idempotency_key.is_none()));
+    }
+
+    #[test]
+    async fn invalid_json_frames_are_answered_with_an_error() {
+        let (handler, interpreter) = handler(binding(None));
+
+        let response = frame_json(handler.invoke("not json").await);
+
+        assert!(response["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Frame is not a valid JSON value"));
+        assert!(interpreter.invocations.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    async fn frames_not_matching_the_request_type_are_answered_with_an_error() {
+        let (handler, interpreter) = handler(binding(None));
+
+        let response = frame_json(handler.invoke("42").await);
+
+        assert!(response["error"].is_string());
+        assert!(response.get("result").is_none());
+        assert!(interpreter.invocations.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,312 @@
+use hyper::http::header::{
+    ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+    ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE, VARY,
+};
+use hyper::http::{HeaderMap, HeaderName, HeaderValue, Method};
+use poem::http::StatusCode;
+use poem::Response;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+use golem_api_grpc::proto::golem::apidefinition as grpc_apidefinition;
+
+use crate::api_definition::http::MethodPattern;
+
+const ANY_ORIGIN: &str = "*";
+
+/// Cross-origin resource sharing policy of an API definition, enforced by the gateway on all of
+/// its routes, including answering the pre-flight requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct CorsPolicy {
+    /// Origins allowed to call the API, or `*` to allow any origin
+    pub allow_origins: Vec<String>,
+    /// Methods allowed by pre-flight requests, defaulting to the methods of the matching routes
+    #[serde(default)]
+    #[oai(default)]
+    pub allow_methods: Vec<MethodPattern>,
+    #[serde(default)]
+    #[oai(default)]
+    pub allow_headers: Vec<String>,
+    #[serde(default)]
+    #[oai(default)]
+    pub expose_headers: Vec<String>,
+    #[serde(default)]
+    #[oai(default)]
+    pub allow_credentials: bool,
+    pub max_age_seconds: Option<u64>,
+}
+
+impl CorsPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.allow_origins.is_empty() {
+            return Err("CORS policy must allow at least one origin".to_string());
+        }
+        for origin in &self.allow_origins {
+            if origin != ANY_ORIGIN && !is_serialized_origin(origin) {
+                return Err(format!("Invalid origin in CORS policy: {origin}"));
+            }
+        }
+        for header in self.allow_headers.iter().chain(&self.expose_headers) {
+            HeaderName::from_bytes(header.as_bytes())
+                .map_err(|_| format!("Invalid header name in CORS policy: {header}"))?;
+        }
+        Ok(())
+    }
+
+    /// The value of `Access-Control-Allow-Origin` for a request coming from `origin`, if the
+    /// origin is allowed. Credentials can't be used with a wildcard, so the origin is echoed then.
+    pub fn allowed_origin(&self, origin: &str) -> Option<String> {
+        if self.allow_origins.iter().any(|allowed| allowed == origin) {
+            Some(origin.to_string())
+        } else if self
+            .allow_origins
+            .iter()
+            .any(|allowed| allowed == ANY_ORIGIN)
+        {
+            if self.allow_credentials {
+                Some(origin.to_string())
+            } else {
+                Some(ANY_ORIGIN.to_string())
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Adds the CORS headers to the response of an actual (not pre-flight) request
+    pub fn add_response_headers(&self, origin: &str, headers: &mut HeaderMap) {
+        if let Some(allowed_origin) = self.allowed_origin(origin) {
+            self.add_common_headers(&allowed_origin, headers);
+            if !self.expose_headers.is_empty() {
+                insert_header(
+                    headers,
+                    ACCESS_CONTROL_EXPOSE_HEADERS,
+                    &self.expose_headers.join(", "),
+                );
+            }
+        }
+    }
+
+    /// Answers a pre-flight request, given the methods of the routes matching its path
+    pub fn preflight_response(&self, origin: &str, route_methods: &[Method]) -> Response {
+        match self.allowed_origin(origin) {
+            Some(allowed_origin) => {
+                let mut response = Response::builder().status(StatusCode::NO_CONTENT).finish();
+                let headers = response.headers_mut();
+                self.add_common_headers(&allowed_origin, headers);
+
+                let methods: Vec<String> = if self.allow_methods.is_empty() {
+                    route_methods.iter().map(|m| m.to_string()).collect()
+                } else {
+                    self.allow_methods
+                        .iter()
+                        .map(|m| Method::from(m.clone()).to_string())
+                        .collect()
+                };
+                insert_header(headers, ACCESS_CONTROL_ALLOW_METHODS, &methods.join(", "));
+                if !self.allow_headers.is_empty() {
+                    insert_header(
+                        headers,
+                        ACCESS_CONTROL_ALLOW_HEADERS,
+                        &self.allow_headers.join(", "),
+                    );
+                }
+                if let Some(max_age_seconds) = self.max_age_seconds {
+                    insert_header(
+                        headers,
+                        ACCESS_CONTROL_MAX_AGE,
+                        &max_age_seconds.to_string(),
+                    );
+                }
+                response
+            }
+            None => Response::builder().status(StatusCode::FORBIDDEN).finish(),
+        }
+    }
+
+    fn add_common_headers(&self, allowed_origin: &str, headers: &mut HeaderMap) {
+        insert_header(headers, ACCESS_CONTROL_ALLOW_ORIGIN, allowed_origin);
+        if allowed_origin != ANY_ORIGIN {
+            headers.append(VARY, HeaderValue::from_static("Origin"));
+        }
+        if self.allow_credentials {
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static("true"),
+            );
+        }
+    }
+}
+
+fn insert_header(headers: &mut HeaderMap, name: HeaderName, value: &str) {
+    if let Ok(value) = HeaderValue::from_str(value) {
+        headers.insert(name, value);
+    }
+}
+
+// An origin as sent by browsers in the `Origin` header: scheme, host and optional port only
+fn is_serialized_origin(origin: &str) -> bool {
+    match url::Url::parse(origin) {
+        Ok(url) => url.origin().ascii_serialization() == origin,
+        Err(_) => false,
+    }
+}
+
+impl From<CorsPolicy> for grpc_apidefinition::CorsPolicy {
+    fn from(value: CorsPolicy) -> Self {
+        grpc_apidefinition::CorsPolicy {
+            allow_origins: value.allow_origins,
+            allow_methods: value
+                .allow_methods
+                .into_iter()
+                .map(|method| grpc_apidefinition::HttpMethod::from(method) as i32)
+                .collect(),
+            allow_headers: value.allow_headers,
+            expose_headers: value.expose_headers,
+            allow_credentials: value.allow_credentials,
+            max_age_seconds: value.max_age_seconds,
+        }
+    }
+}
+
+impl TryFrom<grpc_apidefinition::CorsPolicy> for CorsPolicy {
+    type Error = String;
+
+    fn try_from(value: grpc_apidefinition::CorsPolicy) -> Result<Self, Self::Error> {
+        let allow_methods = value
+            .allow_methods
+            .into_iter()
+            .map(|method| MethodPattern::try_from(method).map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(CorsPolicy {
+            allow_origins: value.allow_origins,
+            allow_methods,
+            allow_headers: value.allow_headers,
+            expose_headers: value.expose_headers,
+            allow_credentials: value.allow_credentials,
+            max_age_seconds: value.max_age_seconds,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use hyper::http::header::{
+        ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_METHODS,
+        ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_MAX_AGE,
+    };
+    use hyper::http::{HeaderMap, Method};
+    use poem::http::StatusCode;
+
+    use crate::api_definition::http::{CorsPolicy, MethodPattern};
+
+    fn policy(allow_origins: Vec<&str>) -> CorsPolicy {
+        CorsPolicy {
+            allow_origins: allow_origins.into_iter().map(|o| o.to_string()).collect(),
+            allow_methods: vec![],
+            allow_headers: vec![],
+            expose_headers: vec![],
+            allow_credentials: false,
+            max_age_seconds: None,
+        }
+    }
+
+    #[test]
+    fn validation() {
+        assert!(policy(vec!["*"]).validate().is_ok());
+        assert!(policy(vec!["https://example.com:8080"]).validate().is_ok());
+        assert!(policy(vec![]).validate().is_err());
+        assert!(policy(vec!["https://example.com/path"]).validate().is_err());
+        assert!(policy(vec!["example.com"]).validate().is_err());
+    }
+
+    #[test]
+    fn allowed_origins() {
+        let specific = policy(vec!["https://example.com"]);
+        assert_eq!(
+            specific.allowed_origin("https://example.com"),
+            Some("https://example.com".to_string())
+        );
+        assert_eq!(specific.allowed_origin("https://other.com"), None);
+
+        let any = policy(vec!["*"]);
+        assert_eq!(
+            any.allowed_origin("https://other.com"),
+            Some("*".to_string())
+        );
+
+        let any_with_credentials = CorsPolicy {
+            allow_credentials: true,
+            ..policy(vec!["*"])
+        };
+        assert_eq!(
+            any_with_credentials.allowed_origin("https://other.com"),
+            Some("https://other.com".to_string())
+        );
+    }
+
+    #[test]
+    fn response_headers_only_added_for_allowed_origins() {
+        let policy = CorsPolicy {
+            allow_credentials: true,
+            ..policy(vec!["https://example.com"])
+        };
+
+        let mut headers = HeaderMap::new();
+        policy.add_response_headers("https://other.com", &mut headers);
+        assert!(headers.is_empty());
+
+        policy.add_response_headers("https://example.com", &mut headers);
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://example.com"
+        );
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_CREDENTIALS).unwrap(),
+            "true"
+        );
+    }
+
+    #[test]
+    fn preflight_allows_route_methods_by_default() {
+        let default_methods = CorsPolicy {
+            max_age_seconds: Some(600),
+            ..policy(vec!["*"])
+        };
+        let response =
+            default_methods.preflight_response("https://example.com", &[Method::GET, Method::POST]);
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response
+                .headers()
+                .get(ACCESS_CONTROL_ALLOW_METHODS)
+                .unwrap(),
+            "GET, POST"
+        );
+        assert_eq!(
+            response.headers().get(ACCESS_CONTROL_MAX_AGE).unwrap(),
+            "600"
+        );
+
+        let configured_methods = CorsPolicy {
+            allow_methods: vec![MethodPattern::Put],
+            ..policy(vec!["https://example.com"])
+        };
+        let response = configured_methods.preflight_response("https://example.com", &[Method::GET]);
+        assert_eq!(
+            response
+                .headers()
+                .get(ACCESS_CONTROL_ALLOW_METHODS)
+                .unwrap(),
+            "PUT"
+        );
+
+        let response = configured_methods.preflight_response("https://other.com", &[Method::GET]);
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
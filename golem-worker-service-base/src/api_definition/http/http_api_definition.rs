@@ -11,6 +11,7 @@ use poem_openapi::Enum;
 use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Value;
 
+use crate::api_definition::http::CorsPolicy;
 use crate::api_definition::{ApiDefinitionId, ApiVersion, HasGolemWorkerBindings};
 use crate::parser::path_pattern_parser::PathPatternParser;
 use crate::parser::{GolemParser, ParseError};
@@ -25,6 +26,8 @@ pub struct HttpApiDefinitionRequest {
     pub routes: Vec<Route>,
     #[serde(default)]
     pub draft: bool,
+    #[serde(default)]
+    pub cors: Option<CorsPolicy>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -35,6 +38,8 @@ pub struct HttpApiDefinition {
     pub routes: Vec<Route>,
     #[serde(default)]
     pub draft: bool,
+    #[serde(default)]
+    pub cors: Option<CorsPolicy>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -48,6 +53,7 @@ impl HttpApiDefinition {
             version: request.version,
             routes: request.routes,
             draft: request.draft,
+            cors: request.cors,
             created_at,
         }
     }
@@ -60,6 +66,7 @@ impl From<HttpApiDefinition> for HttpApiDefinitionRequest {
             version: value.version,
             routes: value.routes,
             draft: value.draft,
+            cors: value.cors,
         }
     }
 }
@@ -75,6 +82,7 @@ impl From<CompiledHttpApiDefinition> for HttpApiDefinition {
                 .map(Route::from)
                 .collect(),
             draft: compiled_http_api_definition.draft,
+            cors: compiled_http_api_definition.cors,
             created_at: compiled_http_api_definition.created_at,
         }
     }
@@ -91,6 +99,7 @@ pub struct CompiledHttpApiDefinition {
     pub version: ApiVersion,
     pub routes: Vec<CompiledRoute>,
     pub draft: bool,
+    pub cors: Option<CorsPolicy>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -111,6 +120,7 @@ impl CompiledHttpApiDefinition {
             version: http_api_definition.version.clone(),
             routes: compiled_routes,
             draft: http_api_definition.draft,
+            cors: http_api_definition.cors.clone(),
             created_at: http_api_definition.created_at,
         })
     }
@@ -375,9 +385,10 @@ impl ComponentMetadataDictionary {
     pub fn from_components(components: &Vec<Component>) -> ComponentMetadataDictionary {
         let mut metadata = HashMap::new();
         for component in components {
+            // Aliases are compiled as root level functions, and resolved when invoked
             metadata.insert(
                 component.versioned_component_id.clone(),
-                component.metadata.exports.clone(),
+                component.metadata.exports_with_aliases(),
             );
         }
 
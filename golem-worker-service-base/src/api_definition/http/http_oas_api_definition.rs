@@ -16,6 +16,8 @@ pub fn get_api_definition(openapi: OpenAPI) -> Result<HttpApiDefinitionRequest,
     let api_definition_version =
         ApiVersion(get_root_extension(&openapi, GOLEM_API_DEFINITION_VERSION)?);
 
+    let cors = get_cors(&openapi)?;
+
     let routes = get_routes(openapi.paths)?;
 
     Ok(HttpApiDefinitionRequest {
@@ -23,6 +25,7 @@ pub fn get_api_definition(openapi: OpenAPI) -> Result<HttpApiDefinitionRequest,
         version: api_definition_version,
         routes,
         draft: true,
+        cors,
     })
 }
 
@@ -75,7 +78,7 @@ impl ParseFromJSON for JsonOpenApiDefinition {
 }
 
 mod internal {
-    use crate::api_definition::http::{AllPathPatterns, MethodPattern, Route};
+    use crate::api_definition::http::{AllPathPatterns, CorsPolicy, MethodPattern, Route};
     use crate::worker_binding::{BindingType, GolemWorkerBinding, ResponseMapping};
     use golem_common::model::ComponentId;
     use openapiv3::{OpenAPI, PathItem, Paths, ReferenceOr};
@@ -88,6 +91,7 @@ mod internal {
     pub(crate) const GOLEM_API_DEFINITION_ID_EXTENSION: &str = "x-golem-api-definition-id";
     pub(crate) const GOLEM_API_DEFINITION_VERSION: &str = "x-golem-api-definition-version";
     pub(crate) const GOLEM_WORKER_BRIDGE_EXTENSION: &str = "x-golem-worker-bridge";
+    pub(crate) const GOLEM_CORS_EXTENSION: &str = "x-golem-cors";
 
     pub(crate) fn get_root_extension(open_api: &OpenAPI, key_name: &str) -> Result<String, String> {
         open_api
@@ -101,6 +105,20 @@ mod internal {
             .map(|x| x.to_string())
     }
 
+    pub(crate) fn get_cors(open_api: &OpenAPI) -> Result<Option<CorsPolicy>, String> {
+        open_api
+            .extensions
+            .iter()
+            .find(|(key, _)| key.to_lowercase() == GOLEM_CORS_EXTENSION)
+            .map(|(_, value)| {
+                let cors = serde_json::from_value::<CorsPolicy>(value.clone())
+                    .map_err(|e| format!("Invalid value for {}: {}", GOLEM_CORS_EXTENSION, e))?;
+                cors.validate()?;
+                Ok(cors)
+            })
+            .transpose()
+    }
+
     pub(crate) fn get_routes(paths: Paths) -> Result<Vec<Route>, String> {
         let mut routes: Vec<Route> = vec![];
 
@@ -158,7 +176,7 @@ mod internal {
             component_id: get_component_id(worker_bridge_info)?,
             idempotency_key: get_idempotency_key(worker_bridge_info)?,
             response: get_response_mapping(worker_bridge_info)?,
-            binding_type: "file-server".to_string(),
+            binding_type: get_binding_type(worker_bridge_info)?,
         };
 
         Ok(Route {
@@ -236,6 +254,20 @@ mod internal {
         }
     }
 
+    pub(crate) fn get_binding_type(worker_bridge_info: &Value) -> Result<String, String> {
+        match worker_bridge_info.get("binding-type") {
+            Some(binding_type) => {
+                let binding_type = binding_type
+                    .as_str()
+                    .ok_or("binding-type is not a string")?;
+                BindingType::from_name(binding_type)
+                    .map(|binding_type| binding_type.to_string())
+                    .ok_or(format!("Unknown binding-type {}", binding_type))
+            }
+            None => Ok("file-server".to_string()),
+        }
+    }
+
     pub(crate) fn get_path_pattern(path: &str) -> Result<AllPathPatterns, String> {
         AllPathPatterns::parse(path).map_err(|err| err.to_string())
     }
@@ -317,4 +349,14 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_get_binding_type() {
+        assert_eq!(
+            get_binding_type(&json!({ "binding-type": "web-socket" })),
+            Ok("web-socket".to_string())
+        );
+        assert_eq!(get_binding_type(&json!({})), Ok("file-server".to_string()));
+        assert!(get_binding_type(&json!({ "binding-type": "unknown" })).is_err());
+    }
 }
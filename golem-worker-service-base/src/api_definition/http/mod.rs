@@ -1,5 +1,7 @@
+pub use cors::*;
 pub use http_api_definition::*;
 pub use http_oas_api_definition::*;
 
+mod cors;
 mod http_api_definition;
 mod http_oas_api_definition;
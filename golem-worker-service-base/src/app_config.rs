@@ -6,11 +6,19 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 use uuid::Uuid;
 
-use golem_common::config::{ConfigExample, HasConfigExamples, RetryConfig};
+use golem_common::config::{ConfigExample, GrpcCompression, HasConfigExamples, RetryConfig};
 use golem_common::config::{DbConfig, DbSqliteConfig};
 use golem_common::tracing::TracingConfig;
 use golem_service_base::routing_table::RoutingTableConfig;
 
+use crate::api::{AdminConfig, RequestCaptureConfig};
+use crate::http::ClientCertificateConfig;
+use crate::service::maintenance::MaintenanceConfig;
+use crate::service::saga::SagaConfig;
+use crate::service::worker::{
+    IdempotencyCacheConfig, InvocationQueueConfig, WorkerRequestLimitsConfig,
+};
+
 // The base configuration for the worker service
 // If there are extra configurations for custom services,
 // it's preferred to reuse base config.
@@ -23,8 +31,17 @@ pub struct WorkerServiceBaseConfig {
     pub port: u16,
     pub custom_request_port: u16,
     pub worker_grpc_port: u16,
+    pub grpc_compression: GrpcCompression,
     pub routing_table: RoutingTableConfig,
     pub worker_executor_retries: RetryConfig,
+    pub limits: WorkerRequestLimitsConfig,
+    pub maintenance: MaintenanceConfig,
+    pub invocation_queue: InvocationQueueConfig,
+    pub idempotency_cache: IdempotencyCacheConfig,
+    pub saga: SagaConfig,
+    pub client_certificate: ClientCertificateConfig,
+    pub request_capture: RequestCaptureConfig,
+    pub admin: AdminConfig,
 }
 
 impl WorkerServiceBaseConfig {
@@ -46,6 +63,7 @@ impl Default for WorkerServiceBaseConfig {
             port: 9005,
             custom_request_port: 9006,
             worker_grpc_port: 9007,
+            grpc_compression: GrpcCompression::default(),
             routing_table: RoutingTableConfig::default(),
             worker_executor_retries: RetryConfig {
                 max_attempts: 5,
@@ -54,6 +72,14 @@ impl Default for WorkerServiceBaseConfig {
                 multiplier: 10.0,
                 max_jitter_factor: Some(0.15),
             },
+            limits: WorkerRequestLimitsConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            invocation_queue: InvocationQueueConfig::default(),
+            idempotency_cache: IdempotencyCacheConfig::default(),
+            saga: SagaConfig::default(),
+            client_certificate: ClientCertificateConfig::default(),
+            request_capture: RequestCaptureConfig::default(),
+            admin: AdminConfig::default(),
         }
     }
 }
@@ -0,0 +1,182 @@
+use golem_common::model::CallerIdentity;
+use hyper::http::HeaderMap;
+use serde::{Deserialize, Serialize};
+
+/// Identifies the API gateway's clients by the TLS client certificates they authenticated with.
+///
+/// TLS is terminated by a proxy in front of the gateway, which verifies the client certificates
+/// and forwards the details of the verified certificate in a header, using the format of Envoy's
+/// `x-forwarded-client-cert`. The proxy must set this header on every request, overwriting the
+/// one sent by the client, otherwise clients could claim any identity.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClientCertificateConfig {
+    pub enabled: bool,
+    pub header: String,
+}
+
+impl ClientCertificateConfig {
+    /// The identity of the client making a request, passed to the invoked workers
+    pub fn caller_identity(&self, headers: &HeaderMap) -> Option<CallerIdentity> {
+        if !self.enabled {
+            return None;
+        }
+        let value = headers.get(&self.header)?.to_str().ok()?;
+        parse_forwarded_client_cert(value)
+    }
+}
+
+impl Default for ClientCertificateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            header: "x-forwarded-client-cert".to_string(),
+        }
+    }
+}
+
+// Every proxy appends an element describing its client, so the last one is the element added by
+// the proxy the gateway trusts
+fn parse_forwarded_client_cert(value: &str) -> Option<CallerIdentity> {
+    let element = split_unquoted(value, ',').pop()?;
+
+    let mut identity = CallerIdentity {
+        subject: String::new(),
+        uris: Vec::new(),
+        dns_names: Vec::new(),
+    };
+    for pair in split_unquoted(&element, ';') {
+        if let Some((key, value)) = pair.split_once('=') {
+            let value = unquote(value.trim());
+            match key.trim().to_ascii_lowercase().as_str() {
+                "subject" => identity.subject = value,
+                "uri" => identity.uris.push(value),
+                "dns" => identity.dns_names.push(value),
+                _ => {}
+            }
+        }
+    }
+
+    if identity.subject.is_empty() && identity.uris.is_empty() && identity.dns_names.is_empty() {
+        None
+    } else {
+        Some(identity)
+    }
+}
+
+// Splits a value at the separators which are not part of a quoted string
+fn split_unquoted(value: &str, separator: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    for c in value.chars() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            quoted = !quoted;
+        } else if c == separator && !quoted {
+            parts.push(std::mem::take(&mut current));
+            continue;
+        }
+        current.push(c);
+    }
+    parts.push(current);
+    parts
+}
+
+fn unquote(value: &str) -> String {
+    match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(quoted) => {
+            let mut result = String::with_capacity(quoted.len());
+            let mut escaped = false;
+            for c in quoted.chars() {
+                if !escaped && c == '\\' {
+                    escaped = true;
+                } else {
+                    escaped = false;
+                    result.push(c);
+                }
+            }
+            result
+        }
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::CallerIdentity;
+    use hyper::http::{HeaderMap, HeaderValue};
+
+    use crate::http::ClientCertificateConfig;
+
+    fn headers(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-client-cert",
+            HeaderValue::from_str(value).unwrap(),
+        );
+        headers
+    }
+
+    fn enabled() -> ClientCertificateConfig {
+        ClientCertificateConfig {
+            enabled: true,
+            ..ClientCertificateConfig::default()
+        }
+    }
+
+    #[test]
+    fn parses_the_forwarded_certificate() {
+        let headers = headers(
+            r#"By=spiffe://golem/gateway;Hash=468ed33be74eee6556d90c0149c1309e9ba61d6425303443c0748a02dd8de688;Subject="CN=client,O=Golem\, Inc.";URI=spiffe://golem/client;DNS=client.golem.cloud;DNS=client.local"#,
+        );
+
+        assert_eq!(
+            enabled().caller_identity(&headers),
+            Some(CallerIdentity {
+                subject: "CN=client,O=Golem, Inc.".to_string(),
+                uris: vec!["spiffe://golem/client".to_string()],
+                dns_names: vec!["client.golem.cloud".to_string(), "client.local".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn uses_the_last_appended_element() {
+        let headers = headers(
+            r#"Subject="CN=first,O=Golem";URI=spiffe://golem/first,Subject="CN=last,O=Golem""#,
+        );
+
+        assert_eq!(
+            enabled().caller_identity(&headers),
+            Some(CallerIdentity {
+                subject: "CN=last,O=Golem".to_string(),
+                uris: vec![],
+                dns_names: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn ignored_when_disabled_or_without_identity() {
+        let headers_with_identity = headers(r#"Subject="CN=client""#);
+        assert_eq!(
+            ClientCertificateConfig::default().caller_identity(&headers_with_identity),
+            None
+        );
+
+        assert_eq!(
+            enabled().caller_identity(&headers("Hash=468ed33be74eee65")),
+            None
+        );
+        assert_eq!(enabled().caller_identity(&HeaderMap::new()), None);
+    }
+}
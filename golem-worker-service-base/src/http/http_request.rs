@@ -49,7 +49,7 @@ impl ApiInputPath {
 }
 
 pub mod router {
-    use crate::api_definition::http::CompiledRoute;
+    use crate::api_definition::http::{CompiledHttpApiDefinition, CorsPolicy};
     use crate::worker_binding::CompiledGolemWorkerBinding;
     use crate::{
         api_definition::http::{PathPattern, QueryInfo, VarInfo},
@@ -62,12 +62,21 @@ pub mod router {
         pub path_params: Vec<(VarInfo, usize)>,
         pub query_params: Vec<QueryInfo>,
         pub binding: CompiledGolemWorkerBinding,
+        // CORS policy of the API definition the route belongs to
+        pub cors: Option<CorsPolicy>,
     }
 
-    pub fn build(routes: Vec<CompiledRoute>) -> Router<RouteEntry> {
+    pub fn build(api_definitions: &[CompiledHttpApiDefinition]) -> Router<RouteEntry> {
         let mut router = Router::new();
 
-        for route in routes {
+        let routes = api_definitions.iter().flat_map(|api_definition| {
+            api_definition
+                .routes
+                .iter()
+                .map(|route| (route.clone(), api_definition.cors.clone()))
+        });
+
+        for (route, cors) in routes {
             let method = route.method.into();
             let path = route.path;
             let binding = route.binding;
@@ -86,6 +95,7 @@ pub mod router {
                 path_params,
                 query_params: path.query_params,
                 binding,
+                cors,
             };
 
             let path: Vec<RouterPattern> = path
@@ -111,6 +121,7 @@ mod tests {
     use crate::getter::Getter;
     use crate::http::http_request::{ApiInputPath, InputHttpRequest};
     use crate::path::Path;
+    use crate::service::worker::ConnectWorkerStream;
     use crate::worker_binding::{
         RequestDetails, RequestToWorkerBindingResolver, RibInputTypeMismatch,
     };
@@ -152,6 +163,14 @@ mod tests {
 
             Ok(WorkerResponse::new(response_dummy))
         }
+
+        async fn connect(
+            &self,
+            _component_id: &ComponentId,
+            _worker_name: &str,
+        ) -> Result<ConnectWorkerStream, WorkerRequestExecutorError> {
+            Err("Test executor does not support connecting to workers".into())
+        }
     }
 
     fn create_tuple(type_annotated_value: Vec<TypeAnnotatedValue>) -> TypeAnnotatedValue {
@@ -1,5 +1,7 @@
+pub use client_certificate::*;
 pub use http_request::*;
 
+pub mod client_certificate;
 pub mod http_request;
 
 pub mod router;
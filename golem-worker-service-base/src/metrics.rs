@@ -27,3 +27,105 @@ pub fn register_all() -> Registry {
 
     default_registry().clone()
 }
+
+pub mod invocation_queue {
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref PENDING_INVOCATIONS_TOTAL: IntCounterVec = register_int_counter_vec!(
+            "pending_invocations_total",
+            "Number of invocations stored in, or removed from, the queue of undelivered invocations",
+            &["event"]
+        )
+        .unwrap();
+        static ref PENDING_INVOCATIONS_DEPTH: IntGauge = register_int_gauge!(
+            "pending_invocations_depth",
+            "Number of invocations in the queue of undelivered invocations"
+        )
+        .unwrap();
+    }
+
+    pub fn record_enqueued() {
+        PENDING_INVOCATIONS_TOTAL
+            .with_label_values(&["enqueued"])
+            .inc();
+    }
+
+    pub fn record_delivered() {
+        PENDING_INVOCATIONS_TOTAL
+            .with_label_values(&["delivered"])
+            .inc();
+    }
+
+    pub fn record_expired(count: u64) {
+        PENDING_INVOCATIONS_TOTAL
+            .with_label_values(&["expired"])
+            .inc_by(count);
+    }
+
+    pub fn record_depth(depth: u64) {
+        PENDING_INVOCATIONS_DEPTH.set(depth as i64);
+    }
+}
+
+pub mod invocation {
+    use std::time::Duration;
+
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    lazy_static! {
+        static ref INVOCATION_PHASE_SECONDS: HistogramVec = register_histogram_vec!(
+            "invocation_phase_seconds",
+            "Time spent in the phases of handling an invocation, to tell apart the worker service's own latency from the worker executor's",
+            &["phase"],
+            golem_common::metrics::DEFAULT_TIME_BUCKETS.to_vec()
+        )
+        .unwrap();
+        static ref TYPE_CHECK_FAILURES_TOTAL: IntCounterVec = register_int_counter_vec!(
+            "invocation_type_check_failures_total",
+            "Number of invocations rejected because their parameters did not type check",
+            &["component_id", "function"]
+        )
+        .unwrap();
+    }
+
+    pub fn record_component_metadata_fetch(duration: Duration) {
+        record_phase("component_metadata", duration);
+    }
+
+    /// The conversion of the type annotated parameters to the values sent to the executor, which
+    /// checks them against their own annotations but not against the function's signature
+    pub fn record_parameter_conversion(duration: Duration) {
+        record_phase("parameter_conversion", duration);
+    }
+
+    /// The check of the parameters against the signature of the invoked function
+    pub fn record_type_check(duration: Duration) {
+        record_phase("type_check", duration);
+    }
+
+    /// Only functions exported by the component are used as labels, as the invoked names come
+    /// from the requests, others are counted as `unknown`
+    pub fn record_type_check_failure(component_id: &str, exported_function: Option<&str>) {
+        TYPE_CHECK_FAILURES_TOTAL
+            .with_label_values(&[component_id, exported_function.unwrap_or("unknown")])
+            .inc();
+    }
+
+    /// Also recorded for the other calls targeting a single worker executor
+    pub fn record_routing(duration: Duration) {
+        record_phase("routing", duration);
+    }
+
+    pub fn record_executor_call(duration: Duration) {
+        record_phase("executor_call", duration);
+    }
+
+    fn record_phase(phase: &'static str, duration: Duration) {
+        INVOCATION_PHASE_SECONDS
+            .with_label_values(&[phase])
+            .observe(duration.as_secs_f64());
+    }
+}
@@ -37,7 +37,7 @@ impl ApiDefinitionRecord {
         definition: CompiledHttpApiDefinition,
         created_at: chrono::DateTime<chrono::Utc>,
     ) -> Result<Self, String> {
-        let data = record_data_serde::serialize(&definition.routes)?;
+        let data = record_data_serde::serialize(&definition.routes, &definition.cors)?;
         Ok(Self {
             namespace: namespace.to_string(),
             id: definition.id.0,
@@ -52,13 +52,14 @@ impl ApiDefinitionRecord {
 impl TryFrom<ApiDefinitionRecord> for CompiledHttpApiDefinition {
     type Error = String;
     fn try_from(value: ApiDefinitionRecord) -> Result<Self, Self::Error> {
-        let routes = record_data_serde::deserialize(&value.data)?;
+        let (routes, cors) = record_data_serde::deserialize(&value.data)?;
 
         Ok(Self {
             id: value.id.into(),
             version: value.version.into(),
             routes,
             draft: value.draft,
+            cors,
             created_at: value.created_at,
         })
     }
@@ -309,7 +310,7 @@ impl ApiDefinitionRepo for DbApiDefinitionRepo<sqlx::Postgres> {
 }
 
 pub mod record_data_serde {
-    use crate::api_definition::http::CompiledRoute;
+    use crate::api_definition::http::{CompiledRoute, CorsPolicy};
     use bytes::{BufMut, Bytes, BytesMut};
     use golem_api_grpc::proto::golem::apidefinition::{
         CompiledHttpApiDefinition, CompiledHttpRoute,
@@ -318,14 +319,17 @@ pub mod record_data_serde {
 
     pub const SERIALIZATION_VERSION_V1: u8 = 1u8;
 
-    pub fn serialize(value: &[CompiledRoute]) -> Result<Bytes, String> {
+    pub fn serialize(value: &[CompiledRoute], cors: &Option<CorsPolicy>) -> Result<Bytes, String> {
         let routes: Vec<CompiledHttpRoute> = value
             .iter()
             .cloned()
             .map(CompiledHttpRoute::try_from)
             .collect::<Result<Vec<CompiledHttpRoute>, String>>()?;
 
-        let proto_value: CompiledHttpApiDefinition = CompiledHttpApiDefinition { routes };
+        let proto_value: CompiledHttpApiDefinition = CompiledHttpApiDefinition {
+            routes,
+            cors: cors.clone().map(|cors| cors.into()),
+        };
 
         let mut bytes = BytesMut::new();
         bytes.put_u8(SERIALIZATION_VERSION_V1);
@@ -333,7 +337,7 @@ pub mod record_data_serde {
         Ok(bytes.freeze())
     }
 
-    pub fn deserialize(bytes: &[u8]) -> Result<Vec<CompiledRoute>, String> {
+    pub fn deserialize(bytes: &[u8]) -> Result<(Vec<CompiledRoute>, Option<CorsPolicy>), String> {
         let (version, data) = bytes.split_at(1);
 
         match version[0] {
@@ -347,7 +351,9 @@ pub mod record_data_serde {
                     .map(CompiledRoute::try_from)
                     .collect::<Result<Vec<CompiledRoute>, String>>()?;
 
-                Ok(value)
+                let cors = proto_value.cors.map(CorsPolicy::try_from).transpose()?;
+
+                Ok((value, cors))
             }
             _ => Err("Unsupported serialization version".to_string()),
         }
@@ -0,0 +1,124 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use conditional_trait_gen::trait_gen;
+use golem_service_base::repo::RepoError;
+use sqlx::{Database, Pool};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A component in maintenance mode
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct ComponentMaintenanceRecord {
+    pub account_id: String,
+    pub component_id: String,
+    pub message: Option<String>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[async_trait]
+pub trait ComponentMaintenanceRepo {
+    /// Creates or replaces the maintenance record of the component
+    async fn upsert(&self, record: &ComponentMaintenanceRecord) -> Result<(), RepoError>;
+
+    async fn get(
+        &self,
+        account_id: &str,
+        component_id: &str,
+    ) -> Result<Option<ComponentMaintenanceRecord>, RepoError>;
+
+    /// Gets every component in maintenance mode, of all the accounts
+    async fn get_all(&self) -> Result<Vec<ComponentMaintenanceRecord>, RepoError>;
+
+    async fn delete(&self, account_id: &str, component_id: &str) -> Result<bool, RepoError>;
+}
+
+pub struct DbComponentMaintenanceRepo<DB: Database> {
+    db_pool: Arc<Pool<DB>>,
+}
+
+impl<DB: Database> DbComponentMaintenanceRepo<DB> {
+    pub fn new(db_pool: Arc<Pool<DB>>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[trait_gen(sqlx::Postgres -> sqlx::Postgres, sqlx::Sqlite)]
+#[async_trait]
+impl ComponentMaintenanceRepo for DbComponentMaintenanceRepo<sqlx::Postgres> {
+    async fn upsert(&self, record: &ComponentMaintenanceRecord) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO component_maintenance
+                (account_id, component_id, message, started_at)
+              VALUES
+                ($1, $2, $3, $4)
+              ON CONFLICT (account_id, component_id) DO UPDATE
+              SET message = $3, started_at = $4
+            "#,
+        )
+        .bind(record.account_id.clone())
+        .bind(record.component_id.clone())
+        .bind(record.message.clone())
+        .bind(record.started_at)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(
+        &self,
+        account_id: &str,
+        component_id: &str,
+    ) -> Result<Option<ComponentMaintenanceRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentMaintenanceRecord>(
+            r#"
+              SELECT account_id, component_id, message, started_at
+              FROM component_maintenance
+              WHERE account_id = $1 AND component_id = $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(component_id)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn get_all(&self) -> Result<Vec<ComponentMaintenanceRecord>, RepoError> {
+        sqlx::query_as::<_, ComponentMaintenanceRecord>(
+            r#"
+              SELECT account_id, component_id, message, started_at
+              FROM component_maintenance
+            "#,
+        )
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn delete(&self, account_id: &str, component_id: &str) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            "DELETE FROM component_maintenance WHERE account_id = $1 AND component_id = $2",
+        )
+        .bind(account_id)
+        .bind(component_id)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
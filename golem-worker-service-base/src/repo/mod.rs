@@ -14,3 +14,7 @@
 
 pub mod api_definition;
 pub mod api_deployment;
+pub mod maintenance;
+pub mod pending_invocation;
+pub mod saga;
+pub mod worker_defaults;
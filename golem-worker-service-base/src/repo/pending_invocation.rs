@@ -0,0 +1,141 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use conditional_trait_gen::{trait_gen, when};
+use golem_service_base::repo::RepoError;
+use sqlx::{Database, Pool};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A fire-and-forget invocation that could not be delivered to a worker executor yet
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct PendingInvocationRecord {
+    pub id: String,
+    pub worker_id: String,
+    /// The encoded `InvokeWorkerRequest` to send to the worker executor
+    pub data: Vec<u8>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[async_trait]
+pub trait PendingInvocationRepo {
+    async fn create(&self, invocation: &PendingInvocationRecord) -> Result<(), RepoError>;
+
+    /// Gets the oldest pending invocations, at most `limit` of them
+    async fn get_oldest(&self, limit: u32) -> Result<Vec<PendingInvocationRecord>, RepoError>;
+
+    async fn delete(&self, id: &str) -> Result<bool, RepoError>;
+
+    /// Deletes the invocations expired before `now`, returning the number of deleted records
+    async fn delete_expired(&self, now: chrono::DateTime<chrono::Utc>) -> Result<u64, RepoError>;
+
+    async fn count(&self) -> Result<u64, RepoError>;
+}
+
+pub struct DbPendingInvocationRepo<DB: Database> {
+    db_pool: Arc<Pool<DB>>,
+}
+
+impl<DB: Database> DbPendingInvocationRepo<DB> {
+    pub fn new(db_pool: Arc<Pool<DB>>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[trait_gen(sqlx::Postgres -> sqlx::Postgres, sqlx::Sqlite)]
+#[async_trait]
+impl PendingInvocationRepo for DbPendingInvocationRepo<sqlx::Postgres> {
+    async fn create(&self, invocation: &PendingInvocationRecord) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO pending_invocations
+                (id, worker_id, data, created_at, expires_at)
+              VALUES
+                ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(invocation.id.clone())
+        .bind(invocation.worker_id.clone())
+        .bind(invocation.data.clone())
+        .bind(invocation.created_at)
+        .bind(invocation.expires_at)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[when(sqlx::Postgres -> get_oldest)]
+    async fn get_oldest_postgres(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<PendingInvocationRecord>, RepoError> {
+        sqlx::query_as::<_, PendingInvocationRecord>(
+            r#"
+              SELECT id, worker_id, data, created_at::timestamptz, expires_at::timestamptz
+              FROM pending_invocations
+              ORDER BY created_at
+              LIMIT $1
+            "#,
+        )
+        .bind(limit as i64)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get_oldest)]
+    async fn get_oldest_sqlite(&self, limit: u32) -> Result<Vec<PendingInvocationRecord>, RepoError> {
+        sqlx::query_as::<_, PendingInvocationRecord>(
+            r#"
+              SELECT id, worker_id, data, created_at, expires_at
+              FROM pending_invocations
+              ORDER BY created_at
+              LIMIT $1
+            "#,
+        )
+        .bind(limit as i64)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn delete(&self, id: &str) -> Result<bool, RepoError> {
+        let result = sqlx::query("DELETE FROM pending_invocations WHERE id = $1")
+            .bind(id)
+            .execute(self.db_pool.deref())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_expired(&self, now: chrono::DateTime<chrono::Utc>) -> Result<u64, RepoError> {
+        let result = sqlx::query("DELETE FROM pending_invocations WHERE expires_at < $1")
+            .bind(now)
+            .execute(self.db_pool.deref())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn count(&self) -> Result<u64, RepoError> {
+        let (count,): (i64,) = sqlx::query_as("SELECT count(*) FROM pending_invocations")
+            .fetch_one(self.db_pool.deref())
+            .await?;
+
+        Ok(count as u64)
+    }
+}
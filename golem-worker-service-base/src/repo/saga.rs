@@ -0,0 +1,252 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use conditional_trait_gen::{trait_gen, when};
+use golem_api_grpc::proto::golem::worker::SagaStatus;
+use golem_service_base::repo::RepoError;
+use sqlx::{Database, Pool};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// The progress of a saga executed by the saga coordinator
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct SagaRecord {
+    pub id: String,
+    /// The encoded `Saga` definition
+    pub data: Vec<u8>,
+    pub account_id: Option<String>,
+    /// A `SagaStatus` value
+    pub status: i32,
+    /// The step being executed, or compensated when the saga is compensating
+    pub step: i32,
+    pub failed_step: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[async_trait]
+pub trait SagaRepo {
+    async fn create(&self, saga: &SagaRecord) -> Result<(), RepoError>;
+
+    /// Updates the progress of the saga: its status, step, failure and update time
+    async fn update(&self, saga: &SagaRecord) -> Result<(), RepoError>;
+
+    async fn get(&self, id: &str) -> Result<Option<SagaRecord>, RepoError>;
+
+    /// Gets the sagas still running or compensating which were not updated since `updated_before`,
+    /// at most `limit` of them
+    async fn get_stale(
+        &self,
+        updated_before: chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<SagaRecord>, RepoError>;
+
+    /// Sets the update time of the saga if it is still running or compensating and it was not
+    /// updated since `updated_before`, returning whether it did, so only one instance takes over
+    /// a stale saga
+    async fn claim(
+        &self,
+        id: &str,
+        updated_before: chrono::DateTime<chrono::Utc>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, RepoError>;
+
+    /// Sets the update time of the saga if it is still running or compensating, so it is not
+    /// considered stale while it is being executed
+    async fn heartbeat(
+        &self,
+        id: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), RepoError>;
+}
+
+pub struct DbSagaRepo<DB: Database> {
+    db_pool: Arc<Pool<DB>>,
+}
+
+impl<DB: Database> DbSagaRepo<DB> {
+    pub fn new(db_pool: Arc<Pool<DB>>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[trait_gen(sqlx::Postgres -> sqlx::Postgres, sqlx::Sqlite)]
+#[async_trait]
+impl SagaRepo for DbSagaRepo<sqlx::Postgres> {
+    async fn create(&self, saga: &SagaRecord) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO sagas
+                (id, data, account_id, status, step, failed_step, error, created_at, updated_at)
+              VALUES
+                ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(saga.id.clone())
+        .bind(saga.data.clone())
+        .bind(saga.account_id.clone())
+        .bind(saga.status)
+        .bind(saga.step)
+        .bind(saga.failed_step)
+        .bind(saga.error.clone())
+        .bind(saga.created_at)
+        .bind(saga.updated_at)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update(&self, saga: &SagaRecord) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              UPDATE sagas
+              SET status = $1, step = $2, failed_step = $3, error = $4, updated_at = $5
+              WHERE id = $6
+            "#,
+        )
+        .bind(saga.status)
+        .bind(saga.step)
+        .bind(saga.failed_step)
+        .bind(saga.error.clone())
+        .bind(saga.updated_at)
+        .bind(saga.id.clone())
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    #[when(sqlx::Postgres -> get)]
+    async fn get_postgres(&self, id: &str) -> Result<Option<SagaRecord>, RepoError> {
+        sqlx::query_as::<_, SagaRecord>(
+            r#"
+              SELECT id, data, account_id, status, step, failed_step, error,
+                created_at::timestamptz, updated_at::timestamptz
+              FROM sagas
+              WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get)]
+    async fn get_sqlite(&self, id: &str) -> Result<Option<SagaRecord>, RepoError> {
+        sqlx::query_as::<_, SagaRecord>(
+            r#"
+              SELECT id, data, account_id, status, step, failed_step, error, created_at, updated_at
+              FROM sagas
+              WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Postgres -> get_stale)]
+    async fn get_stale_postgres(
+        &self,
+        updated_before: chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<SagaRecord>, RepoError> {
+        sqlx::query_as::<_, SagaRecord>(
+            r#"
+              SELECT id, data, account_id, status, step, failed_step, error,
+                created_at::timestamptz, updated_at::timestamptz
+              FROM sagas
+              WHERE status IN ($1, $2) AND updated_at < $3
+              ORDER BY updated_at
+              LIMIT $4
+            "#,
+        )
+        .bind(SagaStatus::SagaRunning as i32)
+        .bind(SagaStatus::SagaCompensating as i32)
+        .bind(updated_before)
+        .bind(limit as i64)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get_stale)]
+    async fn get_stale_sqlite(
+        &self,
+        updated_before: chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<SagaRecord>, RepoError> {
+        sqlx::query_as::<_, SagaRecord>(
+            r#"
+              SELECT id, data, account_id, status, step, failed_step, error, created_at, updated_at
+              FROM sagas
+              WHERE status IN ($1, $2) AND updated_at < $3
+              ORDER BY updated_at
+              LIMIT $4
+            "#,
+        )
+        .bind(SagaStatus::SagaRunning as i32)
+        .bind(SagaStatus::SagaCompensating as i32)
+        .bind(updated_before)
+        .bind(limit as i64)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn claim(
+        &self,
+        id: &str,
+        updated_before: chrono::DateTime<chrono::Utc>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, RepoError> {
+        let result = sqlx::query(
+            r#"
+              UPDATE sagas
+              SET updated_at = $1
+              WHERE id = $2 AND status IN ($3, $4) AND updated_at < $5
+            "#,
+        )
+        .bind(now)
+        .bind(id)
+        .bind(SagaStatus::SagaRunning as i32)
+        .bind(SagaStatus::SagaCompensating as i32)
+        .bind(updated_before)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn heartbeat(
+        &self,
+        id: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), RepoError> {
+        sqlx::query("UPDATE sagas SET updated_at = $1 WHERE id = $2 AND status IN ($3, $4)")
+            .bind(now)
+            .bind(id)
+            .bind(SagaStatus::SagaRunning as i32)
+            .bind(SagaStatus::SagaCompensating as i32)
+            .execute(self.db_pool.deref())
+            .await?;
+
+        Ok(())
+    }
+}
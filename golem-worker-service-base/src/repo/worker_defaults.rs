@@ -0,0 +1,167 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use conditional_trait_gen::trait_gen;
+use golem_service_base::repo::RepoError;
+use sqlx::{Database, Pool};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Worker defaults of an account, or of one of its components
+#[derive(sqlx::FromRow, Debug, Clone)]
+pub struct WorkerDefaultsRecord {
+    pub account_id: String,
+    /// Empty for the defaults of the whole account
+    pub component_id: String,
+    /// The JSON encoded `WorkerDefaults`
+    pub defaults: String,
+}
+
+#[async_trait]
+pub trait WorkerDefaultsRepo {
+    /// Creates or replaces the defaults
+    async fn upsert(&self, record: &WorkerDefaultsRecord) -> Result<(), RepoError>;
+
+    async fn get(
+        &self,
+        account_id: &str,
+        component_id: &str,
+    ) -> Result<Option<WorkerDefaultsRecord>, RepoError>;
+
+    /// Gets the defaults of the account and of the given component, if they exist
+    async fn get_for_component(
+        &self,
+        account_id: &str,
+        component_id: &str,
+    ) -> Result<Vec<WorkerDefaultsRecord>, RepoError>;
+
+    async fn delete(&self, account_id: &str, component_id: &str) -> Result<bool, RepoError>;
+
+    /// Increments the counter of the sequentially named workers of the component, returning
+    /// its new value. The first value is 1.
+    async fn next_name_sequence(
+        &self,
+        account_id: &str,
+        component_id: &str,
+    ) -> Result<u64, RepoError>;
+}
+
+pub struct DbWorkerDefaultsRepo<DB: Database> {
+    db_pool: Arc<Pool<DB>>,
+}
+
+impl<DB: Database> DbWorkerDefaultsRepo<DB> {
+    pub fn new(db_pool: Arc<Pool<DB>>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[trait_gen(sqlx::Postgres -> sqlx::Postgres, sqlx::Sqlite)]
+#[async_trait]
+impl WorkerDefaultsRepo for DbWorkerDefaultsRepo<sqlx::Postgres> {
+    async fn upsert(&self, record: &WorkerDefaultsRecord) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO worker_defaults
+                (account_id, component_id, defaults, updated_at)
+              VALUES
+                ($1, $2, $3, $4)
+              ON CONFLICT (account_id, component_id) DO UPDATE
+              SET defaults = $3, updated_at = $4
+            "#,
+        )
+        .bind(record.account_id.clone())
+        .bind(record.component_id.clone())
+        .bind(record.defaults.clone())
+        .bind(chrono::Utc::now())
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(
+        &self,
+        account_id: &str,
+        component_id: &str,
+    ) -> Result<Option<WorkerDefaultsRecord>, RepoError> {
+        sqlx::query_as::<_, WorkerDefaultsRecord>(
+            r#"
+              SELECT account_id, component_id, defaults
+              FROM worker_defaults
+              WHERE account_id = $1 AND component_id = $2
+            "#,
+        )
+        .bind(account_id)
+        .bind(component_id)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn get_for_component(
+        &self,
+        account_id: &str,
+        component_id: &str,
+    ) -> Result<Vec<WorkerDefaultsRecord>, RepoError> {
+        sqlx::query_as::<_, WorkerDefaultsRecord>(
+            r#"
+              SELECT account_id, component_id, defaults
+              FROM worker_defaults
+              WHERE account_id = $1 AND (component_id = '' OR component_id = $2)
+            "#,
+        )
+        .bind(account_id)
+        .bind(component_id)
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    async fn delete(&self, account_id: &str, component_id: &str) -> Result<bool, RepoError> {
+        let result =
+            sqlx::query("DELETE FROM worker_defaults WHERE account_id = $1 AND component_id = $2")
+                .bind(account_id)
+                .bind(component_id)
+                .execute(self.db_pool.deref())
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn next_name_sequence(
+        &self,
+        account_id: &str,
+        component_id: &str,
+    ) -> Result<u64, RepoError> {
+        let value: i64 = sqlx::query_scalar(
+            r#"
+              INSERT INTO worker_name_sequences
+                (account_id, component_id, last_value)
+              VALUES
+                ($1, $2, 1)
+              ON CONFLICT (account_id, component_id) DO UPDATE
+              SET last_value = worker_name_sequences.last_value + 1
+              RETURNING last_value
+            "#,
+        )
+        .bind(account_id)
+        .bind(component_id)
+        .fetch_one(self.db_pool.deref())
+        .await?;
+
+        Ok(value as u64)
+    }
+}
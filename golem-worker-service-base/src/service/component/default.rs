@@ -1,6 +1,5 @@
 use async_trait::async_trait;
 use http::Uri;
-use tonic::codec::CompressionEncoding;
 use tonic::transport::Channel;
 
 use golem_api_grpc::proto::golem::component::v1::component_service_client::ComponentServiceClient;
@@ -9,9 +8,10 @@ use golem_api_grpc::proto::golem::component::v1::{
     GetVersionedComponentRequest,
 };
 use golem_common::client::{GrpcClient, GrpcClientConfig};
-use golem_common::config::RetryConfig;
+use golem_common::config::{GrpcCompression, RetryConfig};
 use golem_common::model::ComponentId;
 use golem_common::retries::with_retries;
+use golem_common::with_grpc_compression;
 use golem_service_base::model::Component;
 
 use crate::service::component::ComponentServiceError;
@@ -43,13 +43,11 @@ pub struct RemoteComponentService {
 }
 
 impl RemoteComponentService {
-    pub fn new(uri: Uri, retry_config: RetryConfig) -> Self {
+    pub fn new(uri: Uri, retry_config: RetryConfig, grpc_compression: GrpcCompression) -> Self {
         Self {
             client: GrpcClient::new(
-                |channel| {
-                    ComponentServiceClient::new(channel)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
+                move |channel| {
+                    with_grpc_compression!(ComponentServiceClient::new(channel), grpc_compression)
                 },
                 uri.as_http_02(),
                 GrpcClientConfig {
@@ -0,0 +1,282 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use golem_common::model::maintenance::ComponentMaintenance;
+use golem_common::model::{AccountId, ComponentId, Timestamp};
+use golem_common::SafeDisplay;
+use golem_service_base::repo::RepoError;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tracing::error;
+
+use crate::repo::maintenance::{ComponentMaintenanceRecord, ComponentMaintenanceRepo};
+
+/// Configuration of the maintenance mode of components.
+///
+/// The components in maintenance are checked on every invocation, so each worker service
+/// instance keeps them in memory and reloads them every `refresh_interval`. Maintenance
+/// started or ended through another instance takes effect within this interval.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MaintenanceConfig {
+    #[serde(with = "humantime_serde")]
+    pub refresh_interval: Duration,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MaintenanceError {
+    #[error("Component is not in maintenance")]
+    NotFound,
+    #[error("Internal repository error: {0}")]
+    InternalRepoError(RepoError),
+}
+
+impl From<RepoError> for MaintenanceError {
+    fn from(error: RepoError) -> Self {
+        MaintenanceError::InternalRepoError(error)
+    }
+}
+
+impl SafeDisplay for MaintenanceError {
+    fn to_safe_string(&self) -> String {
+        match self {
+            MaintenanceError::NotFound => self.to_string(),
+            MaintenanceError::InternalRepoError(inner) => inner.to_safe_string(),
+        }
+    }
+}
+
+/// Manages the maintenance mode of components, used for planned downtimes. While a component
+/// is in maintenance its workers are not invoked: new invocations are rejected with a
+/// retryable error and the queued ones are held back until the maintenance ends. Reading the
+/// workers' metadata and administrative operations remain available.
+#[async_trait]
+pub trait MaintenanceService {
+    async fn get(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+    ) -> Result<ComponentMaintenance, MaintenanceError>;
+
+    /// Puts the component in maintenance, or updates the message of an ongoing maintenance
+    async fn start(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        message: Option<String>,
+    ) -> Result<ComponentMaintenance, MaintenanceError>;
+
+    async fn end(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+    ) -> Result<(), MaintenanceError>;
+
+    /// Gets the maintenance of the component if it is in maintenance. It does not access the
+    /// database, so it can be called on every invocation.
+    fn in_maintenance(&self, component_id: &ComponentId) -> Option<ComponentMaintenance>;
+}
+
+pub struct MaintenanceServiceDefault {
+    repo: Arc<dyn ComponentMaintenanceRepo + Sync + Send>,
+    snapshot: Arc<MaintenanceSnapshot>,
+    refresher: JoinHandle<()>,
+}
+
+impl MaintenanceServiceDefault {
+    pub fn new(
+        config: MaintenanceConfig,
+        repo: Arc<dyn ComponentMaintenanceRepo + Sync + Send>,
+    ) -> Self {
+        let snapshot = Arc::new(MaintenanceSnapshot::default());
+        let refresher = tokio::spawn(Self::refresh(config, repo.clone(), snapshot.clone()));
+        Self {
+            repo,
+            snapshot,
+            refresher,
+        }
+    }
+
+    async fn refresh(
+        config: MaintenanceConfig,
+        repo: Arc<dyn ComponentMaintenanceRepo + Sync + Send>,
+        snapshot: Arc<MaintenanceSnapshot>,
+    ) {
+        loop {
+            match repo.get_all().await {
+                Ok(records) => snapshot.replace(&records),
+                Err(err) => error!("Failed to load the components in maintenance: {err}"),
+            }
+            tokio::time::sleep(config.refresh_interval).await;
+        }
+    }
+}
+
+impl Drop for MaintenanceServiceDefault {
+    fn drop(&mut self) {
+        self.refresher.abort();
+    }
+}
+
+#[async_trait]
+impl MaintenanceService for MaintenanceServiceDefault {
+    async fn get(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+    ) -> Result<ComponentMaintenance, MaintenanceError> {
+        let record = self
+            .repo
+            .get(&account_id.value, &component_id.to_string())
+            .await?
+            .ok_or(MaintenanceError::NotFound)?;
+        Ok(to_maintenance(&record))
+    }
+
+    async fn start(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+        message: Option<String>,
+    ) -> Result<ComponentMaintenance, MaintenanceError> {
+        let record = ComponentMaintenanceRecord {
+            account_id: account_id.value.clone(),
+            component_id: component_id.to_string(),
+            message,
+            started_at: chrono::Utc::now(),
+        };
+        self.repo.upsert(&record).await?;
+
+        let maintenance = to_maintenance(&record);
+        self.snapshot
+            .insert(component_id.clone(), maintenance.clone());
+        Ok(maintenance)
+    }
+
+    async fn end(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+    ) -> Result<(), MaintenanceError> {
+        let deleted = self
+            .repo
+            .delete(&account_id.value, &component_id.to_string())
+            .await?;
+        self.snapshot.remove(component_id);
+        if deleted {
+            Ok(())
+        } else {
+            Err(MaintenanceError::NotFound)
+        }
+    }
+
+    fn in_maintenance(&self, component_id: &ComponentId) -> Option<ComponentMaintenance> {
+        self.snapshot.get(component_id)
+    }
+}
+
+/// The components in maintenance, as last loaded from the database
+#[derive(Default)]
+struct MaintenanceSnapshot {
+    components: RwLock<HashMap<ComponentId, ComponentMaintenance>>,
+}
+
+impl MaintenanceSnapshot {
+    fn get(&self, component_id: &ComponentId) -> Option<ComponentMaintenance> {
+        self.components.read().unwrap().get(component_id).cloned()
+    }
+
+    fn insert(&self, component_id: ComponentId, maintenance: ComponentMaintenance) {
+        self.components
+            .write()
+            .unwrap()
+            .insert(component_id, maintenance);
+    }
+
+    fn remove(&self, component_id: &ComponentId) {
+        self.components.write().unwrap().remove(component_id);
+    }
+
+    fn replace(&self, records: &[ComponentMaintenanceRecord]) {
+        let components = records
+            .iter()
+            .filter_map(|record| match ComponentId::from_str(&record.component_id) {
+                Ok(component_id) => Some((component_id, to_maintenance(record))),
+                Err(err) => {
+                    error!(
+                        component_id = record.component_id,
+                        "Ignoring the maintenance of an invalid component id: {err}"
+                    );
+                    None
+                }
+            })
+            .collect();
+        *self.components.write().unwrap() = components;
+    }
+}
+
+fn to_maintenance(record: &ComponentMaintenanceRecord) -> ComponentMaintenance {
+    ComponentMaintenance {
+        message: record.message.clone(),
+        started_at: Timestamp::from(record.started_at.timestamp_millis() as u64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::repo::maintenance::ComponentMaintenanceRecord;
+    use crate::service::maintenance::MaintenanceSnapshot;
+    use golem_common::model::ComponentId;
+
+    fn record(component_id: &str) -> ComponentMaintenanceRecord {
+        ComponentMaintenanceRecord {
+            account_id: "account".to_string(),
+            component_id: component_id.to_string(),
+            message: Some("Upgrading".to_string()),
+            started_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn refresh_replaces_the_components_in_maintenance() {
+        let first = ComponentId::new_v4();
+        let second = ComponentId::new_v4();
+        let snapshot = MaintenanceSnapshot::default();
+
+        snapshot.replace(&[record(&first.to_string()), record("invalid")]);
+        assert_eq!(
+            snapshot.get(&first).and_then(|m| m.message),
+            Some("Upgrading".to_string())
+        );
+
+        snapshot.replace(&[record(&second.to_string())]);
+        assert!(snapshot.get(&first).is_none());
+        assert!(snapshot.get(&second).is_some());
+    }
+}
@@ -17,7 +17,10 @@ pub mod api_definition_lookup;
 pub mod api_definition_validator;
 pub mod api_deployment;
 pub mod component;
+pub mod maintenance;
+pub mod saga;
 pub mod worker;
+pub mod worker_defaults;
 
 pub mod http;
 
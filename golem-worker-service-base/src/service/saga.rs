@@ -0,0 +1,1033 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use golem_api_grpc::proto::golem::worker::{
+    InvokeParameters, InvokeResult, SagaOutcome, SagaStatus,
+};
+use golem_common::model::{AccountId, IdempotencyKey, TargetWorkerId, WorkerId};
+use golem_common::retriable_error::IsRetriableError;
+use golem_common::SafeDisplay;
+use golem_service_base::model::GolemError;
+use golem_wasm_rpc::protobuf::Val as ProtoVal;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::repo::saga::{SagaRecord, SagaRepo};
+use crate::service::component::ComponentServiceError;
+use crate::service::worker::{WorkerRequestMetadata, WorkerService, WorkerServiceError};
+
+/// Configuration of the saga coordinator.
+///
+/// The instance executing a saga refreshes its update time every `heartbeat_interval`. Sagas
+/// whose update time is older than `recovery_after` are considered abandoned by the worker
+/// service instance executing them (for example because it was restarted, or it stopped because
+/// of a transient error), and are taken over by another instance, continuing from their last
+/// persisted step. `heartbeat_interval` must be well below `recovery_after`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SagaConfig {
+    #[serde(with = "humantime_serde")]
+    pub recovery_interval: Duration,
+    #[serde(with = "humantime_serde")]
+    pub recovery_after: Duration,
+    pub recovery_batch_size: u32,
+    #[serde(with = "humantime_serde")]
+    pub heartbeat_interval: Duration,
+}
+
+impl Default for SagaConfig {
+    fn default() -> Self {
+        Self {
+            recovery_interval: Duration::from_secs(30),
+            recovery_after: Duration::from_secs(5 * 60),
+            recovery_batch_size: 10,
+            heartbeat_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// A sequence of invocations across multiple workers. If a step fails, the compensations of
+/// the already completed steps are invoked in reverse order.
+#[derive(Debug, Clone)]
+pub struct Saga {
+    pub steps: Vec<SagaStep>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SagaStep {
+    pub worker_id: WorkerId,
+    pub function: String,
+    pub params: Vec<ProtoVal>,
+    pub compensation: Option<SagaCompensation>,
+}
+
+/// Undoes a completed step, invoked on the same worker as the step
+#[derive(Debug, Clone)]
+pub struct SagaCompensation {
+    pub function: String,
+    pub params: Vec<ProtoVal>,
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::Saga> for Saga {
+    type Error = String;
+
+    fn try_from(value: golem_api_grpc::proto::golem::worker::Saga) -> Result<Self, Self::Error> {
+        if value.steps.is_empty() {
+            return Err("Saga has no steps".to_string());
+        }
+        let steps = value
+            .steps
+            .into_iter()
+            .enumerate()
+            .map(|(index, step)| {
+                let worker_id = step
+                    .worker_id
+                    .ok_or_else(|| format!("Missing worker id in step {index}"))?
+                    .try_into()
+                    .map_err(|err| format!("Invalid worker id in step {index}: {err}"))?;
+                Ok(SagaStep {
+                    worker_id,
+                    function: step.function,
+                    params: step.invoke_parameters.unwrap_or_default().params,
+                    compensation: step.compensation.map(|compensation| SagaCompensation {
+                        function: compensation.function,
+                        params: compensation.invoke_parameters.unwrap_or_default().params,
+                    }),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { steps })
+    }
+}
+
+impl From<Saga> for golem_api_grpc::proto::golem::worker::Saga {
+    fn from(value: Saga) -> Self {
+        Self {
+            steps: value
+                .steps
+                .into_iter()
+                .map(|step| golem_api_grpc::proto::golem::worker::SagaStep {
+                    worker_id: Some(step.worker_id.into()),
+                    function: step.function,
+                    invoke_parameters: Some(InvokeParameters {
+                        params: step.params,
+                        blob_params: vec![],
+                    }),
+                    compensation: step.compensation.map(|compensation| {
+                        golem_api_grpc::proto::golem::worker::SagaCompensation {
+                            function: compensation.function,
+                            invoke_parameters: Some(InvokeParameters {
+                                params: compensation.params,
+                                blob_params: vec![],
+                            }),
+                        }
+                    }),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Whether the failed invocation of a step or compensation may succeed if it is retried later,
+/// in which case the saga is left to be resumed instead of being compensated
+fn is_transient(error: &WorkerServiceError) -> bool {
+    match error {
+        WorkerServiceError::ComponentInMaintenance { .. } | WorkerServiceError::Internal(_) => true,
+        WorkerServiceError::InternalCallError(error) => error.is_retriable(),
+        WorkerServiceError::Component(error) => matches!(
+            error,
+            ComponentServiceError::Internal(_)
+                | ComponentServiceError::FailedGrpcStatus(_)
+                | ComponentServiceError::FailedTransport(_)
+        ),
+        WorkerServiceError::Golem(error) => matches!(
+            error,
+            GolemError::InvalidShardId(_)
+                | GolemError::ShardingNotReady(_)
+                | GolemError::ComponentDownloadFailed(_)
+                | GolemError::GetLatestVersionOfComponentFailed(_)
+                | GolemError::InvocationTimedOut(_)
+        ),
+        _ => false,
+    }
+}
+
+/// Invokes the steps and compensations of sagas
+#[async_trait]
+trait SagaStepInvoker {
+    async fn invoke(
+        &self,
+        worker_id: &WorkerId,
+        function: &str,
+        params: &[ProtoVal],
+        idempotency_key: IdempotencyKey,
+        metadata: &WorkerRequestMetadata,
+    ) -> Result<InvokeResult, WorkerServiceError>;
+}
+
+struct WorkerServiceStepInvoker<AuthCtx> {
+    worker_service: Arc<dyn WorkerService<AuthCtx> + Send + Sync>,
+}
+
+#[async_trait]
+impl<AuthCtx: Send + Sync + 'static> SagaStepInvoker for WorkerServiceStepInvoker<AuthCtx> {
+    async fn invoke(
+        &self,
+        worker_id: &WorkerId,
+        function: &str,
+        params: &[ProtoVal],
+        idempotency_key: IdempotencyKey,
+        metadata: &WorkerRequestMetadata,
+    ) -> Result<InvokeResult, WorkerServiceError> {
+        self.worker_service
+            .invoke_and_await(
+                &TargetWorkerId::from(worker_id.clone()),
+                Some(idempotency_key),
+                function.to_string(),
+                params.to_vec(),
+                vec![],
+                None,
+                metadata.clone(),
+            )
+            .await
+    }
+}
+
+/// Executes sagas, persisting their progress so they are finished even if the worker service
+/// is restarted while executing them.
+///
+/// The steps and compensations are invoked with idempotency keys derived from the saga id
+/// (`saga-<id>-<step>` and `saga-<id>-<step>-compensation`), so they are not executed twice
+/// when a saga is resumed, and they are identifiable in the oplogs of the participating workers.
+///
+/// A step or compensation failing with a transient error does not start the compensation: the
+/// saga is returned as still running (or compensating) and it is resumed by the recovery.
+pub struct SagaCoordinator {
+    execution: Arc<SagaExecution>,
+    recovery: JoinHandle<()>,
+}
+
+impl SagaCoordinator {
+    pub fn new<AuthCtx: Send + Sync + 'static>(
+        config: SagaConfig,
+        repo: Arc<dyn SagaRepo + Send + Sync>,
+        worker_service: Arc<dyn WorkerService<AuthCtx> + Send + Sync>,
+    ) -> Self {
+        Self::with_invoker(
+            config,
+            repo,
+            Arc::new(WorkerServiceStepInvoker { worker_service }),
+        )
+    }
+
+    fn with_invoker(
+        config: SagaConfig,
+        repo: Arc<dyn SagaRepo + Send + Sync>,
+        invoker: Arc<dyn SagaStepInvoker + Send + Sync>,
+    ) -> Self {
+        let execution = Arc::new(SagaExecution {
+            config,
+            repo,
+            invoker,
+        });
+        let recovery = tokio::spawn(execution.clone().recover());
+        Self {
+            execution,
+            recovery,
+        }
+    }
+
+    /// Executes the saga and waits until it is completed or compensated
+    pub async fn execute(
+        &self,
+        saga: Saga,
+        metadata: WorkerRequestMetadata,
+    ) -> Result<SagaOutcome, WorkerServiceError> {
+        let now = chrono::Utc::now();
+        let mut record = SagaRecord {
+            id: Uuid::new_v4().to_string(),
+            data: golem_api_grpc::proto::golem::worker::Saga::from(saga.clone()).encode_to_vec(),
+            account_id: metadata.account_id.as_ref().map(|id| id.value.clone()),
+            status: SagaStatus::SagaRunning as i32,
+            step: 0,
+            failed_step: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.execution
+            .repo
+            .create(&record)
+            .await
+            .map_err(|err| WorkerServiceError::Internal(format!("Failed to store saga: {err}")))?;
+
+        info!(
+            saga_id = record.id,
+            steps = saga.steps.len(),
+            "Executing saga"
+        );
+        self.execution.run(&saga, &mut record, metadata).await
+    }
+}
+
+impl Drop for SagaCoordinator {
+    fn drop(&mut self) {
+        self.recovery.abort();
+    }
+}
+
+/// Stops the heartbeat of a saga when its execution ends
+struct Heartbeat(JoinHandle<()>);
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+struct SagaExecution {
+    config: SagaConfig,
+    repo: Arc<dyn SagaRepo + Send + Sync>,
+    invoker: Arc<dyn SagaStepInvoker + Send + Sync>,
+}
+
+impl SagaExecution {
+    /// Continues the saga from the step stored in `record`, persisting the progress after
+    /// each step. Stops without changing the status of the saga when a step or compensation
+    /// fails with a transient error.
+    async fn run(
+        &self,
+        saga: &Saga,
+        record: &mut SagaRecord,
+        metadata: WorkerRequestMetadata,
+    ) -> Result<SagaOutcome, WorkerServiceError> {
+        let _heartbeat = self.heartbeat(&record.id);
+        let mut results = Vec::new();
+        // Set when a step or compensation failed with a transient error
+        let mut interrupted = None;
+
+        if record.status == SagaStatus::SagaRunning as i32 {
+            while let Some(step) = saga.steps.get(record.step as usize) {
+                let idempotency_key = format!("saga-{}-{}", record.id, record.step);
+                match self
+                    .invoke(
+                        step,
+                        &step.function,
+                        &step.params,
+                        idempotency_key,
+                        &metadata,
+                    )
+                    .await
+                {
+                    Ok(result) => {
+                        results.push(result);
+                        record.step += 1;
+                    }
+                    Err(err) if is_transient(&err) => {
+                        warn!(
+                            saga_id = record.id,
+                            step = record.step,
+                            "Saga step failed with a transient error, the saga is resumed later: {err}"
+                        );
+                        interrupted = Some(format!(
+                            "step {} is retried later: {}",
+                            record.step,
+                            err.to_safe_string()
+                        ));
+                        break;
+                    }
+                    Err(err) => {
+                        warn!(
+                            saga_id = record.id,
+                            step = record.step,
+                            "Saga step failed, compensating: {err}"
+                        );
+                        record.status = SagaStatus::SagaCompensating as i32;
+                        record.failed_step = Some(record.step);
+                        record.error = Some(err.to_safe_string());
+                        // The failed step itself is not compensated
+                        record.step -= 1;
+                    }
+                }
+                self.save(record).await?;
+                if record.status != SagaStatus::SagaRunning as i32 {
+                    break;
+                }
+            }
+            if interrupted.is_none() && record.status == SagaStatus::SagaRunning as i32 {
+                record.status = SagaStatus::SagaCompleted as i32;
+                self.save(record).await?;
+            }
+        }
+
+        if interrupted.is_none() && record.status == SagaStatus::SagaCompensating as i32 {
+            while record.step >= 0 {
+                let step = &saga.steps[record.step as usize];
+                if let Some(compensation) = &step.compensation {
+                    let idempotency_key =
+                        format!("saga-{}-{}-compensation", record.id, record.step);
+                    match self
+                        .invoke(
+                            step,
+                            &compensation.function,
+                            &compensation.params,
+                            idempotency_key,
+                            &metadata,
+                        )
+                        .await
+                    {
+                        Ok(_) => {}
+                        Err(err) if is_transient(&err) => {
+                            warn!(
+                                saga_id = record.id,
+                                step = record.step,
+                                "Saga compensation failed with a transient error, the saga is resumed later: {err}"
+                            );
+                            interrupted = Some(format!(
+                                "compensation of step {} is retried later: {}",
+                                record.step,
+                                err.to_safe_string()
+                            ));
+                            break;
+                        }
+                        Err(err) => {
+                            error!(
+                                saga_id = record.id,
+                                step = record.step,
+                                "Saga compensation failed: {err}"
+                            );
+                            record.status = SagaStatus::SagaCompensationFailed as i32;
+                            record.error = Some(format!(
+                                "{}; compensation of step {} failed: {}",
+                                record.error.clone().unwrap_or_default(),
+                                record.step,
+                                err.to_safe_string()
+                            ));
+                            self.save(record).await?;
+                            break;
+                        }
+                    }
+                }
+                record.step -= 1;
+                self.save(record).await?;
+            }
+            if interrupted.is_none() && record.status == SagaStatus::SagaCompensating as i32 {
+                record.status = SagaStatus::SagaCompensated as i32;
+                self.save(record).await?;
+            }
+        }
+
+        if interrupted.is_some() {
+            info!(
+                saga_id = record.id,
+                status = record.status,
+                "Saga interrupted"
+            );
+        } else {
+            info!(saga_id = record.id, status = record.status, "Saga finished");
+        }
+        let error = match (record.error.clone(), interrupted) {
+            (error, None) => error,
+            (None, Some(interrupted)) => Some(interrupted),
+            (Some(error), Some(interrupted)) => Some(format!("{error}; {interrupted}")),
+        };
+        Ok(SagaOutcome {
+            saga_id: record.id.clone(),
+            status: record.status,
+            results,
+            failed_step: record.failed_step.map(|step| step as u32),
+            error,
+        })
+    }
+
+    async fn invoke(
+        &self,
+        step: &SagaStep,
+        function: &str,
+        params: &[ProtoVal],
+        idempotency_key: String,
+        metadata: &WorkerRequestMetadata,
+    ) -> Result<InvokeResult, WorkerServiceError> {
+        self.invoker
+            .invoke(
+                &step.worker_id,
+                function,
+                params,
+                IdempotencyKey::new(idempotency_key),
+                metadata,
+            )
+            .await
+    }
+
+    /// Refreshes the update time of the saga until the returned guard is dropped, so other
+    /// instances do not take it over while it is being executed
+    fn heartbeat(&self, id: &str) -> Heartbeat {
+        let repo = self.repo.clone();
+        let interval = self.config.heartbeat_interval;
+        let id = id.to_string();
+        Heartbeat(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(err) = repo.heartbeat(&id, chrono::Utc::now()).await {
+                    warn!(saga_id = id, "Failed to refresh saga: {err}");
+                }
+            }
+        }))
+    }
+
+    async fn save(&self, record: &mut SagaRecord) -> Result<(), WorkerServiceError> {
+        record.updated_at = chrono::Utc::now();
+        self.repo.update(record).await.map_err(|err| {
+            WorkerServiceError::Internal(format!("Failed to store saga progress: {err}"))
+        })
+    }
+
+    async fn recover(self: Arc<Self>) {
+        loop {
+            tokio::time::sleep(self.config.recovery_interval).await;
+            self.resume_stale().await;
+        }
+    }
+
+    /// Takes over the sagas abandoned by other instances, or by a previous instance of this one
+    async fn resume_stale(self: &Arc<Self>) {
+        let updated_before = chrono::Utc::now() - self.config.recovery_after;
+        let stale = match self
+            .repo
+            .get_stale(updated_before, self.config.recovery_batch_size)
+            .await
+        {
+            Ok(stale) => stale,
+            Err(err) => {
+                error!("Failed to get stale sagas: {err}");
+                return;
+            }
+        };
+
+        for mut record in stale {
+            match self
+                .repo
+                .claim(&record.id, updated_before, chrono::Utc::now())
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(err) => {
+                    error!(saga_id = record.id, "Failed to claim stale saga: {err}");
+                    continue;
+                }
+            }
+
+            let saga =
+                match golem_api_grpc::proto::golem::worker::Saga::decode(record.data.as_slice())
+                    .map_err(|err| err.to_string())
+                    .and_then(Saga::try_from)
+                {
+                    Ok(saga) => saga,
+                    Err(err) => {
+                        error!(saga_id = record.id, "Failed to decode stale saga: {err}");
+                        record.status = SagaStatus::SagaCompensationFailed as i32;
+                        record.error = Some(format!("Failed to decode saga: {err}"));
+                        let _ = self.save(&mut record).await;
+                        continue;
+                    }
+                };
+            let metadata = WorkerRequestMetadata {
+                account_id: record.account_id.clone().map(|value| AccountId { value }),
+                limits: None,
+            };
+
+            info!(
+                saga_id = record.id,
+                step = record.step,
+                "Resuming stale saga"
+            );
+            let this = self.clone();
+            tokio::spawn(async move {
+                if let Err(err) = this.run(&saga, &mut record, metadata).await {
+                    error!(saga_id = record.id, "Failed to resume saga: {err}");
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use golem_api_grpc::proto::golem::worker::{
+        InvokeResult, Saga as GrpcSaga, SagaStatus, SagaStep as GrpcSagaStep,
+    };
+    use golem_common::model::{ComponentId, IdempotencyKey, WorkerId};
+    use golem_service_base::model::{
+        GolemError, GolemErrorInvalidRequest, GolemErrorShardingNotReady,
+    };
+    use golem_service_base::repo::RepoError;
+    use golem_wasm_rpc::protobuf::Val as ProtoVal;
+
+    use super::{
+        is_transient, Saga, SagaCompensation, SagaConfig, SagaCoordinator, SagaStep,
+        SagaStepInvoker,
+    };
+    use crate::repo::saga::{SagaRecord, SagaRepo};
+    use crate::service::worker::{WorkerRequestMetadata, WorkerServiceError};
+
+    #[derive(Default)]
+    struct InMemorySagaRepo {
+        sagas: Mutex<HashMap<String, SagaRecord>>,
+    }
+
+    impl InMemorySagaRepo {
+        fn is_active(saga: &SagaRecord) -> bool {
+            saga.status == SagaStatus::SagaRunning as i32
+                || saga.status == SagaStatus::SagaCompensating as i32
+        }
+
+        fn single(&self) -> SagaRecord {
+            let sagas = self.sagas.lock().unwrap();
+            assert_eq!(sagas.len(), 1);
+            sagas.values().next().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl SagaRepo for InMemorySagaRepo {
+        async fn create(&self, saga: &SagaRecord) -> Result<(), RepoError> {
+            self.sagas
+                .lock()
+                .unwrap()
+                .insert(saga.id.clone(), saga.clone());
+            Ok(())
+        }
+
+        async fn update(&self, saga: &SagaRecord) -> Result<(), RepoError> {
+            if let Some(stored) = self.sagas.lock().unwrap().get_mut(&saga.id) {
+                stored.status = saga.status;
+                stored.step = saga.step;
+                stored.failed_step = saga.failed_step;
+                stored.error = saga.error.clone();
+                stored.updated_at = saga.updated_at;
+            }
+            Ok(())
+        }
+
+        async fn get(&self, id: &str) -> Result<Option<SagaRecord>, RepoError> {
+            Ok(self.sagas.lock().unwrap().get(id).cloned())
+        }
+
+        async fn get_stale(
+            &self,
+            updated_before: chrono::DateTime<chrono::Utc>,
+            limit: u32,
+        ) -> Result<Vec<SagaRecord>, RepoError> {
+            Ok(self
+                .sagas
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|saga| Self::is_active(saga) && saga.updated_at < updated_before)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+
+        async fn claim(
+            &self,
+            id: &str,
+            updated_before: chrono::DateTime<chrono::Utc>,
+            now: chrono::DateTime<chrono::Utc>,
+        ) -> Result<bool, RepoError> {
+            match self.sagas.lock().unwrap().get_mut(id) {
+                Some(saga) if Self::is_active(saga) && saga.updated_at < updated_before => {
+                    saga.updated_at = now;
+                    Ok(true)
+                }
+                _ => Ok(false),
+            }
+        }
+
+        async fn heartbeat(
+            &self,
+            id: &str,
+            now: chrono::DateTime<chrono::Utc>,
+        ) -> Result<(), RepoError> {
+            if let Some(saga) = self.sagas.lock().unwrap().get_mut(id) {
+                if Self::is_active(saga) {
+                    saga.updated_at = now;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Records the invocations, failing the functions set up with `fail`
+    #[derive(Default)]
+    struct TestInvoker {
+        invocations: Mutex<Vec<(String, String)>>,
+        failures: Mutex<HashMap<String, fn() -> WorkerServiceError>>,
+        delay: Option<Duration>,
+    }
+
+    impl TestInvoker {
+        fn fail(&self, function: &str, error: fn() -> WorkerServiceError) {
+            self.failures
+                .lock()
+                .unwrap()
+                .insert(function.to_string(), error);
+        }
+
+        fn succeed(&self, function: &str) {
+            self.failures.lock().unwrap().remove(function);
+        }
+
+        fn invoked_functions(&self) -> Vec<String> {
+            self.invocations
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(function, _)| function.clone())
+                .collect()
+        }
+
+        fn idempotency_keys(&self) -> Vec<String> {
+            self.invocations
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(_, key)| key.clone())
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl SagaStepInvoker for TestInvoker {
+        async fn invoke(
+            &self,
+            _worker_id: &WorkerId,
+            function: &str,
+            _params: &[ProtoVal],
+            idempotency_key: IdempotencyKey,
+            _metadata: &WorkerRequestMetadata,
+        ) -> Result<InvokeResult, WorkerServiceError> {
+            self.invocations
+                .lock()
+                .unwrap()
+                .push((function.to_string(), idempotency_key.value));
+            if let Some(delay) = self.delay {
+                tokio::time::sleep(delay).await;
+            }
+            let failure = self.failures.lock().unwrap().get(function).copied();
+            match failure {
+                Some(error) => Err(error()),
+                None => Ok(InvokeResult { result: vec![] }),
+            }
+        }
+    }
+
+    fn step(function: &str, compensation: Option<&str>) -> SagaStep {
+        SagaStep {
+            worker_id: WorkerId {
+                component_id: ComponentId::from_str("4d4b5ba4-3fa2-4b2c-9f34-4d0d6c1d0fd1")
+                    .unwrap(),
+                worker_name: "worker".to_string(),
+            },
+            function: function.to_string(),
+            params: vec![],
+            compensation: compensation.map(|function| SagaCompensation {
+                function: function.to_string(),
+                params: vec![],
+            }),
+        }
+    }
+
+    fn booking() -> Saga {
+        Saga {
+            steps: vec![
+                step("reserve", Some("release")),
+                step("charge", Some("refund")),
+                step("ship", None),
+            ],
+        }
+    }
+
+    fn metadata() -> WorkerRequestMetadata {
+        WorkerRequestMetadata {
+            account_id: None,
+            limits: None,
+        }
+    }
+
+    fn coordinator(
+        config: SagaConfig,
+        repo: Arc<InMemorySagaRepo>,
+        invoker: Arc<TestInvoker>,
+    ) -> SagaCoordinator {
+        SagaCoordinator::with_invoker(config, repo, invoker)
+    }
+
+    fn transient() -> WorkerServiceError {
+        WorkerServiceError::Golem(GolemError::ShardingNotReady(GolemErrorShardingNotReady {}))
+    }
+
+    fn permanent() -> WorkerServiceError {
+        WorkerServiceError::TypeChecker("invalid parameters".to_string())
+    }
+
+    #[test]
+    fn saga_proto_roundtrip() {
+        let saga = Saga {
+            steps: vec![SagaStep {
+                worker_id: WorkerId {
+                    component_id: ComponentId::from_str("4d4b5ba4-3fa2-4b2c-9f34-4d0d6c1d0fd1")
+                        .unwrap(),
+                    worker_name: "inventory".to_string(),
+                },
+                function: "reserve".to_string(),
+                params: vec![],
+                compensation: Some(SagaCompensation {
+                    function: "release".to_string(),
+                    params: vec![],
+                }),
+            }],
+        };
+
+        let decoded = Saga::try_from(GrpcSaga::from(saga)).unwrap();
+
+        assert_eq!(decoded.steps.len(), 1);
+        assert_eq!(decoded.steps[0].worker_id.worker_name, "inventory");
+        assert_eq!(
+            decoded.steps[0].compensation.as_ref().unwrap().function,
+            "release"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_sagas() {
+        assert!(Saga::try_from(GrpcSaga { steps: vec![] }).is_err());
+        assert!(Saga::try_from(GrpcSaga {
+            steps: vec![GrpcSagaStep {
+                worker_id: None,
+                function: "reserve".to_string(),
+                invoke_parameters: None,
+                compensation: None,
+            }]
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn classifies_transient_errors() {
+        assert!(is_transient(&transient()));
+        assert!(is_transient(&WorkerServiceError::Internal(
+            "connection reset".to_string()
+        )));
+        assert!(is_transient(&WorkerServiceError::ComponentInMaintenance {
+            component_id: ComponentId::from_str("4d4b5ba4-3fa2-4b2c-9f34-4d0d6c1d0fd1").unwrap(),
+            message: None,
+        }));
+        assert!(!is_transient(&permanent()));
+        assert!(!is_transient(&WorkerServiceError::Golem(
+            GolemError::InvalidRequest(GolemErrorInvalidRequest {
+                details: "invalid".to_string()
+            })
+        )));
+    }
+
+    #[test]
+    async fn completes_all_steps() {
+        let repo = Arc::new(InMemorySagaRepo::default());
+        let invoker = Arc::new(TestInvoker::default());
+        let coordinator = coordinator(SagaConfig::default(), repo.clone(), invoker.clone());
+
+        let outcome = coordinator.execute(booking(), metadata()).await.unwrap();
+
+        assert_eq!(outcome.status, SagaStatus::SagaCompleted as i32);
+        assert_eq!(outcome.results.len(), 3);
+        assert_eq!(outcome.failed_step, None);
+        assert_eq!(
+            invoker.invoked_functions(),
+            vec!["reserve", "charge", "ship"]
+        );
+        assert_eq!(
+            invoker.idempotency_keys(),
+            (0..3)
+                .map(|step| format!("saga-{}-{step}", outcome.saga_id))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(repo.single().status, SagaStatus::SagaCompleted as i32);
+    }
+
+    #[test]
+    async fn compensates_completed_steps_on_permanent_failure() {
+        let repo = Arc::new(InMemorySagaRepo::default());
+        let invoker = Arc::new(TestInvoker::default());
+        invoker.fail("ship", permanent);
+        let coordinator = coordinator(SagaConfig::default(), repo.clone(), invoker.clone());
+
+        let outcome = coordinator.execute(booking(), metadata()).await.unwrap();
+
+        assert_eq!(outcome.status, SagaStatus::SagaCompensated as i32);
+        assert_eq!(outcome.failed_step, Some(2));
+        assert!(outcome.error.unwrap().contains("invalid parameters"));
+        assert_eq!(
+            invoker.invoked_functions(),
+            vec!["reserve", "charge", "ship", "refund", "release"]
+        );
+        let record = repo.single();
+        assert_eq!(record.status, SagaStatus::SagaCompensated as i32);
+        assert_eq!(record.step, -1);
+    }
+
+    #[test]
+    async fn marks_permanently_failed_compensations() {
+        let repo = Arc::new(InMemorySagaRepo::default());
+        let invoker = Arc::new(TestInvoker::default());
+        invoker.fail("ship", permanent);
+        invoker.fail("refund", permanent);
+        let coordinator = coordinator(SagaConfig::default(), repo.clone(), invoker.clone());
+
+        let outcome = coordinator.execute(booking(), metadata()).await.unwrap();
+
+        assert_eq!(outcome.status, SagaStatus::SagaCompensationFailed as i32);
+        assert_eq!(
+            invoker.invoked_functions(),
+            vec!["reserve", "charge", "ship", "refund"]
+        );
+        assert_eq!(repo.single().step, 1);
+    }
+
+    #[test]
+    async fn transient_failure_leaves_the_saga_to_the_recovery() {
+        let repo = Arc::new(InMemorySagaRepo::default());
+        let invoker = Arc::new(TestInvoker::default());
+        invoker.fail("charge", transient);
+        let coordinator = coordinator(
+            SagaConfig {
+                recovery_after: Duration::ZERO,
+                ..SagaConfig::default()
+            },
+            repo.clone(),
+            invoker.clone(),
+        );
+
+        let outcome = coordinator.execute(booking(), metadata()).await.unwrap();
+
+        assert_eq!(outcome.status, SagaStatus::SagaRunning as i32);
+        assert_eq!(outcome.failed_step, None);
+        assert!(outcome.error.is_some());
+        assert_eq!(invoker.invoked_functions(), vec!["reserve", "charge"]);
+        let record = repo.single();
+        assert_eq!(record.status, SagaStatus::SagaRunning as i32);
+        assert_eq!(record.step, 1);
+
+        invoker.succeed("charge");
+        coordinator.execution.resume_stale().await;
+        let record = wait_until_finished(&repo).await;
+
+        assert_eq!(record.status, SagaStatus::SagaCompleted as i32);
+        assert_eq!(
+            invoker.invoked_functions(),
+            vec!["reserve", "charge", "charge", "ship"]
+        );
+        let keys = invoker.idempotency_keys();
+        assert_eq!(keys[1], keys[2]);
+    }
+
+    #[test]
+    async fn transient_compensation_failure_is_resumed() {
+        let repo = Arc::new(InMemorySagaRepo::default());
+        let invoker = Arc::new(TestInvoker::default());
+        invoker.fail("ship", permanent);
+        invoker.fail("refund", transient);
+        let coordinator = coordinator(
+            SagaConfig {
+                recovery_after: Duration::ZERO,
+                ..SagaConfig::default()
+            },
+            repo.clone(),
+            invoker.clone(),
+        );
+
+        let outcome = coordinator.execute(booking(), metadata()).await.unwrap();
+
+        assert_eq!(outcome.status, SagaStatus::SagaCompensating as i32);
+        assert_eq!(outcome.failed_step, Some(2));
+
+        invoker.succeed("refund");
+        coordinator.execution.resume_stale().await;
+        let record = wait_until_finished(&repo).await;
+
+        assert_eq!(record.status, SagaStatus::SagaCompensated as i32);
+        assert_eq!(
+            invoker.invoked_functions(),
+            vec!["reserve", "charge", "ship", "refund", "refund", "release"]
+        );
+    }
+
+    #[test]
+    async fn running_sagas_are_not_taken_over() {
+        let repo = Arc::new(InMemorySagaRepo::default());
+        let invoker = Arc::new(TestInvoker {
+            delay: Some(Duration::from_millis(300)),
+            ..TestInvoker::default()
+        });
+        let coordinator = Arc::new(coordinator(
+            SagaConfig {
+                recovery_after: Duration::from_millis(100),
+                heartbeat_interval: Duration::from_millis(20),
+                ..SagaConfig::default()
+            },
+            repo.clone(),
+            invoker.clone(),
+        ));
+
+        let execution = {
+            let coordinator = coordinator.clone();
+            tokio::spawn(async move { coordinator.execute(booking(), metadata()).await })
+        };
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        coordinator.execution.resume_stale().await;
+
+        let outcome = execution.await.unwrap().unwrap();
+        assert_eq!(outcome.status, SagaStatus::SagaCompleted as i32);
+        assert_eq!(
+            invoker.invoked_functions(),
+            vec!["reserve", "charge", "ship"]
+        );
+    }
+
+    async fn wait_until_finished(repo: &InMemorySagaRepo) -> SagaRecord {
+        for _ in 0..100 {
+            let record = repo.single();
+            if !InMemorySagaRepo::is_active(&record) {
+                return record;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("Saga did not finish");
+    }
+}
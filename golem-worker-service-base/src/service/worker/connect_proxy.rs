@@ -19,7 +19,7 @@ use std::{
 
 use futures::{Sink, SinkExt, Stream, StreamExt};
 use golem_api_grpc::proto::golem::worker::LogEvent;
-use golem_common::model::{WorkerEvent, WorkerId};
+use golem_common::model::{ComponentId, NamedWorkerEvent, WorkerEvent, WorkerId};
 use poem::web::websocket::Message;
 use tonic::Status;
 use tracing::{error, info};
@@ -31,7 +31,7 @@ use tracing::{error, info};
 #[tracing::instrument(skip_all, fields(worker_id = worker_id.to_string()))]
 pub async fn proxy_worker_connection(
     worker_id: WorkerId,
-    mut worker_stream: impl Stream<Item = Result<LogEvent, Status>> + Unpin,
+    worker_stream: impl Stream<Item = Result<LogEvent, Status>> + Unpin,
     websocket_sender: impl Sink<Message, Error = IoError> + Unpin,
     websocket_receiver: impl Stream<Item = IoResult<Message>> + Unpin,
     keep_alive_interval: Duration,
@@ -39,6 +39,46 @@ pub async fn proxy_worker_connection(
 ) -> Result<(), ConnectProxyError> {
     info!("Proxying worker connection");
 
+    proxy_connection(
+        worker_stream,
+        websocket_sender,
+        websocket_receiver,
+        keep_alive_interval,
+        max_pong_timeout,
+    )
+    .await
+}
+
+/// Proxies a connection to multiple workers of a component, sending each event to the websocket
+/// together with the name of the worker it belongs to. Works the same way as `proxy_worker_connection`.
+#[tracing::instrument(skip_all, fields(component_id = component_id.to_string()))]
+pub async fn proxy_multi_worker_connection(
+    component_id: ComponentId,
+    worker_stream: impl Stream<Item = Result<(WorkerId, LogEvent), Status>> + Unpin,
+    websocket_sender: impl Sink<Message, Error = IoError> + Unpin,
+    websocket_receiver: impl Stream<Item = IoResult<Message>> + Unpin,
+    keep_alive_interval: Duration,
+    max_pong_timeout: Duration,
+) -> Result<(), ConnectProxyError> {
+    info!("Proxying multi-worker connection");
+
+    proxy_connection(
+        worker_stream,
+        websocket_sender,
+        websocket_receiver,
+        keep_alive_interval,
+        max_pong_timeout,
+    )
+    .await
+}
+
+async fn proxy_connection<M: IntoConnectMessage>(
+    mut worker_stream: impl Stream<Item = Result<M, Status>> + Unpin,
+    websocket_sender: impl Sink<Message, Error = IoError> + Unpin,
+    websocket_receiver: impl Stream<Item = IoResult<Message>> + Unpin,
+    keep_alive_interval: Duration,
+    max_pong_timeout: Duration,
+) -> Result<(), ConnectProxyError> {
     let mut websocket = keep_alive::WebSocketKeepAlive::from_sink_and_stream(
         websocket_receiver,
         websocket_sender,
@@ -102,19 +142,41 @@ pub async fn proxy_worker_connection(
     result
 }
 
-async fn forward_worker_message<E>(
-    message: Result<LogEvent, tonic::Status>,
+async fn forward_worker_message<M: IntoConnectMessage, E>(
+    message: Result<M, tonic::Status>,
     socket: &mut (impl Sink<Message, Error = E> + Unpin),
 ) -> Result<(), ConnectProxyError>
 where
     ConnectProxyError: From<E>,
 {
-    let message: WorkerEvent = message?.try_into().map_err(ConnectProxyError::Proto)?;
-    let msg_json = serde_json::to_string(&message)?;
+    let msg_json = message?.into_connect_message()?;
     socket.send(Message::Text(msg_json)).await?;
     Ok(())
 }
 
+/// The events of the worker streams, as the JSON messages sent to the websocket
+trait IntoConnectMessage {
+    fn into_connect_message(self) -> Result<String, ConnectProxyError>;
+}
+
+impl IntoConnectMessage for LogEvent {
+    fn into_connect_message(self) -> Result<String, ConnectProxyError> {
+        let message: WorkerEvent = self.try_into().map_err(ConnectProxyError::Proto)?;
+        Ok(serde_json::to_string(&message)?)
+    }
+}
+
+impl IntoConnectMessage for (WorkerId, LogEvent) {
+    fn into_connect_message(self) -> Result<String, ConnectProxyError> {
+        let (worker_id, event) = self;
+        let message = NamedWorkerEvent {
+            worker_name: worker_id.worker_name,
+            event: event.try_into().map_err(ConnectProxyError::Proto)?,
+        };
+        Ok(serde_json::to_string(&message)?)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ConnectProxyError {
     #[error(transparent)]
@@ -17,6 +17,7 @@ use std::{
     task::{Context, Poll},
 };
 
+use futures::stream::{select_all, BoxStream, SelectAll};
 use futures::{Stream, StreamExt};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
@@ -27,6 +28,7 @@ use golem_api_grpc::proto::golem::worker::LogEvent;
 use golem_common::metrics::api::{
     record_closed_grpc_api_active_stream, record_new_grpc_api_active_stream,
 };
+use golem_common::model::WorkerId;
 
 pub struct ConnectWorkerStream {
     receiver: mpsc::Receiver<Result<LogEvent, Status>>,
@@ -94,3 +96,37 @@ impl Drop for ConnectWorkerStream {
         self.cancel.cancel();
     }
 }
+
+/// The merged events of the connections to multiple workers, each tagged with its worker's id.
+/// The stream ends when all the connected workers' streams ended.
+pub struct MultiConnectWorkerStream {
+    streams: SelectAll<BoxStream<'static, Result<(WorkerId, LogEvent), Status>>>,
+}
+
+impl MultiConnectWorkerStream {
+    pub fn new(streams: Vec<(WorkerId, ConnectWorkerStream)>) -> Self {
+        Self {
+            streams: select_all(streams.into_iter().map(|(worker_id, stream)| {
+                stream
+                    .map(move |event| event.map(|event| (worker_id.clone(), event)))
+                    .boxed()
+            })),
+        }
+    }
+
+    /// The number of workers whose events are still being streamed
+    pub fn worker_count(&self) -> usize {
+        self.streams.len()
+    }
+}
+
+impl Stream for MultiConnectWorkerStream {
+    type Item = Result<(WorkerId, LogEvent), Status>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<(WorkerId, LogEvent), Status>>> {
+        self.streams.poll_next_unpin(cx)
+    }
+}
@@ -15,45 +15,185 @@
 use std::{collections::HashMap, sync::Arc};
 use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
 use golem_wasm_ast::analysis::AnalysedFunctionResult;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use golem_wasm_rpc::protobuf::TypeAnnotatedValue as ProtoTypeAnnotatedValue;
 use golem_wasm_rpc::protobuf::Val as ProtoVal;
 use nom::combinator::into;
 use poem_openapi::payload::{Binary, Json, PlainText};
+use prost::Message;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tonic::transport::Channel;
 use tonic::Code;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+use uuid::Uuid;
 
+use golem_api_grpc::proto::golem::worker::v1::{worker_execution_error, WorkerExecutionError};
 use golem_api_grpc::proto::golem::worker::UpdateMode;
-use golem_api_grpc::proto::golem::worker::{InvocationContext, InvokeResult};
+use golem_api_grpc::proto::golem::worker::{BlobParameter, InvocationContext, InvokeResult};
 use golem_api_grpc::proto::golem::workerexecutor;
 use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
-use golem_api_grpc::proto::golem::workerexecutor::v1::{CompletePromiseRequest, ConnectWorkerRequest, CreateWorkerRequest, GetFilesRequest, GetFilesResponse, GetFilesSuccessResponse, InterruptWorkerRequest, InvokeAndAwaitWorkerRequest, ResumeWorkerRequest, UpdateWorkerRequest};
+use golem_api_grpc::proto::golem::workerexecutor::v1::{AbortRunningInvocationRequest, CancelInvocationRequest, CompletePromiseRequest, ConnectWorkerRequest, CreateWorkerRequest, GetFilesRequest, GetFilesResponse, GetFilesSuccessResponse, GetWorkerProfileRequest, InterruptWorkerRequest, InvokeAndAwaitWorkerRequest, ResumeWorkerRequest, ScheduleInvocationRequest, SetWorkerProfilingRequest, SetWorkerShadowRequest, UpdateWorkerRequest};
 use golem_common::client::MultiTargetGrpcClient;
 use golem_common::config::RetryConfig;
+use golem_common::model::component_metadata::is_valid_function_alias;
+use golem_common::model::exports::function_by_name;
 use golem_common::model::oplog::OplogIndex;
-use golem_common::model::public_oplog::OplogCursor;
+use golem_common::model::public_oplog::{OplogCursor, OplogQuery};
+use golem_common::model::worker_metadata_projection::WorkerMetadataProjection;
+use golem_common::model::worker_name_query::WorkerNameQuery;
 use golem_common::model::{
-    AccountId, ComponentId, ComponentVersion, FilterComparator, IdempotencyKey, PromiseId,
-    ScanCursor, TargetWorkerId, WorkerFilter, WorkerId, WorkerStatus,
+    AccountId, ComponentId, ComponentVersion, ConnectOptions, FilterComparator, IdempotencyKey,
+    Pod, PromiseId, ScanCursor, TargetWorkerId, WorkerFilter, WorkerId, WorkerShadow,
+    WorkerStatus,
 };
-use golem_service_base::model::{ApiFileNode, ApiFileNodeConversionError, ApiGetFilesResponse, FileOrDirectoryNode, FileOrDirectoryResponse, GetFileOrDirectoryResponse, GetOplogResponse, GolemErrorUnknown, NodeType, ResourceLimits, WorkerMetadata};
-use golem_service_base::routing_table::HasRoutingTableService;
+use golem_common::SafeDisplay;
+use golem_service_base::model::{ApiFileNode, ApiFileNodeConversionError, ApiGetFilesResponse, DomainEvent, ErrorGroup, FileOrDirectoryNode, FunctionStats, FileOrDirectoryResponse, GetFileOrDirectoryResponse, GetOplogResponse, GetRawOplogResponse, GolemErrorUnknown, GolemErrorWorkerNotFound, NodeType, PollEventsResponse, ResourceLimits, UploadInvocationParameterResponse, WorkerGetOrCreateResponse, WorkerMetadata, is_shadow_worker_name, shadow_worker_name};
+use golem_service_base::routing_table::{HasRoutingTableService, MissingEntity};
 use golem_service_base::{
     model::{Component, GolemError},
     routing_table::RoutingTableService,
 };
 
-use crate::service::component::ComponentService;
+use crate::metrics::invocation::{
+    record_component_metadata_fetch, record_parameter_conversion, record_type_check,
+    record_type_check_failure,
+};
+use crate::service::component::{ComponentService, ComponentServiceError};
+use crate::service::maintenance::MaintenanceService;
+use crate::service::worker_defaults::WorkerDefaultsService;
 
+use super::invocation_queue::send_invoke_request;
 use super::{
-    AllExecutors, CallWorkerExecutorError, ConnectWorkerStream, HasWorkerExecutorClients,
-    RandomExecutor, ResponseMapResult, RoutingLogic, WorkerServiceError,
+    idempotency_cache_key, parse_wave_parameters, validate_blob_parameters, validate_invocation,
+    AllExecutors, CallWorkerExecutorError, ConnectWorkerStream, DomainEventPoller,
+    DomainEventStream, HasWorkerExecutorClients, IdempotencyCache, InvocationQueue,
+    InvocationResultStream, InvocationValidation, MultiConnectWorkerStream, RandomExecutor,
+    ResponseMapResult, RoutingLogic, WorkerCreationTemplate, WorkerRequestLimitsConfig,
+    WorkerServiceError, WorkerStatusEventStream,
 };
 
 pub type WorkerResult<T> = Result<T, WorkerServiceError>;
 
+/// Number of names generated by `WorkerService::create_with_generated_name` before giving up
+const GENERATED_NAME_ATTEMPTS: usize = 3;
+
+/// Number of workers scanned by a single executor call of `WorkerService::get_error_groups`
+const ERROR_GROUPS_SCAN_PAGE_SIZE: u64 = 100;
+
+/// Size of the parts an uploaded invocation parameter is sent to the executors in, below the
+/// default 4 MiB gRPC message limit
+const BLOB_PARAMETER_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// The id of the shadow worker used by `WorkerService::enable_shadow`
+pub fn shadow_worker_id(worker_id: &WorkerId) -> WorkerId {
+    WorkerId {
+        component_id: worker_id.component_id.clone(),
+        worker_name: shadow_worker_name(&worker_id.worker_name),
+    }
+}
+
+/// Shadow workers are only created by `WorkerService::enable_shadow`, and they have no shadows
+fn reject_shadow_worker_name(worker_id: &WorkerId) -> WorkerResult<()> {
+    if is_shadow_worker_name(&worker_id.worker_name) {
+        Err(WorkerServiceError::ReservedWorkerName(
+            worker_id.worker_name.clone(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Counts the invocations the worker executor rejected because their parameters did not match
+/// the function's signature. It only reports it for functions found in the worker's component.
+fn record_rejected_parameters(
+    worker_id: &TargetWorkerId,
+    function_name: &str,
+    error: &WorkerExecutionError,
+) {
+    if let Some(worker_execution_error::Error::ParamTypeMismatch(_)) = &error.error {
+        record_type_check_failure(&worker_id.component_id.to_string(), Some(function_name));
+    }
+}
+
+/// The idempotency key of an invocation, generated if the caller did not give one. It must be
+/// generated once per request, before calling the worker executor, so the retries of transient
+/// failures and the queued deliveries reuse it and the invocation is not executed more than once.
+fn invocation_idempotency_key(idempotency_key: Option<IdempotencyKey>) -> IdempotencyKey {
+    idempotency_key.unwrap_or_else(IdempotencyKey::fresh)
+}
+
+/// The request of an awaited invocation, sent as is on every attempt of the executor call
+fn invoke_and_await_request(
+    worker_id: &TargetWorkerId,
+    idempotency_key: Option<IdempotencyKey>,
+    function_name: String,
+    params: Vec<ProtoVal>,
+    blob_params: Vec<BlobParameter>,
+    invocation_context: Option<InvocationContext>,
+    metadata: WorkerRequestMetadata,
+) -> InvokeAndAwaitWorkerRequest {
+    InvokeAndAwaitWorkerRequest {
+        worker_id: Some(worker_id.clone().into()),
+        name: function_name,
+        input: params,
+        idempotency_key: Some(invocation_idempotency_key(idempotency_key).into()),
+        account_id: metadata.account_id.map(|id| id.into()),
+        account_limits: metadata.limits.map(|id| id.into()),
+        context: invocation_context,
+        blob_input: blob_params,
+    }
+}
+
+/// Sends an awaited invocation to the executor owning the worker, retrying transient failures
+/// with the same request
+async fn invoke_and_await_on_executor(
+    context: &(impl HasRoutingTableService + HasWorkerExecutorClients + Send + Sync),
+    worker_id: &TargetWorkerId,
+    function_name: String,
+    request: InvokeAndAwaitWorkerRequest,
+) -> WorkerResult<InvokeResult> {
+    let worker_id = worker_id.clone();
+    context.call_worker_executor(
+        worker_id.clone(),
+        move |worker_executor_client| {
+            info!("Invoke and await function");
+            Box::pin(worker_executor_client.invoke_and_await_worker(request.clone()))
+        },
+        move |response| {
+            match response.into_inner() {
+                workerexecutor::v1::InvokeAndAwaitWorkerResponse {
+                    result:
+                    Some(workerexecutor::v1::invoke_and_await_worker_response::Result::Success(
+                             workerexecutor::v1::InvokeAndAwaitWorkerSuccess {
+                                 output,
+                             },
+                         )),
+                } => {
+                    Ok(InvokeResult { result: output })
+                }
+                workerexecutor::v1::InvokeAndAwaitWorkerResponse {
+                    result:
+                    Some(workerexecutor::v1::invoke_and_await_worker_response::Result::Failure(err)),
+                } => {
+                    error!("Invoked function error: {err:?}");
+                    record_rejected_parameters(&worker_id, &function_name, &err);
+                    Err(err.into())
+                }
+                workerexecutor::v1::InvokeAndAwaitWorkerResponse { .. } => {
+                    error!("Invoked function failed with empty response");
+                    Err("Empty response".into())
+                }
+            }
+        },
+        WorkerServiceError::InternalCallError,
+    ).await
+}
+
 #[async_trait]
 pub trait WorkerService<AuthCtx> {
     async fn create(
@@ -62,29 +202,103 @@ pub trait WorkerService<AuthCtx> {
         component_version: u64,
         arguments: Vec<String>,
         environment_variables: HashMap<String, String>,
+        ttl: Option<Duration>,
+        anti_affinity_key: Option<String>,
         metadata: WorkerRequestMetadata,
         auth_ctx: &AuthCtx,
     ) -> WorkerResult<WorkerId>;
 
+    /// Creates a worker the same way as `create`, with a name generated by the naming strategy
+    /// of the component's worker defaults. If a worker with the generated name already exists,
+    /// a new name is generated a few times before failing with `WorkerAlreadyExists`.
+    async fn create_with_generated_name(
+        &self,
+        component_id: &ComponentId,
+        component_version: u64,
+        arguments: Vec<String>,
+        environment_variables: HashMap<String, String>,
+        ttl: Option<Duration>,
+        anti_affinity_key: Option<String>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<WorkerId>;
+
+    /// Returns the worker if it already exists, otherwise creates it the same way as `create`.
+    /// The check and the creation happen on the worker executor owning the worker, so concurrent
+    /// callers do not fail with `WorkerAlreadyExists`. The arguments, environment and TTL are
+    /// only applied if the worker is created.
+    async fn get_or_create_worker(
+        &self,
+        worker_id: &WorkerId,
+        component_version: u64,
+        arguments: Vec<String>,
+        environment_variables: HashMap<String, String>,
+        ttl: Option<Duration>,
+        anti_affinity_key: Option<String>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<WorkerGetOrCreateResponse>;
+
+    /// Connects to the events of a worker. Depending on `options`, the log events recorded in the
+    /// worker's oplog are sent first, followed by the live events.
     async fn connect(
         &self,
         worker_id: &WorkerId,
+        options: ConnectOptions,
         metadata: WorkerRequestMetadata,
         auth_ctx: &AuthCtx,
     ) -> WorkerResult<ConnectWorkerStream>;
 
+    /// Connects to all the workers of a component matching the filter, merging their events.
+    /// Workers deleted while connecting are skipped.
+    async fn connect_many(
+        &self,
+        component_id: &ComponentId,
+        filter: Option<WorkerFilter>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<MultiConnectWorkerStream>;
+
+    /// Deletes a worker, and with `cascade` the child workers, promises, scheduled invocations and
+    /// files it owns as well. Returns the removed resources, or the ones which would be removed if
+    /// `dry_run` is set, in which case nothing gets deleted.
     async fn delete(
         &self,
         worker_id: &WorkerId,
+        cascade: bool,
+        dry_run: bool,
         metadata: WorkerRequestMetadata,
         auth_ctx: &AuthCtx,
-    ) -> WorkerResult<()>;
+    ) -> WorkerResult<Vec<String>>;
 
     fn validate_typed_parameters(
         &self,
         params: Vec<TypeAnnotatedValue>,
     ) -> WorkerResult<Vec<ProtoVal>>;
 
+    /// Resolves the function and type checks the parameters of an invocation the same way as
+    /// invoking it would, without sending anything to the executors. The function is looked up
+    /// in the worker's component version, or in the latest version if the worker does not exist.
+    async fn validate_invocation(
+        &self,
+        worker_id: &TargetWorkerId,
+        function_name: &str,
+        params: Vec<TypeAnnotatedValue>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<InvocationValidation>;
+
+    /// Resolves a function alias declared by the component to the fully qualified function name,
+    /// so it can be invoked. The alias is looked up in the worker's component version, or in the
+    /// latest version if the worker does not exist. Other names are returned unchanged.
+    async fn resolve_function_name(
+        &self,
+        worker_id: &TargetWorkerId,
+        function_name: String,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<String>;
+
     /// Validates the provided list of `TypeAnnotatedValue` parameters, and then
     /// invokes the worker and waits its results, returning it as a `TypeAnnotatedValue`.
     async fn validate_and_invoke_and_await_typed(
@@ -93,15 +307,21 @@ pub trait WorkerService<AuthCtx> {
         idempotency_key: Option<IdempotencyKey>,
         function_name: String,
         params: Vec<TypeAnnotatedValue>,
+        blob_params: Vec<BlobParameter>,
         invocation_context: Option<InvocationContext>,
         metadata: WorkerRequestMetadata,
     ) -> WorkerResult<TypeAnnotatedValue> {
-        let params = self.validate_typed_parameters(params)?;
+        let start = Instant::now();
+        let params = self.validate_typed_parameters(params);
+        record_parameter_conversion(start.elapsed());
+        let params = params?;
+
         self.invoke_and_await_typed(
             worker_id,
             idempotency_key,
             function_name,
             params,
+            blob_params,
             invocation_context,
             metadata,
         )
@@ -116,8 +336,37 @@ pub trait WorkerService<AuthCtx> {
         idempotency_key: Option<IdempotencyKey>,
         function_name: String,
         params: Vec<ProtoVal>,
+        blob_params: Vec<BlobParameter>,
+        invocation_context: Option<InvocationContext>,
+        metadata: WorkerRequestMetadata,
+    ) -> WorkerResult<TypeAnnotatedValue>;
+
+    /// Invokes a worker using raw `Val` parameter values and streams its results in chunks of
+    /// `TypeAnnotatedValue`. A function returning a single list is streamed element by element,
+    /// any other result is returned in a single chunk, as told by the stream's `list_elements`.
+    async fn invoke_and_await_function_stream(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        params: Vec<ProtoVal>,
+        blob_params: Vec<BlobParameter>,
+        invocation_context: Option<InvocationContext>,
+        metadata: WorkerRequestMetadata,
+    ) -> WorkerResult<InvocationResultStream>;
+
+    /// Invokes a worker with parameters written in WAVE (WebAssembly Value Encoding) text form
+    /// and awaits its results. The parameters are parsed using the function's signature in the
+    /// worker's component version, or in the latest version if the worker does not exist.
+    async fn invoke_and_await_function_wave(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        params: Vec<String>,
         invocation_context: Option<InvocationContext>,
         metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
     ) -> WorkerResult<TypeAnnotatedValue>;
 
     /// Invokes a worker using raw `Val` parameter values and awaits its results returning
@@ -128,6 +377,7 @@ pub trait WorkerService<AuthCtx> {
         idempotency_key: Option<IdempotencyKey>,
         function_name: String,
         params: Vec<ProtoVal>,
+        blob_params: Vec<BlobParameter>,
         invocation_context: Option<InvocationContext>,
         metadata: WorkerRequestMetadata,
     ) -> WorkerResult<InvokeResult>;
@@ -140,15 +390,21 @@ pub trait WorkerService<AuthCtx> {
         idempotency_key: Option<IdempotencyKey>,
         function_name: String,
         params: Vec<TypeAnnotatedValue>,
+        blob_params: Vec<BlobParameter>,
         invocation_context: Option<InvocationContext>,
         metadata: WorkerRequestMetadata,
     ) -> WorkerResult<()> {
-        let params = self.validate_typed_parameters(params)?;
+        let start = Instant::now();
+        let params = self.validate_typed_parameters(params);
+        record_parameter_conversion(start.elapsed());
+        let params = params?;
+
         self.invoke(
             worker_id,
             idempotency_key,
             function_name,
             params,
+            blob_params,
             invocation_context,
             metadata,
         )
@@ -163,6 +419,7 @@ pub trait WorkerService<AuthCtx> {
         idempotency_key: Option<IdempotencyKey>,
         function_name: String,
         params: Vec<ProtoVal>,
+        blob_params: Vec<BlobParameter>,
         invocation_context: Option<InvocationContext>,
         metadata: WorkerRequestMetadata,
     ) -> WorkerResult<()>;
@@ -184,6 +441,80 @@ pub trait WorkerService<AuthCtx> {
         auth_ctx: &AuthCtx,
     ) -> WorkerResult<()>;
 
+    /// Interrupts the worker only if it is currently executing the invocation with the given
+    /// idempotency key, leaving its pending invocations queued. Returns whether it was interrupted.
+    async fn abort_running_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<bool>;
+
+    /// Removes the pending invocation with the given idempotency key from the worker's invocation
+    /// queue before it gets executed. Returns false if the invocation is already running or has
+    /// completed.
+    async fn cancel_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<bool>;
+
+    /// Holds the pending invocation with the given idempotency key in the worker's invocation
+    /// queue. The worker keeps processing its other invocations, and only executes the held one
+    /// once it gets released.
+    async fn hold_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()>;
+
+    /// Releases a pending invocation held by `hold_invocation`
+    async fn release_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()>;
+
+    /// Schedules an invocation of the worker at the given time without awaiting its results. The
+    /// schedule is persisted by the executor, so the invocation happens even if the executor is
+    /// restarted before it is due. Returns the idempotency key of the invocation, which is
+    /// generated if not given.
+    async fn schedule_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        params: Vec<TypeAnnotatedValue>,
+        scheduled_at: DateTime<Utc>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<IdempotencyKey>;
+
+    /// Starts or stops sampling the guest stacks of the worker. Starting discards the previously
+    /// collected profile.
+    async fn set_profiling(
+        &self,
+        worker_id: &WorkerId,
+        enabled: bool,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()>;
+
+    /// Gets the collected profile of the worker in the pprof format
+    async fn get_profile(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<u8>>;
+
     async fn get_metadata(
         &self,
         worker_id: &WorkerId,
@@ -191,6 +522,18 @@ pub trait WorkerService<AuthCtx> {
         auth_ctx: &AuthCtx,
     ) -> WorkerResult<WorkerMetadata>;
 
+    /// Gets the metadata of many workers at once, with one call to each of the executors owning them.
+    /// Workers which do not exist are left out of the result, and the workers whose metadata could
+    /// not be looked up are returned with their errors.
+    async fn get_metadata_many(
+        &self,
+        worker_ids: &[WorkerId],
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<WorkerMetadataMany>;
+
+    /// Lists the workers of a component matching `filter`. With a `projection` only the selected
+    /// parts of the worker metadata are returned, reducing the size of the responses.
     async fn find_metadata(
         &self,
         component_id: &ComponentId,
@@ -198,10 +541,52 @@ pub trait WorkerService<AuthCtx> {
         cursor: ScanCursor,
         count: u64,
         precise: bool,
+        projection: Option<WorkerMetadataProjection>,
         metadata: WorkerRequestMetadata,
         auth_ctx: &AuthCtx,
     ) -> WorkerResult<(Option<ScanCursor>, Vec<WorkerMetadata>)>;
 
+    /// Finds workers by name using the executors' index of worker names, which is much cheaper
+    /// than a `find_metadata` with a name filter as no worker metadata gets scanned
+    async fn search_workers(
+        &self,
+        component_id: &ComponentId,
+        query: WorkerNameQuery,
+        count: u64,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<WorkerId>>;
+
+    /// Sets how long the completed idempotency keys of a component's workers are remembered,
+    /// or removes the limit if `window` is `None`. Invocations repeating an idempotency key
+    /// that is outside of the window are executed again.
+    async fn set_deduplication_window(
+        &self,
+        component_id: &ComponentId,
+        window: Option<Duration>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()>;
+
+    /// Groups the failed and retrying workers of a component by the fingerprint of their last
+    /// error, returning at most `limit` groups, the ones with the most workers first
+    async fn get_error_groups(
+        &self,
+        component_id: &ComponentId,
+        limit: u64,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<ErrorGroup>>;
+
+    /// Collects the invocation statistics of the exported functions of a component from all the
+    /// executors, the most invoked functions first
+    async fn get_function_stats(
+        &self,
+        component_id: &ComponentId,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<FunctionStats>>;
+
     async fn resume(
         &self,
         worker_id: &WorkerId,
@@ -218,6 +603,61 @@ pub trait WorkerService<AuthCtx> {
         auth_ctx: &AuthCtx,
     ) -> WorkerResult<()>;
 
+    /// Replaces the environment variables of a worker. The running worker is not affected, the
+    /// new environment is used from the next restart or update of the worker.
+    async fn update_env(
+        &self,
+        worker_id: &WorkerId,
+        environment_variables: HashMap<String, String>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()>;
+
+    /// Replaces the recorded data of the given oplog entries of a worker by placeholder values of
+    /// the same types, for example to remove personal data logged by mistake. Only the entries
+    /// which do not affect how the worker gets replayed can be redacted. The redaction is
+    /// recorded in the oplog with the given reason, and the index of that entry is returned.
+    async fn redact_oplog(
+        &self,
+        worker_id: &WorkerId,
+        oplog_indices: Vec<OplogIndex>,
+        reason: String,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<OplogIndex>;
+
+    /// Starts duplicating every invocation of a worker to a shadow worker running the given
+    /// candidate component version, to validate it against production traffic before updating.
+    ///
+    /// The shadow worker is created with the worker's arguments and environment if it does not
+    /// exist yet. Returns the id of the shadow worker.
+    async fn enable_shadow(
+        &self,
+        worker_id: &WorkerId,
+        component_version: ComponentVersion,
+        compare_results: bool,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<WorkerId>;
+
+    /// Stops duplicating the invocations of a worker. The shadow worker itself is kept.
+    async fn disable_shadow(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()>;
+
+    /// Makes the worker get deleted with all its storage after `ttl`, counted from now, or
+    /// keeps it until deleted explicitly if `ttl` is `None`
+    async fn set_ttl(
+        &self,
+        worker_id: &WorkerId,
+        ttl: Option<Duration>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()>;
+
     async fn get_component_for_worker(
         &self,
         worker_id: &WorkerId,
@@ -231,10 +671,75 @@ pub trait WorkerService<AuthCtx> {
         from_oplog_index: OplogIndex,
         cursor: Option<OplogCursor>,
         count: u64,
+        query: Option<OplogQuery>,
         metadata: WorkerRequestMetadata,
         auth_ctx: &AuthCtx,
     ) -> Result<GetOplogResponse, WorkerServiceError>;
 
+    /// Reads the oplog entries of a worker as they are stored, without converting them to the
+    /// public oplog representation
+    async fn get_raw_oplog(
+        &self,
+        worker_id: &WorkerId,
+        from_oplog_index: OplogIndex,
+        count: u64,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> Result<GetRawOplogResponse, WorkerServiceError>;
+
+    /// Stores a parameter too large to be sent in an invocation request, returning the key to
+    /// refer to it with in a `BlobParameter`. The upload is rejected as soon as it exceeds the
+    /// maximum blob parameter size, and it is deleted once the invocation using it is completed.
+    async fn upload_invocation_parameter(
+        &self,
+        component_id: &ComponentId,
+        data: Box<dyn AsyncRead + Send + Unpin>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<UploadInvocationParameterResponse>;
+
+    /// Downloads the protobuf encoded result of an invocation which was too large to be returned
+    /// directly, stored with the key returned in its `ResultTooLarge` error
+    async fn get_invocation_result_blob(
+        &self,
+        blob_key: &str,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<u8>>;
+
+    /// Reads the domain events of a topic emitted by the workers of a component, following the
+    /// event with the id `cursor`
+    async fn poll_events(
+        &self,
+        component_id: &ComponentId,
+        topic: &str,
+        cursor: u64,
+        count: u64,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<PollEventsResponse>;
+
+    /// Streams the domain events of a topic emitted by the workers of a component, following the
+    /// event with the id `cursor`, including the ones emitted after subscribing
+    async fn subscribe_events(
+        &self,
+        component_id: &ComponentId,
+        topic: &str,
+        cursor: u64,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<DomainEventStream>;
+
+    /// Streams the status changes of the workers of a component matching the filter: their
+    /// creation, deletion and the changes of their status, as reported by the worker executors
+    async fn watch_workers(
+        &self,
+        component_id: &ComponentId,
+        filter: Option<WorkerFilter>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<WorkerStatusEventStream>;
+
     async fn get_files(
         &self,
         worker_id: WorkerId,
@@ -255,6 +760,15 @@ pub struct TypedResult {
     pub function_result_types: Vec<AnalysedFunctionResult>,
 }
 
+/// The result of `WorkerService::get_metadata_many`
+#[derive(Debug, Default)]
+pub struct WorkerMetadataMany {
+    /// The metadata of the requested workers which exist
+    pub workers: Vec<WorkerMetadata>,
+    /// The requested workers whose metadata could not be looked up
+    pub errors: Vec<(WorkerId, WorkerServiceError)>,
+}
+
 #[derive(Clone, Debug)]
 pub struct WorkerRequestMetadata {
     pub account_id: Option<AccountId>,
@@ -268,56 +782,113 @@ pub struct WorkerServiceDefault<AuthCtx> {
     //       (with retryable errors) does not end the retry loop,
     //       rather it emits a warn log and resets the retry state.
     worker_executor_retries: RetryConfig,
+    limits: WorkerRequestLimitsConfig,
     component_service: Arc<dyn ComponentService<AuthCtx> + Send + Sync>,
     routing_table_service: Arc<dyn RoutingTableService + Send + Sync>,
+    invocation_queue: Option<Arc<InvocationQueue>>,
+    worker_defaults_service: Arc<dyn WorkerDefaultsService + Send + Sync>,
+    maintenance_service: Arc<dyn MaintenanceService + Send + Sync>,
+    idempotency_cache: Option<Arc<dyn IdempotencyCache + Send + Sync>>,
 }
 
 impl<AuthCtx> WorkerServiceDefault<AuthCtx> {
     pub fn new(
         worker_executor_clients: MultiTargetGrpcClient<WorkerExecutorClient<Channel>>,
         worker_executor_retries: RetryConfig,
+        limits: WorkerRequestLimitsConfig,
         component_service: Arc<dyn ComponentService<AuthCtx> + Send + Sync>,
         routing_table_service: Arc<dyn RoutingTableService + Send + Sync>,
+        invocation_queue: Option<Arc<InvocationQueue>>,
+        worker_defaults_service: Arc<dyn WorkerDefaultsService + Send + Sync>,
+        maintenance_service: Arc<dyn MaintenanceService + Send + Sync>,
+        idempotency_cache: Option<Arc<dyn IdempotencyCache + Send + Sync>>,
     ) -> Self {
         Self {
             worker_executor_clients,
             worker_executor_retries,
+            limits,
             component_service,
             routing_table_service,
+            invocation_queue,
+            worker_defaults_service,
+            maintenance_service,
+            idempotency_cache,
         }
     }
-}
 
-impl<AuthCtx> HasRoutingTableService for WorkerServiceDefault<AuthCtx> {
-    fn routing_table_service(&self) -> &Arc<dyn RoutingTableService + Send + Sync> {
-        &self.routing_table_service
+    /// The result of an earlier invocation with the same idempotency key, if it is cached
+    async fn cached_result<T: Message + Default>(&self, cache_key: &Option<String>) -> Option<T> {
+        let (Some(cache), Some(cache_key)) = (&self.idempotency_cache, cache_key) else {
+            return None;
+        };
+        let cached = cache.get(cache_key).await?;
+        match T::decode(cached) {
+            Ok(result) => {
+                info!("Returning the cached result of invocation {cache_key}");
+                Some(result)
+            }
+            Err(err) => {
+                warn!("Failed to decode the cached result of invocation {cache_key}: {err}");
+                None
+            }
+        }
     }
-}
 
-impl<AuthCtx> HasWorkerExecutorClients for WorkerServiceDefault<AuthCtx> {
-    fn worker_executor_clients(&self) -> &MultiTargetGrpcClient<WorkerExecutorClient<Channel>> {
-        &self.worker_executor_clients
+    async fn cache_result<T: Message>(&self, cache_key: &Option<String>, result: &T) {
+        if let (Some(cache), Some(cache_key)) = (&self.idempotency_cache, cache_key) {
+            cache.put(cache_key, result.encode_to_vec().into()).await;
+        }
     }
 
-    fn worker_executor_retry_config(&self) -> &RetryConfig {
-        &self.worker_executor_retries
+    /// Rejects the invocations of the workers of a component in maintenance
+    fn check_maintenance(&self, component_id: &ComponentId) -> WorkerResult<()> {
+        match self.maintenance_service.in_maintenance(component_id) {
+            Some(maintenance) => Err(WorkerServiceError::ComponentInMaintenance {
+                component_id: component_id.clone(),
+                message: maintenance.message,
+            }),
+            None => Ok(()),
+        }
     }
-}
 
-#[async_trait]
-impl<AuthCtx> WorkerService<AuthCtx> for WorkerServiceDefault<AuthCtx>
-where
-    AuthCtx: Send + Sync,
-{
-    async fn create(
+    /// Fails fast for workers recently reported as missing, with the error the executors
+    /// report for them
+    fn check_worker_exists(&self, worker_id: &WorkerId) -> WorkerResult<()> {
+        if self
+            .routing_table_service
+            .is_missing(&MissingEntity::Worker(worker_id.clone()))
+        {
+            Err(WorkerServiceError::Golem(GolemError::WorkerNotFound(
+                GolemErrorWorkerNotFound {
+                    worker_id: worker_id.clone(),
+                },
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Creates the worker, which can be a shadow worker too
+    async fn create_worker(
         &self,
         worker_id: &WorkerId,
         component_version: u64,
         arguments: Vec<String>,
         environment_variables: HashMap<String, String>,
+        ttl: Option<Duration>,
+        anti_affinity_key: Option<String>,
         metadata: WorkerRequestMetadata,
-        _auth_ctx: &AuthCtx,
     ) -> WorkerResult<WorkerId> {
+        let (arguments, environment_variables) = self
+            .creation_parameters(
+                worker_id,
+                component_version,
+                arguments,
+                environment_variables,
+                &metadata,
+            )
+            .await?;
+
         let worker_id_clone = worker_id.clone();
         self.call_worker_executor(
             worker_id.clone(),
@@ -331,6 +902,7 @@ where
                     env: environment_variables.clone(),
                     account_id: metadata.account_id.clone().map(|id| id.into()),
                     account_limits: metadata.limits.clone().map(|id| id.into()),
+                    ttl_millis: ttl.map(|ttl| ttl.as_millis() as u64),
                 }))
             },
             |response| match response.into_inner() {
@@ -345,16 +917,290 @@ where
             WorkerServiceError::InternalCallError,
         )
         .await?;
+        self.worker_created(worker_id, anti_affinity_key).await;
 
         Ok(worker_id.clone())
     }
 
+    /// The arguments and environment variables of a new worker, with the worker defaults applied
+    /// and the templates expanded
+    async fn creation_parameters(
+        &self,
+        worker_id: &WorkerId,
+        component_version: u64,
+        arguments: Vec<String>,
+        environment_variables: HashMap<String, String>,
+        metadata: &WorkerRequestMetadata,
+    ) -> WorkerResult<(Vec<String>, HashMap<String, String>)> {
+        let environment_variables = match &metadata.account_id {
+            Some(account_id) => self
+                .worker_defaults_service
+                .resolve(account_id, &worker_id.component_id)
+                .await
+                .map_err(|err| WorkerServiceError::Internal(err.to_safe_string()))?
+                .apply(environment_variables),
+            None => environment_variables,
+        };
+        let (arguments, environment_variables) = WorkerCreationTemplate {
+            worker_id,
+            component_version,
+            account_id: metadata.account_id.as_ref(),
+        }
+        .expand(arguments, environment_variables)?;
+        self.limits
+            .check_create(&arguments, &environment_variables)?;
+        Ok((arguments, environment_variables))
+    }
+
+    async fn worker_created(&self, worker_id: &WorkerId, anti_affinity_key: Option<String>) {
+        self.routing_table_service
+            .clear_missing(&MissingEntity::Worker(worker_id.clone()));
+
+        // The worker is created even if its shard could not be added to the group, anti-affinity
+        // is only applied where the shard assignment allows it anyway
+        if let Some(key) = anti_affinity_key {
+            if let Err(error) = self
+                .routing_table_service
+                .add_anti_affinity(&key, worker_id)
+                .await
+            {
+                warn!("Failed to add worker {worker_id} to anti-affinity group {key}: {error}");
+            }
+        }
+    }
+
+    /// Applies the worker defaults to the environment of the invocation context, which is used
+    /// when the invocation implicitly creates its target worker
+    async fn with_worker_defaults(
+        &self,
+        worker_id: &TargetWorkerId,
+        invocation_context: Option<InvocationContext>,
+        metadata: &WorkerRequestMetadata,
+    ) -> WorkerResult<Option<InvocationContext>> {
+        match &metadata.account_id {
+            Some(account_id) => {
+                let defaults = self
+                    .worker_defaults_service
+                    .resolve(account_id, &worker_id.component_id)
+                    .await
+                    .map_err(|err| WorkerServiceError::Internal(err.to_safe_string()))?;
+                let mut invocation_context = invocation_context.unwrap_or_default();
+                invocation_context.env = defaults.apply(invocation_context.env);
+                Ok(Some(invocation_context))
+            }
+            None => Ok(invocation_context),
+        }
+    }
+
+    /// Invoking a named worker creates it if it does not exist, so it is no longer missing
+    fn clear_missing_worker(&self, worker_id: &TargetWorkerId) {
+        if let Some(worker_id) = worker_id.clone().try_into_worker_id() {
+            self.routing_table_service
+                .clear_missing(&MissingEntity::Worker(worker_id));
+        }
+    }
+}
+
+impl<AuthCtx> HasRoutingTableService for WorkerServiceDefault<AuthCtx> {
+    fn routing_table_service(&self) -> &Arc<dyn RoutingTableService + Send + Sync> {
+        &self.routing_table_service
+    }
+}
+
+impl<AuthCtx> HasWorkerExecutorClients for WorkerServiceDefault<AuthCtx> {
+    fn worker_executor_clients(&self) -> &MultiTargetGrpcClient<WorkerExecutorClient<Channel>> {
+        &self.worker_executor_clients
+    }
+
+    fn worker_executor_retry_config(&self) -> &RetryConfig {
+        &self.worker_executor_retries
+    }
+}
+
+#[async_trait]
+impl<AuthCtx> WorkerService<AuthCtx> for WorkerServiceDefault<AuthCtx>
+where
+    AuthCtx: Send + Sync,
+{
+    async fn create(
+        &self,
+        worker_id: &WorkerId,
+        component_version: u64,
+        arguments: Vec<String>,
+        environment_variables: HashMap<String, String>,
+        ttl: Option<Duration>,
+        anti_affinity_key: Option<String>,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<WorkerId> {
+        reject_shadow_worker_name(worker_id)?;
+        self.create_worker(
+            worker_id,
+            component_version,
+            arguments,
+            environment_variables,
+            ttl,
+            anti_affinity_key,
+            metadata,
+        )
+        .await
+    }
+
+    async fn create_with_generated_name(
+        &self,
+        component_id: &ComponentId,
+        component_version: u64,
+        arguments: Vec<String>,
+        environment_variables: HashMap<String, String>,
+        ttl: Option<Duration>,
+        anti_affinity_key: Option<String>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<WorkerId> {
+        let mut attempt = 1;
+        loop {
+            let worker_name = match &metadata.account_id {
+                Some(account_id) => self
+                    .worker_defaults_service
+                    .generate_worker_name(account_id, component_id)
+                    .await
+                    .map_err(|err| WorkerServiceError::Internal(err.to_safe_string()))?,
+                None => Uuid::new_v4().to_string(),
+            };
+            let worker_id = WorkerId {
+                component_id: component_id.clone(),
+                worker_name,
+            };
+
+            let result = self
+                .create(
+                    &worker_id,
+                    component_version,
+                    arguments.clone(),
+                    environment_variables.clone(),
+                    ttl,
+                    anti_affinity_key.clone(),
+                    metadata.clone(),
+                    auth_ctx,
+                )
+                .await;
+            match result {
+                // Sequential names collide with the workers created with a numeric name
+                Err(WorkerServiceError::Golem(GolemError::WorkerAlreadyExists(_)))
+                    if attempt < GENERATED_NAME_ATTEMPTS =>
+                {
+                    info!("Generated worker name {worker_id} is taken, generating a new one");
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn get_or_create_worker(
+        &self,
+        worker_id: &WorkerId,
+        component_version: u64,
+        arguments: Vec<String>,
+        environment_variables: HashMap<String, String>,
+        ttl: Option<Duration>,
+        anti_affinity_key: Option<String>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<WorkerGetOrCreateResponse> {
+        reject_shadow_worker_name(worker_id)?;
+
+        // The creation parameters are only resolved and checked for a worker which does not
+        // exist yet. The executor creates the worker only if it still does not exist.
+        match self
+            .get_metadata(worker_id, metadata.clone(), auth_ctx)
+            .await
+        {
+            Ok(existing) => {
+                return Ok(WorkerGetOrCreateResponse {
+                    worker_id: worker_id.clone(),
+                    component_version: existing.component_version,
+                    created: false,
+                })
+            }
+            Err(WorkerServiceError::WorkerNotFound(_))
+            | Err(WorkerServiceError::Golem(GolemError::WorkerNotFound(_))) => {}
+            Err(other) => return Err(other),
+        }
+
+        let (arguments, environment_variables) = self
+            .creation_parameters(
+                worker_id,
+                component_version,
+                arguments,
+                environment_variables,
+                &metadata,
+            )
+            .await?;
+
+        let worker_id_clone = worker_id.clone();
+        let result = self
+            .call_worker_executor(
+                worker_id.clone(),
+                move |worker_executor_client| {
+                    info!("Get or create worker");
+                    let worker_id = worker_id_clone.clone();
+                    Box::pin(
+                        worker_executor_client.get_or_create_worker(CreateWorkerRequest {
+                            worker_id: Some(worker_id.into()),
+                            component_version,
+                            args: arguments.clone(),
+                            env: environment_variables.clone(),
+                            account_id: metadata.account_id.clone().map(|id| id.into()),
+                            account_limits: metadata.limits.clone().map(|id| id.into()),
+                            ttl_millis: ttl.map(|ttl| ttl.as_millis() as u64),
+                        }),
+                    )
+                },
+                |response| match response.into_inner() {
+                    workerexecutor::v1::GetOrCreateWorkerResponse {
+                        result:
+                            Some(workerexecutor::v1::get_or_create_worker_response::Result::Success(
+                                success,
+                            )),
+                    } => Ok(success),
+                    workerexecutor::v1::GetOrCreateWorkerResponse {
+                        result:
+                            Some(workerexecutor::v1::get_or_create_worker_response::Result::Failure(
+                                err,
+                            )),
+                    } => Err(err.into()),
+                    workerexecutor::v1::GetOrCreateWorkerResponse { .. } => {
+                        Err("Empty response".into())
+                    }
+                },
+                WorkerServiceError::InternalCallError,
+            )
+            .await?;
+        if result.created {
+            self.worker_created(worker_id, anti_affinity_key).await;
+        }
+
+        Ok(WorkerGetOrCreateResponse {
+            worker_id: worker_id.clone(),
+            component_version: result.component_version,
+            created: result.created,
+        })
+    }
+
     async fn connect(
         &self,
         worker_id: &WorkerId,
+        options: ConnectOptions,
         metadata: WorkerRequestMetadata,
         _auth_ctx: &AuthCtx,
     ) -> WorkerResult<ConnectWorkerStream> {
+        if self
+            .routing_table_service
+            .is_missing(&MissingEntity::Worker(worker_id.clone()))
+        {
+            return Err(WorkerServiceError::WorkerNotFound(worker_id.clone()));
+        }
         let worker_id = worker_id.clone();
         let worker_id_err: WorkerId = worker_id.clone();
         let stream = self
@@ -367,6 +1213,7 @@ where
                         account_id: metadata.account_id.clone().map(|id| id.into()),
 
                         account_limits: metadata.limits.clone().map(|id| id.into()),
+                        options: Some(options.clone().into()),
                     }))
                 },
                 |response| Ok(ConnectWorkerStream::new(response.into_inner())),
@@ -384,31 +1231,91 @@ where
         Ok(stream)
     }
 
+    async fn connect_many(
+        &self,
+        component_id: &ComponentId,
+        filter: Option<WorkerFilter>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<MultiConnectWorkerStream> {
+        let mut worker_ids = Vec::new();
+        let mut cursor = ScanCursor::default();
+        loop {
+            let (next_cursor, workers) = self
+                .find_metadata(
+                    component_id,
+                    filter.clone(),
+                    cursor,
+                    50,
+                    true,
+                    Some(WorkerMetadataProjection::default()),
+                    metadata.clone(),
+                    auth_ctx,
+                )
+                .await?;
+            worker_ids.extend(workers.into_iter().map(|worker| worker.worker_id));
+            self.limits.check_connected_workers(worker_ids.len())?;
+
+            match next_cursor {
+                Some(next_cursor) => cursor = next_cursor,
+                None => break,
+            }
+        }
+
+        let connections = join_all(worker_ids.into_iter().map(|worker_id| {
+            let metadata = metadata.clone();
+            async move {
+                let result = self
+                    .connect(&worker_id, ConnectOptions::default(), metadata, auth_ctx)
+                    .await;
+                (worker_id, result)
+            }
+        }))
+        .await;
+
+        let mut streams = Vec::new();
+        for (worker_id, result) in connections {
+            match result {
+                Ok(stream) => streams.push((worker_id, stream)),
+                Err(WorkerServiceError::WorkerNotFound(_)) => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(MultiConnectWorkerStream::new(streams))
+    }
+
     async fn delete(
         &self,
         worker_id: &WorkerId,
+        cascade: bool,
+        dry_run: bool,
         metadata: WorkerRequestMetadata,
         _auth_ctx: &AuthCtx,
-    ) -> WorkerResult<()> {
-        let worker_id = worker_id.clone();
-        self.call_worker_executor(
+    ) -> WorkerResult<Vec<String>> {
+        let missing = MissingEntity::Worker(worker_id.clone());
+        let worker_id_clone = worker_id.clone();
+        let removed = self.call_worker_executor(
             worker_id.clone(),
             move |worker_executor_client| {
                 info!("Delete worker");
-                let worker_id = worker_id.clone();
+                let worker_id = worker_id_clone.clone();
                 Box::pin(worker_executor_client.delete_worker(
                     workerexecutor::v1::DeleteWorkerRequest {
                         worker_id: Some(golem_api_grpc::proto::golem::worker::WorkerId::from(
                             worker_id.clone(),
                         )),
                         account_id: metadata.account_id.clone().map(|id| id.into()),
+                        cascade,
+                        dry_run,
                     },
                 ))
             },
             |response| match response.into_inner() {
                 workerexecutor::v1::DeleteWorkerResponse {
-                    result: Some(workerexecutor::v1::delete_worker_response::Result::Success(_)),
-                } => Ok(()),
+                    result:
+                        Some(workerexecutor::v1::delete_worker_response::Result::Success(success)),
+                } => Ok(success.removed),
                 workerexecutor::v1::DeleteWorkerResponse {
                     result: Some(workerexecutor::v1::delete_worker_response::Result::Failure(err)),
                 } => Err(err.into()),
@@ -417,8 +1324,19 @@ where
             WorkerServiceError::InternalCallError,
         )
         .await?;
+        if !dry_run {
+            // Lookups of the deleted worker are answered without reaching the executors for a while
+            self.routing_table_service.mark_missing(missing);
+            if let Err(error) = self
+                .routing_table_service
+                .remove_anti_affinity(worker_id)
+                .await
+            {
+                warn!("Failed to remove worker {worker_id} from its anti-affinity group: {error}");
+            }
+        }
 
-        Ok(())
+        Ok(removed)
     }
 
     fn validate_typed_parameters(
@@ -434,15 +1352,86 @@ where
         Ok(result)
     }
 
+    async fn validate_invocation(
+        &self,
+        worker_id: &TargetWorkerId,
+        function_name: &str,
+        params: Vec<TypeAnnotatedValue>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<InvocationValidation> {
+        let component = self
+            .get_component_for_target(worker_id, metadata, auth_ctx)
+            .await?;
+        let function_name = component.metadata.resolve_function_name(function_name);
+
+        // Not recorded by the type check metrics, which only count the checks of invocations
+        let mut validation =
+            validate_invocation(&component.metadata.exports, function_name, params);
+        if let Some(params) = validation.params() {
+            if let Err(err) = self.limits.check_invocation(&params, &[]) {
+                validation.errors.push(err.to_string());
+            }
+        }
+
+        Ok(validation)
+    }
+
+    async fn resolve_function_name(
+        &self,
+        worker_id: &TargetWorkerId,
+        function_name: String,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<String> {
+        // Only plain kebab-case names can be aliases, fully qualified names need no lookup
+        if !is_valid_function_alias(&function_name) {
+            return Ok(function_name);
+        }
+
+        let component = self
+            .get_component_for_target(worker_id, metadata, auth_ctx)
+            .await?;
+        Ok(component
+            .metadata
+            .resolve_function_name(&function_name)
+            .to_string())
+    }
+
     async fn invoke_and_await_typed(
         &self,
         worker_id: &TargetWorkerId,
         idempotency_key: Option<IdempotencyKey>,
         function_name: String,
         params: Vec<ProtoVal>,
+        blob_params: Vec<BlobParameter>,
         invocation_context: Option<InvocationContext>,
         metadata: WorkerRequestMetadata,
     ) -> WorkerResult<TypeAnnotatedValue> {
+        self.check_maintenance(&worker_id.component_id)?;
+        self.clear_missing_worker(worker_id);
+        self.limits.check_invocation(&params, &blob_params)?;
+        validate_blob_parameters(params.len(), &blob_params)
+            .map_err(WorkerServiceError::InvalidBlobParameter)?;
+
+        let cache_key = idempotency_cache_key(worker_id, idempotency_key.as_ref(), true);
+        if let Some(result) = self
+            .cached_result::<ProtoTypeAnnotatedValue>(&cache_key)
+            .await
+            .and_then(|cached| cached.type_annotated_value)
+        {
+            return Ok(result);
+        }
+
+        let request = invoke_and_await_request(
+            worker_id,
+            idempotency_key,
+            function_name.clone(),
+            params,
+            blob_params,
+            invocation_context,
+            metadata,
+        );
         let worker_id = worker_id.clone();
         let worker_id_clone = worker_id.clone();
         let function_name_clone = function_name.clone();
@@ -451,18 +1440,7 @@ where
             worker_id.clone(),
             move |worker_executor_client| {
                 info!("Invoking function on {}: {}", worker_id_clone, function_name);
-                Box::pin(worker_executor_client.invoke_and_await_worker_typed(
-                    InvokeAndAwaitWorkerRequest {
-                        worker_id: Some(worker_id_clone.clone().into()),
-                        name: function_name.clone(),
-                        input: params.clone(),
-                        idempotency_key: idempotency_key.clone().map(|v| v.into()),
-                        account_id: metadata.account_id.clone().map(|id| id.into()),
-                        account_limits: metadata.limits.clone().map(|id| id.into()),
-                        context: invocation_context.clone(),
-                    }
-                )
-                )
+                Box::pin(worker_executor_client.invoke_and_await_worker_typed(request.clone()))
             },
             move |response| {
                 match response.into_inner() {
@@ -482,6 +1460,7 @@ where
                         Some(workerexecutor::v1::invoke_and_await_worker_response_typed::Result::Failure(err)),
                     } => {
                         error!("Invoked function on {}: {} failed with {err:?}", worker_id, function_name_clone);
+                        record_rejected_parameters(&worker_id, &function_name_clone, &err);
                         Err(err.into())
                     }
                     workerexecutor::v1::InvokeAndAwaitWorkerResponseTyped { .. } => {
@@ -493,112 +1472,265 @@ where
             WorkerServiceError::InternalCallError,
         ).await?;
 
+        if cache_key.is_some() {
+            let cached = ProtoTypeAnnotatedValue {
+                type_annotated_value: Some(invoke_response.clone()),
+            };
+            self.cache_result(&cache_key, &cached).await;
+        }
+
         Ok(invoke_response)
     }
 
-    async fn invoke_and_await(
+    async fn invoke_and_await_function_stream(
         &self,
         worker_id: &TargetWorkerId,
         idempotency_key: Option<IdempotencyKey>,
         function_name: String,
         params: Vec<ProtoVal>,
+        blob_params: Vec<BlobParameter>,
         invocation_context: Option<InvocationContext>,
         metadata: WorkerRequestMetadata,
-    ) -> WorkerResult<InvokeResult> {
+    ) -> WorkerResult<InvocationResultStream> {
+        self.check_maintenance(&worker_id.component_id)?;
+        self.clear_missing_worker(worker_id);
+        self.limits.check_invocation(&params, &blob_params)?;
+        validate_blob_parameters(params.len(), &blob_params)
+            .map_err(WorkerServiceError::InvalidBlobParameter)?;
+
+        let request = invoke_and_await_request(
+            worker_id,
+            idempotency_key,
+            function_name.clone(),
+            params,
+            blob_params,
+            invocation_context,
+            metadata,
+        );
         let worker_id = worker_id.clone();
         let worker_id_clone = worker_id.clone();
+        let function_name_clone = function_name.clone();
 
-        let invoke_response = self.call_worker_executor(
-            worker_id.clone(),
-            move |worker_executor_client| {
-                info!("Invoke and await function");
-                Box::pin(worker_executor_client.invoke_and_await_worker(
-                    workerexecutor::v1::InvokeAndAwaitWorkerRequest {
-                        worker_id: Some(worker_id_clone.clone().into()),
-                        name: function_name.clone(),
-                        input: params.clone(),
-                        idempotency_key: idempotency_key.clone().map(|k| k.into()),
-                        account_id: metadata.account_id.clone().map(|id| id.into()),
-                        account_limits: metadata.limits.clone().map(|id| id.into()),
-                        context: invocation_context.clone(),
+        let stream = self
+            .call_worker_executor(
+                worker_id.clone(),
+                move |worker_executor_client| {
+                    info!(
+                        "Invoking function on {} streaming its results: {}",
+                        worker_id_clone, function_name
+                    );
+                    let request = request.clone();
+                    Box::pin(async move {
+                        // Failures are sent instead of the header, so it is awaited here to let
+                        // them be retried on another executor the same way as other calls
+                        let mut streaming = worker_executor_client
+                            .invoke_and_await_worker_streaming(request)
+                            .await?
+                            .into_inner();
+                        let first = streaming.message().await?;
+                        Ok((first, streaming))
+                    })
+                },
+                move |(first, streaming)| match first {
+                    Some(workerexecutor::v1::InvokeAndAwaitWorkerStreamingResponse {
+                        result:
+                            Some(
+                                workerexecutor::v1::invoke_and_await_worker_streaming_response::Result::Header(
+                                    header,
+                                ),
+                            ),
+                    }) => {
+                        info!("Invoked function on {}: {}", worker_id, function_name_clone);
+                        Ok(InvocationResultStream::new(header.list_elements, streaming))
                     }
-                )
-                )
-            },
-            move |response| {
-                match response.into_inner() {
-                    workerexecutor::v1::InvokeAndAwaitWorkerResponse {
+                    Some(workerexecutor::v1::InvokeAndAwaitWorkerStreamingResponse {
                         result:
-                        Some(workerexecutor::v1::invoke_and_await_worker_response::Result::Success(
-                                 workerexecutor::v1::InvokeAndAwaitWorkerSuccess {
-                                     output,
-                                 },
-                             )),
-                    } => {
-                        Ok(InvokeResult { result: output })
+                            Some(
+                                workerexecutor::v1::invoke_and_await_worker_streaming_response::Result::Chunk(
+                                    _,
+                                ),
+                            ),
+                    }) => {
+                        error!(
+                            "Invoked function on {}: {} failed with missing result header",
+                            worker_id, function_name_clone
+                        );
+                        Err("Missing result header".into())
                     }
-                    workerexecutor::v1::InvokeAndAwaitWorkerResponse {
+                    Some(workerexecutor::v1::InvokeAndAwaitWorkerStreamingResponse {
                         result:
-                        Some(workerexecutor::v1::invoke_and_await_worker_response::Result::Failure(err)),
-                    } => {
-                        error!("Invoked function error: {err:?}");
+                            Some(
+                                workerexecutor::v1::invoke_and_await_worker_streaming_response::Result::Failure(
+                                    err,
+                                ),
+                            ),
+                    }) => {
+                        error!(
+                            "Invoked function on {}: {} failed with {err:?}",
+                            worker_id, function_name_clone
+                        );
+                        record_rejected_parameters(&worker_id, &function_name_clone, &err);
                         Err(err.into())
                     }
-                    workerexecutor::v1::InvokeAndAwaitWorkerResponse { .. } => {
-                        error!("Invoked function failed with empty response");
+                    Some(workerexecutor::v1::InvokeAndAwaitWorkerStreamingResponse {
+                        result: None,
+                    }) => {
+                        error!(
+                            "Invoked function on {}: {} failed with empty response",
+                            worker_id, function_name_clone
+                        );
                         Err("Empty response".into())
                     }
-                }
-            },
-            WorkerServiceError::InternalCallError,
-        ).await?;
+                    None => {
+                        error!(
+                            "Invoked function on {}: {} failed with empty response",
+                            worker_id, function_name_clone
+                        );
+                        Err("Empty response".into())
+                    }
+                },
+                WorkerServiceError::InternalCallError,
+            )
+            .await?;
 
-        Ok(invoke_response)
+        Ok(stream)
     }
 
-    async fn invoke(
+    async fn invoke_and_await_function_wave(
         &self,
         worker_id: &TargetWorkerId,
         idempotency_key: Option<IdempotencyKey>,
         function_name: String,
-        params: Vec<ProtoVal>,
+        params: Vec<String>,
         invocation_context: Option<InvocationContext>,
         metadata: WorkerRequestMetadata,
-    ) -> WorkerResult<()> {
-        let worker_id = worker_id.clone();
-        self.call_worker_executor(
-            worker_id.clone(),
-            move |worker_executor_client| {
-                info!("Invoke function");
-                let worker_id = worker_id.clone();
-                Box::pin(worker_executor_client.invoke_worker(
-                    workerexecutor::v1::InvokeWorkerRequest {
-                        worker_id: Some(worker_id.into()),
-                        idempotency_key: idempotency_key.clone().map(|k| k.into()),
-                        name: function_name.clone(),
-                        input: params.clone(),
-                        account_id: metadata.account_id.clone().map(|id| id.into()),
-                        account_limits: metadata.limits.clone().map(|id| id.into()),
-                        context: invocation_context.clone(),
-                    },
-                ))
-            },
-            |response| match response.into_inner() {
-                workerexecutor::v1::InvokeWorkerResponse {
-                    result: Some(workerexecutor::v1::invoke_worker_response::Result::Success(_)),
-                } => Ok(()),
-                workerexecutor::v1::InvokeWorkerResponse {
-                    result: Some(workerexecutor::v1::invoke_worker_response::Result::Failure(err)),
-                } => {
-                    error!("Invoked function error: {err:?}");
-                    Err(err.into())
-                }
-                workerexecutor::v1::InvokeWorkerResponse { .. } => Err("Empty response".into()),
-            },
-            WorkerServiceError::InternalCallError,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<TypeAnnotatedValue> {
+        let component = self
+            .get_component_for_target(worker_id, metadata.clone(), auth_ctx)
+            .await?;
+        let function_name = component
+            .metadata
+            .resolve_function_name(&function_name)
+            .to_string();
+
+        let start = Instant::now();
+        let params = parse_wave_parameters(&component.metadata.exports, &function_name, &params);
+        record_type_check(start.elapsed());
+        let params = match params {
+            Ok(params) => self.validate_typed_parameters(params)?,
+            Err(errors) => {
+                let exported = function_by_name(&component.metadata.exports, &function_name)
+                    .ok()
+                    .flatten()
+                    .is_some();
+                record_type_check_failure(
+                    &worker_id.component_id.to_string(),
+                    exported.then_some(function_name.as_str()),
+                );
+                return Err(WorkerServiceError::TypeChecker(errors.join(", ")));
+            }
+        };
+
+        self.invoke_and_await_typed(
+            worker_id,
+            idempotency_key,
+            function_name,
+            params,
+            Vec::new(),
+            invocation_context,
+            metadata,
         )
-        .await?;
-        Ok(())
+        .await
+    }
+
+    async fn invoke_and_await(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        params: Vec<ProtoVal>,
+        blob_params: Vec<BlobParameter>,
+        invocation_context: Option<InvocationContext>,
+        metadata: WorkerRequestMetadata,
+    ) -> WorkerResult<InvokeResult> {
+        self.check_maintenance(&worker_id.component_id)?;
+        self.clear_missing_worker(worker_id);
+        self.limits.check_invocation(&params, &blob_params)?;
+        validate_blob_parameters(params.len(), &blob_params)
+            .map_err(WorkerServiceError::InvalidBlobParameter)?;
+
+        let cache_key = idempotency_cache_key(worker_id, idempotency_key.as_ref(), false);
+        if let Some(result) = self.cached_result::<InvokeResult>(&cache_key).await {
+            return Ok(result);
+        }
+        let invocation_context = self
+            .with_worker_defaults(worker_id, invocation_context, &metadata)
+            .await?;
+
+        let request = invoke_and_await_request(
+            worker_id,
+            idempotency_key,
+            function_name.clone(),
+            params,
+            blob_params,
+            invocation_context,
+            metadata,
+        );
+        let invoke_response =
+            invoke_and_await_on_executor(self, worker_id, function_name, request).await?;
+
+        self.cache_result(&cache_key, &invoke_response).await;
+
+        Ok(invoke_response)
+    }
+
+    async fn invoke(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        params: Vec<ProtoVal>,
+        blob_params: Vec<BlobParameter>,
+        invocation_context: Option<InvocationContext>,
+        metadata: WorkerRequestMetadata,
+    ) -> WorkerResult<()> {
+        self.check_maintenance(&worker_id.component_id)?;
+        self.clear_missing_worker(worker_id);
+        self.limits.check_invocation(&params, &blob_params)?;
+        validate_blob_parameters(params.len(), &blob_params)
+            .map_err(WorkerServiceError::InvalidBlobParameter)?;
+
+        let invocation_context = self
+            .with_worker_defaults(worker_id, invocation_context, &metadata)
+            .await?;
+
+        let idempotency_key = invocation_idempotency_key(idempotency_key);
+        let request = workerexecutor::v1::InvokeWorkerRequest {
+            worker_id: Some(worker_id.clone().into()),
+            idempotency_key: Some(idempotency_key.into()),
+            name: function_name,
+            input: params,
+            account_id: metadata.account_id.map(|id| id.into()),
+            account_limits: metadata.limits.map(|id| id.into()),
+            context: invocation_context,
+            blob_input: blob_params,
+        };
+
+        match &self.invocation_queue {
+            Some(queue) => {
+                match tokio::time::timeout(
+                    queue.enqueue_after(),
+                    send_invoke_request(self, worker_id.clone(), request.clone()),
+                )
+                .await
+                {
+                    Ok(result) => result,
+                    Err(_) => queue.enqueue(worker_id, request).await,
+                }
+            }
+            None => send_invoke_request(self, worker_id.clone(), request).await,
+        }
     }
 
     async fn complete_promise(
@@ -609,6 +1741,7 @@ where
         metadata: WorkerRequestMetadata,
         _auth_ctx: &AuthCtx,
     ) -> WorkerResult<bool> {
+        self.check_worker_exists(worker_id)?;
         let promise_id = PromiseId {
             worker_id: worker_id.clone(),
             oplog_idx: OplogIndex::from_u64(oplog_id),
@@ -662,6 +1795,7 @@ where
         metadata: WorkerRequestMetadata,
         _auth_ctx: &AuthCtx,
     ) -> WorkerResult<()> {
+        self.check_worker_exists(worker_id)?;
         let worker_id = worker_id.clone();
         self.call_worker_executor(
             worker_id.clone(),
@@ -673,6 +1807,7 @@ where
                         worker_id: Some(worker_id.into()),
                         recover_immediately,
                         account_id: metadata.account_id.clone().map(|id| id.into()),
+                        idempotency_key: None,
                     }),
                 )
             },
@@ -693,96 +1828,156 @@ where
         Ok(())
     }
 
-    async fn get_metadata(
+    async fn abort_running_invocation(
         &self,
         worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
         metadata: WorkerRequestMetadata,
         _auth_ctx: &AuthCtx,
-    ) -> WorkerResult<WorkerMetadata> {
+    ) -> WorkerResult<bool> {
         let worker_id = worker_id.clone();
-        let metadata = self.call_worker_executor(
-            worker_id.clone(),
-            move |worker_executor_client| {
-                let worker_id = worker_id.clone();
-                info!("Get metadata");
-                Box::pin(worker_executor_client.get_worker_metadata(
-                    workerexecutor::v1::GetWorkerMetadataRequest {
-                        worker_id: Some(golem_api_grpc::proto::golem::worker::WorkerId::from(worker_id)),
-                        account_id: metadata.account_id.clone().map(|id| id.into()),
-                    }
-                ))
-            },
-            |response| {
-                match response.into_inner() {
-                    workerexecutor::v1::GetWorkerMetadataResponse {
+        let idempotency_key = idempotency_key.clone();
+        let aborted = self
+            .call_worker_executor(
+                worker_id.clone(),
+                move |worker_executor_client| {
+                    info!("Abort running invocation");
+                    let worker_id = worker_id.clone();
+                    Box::pin(worker_executor_client.abort_running_invocation(
+                        AbortRunningInvocationRequest {
+                            worker_id: Some(worker_id.into()),
+                            idempotency_key: Some(idempotency_key.clone().into()),
+                            account_id: metadata.account_id.clone().map(|id| id.into()),
+                        },
+                    ))
+                },
+                |response| match response.into_inner() {
+                    workerexecutor::v1::AbortRunningInvocationResponse {
                         result:
-                        Some(workerexecutor::v1::get_worker_metadata_response::Result::Success(metadata)),
-                    } => {
-                        Ok(metadata.try_into().unwrap())
-                    }
-                    workerexecutor::v1::GetWorkerMetadataResponse {
+                            Some(workerexecutor::v1::abort_running_invocation_response::Result::Success(
+                                success,
+                            )),
+                    } => Ok(success.aborted),
+                    workerexecutor::v1::AbortRunningInvocationResponse {
                         result:
-                        Some(workerexecutor::v1::get_worker_metadata_response::Result::Failure(err)),
-                    } => {
-                        error!("Get metadata error: {err:?}");
-                        Err(err.into())
+                            Some(workerexecutor::v1::abort_running_invocation_response::Result::Failure(
+                                err,
+                            )),
+                    } => Err(err.into()),
+                    workerexecutor::v1::AbortRunningInvocationResponse { .. } => {
+                        Err("Empty response".into())
                     }
-                    workerexecutor::v1::GetWorkerMetadataResponse { .. } => {
+                },
+                WorkerServiceError::InternalCallError,
+            )
+            .await?;
+
+        Ok(aborted)
+    }
+
+    async fn cancel_invocation(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<bool> {
+        let worker_id = worker_id.clone();
+        let idempotency_key = idempotency_key.clone();
+        let canceled = self
+            .call_worker_executor(
+                worker_id.clone(),
+                move |worker_executor_client| {
+                    info!("Cancel invocation");
+                    let worker_id = worker_id.clone();
+                    Box::pin(worker_executor_client.cancel_invocation(CancelInvocationRequest {
+                        worker_id: Some(worker_id.into()),
+                        idempotency_key: Some(idempotency_key.clone().into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                    }))
+                },
+                |response| match response.into_inner() {
+                    workerexecutor::v1::CancelInvocationResponse {
+                        result:
+                            Some(workerexecutor::v1::cancel_invocation_response::Result::Success(
+                                success,
+                            )),
+                    } => Ok(success.canceled),
+                    workerexecutor::v1::CancelInvocationResponse {
+                        result:
+                            Some(workerexecutor::v1::cancel_invocation_response::Result::Failure(
+                                err,
+                            )),
+                    } => Err(err.into()),
+                    workerexecutor::v1::CancelInvocationResponse { .. } => {
                         Err("Empty response".into())
                     }
-                }
-            },
-            WorkerServiceError::InternalCallError,
-        ).await?;
+                },
+                WorkerServiceError::InternalCallError,
+            )
+            .await?;
 
-        Ok(metadata)
+        Ok(canceled)
     }
 
-    async fn find_metadata(
+    async fn hold_invocation(
         &self,
-        component_id: &ComponentId,
-        filter: Option<WorkerFilter>,
-        cursor: ScanCursor,
-        count: u64,
-        precise: bool,
+        worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
         metadata: WorkerRequestMetadata,
-        auth_ctx: &AuthCtx,
-    ) -> WorkerResult<(Option<ScanCursor>, Vec<WorkerMetadata>)> {
-        info!("Find metadata");
-        if filter.as_ref().is_some_and(is_filter_with_running_status) {
-            let result = self
-                .find_running_metadata_internal(component_id, filter, auth_ctx)
-                .await?;
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()> {
+        let worker_id = worker_id.clone();
+        let idempotency_key = idempotency_key.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                info!("Hold invocation");
+                let worker_id = worker_id.clone();
+                Box::pin(
+                    worker_executor_client.interrupt_worker(InterruptWorkerRequest {
+                        worker_id: Some(worker_id.into()),
+                        recover_immediately: false,
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        idempotency_key: Some(idempotency_key.clone().into()),
+                    }),
+                )
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::InterruptWorkerResponse {
+                    result: Some(workerexecutor::v1::interrupt_worker_response::Result::Success(_)),
+                } => Ok(()),
+                workerexecutor::v1::InterruptWorkerResponse {
+                    result:
+                        Some(workerexecutor::v1::interrupt_worker_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::InterruptWorkerResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await?;
 
-            Ok((None, result.into_iter().take(count as usize).collect()))
-        } else {
-            self.find_metadata_internal(
-                component_id,
-                filter,
-                cursor,
-                count,
-                precise,
-                metadata,
-                auth_ctx,
-            )
-            .await
-        }
+        Ok(())
     }
 
-    async fn resume(
+    async fn release_invocation(
         &self,
         worker_id: &WorkerId,
+        idempotency_key: &IdempotencyKey,
         metadata: WorkerRequestMetadata,
         _auth_ctx: &AuthCtx,
     ) -> WorkerResult<()> {
         let worker_id = worker_id.clone();
+        let idempotency_key = idempotency_key.clone();
         self.call_worker_executor(
             worker_id.clone(),
             move |worker_executor_client| {
+                info!("Release invocation");
                 let worker_id = worker_id.clone();
                 Box::pin(worker_executor_client.resume_worker(ResumeWorkerRequest {
                     worker_id: Some(worker_id.into()),
                     account_id: metadata.account_id.clone().map(|id| id.into()),
+                    idempotency_key: Some(idempotency_key.clone().into()),
                 }))
             },
             |response| match response.into_inner() {
@@ -797,104 +1992,728 @@ where
             WorkerServiceError::InternalCallError,
         )
         .await?;
+
         Ok(())
     }
 
-    async fn update(
+    async fn schedule_invocation(
         &self,
         worker_id: &WorkerId,
-        update_mode: UpdateMode,
-        target_version: ComponentVersion,
+        idempotency_key: Option<IdempotencyKey>,
+        function_name: String,
+        params: Vec<TypeAnnotatedValue>,
+        scheduled_at: DateTime<Utc>,
         metadata: WorkerRequestMetadata,
         _auth_ctx: &AuthCtx,
-    ) -> WorkerResult<()> {
+    ) -> WorkerResult<IdempotencyKey> {
+        self.check_maintenance(&worker_id.component_id)?;
+        self.clear_missing_worker(worker_id);
+        let params = self.validate_typed_parameters(params)?;
+        self.limits.check_invocation(&params, &[])?;
+
+        let idempotency_key = invocation_idempotency_key(idempotency_key);
+        let scheduled_at = prost_types::Timestamp::from(SystemTime::from(scheduled_at));
+
         let worker_id = worker_id.clone();
+        let key = idempotency_key.clone();
         self.call_worker_executor(
             worker_id.clone(),
             move |worker_executor_client| {
-                info!("Update worker");
+                info!("Schedule invocation");
                 let worker_id = worker_id.clone();
-                Box::pin(worker_executor_client.update_worker(UpdateWorkerRequest {
-                    worker_id: Some(worker_id.into()),
-                    mode: update_mode.into(),
-                    target_version,
-                    account_id: metadata.account_id.clone().map(|id| id.into()),
-                }))
+                Box::pin(worker_executor_client.schedule_invocation(
+                    ScheduleInvocationRequest {
+                        worker_id: Some(worker_id.into()),
+                        name: function_name.clone(),
+                        input: params.clone(),
+                        idempotency_key: Some(key.clone().into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        account_limits: metadata.limits.clone().map(|id| id.into()),
+                        scheduled_at: Some(scheduled_at.clone()),
+                    },
+                ))
             },
             |response| match response.into_inner() {
-                workerexecutor::v1::UpdateWorkerResponse {
-                    result: Some(workerexecutor::v1::update_worker_response::Result::Success(_)),
+                workerexecutor::v1::ScheduleInvocationResponse {
+                    result:
+                        Some(workerexecutor::v1::schedule_invocation_response::Result::Success(_)),
                 } => Ok(()),
-                workerexecutor::v1::UpdateWorkerResponse {
-                    result: Some(workerexecutor::v1::update_worker_response::Result::Failure(err)),
+                workerexecutor::v1::ScheduleInvocationResponse {
+                    result:
+                        Some(workerexecutor::v1::schedule_invocation_response::Result::Failure(
+                            err,
+                        )),
                 } => Err(err.into()),
-                workerexecutor::v1::UpdateWorkerResponse { .. } => Err("Empty response".into()),
+                workerexecutor::v1::ScheduleInvocationResponse { .. } => {
+                    Err("Empty response".into())
+                }
             },
             WorkerServiceError::InternalCallError,
         )
         .await?;
-        Ok(())
-    }
 
-    async fn get_component_for_worker(
-        &self,
-        worker_id: &WorkerId,
-        metadata: WorkerRequestMetadata,
-        auth_ctx: &AuthCtx,
-    ) -> Result<Component, WorkerServiceError> {
-        self.try_get_component_for_worker(worker_id, metadata, auth_ctx)
-            .await
+        Ok(idempotency_key)
     }
 
-    async fn get_oplog(
+    async fn set_profiling(
         &self,
         worker_id: &WorkerId,
-        from_oplog_index: OplogIndex,
-        cursor: Option<OplogCursor>,
-        count: u64,
+        enabled: bool,
         metadata: WorkerRequestMetadata,
         _auth_ctx: &AuthCtx,
-    ) -> Result<GetOplogResponse, WorkerServiceError> {
+    ) -> WorkerResult<()> {
         let worker_id = worker_id.clone();
         self.call_worker_executor(
             worker_id.clone(),
             move |worker_executor_client| {
-                info!("Get oplog");
+                info!("Set worker profiling");
                 let worker_id = worker_id.clone();
                 Box::pin(
-                    worker_executor_client.get_oplog(workerexecutor::v1::GetOplogRequest {
+                    worker_executor_client.set_worker_profiling(SetWorkerProfilingRequest {
                         worker_id: Some(worker_id.into()),
-                        from_oplog_index: from_oplog_index.into(),
-                        cursor: cursor.clone().map(|c| c.into()),
-                        count,
                         account_id: metadata.account_id.clone().map(|id| id.into()),
+                        enabled,
                     }),
                 )
             },
             |response| match response.into_inner() {
-                workerexecutor::v1::GetOplogResponse {
+                workerexecutor::v1::SetWorkerProfilingResponse {
                     result:
-                        Some(workerexecutor::v1::get_oplog_response::Result::Success(
-                            workerexecutor::v1::GetOplogSuccessResponse {
-                                entries,
-                                next,
-                                first_index_in_chunk,
-                                last_index,
-                            },
-                        )),
-                } => Ok(GetOplogResponse {
-                    entries: entries
-                        .into_iter()
-                        .map(|e| e.try_into())
-                        .collect::<Result<Vec<_>, _>>()
-                        .map_err(|err| {
-                            GolemError::Unknown(GolemErrorUnknown {
-                                details: format!("Unexpected oplog entries in error: {err}"),
+                        Some(workerexecutor::v1::set_worker_profiling_response::Result::Success(_)),
+                } => Ok(()),
+                workerexecutor::v1::SetWorkerProfilingResponse {
+                    result:
+                        Some(workerexecutor::v1::set_worker_profiling_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::SetWorkerProfilingResponse { .. } => {
+                    Err("Empty response".into())
+                }
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_profile(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<u8>> {
+        let worker_id = worker_id.clone();
+        let profile = self
+            .call_worker_executor(
+                worker_id.clone(),
+                move |worker_executor_client| {
+                    info!("Get worker profile");
+                    let worker_id = worker_id.clone();
+                    Box::pin(worker_executor_client.get_worker_profile(GetWorkerProfileRequest {
+                        worker_id: Some(worker_id.into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                    }))
+                },
+                |response| match response.into_inner() {
+                    workerexecutor::v1::GetWorkerProfileResponse {
+                        result:
+                            Some(workerexecutor::v1::get_worker_profile_response::Result::Success(
+                                success,
+                            )),
+                    } => Ok(success.profile),
+                    workerexecutor::v1::GetWorkerProfileResponse {
+                        result:
+                            Some(workerexecutor::v1::get_worker_profile_response::Result::Failure(
+                                err,
+                            )),
+                    } => Err(err.into()),
+                    workerexecutor::v1::GetWorkerProfileResponse { .. } => {
+                        Err("Empty response".into())
+                    }
+                },
+                WorkerServiceError::InternalCallError,
+            )
+            .await?;
+
+        Ok(profile)
+    }
+
+    async fn get_metadata(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<WorkerMetadata> {
+        self.check_worker_exists(worker_id)?;
+        get_metadata_from_executor(self, worker_id, metadata).await
+    }
+
+    async fn get_metadata_many(
+        &self,
+        worker_ids: &[WorkerId],
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<WorkerMetadataMany> {
+        info!("Get metadata of {} workers", worker_ids.len());
+        Ok(get_metadata_many_from_executors(self, worker_ids, metadata).await)
+    }
+
+    async fn find_metadata(
+        &self,
+        component_id: &ComponentId,
+        filter: Option<WorkerFilter>,
+        cursor: ScanCursor,
+        count: u64,
+        precise: bool,
+        projection: Option<WorkerMetadataProjection>,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<(Option<ScanCursor>, Vec<WorkerMetadata>)> {
+        info!("Find metadata");
+        if filter.as_ref().is_some_and(is_filter_with_running_status) {
+            let result = self
+                .find_running_metadata_internal(component_id, filter, projection, auth_ctx)
+                .await?;
+
+            Ok((None, result.into_iter().take(count as usize).collect()))
+        } else {
+            self.find_metadata_internal(
+                component_id,
+                filter,
+                cursor,
+                count,
+                precise,
+                projection,
+                metadata,
+                auth_ctx,
+            )
+            .await
+        }
+    }
+
+    async fn search_workers(
+        &self,
+        component_id: &ComponentId,
+        query: WorkerNameQuery,
+        count: u64,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<WorkerId>> {
+        let component_id = component_id.clone();
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                info!("Search workers");
+                Box::pin(worker_executor_client.search_workers(
+                    workerexecutor::v1::SearchWorkersRequest {
+                        component_id: Some(component_id.clone().into()),
+                        query: Some(query.clone().into()),
+                        count,
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::SearchWorkersResponse {
+                    result:
+                        Some(workerexecutor::v1::search_workers_response::Result::Success(
+                            workerexecutor::v1::SearchWorkersSuccessResponse { worker_ids },
+                        )),
+                } => worker_ids
+                    .into_iter()
+                    .map(|id| id.try_into())
+                    .collect::<Result<Vec<WorkerId>, _>>()
+                    .map_err(|err| {
+                        GolemError::Unknown(GolemErrorUnknown {
+                            details: format!("Unexpected worker id in response: {err}"),
+                        })
+                        .into()
+                    }),
+                workerexecutor::v1::SearchWorkersResponse {
+                    result:
+                        Some(workerexecutor::v1::search_workers_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::SearchWorkersResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn set_deduplication_window(
+        &self,
+        component_id: &ComponentId,
+        window: Option<Duration>,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()> {
+        let component_id = component_id.clone();
+        // The window is stored in the storage shared by all executors
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                info!("Set deduplication window");
+                Box::pin(worker_executor_client.set_deduplication_window(
+                    workerexecutor::v1::SetDeduplicationWindowRequest {
+                        component_id: Some(component_id.clone().into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        window_millis: window.map(|window| window.as_millis() as u64),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::SetDeduplicationWindowResponse {
+                    result:
+                        Some(workerexecutor::v1::set_deduplication_window_response::Result::Success(_)),
+                } => Ok(()),
+                workerexecutor::v1::SetDeduplicationWindowResponse {
+                    result:
+                        Some(workerexecutor::v1::set_deduplication_window_response::Result::Failure(
+                            err,
+                        )),
+                } => Err(err.into()),
+                workerexecutor::v1::SetDeduplicationWindowResponse { .. } => {
+                    Err("Empty response".into())
+                }
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn get_error_groups(
+        &self,
+        component_id: &ComponentId,
+        limit: u64,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<ErrorGroup>> {
+        let mut groups = Vec::new();
+        let mut cursor = Some(ScanCursor::default());
+        // The workers are scanned page by page, so no executor call has to go through all of them
+        while let Some(current_cursor) = cursor {
+            let component_id = component_id.clone();
+            let metadata = metadata.clone();
+            // Any executor can scan the workers of a component in the shared storage
+            let (next_cursor, page_groups) = self
+                .call_worker_executor(
+                    RandomExecutor,
+                    move |worker_executor_client| {
+                        info!("Get error groups");
+                        Box::pin(worker_executor_client.get_error_groups(
+                            workerexecutor::v1::GetErrorGroupsRequest {
+                                component_id: Some(component_id.clone().into()),
+                                account_id: metadata.account_id.clone().map(|id| id.into()),
+                                cursor: Some(current_cursor.clone().into()),
+                                count: ERROR_GROUPS_SCAN_PAGE_SIZE,
+                            },
+                        ))
+                    },
+                    |response| match response.into_inner() {
+                        workerexecutor::v1::GetErrorGroupsResponse {
+                            result:
+                                Some(workerexecutor::v1::get_error_groups_response::Result::Success(
+                                    workerexecutor::v1::GetErrorGroupsSuccessResponse {
+                                        groups,
+                                        cursor,
+                                    },
+                                )),
+                        } => groups
+                            .into_iter()
+                            .map(|group| group.try_into())
+                            .collect::<Result<Vec<ErrorGroup>, _>>()
+                            .map(|groups| (cursor.map(|cursor| cursor.into()), groups))
+                            .map_err(|err| {
+                                GolemError::Unknown(GolemErrorUnknown {
+                                    details: format!("Unexpected error group in response: {err}"),
+                                })
+                                .into()
+                            }),
+                        workerexecutor::v1::GetErrorGroupsResponse {
+                            result:
+                                Some(workerexecutor::v1::get_error_groups_response::Result::Failure(
+                                    err,
+                                )),
+                        } => Err(err.into()),
+                        workerexecutor::v1::GetErrorGroupsResponse { .. } => {
+                            Err("Empty response".into())
+                        }
+                    },
+                    WorkerServiceError::InternalCallError,
+                )
+                .await?;
+
+            groups = ErrorGroup::merge(groups.into_iter().chain(page_groups));
+            cursor = next_cursor;
+        }
+
+        if limit > 0 {
+            groups.truncate(limit as usize);
+        }
+        Ok(groups)
+    }
+
+    async fn get_function_stats(
+        &self,
+        component_id: &ComponentId,
+        _metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<FunctionStats>> {
+        let component_id = component_id.clone();
+        // Each executor only knows about the invocations it executed
+        let stats = self
+            .call_worker_executor(
+                AllExecutors,
+                move |worker_executor_client| {
+                    Box::pin(worker_executor_client.get_function_stats(
+                        workerexecutor::v1::GetFunctionStatsRequest {
+                            component_id: Some(component_id.clone().into()),
+                        },
+                    ))
+                },
+                |responses| {
+                    responses
+                        .into_iter()
+                        .map(|response| match response.into_inner() {
+                            workerexecutor::v1::GetFunctionStatsResponse {
+                                result:
+                                    Some(
+                                        workerexecutor::v1::get_function_stats_response::Result::Success(
+                                            workerexecutor::v1::GetFunctionStatsSuccessResponse {
+                                                functions,
+                                            },
+                                        ),
+                                    ),
+                            } => Ok(functions),
+                            workerexecutor::v1::GetFunctionStatsResponse {
+                                result:
+                                    Some(
+                                        workerexecutor::v1::get_function_stats_response::Result::Failure(
+                                            err,
+                                        ),
+                                    ),
+                            } => Err(err.into()),
+                            workerexecutor::v1::GetFunctionStatsResponse { .. } => {
+                                Err("Empty response".into())
+                            }
+                        })
+                        .collect::<Result<Vec<_>, ResponseMapResult>>()
+                },
+                WorkerServiceError::InternalCallError,
+            )
+            .await?;
+
+        Ok(FunctionStats::aggregate(
+            stats.into_iter().flatten().collect(),
+        ))
+    }
+
+    async fn resume(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()> {
+        self.check_worker_exists(worker_id)?;
+        let worker_id = worker_id.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                let worker_id = worker_id.clone();
+                Box::pin(worker_executor_client.resume_worker(ResumeWorkerRequest {
+                    worker_id: Some(worker_id.into()),
+                    account_id: metadata.account_id.clone().map(|id| id.into()),
+                    idempotency_key: None,
+                }))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::ResumeWorkerResponse {
+                    result: Some(workerexecutor::v1::resume_worker_response::Result::Success(_)),
+                } => Ok(()),
+                workerexecutor::v1::ResumeWorkerResponse {
+                    result: Some(workerexecutor::v1::resume_worker_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::ResumeWorkerResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn update(
+        &self,
+        worker_id: &WorkerId,
+        update_mode: UpdateMode,
+        target_version: ComponentVersion,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()> {
+        self.check_worker_exists(worker_id)?;
+        let worker_id = worker_id.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                info!("Update worker");
+                let worker_id = worker_id.clone();
+                Box::pin(worker_executor_client.update_worker(UpdateWorkerRequest {
+                    worker_id: Some(worker_id.into()),
+                    mode: update_mode.into(),
+                    target_version,
+                    account_id: metadata.account_id.clone().map(|id| id.into()),
+                }))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::UpdateWorkerResponse {
+                    result: Some(workerexecutor::v1::update_worker_response::Result::Success(_)),
+                } => Ok(()),
+                workerexecutor::v1::UpdateWorkerResponse {
+                    result: Some(workerexecutor::v1::update_worker_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::UpdateWorkerResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn update_env(
+        &self,
+        worker_id: &WorkerId,
+        environment_variables: HashMap<String, String>,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()> {
+        self.check_worker_exists(worker_id)?;
+        self.limits.check_create(&[], &environment_variables)?;
+        let worker_id = worker_id.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                info!("Update worker environment");
+                let worker_id = worker_id.clone();
+                Box::pin(worker_executor_client.update_worker_env(
+                    workerexecutor::v1::UpdateWorkerEnvRequest {
+                        worker_id: Some(worker_id.into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        env: environment_variables.clone(),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::UpdateWorkerEnvResponse {
+                    result:
+                        Some(workerexecutor::v1::update_worker_env_response::Result::Success(_)),
+                } => Ok(()),
+                workerexecutor::v1::UpdateWorkerEnvResponse {
+                    result:
+                        Some(workerexecutor::v1::update_worker_env_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::UpdateWorkerEnvResponse { .. } => {
+                    Err("Empty response".into())
+                }
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn redact_oplog(
+        &self,
+        worker_id: &WorkerId,
+        oplog_indices: Vec<OplogIndex>,
+        reason: String,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<OplogIndex> {
+        self.check_worker_exists(worker_id)?;
+        let worker_id = worker_id.clone();
+        let oplog_idx = self
+            .call_worker_executor(
+                worker_id.clone(),
+                move |worker_executor_client| {
+                    info!("Redact oplog entries");
+                    let worker_id = worker_id.clone();
+                    Box::pin(worker_executor_client.redact_oplog(
+                        workerexecutor::v1::RedactOplogRequest {
+                            worker_id: Some(worker_id.into()),
+                            account_id: metadata.account_id.clone().map(|id| id.into()),
+                            oplog_indices: oplog_indices.iter().map(|idx| (*idx).into()).collect(),
+                            reason: reason.clone(),
+                        },
+                    ))
+                },
+                |response| match response.into_inner() {
+                    workerexecutor::v1::RedactOplogResponse {
+                        result:
+                            Some(workerexecutor::v1::redact_oplog_response::Result::Success(success)),
+                    } => Ok(OplogIndex::from_u64(success.oplog_index)),
+                    workerexecutor::v1::RedactOplogResponse {
+                        result:
+                            Some(workerexecutor::v1::redact_oplog_response::Result::Failure(err)),
+                    } => Err(err.into()),
+                    workerexecutor::v1::RedactOplogResponse { .. } => Err("Empty response".into()),
+                },
+                WorkerServiceError::InternalCallError,
+            )
+            .await?;
+        Ok(oplog_idx)
+    }
+
+    async fn enable_shadow(
+        &self,
+        worker_id: &WorkerId,
+        component_version: ComponentVersion,
+        compare_results: bool,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<WorkerId> {
+        reject_shadow_worker_name(worker_id)?;
+        let worker_metadata = self
+            .get_metadata(worker_id, metadata.clone(), auth_ctx)
+            .await?;
+
+        let shadow_worker_id = shadow_worker_id(worker_id);
+        match self
+            .create_worker(
+                &shadow_worker_id,
+                component_version,
+                worker_metadata.args,
+                worker_metadata.env,
+                None,
+                None,
+                metadata.clone(),
+            )
+            .await
+        {
+            Ok(_) | Err(WorkerServiceError::Golem(GolemError::WorkerAlreadyExists(_))) => {}
+            Err(err) => return Err(err),
+        }
+
+        self.set_shadow(
+            worker_id,
+            Some(WorkerShadow {
+                shadow_worker_id: shadow_worker_id.clone(),
+                compare_results,
+            }),
+            metadata,
+        )
+        .await?;
+
+        Ok(shadow_worker_id)
+    }
+
+    async fn disable_shadow(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()> {
+        self.set_shadow(worker_id, None, metadata).await
+    }
+
+    async fn set_ttl(
+        &self,
+        worker_id: &WorkerId,
+        ttl: Option<Duration>,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<()> {
+        let worker_id = worker_id.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                info!("Set worker TTL");
+                let worker_id = worker_id.clone();
+                Box::pin(worker_executor_client.set_worker_ttl(
+                    workerexecutor::v1::SetWorkerTtlRequest {
+                        worker_id: Some(worker_id.into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        ttl_millis: ttl.map(|ttl| ttl.as_millis() as u64),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::SetWorkerTtlResponse {
+                    result: Some(workerexecutor::v1::set_worker_ttl_response::Result::Success(_)),
+                } => Ok(()),
+                workerexecutor::v1::SetWorkerTtlResponse {
+                    result: Some(workerexecutor::v1::set_worker_ttl_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::SetWorkerTtlResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn get_component_for_worker(
+        &self,
+        worker_id: &WorkerId,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> Result<Component, WorkerServiceError> {
+        self.try_get_component_for_worker(worker_id, metadata, auth_ctx)
+            .await
+    }
+
+    async fn get_oplog(
+        &self,
+        worker_id: &WorkerId,
+        from_oplog_index: OplogIndex,
+        cursor: Option<OplogCursor>,
+        count: u64,
+        query: Option<OplogQuery>,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> Result<GetOplogResponse, WorkerServiceError> {
+        self.check_worker_exists(worker_id)?;
+        let worker_id = worker_id.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                info!("Get oplog");
+                let worker_id = worker_id.clone();
+                Box::pin(
+                    worker_executor_client.get_oplog(workerexecutor::v1::GetOplogRequest {
+                        worker_id: Some(worker_id.into()),
+                        from_oplog_index: from_oplog_index.into(),
+                        cursor: cursor.clone().map(|c| c.into()),
+                        count,
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        query: query.clone().map(|q| q.into()),
+                    }),
+                )
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::GetOplogResponse {
+                    result:
+                        Some(workerexecutor::v1::get_oplog_response::Result::Success(
+                            workerexecutor::v1::GetOplogSuccessResponse {
+                                entries,
+                                next,
+                                first_index_in_chunk,
+                                last_index,
+                                indices,
+                            },
+                        )),
+                } => Ok(GetOplogResponse {
+                    entries: entries
+                        .into_iter()
+                        .map(|e| e.try_into())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|err| {
+                            GolemError::Unknown(GolemErrorUnknown {
+                                details: format!("Unexpected oplog entries in error: {err}"),
                             })
                         })?,
                     next: next.map(|c| c.into()),
                     first_index_in_chunk,
                     last_index,
+                    indices,
                 }),
                 workerexecutor::v1::GetOplogResponse {
                     result: Some(workerexecutor::v1::get_oplog_response::Result::Failure(err)),
@@ -906,6 +2725,242 @@ where
         .await
     }
 
+    async fn get_raw_oplog(
+        &self,
+        worker_id: &WorkerId,
+        from_oplog_index: OplogIndex,
+        count: u64,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> Result<GetRawOplogResponse, WorkerServiceError> {
+        self.check_worker_exists(worker_id)?;
+        let worker_id = worker_id.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                info!("Get raw oplog");
+                let worker_id = worker_id.clone();
+                Box::pin(worker_executor_client.get_raw_oplog(
+                    workerexecutor::v1::GetRawOplogRequest {
+                        worker_id: Some(worker_id.into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        from_oplog_index: from_oplog_index.into(),
+                        count,
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::GetRawOplogResponse {
+                    result:
+                        Some(workerexecutor::v1::get_raw_oplog_response::Result::Success(response)),
+                } => Ok(raw_oplog_response(response)?),
+                workerexecutor::v1::GetRawOplogResponse {
+                    result: Some(workerexecutor::v1::get_raw_oplog_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::GetRawOplogResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn upload_invocation_parameter(
+        &self,
+        component_id: &ComponentId,
+        data: Box<dyn AsyncRead + Send + Unpin>,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<UploadInvocationParameterResponse> {
+        let max_size = self.limits.max_blob_parameter_size;
+        let mut payload = Vec::new();
+        data.take(max_size as u64 + 1)
+            .read_to_end(&mut payload)
+            .await
+            .map_err(|err| WorkerServiceError::Internal(format!("Failed to read upload: {err}")))?;
+        self.limits.check_blob_parameter_size(payload.len())?;
+
+        let key = format!("{}/{}", component_id, Uuid::new_v4());
+        let mut chunks = payload
+            .chunks(BLOB_PARAMETER_CHUNK_SIZE)
+            .map(|chunk| chunk.to_vec())
+            .collect::<Vec<_>>();
+        if chunks.is_empty() {
+            chunks.push(vec![]);
+        }
+
+        let upload_key = key.clone();
+        // The parameters are stored in the storage shared by all executors
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                info!("Upload invocation parameter");
+                let requests = chunks
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, chunk)| workerexecutor::v1::PutInvocationParameterRequest {
+                        account_id: if idx == 0 {
+                            metadata.account_id.clone().map(|id| id.into())
+                        } else {
+                            None
+                        },
+                        key: if idx == 0 {
+                            upload_key.clone()
+                        } else {
+                            String::new()
+                        },
+                        chunk: chunk.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                Box::pin(
+                    worker_executor_client
+                        .put_invocation_parameter(futures_util::stream::iter(requests)),
+                )
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::PutInvocationParameterResponse {
+                    result:
+                        Some(workerexecutor::v1::put_invocation_parameter_response::Result::Success(_)),
+                } => Ok(()),
+                workerexecutor::v1::PutInvocationParameterResponse {
+                    result:
+                        Some(workerexecutor::v1::put_invocation_parameter_response::Result::Failure(
+                            err,
+                        )),
+                } => Err(err.into()),
+                workerexecutor::v1::PutInvocationParameterResponse { .. } => {
+                    Err("Empty response".into())
+                }
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await?;
+
+        Ok(UploadInvocationParameterResponse {
+            key,
+            size: payload.len() as u64,
+        })
+    }
+
+    async fn get_invocation_result_blob(
+        &self,
+        blob_key: &str,
+        metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<u8>> {
+        let blob_key = blob_key.to_string();
+        // The results are stored in the storage shared by all executors
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                info!("Get invocation result blob {}", blob_key);
+                let request = workerexecutor::v1::GetInvocationResultBlobRequest {
+                    account_id: metadata.account_id.clone().map(|id| id.into()),
+                    blob_key: blob_key.clone(),
+                };
+                Box::pin(async move {
+                    let mut streaming = worker_executor_client
+                        .get_invocation_result_blob(request)
+                        .await?
+                        .into_inner();
+                    let mut responses = Vec::new();
+                    while let Some(response) = streaming.message().await? {
+                        responses.push(response);
+                    }
+                    Ok(responses)
+                })
+            },
+            |responses| {
+                let mut payload = Vec::new();
+                for response in responses {
+                    match response.result {
+                        Some(workerexecutor::v1::get_invocation_result_blob_response::Result::Chunk(
+                            chunk,
+                        )) => payload.extend(chunk),
+                        Some(workerexecutor::v1::get_invocation_result_blob_response::Result::Failure(
+                            err,
+                        )) => return Err(err.into()),
+                        None => return Err("Empty response".into()),
+                    }
+                }
+                Ok(payload)
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
+    async fn poll_events(
+        &self,
+        component_id: &ComponentId,
+        topic: &str,
+        cursor: u64,
+        count: u64,
+        _metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<PollEventsResponse> {
+        let events = self
+            .domain_event_poller()
+            .poll(component_id, topic, cursor, count)
+            .await?
+            .into_iter()
+            .map(|event| event.try_into())
+            .collect::<Result<Vec<DomainEvent>, _>>()
+            .map_err(|err| {
+                WorkerServiceError::Internal(format!("Unexpected event in response: {err}"))
+            })?;
+        let next = events.last().map(|event| event.id).unwrap_or(cursor);
+        Ok(PollEventsResponse { events, next })
+    }
+
+    async fn subscribe_events(
+        &self,
+        component_id: &ComponentId,
+        topic: &str,
+        cursor: u64,
+        _metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<DomainEventStream> {
+        Ok(DomainEventStream::new(
+            self.domain_event_poller(),
+            component_id.clone(),
+            topic.to_string(),
+            cursor,
+        ))
+    }
+
+    async fn watch_workers(
+        &self,
+        component_id: &ComponentId,
+        filter: Option<WorkerFilter>,
+        _metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<WorkerStatusEventStream> {
+        let component_id = component_id.clone();
+        // Each executor only reports the workers it is running
+        self.call_worker_executor(
+            AllExecutors,
+            move |worker_executor_client| {
+                info!("Watch workers");
+                Box::pin(worker_executor_client.watch_workers(
+                    workerexecutor::v1::WatchWorkersRequest {
+                        component_id: Some(component_id.clone().into()),
+                        filter: filter.clone().map(|f| f.into()),
+                    },
+                ))
+            },
+            |responses| {
+                Ok(WorkerStatusEventStream::new(
+                    responses
+                        .into_iter()
+                        .map(|response| response.into_inner())
+                        .collect(),
+                ))
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+
     async fn get_files(
         &self,
         worker_id: WorkerId,
@@ -1094,6 +3149,14 @@ impl<AuthCtx> WorkerServiceDefault<AuthCtx>
 where
     AuthCtx: Send + Sync,
 {
+    fn domain_event_poller(&self) -> DomainEventPoller {
+        DomainEventPoller::new(
+            self.worker_executor_clients.clone(),
+            self.worker_executor_retries.clone(),
+            self.routing_table_service.clone(),
+        )
+    }
+
     async fn try_get_component_for_worker(
         &self,
         worker_id: &WorkerId,
@@ -1105,30 +3168,115 @@ where
             .await
         {
             Ok(metadata) => {
-                let component_version = metadata.component_version;
-                let component_details = self
-                    .component_service
-                    .get_by_version(&worker_id.component_id, component_version, auth_ctx)
-                    .await?;
+                self.get_component(
+                    &worker_id.component_id,
+                    Some(metadata.component_version),
+                    auth_ctx,
+                )
+                .await
+            }
+            Err(WorkerServiceError::WorkerNotFound(_)) => {
+                self.get_component(&worker_id.component_id, None, auth_ctx)
+                    .await
+            }
+            Err(WorkerServiceError::Golem(GolemError::WorkerNotFound(_))) => {
+                self.get_component(&worker_id.component_id, None, auth_ctx)
+                    .await
+            }
+            Err(other) => Err(other),
+        }
+    }
+
+    /// The component version of the worker, or the latest version if the target is not a
+    /// specific worker or the worker does not exist yet
+    async fn get_component_for_target(
+        &self,
+        worker_id: &TargetWorkerId,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> Result<Component, WorkerServiceError> {
+        match worker_id.clone().try_into_worker_id() {
+            Some(worker_id) => {
+                self.try_get_component_for_worker(&worker_id, metadata, auth_ctx)
+                    .await
+            }
+            None => self.get_component(&worker_id.component_id, None, auth_ctx).await,
+        }
+    }
 
-                Ok(component_details)
+    /// Gets the given version of a component, or its latest version if not specified
+    async fn get_component(
+        &self,
+        component_id: &ComponentId,
+        component_version: Option<ComponentVersion>,
+        auth_ctx: &AuthCtx,
+    ) -> Result<Component, WorkerServiceError> {
+        let missing = MissingEntity::Component(component_id.clone());
+        if self.routing_table_service.is_missing(&missing) {
+            return Err(WorkerServiceError::ComponentNotFound(component_id.clone()));
+        }
+        let start = Instant::now();
+        let component = match component_version {
+            Some(component_version) => {
+                self.component_service
+                    .get_by_version(component_id, component_version, auth_ctx)
+                    .await
+            }
+            None => {
+                self.component_service
+                    .get_latest(component_id, auth_ctx)
+                    .await
             }
-            Err(WorkerServiceError::WorkerNotFound(_)) => Ok(self
-                .component_service
-                .get_latest(&worker_id.component_id, auth_ctx)
-                .await?),
-            Err(WorkerServiceError::Golem(GolemError::WorkerNotFound(_))) => Ok(self
-                .component_service
-                .get_latest(&worker_id.component_id, auth_ctx)
-                .await?),
-            Err(other) => Err(other),
+        };
+        record_component_metadata_fetch(start.elapsed());
+        // Only a missing latest version means that the component itself does not exist
+        if let (None, Err(ComponentServiceError::NotFound(_))) = (component_version, &component) {
+            self.routing_table_service.mark_missing(missing);
         }
+
+        Ok(component?)
+    }
+
+    async fn set_shadow(
+        &self,
+        worker_id: &WorkerId,
+        shadow: Option<WorkerShadow>,
+        metadata: WorkerRequestMetadata,
+    ) -> WorkerResult<()> {
+        let worker_id = worker_id.clone();
+        self.call_worker_executor(
+            worker_id.clone(),
+            move |worker_executor_client| {
+                info!("Set worker shadow");
+                let worker_id = worker_id.clone();
+                Box::pin(
+                    worker_executor_client.set_worker_shadow(SetWorkerShadowRequest {
+                        worker_id: Some(worker_id.into()),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                        shadow: shadow.clone().map(|shadow| shadow.into()),
+                    }),
+                )
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::SetWorkerShadowResponse {
+                    result: Some(workerexecutor::v1::set_worker_shadow_response::Result::Success(_)),
+                } => Ok(()),
+                workerexecutor::v1::SetWorkerShadowResponse {
+                    result: Some(workerexecutor::v1::set_worker_shadow_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::SetWorkerShadowResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await?;
+        Ok(())
     }
 
     async fn find_running_metadata_internal(
         &self,
         component_id: &ComponentId,
         filter: Option<WorkerFilter>,
+        projection: Option<WorkerMetadataProjection>,
         _auth_ctx: &AuthCtx,
     ) -> WorkerResult<Vec<WorkerMetadata>> {
         let component_id = component_id.clone();
@@ -1156,7 +3304,13 @@ where
                                                                                                                 workers
                                                                                                             })),
                         } => {
-                            let workers: Vec<WorkerMetadata> = workers.into_iter().map(|w| w.try_into()).collect::<Result<Vec<_>, _>>().map_err(|_| GolemError::Unknown(GolemErrorUnknown {
+                            // The running workers are few, so they are projected here instead of by the executors
+                            let workers: Vec<WorkerMetadata> = workers.into_iter().map(|mut w| {
+                                if let Some(projection) = &projection {
+                                    projection.apply(&mut w);
+                                }
+                                w.try_into()
+                            }).collect::<Result<Vec<_>, _>>().map_err(|_| GolemError::Unknown(GolemErrorUnknown {
                                 details: "Convert response error".to_string(),
                             }))?;
                             Ok(workers)
@@ -1184,6 +3338,7 @@ where
         cursor: ScanCursor,
         count: u64,
         precise: bool,
+        projection: Option<WorkerMetadataProjection>,
         metadata: WorkerRequestMetadata,
         _auth_ctx: &AuthCtx,
     ) -> WorkerResult<(Option<ScanCursor>, Vec<WorkerMetadata>)> {
@@ -1203,6 +3358,7 @@ where
                             count,
                             precise,
                             account_id,
+                            projection: projection.clone().map(|p| p.into()),
                         },
                     ))
                 },
@@ -1247,6 +3403,246 @@ where
     }
 }
 
+/// Decodes the JSON encoded oplog entries returned by the executor
+/// Gets the metadata of a worker from the executor owning it
+async fn get_metadata_from_executor(
+    context: &(impl HasRoutingTableService + HasWorkerExecutorClients + Send + Sync),
+    worker_id: &WorkerId,
+    metadata: WorkerRequestMetadata,
+) -> WorkerResult<WorkerMetadata> {
+    let worker_id = worker_id.clone();
+    context.call_worker_executor(
+        worker_id.clone(),
+        move |worker_executor_client| {
+            let worker_id = worker_id.clone();
+            info!("Get metadata");
+            Box::pin(worker_executor_client.get_worker_metadata(
+                workerexecutor::v1::GetWorkerMetadataRequest {
+                    worker_id: Some(golem_api_grpc::proto::golem::worker::WorkerId::from(worker_id)),
+                    account_id: metadata.account_id.clone().map(|id| id.into()),
+                }
+            ))
+        },
+        |response| {
+            match response.into_inner() {
+                workerexecutor::v1::GetWorkerMetadataResponse {
+                    result:
+                    Some(workerexecutor::v1::get_worker_metadata_response::Result::Success(metadata)),
+                } => {
+                    Ok(metadata.try_into().unwrap())
+                }
+                workerexecutor::v1::GetWorkerMetadataResponse {
+                    result:
+                    Some(workerexecutor::v1::get_worker_metadata_response::Result::Failure(err)),
+                } => {
+                    error!("Get metadata error: {err:?}");
+                    Err(err.into())
+                }
+                workerexecutor::v1::GetWorkerMetadataResponse { .. } => {
+                    Err("Empty response".into())
+                }
+            }
+        },
+        WorkerServiceError::InternalCallError,
+    ).await
+}
+
+/// Gets the metadata of many workers, with one call to each of the executors owning them. The
+/// workers which cannot be looked up together with the others are looked up one by one, so a
+/// failing executor or worker only fails the lookup of its own workers.
+async fn get_metadata_many_from_executors(
+    context: &(impl HasRoutingTableService + HasWorkerExecutorClients + Send + Sync),
+    worker_ids: &[WorkerId],
+    metadata: WorkerRequestMetadata,
+) -> WorkerMetadataMany {
+    let mut batches: HashMap<Pod, Vec<WorkerId>> = HashMap::new();
+    let mut single_lookups = Vec::new();
+    for worker_id in worker_ids {
+        match context
+            .routing_table_service()
+            .lookup_worker(worker_id)
+            .await
+        {
+            Ok(Some(pod)) => batches.entry(pod).or_default().push(worker_id.clone()),
+            Ok(None) => single_lookups.push(worker_id.clone()),
+            Err(err) => {
+                warn!(
+                    worker_id = worker_id.to_string(),
+                    error = err.to_string(),
+                    "Failed to look up the executor of the worker, getting its metadata on its own"
+                );
+                single_lookups.push(worker_id.clone());
+            }
+        }
+    }
+
+    let results = join_all(batches.into_values().map(|worker_ids| {
+        let metadata = metadata.clone();
+        async move {
+            let result = get_metadata_batch(context, worker_ids.clone(), metadata).await;
+            (worker_ids, result)
+        }
+    }))
+    .await;
+
+    let mut result = WorkerMetadataMany::default();
+    for (worker_ids, batch) in results {
+        match batch {
+            Ok(batch) => {
+                result.workers.extend(batch.workers);
+                result.errors.extend(batch.errors);
+                single_lookups.extend(batch.unassigned);
+            }
+            Err(err) => {
+                warn!(
+                    error = err.to_string(),
+                    "Failed to get the metadata of {} workers from their executor, getting them one by one",
+                    worker_ids.len()
+                );
+                single_lookups.extend(worker_ids);
+            }
+        }
+    }
+
+    // Workers whose shard moved since the routing table was fetched are looked up one by one,
+    // as the routing of single worker calls recovers from outdated routing tables
+    for worker_id in single_lookups {
+        if context
+            .routing_table_service()
+            .is_missing(&MissingEntity::Worker(worker_id.clone()))
+        {
+            continue;
+        }
+        match get_metadata_from_executor(context, &worker_id, metadata.clone()).await {
+            Ok(worker) => result.workers.push(worker),
+            Err(WorkerServiceError::WorkerNotFound(_))
+            | Err(WorkerServiceError::Golem(GolemError::WorkerNotFound(_))) => {}
+            Err(err) => result.errors.push((worker_id, err)),
+        }
+    }
+
+    result
+}
+
+/// The metadata of workers looked up together on the executor owning them
+struct WorkerMetadataBatch {
+    workers: Vec<WorkerMetadata>,
+    /// The workers which turned out not to be assigned to the executor
+    unassigned: Vec<WorkerId>,
+    errors: Vec<(WorkerId, WorkerServiceError)>,
+}
+
+/// Gets the metadata of workers belonging to the same executor
+async fn get_metadata_batch(
+    context: &(impl HasRoutingTableService + HasWorkerExecutorClients + Send + Sync),
+    worker_ids: Vec<WorkerId>,
+    metadata: WorkerRequestMetadata,
+) -> WorkerResult<WorkerMetadataBatch> {
+    let Some(first_worker_id) = worker_ids.first().cloned() else {
+        return Ok(WorkerMetadataBatch {
+            workers: Vec::new(),
+            unassigned: Vec::new(),
+            errors: Vec::new(),
+        });
+    };
+
+    context
+        .call_worker_executor(
+            first_worker_id,
+            move |worker_executor_client| {
+                Box::pin(worker_executor_client.get_worker_metadata_many(
+                    workerexecutor::v1::GetWorkerMetadataManyRequest {
+                        worker_ids: worker_ids.iter().cloned().map(|id| id.into()).collect(),
+                        account_id: metadata.account_id.clone().map(|id| id.into()),
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::GetWorkerMetadataManyResponse {
+                    result:
+                        Some(workerexecutor::v1::get_worker_metadata_many_response::Result::Success(
+                            workerexecutor::v1::GetWorkerMetadataManySuccessResponse {
+                                workers,
+                                unassigned_worker_ids,
+                                failures,
+                            },
+                        )),
+                } => {
+                    let workers: Vec<WorkerMetadata> = workers
+                        .into_iter()
+                        .map(|w| w.try_into())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|err| unexpected_response(format!("worker metadata: {err}")))?;
+                    let unassigned: Vec<WorkerId> = unassigned_worker_ids
+                        .into_iter()
+                        .map(|id| id.try_into())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|err| unexpected_response(format!("worker id: {err}")))?;
+                    let errors = failures
+                        .into_iter()
+                        .map(worker_metadata_failure)
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(unexpected_response)?;
+                    Ok(WorkerMetadataBatch {
+                        workers,
+                        unassigned,
+                        errors,
+                    })
+                }
+                workerexecutor::v1::GetWorkerMetadataManyResponse {
+                    result:
+                        Some(workerexecutor::v1::get_worker_metadata_many_response::Result::Failure(
+                            err,
+                        )),
+                } => Err(err.into()),
+                workerexecutor::v1::GetWorkerMetadataManyResponse { .. } => {
+                    Err("Empty response".into())
+                }
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+}
+
+fn unexpected_response(details: String) -> GolemError {
+    GolemError::Unknown(GolemErrorUnknown {
+        details: format!("Unexpected {details} in response"),
+    })
+}
+
+fn worker_metadata_failure(
+    failure: workerexecutor::v1::WorkerMetadataFailure,
+) -> Result<(WorkerId, WorkerServiceError), String> {
+    let worker_id: WorkerId = failure
+        .worker_id
+        .ok_or("worker metadata failure without a worker id")?
+        .try_into()?;
+    let error: GolemError = failure
+        .error
+        .ok_or("worker metadata failure without an error")?
+        .try_into()?;
+    Ok((worker_id, error.into()))
+}
+
+fn raw_oplog_response(
+    response: workerexecutor::v1::GetRawOplogSuccessResponse,
+) -> Result<GetRawOplogResponse, GolemError> {
+    Ok(GetRawOplogResponse {
+        entries: response
+            .entries
+            .iter()
+            .map(|entry| serde_json::from_str(entry))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| {
+                GolemError::Unknown(GolemErrorUnknown {
+                    details: format!("Unexpected raw oplog entry: {err}"),
+                })
+            })?,
+        first_index_in_chunk: response.first_index_in_chunk,
+        last_index: response.last_index,
+    })
+}
+
 fn is_filter_with_running_status(filter: &WorkerFilter) -> bool {
     match filter {
         WorkerFilter::Status(f)
@@ -1258,3 +3654,662 @@ fn is_filter_with_running_status(filter: &WorkerFilter) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::{HashMap, HashSet};
+    use std::convert::Infallible;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use golem_api_grpc::proto::golem::shardmanager;
+    use golem_api_grpc::proto::golem::worker::v1::{
+        worker_execution_error, UnknownError, WorkerExecutionError,
+    };
+    use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
+    use golem_api_grpc::proto::golem::workerexecutor::v1::{
+        get_worker_metadata_many_response, get_worker_metadata_response,
+        invoke_and_await_worker_response, GetRawOplogSuccessResponse, GetWorkerMetadataManyRequest,
+        GetWorkerMetadataManyResponse, GetWorkerMetadataManySuccessResponse,
+        GetWorkerMetadataRequest, GetWorkerMetadataResponse, InvokeAndAwaitWorkerRequest,
+        InvokeAndAwaitWorkerResponse, InvokeAndAwaitWorkerSuccess, WorkerMetadataFailure,
+    };
+    use golem_common::client::{GrpcClientConfig, MultiTargetGrpcClient};
+    use golem_common::config::RetryConfig;
+    use golem_common::model::{
+        ComponentId, IdempotencyKey, Pod, RoutingTable, TargetWorkerId, WorkerId,
+    };
+    use golem_service_base::model::GolemError;
+    use golem_service_base::routing_table::{
+        HasRoutingTableService, MissingEntity, RoutingTableError, RoutingTableService,
+    };
+    use serde_json::json;
+    use tokio::net::TcpListener;
+    use tonic::body::BoxBody;
+    use tonic::codec::ProstCodec;
+    use tonic::codegen::{empty_body, http, Body, BoxFuture, Service, StdError};
+    use tonic::transport::{Channel, Server};
+    use tonic::Status;
+
+    use crate::service::worker::default::{
+        get_metadata_many_from_executors, invoke_and_await_on_executor, invoke_and_await_request,
+        raw_oplog_response,
+    };
+    use crate::service::worker::{
+        HasWorkerExecutorClients, WorkerRequestMetadata, WorkerServiceError,
+    };
+
+    type Handler<Req, Resp> = Arc<dyn Fn(Req) -> Result<Resp, Status> + Send + Sync>;
+
+    struct UnaryHandler<Req, Resp>(Handler<Req, Resp>);
+
+    impl<Req: Send + 'static, Resp: Send + 'static> tonic::server::UnaryService<Req>
+        for UnaryHandler<Req, Resp>
+    {
+        type Response = Resp;
+        type Future = BoxFuture<tonic::Response<Resp>, Status>;
+
+        fn call(&mut self, request: tonic::Request<Req>) -> Self::Future {
+            let result = (self.0)(request.into_inner()).map(tonic::Response::new);
+            Box::pin(async move { result })
+        }
+    }
+
+    async fn serve<Req, Resp, B>(
+        handler: Handler<Req, Resp>,
+        request: http::Request<B>,
+    ) -> http::Response<BoxBody>
+    where
+        Req: prost::Message + Default + Send + 'static,
+        Resp: prost::Message + Send + 'static,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        tonic::server::Grpc::new(ProstCodec::<Resp, Req>::default())
+            .unary(UnaryHandler(handler), request)
+            .await
+    }
+
+    /// A worker executor answering only the calls it has handlers for
+    #[derive(Clone, Default)]
+    struct TestExecutor {
+        invoke_and_await_worker:
+            Option<Handler<InvokeAndAwaitWorkerRequest, InvokeAndAwaitWorkerResponse>>,
+        get_worker_metadata: Option<Handler<GetWorkerMetadataRequest, GetWorkerMetadataResponse>>,
+        get_worker_metadata_many:
+            Option<Handler<GetWorkerMetadataManyRequest, GetWorkerMetadataManyResponse>>,
+    }
+
+    impl TestExecutor {
+        fn on_invoke_and_await_worker(
+            mut self,
+            handler: impl Fn(InvokeAndAwaitWorkerRequest) -> Result<InvokeAndAwaitWorkerResponse, Status>
+                + Send
+                + Sync
+                + 'static,
+        ) -> Self {
+            self.invoke_and_await_worker = Some(Arc::new(handler));
+            self
+        }
+
+        fn on_get_worker_metadata(
+            mut self,
+            handler: impl Fn(GetWorkerMetadataRequest) -> Result<GetWorkerMetadataResponse, Status>
+                + Send
+                + Sync
+                + 'static,
+        ) -> Self {
+            self.get_worker_metadata = Some(Arc::new(handler));
+            self
+        }
+
+        fn on_get_worker_metadata_many(
+            mut self,
+            handler: impl Fn(GetWorkerMetadataManyRequest) -> Result<GetWorkerMetadataManyResponse, Status>
+                + Send
+                + Sync
+                + 'static,
+        ) -> Self {
+            self.get_worker_metadata_many = Some(Arc::new(handler));
+            self
+        }
+
+        async fn start(self) -> Pod {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let incoming = futures::stream::unfold(listener, |listener| async move {
+                let connection = listener.accept().await.map(|(stream, _)| stream);
+                Some((connection, listener))
+            });
+            tokio::spawn(
+                Server::builder()
+                    .add_service(self)
+                    .serve_with_incoming(incoming),
+            );
+            Pod::from(shardmanager::Pod {
+                host: "127.0.0.1".to_string(),
+                port: port as u32,
+                pod_name: None,
+            })
+        }
+    }
+
+    impl tonic::server::NamedService for TestExecutor {
+        const NAME: &'static str = "golem.workerexecutor.v1.WorkerExecutor";
+    }
+
+    impl<B> Service<http::Request<B>> for TestExecutor
+    where
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<BoxBody>;
+        type Error = Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: http::Request<B>) -> Self::Future {
+            let executor = self.clone();
+            Box::pin(async move {
+                let method = request
+                    .uri()
+                    .path()
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                let response = match (method.as_str(), executor) {
+                    (
+                        "InvokeAndAwaitWorker",
+                        TestExecutor {
+                            invoke_and_await_worker: Some(handler),
+                            ..
+                        },
+                    ) => serve(handler, request).await,
+                    (
+                        "GetWorkerMetadata",
+                        TestExecutor {
+                            get_worker_metadata: Some(handler),
+                            ..
+                        },
+                    ) => serve(handler, request).await,
+                    (
+                        "GetWorkerMetadataMany",
+                        TestExecutor {
+                            get_worker_metadata_many: Some(handler),
+                            ..
+                        },
+                    ) => serve(handler, request).await,
+                    _ => http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap(),
+                };
+                Ok(response)
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct TestRoutingTable {
+        assignments: Mutex<HashMap<WorkerId, Pod>>,
+        missing: Mutex<HashSet<MissingEntity>>,
+    }
+
+    impl TestRoutingTable {
+        fn assign(&self, worker_id: &WorkerId, pod: &Pod) {
+            self.assignments
+                .lock()
+                .unwrap()
+                .insert(worker_id.clone(), pod.clone());
+        }
+    }
+
+    #[async_trait]
+    impl RoutingTableService for TestRoutingTable {
+        async fn get_routing_table(&self) -> Result<RoutingTable, RoutingTableError> {
+            Err(RoutingTableError::NoResult)
+        }
+
+        async fn lookup_worker(
+            &self,
+            worker_id: &WorkerId,
+        ) -> Result<Option<Pod>, RoutingTableError> {
+            Ok(self.assignments.lock().unwrap().get(worker_id).cloned())
+        }
+
+        async fn try_invalidate_routing_table(&self) -> bool {
+            false
+        }
+
+        async fn add_anti_affinity(
+            &self,
+            _key: &str,
+            _worker_id: &WorkerId,
+        ) -> Result<(), RoutingTableError> {
+            Ok(())
+        }
+
+        async fn remove_anti_affinity(
+            &self,
+            _worker_id: &WorkerId,
+        ) -> Result<(), RoutingTableError> {
+            Ok(())
+        }
+
+        fn mark_missing(&self, entity: MissingEntity) {
+            self.missing.lock().unwrap().insert(entity);
+        }
+
+        fn is_missing(&self, entity: &MissingEntity) -> bool {
+            self.missing.lock().unwrap().contains(entity)
+        }
+
+        fn clear_missing(&self, entity: &MissingEntity) {
+            self.missing.lock().unwrap().remove(entity);
+        }
+    }
+
+    struct TestContext {
+        routing_table: Arc<dyn RoutingTableService + Send + Sync>,
+        worker_executor_clients: MultiTargetGrpcClient<WorkerExecutorClient<Channel>>,
+        retry_config: RetryConfig,
+    }
+
+    impl TestContext {
+        fn new(routing_table: Arc<TestRoutingTable>) -> Self {
+            Self {
+                routing_table,
+                worker_executor_clients: MultiTargetGrpcClient::new(
+                    WorkerExecutorClient::new,
+                    GrpcClientConfig::default(),
+                ),
+                retry_config: RetryConfig {
+                    max_attempts: 3,
+                    min_delay: Duration::from_millis(1),
+                    max_delay: Duration::from_millis(10),
+                    multiplier: 2.0,
+                    max_jitter_factor: None,
+                },
+            }
+        }
+    }
+
+    impl HasRoutingTableService for TestContext {
+        fn routing_table_service(&self) -> &Arc<dyn RoutingTableService + Send + Sync> {
+            &self.routing_table
+        }
+    }
+
+    impl HasWorkerExecutorClients for TestContext {
+        fn worker_executor_clients(&self) -> &MultiTargetGrpcClient<WorkerExecutorClient<Channel>> {
+            &self.worker_executor_clients
+        }
+
+        fn worker_executor_retry_config(&self) -> &RetryConfig {
+            &self.retry_config
+        }
+    }
+
+    fn no_request_metadata() -> WorkerRequestMetadata {
+        WorkerRequestMetadata {
+            account_id: None,
+            limits: None,
+        }
+    }
+
+    fn worker_ids(component_id: &ComponentId, names: &[&str]) -> Vec<WorkerId> {
+        names
+            .iter()
+            .map(|name| WorkerId {
+                component_id: component_id.clone(),
+                worker_name: name.to_string(),
+            })
+            .collect()
+    }
+
+    fn requested_worker_ids(
+        worker_ids: Vec<golem_api_grpc::proto::golem::worker::WorkerId>,
+    ) -> Vec<WorkerId> {
+        worker_ids
+            .into_iter()
+            .map(|worker_id| worker_id.try_into().unwrap())
+            .collect()
+    }
+
+    fn worker_metadata(
+        worker_id: &WorkerId,
+    ) -> golem_api_grpc::proto::golem::worker::WorkerMetadata {
+        golem_api_grpc::proto::golem::worker::WorkerMetadata {
+            worker_id: Some(worker_id.clone().into()),
+            created_at: Some(prost_types::Timestamp::default()),
+            ..Default::default()
+        }
+    }
+
+    fn unknown_error(details: &str) -> WorkerExecutionError {
+        WorkerExecutionError {
+            error: Some(worker_execution_error::Error::Unknown(UnknownError {
+                details: details.to_string(),
+            })),
+        }
+    }
+
+    fn batch_response(
+        workers: Vec<golem_api_grpc::proto::golem::worker::WorkerMetadata>,
+        unassigned: Vec<WorkerId>,
+        failures: Vec<WorkerMetadataFailure>,
+    ) -> GetWorkerMetadataManyResponse {
+        GetWorkerMetadataManyResponse {
+            result: Some(get_worker_metadata_many_response::Result::Success(
+                GetWorkerMetadataManySuccessResponse {
+                    workers,
+                    unassigned_worker_ids: unassigned.into_iter().map(|id| id.into()).collect(),
+                    failures,
+                },
+            )),
+        }
+    }
+
+    fn single_response(
+        result: get_worker_metadata_response::Result,
+    ) -> Result<GetWorkerMetadataResponse, Status> {
+        Ok(GetWorkerMetadataResponse {
+            result: Some(result),
+        })
+    }
+
+    /// An executor owning all the workers it gets asked about, recording the batches it gets
+    async fn owning_executor(batches: Arc<Mutex<Vec<Vec<WorkerId>>>>) -> Pod {
+        TestExecutor::default()
+            .on_get_worker_metadata_many(move |request| {
+                let worker_ids = requested_worker_ids(request.worker_ids);
+                batches.lock().unwrap().push(worker_ids.clone());
+                Ok(batch_response(
+                    worker_ids.iter().map(worker_metadata).collect(),
+                    vec![],
+                    vec![],
+                ))
+            })
+            .start()
+            .await
+    }
+
+    fn found_worker_names(workers: &[golem_service_base::model::WorkerMetadata]) -> Vec<String> {
+        let mut names: Vec<String> = workers
+            .iter()
+            .map(|worker| worker.worker_id.worker_name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn request(
+        idempotency_key: Option<IdempotencyKey>,
+    ) -> golem_api_grpc::proto::golem::workerexecutor::v1::InvokeAndAwaitWorkerRequest {
+        invoke_and_await_request(
+            &TargetWorkerId {
+                component_id: ComponentId::new_v4(),
+                worker_name: Some("worker-1".to_string()),
+            },
+            idempotency_key,
+            "golem:it/api.{f}".to_string(),
+            vec![],
+            vec![],
+            None,
+            WorkerRequestMetadata {
+                account_id: None,
+                limits: None,
+            },
+        )
+    }
+
+    #[test]
+    fn given_idempotency_key_is_sent() {
+        let key = IdempotencyKey::fresh();
+
+        assert_eq!(request(Some(key.clone())).idempotency_key, Some(key.into()));
+    }
+
+    #[test]
+    async fn retries_reuse_the_generated_idempotency_key() {
+        let received_keys = Arc::new(Mutex::new(Vec::new()));
+        let executor = TestExecutor::default()
+            .on_invoke_and_await_worker({
+                let received_keys = received_keys.clone();
+                move |request| {
+                    let mut received_keys = received_keys.lock().unwrap();
+                    received_keys.push(request.idempotency_key);
+                    if received_keys.len() < 3 {
+                        Err(Status::internal("Transient executor failure"))
+                    } else {
+                        Ok(InvokeAndAwaitWorkerResponse {
+                            result: Some(invoke_and_await_worker_response::Result::Success(
+                                InvokeAndAwaitWorkerSuccess { output: vec![] },
+                            )),
+                        })
+                    }
+                }
+            })
+            .start()
+            .await;
+
+        let request = request(None);
+        let worker_id: TargetWorkerId = request.worker_id.clone().unwrap().try_into().unwrap();
+        let routing_table = Arc::new(TestRoutingTable::default());
+        routing_table.assign(&worker_id.clone().try_into_worker_id().unwrap(), &executor);
+        let context = TestContext::new(routing_table);
+
+        let result = invoke_and_await_on_executor(
+            &context,
+            &worker_id,
+            request.name.clone(),
+            request.clone(),
+        )
+        .await;
+
+        assert!(result.is_ok());
+        let received_keys = received_keys.lock().unwrap();
+        assert_eq!(received_keys.len(), 3);
+        assert!(request.idempotency_key.is_some());
+        assert!(received_keys
+            .iter()
+            .all(|key| *key == request.idempotency_key));
+    }
+
+    #[test]
+    fn idempotency_keys_are_generated_per_request() {
+        assert_ne!(request(None).idempotency_key, request(None).idempotency_key);
+    }
+
+    #[test]
+    fn raw_oplog_entries_are_decoded() {
+        let response = raw_oplog_response(GetRawOplogSuccessResponse {
+            entries: vec![
+                r#"{"Suspend":{"timestamp":"2024-08-18T16:53:20.123Z"}}"#.to_string(),
+                r#"{"Exited":{"timestamp":"2024-08-18T16:53:21Z"}}"#.to_string(),
+            ],
+            first_index_in_chunk: 5,
+            last_index: 12,
+        })
+        .unwrap();
+
+        assert_eq!(
+            response.entries,
+            vec![
+                json!({"Suspend": {"timestamp": "2024-08-18T16:53:20.123Z"}}),
+                json!({"Exited": {"timestamp": "2024-08-18T16:53:21Z"}}),
+            ]
+        );
+        assert_eq!(response.first_index_in_chunk, 5);
+        assert_eq!(response.last_index, 12);
+    }
+
+    #[test]
+    fn invalid_raw_oplog_entries_are_rejected() {
+        let result = raw_oplog_response(GetRawOplogSuccessResponse {
+            entries: vec!["{}".to_string(), "not json".to_string()],
+            first_index_in_chunk: 1,
+            last_index: 2,
+        });
+
+        assert!(matches!(
+            result,
+            Err(GolemError::Unknown(error)) if error.details.starts_with("Unexpected raw oplog entry")
+        ));
+    }
+
+    #[test]
+    async fn metadata_of_many_workers_is_looked_up_with_one_call_per_executor() {
+        let component_id = ComponentId::new_v4();
+        let workers = worker_ids(&component_id, &["w1", "w2", "w3"]);
+        let batches_a = Arc::new(Mutex::new(Vec::new()));
+        let batches_b = Arc::new(Mutex::new(Vec::new()));
+        let executor_a = owning_executor(batches_a.clone()).await;
+        let executor_b = owning_executor(batches_b.clone()).await;
+
+        let routing_table = Arc::new(TestRoutingTable::default());
+        routing_table.assign(&workers[0], &executor_a);
+        routing_table.assign(&workers[1], &executor_b);
+        routing_table.assign(&workers[2], &executor_a);
+        let context = TestContext::new(routing_table);
+
+        let result =
+            get_metadata_many_from_executors(&context, &workers, no_request_metadata()).await;
+
+        assert_eq!(found_worker_names(&result.workers), vec!["w1", "w2", "w3"]);
+        assert!(result.errors.is_empty());
+        assert_eq!(
+            *batches_a.lock().unwrap(),
+            vec![vec![workers[0].clone(), workers[2].clone()]]
+        );
+        assert_eq!(*batches_b.lock().unwrap(), vec![vec![workers[1].clone()]]);
+    }
+
+    #[test]
+    async fn workers_not_assigned_to_the_executor_are_looked_up_on_their_own() {
+        let component_id = ComponentId::new_v4();
+        let workers = worker_ids(&component_id, &["w1"]);
+        let routing_table = Arc::new(TestRoutingTable::default());
+
+        let executor_b = TestExecutor::default()
+            .on_get_worker_metadata(|request| {
+                let worker_id: WorkerId = request.worker_id.unwrap().try_into().unwrap();
+                single_response(get_worker_metadata_response::Result::Success(
+                    worker_metadata(&worker_id),
+                ))
+            })
+            .start()
+            .await;
+        // The shard of the worker moves to executor B after the routing table was read
+        let executor_a = TestExecutor::default()
+            .on_get_worker_metadata_many({
+                let routing_table = routing_table.clone();
+                let executor_b = executor_b.clone();
+                move |request| {
+                    let worker_ids = requested_worker_ids(request.worker_ids);
+                    for worker_id in &worker_ids {
+                        routing_table.assign(worker_id, &executor_b);
+                    }
+                    Ok(batch_response(vec![], worker_ids, vec![]))
+                }
+            })
+            .start()
+            .await;
+        routing_table.assign(&workers[0], &executor_a);
+        let context = TestContext::new(routing_table);
+
+        let result =
+            get_metadata_many_from_executors(&context, &workers, no_request_metadata()).await;
+
+        assert_eq!(found_worker_names(&result.workers), vec!["w1"]);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    async fn missing_workers_are_omitted_and_failures_are_reported_per_worker() {
+        let component_id = ComponentId::new_v4();
+        let workers = worker_ids(&component_id, &["w1", "w2", "w3", "w4", "w5"]);
+
+        // Executor A has w1, does not have w2 and fails to read w4
+        let executor_a = TestExecutor::default()
+            .on_get_worker_metadata_many(|request| {
+                let mut found = vec![];
+                let mut failures = vec![];
+                for worker_id in requested_worker_ids(request.worker_ids) {
+                    match worker_id.worker_name.as_str() {
+                        "w1" => found.push(worker_metadata(&worker_id)),
+                        "w4" => failures.push(WorkerMetadataFailure {
+                            worker_id: Some(worker_id.into()),
+                            error: Some(unknown_error("Failed to read the oplog")),
+                        }),
+                        _ => {}
+                    }
+                }
+                Ok(batch_response(found, vec![], failures))
+            })
+            .start()
+            .await;
+        // Executor B fails batch lookups, fails to read w3 and does not have w5
+        let executor_b = TestExecutor::default()
+            .on_get_worker_metadata_many(|_| Err(Status::failed_precondition("Not supported")))
+            .on_get_worker_metadata(|request| {
+                let worker_id: WorkerId = request.worker_id.unwrap().try_into().unwrap();
+                if worker_id.worker_name == "w3" {
+                    single_response(get_worker_metadata_response::Result::Failure(
+                        unknown_error("Failed to read the oplog"),
+                    ))
+                } else {
+                    single_response(get_worker_metadata_response::Result::Failure(
+                        WorkerExecutionError {
+                            error: Some(worker_execution_error::Error::WorkerNotFound(
+                                golem_api_grpc::proto::golem::worker::v1::WorkerNotFound {
+                                    worker_id: Some(worker_id.into()),
+                                },
+                            )),
+                        },
+                    ))
+                }
+            })
+            .start()
+            .await;
+
+        let routing_table = Arc::new(TestRoutingTable::default());
+        for worker_id in &workers {
+            match worker_id.worker_name.as_str() {
+                "w3" | "w5" => routing_table.assign(worker_id, &executor_b),
+                _ => routing_table.assign(worker_id, &executor_a),
+            }
+        }
+        let context = TestContext::new(routing_table);
+
+        let result =
+            get_metadata_many_from_executors(&context, &workers, no_request_metadata()).await;
+
+        assert_eq!(found_worker_names(&result.workers), vec!["w1"]);
+        let mut errors: Vec<(String, bool)> = result
+            .errors
+            .iter()
+            .map(|(worker_id, error)| {
+                (
+                    worker_id.worker_name.clone(),
+                    matches!(error, WorkerServiceError::Golem(GolemError::Unknown(_))),
+                )
+            })
+            .collect();
+        errors.sort();
+        assert_eq!(
+            errors,
+            vec![("w3".to_string(), true), ("w4".to_string(), true)]
+        );
+    }
+}
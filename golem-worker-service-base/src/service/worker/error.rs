@@ -20,7 +20,7 @@ use golem_common::SafeDisplay;
 use golem_service_base::model::{GolemError, VersionedComponentId};
 
 use crate::service::component::ComponentServiceError;
-use crate::service::worker::CallWorkerExecutorError;
+use crate::service::worker::{CallWorkerExecutorError, WorkerRequestLimit};
 
 #[derive(Debug, thiserror::Error)]
 pub enum WorkerServiceError {
@@ -28,6 +28,18 @@ pub enum WorkerServiceError {
     Component(#[from] ComponentServiceError),
     #[error("Type checker error: {0}")]
     TypeChecker(String),
+    #[error("Request limit exceeded: the {limit} is {actual}, but at most {max} is allowed")]
+    LimitExceeded {
+        limit: WorkerRequestLimit,
+        actual: usize,
+        max: usize,
+    },
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(String),
+    #[error("Invalid blob parameter: {0}")]
+    InvalidBlobParameter(String),
+    #[error("Worker name {0} is reserved for a shadow worker")]
+    ReservedWorkerName(String),
     #[error("Component not found: {0}")]
     VersionedComponentIdNotFound(VersionedComponentId),
     #[error("Component not found: {0}")]
@@ -36,6 +48,16 @@ pub enum WorkerServiceError {
     AccountIdNotFound(AccountId),
     #[error("Worker not found: {0}")]
     WorkerNotFound(WorkerId),
+    /// The component's workers cannot be invoked until its maintenance ends, the request can
+    /// be retried later
+    #[error(
+        "Component {component_id} is in maintenance{}",
+        .message.as_ref().map(|message| format!(": {message}")).unwrap_or_default()
+    )]
+    ComponentInMaintenance {
+        component_id: ComponentId,
+        message: Option<String>,
+    },
     #[error("Internal error: {0}")]
     Internal(String),
     #[error(transparent)]
@@ -49,10 +71,15 @@ impl SafeDisplay for WorkerServiceError {
         match self {
             WorkerServiceError::Component(inner) => inner.to_safe_string(),
             WorkerServiceError::TypeChecker(_) => self.to_string(),
+            WorkerServiceError::LimitExceeded { .. } => self.to_string(),
+            WorkerServiceError::InvalidTemplate(_) => self.to_string(),
+            WorkerServiceError::InvalidBlobParameter(_) => self.to_string(),
+            WorkerServiceError::ReservedWorkerName(_) => self.to_string(),
             WorkerServiceError::VersionedComponentIdNotFound(_) => self.to_string(),
             WorkerServiceError::ComponentNotFound(_) => self.to_string(),
             WorkerServiceError::AccountIdNotFound(_) => self.to_string(),
             WorkerServiceError::WorkerNotFound(_) => self.to_string(),
+            WorkerServiceError::ComponentInMaintenance { .. } => self.to_string(),
             WorkerServiceError::Internal(_) => self.to_string(),
             WorkerServiceError::Golem(inner) => inner.to_safe_string(),
             WorkerServiceError::InternalCallError(inner) => inner.to_safe_string(),
@@ -80,6 +107,11 @@ impl From<WorkerServiceError> for worker_error::Error {
             | WorkerServiceError::WorkerNotFound(_)) => worker_error::Error::NotFound(ErrorBody {
                 error: error.to_safe_string(),
             }),
+            error @ WorkerServiceError::ComponentInMaintenance { .. } => {
+                worker_error::Error::Unavailable(ErrorBody {
+                    error: error.to_safe_string(),
+                })
+            }
             WorkerServiceError::Internal(_) => {
                 worker_error::Error::InternalError(WorkerExecutionError {
                     error: Some(worker_execution_error::Error::Unknown(UnknownError {
@@ -97,6 +129,14 @@ impl From<WorkerServiceError> for worker_error::Error {
             WorkerServiceError::TypeChecker(error) => worker_error::Error::BadRequest(ErrorsBody {
                 errors: vec![error],
             }),
+            error @ (WorkerServiceError::LimitExceeded { .. }
+            | WorkerServiceError::InvalidTemplate(_)
+            | WorkerServiceError::InvalidBlobParameter(_)
+            | WorkerServiceError::ReservedWorkerName(_)) => {
+                worker_error::Error::BadRequest(ErrorsBody {
+                    errors: vec![error.to_safe_string()],
+                })
+            }
             WorkerServiceError::Component(component) => component.into(),
             WorkerServiceError::Golem(worker_execution_error) => {
                 worker_error::Error::InternalError(worker_execution_error.into())
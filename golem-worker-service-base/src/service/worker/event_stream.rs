@@ -0,0 +1,204 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::Stream;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tonic::transport::Channel;
+use tonic::Status;
+use tracing::{info, Instrument};
+
+use golem_api_grpc::proto::golem::worker::DomainEvent;
+use golem_api_grpc::proto::golem::workerexecutor;
+use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
+use golem_common::client::MultiTargetGrpcClient;
+use golem_common::config::RetryConfig;
+use golem_common::metrics::api::{
+    record_closed_grpc_api_active_stream, record_new_grpc_api_active_stream,
+};
+use golem_common::model::ComponentId;
+use golem_service_base::routing_table::{HasRoutingTableService, RoutingTableService};
+
+use super::{HasWorkerExecutorClients, RandomExecutor, RoutingLogic, WorkerServiceError};
+
+/// The number of events requested from the executors by each poll of a subscription
+const SUBSCRIPTION_POLL_COUNT: u64 = 100;
+
+/// How long a subscription waits before polling again once it has consumed all the events
+const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Polls the domain events of the workers from any of the worker executors, as they are
+/// read from the shared storage
+#[derive(Clone)]
+pub struct DomainEventPoller {
+    worker_executor_clients: MultiTargetGrpcClient<WorkerExecutorClient<Channel>>,
+    worker_executor_retries: RetryConfig,
+    routing_table_service: Arc<dyn RoutingTableService + Send + Sync>,
+}
+
+impl DomainEventPoller {
+    pub fn new(
+        worker_executor_clients: MultiTargetGrpcClient<WorkerExecutorClient<Channel>>,
+        worker_executor_retries: RetryConfig,
+        routing_table_service: Arc<dyn RoutingTableService + Send + Sync>,
+    ) -> Self {
+        Self {
+            worker_executor_clients,
+            worker_executor_retries,
+            routing_table_service,
+        }
+    }
+
+    pub async fn poll(
+        &self,
+        component_id: &ComponentId,
+        topic: &str,
+        cursor: u64,
+        count: u64,
+    ) -> Result<Vec<DomainEvent>, WorkerServiceError> {
+        let component_id = component_id.clone();
+        let topic = topic.to_string();
+        self.call_worker_executor(
+            RandomExecutor,
+            move |worker_executor_client| {
+                info!("Poll events");
+                Box::pin(worker_executor_client.poll_events(
+                    workerexecutor::v1::PollEventsRequest {
+                        component_id: Some(component_id.clone().into()),
+                        topic: topic.clone(),
+                        cursor,
+                        count,
+                    },
+                ))
+            },
+            |response| match response.into_inner() {
+                workerexecutor::v1::PollEventsResponse {
+                    result:
+                        Some(workerexecutor::v1::poll_events_response::Result::Success(
+                            workerexecutor::v1::PollEventsSuccessResponse { events },
+                        )),
+                } => Ok(events),
+                workerexecutor::v1::PollEventsResponse {
+                    result: Some(workerexecutor::v1::poll_events_response::Result::Failure(err)),
+                } => Err(err.into()),
+                workerexecutor::v1::PollEventsResponse { .. } => Err("Empty response".into()),
+            },
+            WorkerServiceError::InternalCallError,
+        )
+        .await
+    }
+}
+
+impl HasRoutingTableService for DomainEventPoller {
+    fn routing_table_service(&self) -> &Arc<dyn RoutingTableService + Send + Sync> {
+        &self.routing_table_service
+    }
+}
+
+impl HasWorkerExecutorClients for DomainEventPoller {
+    fn worker_executor_clients(&self) -> &MultiTargetGrpcClient<WorkerExecutorClient<Channel>> {
+        &self.worker_executor_clients
+    }
+
+    fn worker_executor_retry_config(&self) -> &RetryConfig {
+        &self.worker_executor_retries
+    }
+}
+
+/// Subscription to a topic of domain events, polling for the new events periodically
+pub struct DomainEventStream {
+    receiver: mpsc::Receiver<Result<DomainEvent, Status>>,
+    cancel: CancellationToken,
+}
+
+impl DomainEventStream {
+    pub fn new(
+        poller: DomainEventPoller,
+        component_id: ComponentId,
+        topic: String,
+        cursor: u64,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel(SUBSCRIPTION_POLL_COUNT as usize);
+
+        let cancel = CancellationToken::new();
+        let cancel_clone = cancel.clone();
+
+        tokio::spawn(
+            async move {
+                record_new_grpc_api_active_stream();
+
+                let mut cursor = cursor;
+                'subscription: loop {
+                    let events = tokio::select! {
+                        _ = cancel_clone.cancelled() => break,
+                        events = poller.poll(&component_id, &topic, cursor, SUBSCRIPTION_POLL_COUNT) => events,
+                    };
+                    match events {
+                        Ok(events) => {
+                            let caught_up = (events.len() as u64) < SUBSCRIPTION_POLL_COUNT;
+                            for event in events {
+                                cursor = event.id;
+                                if sender.send(Ok(event)).await.is_err() {
+                                    break 'subscription;
+                                }
+                            }
+                            if caught_up {
+                                tokio::select! {
+                                    _ = cancel_clone.cancelled() => break,
+                                    _ = tokio::time::sleep(SUBSCRIPTION_POLL_INTERVAL) => {}
+                                }
+                            }
+                        }
+                        Err(error) => {
+                            let _ = sender
+                                .send(Err(Status::internal(format!(
+                                    "Failed to poll events: {error}"
+                                ))))
+                                .await;
+                            break;
+                        }
+                    }
+                }
+
+                drop(sender);
+                record_closed_grpc_api_active_stream();
+            }
+            .in_current_span(),
+        );
+
+        Self { receiver, cancel }
+    }
+}
+
+impl Stream for DomainEventStream {
+    type Item = Result<DomainEvent, Status>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<DomainEvent, Status>>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for DomainEventStream {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
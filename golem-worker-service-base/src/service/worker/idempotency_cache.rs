@@ -0,0 +1,322 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use fred::types::Expiration;
+use golem_common::cache::{BackgroundEvictionMode, Cache, FullCacheEvictionMode, SimpleCache};
+use golem_common::config::RedisConfig;
+use golem_common::model::{IdempotencyKey, TargetWorkerId};
+use golem_common::redis::RedisPool;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Configuration of the cache of invocation results by idempotency key.
+///
+/// Awaited invocations repeated with the same idempotency key get the result of the first one
+/// from this cache, without calling the worker executor again. Only successful results are
+/// cached, and they are kept for `ttl`. The cache is disabled by default.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IdempotencyCacheConfig {
+    #[serde(with = "humantime_serde")]
+    pub ttl: Duration,
+    pub storage: IdempotencyCacheStorageConfig,
+}
+
+impl Default for IdempotencyCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(10 * 60),
+            storage: IdempotencyCacheStorageConfig::Disabled,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum IdempotencyCacheStorageConfig {
+    Disabled,
+    /// Each worker service instance caches the results it returned
+    InMemory(InMemoryIdempotencyCacheConfig),
+    /// The results are shared by all worker service instances
+    Redis(RedisConfig),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InMemoryIdempotencyCacheConfig {
+    pub capacity: usize,
+}
+
+impl Default for InMemoryIdempotencyCacheConfig {
+    fn default() -> Self {
+        Self { capacity: 4096 }
+    }
+}
+
+/// Key of the cached result of an invocation, or `None` if its result is not cached.
+///
+/// Only the invocations of a named worker with an idempotency key given by the caller are
+/// cached: the keys generated by the worker service are never repeated, and the invocations of
+/// a component without a worker name go to a different worker each time. The typed and untyped
+/// results of the same invocation are cached separately.
+pub fn idempotency_cache_key(
+    worker_id: &TargetWorkerId,
+    idempotency_key: Option<&IdempotencyKey>,
+    typed: bool,
+) -> Option<String> {
+    let worker_name = worker_id.worker_name.as_ref()?;
+    let idempotency_key = idempotency_key?;
+    let kind = if typed { "typed" } else { "untyped" };
+    Some(format!(
+        "{}/{worker_name}/{idempotency_key}/{kind}",
+        worker_id.component_id
+    ))
+}
+
+/// Stores the encoded results of invocations by idempotency key. Failing to access the cache
+/// is not an error, the invocation just goes to the worker executor.
+#[async_trait]
+pub trait IdempotencyCache {
+    async fn get(&self, key: &str) -> Option<Bytes>;
+
+    async fn put(&self, key: &str, result: Bytes);
+}
+
+/// Creates the cache configured by `config`, or `None` if it is disabled
+pub async fn configured_idempotency_cache(
+    config: &IdempotencyCacheConfig,
+) -> Result<Option<Arc<dyn IdempotencyCache + Send + Sync>>, String> {
+    match &config.storage {
+        IdempotencyCacheStorageConfig::Disabled => Ok(None),
+        IdempotencyCacheStorageConfig::InMemory(in_memory) => Ok(Some(Arc::new(
+            InMemoryIdempotencyCache::new(config.ttl, in_memory.capacity),
+        ))),
+        IdempotencyCacheStorageConfig::Redis(redis) => {
+            let pool = RedisPool::configured(redis)
+                .await
+                .map_err(|err| format!("Failed to connect to Redis: {err}"))?;
+            Ok(Some(Arc::new(RedisIdempotencyCache::new(pool, config.ttl))))
+        }
+    }
+}
+
+pub struct InMemoryIdempotencyCache {
+    cache: Cache<String, (), Bytes, ()>,
+}
+
+impl InMemoryIdempotencyCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            cache: Cache::new(
+                Some(capacity),
+                FullCacheEvictionMode::LeastRecentlyUsed(1),
+                BackgroundEvictionMode::OlderThan {
+                    ttl,
+                    period: Duration::from_secs(60).min(ttl),
+                },
+                "idempotency_cache",
+            ),
+        }
+    }
+}
+
+#[async_trait]
+impl IdempotencyCache for InMemoryIdempotencyCache {
+    async fn get(&self, key: &str) -> Option<Bytes> {
+        self.cache.try_get(&key.to_string())
+    }
+
+    async fn put(&self, key: &str, result: Bytes) {
+        let _ = self
+            .cache
+            .get_or_insert_simple(&key.to_string(), || Box::pin(async move { Ok(result) }))
+            .await;
+    }
+}
+
+pub struct RedisIdempotencyCache {
+    pool: RedisPool,
+    ttl: Duration,
+}
+
+impl RedisIdempotencyCache {
+    pub fn new(pool: RedisPool, ttl: Duration) -> Self {
+        Self { pool, ttl }
+    }
+
+    fn redis_key(key: &str) -> String {
+        format!("worker-service:idempotency-cache:{key}")
+    }
+}
+
+#[async_trait]
+impl IdempotencyCache for RedisIdempotencyCache {
+    async fn get(&self, key: &str) -> Option<Bytes> {
+        match self
+            .pool
+            .with("idempotency_cache", "get")
+            .get::<Option<Bytes>, _>(Self::redis_key(key))
+            .await
+        {
+            Ok(result) => result,
+            Err(err) => {
+                warn!("Failed to read cached invocation result {key}: {err}");
+                None
+            }
+        }
+    }
+
+    async fn put(&self, key: &str, result: Bytes) {
+        let written = self
+            .pool
+            .with("idempotency_cache", "put")
+            .set::<(), _, _>(
+                Self::redis_key(key),
+                result,
+                Some(Expiration::PX(self.ttl.as_millis().max(1) as i64)),
+                None,
+                false,
+            )
+            .await;
+        if let Err(err) = written {
+            warn!("Failed to cache invocation result {key}: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::time::Duration;
+
+    use bytes::Bytes;
+    use golem_common::model::{ComponentId, IdempotencyKey, TargetWorkerId};
+
+    use crate::service::worker::{
+        configured_idempotency_cache, idempotency_cache_key, IdempotencyCache,
+        IdempotencyCacheConfig, InMemoryIdempotencyCache,
+    };
+
+    fn target(component_id: &ComponentId, worker_name: Option<&str>) -> TargetWorkerId {
+        TargetWorkerId {
+            component_id: component_id.clone(),
+            worker_name: worker_name.map(|name| name.to_string()),
+        }
+    }
+
+    #[test]
+    fn only_named_workers_with_given_keys_are_cached() {
+        let component_id = ComponentId::new_v4();
+        let key = IdempotencyKey::fresh();
+
+        assert_eq!(
+            idempotency_cache_key(&target(&component_id, None), Some(&key), true),
+            None
+        );
+        assert_eq!(
+            idempotency_cache_key(&target(&component_id, Some("worker-1")), None, true),
+            None
+        );
+        assert!(
+            idempotency_cache_key(&target(&component_id, Some("worker-1")), Some(&key), true)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn cache_keys_are_distinct_per_worker_and_result_kind() {
+        let component_id = ComponentId::new_v4();
+        let key = IdempotencyKey::fresh();
+        let worker_1 = target(&component_id, Some("worker-1"));
+
+        let typed = idempotency_cache_key(&worker_1, Some(&key), true);
+        assert_eq!(typed, idempotency_cache_key(&worker_1, Some(&key), true));
+        assert_ne!(typed, idempotency_cache_key(&worker_1, Some(&key), false));
+        assert_ne!(
+            typed,
+            idempotency_cache_key(&target(&component_id, Some("worker-2")), Some(&key), true)
+        );
+        assert_ne!(
+            typed,
+            idempotency_cache_key(
+                &target(&ComponentId::new_v4(), Some("worker-1")),
+                Some(&key),
+                true
+            )
+        );
+        assert_ne!(
+            typed,
+            idempotency_cache_key(&worker_1, Some(&IdempotencyKey::fresh()), true)
+        );
+    }
+
+    #[test]
+    async fn disabled_by_default() {
+        assert!(
+            configured_idempotency_cache(&IdempotencyCacheConfig::default())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    async fn in_memory_cache_keeps_the_first_result() {
+        let cache = InMemoryIdempotencyCache::new(Duration::from_secs(60), 16);
+
+        assert_eq!(cache.get("worker/key/typed").await, None);
+
+        cache
+            .put("worker/key/typed", Bytes::from_static(b"first"))
+            .await;
+        cache
+            .put("worker/key/typed", Bytes::from_static(b"second"))
+            .await;
+
+        assert_eq!(
+            cache.get("worker/key/typed").await,
+            Some(Bytes::from_static(b"first"))
+        );
+        assert_eq!(cache.get("worker/key/untyped").await, None);
+    }
+
+    #[test]
+    async fn in_memory_cache_is_bounded() {
+        let cache = InMemoryIdempotencyCache::new(Duration::from_secs(60), 2);
+
+        // The least recently used result is evicted, so the access times must differ
+        for key in ["key-1", "key-2", "key-3"] {
+            cache.put(key, Bytes::from(key)).await;
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        assert_eq!(cache.get("key-1").await, None);
+        assert_eq!(cache.get("key-3").await, Some(Bytes::from("key-3")));
+    }
+
+    #[test]
+    async fn in_memory_results_expire() {
+        let cache = InMemoryIdempotencyCache::new(Duration::from_millis(100), 16);
+
+        cache.put("key", Bytes::from_static(b"result")).await;
+        assert_eq!(cache.get("key").await, Some(Bytes::from_static(b"result")));
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(cache.get("key").await, None);
+    }
+}
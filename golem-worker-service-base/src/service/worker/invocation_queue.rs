@@ -0,0 +1,561 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use golem_api_grpc::proto::golem::workerexecutor;
+use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
+use golem_api_grpc::proto::golem::workerexecutor::v1::InvokeWorkerRequest;
+use golem_common::client::MultiTargetGrpcClient;
+use golem_common::config::RetryConfig;
+use golem_common::model::TargetWorkerId;
+use golem_service_base::routing_table::{HasRoutingTableService, RoutingTableService};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+use tonic::transport::Channel;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::metrics::invocation_queue::{
+    record_delivered, record_depth, record_enqueued, record_expired,
+};
+use crate::repo::pending_invocation::{PendingInvocationRecord, PendingInvocationRepo};
+use crate::service::maintenance::MaintenanceService;
+
+use super::{HasWorkerExecutorClients, RoutingLogic, WorkerServiceError};
+
+/// Configuration of the durable queue of fire-and-forget invocations.
+///
+/// When enabled, invocations which could not be delivered to a worker executor within
+/// `enqueue_after` (because all executors of the worker's shard are unreachable) are stored in
+/// the database and delivered in the background once the shard recovers, instead of blocking
+/// the caller until then.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InvocationQueueConfig {
+    pub enabled: bool,
+    #[serde(with = "humantime_serde")]
+    pub enqueue_after: Duration,
+    /// Queued invocations not delivered within this time are dropped
+    #[serde(with = "humantime_serde")]
+    pub ttl: Duration,
+    #[serde(with = "humantime_serde")]
+    pub delivery_interval: Duration,
+    #[serde(with = "humantime_serde")]
+    pub delivery_timeout: Duration,
+    pub delivery_batch_size: u32,
+}
+
+impl Default for InvocationQueueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            enqueue_after: Duration::from_secs(30),
+            ttl: Duration::from_secs(24 * 60 * 60),
+            delivery_interval: Duration::from_secs(10),
+            delivery_timeout: Duration::from_secs(5),
+            delivery_batch_size: 100,
+        }
+    }
+}
+
+pub struct InvocationQueue {
+    delivery: Arc<InvocationDelivery>,
+    deliverer: JoinHandle<()>,
+}
+
+impl InvocationQueue {
+    pub fn new(
+        config: InvocationQueueConfig,
+        repo: Arc<dyn PendingInvocationRepo + Send + Sync>,
+        worker_executor_clients: MultiTargetGrpcClient<WorkerExecutorClient<Channel>>,
+        worker_executor_retries: RetryConfig,
+        routing_table_service: Arc<dyn RoutingTableService + Send + Sync>,
+        maintenance_service: Arc<dyn MaintenanceService + Send + Sync>,
+    ) -> Self {
+        let delivery = Arc::new(InvocationDelivery {
+            config,
+            repo,
+            worker_executor_clients,
+            worker_executor_retries,
+            routing_table_service,
+            maintenance_service,
+        });
+        let deliverer = tokio::spawn(delivery.clone().run());
+        Self {
+            delivery,
+            deliverer,
+        }
+    }
+
+    /// How long an invocation is attempted to be delivered directly before it gets queued
+    pub fn enqueue_after(&self) -> Duration {
+        self.delivery.config.enqueue_after
+    }
+
+    /// Stores an invocation to be delivered later. The request should have an idempotency key,
+    /// so a delivery attempt that reached the executor before being given up on is not repeated.
+    pub async fn enqueue(
+        &self,
+        worker_id: &TargetWorkerId,
+        request: InvokeWorkerRequest,
+    ) -> Result<(), WorkerServiceError> {
+        let now = chrono::Utc::now();
+        let record = PendingInvocationRecord {
+            id: Uuid::new_v4().to_string(),
+            worker_id: worker_id.to_string(),
+            data: request.encode_to_vec(),
+            created_at: now,
+            expires_at: now + self.delivery.config.ttl,
+        };
+        self.delivery.repo.create(&record).await.map_err(|err| {
+            WorkerServiceError::Internal(format!("Failed to queue invocation: {err}"))
+        })?;
+
+        warn!(
+            worker_id = record.worker_id,
+            invocation_id = record.id,
+            "Worker executor unreachable, invocation queued for later delivery"
+        );
+        record_enqueued();
+        self.delivery.update_depth().await;
+        Ok(())
+    }
+}
+
+impl Drop for InvocationQueue {
+    fn drop(&mut self) {
+        self.deliverer.abort();
+    }
+}
+
+struct InvocationDelivery {
+    config: InvocationQueueConfig,
+    repo: Arc<dyn PendingInvocationRepo + Send + Sync>,
+    worker_executor_clients: MultiTargetGrpcClient<WorkerExecutorClient<Channel>>,
+    worker_executor_retries: RetryConfig,
+    routing_table_service: Arc<dyn RoutingTableService + Send + Sync>,
+    maintenance_service: Arc<dyn MaintenanceService + Send + Sync>,
+}
+
+impl HasRoutingTableService for InvocationDelivery {
+    fn routing_table_service(&self) -> &Arc<dyn RoutingTableService + Send + Sync> {
+        &self.routing_table_service
+    }
+}
+
+impl HasWorkerExecutorClients for InvocationDelivery {
+    fn worker_executor_clients(&self) -> &MultiTargetGrpcClient<WorkerExecutorClient<Channel>> {
+        &self.worker_executor_clients
+    }
+
+    fn worker_executor_retry_config(&self) -> &RetryConfig {
+        &self.worker_executor_retries
+    }
+}
+
+impl InvocationDelivery {
+    async fn run(self: Arc<Self>) {
+        // Invocations queued by a previous instance of the service are delivered too
+        self.update_depth().await;
+        loop {
+            tokio::time::sleep(self.config.delivery_interval).await;
+            self.deliver_pending().await;
+        }
+    }
+
+    /// Delivers the oldest pending invocations, keeping the ones whose executor is still
+    /// unreachable or whose component is in maintenance.
+    ///
+    /// With multiple worker service instances an invocation may be delivered more than once,
+    /// which is handled by the executor based on its idempotency key.
+    async fn deliver_pending(&self) {
+        match self.repo.delete_expired(chrono::Utc::now()).await {
+            Ok(0) => {}
+            Ok(count) => {
+                warn!(count, "Dropped expired queued invocations");
+                record_expired(count);
+            }
+            Err(err) => error!("Failed to delete expired queued invocations: {err}"),
+        }
+
+        let pending = match self.repo.get_oldest(self.config.delivery_batch_size).await {
+            Ok(pending) => pending,
+            Err(err) => {
+                error!("Failed to get queued invocations: {err}");
+                return;
+            }
+        };
+
+        for record in pending {
+            if self.deliver(&record).await {
+                if let Err(err) = self.repo.delete(&record.id).await {
+                    error!(
+                        invocation_id = record.id,
+                        "Failed to delete delivered invocation: {err}"
+                    );
+                }
+            }
+        }
+
+        self.update_depth().await;
+    }
+
+    /// Tries to deliver a queued invocation, returning whether it can be removed from the queue
+    async fn deliver(&self, record: &PendingInvocationRecord) -> bool {
+        let request = match InvokeWorkerRequest::decode(record.data.as_slice()) {
+            Ok(request) => request,
+            Err(err) => {
+                error!(
+                    invocation_id = record.id,
+                    "Dropping undecodable queued invocation: {err}"
+                );
+                return true;
+            }
+        };
+        let worker_id = match request.worker_id.clone().map(TargetWorkerId::try_from) {
+            Some(Ok(worker_id)) => worker_id,
+            _ => {
+                error!(
+                    invocation_id = record.id,
+                    "Dropping queued invocation without a valid worker id"
+                );
+                return true;
+            }
+        };
+
+        // Held back until the maintenance ends, or dropped if it outlasts the invocation's ttl
+        if self
+            .maintenance_service
+            .in_maintenance(&worker_id.component_id)
+            .is_some()
+        {
+            return false;
+        }
+
+        match timeout(
+            self.config.delivery_timeout,
+            send_invoke_request(self, worker_id, request),
+        )
+        .await
+        {
+            Ok(Ok(())) => {
+                info!(
+                    worker_id = record.worker_id,
+                    invocation_id = record.id,
+                    "Delivered queued invocation"
+                );
+                record_delivered();
+                true
+            }
+            Ok(Err(err)) => {
+                warn!(
+                    worker_id = record.worker_id,
+                    invocation_id = record.id,
+                    "Dropping queued invocation rejected by the worker executor: {err}"
+                );
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    async fn update_depth(&self) {
+        match self.repo.count().await {
+            Ok(depth) => record_depth(depth),
+            Err(err) => error!("Failed to count queued invocations: {err}"),
+        }
+    }
+}
+
+/// Sends a fire-and-forget invocation request to the executor owning the worker
+pub(crate) async fn send_invoke_request<T: RoutingLogic + Sync>(
+    this: &T,
+    worker_id: TargetWorkerId,
+    request: InvokeWorkerRequest,
+) -> Result<(), WorkerServiceError> {
+    this.call_worker_executor(
+        worker_id,
+        move |worker_executor_client| {
+            info!("Invoke function");
+            Box::pin(worker_executor_client.invoke_worker(request.clone()))
+        },
+        |response| match response.into_inner() {
+            workerexecutor::v1::InvokeWorkerResponse {
+                result: Some(workerexecutor::v1::invoke_worker_response::Result::Success(_)),
+            } => Ok(()),
+            workerexecutor::v1::InvokeWorkerResponse {
+                result: Some(workerexecutor::v1::invoke_worker_response::Result::Failure(err)),
+            } => {
+                error!("Invoked function error: {err:?}");
+                Err(err.into())
+            }
+            workerexecutor::v1::InvokeWorkerResponse { .. } => Err("Empty response".into()),
+        },
+        WorkerServiceError::InternalCallError,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
+    use golem_api_grpc::proto::golem::workerexecutor::v1::InvokeWorkerRequest;
+    use golem_common::client::{GrpcClientConfig, MultiTargetGrpcClient};
+    use golem_common::config::RetryConfig;
+    use golem_common::model::maintenance::ComponentMaintenance;
+    use golem_common::model::{AccountId, ComponentId, IdempotencyKey, TargetWorkerId, Timestamp};
+    use golem_service_base::repo::RepoError;
+    use golem_service_base::routing_table::RoutingTableServiceNoop;
+    use prost::Message;
+
+    use crate::repo::pending_invocation::{PendingInvocationRecord, PendingInvocationRepo};
+    use crate::service::maintenance::{MaintenanceError, MaintenanceService};
+    use crate::service::worker::invocation_queue::InvocationDelivery;
+    use crate::service::worker::{InvocationQueue, InvocationQueueConfig};
+
+    #[derive(Default)]
+    struct TestPendingInvocationRepo {
+        records: Mutex<Vec<PendingInvocationRecord>>,
+    }
+
+    impl TestPendingInvocationRepo {
+        fn ids(&self) -> Vec<String> {
+            let records = self.records.lock().unwrap();
+            records.iter().map(|record| record.id.clone()).collect()
+        }
+    }
+
+    #[async_trait]
+    impl PendingInvocationRepo for TestPendingInvocationRepo {
+        async fn create(&self, invocation: &PendingInvocationRecord) -> Result<(), RepoError> {
+            self.records.lock().unwrap().push(invocation.clone());
+            Ok(())
+        }
+
+        async fn get_oldest(&self, limit: u32) -> Result<Vec<PendingInvocationRecord>, RepoError> {
+            let mut records = self.records.lock().unwrap().clone();
+            records.sort_by_key(|record| record.created_at);
+            records.truncate(limit as usize);
+            Ok(records)
+        }
+
+        async fn delete(&self, id: &str) -> Result<bool, RepoError> {
+            let mut records = self.records.lock().unwrap();
+            let count = records.len();
+            records.retain(|record| record.id != id);
+            Ok(records.len() < count)
+        }
+
+        async fn delete_expired(
+            &self,
+            now: chrono::DateTime<chrono::Utc>,
+        ) -> Result<u64, RepoError> {
+            let mut records = self.records.lock().unwrap();
+            let count = records.len();
+            records.retain(|record| record.expires_at >= now);
+            Ok((count - records.len()) as u64)
+        }
+
+        async fn count(&self) -> Result<u64, RepoError> {
+            Ok(self.records.lock().unwrap().len() as u64)
+        }
+    }
+
+    struct TestMaintenanceService {
+        in_maintenance: Option<ComponentId>,
+    }
+
+    #[async_trait]
+    impl MaintenanceService for TestMaintenanceService {
+        async fn get(
+            &self,
+            _account_id: &AccountId,
+            _component_id: &ComponentId,
+        ) -> Result<ComponentMaintenance, MaintenanceError> {
+            unimplemented!()
+        }
+
+        async fn start(
+            &self,
+            _account_id: &AccountId,
+            _component_id: &ComponentId,
+            _message: Option<String>,
+        ) -> Result<ComponentMaintenance, MaintenanceError> {
+            unimplemented!()
+        }
+
+        async fn end(
+            &self,
+            _account_id: &AccountId,
+            _component_id: &ComponentId,
+        ) -> Result<(), MaintenanceError> {
+            unimplemented!()
+        }
+
+        fn in_maintenance(&self, component_id: &ComponentId) -> Option<ComponentMaintenance> {
+            (self.in_maintenance.as_ref() == Some(component_id)).then(|| ComponentMaintenance {
+                message: None,
+                started_at: Timestamp::now_utc(),
+            })
+        }
+    }
+
+    fn config() -> InvocationQueueConfig {
+        InvocationQueueConfig {
+            enabled: true,
+            delivery_interval: Duration::from_secs(3600),
+            delivery_timeout: Duration::from_millis(200),
+            ..Default::default()
+        }
+    }
+
+    fn worker_executor_clients(
+    ) -> MultiTargetGrpcClient<WorkerExecutorClient<tonic::transport::Channel>> {
+        MultiTargetGrpcClient::new(WorkerExecutorClient::new, GrpcClientConfig::default())
+    }
+
+    fn delivery(
+        repo: Arc<TestPendingInvocationRepo>,
+        in_maintenance: Option<ComponentId>,
+    ) -> InvocationDelivery {
+        InvocationDelivery {
+            config: config(),
+            repo,
+            worker_executor_clients: worker_executor_clients(),
+            worker_executor_retries: RetryConfig::default(),
+            // Every executor is unreachable
+            routing_table_service: Arc::new(RoutingTableServiceNoop {}),
+            maintenance_service: Arc::new(TestMaintenanceService { in_maintenance }),
+        }
+    }
+
+    fn request(worker_id: &TargetWorkerId) -> InvokeWorkerRequest {
+        InvokeWorkerRequest {
+            worker_id: Some(worker_id.clone().into()),
+            name: "golem:it/api.{f}".to_string(),
+            idempotency_key: Some(IdempotencyKey::fresh().into()),
+            ..Default::default()
+        }
+    }
+
+    fn record(id: &str, data: Vec<u8>, expires_in: chrono::Duration) -> PendingInvocationRecord {
+        let now = chrono::Utc::now();
+        PendingInvocationRecord {
+            id: id.to_string(),
+            worker_id: "component/worker".to_string(),
+            data,
+            created_at: now,
+            expires_at: now + expires_in,
+        }
+    }
+
+    fn worker_id(component_id: &ComponentId) -> TargetWorkerId {
+        TargetWorkerId {
+            component_id: component_id.clone(),
+            worker_name: Some("worker".to_string()),
+        }
+    }
+
+    #[test]
+    async fn enqueued_invocations_are_stored_with_their_ttl() {
+        let repo = Arc::new(TestPendingInvocationRepo::default());
+        let queue = InvocationQueue::new(
+            config(),
+            repo.clone(),
+            worker_executor_clients(),
+            RetryConfig::default(),
+            Arc::new(RoutingTableServiceNoop {}),
+            Arc::new(TestMaintenanceService {
+                in_maintenance: None,
+            }),
+        );
+        let worker_id = worker_id(&ComponentId::new_v4());
+        let request = request(&worker_id);
+
+        queue.enqueue(&worker_id, request.clone()).await.unwrap();
+
+        let records = repo.records.lock().unwrap().clone();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].worker_id, worker_id.to_string());
+        assert_eq!(
+            InvokeWorkerRequest::decode(records[0].data.as_slice()).unwrap(),
+            request
+        );
+        assert_eq!(
+            records[0].expires_at - records[0].created_at,
+            chrono::Duration::from_std(config().ttl).unwrap()
+        );
+    }
+
+    #[test]
+    async fn expired_and_undecodable_invocations_are_dropped() {
+        let repo = Arc::new(TestPendingInvocationRepo::default());
+        let component_id = ComponentId::new_v4();
+        let data = request(&worker_id(&component_id)).encode_to_vec();
+        repo.create(&record("expired", data, -chrono::Duration::minutes(1)))
+            .await
+            .unwrap();
+        repo.create(&record(
+            "undecodable",
+            vec![0xff],
+            chrono::Duration::hours(1),
+        ))
+        .await
+        .unwrap();
+
+        delivery(repo.clone(), Some(component_id))
+            .deliver_pending()
+            .await;
+
+        assert!(repo.ids().is_empty());
+    }
+
+    #[test]
+    async fn invocations_of_components_in_maintenance_are_kept() {
+        let repo = Arc::new(TestPendingInvocationRepo::default());
+        let component_id = ComponentId::new_v4();
+        let data = request(&worker_id(&component_id)).encode_to_vec();
+        repo.create(&record("pending", data, chrono::Duration::hours(1)))
+            .await
+            .unwrap();
+
+        delivery(repo.clone(), Some(component_id))
+            .deliver_pending()
+            .await;
+
+        assert_eq!(repo.ids(), vec!["pending".to_string()]);
+    }
+
+    #[test]
+    async fn invocations_to_unreachable_executors_are_kept() {
+        let repo = Arc::new(TestPendingInvocationRepo::default());
+        let data = request(&worker_id(&ComponentId::new_v4())).encode_to_vec();
+        repo.create(&record("pending", data, chrono::Duration::hours(1)))
+            .await
+            .unwrap();
+
+        delivery(repo.clone(), None).deliver_pending().await;
+
+        assert_eq!(repo.ids(), vec!["pending".to_string()]);
+    }
+}
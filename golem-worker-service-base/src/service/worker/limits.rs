@@ -0,0 +1,256 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use golem_api_grpc::proto::golem::worker::BlobParameter;
+use golem_wasm_rpc::protobuf::Val as ProtoVal;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+use super::WorkerServiceError;
+
+/// Size and count limits of the requests accepted by the worker service.
+///
+/// These are checked before anything is sent to the worker executors, so oversized requests
+/// are rejected with an error naming the exceeded limit instead of failing on gRPC message size.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WorkerRequestLimitsConfig {
+    /// Maximum number of command line arguments of a new worker
+    pub max_arguments: usize,
+    /// Maximum total size of the environment variables (keys and values) of a new worker, in bytes
+    pub max_environment_size: usize,
+    /// Maximum number of parameters of an invocation
+    pub max_invocation_parameters: usize,
+    /// Maximum encoded size of the parameters of an invocation, in bytes
+    pub max_invocation_payload_size: usize,
+    /// Maximum size of a single parameter uploaded to the blob storage, in bytes
+    pub max_blob_parameter_size: usize,
+    /// Maximum number of workers a single connection can follow the events of
+    pub max_connected_workers: usize,
+}
+
+impl WorkerRequestLimitsConfig {
+    pub fn check_create(
+        &self,
+        arguments: &[String],
+        environment_variables: &HashMap<String, String>,
+    ) -> Result<(), WorkerServiceError> {
+        check(
+            WorkerRequestLimit::Arguments,
+            arguments.len(),
+            self.max_arguments,
+        )?;
+        check(
+            WorkerRequestLimit::EnvironmentSize,
+            environment_variables
+                .iter()
+                .map(|(key, value)| key.len() + value.len())
+                .sum(),
+            self.max_environment_size,
+        )
+    }
+
+    pub fn check_invocation(
+        &self,
+        params: &[ProtoVal],
+        blob_params: &[BlobParameter],
+    ) -> Result<(), WorkerServiceError> {
+        check(
+            WorkerRequestLimit::InvocationParameters,
+            params.len() + blob_params.len(),
+            self.max_invocation_parameters,
+        )?;
+        check(
+            WorkerRequestLimit::InvocationPayloadSize,
+            params.iter().map(|param| param.encoded_len()).sum(),
+            self.max_invocation_payload_size,
+        )?;
+        for blob_param in blob_params {
+            self.check_blob_parameter_size(blob_param.size as usize)?;
+        }
+        Ok(())
+    }
+
+    pub fn check_blob_parameter_size(&self, size: usize) -> Result<(), WorkerServiceError> {
+        check(
+            WorkerRequestLimit::BlobParameterSize,
+            size,
+            self.max_blob_parameter_size,
+        )
+    }
+
+    pub fn check_connected_workers(&self, count: usize) -> Result<(), WorkerServiceError> {
+        check(
+            WorkerRequestLimit::ConnectedWorkers,
+            count,
+            self.max_connected_workers,
+        )
+    }
+}
+
+impl Default for WorkerRequestLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_arguments: 1024,
+            max_environment_size: 128 * 1024,
+            max_invocation_parameters: 1024,
+            // leaves room for the rest of the request within the default 4 MiB gRPC message limit
+            max_invocation_payload_size: 3 * 1024 * 1024,
+            // the executor expands a list<u8> parameter into one value per byte
+            max_blob_parameter_size: 16 * 1024 * 1024,
+            max_connected_workers: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerRequestLimit {
+    Arguments,
+    EnvironmentSize,
+    InvocationParameters,
+    InvocationPayloadSize,
+    BlobParameterSize,
+    ConnectedWorkers,
+}
+
+impl Display for WorkerRequestLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkerRequestLimit::Arguments => write!(f, "number of arguments"),
+            WorkerRequestLimit::EnvironmentSize => {
+                write!(f, "total size of environment variables in bytes")
+            }
+            WorkerRequestLimit::InvocationParameters => {
+                write!(f, "number of invocation parameters")
+            }
+            WorkerRequestLimit::InvocationPayloadSize => {
+                write!(f, "size of invocation parameters in bytes")
+            }
+            WorkerRequestLimit::BlobParameterSize => {
+                write!(f, "size of a blob parameter in bytes")
+            }
+            WorkerRequestLimit::ConnectedWorkers => write!(f, "number of connected workers"),
+        }
+    }
+}
+
+fn check(limit: WorkerRequestLimit, actual: usize, max: usize) -> Result<(), WorkerServiceError> {
+    if actual > max {
+        Err(WorkerServiceError::LimitExceeded { limit, actual, max })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+
+    use golem_api_grpc::proto::golem::worker::{BlobParameter, BlobParameterEncoding};
+    use golem_wasm_rpc::protobuf::Val as ProtoVal;
+    use golem_wasm_rpc::Value;
+
+    use super::{WorkerRequestLimit, WorkerRequestLimitsConfig};
+    use crate::service::worker::WorkerServiceError;
+
+    fn limits() -> WorkerRequestLimitsConfig {
+        WorkerRequestLimitsConfig {
+            max_arguments: 2,
+            max_environment_size: 8,
+            max_invocation_parameters: 2,
+            max_invocation_payload_size: 16,
+            max_blob_parameter_size: 64,
+            max_connected_workers: 2,
+        }
+    }
+
+    fn exceeded(result: Result<(), WorkerServiceError>) -> Option<(WorkerRequestLimit, usize)> {
+        match result {
+            Err(WorkerServiceError::LimitExceeded { limit, actual, .. }) => Some((limit, actual)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn create_limits() {
+        let limits = limits();
+        let env = HashMap::from([("KEY".to_string(), "VALUE".to_string())]);
+        assert!(limits.check_create(&["a".to_string()], &env).is_ok());
+        assert_eq!(
+            exceeded(
+                limits.check_create(&["a".to_string(), "b".to_string(), "c".to_string()], &env)
+            ),
+            Some((WorkerRequestLimit::Arguments, 3))
+        );
+        let env = HashMap::from([("KEY".to_string(), "LONG_VALUE".to_string())]);
+        assert_eq!(
+            exceeded(limits.check_create(&[], &env)),
+            Some((WorkerRequestLimit::EnvironmentSize, 13))
+        );
+    }
+
+    #[test]
+    fn invocation_limits() {
+        let limits = limits();
+        let string = |s: &str| ProtoVal::from(Value::String(s.to_string()));
+        assert!(limits.check_invocation(&[string("hello")], &[]).is_ok());
+        assert_eq!(
+            exceeded(limits.check_invocation(&[string("a"), string("b"), string("c")], &[])),
+            Some((WorkerRequestLimit::InvocationParameters, 3))
+        );
+        assert!(matches!(
+            exceeded(limits.check_invocation(&[string("a very long string parameter")], &[])),
+            Some((WorkerRequestLimit::InvocationPayloadSize, _))
+        ));
+    }
+
+    #[test]
+    fn blob_parameter_limits() {
+        let limits = limits();
+        let blob_param = |size: u64| BlobParameter {
+            index: 0,
+            key: "payload".to_string(),
+            size,
+            encoding: BlobParameterEncoding::Bytes as i32,
+        };
+        assert!(limits.check_invocation(&[], &[blob_param(64)]).is_ok());
+        assert!(limits.check_blob_parameter_size(64).is_ok());
+        assert_eq!(
+            exceeded(limits.check_blob_parameter_size(65)),
+            Some((WorkerRequestLimit::BlobParameterSize, 65))
+        );
+        assert_eq!(
+            exceeded(limits.check_invocation(&[], &[blob_param(65)])),
+            Some((WorkerRequestLimit::BlobParameterSize, 65))
+        );
+        assert_eq!(
+            exceeded(limits.check_invocation(&[], &[blob_param(1), blob_param(1), blob_param(1)])),
+            Some((WorkerRequestLimit::InvocationParameters, 3))
+        );
+    }
+
+    #[test]
+    fn connected_worker_limits() {
+        let limits = limits();
+        assert!(limits.check_connected_workers(2).is_ok());
+        assert_eq!(
+            exceeded(limits.check_connected_workers(3)),
+            Some((WorkerRequestLimit::ConnectedWorkers, 3))
+        );
+    }
+}
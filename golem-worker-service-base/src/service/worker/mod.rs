@@ -16,10 +16,26 @@ pub use connect_proxy::*;
 pub use connect_stream::*;
 pub use default::*;
 pub use error::*;
+pub use event_stream::*;
+pub use idempotency_cache::*;
+pub use invocation_queue::*;
+pub use limits::*;
+pub use result_stream::*;
 pub use routing_logic::*;
+pub use status_stream::*;
+pub use template::*;
+pub use validation::*;
 
 mod connect_proxy;
 mod connect_stream;
 mod default;
 mod error;
+mod event_stream;
+mod idempotency_cache;
+mod invocation_queue;
+mod limits;
+mod result_stream;
 mod routing_logic;
+mod status_stream;
+mod template;
+mod validation;
@@ -0,0 +1,103 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt};
+use golem_api_grpc::proto::golem::workerexecutor::v1::invoke_and_await_worker_streaming_response;
+use golem_api_grpc::proto::golem::workerexecutor::v1::InvokeAndAwaitWorkerStreamingResponse;
+use golem_service_base::model::{GolemError, GolemErrorUnknown};
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use tonic::{Status, Streaming};
+
+use crate::service::worker::{CallWorkerExecutorError, WorkerResult, WorkerServiceError};
+
+/// The result of an invocation, yielding the chunks sent by the worker executor as they arrive
+/// instead of buffering the whole result
+pub struct InvocationResultStream {
+    list_elements: bool,
+    inner: BoxStream<'static, WorkerResult<TypeAnnotatedValue>>,
+}
+
+impl InvocationResultStream {
+    /// Continues the executor's stream after its header, which was already received to check
+    /// whether the invocation failed
+    pub fn new(
+        list_elements: bool,
+        streaming: Streaming<InvokeAndAwaitWorkerStreamingResponse>,
+    ) -> Self {
+        Self::from_stream(list_elements, streaming.map(to_chunk).boxed())
+    }
+
+    pub fn from_stream(
+        list_elements: bool,
+        inner: BoxStream<'static, WorkerResult<TypeAnnotatedValue>>,
+    ) -> Self {
+        Self {
+            list_elements,
+            inner,
+        }
+    }
+
+    /// Whether the chunks are the elements of the list returned by the function, instead of a
+    /// single chunk holding its whole result
+    pub fn list_elements(&self) -> bool {
+        self.list_elements
+    }
+}
+
+impl Stream for InvocationResultStream {
+    type Item = WorkerResult<TypeAnnotatedValue>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+fn to_chunk(
+    response: Result<InvokeAndAwaitWorkerStreamingResponse, Status>,
+) -> WorkerResult<TypeAnnotatedValue> {
+    match response {
+        Ok(InvokeAndAwaitWorkerStreamingResponse {
+            result: Some(invoke_and_await_worker_streaming_response::Result::Chunk(chunk)),
+        }) => chunk
+            .type_annotated_value
+            .ok_or(WorkerServiceError::Internal(
+                "Empty result chunk".to_string(),
+            )),
+        Ok(InvokeAndAwaitWorkerStreamingResponse {
+            result: Some(invoke_and_await_worker_streaming_response::Result::Failure(err)),
+        }) => {
+            let golem_error: GolemError = err.try_into().unwrap_or_else(|_| {
+                GolemError::Unknown(GolemErrorUnknown {
+                    details: "Unknown worker execution error".to_string(),
+                })
+            });
+            Err(golem_error.into())
+        }
+        Ok(InvokeAndAwaitWorkerStreamingResponse {
+            result: Some(invoke_and_await_worker_streaming_response::Result::Header(_)),
+        }) => Err(WorkerServiceError::Internal(
+            "Unexpected result header".to_string(),
+        )),
+        Ok(InvokeAndAwaitWorkerStreamingResponse { result: None }) => {
+            Err(WorkerServiceError::Internal("Empty response".to_string()))
+        }
+        Err(status) => Err(WorkerServiceError::InternalCallError(
+            CallWorkerExecutorError::FailedToConnectToPod(status),
+        )),
+    }
+}
@@ -32,9 +32,12 @@ use golem_common::model::{Pod, ShardId, TargetWorkerId, WorkerId};
 use golem_common::retriable_error::IsRetriableError;
 use golem_common::retries::get_delay;
 use golem_common::SafeDisplay;
-use golem_service_base::model::{GolemError, GolemErrorInvalidShardId, GolemErrorUnknown};
-use golem_service_base::routing_table::{HasRoutingTableService, RoutingTableError};
+use golem_service_base::model::{
+    GolemError, GolemErrorInvalidShardId, GolemErrorUnknown, GolemErrorWorkerNotFound,
+};
+use golem_service_base::routing_table::{HasRoutingTableService, MissingEntity, RoutingTableError};
 
+use crate::metrics::invocation::{record_executor_call, record_routing};
 use crate::service::worker::WorkerServiceError;
 
 #[async_trait]
@@ -103,29 +106,33 @@ impl<Out: Send + 'static> CallOnExecutor<Out> for WorkerId {
             + Clone
             + 'static,
     {
-        let routing_table = context
+        let start = Instant::now();
+        let pod = context
             .routing_table_service()
-            .get_routing_table()
+            .lookup_worker(self)
             .await
             .map_err(CallWorkerExecutorErrorWithContext::failed_to_get_routing_table)?;
+        record_routing(start.elapsed());
 
-        match routing_table.lookup(self) {
+        match pod {
             None => Ok((None, None)),
-            Some(pod) => Ok((
-                Some(
-                    context
-                        .worker_executor_clients()
-                        .call(pod.uri_02(), f)
-                        .await
-                        .map_err(|err| {
-                            CallWorkerExecutorErrorWithContext::failed_to_connect_to_pod(
-                                err,
-                                pod.clone(),
-                            )
-                        })?,
-                ),
-                Some(pod.clone()),
-            )),
+            Some(pod) => {
+                let start = Instant::now();
+                let result = context
+                    .worker_executor_clients()
+                    .call(pod.uri_02(), f)
+                    .await;
+                record_executor_call(start.elapsed());
+                Ok((
+                    Some(result.map_err(|err| {
+                        CallWorkerExecutorErrorWithContext::failed_to_connect_to_pod(
+                            err,
+                            pod.clone(),
+                        )
+                    })?),
+                    Some(pod),
+                ))
+            }
         }
     }
 
@@ -193,29 +200,33 @@ impl<Out: Send + 'static> CallOnExecutor<Out> for RandomExecutor {
             + Clone
             + 'static,
     {
+        let start = Instant::now();
         let routing_table = context
             .routing_table_service()
             .get_routing_table()
             .await
             .map_err(CallWorkerExecutorErrorWithContext::failed_to_get_routing_table)?;
+        record_routing(start.elapsed());
 
         match routing_table.random() {
             None => Ok((None, None)),
-            Some(pod) => Ok((
-                Some(
-                    context
-                        .worker_executor_clients()
-                        .call(pod.uri_02(), f)
-                        .await
-                        .map_err(|status| {
-                            CallWorkerExecutorErrorWithContext::failed_to_connect_to_pod(
-                                status,
-                                pod.clone(),
-                            )
-                        })?,
-                ),
-                Some(pod.clone()),
-            )),
+            Some(pod) => {
+                let start = Instant::now();
+                let result = context
+                    .worker_executor_clients()
+                    .call(pod.uri_02(), f)
+                    .await;
+                record_executor_call(start.elapsed());
+                Ok((
+                    Some(result.map_err(|status| {
+                        CallWorkerExecutorErrorWithContext::failed_to_connect_to_pod(
+                            status,
+                            pod.clone(),
+                        )
+                    })?),
+                    Some(pod.clone()),
+                ))
+            }
         }
     }
 
@@ -414,6 +425,10 @@ impl<T: HasRoutingTableService + HasWorkerExecutorClients + Send + Sync> Routing
                     // NOP, retry
                 }
                 Err(error) => {
+                    if let Some(worker_id) = missing_worker(&error) {
+                        self.routing_table_service()
+                            .mark_missing(MissingEntity::Worker(worker_id));
+                    }
                     break Err(error);
                 }
             }
@@ -421,6 +436,17 @@ impl<T: HasRoutingTableService + HasWorkerExecutorClients + Send + Sync> Routing
     }
 }
 
+/// The worker an executor reported as not existing, if any
+fn missing_worker(error: &WorkerServiceError) -> Option<WorkerId> {
+    match error {
+        WorkerServiceError::WorkerNotFound(worker_id)
+        | WorkerServiceError::Golem(GolemError::WorkerNotFound(GolemErrorWorkerNotFound {
+            worker_id,
+        })) => Some(worker_id.clone()),
+        _ => None,
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum CallWorkerExecutorError {
     #[error("Failed to get routing table: {0}")]
@@ -0,0 +1,187 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::stream::{select_all, BoxStream, SelectAll};
+use futures::{Stream, StreamExt};
+use tonic::Status;
+
+use golem_api_grpc::proto::golem::worker::WorkerStatusEvent;
+use golem_common::metrics::api::{
+    record_closed_grpc_api_active_stream, record_new_grpc_api_active_stream,
+};
+
+/// The merged status changes of the watched workers, reported by all the worker executors.
+///
+/// Each executor only reports the workers it is running, so the executors have to be watched
+/// together. The stream fails once the stream of any of them ends, for example because the
+/// executor got restarted, and has to be reopened to get the current set of executors.
+pub struct WorkerStatusEventStream {
+    streams: SelectAll<BoxStream<'static, Option<Result<WorkerStatusEvent, Status>>>>,
+    closed: bool,
+}
+
+impl WorkerStatusEventStream {
+    pub fn new<S>(streams: Vec<S>) -> Self
+    where
+        S: Stream<Item = Result<WorkerStatusEvent, Status>> + Send + 'static,
+    {
+        record_new_grpc_api_active_stream();
+        Self {
+            // Each executor's stream is terminated by a `None`, marking where it ended
+            streams: select_all(streams.into_iter().map(|stream| {
+                stream
+                    .map(Some)
+                    .chain(futures::stream::once(async { None }))
+                    .boxed()
+            })),
+            closed: false,
+        }
+    }
+}
+
+impl Stream for WorkerStatusEventStream {
+    type Item = Result<WorkerStatusEvent, Status>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<WorkerStatusEvent, Status>>> {
+        if self.closed {
+            return Poll::Ready(None);
+        }
+        match self.streams.poll_next_unpin(cx) {
+            Poll::Ready(Some(Some(event))) => Poll::Ready(Some(event)),
+            Poll::Ready(Some(None)) => {
+                self.closed = true;
+                Poll::Ready(Some(Err(Status::unavailable(
+                    "The connection to a worker executor got closed, the workers have to be watched again",
+                ))))
+            }
+            Poll::Ready(None) => {
+                self.closed = true;
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for WorkerStatusEventStream {
+    fn drop(&mut self) {
+        record_closed_grpc_api_active_stream();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use futures::stream::{iter, pending};
+    use futures::{Stream, StreamExt};
+    use tonic::{Code, Status};
+
+    use golem_api_grpc::proto::golem::worker::WorkerStatusEvent;
+    use golem_common::model::worker_status_event::WorkerStatusEventKind;
+    use golem_common::model::{ComponentId, Timestamp, WorkerId, WorkerStatus};
+
+    use super::WorkerStatusEventStream;
+
+    fn event(worker_name: &str) -> WorkerStatusEvent {
+        golem_common::model::worker_status_event::WorkerStatusEvent {
+            worker_id: WorkerId {
+                component_id: ComponentId::new_v4(),
+                worker_name: worker_name.to_string(),
+            },
+            timestamp: Timestamp::now_utc(),
+            kind: WorkerStatusEventKind::Created,
+            status: WorkerStatus::Idle,
+        }
+        .into()
+    }
+
+    /// An executor's stream reporting the given events and then staying open
+    fn open_executor_stream(
+        events: Vec<WorkerStatusEvent>,
+    ) -> impl Stream<Item = Result<WorkerStatusEvent, Status>> + Send + 'static {
+        iter(events.into_iter().map(Ok)).chain(pending())
+    }
+
+    fn worker_name(event: &Result<WorkerStatusEvent, Status>) -> String {
+        event
+            .as_ref()
+            .unwrap()
+            .worker_id
+            .as_ref()
+            .unwrap()
+            .name
+            .clone()
+    }
+
+    #[test]
+    async fn events_of_all_executors_are_merged() {
+        let stream = WorkerStatusEventStream::new(vec![
+            open_executor_stream(vec![event("worker-1"), event("worker-2")]),
+            open_executor_stream(vec![event("worker-3")]),
+        ]);
+
+        let mut names = stream
+            .take(3)
+            .map(|event| worker_name(&event))
+            .collect::<Vec<_>>()
+            .await;
+        names.sort();
+
+        assert_eq!(names, vec!["worker-1", "worker-2", "worker-3"]);
+    }
+
+    #[test]
+    async fn stream_fails_and_ends_when_an_executor_stream_ends() {
+        let stream = WorkerStatusEventStream::new(vec![
+            open_executor_stream(vec![]).boxed(),
+            iter(vec![Ok(event("worker-1"))]).boxed(),
+        ]);
+
+        let events = stream.collect::<Vec<_>>().await;
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(worker_name(&events[0]), "worker-1");
+        assert_eq!(events[1].as_ref().unwrap_err().code(), Code::Unavailable);
+    }
+
+    #[test]
+    async fn executor_errors_are_passed_through() {
+        let stream = WorkerStatusEventStream::new(vec![iter(vec![
+            Err(Status::data_loss("Lagged by 2 events")),
+            Ok(event("worker-1")),
+        ])
+        .chain(pending())]);
+
+        let events = stream.take(2).collect::<Vec<_>>().await;
+
+        assert_eq!(events[0].as_ref().unwrap_err().code(), Code::DataLoss);
+        assert_eq!(worker_name(&events[1]), "worker-1");
+    }
+
+    #[test]
+    async fn stream_without_executors_ends_immediately() {
+        let stream = WorkerStatusEventStream::new(Vec::<
+            futures::stream::Empty<Result<WorkerStatusEvent, Status>>,
+        >::new());
+
+        assert_eq!(stream.count().await, 0);
+    }
+}
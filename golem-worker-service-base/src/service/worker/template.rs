@@ -0,0 +1,207 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use golem_common::model::{AccountId, ComponentVersion, WorkerId};
+
+use super::WorkerServiceError;
+
+const PLACEHOLDERS: &str = "worker_name, component_id, component_version, account_id";
+
+/// The account of the workers created by requests without one, as in the open source deployment
+const DEFAULT_ACCOUNT_ID: &str = "-1";
+
+/// Expands the `{{placeholder}}` templates in the arguments and environment variable values
+/// of a worker being created, so a fleet of workers can be created with the same parameters.
+/// A literal `{{` is written as `\{{`.
+pub struct WorkerCreationTemplate<'a> {
+    pub worker_id: &'a WorkerId,
+    pub component_version: ComponentVersion,
+    pub account_id: Option<&'a AccountId>,
+}
+
+impl WorkerCreationTemplate<'_> {
+    pub fn expand(
+        &self,
+        arguments: Vec<String>,
+        environment_variables: HashMap<String, String>,
+    ) -> Result<(Vec<String>, HashMap<String, String>), WorkerServiceError> {
+        let arguments = arguments
+            .iter()
+            .map(|arg| {
+                self.expand_template(arg)
+                    .map_err(WorkerServiceError::InvalidTemplate)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let environment_variables = environment_variables
+            .iter()
+            .map(|(key, value)| {
+                self.expand_template(value)
+                    .map(|value| (key.clone(), value))
+                    .map_err(|err| {
+                        WorkerServiceError::InvalidTemplate(format!(
+                            "environment variable {key}: {err}"
+                        ))
+                    })
+            })
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok((arguments, environment_variables))
+    }
+
+    fn expand_template(&self, template: &str) -> Result<String, String> {
+        let mut result = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            if let Some(escaped) = rest[..start].strip_suffix('\\') {
+                result.push_str(escaped);
+                result.push_str("{{");
+                rest = &rest[start + 2..];
+                continue;
+            }
+            result.push_str(&rest[..start]);
+            let placeholder = &rest[start + 2..];
+            let end = placeholder
+                .find("}}")
+                .ok_or_else(|| format!("unterminated placeholder in {template:?}"))?;
+            result.push_str(&self.value(placeholder[..end].trim(), template)?);
+            rest = &placeholder[end + 2..];
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+
+    fn value(&self, placeholder: &str, template: &str) -> Result<String, String> {
+        match placeholder {
+            "worker_name" => Ok(self.worker_id.worker_name.clone()),
+            "component_id" => Ok(self.worker_id.component_id.to_string()),
+            "component_version" => Ok(self.component_version.to_string()),
+            "account_id" => Ok(self
+                .account_id
+                .map(|account_id| account_id.value.clone())
+                .unwrap_or_else(|| DEFAULT_ACCOUNT_ID.to_string())),
+            _ => Err(format!(
+                "unknown placeholder {placeholder:?} in {template:?}, known ones: {PLACEHOLDERS}"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    use golem_common::model::{AccountId, ComponentId, WorkerId};
+
+    use super::WorkerCreationTemplate;
+    use crate::service::worker::WorkerServiceError;
+
+    fn worker_id() -> WorkerId {
+        WorkerId {
+            component_id: ComponentId::from_str("4d4b5ba4-3fa2-4b2c-9f34-4d0d6c1d0fd1").unwrap(),
+            worker_name: "shop-eu-1".to_string(),
+        }
+    }
+
+    #[test]
+    fn expands_placeholders() {
+        let worker_id = worker_id();
+        let account_id = AccountId {
+            value: "account-1".to_string(),
+        };
+        let template = WorkerCreationTemplate {
+            worker_id: &worker_id,
+            component_version: 3,
+            account_id: Some(&account_id),
+        };
+
+        let (args, env) = template
+            .expand(
+                vec!["--name={{worker_name}}".to_string(), "plain".to_string()],
+                HashMap::from([
+                    (
+                        "ORIGIN".to_string(),
+                        "{{ account_id }}/{{component_id}}@{{component_version}}".to_string(),
+                    ),
+                    ("BRACES".to_string(), "{single}".to_string()),
+                ]),
+            )
+            .unwrap();
+
+        assert_eq!(args, vec!["--name=shop-eu-1", "plain"]);
+        assert_eq!(
+            env.get("ORIGIN").unwrap(),
+            "account-1/4d4b5ba4-3fa2-4b2c-9f34-4d0d6c1d0fd1@3"
+        );
+        assert_eq!(env.get("BRACES").unwrap(), "{single}");
+    }
+
+    #[test]
+    fn rejects_invalid_placeholders() {
+        let worker_id = worker_id();
+        let template = WorkerCreationTemplate {
+            worker_id: &worker_id,
+            component_version: 0,
+            account_id: None,
+        };
+
+        for arg in ["{{region}}", "{{worker_name", "{{}}"] {
+            assert!(matches!(
+                template.expand(vec![arg.to_string()], HashMap::new()),
+                Err(WorkerServiceError::InvalidTemplate(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn escaped_braces_are_kept() {
+        let worker_id = worker_id();
+        let template = WorkerCreationTemplate {
+            worker_id: &worker_id,
+            component_version: 0,
+            account_id: None,
+        };
+
+        let (args, _) = template
+            .expand(
+                vec![
+                    r"\{{worker_name}}={{worker_name}}".to_string(),
+                    r"a\b\{{".to_string(),
+                ],
+                HashMap::new(),
+            )
+            .unwrap();
+
+        assert_eq!(args, vec!["{{worker_name}}=shop-eu-1", r"a\b{{"]);
+    }
+
+    #[test]
+    fn account_id_defaults_without_an_account() {
+        let worker_id = worker_id();
+        let template = WorkerCreationTemplate {
+            worker_id: &worker_id,
+            component_version: 0,
+            account_id: None,
+        };
+
+        let (args, _) = template
+            .expand(vec!["{{account_id}}".to_string()], HashMap::new())
+            .unwrap();
+
+        assert_eq!(args, vec!["-1"]);
+    }
+}
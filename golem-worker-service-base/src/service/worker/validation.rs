@@ -0,0 +1,381 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::path::{Component, Path};
+
+use golem_api_grpc::proto::golem::worker::BlobParameter;
+use golem_common::model::exports::function_by_name;
+use golem_common::type_check::TypeCheckIn;
+use golem_service_base::model::{ParameterValidationResult, ValidateInvocationResponse};
+use golem_wasm_ast::analysis::{AnalysedExport, AnalysedFunctionParameter};
+use golem_wasm_rpc::json::TypeAnnotatedValueJsonExtensions;
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use golem_wasm_rpc::protobuf::Val as ProtoVal;
+use golem_wasm_rpc::type_annotated_value_from_str;
+use rib::{ParsedFunctionName, ParsedFunctionReference};
+
+/// The outcome of checking an invocation against the exports of a component, without invoking it
+#[derive(Debug, Clone)]
+pub struct InvocationValidation {
+    /// Errors not related to a single parameter, such as an unknown function
+    pub errors: Vec<String>,
+    pub parameters: Vec<ParameterValidation>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParameterValidation {
+    /// The parameter's name in the function's signature, or its position if there is no such parameter
+    pub name: String,
+    /// The parameter coerced to the type expected by the function, if it type checks
+    pub value: Option<(TypeAnnotatedValue, ProtoVal)>,
+    pub errors: Vec<String>,
+}
+
+impl InvocationValidation {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+            && self
+                .parameters
+                .iter()
+                .all(|parameter| parameter.errors.is_empty())
+    }
+
+    /// The coerced parameters, ready to be sent to the executor, if all of them are valid
+    pub fn params(&self) -> Option<Vec<ProtoVal>> {
+        if self.is_valid() {
+            self.parameters
+                .iter()
+                .map(|parameter| parameter.value.as_ref().map(|(_, value)| value.clone()))
+                .collect()
+        } else {
+            None
+        }
+    }
+}
+
+impl From<InvocationValidation> for ValidateInvocationResponse {
+    fn from(validation: InvocationValidation) -> Self {
+        Self {
+            valid: validation.is_valid(),
+            errors: validation.errors,
+            parameters: validation
+                .parameters
+                .into_iter()
+                .map(|parameter| ParameterValidationResult {
+                    name: parameter.name,
+                    value: parameter.value.map(|(value, _)| value),
+                    errors: parameter.errors,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Resolves a function name in a component's exports and type checks the parameters against
+/// the function's signature, coercing them to the expected types.
+pub fn validate_invocation(
+    exports: &Vec<AnalysedExport>,
+    function_name: &str,
+    params: Vec<TypeAnnotatedValue>,
+) -> InvocationValidation {
+    match expected_parameters(exports, function_name) {
+        Ok(expected) => {
+            let mut errors = Vec::new();
+            if expected.len() != params.len() {
+                errors.push(format!(
+                    "Function {function_name} expects {} parameters, but {} were given",
+                    expected.len(),
+                    params.len()
+                ));
+            }
+
+            let parameters = params
+                .into_iter()
+                .enumerate()
+                .map(|(idx, param)| match expected.get(idx) {
+                    Some(expected) => validate_parameter(expected, param),
+                    None => ParameterValidation {
+                        name: idx.to_string(),
+                        value: None,
+                        errors: vec!["Unexpected parameter".to_string()],
+                    },
+                })
+                .collect();
+
+            InvocationValidation { errors, parameters }
+        }
+        Err(error) => InvocationValidation {
+            errors: vec![error],
+            parameters: Vec::new(),
+        },
+    }
+}
+
+/// Parses the parameters of a function call written in WAVE (WebAssembly Value Encoding) text
+/// form, using the types of the function's parameters in the component's exports.
+pub fn parse_wave_parameters(
+    exports: &Vec<AnalysedExport>,
+    function_name: &str,
+    params: &[String],
+) -> Result<Vec<TypeAnnotatedValue>, Vec<String>> {
+    let expected = expected_parameters(exports, function_name).map_err(|err| vec![err])?;
+    if expected.len() != params.len() {
+        return Err(vec![format!(
+            "Function {function_name} expects {} parameters, but {} were given",
+            expected.len(),
+            params.len()
+        )]);
+    }
+
+    let mut values = Vec::new();
+    let mut errors = Vec::new();
+    for (expected, param) in expected.iter().zip(params) {
+        match type_annotated_value_from_str(&expected.typ, param) {
+            Ok(value) => values.push(value),
+            Err(err) => errors.push(format!(
+                "Failed to parse parameter {} in WAVE format: {err}",
+                expected.name
+            )),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Checks that the blob parameters of an invocation with `value_count` parameters passed by value
+/// fill distinct positions among the function's parameters, and refer to valid storage keys.
+///
+/// The payloads themselves are only read and type checked by the executor.
+pub fn validate_blob_parameters(
+    value_count: usize,
+    blob_params: &[BlobParameter],
+) -> Result<(), String> {
+    let count = value_count + blob_params.len();
+    let mut indices = HashSet::new();
+    for blob_param in blob_params {
+        if blob_param.index as usize >= count {
+            return Err(format!(
+                "Blob parameter index {} is out of range for {count} parameters",
+                blob_param.index
+            ));
+        }
+        if !indices.insert(blob_param.index) {
+            return Err(format!(
+                "Multiple blob parameters with index {}",
+                blob_param.index
+            ));
+        }
+        let path = Path::new(&blob_param.key);
+        if blob_param.key.is_empty()
+            || !path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(format!(
+                "Blob parameter key {} is not a relative path",
+                blob_param.key
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn expected_parameters(
+    exports: &Vec<AnalysedExport>,
+    function_name: &str,
+) -> Result<Vec<AnalysedFunctionParameter>, String> {
+    let parsed = ParsedFunctionName::parse(function_name)
+        .map_err(|err| format!("Invalid function name {function_name}: {err}"))?;
+
+    match parsed.function() {
+        // Dropping an indexed resource is handled by the executor, without calling any export
+        ParsedFunctionReference::IndexedResourceDrop { .. } => Ok(Vec::new()),
+        function => {
+            let exported = function_by_name(exports, function_name)?
+                .ok_or(format!("Function {function_name} not found"))?;
+
+            // The executor passes the handle of the indexed resource as the first parameter
+            let skipped = match function {
+                ParsedFunctionReference::IndexedResourceMethod { .. } => 1,
+                _ => 0,
+            };
+            Ok(exported.parameters.into_iter().skip(skipped).collect())
+        }
+    }
+}
+
+fn validate_parameter(
+    expected: &AnalysedFunctionParameter,
+    param: TypeAnnotatedValue,
+) -> ParameterValidation {
+    let result = param
+        .to_json_value()
+        .type_check_in(&expected.typ)
+        .and_then(|typed| {
+            let value = golem_wasm_rpc::Value::try_from(typed.clone()).map_err(|err| vec![err])?;
+            Ok((typed, ProtoVal::from(value)))
+        });
+
+    match result {
+        Ok(value) => ParameterValidation {
+            name: expected.name.clone(),
+            value: Some(value),
+            errors: Vec::new(),
+        },
+        Err(errors) => ParameterValidation {
+            name: expected.name.clone(),
+            value: None,
+            errors,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_api_grpc::proto::golem::worker::{BlobParameter, BlobParameterEncoding};
+    use golem_wasm_ast::analysis::analysed_type::{str, u32};
+    use golem_wasm_ast::analysis::{
+        AnalysedExport, AnalysedFunction, AnalysedFunctionParameter, AnalysedInstance,
+    };
+    use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+
+    use super::{parse_wave_parameters, validate_blob_parameters, validate_invocation};
+
+    fn exports() -> Vec<AnalysedExport> {
+        vec![AnalysedExport::Instance(AnalysedInstance {
+            name: "golem:it/api".to_string(),
+            functions: vec![AnalysedFunction {
+                name: "add-item".to_string(),
+                parameters: vec![
+                    AnalysedFunctionParameter {
+                        name: "name".to_string(),
+                        typ: str(),
+                    },
+                    AnalysedFunctionParameter {
+                        name: "quantity".to_string(),
+                        typ: u32(),
+                    },
+                ],
+                results: vec![],
+            }],
+        })]
+    }
+
+    #[test]
+    fn valid_invocation() {
+        let validation = validate_invocation(
+            &exports(),
+            "golem:it/api.{add-item}",
+            vec![
+                TypeAnnotatedValue::Str("apple".to_string()),
+                TypeAnnotatedValue::U32(2),
+            ],
+        );
+        assert!(validation.is_valid());
+        assert_eq!(validation.params().map(|params| params.len()), Some(2));
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let validation = validate_invocation(
+            &exports(),
+            "golem:it/api.{add-item}",
+            vec![
+                TypeAnnotatedValue::Str("apple".to_string()),
+                TypeAnnotatedValue::Str("two".to_string()),
+            ],
+        );
+        assert!(!validation.is_valid());
+        assert!(validation.errors.is_empty());
+        assert!(validation.parameters[0].errors.is_empty());
+        assert_eq!(validation.parameters[1].name, "quantity");
+        assert!(!validation.parameters[1].errors.is_empty());
+        assert_eq!(validation.params(), None);
+    }
+
+    #[test]
+    fn unknown_function() {
+        let validation = validate_invocation(&exports(), "golem:it/api.{remove-item}", vec![]);
+        assert!(!validation.is_valid());
+        assert_eq!(validation.errors.len(), 1);
+    }
+
+    #[test]
+    fn wrong_parameter_count() {
+        let validation = validate_invocation(
+            &exports(),
+            "golem:it/api.{add-item}",
+            vec![TypeAnnotatedValue::Str("apple".to_string())],
+        );
+        assert!(!validation.is_valid());
+        assert_eq!(validation.errors.len(), 1);
+        assert_eq!(validation.parameters.len(), 1);
+    }
+
+    #[test]
+    fn wave_parameters() {
+        let params = parse_wave_parameters(
+            &exports(),
+            "golem:it/api.{add-item}",
+            &["\"apple\"".to_string(), "2".to_string()],
+        );
+        assert_eq!(
+            params,
+            Ok(vec![
+                TypeAnnotatedValue::Str("apple".to_string()),
+                TypeAnnotatedValue::U32(2),
+            ])
+        );
+
+        let errors = parse_wave_parameters(
+            &exports(),
+            "golem:it/api.{add-item}",
+            &["\"apple\"".to_string(), "two".to_string()],
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 1);
+
+        assert!(parse_wave_parameters(
+            &exports(),
+            "golem:it/api.{add-item}",
+            &["\"apple\"".to_string()],
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn blob_parameters() {
+        let blob_param = |index: u32, key: &str| BlobParameter {
+            index,
+            key: key.to_string(),
+            size: 1024,
+            encoding: BlobParameterEncoding::Bytes as i32,
+        };
+
+        assert!(validate_blob_parameters(1, &[blob_param(0, "uploads/image.png")]).is_ok());
+        assert!(validate_blob_parameters(1, &[blob_param(1, "image.png")]).is_ok());
+        assert!(validate_blob_parameters(1, &[blob_param(2, "image.png")]).is_err());
+        assert!(validate_blob_parameters(0, &[blob_param(0, "a"), blob_param(0, "b")]).is_err());
+        assert!(validate_blob_parameters(0, &[blob_param(0, "../other/image.png")]).is_err());
+        assert!(validate_blob_parameters(0, &[blob_param(0, "/image.png")]).is_err());
+        assert!(validate_blob_parameters(0, &[blob_param(0, "")]).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use golem_common::model::worker_defaults::{ulid, WorkerDefaults, WorkerNameGeneration};
+use golem_common::model::{AccountId, ComponentId};
+use golem_common::SafeDisplay;
+use golem_service_base::repo::RepoError;
+use uuid::Uuid;
+
+use crate::repo::worker_defaults::{WorkerDefaultsRecord, WorkerDefaultsRepo};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkerDefaultsError {
+    #[error("Invalid worker defaults: {}", .0.join(", "))]
+    Invalid(Vec<String>),
+    #[error("Worker defaults not found")]
+    NotFound,
+    #[error("Internal repository error: {0}")]
+    InternalRepoError(RepoError),
+    #[error("Internal error: failed to convert worker defaults: {0}")]
+    InternalConversionError(String),
+}
+
+impl From<RepoError> for WorkerDefaultsError {
+    fn from(error: RepoError) -> Self {
+        WorkerDefaultsError::InternalRepoError(error)
+    }
+}
+
+impl SafeDisplay for WorkerDefaultsError {
+    fn to_safe_string(&self) -> String {
+        match self {
+            WorkerDefaultsError::Invalid(_) => self.to_string(),
+            WorkerDefaultsError::NotFound => self.to_string(),
+            WorkerDefaultsError::InternalRepoError(inner) => inner.to_safe_string(),
+            WorkerDefaultsError::InternalConversionError(_) => self.to_string(),
+        }
+    }
+}
+
+/// Manages the defaults applied to the workers created for an account. Defaults can be set
+/// for the whole account and for each of its components, the component level ones overriding
+/// the account level ones. They are applied both to the workers created explicitly and to the
+/// ones created implicitly by their first invocation.
+#[async_trait]
+pub trait WorkerDefaultsService {
+    /// Gets the defaults of the account, or of one of its components if `component_id` is given
+    async fn get(
+        &self,
+        account_id: &AccountId,
+        component_id: Option<&ComponentId>,
+    ) -> Result<WorkerDefaults, WorkerDefaultsError>;
+
+    async fn set(
+        &self,
+        account_id: &AccountId,
+        component_id: Option<&ComponentId>,
+        defaults: WorkerDefaults,
+    ) -> Result<(), WorkerDefaultsError>;
+
+    async fn delete(
+        &self,
+        account_id: &AccountId,
+        component_id: Option<&ComponentId>,
+    ) -> Result<(), WorkerDefaultsError>;
+
+    /// Gets the defaults applied to a new worker of the component, merging the account and the
+    /// component level defaults
+    async fn resolve(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+    ) -> Result<WorkerDefaults, WorkerDefaultsError>;
+
+    /// Generates the name of a new worker of the component, with the naming strategy of its
+    /// resolved defaults. The generated names are not checked against the existing workers.
+    async fn generate_worker_name(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+    ) -> Result<String, WorkerDefaultsError>;
+}
+
+pub struct WorkerDefaultsServiceDefault {
+    repo: Arc<dyn WorkerDefaultsRepo + Sync + Send>,
+}
+
+impl WorkerDefaultsServiceDefault {
+    pub fn new(repo: Arc<dyn WorkerDefaultsRepo + Sync + Send>) -> Self {
+        Self { repo }
+    }
+
+    fn component_key(component_id: Option<&ComponentId>) -> String {
+        component_id
+            .map(|component_id| component_id.to_string())
+            .unwrap_or_default()
+    }
+
+    fn decode(record: &WorkerDefaultsRecord) -> Result<WorkerDefaults, WorkerDefaultsError> {
+        serde_json::from_str(&record.defaults)
+            .map_err(|err| WorkerDefaultsError::InternalConversionError(err.to_string()))
+    }
+}
+
+#[async_trait]
+impl WorkerDefaultsService for WorkerDefaultsServiceDefault {
+    async fn get(
+        &self,
+        account_id: &AccountId,
+        component_id: Option<&ComponentId>,
+    ) -> Result<WorkerDefaults, WorkerDefaultsError> {
+        let record = self
+            .repo
+            .get(&account_id.value, &Self::component_key(component_id))
+            .await?
+            .ok_or(WorkerDefaultsError::NotFound)?;
+        Self::decode(&record)
+    }
+
+    async fn set(
+        &self,
+        account_id: &AccountId,
+        component_id: Option<&ComponentId>,
+        defaults: WorkerDefaults,
+    ) -> Result<(), WorkerDefaultsError> {
+        defaults.validate().map_err(WorkerDefaultsError::Invalid)?;
+        let defaults = serde_json::to_string(&defaults)
+            .map_err(|err| WorkerDefaultsError::InternalConversionError(err.to_string()))?;
+        self.repo
+            .upsert(&WorkerDefaultsRecord {
+                account_id: account_id.value.clone(),
+                component_id: Self::component_key(component_id),
+                defaults,
+            })
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(
+        &self,
+        account_id: &AccountId,
+        component_id: Option<&ComponentId>,
+    ) -> Result<(), WorkerDefaultsError> {
+        if self
+            .repo
+            .delete(&account_id.value, &Self::component_key(component_id))
+            .await?
+        {
+            Ok(())
+        } else {
+            Err(WorkerDefaultsError::NotFound)
+        }
+    }
+
+    async fn resolve(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+    ) -> Result<WorkerDefaults, WorkerDefaultsError> {
+        let records = self
+            .repo
+            .get_for_component(&account_id.value, &component_id.to_string())
+            .await?;
+
+        let mut account_defaults = WorkerDefaults::default();
+        let mut component_defaults = WorkerDefaults::default();
+        for record in &records {
+            if record.component_id.is_empty() {
+                account_defaults = Self::decode(record)?;
+            } else {
+                component_defaults = Self::decode(record)?;
+            }
+        }
+        Ok(account_defaults.merge(component_defaults))
+    }
+
+    async fn generate_worker_name(
+        &self,
+        account_id: &AccountId,
+        component_id: &ComponentId,
+    ) -> Result<String, WorkerDefaultsError> {
+        let defaults = self.resolve(account_id, component_id).await?;
+        match defaults.name_generation.unwrap_or_default() {
+            WorkerNameGeneration::Uuid => Ok(Uuid::new_v4().to_string()),
+            WorkerNameGeneration::Ulid => Ok(ulid(
+                chrono::Utc::now().timestamp_millis() as u64,
+                rand::random(),
+            )),
+            WorkerNameGeneration::Sequential => {
+                let sequence = self
+                    .repo
+                    .next_name_sequence(&account_id.value, &component_id.to_string())
+                    .await?;
+                Ok(sequence.to_string())
+            }
+        }
+    }
+}
@@ -139,7 +139,9 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::CompiledWorkerBinding>
             Some(x) => Some(RibInputTypeInfo::try_from(x)?),
             None => None,
         };
-        let binding_type = "file-server".to_string();
+        let binding_type = BindingType::from_i32(value.binding_type)
+            .ok_or("Invalid binding type".to_string())?
+            .to_string();
 
         let response_compiled = value
             .compiled_response_expr
@@ -216,7 +218,7 @@ impl TryFrom<CompiledGolemWorkerBinding>
         let response = Some(value.response_compiled.response_rib_expr.into());
         let compiled_response_expr = Some(value.response_compiled.compiled_response.into());
         let response_rib_input = Some(value.response_compiled.rib_input.into());
-        let binding_type = 1;
+        let binding_type = BindingType::i32_from_name(&value.binding_type);
         Ok(
             golem_api_grpc::proto::golem::apidefinition::CompiledWorkerBinding {
                 component,
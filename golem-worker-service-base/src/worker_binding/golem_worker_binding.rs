@@ -26,6 +26,8 @@ pub enum BindingType {
     WitWorker,
     #[serde(rename = "file-server")]
     FileServer,
+    #[serde(rename = "web-socket")]
+    WebSocket,
 }
 
 impl BindingType {
@@ -33,20 +35,41 @@ impl BindingType {
         match value {
             0 => Some(BindingType::WitWorker),
             1 => Some(BindingType::FileServer),
+            2 => Some(BindingType::WebSocket),
             _ => None, // Return None for any invalid values
         }
     }
     pub(crate) fn to_i32(&self) -> i32 {
         match self {
             BindingType::WitWorker => 0,
-            BindingType::FileServer => 1
+            BindingType::FileServer => 1,
+            BindingType::WebSocket => 2,
         }
     }
 
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "wit-worker" => Some(BindingType::WitWorker),
+            "file-server" => Some(BindingType::FileServer),
+            "web-socket" => Some(BindingType::WebSocket),
+            _ => None,
+        }
+    }
+
+    // Binding types were not validated in the past, unknown names are kept as file-server
+    pub(crate) fn i32_from_name(name: &str) -> i32 {
+        Self::from_name(name)
+            .unwrap_or(BindingType::FileServer)
+            .to_i32()
+    }
+}
+
+impl std::fmt::Display for BindingType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             BindingType::WitWorker => write!(f, "wit-worker"),
             BindingType::FileServer => write!(f, "file-server"),
+            BindingType::WebSocket => write!(f, "web-socket"),
         }
     }
 }
@@ -69,3 +92,44 @@ impl From<CompiledGolemWorkerBinding> for GolemWorkerBinding {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::worker_binding::BindingType;
+
+    #[test]
+    fn binding_types_round_trip_through_their_names_and_numbers() {
+        for binding_type in [
+            BindingType::WitWorker,
+            BindingType::FileServer,
+            BindingType::WebSocket,
+        ] {
+            assert_eq!(
+                BindingType::from_name(&binding_type.to_string()),
+                Some(binding_type.clone())
+            );
+            assert_eq!(
+                BindingType::from_i32(binding_type.to_i32()),
+                Some(binding_type)
+            );
+        }
+    }
+
+    #[test]
+    fn web_socket_binding_type_is_named_and_numbered() {
+        assert_eq!(BindingType::WebSocket.to_string(), "web-socket");
+        assert_eq!(BindingType::i32_from_name("web-socket"), 2);
+        assert_eq!(BindingType::from_i32(3), None);
+    }
+
+    #[test]
+    fn unknown_binding_type_names_are_kept_as_file_server() {
+        assert_eq!(BindingType::from_name("wit-socket"), None);
+        assert_eq!(
+            BindingType::i32_from_name("wit-socket"),
+            BindingType::FileServer.to_i32()
+        );
+    }
+}
@@ -25,6 +25,16 @@ impl RequestDetails {
         )?))
     }
 
+    // The same request with a different body, such as a frame received on a WebSocket connection
+    pub fn with_body(&self, request_body: &Value) -> Result<Self, Vec<String>> {
+        match self {
+            RequestDetails::Http(http_request_details) => Ok(Self::Http(HttpRequestDetails {
+                request_body: RequestBody::from(request_body)?,
+                ..http_request_details.clone()
+            })),
+        }
+    }
+
     pub fn as_json(&self) -> Value {
         match self {
             RequestDetails::Http(http_request_details) => {
@@ -1,5 +1,5 @@
 use crate::worker_binding::{RequestDetails, WorkerDetail};
-use golem_wasm_rpc::json::TypeAnnotatedValueJsonExtensions;
+use golem_common::type_check::TypeCheckIn;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use rib::RibInputTypeInfo;
 use std::collections::HashMap;
@@ -55,7 +55,7 @@ impl RibInputValueResolver for RequestDetails {
 
         match request_type_info {
             Some(request_type) => {
-                let input = TypeAnnotatedValue::parse_with_type(rib_input_with_request_content, request_type)
+                let input = rib_input_with_request_content.type_check_in(request_type)
                         .map_err(|err| RibInputTypeMismatch(format!("Input request details don't match the requirements for rib expression to execute: {}. Requirements. {:?}", err.join(", "), request_type)))?;
 
                 let mut rib_input_map = HashMap::new();
@@ -80,7 +80,7 @@ impl RibInputValueResolver for WorkerDetail {
             Some(worker_details_type) => {
                 let rib_input_with_request_content = &self.as_json();
                 let request_value =
-                    TypeAnnotatedValue::parse_with_type(rib_input_with_request_content, worker_details_type)
+                    rib_input_with_request_content.type_check_in(worker_details_type)
                         .map_err(|err| RibInputTypeMismatch(format!("Worker details don't match the requirements for rib expression to execute: {}. Requirements. {:?}", err.join(", "), worker_details_type)))?;
 
                 let mut rib_input_map = HashMap::new();
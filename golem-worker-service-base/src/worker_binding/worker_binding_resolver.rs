@@ -1,12 +1,14 @@
-use crate::api_definition::http::{CompiledHttpApiDefinition, VarInfo};
+use crate::api_definition::http::{CompiledHttpApiDefinition, CorsPolicy, MethodPattern, VarInfo};
 use crate::http::http_request::router;
 use crate::http::router::RouterPattern;
 use crate::http::InputHttpRequest;
 use crate::worker_service_rib_interpreter::EvaluationError;
 use crate::worker_service_rib_interpreter::WorkerServiceRibInterpreter;
 use async_trait::async_trait;
-use golem_common::model::IdempotencyKey;
+use golem_common::model::{CallerIdentity, IdempotencyKey};
 use golem_service_base::model::VersionedComponentId;
+use hyper::http::header::ACCESS_CONTROL_REQUEST_METHOD;
+use hyper::http::Method;
 use rib::RibInterpreterResult;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -26,6 +28,13 @@ pub trait RequestToWorkerBindingResolver<ApiDefinition> {
         &self,
         api_definitions: Vec<ApiDefinition>,
     ) -> Result<ResolvedWorkerBindingFromRequest, WorkerBindingResolutionError>;
+
+    // Resolves the CORS policy answering a pre-flight request, together with the methods of the
+    // routes matching the request path
+    fn resolve_cors_preflight(
+        &self,
+        api_definitions: &[ApiDefinition],
+    ) -> Option<(CorsPolicy, Vec<Method>)>;
 }
 
 #[derive(Debug)]
@@ -48,6 +57,8 @@ pub struct ResolvedWorkerBindingFromRequest {
     pub worker_detail: WorkerDetail,
     pub request_details: RequestDetails,
     pub compiled_response_mapping: ResponseMappingCompiled,
+    pub binding_type: String,
+    pub cors: Option<CorsPolicy>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -55,6 +66,8 @@ pub struct WorkerDetail {
     pub component_id: VersionedComponentId,
     pub worker_name: String,
     pub idempotency_key: Option<IdempotencyKey>,
+    /// The identity of the client, from its TLS client certificate
+    pub caller: Option<CallerIdentity>,
 }
 
 impl WorkerDetail {
@@ -104,6 +117,7 @@ impl ResolvedWorkerBindingFromRequest {
                         &self.worker_detail.worker_name,
                         &self.worker_detail.component_id.component_id,
                         &self.worker_detail.idempotency_key,
+                        &self.worker_detail.caller,
                         &self.compiled_response_mapping.compiled_response.clone(),
                         &rib_input,
                     )
@@ -126,13 +140,8 @@ impl RequestToWorkerBindingResolver<CompiledHttpApiDefinition> for InputHttpRequ
         &self,
         compiled_api_definitions: Vec<CompiledHttpApiDefinition>,
     ) -> Result<ResolvedWorkerBindingFromRequest, WorkerBindingResolutionError> {
-        let compiled_routes = compiled_api_definitions
-            .iter()
-            .flat_map(|x| x.routes.clone())
-            .collect::<Vec<_>>();
-
         let api_request = self;
-        let router = router::build(compiled_routes);
+        let router = router::build(&compiled_api_definitions);
         let path: Vec<&str> = RouterPattern::split(&api_request.input_path.base_path).collect();
         let request_query_variables = self.input_path.query_components().unwrap_or_default();
         let request_body = &self.req_body;
@@ -142,6 +151,7 @@ impl RequestToWorkerBindingResolver<CompiledHttpApiDefinition> for InputHttpRequ
             path_params,
             query_params,
             binding,
+            cors,
         } = router
             .check_path(&api_request.req_method, &path)
             .ok_or("Failed to resolve route")?;
@@ -210,14 +220,53 @@ impl RequestToWorkerBindingResolver<CompiledHttpApiDefinition> for InputHttpRequ
             component_id: component_id.clone(),
             worker_name,
             idempotency_key,
+            caller: None,
         };
 
         let resolved_binding = ResolvedWorkerBindingFromRequest {
             worker_detail,
             request_details: http_request_details,
             compiled_response_mapping: binding.response_compiled.clone(),
+            binding_type: binding.binding_type.clone(),
+            cors: cors.clone(),
         };
 
         Ok(resolved_binding)
     }
+
+    fn resolve_cors_preflight(
+        &self,
+        compiled_api_definitions: &[CompiledHttpApiDefinition],
+    ) -> Option<(CorsPolicy, Vec<Method>)> {
+        let requested_method = self
+            .headers
+            .get(ACCESS_CONTROL_REQUEST_METHOD)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|method| Method::from_bytes(method.as_bytes()).ok())?;
+
+        let router = router::build(compiled_api_definitions);
+        let path: Vec<&str> = RouterPattern::split(&self.input_path.base_path).collect();
+
+        let cors = router.check_path(&requested_method, &path)?.cors.clone()?;
+
+        let route_methods = ALL_METHODS
+            .iter()
+            .map(|method| Method::from(method.clone()))
+            .filter(|method| router.check_path(method, &path).is_some())
+            .collect();
+
+        Some((cors, route_methods))
+    }
 }
+
+const ALL_METHODS: [MethodPattern; 9] = [
+    MethodPattern::Get,
+    MethodPattern::Connect,
+    MethodPattern::Post,
+    MethodPattern::Delete,
+    MethodPattern::Put,
+    MethodPattern::Patch,
+    MethodPattern::Options,
+    MethodPattern::Trace,
+    MethodPattern::Head,
+];
@@ -1,15 +1,61 @@
 use golem_wasm_ast::analysis::AnalysedType;
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use poem::http::{header, HeaderValue};
 use poem::web::headers::ContentType;
-use poem::web::WithContentType;
-use poem::Body;
+use poem::{IntoResponse, Response};
 use std::fmt::{Display, Formatter};
 
+/// Response header identifying the schema of a binary encoded body
+pub const SCHEMA_HEADER: &str = "X-Golem-Schema";
+
 pub trait HttpContentTypeResponseMapper {
     fn to_http_resp_with_content_type(
         &self,
         content_type_headers: ContentTypeHeaders,
-    ) -> Result<WithContentType<Body>, ContentTypeMapError>;
+    ) -> Result<Response, ContentTypeMapError>;
+}
+
+/// Binary encodings of the results for bandwidth-sensitive machine-to-machine consumers, used
+/// when the client accepts them or the response mapping of the route sets them as content type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryFormat {
+    /// The `wasm.rpc.TypeAnnotatedValue` protobuf message, carrying the type of the value too
+    Protobuf,
+    /// The same structure as the JSON representation of the value
+    MessagePack,
+}
+
+impl BinaryFormat {
+    fn from_media_type(media_type: &str) -> Option<BinaryFormat> {
+        let essence = media_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match essence.as_str() {
+            "application/x-protobuf" | "application/protobuf" => Some(BinaryFormat::Protobuf),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(BinaryFormat::MessagePack)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            BinaryFormat::Protobuf => "application/x-protobuf",
+            BinaryFormat::MessagePack => "application/msgpack",
+        }
+    }
+
+    /// Identifies the schema of the body, sent in the `SCHEMA_HEADER` response header
+    pub fn schema(&self) -> &'static str {
+        match self {
+            BinaryFormat::Protobuf => "wasm.rpc.TypeAnnotatedValue",
+            BinaryFormat::MessagePack => "golem.value.json",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -71,6 +117,19 @@ impl AcceptHeaders {
             .iter()
             .any(|accept_header| accept_header.contains(content_type.to_string().as_str()))
     }
+
+    /// The binary format to respond with, if the client lists one of them before JSON
+    fn binary_format(&self) -> Option<BinaryFormat> {
+        for accept_header in &self.0 {
+            if let Some(format) = BinaryFormat::from_media_type(accept_header) {
+                return Some(format);
+            }
+            if accept_header.contains(ContentType::json().to_string().as_str()) {
+                return None;
+            }
+        }
+        None
+    }
 }
 
 impl ContentTypeHeaders {
@@ -86,22 +145,52 @@ impl ContentTypeHeaders {
             ContentTypeHeaders::Empty
         }
     }
+
+    fn binary_format(&self) -> Option<BinaryFormat> {
+        match self {
+            ContentTypeHeaders::FromClientAccept(accept_content_headers) => {
+                accept_content_headers.binary_format()
+            }
+            ContentTypeHeaders::FromUserDefinedResponseMapping(content_type) => {
+                BinaryFormat::from_media_type(&content_type.to_string())
+            }
+            ContentTypeHeaders::Empty => None,
+        }
+    }
 }
 
 impl HttpContentTypeResponseMapper for TypeAnnotatedValue {
     fn to_http_resp_with_content_type(
         &self,
         content_type_headers: ContentTypeHeaders,
-    ) -> Result<WithContentType<Body>, ContentTypeMapError> {
-        match content_type_headers {
-            ContentTypeHeaders::FromUserDefinedResponseMapping(content_type) => {
-                internal::get_response_body_based_on_content_type(self, &content_type)
-            }
-            ContentTypeHeaders::FromClientAccept(accept_content_headers) => {
-                internal::get_response_body_based_on_content_type(self, &accept_content_headers)
-            }
-            ContentTypeHeaders::Empty => internal::get_response_body(self),
+    ) -> Result<Response, ContentTypeMapError> {
+        // Caches must not reuse a response negotiated from the Accept header for other clients
+        let negotiated = matches!(
+            content_type_headers,
+            ContentTypeHeaders::FromClientAccept(_)
+        );
+
+        let mut response = if let Some(format) = content_type_headers.binary_format() {
+            internal::get_binary_response(self, format)?
+        } else {
+            match content_type_headers {
+                ContentTypeHeaders::FromUserDefinedResponseMapping(content_type) => {
+                    internal::get_response_body_based_on_content_type(self, &content_type)
+                }
+                ContentTypeHeaders::FromClientAccept(accept_content_headers) => {
+                    internal::get_response_body_based_on_content_type(self, &accept_content_headers)
+                }
+                ContentTypeHeaders::Empty => internal::get_response_body(self),
+            }?
+            .into_response()
+        };
+
+        if negotiated {
+            response
+                .headers_mut()
+                .insert(header::VARY, HeaderValue::from_static("Accept"));
         }
+        Ok(response)
     }
 }
 
@@ -152,7 +241,7 @@ impl Display for ContentTypeMapError {
 
 mod internal {
     use crate::worker_bridge_execution::content_type_mapper::{
-        AcceptHeaders, ContentTypeHeaderExt, ContentTypeMapError,
+        AcceptHeaders, BinaryFormat, ContentTypeHeaderExt, ContentTypeMapError, SCHEMA_HEADER,
     };
     use golem_wasm_ast::analysis::analysed_type::{
         bool, chr, f32, f64, s16, s32, s64, s8, u16, u32, u64, u8,
@@ -163,7 +252,8 @@ mod internal {
     use golem_wasm_rpc::protobuf::{PrimitiveType, TypedEnum, TypedList};
     use poem::web::headers::ContentType;
     use poem::web::WithContentType;
-    use poem::{Body, IntoResponse};
+    use poem::{Body, IntoResponse, Response};
+    use prost::Message;
     use std::fmt::Display;
 
     pub(crate) fn get_response_body_based_on_content_type<A: ContentTypeHeaderExt + Display>(
@@ -305,6 +395,27 @@ mod internal {
         }
     }
 
+    pub(crate) fn get_binary_response(
+        type_annotated_value: &TypeAnnotatedValue,
+        format: BinaryFormat,
+    ) -> Result<Response, ContentTypeMapError> {
+        let bytes = match format {
+            BinaryFormat::Protobuf => golem_wasm_rpc::protobuf::TypeAnnotatedValue {
+                type_annotated_value: Some(type_annotated_value.clone()),
+            }
+            .encode_to_vec(),
+            BinaryFormat::MessagePack => rmp_serde::to_vec(&type_annotated_value.to_json_value())
+                .map_err(|err| {
+                ContentTypeMapError::internal(format!("Failed to convert to msgpack body: {err}"))
+            })?,
+        };
+
+        Ok(Body::from_bytes(bytes::Bytes::from(bytes))
+            .with_content_type(format.content_type())
+            .with_header(SCHEMA_HEADER, format.schema())
+            .into_response())
+    }
+
     pub(crate) fn pick_highest_priority_content_type(
         input_content_types: &AcceptHeaders,
     ) -> Result<ContentType, ContentTypeMapError> {
@@ -479,7 +590,7 @@ mod tests {
     use golem_wasm_ast::analysis::analysed_type::str;
     use golem_wasm_rpc::protobuf::{NameTypePair, NameValuePair, TypedList, TypedRecord};
     use poem::web::headers::ContentType;
-    use poem::IntoResponse;
+    use poem::{Body, IntoResponse};
     use serde_json::Value;
 
     fn sample_record() -> TypeAnnotatedValue {
@@ -956,4 +1067,144 @@ mod tests {
             ));
         }
     }
+
+    #[cfg(test)]
+    mod binary_formats {
+        use test_r::test;
+
+        use super::*;
+        use prost::Message;
+
+        async fn get_response_parts(
+            input: &TypeAnnotatedValue,
+            content_type_headers: ContentTypeHeaders,
+        ) -> (Option<String>, Option<String>, bytes::Bytes) {
+            let response = input
+                .to_http_resp_with_content_type(content_type_headers)
+                .unwrap();
+            let (parts, body) = response.into_parts();
+            let header = |name: &str| {
+                parts
+                    .headers
+                    .get(name)
+                    .map(|v| v.to_str().unwrap().to_string())
+            };
+            (
+                header("content-type"),
+                header(SCHEMA_HEADER),
+                body.into_bytes().await.unwrap(),
+            )
+        }
+
+        #[test]
+        async fn test_record_type_protobuf() {
+            let type_annotated_value = sample_record();
+            let (content_type, schema, bytes) = get_response_parts(
+                &type_annotated_value,
+                ContentTypeHeaders::from(None, Some("application/x-protobuf".to_string())),
+            )
+            .await;
+
+            let decoded = golem_wasm_rpc::protobuf::TypeAnnotatedValue::decode(bytes).unwrap();
+            assert_eq!(
+                (decoded.type_annotated_value, content_type, schema),
+                (
+                    Some(type_annotated_value),
+                    Some("application/x-protobuf".to_string()),
+                    Some("wasm.rpc.TypeAnnotatedValue".to_string())
+                )
+            );
+        }
+
+        #[test]
+        async fn test_record_type_msgpack() {
+            let type_annotated_value = sample_record();
+            let (content_type, schema, bytes) = get_response_parts(
+                &type_annotated_value,
+                ContentTypeHeaders::from(None, Some("application/msgpack, */*".to_string())),
+            )
+            .await;
+
+            let decoded: Value = rmp_serde::from_slice(&bytes).unwrap();
+            assert_eq!(
+                (decoded, content_type, schema),
+                (
+                    serde_json::json!({"name": "Hello"}),
+                    Some("application/msgpack".to_string()),
+                    Some("golem.value.json".to_string())
+                )
+            );
+        }
+
+        #[test]
+        async fn test_json_listed_first_is_preferred() {
+            let type_annotated_value = sample_record();
+            let (content_type, schema, _) = get_response_parts(
+                &type_annotated_value,
+                ContentTypeHeaders::from(
+                    None,
+                    Some("application/json, application/x-protobuf".to_string()),
+                ),
+            )
+            .await;
+
+            assert_eq!(
+                (content_type, schema),
+                (Some("application/json".to_string()), None)
+            );
+        }
+
+        #[test]
+        async fn test_response_mapping_content_type_protobuf() {
+            let type_annotated_value = TypeAnnotatedValue::U32(42);
+            let content_type: ContentType = "application/protobuf".parse().unwrap();
+            let (content_type, _, bytes) = get_response_parts(
+                &type_annotated_value,
+                ContentTypeHeaders::from(Some(content_type), None),
+            )
+            .await;
+
+            let decoded = golem_wasm_rpc::protobuf::TypeAnnotatedValue::decode(bytes).unwrap();
+            assert_eq!(
+                (decoded.type_annotated_value, content_type),
+                (
+                    Some(TypeAnnotatedValue::U32(42)),
+                    Some("application/x-protobuf".to_string())
+                )
+            );
+        }
+
+        #[test]
+        fn test_only_negotiated_responses_vary_by_accept() {
+            let vary = |content_type_headers: ContentTypeHeaders| {
+                TypeAnnotatedValue::U32(42)
+                    .to_http_resp_with_content_type(content_type_headers)
+                    .unwrap()
+                    .headers()
+                    .get("vary")
+                    .map(|v| v.to_str().unwrap().to_string())
+            };
+            let content_type: ContentType = "application/protobuf".parse().unwrap();
+
+            assert_eq!(
+                vary(ContentTypeHeaders::from(
+                    None,
+                    Some("application/x-protobuf".to_string())
+                )),
+                Some("Accept".to_string())
+            );
+            assert_eq!(
+                vary(ContentTypeHeaders::from(
+                    None,
+                    Some("application/json".to_string())
+                )),
+                Some("Accept".to_string())
+            );
+            assert_eq!(
+                vary(ContentTypeHeaders::from(Some(content_type), None)),
+                None
+            );
+            assert_eq!(vary(ContentTypeHeaders::from(None, None)), None);
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use golem_common::model::{ComponentId, IdempotencyKey};
+use golem_common::model::{CallerIdentity, ComponentId, IdempotencyKey};
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 
 mod content_type_mapper;
@@ -13,4 +13,5 @@ pub struct WorkerRequest {
     pub function_name: String,
     pub function_params: Vec<TypeAnnotatedValue>,
     pub idempotency_key: Option<IdempotencyKey>,
+    pub caller: Option<CallerIdentity>,
 }
@@ -1,7 +1,9 @@
 use crate::worker_binding::{RequestDetails, RibInputTypeMismatch};
 use crate::worker_service_rib_interpreter::EvaluationError;
 
+use golem_wasm_rpc::json::TypeAnnotatedValueJsonExtensions;
 use http::StatusCode;
+use poem::web::websocket::Message;
 use poem::Body;
 use rib::RibInterpreterResult;
 
@@ -47,6 +49,35 @@ impl ToResponse<poem::Response> for String {
     }
 }
 
+// Results of invocations triggered by WebSocket frames are sent back as JSON text frames
+impl ToResponse<Message> for RibInterpreterResult {
+    fn to_response(&self, _request_details: &RequestDetails) -> Message {
+        let result = match self {
+            RibInterpreterResult::Val(typed_value) => typed_value.to_json_value(),
+            RibInterpreterResult::Unit => serde_json::Value::Null,
+        };
+        Message::Text(serde_json::json!({ "result": result }).to_string())
+    }
+}
+
+impl ToResponse<Message> for RibInputTypeMismatch {
+    fn to_response(&self, _request_details: &RequestDetails) -> Message {
+        Message::Text(serde_json::json!({ "error": self.0 }).to_string())
+    }
+}
+
+impl ToResponse<Message> for EvaluationError {
+    fn to_response(&self, _request_details: &RequestDetails) -> Message {
+        Message::Text(serde_json::json!({ "error": self.to_string() }).to_string())
+    }
+}
+
+impl ToResponse<Message> for String {
+    fn to_response(&self, _request_details: &RequestDetails) -> Message {
+        Message::Text(serde_json::json!({ "error": self }).to_string())
+    }
+}
+
 mod internal {
     use crate::worker_binding::RequestDetails;
     use crate::worker_bridge_execution::content_type_mapper::{
@@ -252,10 +283,12 @@ mod test {
     use golem_wasm_rpc::protobuf::Type;
     use golem_wasm_rpc::protobuf::{NameTypePair, NameValuePair, TypedRecord};
 
-    use crate::worker_binding::{HttpRequestDetails, RequestDetails};
+    use crate::worker_binding::{HttpRequestDetails, RequestDetails, RibInputTypeMismatch};
     use crate::worker_bridge_execution::to_response::ToResponse;
+    use crate::worker_service_rib_interpreter::EvaluationError;
     use http::header::CONTENT_TYPE;
     use http::StatusCode;
+    use poem::web::websocket::Message;
     use rib::RibInterpreterResult;
     use std::collections::HashMap;
 
@@ -372,4 +405,60 @@ mod test {
 
         assert_eq!(resolved_headers, expected)
     }
+
+    fn frame_json(message: Message) -> serde_json::Value {
+        match message {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("Expected a text frame, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_evaluation_result_to_websocket_frame() {
+        let evaluation_result: RibInterpreterResult =
+            RibInterpreterResult::Val(create_record(vec![(
+                "message".to_string(),
+                TypeAnnotatedValue::Str("Hello".to_string()),
+            )]));
+
+        let frame: Message =
+            evaluation_result.to_response(&RequestDetails::Http(HttpRequestDetails::empty()));
+
+        assert_eq!(
+            frame_json(frame),
+            serde_json::json!({ "result": { "message": "Hello" } })
+        );
+    }
+
+    #[test]
+    fn test_unit_evaluation_result_to_websocket_frame() {
+        let frame: Message = RibInterpreterResult::Unit
+            .to_response(&RequestDetails::Http(HttpRequestDetails::empty()));
+
+        assert_eq!(frame_json(frame), serde_json::json!({ "result": null }));
+    }
+
+    #[test]
+    fn test_errors_to_websocket_frame() {
+        let request_details = RequestDetails::Http(HttpRequestDetails::empty());
+
+        let evaluation_error: Message =
+            EvaluationError("Worker failed".to_string()).to_response(&request_details);
+        let type_mismatch: Message =
+            RibInputTypeMismatch("Invalid body".to_string()).to_response(&request_details);
+        let message: Message = "Invalid frame".to_string().to_response(&request_details);
+
+        assert_eq!(
+            frame_json(evaluation_error),
+            serde_json::json!({ "error": "Worker failed" })
+        );
+        assert_eq!(
+            frame_json(type_mismatch),
+            serde_json::json!({ "error": "Invalid body" })
+        );
+        assert_eq!(
+            frame_json(message),
+            serde_json::json!({ "error": "Invalid frame" })
+        );
+    }
 }
@@ -1,5 +1,7 @@
+use crate::service::worker::ConnectWorkerStream;
 use crate::worker_bridge_execution::WorkerRequest;
 use async_trait::async_trait;
+use golem_common::model::ComponentId;
 
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 use std::fmt::Display;
@@ -10,6 +12,13 @@ pub trait WorkerRequestExecutor {
         &self,
         resolved_worker_request: WorkerRequest,
     ) -> Result<WorkerResponse, WorkerRequestExecutorError>;
+
+    // Streams the events (standard output, logs, etc.) of a running worker
+    async fn connect(
+        &self,
+        component_id: &ComponentId,
+        worker_name: &str,
+    ) -> Result<ConnectWorkerStream, WorkerRequestExecutorError>;
 }
 
 // The result of a worker execution from worker-bridge,
@@ -5,7 +5,7 @@ use std::sync::Arc;
 
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 
-use golem_common::model::{ComponentId, IdempotencyKey};
+use golem_common::model::{CallerIdentity, ComponentId, IdempotencyKey};
 
 use crate::worker_binding::RibInputValue;
 use rib::{RibByteCode, RibFunctionInvoke, RibInterpreterResult};
@@ -23,6 +23,7 @@ pub trait WorkerServiceRibInterpreter {
         worker_name: &str,
         component_id: &ComponentId,
         idempotency_key: &Option<IdempotencyKey>,
+        caller: &Option<CallerIdentity>,
         rib_byte_code: &RibByteCode,
         rib_input: &RibInputValue,
     ) -> Result<RibInterpreterResult, EvaluationError>;
@@ -64,6 +65,7 @@ impl WorkerServiceRibInterpreter for DefaultRibInterpreter {
         worker_name: &str,
         component_id: &ComponentId,
         idempotency_key: &Option<IdempotencyKey>,
+        caller: &Option<CallerIdentity>,
         expr: &RibByteCode,
         rib_input: &RibInputValue,
     ) -> Result<RibInterpreterResult, EvaluationError> {
@@ -72,6 +74,7 @@ impl WorkerServiceRibInterpreter for DefaultRibInterpreter {
         let worker_name = worker_name.to_string();
         let component_id = component_id.clone();
         let idempotency_key = idempotency_key.clone();
+        let caller = caller.clone();
 
         let worker_invoke_function: RibFunctionInvoke = Arc::new(
             move |function_name: String, parameters: Vec<TypeAnnotatedValue>| {
@@ -79,6 +82,7 @@ impl WorkerServiceRibInterpreter for DefaultRibInterpreter {
                 let component_id = component_id.clone();
                 let worker_name = worker_name.clone();
                 let idempotency_key = idempotency_key.clone();
+                let caller = caller.clone();
                 let executor = executor.clone();
 
                 async move {
@@ -88,6 +92,7 @@ impl WorkerServiceRibInterpreter for DefaultRibInterpreter {
                         function_name,
                         function_params: parameters,
                         idempotency_key,
+                        caller,
                     };
 
                     executor
@@ -1,6 +1,7 @@
 use test_r::test;
 
 use async_trait::async_trait;
+use golem_api_grpc::proto::golem::worker::SagaStatus;
 use golem_common::config::{DbPostgresConfig, DbSqliteConfig};
 use golem_common::model::ComponentId;
 use golem_service_base::auth::{DefaultNamespace, EmptyAuthCtx};
@@ -15,7 +16,7 @@ use golem_worker_service_base::api_definition::http::HttpApiDefinitionRequest;
 use golem_worker_service_base::api_definition::{
     ApiDefinitionId, ApiDeploymentRequest, ApiSite, ApiSiteString, ApiVersion,
 };
-use golem_worker_service_base::repo::{api_definition, api_deployment};
+use golem_worker_service_base::repo::{api_definition, api_deployment, pending_invocation, saga};
 use golem_worker_service_base::service::api_definition::{
     ApiDefinitionError, ApiDefinitionIdWithVersion, ApiDefinitionService,
     ApiDefinitionServiceDefault,
@@ -30,6 +31,7 @@ use golem_worker_service_base::service::http::http_api_definition_validator::{
 
 use chrono::Utc;
 use golem_wasm_ast::analysis::analysed_type::str;
+use std::collections::HashMap;
 use std::sync::Arc;
 use testcontainers::runners::AsyncRunner;
 use testcontainers::{ContainerAsync, ImageExt};
@@ -97,6 +99,18 @@ pub async fn test_with_postgres_db() {
     );
 
     test_services(api_definition_repo, api_deployment_repo).await;
+
+    let saga_repo: Arc<dyn saga::SagaRepo + Sync + Send> =
+        Arc::new(saga::DbSagaRepo::new(db_pool.clone().into()));
+
+    test_saga_repo(saga_repo).await;
+
+    let pending_invocation_repo: Arc<dyn pending_invocation::PendingInvocationRepo + Sync + Send> =
+        Arc::new(pending_invocation::DbPendingInvocationRepo::new(
+            db_pool.clone().into(),
+        ));
+
+    test_pending_invocation_repo(pending_invocation_repo).await;
 }
 
 #[test]
@@ -121,6 +135,18 @@ pub async fn test_with_sqlite_db() {
     );
 
     test_services(api_definition_repo, api_deployment_repo).await;
+
+    let saga_repo: Arc<dyn saga::SagaRepo + Sync + Send> =
+        Arc::new(saga::DbSagaRepo::new(db_pool.clone().into()));
+
+    test_saga_repo(saga_repo).await;
+
+    let pending_invocation_repo: Arc<dyn pending_invocation::PendingInvocationRepo + Sync + Send> =
+        Arc::new(pending_invocation::DbPendingInvocationRepo::new(
+            db_pool.clone().into(),
+        ));
+
+    test_pending_invocation_repo(pending_invocation_repo).await;
 }
 
 struct TestComponentService;
@@ -143,6 +169,9 @@ impl TestComponentService {
                 exports: Self::get_metadata(),
                 producers: vec![],
                 memories: vec![],
+                files: vec![],
+                function_aliases: HashMap::new(),
+                capabilities: Default::default(),
             },
             created_at: Some(Utc::now()),
             component_type: None,
@@ -775,3 +804,112 @@ fn contains_definitions(
 
     true
 }
+
+async fn test_saga_repo(saga_repo: Arc<dyn saga::SagaRepo + Sync + Send>) {
+    let now = Utc::now();
+    let updated_at = now - chrono::Duration::minutes(10);
+    let mut record = saga::SagaRecord {
+        id: Uuid::new_v4().to_string(),
+        data: vec![1, 2, 3],
+        account_id: None,
+        status: SagaStatus::SagaRunning as i32,
+        step: 0,
+        failed_step: None,
+        error: None,
+        created_at: updated_at,
+        updated_at,
+    };
+    saga_repo.create(&record).await.unwrap();
+
+    let updated_before = now - chrono::Duration::minutes(5);
+    let stale = saga_repo.get_stale(updated_before, 10).await.unwrap();
+    assert!(stale.iter().any(|saga| saga.id == record.id));
+
+    // Only the first of the instances finding the stale saga takes it over
+    assert!(saga_repo
+        .claim(&record.id, updated_before, now)
+        .await
+        .unwrap());
+    assert!(!saga_repo
+        .claim(&record.id, updated_before, now)
+        .await
+        .unwrap());
+
+    // A saga kept alive by the heartbeat of the instance executing it is not stale
+    saga_repo
+        .heartbeat(&record.id, now + chrono::Duration::minutes(10))
+        .await
+        .unwrap();
+    assert!(!saga_repo
+        .claim(&record.id, now + chrono::Duration::minutes(5), now)
+        .await
+        .unwrap());
+
+    // Finished sagas are neither refreshed nor taken over
+    record.status = SagaStatus::SagaCompleted as i32;
+    record.step = 1;
+    record.updated_at = updated_at;
+    saga_repo.update(&record).await.unwrap();
+    saga_repo.heartbeat(&record.id, now).await.unwrap();
+    let stored = saga_repo.get(&record.id).await.unwrap().unwrap();
+    assert_eq!(stored.status, SagaStatus::SagaCompleted as i32);
+    assert_eq!(stored.updated_at.timestamp(), updated_at.timestamp());
+    assert!(!saga_repo
+        .claim(&record.id, updated_before, now)
+        .await
+        .unwrap());
+}
+
+async fn test_pending_invocation_repo(
+    repo: Arc<dyn pending_invocation::PendingInvocationRepo + Sync + Send>,
+) {
+    let now = Utc::now();
+    let record = |data: u8, created_at: chrono::DateTime<Utc>, expires_at| {
+        pending_invocation::PendingInvocationRecord {
+            id: Uuid::new_v4().to_string(),
+            worker_id: "component/worker".to_string(),
+            data: vec![data],
+            created_at,
+            expires_at,
+        }
+    };
+    let expired = record(
+        0,
+        now - chrono::Duration::hours(2),
+        now - chrono::Duration::hours(1),
+    );
+    let older = record(
+        1,
+        now - chrono::Duration::minutes(2),
+        now + chrono::Duration::hours(1),
+    );
+    let newer = record(
+        2,
+        now - chrono::Duration::minutes(1),
+        now + chrono::Duration::hours(1),
+    );
+    for invocation in [&newer, &expired, &older] {
+        repo.create(invocation).await.unwrap();
+    }
+    assert_eq!(repo.count().await.unwrap(), 3);
+
+    assert_eq!(repo.delete_expired(now).await.unwrap(), 1);
+    assert_eq!(repo.count().await.unwrap(), 2);
+
+    // The oldest invocations are delivered first
+    let oldest = repo.get_oldest(1).await.unwrap();
+    assert_eq!(oldest.len(), 1);
+    assert_eq!(oldest[0].id, older.id);
+    assert_eq!(oldest[0].data, older.data);
+
+    assert!(repo.delete(&older.id).await.unwrap());
+    assert!(!repo.delete(&older.id).await.unwrap());
+    let pending = repo.get_oldest(10).await.unwrap();
+    assert_eq!(
+        pending
+            .iter()
+            .map(|record| record.id.clone())
+            .collect::<Vec<_>>(),
+        vec![newer.id.clone()]
+    );
+}
@@ -0,0 +1,11 @@
+CREATE TABLE pending_invocations
+(
+    id         text      NOT NULL,
+    worker_id  text      NOT NULL,
+    data       bytea     NOT NULL,
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at timestamp NOT NULL,
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX pending_invocations_created_at_idx ON pending_invocations (created_at);
@@ -0,0 +1,8 @@
+CREATE TABLE worker_defaults
+(
+    account_id   text      NOT NULL,
+    component_id text      NOT NULL,
+    defaults     text      NOT NULL,
+    updated_at   timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (account_id, component_id)
+);
@@ -0,0 +1,8 @@
+CREATE TABLE component_maintenance
+(
+    account_id   text      NOT NULL,
+    component_id text      NOT NULL,
+    message      text,
+    started_at   timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (account_id, component_id)
+);
@@ -0,0 +1,7 @@
+CREATE TABLE worker_name_sequences
+(
+    account_id   text   NOT NULL,
+    component_id text   NOT NULL,
+    last_value   bigint NOT NULL,
+    PRIMARY KEY (account_id, component_id)
+);
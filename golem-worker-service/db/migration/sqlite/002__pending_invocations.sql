@@ -0,0 +1,11 @@
+CREATE TABLE pending_invocations
+(
+    id         text NOT NULL,
+    worker_id  text NOT NULL,
+    data       blob NOT NULL,
+    created_at timestamp without time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    expires_at timestamp without time zone NOT NULL,
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX pending_invocations_created_at_idx ON pending_invocations (created_at);
@@ -0,0 +1,15 @@
+CREATE TABLE sagas
+(
+    id          text    NOT NULL,
+    data        blob    NOT NULL,
+    account_id  text,
+    status      integer NOT NULL,
+    step        integer NOT NULL,
+    failed_step integer,
+    error       text,
+    created_at  timestamp without time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    updated_at  timestamp without time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX sagas_status_updated_at_idx ON sagas (status, updated_at);
@@ -0,0 +1,8 @@
+CREATE TABLE worker_defaults
+(
+    account_id   text NOT NULL,
+    component_id text NOT NULL,
+    defaults     text NOT NULL,
+    updated_at   timestamp without time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (account_id, component_id)
+);
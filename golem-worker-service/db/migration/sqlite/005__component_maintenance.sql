@@ -0,0 +1,8 @@
+CREATE TABLE component_maintenance
+(
+    account_id   text NOT NULL,
+    component_id text NOT NULL,
+    message      text,
+    started_at   timestamp without time zone DEFAULT CURRENT_TIMESTAMP NOT NULL,
+    PRIMARY KEY (account_id, component_id)
+);
@@ -411,6 +411,7 @@ mod test {
                 version: ApiVersion("1.0".to_string()),
                 routes: vec![],
                 draft: false,
+                cors: None,
             };
 
         let response = client
@@ -441,6 +442,7 @@ mod test {
                 version: ApiVersion("42.0".to_string()),
                 routes: vec![],
                 draft: false,
+                cors: None,
             };
 
         let response = client
@@ -466,6 +468,7 @@ mod test {
                 version: ApiVersion("1.0".to_string()),
                 routes: vec![],
                 draft: false,
+                cors: None,
             };
         let response = client
             .post("/v1/api/definitions")
@@ -480,6 +483,7 @@ mod test {
                 version: ApiVersion("2.0".to_string()),
                 routes: vec![],
                 draft: false,
+                cors: None,
             };
         let response = client
             .post("/v1/api/definitions")
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use crate::default_account_id;
+use golem_common::model::maintenance::{ComponentMaintenance, StartComponentMaintenance};
+use golem_common::model::ComponentId;
+use golem_common::recorded_http_api_request;
+use golem_service_base::api_tags::ApiTags;
+use golem_worker_service_base::api::WorkerApiBaseError;
+use golem_worker_service_base::service::maintenance::MaintenanceService;
+use poem_openapi::param::Path;
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use tracing::Instrument;
+
+pub struct MaintenanceApi {
+    maintenance_service: Arc<dyn MaintenanceService + Sync + Send>,
+}
+
+type Result<T> = std::result::Result<T, WorkerApiBaseError>;
+
+#[OpenApi(prefix_path = "/v1", tag = ApiTags::Worker)]
+impl MaintenanceApi {
+    pub fn new(maintenance_service: Arc<dyn MaintenanceService + Sync + Send>) -> Self {
+        Self {
+            maintenance_service,
+        }
+    }
+
+    /// Get the maintenance of a component
+    ///
+    /// Returns 404 if the component is not in maintenance.
+    #[oai(
+        path = "/components/:component_id/maintenance",
+        method = "get",
+        operation_id = "get_component_maintenance"
+    )]
+    async fn get_component_maintenance(
+        &self,
+        component_id: Path<ComponentId>,
+    ) -> Result<Json<ComponentMaintenance>> {
+        let record = recorded_http_api_request!(
+            "get_component_maintenance",
+            component_id = component_id.0.to_string()
+        );
+        let response = self
+            .maintenance_service
+            .get(&default_account_id(), &component_id.0)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Start the maintenance of a component
+    ///
+    /// While the component is in maintenance, invoking its workers fails with 503 Service
+    /// Unavailable and the queued invocations are held back. The workers' metadata can still be
+    /// read, and they can still be created, updated, interrupted or deleted. Starting the
+    /// maintenance again replaces its message.
+    #[oai(
+        path = "/components/:component_id/maintenance",
+        method = "put",
+        operation_id = "start_component_maintenance"
+    )]
+    async fn start_component_maintenance(
+        &self,
+        component_id: Path<ComponentId>,
+        request: Json<StartComponentMaintenance>,
+    ) -> Result<Json<ComponentMaintenance>> {
+        let record = recorded_http_api_request!(
+            "start_component_maintenance",
+            component_id = component_id.0.to_string()
+        );
+        let response = self
+            .maintenance_service
+            .start(&default_account_id(), &component_id.0, request.0.message)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// End the maintenance of a component
+    #[oai(
+        path = "/components/:component_id/maintenance",
+        method = "delete",
+        operation_id = "end_component_maintenance"
+    )]
+    async fn end_component_maintenance(
+        &self,
+        component_id: Path<ComponentId>,
+    ) -> Result<Json<String>> {
+        let record = recorded_http_api_request!(
+            "end_component_maintenance",
+            component_id = component_id.0.to_string()
+        );
+        let response = self
+            .maintenance_service
+            .end(&default_account_id(), &component_id.0)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json("Component maintenance ended".to_string()));
+
+        record.result(response)
+    }
+}
@@ -1,12 +1,16 @@
 pub mod api_definition;
 pub mod api_deployment;
+pub mod maintenance;
 pub mod worker;
+pub mod worker_admin;
 pub mod worker_connect;
+pub mod worker_defaults;
 
 use crate::api::worker::WorkerApi;
 use crate::service::Services;
 use golem_worker_service_base::api::CustomHttpRequestApi;
 use golem_worker_service_base::api::HealthcheckApi;
+use golem_worker_service_base::http::ClientCertificateConfig;
 use poem::endpoint::PrometheusExporter;
 use poem::{get, EndpointExt, Route};
 use poem_openapi::OpenApiService;
@@ -18,6 +22,9 @@ type ApiServices = (
     WorkerApi,
     api_definition::RegisterApiDefinitionApi,
     api_deployment::ApiDeploymentApi,
+    worker_defaults::WorkerDefaultsApi,
+    maintenance::MaintenanceApi,
+    worker_admin::WorkerAdminApi,
     HealthcheckApi,
 );
 
@@ -37,14 +44,22 @@ pub fn combined_routes(prometheus_registry: Arc<Registry>, services: &Services)
         .nest("/metrics", metrics)
         .at(
             "/v1/components/:component_id/workers/:worker_name/connect",
-            get(worker_connect::ws.data(connect_services)),
+            get(worker_connect::ws.data(connect_services.clone())),
+        )
+        .at(
+            "/v1/components/:component_id/connect",
+            get(worker_connect::ws_many.data(connect_services)),
         )
 }
 
-pub fn custom_request_route(services: Services) -> Route {
+pub fn custom_request_route(
+    services: Services,
+    client_certificate: ClientCertificateConfig,
+) -> Route {
     let custom_request_executor = CustomHttpRequestApi::new(
         services.worker_to_http_service,
         services.http_definition_lookup_service,
+        client_certificate,
     );
 
     Route::new().nest("/", custom_request_executor)
@@ -59,6 +74,12 @@ pub fn make_open_api_service(services: &Services) -> OpenApiService<ApiServices,
             },
             api_definition::RegisterApiDefinitionApi::new(services.definition_service.clone()),
             api_deployment::ApiDeploymentApi::new(services.deployment_service.clone()),
+            worker_defaults::WorkerDefaultsApi::new(services.worker_defaults_service.clone()),
+            maintenance::MaintenanceApi::new(services.maintenance_service.clone()),
+            worker_admin::WorkerAdminApi {
+                worker_service: services.worker_service.clone(),
+                admin_config: services.admin_config.clone(),
+            },
             HealthcheckApi,
         ),
         "Golem API",
@@ -1,26 +1,37 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 use crate::empty_worker_metadata;
 use crate::service::{component::ComponentService, worker::WorkerService};
 use golem_common::model::{
-    ComponentId, IdempotencyKey, ScanCursor, TargetWorkerId, WorkerFilter, WorkerId,
+    ComponentId, IdempotencyKey, ScanCursor, TargetWorkerId, Timestamp, WorkerFilter, WorkerId,
 };
 use golem_common::recorded_http_api_request;
+use golem_common::SafeDisplay;
 use golem_service_base::api_tags::ApiTags;
 use golem_service_base::auth::EmptyAuthCtx;
 use golem_service_base::model::*;
 use golem_worker_service_base::api::WorkerApiBaseError;
+use poem::Body;
 use poem_openapi::param::{Header, Path, Query};
 use poem_openapi::payload::{Binary, Json};
 use poem_openapi::*;
 use std::str::FromStr;
+use futures::StreamExt;
 use tap::TapFallible;
+use uuid::Uuid;
 
 use golem_common::model::oplog::OplogIndex;
-use golem_common::model::public_oplog::OplogCursor;
+use golem_common::model::public_oplog::{OplogCursor, OplogQuery, PublicOplogEntry};
+use golem_common::model::timeline::WorkerTimeline;
+use golem_common::model::worker_metadata_projection::{
+    WorkerMetadataField, WorkerMetadataProjection,
+};
 use tracing::Instrument;
 use tracing::log::info;
+use golem_api_grpc::proto::golem::worker::InvocationContext;
 use golem_api_grpc::proto::golem::workerexecutor::v1::GetFilesResponse;
-use golem_worker_service_base::service::worker::WorkerServiceError;
+use golem_worker_service_base::service::worker::{InvocationResultStream, WorkerServiceError};
+use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 
 pub struct WorkerApi {
     pub component_service: ComponentService,
@@ -29,6 +40,15 @@ pub struct WorkerApi {
 
 type Result<T> = std::result::Result<T, WorkerApiBaseError>;
 
+#[derive(ApiResponse)]
+pub enum InvokeAndAwaitStreamResponse {
+    /// The result as newline delimited JSON, each line holding an `InvokeResult`. The
+    /// `Golem-List-Elements` header tells whether the lines are the elements of the list returned
+    /// by the function, or a single line holding its whole result.
+    #[oai(status = 200, content_type = "application/x-ndjson")]
+    Ok(Binary<Body>, #[oai(header = "Golem-List-Elements")] bool),
+}
+
 #[OpenApi(prefix_path = "/v1/components", tag = ApiTags::Worker)]
 impl WorkerApi {
     /// Launch a new worker.
@@ -36,9 +56,15 @@ impl WorkerApi {
     /// Creates a new worker. The worker initially is in `Idle`` status, waiting to be invoked.
     ///
     /// The parameters in the request are the following:
-    /// - `name` is the name of the created worker. This has to be unique, but only for a given component
+    /// - `name` is the name of the created worker. This has to be unique, but only for a given component.
+    ///   If it is not given, the name is generated with the naming strategy set in the component's worker
+    ///   defaults, and the created worker's id is returned in the response.
     /// - `args` is a list of strings which appear as command line arguments for the worker
     /// - `env` is a list of key-value pairs (represented by arrays) which appear as environment variables for the worker
+    ///
+    /// The `args` and the values of `env` can contain the placeholders `{{worker_name}}`, `{{component_id}}`,
+    /// `{{component_version}}` and `{{account_id}}`, which are replaced before the worker is created.
+    /// A literal `{{` is written as `\{{`.
     #[oai(
         path = "/:component_id/workers",
         method = "post",
@@ -73,26 +99,128 @@ impl WorkerApi {
                     }))
                 })?;
 
-            let WorkerCreationRequest { name, args, env } = request.0;
+            let WorkerCreationRequest {
+                name,
+                args,
+                env,
+                ttl_seconds,
+                anti_affinity_key,
+            } = request.0;
+
+            let worker_id = match name {
+                Some(name) => {
+                    let worker_id = make_worker_id(component_id, name)?;
+                    self.worker_service
+                        .create(
+                            &worker_id,
+                            latest_component.versioned_component_id.version,
+                            args,
+                            env,
+                            ttl_seconds.map(std::time::Duration::from_secs),
+                            anti_affinity_key,
+                            empty_worker_metadata(),
+                            &EmptyAuthCtx::default(),
+                        )
+                        .instrument(record.span.clone())
+                        .await?
+                }
+                None => {
+                    self.worker_service
+                        .create_with_generated_name(
+                            &component_id,
+                            latest_component.versioned_component_id.version,
+                            args,
+                            env,
+                            ttl_seconds.map(std::time::Duration::from_secs),
+                            anti_affinity_key,
+                            empty_worker_metadata(),
+                            &EmptyAuthCtx::default(),
+                        )
+                        .instrument(record.span.clone())
+                        .await?
+                }
+            };
+            Ok(Json(WorkerCreationResponse {
+                worker_id,
+                component_version: latest_component.versioned_component_id.version,
+            }))
+        };
+
+        record.result(response)
+    }
+
+    /// Get or create a worker
+    ///
+    /// Returns the worker with the given name if it already exists, otherwise creates it with the
+    /// latest version of the component, the same way as launching a new worker. Unlike checking
+    /// for the worker before launching it, this does not fail if the worker is created
+    /// concurrently by another caller.
+    ///
+    /// The `args`, `env` and `ttlSeconds` in the request are only applied if the worker is created,
+    /// which is reported by the `created` field of the response.
+    #[oai(
+        path = "/:component_id/workers/get-or-create",
+        method = "post",
+        operation_id = "get_or_create_worker"
+    )]
+    async fn get_or_create_worker(
+        &self,
+        component_id: Path<ComponentId>,
+        request: Json<WorkerCreationRequest>,
+    ) -> Result<Json<WorkerGetOrCreateResponse>> {
+        let record = recorded_http_api_request!(
+            "get_or_create_worker",
+            component_id = component_id.0.to_string(),
+            name = request.name
+        );
+
+        let response = {
+            let component_id = component_id.0;
+            let latest_component = self
+                .component_service
+                .get_latest(&component_id, &EmptyAuthCtx::default())
+                .instrument(record.span.clone())
+                .await
+                .tap_err(|error| tracing::error!("Error getting latest component: {:?}", error))
+                .map_err(|error| {
+                    WorkerApiBaseError::NotFound(Json(ErrorBody {
+                        error: format!(
+                            "Couldn't retrieve the component: {}. error: {}",
+                            &component_id, error
+                        ),
+                    }))
+                })?;
 
+            let WorkerCreationRequest {
+                name,
+                args,
+                env,
+                ttl_seconds,
+                anti_affinity_key,
+            } = request.0;
+
+            let name = name.ok_or_else(|| {
+                WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+                    errors: vec!["The name of the worker to get or create is required".to_string()],
+                }))
+            })?;
             let worker_id = make_worker_id(component_id, name)?;
 
-            let worker_id = self
-                .worker_service
-                .create(
+            self.worker_service
+                .get_or_create_worker(
                     &worker_id,
                     latest_component.versioned_component_id.version,
                     args,
                     env,
+                    ttl_seconds.map(std::time::Duration::from_secs),
+                    anti_affinity_key,
                     empty_worker_metadata(),
                     &EmptyAuthCtx::default(),
                 )
                 .instrument(record.span.clone())
-                .await?;
-            Ok(Json(WorkerCreationResponse {
-                worker_id,
-                component_version: latest_component.versioned_component_id.version,
-            }))
+                .await
+                .map_err(|e| e.into())
+                .map(Json)
         };
 
         record.result(response)
@@ -167,6 +295,8 @@ impl WorkerApi {
     /// Delete a worker
     ///
     /// Interrupts and deletes an existing worker.
+    /// With `cascade=true` the child workers it spawned, its promises, its scheduled invocations and its files are deleted as well, and listed in the response.
+    /// With `dry-run=true` nothing is deleted, the response lists what would be removed.
     #[oai(
         path = "/:component_id/workers/:worker_name",
         method = "delete",
@@ -176,6 +306,8 @@ impl WorkerApi {
         &self,
         component_id: Path<ComponentId>,
         worker_name: Path<String>,
+        cascade: Query<Option<bool>>,
+        #[oai(name = "dry-run")] dry_run: Query<Option<bool>>,
     ) -> Result<Json<DeleteWorkerResponse>> {
         let worker_id = make_worker_id(component_id.0, worker_name.0)?;
         let record =
@@ -184,13 +316,15 @@ impl WorkerApi {
             .worker_service
             .delete(
                 &worker_id,
+                cascade.0.unwrap_or(false),
+                dry_run.0.unwrap_or(false),
                 empty_worker_metadata(),
                 &EmptyAuthCtx::default(),
             )
             .instrument(record.span.clone())
             .await
             .map_err(|e| e.into())
-            .map(|_| Json(DeleteWorkerResponse {}));
+            .map(|removed| Json(DeleteWorkerResponse { removed }));
 
         record.result(response)
     }
@@ -199,6 +333,8 @@ impl WorkerApi {
     ///
     /// Ideal for invoking ephemeral components, but works with durable ones as well.
     /// Supply the parameters in the request body as JSON.
+    /// With `transform=true` the result is passed through the component's `__map_result` export, if it has one.
+    /// With `timeout-millis` the invocation fails with `InvocationTimedOut` if it does not complete in time.
     #[oai(
         path = "/:component_id/invoke-and-await",
         method = "post",
@@ -209,6 +345,8 @@ impl WorkerApi {
         component_id: Path<ComponentId>,
         #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
         function: Query<String>,
+        transform: Query<Option<bool>>,
+        #[oai(name = "timeout-millis")] timeout_millis: Query<Option<u64>>,
         params: Json<InvokeParameters>,
     ) -> Result<Json<InvokeResult>> {
         let worker_id = make_target_worker_id(component_id.0, None)?;
@@ -221,14 +359,13 @@ impl WorkerApi {
         );
 
         let response = self
-            .worker_service
-            .validate_and_invoke_and_await_typed(
+            .invoke_and_await_and_transform(
                 &worker_id,
                 idempotency_key.0,
                 function.0,
-                params.0.params,
-                None,
-                empty_worker_metadata(),
+                params.0,
+                transform.0.unwrap_or(false),
+                timeout_millis.0,
             )
             .instrument(record.span.clone())
             .await
@@ -241,6 +378,8 @@ impl WorkerApi {
     /// Invoke a function and await its resolution
     ///
     /// Supply the parameters in the request body as JSON.
+    /// With `transform=true` the result is passed through the component's `__map_result` export, if it has one.
+    /// With `timeout-millis` the invocation fails with `InvocationTimedOut` if it does not complete in time.
     #[oai(
         path = "/:component_id/workers/:worker_name/invoke-and-await",
         method = "post",
@@ -252,6 +391,8 @@ impl WorkerApi {
         worker_name: Path<String>,
         #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
         function: Query<String>,
+        transform: Query<Option<bool>>,
+        #[oai(name = "timeout-millis")] timeout_millis: Query<Option<u64>>,
         params: Json<InvokeParameters>,
     ) -> Result<Json<InvokeResult>> {
         let worker_id = make_target_worker_id(component_id.0, Some(worker_name.0))?;
@@ -264,14 +405,111 @@ impl WorkerApi {
         );
 
         let response = self
-            .worker_service
-            .validate_and_invoke_and_await_typed(
+            .invoke_and_await_and_transform(
                 &worker_id,
                 idempotency_key.0,
                 function.0,
-                params.0.params,
-                None,
-                empty_worker_metadata(),
+                params.0,
+                transform.0.unwrap_or(false),
+                timeout_millis.0,
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|result| Json(InvokeResult { result }));
+        record.result(response)
+    }
+
+    /// Invoke a function and stream its result
+    ///
+    /// Supply the parameters in the request body as JSON. The result is returned as newline delimited JSON:
+    /// a function returning a single list is streamed element by element, so results too large to be returned
+    /// at once can be received, and any other result is returned in a single line. The `Golem-List-Elements`
+    /// response header tells which one the lines are. The response is cut short if the invocation fails while
+    /// streaming.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/invoke-and-await-stream",
+        method = "post",
+        operation_id = "invoke_and_await_function_stream"
+    )]
+    async fn invoke_and_await_function_stream(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
+        function: Query<String>,
+        params: Json<InvokeParameters>,
+    ) -> Result<InvokeAndAwaitStreamResponse> {
+        let worker_id = make_target_worker_id(component_id.0, Some(worker_name.0))?;
+
+        let record = recorded_http_api_request!(
+            "invoke_and_await_function_stream",
+            worker_id = worker_id.to_string(),
+            idempotency_key = idempotency_key.0.as_ref().map(|v| v.value.clone()),
+            function = function.0
+        );
+
+        let response = self
+            .invoke_and_await_stream(&worker_id, idempotency_key.0, function.0, params.0)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|stream| {
+                let list_elements = stream.list_elements();
+                let lines = stream.map(|chunk| {
+                    let result = chunk.map_err(|err| {
+                        std::io::Error::new(std::io::ErrorKind::Other, err.to_string())
+                    })?;
+                    let mut line = serde_json::to_vec(&InvokeResult { result })?;
+                    line.push(b'\n');
+                    Ok::<_, std::io::Error>(line)
+                });
+                InvokeAndAwaitStreamResponse::Ok(
+                    Binary(Body::from_bytes_stream(lines)),
+                    list_elements,
+                )
+            });
+        record.result(response)
+    }
+
+    /// Invoke a function with WAVE parameters and await its resolution
+    ///
+    /// Supply the parameters in the request body in WAVE (WebAssembly Value Encoding) text form,
+    /// they are parsed using the function's signature.
+    /// With `transform=true` the result is passed through the component's `__map_result` export, if it has one.
+    /// With `timeout-millis` the invocation fails with `InvocationTimedOut` if it does not complete in time.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/invoke-and-await-wave",
+        method = "post",
+        operation_id = "invoke_and_await_function_wave"
+    )]
+    async fn invoke_and_await_function_wave(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
+        function: Query<String>,
+        transform: Query<Option<bool>>,
+        #[oai(name = "timeout-millis")] timeout_millis: Query<Option<u64>>,
+        params: Json<InvokeWaveParameters>,
+    ) -> Result<Json<InvokeResult>> {
+        let worker_id = make_target_worker_id(component_id.0, Some(worker_name.0))?;
+
+        let record = recorded_http_api_request!(
+            "invoke_and_await_function_wave",
+            worker_id = worker_id.to_string(),
+            idempotency_key = idempotency_key.0.as_ref().map(|v| v.value.clone()),
+            function = function.0
+        );
+
+        let response = self
+            .invoke_and_await_wave_and_transform(
+                &worker_id,
+                idempotency_key.0,
+                function.0,
+                params.0,
+                transform.0.unwrap_or(false),
+                timeout_millis.0,
             )
             .instrument(record.span.clone())
             .await
@@ -280,6 +518,146 @@ impl WorkerApi {
         record.result(response)
     }
 
+    /// Upload an invocation parameter
+    ///
+    /// Stores a parameter too large to be sent in an invocation request. The returned key can be passed in the
+    /// `blobParams` of an invocation of the component's workers, and the uploaded parameter is deleted once that
+    /// invocation is completed.
+    #[oai(
+        path = "/:component_id/invocation-parameters",
+        method = "post",
+        operation_id = "upload_invocation_parameter"
+    )]
+    async fn upload_invocation_parameter(
+        &self,
+        component_id: Path<ComponentId>,
+        data: Binary<Body>,
+    ) -> Result<Json<UploadInvocationParameterResponse>> {
+        let record = recorded_http_api_request!(
+            "upload_invocation_parameter",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .upload_invocation_parameter(
+                &component_id.0,
+                Box::new(data.0.into_async_read()),
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Download a large invocation result
+    ///
+    /// Returns the protobuf encoded result of an invocation which was too large to be returned directly. The
+    /// `result_id` is the last segment of the key returned in the invocation's `ResultTooLarge` error.
+    #[oai(
+        path = "/:component_id/invocation-results/:result_id",
+        method = "get",
+        operation_id = "get_invocation_result_blob"
+    )]
+    async fn get_invocation_result_blob(
+        &self,
+        component_id: Path<ComponentId>,
+        result_id: Path<Uuid>,
+    ) -> Result<Binary<Vec<u8>>> {
+        let blob_key = format!("{}/{}", component_id.0, result_id.0);
+
+        let record =
+            recorded_http_api_request!("get_invocation_result_blob", blob_key = blob_key.clone());
+
+        let response = self
+            .worker_service
+            .get_invocation_result_blob(
+                &blob_key,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Binary);
+
+        record.result(response)
+    }
+
+    /// Validate an invocation on a new worker with a random generated name
+    ///
+    /// Performs the same function lookup and type checking as `invoke_function_without_name` without invoking
+    /// anything, using the latest version of the component.
+    #[oai(
+        path = "/:component_id/validate-invocation",
+        method = "post",
+        operation_id = "validate_invocation_without_name"
+    )]
+    async fn validate_invocation_without_name(
+        &self,
+        component_id: Path<ComponentId>,
+        function: Query<String>,
+        params: Json<InvokeParameters>,
+    ) -> Result<Json<ValidateInvocationResponse>> {
+        let worker_id = make_target_worker_id(component_id.0, None)?;
+
+        let record = recorded_http_api_request!(
+            "validate_invocation_without_name",
+            worker_id = worker_id.to_string(),
+            function = function.0
+        );
+
+        let response = self
+            .validate_invocation_and_respond(&worker_id, &function.0, params.0.params)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Validate an invocation
+    ///
+    /// Performs the same function lookup and type checking as `invoke_function` without invoking anything,
+    /// so forms can be validated before submitting them. The function is looked up in the component version
+    /// of the worker, or in the latest version if the worker does not exist yet.
+    ///
+    /// Returns the errors of each parameter, and the parameters converted to the types the function expects.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/validate-invocation",
+        method = "post",
+        operation_id = "validate_invocation"
+    )]
+    async fn validate_invocation(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        function: Query<String>,
+        params: Json<InvokeParameters>,
+    ) -> Result<Json<ValidateInvocationResponse>> {
+        let worker_id = make_target_worker_id(component_id.0, Some(worker_name.0))?;
+
+        let record = recorded_http_api_request!(
+            "validate_invocation",
+            worker_id = worker_id.to_string(),
+            function = function.0
+        );
+
+        let response = self
+            .validate_invocation_and_respond(&worker_id, &function.0, params.0.params)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
     /// Invoke a function
     ///
     /// Ideal for invoking ephemeral components, but works with durable ones as well.
@@ -306,15 +684,7 @@ impl WorkerApi {
         );
 
         let response = self
-            .worker_service
-            .validate_and_invoke(
-                &worker_id,
-                idempotency_key.0,
-                function.0,
-                params.0.params,
-                None,
-                empty_worker_metadata(),
-            )
+            .invoke_and_forget(&worker_id, idempotency_key.0, function.0, params.0)
             .instrument(record.span.clone())
             .await
             .map_err(|e| e.into())
@@ -349,19 +719,74 @@ impl WorkerApi {
         );
 
         let response = self
-            .worker_service
-            .validate_and_invoke(
+            .invoke_and_forget(&worker_id, idempotency_key.0, function.0, params.0)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(InvokeResponse {}));
+
+        record.result(response)
+    }
+
+    /// Schedule an invocation
+    ///
+    /// Invokes a function of the worker at a given time, either an RFC3339 timestamp given in `scheduledAt` or after `delayMillis` counted from now, and immediately returns.
+    /// The schedule is persisted by the worker executor, so the invocation happens even if the executor is restarted in between, and the worker does not need to be running until it is due.
+    /// Returns the idempotency key of the invocation, which can be used to cancel it while it is pending.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/schedule",
+        method = "post",
+        operation_id = "schedule_invocation"
+    )]
+    async fn schedule_invocation(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        #[oai(name = "Idempotency-Key")] idempotency_key: Header<Option<IdempotencyKey>>,
+        function: Query<String>,
+        params: Json<ScheduleInvocationParameters>,
+    ) -> Result<Json<ScheduleInvocationResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let ScheduleInvocationParameters {
+            params,
+            scheduled_at,
+            delay_millis,
+        } = params.0;
+        let scheduled_at = match (scheduled_at, delay_millis) {
+            (Some(scheduled_at), None) => scheduled_at,
+            (None, Some(delay_millis)) => {
+                chrono::Utc::now() + std::time::Duration::from_millis(delay_millis)
+            }
+            _ => {
+                return Err(WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+                    errors: vec![
+                        "Exactly one of scheduledAt and delayMillis must be given".to_string()
+                    ],
+                })))
+            }
+        };
+
+        let record = recorded_http_api_request!(
+            "schedule_invocation",
+            worker_id = worker_id.to_string(),
+            idempotency_key = idempotency_key.0.as_ref().map(|v| v.value.clone()),
+            function = function.0,
+            scheduled_at = scheduled_at.to_rfc3339()
+        );
+
+        let response = self
+            .schedule_invocation_at(
                 &worker_id,
                 idempotency_key.0,
                 function.0,
-                params.0.params,
-                None,
-                empty_worker_metadata(),
+                params,
+                scheduled_at,
             )
             .instrument(record.span.clone())
             .await
             .map_err(|e| e.into())
-            .map(|_| Json(InvokeResponse {}));
+            .map(|idempotency_key| Json(ScheduleInvocationResponse { idempotency_key }));
 
         record.result(response)
     }
@@ -444,10 +869,246 @@ impl WorkerApi {
         record.result(response)
     }
 
-    /// Get metadata of a worker
+    /// Abort the running invocation of a worker
     ///
-    /// Returns metadata about an existing worker:
-    /// - `workerId` is a combination of the used component and the worker's user specified name
+    /// Interrupts the worker like the interrupt endpoint, but only if it is currently executing the
+    /// invocation with the given idempotency key. Otherwise, for example because the invocation
+    /// already finished or is still waiting in the queue, the worker is left untouched.
+    /// The invocations queued after the aborted one are kept, and get processed once the worker is resumed.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/invocations/:idempotency_key/abort",
+        method = "post",
+        operation_id = "abort_running_invocation"
+    )]
+    async fn abort_running_invocation(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        idempotency_key: Path<IdempotencyKey>,
+    ) -> Result<Json<AbortRunningInvocationResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record = recorded_http_api_request!(
+            "abort_running_invocation",
+            worker_id = worker_id.to_string(),
+            idempotency_key = idempotency_key.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .abort_running_invocation(
+                &worker_id,
+                &idempotency_key.0,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|aborted| Json(AbortRunningInvocationResponse { aborted }));
+
+        record.result(response)
+    }
+
+    /// Cancel a pending invocation of a worker
+    ///
+    /// Removes the invocation with the given idempotency key from the worker's invocation queue
+    /// before the worker starts executing it. Callers awaiting its result get an interrupted error.
+    /// Invocations which are already running or have completed are left untouched, to stop a running
+    /// invocation use the abort endpoint.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/invocations/:idempotency_key/cancel",
+        method = "post",
+        operation_id = "cancel_invocation"
+    )]
+    async fn cancel_invocation(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        idempotency_key: Path<IdempotencyKey>,
+    ) -> Result<Json<CancelInvocationResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record = recorded_http_api_request!(
+            "cancel_invocation",
+            worker_id = worker_id.to_string(),
+            idempotency_key = idempotency_key.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .cancel_invocation(
+                &worker_id,
+                &idempotency_key.0,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|canceled| Json(CancelInvocationResponse { canceled }));
+
+        record.result(response)
+    }
+
+    /// Hold a pending invocation of a worker
+    ///
+    /// Keeps the invocation with the given idempotency key in the worker's invocation queue without
+    /// executing it, while the worker goes on with the invocations queued after it. The hold is
+    /// recorded in the worker's oplog, so it is kept when the worker gets restarted. A worker
+    /// having only held invocations left is considered idle.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/invocations/:idempotency_key/hold",
+        method = "post",
+        operation_id = "hold_invocation"
+    )]
+    async fn hold_invocation(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        idempotency_key: Path<IdempotencyKey>,
+    ) -> Result<Json<HoldInvocationResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record = recorded_http_api_request!(
+            "hold_invocation",
+            worker_id = worker_id.to_string(),
+            idempotency_key = idempotency_key.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .hold_invocation(
+                &worker_id,
+                &idempotency_key.0,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(HoldInvocationResponse {}));
+
+        record.result(response)
+    }
+
+    /// Release a held invocation of a worker
+    ///
+    /// Releases an invocation held by the hold endpoint, so the worker executes it in its turn.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/invocations/:idempotency_key/release",
+        method = "post",
+        operation_id = "release_invocation"
+    )]
+    async fn release_invocation(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        idempotency_key: Path<IdempotencyKey>,
+    ) -> Result<Json<ReleaseInvocationResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record = recorded_http_api_request!(
+            "release_invocation",
+            worker_id = worker_id.to_string(),
+            idempotency_key = idempotency_key.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .release_invocation(
+                &worker_id,
+                &idempotency_key.0,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(ReleaseInvocationResponse {}));
+
+        record.result(response)
+    }
+
+    /// Start or stop profiling a worker
+    ///
+    /// While profiling is enabled, the guest stack of the worker is sampled periodically, and the
+    /// growth of its linear memory is attributed to the sampled stacks. Starting discards the previously
+    /// collected profile. The profile is kept in memory of the worker executor, so it is lost if the
+    /// worker gets unloaded.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/profiling",
+        method = "post",
+        operation_id = "set_worker_profiling"
+    )]
+    async fn set_worker_profiling(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        enabled: Query<bool>,
+    ) -> Result<Json<SetWorkerProfilingResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record = recorded_http_api_request!(
+            "set_worker_profiling",
+            worker_id = worker_id.to_string(),
+            enabled = enabled.0
+        );
+
+        let response = self
+            .worker_service
+            .set_profiling(
+                &worker_id,
+                enabled.0,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(SetWorkerProfilingResponse {}));
+
+        record.result(response)
+    }
+
+    /// Get the profile of a worker
+    ///
+    /// Returns the samples collected since profiling was last started as an uncompressed pprof profile,
+    /// which can be inspected for example with `go tool pprof`.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/profile",
+        method = "get",
+        operation_id = "get_worker_profile"
+    )]
+    async fn get_worker_profile(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+    ) -> Result<Binary<Vec<u8>>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record =
+            recorded_http_api_request!("get_worker_profile", worker_id = worker_id.to_string());
+
+        let response = self
+            .worker_service
+            .get_profile(
+                &worker_id,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Binary);
+
+        record.result(response)
+    }
+
+    /// Get metadata of a worker
+    ///
+    /// Returns metadata about an existing worker:
+    /// - `workerId` is a combination of the used component and the worker's user specified name
     /// - `accountId` the account the worker is created by
     /// - `args` is the provided command line arguments passed to the worker
     /// - `env` is the provided map of environment variables passed to the worker
@@ -509,6 +1170,19 @@ impl WorkerApi {
     /// - StringFilterComparator: `eq|equal|=|==`, `ne|notequal|!=`, `like`, `notlike`
     /// - FilterComparator: `eq|equal|=|==`, `ne|notequal|!=`, `ge|greaterequal|>=`, `gt|greater|>`, `le|lessequal|<=`, `lt|less|<`
     ///
+    /// ### Combining filters
+    ///
+    /// A filter can combine conditions with `&&` (`AND`), `||` (`OR`), `!` (`NOT`) and parentheses,
+    /// for example `status == Running && (env.REGION == "eu" || created_at > 2024-06-01)`.
+    /// Values containing whitespace or operator characters must be double quoted.
+    /// Multiple `filter` parameters are combined with AND.
+    ///
+    /// ### Selecting fields
+    ///
+    /// With `fields`, only the selected parts of the worker metadata are returned besides the
+    /// worker id, status and creation time, for example `fields=Env&fields=LastError`.
+    /// The other fields are left empty.
+    ///
     /// Returns metadata about an existing component workers:
     /// - `workers` list of workers metadata
     /// - `cursor` cursor for next request, if cursor is empty/null, there are no other values
@@ -524,6 +1198,7 @@ impl WorkerApi {
         cursor: Query<Option<String>>,
         count: Query<Option<u64>>,
         precise: Query<Option<bool>>,
+        fields: Query<Option<Vec<WorkerMetadataField>>>,
     ) -> Result<Json<WorkersMetadataResponse>> {
         let record = recorded_http_api_request!(
             "get_workers_metadata",
@@ -553,6 +1228,9 @@ impl WorkerApi {
                     cursor.unwrap_or_default(),
                     count.0.unwrap_or(50),
                     precise.0.unwrap_or(false),
+                    fields
+                        .0
+                        .map(|fields| fields.into_iter().collect::<WorkerMetadataProjection>()),
                     empty_worker_metadata(),
                     &EmptyAuthCtx::default(),
                 )
@@ -575,6 +1253,7 @@ impl WorkerApi {
     /// | Status    | FilterComparator       | Status of worker               | `{ "type": "Status", "comparator": "Equal", "value": "Running" }`                             |
     /// | Env       | StringFilterComparator | Environment variable of worker | `{ "type": "Env", "name": "var1", "comparator": "Equal", "value": "value" }`                  |
     /// | CreatedAt | FilterComparator       | Creation time of worker        | `{ "type": "CreatedAt", "comparator": "Greater", "value": "2024-04-01T12:10:00Z" }`           |
+    /// | ExpiresAt | FilterComparator       | Expiry of worker with a TTL    | `{ "type": "ExpiresAt", "comparator": "Less", "value": "2024-04-01T12:10:00Z" }`              |
     /// | And       |                        | And filter combinator          | `{ "type": "And", "filters": [ ... ] }`                                                       |
     /// | Or        |                        | Or filter combinator           | `{ "type": "Or", "filters": [ ... ] }`                                                        |
     /// | Not       |                        | Negates the specified filter   | `{ "type": "Not", "filter": { "type": "Version", "comparator": "GreaterEqual", "value": 0 } }`|
@@ -583,6 +1262,11 @@ impl WorkerApi {
     /// - StringFilterComparator: `Equal`, `NotEqual`, `Like`, `NotLike`
     /// - FilterComparator: `Equal`, `NotEqual`, `GreaterEqual`, `Greater`, `LessEqual`, `Less`
     ///
+    /// ### Selecting fields
+    ///
+    /// With `fields`, only the selected parts of the worker metadata are returned besides the
+    /// worker id, status and creation time. An empty list returns only these.
+    ///
     /// Returns metadata about an existing component workers:
     /// - `workers` list of workers metadata
     /// - `cursor` cursor for next request, if cursor is empty/null, there are no other values
@@ -609,6 +1293,10 @@ impl WorkerApi {
                 params.cursor.clone().unwrap_or_default(),
                 params.count.unwrap_or(50),
                 params.precise.unwrap_or(false),
+                params
+                    .fields
+                    .clone()
+                    .map(|fields| fields.into_iter().collect::<WorkerMetadataProjection>()),
                 empty_worker_metadata(),
                 &EmptyAuthCtx::default(),
             )
@@ -620,6 +1308,107 @@ impl WorkerApi {
         record.result(response)
     }
 
+    /// Get the metadata of many workers
+    ///
+    /// Returns the metadata of the workers with the given names, looked up with one call to each
+    /// of the executors owning them:
+    /// - `workers` the metadata of the workers which exist; the ones which do not are left out
+    /// - `errors` the workers whose metadata could not be looked up, with the reason
+    #[oai(
+        path = "/:component_id/workers/metadata",
+        method = "post",
+        operation_id = "get_workers_metadata_many"
+    )]
+    async fn get_workers_metadata_many(
+        &self,
+        component_id: Path<ComponentId>,
+        params: Json<WorkersMetadataManyRequest>,
+    ) -> Result<Json<WorkersMetadataManyResponse>> {
+        let record = recorded_http_api_request!(
+            "get_workers_metadata_many",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = match params
+            .worker_names
+            .iter()
+            .map(|name| make_worker_id(component_id.0.clone(), name.clone()))
+            .collect::<std::result::Result<Vec<_>, _>>()
+        {
+            Ok(worker_ids) => self
+                .worker_service
+                .get_metadata_many(
+                    &worker_ids,
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await
+                .map_err(|e| e.into())
+                .map(|result| {
+                    Json(WorkersMetadataManyResponse {
+                        workers: result.workers,
+                        errors: result
+                            .errors
+                            .into_iter()
+                            .map(|(worker_id, error)| WorkerMetadataLookupError {
+                                worker_id,
+                                error: error.to_safe_string(),
+                            })
+                            .collect(),
+                    })
+                }),
+            Err(err) => Err(err),
+        };
+
+        record.result(response)
+    }
+
+    /// Search for workers by name
+    ///
+    /// Finds workers whose name starts with `prefix`, or fuzzily matches `pattern`, for example
+    /// `ordsvc` matches `order-service-1`. Unlike the name filters of `find_workers_metadata`,
+    /// this does not scan the metadata of every worker, so it is fast even for components with
+    /// many workers.
+    ///
+    /// Returns the ids of at most `count` (default 50) matching workers, the closest matches first.
+    #[oai(
+        path = "/:component_id/workers/search",
+        method = "post",
+        operation_id = "search_workers"
+    )]
+    async fn search_workers(
+        &self,
+        component_id: Path<ComponentId>,
+        params: Json<WorkersSearchRequest>,
+    ) -> Result<Json<WorkersSearchResponse>> {
+        let record = recorded_http_api_request!(
+            "search_workers",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = match params.query() {
+            Ok(query) => self
+                .worker_service
+                .search_workers(
+                    &component_id.0,
+                    query,
+                    params.count.unwrap_or(50),
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await
+                .map_err(|e| e.into())
+                .map(|workers| Json(WorkersSearchResponse { workers })),
+            Err(error) => Err(WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+                errors: vec![error],
+            }))),
+        };
+
+        record.result(response)
+    }
+
     /// Resume a worker
     #[oai(
         path = "/:component_id/workers/:worker_name/resume",
@@ -682,66 +1471,654 @@ impl WorkerApi {
         record.result(response)
     }
 
-    /// Get the oplog of a worker
+    /// Update the environment variables of a worker
+    ///
+    /// Replaces all the environment variables of the worker, without having to delete and recreate it.
+    /// A running worker keeps its current environment, the new one is used from the next restart or
+    /// update of the worker. The change is recorded in the worker's oplog.
     #[oai(
-        path = "/:component_id/workers/:worker_name/oplog",
-        method = "get",
-        operation_id = "get_oplog"
+        path = "/:component_id/workers/:worker_name/env",
+        method = "put",
+        operation_id = "update_worker_env"
     )]
-    async fn get_oplog(
+    async fn update_worker_env(
         &self,
         component_id: Path<ComponentId>,
         worker_name: Path<String>,
-        from: Query<u64>,
-        count: Query<u64>,
-        cursor: Query<Option<OplogCursor>>,
-    ) -> Result<Json<GetOplogResponse>> {
+        params: Json<UpdateWorkerEnvRequest>,
+    ) -> Result<Json<UpdateWorkerEnvResponse>> {
         let worker_id = make_worker_id(component_id.0, worker_name.0)?;
 
-        let record = recorded_http_api_request!("get_oplog", worker_id = worker_id.to_string());
+        let record =
+            recorded_http_api_request!("update_worker_env", worker_id = worker_id.to_string());
 
         let response = self
             .worker_service
-            .get_oplog(
+            .update_env(
                 &worker_id,
-                OplogIndex::from_u64(from.0),
-                cursor.0,
-                count.0,
+                params.0.env,
                 empty_worker_metadata(),
                 &EmptyAuthCtx::default(),
             )
             .instrument(record.span.clone())
             .await
             .map_err(|e| e.into())
-            .map(Json);
+            .map(|_| Json(UpdateWorkerEnvResponse {}));
 
         record.result(response)
     }
 
-}
+    /// Enable shadow invocations for a worker
+    ///
+    /// Every invocation of the worker is also sent to a shadow worker running the given candidate
+    /// component version, which makes it possible to validate a new version with production traffic
+    /// before updating the worker. The shadow worker is named `<worker_name>.shadow`, which is not a valid
+    /// name for other workers, and it is created with the same arguments and environment as the original
+    /// worker if it does not exist yet.
+    ///
+    /// The results of the shadow invocations are discarded, or if `compareResults` is set, compared to
+    /// the original results with the differences logged by the worker executor.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/shadow",
+        method = "put",
+        operation_id = "enable_worker_shadow"
+    )]
+    async fn enable_worker_shadow(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        params: Json<EnableWorkerShadowRequest>,
+    ) -> Result<Json<EnableWorkerShadowResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
 
-fn make_worker_id(
-    component_id: ComponentId,
-    worker_name: String,
-) -> std::result::Result<WorkerId, WorkerApiBaseError> {
-    validate_worker_name(&worker_name).map_err(|error| {
-        WorkerApiBaseError::BadRequest(Json(ErrorsBody {
-            errors: vec![format!("Invalid worker name: {error}")],
-        }))
-    })?;
-    Ok(WorkerId {
-        component_id,
-        worker_name,
-    })
-}
+        let record =
+            recorded_http_api_request!("enable_worker_shadow", worker_id = worker_id.to_string());
 
-fn make_target_worker_id(
-    component_id: ComponentId,
-    worker_name: Option<String>,
-) -> std::result::Result<TargetWorkerId, WorkerApiBaseError> {
-    if let Some(worker_name) = &worker_name {
-        validate_worker_name(worker_name).map_err(|error| {
-            WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+        let response = self
+            .worker_service
+            .enable_shadow(
+                &worker_id,
+                params.component_version,
+                params.compare_results,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|shadow_worker_id| Json(EnableWorkerShadowResponse { shadow_worker_id }));
+
+        record.result(response)
+    }
+
+    /// Disable shadow invocations for a worker
+    ///
+    /// The shadow worker itself is not deleted.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/shadow",
+        method = "delete",
+        operation_id = "disable_worker_shadow"
+    )]
+    async fn disable_worker_shadow(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+    ) -> Result<Json<DisableWorkerShadowResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record =
+            recorded_http_api_request!("disable_worker_shadow", worker_id = worker_id.to_string());
+
+        let response = self
+            .worker_service
+            .disable_shadow(
+                &worker_id,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(DisableWorkerShadowResponse {}));
+
+        record.result(response)
+    }
+
+    /// Set the TTL of a worker
+    ///
+    /// The worker and all its storage is deleted once the TTL elapses, counted from now. Setting a
+    /// new TTL replaces the previous one, and without a TTL the worker is kept until it is deleted
+    /// explicitly. The expiry and the remaining TTL of a worker is included in its metadata.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/ttl",
+        method = "put",
+        operation_id = "set_worker_ttl"
+    )]
+    async fn set_worker_ttl(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        params: Json<SetWorkerTtlRequest>,
+    ) -> Result<Json<SetWorkerTtlResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record =
+            recorded_http_api_request!("set_worker_ttl", worker_id = worker_id.to_string());
+
+        let response = self
+            .worker_service
+            .set_ttl(
+                &worker_id,
+                params.ttl_seconds.map(std::time::Duration::from_secs),
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(SetWorkerTtlResponse {}));
+
+        record.result(response)
+    }
+
+    /// Set the deduplication window of a component
+    ///
+    /// Invocations with an idempotency key that was already used are not executed again, instead
+    /// they get the result of the earlier invocation. The deduplication window limits how long the
+    /// completed idempotency keys of the component's workers are remembered, which bounds the memory
+    /// used by workers handling many invocations. Without a window the keys are never forgotten.
+    ///
+    /// Changes are picked up by the running workers within a minute.
+    #[oai(
+        path = "/:component_id/deduplication-window",
+        method = "put",
+        operation_id = "set_deduplication_window"
+    )]
+    async fn set_deduplication_window(
+        &self,
+        component_id: Path<ComponentId>,
+        params: Json<SetDeduplicationWindowRequest>,
+    ) -> Result<Json<SetDeduplicationWindowResponse>> {
+        let record = recorded_http_api_request!(
+            "set_deduplication_window",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .set_deduplication_window(
+                &component_id.0,
+                params.window_seconds.map(std::time::Duration::from_secs),
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json(SetDeduplicationWindowResponse {}));
+
+        record.result(response)
+    }
+
+    /// Get the most common errors of a component's workers
+    ///
+    /// Groups the failed and retrying workers of the component by the fingerprint of their last
+    /// error. Errors only differing in details such as numbers, identifiers or code addresses have
+    /// the same fingerprint. The groups with the most workers come first, and each lists a few of
+    /// its workers.
+    #[oai(
+        path = "/:component_id/error-groups",
+        method = "get",
+        operation_id = "get_error_groups"
+    )]
+    async fn get_error_groups(
+        &self,
+        component_id: Path<ComponentId>,
+        /// Maximum number of groups to return, defaults to 5
+        limit: Query<Option<u64>>,
+    ) -> Result<Json<ErrorGroupsResponse>> {
+        let record = recorded_http_api_request!(
+            "get_error_groups",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .get_error_groups(
+                &component_id.0,
+                limit.0.unwrap_or(5),
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|groups| Json(ErrorGroupsResponse { groups }));
+
+        record.result(response)
+    }
+
+    /// Get the invocation statistics of a component's functions
+    ///
+    /// Returns the number of invocations, the failure rate, the consumed fuel and the latency
+    /// percentiles of each invoked exported function of the component, the most invoked functions
+    /// first. Only live invocations are counted, since the executors started. The executors keep
+    /// the statistics of a limited number of components, dropping those of the least recently
+    /// invoked ones.
+    #[oai(
+        path = "/:component_id/function-stats",
+        method = "get",
+        operation_id = "get_function_stats"
+    )]
+    async fn get_function_stats(
+        &self,
+        component_id: Path<ComponentId>,
+    ) -> Result<Json<FunctionStatsResponse>> {
+        let record = recorded_http_api_request!(
+            "get_function_stats",
+            component_id = component_id.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .get_function_stats(
+                &component_id.0,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|functions| Json(FunctionStatsResponse { functions }));
+
+        record.result(response)
+    }
+
+    /// Poll the events emitted by the workers of a component
+    ///
+    /// Returns the events the component's workers emitted to the topic through `golem:api/events`,
+    /// following the event with the id `cursor`, or from the beginning of the topic if it is
+    /// missing. Polling again with the returned `next` cursor returns the subsequent events.
+    #[oai(
+        path = "/:component_id/events/:topic",
+        method = "get",
+        operation_id = "poll_events"
+    )]
+    async fn poll_events(
+        &self,
+        component_id: Path<ComponentId>,
+        topic: Path<String>,
+        cursor: Query<Option<u64>>,
+        /// Maximum number of events to return, defaults to 100
+        count: Query<Option<u64>>,
+    ) -> Result<Json<PollEventsResponse>> {
+        let record = recorded_http_api_request!(
+            "poll_events",
+            component_id = component_id.0.to_string(),
+            topic = topic.0.clone(),
+        );
+
+        let response = self
+            .worker_service
+            .poll_events(
+                &component_id.0,
+                &topic.0,
+                cursor.0.unwrap_or(0),
+                count.0.unwrap_or(100),
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Get the oplog of a worker
+    ///
+    /// The entries can be filtered by the invoked exported function (`function`), the interface of
+    /// the called host functions (`interface`), a time window (`from_timestamp` and `to_timestamp`)
+    /// or to the ones recording errors (`errors`). When filtered, the returned entries are not
+    /// contiguous and their oplog indices are returned in `indices`.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/oplog",
+        method = "get",
+        operation_id = "get_oplog"
+    )]
+    async fn get_oplog(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        from: Query<u64>,
+        count: Query<u64>,
+        cursor: Query<Option<OplogCursor>>,
+        function: Query<Option<String>>,
+        interface: Query<Option<String>>,
+        from_timestamp: Query<Option<Timestamp>>,
+        to_timestamp: Query<Option<Timestamp>>,
+        errors: Query<Option<bool>>,
+    ) -> Result<Json<GetOplogResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let query = OplogQuery {
+            exported_function: function.0,
+            imported_interface: interface.0,
+            from_timestamp: from_timestamp.0,
+            to_timestamp: to_timestamp.0,
+            errors_only: errors.0.unwrap_or(false),
+        };
+        let query = (query != OplogQuery::default()).then_some(query);
+
+        let record = recorded_http_api_request!("get_oplog", worker_id = worker_id.to_string());
+
+        let response = self
+            .worker_service
+            .get_oplog(
+                &worker_id,
+                OplogIndex::from_u64(from.0),
+                cursor.0,
+                count.0,
+                query,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Get the raw oplog of a worker
+    ///
+    /// Returns the oplog entries as they are stored by the worker executor, serialized as JSON,
+    /// for debugging workers. At most 100 entries are returned by a single request.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/oplog/raw",
+        method = "get",
+        operation_id = "get_raw_oplog"
+    )]
+    async fn get_raw_oplog(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        from: Query<u64>,
+        count: Query<u64>,
+    ) -> Result<Json<GetRawOplogResponse>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record = recorded_http_api_request!("get_raw_oplog", worker_id = worker_id.to_string());
+
+        let response = self
+            .worker_service
+            .get_raw_oplog(
+                &worker_id,
+                OplogIndex::from_u64(from.0),
+                count.0,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Get the execution timeline of a worker
+    ///
+    /// Converts the worker's oplog into a timeline of invocations (with their durations, retries
+    /// and the host functions they called) and worker level events such as failures, restarts and updates.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/timeline",
+        method = "get",
+        operation_id = "get_worker_timeline"
+    )]
+    async fn get_worker_timeline(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+    ) -> Result<Json<WorkerTimeline>> {
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record =
+            recorded_http_api_request!("get_worker_timeline", worker_id = worker_id.to_string());
+
+        let response = self
+            .get_full_oplog(&worker_id)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|entries| {
+                Json(WorkerTimeline::from_oplog(
+                    entries.iter().map(|(idx, entry)| (*idx, entry)),
+                ))
+            });
+
+        record.result(response)
+    }
+}
+
+impl WorkerApi {
+    async fn invoke_and_await_and_transform(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function: String,
+        params: InvokeParameters,
+        transform: bool,
+        timeout_millis: Option<u64>,
+    ) -> std::result::Result<TypeAnnotatedValue, WorkerServiceError> {
+        let function = self.resolve_function_name(worker_id, function).await?;
+        self.worker_service
+            .validate_and_invoke_and_await_typed(
+                worker_id,
+                idempotency_key,
+                function,
+                params.params,
+                params.blob_params.into_iter().map(|p| p.into()).collect(),
+                invocation_context(timeout_millis, transform),
+                empty_worker_metadata(),
+            )
+            .await
+    }
+
+    async fn invoke_and_await_stream(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function: String,
+        params: InvokeParameters,
+    ) -> std::result::Result<InvocationResultStream, WorkerServiceError> {
+        let function = self.resolve_function_name(worker_id, function).await?;
+        let values = self
+            .worker_service
+            .validate_typed_parameters(params.params)?;
+        self.worker_service
+            .invoke_and_await_function_stream(
+                worker_id,
+                idempotency_key,
+                function,
+                values,
+                params.blob_params.into_iter().map(|p| p.into()).collect(),
+                None,
+                empty_worker_metadata(),
+            )
+            .await
+    }
+
+    async fn invoke_and_await_wave_and_transform(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function: String,
+        params: InvokeWaveParameters,
+        transform: bool,
+        timeout_millis: Option<u64>,
+    ) -> std::result::Result<TypeAnnotatedValue, WorkerServiceError> {
+        self.worker_service
+            .invoke_and_await_function_wave(
+                worker_id,
+                idempotency_key,
+                function,
+                params.params,
+                invocation_context(timeout_millis, transform),
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await
+    }
+
+    async fn invoke_and_forget(
+        &self,
+        worker_id: &TargetWorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function: String,
+        params: InvokeParameters,
+    ) -> std::result::Result<(), WorkerServiceError> {
+        let function = self.resolve_function_name(worker_id, function).await?;
+        self.worker_service
+            .validate_and_invoke(
+                worker_id,
+                idempotency_key,
+                function,
+                params.params,
+                params.blob_params.into_iter().map(|p| p.into()).collect(),
+                None,
+                empty_worker_metadata(),
+            )
+            .await
+    }
+
+    async fn schedule_invocation_at(
+        &self,
+        worker_id: &WorkerId,
+        idempotency_key: Option<IdempotencyKey>,
+        function: String,
+        params: Vec<TypeAnnotatedValue>,
+        scheduled_at: chrono::DateTime<chrono::Utc>,
+    ) -> std::result::Result<IdempotencyKey, WorkerServiceError> {
+        let function = self
+            .resolve_function_name(&worker_id.clone().into_target_worker_id(), function)
+            .await?;
+        self.worker_service
+            .schedule_invocation(
+                worker_id,
+                idempotency_key,
+                function,
+                params,
+                scheduled_at,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await
+    }
+
+    /// Resolves the function aliases declared by the component to the fully qualified name
+    async fn resolve_function_name(
+        &self,
+        worker_id: &TargetWorkerId,
+        function: String,
+    ) -> std::result::Result<String, WorkerServiceError> {
+        self.worker_service
+            .resolve_function_name(
+                worker_id,
+                function,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await
+    }
+
+    async fn validate_invocation_and_respond(
+        &self,
+        worker_id: &TargetWorkerId,
+        function: &str,
+        params: Vec<TypeAnnotatedValue>,
+    ) -> std::result::Result<ValidateInvocationResponse, WorkerServiceError> {
+        let validation = self
+            .worker_service
+            .validate_invocation(
+                worker_id,
+                function,
+                params,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await?;
+        Ok(validation.into())
+    }
+
+    async fn get_full_oplog(
+        &self,
+        worker_id: &WorkerId,
+    ) -> std::result::Result<Vec<(u64, PublicOplogEntry)>, WorkerServiceError> {
+        let mut entries = Vec::new();
+        let mut cursor: Option<OplogCursor> = None;
+
+        loop {
+            let chunk = self
+                .worker_service
+                .get_oplog(
+                    worker_id,
+                    OplogIndex::INITIAL,
+                    cursor,
+                    OPLOG_CHUNK_SIZE,
+                    None,
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?;
+
+            if chunk.entries.is_empty() {
+                break;
+            }
+
+            entries.extend(
+                chunk
+                    .entries
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, e)| (chunk.first_index_in_chunk + i as u64, e)),
+            );
+            cursor = chunk.next;
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+const OPLOG_CHUNK_SIZE: u64 = 100;
+
+pub(crate) fn make_worker_id(
+    component_id: ComponentId,
+    worker_name: String,
+) -> std::result::Result<WorkerId, WorkerApiBaseError> {
+    validate_worker_name(&worker_name).map_err(|error| {
+        WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+            errors: vec![format!("Invalid worker name: {error}")],
+        }))
+    })?;
+    Ok(WorkerId {
+        component_id,
+        worker_name,
+    })
+}
+
+fn make_target_worker_id(
+    component_id: ComponentId,
+    worker_name: Option<String>,
+) -> std::result::Result<TargetWorkerId, WorkerApiBaseError> {
+    if let Some(worker_name) = &worker_name {
+        validate_worker_name(worker_name).map_err(|error| {
+            WorkerApiBaseError::BadRequest(Json(ErrorsBody {
                 errors: vec![format!("Invalid worker name: {error}")],
             }))
         })?;
@@ -752,3 +2129,16 @@ fn make_target_worker_id(
         worker_name,
     })
 }
+
+/// The invocation context carrying the timeout and the result transformation requested by the
+/// query parameters, if any
+fn invocation_context(timeout_millis: Option<u64>, transform: bool) -> Option<InvocationContext> {
+    (timeout_millis.is_some() || transform).then(|| InvocationContext {
+        parent: None,
+        args: vec![],
+        env: HashMap::new(),
+        caller: None,
+        timeout_millis,
+        map_result: transform,
+    })
+}
@@ -0,0 +1,75 @@
+use crate::api::worker::make_worker_id;
+use crate::empty_worker_metadata;
+use crate::service::worker::WorkerService;
+use golem_common::model::ComponentId;
+use golem_common::recorded_http_api_request;
+use golem_service_base::api_tags::ApiTags;
+use golem_service_base::auth::EmptyAuthCtx;
+use golem_service_base::model::{RedactOplogRequest, RedactOplogResponse};
+use golem_worker_service_base::api::{AdminConfig, WorkerApiBaseError};
+use poem_openapi::param::{Header, Path};
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use tracing::Instrument;
+
+pub struct WorkerAdminApi {
+    pub worker_service: WorkerService,
+    pub admin_config: AdminConfig,
+}
+
+type Result<T> = std::result::Result<T, WorkerApiBaseError>;
+
+#[OpenApi(prefix_path = "/v1/admin/components", tag = ApiTags::Worker)]
+impl WorkerAdminApi {
+    /// Redact entries of a worker's oplog
+    ///
+    /// Replaces the recorded data of the given oplog entries by placeholder values of the same types,
+    /// for example to remove personal data logged by mistake. Strings are replaced by `<redacted>`,
+    /// numbers by zero, and lists, options and flags are emptied.
+    ///
+    /// Only the entries which do not affect how the worker gets replayed can be redacted: log
+    /// messages, the results of invocations, and the parameters of invocations in deleted regions of
+    /// the oplog. The request fails without changing anything if any of the entries cannot be
+    /// redacted. The redaction is recorded in the oplog with the given reason, and the index of that
+    /// entry is returned.
+    ///
+    /// Requires one of the configured admin tokens as a bearer token in the `Authorization` header.
+    /// A running worker is stopped once its current invocation completes, and restarted after the redaction.
+    #[oai(
+        path = "/:component_id/workers/:worker_name/oplog/redact",
+        method = "post",
+        operation_id = "redact_oplog"
+    )]
+    async fn redact_oplog(
+        &self,
+        component_id: Path<ComponentId>,
+        worker_name: Path<String>,
+        #[oai(name = "Authorization")] authorization: Header<Option<String>>,
+        request: Json<RedactOplogRequest>,
+    ) -> Result<Json<RedactOplogResponse>> {
+        self.admin_config.authorize(authorization.0.as_deref())?;
+        let worker_id = make_worker_id(component_id.0, worker_name.0)?;
+
+        let record = recorded_http_api_request!(
+            "redact_oplog",
+            worker_id = worker_id.to_string(),
+            entries = request.0.oplog_indices.len()
+        );
+
+        let response = self
+            .worker_service
+            .redact_oplog(
+                &worker_id,
+                request.0.oplog_indices,
+                request.0.reason,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|oplog_index| Json(RedactOplogResponse { oplog_index }));
+
+        record.result(response)
+    }
+}
@@ -17,12 +17,15 @@ use std::time::Duration;
 use crate::empty_worker_metadata;
 use crate::service::worker::WorkerService;
 use futures::StreamExt;
-use golem_common::model::{ComponentId, WorkerId};
+use golem_common::model::{ComponentId, ConnectOptions, WorkerFilter, WorkerId};
 use golem_common::recorded_http_api_request;
 use golem_service_base::auth::EmptyAuthCtx;
-use golem_service_base::model::{validate_worker_name, ErrorsBody};
+use golem_service_base::model::{validate_worker_name, ErrorBody, ErrorsBody};
 use golem_worker_service_base::api::WorkerApiBaseError;
-use golem_worker_service_base::service::worker::{proxy_worker_connection, ConnectWorkerStream};
+use golem_worker_service_base::service::worker::{
+    proxy_multi_worker_connection, proxy_worker_connection, ConnectWorkerStream,
+    MultiConnectWorkerStream,
+};
 use poem::web::websocket::WebSocket;
 use poem::web::{Data, Path};
 use poem::*;
@@ -40,13 +43,26 @@ impl ConnectService {
     }
 }
 
+/// Connects to a worker. With the `from_beginning=true` or `last_n=<n>` query parameters, all or
+/// the last `n` log events recorded in the worker's oplog are sent before the live events
 #[handler]
 pub async fn ws(
     Path((component_id, worker_name)): Path<(ComponentId, String)>,
+    request: &Request,
     websocket: WebSocket,
     Data(service): Data<&ConnectService>,
 ) -> Response {
-    connect_to_worker(service, component_id, worker_name)
+    let options = match parse_connect_options(request.uri().query().unwrap_or_default()) {
+        Ok(options) => options,
+        Err(error) => {
+            let error = WorkerApiBaseError::BadRequest(Json(ErrorsBody {
+                errors: vec![error],
+            }));
+            return error.into_response();
+        }
+    };
+
+    connect_to_worker(service, component_id, worker_name, options)
         .await
         .map(|(worker_id, worker_stream)| {
             websocket
@@ -69,13 +85,73 @@ pub async fn ws(
         .unwrap_or_else(|err| err)
 }
 
+/// Connects to all the workers of a component matching the `filter` query parameters (combined
+/// with AND, in the same syntax as for listing workers), or to all of its workers if there is none
+#[handler]
+pub async fn ws_many(
+    Path(component_id): Path<ComponentId>,
+    request: &Request,
+    websocket: WebSocket,
+    Data(service): Data<&ConnectService>,
+) -> Response {
+    let filters = url::form_urlencoded::parse(request.uri().query().unwrap_or_default().as_bytes())
+        .filter(|(key, _)| key == "filter")
+        .map(|(_, value)| value.to_string())
+        .collect::<Vec<_>>();
+
+    connect_to_workers(service, component_id.clone(), filters)
+        .await
+        .map(|worker_stream| {
+            websocket
+                .on_upgrade(move |socket| {
+                    tokio::spawn(async move {
+                        let (sink, stream) = socket.split();
+                        let _ = proxy_multi_worker_connection(
+                            component_id,
+                            worker_stream,
+                            sink,
+                            stream,
+                            PING_INTERVAL,
+                            PING_TIMEOUT,
+                        )
+                        .await;
+                    })
+                })
+                .into_response()
+        })
+        .unwrap_or_else(|err| err)
+}
+
 const PING_INTERVAL: Duration = Duration::from_secs(30);
 const PING_TIMEOUT: Duration = Duration::from_secs(15);
 
+fn parse_connect_options(query: &str) -> Result<ConnectOptions, String> {
+    let mut options = ConnectOptions::default();
+    for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+        match key.as_ref() {
+            "from_beginning" => {
+                options.from_beginning = value
+                    .parse()
+                    .map_err(|_| format!("Invalid from_beginning parameter: {value}"))?
+            }
+            "last_n" => {
+                options.last_n = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("Invalid last_n parameter: {value}"))?,
+                )
+            }
+            _ => {}
+        }
+    }
+    Ok(options)
+}
+
 async fn connect_to_worker(
     service: &ConnectService,
     component_id: ComponentId,
     worker_name: String,
+    options: ConnectOptions,
 ) -> Result<(WorkerId, ConnectWorkerStream), Response> {
     validate_worker_name(&worker_name).map_err(|e| {
         let error = WorkerApiBaseError::BadRequest(Json(ErrorsBody {
@@ -94,6 +170,7 @@ async fn connect_to_worker(
         .worker_service
         .connect(
             &worker_id,
+            options,
             empty_worker_metadata(),
             &EmptyAuthCtx::default(),
         )
@@ -110,3 +187,49 @@ async fn connect_to_worker(
         }
     }
 }
+
+async fn connect_to_workers(
+    service: &ConnectService,
+    component_id: ComponentId,
+    filters: Vec<String>,
+) -> Result<MultiConnectWorkerStream, Response> {
+    let filter = if filters.is_empty() {
+        None
+    } else {
+        Some(WorkerFilter::from(filters).map_err(|e| {
+            let error = WorkerApiBaseError::BadRequest(Json(ErrorsBody { errors: vec![e] }));
+            error.into_response()
+        })?)
+    };
+
+    let record =
+        recorded_http_api_request!("connect_workers", component_id = component_id.to_string());
+
+    let result = service
+        .worker_service
+        .connect_many(
+            &component_id,
+            filter,
+            empty_worker_metadata(),
+            &EmptyAuthCtx::default(),
+        )
+        .instrument(record.span.clone())
+        .await;
+
+    match result {
+        Ok(worker_stream) if worker_stream.worker_count() == 0 => {
+            let error = WorkerApiBaseError::NotFound(Json(ErrorBody {
+                error: "No workers match the filter".to_string(),
+            }));
+            let error = record.fail(error.clone(), &error);
+            Err(error.into_response())
+        }
+        Ok(worker_stream) => record.succeed(Ok(worker_stream)),
+        Err(error) => {
+            tracing::error!("Error connecting to workers: {error}");
+            let error = WorkerApiBaseError::from(error);
+            let error = record.fail(error.clone(), &error);
+            Err(error.into_response())
+        }
+    }
+}
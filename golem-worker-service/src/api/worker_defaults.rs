@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use crate::default_account_id;
+use golem_common::model::worker_defaults::WorkerDefaults;
+use golem_common::model::ComponentId;
+use golem_common::recorded_http_api_request;
+use golem_service_base::api_tags::ApiTags;
+use golem_worker_service_base::api::WorkerApiBaseError;
+use golem_worker_service_base::service::worker_defaults::WorkerDefaultsService;
+use poem_openapi::param::Path;
+use poem_openapi::payload::Json;
+use poem_openapi::*;
+use tracing::Instrument;
+
+pub struct WorkerDefaultsApi {
+    worker_defaults_service: Arc<dyn WorkerDefaultsService + Sync + Send>,
+}
+
+type Result<T> = std::result::Result<T, WorkerApiBaseError>;
+
+#[OpenApi(prefix_path = "/v1", tag = ApiTags::Worker)]
+impl WorkerDefaultsApi {
+    pub fn new(worker_defaults_service: Arc<dyn WorkerDefaultsService + Sync + Send>) -> Self {
+        Self {
+            worker_defaults_service,
+        }
+    }
+
+    /// Get the worker defaults of the account
+    ///
+    /// Returns the environment variables, retry policy and limits applied to every new worker
+    /// of the account.
+    #[oai(
+        path = "/worker-defaults",
+        method = "get",
+        operation_id = "get_account_worker_defaults"
+    )]
+    async fn get_account_worker_defaults(&self) -> Result<Json<WorkerDefaults>> {
+        let record = recorded_http_api_request!("get_account_worker_defaults",);
+        let response = self
+            .worker_defaults_service
+            .get(&default_account_id(), None)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Set the worker defaults of the account
+    ///
+    /// The defaults are applied to the workers created after this call. The environment
+    /// variables given when creating a worker take precedence over the defaults.
+    #[oai(
+        path = "/worker-defaults",
+        method = "put",
+        operation_id = "set_account_worker_defaults"
+    )]
+    async fn set_account_worker_defaults(
+        &self,
+        defaults: Json<WorkerDefaults>,
+    ) -> Result<Json<WorkerDefaults>> {
+        let record = recorded_http_api_request!("set_account_worker_defaults",);
+        let response = self
+            .worker_defaults_service
+            .set(&default_account_id(), None, defaults.0.clone())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| defaults);
+
+        record.result(response)
+    }
+
+    /// Delete the worker defaults of the account
+    #[oai(
+        path = "/worker-defaults",
+        method = "delete",
+        operation_id = "delete_account_worker_defaults"
+    )]
+    async fn delete_account_worker_defaults(&self) -> Result<Json<String>> {
+        let record = recorded_http_api_request!("delete_account_worker_defaults",);
+        let response = self
+            .worker_defaults_service
+            .delete(&default_account_id(), None)
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json("Worker defaults deleted".to_string()));
+
+        record.result(response)
+    }
+
+    /// Get the worker defaults of a component
+    ///
+    /// Returns the defaults set for the component, without the account level defaults they
+    /// override.
+    #[oai(
+        path = "/components/:component_id/worker-defaults",
+        method = "get",
+        operation_id = "get_component_worker_defaults"
+    )]
+    async fn get_component_worker_defaults(
+        &self,
+        component_id: Path<ComponentId>,
+    ) -> Result<Json<WorkerDefaults>> {
+        let record = recorded_http_api_request!(
+            "get_component_worker_defaults",
+            component_id = component_id.0.to_string()
+        );
+        let response = self
+            .worker_defaults_service
+            .get(&default_account_id(), Some(&component_id.0))
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(Json);
+
+        record.result(response)
+    }
+
+    /// Set the worker defaults of a component
+    ///
+    /// The retry policy and limits set here override the ones of the account, and the
+    /// environment variables are merged with the account level ones.
+    #[oai(
+        path = "/components/:component_id/worker-defaults",
+        method = "put",
+        operation_id = "set_component_worker_defaults"
+    )]
+    async fn set_component_worker_defaults(
+        &self,
+        component_id: Path<ComponentId>,
+        defaults: Json<WorkerDefaults>,
+    ) -> Result<Json<WorkerDefaults>> {
+        let record = recorded_http_api_request!(
+            "set_component_worker_defaults",
+            component_id = component_id.0.to_string()
+        );
+        let response = self
+            .worker_defaults_service
+            .set(
+                &default_account_id(),
+                Some(&component_id.0),
+                defaults.0.clone(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| defaults);
+
+        record.result(response)
+    }
+
+    /// Delete the worker defaults of a component
+    #[oai(
+        path = "/components/:component_id/worker-defaults",
+        method = "delete",
+        operation_id = "delete_component_worker_defaults"
+    )]
+    async fn delete_component_worker_defaults(
+        &self,
+        component_id: Path<ComponentId>,
+    ) -> Result<Json<String>> {
+        let record = recorded_http_api_request!(
+            "delete_component_worker_defaults",
+            component_id = component_id.0.to_string()
+        );
+        let response = self
+            .worker_defaults_service
+            .delete(&default_account_id(), Some(&component_id.0))
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|_| Json("Worker defaults deleted".to_string()));
+
+        record.result(response)
+    }
+}
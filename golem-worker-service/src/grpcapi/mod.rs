@@ -1,8 +1,10 @@
 use golem_api_grpc::proto;
 use golem_api_grpc::proto::golem::apidefinition::v1::api_definition_service_server::ApiDefinitionServiceServer;
 use golem_api_grpc::proto::golem::worker::v1::worker_service_server::WorkerServiceServer;
+use golem_common::config::GrpcCompression;
+use golem_common::metrics::grpc::GrpcMessageMetricsLayer;
+use golem_common::with_grpc_compression;
 use std::net::SocketAddr;
-use tonic::codec::CompressionEncoding;
 use tonic::transport::{Error, Server};
 
 use crate::grpcapi::api_definition::GrpcApiDefinitionService;
@@ -12,7 +14,11 @@ use crate::service::Services;
 mod api_definition;
 mod worker;
 
-pub async fn start_grpc_server(addr: SocketAddr, services: &Services) -> Result<(), Error> {
+pub async fn start_grpc_server(
+    addr: SocketAddr,
+    grpc_compression: GrpcCompression,
+    services: &Services,
+) -> Result<(), Error> {
     let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
 
     health_reporter
@@ -29,23 +35,24 @@ pub async fn start_grpc_server(addr: SocketAddr, services: &Services) -> Result<
         .unwrap();
 
     Server::builder()
+        .layer(GrpcMessageMetricsLayer)
         .add_service(reflection_service)
         .add_service(health_service)
-        .add_service(
+        .add_service(with_grpc_compression!(
             WorkerServiceServer::new(WorkerGrpcApi::new(
                 services.component_service.clone(),
                 services.worker_service.clone(),
-            ))
-            .accept_compressed(CompressionEncoding::Gzip)
-            .send_compressed(CompressionEncoding::Gzip),
-        )
-        .add_service(
+                services.saga_coordinator.clone(),
+                services.maintenance_service.clone(),
+            )),
+            grpc_compression
+        ))
+        .add_service(with_grpc_compression!(
             ApiDefinitionServiceServer::new(GrpcApiDefinitionService::new(
                 services.definition_service.clone(),
-            ))
-            .accept_compressed(CompressionEncoding::Gzip)
-            .send_compressed(CompressionEncoding::Gzip),
-        )
+            )),
+            grpc_compression
+        ))
         .serve(addr)
         .await
 }
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use futures::{Stream, StreamExt};
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
+use std::pin::Pin;
+use std::sync::Arc;
 use tap::TapFallible;
 use tonic::{Request, Response, Status};
 use tracing::Instrument;
@@ -20,49 +23,85 @@ use tracing::Instrument;
 use golem_api_grpc::proto::golem::common::{Empty, ErrorBody, ErrorsBody};
 use golem_api_grpc::proto::golem::worker::v1::worker_service_server::WorkerService as GrpcWorkerService;
 use golem_api_grpc::proto::golem::worker::v1::{
-    complete_promise_response, delete_worker_response, get_oplog_response,
+    abort_running_invocation_response, cancel_invocation_response, complete_promise_response,
+    delete_worker_response, execute_saga_response, get_component_maintenance_response,
+    get_oplog_response, get_or_create_worker_response, get_worker_metadata_many_response,
     get_worker_metadata_response, get_workers_metadata_response, interrupt_worker_response,
-    invoke_and_await_json_response, invoke_and_await_response, invoke_and_await_typed_response,
-    invoke_response, launch_new_worker_response, resume_worker_response, update_worker_response,
-    worker_error, worker_execution_error, CompletePromiseRequest, CompletePromiseResponse,
-    ConnectWorkerRequest, DeleteWorkerRequest, DeleteWorkerResponse, GetOplogRequest,
-    GetOplogResponse, GetOplogSuccessResponse, GetWorkerMetadataRequest, GetWorkerMetadataResponse,
+    invoke_and_await_json_response, invoke_and_await_response, invoke_and_await_streaming_response,
+    invoke_and_await_typed_response, invoke_response, launch_new_worker_response,
+    poll_events_response, resume_worker_response, search_workers_response,
+    update_worker_env_response, update_worker_response, worker_error, worker_execution_error,
+    AbortRunningInvocationRequest, AbortRunningInvocationResponse,
+    AbortRunningInvocationSuccessResponse, CancelInvocationRequest, CancelInvocationResponse,
+    CancelInvocationSuccessResponse, CompletePromiseRequest, CompletePromiseResponse,
+    ConnectWorkerRequest, DeleteWorkerRequest, DeleteWorkerResponse, DeleteWorkerSuccessResponse,
+    ExecuteSagaRequest, ExecuteSagaResponse, GetComponentMaintenanceRequest,
+    GetComponentMaintenanceResponse, GetComponentMaintenanceSuccessResponse, GetOplogRequest,
+    GetOplogResponse, GetOplogSuccessResponse, GetOrCreateWorkerResponse,
+    GetOrCreateWorkerSuccessResponse, GetWorkerMetadataManyRequest, GetWorkerMetadataManyResponse,
+    GetWorkerMetadataManySuccessResponse, GetWorkerMetadataRequest, GetWorkerMetadataResponse,
     GetWorkersMetadataRequest, GetWorkersMetadataResponse, GetWorkersMetadataSuccessResponse,
     InterruptWorkerRequest, InterruptWorkerResponse, InvokeAndAwaitJsonRequest,
     InvokeAndAwaitJsonResponse, InvokeAndAwaitRequest, InvokeAndAwaitResponse,
-    InvokeAndAwaitTypedResponse, InvokeJsonRequest, InvokeRequest, InvokeResponse,
-    LaunchNewWorkerRequest, LaunchNewWorkerResponse, LaunchNewWorkerSuccessResponse,
-    ResumeWorkerRequest, ResumeWorkerResponse, UnknownError, UpdateWorkerRequest,
-    UpdateWorkerResponse, WorkerError as GrpcWorkerError, WorkerExecutionError,
+    InvokeAndAwaitStreamingHeader, InvokeAndAwaitStreamingResponse, InvokeAndAwaitTypedResponse,
+    InvokeJsonRequest, InvokeRequest, InvokeResponse, LaunchNewWorkerRequest,
+    LaunchNewWorkerResponse, LaunchNewWorkerSuccessResponse, PollEventsRequest, PollEventsResponse,
+    PollEventsSuccessResponse, ResumeWorkerRequest, ResumeWorkerResponse, SearchWorkersRequest,
+    SearchWorkersResponse, SearchWorkersSuccessResponse, SubscribeEventsRequest, UnknownError,
+    UpdateWorkerEnvRequest, UpdateWorkerEnvResponse, UpdateWorkerRequest, UpdateWorkerResponse,
+    WatchWorkersRequest, WorkerError as GrpcWorkerError, WorkerExecutionError,
+    WorkerMetadataLookupError,
+};
+use golem_api_grpc::proto::golem::worker::{
+    InvocationContext, InvokeResult, InvokeResultTyped, SagaOutcome, WorkerMetadata,
 };
-use golem_api_grpc::proto::golem::worker::{InvokeResult, InvokeResultTyped, WorkerMetadata};
 use golem_common::grpc::{
     proto_component_id_string, proto_idempotency_key_string,
     proto_invocation_context_parent_worker_id_string, proto_target_worker_id_string,
     proto_worker_id_string,
 };
 use golem_common::model::oplog::OplogIndex;
-use golem_common::model::{ComponentVersion, ScanCursor, TargetWorkerId, WorkerFilter, WorkerId};
+use golem_common::model::public_oplog::OplogQuery;
+use golem_common::model::worker_name_query::WorkerNameQuery;
+use golem_common::model::{
+    ComponentVersion, ConnectOptions, ScanCursor, TargetWorkerId, WorkerFilter, WorkerId,
+};
 use golem_common::recorded_grpc_api_request;
 use golem_service_base::auth::EmptyAuthCtx;
-use golem_service_base::model::validate_worker_name;
+use golem_service_base::model::{validate_worker_name, WorkerGetOrCreateResponse};
 use golem_worker_service_base::api::WorkerTraceErrorKind;
-use golem_worker_service_base::service::worker::ConnectWorkerStream;
+use golem_worker_service_base::service::maintenance::MaintenanceService;
+use golem_worker_service_base::service::saga::Saga;
+use golem_worker_service_base::service::worker::{
+    ConnectWorkerStream, DomainEventStream, InvocationResultStream, WorkerStatusEventStream,
+};
 
 use crate::empty_worker_metadata;
 use crate::service::component::ComponentService;
-use crate::service::worker::WorkerService;
+use crate::service::worker::{SagaCoordinator, WorkerService};
+
+type InvocationResultResponseStream =
+    Pin<Box<dyn Stream<Item = Result<InvokeAndAwaitStreamingResponse, Status>> + Send>>;
 
 pub struct WorkerGrpcApi {
     component_service: ComponentService,
     worker_service: WorkerService,
+    saga_coordinator: SagaCoordinator,
+    maintenance_service: Arc<dyn MaintenanceService + Sync + Send>,
 }
 
 impl WorkerGrpcApi {
-    pub fn new(component_service: ComponentService, worker_service: WorkerService) -> Self {
+    pub fn new(
+        component_service: ComponentService,
+        worker_service: WorkerService,
+        saga_coordinator: SagaCoordinator,
+        maintenance_service: Arc<dyn MaintenanceService + Sync + Send>,
+    ) -> Self {
         Self {
             component_service,
             worker_service,
+            saga_coordinator,
+            maintenance_service,
         }
     }
 }
@@ -102,6 +141,40 @@ impl GrpcWorkerService for WorkerGrpcApi {
         }))
     }
 
+    async fn get_or_create_worker(
+        &self,
+        request: Request<LaunchNewWorkerRequest>,
+    ) -> Result<Response<GetOrCreateWorkerResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_or_create_worker",
+            component_id = proto_component_id_string(&request.component_id),
+            name = request.name
+        );
+
+        let response = match self
+            .get_or_create_worker(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(result) => record.succeed(get_or_create_worker_response::Result::Success(
+                GetOrCreateWorkerSuccessResponse {
+                    worker_id: Some(result.worker_id.into()),
+                    component_version: result.component_version,
+                    created: result.created,
+                },
+            )),
+            Err(error) => record.fail(
+                get_or_create_worker_response::Result::Error(error.clone()),
+                &WorkerTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(GetOrCreateWorkerResponse {
+            result: Some(response),
+        }))
+    }
+
     async fn complete_promise(
         &self,
         request: Request<CompletePromiseRequest>,
@@ -144,7 +217,9 @@ impl GrpcWorkerService for WorkerGrpcApi {
             .instrument(record.span.clone())
             .await
         {
-            Ok(()) => record.succeed(delete_worker_response::Result::Success(Empty {})),
+            Ok(removed) => record.succeed(delete_worker_response::Result::Success(
+                DeleteWorkerSuccessResponse { removed },
+            )),
             Err(error) => record.fail(
                 delete_worker_response::Result::Error(error.clone()),
                 &WorkerTraceErrorKind(&error),
@@ -183,6 +258,35 @@ impl GrpcWorkerService for WorkerGrpcApi {
         }))
     }
 
+    async fn get_worker_metadata_many(
+        &self,
+        request: Request<GetWorkerMetadataManyRequest>,
+    ) -> Result<Response<GetWorkerMetadataManyResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_worker_metadata_many",
+            worker_count = request.worker_ids.len(),
+        );
+
+        let response = match self
+            .get_worker_metadata_many(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(result) => {
+                record.succeed(get_worker_metadata_many_response::Result::Success(result))
+            }
+            Err(error) => record.fail(
+                get_worker_metadata_many_response::Result::Error(error.clone()),
+                &WorkerTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(GetWorkerMetadataManyResponse {
+            result: Some(response),
+        }))
+    }
+
     async fn interrupt_worker(
         &self,
         request: Request<InterruptWorkerRequest>,
@@ -211,6 +315,66 @@ impl GrpcWorkerService for WorkerGrpcApi {
         }))
     }
 
+    async fn abort_running_invocation(
+        &self,
+        request: Request<AbortRunningInvocationRequest>,
+    ) -> Result<Response<AbortRunningInvocationResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "abort_running_invocation",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
+        );
+
+        let response = match self
+            .abort_running_invocation(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(aborted) => record.succeed(abort_running_invocation_response::Result::Success(
+                AbortRunningInvocationSuccessResponse { aborted },
+            )),
+            Err(error) => record.fail(
+                abort_running_invocation_response::Result::Error(error.clone()),
+                &WorkerTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(AbortRunningInvocationResponse {
+            result: Some(response),
+        }))
+    }
+
+    async fn cancel_invocation(
+        &self,
+        request: Request<CancelInvocationRequest>,
+    ) -> Result<Response<CancelInvocationResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "cancel_invocation",
+            worker_id = proto_worker_id_string(&request.worker_id),
+            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
+        );
+
+        let response = match self
+            .cancel_invocation(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(canceled) => record.succeed(cancel_invocation_response::Result::Success(
+                CancelInvocationSuccessResponse { canceled },
+            )),
+            Err(error) => record.fail(
+                cancel_invocation_response::Result::Error(error.clone()),
+                &WorkerTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(CancelInvocationResponse {
+            result: Some(response),
+        }))
+    }
+
     async fn invoke_and_await(
         &self,
         request: Request<InvokeAndAwaitRequest>,
@@ -304,6 +468,71 @@ impl GrpcWorkerService for WorkerGrpcApi {
         }))
     }
 
+    type InvokeAndAwaitStreamingStream = InvocationResultResponseStream;
+
+    async fn invoke_and_await_streaming(
+        &self,
+        request: Request<InvokeAndAwaitRequest>,
+    ) -> Result<Response<Self::InvokeAndAwaitStreamingStream>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "invoke_and_await_streaming",
+            worker_id = proto_target_worker_id_string(&request.worker_id),
+            idempotency_key = proto_idempotency_key_string(&request.idempotency_key),
+            function = request.function,
+            context_parent_worker_id =
+                proto_invocation_context_parent_worker_id_string(&request.context)
+        );
+
+        let stream: InvocationResultResponseStream = match self
+            .invoke_and_await_streaming(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(result_stream) => {
+                let header = InvokeAndAwaitStreamingResponse {
+                    result: Some(invoke_and_await_streaming_response::Result::Header(
+                        InvokeAndAwaitStreamingHeader {
+                            list_elements: result_stream.list_elements(),
+                        },
+                    )),
+                };
+                let chunks = result_stream.map(|chunk| {
+                    let result = match chunk {
+                        Ok(value) => {
+                            invoke_and_await_streaming_response::Result::Chunk(InvokeResultTyped {
+                                result: Some(golem_wasm_rpc::protobuf::TypeAnnotatedValue {
+                                    type_annotated_value: Some(value),
+                                }),
+                            })
+                        }
+                        Err(error) => {
+                            invoke_and_await_streaming_response::Result::Error(error.into())
+                        }
+                    };
+                    Ok(InvokeAndAwaitStreamingResponse {
+                        result: Some(result),
+                    })
+                });
+                record.succeed(Box::pin(
+                    futures::stream::once(async move { Ok(header) }).chain(chunks),
+                ))
+            }
+            Err(error) => record.fail(
+                Box::pin(futures::stream::once(futures::future::ready(Ok(
+                    InvokeAndAwaitStreamingResponse {
+                        result: Some(invoke_and_await_streaming_response::Result::Error(
+                            error.clone(),
+                        )),
+                    },
+                )))),
+                &WorkerTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(stream))
+    }
+
     async fn invoke(
         &self,
         request: Request<InvokeRequest>,
@@ -443,6 +672,62 @@ impl GrpcWorkerService for WorkerGrpcApi {
         }))
     }
 
+    async fn search_workers(
+        &self,
+        request: Request<SearchWorkersRequest>,
+    ) -> Result<Response<SearchWorkersResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "search_workers",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        let response = match self
+            .search_workers(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(worker_ids) => record.succeed(search_workers_response::Result::Success(
+                SearchWorkersSuccessResponse { worker_ids },
+            )),
+            Err(error) => record.fail(
+                search_workers_response::Result::Error(error.clone()),
+                &WorkerTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(SearchWorkersResponse {
+            result: Some(response),
+        }))
+    }
+
+    async fn execute_saga(
+        &self,
+        request: Request<ExecuteSagaRequest>,
+    ) -> Result<Response<ExecuteSagaResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "execute_saga",
+            steps = request.saga.as_ref().map_or(0, |saga| saga.steps.len()),
+        );
+
+        let response = match self
+            .execute_saga(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(outcome) => record.succeed(execute_saga_response::Result::Success(outcome)),
+            Err(error) => record.fail(
+                execute_saga_response::Result::Error(error.clone()),
+                &WorkerTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(ExecuteSagaResponse {
+            result: Some(response),
+        }))
+    }
+
     async fn update_worker(
         &self,
         request: Request<UpdateWorkerRequest>,
@@ -470,6 +755,33 @@ impl GrpcWorkerService for WorkerGrpcApi {
         }))
     }
 
+    async fn update_worker_env(
+        &self,
+        request: Request<UpdateWorkerEnvRequest>,
+    ) -> Result<Response<UpdateWorkerEnvResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "update_worker_env",
+            worker_id = proto_worker_id_string(&request.worker_id),
+        );
+
+        let response = match self
+            .update_worker_env(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(()) => record.succeed(update_worker_env_response::Result::Success(Empty {})),
+            Err(error) => record.fail(
+                update_worker_env_response::Result::Error(error.clone()),
+                &WorkerTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(UpdateWorkerEnvResponse {
+            result: Some(response),
+        }))
+    }
+
     async fn get_oplog(
         &self,
         request: Request<GetOplogRequest>,
@@ -496,6 +808,110 @@ impl GrpcWorkerService for WorkerGrpcApi {
             result: Some(response),
         }))
     }
+
+    async fn poll_events(
+        &self,
+        request: Request<PollEventsRequest>,
+    ) -> Result<Response<PollEventsResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "poll_events",
+            component_id = proto_component_id_string(&request.component_id),
+            topic = request.topic,
+            cursor = request.cursor,
+        );
+
+        let response = match self
+            .poll_events(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(response) => record.succeed(poll_events_response::Result::Success(response)),
+            Err(error) => record.fail(
+                poll_events_response::Result::Error(error.clone()),
+                &WorkerTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(PollEventsResponse {
+            result: Some(response),
+        }))
+    }
+
+    type SubscribeEventsStream = DomainEventStream;
+
+    async fn subscribe_events(
+        &self,
+        request: Request<SubscribeEventsRequest>,
+    ) -> Result<Response<Self::SubscribeEventsStream>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "subscribe_events",
+            component_id = proto_component_id_string(&request.component_id),
+            topic = request.topic,
+            cursor = request.cursor,
+        );
+
+        let stream = self
+            .subscribe_events(request)
+            .instrument(record.span.clone())
+            .await;
+        match stream {
+            Ok(stream) => Ok(Response::new(stream)),
+            Err(error) => Err(error_to_status(error)),
+        }
+    }
+
+    type WatchWorkersStream = WorkerStatusEventStream;
+
+    async fn watch_workers(
+        &self,
+        request: Request<WatchWorkersRequest>,
+    ) -> Result<Response<Self::WatchWorkersStream>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "watch_workers",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        let stream = self
+            .watch_workers(request)
+            .instrument(record.span.clone())
+            .await;
+        match stream {
+            Ok(stream) => Ok(Response::new(stream)),
+            Err(error) => Err(error_to_status(error)),
+        }
+    }
+
+    async fn get_component_maintenance(
+        &self,
+        request: Request<GetComponentMaintenanceRequest>,
+    ) -> Result<Response<GetComponentMaintenanceResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_component_maintenance",
+            component_id = proto_component_id_string(&request.component_id),
+        );
+
+        let response = match self
+            .get_component_maintenance(request)
+            .instrument(record.span.clone())
+            .await
+        {
+            Ok(response) => record.succeed(get_component_maintenance_response::Result::Success(
+                response,
+            )),
+            Err(error) => record.fail(
+                get_component_maintenance_response::Result::Error(error.clone()),
+                &WorkerTraceErrorKind(&error),
+            ),
+        };
+
+        Ok(Response::new(GetComponentMaintenanceResponse {
+            result: Some(response),
+        }))
+    }
 }
 
 impl WorkerGrpcApi {
@@ -521,35 +937,97 @@ impl WorkerGrpcApi {
                 })),
             })?;
 
+        let worker = if request.name.is_empty() {
+            self.worker_service
+                .create_with_generated_name(
+                    &component_id,
+                    latest_component.versioned_component_id.version,
+                    request.args,
+                    request.env,
+                    request.ttl_millis.map(std::time::Duration::from_millis),
+                    request.anti_affinity_key,
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?
+        } else {
+            let worker_id = validated_worker_id(component_id, request.name)?;
+            self.worker_service
+                .create(
+                    &worker_id,
+                    latest_component.versioned_component_id.version,
+                    request.args,
+                    request.env,
+                    request.ttl_millis.map(std::time::Duration::from_millis),
+                    request.anti_affinity_key,
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?
+        };
+
+        Ok((worker, latest_component.versioned_component_id.version))
+    }
+
+    async fn get_or_create_worker(
+        &self,
+        request: LaunchNewWorkerRequest,
+    ) -> Result<WorkerGetOrCreateResponse, GrpcWorkerError> {
+        let component_id: golem_common::model::ComponentId = request
+            .component_id
+            .and_then(|id| id.try_into().ok())
+            .ok_or_else(|| bad_request_error("Missing component id"))?;
+
+        let latest_component = self
+            .component_service
+            .get_latest(&component_id, &EmptyAuthCtx::default())
+            .await
+            .tap_err(|error| {
+                tracing::error!(error = error.to_string(), "Error getting latest component")
+            })
+            .map_err(|_| GrpcWorkerError {
+                error: Some(worker_error::Error::NotFound(ErrorBody {
+                    error: format!("Component not found: {}", &component_id),
+                })),
+            })?;
+
         let worker_id = validated_worker_id(component_id, request.name)?;
 
-        let worker = self
+        let result = self
             .worker_service
-            .create(
+            .get_or_create_worker(
                 &worker_id,
                 latest_component.versioned_component_id.version,
                 request.args,
                 request.env,
+                request.ttl_millis.map(std::time::Duration::from_millis),
+                request.anti_affinity_key,
                 empty_worker_metadata(),
                 &EmptyAuthCtx::default(),
             )
             .await?;
 
-        Ok((worker, latest_component.versioned_component_id.version))
+        Ok(result)
     }
 
-    async fn delete_worker(&self, request: DeleteWorkerRequest) -> Result<(), GrpcWorkerError> {
+    async fn delete_worker(
+        &self,
+        request: DeleteWorkerRequest,
+    ) -> Result<Vec<String>, GrpcWorkerError> {
         let worker_id = validate_protobuf_worker_id(request.worker_id)?;
 
-        self.worker_service
+        let removed = self
+            .worker_service
             .delete(
                 &worker_id,
+                request.cascade,
+                request.dry_run,
                 empty_worker_metadata(),
                 &EmptyAuthCtx::default(),
             )
             .await?;
 
-        Ok(())
+        Ok(removed)
     }
 
     async fn complete_promise(
@@ -594,6 +1072,38 @@ impl WorkerGrpcApi {
         Ok(metadata.into())
     }
 
+    async fn get_worker_metadata_many(
+        &self,
+        request: GetWorkerMetadataManyRequest,
+    ) -> Result<GetWorkerMetadataManySuccessResponse, GrpcWorkerError> {
+        let worker_ids = request
+            .worker_ids
+            .into_iter()
+            .map(|worker_id| validate_protobuf_worker_id(Some(worker_id)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let result = self
+            .worker_service
+            .get_metadata_many(
+                &worker_ids,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await?;
+
+        Ok(GetWorkerMetadataManySuccessResponse {
+            workers: result.workers.into_iter().map(|w| w.into()).collect(),
+            errors: result
+                .errors
+                .into_iter()
+                .map(|(worker_id, error)| WorkerMetadataLookupError {
+                    worker_id: Some(worker_id.into()),
+                    error: Some(error.into()),
+                })
+                .collect(),
+        })
+    }
+
     async fn get_workers_metadata(
         &self,
         request: GetWorkersMetadataRequest,
@@ -620,6 +1130,7 @@ impl WorkerGrpcApi {
                 request.cursor.map(|c| c.into()).unwrap_or_default(),
                 request.count,
                 request.precise,
+                request.projection.map(|p| p.into()),
                 empty_worker_metadata(),
                 &EmptyAuthCtx::default(),
             )
@@ -630,12 +1141,72 @@ impl WorkerGrpcApi {
         Ok((new_cursor, result))
     }
 
+    async fn search_workers(
+        &self,
+        request: SearchWorkersRequest,
+    ) -> Result<Vec<golem_api_grpc::proto::golem::worker::WorkerId>, GrpcWorkerError> {
+        let component_id: golem_common::model::ComponentId = request
+            .component_id
+            .ok_or_else(|| bad_request_error("Missing component id"))?
+            .try_into()
+            .map_err(|_| bad_request_error("Invalid component id"))?;
+
+        let query: WorkerNameQuery = request
+            .query
+            .ok_or_else(|| bad_request_error("Missing query"))?
+            .try_into()
+            .map_err(|error| bad_request_error(format!("Invalid query: {error}")))?;
+
+        let worker_ids = self
+            .worker_service
+            .search_workers(
+                &component_id,
+                query,
+                request.count,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await?;
+
+        Ok(worker_ids.into_iter().map(|id| id.into()).collect())
+    }
+
+    async fn execute_saga(
+        &self,
+        request: ExecuteSagaRequest,
+    ) -> Result<SagaOutcome, GrpcWorkerError> {
+        let saga: Saga = request
+            .saga
+            .ok_or_else(|| bad_request_error("Missing saga"))?
+            .try_into()
+            .map_err(|error| bad_request_error(format!("Invalid saga: {error}")))?;
+
+        let outcome = self
+            .saga_coordinator
+            .execute(saga, empty_worker_metadata())
+            .await?;
+
+        Ok(outcome)
+    }
+
     async fn interrupt_worker(
         &self,
         request: InterruptWorkerRequest,
     ) -> Result<(), GrpcWorkerError> {
         let worker_id = validate_protobuf_worker_id(request.worker_id)?;
 
+        if let Some(idempotency_key) = request.idempotency_key {
+            self.worker_service
+                .hold_invocation(
+                    &worker_id,
+                    &idempotency_key.into(),
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?;
+            return Ok(());
+        }
+
         self.worker_service
             .interrupt(
                 &worker_id,
@@ -648,19 +1219,69 @@ impl WorkerGrpcApi {
         Ok(())
     }
 
+    async fn abort_running_invocation(
+        &self,
+        request: AbortRunningInvocationRequest,
+    ) -> Result<bool, GrpcWorkerError> {
+        let worker_id = validate_protobuf_worker_id(request.worker_id)?;
+        let idempotency_key = request
+            .idempotency_key
+            .ok_or_else(|| bad_request_error("Missing idempotency key"))?
+            .into();
+
+        let aborted = self
+            .worker_service
+            .abort_running_invocation(
+                &worker_id,
+                &idempotency_key,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await?;
+
+        Ok(aborted)
+    }
+
+    async fn cancel_invocation(
+        &self,
+        request: CancelInvocationRequest,
+    ) -> Result<bool, GrpcWorkerError> {
+        let worker_id = validate_protobuf_worker_id(request.worker_id)?;
+        let idempotency_key = request
+            .idempotency_key
+            .ok_or_else(|| bad_request_error("Missing idempotency key"))?
+            .into();
+
+        let canceled = self
+            .worker_service
+            .cancel_invocation(
+                &worker_id,
+                &idempotency_key,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await?;
+
+        Ok(canceled)
+    }
+
     async fn invoke(&self, request: InvokeRequest) -> Result<(), GrpcWorkerError> {
         let worker_id = validate_protobuf_target_worker_id(request.worker_id)?;
 
         let params = request
             .invoke_parameters
             .ok_or_else(|| bad_request_error("Missing invoke parameters"))?;
+        let function = self
+            .resolve_function_name(&worker_id, request.function)
+            .await?;
 
         self.worker_service
             .invoke(
                 &worker_id,
                 request.idempotency_key.map(|k| k.into()),
-                request.function,
+                function,
                 params.params,
+                params.blob_params,
                 request.context,
                 empty_worker_metadata(),
             )
@@ -678,13 +1299,17 @@ impl WorkerGrpcApi {
             .idempotency_key
             .ok_or_else(|| bad_request_error("Missing idempotency key"))?
             .into();
+        let function = self
+            .resolve_function_name(&worker_id, request.function)
+            .await?;
 
         self.worker_service
             .validate_and_invoke(
                 &worker_id,
                 Some(idempotency_key),
-                request.function,
+                function,
                 params,
+                vec![],
                 request.context,
                 empty_worker_metadata(),
             )
@@ -702,14 +1327,45 @@ impl WorkerGrpcApi {
         let params = request
             .invoke_parameters
             .ok_or(bad_request_error("Missing invoke parameters"))?;
+        let function = self
+            .resolve_function_name(&worker_id, request.function)
+            .await?;
+
+        if request.transform {
+            let result = self
+                .worker_service
+                .invoke_and_await_typed(
+                    &worker_id,
+                    request.idempotency_key.map(|k| k.into()),
+                    function,
+                    params.params,
+                    params.blob_params,
+                    with_map_result(request.context),
+                    empty_worker_metadata(),
+                )
+                .await?;
+            let result = match result {
+                TypeAnnotatedValue::Tuple(tuple) => tuple
+                    .value
+                    .into_iter()
+                    .filter_map(|v| v.type_annotated_value)
+                    .collect(),
+                other => vec![other],
+            };
+
+            return Ok(InvokeResult {
+                result: self.worker_service.validate_typed_parameters(result)?,
+            });
+        }
 
         let result = self
             .worker_service
             .invoke_and_await(
                 &worker_id,
                 request.idempotency_key.map(|k| k.into()),
-                request.function,
+                function,
                 params.params,
+                params.blob_params,
                 request.context,
                 empty_worker_metadata(),
             )
@@ -718,6 +1374,24 @@ impl WorkerGrpcApi {
         Ok(result)
     }
 
+    async fn resolve_function_name(
+        &self,
+        worker_id: &TargetWorkerId,
+        function: String,
+    ) -> Result<String, GrpcWorkerError> {
+        let function = self
+            .worker_service
+            .resolve_function_name(
+                worker_id,
+                function,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await?;
+
+        Ok(function)
+    }
+
     async fn invoke_and_await_json(
         &self,
         request: InvokeAndAwaitJsonRequest,
@@ -729,15 +1403,24 @@ impl WorkerGrpcApi {
             .idempotency_key
             .ok_or_else(|| bad_request_error("Missing idempotency key"))?
             .into();
+        let function = self
+            .resolve_function_name(&worker_id, request.function)
+            .await?;
 
+        let context = if request.transform {
+            with_map_result(request.context)
+        } else {
+            request.context
+        };
         let result = self
             .worker_service
             .validate_and_invoke_and_await_typed(
                 &worker_id,
                 Some(idempotency_key),
-                request.function,
+                function,
                 params,
-                request.context,
+                vec![],
+                context,
                 empty_worker_metadata(),
             )
             .await?;
@@ -766,15 +1449,24 @@ impl WorkerGrpcApi {
             .idempotency_key
             .ok_or_else(|| bad_request_error("Missing idempotency key"))?
             .into();
+        let function = self
+            .resolve_function_name(&worker_id, request.function)
+            .await?;
 
+        let context = if request.transform {
+            with_map_result(request.context)
+        } else {
+            request.context
+        };
         let result = self
             .worker_service
             .invoke_and_await_typed(
                 &worker_id,
                 Some(idempotency_key),
-                request.function,
+                function,
                 params.params,
-                request.context,
+                params.blob_params,
+                context,
                 empty_worker_metadata(),
             )
             .await?;
@@ -786,9 +1478,57 @@ impl WorkerGrpcApi {
         })
     }
 
+    async fn invoke_and_await_streaming(
+        &self,
+        request: InvokeAndAwaitRequest,
+    ) -> Result<InvocationResultStream, GrpcWorkerError> {
+        let worker_id = validate_protobuf_target_worker_id(request.worker_id)?;
+        let params = request
+            .invoke_parameters
+            .ok_or(bad_request_error("Missing invoke parameters"))?;
+
+        let idempotency_key = request
+            .idempotency_key
+            .ok_or_else(|| bad_request_error("Missing idempotency key"))?
+            .into();
+        if request.transform {
+            return Err(bad_request_error("Streamed results cannot be transformed"));
+        }
+        let function = self
+            .resolve_function_name(&worker_id, request.function)
+            .await?;
+
+        let stream = self
+            .worker_service
+            .invoke_and_await_function_stream(
+                &worker_id,
+                Some(idempotency_key),
+                function,
+                params.params,
+                params.blob_params,
+                request.context,
+                empty_worker_metadata(),
+            )
+            .await?;
+
+        Ok(stream)
+    }
+
     async fn resume_worker(&self, request: ResumeWorkerRequest) -> Result<(), GrpcWorkerError> {
         let worker_id = validate_protobuf_worker_id(request.worker_id)?;
 
+        if let Some(idempotency_key) = request.idempotency_key {
+            self.worker_service
+                .release_invocation(
+                    &worker_id,
+                    &idempotency_key.into(),
+                    empty_worker_metadata(),
+                    &EmptyAuthCtx::default(),
+                )
+                .await?;
+            return Ok(());
+        }
+
         self.worker_service
             .resume(
                 &worker_id,
@@ -805,10 +1545,12 @@ impl WorkerGrpcApi {
         request: ConnectWorkerRequest,
     ) -> Result<ConnectWorkerStream, GrpcWorkerError> {
         let worker_id = validate_protobuf_worker_id(request.worker_id)?;
+        let options: ConnectOptions = request.options.map(|o| o.into()).unwrap_or_default();
         let stream = self
             .worker_service
             .connect(
                 &worker_id,
+                options,
                 empty_worker_metadata(),
                 &EmptyAuthCtx::default(),
             )
@@ -833,6 +1575,24 @@ impl WorkerGrpcApi {
         Ok(())
     }
 
+    async fn update_worker_env(
+        &self,
+        request: UpdateWorkerEnvRequest,
+    ) -> Result<(), GrpcWorkerError> {
+        let worker_id = validate_protobuf_worker_id(request.worker_id)?;
+
+        self.worker_service
+            .update_env(
+                &worker_id,
+                request.env,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
     async fn get_oplog(
         &self,
         request: GetOplogRequest,
@@ -846,6 +1606,7 @@ impl WorkerGrpcApi {
                 OplogIndex::from_u64(request.from_oplog_index),
                 request.cursor.map(|cursor| cursor.into()),
                 request.count,
+                request.query.map(OplogQuery::from),
                 empty_worker_metadata(),
                 &EmptyAuthCtx::default(),
             )
@@ -871,6 +1632,94 @@ impl WorkerGrpcApi {
             next: result.next.map(|c| c.into()),
             first_index_in_chunk: result.first_index_in_chunk,
             last_index: result.last_index,
+            indices: result.indices,
+        })
+    }
+
+    async fn poll_events(
+        &self,
+        request: PollEventsRequest,
+    ) -> Result<PollEventsSuccessResponse, GrpcWorkerError> {
+        let component_id = validate_protobuf_component_id(request.component_id)?;
+        let topic = validated_topic(request.topic)?;
+
+        let result = self
+            .worker_service
+            .poll_events(
+                &component_id,
+                &topic,
+                request.cursor,
+                request.count,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await?;
+
+        Ok(PollEventsSuccessResponse {
+            events: result.events.into_iter().map(|e| e.into()).collect(),
+            next: result.next,
+        })
+    }
+
+    async fn subscribe_events(
+        &self,
+        request: SubscribeEventsRequest,
+    ) -> Result<DomainEventStream, GrpcWorkerError> {
+        let component_id = validate_protobuf_component_id(request.component_id)?;
+        let topic = validated_topic(request.topic)?;
+
+        let stream = self
+            .worker_service
+            .subscribe_events(
+                &component_id,
+                &topic,
+                request.cursor,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await?;
+
+        Ok(stream)
+    }
+
+    async fn watch_workers(
+        &self,
+        request: WatchWorkersRequest,
+    ) -> Result<WorkerStatusEventStream, GrpcWorkerError> {
+        let component_id = validate_protobuf_component_id(request.component_id)?;
+
+        let filter: Option<WorkerFilter> =
+            match request.filter {
+                Some(f) => Some(f.try_into().map_err(|error| {
+                    bad_request_error(format!("Invalid worker filter: {error}"))
+                })?),
+                _ => None,
+            };
+
+        let stream = self
+            .worker_service
+            .watch_workers(
+                &component_id,
+                filter,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await?;
+
+        Ok(stream)
+    }
+
+    async fn get_component_maintenance(
+        &self,
+        request: GetComponentMaintenanceRequest,
+    ) -> Result<GetComponentMaintenanceSuccessResponse, GrpcWorkerError> {
+        let component_id = validate_protobuf_component_id(request.component_id)?;
+
+        let maintenance = self.maintenance_service.in_maintenance(&component_id);
+
+        Ok(GetComponentMaintenanceSuccessResponse {
+            in_maintenance: maintenance.is_some(),
+            message: maintenance.and_then(|maintenance| maintenance.message),
         })
     }
 }
@@ -921,6 +1770,23 @@ fn validate_protobuf_target_worker_id(
     validated_target_worker_id(worker_id.component_id, worker_id.worker_name)
 }
 
+fn validate_protobuf_component_id(
+    component_id: Option<golem_api_grpc::proto::golem::component::ComponentId>,
+) -> Result<golem_common::model::ComponentId, GrpcWorkerError> {
+    component_id
+        .ok_or_else(|| bad_request_error("Missing component id"))?
+        .try_into()
+        .map_err(|_| bad_request_error("Invalid component id"))
+}
+
+fn validated_topic(topic: String) -> Result<String, GrpcWorkerError> {
+    if topic.is_empty() {
+        Err(bad_request_error("Missing topic"))
+    } else {
+        Ok(topic)
+    }
+}
+
 fn bad_request_error<T>(error: T) -> GrpcWorkerError
 where
     T: Into<String>,
@@ -947,6 +1813,7 @@ fn error_to_status(error: GrpcWorkerError) -> Status {
         Some(worker_error::Error::AlreadyExists(ErrorBody { error })) => {
             Status::already_exists(error)
         }
+        Some(worker_error::Error::Unavailable(ErrorBody { error })) => Status::unavailable(error),
         Some(worker_error::Error::InternalError(WorkerExecutionError { error: None })) => {
             Status::unknown("Unknown error")
         }
@@ -1030,6 +1897,13 @@ fn error_to_status(error: GrpcWorkerError) -> Status {
                 worker_execution_error::Error::ShardingNotReady(_) => {
                     "Sharding Not Ready".to_string()
                 }
+                worker_execution_error::Error::ResultTooLarge(err) => format!(
+                    "Result Too Large: Size = {}, Limit = {}, Blob Key = {}",
+                    err.size, err.limit, err.blob_key
+                ),
+                worker_execution_error::Error::InvocationTimedOut(err) => {
+                    format!("Invocation Timed Out: Timeout = {} ms", err.timeout_millis)
+                }
             };
             Status::internal(message)
         }
@@ -1046,3 +1920,11 @@ fn parse_json_invoke_parameters(
         .collect::<Result<Vec<_>, _>>()
         .map_err(|err| bad_request_error(format!("Failed to parse JSON parameters: {err:?}")))
 }
+
+/// Asks the worker executor to pass the result through the component's `__map_result` export
+fn with_map_result(context: Option<InvocationContext>) -> Option<InvocationContext> {
+    Some(InvocationContext {
+        map_result: true,
+        ..context.unwrap_or_default()
+    })
+}
@@ -9,11 +9,15 @@ pub mod worker_bridge_request_executor;
 #[cfg(test)]
 test_r::enable!();
 
+fn default_account_id() -> golem_common::model::AccountId {
+    golem_common::model::AccountId {
+        value: "-1".to_string(),
+    }
+}
+
 fn empty_worker_metadata() -> WorkerRequestMetadata {
     WorkerRequestMetadata {
-        account_id: Some(golem_common::model::AccountId {
-            value: "-1".to_string(),
-        }),
+        account_id: Some(default_account_id()),
         limits: None,
     }
 }
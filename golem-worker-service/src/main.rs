@@ -11,6 +11,7 @@ use tokio::select;
 use tracing::error;
 
 use golem_common::config::DbConfig;
+use golem_common::request_capture::CapturedServer;
 use golem_common::tracing::init_tracing_with_default_env_filter;
 use golem_service_base::db;
 use golem_worker_service::api;
@@ -18,6 +19,7 @@ use golem_worker_service::api::make_open_api_service;
 use golem_worker_service::config::make_config_loader;
 use golem_worker_service::grpcapi;
 use golem_worker_service::service::Services;
+use golem_worker_service_base::api::{RequestCapture, RequestCaptureMiddleware};
 use golem_worker_service_base::app_config::WorkerServiceBaseConfig;
 use golem_worker_service_base::metrics;
 
@@ -76,12 +78,31 @@ pub async fn app(
         .await
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
 
+    let request_capture = if config.request_capture.enabled {
+        Some(
+            RequestCapture::start(&config.request_capture)
+                .await
+                .map_err(|e| {
+                    error!(error = %e, "Request capture - init error");
+                    e
+                })?,
+        )
+    } else {
+        None
+    };
+    let gateway_request_capture = request_capture.clone();
+
     let http_service1 = services.clone();
     let http_service2 = services.clone();
     let grpc_services = services.clone();
+    let client_certificate = config.client_certificate.clone();
 
     let custom_request_server = tokio::spawn(async move {
-        let route = api::custom_request_route(http_service1)
+        let route = api::custom_request_route(http_service1, client_certificate)
+            .with(RequestCaptureMiddleware::new(
+                gateway_request_capture,
+                CapturedServer::Gateway,
+            ))
             .with(OpenTelemetryMetrics::new())
             .with(Tracing);
 
@@ -95,6 +116,10 @@ pub async fn app(
     let worker_server = tokio::spawn(async move {
         let prometheus_registry = Arc::new(prometheus_registry);
         let app = api::combined_routes(prometheus_registry, &http_service2)
+            .with(RequestCaptureMiddleware::new(
+                request_capture,
+                CapturedServer::Api,
+            ))
             .with(OpenTelemetryMetrics::new())
             .with(Tracing);
 
@@ -107,6 +132,7 @@ pub async fn app(
     let grpc_server = tokio::spawn(async move {
         grpcapi::start_grpc_server(
             SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), config.worker_grpc_port).into(),
+            config.grpc_compression,
             &grpc_services,
         )
         .await
@@ -8,11 +8,16 @@ use golem_worker_service_base::api_definition::http::{
 };
 
 use golem_service_base::auth::{DefaultNamespace, EmptyAuthCtx};
+use golem_worker_service_base::api::AdminConfig;
 use golem_worker_service_base::app_config::WorkerServiceBaseConfig;
 use golem_worker_service_base::http::InputHttpRequest;
 
 use golem_worker_service_base::repo::api_definition;
 use golem_worker_service_base::repo::api_deployment;
+use golem_worker_service_base::repo::maintenance;
+use golem_worker_service_base::repo::pending_invocation;
+use golem_worker_service_base::repo::saga;
+use golem_worker_service_base::repo::worker_defaults;
 use golem_worker_service_base::service::api_definition::{
     ApiDefinitionService, ApiDefinitionServiceDefault,
 };
@@ -24,12 +29,22 @@ use golem_worker_service_base::service::component::RemoteComponentService;
 use golem_worker_service_base::service::http::http_api_definition_validator::{
     HttpApiDefinitionValidator, RouteValidationError,
 };
-use golem_worker_service_base::service::worker::WorkerServiceDefault;
+use golem_worker_service_base::service::maintenance::{
+    MaintenanceService, MaintenanceServiceDefault,
+};
+use golem_worker_service_base::service::saga::SagaCoordinator;
+use golem_worker_service_base::service::worker::{
+    configured_idempotency_cache, InvocationQueue, WorkerServiceDefault,
+};
+use golem_worker_service_base::service::worker_defaults::{
+    WorkerDefaultsService, WorkerDefaultsServiceDefault,
+};
 use golem_worker_service_base::worker_bridge_execution::WorkerRequestExecutor;
 
 use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_client::WorkerExecutorClient;
 use golem_common::client::{GrpcClientConfig, MultiTargetGrpcClient};
 use golem_common::config::RetryConfig;
+use golem_common::with_grpc_compression;
 
 use golem_common::config::DbConfig;
 use golem_service_base::db;
@@ -38,11 +53,13 @@ use golem_worker_service_base::service::api_deployment::{
 };
 use std::sync::Arc;
 use std::time::Duration;
-use tonic::codec::CompressionEncoding;
 
 #[derive(Clone)]
 pub struct Services {
     pub worker_service: worker::WorkerService,
+    pub saga_coordinator: worker::SagaCoordinator,
+    pub worker_defaults_service: Arc<dyn WorkerDefaultsService + Sync + Send>,
+    pub maintenance_service: Arc<dyn MaintenanceService + Sync + Send>,
     pub component_service: component::ComponentService,
     pub definition_service: Arc<
         dyn ApiDefinitionService<EmptyAuthCtx, DefaultNamespace, RouteValidationError>
@@ -56,6 +73,7 @@ pub struct Services {
     pub api_definition_validator_service: Arc<
         dyn ApiDefinitionValidatorService<HttpApiDefinition, RouteValidationError> + Sync + Send,
     >,
+    pub admin_config: AdminConfig,
 }
 
 impl Services {
@@ -65,14 +83,14 @@ impl Services {
         > = Arc::new(
             golem_service_base::routing_table::RoutingTableServiceDefault::new(
                 config.routing_table.clone(),
+                config.grpc_compression,
             ),
         );
 
+        let grpc_compression = config.grpc_compression;
         let worker_executor_grpc_clients = MultiTargetGrpcClient::new(
-            |channel| {
-                WorkerExecutorClient::new(channel)
-                    .send_compressed(CompressionEncoding::Gzip)
-                    .accept_compressed(CompressionEncoding::Gzip)
+            move |channel| {
+                with_grpc_compression!(WorkerExecutorClient::new(channel), grpc_compression)
             },
             GrpcClientConfig {
                 retries_on_unavailable: RetryConfig {
@@ -91,21 +109,21 @@ impl Services {
             let uri = config.uri();
             let retry_config = config.retries.clone();
 
-            Arc::new(RemoteComponentService::new(uri, retry_config))
+            Arc::new(RemoteComponentService::new(
+                uri,
+                retry_config,
+                grpc_compression,
+            ))
         };
 
-        let worker_service: worker::WorkerService = Arc::new(WorkerServiceDefault::new(
-            worker_executor_grpc_clients.clone(),
-            config.worker_executor_retries.clone(),
-            component_service.clone(),
-            routing_table_service.clone(),
-        ));
-
-        let worker_to_http_service: Arc<dyn WorkerRequestExecutor + Sync + Send> = Arc::new(
-            UnauthorisedWorkerRequestExecutor::new(worker_service.clone()),
-        );
-
-        let (api_definition_repo, api_deployment_repo) = match config.db.clone() {
+        let (
+            api_definition_repo,
+            api_deployment_repo,
+            pending_invocation_repo,
+            saga_repo,
+            worker_defaults_repo,
+            maintenance_repo,
+        ) = match config.db.clone() {
             DbConfig::Postgres(c) => {
                 let db_pool = db::create_postgres_pool(&c)
                     .await
@@ -118,7 +136,30 @@ impl Services {
                     Arc::new(api_deployment::DbApiDeploymentRepo::new(
                         db_pool.clone().into(),
                     ));
-                (api_definition_repo, api_deployment_repo)
+                let pending_invocation_repo: Arc<
+                    dyn pending_invocation::PendingInvocationRepo + Sync + Send,
+                > = Arc::new(pending_invocation::DbPendingInvocationRepo::new(
+                    db_pool.clone().into(),
+                ));
+                let saga_repo: Arc<dyn saga::SagaRepo + Sync + Send> =
+                    Arc::new(saga::DbSagaRepo::new(db_pool.clone().into()));
+                let worker_defaults_repo: Arc<
+                    dyn worker_defaults::WorkerDefaultsRepo + Sync + Send,
+                > = Arc::new(worker_defaults::DbWorkerDefaultsRepo::new(
+                    db_pool.clone().into(),
+                ));
+                let maintenance_repo: Arc<dyn maintenance::ComponentMaintenanceRepo + Sync + Send> =
+                    Arc::new(maintenance::DbComponentMaintenanceRepo::new(
+                        db_pool.clone().into(),
+                    ));
+                (
+                    api_definition_repo,
+                    api_deployment_repo,
+                    pending_invocation_repo,
+                    saga_repo,
+                    worker_defaults_repo,
+                    maintenance_repo,
+                )
             }
             DbConfig::Sqlite(c) => {
                 let db_pool = db::create_sqlite_pool(&c)
@@ -132,10 +173,77 @@ impl Services {
                     Arc::new(api_deployment::DbApiDeploymentRepo::new(
                         db_pool.clone().into(),
                     ));
-                (api_definition_repo, api_deployment_repo)
+                let pending_invocation_repo: Arc<
+                    dyn pending_invocation::PendingInvocationRepo + Sync + Send,
+                > = Arc::new(pending_invocation::DbPendingInvocationRepo::new(
+                    db_pool.clone().into(),
+                ));
+                let saga_repo: Arc<dyn saga::SagaRepo + Sync + Send> =
+                    Arc::new(saga::DbSagaRepo::new(db_pool.clone().into()));
+                let worker_defaults_repo: Arc<
+                    dyn worker_defaults::WorkerDefaultsRepo + Sync + Send,
+                > = Arc::new(worker_defaults::DbWorkerDefaultsRepo::new(
+                    db_pool.clone().into(),
+                ));
+                let maintenance_repo: Arc<dyn maintenance::ComponentMaintenanceRepo + Sync + Send> =
+                    Arc::new(maintenance::DbComponentMaintenanceRepo::new(
+                        db_pool.clone().into(),
+                    ));
+                (
+                    api_definition_repo,
+                    api_deployment_repo,
+                    pending_invocation_repo,
+                    saga_repo,
+                    worker_defaults_repo,
+                    maintenance_repo,
+                )
             }
         };
 
+        let maintenance_service: Arc<dyn MaintenanceService + Sync + Send> = Arc::new(
+            MaintenanceServiceDefault::new(config.maintenance.clone(), maintenance_repo),
+        );
+
+        let invocation_queue = if config.invocation_queue.enabled {
+            Some(Arc::new(InvocationQueue::new(
+                config.invocation_queue.clone(),
+                pending_invocation_repo,
+                worker_executor_grpc_clients.clone(),
+                config.worker_executor_retries.clone(),
+                routing_table_service.clone(),
+                maintenance_service.clone(),
+            )))
+        } else {
+            None
+        };
+
+        let worker_defaults_service: Arc<dyn WorkerDefaultsService + Sync + Send> =
+            Arc::new(WorkerDefaultsServiceDefault::new(worker_defaults_repo));
+
+        let idempotency_cache = configured_idempotency_cache(&config.idempotency_cache).await?;
+
+        let worker_service: worker::WorkerService = Arc::new(WorkerServiceDefault::new(
+            worker_executor_grpc_clients.clone(),
+            config.worker_executor_retries.clone(),
+            config.limits.clone(),
+            component_service.clone(),
+            routing_table_service.clone(),
+            invocation_queue,
+            worker_defaults_service.clone(),
+            maintenance_service.clone(),
+            idempotency_cache,
+        ));
+
+        let saga_coordinator: worker::SagaCoordinator = Arc::new(SagaCoordinator::new(
+            config.saga.clone(),
+            saga_repo,
+            worker_service.clone(),
+        ));
+
+        let worker_to_http_service: Arc<dyn WorkerRequestExecutor + Sync + Send> = Arc::new(
+            UnauthorisedWorkerRequestExecutor::new(worker_service.clone()),
+        );
+
         let api_definition_validator_service = Arc::new(HttpApiDefinitionValidator {});
 
         let definition_service: Arc<
@@ -160,12 +268,16 @@ impl Services {
 
         Ok(Services {
             worker_service,
+            saga_coordinator,
+            worker_defaults_service,
+            maintenance_service,
             definition_service,
             deployment_service,
             http_definition_lookup_service,
             worker_to_http_service,
             component_service,
             api_definition_validator_service,
+            admin_config: config.admin.clone(),
         })
     }
 }
@@ -4,3 +4,5 @@ use golem_service_base::auth::EmptyAuthCtx;
 
 pub type WorkerService =
     Arc<dyn golem_worker_service_base::service::worker::WorkerService<EmptyAuthCtx> + Sync + Send>;
+
+pub type SagaCoordinator = Arc<golem_worker_service_base::service::saga::SagaCoordinator>;
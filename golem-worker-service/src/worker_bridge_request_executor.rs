@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
+use crate::empty_worker_metadata;
 use async_trait::async_trait;
+use golem_common::model::{ComponentId, ConnectOptions, WorkerId};
 use golem_service_base::auth::EmptyAuthCtx;
-use golem_worker_service_base::service::worker::WorkerService;
+use golem_service_base::model::validate_worker_name;
+use golem_worker_service_base::service::worker::{ConnectWorkerStream, WorkerService};
 use golem_worker_service_base::worker_bridge_execution::{
     WorkerRequest, WorkerRequestExecutor, WorkerRequestExecutorError, WorkerResponse,
 };
@@ -26,17 +29,43 @@ impl WorkerRequestExecutor for UnauthorisedWorkerRequestExecutor {
     ) -> Result<WorkerResponse, WorkerRequestExecutorError> {
         internal::execute(self, worker_request_params.clone()).await
     }
+
+    async fn connect(
+        &self,
+        component_id: &ComponentId,
+        worker_name: &str,
+    ) -> Result<ConnectWorkerStream, WorkerRequestExecutorError> {
+        validate_worker_name(worker_name)?;
+
+        let worker_id = WorkerId {
+            component_id: component_id.clone(),
+            worker_name: worker_name.to_string(),
+        };
+
+        self.worker_service
+            .connect(
+                &worker_id,
+                ConnectOptions::default(),
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await
+            .map_err(|e| e.to_string().into())
+    }
 }
 
 mod internal {
     use crate::empty_worker_metadata;
     use crate::worker_bridge_request_executor::UnauthorisedWorkerRequestExecutor;
 
+    use golem_api_grpc::proto::golem::worker::InvocationContext;
     use golem_common::model::WorkerId;
+    use golem_service_base::auth::EmptyAuthCtx;
     use golem_service_base::model::validate_worker_name;
     use golem_worker_service_base::worker_bridge_execution::{
         WorkerRequest, WorkerRequestExecutorError, WorkerResponse,
     };
+    use std::collections::HashMap;
     use tracing::{debug, info};
 
     pub(crate) async fn execute(
@@ -87,14 +116,40 @@ mod internal {
             "Invocation parameters"
         );
 
+        let invocation_context = worker_request_params
+            .caller
+            .map(|caller| InvocationContext {
+                parent: None,
+                args: vec![],
+                env: HashMap::new(),
+                caller: Some(caller.into()),
+                timeout_millis: None,
+                map_result: false,
+            });
+
+        let worker_id = worker_id.into_target_worker_id();
+
+        // Bindings can call functions by their aliases, which are resolved here
+        let function_name = default_executor
+            .worker_service
+            .resolve_function_name(
+                &worker_id,
+                worker_request_params.function_name,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
         let type_annotated_value = default_executor
             .worker_service
             .validate_and_invoke_and_await_typed(
-                &worker_id.into_target_worker_id(),
+                &worker_id,
                 worker_request_params.idempotency_key,
-                worker_request_params.function_name,
+                function_name,
                 invoke_parameters,
-                None,
+                vec![],
+                invocation_context,
                 empty_worker_metadata(),
             )
             .await
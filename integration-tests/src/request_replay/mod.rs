@@ -0,0 +1,366 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use golem_common::request_capture::{CapturedBody, CapturedExchange, CapturedServer, REDACTED};
+use reqwest::header::{HeaderName, HeaderValue, CONTENT_LENGTH, HOST};
+use reqwest::{Client, Method, Url};
+use serde_json::Value;
+
+/// Where the captured requests are sent to
+#[derive(Debug, Clone)]
+pub struct ReplayTargets {
+    pub api: Url,
+    pub gateway: Url,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplayOptions {
+    /// JSON fields ignored when comparing the responses, such as timestamps and generated ids
+    pub ignored_fields: Vec<String>,
+    /// Headers set on every replayed request, replacing the redacted ones such as credentials
+    pub headers: Vec<(String, String)>,
+    /// Compare only the status codes of the responses
+    pub status_only: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum ReplayOutcome {
+    Matched,
+    Skipped {
+        reason: String,
+    },
+    Mismatched {
+        expected_status: u16,
+        actual_status: u16,
+        expected_body: CapturedBody,
+        actual_body: CapturedBody,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayedExchange {
+    pub line: usize,
+    pub method: String,
+    pub uri: String,
+    pub outcome: ReplayOutcome,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplayReport {
+    pub exchanges: Vec<ReplayedExchange>,
+}
+
+impl ReplayReport {
+    pub fn matched(&self) -> usize {
+        self.count(|outcome| matches!(outcome, ReplayOutcome::Matched))
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.count(|outcome| matches!(outcome, ReplayOutcome::Skipped { .. }))
+    }
+
+    pub fn failures(&self) -> Vec<&ReplayedExchange> {
+        self.exchanges
+            .iter()
+            .filter(|exchange| {
+                matches!(
+                    exchange.outcome,
+                    ReplayOutcome::Mismatched { .. } | ReplayOutcome::Failed { .. }
+                )
+            })
+            .collect()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failures().is_empty()
+    }
+
+    fn count(&self, f: impl Fn(&ReplayOutcome) -> bool) -> usize {
+        self.exchanges
+            .iter()
+            .filter(|exchange| f(&exchange.outcome))
+            .count()
+    }
+}
+
+/// Reads a request capture file written by the worker service
+pub async fn read_capture_file(path: &Path) -> Result<Vec<(usize, CapturedExchange)>, String> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|err| format!("Failed to read capture file {path:?}: {err}"))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str(line)
+                .map(|exchange| (idx + 1, exchange))
+                .map_err(|err| format!("Invalid captured request at line {}: {err}", idx + 1))
+        })
+        .collect()
+}
+
+/// Sends the captured requests in order to the target deployment, comparing the responses
+/// with the captured ones
+pub async fn replay_capture_file(
+    client: &Client,
+    path: &Path,
+    targets: &ReplayTargets,
+    options: &ReplayOptions,
+) -> Result<ReplayReport, String> {
+    let mut report = ReplayReport::default();
+    for (line, exchange) in read_capture_file(path).await? {
+        let outcome = replay_exchange(client, &exchange, targets, options).await;
+        report.exchanges.push(ReplayedExchange {
+            line,
+            method: exchange.request.method,
+            uri: exchange.request.uri,
+            outcome,
+        });
+    }
+    Ok(report)
+}
+
+pub async fn replay_exchange(
+    client: &Client,
+    exchange: &CapturedExchange,
+    targets: &ReplayTargets,
+    options: &ReplayOptions,
+) -> ReplayOutcome {
+    let body = match exchange.request.body.to_bytes() {
+        Some(body) => body,
+        None => {
+            return ReplayOutcome::Skipped {
+                reason: "request body was not captured".to_string(),
+            }
+        }
+    };
+    if exchange.request.body.is_redacted() {
+        return ReplayOutcome::Skipped {
+            reason: "request body has redacted fields".to_string(),
+        };
+    }
+    let has_redacted_headers = exchange
+        .request
+        .headers
+        .iter()
+        .any(|(name, value)| value == REDACTED && !overrides_header(options, name));
+    if has_redacted_headers {
+        return ReplayOutcome::Skipped {
+            reason: "request has redacted headers without replacement".to_string(),
+        };
+    }
+
+    let base = match exchange.server {
+        CapturedServer::Api => &targets.api,
+        CapturedServer::Gateway => &targets.gateway,
+    };
+    let url = match base.join(&exchange.request.uri) {
+        Ok(url) => url,
+        Err(err) => {
+            return ReplayOutcome::Failed {
+                error: format!("Invalid request URI: {err}"),
+            }
+        }
+    };
+    let method = match Method::from_bytes(exchange.request.method.as_bytes()) {
+        Ok(method) => method,
+        Err(err) => {
+            return ReplayOutcome::Failed {
+                error: format!("Invalid request method: {err}"),
+            }
+        }
+    };
+
+    let mut request = client.request(method, url).body(body);
+    for (name, value) in &exchange.request.headers {
+        // The API gateway routes by the host, so it is kept from the captured request
+        let is_transport_header = name == CONTENT_LENGTH.as_str()
+            || (name == HOST.as_str() && exchange.server == CapturedServer::Api);
+        if !is_transport_header && !overrides_header(options, name) {
+            request = request.header(name, value);
+        }
+    }
+    for (name, value) in &options.headers {
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => request = request.header(name, value),
+            _ => {
+                return ReplayOutcome::Failed {
+                    error: format!("Invalid replacement header {name}"),
+                }
+            }
+        }
+    }
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(err) => {
+            return ReplayOutcome::Failed {
+                error: err.to_string(),
+            }
+        }
+    };
+    let actual_status = response.status().as_u16();
+    let actual_body = match response.bytes().await {
+        Ok(bytes) => CapturedBody::from_bytes(&bytes, usize::MAX, &[]),
+        Err(err) => {
+            return ReplayOutcome::Failed {
+                error: err.to_string(),
+            }
+        }
+    };
+
+    let expected_status = exchange.response.status;
+    let expected_body = &exchange.response.body;
+    let matches = expected_status == actual_status
+        && (options.status_only
+            || !expected_body.is_replayable()
+            || bodies_match(expected_body, &actual_body, &options.ignored_fields));
+    if matches {
+        ReplayOutcome::Matched
+    } else {
+        ReplayOutcome::Mismatched {
+            expected_status,
+            actual_status,
+            expected_body: expected_body.clone(),
+            actual_body,
+        }
+    }
+}
+
+fn overrides_header(options: &ReplayOptions, name: &str) -> bool {
+    options
+        .headers
+        .iter()
+        .any(|(header, _)| header.eq_ignore_ascii_case(name))
+}
+
+/// Compares two bodies, treating the ignored and the redacted JSON fields as equal
+fn bodies_match(expected: &CapturedBody, actual: &CapturedBody, ignored_fields: &[String]) -> bool {
+    match (expected, actual) {
+        (CapturedBody::Json(expected), CapturedBody::Json(actual)) => {
+            json_matches(expected, actual, ignored_fields)
+        }
+        (expected, actual) => expected == actual,
+    }
+}
+
+fn json_matches(expected: &Value, actual: &Value, ignored_fields: &[String]) -> bool {
+    match (expected, actual) {
+        (Value::String(redacted), _) if redacted == REDACTED => true,
+        (Value::Object(expected), Value::Object(actual)) => {
+            let relevant = |name: &String| !ignored_fields.contains(name);
+            expected.keys().filter(|name| relevant(name)).count()
+                == actual.keys().filter(|name| relevant(name)).count()
+                && expected
+                    .iter()
+                    .filter(|(name, _)| relevant(name))
+                    .all(|(name, expected)| {
+                        actual
+                            .get(name)
+                            .is_some_and(|actual| json_matches(expected, actual, ignored_fields))
+                    })
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            expected.len() == actual.len()
+                && expected
+                    .iter()
+                    .zip(actual)
+                    .all(|(expected, actual)| json_matches(expected, actual, ignored_fields))
+        }
+        (expected, actual) => expected == actual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::request_replay::{
+        json_matches, replay_exchange, ReplayOptions, ReplayOutcome, ReplayTargets,
+    };
+    use golem_common::request_capture::{CapturedExchange, REDACTED};
+    use reqwest::{Client, Url};
+    use serde_json::{json, Value};
+
+    fn exchange(request_body: Value) -> CapturedExchange {
+        serde_json::from_value(json!({
+            "captured_at": "2024-01-01T00:00:00Z",
+            "server": "api",
+            "request": {
+                "method": "POST",
+                "uri": "/v1/components/c1/workers",
+                "headers": {},
+                "body": { "type": "json", "value": request_body }
+            },
+            "response": { "status": 200, "headers": {}, "body": { "type": "empty" } }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn ignored_and_redacted_fields_are_not_compared() {
+        let expected = json!({
+            "workerId": "w1",
+            "createdAt": "2024-01-01T00:00:00Z",
+            "env": REDACTED,
+            "results": [1, 2]
+        });
+        let actual = json!({
+            "workerId": "w1",
+            "createdAt": "2024-06-01T00:00:00Z",
+            "env": { "A": "B" },
+            "results": [1, 2]
+        });
+
+        assert!(json_matches(&expected, &actual, &["createdAt".to_string()]));
+        assert!(!json_matches(&expected, &actual, &[]));
+    }
+
+    #[test]
+    fn different_values_do_not_match() {
+        let expected = json!({ "results": [1, 2] });
+        let actual = json!({ "results": [1, 3] });
+
+        assert!(!json_matches(&expected, &actual, &[]));
+    }
+
+    #[test]
+    async fn requests_with_redacted_bodies_are_skipped() {
+        // Nothing listens on the targets, the request must not be sent
+        let targets = ReplayTargets {
+            api: Url::parse("http://localhost:1").unwrap(),
+            gateway: Url::parse("http://localhost:1").unwrap(),
+        };
+        let exchange = exchange(json!({ "name": "w1", "env": REDACTED }));
+
+        let outcome = replay_exchange(
+            &Client::new(),
+            &exchange,
+            &targets,
+            &ReplayOptions::default(),
+        )
+        .await;
+
+        assert!(matches!(outcome, ReplayOutcome::Skipped { .. }));
+    }
+}
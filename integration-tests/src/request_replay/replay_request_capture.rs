@@ -0,0 +1,109 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+use integration_tests::request_replay::{
+    replay_capture_file, ReplayOptions, ReplayOutcome, ReplayTargets,
+};
+use reqwest::{Client, Url};
+
+/// Replays a request capture file of the worker service against a test deployment and reports
+/// the requests whose responses differ from the captured ones
+#[derive(Parser, Debug)]
+struct ReplayParams {
+    /// The request capture file written by the worker service
+    file: PathBuf,
+    /// Base URL of the worker service API of the test deployment
+    #[arg(long, default_value = "http://localhost:9005")]
+    api_url: Url,
+    /// Base URL of the API gateway of the test deployment
+    #[arg(long, default_value = "http://localhost:9006")]
+    gateway_url: Url,
+    /// JSON fields not compared, such as timestamps or generated ids
+    #[arg(long = "ignore-field")]
+    ignored_fields: Vec<String>,
+    /// Header added to every replayed request, in NAME=VALUE form
+    #[arg(long = "header", value_parser = parse_header)]
+    headers: Vec<(String, String)>,
+    /// Compare only the status codes of the responses
+    #[arg(long)]
+    status_only: bool,
+}
+
+fn parse_header(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("invalid NAME=VALUE: no `=` found in `{s}`"))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let params = ReplayParams::parse();
+    let targets = ReplayTargets {
+        api: params.api_url,
+        gateway: params.gateway_url,
+    };
+    let options = ReplayOptions {
+        ignored_fields: params.ignored_fields,
+        headers: params.headers,
+        status_only: params.status_only,
+    };
+
+    let report = match replay_capture_file(&Client::new(), &params.file, &targets, &options).await {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for exchange in report.failures() {
+        match &exchange.outcome {
+            ReplayOutcome::Mismatched {
+                expected_status,
+                actual_status,
+                expected_body,
+                actual_body,
+            } => {
+                println!(
+                    "line {}: {} {}: expected {expected_status} {expected_body:?}, got {actual_status} {actual_body:?}",
+                    exchange.line, exchange.method, exchange.uri
+                );
+            }
+            ReplayOutcome::Failed { error } => {
+                println!(
+                    "line {}: {} {}: failed: {error}",
+                    exchange.line, exchange.method, exchange.uri
+                );
+            }
+            _ => {}
+        }
+    }
+    println!(
+        "{} replayed, {} matched, {} skipped, {} failed",
+        report.exchanges.len(),
+        report.matched(),
+        report.skipped(),
+        report.failures().len()
+    );
+
+    if report.is_success() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}